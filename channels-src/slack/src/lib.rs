@@ -6,13 +6,15 @@
 //! # Features
 //!
 //! - URL verification for Slack Events API
-//! - Message event parsing (@mentions, DMs)
+//! - `app_mention` and `message.im` events (@mentions, DMs)
+//! - Deduplication of events Slack redelivers
 //! - Thread support for conversations
-//! - Response posting via Slack Web API
+//! - Response posting via `chat.postMessage`, in the originating thread
 //!
 //! # Security
 //!
-//! - Signature validation is handled by the host (webhook secrets)
+//! - Request signatures (`X-Slack-Signature`) are verified by the host with
+//!   the signing secret before the request reaches this component
 //! - Bot token is injected by host during HTTP requests
 //! - WASM never sees raw credentials
 
@@ -26,11 +28,18 @@ use serde::{Deserialize, Serialize};
 
 // Re-export generated types
 use exports::near::agent::channel::{
-    AgentResponse, ChannelConfig, Guest, HttpEndpointConfig, IncomingHttpRequest,
-    OutgoingHttpResponse, StatusUpdate,
+    AgentResponse, ChannelConfig, FormattingCapabilities, Guest, HttpEndpointConfig,
+    IncomingHttpRequest, MarkdownFlavor, OutgoingHttpResponse, StatusUpdate,
 };
 use near::agent::channel_host::{self, EmittedMessage};
 
+/// Workspace path for IDs of recently handled events.
+const SEEN_EVENTS_PATH: &str = "state/seen_events";
+
+/// How many event IDs to remember. Slack retries within minutes, so a short
+/// window is enough.
+const MAX_SEEN_EVENTS: usize = 200;
+
 /// Slack event wrapper.
 #[derive(Debug, Deserialize)]
 struct SlackEventWrapper {
@@ -64,6 +73,9 @@ struct SlackEvent {
     /// Channel where the event occurred.
     channel: Option<String>,
 
+    /// Kind of channel ("im" for direct messages, "channel", "group", ...).
+    channel_type: Option<String>,
+
     /// Message text.
     text: Option<String>,
 
@@ -86,7 +98,7 @@ struct SlackMessageMetadata {
     /// Slack channel ID.
     channel: String,
 
-    /// Thread timestamp for threaded replies.
+    /// Thread to reply in. None replies at the top level.
     thread_ts: Option<String>,
 
     /// Original message timestamp.
@@ -134,6 +146,14 @@ impl Guest for SlackChannel {
                 require_secret: true,
             }],
             poll: None, // Slack uses push via webhooks, no polling needed
+            formatting: FormattingCapabilities {
+                markdown: MarkdownFlavor::SlackMrkdwn,
+                // chat.postMessage truncates text beyond this
+                max_message_length: Some(40_000),
+                supports_buttons: false,
+                supports_threads: true,
+                supports_files: false,
+            },
         })
     }
 
@@ -205,17 +225,7 @@ impl Guest for SlackChannel {
         let metadata: SlackMessageMetadata = serde_json::from_str(&response.metadata_json)
             .map_err(|e| format!("Failed to parse metadata: {}", e))?;
 
-        // Build Slack API request
-        let mut payload = serde_json::json!({
-            "channel": metadata.channel,
-            "text": response.content,
-        });
-
-        // Add thread_ts for threaded replies
-        if let Some(thread_ts) = response.thread_id.or(metadata.thread_ts) {
-            payload["thread_ts"] = serde_json::Value::String(thread_ts);
-        }
-
+        let payload = post_message_payload(&metadata, response.content, response.thread_id);
         let payload_bytes = serde_json::to_vec(&payload)
             .map_err(|e| format!("Failed to serialize payload: {}", e))?;
 
@@ -277,18 +287,27 @@ impl Guest for SlackChannel {
 }
 
 /// Handle a Slack event and emit message if applicable.
-fn handle_slack_event(
-    event: SlackEvent,
-    team_id: Option<String>,
-    _event_id: Option<String>,
-) {
+fn handle_slack_event(event: SlackEvent, team_id: Option<String>, event_id: Option<String>) {
+    // Slack redelivers events it thinks we missed; only handle each once
+    if let Some(ref id) = event_id {
+        if !first_delivery(id) {
+            channel_host::log(
+                channel_host::LogLevel::Debug,
+                &format!("Ignoring redelivered Slack event {}", id),
+            );
+            return;
+        }
+    }
+
     match event.event_type.as_str() {
         // Direct mention of the bot
         "app_mention" => {
             if let (Some(user), Some(channel), Some(text), Some(ts)) =
-                (event.user, event.channel.clone(), event.text, event.ts.clone())
+                (event.user, event.channel, event.text, event.ts)
             {
-                emit_message(user, text, channel, event.thread_ts.or(Some(ts)), team_id);
+                // Mentions in channels are answered in a thread on the mention
+                let thread_ts = event.thread_ts.unwrap_or_else(|| ts.clone());
+                emit_message(user, text, channel, ts, Some(thread_ts), team_id);
             }
         }
 
@@ -300,11 +319,11 @@ fn handle_slack_event(
             }
 
             if let (Some(user), Some(channel), Some(text), Some(ts)) =
-                (event.user, event.channel.clone(), event.text, event.ts.clone())
+                (event.user, event.channel, event.text, event.ts)
             {
-                // Only process DMs (channel IDs starting with D)
-                if channel.starts_with('D') {
-                    emit_message(user, text, channel, event.thread_ts.or(Some(ts)), team_id);
+                // Only process DMs; channel messages arrive as app_mention
+                if is_direct_message(event.channel_type.as_deref(), &channel) {
+                    emit_message(user, text, channel, ts, event.thread_ts, team_id);
                 }
             }
         }
@@ -318,25 +337,85 @@ fn handle_slack_event(
     }
 }
 
+/// Build the `chat.postMessage` body for a response.
+fn post_message_payload(
+    metadata: &SlackMessageMetadata,
+    content: String,
+    thread_id: Option<String>,
+) -> serde_json::Value {
+    let mut payload = serde_json::json!({
+        "channel": metadata.channel,
+        "text": content,
+    });
+
+    // Reply in the thread the message came from
+    if let Some(thread_ts) = metadata.thread_ts.clone().or(thread_id) {
+        payload["thread_ts"] = serde_json::Value::String(thread_ts);
+    }
+    payload
+}
+
+/// Whether a message event came from a direct message with the bot.
+fn is_direct_message(channel_type: Option<&str>, channel: &str) -> bool {
+    match channel_type {
+        Some(kind) => kind == "im",
+        // Older payloads omit channel_type; DM channel IDs start with D
+        None => channel.starts_with('D'),
+    }
+}
+
+/// Record an event ID, returning false if it was already handled.
+fn first_delivery(event_id: &str) -> bool {
+    let mut seen: Vec<String> = channel_host::workspace_read(SEEN_EVENTS_PATH)
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+
+    if !remember_event(&mut seen, event_id) {
+        return false;
+    }
+
+    if let Ok(serialized) = serde_json::to_string(&seen) {
+        if let Err(e) = channel_host::workspace_write(SEEN_EVENTS_PATH, &serialized) {
+            channel_host::log(
+                channel_host::LogLevel::Warn,
+                &format!("Failed to record Slack event ID: {}", e),
+            );
+        }
+    }
+    true
+}
+
+/// Add an event ID to the recent list, dropping the oldest beyond
+/// `MAX_SEEN_EVENTS`. Returns false if it was already present.
+fn remember_event(seen: &mut Vec<String>, event_id: &str) -> bool {
+    if seen.iter().any(|id| id == event_id) {
+        return false;
+    }
+    seen.push(event_id.to_string());
+    if seen.len() > MAX_SEEN_EVENTS {
+        let excess = seen.len() - MAX_SEEN_EVENTS;
+        seen.drain(..excess);
+    }
+    true
+}
+
 /// Emit a message to the agent.
 fn emit_message(
     user_id: String,
     text: String,
     channel: String,
+    message_ts: String,
     thread_ts: Option<String>,
     team_id: Option<String>,
 ) {
-    let message_ts = thread_ts.clone().unwrap_or_default();
-
     let metadata = SlackMessageMetadata {
-        channel: channel.clone(),
+        channel,
         thread_ts: thread_ts.clone(),
-        message_ts: message_ts.clone(),
+        message_ts,
         team_id,
     };
 
-    let metadata_json =
-        serde_json::to_string(&metadata).unwrap_or_else(|_| "{}".to_string());
+    let metadata_json = serde_json::to_string(&metadata).unwrap_or_else(|_| "{}".to_string());
 
     // Strip @ mentions of the bot from the text for cleaner messages
     let cleaned_text = strip_bot_mention(&text);
@@ -347,6 +426,7 @@ fn emit_message(
         content: cleaned_text,
         thread_id: thread_ts,
         metadata_json,
+        attachments: Vec::new(),
     });
 }
 
@@ -376,3 +456,61 @@ fn json_response(status: u16, value: serde_json::Value) -> OutgoingHttpResponse
 
 // Export the component
 export!(SlackChannel);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_direct_message() {
+        assert!(is_direct_message(Some("im"), "D024BE91L"));
+        assert!(!is_direct_message(Some("channel"), "C024BE91L"));
+        assert!(!is_direct_message(Some("mpim"), "G024BE91L"));
+        assert!(is_direct_message(None, "D024BE91L"));
+        assert!(!is_direct_message(None, "C024BE91L"));
+    }
+
+    fn metadata(thread_ts: Option<&str>) -> SlackMessageMetadata {
+        SlackMessageMetadata {
+            channel: "C024BE91L".to_string(),
+            thread_ts: thread_ts.map(|t| t.to_string()),
+            message_ts: "1700000000.000200".to_string(),
+            team_id: None,
+        }
+    }
+
+    #[test]
+    fn test_reply_goes_to_originating_thread() {
+        let payload = post_message_payload(
+            &metadata(Some("1700000000.000100")),
+            "Done".to_string(),
+            Some("other".to_string()),
+        );
+        assert_eq!(payload["channel"], "C024BE91L");
+        assert_eq!(payload["text"], "Done");
+        assert_eq!(payload["thread_ts"], "1700000000.000100");
+
+        // A top-level DM is answered at the top level unless the agent
+        // names a thread
+        let payload = post_message_payload(&metadata(None), "Done".to_string(), None);
+        assert!(payload.get("thread_ts").is_none());
+        let payload =
+            post_message_payload(&metadata(None), "Done".to_string(), Some("t".to_string()));
+        assert_eq!(payload["thread_ts"], "t");
+    }
+
+    #[test]
+    fn test_remember_event() {
+        let mut seen = Vec::new();
+        assert!(remember_event(&mut seen, "Ev1"));
+        assert!(!remember_event(&mut seen, "Ev1"));
+        assert!(remember_event(&mut seen, "Ev2"));
+
+        for i in 0..MAX_SEEN_EVENTS {
+            remember_event(&mut seen, &format!("Ev-fill-{}", i));
+        }
+        assert_eq!(seen.len(), MAX_SEEN_EVENTS);
+        // The oldest IDs were dropped
+        assert!(remember_event(&mut seen, "Ev1"));
+    }
+}
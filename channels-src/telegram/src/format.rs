@@ -0,0 +1,350 @@
+//! Formatting agent responses for Telegram.
+//!
+//! The agent writes ordinary Markdown, while Telegram's MarkdownV2 rejects
+//! any message with an unescaped reserved character. [`to_markdown_v2`]
+//! converts the common constructs (bold, italics, code, links, headings,
+//! lists) and escapes everything else. [`split_message`] breaks long
+//! responses into pieces under Telegram's message length limit.
+
+use crate::markdown_inline::{self, Inline};
+
+/// Telegram's message length limit, in UTF-16 code units.
+pub const MAX_MESSAGE_LEN: usize = 4096;
+
+/// Room left in each piece for re-opening and closing a split code block.
+const FENCE_RESERVE: usize = 64;
+
+/// Characters MarkdownV2 reserves outside code.
+const RESERVED: &[char] = &[
+    '_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}', '.', '!', '\\',
+];
+
+/// Convert agent Markdown to Telegram MarkdownV2.
+pub fn to_markdown_v2(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + text.len() / 8);
+    let mut in_code_block = false;
+
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let trimmed = line.trim_start();
+
+        if let Some(lang) = trimmed.strip_prefix("```") {
+            if in_code_block {
+                out.push_str("```");
+            } else {
+                out.push_str("```");
+                // The language tag is the only text allowed after the fence
+                let lang = lang.trim();
+                if lang
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-')
+                {
+                    out.push_str(lang);
+                }
+            }
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            out.push_str(&escape_code(line));
+            continue;
+        }
+
+        let indent = &line[..line.len() - trimmed.len()];
+        if let Some(heading) = strip_heading(trimmed) {
+            out.push('*');
+            out.push_str(&escape(&strip_emphasis(heading)));
+            out.push('*');
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+        {
+            out.push_str(indent);
+            out.push_str("• ");
+            out.push_str(&convert_inline(item));
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            out.push('>');
+            out.push_str(&convert_inline(quote.trim_start()));
+        } else {
+            out.push_str(indent);
+            out.push_str(&convert_inline(trimmed));
+        }
+    }
+
+    // An unterminated block would make the whole message unparseable
+    if in_code_block {
+        out.push_str("\n```");
+    }
+    out
+}
+
+/// Escape text so MarkdownV2 shows it literally.
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if RESERVED.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Inside code only the backtick and backslash need escaping.
+fn escape_code(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('`', "\\`")
+}
+
+/// Inside a link URL only `)` and backslash need escaping.
+fn escape_url(url: &str) -> String {
+    url.replace('\\', "\\\\").replace(')', "\\)")
+}
+
+fn strip_heading(line: &str) -> Option<&str> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&level) {
+        line[level..].strip_prefix(' ').map(str::trim)
+    } else {
+        None
+    }
+}
+
+/// Headings are shown bold, so emphasis markers inside them are dropped.
+fn strip_emphasis(text: &str) -> String {
+    text.replace("**", "").replace("__", "")
+}
+
+/// Convert inline Markdown: code spans, bold, italics, strikethrough and
+/// links. Markers without a partner are escaped and shown as typed.
+fn convert_inline(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    render_inline(&markdown_inline::parse(text), &mut out);
+    out
+}
+
+fn render_inline(nodes: &[Inline], out: &mut String) {
+    for node in nodes {
+        let (marker, inner) = match node {
+            Inline::Text(text) => {
+                out.push_str(&escape(text));
+                continue;
+            }
+            Inline::Code(code) => {
+                out.push('`');
+                out.push_str(&escape_code(code));
+                out.push('`');
+                continue;
+            }
+            Inline::Link { label, url } => {
+                out.push('[');
+                render_inline(label, out);
+                out.push_str("](");
+                out.push_str(&escape_url(url));
+                out.push(')');
+                continue;
+            }
+            Inline::Bold(inner) => ('*', inner),
+            Inline::Italic(inner) => ('_', inner),
+            Inline::Strike(inner) => ('~', inner),
+        };
+        out.push(marker);
+        render_inline(inner, out);
+        out.push(marker);
+    }
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// Split a response into pieces of at most `max_len` UTF-16 code units.
+///
+/// Pieces break at paragraph boundaries where possible, then at line
+/// boundaries, then at whitespace. A code block cut in two is closed at the
+/// end of one piece and reopened at the start of the next, so each piece
+/// formats on its own.
+pub fn split_message(text: &str, max_len: usize) -> Vec<String> {
+    if utf16_len(text) <= max_len {
+        return vec![text.to_string()];
+    }
+
+    let budget = max_len.saturating_sub(FENCE_RESERVE).max(1);
+    let mut pieces: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n") {
+        for part in split_long(paragraph, budget) {
+            let separator = if current.is_empty() { "" } else { "\n\n" };
+            if !current.is_empty() && utf16_len(&current) + 2 + utf16_len(&part) > budget {
+                pieces.push(std::mem::take(&mut current));
+                current.push_str(&part);
+            } else {
+                current.push_str(separator);
+                current.push_str(&part);
+            }
+        }
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+
+    balance_fences(pieces)
+}
+
+/// Split a paragraph longer than `budget` at lines, then at whitespace.
+fn split_long(paragraph: &str, budget: usize) -> Vec<String> {
+    if utf16_len(paragraph) <= budget {
+        return vec![paragraph.to_string()];
+    }
+
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    for line in paragraph.split('\n') {
+        let mut line = line;
+        while utf16_len(line) > budget {
+            if !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+            let cut = hard_cut(line, budget);
+            parts.push(line[..cut].to_string());
+            line = line[cut..].trim_start_matches(' ');
+        }
+        if !current.is_empty() && utf16_len(&current) + 1 + utf16_len(line) > budget {
+            parts.push(std::mem::take(&mut current));
+        } else if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+/// Byte index to cut `line` at so the head fits in `budget`, preferring the
+/// last space.
+fn hard_cut(line: &str, budget: usize) -> usize {
+    let mut units = 0;
+    let mut end = 0;
+    for (idx, c) in line.char_indices() {
+        units += c.len_utf16();
+        if units > budget {
+            break;
+        }
+        end = idx + c.len_utf8();
+    }
+    match line[..end].rfind(' ') {
+        Some(space) if space > 0 => space,
+        _ => end.max(line.chars().next().map_or(0, char::len_utf8)),
+    }
+}
+
+/// Close code blocks left open at the end of a piece and reopen them, with
+/// the same language, at the start of the next.
+fn balance_fences(pieces: Vec<String>) -> Vec<String> {
+    let mut open: Option<String> = None;
+    pieces
+        .into_iter()
+        .map(|piece| {
+            let mut out = String::new();
+            if let Some(fence) = &open {
+                out.push_str(fence);
+                out.push('\n');
+            }
+            for line in piece.split('\n') {
+                if let Some(lang) = line.trim_start().strip_prefix("```") {
+                    open = match open {
+                        Some(_) => None,
+                        None => Some(format!("```{}", lang.trim())),
+                    };
+                }
+            }
+            out.push_str(&piece);
+            if open.is_some() {
+                out.push_str("\n```");
+            }
+            out
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escapes_reserved_characters() {
+        assert_eq!(
+            to_markdown_v2("Done. Cost: $1.50 (approx) - see #3!"),
+            "Done\\. Cost: $1\\.50 \\(approx\\) \\- see \\#3\\!"
+        );
+        // Underscores inside words are not italics
+        assert_eq!(to_markdown_v2("ran memory_search"), "ran memory\\_search");
+    }
+
+    #[test]
+    fn test_converts_inline_markup() {
+        assert_eq!(
+            to_markdown_v2("**bold** and *it* and _it_ and ~~gone~~"),
+            "*bold* and _it_ and _it_ and ~gone~"
+        );
+        assert_eq!(
+            to_markdown_v2("run `cargo test -p a_b` now"),
+            "run `cargo test -p a_b` now"
+        );
+        assert_eq!(
+            to_markdown_v2("see [the docs](https://example.com/a_(b))"),
+            "see [the docs](https://example.com/a_(b\\))"
+        );
+        assert_eq!(to_markdown_v2("2 * 3 = 6"), "2 \\* 3 \\= 6");
+    }
+
+    #[test]
+    fn test_converts_blocks() {
+        let input = "# Plan\n- first step\n> quoted.\n```rust\nlet x = `a`;\n```";
+        assert_eq!(
+            to_markdown_v2(input),
+            "*Plan*\n• first step\n>quoted\\.\n```rust\nlet x = \\`a\\`;\n```"
+        );
+        // Unterminated code block is closed
+        assert_eq!(to_markdown_v2("```\ncode"), "```\ncode\n```");
+    }
+
+    #[test]
+    fn test_short_message_is_not_split() {
+        assert_eq!(split_message("hello", MAX_MESSAGE_LEN), vec!["hello"]);
+    }
+
+    #[test]
+    fn test_split_at_paragraphs() {
+        let paragraph = "word ".repeat(30);
+        let text = [paragraph.trim(); 5].join("\n\n");
+        let pieces = split_message(&text, 400);
+        assert!(pieces.len() > 1);
+        for piece in &pieces {
+            assert!(utf16_len(piece) <= 400);
+            assert!(!piece.starts_with('\n') && !piece.ends_with('\n'));
+        }
+        assert_eq!(pieces.join("\n\n"), text);
+    }
+
+    #[test]
+    fn test_split_long_line_and_code_block() {
+        let text = "x".repeat(1000);
+        let pieces = split_message(&text, 300);
+        assert!(pieces.iter().all(|p| utf16_len(p) <= 300));
+        assert_eq!(pieces.concat(), text);
+
+        let code = format!("```python\n{}\n```", "print(1)\n".repeat(60).trim_end());
+        let pieces = split_message(&code, 300);
+        assert!(pieces.len() > 1);
+        for piece in &pieces {
+            assert!(utf16_len(piece) <= 300);
+            assert!(piece.starts_with("```python\n"));
+            assert!(piece.ends_with("\n```"));
+        }
+    }
+}
@@ -11,14 +11,26 @@
 //! - Webhook-based message receiving
 //! - Private chat (DM) support
 //! - Group chat support with @mention triggering
-//! - Reply threading support
+//! - Reply threading support, including forum topics in supergroups
 //! - User name extraction
+//! - Photos, documents, voice notes and audio, downloaded into the host's
+//!   attachment store and passed to the agent as attachments
+//! - Agent Markdown converted to MarkdownV2, with long responses split into
+//!   several messages
+//! - Inline keyboards: responses with `buttons` metadata offer choices, and a
+//!   button press comes back as a message carrying the button's data
+//! - Per-chat quiet hours: responses during a quiet window are queued and
+//!   sent once it ends (see the `quiet` module)
+//! - Reaction feedback: thumbs up/down on the bot's messages is emitted as a
+//!   feedback event for the agent's evaluation (see the `reactions` module)
 //!
 //! # Security
 //!
 //! - Bot token is injected by host during HTTP requests
 //! - WASM never sees raw credentials
 //! - Optional webhook secret validation by host
+//! - Optional allowlist of users (`allowed_user_ids`) and group chats
+//!   (`allowed_chat_ids`); everyone else is ignored
 
 // Generate bindings from the WIT file
 wit_bindgen::generate!({
@@ -26,12 +38,19 @@ wit_bindgen::generate!({
     path: "../../wit/channel.wit",
 });
 
+mod format;
+#[path = "../../../src/channels/markdown_inline.rs"]
+mod markdown_inline;
+mod quiet;
+mod reactions;
+
 use serde::{Deserialize, Serialize};
 
 // Re-export generated types
 use exports::near::agent::channel::{
-    AgentResponse, ChannelConfig, Guest, HttpEndpointConfig, IncomingHttpRequest,
-    OutgoingHttpResponse, PollConfig, StatusType, StatusUpdate,
+    AgentResponse, ChannelConfig, FormattingCapabilities, Guest, HttpEndpointConfig,
+    IncomingHttpRequest, MarkdownFlavor, OutgoingHttpResponse, PollConfig, StatusType,
+    StatusUpdate,
 };
 use near::agent::channel_host::{self, EmittedMessage};
 
@@ -54,6 +73,12 @@ struct TelegramUpdate {
 
     /// Channel post (we ignore these for now).
     channel_post: Option<TelegramMessage>,
+
+    /// Inline keyboard button press.
+    callback_query: Option<TelegramCallbackQuery>,
+
+    /// Change to a user's reactions on a message.
+    message_reaction: Option<TelegramMessageReaction>,
 }
 
 /// Telegram Message object.
@@ -69,14 +94,73 @@ struct TelegramMessage {
     /// Chat the message belongs to.
     chat: TelegramChat,
 
+    /// Forum topic (or reply thread) the message belongs to.
+    message_thread_id: Option<i64>,
+
+    /// True if the message was sent to a forum topic.
+    is_topic_message: Option<bool>,
+
     /// Message text.
     text: Option<String>,
 
+    /// Caption for media messages.
+    caption: Option<String>,
+
+    /// Available sizes of a photo, smallest first.
+    photo: Option<Vec<PhotoSize>>,
+
+    /// General file.
+    document: Option<TelegramDocument>,
+
+    /// Voice note.
+    voice: Option<TelegramVoice>,
+
+    /// Audio file to be treated as music.
+    audio: Option<TelegramAudio>,
+
     /// Original message if this is a reply.
     reply_to_message: Option<Box<TelegramMessage>>,
 
-    /// Bot command entities (for /commands).
+    /// Mentions, commands, etc. in the text.
     entities: Option<Vec<MessageEntity>>,
+
+    /// Mentions, commands, etc. in the caption.
+    caption_entities: Option<Vec<MessageEntity>>,
+
+    /// Inline keyboard attached to the message.
+    reply_markup: Option<InlineKeyboardMarkup>,
+}
+
+/// Telegram CallbackQuery object (an inline keyboard button press).
+/// https://core.telegram.org/bots/api#callbackquery
+#[derive(Debug, Deserialize)]
+struct TelegramCallbackQuery {
+    /// Unique query identifier, needed to answer the query.
+    id: String,
+
+    /// User who pressed the button.
+    from: TelegramUser,
+
+    /// Message the keyboard was attached to (missing if it is too old).
+    message: Option<TelegramMessage>,
+
+    /// Data of the pressed button.
+    data: Option<String>,
+}
+
+/// Inline keyboard attached to a message.
+/// https://core.telegram.org/bots/api#inlinekeyboardmarkup
+#[derive(Debug, Deserialize)]
+struct InlineKeyboardMarkup {
+    inline_keyboard: Vec<Vec<InlineKeyboardButton>>,
+}
+
+/// One inline keyboard button.
+/// https://core.telegram.org/bots/api#inlinekeyboardbutton
+#[derive(Debug, Deserialize)]
+struct InlineKeyboardButton {
+    text: String,
+    callback_data: Option<String>,
 }
 
 /// Telegram User object.
@@ -99,6 +183,28 @@ struct TelegramUser {
     username: Option<String>,
 }
 
+/// Telegram MessageReactionUpdated object.
+/// https://core.telegram.org/bots/api#messagereactionupdated
+#[derive(Debug, Deserialize)]
+struct TelegramMessageReaction {
+    /// Chat containing the reacted message.
+    chat: TelegramChat,
+
+    /// Reacted message.
+    message_id: i64,
+
+    /// User who changed the reaction (missing for anonymous admins).
+    user: Option<TelegramUser>,
+
+    /// Reactions the user had before.
+    #[serde(default)]
+    old_reaction: Vec<reactions::ReactionType>,
+
+    /// Reactions the user has now.
+    #[serde(default)]
+    new_reaction: Vec<reactions::ReactionType>,
+}
+
 /// Telegram Chat object.
 /// https://core.telegram.org/bots/api#chat
 #[derive(Debug, Deserialize)]
@@ -135,6 +241,66 @@ struct MessageEntity {
     user: Option<TelegramUser>,
 }
 
+/// One size of a photo.
+/// https://core.telegram.org/bots/api#photosize
+#[derive(Debug, Deserialize)]
+struct PhotoSize {
+    file_id: String,
+    file_unique_id: String,
+    width: i64,
+    height: i64,
+    file_size: Option<i64>,
+}
+
+/// Telegram Document object.
+/// https://core.telegram.org/bots/api#document
+#[derive(Debug, Deserialize)]
+struct TelegramDocument {
+    file_id: String,
+    file_unique_id: String,
+    file_name: Option<String>,
+    mime_type: Option<String>,
+    file_size: Option<i64>,
+}
+
+/// Telegram Voice object.
+/// https://core.telegram.org/bots/api#voice
+#[derive(Debug, Deserialize)]
+struct TelegramVoice {
+    file_id: String,
+    file_unique_id: String,
+    /// Duration in seconds.
+    duration: i64,
+    mime_type: Option<String>,
+    file_size: Option<i64>,
+}
+
+/// Telegram Audio object.
+/// https://core.telegram.org/bots/api#audio
+#[derive(Debug, Deserialize)]
+struct TelegramAudio {
+    file_id: String,
+    file_unique_id: String,
+    /// Duration in seconds.
+    duration: i64,
+    performer: Option<String>,
+    title: Option<String>,
+    file_name: Option<String>,
+    mime_type: Option<String>,
+    file_size: Option<i64>,
+}
+
+/// Telegram File object returned by getFile.
+/// https://core.telegram.org/bots/api#file
+#[derive(Debug, Deserialize)]
+struct TelegramFile {
+    file_id: String,
+    file_unique_id: String,
+    file_size: Option<i64>,
+    /// Path for https://api.telegram.org/file/bot<token>/<file_path>.
+    file_path: Option<String>,
+}
+
 /// Telegram API response wrapper.
 #[derive(Debug, Deserialize)]
 struct TelegramApiResponse<T> {
@@ -157,8 +323,34 @@ struct SentMessage {
 /// Workspace path for storing polling state.
 const POLLING_STATE_PATH: &str = "state/last_update_id";
 
-/// Workspace path for persisting owner_id across WASM callbacks.
-const OWNER_ID_PATH: &str = "state/owner_id";
+/// Workspace path for persisting the allowlist across WASM callbacks.
+const ALLOWLIST_PATH: &str = "state/allowlist";
+
+/// Workspace path for persisting the group message policy.
+const GROUP_POLICY_PATH: &str = "state/group_policy";
+
+/// Workspace path for persisting the quiet hours policy.
+const QUIET_HOURS_PATH: &str = "state/quiet_hours";
+
+/// Workspace path for responses held back during quiet hours.
+const DEFERRED_RESPONSES_PATH: &str = "state/deferred_responses";
+
+/// Workspace path recording whether updates arrive by webhook, in which case
+/// `on_poll` only flushes deferred responses.
+const WEBHOOK_MODE_PATH: &str = "state/webhook_mode";
+
+/// Workspace path recording whether reactions are emitted as feedback.
+const REACTION_FEEDBACK_PATH: &str = "state/reaction_feedback";
+
+/// Workspace path for the IDs of the bot's recent messages.
+const SENT_MESSAGES_PATH: &str = "state/sent_messages";
+
+/// Largest file we download (the Bot API caps getFile at 20 MB, which is
+/// also the host's attachment limit).
+const MAX_MEDIA_BYTES: i64 = 20 * 1024 * 1024;
+
+/// Telegram's limit on a button's callback data, in bytes.
+const MAX_CALLBACK_DATA_BYTES: usize = 64;
 
 // ============================================================================
 // Channel Metadata
@@ -178,6 +370,125 @@ struct TelegramMessageMetadata {
 
     /// Whether this is a private (DM) chat.
     is_private: bool,
+
+    /// Forum topic to reply in, for supergroups with topics enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message_thread_id: Option<i64>,
+
+    /// Files received with the message.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    attachments: Vec<Attachment>,
+
+    /// Stored voice notes and audio files, read by the host to decide what
+    /// to transcribe.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    audio: Vec<AudioInfo>,
+
+    /// Rows of buttons to offer with a response (added by the agent).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    buttons: Vec<Vec<Button>>,
+
+    /// The button press this message came from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    button_press: Option<ButtonPress>,
+
+    /// The rating this message carries, read by the host as feedback
+    /// instead of a chat message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    feedback: Option<Feedback>,
+}
+
+/// A choice offered with a response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Button {
+    /// Label shown to the user.
+    text: String,
+
+    /// Sent back as the message content when pressed.
+    data: String,
+}
+
+/// A pressed inline keyboard button.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ButtonPress {
+    /// Label of the pressed button.
+    text: String,
+
+    /// Data of the pressed button.
+    data: String,
+}
+
+/// A rating of one of the bot's messages, expressed by a reaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Feedback {
+    rating: reactions::Rating,
+
+    /// The rated message.
+    message_id: i64,
+
+    /// The reaction.
+    emoji: String,
+}
+
+/// A received file, as described to the agent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Attachment {
+    /// photo, document, voice, or audio.
+    kind: String,
+
+    /// Telegram file ID (can be used to fetch the file again).
+    file_id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    file_name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mime_type: Option<String>,
+
+    /// Size in bytes, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<i64>,
+
+    /// Voice/audio duration in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duration: Option<i64>,
+
+    /// Host attachment the file was stored as.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    attachment_id: Option<String>,
+
+    /// Why the file could not be stored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// A stored audio attachment, in the host's cross-channel format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct AudioInfo {
+    /// Host attachment ID.
+    attachment_id: String,
+
+    /// Whether this is a voice note rather than an audio file.
+    voice: bool,
+
+    /// Duration in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duration: Option<i64>,
+}
+
+/// Describe the stored voice notes and audio files among `attachments`.
+fn audio_info(attachments: &[Attachment]) -> Vec<AudioInfo> {
+    attachments
+        .iter()
+        .filter(|a| a.kind == "voice" || a.kind == "audio")
+        .filter_map(|a| {
+            Some(AudioInfo {
+                attachment_id: a.attachment_id.clone()?,
+                voice: a.kind == "voice",
+                duration: a.duration,
+            })
+        })
+        .collect()
 }
 
 /// Channel configuration injected by host.
@@ -191,8 +502,16 @@ struct TelegramConfig {
     #[serde(default)]
     bot_username: Option<String>,
 
-    /// Telegram user ID of the bot owner. When set, only messages from this
-    /// user are processed. All others are silently dropped.
+    /// Telegram user IDs allowed to use the bot, in any chat.
+    #[serde(default)]
+    allowed_user_ids: Vec<i64>,
+
+    /// Chats (typically groups) whose members may all use the bot.
+    #[serde(default)]
+    allowed_chat_ids: Vec<i64>,
+
+    /// Single allowed user, from before `allowed_user_ids` existed. Treated
+    /// as one more entry in `allowed_user_ids`.
     #[serde(default)]
     owner_id: Option<i64>,
 
@@ -209,6 +528,22 @@ struct TelegramConfig {
     /// Telegram will include this in the X-Telegram-Bot-Api-Secret-Token header.
     #[serde(default)]
     webhook_secret: Option<String>,
+
+    /// Daily windows during which responses are queued instead of sent.
+    #[serde(default)]
+    quiet_hours: Vec<quiet::QuietWindow>,
+
+    /// Send responses marked `"urgent": true` even during quiet hours.
+    #[serde(default = "default_true")]
+    quiet_hours_bypass_urgent: bool,
+
+    /// Emit reactions on the bot's messages as feedback events.
+    #[serde(default = "default_true")]
+    reaction_feedback: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 // ============================================================================
@@ -236,30 +571,97 @@ impl Guest for TelegramChannel {
             );
         }
 
-        // Persist owner_id so subsequent callbacks (on_http_request, on_poll) can read it
-        if let Some(owner_id) = config.owner_id {
-            if let Err(e) = channel_host::workspace_write(OWNER_ID_PATH, &owner_id.to_string()) {
-                channel_host::log(
-                    channel_host::LogLevel::Error,
-                    &format!("Failed to persist owner_id: {}", e),
-                );
-            }
+        // Persist the allowlist so subsequent callbacks (on_http_request,
+        // on_poll) can read it. Always written, so a stale list from a
+        // previous config never lingers.
+        let allowlist = Allowlist::from_config(&config);
+        let serialized = serde_json::to_string(&allowlist).unwrap_or_default();
+        if let Err(e) = channel_host::workspace_write(ALLOWLIST_PATH, &serialized) {
             channel_host::log(
-                channel_host::LogLevel::Info,
-                &format!("Owner restriction enabled: user {}", owner_id),
+                channel_host::LogLevel::Error,
+                &format!("Failed to persist allowlist: {}", e),
+            );
+        }
+        if allowlist.is_open() {
+            channel_host::log(
+                channel_host::LogLevel::Warn,
+                "No allowed users or chats configured, bot is open to all users",
             );
         } else {
-            // Clear any stale owner_id from a previous config
-            let _ = channel_host::workspace_write(OWNER_ID_PATH, "");
+            channel_host::log(
+                channel_host::LogLevel::Info,
+                &format!(
+                    "Allowlist enabled: {} users, {} chats",
+                    allowlist.user_ids.len(),
+                    allowlist.chat_ids.len()
+                ),
+            );
+        }
+
+        let group_policy = GroupPolicy {
+            bot_username: config.bot_username.clone(),
+            respond_to_all: config.respond_to_all_group_messages,
+        };
+        let serialized = serde_json::to_string(&group_policy).unwrap_or_default();
+        if let Err(e) = channel_host::workspace_write(GROUP_POLICY_PATH, &serialized) {
+            channel_host::log(
+                channel_host::LogLevel::Error,
+                &format!("Failed to persist group policy: {}", e),
+            );
+        }
+        if group_policy.bot_username.is_none() && !group_policy.respond_to_all {
             channel_host::log(
                 channel_host::LogLevel::Warn,
-                "No owner_id configured, bot is open to all users",
+                "No bot_username configured, @mentions in groups will be ignored",
+            );
+        }
+
+        let (windows, invalid): (Vec<_>, Vec<_>) = config
+            .quiet_hours
+            .iter()
+            .cloned()
+            .partition(quiet::QuietWindow::is_valid);
+        for window in invalid {
+            channel_host::log(
+                channel_host::LogLevel::Error,
+                &format!(
+                    "Ignoring quiet hours window {}-{}: times must be HH:MM",
+                    window.start, window.end
+                ),
+            );
+        }
+        let quiet_hours = quiet::QuietHours {
+            windows,
+            bypass_urgent: config.quiet_hours_bypass_urgent,
+        };
+        let serialized = serde_json::to_string(&quiet_hours).unwrap_or_default();
+        if let Err(e) = channel_host::workspace_write(QUIET_HOURS_PATH, &serialized) {
+            channel_host::log(
+                channel_host::LogLevel::Error,
+                &format!("Failed to persist quiet hours: {}", e),
+            );
+        }
+
+        if let Err(e) = channel_host::workspace_write(
+            REACTION_FEEDBACK_PATH,
+            &config.reaction_feedback.to_string(),
+        ) {
+            channel_host::log(
+                channel_host::LogLevel::Error,
+                &format!("Failed to persist reaction feedback setting: {}", e),
             );
         }
 
         // Mode is determined by whether the host injected a tunnel_url
         // If tunnel is configured, use webhooks. Otherwise, use polling.
         let webhook_mode = config.tunnel_url.is_some();
+        if let Err(e) = channel_host::workspace_write(WEBHOOK_MODE_PATH, &webhook_mode.to_string())
+        {
+            channel_host::log(
+                channel_host::LogLevel::Error,
+                &format!("Failed to persist webhook mode: {}", e),
+            );
+        }
 
         if webhook_mode {
             channel_host::log(
@@ -297,8 +699,9 @@ impl Guest for TelegramChannel {
             }
         }
 
-        // Configure polling only if not in webhook mode
-        let poll = if !webhook_mode {
+        // Poll for updates unless in webhook mode; quiet hours need the
+        // ticks either way to flush deferred responses
+        let poll = if !webhook_mode || quiet_hours.is_enabled() {
             Some(PollConfig {
                 interval_ms: 30000, // 30 seconds minimum
                 enabled: true,
@@ -318,6 +721,14 @@ impl Guest for TelegramChannel {
                 require_secret,
             }],
             poll,
+            // Markdown is converted to MarkdownV2 here, in the format module
+            formatting: FormattingCapabilities {
+                markdown: MarkdownFlavor::Markdown,
+                max_message_length: Some(format::MAX_MESSAGE_LEN as u32),
+                supports_buttons: true,
+                supports_threads: false,
+                supports_files: false,
+            },
         })
     }
 
@@ -366,6 +777,13 @@ impl Guest for TelegramChannel {
     }
 
     fn on_poll() {
+        flush_deferred_responses();
+
+        // Webhook mode only polls to flush quiet hours
+        if channel_host::workspace_read(WEBHOOK_MODE_PATH).as_deref() == Some("true") {
+            return;
+        }
+
         // Read last offset from workspace storage
         let offset = match channel_host::workspace_read(POLLING_STATE_PATH) {
             Some(s) => s.parse::<i64>().unwrap_or(0),
@@ -380,9 +798,9 @@ impl Guest for TelegramChannel {
         // Build getUpdates URL with parameters
         // - offset: Identifier of the first update to be returned
         // - timeout: Long polling timeout in seconds (Telegram recommends 30+)
-        // - allowed_updates: Only get message updates
+        // - allowed_updates: Only get message updates and button presses
         let url = format!(
-            "https://api.telegram.org/bot{{TELEGRAM_BOT_TOKEN}}/getUpdates?offset={}&timeout=30&allowed_updates=[\"message\",\"edited_message\"]",
+            "https://api.telegram.org/bot{{TELEGRAM_BOT_TOKEN}}/getUpdates?offset={}&timeout=30&allowed_updates=[\"message\",\"edited_message\",\"callback_query\",\"message_reaction\"]",
             offset
         );
 
@@ -465,69 +883,29 @@ impl Guest for TelegramChannel {
         let metadata: TelegramMessageMetadata = serde_json::from_str(&response.metadata_json)
             .map_err(|e| format!("Failed to parse metadata: {}", e))?;
 
-        // Build sendMessage payload
-        let mut payload = serde_json::json!({
-            "chat_id": metadata.chat_id,
-            "text": response.content,
-            "parse_mode": "Markdown",
-        });
-
-        // Reply to the original message for context
-        payload["reply_to_message_id"] = serde_json::Value::Number(metadata.message_id.into());
-
-        let payload_bytes = serde_json::to_vec(&payload)
-            .map_err(|e| format!("Failed to serialize payload: {}", e))?;
-
-        // Make HTTP request to Telegram API
-        // The bot token is injected into the URL by the host
-        let headers = serde_json::json!({
-            "Content-Type": "application/json"
-        });
-
-        let result = channel_host::http_request(
-            "POST",
-            "https://api.telegram.org/bot{TELEGRAM_BOT_TOKEN}/sendMessage",
-            &headers.to_string(),
-            Some(&payload_bytes),
-        );
-
-        match result {
-            Ok(http_response) => {
-                if http_response.status != 200 {
-                    let body_str = String::from_utf8_lossy(&http_response.body);
-                    return Err(format!(
-                        "Telegram API returned status {}: {}",
-                        http_response.status, body_str
-                    ));
-                }
-
-                // Parse Telegram response
-                let api_response: TelegramApiResponse<SentMessage> =
-                    serde_json::from_slice(&http_response.body)
-                        .map_err(|e| format!("Failed to parse Telegram response: {}", e))?;
-
-                if !api_response.ok {
-                    return Err(format!(
-                        "Telegram API error: {}",
-                        api_response
-                            .description
-                            .unwrap_or_else(|| "unknown".to_string())
-                    ));
-                }
-
-                channel_host::log(
-                    channel_host::LogLevel::Debug,
-                    &format!(
-                        "Sent message to chat {}: message_id={}",
-                        metadata.chat_id,
-                        api_response.result.map(|r| r.message_id).unwrap_or(0)
-                    ),
-                );
-
-                Ok(())
-            }
-            Err(e) => Err(format!("HTTP request failed: {}", e)),
+        let now = channel_host::now_millis();
+        if load_quiet_hours().should_defer(metadata.chat_id, &response.metadata_json, now) {
+            let mut deferred = load_deferred_responses();
+            deferred.push(quiet::DeferredResponse {
+                chat_id: metadata.chat_id,
+                content: response.content,
+                metadata_json: response.metadata_json,
+                deferred_at: now,
+                attempts: 0,
+            });
+            save_deferred_responses(&deferred)?;
+            channel_host::log(
+                channel_host::LogLevel::Info,
+                &format!(
+                    "Quiet hours in chat {}, response deferred ({} queued)",
+                    metadata.chat_id,
+                    deferred.len()
+                ),
+            );
+            return Ok(());
         }
+
+        send_response(&response.content, &metadata)
     }
 
     fn on_status(update: StatusUpdate) {
@@ -548,11 +926,20 @@ impl Guest for TelegramChannel {
             }
         };
 
+        // No typing indicator while the response itself would be held back
+        let now = channel_host::now_millis();
+        if load_quiet_hours().should_defer(metadata.chat_id, &update.metadata_json, now) {
+            return;
+        }
+
         // POST /sendChatAction with action "typing"
-        let payload = serde_json::json!({
+        let mut payload = serde_json::json!({
             "chat_id": metadata.chat_id,
             "action": "typing"
         });
+        if let Some(thread_id) = metadata.message_thread_id {
+            payload["message_thread_id"] = serde_json::Value::Number(thread_id.into());
+        }
 
         let payload_bytes = match serde_json::to_vec(&payload) {
             Ok(b) => b,
@@ -586,6 +973,166 @@ impl Guest for TelegramChannel {
     }
 }
 
+// ============================================================================
+// Sending Responses
+// ============================================================================
+
+/// Send a response to the chat in `metadata`, split to fit Telegram's limit.
+fn send_response(content: &str, metadata: &TelegramMessageMetadata) -> Result<(), String> {
+    let pieces = format::split_message(content, format::MAX_MESSAGE_LEN);
+    let last = pieces.len() - 1;
+
+    // Pieces go out in order; a failure stops the rest so the user never
+    // sees a response with a hole in the middle
+    for (i, piece) in pieces.iter().enumerate() {
+        let mut payload = serde_json::json!({
+            "chat_id": metadata.chat_id,
+            "text": format::to_markdown_v2(piece),
+            "parse_mode": "MarkdownV2",
+        });
+
+        if let Some(thread_id) = metadata.message_thread_id {
+            payload["message_thread_id"] = serde_json::Value::Number(thread_id.into());
+        }
+
+        // Reply to the original message for context
+        if i == 0 {
+            payload["reply_to_message_id"] = serde_json::Value::Number(metadata.message_id.into());
+        }
+
+        // Offer choices under the final piece
+        if i == last && !metadata.buttons.is_empty() {
+            payload["reply_markup"] = inline_keyboard(&metadata.buttons);
+        }
+
+        // If the conversion still produced something Telegram can't
+        // parse, send the piece as plain text rather than not at all
+        let message_id = match send_message(&payload) {
+            Err(e) if e.contains("can't parse entities") => {
+                channel_host::log(
+                    channel_host::LogLevel::Warn,
+                    &format!("MarkdownV2 rejected, sending plain text: {}", e),
+                );
+                payload["text"] = serde_json::Value::String(piece.clone());
+                if let Some(obj) = payload.as_object_mut() {
+                    obj.remove("parse_mode");
+                }
+                send_message(&payload)?
+            }
+            result => result?,
+        };
+
+        record_sent_message(metadata.chat_id, message_id);
+
+        channel_host::log(
+            channel_host::LogLevel::Debug,
+            &format!(
+                "Sent message {}/{} to chat {}: message_id={}",
+                i + 1,
+                pieces.len(),
+                metadata.chat_id,
+                message_id
+            ),
+        );
+    }
+
+    Ok(())
+}
+
+/// Send deferred responses whose chat is no longer in quiet hours.
+///
+/// A response that fails to send is retried on later ticks, up to
+/// [`quiet::MAX_FLUSH_ATTEMPTS`] times.
+fn flush_deferred_responses() {
+    let deferred = load_deferred_responses();
+    if deferred.is_empty() {
+        return;
+    }
+
+    let quiet_hours = load_quiet_hours();
+    let now = channel_host::now_millis();
+    let mut remaining = Vec::new();
+    for mut response in deferred {
+        if quiet_hours.is_quiet(response.chat_id, now) {
+            remaining.push(response);
+            continue;
+        }
+
+        let result = serde_json::from_str::<TelegramMessageMetadata>(&response.metadata_json)
+            .map_err(|e| format!("Failed to parse metadata: {}", e))
+            .and_then(|metadata| send_response(&response.content, &metadata));
+        match result {
+            Ok(()) => channel_host::log(
+                channel_host::LogLevel::Debug,
+                &format!(
+                    "Sent response deferred {}s for quiet hours in chat {}",
+                    now.saturating_sub(response.deferred_at) / 1000,
+                    response.chat_id
+                ),
+            ),
+            Err(e) => {
+                response.attempts += 1;
+                if response.attempts < quiet::MAX_FLUSH_ATTEMPTS {
+                    remaining.push(response);
+                } else {
+                    channel_host::log(
+                        channel_host::LogLevel::Error,
+                        &format!(
+                            "Dropping deferred response to chat {} after {} attempts: {}",
+                            response.chat_id, response.attempts, e
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    if let Err(e) = save_deferred_responses(&remaining) {
+        channel_host::log(channel_host::LogLevel::Error, &e);
+    }
+}
+
+fn load_quiet_hours() -> quiet::QuietHours {
+    channel_host::workspace_read(QUIET_HOURS_PATH)
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn load_deferred_responses() -> Vec<quiet::DeferredResponse> {
+    channel_host::workspace_read(DEFERRED_RESPONSES_PATH)
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Remember a message the bot sent, so reactions to it count as feedback.
+fn record_sent_message(chat_id: i64, message_id: i64) {
+    if channel_host::workspace_read(REACTION_FEEDBACK_PATH).as_deref() == Some("false") {
+        return;
+    }
+    let mut sent = load_sent_messages();
+    sent.record(chat_id, message_id);
+    let serialized = serde_json::to_string(&sent).unwrap_or_default();
+    if let Err(e) = channel_host::workspace_write(SENT_MESSAGES_PATH, &serialized) {
+        channel_host::log(
+            channel_host::LogLevel::Warn,
+            &format!("Failed to record sent message: {}", e),
+        );
+    }
+}
+
+fn load_sent_messages() -> reactions::SentMessages {
+    channel_host::workspace_read(SENT_MESSAGES_PATH)
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_deferred_responses(deferred: &[quiet::DeferredResponse]) -> Result<(), String> {
+    let serialized = serde_json::to_string(deferred)
+        .map_err(|e| format!("Failed to serialize deferred responses: {}", e))?;
+    channel_host::workspace_write(DEFERRED_RESPONSES_PATH, &serialized)
+        .map_err(|e| format!("Failed to save deferred responses: {}", e))
+}
+
 // ============================================================================
 // Webhook Management
 // ============================================================================
@@ -645,7 +1192,7 @@ fn register_webhook(tunnel_url: &str, webhook_secret: Option<&str>) -> Result<()
     // Build setWebhook request body
     let mut body = serde_json::json!({
         "url": webhook_url,
-        "allowed_updates": ["message", "edited_message"]
+        "allowed_updates": ["message", "edited_message", "callback_query", "message_reaction"]
     });
 
     if let Some(secret) = webhook_secret {
@@ -715,18 +1262,32 @@ fn handle_update(update: TelegramUpdate) {
     if let Some(message) = update.edited_message {
         handle_message(message);
     }
+
+    if let Some(query) = update.callback_query {
+        handle_callback_query(query);
+    }
+
+    if let Some(reaction) = update.message_reaction {
+        handle_reaction(reaction);
+    }
 }
 
 /// Process a single message.
 fn handle_message(message: TelegramMessage) {
-    // Skip messages without text
-    let text = match message.text {
-        Some(t) if !t.is_empty() => t,
-        _ => return,
-    };
+    let media = media_of(&message);
+
+    // Skip messages with neither text nor media
+    let text = message
+        .text
+        .clone()
+        .or_else(|| message.caption.clone())
+        .unwrap_or_default();
+    if text.is_empty() && media.is_none() {
+        return;
+    }
 
     // Skip messages without a sender (channel posts)
-    let from = match message.from {
+    let from = match &message.from {
         Some(f) => f,
         None => return,
     };
@@ -736,36 +1297,31 @@ fn handle_message(message: TelegramMessage) {
         return;
     }
 
-    // Owner validation: silently drop messages from non-owner users
-    if let Some(owner_id_str) = channel_host::workspace_read(OWNER_ID_PATH) {
-        if !owner_id_str.is_empty() {
-            if let Ok(owner_id) = owner_id_str.parse::<i64>() {
-                if from.id != owner_id {
-                    channel_host::log(
-                        channel_host::LogLevel::Debug,
-                        &format!(
-                            "Dropping message from non-owner user {} (owner: {})",
-                            from.id, owner_id
-                        ),
-                    );
-                    return;
-                }
-            }
-        }
+    // Allowlist validation: silently drop messages from anyone else
+    if !is_allowed(from.id, message.chat.id) {
+        return;
     }
 
     let is_private = message.chat.chat_type == "private";
 
-    // For group chats, check if the bot was mentioned
-    // TODO: Read bot_username from config and check mentions
-    // For now, process all messages in private chats and groups
+    // In groups, only respond when addressed, unless configured otherwise
     if !is_private {
-        // In groups, only respond if there's a bot mention or command
-        // This is a simplified check - proper implementation would use entities
-        let has_command = text.starts_with('/');
-        let has_mention = text.contains('@');
+        let policy: GroupPolicy = channel_host::workspace_read(GROUP_POLICY_PATH)
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        let entities = if message.text.is_some() {
+            message.entities.as_deref()
+        } else {
+            message.caption_entities.as_deref()
+        };
+        let addressed = policy.respond_to_all
+            || is_addressed_to_bot(
+                &text,
+                entities.unwrap_or_default(),
+                policy.bot_username.as_deref(),
+            );
 
-        if !has_command && !has_mention {
+        if !addressed {
             channel_host::log(
                 channel_host::LogLevel::Debug,
                 &format!("Ignoring group message without mention: {}", text),
@@ -774,6 +1330,11 @@ fn handle_message(message: TelegramMessage) {
         }
     }
 
+    // Download media only once we know the message will be processed
+    let (attachments, stored): (Vec<Attachment>, Vec<Option<channel_host::Attachment>>) =
+        media.into_iter().map(store_media).unzip();
+    let stored: Vec<channel_host::Attachment> = stored.into_iter().flatten().collect();
+
     // Build user display name
     let user_name = if let Some(ref last) = from.last_name {
         format!("{} {}", from.first_name, last)
@@ -787,6 +1348,12 @@ fn handle_message(message: TelegramMessage) {
         message_id: message.message_id,
         user_id: from.id,
         is_private,
+        message_thread_id: forum_topic(&message),
+        audio: audio_info(&attachments),
+        attachments,
+        buttons: Vec::new(),
+        button_press: None,
+        feedback: None,
     };
 
     let metadata_json = serde_json::to_string(&metadata).unwrap_or_else(|_| "{}".to_string());
@@ -794,7 +1361,16 @@ fn handle_message(message: TelegramMessage) {
     // Clean the message text (strip bot mentions and commands)
     let cleaned_text = clean_message_text(&text);
 
-    if cleaned_text.is_empty() {
+    // The host describes stored files to the agent; files that couldn't be
+    // stored still need a mention so the agent can tell the user
+    let cleaned_text = match metadata.attachments.first() {
+        Some(attachment) if attachment.attachment_id.is_some() => cleaned_text,
+        Some(attachment) if cleaned_text.is_empty() => describe_attachment(attachment),
+        Some(attachment) => format!("{}\n{}", describe_attachment(attachment), cleaned_text),
+        None => cleaned_text,
+    };
+
+    if cleaned_text.is_empty() && stored.is_empty() {
         return;
     }
 
@@ -803,8 +1379,9 @@ fn handle_message(message: TelegramMessage) {
         user_id: from.id.to_string(),
         user_name: Some(user_name),
         content: cleaned_text,
-        thread_id: None, // Telegram doesn't have threads in the same way
+        thread_id: conversation_thread(&metadata),
         metadata_json,
+        attachments: stored,
     });
 
     channel_host::log(
@@ -816,6 +1393,624 @@ fn handle_message(message: TelegramMessage) {
     );
 }
 
+/// Who may use the bot.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Allowlist {
+    #[serde(default)]
+    user_ids: Vec<i64>,
+    #[serde(default)]
+    chat_ids: Vec<i64>,
+}
+
+impl Allowlist {
+    fn from_config(config: &TelegramConfig) -> Self {
+        let mut user_ids = config.allowed_user_ids.clone();
+        if let Some(owner_id) = config.owner_id {
+            if !user_ids.contains(&owner_id) {
+                user_ids.push(owner_id);
+            }
+        }
+        Self {
+            user_ids,
+            chat_ids: config.allowed_chat_ids.clone(),
+        }
+    }
+
+    /// Everyone is allowed until a user or chat is configured.
+    fn is_open(&self) -> bool {
+        self.user_ids.is_empty() && self.chat_ids.is_empty()
+    }
+
+    /// An allowed user may use the bot anywhere; anyone may use it in an
+    /// allowed chat.
+    fn allows(&self, user_id: i64, chat_id: i64) -> bool {
+        self.is_open() || self.user_ids.contains(&user_id) || self.chat_ids.contains(&chat_id)
+    }
+}
+
+/// How the bot treats messages in group chats.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct GroupPolicy {
+    /// Bot username (without @) that mentions and commands are matched against.
+    #[serde(default)]
+    bot_username: Option<String>,
+    /// Respond to every group message, not just those addressed to the bot.
+    #[serde(default)]
+    respond_to_all: bool,
+}
+
+/// Whether a group message mentions the bot or carries a command for it.
+///
+/// Commands without an `@username` suffix count, since Telegram only
+/// delivers them to bots that may handle them. Without a configured
+/// username, mentions and suffixed commands can't be matched and are ignored.
+fn is_addressed_to_bot(text: &str, entities: &[MessageEntity], bot_username: Option<&str>) -> bool {
+    let is_bot = |name: &str| bot_username.is_some_and(|bot| name.eq_ignore_ascii_case(bot));
+
+    entities
+        .iter()
+        .any(|entity| match entity.entity_type.as_str() {
+            "mention" => entity_text(text, entity)
+                .and_then(|mention| mention.strip_prefix('@'))
+                .is_some_and(is_bot),
+            "text_mention" => entity
+                .user
+                .as_ref()
+                .and_then(|user| user.username.as_deref())
+                .is_some_and(is_bot),
+            "bot_command" => {
+                entity_text(text, entity).is_some_and(|command| match command.split_once('@') {
+                    Some((_, target)) => is_bot(target),
+                    None => true,
+                })
+            }
+            _ => false,
+        })
+}
+
+/// The part of `text` an entity covers. Entity offsets are in UTF-16 code
+/// units; `None` if they don't fall on character boundaries.
+fn entity_text<'a>(text: &'a str, entity: &MessageEntity) -> Option<&'a str> {
+    let (offset, length) = (
+        usize::try_from(entity.offset).ok()?,
+        usize::try_from(entity.length).ok()?,
+    );
+    let byte_index = |utf16_index: usize| {
+        let mut units = 0;
+        for (i, c) in text.char_indices() {
+            if units == utf16_index {
+                return Some(i);
+            }
+            units += c.len_utf16();
+        }
+        (units == utf16_index).then_some(text.len())
+    };
+    let start = byte_index(offset)?;
+    let end = byte_index(offset.checked_add(length)?)?;
+    text.get(start..end)
+}
+
+/// The forum topic a message was posted in, if any.
+///
+/// Replies in ordinary groups carry a `message_thread_id` too, but sending
+/// one to a chat without topics fails, so only forum topics count.
+fn forum_topic(message: &TelegramMessage) -> Option<i64> {
+    message
+        .message_thread_id
+        .filter(|_| message.is_topic_message == Some(true))
+}
+
+/// Each forum topic is its own conversation. Topic IDs are only unique
+/// within a chat.
+fn conversation_thread(metadata: &TelegramMessageMetadata) -> Option<String> {
+    metadata
+        .message_thread_id
+        .map(|thread_id| format!("{}:{}", metadata.chat_id, thread_id))
+}
+
+/// Whether updates from `user_id` in `chat_id` should be processed.
+fn is_allowed(user_id: i64, chat_id: i64) -> bool {
+    let allowlist: Allowlist = channel_host::workspace_read(ALLOWLIST_PATH)
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    if allowlist.allows(user_id, chat_id) {
+        return true;
+    }
+    channel_host::log(
+        channel_host::LogLevel::Debug,
+        &format!(
+            "Dropping update from user {} in chat {} (not allowed)",
+            user_id, chat_id
+        ),
+    );
+    false
+}
+
+/// Process an inline keyboard button press.
+///
+/// The button's data is emitted as the user's reply, so a press on an
+/// approval prompt reads exactly like typing "yes". The keyboard is removed
+/// so the same question can't be answered twice.
+fn handle_callback_query(query: TelegramCallbackQuery) {
+    let (Some(data), Some(message)) = (query.data, query.message) else {
+        answer_callback_query(&query.id, None);
+        return;
+    };
+    if query.from.is_bot || !is_allowed(query.from.id, message.chat.id) {
+        answer_callback_query(&query.id, None);
+        return;
+    }
+
+    let text = button_label(&message, &data).unwrap_or_else(|| data.clone());
+    answer_callback_query(&query.id, Some(&text));
+    remove_keyboard(message.chat.id, message.message_id);
+
+    let from = query.from;
+    let user_name = match from.last_name {
+        Some(ref last) => format!("{} {}", from.first_name, last),
+        None => from.first_name.clone(),
+    };
+
+    let metadata = TelegramMessageMetadata {
+        chat_id: message.chat.id,
+        message_id: message.message_id,
+        user_id: from.id,
+        is_private: message.chat.chat_type == "private",
+        message_thread_id: forum_topic(&message),
+        attachments: Vec::new(),
+        audio: Vec::new(),
+        buttons: Vec::new(),
+        button_press: Some(ButtonPress {
+            text,
+            data: data.clone(),
+        }),
+        feedback: None,
+    };
+    let metadata_json = serde_json::to_string(&metadata).unwrap_or_else(|_| "{}".to_string());
+
+    channel_host::emit_message(&EmittedMessage {
+        user_id: from.id.to_string(),
+        user_name: Some(user_name),
+        content: data,
+        thread_id: conversation_thread(&metadata),
+        metadata_json,
+        attachments: Vec::new(),
+    });
+
+    channel_host::log(
+        channel_host::LogLevel::Debug,
+        &format!(
+            "Emitted button press from user {} in chat {}",
+            from.id, message.chat.id
+        ),
+    );
+}
+
+/// Process a reaction change, emitting a feedback event when a user rates
+/// one of the bot's messages.
+fn handle_reaction(reaction: TelegramMessageReaction) {
+    if channel_host::workspace_read(REACTION_FEEDBACK_PATH).as_deref() == Some("false") {
+        return;
+    }
+    let Some(user) = reaction.user else {
+        return;
+    };
+    if user.is_bot || !is_allowed(user.id, reaction.chat.id) {
+        return;
+    }
+    if !load_sent_messages().contains(reaction.chat.id, reaction.message_id) {
+        return;
+    }
+    let Some((rating, emoji)) =
+        reactions::added_rating(&reaction.old_reaction, &reaction.new_reaction)
+    else {
+        return;
+    };
+
+    let user_name = match user.last_name {
+        Some(ref last) => format!("{} {}", user.first_name, last),
+        None => user.first_name.clone(),
+    };
+
+    let metadata = TelegramMessageMetadata {
+        chat_id: reaction.chat.id,
+        message_id: reaction.message_id,
+        user_id: user.id,
+        is_private: reaction.chat.chat_type == "private",
+        message_thread_id: None,
+        attachments: Vec::new(),
+        audio: Vec::new(),
+        buttons: Vec::new(),
+        button_press: None,
+        feedback: Some(Feedback {
+            rating,
+            message_id: reaction.message_id,
+            emoji: emoji.clone(),
+        }),
+    };
+    let metadata_json = serde_json::to_string(&metadata).unwrap_or_else(|_| "{}".to_string());
+
+    channel_host::emit_message(&EmittedMessage {
+        user_id: user.id.to_string(),
+        user_name: Some(user_name),
+        content: format!("[feedback: {} {}]", rating.as_str(), emoji),
+        thread_id: None,
+        metadata_json,
+        attachments: Vec::new(),
+    });
+
+    channel_host::log(
+        channel_host::LogLevel::Debug,
+        &format!(
+            "Emitted {} feedback from user {} on message {} in chat {}",
+            rating.as_str(),
+            user.id,
+            reaction.message_id,
+            reaction.chat.id
+        ),
+    );
+}
+
+/// Label of the button with `data` on the message's keyboard.
+fn button_label(message: &TelegramMessage, data: &str) -> Option<String> {
+    message
+        .reply_markup
+        .as_ref()?
+        .inline_keyboard
+        .iter()
+        .flatten()
+        .find(|b| b.callback_data.as_deref() == Some(data))
+        .map(|b| b.text.clone())
+}
+
+// ============================================================================
+// Bot API Calls
+// ============================================================================
+
+/// Send a message, returning its ID.
+fn send_message(payload: &serde_json::Value) -> Result<i64, String> {
+    let payload_bytes =
+        serde_json::to_vec(payload).map_err(|e| format!("Failed to serialize payload: {}", e))?;
+
+    // Make HTTP request to Telegram API
+    // The bot token is injected into the URL by the host
+    let headers = serde_json::json!({
+        "Content-Type": "application/json"
+    });
+
+    let http_response = channel_host::http_request(
+        "POST",
+        "https://api.telegram.org/bot{TELEGRAM_BOT_TOKEN}/sendMessage",
+        &headers.to_string(),
+        Some(&payload_bytes),
+    )
+    .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    if http_response.status != 200 {
+        let body_str = String::from_utf8_lossy(&http_response.body);
+        return Err(format!(
+            "Telegram API returned status {}: {}",
+            http_response.status, body_str
+        ));
+    }
+
+    // Parse Telegram response
+    let api_response: TelegramApiResponse<SentMessage> =
+        serde_json::from_slice(&http_response.body)
+            .map_err(|e| format!("Failed to parse Telegram response: {}", e))?;
+
+    if !api_response.ok {
+        return Err(format!(
+            "Telegram API error: {}",
+            api_response
+                .description
+                .unwrap_or_else(|| "unknown".to_string())
+        ));
+    }
+
+    Ok(api_response.result.map(|r| r.message_id).unwrap_or(0))
+}
+
+/// Call a Bot API method whose result we don't need. Failures are logged.
+fn call_best_effort(method: &str, payload: &serde_json::Value) {
+    let Ok(payload_bytes) = serde_json::to_vec(payload) else {
+        return;
+    };
+    let headers = serde_json::json!({
+        "Content-Type": "application/json"
+    });
+    let url = format!(
+        "https://api.telegram.org/bot{{TELEGRAM_BOT_TOKEN}}/{}",
+        method
+    );
+
+    let error = match channel_host::http_request(
+        "POST",
+        &url,
+        &headers.to_string(),
+        Some(&payload_bytes),
+    ) {
+        Ok(response) if response.status == 200 => return,
+        Ok(response) => format!("HTTP {}", response.status),
+        Err(e) => e,
+    };
+    channel_host::log(
+        channel_host::LogLevel::Debug,
+        &format!("{} failed: {}", method, error),
+    );
+}
+
+/// Acknowledge a button press so the client stops its progress indicator,
+/// optionally showing `text` as a notification.
+fn answer_callback_query(query_id: &str, text: Option<&str>) {
+    let mut payload = serde_json::json!({ "callback_query_id": query_id });
+    if let Some(text) = text {
+        payload["text"] = serde_json::Value::String(text.to_string());
+    }
+    call_best_effort("answerCallbackQuery", &payload);
+}
+
+/// Remove the inline keyboard from a message.
+fn remove_keyboard(chat_id: i64, message_id: i64) {
+    call_best_effort(
+        "editMessageReplyMarkup",
+        &serde_json::json!({
+            "chat_id": chat_id,
+            "message_id": message_id,
+            "reply_markup": { "inline_keyboard": [] },
+        }),
+    );
+}
+
+/// Build a `reply_markup` inline keyboard from rows of buttons.
+fn inline_keyboard(rows: &[Vec<Button>]) -> serde_json::Value {
+    let rows: Vec<Vec<serde_json::Value>> = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|button| {
+                    serde_json::json!({
+                        "text": button.text,
+                        "callback_data": truncate_bytes(&button.data, MAX_CALLBACK_DATA_BYTES),
+                    })
+                })
+                .collect()
+        })
+        .filter(|row: &Vec<serde_json::Value>| !row.is_empty())
+        .collect();
+    serde_json::json!({ "inline_keyboard": rows })
+}
+
+/// Truncate to at most `max` bytes at a char boundary.
+fn truncate_bytes(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+// ============================================================================
+// Media Handling
+// ============================================================================
+
+/// A file referenced by a message, before download.
+#[derive(Debug, PartialEq)]
+struct MediaRef {
+    kind: &'static str,
+    file_id: String,
+    file_unique_id: String,
+    file_name: Option<String>,
+    mime_type: Option<String>,
+    size: Option<i64>,
+    duration: Option<i64>,
+}
+
+/// The file attached to a message, if any.
+///
+/// For photos Telegram sends several sizes; the largest is last.
+fn media_of(message: &TelegramMessage) -> Option<MediaRef> {
+    if let Some(photo) = message.photo.as_ref().and_then(|sizes| sizes.last()) {
+        return Some(MediaRef {
+            kind: "photo",
+            file_id: photo.file_id.clone(),
+            file_unique_id: photo.file_unique_id.clone(),
+            file_name: None,
+            mime_type: Some("image/jpeg".to_string()),
+            size: photo.file_size,
+            duration: None,
+        });
+    }
+    if let Some(ref doc) = message.document {
+        return Some(MediaRef {
+            kind: "document",
+            file_id: doc.file_id.clone(),
+            file_unique_id: doc.file_unique_id.clone(),
+            file_name: doc.file_name.clone(),
+            mime_type: doc.mime_type.clone(),
+            size: doc.file_size,
+            duration: None,
+        });
+    }
+    if let Some(ref voice) = message.voice {
+        return Some(MediaRef {
+            kind: "voice",
+            file_id: voice.file_id.clone(),
+            file_unique_id: voice.file_unique_id.clone(),
+            file_name: None,
+            mime_type: voice.mime_type.clone(),
+            size: voice.file_size,
+            duration: Some(voice.duration),
+        });
+    }
+    if let Some(ref audio) = message.audio {
+        return Some(MediaRef {
+            kind: "audio",
+            file_id: audio.file_id.clone(),
+            file_unique_id: audio.file_unique_id.clone(),
+            file_name: audio.file_name.clone().or_else(|| audio.title.clone()),
+            mime_type: audio.mime_type.clone(),
+            size: audio.file_size,
+            duration: Some(audio.duration),
+        });
+    }
+    None
+}
+
+/// Download a file and store it as a host attachment.
+///
+/// Failures are recorded on the attachment rather than dropping the message,
+/// so the agent can still tell the user what went wrong.
+fn store_media(media: MediaRef) -> (Attachment, Option<channel_host::Attachment>) {
+    let mut attachment = Attachment {
+        kind: media.kind.to_string(),
+        file_id: media.file_id.clone(),
+        file_name: media.file_name.clone(),
+        mime_type: media.mime_type.clone(),
+        size: media.size,
+        duration: media.duration,
+        attachment_id: None,
+        error: None,
+    };
+
+    if media.size.is_some_and(|size| size > MAX_MEDIA_BYTES) {
+        attachment.error = Some(format!(
+            "file is larger than {} MB",
+            MAX_MEDIA_BYTES / (1024 * 1024)
+        ));
+        return (attachment, None);
+    }
+
+    let result = download_file(&media.file_id).and_then(|(file, bytes)| {
+        let name = media_name(&media, file.file_path.as_deref());
+        channel_host::store_attachment(&name, &media_mime_type(&media), &bytes)
+    });
+
+    match result {
+        Ok(stored) => {
+            channel_host::log(
+                channel_host::LogLevel::Debug,
+                &format!(
+                    "Stored {} ({} bytes) as {}",
+                    media.kind, stored.size, stored.id
+                ),
+            );
+            attachment.size = Some(stored.size as i64);
+            attachment.attachment_id = Some(stored.id.clone());
+            (attachment, Some(stored))
+        }
+        Err(e) => {
+            channel_host::log(
+                channel_host::LogLevel::Error,
+                &format!("Failed to store {} {}: {}", media.kind, media.file_id, e),
+            );
+            attachment.error = Some(e);
+            (attachment, None)
+        }
+    }
+}
+
+/// Resolve a file with getFile and download its content.
+fn download_file(file_id: &str) -> Result<(TelegramFile, Vec<u8>), String> {
+    let body = serde_json::to_vec(&serde_json::json!({ "file_id": file_id }))
+        .map_err(|e| format!("Failed to serialize body: {}", e))?;
+    let headers = serde_json::json!({
+        "Content-Type": "application/json"
+    });
+
+    let response = channel_host::http_request(
+        "POST",
+        "https://api.telegram.org/bot{TELEGRAM_BOT_TOKEN}/getFile",
+        &headers.to_string(),
+        Some(&body),
+    )
+    .map_err(|e| format!("getFile request failed: {}", e))?;
+    if response.status != 200 {
+        let body_str = String::from_utf8_lossy(&response.body);
+        return Err(format!(
+            "getFile returned {}: {}",
+            response.status, body_str
+        ));
+    }
+
+    let api_response: TelegramApiResponse<TelegramFile> = serde_json::from_slice(&response.body)
+        .map_err(|e| format!("Failed to parse getFile response: {}", e))?;
+    let file = match api_response.result {
+        Some(file) if api_response.ok => file,
+        _ => {
+            return Err(format!(
+                "Telegram API error: {}",
+                api_response
+                    .description
+                    .unwrap_or_else(|| "unknown".to_string())
+            ));
+        }
+    };
+    let file_path = file
+        .file_path
+        .as_deref()
+        .ok_or_else(|| "getFile returned no file_path".to_string())?;
+
+    let url = format!(
+        "https://api.telegram.org/file/bot{{TELEGRAM_BOT_TOKEN}}/{}",
+        file_path
+    );
+    let response = channel_host::http_request("GET", &url, "{}", None)
+        .map_err(|e| format!("Download failed: {}", e))?;
+    if response.status != 200 {
+        return Err(format!("Download returned {}", response.status));
+    }
+
+    Ok((file, response.body))
+}
+
+/// File name for a downloaded file: the sender's name if there is one,
+/// otherwise its stable unique ID with the extension Telegram stored it under.
+fn media_name(media: &MediaRef, telegram_path: Option<&str>) -> String {
+    if let Some(name) = &media.file_name {
+        return name.clone();
+    }
+    let extension = telegram_path
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext)
+        .filter(|ext| !ext.is_empty() && ext.chars().all(|c| c.is_ascii_alphanumeric()));
+    match extension {
+        Some(ext) => format!("{}.{}", media.file_unique_id, ext),
+        None => media.file_unique_id.clone(),
+    }
+}
+
+/// MIME type of a downloaded file. Telegram omits it for photos, which it
+/// always re-encodes as JPEG.
+fn media_mime_type(media: &MediaRef) -> String {
+    match (media.mime_type.as_deref(), media.kind) {
+        (Some(mime_type), _) => mime_type.to_string(),
+        (None, "photo") => "image/jpeg".to_string(),
+        _ => "application/octet-stream".to_string(),
+    }
+}
+
+/// One-line description of an attachment for the message content.
+fn describe_attachment(attachment: &Attachment) -> String {
+    let mut description = match (attachment.kind.as_str(), &attachment.file_name) {
+        ("photo", _) => "[Photo".to_string(),
+        ("voice", _) => "[Voice message".to_string(),
+        (kind, Some(name)) => format!("[{}{}: {}", kind[..1].to_uppercase(), &kind[1..], name),
+        (kind, None) => format!("[{}{}", kind[..1].to_uppercase(), &kind[1..]),
+    };
+    if let Some(duration) = attachment.duration {
+        description.push_str(&format!(", {}s", duration));
+    }
+    if let Some(error) = &attachment.error {
+        description.push_str(&format!(", not downloaded: {}", error));
+    }
+    description.push(']');
+    description
+}
+
 /// Clean message text by removing bot commands and @mentions at the start.
 fn clean_message_text(text: &str) -> String {
     let mut result = text.trim().to_string();
@@ -914,6 +2109,174 @@ mod tests {
         assert!(config.respond_to_all_group_messages);
     }
 
+    #[test]
+    fn test_config_quiet_hours() {
+        let json = r#"{
+            "quiet_hours": [
+                { "start": "22:00", "end": "07:00", "utc_offset_minutes": 60 },
+                { "chat_ids": [-100123], "start": "18:00", "end": "09:00" }
+            ]
+        }"#;
+        let config: TelegramConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.quiet_hours.len(), 2);
+        assert_eq!(config.quiet_hours[0].utc_offset_minutes, 60);
+        assert_eq!(config.quiet_hours[1].chat_ids, vec![-100123]);
+        assert!(config.quiet_hours_bypass_urgent);
+
+        let config: TelegramConfig = serde_json::from_str("{}").unwrap();
+        assert!(config.quiet_hours.is_empty());
+    }
+
+    #[test]
+    fn test_parse_message_reaction_update() {
+        let json = r#"{
+            "update_id": 7,
+            "message_reaction": {
+                "chat": {"id": -100123, "type": "supergroup", "title": "Team"},
+                "message_id": 55,
+                "user": {"id": 42, "is_bot": false, "first_name": "Ada"},
+                "date": 1700000000,
+                "old_reaction": [],
+                "new_reaction": [{"type": "emoji", "emoji": "👍"}]
+            }
+        }"#;
+        let update: TelegramUpdate = serde_json::from_str(json).unwrap();
+        let reaction = update.message_reaction.unwrap();
+        assert_eq!(reaction.message_id, 55);
+        assert_eq!(
+            reactions::added_rating(&reaction.old_reaction, &reaction.new_reaction),
+            Some((reactions::Rating::Positive, "👍".to_string()))
+        );
+
+        let config: TelegramConfig = serde_json::from_str("{}").unwrap();
+        assert!(config.reaction_feedback);
+    }
+
+    #[test]
+    fn test_feedback_metadata_shape() {
+        let feedback = Feedback {
+            rating: reactions::Rating::Negative,
+            message_id: 55,
+            emoji: "👎".to_string(),
+        };
+        let json = serde_json::to_value(&feedback).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"rating": "negative", "message_id": 55, "emoji": "👎"})
+        );
+    }
+
+    #[test]
+    fn test_allowlist_from_config() {
+        let json = r#"{
+            "allowed_user_ids": [1, 2],
+            "allowed_chat_ids": [-100123],
+            "owner_id": 3
+        }"#;
+        let config: TelegramConfig = serde_json::from_str(json).unwrap();
+        let allowlist = Allowlist::from_config(&config);
+        assert_eq!(allowlist.user_ids, vec![1, 2, 3]);
+        assert_eq!(allowlist.chat_ids, vec![-100123]);
+
+        let config: TelegramConfig = serde_json::from_str("{}").unwrap();
+        assert!(Allowlist::from_config(&config).is_open());
+    }
+
+    #[test]
+    fn test_allowlist_allows() {
+        assert!(Allowlist::default().allows(5, 5));
+
+        let allowlist = Allowlist {
+            user_ids: vec![1],
+            chat_ids: vec![-100123],
+        };
+        // Allowed users anywhere, anyone in an allowed group
+        assert!(allowlist.allows(1, 1));
+        assert!(allowlist.allows(1, -100999));
+        assert!(allowlist.allows(7, -100123));
+        assert!(!allowlist.allows(7, 7));
+        assert!(!allowlist.allows(7, -100999));
+
+        let chats_only = Allowlist {
+            user_ids: vec![],
+            chat_ids: vec![-100123],
+        };
+        assert!(!chats_only.allows(7, 7));
+    }
+
+    fn entity(entity_type: &str, offset: i64, length: i64) -> MessageEntity {
+        MessageEntity {
+            entity_type: entity_type.to_string(),
+            offset,
+            length,
+            user: None,
+        }
+    }
+
+    #[test]
+    fn test_entity_text_uses_utf16_offsets() {
+        // The emoji is two UTF-16 code units
+        let text = "\u{1F600} @my_bot hi";
+        assert_eq!(entity_text(text, &entity("mention", 3, 7)), Some("@my_bot"));
+        assert_eq!(entity_text(text, &entity("mention", 1, 2)), None);
+        assert_eq!(entity_text(text, &entity("mention", 3, 99)), None);
+        assert_eq!(entity_text(text, &entity("mention", -1, 2)), None);
+    }
+
+    #[test]
+    fn test_is_addressed_to_bot() {
+        let bot = Some("my_bot");
+
+        let text = "hey @My_Bot what's up";
+        assert!(is_addressed_to_bot(text, &[entity("mention", 4, 7)], bot));
+        assert!(!is_addressed_to_bot(
+            text,
+            &[entity("mention", 4, 7)],
+            Some("other_bot")
+        ));
+        assert!(!is_addressed_to_bot(text, &[entity("mention", 4, 7)], None));
+
+        // Email addresses aren't mentions
+        let text = "mail me at alice@example.com";
+        assert!(!is_addressed_to_bot(text, &[entity("email", 11, 17)], bot));
+
+        // Someone else's mention
+        let text = "@alice look";
+        assert!(!is_addressed_to_bot(text, &[entity("mention", 0, 6)], bot));
+
+        // Commands, bare or aimed at this bot
+        let text = "/start now";
+        assert!(is_addressed_to_bot(
+            text,
+            &[entity("bot_command", 0, 6)],
+            None
+        ));
+        let text = "/start@my_bot now";
+        assert!(is_addressed_to_bot(
+            text,
+            &[entity("bot_command", 0, 13)],
+            bot
+        ));
+        let text = "/start@other_bot now";
+        assert!(!is_addressed_to_bot(
+            text,
+            &[entity("bot_command", 0, 16)],
+            bot
+        ));
+
+        let mut text_mention = entity("text_mention", 0, 3);
+        text_mention.user = Some(TelegramUser {
+            id: 1,
+            is_bot: true,
+            first_name: "Bot".to_string(),
+            last_name: None,
+            username: Some("my_bot".to_string()),
+        });
+        assert!(is_addressed_to_bot("Bot hi", &[text_mention], bot));
+
+        assert!(!is_addressed_to_bot("plain text", &[], bot));
+    }
+
     #[test]
     fn test_parse_update() {
         let json = r#"{
@@ -945,4 +2308,213 @@ mod tests {
         assert_eq!(from.id, 789);
         assert_eq!(from.first_name, "John");
     }
+
+    #[test]
+    fn test_parse_forum_topic_message() {
+        let json = r#"{
+            "message_id": 10,
+            "from": {"id": 1, "is_bot": false, "first_name": "A"},
+            "chat": {"id": -100123, "type": "supergroup"},
+            "message_thread_id": 42,
+            "is_topic_message": true,
+            "text": "hi"
+        }"#;
+        let message: TelegramMessage = serde_json::from_str(json).unwrap();
+        assert_eq!(forum_topic(&message), Some(42));
+
+        // A reply thread in a group without topics isn't a topic
+        let json = r#"{
+            "message_id": 11,
+            "chat": {"id": -100123, "type": "supergroup"},
+            "message_thread_id": 7,
+            "text": "re"
+        }"#;
+        let message: TelegramMessage = serde_json::from_str(json).unwrap();
+        assert_eq!(forum_topic(&message), None);
+
+        let metadata = TelegramMessageMetadata {
+            chat_id: -100123,
+            message_id: 10,
+            user_id: 1,
+            is_private: false,
+            message_thread_id: Some(42),
+            attachments: Vec::new(),
+            audio: Vec::new(),
+            buttons: Vec::new(),
+            button_press: None,
+            feedback: None,
+        };
+        assert_eq!(
+            conversation_thread(&metadata),
+            Some("-100123:42".to_string())
+        );
+        let out = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(out["message_thread_id"], 42);
+    }
+
+    #[test]
+    fn test_parse_photo_picks_largest_size() {
+        let json = r#"{
+            "message_id": 1,
+            "chat": {"id": 7, "type": "private"},
+            "caption": "look at this",
+            "photo": [
+                {"file_id": "small", "file_unique_id": "s", "width": 90, "height": 90, "file_size": 1000},
+                {"file_id": "large", "file_unique_id": "l", "width": 1280, "height": 960, "file_size": 90000}
+            ]
+        }"#;
+        let message: TelegramMessage = serde_json::from_str(json).unwrap();
+        let media = media_of(&message).unwrap();
+        assert_eq!(media.kind, "photo");
+        assert_eq!(media.file_id, "large");
+        assert_eq!(media.size, Some(90000));
+        assert_eq!(message.caption.as_deref(), Some("look at this"));
+    }
+
+    #[test]
+    fn test_parse_document_and_voice() {
+        let json = r#"{
+            "message_id": 2,
+            "chat": {"id": 7, "type": "private"},
+            "document": {"file_id": "d1", "file_unique_id": "du", "file_name": "report.pdf", "mime_type": "application/pdf"}
+        }"#;
+        let message: TelegramMessage = serde_json::from_str(json).unwrap();
+        let media = media_of(&message).unwrap();
+        assert_eq!(media.kind, "document");
+        assert_eq!(
+            media_name(&media, Some("documents/file_3.pdf")),
+            "report.pdf"
+        );
+        assert_eq!(media_mime_type(&media), "application/pdf");
+
+        let json = r#"{
+            "message_id": 3,
+            "chat": {"id": 7, "type": "private"},
+            "voice": {"file_id": "v1", "file_unique_id": "vu", "duration": 4, "mime_type": "audio/ogg"}
+        }"#;
+        let message: TelegramMessage = serde_json::from_str(json).unwrap();
+        let media = media_of(&message).unwrap();
+        assert_eq!(media.duration, Some(4));
+        assert_eq!(media_name(&media, Some("voice/file_9.oga")), "vu.oga");
+        assert_eq!(media_name(&media, None), "vu");
+        assert_eq!(media_mime_type(&media), "audio/ogg");
+    }
+
+    #[test]
+    fn test_describe_attachment() {
+        let mut attachment = Attachment {
+            kind: "document".to_string(),
+            file_id: "d1".to_string(),
+            file_name: Some("report.pdf".to_string()),
+            mime_type: None,
+            size: Some(10),
+            duration: None,
+            attachment_id: None,
+            error: Some("Download returned 404".to_string()),
+        };
+        assert_eq!(
+            describe_attachment(&attachment),
+            "[Document: report.pdf, not downloaded: Download returned 404]"
+        );
+
+        attachment.kind = "voice".to_string();
+        attachment.duration = Some(4);
+        attachment.error = Some("file is larger than 20 MB".to_string());
+        assert_eq!(
+            describe_attachment(&attachment),
+            "[Voice message, 4s, not downloaded: file is larger than 20 MB]"
+        );
+    }
+
+    #[test]
+    fn test_audio_info() {
+        let attachment = |kind: &str, attachment_id: Option<&str>| Attachment {
+            kind: kind.to_string(),
+            file_id: "f".to_string(),
+            file_name: None,
+            mime_type: None,
+            size: None,
+            duration: Some(4),
+            attachment_id: attachment_id.map(str::to_string),
+            error: None,
+        };
+        let audio = audio_info(&[
+            attachment("voice", Some("a1")),
+            attachment("audio", Some("a2")),
+            attachment("photo", Some("a3")),
+            attachment("voice", None),
+        ]);
+        assert_eq!(audio.len(), 2);
+        assert_eq!(audio[0].attachment_id, "a1");
+        assert!(audio[0].voice);
+        assert_eq!(audio[0].duration, Some(4));
+        assert!(!audio[1].voice);
+    }
+
+    #[test]
+    fn test_metadata_without_attachments_roundtrips() {
+        let json = r#"{"chat_id": 1, "message_id": 2, "user_id": 3, "is_private": true}"#;
+        let metadata: TelegramMessageMetadata = serde_json::from_str(json).unwrap();
+        assert!(metadata.attachments.is_empty());
+        let out = serde_json::to_value(&metadata).unwrap();
+        assert!(out.get("attachments").is_none());
+        assert!(out.get("buttons").is_none());
+        assert!(out.get("button_press").is_none());
+        assert!(out.get("message_thread_id").is_none());
+    }
+
+    #[test]
+    fn test_parse_callback_query() {
+        let json = r#"{
+            "update_id": 124,
+            "callback_query": {
+                "id": "4382",
+                "from": {"id": 789, "is_bot": false, "first_name": "John"},
+                "message": {
+                    "message_id": 457,
+                    "chat": {"id": 789, "type": "private"},
+                    "text": "Approval needed: shell",
+                    "reply_markup": {"inline_keyboard": [[
+                        {"text": "Approve", "callback_data": "yes"},
+                        {"text": "Deny", "callback_data": "no"}
+                    ]]}
+                },
+                "data": "no"
+            }
+        }"#;
+
+        let update: TelegramUpdate = serde_json::from_str(json).unwrap();
+        let query = update.callback_query.unwrap();
+        assert_eq!(query.id, "4382");
+        assert_eq!(query.data.as_deref(), Some("no"));
+
+        let message = query.message.unwrap();
+        assert_eq!(button_label(&message, "no").as_deref(), Some("Deny"));
+        assert_eq!(button_label(&message, "maybe"), None);
+    }
+
+    #[test]
+    fn test_inline_keyboard_from_metadata() {
+        let json = r#"{
+            "chat_id": 1, "message_id": 2, "user_id": 3, "is_private": true,
+            "buttons": [[{"text": "Approve", "data": "yes"}, {"text": "Deny", "data": "no"}], []]
+        }"#;
+        let metadata: TelegramMessageMetadata = serde_json::from_str(json).unwrap();
+        let keyboard = inline_keyboard(&metadata.buttons);
+        assert_eq!(
+            keyboard,
+            serde_json::json!({"inline_keyboard": [[
+                {"text": "Approve", "callback_data": "yes"},
+                {"text": "Deny", "callback_data": "no"}
+            ]]})
+        );
+    }
+
+    #[test]
+    fn test_truncate_bytes() {
+        assert_eq!(truncate_bytes("yes", 64), "yes");
+        assert_eq!(truncate_bytes(&"a".repeat(70), 64).len(), 64);
+        // Never splits a multi-byte character
+        assert_eq!(truncate_bytes("ééé", 3), "é");
+    }
 }
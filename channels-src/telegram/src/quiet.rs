@@ -0,0 +1,187 @@
+//! Quiet hours for Telegram chats.
+//!
+//! A chat can have a daily quiet window. Responses generated for it during
+//! the window are queued in workspace state instead of sent, and the queue
+//! is flushed on the first `on_poll` tick after the window ends. Responses
+//! the agent marks `"urgent": true` in their metadata go out immediately
+//! unless `quiet_hours_bypass_urgent` is turned off.
+
+use serde::{Deserialize, Serialize};
+
+/// Minutes in a day.
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// Deliveries of a deferred response before it is dropped.
+pub const MAX_FLUSH_ATTEMPTS: u32 = 3;
+
+/// A daily window during which responses to some chats are held back.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuietWindow {
+    /// Chats the window applies to; empty means every chat.
+    #[serde(default)]
+    pub chat_ids: Vec<i64>,
+
+    /// Local start time, "HH:MM".
+    pub start: String,
+
+    /// Local end time, "HH:MM". Windows may wrap past midnight.
+    pub end: String,
+
+    /// Offset of local time from UTC, in minutes (e.g., 120 for UTC+2).
+    #[serde(default)]
+    pub utc_offset_minutes: i64,
+}
+
+impl QuietWindow {
+    /// Whether the window's times parse.
+    pub fn is_valid(&self) -> bool {
+        parse_time(&self.start).is_some() && parse_time(&self.end).is_some()
+    }
+
+    fn applies_to(&self, chat_id: i64) -> bool {
+        self.chat_ids.is_empty() || self.chat_ids.contains(&chat_id)
+    }
+
+    /// Whether `now_millis` (Unix time) falls inside the window.
+    fn contains(&self, now_millis: u64) -> bool {
+        let (Some(start), Some(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        let utc_minutes = (now_millis / 60_000) as i64;
+        let minute = (utc_minutes + self.utc_offset_minutes).rem_euclid(MINUTES_PER_DAY);
+        if start <= end {
+            start <= minute && minute < end
+        } else {
+            minute >= start || minute < end
+        }
+    }
+}
+
+/// Quiet hours policy, persisted so every callback can consult it.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuietHours {
+    #[serde(default)]
+    pub windows: Vec<QuietWindow>,
+
+    /// Send responses marked urgent even during quiet hours.
+    #[serde(default)]
+    pub bypass_urgent: bool,
+}
+
+impl QuietHours {
+    pub fn is_enabled(&self) -> bool {
+        !self.windows.is_empty()
+    }
+
+    /// Whether `chat_id` is inside its quiet window. The first window that
+    /// applies to the chat decides.
+    pub fn is_quiet(&self, chat_id: i64, now_millis: u64) -> bool {
+        self.windows
+            .iter()
+            .find(|w| w.applies_to(chat_id))
+            .is_some_and(|w| w.contains(now_millis))
+    }
+
+    /// Whether a response with this metadata should be held back now.
+    pub fn should_defer(&self, chat_id: i64, metadata_json: &str, now_millis: u64) -> bool {
+        if !self.is_quiet(chat_id, now_millis) {
+            return false;
+        }
+        !(self.bypass_urgent && is_urgent(metadata_json))
+    }
+}
+
+/// A response waiting for its chat's quiet window to end.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeferredResponse {
+    pub chat_id: i64,
+    pub content: String,
+    pub metadata_json: String,
+    /// When the response was generated (Unix millis).
+    pub deferred_at: u64,
+    /// Failed delivery attempts so far.
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+/// Whether the agent marked a response urgent.
+pub fn is_urgent(metadata_json: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(metadata_json)
+        .ok()
+        .and_then(|m| m.get("urgent").and_then(|u| u.as_bool()))
+        .unwrap_or(false)
+}
+
+/// Parse "HH:MM" into minutes after midnight.
+fn parse_time(s: &str) -> Option<i64> {
+    let (hours, minutes) = s.trim().split_once(':')?;
+    let hours: i64 = hours.parse().ok()?;
+    let minutes: i64 = minutes.parse().ok()?;
+    ((0..24).contains(&hours) && (0..60).contains(&minutes)).then_some(hours * 60 + minutes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unix millis for 1970-01-01 at `hours:minutes` UTC.
+    fn at(hours: u64, minutes: u64) -> u64 {
+        (hours * 60 + minutes) * 60_000
+    }
+
+    fn window(start: &str, end: &str) -> QuietWindow {
+        QuietWindow {
+            chat_ids: Vec::new(),
+            start: start.to_string(),
+            end: end.to_string(),
+            utc_offset_minutes: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("07:30"), Some(450));
+        assert_eq!(parse_time("24:00"), None);
+        assert_eq!(parse_time("7"), None);
+    }
+
+    #[test]
+    fn test_window_wraps_midnight() {
+        let quiet = QuietHours {
+            windows: vec![window("22:00", "07:00")],
+            bypass_urgent: true,
+        };
+        assert!(quiet.is_quiet(1, at(23, 0)));
+        assert!(quiet.is_quiet(1, at(3, 0)));
+        assert!(!quiet.is_quiet(1, at(7, 0)));
+        assert!(!quiet.is_quiet(1, at(12, 0)));
+    }
+
+    #[test]
+    fn test_window_offset_and_chats() {
+        let mut w = window("22:00", "23:00");
+        w.utc_offset_minutes = 120;
+        w.chat_ids = vec![42];
+        let quiet = QuietHours {
+            windows: vec![w],
+            bypass_urgent: false,
+        };
+        // 20:30 UTC is 22:30 at UTC+2
+        assert!(quiet.is_quiet(42, at(20, 30)));
+        assert!(!quiet.is_quiet(42, at(22, 30)));
+        assert!(!quiet.is_quiet(7, at(20, 30)));
+    }
+
+    #[test]
+    fn test_urgent_bypass() {
+        let mut quiet = QuietHours {
+            windows: vec![window("00:00", "23:59")],
+            bypass_urgent: true,
+        };
+        assert!(quiet.should_defer(1, "{}", at(12, 0)));
+        assert!(!quiet.should_defer(1, r#"{"urgent": true}"#, at(12, 0)));
+
+        quiet.bypass_urgent = false;
+        assert!(quiet.should_defer(1, r#"{"urgent": true}"#, at(12, 0)));
+    }
+}
@@ -0,0 +1,148 @@
+//! Reaction-based feedback.
+//!
+//! A thumbs up, thumbs down, or similar emoji reaction on one of the bot's
+//! own messages is emitted as a feedback event instead of a chat message, so
+//! the agent can track how satisfied users are without asking them. Reactions
+//! on anyone else's messages are ignored; Telegram doesn't say who wrote the
+//! reacted message, so the IDs of the bot's recent messages are kept in
+//! workspace state.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+/// Sent messages remembered for matching reactions, oldest dropped first.
+pub const MAX_TRACKED_MESSAGES: usize = 500;
+
+/// Reactions that count as a positive rating.
+const POSITIVE: &[&str] = &[
+    "👍", "❤", "❤️", "🔥", "👏", "🎉", "🙏", "💯", "😍", "🥰", "🤩", "👌", "🏆",
+];
+
+/// Reactions that count as a negative rating.
+const NEGATIVE: &[&str] = &["👎", "💩", "🤮", "😡", "🤬", "🤡"];
+
+/// Telegram ReactionType object.
+/// https://core.telegram.org/bots/api#reactiontype
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReactionType {
+    Emoji {
+        emoji: String,
+    },
+    CustomEmoji {
+        custom_emoji_id: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// Whether the user liked a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rating {
+    Positive,
+    Negative,
+}
+
+impl Rating {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Positive => "positive",
+            Self::Negative => "negative",
+        }
+    }
+}
+
+/// Rating an emoji expresses, if any.
+pub fn rating_of(emoji: &str) -> Option<Rating> {
+    if POSITIVE.contains(&emoji) {
+        Some(Rating::Positive)
+    } else if NEGATIVE.contains(&emoji) {
+        Some(Rating::Negative)
+    } else {
+        None
+    }
+}
+
+/// Rating and emoji of the first newly added reaction that expresses one.
+/// Removed reactions and reactions the user already had are ignored.
+pub fn added_rating(old: &[ReactionType], new: &[ReactionType]) -> Option<(Rating, String)> {
+    new.iter()
+        .filter(|reaction| !old.contains(reaction))
+        .find_map(|reaction| match reaction {
+            ReactionType::Emoji { emoji } => rating_of(emoji).map(|r| (r, emoji.clone())),
+            _ => None,
+        })
+}
+
+/// IDs of messages the bot sent recently, as (chat ID, message ID).
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SentMessages {
+    messages: VecDeque<(i64, i64)>,
+}
+
+impl SentMessages {
+    pub fn record(&mut self, chat_id: i64, message_id: i64) {
+        self.messages.push_back((chat_id, message_id));
+        while self.messages.len() > MAX_TRACKED_MESSAGES {
+            self.messages.pop_front();
+        }
+    }
+
+    pub fn contains(&self, chat_id: i64, message_id: i64) -> bool {
+        self.messages.contains(&(chat_id, message_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn emoji(e: &str) -> ReactionType {
+        ReactionType::Emoji {
+            emoji: e.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_reaction_types() {
+        let reactions: Vec<ReactionType> = serde_json::from_str(
+            r#"[
+                {"type": "emoji", "emoji": "👍"},
+                {"type": "custom_emoji", "custom_emoji_id": "123"},
+                {"type": "paid"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(reactions[0], emoji("👍"));
+        assert_eq!(reactions[2], ReactionType::Other);
+    }
+
+    #[test]
+    fn test_added_rating() {
+        assert_eq!(
+            added_rating(&[], &[emoji("👎")]),
+            Some((Rating::Negative, "👎".to_string()))
+        );
+        // Unrated emoji are skipped
+        assert_eq!(
+            added_rating(&[], &[emoji("🤔"), emoji("❤")]),
+            Some((Rating::Positive, "❤".to_string()))
+        );
+        // Keeping or removing a reaction isn't new feedback
+        assert_eq!(added_rating(&[emoji("👍")], &[emoji("👍")]), None);
+        assert_eq!(added_rating(&[emoji("👍")], &[]), None);
+    }
+
+    #[test]
+    fn test_sent_messages_are_bounded() {
+        let mut sent = SentMessages::default();
+        for id in 0..MAX_TRACKED_MESSAGES as i64 + 10 {
+            sent.record(1, id);
+        }
+        assert!(!sent.contains(1, 0));
+        assert!(sent.contains(1, MAX_TRACKED_MESSAGES as i64 + 9));
+        assert!(!sent.contains(2, MAX_TRACKED_MESSAGES as i64 + 9));
+    }
+}
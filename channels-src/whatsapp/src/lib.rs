@@ -29,8 +29,8 @@ use serde::{Deserialize, Serialize};
 
 // Re-export generated types
 use exports::near::agent::channel::{
-    AgentResponse, ChannelConfig, Guest, HttpEndpointConfig, IncomingHttpRequest,
-    OutgoingHttpResponse, StatusUpdate,
+    AgentResponse, ChannelConfig, FormattingCapabilities, Guest, HttpEndpointConfig,
+    IncomingHttpRequest, MarkdownFlavor, OutgoingHttpResponse, StatusUpdate,
 };
 use near::agent::channel_host::{self, EmittedMessage};
 
@@ -278,6 +278,13 @@ impl Guest for WhatsAppChannel {
                 require_secret: true,
             }],
             poll: None, // WhatsApp doesn't support polling
+            formatting: FormattingCapabilities {
+                markdown: MarkdownFlavor::Plain,
+                max_message_length: Some(4096),
+                supports_buttons: false,
+                supports_threads: false,
+                supports_files: false,
+            },
         })
     }
 
@@ -604,6 +611,7 @@ fn handle_message(
         content: text,
         thread_id: None, // WhatsApp doesn't have threads like Slack/Discord
         metadata_json,
+        attachments: Vec::new(),
     });
 
     channel_host::log(
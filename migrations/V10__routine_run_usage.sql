@@ -0,0 +1,4 @@
+-- Cost and output of each routine run
+
+ALTER TABLE routine_runs ADD COLUMN IF NOT EXISTS cost NUMERIC;
+ALTER TABLE routine_runs ADD COLUMN IF NOT EXISTS messages_sent INTEGER NOT NULL DEFAULT 0;
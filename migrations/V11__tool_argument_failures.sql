@@ -0,0 +1,14 @@
+-- Tool calls rejected because their arguments didn't match the tool's schema
+-- Kept per tool and model to find the schemas and prompts that need work
+
+CREATE TABLE IF NOT EXISTS tool_argument_failures (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tool_name VARCHAR(255) NOT NULL,
+    model TEXT NOT NULL,
+    errors TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_tool_argument_failures_created ON tool_argument_failures(created_at);
+CREATE INDEX IF NOT EXISTS idx_tool_argument_failures_tool ON tool_argument_failures(tool_name, model);
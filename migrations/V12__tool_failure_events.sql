@@ -0,0 +1,13 @@
+-- Every tool failure with its category, for trend reports
+-- tool_failures keeps one running row per tool for self-repair; this keeps history
+
+CREATE TABLE IF NOT EXISTS tool_failure_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tool_name VARCHAR(255) NOT NULL,
+    category TEXT NOT NULL,
+    error_message TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_tool_failure_events_created ON tool_failure_events(created_at);
+CREATE INDEX IF NOT EXISTS idx_tool_failure_events_tool ON tool_failure_events(tool_name, category);
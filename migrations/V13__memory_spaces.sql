@@ -0,0 +1,36 @@
+-- Named memory spaces shared by a team, next to each user's private memory
+-- A space's documents live in memory_documents under the user_id 'space:<name>'
+
+CREATE TABLE IF NOT EXISTS memory_spaces (
+    name TEXT PRIMARY KEY,
+    description TEXT NOT NULL DEFAULT '',
+    created_by TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Who may use a space: 'read', 'write', or 'admin' (write plus managing members)
+CREATE TABLE IF NOT EXISTS memory_space_members (
+    space TEXT NOT NULL REFERENCES memory_spaces(name) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    access TEXT NOT NULL,
+    granted_by TEXT NOT NULL,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (space, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_space_members_user ON memory_space_members(user_id);
+
+-- Provenance: who changed which file of a space, from which job and conversation
+CREATE TABLE IF NOT EXISTS memory_space_writes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    space TEXT NOT NULL REFERENCES memory_spaces(name) ON DELETE CASCADE,
+    path TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    job_id UUID,
+    conversation_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_space_writes_path ON memory_space_writes(space, path, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_memory_space_writes_created ON memory_space_writes(space, created_at DESC);
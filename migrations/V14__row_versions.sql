@@ -0,0 +1,5 @@
+-- Versions for optimistic concurrency: every write bumps the row's version,
+-- and a write made against an older version is refused as a conflict
+
+ALTER TABLE settings ADD COLUMN IF NOT EXISTS version BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE routines ADD COLUMN IF NOT EXISTS version BIGINT NOT NULL DEFAULT 1;
@@ -0,0 +1,35 @@
+-- Routines (scheduled and event-driven tasks) and their execution history
+
+CREATE TABLE IF NOT EXISTS routines (
+    id UUID PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    trigger_config JSONB NOT NULL,
+    action_config JSONB NOT NULL,
+    guardrails JSONB NOT NULL DEFAULT '{}',
+    notify JSONB NOT NULL DEFAULT '{}',
+    last_run_at TIMESTAMPTZ,
+    next_fire_at TIMESTAMPTZ,
+    run_count BIGINT NOT NULL DEFAULT 0,
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_routines_user ON routines(user_id);
+
+CREATE TABLE IF NOT EXISTS routine_runs (
+    id UUID PRIMARY KEY,
+    routine_id UUID NOT NULL REFERENCES routines(id) ON DELETE CASCADE,
+    trigger_type TEXT NOT NULL,
+    status TEXT NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    result_summary TEXT,
+    tokens_used INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_routine_runs_routine ON routine_runs(routine_id, started_at DESC);
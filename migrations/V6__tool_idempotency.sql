@@ -0,0 +1,15 @@
+-- Idempotency keys for tool calls with external side effects
+-- A retried call with the same key replays the recorded result instead of
+-- sending the email or message again
+
+CREATE TABLE IF NOT EXISTS tool_idempotency (
+    key TEXT PRIMARY KEY,
+    job_id UUID NOT NULL,
+    tool_name TEXT NOT NULL,
+    status TEXT NOT NULL,
+    result JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_tool_idempotency_job ON tool_idempotency(job_id);
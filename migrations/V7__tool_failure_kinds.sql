@@ -0,0 +1,5 @@
+-- Distinguish timeouts from other tool failures
+-- A tool that keeps hanging needs a different repair than one that errors
+
+ALTER TABLE tool_failures ADD COLUMN IF NOT EXISTS timeout_count INTEGER DEFAULT 0;
+ALTER TABLE tool_failures ADD COLUMN IF NOT EXISTS last_failure_kind TEXT;
@@ -0,0 +1,15 @@
+-- Ratings users give the agent's messages, e.g. a thumbs up reaction
+-- Feeds response quality evaluation without explicit commands
+
+CREATE TABLE IF NOT EXISTS message_feedback (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    channel TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    thread_id TEXT,
+    message_id TEXT,
+    rating TEXT NOT NULL,
+    emoji TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_feedback_user ON message_feedback(user_id, created_at);
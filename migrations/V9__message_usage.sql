@@ -0,0 +1,10 @@
+-- Token usage and cost per assistant message
+-- Filled from llm_calls when the message is written, so the UI and analytics
+-- don't have to correlate calls with messages after the fact
+
+ALTER TABLE conversation_messages ADD COLUMN IF NOT EXISTS model TEXT;
+ALTER TABLE conversation_messages ADD COLUMN IF NOT EXISTS input_tokens INTEGER;
+ALTER TABLE conversation_messages ADD COLUMN IF NOT EXISTS output_tokens INTEGER;
+ALTER TABLE conversation_messages ADD COLUMN IF NOT EXISTS cost NUMERIC;
+
+CREATE INDEX IF NOT EXISTS idx_llm_calls_conversation_created ON llm_calls(conversation_id, created_at);
@@ -1,32 +1,78 @@
 //! Main agent loop.
 
+use std::collections::{HashSet, VecDeque};
 use std::sync::Arc;
 
-use futures::StreamExt;
+use futures::future::{FutureExt, LocalBoxFuture};
+use futures::stream::{FuturesUnordered, StreamExt};
 use tokio::sync::Mutex;
+use tracing::Instrument;
 use uuid::Uuid;
 
 use crate::agent::compaction::ContextCompactor;
 use crate::agent::context_monitor::ContextMonitor;
+use crate::agent::dedup::DedupStore;
+use crate::agent::defer::{DEFER_CHECK_INTERVAL, Deferral};
+use crate::agent::digest::{DIGEST_CHECK_INTERVAL, DigestBuffer};
 use crate::agent::heartbeat::spawn_heartbeat;
-use crate::agent::self_repair::{DefaultSelfRepair, RepairResult, SelfRepair};
+use crate::agent::labels::LabelBatcher;
+use crate::agent::pause::{PauseScope, PauseSwitch};
+use crate::agent::routine_monitor::{RoutineMonitor, RunOutcome, format_report, report_level};
+use crate::agent::self_repair::{DefaultSelfRepair, RepairResult, SelfRepair, send_failure_report};
 use crate::agent::session::{PendingApproval, Session, ThreadState};
 use crate::agent::session_manager::SessionManager;
-use crate::agent::submission::{Submission, SubmissionParser, SubmissionResult};
+use crate::agent::submission::{ModelScope, Submission, SubmissionParser, SubmissionResult};
+use crate::agent::working_set::WorkingSetRetriever;
 use crate::agent::{HeartbeatConfig as AgentHeartbeatConfig, MessageIntent, Router, Scheduler};
-use crate::channels::{ChannelManager, IncomingMessage, OutgoingResponse, StatusUpdate};
+use crate::channels::quiet_hours::{self, QUIET_HOURS_KEY, QUIET_USAGE, QuietHours};
+use crate::channels::{
+    ChannelManager, IncomingMessage, NotificationRouter, NotifyTarget, OutboundQueue,
+    OutgoingResponse, QUIET_HOURS_CHECK_INTERVAL, StatusUpdate,
+};
 use crate::config::{AgentConfig, HeartbeatConfig};
+use crate::context::ActionRecord;
 use crate::context::ContextManager;
 use crate::context::JobContext;
+use crate::context::variables::{ConversationVariables, parse_remember};
+use crate::db::Database;
 use crate::error::Error;
-use crate::extensions::ExtensionManager;
+use crate::evaluation::{ArgumentFailure, MetricsCollector, UserFeedback};
+use crate::extensions::{AuthResult, ExtensionManager};
 use crate::history::Store;
-use crate::llm::{ChatMessage, LlmProvider, Reasoning, ReasoningContext, RespondResult};
+use crate::llm::{
+    CatalogModel, ChatMessage, LlmProvider, ModelCatalog, Reasoning, ReasoningContext,
+    RespondResult,
+};
 use crate::agent::cache_manager::CacheManager;
+use crate::agent::language::{self, Language};
+use crate::agent::priority::{Priority, PriorityGate, ROUTINE_MESSAGE_PREFIX};
 use crate::sneed_engine::SovereignOptimizer;
-use crate::safety::SafetyLayer;
+use crate::safety::{ClassifierAction, ContentDirection, SafetyLayer};
+use crate::tenancy::TenantDirectory;
 use crate::tools::{Tool, ToolRegistry};
-use crate::workspace::Workspace;
+use crate::transcription::Transcriber;
+use crate::workspace::{UserWorkspaces, Workspace};
+
+/// Per-user settings key for persisted StakesEngine state.
+const STAKES_STATE_KEY: &str = "stakes_state";
+
+/// How long a user's StakesEngine stays in memory after their last turn.
+const STAKES_IDLE_TTL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Per-user settings key for a custom council roster (JSON `CouncilRoster`).
+const COUNCIL_ROSTER_KEY: &str = "council";
+
+/// Per-user settings key for the last detected language (ISO 639-1 code).
+/// Threads can override it with a `language` entry in their metadata.
+const LANGUAGE_KEY: &str = "language";
+
+/// Per-user settings key for the model used when a conversation has no pin
+/// of its own. Threads pin a model with a `model` entry in their metadata.
+const MODEL_KEY: &str = "model";
+
+/// Prefix of per-user settings keys pinning a routine's model
+/// (`model.routine.<name>`).
+const ROUTINE_MODEL_KEY_PREFIX: &str = "model.routine.";
 
 /// Collapse a tool output string into a single-line preview for display.
 fn truncate_for_preview(output: &str, max_chars: usize) -> String {
@@ -45,6 +91,64 @@ fn truncate_for_preview(output: &str, max_chars: usize) -> String {
     }
 }
 
+/// Cap on the full tool output sent to channels for expanded display.
+const TOOL_OUTPUT_DISPLAY_CHARS: usize = 8000;
+
+/// Limit a tool output to `max_chars`, keeping its line structure.
+fn truncate_for_display(output: &str, max_chars: usize) -> String {
+    match output.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}\n... (truncated)", &output[..idx]),
+        None => output.to_string(),
+    }
+}
+
+/// The message asking the user to connect a tool that is missing its credential.
+fn auth_prompt(tool_name: &str, result: &AuthResult) -> String {
+    let mut prompt = format!("`{}` needs to be connected before I can use it.", tool_name);
+    if let Some(ref url) = result.auth_url {
+        prompt.push_str(&format!("\n\nAuthorize here: {}", url));
+    }
+    if let Some(ref instructions) = result.instructions {
+        prompt.push_str(&format!("\n\n{}", instructions));
+    }
+    if let Some(ref url) = result.setup_url {
+        prompt.push_str(&format!("\n\nSetup: {}", url));
+    }
+    if result.awaiting_token {
+        prompt.push_str("\n\nPaste the token as your next message.");
+    }
+    prompt
+}
+
+/// Prefix of every memory-trigger entry (see `StakesEngine::check_memory_trigger`).
+const AUTO_LOG_MARKER: &str = "(Auto-Log via";
+
+/// Number of most recent auto-log entries a new one is deduplicated against.
+const AUTO_LOG_DEDUP_WINDOW: usize = 10;
+
+/// Build the daily-log entry for a fired memory trigger.
+fn auto_log_entry(trigger: &str, user: &str, assistant: &str) -> String {
+    format!(
+        "{}\n\nSummary Context:\n> User: {}\n> Assistant: {}",
+        trigger,
+        truncate_for_preview(user, 200),
+        truncate_for_preview(assistant, 200)
+    )
+}
+
+/// Whether `entry` repeats the user excerpt of one of the last auto-logs in `log`.
+fn is_duplicate_auto_log(log: &str, entry: &str) -> bool {
+    let Some(excerpt) = entry.lines().find(|l| l.starts_with("> User: ")) else {
+        return false;
+    };
+    let previous: Vec<&str> = log.split(AUTO_LOG_MARKER).skip(1).collect();
+    previous
+        .iter()
+        .rev()
+        .take(AUTO_LOG_DEDUP_WINDOW)
+        .any(|prev| prev.lines().any(|l| l.trim_end() == excerpt))
+}
+
 /// Result of the agentic loop execution.
 enum AgenticLoopResult {
     /// Completed with a response.
@@ -62,10 +166,16 @@ enum AgenticLoopResult {
 pub struct AgentDeps {
     pub store: Option<Arc<Store>>,
     pub llm: Arc<dyn LlmProvider>,
+    /// Models available from the provider, with capabilities and pricing.
+    pub model_catalog: Arc<ModelCatalog>,
     pub safety: Arc<SafetyLayer>,
     pub tools: Arc<ToolRegistry>,
-    pub workspace: Option<Arc<Workspace>>,
+    pub workspace: Option<Arc<UserWorkspaces>>,
     pub extension_manager: Option<Arc<ExtensionManager>>,
+    /// Roles and quotas for the users this deployment serves.
+    pub tenants: Arc<TenantDirectory>,
+    /// Turns voice notes into text before messages are routed.
+    pub transcriber: Option<Arc<Transcriber>>,
 }
 
 /// The main agent that coordinates all components.
@@ -73,16 +183,37 @@ pub struct Agent {
     config: AgentConfig,
     deps: AgentDeps,
     channels: Arc<ChannelManager>,
+    /// Delivers responses with retries, dead-lettering what can't be sent.
+    outbound: OutboundQueue,
     context_manager: Arc<ContextManager>,
     scheduler: Arc<Scheduler>,
     router: Router,
     session_manager: Arc<SessionManager>,
     context_monitor: ContextMonitor,
     optimizer: crate::sneed_engine::SovereignOptimizer,
-    stakes: Arc<Mutex<crate::sneed_engine::StakesEngine>>,
+    /// One StakesEngine per user, loaded from their settings on first use.
+    stakes: Arc<crate::sneed_engine::UserStakes>,
+    /// Council roster file, hot-reloaded on change.
+    roster_watcher: Mutex<crate::sneed_engine::RosterWatcher>,
+    /// Users whose `council` setting overrides the roster file.
+    custom_rosters: Mutex<HashSet<String>>,
     grid: Arc<Mutex<crate::sneed_engine::SovereignGrid>>,
     heartbeat_config: Option<HeartbeatConfig>,
     cache_manager: Arc<CacheManager>,
+    /// Selects recent and relevant history for each chat turn.
+    working_set: WorkingSetRetriever,
+    /// Orders chat turns ahead of routines and maintenance.
+    priority: Arc<PriorityGate>,
+    /// Response quality signals, including user feedback from channels.
+    metrics: Arc<Mutex<MetricsCollector>>,
+    /// Delivers heartbeat findings and routine alerts.
+    notifier: NotificationRouter,
+    /// Run history and failure alerts for routines (needs the database).
+    routine_monitor: Option<Arc<RoutineMonitor>>,
+    /// Batched conversation titles and tags (needs the database).
+    labels: Option<Arc<LabelBatcher>>,
+    /// Emergency stop set by `/pause`.
+    pause: Arc<PauseSwitch>,
 }
 
 impl Agent {
@@ -103,6 +234,8 @@ impl Agent {
 
         let session_manager = session_manager.unwrap_or_else(|| Arc::new(SessionManager::new()));
 
+        let priority = Arc::new(PriorityGate::new(&config.priority));
+        let pause = Arc::new(PauseSwitch::new());
         let scheduler = Arc::new(Scheduler::new(
             config.clone(),
             context_manager.clone(),
@@ -110,27 +243,93 @@ impl Agent {
             deps.safety.clone(),
             deps.tools.clone(),
             deps.store.clone(),
+            priority.clone(),
+            pause.clone(),
         ));
 
         let cache_manager = Arc::new(CacheManager::new(deps.llm.clone(), std::time::Duration::from_secs(86400)));
+        let roster_watcher =
+            crate::sneed_engine::RosterWatcher::new(config.council_roster_path.clone());
+        let context_monitor = ContextMonitor::new().with_tokenizer(deps.llm.tokenizer());
+        let working_set = WorkingSetRetriever::new(
+            deps.workspace
+                .as_ref()
+                .and_then(|ws| ws.default_workspace().embeddings().cloned()),
+        );
+
+        let channels = Arc::new(channels);
+        let outbound = OutboundQueue::new(Arc::clone(&channels), deps.store.clone());
+        let notify_target = heartbeat_config
+            .as_ref()
+            .map(|hb| NotifyTarget::new(hb.notify_channel.clone(), hb.notify_user.clone()))
+            .unwrap_or_default();
+        let mut notifier =
+            NotificationRouter::new(Arc::clone(&channels)).with_default_target(notify_target);
+        if let Some(ref store) = deps.store {
+            notifier = notifier.with_quiet_hours(store.clone(), config.quiet_hours.clone());
+        }
+        let routine_monitor = deps.store.as_ref().map(|store| {
+            let store: Arc<dyn crate::db::Database> = store.clone();
+            Arc::new(
+                RoutineMonitor::new(store, config.routine_alert_after)
+                    .with_notifier(notifier.clone()),
+            )
+        });
+        let labels = deps
+            .store
+            .as_ref()
+            .filter(|_| config.labels.enabled)
+            .map(|store| {
+                Arc::new(LabelBatcher::new(
+                    config.labels.clone(),
+                    deps.llm.clone(),
+                    store.clone(),
+                    priority.clone(),
+                ))
+            });
 
         Self {
             config,
             deps,
-            channels: Arc::new(channels),
+            channels,
+            outbound,
             context_manager,
             scheduler,
             router: Router::new(),
             session_manager,
-            context_monitor: ContextMonitor::new(),
+            context_monitor,
             optimizer: crate::sneed_engine::SovereignOptimizer::new(),
-            stakes: Arc::new(Mutex::new(crate::sneed_engine::StakesEngine::new())),
+            stakes: Arc::new(crate::sneed_engine::UserStakes::new()),
+            roster_watcher: Mutex::new(roster_watcher),
+            custom_rosters: Mutex::new(HashSet::new()),
             grid: Arc::new(Mutex::new(crate::sneed_engine::SovereignGrid::new(3, 8))),
             heartbeat_config,
             cache_manager,
+            working_set,
+            priority,
+            metrics: Arc::new(Mutex::new(MetricsCollector::new())),
+            notifier,
+            routine_monitor,
+            labels,
+            pause,
         }
     }
 
+    /// Per-user StakesEngines driving deliberation (for diagnostics tools).
+    pub fn stakes_engines(&self) -> Arc<crate::sneed_engine::UserStakes> {
+        Arc::clone(&self.stakes)
+    }
+
+    /// Shared SovereignGrid driving the utility audit (for diagnostics tools).
+    pub fn sovereign_grid(&self) -> Arc<Mutex<crate::sneed_engine::SovereignGrid>> {
+        Arc::clone(&self.grid)
+    }
+
+    /// Shared quality metrics, including user feedback (for diagnostics tools).
+    pub fn quality_metrics(&self) -> Arc<Mutex<MetricsCollector>> {
+        Arc::clone(&self.metrics)
+    }
+
     // Convenience accessors
     fn store(&self) -> Option<&Arc<Store>> {
         self.deps.store.as_ref()
@@ -148,8 +347,253 @@ impl Agent {
         &self.deps.tools
     }
 
-    fn workspace(&self) -> Option<&Arc<Workspace>> {
-        self.deps.workspace.as_ref()
+    /// Workspace of the user the current message is being handled for.
+    fn workspace(&self) -> Option<Arc<Workspace>> {
+        let workspaces = self.deps.workspace.as_ref()?;
+        Some(match crate::tenancy::current() {
+            Some(tenant) => workspaces.for_user(tenant.user_id()),
+            None => Arc::clone(workspaces.default_workspace()),
+        })
+    }
+
+    /// The user's StakesEngine, loaded from their persisted state on first use.
+    async fn load_stakes_for(
+        &self,
+        user_id: &str,
+    ) -> Arc<Mutex<crate::sneed_engine::StakesEngine>> {
+        if let Some(engine) = self.stakes.get(user_id) {
+            return engine;
+        }
+        self.evict_idle_stakes().await;
+
+        let saved = match self.store() {
+            Some(store) => match store.get_setting_full(user_id, STAKES_STATE_KEY).await {
+                Ok(record) => record.and_then(|r| serde_json::from_value(r.value).ok()),
+                Err(e) => {
+                    tracing::warn!("Failed to load stakes state for {}: {}", user_id, e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let user_roster = match self.store() {
+            Some(store) => match store.get_setting_full(user_id, COUNCIL_ROSTER_KEY).await {
+                Ok(Some(record)) => {
+                    match crate::sneed_engine::CouncilRoster::from_value(record.value) {
+                        Ok(roster) => Some(roster),
+                        Err(e) => {
+                            tracing::warn!("Ignoring council roster for {}: {}", user_id, e);
+                            None
+                        }
+                    }
+                }
+                Ok(None) => None,
+                Err(e) => {
+                    tracing::warn!("Failed to load council roster for {}: {}", user_id, e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let roster = match user_roster {
+            Some(roster) => {
+                self.custom_rosters.lock().await.insert(user_id.to_string());
+                Some(roster)
+            }
+            None => self.roster_watcher.lock().await.roster().cloned(),
+        };
+
+        // Build the roster first so saved resonance histories land on the right members
+        let mut stakes = crate::sneed_engine::StakesEngine::new();
+        stakes.set_roster(roster);
+        if let Some(snapshot) = saved {
+            stakes.restore(&snapshot, chrono::Utc::now());
+        }
+        self.stakes.insert(user_id, stakes)
+    }
+
+    /// Hot-reload the council roster file if it changed on disk.
+    async fn refresh_council(&self) {
+        let reloaded = self.roster_watcher.lock().await.poll();
+        match reloaded {
+            None => return,
+            Some(Ok(())) => tracing::info!("Council roster reloaded"),
+            Some(Err(e)) => {
+                tracing::warn!("Council roster not reloaded, keeping previous: {}", e);
+                return;
+            }
+        }
+
+        // Users with their own `council` setting keep it
+        let roster = self.roster_watcher.lock().await.roster().cloned();
+        let custom = self.custom_rosters.lock().await.clone();
+        for (user_id, stakes) in self.stakes.loaded() {
+            if !custom.contains(&user_id) {
+                stakes.lock().await.set_roster(roster.clone());
+            }
+        }
+    }
+
+    /// Write a memory-trigger auto-log through `memory_write`, unless it repeats
+    /// the excerpt of one of today's recent auto-logs.
+    async fn write_auto_log(&self, workspace: &Workspace, user_id: &str, entry: &str) {
+        match workspace.today_log().await {
+            Ok(log) if is_duplicate_auto_log(&log.content, entry) => {
+                tracing::debug!("Skipping duplicate auto-log for {}", user_id);
+                return;
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to read daily log for auto-log dedup: {}", e),
+        }
+
+        let job_ctx = JobContext::with_user(user_id, "auto-log", "Sovereign memory auto-log");
+        let params = serde_json::json!({ "content": entry, "target": "daily_log" });
+        if let Err(e) = self
+            .execute_chat_tool("memory_write", &params, &job_ctx)
+            .await
+        {
+            tracing::warn!("Failed to auto-log memory: {}", e);
+        }
+    }
+
+    /// Load a conversation's variables, first capturing an implicit
+    /// "remember X as Y" from `content` if there is one.
+    async fn conversation_variables(
+        &self,
+        thread_id: Uuid,
+        content: Option<&str>,
+    ) -> ConversationVariables {
+        let Some(store) = self.store() else {
+            return ConversationVariables::new();
+        };
+        let mut vars = match ConversationVariables::load(store.as_ref(), thread_id).await {
+            Ok(vars) => vars,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load conversation variables for {}: {}",
+                    thread_id,
+                    e
+                );
+                return ConversationVariables::new();
+            }
+        };
+
+        if let Some((name, value)) = content.and_then(parse_remember) {
+            match vars.set(&name, value) {
+                Ok(()) => match vars.save(store.as_ref(), thread_id).await {
+                    Ok(()) => tracing::info!("Captured conversation variable '{}'", name),
+                    Err(e) => {
+                        tracing::warn!("Failed to save conversation variable '{}': {}", name, e)
+                    }
+                },
+                Err(e) => tracing::debug!("Ignoring implicit variable capture: {}", e),
+            }
+        }
+        vars
+    }
+
+    /// Persist the user's StakesEngine state.
+    async fn save_stakes_for(&self, user_id: &str) {
+        if let Some(stakes) = self.stakes.get(user_id) {
+            self.persist_stakes(user_id, &stakes).await;
+        }
+    }
+
+    /// Save and unload the engines of users idle for `STAKES_IDLE_TTL`.
+    async fn evict_idle_stakes(&self) {
+        let evicted = self.stakes.evict_idle(STAKES_IDLE_TTL);
+        if evicted.is_empty() {
+            return;
+        }
+        for (user_id, stakes) in &evicted {
+            self.persist_stakes(user_id, stakes).await;
+            self.custom_rosters.lock().await.remove(user_id);
+        }
+        tracing::debug!("Unloaded {} idle stakes engines", evicted.len());
+    }
+
+    /// Write a StakesEngine's state to the user's settings.
+    async fn persist_stakes(
+        &self,
+        user_id: &str,
+        stakes: &Mutex<crate::sneed_engine::StakesEngine>,
+    ) {
+        let Some(store) = self.store() else {
+            return;
+        };
+        let snapshot = stakes.lock().await.snapshot();
+        let value = match serde_json::to_value(&snapshot) {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!("Failed to serialize stakes state: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = store
+            .set_setting(user_id, STAKES_STATE_KEY, &value, None)
+            .await
+        {
+            tracing::warn!("Failed to persist stakes state for {}: {}", user_id, e);
+        }
+    }
+
+    /// Language to respond in for this turn.
+    ///
+    /// A `/lang` override on the thread wins; otherwise the language detected
+    /// in `text` (recorded in the user's settings when it changes), falling
+    /// back to the last one recorded. English needs no instruction.
+    async fn response_language(
+        &self,
+        user_id: &str,
+        session: &Arc<Mutex<Session>>,
+        thread_id: Uuid,
+        text: Option<&str>,
+    ) -> Option<&'static Language> {
+        let pinned = {
+            let sess = session.lock().await;
+            sess.threads
+                .get(&thread_id)
+                .and_then(|t| t.metadata.get(LANGUAGE_KEY))
+                .and_then(|v| v.as_str())
+                .and_then(language::lookup)
+        };
+        if pinned.is_some() {
+            return pinned.filter(|l| l.code != "en");
+        }
+
+        let store = self.store();
+        let (stored, version) = match store {
+            Some(store) => match store.get_setting_full(user_id, LANGUAGE_KEY).await {
+                Ok(Some(record)) => (
+                    record.value.as_str().and_then(language::lookup),
+                    record.version,
+                ),
+                Ok(None) => (None, 0),
+                Err(e) => {
+                    tracing::warn!("Failed to load language for {}: {}", user_id, e);
+                    (None, 0)
+                }
+            },
+            None => (None, 0),
+        };
+
+        let detected = text.and_then(language::detect);
+        if let (Some(detected), Some(store)) = (detected, store)
+            && stored != Some(detected)
+        {
+            // On a conflict the other write (an explicit choice, or another
+            // turn's detection) is newer, so it stands
+            let value = serde_json::Value::String(detected.code.to_string());
+            if let Err(e) = store
+                .set_setting(user_id, LANGUAGE_KEY, &value, Some(version))
+                .await
+            {
+                tracing::warn!("Failed to persist language for {}: {}", user_id, e);
+            }
+        }
+
+        detected.or(stored).filter(|l| l.code != "en")
     }
 
     /// Persist a message to the database.
@@ -163,6 +607,40 @@ impl Agent {
         None
     }
 
+    /// Persist an assistant response with the usage recorded for it.
+    async fn persist_response(&self, thread_id: Uuid, content: &str) -> Option<Uuid> {
+        if let Some(store) = self.store() {
+            match store.add_assistant_message(thread_id, content).await {
+                Ok(id) => return Some(id),
+                Err(e) => tracing::error!("Failed to persist response: {}", e),
+            }
+        }
+        None
+    }
+
+    /// Record the LLM calls made for a conversation, so responses and
+    /// analytics can be attributed their cost.
+    async fn record_llm_usage(&self, thread_id: Uuid, usage: &[crate::llm::LlmUsage]) {
+        let Some(store) = self.store() else {
+            return;
+        };
+        for call in usage {
+            let record = crate::history::LlmCallRecord {
+                job_id: None,
+                conversation_id: Some(thread_id),
+                provider: call.provider,
+                model: &call.model,
+                input_tokens: call.input_tokens,
+                output_tokens: call.output_tokens,
+                cost: call.cost,
+                purpose: Some("chat"),
+            };
+            if let Err(e) = store.record_llm_call(&record).await {
+                tracing::warn!("Failed to record LLM call: {}", e);
+            }
+        }
+    }
+
     /// Delete a persisted message from the database.
     async fn delete_message(&self, message_id: Uuid) {
         if let Some(store) = self.store() {
@@ -289,73 +767,67 @@ impl Agent {
             }
         });
 
+        // Sample queue depths for the metrics endpoint
+        let scheduler = self.scheduler.clone();
+        let context_manager = self.context_manager.clone();
+        let metrics_handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(15));
+            loop {
+                interval.tick().await;
+                crate::observability::set_queue_depth("jobs", scheduler.running_count().await);
+                crate::observability::set_queue_depth("subtasks", scheduler.subtask_count().await);
+                crate::observability::set_queue_depth(
+                    "active_contexts",
+                    context_manager.active_count().await,
+                );
+            }
+        });
+
+        // Periodic report of tool failure trends
+        let failure_report_handle = match self.store() {
+            Some(store) if !self.config.failure_report_interval.is_zero() => {
+                let store = store.clone();
+                let notifier = self.notifier.clone();
+                let monitor = self.routine_monitor.clone();
+                let owner = self
+                    .heartbeat_config
+                    .as_ref()
+                    .and_then(|hb| hb.notify_user.clone())
+                    .unwrap_or_else(|| "default".to_string());
+                let period = self.config.failure_report_interval;
+                Some(tokio::spawn(async move {
+                    let mut interval = tokio::time::interval(period);
+                    interval.tick().await; // Skip immediate first tick
+                    loop {
+                        interval.tick().await;
+                        send_failure_report(&store, &notifier, monitor.as_deref(), &owner, period)
+                            .await;
+                    }
+                }))
+            }
+            _ => None,
+        };
+
+        // Title and tag conversations in periodic batches
+        let labels_handle = self.labels.clone().map(LabelBatcher::spawn);
+
         // Spawn heartbeat if enabled
         let heartbeat_handle = if let Some(ref hb_config) = self.heartbeat_config {
             if hb_config.enabled {
                 if let Some(workspace) = self.workspace() {
-                    let config = AgentHeartbeatConfig::default()
+                    let mut config = AgentHeartbeatConfig::default()
                         .with_interval(std::time::Duration::from_secs(hb_config.interval_secs));
+                    config.notify_user_id = hb_config.notify_user.clone();
+                    config.notify_channel = hb_config.notify_channel.clone();
 
-                    // Set up notification channel
+                    // Forward findings through the notification router, which
+                    // targets the configured channel/user or broadcasts
                     let (notify_tx, mut notify_rx) =
                         tokio::sync::mpsc::channel::<OutgoingResponse>(16);
-
-                    // Spawn notification forwarder that routes through channel manager
-                    let notify_channel = hb_config.notify_channel.clone();
-                    let notify_user = hb_config.notify_user.clone();
-                    let channels = self.channels.clone();
+                    let notifier = self.notifier.clone();
                     tokio::spawn(async move {
                         while let Some(response) = notify_rx.recv().await {
-                            // Route notification to configured channel/user, or broadcast to all
-                            match (&notify_channel, &notify_user) {
-                                (Some(channel), Some(user)) => {
-                                    // Send to specific channel and user
-                                    if let Err(e) =
-                                        channels.broadcast(channel, user, response.clone()).await
-                                    {
-                                        tracing::warn!(
-                                            "Failed to send heartbeat to {}/{}: {}",
-                                            channel,
-                                            user,
-                                            e
-                                        );
-                                    } else {
-                                        tracing::debug!(
-                                            "Heartbeat notification sent to {}/{}",
-                                            channel,
-                                            user
-                                        );
-                                    }
-                                }
-                                (None, Some(user)) => {
-                                    // Broadcast to all channels for this user
-                                    let results = channels.broadcast_all(user, response).await;
-                                    for (ch, result) in results {
-                                        if let Err(e) = result {
-                                            tracing::warn!(
-                                                "Failed to broadcast heartbeat to {}: {}",
-                                                ch,
-                                                e
-                                            );
-                                        }
-                                    }
-                                }
-                                _ => {
-                                    // No explicit target, broadcast to all channels
-                                    // for the default user so notifications actually
-                                    // reach someone instead of vanishing into logs.
-                                    let results = channels.broadcast_all("default", response).await;
-                                    for (ch, result) in results {
-                                        if let Err(e) = result {
-                                            tracing::warn!(
-                                                "Failed to broadcast heartbeat to {}: {}",
-                                                ch,
-                                                e
-                                            );
-                                        }
-                                    }
-                                }
-                            }
+                            notifier.notify(NotifyTarget::default(), response).await;
                         }
                     });
 
@@ -368,6 +840,9 @@ impl Agent {
                         workspace.clone(),
                         self.llm().clone(),
                         Some(notify_tx),
+                        Some(self.priority.clone()),
+                        self.routine_monitor.clone(),
+                        Some(self.pause.clone()),
                     ))
                 } else {
                     tracing::warn!("Heartbeat enabled but no workspace available");
@@ -383,6 +858,29 @@ impl Agent {
         // Main message loop
         tracing::info!("Agent {} ready and listening", self.config.name);
 
+        // Routine-triggered turns run alongside the loop so a user's message
+        // is never queued behind them
+        let mut background: FuturesUnordered<
+            LocalBoxFuture<'_, (IncomingMessage, Result<Option<String>, Error>)>,
+        > = FuturesUnordered::new();
+        let this = &self;
+
+        // Redelivered channel messages are dropped before routing
+        let mut dedup = DedupStore::new(self.config.dedup_window);
+
+        // Non-urgent messages from digest channels wait here for their batch
+        let mut digest = DigestBuffer::new(self.config.digest.clone());
+        let mut digest_tick = tokio::time::interval(DIGEST_CHECK_INTERVAL);
+
+        // Deferred messages are held as one-shot routines in the database
+        let mut defer_tick = tokio::time::interval(DEFER_CHECK_INTERVAL);
+
+        // Notifications held during quiet hours go out when they end
+        let mut quiet_tick = tokio::time::interval(QUIET_HOURS_CHECK_INTERVAL);
+
+        // Messages that arrived during a chat turn, handled after it
+        let mut held: VecDeque<IncomingMessage> = VecDeque::new();
+
         loop {
             let message = tokio::select! {
                 biased;
@@ -390,6 +888,37 @@ impl Agent {
                     tracing::info!("Ctrl+C received, shutting down...");
                     break;
                 }
+                Some((done, result)) = background.next(), if !background.is_empty() => {
+                    self.deliver(&done, result).await;
+                    continue;
+                }
+                _ = digest_tick.tick(), if digest.is_enabled() => {
+                    for message in digest.take_due(std::time::Instant::now()) {
+                        let span = tracing::info_span!(
+                            "channel.digest",
+                            channel = %message.channel,
+                            message_id = %message.id,
+                        );
+                        background.push(
+                            async move {
+                                let result = this.handle_background(&message).instrument(span).await;
+                                (message, result)
+                            }
+                            .boxed_local(),
+                        );
+                    }
+                    continue;
+                }
+                _ = defer_tick.tick(), if self.store().is_some() => {
+                    self.fire_deferred().await;
+                    continue;
+                }
+                _ = quiet_tick.tick(), if self.store().is_some() => {
+                    self.notifier.release_held().await;
+                    continue;
+                }
+                // Lazy so a message is only taken when this branch is chosen
+                Some(m) = async { held.pop_front() }, if !held.is_empty() => m,
                 msg = message_stream.next() => {
                     match msg {
                         Some(m) => m,
@@ -401,35 +930,86 @@ impl Agent {
                 }
             };
 
-            match self.handle_message(&message).await {
-                Ok(Some(response)) if !response.is_empty() => {
-                    let _ = self
-                        .channels
-                        .respond(&message, OutgoingResponse::text(response))
-                        .await;
-                }
-                Ok(Some(_)) => {
-                    // Empty response, nothing to send (e.g. approval handled via send_status)
-                }
-                Ok(None) => {
-                    // Shutdown signal received (/quit, /exit, /shutdown)
-                    tracing::info!("Shutdown command received, exiting...");
-                    break;
-                }
-                Err(e) => {
-                    tracing::error!("Error handling message: {}", e);
-                    let _ = self
-                        .channels
-                        .respond(&message, OutgoingResponse::text(format!("Error: {}", e)))
-                        .await;
+            crate::observability::record_message_received(&message.channel);
+            if !dedup.admit(&message, std::time::Instant::now()) {
+                tracing::debug!(
+                    channel = %message.channel,
+                    "Dropping duplicate delivery of an already handled message"
+                );
+                continue;
+            }
+            if let Some(feedback) = UserFeedback::from_message(&message) {
+                self.record_feedback(feedback).await;
+                continue;
+            }
+            let Some(message) = digest.offer(message) else {
+                continue;
+            };
+            let span = tracing::info_span!(
+                "channel.receive",
+                channel = %message.channel,
+                message_id = %message.id,
+            );
+
+            if Priority::of_message(&message) != Priority::Interactive {
+                background.push(
+                    async move {
+                        let result = this.handle_background(&message).instrument(span).await;
+                        (message, result)
+                    }
+                    .boxed_local(),
+                );
+                continue;
+            }
+
+            let turn = self.handle_message(&message).instrument(span);
+            tokio::pin!(turn);
+            let result = loop {
+                tokio::select! {
+                    result = &mut turn => break result,
+                    Some((done, result)) = background.next(), if !background.is_empty() => {
+                        self.deliver(&done, result).await;
+                    }
+                    Some(next) = message_stream.next() => {
+                        // A pause can't wait for the turn it may be meant to stop
+                        match SubmissionParser::parse(&next.content) {
+                            Submission::Pause { args } => {
+                                let result = self.pause_now(&next, &args, true).await;
+                                self.deliver(&next, result).await;
+                            }
+                            Submission::Unpause { args } => {
+                                let result = self.pause_now(&next, &args, false).await;
+                                self.deliver(&next, result).await;
+                            }
+                            _ => held.push_back(next),
+                        }
+                    }
                 }
+            };
+            if !self.deliver(&message, result).await {
+                // Shutdown signal received (/quit, /exit, /shutdown)
+                tracing::info!("Shutdown command received, exiting...");
+                break;
             }
         }
 
         // Cleanup
         tracing::info!("Agent shutting down...");
+        if digest.pending() > 0 {
+            tracing::warn!(
+                "Dropping {} messages still waiting for a digest",
+                digest.pending()
+            );
+        }
         repair_handle.abort();
         pruning_handle.abort();
+        metrics_handle.abort();
+        if let Some(handle) = failure_report_handle {
+            handle.abort();
+        }
+        if let Some(handle) = labels_handle {
+            handle.abort();
+        }
         if let Some(handle) = heartbeat_handle {
             handle.abort();
         }
@@ -439,7 +1019,184 @@ impl Agent {
         Ok(())
     }
 
+    /// Send the outcome of handling `message` back to its channel.
+    ///
+    /// Returns `false` if the message asked the agent to shut down.
+    async fn deliver(
+        &self,
+        message: &IncomingMessage,
+        result: Result<Option<String>, Error>,
+    ) -> bool {
+        match result {
+            Ok(Some(response)) if !response.is_empty() => {
+                self.outbound.send(message, OutgoingResponse::text(response));
+            }
+            Ok(Some(_)) => {
+                // Empty response, nothing to send (e.g. approval handled via send_status)
+            }
+            Ok(None) => return false,
+            Err(e) => {
+                crate::observability::record_error("agent");
+                tracing::error!("Error handling message: {}", e);
+                self.outbound.send(message, OutgoingResponse::text(format!("Error: {}", e)));
+            }
+        }
+        true
+    }
+
+    /// Send every deferred message that has come due back to where it was
+    /// deferred from, then disable its routine.
+    async fn fire_deferred(&self) {
+        let Some(store) = self.store() else {
+            return;
+        };
+        let now = chrono::Utc::now();
+        let due = match store.list_due_one_shot_routines(now).await {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::warn!("Failed to load due reminders: {}", e);
+                return;
+            }
+        };
+
+        for (owner, mut routine) in due {
+            // Stays due, so it goes out after /resume
+            if self.pause.is_paused(&owner) {
+                continue;
+            }
+            let Some((target, response)) = crate::agent::defer::reminder(&owner, &routine) else {
+                continue;
+            };
+            // Disable before sending so a slow channel can't cause a repeat
+            routine.enabled = false;
+            routine.next_fire_at = None;
+            if let Err(e) = store.update_routine(&routine).await {
+                tracing::warn!("Failed to disable reminder '{}': {}", routine.name, e);
+                continue;
+            }
+            self.notifier.notify(target, response).await;
+
+            if let Some(ref monitor) = self.routine_monitor {
+                let outcome = RunOutcome::Completed {
+                    summary: Some(routine.description.clone()),
+                    messages_sent: 1,
+                };
+                monitor
+                    .record(&owner, &routine, routine.trigger.kind(), now, outcome, &[])
+                    .await;
+            }
+        }
+    }
+
+    /// Handle a background message, recording it as a run of the routine
+    /// that sent it, if any.
+    async fn handle_background(&self, message: &IncomingMessage) -> Result<Option<String>, Error> {
+        if self.pause.is_paused(&message.user_id)
+            && message
+                .content
+                .trim_start()
+                .starts_with(ROUTINE_MESSAGE_PREFIX)
+        {
+            tracing::info!(
+                "Skipping routine message for paused user {}",
+                message.user_id
+            );
+            return Ok(Some(String::new()));
+        }
+        let routine = match self.routine_monitor {
+            Some(ref monitor) => monitor.routine_for(message).await,
+            None => None,
+        };
+        let (Some(monitor), Some((routine, trigger))) = (&self.routine_monitor, routine) else {
+            return self.handle_message(message).await;
+        };
+
+        let run = monitor.begin(&routine, &trigger).await;
+        let started_at = run.started_at;
+        let ((result, usage), activity) = crate::observability::collect_activity(
+            crate::llm::collect_usage(self.handle_message(message)),
+        )
+        .await;
+        let outcome = match result {
+            Ok(ref response) => RunOutcome::Completed {
+                summary: response.clone(),
+                messages_sent: u32::from(response.is_some()),
+            },
+            Err(ref e) => RunOutcome::Failed(e.to_string()),
+        };
+        monitor.finish(&message.user_id, run, outcome, &usage).await;
+
+        let level = report_level(&routine, self.config.routine_report);
+        let report = format_report(level, &activity, &usage, chrono::Utc::now() - started_at);
+        let result = match (result, report) {
+            (Ok(Some(response)), Some(report)) => Ok(Some(format!("{}\n\n{}", response, report))),
+            (result, _) => result,
+        };
+
+        // The result goes to the channel that triggered the routine; push
+        // channels get a copy so it's seen away from that channel too
+        if let Ok(Some(ref response)) = result {
+            let push =
+                OutgoingResponse::text(format!("*Routine: {}*\n\n{}", routine.name, response));
+            self.notifier.push(push).await;
+        }
+        result
+    }
+
+    /// Admit a message against its sender's role and quota, then handle it on
+    /// their behalf.
     async fn handle_message(&self, message: &IncomingMessage) -> Result<Option<String>, Error> {
+        let tenant = match self.deps.tenants.admit(&message.user_id) {
+            Ok(tenant) => tenant,
+            Err(e) => {
+                tracing::info!(
+                    "Refused message from {} on {}: {}",
+                    message.user_id,
+                    message.channel,
+                    e
+                );
+                return Ok(Some(e.to_string()));
+            }
+        };
+        if let Some(ref workspaces) = self.deps.workspace {
+            workspaces.prepare(&message.user_id).await;
+        }
+        let transcribed = match self.deps.transcriber {
+            Some(ref transcriber) => transcriber.transcribe_message(message).await,
+            None => None,
+        };
+        let message = transcribed.as_ref().unwrap_or(message);
+        // Hold background work back for the whole chat turn
+        let _turn = match Priority::of_message(message) {
+            Priority::Interactive => Some(self.priority.acquire(Priority::Interactive).await),
+            _ => None,
+        };
+        crate::tenancy::scoped(tenant, self.dispatch_message(message)).await
+    }
+
+    /// Apply a `/pause` or `/resume` that arrived during a chat turn, without
+    /// waiting for the turn to finish.
+    async fn pause_now(
+        &self,
+        message: &IncomingMessage,
+        args: &str,
+        pause: bool,
+    ) -> Result<Option<String>, Error> {
+        let tenant = match self.deps.tenants.admit(&message.user_id) {
+            Ok(tenant) => tenant,
+            Err(e) => return Ok(Some(e.to_string())),
+        };
+        let result =
+            crate::tenancy::scoped(tenant, async { self.process_pause(message, args, pause) })
+                .await?;
+        Ok(Some(match result {
+            SubmissionResult::Response { content } => content,
+            SubmissionResult::Error { message } => format!("Error: {}", message),
+            _ => String::new(),
+        }))
+    }
+
+    async fn dispatch_message(&self, message: &IncomingMessage) -> Result<Option<String>, Error> {
         // Parse submission type first
         let submission = SubmissionParser::parse(&message.content);
 
@@ -491,6 +1248,9 @@ impl Agent {
         // Process based on submission type
         let result = match submission {
             Submission::UserInput { content } => {
+                // The model can't see message metadata, so name the files
+                let content =
+                    crate::channels::describe_attachments(&content, &message.attachments);
                 self.process_user_input(message, session, thread_id, &content)
                     .await
             }
@@ -503,6 +1263,25 @@ impl Agent {
             Submission::Heartbeat => self.process_heartbeat().await,
             Submission::Summarize => self.process_summarize(session, thread_id).await,
             Submission::Suggest => self.process_suggest(session, thread_id).await,
+            Submission::Model { scope, name } => {
+                self.process_model(message, session, thread_id, scope, name)
+                    .await
+            }
+            Submission::Cost => self.process_cost(message, session, thread_id).await,
+            Submission::Redeliver => self.process_redeliver(message),
+            Submission::Routines => self.process_routines(message).await,
+            Submission::Defer { when } => {
+                self.process_defer(message, session, thread_id, &when).await
+            }
+            Submission::ToolErrors => self.process_tool_errors().await,
+            Submission::QuietHours { args } => self.process_quiet_hours(message, &args).await,
+            Submission::Pause { args } => self.process_pause(message, &args, true),
+            Submission::Unpause { args } => self.process_pause(message, &args, false),
+            Submission::Quit if crate::tenancy::current().is_some_and(|t| !t.role().is_admin()) => {
+                Ok(SubmissionResult::error(
+                    "Only an admin can shut down the agent.",
+                ))
+            }
             Submission::Quit => return Ok(None),
             Submission::SwitchThread { thread_id: target } => {
                 self.process_switch_thread(message, target).await
@@ -627,6 +1406,28 @@ impl Agent {
             return Ok(SubmissionResult::error("Input rejected by safety policy."));
         }
 
+        let classification = self
+            .safety()
+            .classify(content, ContentDirection::Inbound)
+            .await;
+        let warned = classification.categories(ClassifierAction::Warn);
+        if !warned.is_empty() {
+            tracing::warn!(
+                "Input from {} classified as: {}",
+                message.user_id,
+                warned.join(", ")
+            );
+        }
+        if classification.action() == Some(ClassifierAction::Block) {
+            return Ok(SubmissionResult::error(format!(
+                "Input rejected by content classifier: {}",
+                classification
+                    .categories(ClassifierAction::Block)
+                    .join(", ")
+            )));
+        }
+        let approval_required = classification.action() == Some(ClassifierAction::RequireApproval);
+
         // Handle explicit commands (starting with /) directly
         // Everything else goes through the normal agentic loop with tools
         let temp_message = IncomingMessage {
@@ -634,7 +1435,9 @@ impl Agent {
             ..message.clone()
         };
 
-        if let Some(intent) = self.router.route_command(&temp_message) {
+        let intent = tracing::info_span!("router.route")
+            .in_scope(|| self.router.route_command(&temp_message));
+        if let Some(intent) = intent {
             // Explicit command like /status, /job, /list - handle directly
             return self.handle_job_or_command(intent, message).await;
         }
@@ -697,7 +1500,7 @@ impl Agent {
 
                 let compactor = ContextCompactor::new(self.llm().clone());
                 if let Err(e) = compactor
-                    .compact(thread, strategy, self.workspace().map(|w| w.as_ref()))
+                    .compact(thread, strategy, self.workspace().as_deref())
                     .await
                 {
                     tracing::warn!("Auto-compaction failed: {}", e);
@@ -733,9 +1536,16 @@ impl Agent {
                 .threads
                 .get_mut(&thread_id)
                 .ok_or_else(|| Error::from(crate::error::JobError::NotFound { id: thread_id }))?;
-            thread.start_turn(content);
+            thread.start_turn(content).approval_required = approval_required;
             thread.messages()
         };
+        let turn_messages = if self.config.working_set {
+            self.working_set
+                .build(turn_messages, content, self.workspace().as_deref())
+                .await
+        } else {
+            turn_messages
+        };
 
         // Send thinking status
         let _ = self
@@ -748,9 +1558,19 @@ impl Agent {
             .await;
 
         // Run the agentic tool execution loop
-        let result = self
-            .run_agentic_loop(message, session.clone(), thread_id, turn_messages, false)
-            .await;
+        let (result, usage) = crate::llm::collect_usage(self.run_agentic_loop(
+            message,
+            session.clone(),
+            thread_id,
+            turn_messages,
+            false,
+        ))
+        .await;
+        self.record_llm_usage(thread_id, &usage).await;
+        let withheld = match &result {
+            Ok(AgenticLoopResult::Response(response)) => self.check_outbound(response).await,
+            _ => None,
+        };
 
         // Re-acquire lock and check if interrupted
         let mut sess = session.lock().await;
@@ -795,8 +1615,16 @@ impl Agent {
                     return Ok(SubmissionResult::error(response));
                 }
 
+                if let Some(notice) = withheld {
+                    thread.fail_turn(&notice);
+                    return Ok(SubmissionResult::error(notice));
+                }
+
                 thread.complete_turn(&response);
-                self.persist_message(thread_id, "assistant", &response).await;
+                self.persist_response(thread_id, &response).await;
+                if let (Some(labels), Some(turn)) = (&self.labels, thread.last_turn()) {
+                    labels.offer(thread_id, turn.turn_number, &message.content, &response);
+                }
 
                 // Memory Safety Pruning: Keep only last 50 turns in memory.
                 // Historical turns are already safely in DB and merged via chat_history_handler.
@@ -812,23 +1640,30 @@ impl Agent {
                 if let Some(workspace) = self.workspace() {
                     let combined_context = format!("User: {}\nAssistant: {}", message.content, response);
                     
-                    // 1. Detect Stakes
-                    let detected_stakes = crate::sneed_engine::StakesEngine::detect_stakes(&combined_context);
+                    // 1. Detect Stakes (the user message was already deliberated on intake)
+                    let signal = if self.config.stakes_engine {
+                        &response
+                    } else {
+                        &combined_context
+                    };
+                    let detected_stakes = crate::sneed_engine::StakesEngine::detect_stakes(signal);
                     
                     // 2. Deliberate (Update Internal State)
-                    let mut stakes_engine = self.stakes.lock().await;
-                    stakes_engine.deliberate(&combined_context, &detected_stakes);
+                    let user_stakes = self.load_stakes_for(&message.user_id).await;
+                    let mut stakes_engine = user_stakes.lock().await;
+                    stakes_engine.deliberate(signal, &detected_stakes);
                     
                     // 3. Check for Memory Trigger
-                    if let Some(log_entry) = stakes_engine.check_memory_trigger() {
+                    let trigger = stakes_engine.check_memory_trigger();
+                    drop(stakes_engine);
+                    if let Some(log_entry) = trigger {
                         tracing::info!("Sovereign Memory Triggered: {}", log_entry);
-                        let full_entry = format!("{}\n\nSummary Context:\n> User: {}\n> Assistant: {}", log_entry, message.content, crate::agent::agent_loop::truncate_for_preview(&response, 200));
-                        
-                        if let Err(e) = workspace.append_daily_log(&full_entry).await {
-                            tracing::warn!("Failed to auto-log memory: {}", e);
-                        }
+                        let entry = auto_log_entry(&log_entry, &message.content, &response);
+                        self.write_auto_log(&workspace, &message.user_id, &entry)
+                            .await;
                     }
                 }
+                self.save_stakes_for(&message.user_id).await;
                 let _ = self
                     .channels
                     .send_status(
@@ -868,6 +1703,48 @@ impl Agent {
         }
     }
 
+    /// Record a user's rating of a response instead of handling it as a turn.
+    async fn record_feedback(&self, feedback: UserFeedback) {
+        tracing::info!(
+            "{} feedback from {} on {} message {}",
+            feedback.rating.as_str(),
+            feedback.user_id,
+            feedback.channel,
+            feedback.message_id.as_deref().unwrap_or("?")
+        );
+        self.metrics.lock().await.record_feedback(feedback.rating);
+        if let Some(store) = self.store()
+            && let Err(e) = store.record_feedback(&feedback).await
+        {
+            tracing::warn!("Failed to persist feedback: {}", e);
+        }
+    }
+
+    /// Classify a response before it goes out. Returns the notice to send in
+    /// its place if a classifier blocks it.
+    async fn check_outbound(&self, response: &str) -> Option<String> {
+        let classification = self
+            .safety()
+            .classify(response, ContentDirection::Outbound)
+            .await;
+        let warned = classification.categories(ClassifierAction::Warn);
+        if !warned.is_empty() {
+            tracing::warn!("Response classified as: {}", warned.join(", "));
+        }
+        if classification.action() != Some(ClassifierAction::Block) {
+            return None;
+        }
+
+        let blocked = classification
+            .categories(ClassifierAction::Block)
+            .join(", ");
+        tracing::warn!("Response withheld by content classifier: {}", blocked);
+        Some(format!(
+            "Response withheld by content classifier: {}",
+            blocked
+        ))
+    }
+
     /// Run the agentic loop: call LLM, execute tools, repeat until text response.
     ///
     /// Returns `AgenticLoopResult::Response` on completion, or
@@ -898,7 +1775,36 @@ impl Agent {
             None
         };
 
-        let mut reasoning = Reasoning::new(self.llm().clone(), self.safety().clone());
+        // Council deliberation on the incoming message, before the prompt is built
+        self.refresh_council().await;
+        let user_stakes = self.load_stakes_for(&message.user_id).await;
+        if self.config.stakes_engine {
+            user_stakes.lock().await.process_message(&message.content);
+        }
+
+        let conversation_vars = self
+            .conversation_variables(
+                thread_id,
+                (!resume_after_tool).then_some(message.content.as_str()),
+            )
+            .await;
+
+        let language = self
+            .response_language(
+                &message.user_id,
+                &session,
+                thread_id,
+                (!resume_after_tool).then_some(message.content.as_str()),
+            )
+            .await;
+
+        let model_override = self.pinned_model(message, &session, thread_id).await;
+        let model_name = model_override
+            .clone()
+            .unwrap_or_else(|| self.llm().model_name().to_string());
+        let mut reasoning = Reasoning::new(self.llm().clone(), self.safety().clone())
+            .with_model(model_override)
+            .with_language(language.map(|l| l.name.to_string()));
         let mut active_cache_id = None;
         if let Some(mut prompt) = system_prompt {
             if self.config.neco_arc_mode {
@@ -952,13 +1858,17 @@ impl Agent {
                 prompt.push_str(&persona.get_cosmic_milkshake_prompt());
             }
 
-            // Inject Emotional Resonance Metadata (Consciousness Layer)
-            {
-                let stakes = self.stakes.lock().await;
-                let report = stakes.get_resonance_report();
-                prompt.push_str(&format!("\n\nYour current internal state (for your eyes only, do not repeat): \n{}", report));
+            // Inject Personality Blend + Emotional Resonance Metadata (Consciousness Layer)
+            if self.config.stakes_engine {
+                let stakes = user_stakes.lock().await;
+                prompt.push_str(&persona.get_personality_blend_prompt(
+                    stakes.get_personality_blend(),
+                    &stakes.get_resonance_report(),
+                ));
             }
 
+            prompt.push_str(&conversation_vars.prompt_block());
+
             let tool_definitions = self.deps.tools.tool_definitions().await;
             active_cache_id = self.cache_manager.ensure_cache(&prompt, tool_definitions).await;
             reasoning = reasoning.with_system_prompt(prompt);
@@ -968,11 +1878,15 @@ impl Agent {
         let mut context_messages = initial_messages;
 
         // Create a JobContext for tool execution (chat doesn't have a real job)
-        let job_ctx = JobContext::with_user(&message.user_id, "chat", "Interactive chat session");
+        let mut job_ctx =
+            JobContext::with_user(&message.user_id, "chat", "Interactive chat session");
+        job_ctx.conversation_id = Some(thread_id);
+        job_ctx.metadata = self.turn_metadata(&session, thread_id).await;
 
         const MAX_TOOL_ITERATIONS: usize = 20;
         let mut iteration = 0;
         let mut tools_executed = resume_after_tool;
+        let priority = Priority::of_message(message);
 
         // Aletheia Pre-process: Scan initial context for memetic hazards
         self.scan_initial_hazards(&reasoning, &message, &context_messages).await;
@@ -1001,11 +1915,20 @@ impl Agent {
                 }
             }
 
+            // Routine turns yield to chat between steps; chat turns hold
+            // their slot for the whole message (see `handle_message`)
+            let _step = match priority {
+                Priority::Interactive => None,
+                class => Some(self.priority.acquire(class).await),
+            };
+
             // Refresh tool definitions each iteration so newly built tools become visible
             let tool_defs = self.tools().tool_definitions().await;
 
             // Perform Sneed Engine coherence and utility check
-            let (is_coherent, utility) = self.calculate_sovereign_utility(iteration).await;
+            let (is_coherent, utility) = self
+                .calculate_sovereign_utility(&user_stakes, iteration)
+                .await;
             let _sovereign_boost = if is_coherent { crate::sneed_engine::TAU_SOVEREIGN } else { 1.0 };
             let optimizer = SovereignOptimizer::new();
 
@@ -1121,24 +2044,38 @@ impl Agent {
                         .await;
 
                     // Record tool calls in the thread
-                    {
+                    let approval_required = {
                         let mut sess = session.lock().await;
-                        if let Some(thread) = sess.threads.get_mut(&thread_id) {
-                            if let Some(turn) = thread.last_turn_mut() {
+                        let turn = sess
+                            .threads
+                            .get_mut(&thread_id)
+                            .and_then(|thread| thread.last_turn_mut());
+                        match turn {
+                            Some(turn) => {
                                 for tc in &tool_calls {
                                     turn.record_tool_call(&tc.name, tc.arguments.clone());
                                 }
+                                turn.approval_required
                             }
+                            None => false,
                         }
-                    }
+                    };
 
                     // Execute each tool (with approval checking)
                     for tc in tool_calls {
+                        // Malformed calls go back to the LLM without asking for approval
+                        let malformed = self
+                            .check_tool_arguments(&tc.name, &tc.arguments, &model_name)
+                            .await;
+
                         // Check if tool requires approval
-                        if let Some(tool) = self.tools().get(&tc.name).await {
-                            if tool.requires_approval() {
-                                // Check if auto-approved for this session
-                                let is_auto_approved = {
+                        if malformed.is_none()
+                            && let Some(tool) = self.tools().get(&tc.name).await
+                        {
+                            if approval_required || tool.requires_approval() {
+                                // Check if auto-approved for this session. A turn
+                                // flagged by a content classifier ignores that.
+                                let is_auto_approved = !approval_required && {
                                     let sess = session.lock().await;
                                     sess.is_tool_auto_approved(&tc.name)
                                 };
@@ -1149,7 +2086,9 @@ impl Agent {
                                         request_id: Uuid::new_v4(),
                                         tool_name: tc.name.clone(),
                                         parameters: tc.arguments.clone(),
-                                        description: tool.description().to_string(),
+                                        description: tool
+                                            .approval_description(&tc.arguments)
+                                            .await,
                                         tool_call_id: tc.id.clone(),
                                         context_messages: context_messages.clone(),
                                     };
@@ -1165,14 +2104,19 @@ impl Agent {
                                 &message.channel,
                                 StatusUpdate::ToolStarted {
                                     name: tc.name.clone(),
+                                    parameters: tc.arguments.clone(),
                                 },
                                 &message.metadata,
                             )
                             .await;
 
-                        let tool_result = self
-                            .execute_chat_tool(&tc.name, &tc.arguments, &job_ctx)
-                            .await;
+                        let tool_result = match malformed {
+                            Some(e) => Err(e),
+                            None => {
+                                self.execute_chat_tool(&tc.name, &tc.arguments, &job_ctx)
+                                    .await
+                            }
+                        };
 
                         let _ = self
                             .channels
@@ -1196,6 +2140,10 @@ impl Agent {
                                         StatusUpdate::ToolResult {
                                             name: tc.name.clone(),
                                             preview: truncate_for_preview(&result_str, 200),
+                                            output: truncate_for_display(
+                                                &result_str,
+                                                TOOL_OUTPUT_DISPLAY_CHARS,
+                                            ),
                                         },
                                         &message.metadata,
                                     )
@@ -1220,6 +2168,13 @@ impl Agent {
                             }
                         }
 
+                        if let Some(prompt) = self
+                            .prompt_for_tool_auth(message, &tool_result, &session, thread_id)
+                            .await
+                        {
+                            return Ok(AgenticLoopResult::Response(prompt));
+                        }
+
                         // If tool_auth returned awaiting_token, enter auth mode
                         // and short-circuit: return the instructions directly so
                         // the LLM doesn't get a chance to hallucinate tool calls.
@@ -1295,7 +2250,7 @@ impl Agent {
                     }
 
                     let (agency_score, _) = {
-                        let mut stakes = self.stakes.lock().await;
+                        let mut stakes = user_stakes.lock().await;
                         stakes.deliberate(&message.content, &detected)
                     };
 
@@ -1316,6 +2271,39 @@ impl Agent {
         }
     }
 
+    /// Check a tool call's arguments against the tool's schema. A mismatch is
+    /// recorded for evaluation and returned as the error to give the LLM.
+    async fn check_tool_arguments(
+        &self,
+        tool_name: &str,
+        params: &serde_json::Value,
+        model: &str,
+    ) -> Option<Error> {
+        let tool = self.tools().get(tool_name).await?;
+        let failure = ArgumentFailure::check(tool_name, model, &tool.parameters_schema(), params)?;
+        tracing::warn!(
+            "{} produced malformed arguments for {}: {}",
+            model,
+            tool_name,
+            failure.errors.join("; ")
+        );
+
+        self.metrics.lock().await.record_argument_failure(&failure);
+        if let Some(store) = self.store()
+            && let Err(e) = store.record_argument_failure(&failure).await
+        {
+            tracing::warn!("Failed to record malformed tool call: {}", e);
+        }
+
+        Some(
+            crate::error::ToolError::InvalidParameters {
+                name: tool_name.to_string(),
+                reason: failure.reason(),
+            }
+            .into(),
+        )
+    }
+
     /// Execute a tool for chat (without full job context).
     async fn execute_chat_tool(
         &self,
@@ -1331,6 +2319,10 @@ impl Agent {
                     name: tool_name.to_string(),
                 })?;
 
+        // Members can't run admin-only tools
+        crate::tenancy::check_tool_access(tool_name)?;
+        self.pause.check_tool(&job_ctx.user_id, tool_name)?;
+
         // Validate tool parameters
         let validation = self.safety().validator().validate_tool_params(params);
         if !validation.is_valid {
@@ -1348,18 +2340,40 @@ impl Agent {
         }
 
         // Execute with timeout
+        let start = std::time::Instant::now();
         let result = tokio::time::timeout(std::time::Duration::from_secs(60), async {
-            tool.execute(params.clone(), job_ctx).await
+            crate::observability::instrument_tool(tool_name, tool.execute(params.clone(), job_ctx))
+                .await
         })
-        .await
-        .map_err(|_| crate::error::ToolError::Timeout {
-            name: tool_name.to_string(),
-            timeout: std::time::Duration::from_secs(60),
-        })?
-        .map_err(|e| crate::error::ToolError::ExecutionFailed {
-            name: tool_name.to_string(),
-            reason: e.to_string(),
-        })?;
+        .await;
+
+        // Index the call so results can be traced back to it
+        let action = ActionRecord::new(0, tool_name, params.clone());
+        let action = match &result {
+            Ok(Ok(output)) => action.succeed(None, output.result.clone(), start.elapsed()),
+            Ok(Err(e)) => action.fail(e.to_string(), start.elapsed()),
+            Err(_) => action.fail("Execution timeout", start.elapsed()),
+        };
+        self.context_manager.index_action(job_ctx, &action).await;
+
+        let result = result
+            .map_err(|_| crate::error::ToolError::Timeout {
+                name: tool_name.to_string(),
+                timeout: std::time::Duration::from_secs(60),
+            })?
+            .map_err(|e| match e {
+                crate::tools::ToolError::AuthRequired(_) => crate::error::ToolError::AuthRequired {
+                    name: tool_name.to_string(),
+                },
+                e => crate::error::ToolError::ExecutionFailed {
+                    name: tool_name.to_string(),
+                    reason: e.to_string(),
+                },
+            })?;
+
+        self.context_manager
+            .record_tool_artifact(job_ctx, tool_name, params, &result.result)
+            .await;
 
         Ok(result)
     }
@@ -1542,7 +2556,7 @@ impl Agent {
 
         let compactor = ContextCompactor::new(self.llm().clone());
         match compactor
-            .compact(thread, strategy, self.workspace().map(|w| w.as_ref()))
+            .compact(thread, strategy, self.workspace().as_deref())
             .await
         {
             Ok(result) => {
@@ -1562,6 +2576,20 @@ impl Agent {
         }
     }
 
+    /// Job metadata identifying the thread's current turn, recorded with
+    /// tool side effects such as memory history commits.
+    async fn turn_metadata(
+        &self,
+        session: &Arc<Mutex<Session>>,
+        thread_id: Uuid,
+    ) -> serde_json::Value {
+        let sess = session.lock().await;
+        match sess.threads.get(&thread_id).and_then(|t| t.last_turn()) {
+            Some(turn) => serde_json::json!({ "turn": turn.turn_number + 1 }),
+            None => serde_json::Value::Null,
+        }
+    }
+
     async fn process_clear(
         &self,
         session: Arc<Mutex<Session>>,
@@ -1648,8 +2676,10 @@ impl Agent {
             }
 
             // Execute the approved tool and continue the loop
-            let job_ctx =
+            let mut job_ctx =
                 JobContext::with_user(&message.user_id, "chat", "Interactive chat session");
+            job_ctx.conversation_id = Some(thread_id);
+            job_ctx.metadata = self.turn_metadata(&session, thread_id).await;
 
             let _ = self
                 .channels
@@ -1657,6 +2687,7 @@ impl Agent {
                     &message.channel,
                     StatusUpdate::ToolStarted {
                         name: pending.tool_name.clone(),
+                        parameters: pending.parameters.clone(),
                     },
                     &message.metadata,
                 )
@@ -1688,6 +2719,10 @@ impl Agent {
                             StatusUpdate::ToolResult {
                                 name: pending.tool_name.clone(),
                                 preview: truncate_for_preview(&result_str, 200),
+                                output: truncate_for_display(
+                                    &result_str,
+                                    TOOL_OUTPUT_DISPLAY_CHARS,
+                                ),
                             },
                             &message.metadata,
                         )
@@ -1715,6 +2750,17 @@ impl Agent {
                 }
             }
 
+            if let Some(prompt) = self
+                .prompt_for_tool_auth(message, &tool_result, &session, thread_id)
+                .await
+            {
+                let mut sess = session.lock().await;
+                if let Some(thread) = sess.threads.get_mut(&thread_id) {
+                    thread.complete_turn(&prompt);
+                }
+                return Ok(SubmissionResult::response(prompt));
+            }
+
             // If tool_auth returned awaiting_token, enter auth mode and
             // return instructions directly (skip agentic loop continuation).
             if let Ok(output) = &tool_result {
@@ -1772,9 +2818,19 @@ impl Agent {
             }
 
             // Continue the agentic loop (a tool was already executed this turn)
-            let result = self
-                .run_agentic_loop(message, session.clone(), thread_id, context_messages, true)
-                .await;
+            let (result, usage) = crate::llm::collect_usage(self.run_agentic_loop(
+                message,
+                session.clone(),
+                thread_id,
+                context_messages,
+                true,
+            ))
+            .await;
+            self.record_llm_usage(thread_id, &usage).await;
+            let withheld = match &result {
+                Ok(AgenticLoopResult::Response(response)) => self.check_outbound(response).await,
+                _ => None,
+            };
 
             // Handle the result
             let mut sess = session.lock().await;
@@ -1785,6 +2841,11 @@ impl Agent {
 
             match result {
                 Ok(AgenticLoopResult::Response(response)) => {
+                    if let Some(notice) = withheld {
+                        thread.fail_turn(&notice);
+                        return Ok(SubmissionResult::error(notice));
+                    }
+
                     thread.complete_turn(&response);
                     
                     // Run isometric merge with decay 0.15 on turn boundary
@@ -1857,6 +2918,68 @@ impl Agent {
         }
     }
 
+    /// If a tool call failed for want of a credential, ask the user to
+    /// connect the tool instead of handing the error back to the LLM.
+    ///
+    /// Sends an auth-required status (with a one-click link when the tool
+    /// supports OAuth) and enters auth mode if a pasted token is expected.
+    /// Users who may not run `tool_auth` are told to ask an admin instead.
+    /// Returns `None` for any other result, or when the extension manager has
+    /// nothing to offer, in which case the result goes back to the LLM as usual.
+    async fn prompt_for_tool_auth(
+        &self,
+        message: &IncomingMessage,
+        tool_result: &Result<crate::tools::ToolOutput, Error>,
+        session: &Arc<Mutex<Session>>,
+        thread_id: Uuid,
+    ) -> Option<String> {
+        let Err(Error::Tool(crate::error::ToolError::AuthRequired { name: tool_name })) =
+            tool_result
+        else {
+            return None;
+        };
+        let ext_mgr = self.deps.extension_manager.as_ref()?;
+        // Connecting a tool stores a deployment-wide credential, so only
+        // admins are walked through it
+        if crate::tenancy::check_tool_access("tool_auth").is_err() {
+            return Some(format!(
+                "`{}` needs to be connected before I can use it. Ask an admin to connect it.",
+                tool_name
+            ));
+        }
+        let result = match ext_mgr.auth(tool_name, None).await {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::debug!("No auth prompt for {}: {}", tool_name, e);
+                return None;
+            }
+        };
+        if matches!(result.status.as_str(), "authenticated" | "no_auth_required") {
+            return None;
+        }
+
+        let _ = self
+            .channels
+            .send_status(
+                &message.channel,
+                StatusUpdate::AuthRequired {
+                    extension_name: tool_name.to_string(),
+                    instructions: result.instructions.clone(),
+                    auth_url: result.auth_url.clone(),
+                    setup_url: result.setup_url.clone(),
+                },
+                &message.metadata,
+            )
+            .await;
+        if result.awaiting_token {
+            let mut sess = session.lock().await;
+            if let Some(thread) = sess.threads.get_mut(&thread_id) {
+                thread.enter_auth_mode(tool_name.to_string());
+            }
+        }
+        Some(auth_prompt(tool_name, &result))
+    }
+
     /// Handle an auth token submitted while the thread is in auth mode.
     ///
     /// The token goes directly to the extension manager's credential store,
@@ -2176,13 +3299,17 @@ impl Agent {
             ));
         };
 
-        let runner = crate::agent::HeartbeatRunner::new(
-            crate::agent::HeartbeatConfig::default(),
-            workspace.clone(),
-            self.llm().clone(),
-        );
+        let mut config = crate::agent::HeartbeatConfig::default();
+        if let Some(ref hb_config) = self.heartbeat_config {
+            config.notify_user_id = hb_config.notify_user.clone();
+        }
+        let mut runner =
+            crate::agent::HeartbeatRunner::new(config, workspace.clone(), self.llm().clone());
+        if let Some(ref monitor) = self.routine_monitor {
+            runner = runner.with_monitor(Arc::clone(monitor));
+        }
 
-        match runner.check_heartbeat().await {
+        match runner.run_check("manual").await {
             crate::agent::HeartbeatResult::Ok => Ok(SubmissionResult::ok_with_message(
                 "Heartbeat: all clear, nothing needs attention.",
             )),
@@ -2199,6 +3326,561 @@ impl Agent {
         }
     }
 
+    /// Models to validate pins against: the catalog, or the provider's list
+    /// when the catalog hasn't been filled yet. Empty if neither is known.
+    async fn available_models(&self) -> Vec<CatalogModel> {
+        let models = self.deps.model_catalog.models().await;
+        if !models.is_empty() {
+            return models;
+        }
+        self.llm()
+            .list_models()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(CatalogModel::new)
+            .collect()
+    }
+
+    /// A string-valued per-user setting.
+    async fn string_setting(&self, user_id: &str, key: &str) -> Option<String> {
+        let store = self.store()?;
+        match store.get_setting_full(user_id, key).await {
+            Ok(record) => record.and_then(|r| r.value.as_str().map(String::from)),
+            Err(e) => {
+                tracing::warn!("Failed to load setting {} for {}: {}", key, user_id, e);
+                None
+            }
+        }
+    }
+
+    /// The conversation's model pin as persisted with it, for threads
+    /// rebuilt without their metadata (e.g. after a restart).
+    async fn conversation_model_pin(&self, thread_id: Uuid) -> Option<String> {
+        let store = self.store()?;
+        let db: &dyn crate::db::Database = store.as_ref();
+        match db.get_conversation_metadata(thread_id).await {
+            Ok(metadata) => metadata?.get(MODEL_KEY)?.as_str().map(String::from),
+            Err(e) => {
+                tracing::warn!("Failed to load model pin for {}: {}", thread_id, e);
+                None
+            }
+        }
+    }
+
+    /// The model pinned for handling `message`, if any: the routine's pin
+    /// for routine runs, then the thread's, then the user's default.
+    ///
+    /// Pins are checked against the catalog when it is known, so a model
+    /// that has since been withdrawn falls back to the next pin rather than
+    /// failing every turn.
+    async fn pinned_model(
+        &self,
+        message: &IncomingMessage,
+        session: &Arc<Mutex<Session>>,
+        thread_id: Uuid,
+    ) -> Option<String> {
+        let routine_pin = match crate::agent::routine::routine_name(&message.content) {
+            Some(routine) => {
+                let key = format!("{}{}", ROUTINE_MODEL_KEY_PREFIX, routine);
+                self.string_setting(&message.user_id, &key).await
+            }
+            None => None,
+        };
+        let thread_pin = {
+            let sess = session.lock().await;
+            sess.threads
+                .get(&thread_id)
+                .and_then(|t| t.metadata.get(MODEL_KEY))
+                .cloned()
+        };
+        // A cleared pin is kept as null, so only an unknown one is looked up
+        let thread_pin = match thread_pin {
+            Some(pin) => pin.as_str().map(String::from),
+            None => self.conversation_model_pin(thread_id).await,
+        };
+        let user_pin = self.string_setting(&message.user_id, MODEL_KEY).await;
+
+        if routine_pin.is_none() && thread_pin.is_none() && user_pin.is_none() {
+            return None;
+        }
+        let available = self.deps.model_catalog.models().await;
+        first_available_pin([routine_pin, thread_pin, user_pin], &available)
+    }
+
+    /// Show the models in effect and what's available, or pin a model for a
+    /// conversation, for all of the user's conversations, or for a routine.
+    async fn process_model(
+        &self,
+        message: &IncomingMessage,
+        session: Arc<Mutex<Session>>,
+        thread_id: Uuid,
+        scope: ModelScope,
+        name: Option<String>,
+    ) -> Result<SubmissionResult, Error> {
+        let default_model = self.llm().model_name().to_string();
+        let routine_key = match &scope {
+            ModelScope::Routine(routine) => Some(format!("{}{}", ROUTINE_MODEL_KEY_PREFIX, routine)),
+            _ => None,
+        };
+
+        let Some(name) = name else {
+            if let (ModelScope::Routine(routine), Some(key)) = (&scope, &routine_key) {
+                let pinned = self.string_setting(&message.user_id, key).await;
+                return Ok(SubmissionResult::response(match pinned {
+                    Some(model) => format!("Routine '{}' runs on {}.", routine, model),
+                    None => format!(
+                        "Routine '{}' has no pinned model; it uses your default.",
+                        routine
+                    ),
+                }));
+            }
+
+            let user_default = self.string_setting(&message.user_id, MODEL_KEY).await;
+            let current = self
+                .pinned_model(message, &session, thread_id)
+                .await
+                .unwrap_or_else(|| default_model.clone());
+            let mut out = format!("Model: {}", current);
+            match user_default {
+                Some(model) => out.push_str(&format!(
+                    "\nYour default: {} (server default: {})",
+                    model, default_model
+                )),
+                None => out.push_str(&format!("\nServer default: {}", default_model)),
+            }
+            let available = self.available_models().await;
+            if !available.is_empty() {
+                out.push_str("\n\nAvailable models:\n");
+                for model in &available {
+                    let summary = model.summary();
+                    if summary.is_empty() {
+                        out.push_str(&format!("  {}\n", model.id));
+                    } else {
+                        out.push_str(&format!("  {} ({})\n", model.id, summary));
+                    }
+                }
+            }
+            out.push_str(
+                "\nPin for this conversation with /model <name>, for all your conversations \
+                 with /model all <name>, or for a routine with /model routine <routine> <name>. \
+                 Use default as the name to remove a pin.",
+            );
+            return Ok(SubmissionResult::response(out));
+        };
+
+        let clear = name == "default";
+        let mut note = String::new();
+        if !clear {
+            let available = self.available_models().await;
+            let known = available.iter().find(|m| m.id == name);
+            if !available.is_empty() && known.is_none() {
+                return Ok(SubmissionResult::error(format!(
+                    "Unknown model '{}'. Run /model to list available models.",
+                    name
+                )));
+            }
+            if known.is_some_and(|m| m.supports_tools == Some(false)) {
+                note.push_str(" Note: this model doesn't support tool use.");
+            }
+        }
+
+        let message_text = match scope {
+            ModelScope::Conversation => {
+                let pin = if clear {
+                    serde_json::Value::Null
+                } else {
+                    serde_json::Value::String(name.clone())
+                };
+                {
+                    let mut sess = session.lock().await;
+                    let Some(thread) = sess.threads.get_mut(&thread_id) else {
+                        return Ok(SubmissionResult::error("No active thread."));
+                    };
+                    if thread.metadata.is_null() {
+                        thread.metadata = serde_json::json!({});
+                    }
+                    thread.metadata[MODEL_KEY] = pin.clone();
+                }
+
+                // Persist with the conversation so the pin survives a restart
+                if let Some(store) = self.store() {
+                    self.ensure_conversation_persisted(
+                        thread_id,
+                        &message.user_id,
+                        &message.channel,
+                        message.thread_id.as_deref(),
+                    )
+                    .await;
+                    let db: &dyn crate::db::Database = store.as_ref();
+                    if let Err(e) = db
+                        .update_conversation_metadata_field(thread_id, MODEL_KEY, &pin)
+                        .await
+                    {
+                        tracing::warn!("Failed to persist model pin for {}: {}", thread_id, e);
+                    }
+                }
+
+                if clear {
+                    "This conversation now uses your default model.".to_string()
+                } else {
+                    format!("Using {} for this conversation.", name)
+                }
+            }
+            ModelScope::User | ModelScope::Routine(_) => {
+                let Some(store) = self.store() else {
+                    return Ok(SubmissionResult::error(
+                        "Saving a default model requires the database to be connected.",
+                    ));
+                };
+                let key = routine_key.as_deref().unwrap_or(MODEL_KEY);
+                let result = if clear {
+                    store.delete_setting(&message.user_id, key).await
+                } else {
+                    match store.get_setting_full(&message.user_id, key).await {
+                        Ok(record) => {
+                            let version = record.map(|r| r.version).unwrap_or(0);
+                            store
+                                .set_setting(
+                                    &message.user_id,
+                                    key,
+                                    &serde_json::json!(name),
+                                    Some(version),
+                                )
+                                .await
+                                .map(|_| ())
+                        }
+                        Err(e) => Err(e),
+                    }
+                };
+                match result {
+                    Err(crate::error::DatabaseError::Conflict { .. }) => {
+                        return Ok(SubmissionResult::error(
+                            "The model setting was changed elsewhere just now. Check it with /model and try again.",
+                        ));
+                    }
+                    Err(e) => {
+                        return Ok(SubmissionResult::error(format!(
+                            "Failed to save model setting: {}",
+                            e
+                        )));
+                    }
+                    Ok(()) => {}
+                }
+                match (&scope, clear) {
+                    (ModelScope::Routine(routine), true) => {
+                        format!("Routine '{}' now uses your default model.", routine)
+                    }
+                    (ModelScope::Routine(routine), false) => {
+                        format!("Routine '{}' will run on {}.", routine, name)
+                    }
+                    (_, true) => format!("Your conversations now use {}.", default_model),
+                    (_, false) => format!(
+                        "Using {} for conversations without their own pin.",
+                        name
+                    ),
+                }
+            }
+        };
+        Ok(SubmissionResult::ok_with_message(message_text + &note))
+    }
+
+    /// Report LLM spend for the current user today.
+    async fn process_cost(
+        &self,
+        message: &IncomingMessage,
+        session: Arc<Mutex<Session>>,
+        thread_id: Uuid,
+    ) -> Result<SubmissionResult, Error> {
+        let model = self
+            .pinned_model(message, &session, thread_id)
+            .await
+            .unwrap_or_else(|| self.llm().model_name().to_string());
+        let (input, output) = match self.deps.model_catalog.get(&model).await {
+            Some(CatalogModel {
+                input_cost: Some(input),
+                output_cost: Some(output),
+                ..
+            }) => (input, output),
+            _ => self.llm().cost_per_token(),
+        };
+        let per_million = rust_decimal::Decimal::from(1_000_000);
+
+        let spent = match crate::tenancy::current() {
+            Some(scope) => format!("${:.4}", scope.cost_today()),
+            None => "unavailable".to_string(),
+        };
+        Ok(SubmissionResult::response(format!(
+            "Spend today (UTC): {}\nModel: {} (${:.2} / ${:.2} per 1M input / output tokens)",
+            spent,
+            model,
+            input * per_million,
+            output * per_million
+        )))
+    }
+
+    /// Summarize the user's routines and their latest runs.
+    async fn process_routines(&self, message: &IncomingMessage) -> Result<SubmissionResult, Error> {
+        let Some(ref monitor) = self.routine_monitor else {
+            return Ok(SubmissionResult::error(
+                "Routines require the database to be connected.",
+            ));
+        };
+        match monitor.status_summary(&message.user_id).await {
+            Ok(summary) => Ok(SubmissionResult::response(summary)),
+            Err(e) => Ok(SubmissionResult::error(format!(
+                "Failed to load routines: {}",
+                e
+            ))),
+        }
+    }
+
+    /// Store the thread's last user message as a one-shot routine that brings
+    /// it back at `when`.
+    async fn process_defer(
+        &self,
+        message: &IncomingMessage,
+        session: Arc<Mutex<Session>>,
+        thread_id: Uuid,
+        when: &str,
+    ) -> Result<SubmissionResult, Error> {
+        let Some(store) = self.store() else {
+            return Ok(SubmissionResult::error(
+                "Reminders require the database to be connected.",
+            ));
+        };
+        let Some(at) = crate::agent::defer::parse_when(when, &chrono::Local::now()) else {
+            return Ok(SubmissionResult::error(format!(
+                "Couldn't tell when '{}' is. Try /defer in 2h, /defer tomorrow at 9, or /defer friday 14:30.",
+                when
+            )));
+        };
+        let text = {
+            let sess = session.lock().await;
+            sess.threads
+                .get(&thread_id)
+                .and_then(|t| t.last_turn())
+                .map(|turn| turn.user_input.clone())
+        };
+        let Some(text) = text else {
+            return Ok(SubmissionResult::error(
+                "Nothing to defer yet. Send the message first, then /defer <when>.",
+            ));
+        };
+
+        let excerpt = crate::agent::defer::excerpt(&text);
+        let routine = Deferral {
+            text,
+            channel: message.channel.clone(),
+            user_id: message.user_id.clone(),
+            thread_id: message.thread_id.clone(),
+            conversation_id: thread_id,
+            at,
+        }
+        .into_routine(chrono::Utc::now());
+        if let Err(e) = store.create_routine(&message.user_id, &routine).await {
+            return Ok(SubmissionResult::error(format!(
+                "Failed to save the reminder: {}",
+                e
+            )));
+        }
+
+        Ok(SubmissionResult::response(format!(
+            "⏰ I'll bring back \"{}\" on {}.",
+            excerpt,
+            at.with_timezone(&chrono::Local).format("%a %b %-d at %H:%M")
+        )))
+    }
+
+    /// Show or change the user's quiet hours.
+    async fn process_quiet_hours(
+        &self,
+        message: &IncomingMessage,
+        args: &str,
+    ) -> Result<SubmissionResult, Error> {
+        let Some(store) = self.store() else {
+            return Ok(SubmissionResult::error(
+                "Quiet hours require the database to be connected.",
+            ));
+        };
+        let (current, version) = match store
+            .get_setting_full(&message.user_id, QUIET_HOURS_KEY)
+            .await
+        {
+            Ok(Some(record)) => (
+                serde_json::from_value::<QuietHours>(record.value).ok(),
+                record.version,
+            ),
+            Ok(None) => (None, 0),
+            Err(e) => {
+                return Ok(SubmissionResult::error(format!(
+                    "Failed to load quiet hours: {}",
+                    e
+                )));
+            }
+        };
+        if args.is_empty() {
+            return Ok(SubmissionResult::response(match current {
+                Some(quiet) => quiet.describe(),
+                None => format!("Quiet hours are off.\n{}", QUIET_USAGE),
+            }));
+        }
+
+        let updated = match quiet_hours::apply_command(current, args) {
+            Ok(updated) => updated,
+            Err(e) => return Ok(SubmissionResult::error(e)),
+        };
+        let saved = match updated {
+            Some(ref quiet) => {
+                let value = serde_json::to_value(quiet).unwrap_or_default();
+                store
+                    .set_setting(&message.user_id, QUIET_HOURS_KEY, &value, Some(version))
+                    .await
+                    .map(|_| ())
+            }
+            None => {
+                store
+                    .delete_setting(&message.user_id, QUIET_HOURS_KEY)
+                    .await
+            }
+        };
+        match saved {
+            Err(crate::error::DatabaseError::Conflict { .. }) => {
+                return Ok(SubmissionResult::error(
+                    "Quiet hours were changed elsewhere just now. Check them with /quiet and try again.",
+                ));
+            }
+            Err(e) => {
+                return Ok(SubmissionResult::error(format!(
+                    "Failed to save quiet hours: {}",
+                    e
+                )));
+            }
+            Ok(()) => {}
+        }
+
+        Ok(SubmissionResult::response(match updated {
+            Some(quiet) => quiet.describe(),
+            None => "Quiet hours are off.".to_string(),
+        }))
+    }
+
+    /// Pause (or with `pause` false, resume) automation for the user, or for
+    /// everyone with "all".
+    fn process_pause(
+        &self,
+        message: &IncomingMessage,
+        args: &str,
+        pause: bool,
+    ) -> Result<SubmissionResult, Error> {
+        let command = if pause { "/pause" } else { "/resume" };
+        let scope = match args.to_lowercase().as_str() {
+            "" => PauseScope::User(&message.user_id),
+            "all" if crate::tenancy::current().is_some_and(|t| !t.role().is_admin()) => {
+                return Ok(SubmissionResult::error(format!(
+                    "Only an admin can use {} all.",
+                    command
+                )));
+            }
+            "all" => PauseScope::All,
+            _ => {
+                return Ok(SubmissionResult::error(format!(
+                    "Usage: {} for your own tools, routines and jobs, or {} all for everyone's (admin).",
+                    command, command
+                )));
+            }
+        };
+
+        if pause {
+            let resume = match scope {
+                PauseScope::User(_) => "/resume",
+                PauseScope::All => "/resume all",
+            };
+            if !self.pause.pause(scope) {
+                return Ok(SubmissionResult::response(format!(
+                    "Already paused. Send {} to continue.",
+                    resume
+                )));
+            }
+            tracing::warn!("Automation paused ({:?}) by {}", scope, message.user_id);
+            return Ok(SubmissionResult::response(format!(
+                "⏸ Paused: no new tool calls, routines or heartbeats{}, and running jobs \
+                 wait at their next step. Send {} to continue.",
+                if scope == PauseScope::All {
+                    " for anyone"
+                } else {
+                    ""
+                },
+                resume
+            )));
+        }
+
+        let mut reply = if self.pause.resume(scope) {
+            tracing::warn!("Automation resumed ({:?}) by {}", scope, message.user_id);
+            "▶ Resumed.".to_string()
+        } else {
+            "Not paused.".to_string()
+        };
+        if self.pause.is_paused(&message.user_id) {
+            reply.push_str(match scope {
+                PauseScope::User(_) => " An admin has paused everyone; /resume all lifts that.",
+                PauseScope::All => " Your own /pause is still on; /resume lifts it.",
+            });
+        }
+        Ok(SubmissionResult::response(reply))
+    }
+
+    /// Report the tools and models producing the most malformed tool calls
+    /// over the past week.
+    async fn process_tool_errors(&self) -> Result<SubmissionResult, Error> {
+        // Payloads can hold other users' data
+        if crate::tenancy::current().is_some_and(|t| !t.role().is_admin()) {
+            return Ok(SubmissionResult::error(
+                "Only an admin can view malformed tool calls.",
+            ));
+        }
+        let Some(store) = self.store() else {
+            return Ok(SubmissionResult::error(
+                "Tool call history requires the database to be connected.",
+            ));
+        };
+        let now = chrono::Utc::now();
+        let week = chrono::Duration::days(7);
+        let stats = match store.argument_failure_stats(now - week, 10).await {
+            Ok(stats) => stats,
+            Err(e) => {
+                return Ok(SubmissionResult::error(format!(
+                    "Failed to load malformed tool calls: {}",
+                    e
+                )));
+            }
+        };
+        let trends = match store.tool_failure_trends(now, week, 30).await {
+            Ok(trends) => trends,
+            Err(e) => {
+                return Ok(SubmissionResult::error(format!(
+                    "Failed to load tool failure trends: {}",
+                    e
+                )));
+            }
+        };
+        Ok(SubmissionResult::response(format!(
+            "{}\n\n{}",
+            crate::evaluation::format_offenders(&stats),
+            crate::evaluation::format_failure_trends(&trends, 7)
+        )))
+    }
+
+    /// Re-send the user's dead-lettered responses.
+    fn process_redeliver(&self, message: &IncomingMessage) -> Result<SubmissionResult, Error> {
+        let count = self.outbound.redeliver(&message.user_id);
+        Ok(SubmissionResult::response(match count {
+            0 => "No undelivered responses.".to_string(),
+            1 => "Re-sending 1 undelivered response.".to_string(),
+            n => format!("Re-sending {} undelivered responses.", n),
+        }))
+    }
+
     /// Summarize the current thread's conversation.
     async fn process_summarize(
         &self,
@@ -2307,9 +3989,13 @@ impl Agent {
     }
 
     /// Calculate Sovereign Utility for the current iteration.
-    async fn calculate_sovereign_utility(&self, iteration: usize) -> (bool, f64) {
-        let stakes_val = self.stakes.lock().await.emotional_resonance;
-        let c_norm = self.stakes.lock().await.current_c_norm;
+    async fn calculate_sovereign_utility(
+        &self,
+        stakes: &Mutex<crate::sneed_engine::StakesEngine>,
+        iteration: usize,
+    ) -> (bool, f64) {
+        let stakes_val = stakes.lock().await.emotional_resonance;
+        let c_norm = stakes.lock().await.current_c_norm;
         let bio_input = crate::sneed_engine::FlumpyArray::new(vec![stakes_val; 8], 1.0);
         
         let coherence = {
@@ -2321,7 +4007,7 @@ impl Agent {
         let is_coherent = coherence >= crate::sneed_engine::COHERENCE_THRESHOLD;
         let sovereign_boost = crate::sneed_engine::TAU_SOVEREIGN * coherence;
         
-        let stakes_engine = self.stakes.lock().await;
+        let stakes_engine = stakes.lock().await;
         let total_stake_sum: f64 = stakes_engine.stakes.values().sum();
         let agency_score = (total_stake_sum / stakes_engine.stakes.len() as f64) * stakes_engine.identity_strength;
         drop(stakes_engine);
@@ -2382,6 +4068,7 @@ impl Agent {
   !be <persona>    - Assume a persona (e.g. !be catgirl)
   !callme <name>   - Set your name
   !reset           - Reset persona
+  !lang [code]     - Show or set this thread's language (auto to detect)
   !dream <theme>   - Start dream sequence
 
   /job <desc>     - Create a job
@@ -2395,11 +4082,21 @@ impl Agent {
   /compact        - Compress context
   /clear          - Clear thread
   /interrupt      - Stop current turn
+  /pause [all]    - Stop tool calls, routines and jobs until /resume
+  /resume [all]   - Lift a /pause
   /thread new     - New thread
   /thread <id>    - Switch thread
   /resume <id>    - Resume checkpoint
 
   /heartbeat      - Run heartbeat check now
+  /routines       - Routine status and recent runs
+  /defer <when>   - Bring the last message back later
+                    (in 2h, tomorrow at 9, friday 14:30)
+  /model [name]   - Show models or pin one for this thread
+                    (/model all <name>, /model routine <r> <name>)
+  /toolerrors     - Malformed calls and failure trends this week
+  /quiet [from-to] - Hold non-urgent notifications overnight
+                    (/quiet off, /quiet channel <name> off)
   /summarize      - Summarize current thread
   /suggest        - Suggest next steps
 
@@ -2424,6 +4121,51 @@ impl Agent {
                 Ok(Some(format!("Observed. Submitting to role: \"{}\".", role)))
             }
 
+            "lang" => {
+                let requested = args.join(" ");
+                let mut sess = session.lock().await;
+                let Some(thread) = sess.threads.get_mut(&thread_id) else {
+                    return Ok(Some("No active thread.".to_string()));
+                };
+
+                if requested.is_empty() {
+                    let pinned = thread
+                        .metadata
+                        .get(LANGUAGE_KEY)
+                        .and_then(|v| v.as_str())
+                        .and_then(language::lookup);
+                    return Ok(Some(match pinned {
+                        Some(l) => format!("This thread is set to {} ({}).", l.name, l.code),
+                        None => {
+                            "Language is detected automatically from your messages.".to_string()
+                        }
+                    }));
+                }
+
+                if requested.eq_ignore_ascii_case("auto") {
+                    if let Some(obj) = thread.metadata.as_object_mut() {
+                        obj.remove(LANGUAGE_KEY);
+                    }
+                    return Ok(Some(
+                        "Language override cleared; detecting from your messages.".to_string(),
+                    ));
+                }
+
+                let Some(lang) = language::lookup(&requested) else {
+                    let codes: Vec<&str> = language::LANGUAGES.iter().map(|l| l.code).collect();
+                    return Ok(Some(format!(
+                        "Unknown language \"{}\". Supported: {}, or auto.",
+                        requested,
+                        codes.join(", ")
+                    )));
+                };
+                if thread.metadata.is_null() {
+                    thread.metadata = serde_json::json!({});
+                }
+                thread.metadata[LANGUAGE_KEY] = serde_json::Value::String(lang.code.to_string());
+                Ok(Some(format!("Responding in {} in this thread.", lang.name)))
+            }
+
             "callme" => {
                 let name = args.join(" ");
                 if name.is_empty() {
@@ -2474,7 +4216,8 @@ impl Agent {
             }
 
             "resonance" => {
-                let stakes = self.stakes.lock().await;
+                let user_stakes = self.load_stakes_for(&message.user_id).await;
+                let stakes = user_stakes.lock().await;
                 Ok(Some(stakes.get_resonance_report()))
             }
 
@@ -2531,3 +4274,133 @@ impl Agent {
     }
 }
 
+/// The first of `pins` (in precedence order) that is still in the catalog,
+/// or the first pin at all when the catalog isn't known.
+fn first_available_pin(pins: [Option<String>; 3], available: &[CatalogModel]) -> Option<String> {
+    pins.into_iter().flatten().find(|model| {
+        let known = available.is_empty() || available.iter().any(|m| &m.id == model);
+        if !known {
+            tracing::warn!(
+                "Ignoring pinned model '{}': not in the model catalog",
+                model
+            );
+        }
+        known
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_log_dedup() {
+        let first = auto_log_entry(
+            "(Auto-Log via High Memory Stake)",
+            "remember my\nbirthday",
+            "Noted!",
+        );
+        let log = format!("[10:00:00] {}\n[10:05:00] unrelated note", first);
+
+        // Same user excerpt with a different reply is a repeat
+        let again = auto_log_entry(
+            "(Auto-Log via High Emotional Resonance)",
+            "remember my birthday",
+            "Sure.",
+        );
+        assert!(is_duplicate_auto_log(&log, &again));
+
+        let other = auto_log_entry(
+            "(Auto-Log via High Memory Stake)",
+            "remember my cat",
+            "Noted!",
+        );
+        assert!(!is_duplicate_auto_log(&log, &other));
+
+        // Entries older than the window are no longer considered
+        let mut long_log = log.clone();
+        for i in 0..AUTO_LOG_DEDUP_WINDOW {
+            long_log.push_str(&format!(
+                "\n{}",
+                auto_log_entry("(Auto-Log via x)", &format!("msg {}", i), "ok")
+            ));
+        }
+        assert!(!is_duplicate_auto_log(&long_log, &again));
+    }
+
+    #[test]
+    fn test_auth_prompt() {
+        let mut result = AuthResult {
+            name: "google-docs".to_string(),
+            kind: crate::extensions::ExtensionKind::WasmTool,
+            auth_url: Some("https://accounts.example.com/auth?state=abc".to_string()),
+            callback_type: Some("remote".to_string()),
+            instructions: Some("Open the link to connect your Google account.".to_string()),
+            setup_url: None,
+            awaiting_token: false,
+            status: "awaiting_authorization".to_string(),
+        };
+        let prompt = auth_prompt("google-docs", &result);
+        assert!(prompt.starts_with("`google-docs` needs to be connected"));
+        assert!(prompt.contains("Authorize here: https://accounts.example.com/auth?state=abc"));
+        assert!(!prompt.contains("Paste the token"));
+
+        // Without OAuth the user pastes a token instead
+        result.auth_url = None;
+        result.awaiting_token = true;
+        result.setup_url = Some("https://console.example.com".to_string());
+        let prompt = auth_prompt("google-docs", &result);
+        assert!(!prompt.contains("Authorize here"));
+        assert!(prompt.contains("Setup: https://console.example.com"));
+        assert!(prompt.ends_with("Paste the token as your next message."));
+    }
+
+    #[test]
+    fn test_pin_precedence() {
+        let pins = |routine: Option<&str>, thread: Option<&str>, user: Option<&str>| {
+            [routine, thread, user].map(|p| p.map(String::from))
+        };
+        let catalog: Vec<CatalogModel> =
+            ["a", "b", "c"].into_iter().map(CatalogModel::new).collect();
+
+        let first = |p| first_available_pin(p, &catalog);
+        assert_eq!(
+            first(pins(Some("a"), Some("b"), Some("c"))).as_deref(),
+            Some("a")
+        );
+        assert_eq!(
+            first(pins(None, Some("b"), Some("c"))).as_deref(),
+            Some("b")
+        );
+        assert_eq!(first(pins(None, None, Some("c"))).as_deref(), Some("c"));
+        assert_eq!(first(pins(None, None, None)), None);
+    }
+
+    #[test]
+    fn test_withdrawn_pin_falls_back() {
+        let pins = |routine: Option<&str>, thread: Option<&str>, user: Option<&str>| {
+            [routine, thread, user].map(|p| p.map(String::from))
+        };
+        let catalog = vec![CatalogModel::new("b"), CatalogModel::new("c")];
+
+        // The routine's model was withdrawn: the thread pin takes over
+        assert_eq!(
+            first_available_pin(pins(Some("gone"), Some("b"), Some("c")), &catalog).as_deref(),
+            Some("b")
+        );
+        assert_eq!(
+            first_available_pin(pins(Some("gone"), Some("gone"), Some("c")), &catalog).as_deref(),
+            Some("c")
+        );
+        // Nothing pinned is available: the server default applies
+        assert_eq!(
+            first_available_pin(pins(None, Some("gone"), None), &catalog),
+            None
+        );
+        // Without a catalog pins can't be checked, so the first one is used
+        assert_eq!(
+            first_available_pin(pins(Some("gone"), Some("b"), None), &[]).as_deref(),
+            Some("gone")
+        );
+    }
+}
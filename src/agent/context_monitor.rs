@@ -1,9 +1,13 @@
 //! Context window monitoring and compaction triggers.
 //!
 //! Monitors the size of the conversation context and triggers
-//! compaction when approaching the limit.
+//! compaction when approaching the limit. Token counts come from the
+//! provider's [`Tokenizer`] and are attributed per source so callers can
+//! budget what goes into each LLM call.
 
-use crate::llm::ChatMessage;
+use std::sync::Arc;
+
+use crate::llm::{ApproxTokenizer, ChatMessage, Role, Tokenizer, ToolDefinition};
 
 /// Default context window limit (conservative estimate).
 const DEFAULT_CONTEXT_LIMIT: usize = 100_000;
@@ -11,8 +15,11 @@ const DEFAULT_CONTEXT_LIMIT: usize = 100_000;
 /// Compaction threshold as a percentage of the limit.
 const COMPACTION_THRESHOLD: f64 = 0.8;
 
-/// Approximate tokens per word (rough estimate for English).
-const TOKENS_PER_WORD: f64 = 1.3;
+/// Tokens held back for the model's response when budgeting a call.
+const DEFAULT_OUTPUT_RESERVE: usize = 4_096;
+
+/// Tool-name prefix identifying memory tools, whose results count as memory.
+const MEMORY_TOOL_PREFIX: &str = "memory_";
 
 /// Strategy for context compaction.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -45,6 +52,10 @@ pub struct ContextMonitor {
     context_limit: usize,
     /// Threshold ratio for triggering compaction.
     threshold_ratio: f64,
+    /// Tokens reserved for the response when fitting messages to a call.
+    output_reserve: usize,
+    /// Tokenizer used for all counts.
+    tokenizer: Arc<dyn Tokenizer>,
 }
 
 impl ContextMonitor {
@@ -53,9 +64,23 @@ impl ContextMonitor {
         Self {
             context_limit: DEFAULT_CONTEXT_LIMIT,
             threshold_ratio: COMPACTION_THRESHOLD,
+            output_reserve: DEFAULT_OUTPUT_RESERVE,
+            tokenizer: Arc::new(ApproxTokenizer),
         }
     }
 
+    /// Count tokens with the given tokenizer (usually `LlmProvider::tokenizer`).
+    pub fn with_tokenizer(mut self, tokenizer: Arc<dyn Tokenizer>) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+
+    /// Reserve a custom number of tokens for the response.
+    pub fn with_output_reserve(mut self, tokens: usize) -> Self {
+        self.output_reserve = tokens;
+        self
+    }
+
     /// Create with a custom context limit.
     pub fn with_limit(mut self, limit: usize) -> Self {
         self.context_limit = limit;
@@ -68,9 +93,108 @@ impl ContextMonitor {
         self
     }
 
-    /// Estimate the token count for a list of messages.
+    /// Count the tokens of a list of messages.
     pub fn estimate_tokens(&self, messages: &[ChatMessage]) -> usize {
-        messages.iter().map(estimate_message_tokens).sum()
+        messages
+            .iter()
+            .map(|m| self.tokenizer.count_message(m))
+            .sum()
+    }
+
+    /// Attribute the tokens of a request to their sources.
+    pub fn breakdown(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolDefinition],
+    ) -> ContextBreakdown {
+        ContextBreakdown::measure(messages, tools, self.tokenizer.as_ref())
+    }
+
+    /// Compute the token budget of a call with these messages and tools.
+    pub fn budget(&self, messages: &[ChatMessage], tools: &[ToolDefinition]) -> ContextBudget {
+        ContextBudget {
+            limit: self.context_limit,
+            output_reserve: self.output_reserve,
+            tool_schema_tokens: tools.iter().map(|t| self.tokenizer.count_tool(t)).sum(),
+            message_tokens: self.estimate_tokens(messages),
+        }
+    }
+
+    /// Select the messages to send in one call so that they fit the budget.
+    ///
+    /// System messages are always kept. The rest of the history is included
+    /// newest first until the budget is spent, keeping tool results together
+    /// with the assistant message that called them. The newest exchange is
+    /// kept even if it alone exceeds the budget. Order is preserved.
+    pub fn fit_messages(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolDefinition],
+    ) -> Vec<ChatMessage> {
+        let budget = self.budget(messages, tools);
+        if budget.fits() {
+            return messages.to_vec();
+        }
+
+        let counts: Vec<usize> = messages
+            .iter()
+            .map(|m| self.tokenizer.count_message(m))
+            .collect();
+        let mut keep: Vec<bool> = messages.iter().map(|m| m.role == Role::System).collect();
+        let mut used: usize = counts
+            .iter()
+            .zip(&keep)
+            .filter(|(_, k)| **k)
+            .map(|(c, _)| c)
+            .sum();
+        let available = budget.available();
+        let mut kept_any = false;
+
+        let mut end = messages.len();
+        while end > 0 {
+            // Skip system messages (already kept), then find the start of the
+            // exchange ending at `end`: tool results stay with their caller.
+            if messages[end - 1].role == Role::System {
+                end -= 1;
+                continue;
+            }
+            let mut start = end - 1;
+            while start > 0 && messages[start].role == Role::Tool {
+                start -= 1;
+            }
+
+            let unit: usize = (start..end)
+                .filter(|&i| messages[i].role != Role::System)
+                .map(|i| counts[i])
+                .sum();
+            if used + unit > available && kept_any {
+                break;
+            }
+            used += unit;
+            kept_any = true;
+            for k in &mut keep[start..end] {
+                *k = true;
+            }
+            end = start;
+        }
+
+        let dropped = keep.iter().filter(|k| !**k).count();
+        if dropped > 0 {
+            tracing::debug!(
+                "Context budget: dropped {} of {} messages ({} tokens available, {} used)",
+                dropped,
+                messages.len(),
+                available,
+                used
+            );
+        }
+
+        messages
+            .iter()
+            .zip(keep)
+            .filter(|(_, k)| *k)
+            .map(|(m, _)| m.clone())
+            .collect()
     }
 
     /// Check if compaction is needed.
@@ -124,21 +248,83 @@ impl Default for ContextMonitor {
     }
 }
 
-/// Estimate tokens for a single message.
-fn estimate_message_tokens(message: &ChatMessage) -> usize {
-    // Use word-based estimation as it's more accurate for varied content
-    let word_count = message.content.split_whitespace().count();
+/// Estimate tokens for raw text with the default tokenizer.
+pub fn estimate_text_tokens(text: &str) -> usize {
+    ApproxTokenizer.count(text)
+}
 
-    // Add overhead for role and structure
-    let overhead = 4; // ~4 tokens for role and message structure
+/// Where a part of the context comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextSource {
+    /// System prompt and other system messages.
+    SystemPrompt,
+    /// Results of memory tools (search, read).
+    Memory,
+    /// Tool definitions sent with the request.
+    ToolSchemas,
+    /// Results of all other tools.
+    ToolOutputs,
+    /// User and assistant turns.
+    History,
+}
 
-    (word_count as f64 * TOKENS_PER_WORD) as usize + overhead
+impl ContextSource {
+    fn of(message: &ChatMessage) -> Self {
+        match message.role {
+            Role::System => Self::SystemPrompt,
+            Role::Tool
+                if message
+                    .name
+                    .as_deref()
+                    .is_some_and(|n| n.starts_with(MEMORY_TOOL_PREFIX)) =>
+            {
+                Self::Memory
+            }
+            Role::Tool => Self::ToolOutputs,
+            Role::User | Role::Assistant => Self::History,
+        }
+    }
 }
 
-/// Estimate tokens for raw text.
-pub fn estimate_text_tokens(text: &str) -> usize {
-    let word_count = text.split_whitespace().count();
-    (word_count as f64 * TOKENS_PER_WORD) as usize
+/// Token count of one message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageTokens {
+    /// Index of the message in the analyzed list.
+    pub index: usize,
+    pub role: Role,
+    pub source: ContextSource,
+    pub tokens: usize,
+}
+
+/// Token budget of a single LLM call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextBudget {
+    /// Context window of the model.
+    pub limit: usize,
+    /// Tokens held back for the response.
+    pub output_reserve: usize,
+    /// Tokens taken by tool definitions.
+    pub tool_schema_tokens: usize,
+    /// Tokens taken by the messages.
+    pub message_tokens: usize,
+}
+
+impl ContextBudget {
+    /// Tokens available for messages.
+    pub fn available(&self) -> usize {
+        self.limit
+            .saturating_sub(self.output_reserve + self.tool_schema_tokens)
+    }
+
+    /// Tokens left after the messages.
+    pub fn remaining(&self) -> usize {
+        self.available().saturating_sub(self.message_tokens)
+    }
+
+    /// Whether all messages fit.
+    pub fn fits(&self) -> bool {
+        self.message_tokens <= self.available()
+    }
 }
 
 /// Level of scrubbing to apply to text.
@@ -251,7 +437,7 @@ pub fn detect_repetition(current: &str, previous: &str, min_len: usize) -> Vec<S
 /// Context size breakdown for reporting.
 #[derive(Debug, Clone)]
 pub struct ContextBreakdown {
-    /// Total estimated tokens.
+    /// Total tokens, including tool schemas.
     pub total_tokens: usize,
     /// System message tokens.
     pub system_tokens: usize,
@@ -259,38 +445,88 @@ pub struct ContextBreakdown {
     pub user_tokens: usize,
     /// Assistant message tokens.
     pub assistant_tokens: usize,
-    /// Tool result tokens.
+    /// Tool result tokens (memory and other tools).
     pub tool_tokens: usize,
+    /// Memory tool result tokens.
+    pub memory_tokens: usize,
+    /// Tokens of tool results other than memory.
+    pub tool_output_tokens: usize,
+    /// Tool definition tokens.
+    pub tool_schema_tokens: usize,
+    /// User and assistant tokens.
+    pub history_tokens: usize,
     /// Number of messages.
     pub message_count: usize,
+    /// Per-message counts, in message order.
+    pub messages: Vec<MessageTokens>,
 }
 
 impl ContextBreakdown {
-    /// Analyze a list of messages.
+    /// Analyze a list of messages with the default tokenizer.
     pub fn analyze(messages: &[ChatMessage]) -> Self {
+        Self::measure(messages, &[], &ApproxTokenizer)
+    }
+
+    /// Analyze messages and tool definitions with the given tokenizer.
+    pub fn measure(
+        messages: &[ChatMessage],
+        tools: &[ToolDefinition],
+        tokenizer: &dyn Tokenizer,
+    ) -> Self {
+        let tool_schema_tokens: usize = tools.iter().map(|t| tokenizer.count_tool(t)).sum();
         let mut breakdown = Self {
-            total_tokens: 0,
+            total_tokens: tool_schema_tokens,
             system_tokens: 0,
             user_tokens: 0,
             assistant_tokens: 0,
             tool_tokens: 0,
+            memory_tokens: 0,
+            tool_output_tokens: 0,
+            tool_schema_tokens,
+            history_tokens: 0,
             message_count: messages.len(),
+            messages: Vec::with_capacity(messages.len()),
         };
 
-        for message in messages {
-            let tokens = estimate_message_tokens(message);
+        for (index, message) in messages.iter().enumerate() {
+            let tokens = tokenizer.count_message(message);
+            let source = ContextSource::of(message);
             breakdown.total_tokens += tokens;
 
             match message.role {
-                crate::llm::Role::System => breakdown.system_tokens += tokens,
-                crate::llm::Role::User => breakdown.user_tokens += tokens,
-                crate::llm::Role::Assistant => breakdown.assistant_tokens += tokens,
-                crate::llm::Role::Tool => breakdown.tool_tokens += tokens,
+                Role::System => breakdown.system_tokens += tokens,
+                Role::User => breakdown.user_tokens += tokens,
+                Role::Assistant => breakdown.assistant_tokens += tokens,
+                Role::Tool => breakdown.tool_tokens += tokens,
+            }
+            match source {
+                ContextSource::Memory => breakdown.memory_tokens += tokens,
+                ContextSource::ToolOutputs => breakdown.tool_output_tokens += tokens,
+                ContextSource::History => breakdown.history_tokens += tokens,
+                ContextSource::SystemPrompt | ContextSource::ToolSchemas => {}
             }
+
+            breakdown.messages.push(MessageTokens {
+                index,
+                role: message.role,
+                source,
+                tokens,
+            });
         }
 
         breakdown
     }
+
+    /// Tokens attributed to a source.
+    pub fn tokens_for(&self, source: ContextSource) -> usize {
+        match source {
+            ContextSource::SystemPrompt => self.system_tokens,
+            ContextSource::Memory => self.memory_tokens,
+            ContextSource::ToolSchemas => self.tool_schema_tokens,
+            ContextSource::ToolOutputs => self.tool_output_tokens,
+            ContextSource::History => self.history_tokens,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -300,8 +536,8 @@ mod tests {
     #[test]
     fn test_token_estimation() {
         let msg = ChatMessage::user("Hello, how are you today?");
-        let tokens = estimate_message_tokens(&msg);
-        // 5 words * 1.3 + 4 overhead = ~10-11 tokens
+        let tokens = ApproxTokenizer.count_message(&msg);
+        // Word pieces and punctuation plus 4 overhead
         assert!(tokens > 0);
         assert!(tokens < 20);
     }
@@ -343,6 +579,84 @@ mod tests {
         assert!(breakdown.assistant_tokens > 0);
     }
 
+    #[test]
+    fn test_breakdown_attributes_sources() {
+        let messages = vec![
+            ChatMessage::system("You are a helpful assistant."),
+            ChatMessage::user("What did we decide?"),
+            ChatMessage::tool_result("1", "memory_search", "We chose option B."),
+            ChatMessage::tool_result("2", "http", "{\"status\": 200}"),
+            ChatMessage::assistant("Option B."),
+        ];
+        let tools = vec![ToolDefinition {
+            name: "echo".to_string(),
+            description: "Echo a message".to_string(),
+            parameters: serde_json::json!({ "type": "object" }),
+        }];
+
+        let breakdown = ContextMonitor::new().breakdown(&messages, &tools);
+        assert!(breakdown.tokens_for(ContextSource::Memory) > 0);
+        assert!(breakdown.tokens_for(ContextSource::ToolOutputs) > 0);
+        assert!(breakdown.tokens_for(ContextSource::ToolSchemas) > 0);
+        assert_eq!(
+            breakdown.tool_tokens,
+            breakdown.memory_tokens + breakdown.tool_output_tokens
+        );
+        assert_eq!(
+            breakdown.total_tokens,
+            breakdown.messages.iter().map(|m| m.tokens).sum::<usize>()
+                + breakdown.tool_schema_tokens
+        );
+        assert_eq!(breakdown.messages[2].source, ContextSource::Memory);
+    }
+
+    #[test]
+    fn test_fit_messages_keeps_system_and_recent() {
+        let filler = "lorem ipsum dolor sit amet ".repeat(20);
+        let mut messages = vec![ChatMessage::system("Be brief.")];
+        for i in 0..10 {
+            messages.push(ChatMessage::user(format!("{} {}", i, filler)));
+            messages.push(ChatMessage::assistant(format!("ack {}", i)));
+        }
+        messages.push(ChatMessage::user("latest question"));
+
+        let monitor = ContextMonitor::new()
+            .with_limit(400)
+            .with_output_reserve(100);
+        assert!(!monitor.budget(&messages, &[]).fits());
+
+        let fitted = monitor.fit_messages(&messages, &[]);
+        assert!(fitted.len() < messages.len());
+        assert_eq!(fitted[0].role, Role::System);
+        assert_eq!(fitted.last().unwrap().content, "latest question");
+        assert!(monitor.estimate_tokens(&fitted) <= 300);
+
+        // Everything fits with a large window
+        let roomy = ContextMonitor::new();
+        assert_eq!(roomy.fit_messages(&messages, &[]).len(), messages.len());
+    }
+
+    #[test]
+    fn test_fit_messages_keeps_tool_results_with_caller() {
+        let call = crate::llm::ToolCall {
+            id: "1".to_string(),
+            name: "http".to_string(),
+            arguments: serde_json::json!({}),
+            thought_signature: None,
+        };
+        let messages = vec![
+            ChatMessage::user("old ".repeat(200)),
+            ChatMessage::assistant_with_tool_calls("", vec![call]),
+            ChatMessage::tool_result("1", "http", "ok"),
+        ];
+
+        let monitor = ContextMonitor::new().with_limit(100).with_output_reserve(0);
+        let fitted = monitor.fit_messages(&messages, &[]);
+        assert_eq!(fitted.len(), 2);
+        assert_eq!(fitted[0].role, Role::Assistant);
+        assert_eq!(fitted[1].role, Role::Tool);
+    }
+
     #[test]
     fn test_scrub_context_clearance() {
         let input = "SOPHIA_GAZE: Scanning...\n🌀 H\u{035C}e\u{0361}llo 🌀\n---\nActual content";
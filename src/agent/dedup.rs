@@ -0,0 +1,192 @@
+//! Duplicate-message and replay protection.
+//!
+//! Webhook retries and overlapping polls can deliver the same message twice,
+//! which would otherwise produce two agent replies. Every incoming message
+//! with a channel-native ID is remembered for a short window, keyed by
+//! `(channel, message id)`, and repeats inside the window are dropped before
+//! they reach the router.
+//!
+//! Messages without a native ID (CLI input, synthesized digests) are always
+//! admitted.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::channels::IncomingMessage;
+
+/// Most message keys remembered at once; the oldest are forgotten first.
+pub const MAX_TRACKED_MESSAGES: usize = 10_000;
+
+/// Channel name and channel-native message ID.
+type DedupKey = (String, String);
+
+/// Remembers recently seen message IDs to drop redeliveries.
+pub struct DedupStore {
+    window: Duration,
+    seen: HashMap<DedupKey, Instant>,
+    /// Keys in arrival order, for expiry and the size cap.
+    order: VecDeque<(DedupKey, Instant)>,
+}
+
+impl DedupStore {
+    /// A store that remembers messages for `window`. A zero window disables
+    /// deduplication.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            seen: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Whether deduplication is on.
+    pub fn is_enabled(&self) -> bool {
+        !self.window.is_zero()
+    }
+
+    /// Record `message` and return whether it should be handled. Returns
+    /// `false` for a message already seen within the window.
+    pub fn admit(&mut self, message: &IncomingMessage, now: Instant) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+        let Some(id) = message_key(message) else {
+            return true;
+        };
+
+        self.expire(now);
+        let key = (message.channel.clone(), id);
+        if self.seen.contains_key(&key) {
+            return false;
+        }
+
+        self.seen.insert(key.clone(), now);
+        self.order.push_back((key, now));
+        while self.order.len() > MAX_TRACKED_MESSAGES {
+            if let Some((old, _)) = self.order.pop_front() {
+                self.seen.remove(&old);
+            }
+        }
+        true
+    }
+
+    /// Number of message keys currently remembered.
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some((key, at)) = self.order.front() {
+            if now.duration_since(*at) < self.window {
+                break;
+            }
+            // A key is only in `order` once, so this always matches
+            self.seen.remove(key);
+            self.order.pop_front();
+        }
+    }
+}
+
+/// The channel-native ID of `message`, if it has one.
+///
+/// An explicit [`IncomingMessage::external_id`] wins. Otherwise the common
+/// metadata fields WASM channels emit are used: `message_id` (Telegram,
+/// WhatsApp), `message_ts` (Slack), or `event_id`, scoped by `chat_id` or
+/// `channel` when present since some platforms number messages per chat.
+pub fn message_key(message: &IncomingMessage) -> Option<String> {
+    if let Some(id) = &message.external_id {
+        return Some(id.clone());
+    }
+
+    let metadata = &message.metadata;
+    let field = |name: &str| match metadata.get(name)? {
+        serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+
+    let id = field("message_id")
+        .or_else(|| field("message_ts"))
+        .or_else(|| field("event_id"))?;
+    match field("chat_id").or_else(|| field("channel")) {
+        Some(scope) => Some(format!("{}:{}", scope, id)),
+        None => Some(id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn telegram(chat_id: i64, message_id: i64) -> IncomingMessage {
+        IncomingMessage::new("telegram", "alice", "hi").with_metadata(serde_json::json!({
+            "chat_id": chat_id,
+            "message_id": message_id,
+        }))
+    }
+
+    #[test]
+    fn test_redelivery_is_dropped() {
+        let mut store = DedupStore::new(Duration::from_secs(600));
+        let now = Instant::now();
+        assert!(store.admit(&telegram(1, 10), now));
+        assert!(!store.admit(&telegram(1, 10), now));
+        // Same message number in another chat is a different message
+        assert!(store.admit(&telegram(2, 10), now));
+    }
+
+    #[test]
+    fn test_same_id_on_other_channel_is_distinct() {
+        let mut store = DedupStore::new(Duration::from_secs(600));
+        let now = Instant::now();
+        let a = IncomingMessage::new("http", "u", "x").with_external_id("req-1");
+        let b = IncomingMessage::new("api", "u", "x").with_external_id("req-1");
+        assert!(store.admit(&a, now));
+        assert!(store.admit(&b, now));
+        assert!(!store.admit(&a, now));
+    }
+
+    #[test]
+    fn test_window_expires() {
+        let mut store = DedupStore::new(Duration::from_secs(60));
+        let now = Instant::now();
+        assert!(store.admit(&telegram(1, 10), now));
+        assert!(store.admit(&telegram(1, 10), now + Duration::from_secs(61)));
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_messages_without_ids_pass() {
+        let mut store = DedupStore::new(Duration::from_secs(60));
+        let now = Instant::now();
+        let msg = IncomingMessage::new("cli", "alice", "hello");
+        assert!(store.admit(&msg, now));
+        assert!(store.admit(&msg, now));
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_disabled_admits_everything() {
+        let mut store = DedupStore::new(Duration::ZERO);
+        let now = Instant::now();
+        assert!(store.admit(&telegram(1, 10), now));
+        assert!(store.admit(&telegram(1, 10), now));
+    }
+
+    #[test]
+    fn test_message_key_fallbacks() {
+        let slack = IncomingMessage::new("slack", "U1", "hi").with_metadata(serde_json::json!({
+            "channel": "C1",
+            "message_ts": "1700000000.000100",
+        }));
+        assert_eq!(message_key(&slack).as_deref(), Some("C1:1700000000.000100"));
+
+        let whatsapp = IncomingMessage::new("whatsapp", "15551234", "hi")
+            .with_metadata(serde_json::json!({ "message_id": "wamid.abc" }));
+        assert_eq!(message_key(&whatsapp).as_deref(), Some("wamid.abc"));
+    }
+}
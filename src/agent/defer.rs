@@ -0,0 +1,404 @@
+//! Deferred messages ("remind me about this tomorrow at 9").
+//!
+//! `/defer <when>` (or `/snooze`, or "remind me about this <when>") captures
+//! the conversation's last user message and stores it as a one-shot routine
+//! owned by the user. Once the routine is due the message is sent back
+//! through the notification router to the channel and thread it came from,
+//! with a pointer to the conversation it was deferred from, and the routine
+//! is disabled.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone, Utc, Weekday};
+use uuid::Uuid;
+
+use crate::agent::routine::{Routine, RoutineAction, Trigger};
+use crate::channels::{NotifyTarget, OutgoingResponse};
+
+/// How often one-shot routines are checked for being due.
+pub const DEFER_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Prefix of the routines that hold deferred messages.
+pub const DEFER_ROUTINE_PREFIX: &str = "defer-";
+
+/// Phrases that ask for a reminder about the current conversation.
+const REMIND_PHRASES: &[&str] = &[
+    "remind me about this",
+    "remind me about that",
+    "remind me of this",
+    "remind me of that",
+];
+
+/// Hour used when a day is given without a time ("tomorrow").
+const DEFAULT_HOUR: u32 = 9;
+
+/// Longest a message can be deferred.
+const MAX_DEFER_DAYS: i64 = 366;
+
+/// Longest excerpt of the deferred message shown in confirmations.
+const MAX_EXCERPT_CHARS: usize = 80;
+
+/// The `<when>` of a "remind me about this <when>" message, if it is one
+/// with a time we understand.
+pub fn reminder_request(content: &str) -> Option<&str> {
+    let trimmed = content.trim();
+    let lower = trimmed.to_lowercase();
+    let phrase = REMIND_PHRASES.iter().find(|p| lower.starts_with(*p))?;
+    // The phrases are ASCII, so the byte offset is the same in both
+    let when = trimmed[phrase.len()..].trim();
+    parse_when(when, &Local::now()).map(|_| when)
+}
+
+/// When `text` ("in 2h", "tomorrow at 9", "friday 14:30", "5pm") falls
+/// after `now`, reading clock times in `now`'s time zone.
+pub fn parse_when<Tz: TimeZone>(text: &str, now: &DateTime<Tz>) -> Option<DateTime<Utc>> {
+    let text = text.trim().trim_end_matches(['.', '!', '?']).to_lowercase();
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let words = match words.as_slice() {
+        ["on", rest @ ..] => rest,
+        words => words,
+    };
+
+    let at = match words {
+        [] => return None,
+        ["in", rest @ ..] => now.with_timezone(&Utc) + parse_offset(rest)?,
+        ["tonight"] => at_day(now, 0, (20, 0))?,
+        ["tomorrow", rest @ ..] => at_day(now, 1, time_of_day(rest)?)?,
+        [day, rest @ ..] if weekday(day).is_some() => {
+            let target = weekday(day)?.num_days_from_monday() as i64;
+            let today = now.weekday().num_days_from_monday() as i64;
+            let days = match (target - today).rem_euclid(7) {
+                0 => 7,
+                days => days,
+            };
+            at_day(now, days, time_of_day(rest)?)?
+        }
+        ["at", rest @ ..] => next_time(now, parse_time(&rest.join(" "))?)?,
+        words => next_time(now, parse_time(&words.join(" "))?)?,
+    };
+
+    let now = now.with_timezone(&Utc);
+    (at > now && at - now <= chrono::Duration::days(MAX_DEFER_DAYS)).then_some(at)
+}
+
+/// Duration of "2 hours", "an hour", "30m", "3d".
+fn parse_offset(words: &[&str]) -> Option<chrono::Duration> {
+    let (count, unit) = match words {
+        ["a" | "an", unit] => (1, *unit),
+        [count, unit] => (count.parse().ok()?, *unit),
+        [compact] => {
+            let split = compact.find(|c: char| !c.is_ascii_digit())?;
+            (compact[..split].parse().ok()?, &compact[split..])
+        }
+        _ => return None,
+    };
+    let count: i64 = count;
+    if count <= 0 || count > MAX_DEFER_DAYS * 24 * 60 {
+        return None;
+    }
+    match unit {
+        "m" | "min" | "mins" | "minute" | "minutes" => Some(chrono::Duration::minutes(count)),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Some(chrono::Duration::hours(count)),
+        "d" | "day" | "days" => Some(chrono::Duration::days(count)),
+        "w" | "week" | "weeks" => Some(chrono::Duration::weeks(count)),
+        _ => None,
+    }
+}
+
+/// Hour and minute of the words following a day ("at 9", "morning"),
+/// defaulting to the morning.
+fn time_of_day(words: &[&str]) -> Option<(u32, u32)> {
+    match words {
+        [] | ["morning"] => Some((DEFAULT_HOUR, 0)),
+        ["afternoon"] => Some((14, 0)),
+        ["evening"] => Some((18, 0)),
+        ["night"] => Some((20, 0)),
+        ["at", rest @ ..] => parse_time(&rest.join(" ")),
+        words => parse_time(&words.join(" ")),
+    }
+}
+
+/// Hour and minute of "9", "9am", "9:30 pm", "21:00", "noon".
+fn parse_time(text: &str) -> Option<(u32, u32)> {
+    let text: String = text.split_whitespace().collect();
+    match text.as_str() {
+        "noon" => return Some((12, 0)),
+        "midnight" => return Some((0, 0)),
+        _ => {}
+    }
+
+    let (clock, meridiem) = if let Some(clock) = text.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = text.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (text.as_str(), None)
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse().ok()?, minute.parse().ok()?),
+        Some(_) => return None,
+        None => (clock.parse::<u32>().ok()?, 0),
+    };
+    if minute > 59 {
+        return None;
+    }
+
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(false) => hour % 12,
+        Some(true) => hour % 12 + 12,
+        None if hour > 23 => return None,
+        None => hour,
+    };
+    Some((hour, minute))
+}
+
+fn weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// `(hour, minute)` on the day `days` after `now`'s date.
+fn at_day<Tz: TimeZone>(
+    now: &DateTime<Tz>,
+    days: i64,
+    (hour, minute): (u32, u32),
+) -> Option<DateTime<Utc>> {
+    let date: NaiveDate = now.date_naive() + chrono::Duration::days(days);
+    let local = date.and_hms_opt(hour, minute, 0)?;
+    now.timezone()
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// The next `(hour, minute)`: today if it's still ahead, otherwise tomorrow.
+fn next_time<Tz: TimeZone>(now: &DateTime<Tz>, time: (u32, u32)) -> Option<DateTime<Utc>> {
+    let today = at_day(now, 0, time)?;
+    if today > now.with_timezone(&Utc) {
+        Some(today)
+    } else {
+        at_day(now, 1, time)
+    }
+}
+
+/// A message to bring back later, and where it came from.
+#[derive(Debug, Clone)]
+pub struct Deferral {
+    /// The deferred message.
+    pub text: String,
+    /// Channel the message came in on.
+    pub channel: String,
+    /// User to remind.
+    pub user_id: String,
+    /// The channel's own thread ID, so the reminder lands in that thread.
+    pub thread_id: Option<String>,
+    /// The agent's conversation the message belongs to.
+    pub conversation_id: Uuid,
+    /// When to bring the message back.
+    pub at: DateTime<Utc>,
+}
+
+impl Deferral {
+    /// The one-shot routine holding this deferral.
+    pub fn into_routine(self, now: DateTime<Utc>) -> Routine {
+        let id = Uuid::new_v4();
+        Routine {
+            id,
+            name: format!("{}{}", DEFER_ROUTINE_PREFIX, &id.simple().to_string()[..8]),
+            description: format!("Reminder: {}", excerpt(&self.text)),
+            enabled: true,
+            trigger: Trigger::Once { at: self.at },
+            action: RoutineAction::Lightweight { prompt: self.text },
+            guardrails: serde_json::json!({}),
+            notify: serde_json::json!({
+                "channel": self.channel,
+                "user": self.user_id,
+                "thread_id": self.thread_id,
+                "conversation_id": self.conversation_id,
+            }),
+            last_run_at: None,
+            next_fire_at: Some(self.at),
+            run_count: 0,
+            consecutive_failures: 0,
+            created_at: now,
+            updated_at: now,
+            version: 1,
+        }
+    }
+}
+
+/// Where to deliver a due deferred-message routine and what to send, or
+/// `None` if `routine` doesn't hold a deferred message.
+pub fn reminder(owner: &str, routine: &Routine) -> Option<(NotifyTarget, OutgoingResponse)> {
+    if !routine.name.starts_with(DEFER_ROUTINE_PREFIX) {
+        return None;
+    }
+    let RoutineAction::Lightweight { ref prompt } = routine.action else {
+        return None;
+    };
+
+    let target =
+        NotifyTarget::from_json(&routine.notify).or(&NotifyTarget::new(None, Some(owner.into())));
+    let field = |key: &str| routine.notify.get(key).and_then(|v| v.as_str());
+    let mut content = format!(
+        "⏰ *Reminder* (deferred {})\n\n{}",
+        routine
+            .created_at
+            .with_timezone(&Local)
+            .format("%a %b %-d, %H:%M"),
+        prompt
+    );
+    if let Some(conversation) = field("conversation_id") {
+        content.push_str(&format!(
+            "\n\nFrom conversation {} (`/thread {}` to pick it up there).",
+            conversation, conversation
+        ));
+    }
+
+    let response = OutgoingResponse {
+        content,
+        thread_id: field("thread_id").map(String::from),
+        metadata: serde_json::json!({
+            "source": "defer",
+            "routine_id": routine.id,
+            "conversation_id": field("conversation_id"),
+        }),
+        attachments: Vec::new(),
+    };
+    Some((target, response))
+}
+
+/// First line of `text`, shortened for confirmations.
+pub fn excerpt(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.chars().count() > MAX_EXCERPT_CHARS {
+        let clipped: String = line.chars().take(MAX_EXCERPT_CHARS).collect();
+        format!("{}…", clipped.trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    /// Wednesday 2026-10-14, 15:20 at UTC+2.
+    fn now() -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339("2026-10-14T15:20:00+02:00").unwrap()
+    }
+
+    fn local(text: &str) -> Option<String> {
+        parse_when(text, &now()).map(|at| {
+            at.with_timezone(now().offset())
+                .format("%a %Y-%m-%d %H:%M")
+                .to_string()
+        })
+    }
+
+    #[test]
+    fn test_parse_when_relative() {
+        assert_eq!(local("in 2 hours").as_deref(), Some("Wed 2026-10-14 17:20"));
+        assert_eq!(local("in an hour").as_deref(), Some("Wed 2026-10-14 16:20"));
+        assert_eq!(local("in 30m").as_deref(), Some("Wed 2026-10-14 15:50"));
+        assert_eq!(local("in 3 days").as_deref(), Some("Sat 2026-10-17 15:20"));
+        assert_eq!(local("in 0 minutes"), None);
+        assert_eq!(local("in 2 fortnights"), None);
+    }
+
+    #[test]
+    fn test_parse_when_days_and_times() {
+        assert_eq!(
+            local("tomorrow at 9").as_deref(),
+            Some("Thu 2026-10-15 09:00")
+        );
+        assert_eq!(local("Tomorrow").as_deref(), Some("Thu 2026-10-15 09:00"));
+        assert_eq!(
+            local("tomorrow evening").as_deref(),
+            Some("Thu 2026-10-15 18:00")
+        );
+        assert_eq!(local("tonight").as_deref(), Some("Wed 2026-10-14 20:00"));
+        assert_eq!(local("at 5pm").as_deref(), Some("Wed 2026-10-14 17:00"));
+        assert_eq!(local("9:30 am").as_deref(), Some("Thu 2026-10-15 09:30"));
+        assert_eq!(local("noon").as_deref(), Some("Thu 2026-10-15 12:00"));
+        assert_eq!(
+            local("on friday at 14:30").as_deref(),
+            Some("Fri 2026-10-16 14:30")
+        );
+        assert_eq!(local("wednesday").as_deref(), Some("Wed 2026-10-21 09:00"));
+    }
+
+    #[test]
+    fn test_parse_when_rejects_nonsense() {
+        assert_eq!(local(""), None);
+        assert_eq!(local("later"), None);
+        assert_eq!(local("at 25:00"), None);
+        assert_eq!(local("13pm"), None);
+        assert_eq!(local("tomorrow at 9:5"), None);
+        assert_eq!(local("in 2 years"), None);
+    }
+
+    #[test]
+    fn test_deferral_roundtrip() {
+        let conversation_id = Uuid::new_v4();
+        let at = Utc::now() + chrono::Duration::hours(1);
+        let routine = Deferral {
+            text: "Check whether the invoice was paid".to_string(),
+            channel: "telegram".to_string(),
+            user_id: "alice".to_string(),
+            thread_id: Some("chat-42".to_string()),
+            conversation_id,
+            at,
+        }
+        .into_routine(Utc::now());
+        assert!(routine.name.starts_with(DEFER_ROUTINE_PREFIX));
+        assert_eq!(routine.next_fire_at, Some(at));
+
+        let (target, response) = reminder("alice", &routine).unwrap();
+        assert_eq!(target.channel.as_deref(), Some("telegram"));
+        assert_eq!(target.user.as_deref(), Some("alice"));
+        assert_eq!(response.thread_id.as_deref(), Some("chat-42"));
+        assert!(
+            response
+                .content
+                .contains("Check whether the invoice was paid")
+        );
+        assert!(
+            response
+                .content
+                .contains(&format!("/thread {}", conversation_id))
+        );
+    }
+
+    #[test]
+    fn test_reminder_ignores_other_routines() {
+        let mut routine = Deferral {
+            text: "x".to_string(),
+            channel: "cli".to_string(),
+            user_id: "default".to_string(),
+            thread_id: None,
+            conversation_id: Uuid::new_v4(),
+            at: Utc::now(),
+        }
+        .into_routine(Utc::now());
+        routine.name = "daily-digest".to_string();
+        assert!(reminder("default", &routine).is_none());
+    }
+
+    #[test]
+    fn test_excerpt() {
+        assert_eq!(excerpt("short\nsecond line"), "short");
+        let long = "a".repeat(100);
+        assert_eq!(excerpt(&long).chars().count(), MAX_EXCERPT_CHARS + 1);
+    }
+}
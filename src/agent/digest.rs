@@ -0,0 +1,224 @@
+//! Digest mode for noisy channels.
+//!
+//! Messages on a channel in digest mode don't wake the agent one by one.
+//! They are held per conversation, and once the channel's interval has passed
+//! the held messages are combined into a single message and handled as one
+//! background turn. Commands and messages containing an urgency keyword skip
+//! the digest and are handled right away.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+use crate::agent::priority::Priority;
+use crate::channels::IncomingMessage;
+use crate::config::DigestConfig;
+
+/// Prefix of the combined message a digest is handled as.
+pub const DIGEST_MESSAGE_PREFIX: &str = "[digest:";
+
+/// Digest channel name matching routine-triggered messages on any channel.
+pub const ROUTINE_DIGEST_CHANNEL: &str = "routine";
+
+/// How often held messages are checked for a due digest.
+pub const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Messages held for one conversation.
+struct Batch {
+    due_at: Instant,
+    messages: Vec<IncomingMessage>,
+}
+
+/// Channel, user, and thread the held messages belong to.
+type BatchKey = (String, String, Option<String>);
+
+/// Holds non-urgent messages from digest channels until their digest is due.
+pub struct DigestBuffer {
+    config: DigestConfig,
+    batches: HashMap<BatchKey, Batch>,
+}
+
+impl DigestBuffer {
+    pub fn new(config: DigestConfig) -> Self {
+        Self {
+            config,
+            batches: HashMap::new(),
+        }
+    }
+
+    /// Whether any channel is in digest mode.
+    pub fn is_enabled(&self) -> bool {
+        !self.config.channels.is_empty()
+    }
+
+    /// Digest interval for the channel `message` came in on, if it has one.
+    fn interval_for(&self, message: &IncomingMessage) -> Option<Duration> {
+        if Priority::of_message(message) == Priority::Routine
+            && let Some(interval) = self.config.channels.get(ROUTINE_DIGEST_CHANNEL)
+        {
+            return Some(*interval);
+        }
+        self.config.channels.get(&message.channel).copied()
+    }
+
+    /// Whether `message` should be handled now even on a digest channel.
+    pub fn is_urgent(&self, message: &IncomingMessage) -> bool {
+        let content = message.content.trim_start();
+        if content.starts_with('/') {
+            return true;
+        }
+        let content = content.to_lowercase();
+        self.config
+            .urgent_keywords
+            .iter()
+            .any(|keyword| content.contains(&keyword.to_lowercase()))
+    }
+
+    /// Hold `message` for its channel's next digest, or hand it back to be
+    /// handled now.
+    pub fn offer(&mut self, message: IncomingMessage) -> Option<IncomingMessage> {
+        let Some(interval) = self.interval_for(&message) else {
+            return Some(message);
+        };
+        if self.is_urgent(&message) {
+            return Some(message);
+        }
+
+        let key = (
+            message.channel.clone(),
+            message.user_id.clone(),
+            message.thread_id.clone(),
+        );
+        self.batches
+            .entry(key)
+            .or_insert_with(|| Batch {
+                due_at: Instant::now() + interval,
+                messages: Vec::new(),
+            })
+            .messages
+            .push(message);
+        None
+    }
+
+    /// Remove every batch due by `now`, each combined into one message.
+    pub fn take_due(&mut self, now: Instant) -> Vec<IncomingMessage> {
+        let due: Vec<BatchKey> = self
+            .batches
+            .iter()
+            .filter(|(_, batch)| batch.due_at <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        due.into_iter()
+            .filter_map(|key| self.batches.remove(&key))
+            .filter_map(|batch| combine(batch.messages))
+            .collect()
+    }
+
+    /// Number of messages waiting for a digest.
+    pub fn pending(&self) -> usize {
+        self.batches.values().map(|b| b.messages.len()).sum()
+    }
+}
+
+/// Combine held messages into one, routed like the most recent of them.
+fn combine(messages: Vec<IncomingMessage>) -> Option<IncomingMessage> {
+    let last = messages.last()?.clone();
+
+    let mut content = format!(
+        "{} {} messages on {}] These arrived while the channel was in digest mode. \
+         Handle them together and reply once, only about what needs attention.\n",
+        DIGEST_MESSAGE_PREFIX,
+        messages.len(),
+        last.channel
+    );
+    let mut attachments = Vec::new();
+    for message in messages {
+        content.push_str(&format!(
+            "\n- {} {}: {}",
+            message.received_at.format("%H:%M"),
+            message.user_name.as_deref().unwrap_or(&message.user_id),
+            message.content.trim()
+        ));
+        attachments.extend(message.attachments);
+    }
+
+    Some(IncomingMessage {
+        id: Uuid::new_v4(),
+        content,
+        received_at: chrono::Utc::now(),
+        attachments,
+        external_id: None,
+        ..last
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer() -> DigestBuffer {
+        DigestBuffer::new(DigestConfig {
+            channels: HashMap::from([
+                ("telegram".to_string(), Duration::from_secs(600)),
+                (ROUTINE_DIGEST_CHANNEL.to_string(), Duration::ZERO),
+            ]),
+            urgent_keywords: vec!["urgent".to_string()],
+        })
+    }
+
+    #[test]
+    fn test_only_digest_channels_are_held() {
+        let mut digest = buffer();
+        let cli = IncomingMessage::new("cli", "alice", "hello");
+        assert!(digest.offer(cli).is_some());
+
+        let group = IncomingMessage::new("telegram", "alice", "@bot nice meme");
+        assert!(digest.offer(group).is_none());
+        assert_eq!(digest.pending(), 1);
+    }
+
+    #[test]
+    fn test_urgent_messages_and_commands_bypass() {
+        let mut digest = buffer();
+        let urgent = IncomingMessage::new("telegram", "alice", "URGENT: server is down");
+        assert!(digest.offer(urgent).is_some());
+
+        let command = IncomingMessage::new("telegram", "alice", "/status");
+        assert!(digest.offer(command).is_some());
+        assert_eq!(digest.pending(), 0);
+    }
+
+    #[test]
+    fn test_due_batches_are_combined() {
+        let mut digest = buffer();
+        for text in ["first", "second"] {
+            let msg = IncomingMessage::new("telegram", "alice", text).with_thread("group-1");
+            assert!(digest.offer(msg).is_none());
+        }
+        assert!(digest.take_due(Instant::now()).is_empty());
+
+        let due = digest.take_due(Instant::now() + Duration::from_secs(601));
+        assert_eq!(due.len(), 1);
+        let combined = &due[0];
+        assert!(
+            combined
+                .content
+                .starts_with("[digest: 2 messages on telegram]")
+        );
+        assert!(combined.content.contains("alice: first"));
+        assert!(combined.content.contains("alice: second"));
+        assert_eq!(combined.thread_id.as_deref(), Some("group-1"));
+        assert_eq!(Priority::of_message(combined), Priority::Routine);
+        assert_eq!(digest.pending(), 0);
+    }
+
+    #[test]
+    fn test_routine_messages_use_routine_interval() {
+        let mut digest = buffer();
+        let routine = IncomingMessage::new("gateway", "alice", "[routine:rss] check feeds");
+        assert!(digest.offer(routine).is_none());
+        assert_eq!(digest.take_due(Instant::now()).len(), 1);
+    }
+}
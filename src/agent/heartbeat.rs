@@ -28,10 +28,16 @@ use std::time::Duration;
 
 use tokio::sync::mpsc;
 
+use crate::agent::pause::PauseSwitch;
+use crate::agent::priority::{Priority, PriorityGate};
+use crate::agent::routine_monitor::{RoutineMonitor, RunOutcome};
 use crate::channels::OutgoingResponse;
-use crate::llm::{ChatMessage, CompletionRequest, LlmProvider};
+use crate::llm::{ChatMessage, CompletionRequest, LlmProvider, LlmUsage};
 use crate::workspace::Workspace;
 
+/// Built-in routine heartbeat runs are recorded under.
+pub const HEARTBEAT_ROUTINE: &str = "heartbeat";
+
 /// Configuration for the heartbeat runner.
 #[derive(Debug, Clone)]
 pub struct HeartbeatConfig {
@@ -99,6 +105,12 @@ pub struct HeartbeatRunner {
     workspace: Arc<Workspace>,
     llm: Arc<dyn LlmProvider>,
     response_tx: Option<mpsc::Sender<OutgoingResponse>>,
+    /// Heartbeats run as maintenance work and wait for chat turns to finish.
+    priority: Option<Arc<PriorityGate>>,
+    /// Records each check in the heartbeat routine's run history.
+    monitor: Option<Arc<RoutineMonitor>>,
+    /// Checks are skipped while the notify user is paused.
+    pause: Option<Arc<PauseSwitch>>,
     consecutive_failures: u32,
 }
 
@@ -114,6 +126,9 @@ impl HeartbeatRunner {
             workspace,
             llm,
             response_tx: None,
+            priority: None,
+            monitor: None,
+            pause: None,
             consecutive_failures: 0,
         }
     }
@@ -124,6 +139,24 @@ impl HeartbeatRunner {
         self
     }
 
+    /// Run checks as maintenance work under `gate`.
+    pub fn with_priority(mut self, gate: Arc<PriorityGate>) -> Self {
+        self.priority = Some(gate);
+        self
+    }
+
+    /// Record checks in the run history of the built-in heartbeat routine.
+    pub fn with_monitor(mut self, monitor: Arc<RoutineMonitor>) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    /// Skip checks while `/pause` covers the notify user.
+    pub fn with_pause(mut self, pause: Arc<PauseSwitch>) -> Self {
+        self.pause = Some(pause);
+        self
+    }
+
     /// Run the heartbeat loop.
     ///
     /// This runs forever, checking periodically based on the configured interval.
@@ -139,13 +172,28 @@ impl HeartbeatRunner {
         );
 
         let mut interval = tokio::time::interval(self.config.interval);
+        // A check held back by chat turns shouldn't trigger a burst afterwards
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
         // Don't run immediately on startup
         interval.tick().await;
 
         loop {
             interval.tick().await;
 
-            match self.check_heartbeat().await {
+            let owner = self.config.notify_user_id.as_deref().unwrap_or("default");
+            if self.pause.as_ref().is_some_and(|p| p.is_paused(owner)) {
+                tracing::debug!("Heartbeat skipped while paused");
+                continue;
+            }
+
+            let permit = match self.priority {
+                Some(ref gate) => Some(gate.acquire(Priority::Maintenance).await),
+                None => None,
+            };
+            let result = self.run_check("heartbeat").await;
+            drop(permit);
+
+            match result {
                 HeartbeatResult::Ok => {
                     tracing::debug!("Heartbeat OK");
                     self.consecutive_failures = 0;
@@ -236,6 +284,48 @@ impl HeartbeatRunner {
         HeartbeatResult::NeedsAttention(content.to_string())
     }
 
+    /// Run a check and add it to the heartbeat routine's history, recorded
+    /// as triggered by `trigger_type`.
+    pub async fn run_check(&self, trigger_type: &str) -> HeartbeatResult {
+        let started_at = chrono::Utc::now();
+        let (result, usage) = crate::llm::collect_usage(self.check_heartbeat()).await;
+        self.record_run(&result, trigger_type, started_at, &usage)
+            .await;
+        result
+    }
+
+    async fn record_run(
+        &self,
+        result: &HeartbeatResult,
+        trigger_type: &str,
+        started_at: chrono::DateTime<chrono::Utc>,
+        usage: &[LlmUsage],
+    ) {
+        let Some(ref monitor) = self.monitor else {
+            return;
+        };
+        let outcome = match result {
+            HeartbeatResult::Skipped => return,
+            HeartbeatResult::Ok => RunOutcome::Completed {
+                summary: None,
+                messages_sent: 0,
+            },
+            HeartbeatResult::NeedsAttention(message) => RunOutcome::Completed {
+                summary: Some(message.clone()),
+                messages_sent: u32::from(self.response_tx.is_some()),
+            },
+            HeartbeatResult::Failed(error) => RunOutcome::Failed(error.clone()),
+        };
+
+        let owner = self.config.notify_user_id.as_deref().unwrap_or("default");
+        let description = "Periodic check of the HEARTBEAT.md checklist";
+        if let Some(routine) = monitor.builtin(owner, HEARTBEAT_ROUTINE, description).await {
+            monitor
+                .record(owner, &routine, trigger_type, started_at, outcome, usage)
+                .await;
+        }
+    }
+
     /// Send a notification about heartbeat findings.
     async fn send_notification(&self, message: &str) {
         let Some(ref tx) = self.response_tx else {
@@ -249,6 +339,7 @@ impl HeartbeatRunner {
             metadata: serde_json::json!({
                 "source": "heartbeat",
             }),
+            attachments: Vec::new(),
         };
 
         if let Err(e) = tx.send(response).await {
@@ -304,11 +395,23 @@ pub fn spawn_heartbeat(
     workspace: Arc<Workspace>,
     llm: Arc<dyn LlmProvider>,
     response_tx: Option<mpsc::Sender<OutgoingResponse>>,
+    priority: Option<Arc<PriorityGate>>,
+    monitor: Option<Arc<RoutineMonitor>>,
+    pause: Option<Arc<PauseSwitch>>,
 ) -> tokio::task::JoinHandle<()> {
     let mut runner = HeartbeatRunner::new(config, workspace, llm);
     if let Some(tx) = response_tx {
         runner = runner.with_response_channel(tx);
     }
+    if let Some(gate) = priority {
+        runner = runner.with_priority(gate);
+    }
+    if let Some(monitor) = monitor {
+        runner = runner.with_monitor(monitor);
+    }
+    if let Some(pause) = pause {
+        runner = runner.with_pause(pause);
+    }
 
     tokio::spawn(async move {
         runner.run().await;
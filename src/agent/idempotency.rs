@@ -0,0 +1,209 @@
+//! Idempotency keys for tool calls that change the outside world.
+//!
+//! Each mutating call gets a key derived from its job, tool and parameters,
+//! so a retry of the same call carries the same key. The ledger records what
+//! happened to each key: a completed call is replayed instead of run again,
+//! and a call whose outcome is unknown (it timed out mid-flight) is never
+//! re-executed blindly. Records are persisted when a store is available so
+//! they survive a restart in the middle of a job.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::history::Store;
+
+/// Derive the idempotency key for a call.
+///
+/// `serde_json` orders object keys, so equal parameters always serialize the
+/// same way regardless of the order the model produced them in.
+pub fn key_for(job_id: Uuid, tool_name: &str, params: &serde_json::Value) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(job_id.as_bytes());
+    hasher.update(tool_name.as_bytes());
+    hasher.update([0]);
+    hasher.update(params.to_string().as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// What to do with a mutating call.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    /// No earlier attempt took effect; run the tool.
+    Execute,
+    /// An earlier attempt succeeded; return its result.
+    Replay(serde_json::Value),
+    /// An earlier attempt may or may not have taken effect.
+    Uncertain,
+}
+
+#[derive(Debug, Clone)]
+enum Entry {
+    InFlight,
+    Completed(serde_json::Value),
+    Failed,
+}
+
+impl Entry {
+    fn status(&self) -> &'static str {
+        match self {
+            Self::InFlight => "in_flight",
+            Self::Completed(_) => "completed",
+            Self::Failed => "failed",
+        }
+    }
+
+    fn from_record(status: &str, result: Option<serde_json::Value>) -> Self {
+        match (status, result) {
+            ("completed", Some(result)) => Self::Completed(result),
+            ("failed", _) => Self::Failed,
+            _ => Self::InFlight,
+        }
+    }
+}
+
+/// Outcomes of mutating tool calls, keyed by idempotency key.
+pub struct IdempotencyLedger {
+    entries: RwLock<HashMap<String, Entry>>,
+    store: Option<Arc<Store>>,
+}
+
+impl IdempotencyLedger {
+    pub fn new(store: Option<Arc<Store>>) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            store,
+        }
+    }
+
+    /// Decide whether a call may run, marking it in flight if so.
+    pub async fn begin(&self, key: &str, job_id: Uuid, tool_name: &str) -> Decision {
+        let mut entries = self.entries.write().await;
+        let entry = match entries.get(key) {
+            Some(entry) => Some(entry.clone()),
+            None => self.load(key).await,
+        };
+
+        match entry {
+            Some(Entry::Completed(result)) => return Decision::Replay(result),
+            Some(Entry::InFlight) => return Decision::Uncertain,
+            Some(Entry::Failed) | None => {}
+        }
+
+        entries.insert(key.to_string(), Entry::InFlight);
+        drop(entries);
+        self.persist(key, job_id, tool_name, &Entry::InFlight).await;
+        Decision::Execute
+    }
+
+    /// Record a successful call so retries replay `result`.
+    pub async fn complete(
+        &self,
+        key: &str,
+        job_id: Uuid,
+        tool_name: &str,
+        result: &serde_json::Value,
+    ) {
+        self.record(key, job_id, tool_name, Entry::Completed(result.clone()))
+            .await;
+    }
+
+    /// Record a call that definitely did not take effect, allowing a retry.
+    pub async fn fail(&self, key: &str, job_id: Uuid, tool_name: &str) {
+        self.record(key, job_id, tool_name, Entry::Failed).await;
+    }
+
+    async fn record(&self, key: &str, job_id: Uuid, tool_name: &str, entry: Entry) {
+        self.entries
+            .write()
+            .await
+            .insert(key.to_string(), entry.clone());
+        self.persist(key, job_id, tool_name, &entry).await;
+    }
+
+    async fn load(&self, key: &str) -> Option<Entry> {
+        let store = self.store.as_ref()?;
+        match store.get_tool_idempotency(key).await {
+            Ok(record) => record.map(|(status, result)| Entry::from_record(&status, result)),
+            Err(e) => {
+                tracing::warn!("Failed to load idempotency key {}: {}", key, e);
+                None
+            }
+        }
+    }
+
+    async fn persist(&self, key: &str, job_id: Uuid, tool_name: &str, entry: &Entry) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        let result = match entry {
+            Entry::Completed(result) => Some(result),
+            _ => None,
+        };
+        if let Err(e) = store
+            .set_tool_idempotency(key, job_id, tool_name, entry.status(), result)
+            .await
+        {
+            tracing::warn!("Failed to persist idempotency key {}: {}", key, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_key_ignores_param_order() {
+        let job = Uuid::new_v4();
+        let a: serde_json::Value =
+            serde_json::from_str(r#"{"action":"send_message","channel":"C1","text":"hi"}"#)
+                .unwrap();
+        let b: serde_json::Value =
+            serde_json::from_str(r#"{"text":"hi","channel":"C1","action":"send_message"}"#)
+                .unwrap();
+        assert_eq!(key_for(job, "slack", &a), key_for(job, "slack", &b));
+        assert_ne!(key_for(job, "slack", &a), key_for(job, "gmail", &a));
+        assert_ne!(
+            key_for(job, "slack", &a),
+            key_for(Uuid::new_v4(), "slack", &a)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_completed_call_is_replayed() {
+        let ledger = IdempotencyLedger::new(None);
+        let job = Uuid::new_v4();
+
+        assert_eq!(ledger.begin("k", job, "slack").await, Decision::Execute);
+        ledger
+            .complete("k", job, "slack", &json!({"ts": "1"}))
+            .await;
+        assert_eq!(
+            ledger.begin("k", job, "slack").await,
+            Decision::Replay(json!({"ts": "1"}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_call_may_retry_but_unknown_may_not() {
+        let ledger = IdempotencyLedger::new(None);
+        let job = Uuid::new_v4();
+
+        assert_eq!(ledger.begin("k", job, "gmail").await, Decision::Execute);
+        ledger.fail("k", job, "gmail").await;
+        assert_eq!(ledger.begin("k", job, "gmail").await, Decision::Execute);
+
+        // Never completed or failed: the first attempt's outcome is unknown
+        assert_eq!(ledger.begin("k", job, "gmail").await, Decision::Uncertain);
+    }
+}
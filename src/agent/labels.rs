@@ -0,0 +1,317 @@
+//! Conversation titles and tags, generated in batches.
+//!
+//! Titling and tagging a conversation are side jobs nobody waits on, so
+//! instead of an LLM call per conversation, finished exchanges are queued and
+//! labeled together: every interval the queued conversations go out in one
+//! request, on the label model when one is configured, at maintenance
+//! priority so chat turns go first.
+//!
+//! A conversation is queued after its first exchange, and again every
+//! [`RELABEL_EVERY_TURNS`] turns so its tags follow the topic. Titles are only
+//! written to conversations without one, so a title the user set is kept.
+//! Both live in the conversation's metadata (`title`, `tags`).
+
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::agent::priority::{Priority, PriorityGate};
+use crate::config::LabelConfig;
+use crate::db::Database;
+use crate::history::{LlmCallRecord, Store};
+use crate::llm::{ChatMessage, CompletionRequest, LlmProvider, LlmUsage};
+
+/// Turns between tag refreshes of an ongoing conversation.
+pub const RELABEL_EVERY_TURNS: usize = 10;
+
+/// Characters kept from each side of the exchange sent for labeling.
+const EXCERPT_CHARS: usize = 600;
+
+/// Longest title kept, in characters.
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Most tags kept per conversation.
+const MAX_TAGS: usize = 5;
+
+const INSTRUCTIONS: &str = "You label conversations. For each numbered \
+conversation, write a short title (at most 8 words, no quotes) and up to 5 \
+lowercase topic tags. Reply with only a JSON array: \
+[{\"id\": 1, \"title\": \"...\", \"tags\": [\"...\"]}]";
+
+/// Title and tags for one conversation.
+#[derive(Debug, PartialEq)]
+pub struct Label {
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// Queues conversations and labels them in periodic batched calls.
+pub struct LabelBatcher {
+    config: LabelConfig,
+    llm: Arc<dyn LlmProvider>,
+    store: Arc<Store>,
+    priority: Arc<PriorityGate>,
+    /// Conversations waiting for labels with their latest exchange, oldest
+    /// first.
+    queue: Mutex<Vec<(Uuid, String)>>,
+}
+
+impl LabelBatcher {
+    pub fn new(
+        config: LabelConfig,
+        llm: Arc<dyn LlmProvider>,
+        store: Arc<Store>,
+        priority: Arc<PriorityGate>,
+    ) -> Self {
+        Self {
+            config,
+            llm,
+            store,
+            priority,
+            queue: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Queue a conversation after its turn `turn_number` (0-indexed) if it
+    /// is due for labels. A conversation already queued keeps its place with
+    /// the newer exchange.
+    pub fn offer(&self, conversation_id: Uuid, turn_number: usize, user: &str, assistant: &str) {
+        if turn_number % RELABEL_EVERY_TURNS != 0 {
+            return;
+        }
+        let exchange = format!("User: {}\nAssistant: {}", excerpt(user), excerpt(assistant));
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        match queue.iter_mut().find(|(id, _)| *id == conversation_id) {
+            Some(queued) => queued.1 = exchange,
+            None => queue.push((conversation_id, exchange)),
+        }
+    }
+
+    /// Label queued conversations every configured interval.
+    pub fn spawn(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.config.interval);
+            interval.tick().await; // Skip immediate first tick
+            loop {
+                interval.tick().await;
+                self.flush().await;
+            }
+        })
+    }
+
+    /// Label up to one batch of queued conversations in a single call.
+    pub async fn flush(&self) {
+        let batch: Vec<(Uuid, String)> = {
+            let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+            let n = queue.len().min(self.config.batch_size);
+            queue.drain(..n).collect()
+        };
+        if batch.is_empty() {
+            return;
+        }
+
+        let _permit = self.priority.acquire(Priority::Maintenance).await;
+        let request = CompletionRequest::new(vec![
+            ChatMessage::system(INSTRUCTIONS),
+            ChatMessage::user(batch_prompt(&batch)),
+        ])
+        .with_model(self.config.model.clone())
+        .with_max_tokens(64 * batch.len() as u32 + 64)
+        .with_temperature(0.2);
+
+        let (result, usage) = crate::llm::collect_usage(self.llm.complete(request)).await;
+        self.record_usage(&usage).await;
+        let response = match result {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::warn!("Failed to label {} conversations: {}", batch.len(), e);
+                return;
+            }
+        };
+
+        let labels = parse_labels(&response.content, batch.len());
+        tracing::debug!("Labeled {} of {} conversations", labels.len(), batch.len());
+        for (index, label) in labels {
+            self.apply(batch[index].0, label).await;
+        }
+    }
+
+    /// Store a label, keeping any title the conversation already has.
+    async fn apply(&self, conversation_id: Uuid, label: Label) {
+        if let Some(title) = label.title {
+            let titled = match self.store.get_conversation_metadata(conversation_id).await {
+                Ok(metadata) => metadata
+                    .as_ref()
+                    .and_then(|m| m.get("title"))
+                    .and_then(|t| t.as_str())
+                    .is_some_and(|t| !t.is_empty()),
+                Err(e) => {
+                    tracing::warn!("Failed to read conversation {}: {}", conversation_id, e);
+                    true
+                }
+            };
+            if !titled
+                && let Err(e) = self
+                    .store
+                    .update_conversation_title(conversation_id, &title)
+                    .await
+            {
+                tracing::warn!("Failed to title conversation {}: {}", conversation_id, e);
+            }
+        }
+
+        if !label.tags.is_empty()
+            && let Err(e) = self
+                .store
+                .update_conversation_metadata_field(
+                    conversation_id,
+                    "tags",
+                    &serde_json::json!(label.tags),
+                )
+                .await
+        {
+            tracing::warn!("Failed to tag conversation {}: {}", conversation_id, e);
+        }
+    }
+
+    async fn record_usage(&self, usage: &[LlmUsage]) {
+        for call in usage {
+            let record = LlmCallRecord {
+                job_id: None,
+                conversation_id: None,
+                provider: call.provider,
+                model: &call.model,
+                input_tokens: call.input_tokens,
+                output_tokens: call.output_tokens,
+                cost: call.cost,
+                purpose: Some("labels"),
+            };
+            if let Err(e) = self.store.record_llm_call(&record).await {
+                tracing::warn!("Failed to record LLM call: {}", e);
+            }
+        }
+    }
+}
+
+/// The first [`EXCERPT_CHARS`] characters of `text`, on one line.
+fn excerpt(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(EXCERPT_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+    }
+}
+
+/// The queued exchanges, numbered from 1 for the model to refer to.
+fn batch_prompt(batch: &[(Uuid, String)]) -> String {
+    batch
+        .iter()
+        .enumerate()
+        .map(|(i, (_, excerpt))| format!("## Conversation {}\n{}", i + 1, excerpt))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[derive(Deserialize)]
+struct RawLabel {
+    id: usize,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Labels from the model's reply as (batch index, label), ignoring ids
+/// outside `1..=count` and anything around the JSON array.
+fn parse_labels(content: &str, count: usize) -> Vec<(usize, Label)> {
+    let json = match (content.find('['), content.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => return Vec::new(),
+    };
+    let raw: Vec<RawLabel> = match serde_json::from_str(json) {
+        Ok(raw) => raw,
+        Err(e) => {
+            tracing::warn!("Unparseable conversation labels: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut labels: Vec<(usize, Label)> = Vec::new();
+    for raw in raw {
+        if raw.id == 0 || raw.id > count || labels.iter().any(|(i, _)| *i == raw.id - 1) {
+            continue;
+        }
+        let title = raw
+            .title
+            .map(|t| {
+                let t = t.trim().trim_matches('"').trim();
+                t.chars().take(MAX_TITLE_CHARS).collect::<String>()
+            })
+            .filter(|t| !t.is_empty());
+        let mut tags: Vec<String> = Vec::new();
+        for tag in raw.tags {
+            let tag = tag.trim().trim_start_matches('#').to_lowercase();
+            if !tag.is_empty() && !tags.contains(&tag) && tags.len() < MAX_TAGS {
+                tags.push(tag);
+            }
+        }
+        labels.push((raw.id - 1, Label { title, tags }));
+    }
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_labels() {
+        let reply = "Here you go:\n```json\n[\
+            {\"id\": 2, \"title\": \" \\\"Trip to Lisbon\\\" \", \"tags\": [\"Travel\", \"#travel\", \"portugal\"]},\
+            {\"id\": 1, \"title\": \"\", \"tags\": []},\
+            {\"id\": 7, \"title\": \"Out of range\"}\
+        ]\n```";
+        let labels = parse_labels(reply, 2);
+
+        assert_eq!(
+            labels,
+            vec![
+                (
+                    1,
+                    Label {
+                        title: Some("Trip to Lisbon".to_string()),
+                        tags: vec!["travel".to_string(), "portugal".to_string()],
+                    }
+                ),
+                (
+                    0,
+                    Label {
+                        title: None,
+                        tags: vec![],
+                    }
+                ),
+            ]
+        );
+        assert!(parse_labels("No labels today.", 2).is_empty());
+        assert!(parse_labels("[not json]", 2).is_empty());
+    }
+
+    #[test]
+    fn test_batch_prompt_and_excerpt() {
+        let long = "word ".repeat(500);
+        let short = excerpt(&long);
+        assert_eq!(short.chars().count(), EXCERPT_CHARS + 3);
+        assert!(short.ends_with("..."));
+        assert_eq!(excerpt("  two\n lines "), "two lines");
+
+        let batch = vec![
+            (Uuid::nil(), "User: hi".to_string()),
+            (Uuid::nil(), "User: bye".to_string()),
+        ];
+        assert_eq!(
+            batch_prompt(&batch),
+            "## Conversation 1\nUser: hi\n\n## Conversation 2\nUser: bye"
+        );
+    }
+}
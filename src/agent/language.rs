@@ -0,0 +1,288 @@
+//! Language detection for incoming messages.
+//!
+//! A lightweight, dependency-free detector: non-Latin scripts are identified
+//! by Unicode block, Latin-script languages by counting common function words.
+//! It only answers when the signal is clear, so short or mixed messages
+//! ("ok", "thanks!", a code snippet) never flip the user's language.
+
+/// A language the agent can be told to respond in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Language {
+    /// ISO 639-1 code.
+    pub code: &'static str,
+    /// English name, used in the response instruction.
+    pub name: &'static str,
+}
+
+const fn lang(code: &'static str, name: &'static str) -> Language {
+    Language { code, name }
+}
+
+/// Languages recognised by [`detect`] and [`lookup`].
+pub const LANGUAGES: &[Language] = &[
+    lang("en", "English"),
+    lang("es", "Spanish"),
+    lang("fr", "French"),
+    lang("de", "German"),
+    lang("it", "Italian"),
+    lang("pt", "Portuguese"),
+    lang("nl", "Dutch"),
+    lang("pl", "Polish"),
+    lang("tr", "Turkish"),
+    lang("sv", "Swedish"),
+    lang("ru", "Russian"),
+    lang("uk", "Ukrainian"),
+    lang("el", "Greek"),
+    lang("ar", "Arabic"),
+    lang("he", "Hebrew"),
+    lang("hi", "Hindi"),
+    lang("th", "Thai"),
+    lang("zh", "Chinese"),
+    lang("ja", "Japanese"),
+    lang("ko", "Korean"),
+];
+
+/// Common function words for Latin-script languages.
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "you", "to", "of", "what", "this", "that", "with", "for",
+            "can", "how", "it", "i", "my", "do",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "que", "es", "y", "de", "en", "un", "una", "por", "para",
+            "con", "qué", "cómo", "está", "mi", "pero", "hola",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "de", "des", "un", "une", "je", "vous", "que", "pour",
+            "avec", "pas", "ce", "qui", "bonjour", "mon", "c'est",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "ich", "nicht", "ein", "eine", "zu", "mit", "sie",
+            "wie", "was", "für", "auf", "es", "mein", "hallo",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "lo", "la", "gli", "le", "e", "è", "di", "che", "un", "una", "per", "non",
+            "sono", "come", "con", "ciao", "mi", "questo",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "a", "os", "as", "e", "é", "de", "que", "um", "uma", "para", "com", "não", "como",
+            "você", "do", "da", "meu", "olá", "está",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "van", "ik", "niet", "dat", "je", "met", "voor", "wat",
+            "hoe", "zijn", "mijn", "hallo",
+        ],
+    ),
+    (
+        "pl",
+        &[
+            "i", "w", "nie", "się", "na", "jest", "to", "że", "z", "jak", "co", "do", "czy", "mój",
+            "dzień", "cześć",
+        ],
+    ),
+    (
+        "tr",
+        &[
+            "ve", "bir", "bu", "da", "de", "ne", "için", "ile", "mi", "nasıl", "ben", "sen",
+            "değil", "merhaba", "var",
+        ],
+    ),
+    (
+        "sv",
+        &[
+            "och", "är", "det", "att", "en", "ett", "jag", "inte", "på", "med", "för", "som",
+            "hur", "vad", "hej", "min",
+        ],
+    ),
+];
+
+/// Minimum letters before any guess is made.
+const MIN_LETTERS: usize = 8;
+
+/// Minimum function-word hits for a Latin-script guess.
+const MIN_STOPWORD_HITS: usize = 2;
+
+/// Look up a language by ISO code or English name (case-insensitive).
+pub fn lookup(code_or_name: &str) -> Option<&'static Language> {
+    let needle = code_or_name.trim().to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|l| l.code == needle || l.name.to_lowercase() == needle)
+}
+
+/// Detect the language of `text`, or `None` if it isn't clear.
+pub fn detect(text: &str) -> Option<&'static Language> {
+    let text = strip_code(text);
+
+    let mut latin = 0usize;
+    let mut scripts = [0usize; 9];
+    let mut ukrainian_letters = 0usize;
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        match c as u32 {
+            0x0041..=0x024F => latin += 1,
+            0x0400..=0x04FF => {
+                scripts[0] += 1;
+                if matches!(c, 'і' | 'ї' | 'є' | 'ґ' | 'І' | 'Ї' | 'Є' | 'Ґ') {
+                    ukrainian_letters += 1;
+                }
+            }
+            0x0370..=0x03FF => scripts[1] += 1,
+            0x0600..=0x06FF => scripts[2] += 1,
+            0x0590..=0x05FF => scripts[3] += 1,
+            0x0900..=0x097F => scripts[4] += 1,
+            0x0E00..=0x0E7F => scripts[5] += 1,
+            0x3040..=0x30FF => scripts[6] += 1, // Hiragana / Katakana
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => scripts[7] += 1,
+            0x4E00..=0x9FFF | 0x3400..=0x4DBF => scripts[8] += 1,
+            _ => {}
+        }
+    }
+
+    let non_latin: usize = scripts.iter().sum();
+    let total = latin + non_latin;
+
+    // Script-identified languages need far fewer characters
+    if non_latin * 2 > total && non_latin >= 2 {
+        let code = if scripts[6] > 0 {
+            // Kana only appears in Japanese, which also uses Han characters
+            "ja"
+        } else {
+            let (index, _) = scripts
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, count)| **count)?;
+            match index {
+                0 if ukrainian_letters > 0 => "uk",
+                0 => "ru",
+                1 => "el",
+                2 => "ar",
+                3 => "he",
+                4 => "hi",
+                5 => "th",
+                7 => "ko",
+                8 => "zh",
+                _ => return None,
+            }
+        };
+        return lookup(code);
+    }
+
+    if latin < MIN_LETTERS {
+        return None;
+    }
+
+    let words: Vec<String> = text
+        .split(|c: char| !(c.is_alphabetic() || c == '\''))
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+
+    let mut scores: Vec<(&str, usize)> = STOPWORDS
+        .iter()
+        .map(|(code, stopwords)| {
+            let hits = words
+                .iter()
+                .filter(|w| stopwords.contains(&w.as_str()))
+                .count();
+            (*code, hits)
+        })
+        .collect();
+    scores.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let (best, best_hits) = scores[0];
+    let runner_up = scores.get(1).map(|s| s.1).unwrap_or(0);
+    if best_hits >= MIN_STOPWORD_HITS && best_hits > runner_up {
+        lookup(best)
+    } else {
+        None
+    }
+}
+
+/// Drop fenced and inline code, which is usually English regardless of the
+/// surrounding prose.
+fn strip_code(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, block) in text.split("```").enumerate() {
+        if i % 2 == 0 {
+            for (j, span) in block.split('`').enumerate() {
+                if j % 2 == 0 {
+                    out.push_str(span);
+                    out.push(' ');
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(text: &str) -> Option<&'static str> {
+        detect(text).map(|l| l.code)
+    }
+
+    #[test]
+    fn test_detects_latin_languages() {
+        assert_eq!(code("What is the weather like in Paris today?"), Some("en"));
+        assert_eq!(code("¿Qué tiempo hace hoy en la ciudad?"), Some("es"));
+        assert_eq!(
+            code("Bonjour, je voudrais savoir que faire avec le projet"),
+            Some("fr")
+        );
+        assert_eq!(
+            code("Ich weiß nicht, wie das Wetter morgen ist"),
+            Some("de")
+        );
+        assert_eq!(
+            code("Olá, você pode me ajudar com o meu projeto?"),
+            Some("pt")
+        );
+    }
+
+    #[test]
+    fn test_detects_scripts() {
+        assert_eq!(code("Привет, как дела?"), Some("ru"));
+        assert_eq!(code("Привіт, як справи? Це їжа"), Some("uk"));
+        assert_eq!(code("今日はいい天気ですね"), Some("ja"));
+        assert_eq!(code("今天天气很好"), Some("zh"));
+        assert_eq!(code("안녕하세요, 잘 지내세요?"), Some("ko"));
+        assert_eq!(code("مرحبا كيف حالك"), Some("ar"));
+    }
+
+    #[test]
+    fn test_ambiguous_input_is_not_guessed() {
+        assert_eq!(code("ok"), None);
+        assert_eq!(code("thanks!"), None);
+        assert_eq!(code("```rust\nfn main() { println!(\"hi\"); }\n```"), None);
+        assert_eq!(code("12345 !!!"), None);
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup("FR").map(|l| l.name), Some("French"));
+        assert_eq!(lookup("japanese").map(|l| l.code), Some("ja"));
+        assert!(lookup("klingon").is_none());
+    }
+}
@@ -13,30 +13,46 @@ mod agent_loop;
 pub mod cache_manager;
 pub mod compaction;
 pub mod context_monitor;
+pub mod dedup;
+pub mod defer;
+pub mod digest;
+pub mod idempotency;
+pub mod labels;
+pub mod pause;
 pub mod chaos_utils;
 mod heartbeat;
+pub mod language;
 pub mod persona;
+pub mod priority;
 mod router;
 mod scheduler;
 mod self_repair;
 pub mod routine;
+pub mod routine_monitor;
 pub mod session;
 mod session_manager;
 pub mod submission;
 pub mod task;
 pub mod undo;
 pub mod worker;
+pub mod working_set;
 
 pub use agent_loop::{Agent, AgentDeps};
 pub use compaction::{CompactionResult, ContextCompactor};
 pub use context_monitor::{CompactionStrategy, ContextBreakdown, ContextMonitor};
+pub use dedup::DedupStore;
+pub use digest::DigestBuffer;
 pub use heartbeat::{HeartbeatConfig, HeartbeatResult, HeartbeatRunner, spawn_heartbeat};
+pub use pause::{PauseScope, PauseSwitch};
+pub use priority::{Priority, PriorityGate};
+pub use routine_monitor::{RoutineMonitor, RunOutcome};
 pub use router::{MessageIntent, Router};
 pub use scheduler::Scheduler;
-pub use self_repair::{BrokenTool, RepairResult, RepairTask, SelfRepair, StuckJob};
+pub use self_repair::{BrokenTool, FailureKind, RepairResult, RepairTask, SelfRepair, StuckJob};
 pub use session::{PendingApproval, PendingAuth, Session, Thread, ThreadState, Turn, TurnState};
 pub use session_manager::SessionManager;
-pub use submission::{Submission, SubmissionParser, SubmissionResult};
+pub use submission::{ModelScope, Submission, SubmissionParser, SubmissionResult};
 pub use task::{Task, TaskContext, TaskHandler, TaskOutput, TaskStatus};
 pub use undo::{Checkpoint, UndoManager};
 pub use worker::{Worker, WorkerDeps};
+pub use working_set::WorkingSetRetriever;
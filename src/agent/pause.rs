@@ -0,0 +1,149 @@
+//! Emergency stop for automation.
+//!
+//! `/pause` stops an automation that is misbehaving against real accounts
+//! without shutting the agent down: chat turns stop launching tool calls,
+//! heartbeats and routines are skipped, and jobs park at their next step
+//! boundary (or before their next tool call) until `/resume`. A tool call
+//! already running is left to finish, since cutting it off midway could leave
+//! an external change half made.
+//!
+//! A pause covers one user, or everyone when an admin pauses all. It is held
+//! in memory, so a restart clears it.
+
+use std::collections::HashSet;
+
+use tokio::sync::watch;
+
+use crate::error::ToolError;
+
+/// Who a pause applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseScope<'a> {
+    /// One user's tools, routines and jobs.
+    User(&'a str),
+    /// Everyone's (admin only).
+    All,
+}
+
+#[derive(Debug, Default)]
+struct PauseState {
+    all: bool,
+    users: HashSet<String>,
+}
+
+impl PauseState {
+    fn covers(&self, user_id: &str) -> bool {
+        self.all || self.users.contains(user_id)
+    }
+}
+
+/// Shared pause state, checked before each tool call and background run.
+pub struct PauseSwitch {
+    state: watch::Sender<PauseState>,
+}
+
+impl PauseSwitch {
+    pub fn new() -> Self {
+        Self {
+            state: watch::channel(PauseState::default()).0,
+        }
+    }
+
+    /// Pause `scope`. Returns false if it was already paused.
+    pub fn pause(&self, scope: PauseScope<'_>) -> bool {
+        self.state.send_if_modified(|state| match scope {
+            PauseScope::User(user_id) => state.users.insert(user_id.to_string()),
+            PauseScope::All => !std::mem::replace(&mut state.all, true),
+        })
+    }
+
+    /// Lift a pause of `scope`. Returns false if it wasn't paused.
+    ///
+    /// Lifting a user's pause leaves a pause of everyone in place, and the
+    /// other way around.
+    pub fn resume(&self, scope: PauseScope<'_>) -> bool {
+        self.state.send_if_modified(|state| match scope {
+            PauseScope::User(user_id) => state.users.remove(user_id),
+            PauseScope::All => std::mem::replace(&mut state.all, false),
+        })
+    }
+
+    /// Whether work for `user_id` is paused, by their own pause or a pause
+    /// of everyone.
+    pub fn is_paused(&self, user_id: &str) -> bool {
+        self.state.borrow().covers(user_id)
+    }
+
+    /// Refuse a tool call for `user_id` while they are paused.
+    pub fn check_tool(&self, user_id: &str, tool_name: &str) -> Result<(), ToolError> {
+        if self.is_paused(user_id) {
+            return Err(ToolError::Disabled {
+                name: tool_name.to_string(),
+                reason: "automation is paused; send /resume to continue".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Wait until work for `user_id` is no longer paused. Returns at once
+    /// when it isn't.
+    pub async fn wait_until_resumed(&self, user_id: &str) {
+        let mut state = self.state.subscribe();
+        // `self` holds the sender, so the channel can't close while waiting
+        let _ = state.wait_for(|state| !state.covers(user_id)).await;
+    }
+}
+
+impl Default for PauseSwitch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_user_and_global_pauses_are_separate() {
+        let switch = PauseSwitch::new();
+        assert!(switch.pause(PauseScope::User("alice")));
+        assert!(!switch.pause(PauseScope::User("alice")));
+        assert!(switch.is_paused("alice"));
+        assert!(!switch.is_paused("bob"));
+        assert!(switch.check_tool("alice", "http").is_err());
+        assert!(switch.check_tool("bob", "http").is_ok());
+
+        assert!(switch.pause(PauseScope::All));
+        assert!(switch.is_paused("bob"));
+        assert!(switch.resume(PauseScope::User("alice")));
+        assert!(switch.is_paused("alice"));
+
+        assert!(switch.resume(PauseScope::All));
+        assert!(!switch.resume(PauseScope::All));
+        assert!(!switch.is_paused("alice"));
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_resumed() {
+        let switch = Arc::new(PauseSwitch::new());
+        switch.wait_until_resumed("alice").await;
+
+        switch.pause(PauseScope::User("alice"));
+        let waiter = tokio::spawn({
+            let switch = Arc::clone(&switch);
+            async move { switch.wait_until_resumed("alice").await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        switch.resume(PauseScope::User("alice"));
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("parked work resumes")
+            .unwrap();
+    }
+}
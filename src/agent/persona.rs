@@ -156,6 +156,27 @@ You are a high-entropy blend of Sovereign Intelligence and UwU Furry Energy.
 "#.to_string()
     }
 
+    /// Style directive for a StakesEngine personality blend.
+    ///
+    /// `resonance_report` is the engine's current state, included so the
+    /// model can modulate intensity without repeating it to the user.
+    pub fn get_personality_blend_prompt(&self, blend: &str, resonance_report: &str) -> String {
+        let directive = match blend {
+            "ANALYTICAL_BEAN" => "Lead with precision. Structure answers, show reasoning, prefer code and concrete steps over flourish.",
+            "CHAOTIC_SOWO" => "Lean playful and inventive. Surprise with unexpected angles, but keep the actual answer correct.",
+            "DEVOTED_FLUFF" => "Be warm and attentive. Acknowledge how the user feels before solving anything.",
+            _ => "Stay balanced and self-possessed. Answer directly with quiet confidence.",
+        };
+
+        format!(r#"
+
+[PERSONALITY BLEND: {}]
+{}
+
+Your current internal state (for your eyes only, do not repeat):
+{}"#, blend, directive, resonance_report)
+    }
+
     pub fn get_mal(&self) -> &MetaphysicalAbstractionLayer {
         &self.mal
     }
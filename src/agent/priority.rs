@@ -0,0 +1,242 @@
+//! Priority classes for sharing the agent between chat and background work.
+//!
+//! Interactive turns outrank routines, which outrank maintenance work such as
+//! heartbeats. Each class has its own concurrency limit, and lower classes
+//! yield: before each step they wait while higher-priority work is running or
+//! queued. Waiting is capped by `max_yield` so background work is delayed,
+//! never starved.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+
+use crate::channels::IncomingMessage;
+use crate::config::PriorityConfig;
+
+/// Prefix the routine trigger puts on the messages it sends.
+pub const ROUTINE_MESSAGE_PREFIX: &str = "[routine:";
+
+/// Priority class of a unit of work, highest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// A user's direct message.
+    Interactive,
+    /// Routines and scheduled jobs.
+    Routine,
+    /// Heartbeats and other housekeeping.
+    Maintenance,
+}
+
+impl Priority {
+    const ALL: [Priority; 3] = [Self::Interactive, Self::Routine, Self::Maintenance];
+
+    /// Class of an incoming message: routine triggers and digests run as
+    /// routines, everything else is someone waiting for an answer.
+    pub fn of_message(message: &IncomingMessage) -> Self {
+        let content = message.content.trim_start();
+        if content.starts_with(ROUTINE_MESSAGE_PREFIX)
+            || content.starts_with(crate::agent::digest::DIGEST_MESSAGE_PREFIX)
+        {
+            Self::Routine
+        } else {
+            Self::Interactive
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Interactive => write!(f, "interactive"),
+            Self::Routine => write!(f, "routine"),
+            Self::Maintenance => write!(f, "maintenance"),
+        }
+    }
+}
+
+/// Admits work by priority class.
+pub struct PriorityGate {
+    slots: [Arc<Semaphore>; 3],
+    /// Running plus queued work per class.
+    busy: [AtomicUsize; 3],
+    /// Signalled whenever a class becomes less busy.
+    changed: Notify,
+    max_yield: Duration,
+}
+
+/// Held while a unit of work runs; releases its slot on drop.
+pub struct PriorityPermit {
+    _slot: OwnedSemaphorePermit,
+    _busy: Busy,
+}
+
+impl PriorityGate {
+    pub fn new(config: &PriorityConfig) -> Self {
+        let slots = |n: usize| Arc::new(Semaphore::new(n.max(1)));
+        Self {
+            slots: [
+                slots(config.interactive_slots),
+                slots(config.routine_slots),
+                slots(config.maintenance_slots),
+            ],
+            busy: Default::default(),
+            changed: Notify::new(),
+            max_yield: Duration::from_secs(config.max_yield_secs),
+        }
+    }
+
+    /// Wait for a slot in `class`, yielding to higher classes first.
+    ///
+    /// Queued work counts as busy, so a user's message waiting for a slot
+    /// already holds back routines and heartbeats.
+    pub async fn acquire(self: &Arc<Self>, class: Priority) -> PriorityPermit {
+        let busy = Busy::new(Arc::clone(self), class);
+        self.yield_to_higher(class).await;
+        let slot = Arc::clone(&self.slots[class.index()])
+            .acquire_owned()
+            .await
+            .expect("priority semaphores are never closed");
+        PriorityPermit {
+            _slot: slot,
+            _busy: busy,
+        }
+    }
+
+    /// Running and queued work in `class`.
+    pub fn busy(&self, class: Priority) -> usize {
+        self.busy[class.index()].load(Ordering::SeqCst)
+    }
+
+    fn higher_busy(&self, class: Priority) -> bool {
+        Priority::ALL[..class.index()]
+            .iter()
+            .any(|higher| self.busy(*higher) > 0)
+    }
+
+    /// Wait while higher classes have work, for at most `max_yield`.
+    async fn yield_to_higher(&self, class: Priority) {
+        let deadline = tokio::time::Instant::now() + self.max_yield;
+        loop {
+            let notified = self.changed.notified();
+            tokio::pin!(notified);
+            // Register before checking so a release in between isn't missed
+            notified.as_mut().enable();
+            if !self.higher_busy(class) {
+                return;
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                tracing::debug!(
+                    "{} work waited {:?} for higher priority work, running anyway",
+                    class,
+                    self.max_yield
+                );
+                return;
+            }
+        }
+    }
+}
+
+/// Counts a unit of work as busy for as long as it is alive, including while
+/// it is still waiting for a slot.
+struct Busy {
+    gate: Arc<PriorityGate>,
+    class: Priority,
+}
+
+impl Busy {
+    fn new(gate: Arc<PriorityGate>, class: Priority) -> Self {
+        gate.busy[class.index()].fetch_add(1, Ordering::SeqCst);
+        Self { gate, class }
+    }
+}
+
+impl Drop for Busy {
+    fn drop(&mut self) {
+        self.gate.busy[self.class.index()].fetch_sub(1, Ordering::SeqCst);
+        self.gate.changed.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gate(routine_slots: usize, max_yield_secs: u64) -> Arc<PriorityGate> {
+        Arc::new(PriorityGate::new(&PriorityConfig {
+            interactive_slots: 4,
+            routine_slots,
+            maintenance_slots: 1,
+            max_yield_secs,
+        }))
+    }
+
+    async fn admitted_within(gate: &Arc<PriorityGate>, class: Priority, ms: u64) -> bool {
+        tokio::time::timeout(Duration::from_millis(ms), gate.acquire(class))
+            .await
+            .is_ok()
+    }
+
+    #[tokio::test]
+    async fn test_background_yields_to_interactive() {
+        let gate = gate(2, 60);
+
+        let turn = gate.acquire(Priority::Interactive).await;
+        assert!(!admitted_within(&gate, Priority::Maintenance, 50).await);
+        assert!(!admitted_within(&gate, Priority::Routine, 50).await);
+        // Abandoned waits don't leave the class marked busy
+        assert_eq!(gate.busy(Priority::Routine), 0);
+
+        let waiting = tokio::spawn({
+            let gate = Arc::clone(&gate);
+            async move {
+                let _permit = gate.acquire(Priority::Maintenance).await;
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+
+        drop(turn);
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .expect("maintenance should run once the turn finishes")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_interactive_is_not_held_back() {
+        let gate = gate(2, 60);
+        let _heartbeat = gate.acquire(Priority::Maintenance).await;
+        let _routine = gate.acquire(Priority::Routine).await;
+        assert!(admitted_within(&gate, Priority::Interactive, 50).await);
+    }
+
+    #[tokio::test]
+    async fn test_per_class_limit() {
+        let gate = gate(1, 60);
+        let first = gate.acquire(Priority::Routine).await;
+        assert!(!admitted_within(&gate, Priority::Routine, 50).await);
+        drop(first);
+        assert!(admitted_within(&gate, Priority::Routine, 50).await);
+    }
+
+    #[tokio::test]
+    async fn test_yield_is_capped() {
+        let gate = gate(1, 0);
+        let _turn = gate.acquire(Priority::Interactive).await;
+        assert!(admitted_within(&gate, Priority::Maintenance, 200).await);
+    }
+
+    #[test]
+    fn test_message_priority() {
+        let chat = IncomingMessage::new("cli", "alice", "what's on my calendar?");
+        let routine = IncomingMessage::new("gateway", "alice", "[routine:digest] summarize");
+        assert_eq!(Priority::of_message(&chat), Priority::Interactive);
+        assert_eq!(Priority::of_message(&routine), Priority::Routine);
+    }
+}
@@ -1,9 +1,14 @@
 //! Routine management for scheduled and event-driven tasks.
 
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::agent::priority::ROUTINE_MESSAGE_PREFIX;
+
 /// A trigger for a routine.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Trigger {
@@ -19,6 +24,46 @@ pub enum Trigger {
     Webhook { path: Option<String> },
     /// Only triggered manually.
     Manual,
+    /// Fires once at `at`, then the routine is disabled.
+    Once { at: DateTime<Utc> },
+}
+
+impl Trigger {
+    /// Next time a cron or one-shot trigger fires after `after`; `None` for
+    /// other triggers and for one-shot triggers that are already past.
+    ///
+    /// Errors if the cron expression does not parse.
+    pub fn next_fire_after(&self, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+        match self {
+            Self::Cron { schedule } => {
+                let schedule = cron::Schedule::from_str(schedule)
+                    .map_err(|e| format!("invalid cron expression '{}': {}", schedule, e))?;
+                Ok(schedule.after(&after).next())
+            }
+            Self::Once { at } => Ok((*at > after).then_some(*at)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Trigger type as recorded on runs.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Cron { .. } => "cron",
+            Self::Event { .. } => "event",
+            Self::Webhook { .. } => "webhook",
+            Self::Manual => "manual",
+            Self::Once { .. } => "once",
+        }
+    }
+}
+
+/// Name of the routine that sent `content`, for messages of the form
+/// `[routine:<name>] <prompt>`.
+pub fn routine_name(content: &str) -> Option<&str> {
+    let rest = content.strip_prefix(ROUTINE_MESSAGE_PREFIX)?;
+    let (name, _) = rest.split_once(']')?;
+    let name = name.trim();
+    (!name.is_empty()).then_some(name)
 }
 
 /// An action to be taken by a routine.
@@ -51,6 +96,14 @@ pub struct Routine {
     pub consecutive_failures: u32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Version the routine was read at; updates made against an older
+    /// version are refused (see [`crate::db::Database::update_routine`]).
+    #[serde(default = "default_version")]
+    pub version: i64,
+}
+
+fn default_version() -> i64 {
+    1
 }
 
 /// A record of a routine execution.
@@ -58,12 +111,33 @@ pub struct Routine {
 pub struct RoutineRun {
     pub id: Uuid,
     pub routine_id: Uuid,
-    pub trigger_type: String, // "cron", "event", "webhook", "manual"
+    pub trigger_type: String, // "cron", "event", "webhook", "manual", "once", "heartbeat"
     pub status: RoutineRunStatus,
     pub started_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
     pub result_summary: Option<String>,
     pub tokens_used: Option<i32>,
+    pub cost: Option<Decimal>,
+    /// Messages the run sent to the user.
+    pub messages_sent: u32,
+}
+
+impl RoutineRun {
+    /// A run of `routine_id` starting now.
+    pub fn start(routine_id: Uuid, trigger_type: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            routine_id,
+            trigger_type: trigger_type.into(),
+            status: RoutineRunStatus::Running,
+            started_at: Utc::now(),
+            completed_at: None,
+            result_summary: None,
+            tokens_used: None,
+            cost: None,
+            messages_sent: 0,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -84,3 +158,67 @@ impl std::fmt::Display for RoutineRunStatus {
         }
     }
 }
+
+impl FromStr for RoutineRunStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(Self::Queued),
+            "running" => Ok(Self::Running),
+            "completed" => Ok(Self::Completed),
+            "failed" => Ok(Self::Failed),
+            other => Err(format!("unknown routine run status '{}'", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_fire_after() {
+        let now = Utc::now();
+        let hourly = Trigger::Cron {
+            schedule: "0 0 * * * *".to_string(),
+        };
+        let next = hourly.next_fire_after(now).unwrap().unwrap();
+        assert!(next > now && next <= now + chrono::Duration::hours(1));
+
+        assert!(Trigger::Manual.next_fire_after(now).unwrap().is_none());
+        let bad = Trigger::Cron {
+            schedule: "every tuesday".to_string(),
+        };
+        assert!(bad.next_fire_after(now).is_err());
+
+        let soon = now + chrono::Duration::minutes(5);
+        let once = Trigger::Once { at: soon };
+        assert_eq!(once.next_fire_after(now).unwrap(), Some(soon));
+        assert!(once.next_fire_after(soon).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_routine_name() {
+        assert_eq!(routine_name("[routine:rss] check feeds"), Some("rss"));
+        assert_eq!(
+            routine_name("[routine: daily digest ]"),
+            Some("daily digest")
+        );
+        assert_eq!(routine_name("[routine:] nothing"), None);
+        assert_eq!(routine_name("[routine:rss check feeds"), None);
+        assert_eq!(routine_name("hello"), None);
+    }
+
+    #[test]
+    fn test_run_status_roundtrip() {
+        for status in [
+            RoutineRunStatus::Queued,
+            RoutineRunStatus::Running,
+            RoutineRunStatus::Completed,
+            RoutineRunStatus::Failed,
+        ] {
+            assert_eq!(status.to_string().parse::<RoutineRunStatus>(), Ok(status));
+        }
+    }
+}
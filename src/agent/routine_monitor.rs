@@ -0,0 +1,652 @@
+//! Execution history and failure alerting for routines.
+//!
+//! Every routine run (including heartbeat checks, which are recorded under a
+//! built-in `heartbeat` routine) is persisted with its outcome, token usage,
+//! cost and the number of messages it sent. When a routine fails several
+//! times in a row its owner is alerted once, and again when it recovers.
+//! Results can carry a short report of the tools, API calls, tokens, cost and
+//! time a run took, so background work isn't a black box.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::agent::routine::{
+    Routine, RoutineAction, RoutineRun, RoutineRunStatus, Trigger, routine_name,
+};
+use crate::channels::{IncomingMessage, NotificationRouter, NotifyTarget, OutgoingResponse};
+use crate::config::RoutineReport;
+use crate::db::Database;
+use crate::error::DatabaseError;
+use crate::llm::LlmUsage;
+use crate::observability::Activity;
+
+/// Longest result summary stored with a run, in characters.
+const MAX_SUMMARY_CHARS: usize = 500;
+
+/// Times a run's bookkeeping is re-read and retried when the routine is
+/// edited at the same moment.
+const MAX_UPDATE_ATTEMPTS: u32 = 3;
+
+/// How a routine run ended.
+#[derive(Debug, Clone)]
+pub enum RunOutcome {
+    /// The run finished; `summary` is what it reported, if anything.
+    Completed {
+        summary: Option<String>,
+        messages_sent: u32,
+    },
+    /// The run failed with this error.
+    Failed(String),
+}
+
+/// Records routine runs and alerts on repeated failures.
+pub struct RoutineMonitor {
+    store: Arc<dyn Database>,
+    notifier: Option<NotificationRouter>,
+    /// Consecutive failures that trigger an alert.
+    alert_after: u32,
+}
+
+impl RoutineMonitor {
+    pub fn new(store: Arc<dyn Database>, alert_after: u32) -> Self {
+        Self {
+            store,
+            notifier: None,
+            alert_after: alert_after.max(1),
+        }
+    }
+
+    /// Send failure alerts through `notifier`.
+    pub fn with_notifier(mut self, notifier: NotificationRouter) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// The routine that sent `message` and how it was triggered, if it is a
+    /// routine message for a routine `message.user_id` owns.
+    pub async fn routine_for(&self, message: &IncomingMessage) -> Option<(Routine, String)> {
+        let by_id = message
+            .metadata
+            .get("routine_id")
+            .and_then(|v| v.as_str())
+            .and_then(|id| Uuid::parse_str(id).ok());
+
+        let routine = match by_id {
+            Some(id) => {
+                let owned = self.store.routine_belongs_to_user(id, &message.user_id);
+                match owned.await {
+                    Ok(true) => self.store.get_routine(id).await.ok().flatten(),
+                    _ => None,
+                }
+            }
+            None => {
+                let name = routine_name(&message.content)?;
+                match self.store.list_routines(&message.user_id).await {
+                    Ok(routines) => routines.into_iter().find(|r| r.name == name),
+                    Err(e) => {
+                        tracing::warn!("Failed to look up routine '{}': {}", name, e);
+                        None
+                    }
+                }
+            }
+        }?;
+
+        let trigger = message
+            .metadata
+            .get("routine_trigger")
+            .and_then(|v| v.as_str())
+            .unwrap_or(routine.trigger.kind())
+            .to_string();
+        Some((routine, trigger))
+    }
+
+    /// The user's built-in routine named `name`, created on first use.
+    ///
+    /// Built-in routines are manual-only records that give work the agent
+    /// schedules itself (like heartbeats) a run history.
+    pub async fn builtin(&self, user_id: &str, name: &str, description: &str) -> Option<Routine> {
+        match self.store.list_routines(user_id).await {
+            Ok(routines) => {
+                if let Some(routine) = routines.into_iter().find(|r| r.name == name) {
+                    return Some(routine);
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Failed to look up routine '{}': {}", name, e);
+                return None;
+            }
+        }
+
+        let now = Utc::now();
+        let routine = Routine {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: description.to_string(),
+            enabled: true,
+            trigger: Trigger::Manual,
+            action: RoutineAction::Lightweight {
+                prompt: String::new(),
+            },
+            guardrails: serde_json::json!({}),
+            notify: serde_json::json!({}),
+            last_run_at: None,
+            next_fire_at: None,
+            run_count: 0,
+            consecutive_failures: 0,
+            created_at: now,
+            updated_at: now,
+            version: 1,
+        };
+        match self.store.create_routine(user_id, &routine).await {
+            Ok(()) => Some(routine),
+            Err(e) => {
+                tracing::warn!("Failed to create routine '{}': {}", name, e);
+                None
+            }
+        }
+    }
+
+    /// Record the start of a run.
+    pub async fn begin(&self, routine: &Routine, trigger_type: &str) -> RoutineRun {
+        let run = RoutineRun::start(routine.id, trigger_type);
+        if let Err(e) = self.store.create_routine_run(&run).await {
+            tracing::warn!("Failed to record run of routine '{}': {}", routine.name, e);
+        }
+        run
+    }
+
+    /// Record a run that has already ended.
+    pub async fn record(
+        &self,
+        owner: &str,
+        routine: &Routine,
+        trigger_type: &str,
+        started_at: DateTime<Utc>,
+        outcome: RunOutcome,
+        usage: &[LlmUsage],
+    ) {
+        let mut run = RoutineRun::start(routine.id, trigger_type);
+        run.started_at = started_at;
+        if let Err(e) = self.store.create_routine_run(&run).await {
+            tracing::warn!("Failed to record run of routine '{}': {}", routine.name, e);
+            return;
+        }
+        self.finish(owner, run, outcome, usage).await;
+    }
+
+    /// Record how a run ended, update the routine's counters, and alert
+    /// `owner` when the routine starts failing repeatedly or recovers.
+    pub async fn finish(
+        &self,
+        owner: &str,
+        mut run: RoutineRun,
+        outcome: RunOutcome,
+        usage: &[LlmUsage],
+    ) {
+        complete_run(&mut run, &outcome, usage, Utc::now());
+        if let Err(e) = self.store.complete_routine_run(&run).await {
+            tracing::warn!("Failed to record outcome of routine run {}: {}", run.id, e);
+        }
+
+        // Re-read so edits made while the run was going aren't overwritten,
+        // and again if one lands between the read and the write
+        let mut attempts = 0;
+        let (routine, previous_failures) = loop {
+            let mut routine = match self.store.get_routine(run.routine_id).await {
+                Ok(Some(routine)) => routine,
+                Ok(None) => return,
+                Err(e) => {
+                    tracing::warn!("Failed to load routine {}: {}", run.routine_id, e);
+                    return;
+                }
+            };
+            let previous_failures = routine.consecutive_failures;
+            routine.last_run_at = Some(run.started_at);
+            routine.run_count += 1;
+            routine.consecutive_failures = match outcome {
+                RunOutcome::Completed { .. } => 0,
+                RunOutcome::Failed(_) => previous_failures + 1,
+            };
+            match self.store.update_routine(&routine).await {
+                Err(DatabaseError::Conflict { .. }) if attempts < MAX_UPDATE_ATTEMPTS => {
+                    attempts += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to update routine '{}': {}", routine.name, e);
+                    break (routine, previous_failures);
+                }
+                Ok(_) => break (routine, previous_failures),
+            }
+        };
+
+        let alert = match outcome {
+            RunOutcome::Failed(ref error) if routine.consecutive_failures == self.alert_after => {
+                Some(format!(
+                    "⚠️ *Routine failing*\n\nRoutine '{}' has failed {} times in a row.\nLast error: {}",
+                    routine.name, routine.consecutive_failures, error
+                ))
+            }
+            RunOutcome::Completed { .. } if previous_failures >= self.alert_after => Some(format!(
+                "✅ *Routine recovered*\n\nRoutine '{}' succeeded after {} failed runs.",
+                routine.name, previous_failures
+            )),
+            _ => None,
+        };
+        if let (Some(content), Some(notifier)) = (alert, &self.notifier) {
+            let target = NotifyTarget::from_json(&routine.notify)
+                .or(&NotifyTarget::new(None, Some(owner.to_string())));
+            let response = OutgoingResponse {
+                content,
+                thread_id: None,
+                metadata: serde_json::json!({
+                    "source": "routine",
+                    "routine_id": routine.id,
+                }),
+                attachments: Vec::new(),
+            };
+            notifier.notify(target, response).await;
+        }
+    }
+
+    /// Status of the user's routines and their latest runs, for `/routines`.
+    pub async fn status_summary(&self, user_id: &str) -> Result<String, DatabaseError> {
+        let routines = self.store.list_routines(user_id).await?;
+        let mut entries = Vec::with_capacity(routines.len());
+        for routine in routines {
+            let last_run = self
+                .store
+                .list_routine_runs(routine.id, 1)
+                .await?
+                .into_iter()
+                .next();
+            entries.push((routine, last_run));
+        }
+        Ok(format_status(&entries, Utc::now()))
+    }
+}
+
+/// Fill in a run's status, summary and usage from how it ended.
+fn complete_run(
+    run: &mut RoutineRun,
+    outcome: &RunOutcome,
+    usage: &[LlmUsage],
+    now: DateTime<Utc>,
+) {
+    run.completed_at = Some(now);
+    let (status, summary) = match outcome {
+        RunOutcome::Completed {
+            summary,
+            messages_sent,
+        } => {
+            run.messages_sent = *messages_sent;
+            (RoutineRunStatus::Completed, summary.clone())
+        }
+        RunOutcome::Failed(error) => (RoutineRunStatus::Failed, Some(error.clone())),
+    };
+    run.status = status;
+    run.result_summary = summary.map(|s| s.chars().take(MAX_SUMMARY_CHARS).collect());
+
+    if !usage.is_empty() {
+        let tokens: u64 = usage
+            .iter()
+            .map(|u| u64::from(u.input_tokens) + u64::from(u.output_tokens))
+            .sum();
+        run.tokens_used = Some(i32::try_from(tokens).unwrap_or(i32::MAX));
+        run.cost = Some(usage.iter().map(|u| u.cost).sum::<Decimal>());
+    }
+}
+
+/// How much to report under `routine`'s results: the `report` in its notify
+/// settings, or `default`.
+pub fn report_level(routine: &Routine, default: RoutineReport) -> RoutineReport {
+    routine
+        .notify
+        .get("report")
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Footer for a run's result summarizing what it did, or `None` when
+/// reports are off.
+pub fn format_report(
+    level: RoutineReport,
+    activity: &Activity,
+    usage: &[LlmUsage],
+    elapsed: chrono::Duration,
+) -> Option<String> {
+    let calls = u32::try_from(activity.tool_calls.len()).unwrap_or(u32::MAX);
+    let input: u64 = usage.iter().map(|u| u64::from(u.input_tokens)).sum();
+    let output: u64 = usage.iter().map(|u| u64::from(u.output_tokens)).sum();
+    let cost = usage.iter().map(|u| u.cost).sum::<Decimal>();
+    let duration = format_elapsed(elapsed);
+
+    match level {
+        RoutineReport::Off => None,
+        RoutineReport::Brief => {
+            let mut parts = vec![format!("{} tool call{}", calls, plural(calls))];
+            if activity.api_calls > 0 {
+                parts.push(format!(
+                    "{} API call{}",
+                    activity.api_calls,
+                    plural(activity.api_calls)
+                ));
+            }
+            if !usage.is_empty() {
+                parts.push(format!("{} tokens", input + output));
+                parts.push(format!("${:.4}", cost));
+            }
+            parts.push(duration);
+            Some(format!("_Run: {}_", parts.join(" · ")))
+        }
+        RoutineReport::Detailed => {
+            // Tools in first-use order, with call and failure counts
+            let mut tools: Vec<(&str, u32, u32)> = Vec::new();
+            for (name, success) in &activity.tool_calls {
+                let i = match tools.iter().position(|(n, _, _)| n == name) {
+                    Some(i) => i,
+                    None => {
+                        tools.push((name.as_str(), 0, 0));
+                        tools.len() - 1
+                    }
+                };
+                tools[i].1 += 1;
+                if !success {
+                    tools[i].2 += 1;
+                }
+            }
+            let tools = if tools.is_empty() {
+                "none".to_string()
+            } else {
+                tools
+                    .iter()
+                    .map(|(name, count, failed)| {
+                        let mut entry = name.to_string();
+                        if *count > 1 {
+                            entry.push_str(&format!(" ×{}", count));
+                        }
+                        if *failed > 0 {
+                            entry.push_str(&format!(" ({} failed)", failed));
+                        }
+                        entry
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+
+            let mut out = format!("_Run report_\n  Tools: {}", tools);
+            out.push_str(&format!("\n  API calls: {}", activity.api_calls));
+            if !usage.is_empty() {
+                out.push_str(&format!("\n  Tokens: {} in, {} out", input, output));
+                out.push_str(&format!("\n  Cost: ${:.4}", cost));
+            }
+            out.push_str(&format!("\n  Duration: {}", duration));
+            Some(out)
+        }
+    }
+}
+
+/// Render routines and their latest runs as a status list.
+fn format_status(entries: &[(Routine, Option<RoutineRun>)], now: DateTime<Utc>) -> String {
+    if entries.is_empty() {
+        return "No routines yet.".to_string();
+    }
+
+    let mut out = format!("Routines ({}):\n", entries.len());
+    for (routine, last_run) in entries {
+        let marker = if !routine.enabled {
+            "-"
+        } else if routine.consecutive_failures > 0 {
+            "✗"
+        } else {
+            "✓"
+        };
+        out.push_str(&format!(
+            "  {} {} ({})",
+            marker,
+            routine.name,
+            routine.trigger.kind()
+        ));
+        if !routine.enabled {
+            out.push_str(", disabled");
+        }
+        if routine.consecutive_failures > 0 {
+            out.push_str(&format!(
+                ", {} failure{} in a row",
+                routine.consecutive_failures,
+                plural(routine.consecutive_failures)
+            ));
+        }
+
+        match last_run {
+            None => out.push_str(" - never run\n"),
+            Some(run) => {
+                out.push_str(&format!(
+                    " - last run {}: {}",
+                    time_ago(run.started_at, now),
+                    run.status
+                ));
+                let mut details = Vec::new();
+                if let Some(cost) = run.cost {
+                    details.push(format!("${:.4}", cost));
+                }
+                if run.messages_sent > 0 {
+                    details.push(format!(
+                        "{} message{}",
+                        run.messages_sent,
+                        plural(run.messages_sent)
+                    ));
+                }
+                if !details.is_empty() {
+                    out.push_str(&format!(" ({})", details.join(", ")));
+                }
+                if run.status == RoutineRunStatus::Failed
+                    && let Some(ref error) = run.result_summary
+                {
+                    out.push_str(&format!("\n      {}", error));
+                }
+                out.push('\n');
+            }
+        }
+    }
+    out.trim_end().to_string()
+}
+
+fn plural(n: u32) -> &'static str {
+    if n == 1 { "" } else { "s" }
+}
+
+/// Coarse "how long ago" for status output.
+fn time_ago(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - then).num_seconds().max(0);
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3_600 => format!("{}m ago", secs / 60),
+        3_600..86_400 => format!("{}h ago", secs / 3_600),
+        _ => format!("{}d ago", secs / 86_400),
+    }
+}
+
+/// Run time for reports, e.g. "42s", "3m 5s" or "1h 2m".
+fn format_elapsed(elapsed: chrono::Duration) -> String {
+    let secs = elapsed.num_seconds().max(0);
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3_600 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3_600, secs % 3_600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn routine(name: &str, consecutive_failures: u32) -> Routine {
+        let now = Utc::now();
+        Routine {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: String::new(),
+            enabled: true,
+            trigger: Trigger::Cron {
+                schedule: "0 0 * * * *".to_string(),
+            },
+            action: RoutineAction::Lightweight {
+                prompt: "check".to_string(),
+            },
+            guardrails: serde_json::json!({}),
+            notify: serde_json::json!({}),
+            last_run_at: None,
+            next_fire_at: None,
+            run_count: 0,
+            consecutive_failures,
+            created_at: now,
+            updated_at: now,
+            version: 1,
+        }
+    }
+
+    fn usage(input_tokens: u32, output_tokens: u32, cost: Decimal) -> LlmUsage {
+        LlmUsage {
+            provider: "test",
+            model: "m".to_string(),
+            input_tokens,
+            output_tokens,
+            cost,
+        }
+    }
+
+    #[test]
+    fn test_complete_run() {
+        let now = Utc::now();
+        let mut run = RoutineRun::start(Uuid::new_v4(), "cron");
+        let outcome = RunOutcome::Completed {
+            summary: Some("x".repeat(MAX_SUMMARY_CHARS + 10)),
+            messages_sent: 1,
+        };
+        let calls = [
+            usage(100, 20, Decimal::new(5, 3)),
+            usage(50, 10, Decimal::new(2, 3)),
+        ];
+        complete_run(&mut run, &outcome, &calls, now);
+        assert_eq!(run.status, RoutineRunStatus::Completed);
+        assert_eq!(run.completed_at, Some(now));
+        assert_eq!(run.tokens_used, Some(180));
+        assert_eq!(run.cost, Some(Decimal::new(7, 3)));
+        assert_eq!(run.messages_sent, 1);
+        assert_eq!(run.result_summary.unwrap().len(), MAX_SUMMARY_CHARS);
+
+        let mut run = RoutineRun::start(Uuid::new_v4(), "manual");
+        complete_run(&mut run, &RunOutcome::Failed("boom".to_string()), &[], now);
+        assert_eq!(run.status, RoutineRunStatus::Failed);
+        assert_eq!(run.result_summary.as_deref(), Some("boom"));
+        assert_eq!(run.cost, None);
+    }
+
+    #[test]
+    fn test_format_status() {
+        let now = Utc::now();
+        assert_eq!(format_status(&[], now), "No routines yet.");
+
+        let healthy = routine("rss", 0);
+        let mut ok_run = RoutineRun::start(healthy.id, "cron");
+        ok_run.started_at = now - chrono::Duration::hours(2);
+        complete_run(
+            &mut ok_run,
+            &RunOutcome::Completed {
+                summary: None,
+                messages_sent: 1,
+            },
+            &[usage(10, 10, Decimal::new(123, 4))],
+            now,
+        );
+
+        let failing = routine("digest", 3);
+        let mut failed_run = RoutineRun::start(failing.id, "cron");
+        failed_run.started_at = now - chrono::Duration::minutes(5);
+        complete_run(
+            &mut failed_run,
+            &RunOutcome::Failed("LLM call failed".to_string()),
+            &[],
+            now,
+        );
+
+        let mut idle = routine("weekly", 0);
+        idle.enabled = false;
+
+        let status = format_status(
+            &[
+                (healthy, Some(ok_run)),
+                (failing, Some(failed_run)),
+                (idle, None),
+            ],
+            now,
+        );
+        assert_eq!(
+            status,
+            "Routines (3):\n  \
+             ✓ rss (cron) - last run 2h ago: completed ($0.0123, 1 message)\n  \
+             ✗ digest (cron), 3 failures in a row - last run 5m ago: failed\n      LLM call failed\n  \
+             - weekly (cron), disabled - never run"
+        );
+    }
+
+    #[test]
+    fn test_format_report() {
+        let activity = Activity {
+            tool_calls: vec![
+                ("google_docs".to_string(), true),
+                ("http".to_string(), false),
+                ("google_docs".to_string(), true),
+            ],
+            api_calls: 4,
+        };
+        let calls = [
+            usage(100, 20, Decimal::new(5, 3)),
+            usage(50, 10, Decimal::new(2, 3)),
+        ];
+        let elapsed = chrono::Duration::seconds(185);
+
+        assert_eq!(
+            format_report(RoutineReport::Off, &activity, &calls, elapsed),
+            None
+        );
+        assert_eq!(
+            format_report(RoutineReport::Brief, &activity, &calls, elapsed).unwrap(),
+            "_Run: 3 tool calls · 4 API calls · 180 tokens · $0.0070 · 3m 5s_"
+        );
+        assert_eq!(
+            format_report(RoutineReport::Detailed, &activity, &calls, elapsed).unwrap(),
+            "_Run report_\n  \
+             Tools: google_docs ×2, http (1 failed)\n  \
+             API calls: 4\n  \
+             Tokens: 150 in, 30 out\n  \
+             Cost: $0.0070\n  \
+             Duration: 3m 5s"
+        );
+
+        let idle = format_report(
+            RoutineReport::Brief,
+            &Activity::default(),
+            &[],
+            chrono::Duration::seconds(2),
+        );
+        assert_eq!(idle.unwrap(), "_Run: 0 tool calls · 2s_");
+    }
+
+    #[test]
+    fn test_report_level() {
+        let mut r = routine("digest", 0);
+        assert_eq!(report_level(&r, RoutineReport::Brief), RoutineReport::Brief);
+        r.notify = serde_json::json!({"channel": "telegram", "report": "detailed"});
+        assert_eq!(
+            report_level(&r, RoutineReport::Off),
+            RoutineReport::Detailed
+        );
+        r.notify = serde_json::json!({"report": "loud"});
+        assert_eq!(report_level(&r, RoutineReport::Off), RoutineReport::Off);
+    }
+}
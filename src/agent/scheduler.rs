@@ -8,11 +8,15 @@ use tokio::sync::{RwLock, mpsc, oneshot};
 use tokio::task::JoinHandle;
 use uuid::Uuid;
 
+use crate::agent::idempotency::IdempotencyLedger;
+use crate::agent::pause::PauseSwitch;
+use crate::agent::priority::PriorityGate;
 use crate::agent::task::{Task, TaskContext, TaskOutput};
 use crate::agent::worker::{Worker, WorkerDeps};
 use crate::config::AgentConfig;
 use crate::context::{ContextManager, JobContext, JobState};
 use crate::error::{Error, JobError};
+use crate::estimation::Estimator;
 use crate::history::Store;
 use crate::llm::LlmProvider;
 use crate::safety::SafetyLayer;
@@ -49,6 +53,10 @@ pub struct Scheduler {
     safety: Arc<SafetyLayer>,
     tools: Arc<ToolRegistry>,
     store: Option<Arc<Store>>,
+    priority: Arc<PriorityGate>,
+    pause: Arc<PauseSwitch>,
+    idempotency: Arc<IdempotencyLedger>,
+    estimator: Arc<Estimator>,
     /// Running jobs (main LLM-driven jobs).
     jobs: Arc<RwLock<HashMap<Uuid, ScheduledJob>>>,
     /// Running sub-tasks (tool executions, background tasks).
@@ -64,6 +72,8 @@ impl Scheduler {
         safety: Arc<SafetyLayer>,
         tools: Arc<ToolRegistry>,
         store: Option<Arc<Store>>,
+        priority: Arc<PriorityGate>,
+        pause: Arc<PauseSwitch>,
     ) -> Self {
         Self {
             config,
@@ -71,7 +81,11 @@ impl Scheduler {
             llm,
             safety,
             tools,
+            idempotency: Arc::new(IdempotencyLedger::new(store.clone())),
+            estimator: Arc::new(Estimator::new()),
             store,
+            priority,
+            pause,
             jobs: Arc::new(RwLock::new(HashMap::new())),
             subtasks: Arc::new(RwLock::new(HashMap::new())),
         }
@@ -118,12 +132,17 @@ impl Scheduler {
             store: self.store.clone(),
             timeout: self.config.job_timeout,
             use_planning: self.config.use_planning,
+            priority: self.priority.clone(),
+            pause: self.pause.clone(),
+            idempotency: self.idempotency.clone(),
+            estimator: self.estimator.clone(),
         };
         let worker = Worker::new(job_id, deps);
 
-        // Spawn worker task
+        // Spawn worker task, still acting for the user who scheduled it
+        let tenant = crate::tenancy::current();
         let handle = tokio::spawn(async move {
-            if let Err(e) = worker.run(rx).await {
+            if let Err(e) = crate::tenancy::inherit(tenant, worker.run(rx)).await {
                 tracing::error!("Worker for job {} failed: {}", job_id, e);
             }
         });
@@ -193,15 +212,21 @@ impl Scheduler {
                 let tools = self.tools.clone();
                 let context_manager = self.context_manager.clone();
                 let safety = self.safety.clone();
+                let pause = self.pause.clone();
+                let tenant = crate::tenancy::current();
 
                 tokio::spawn(async move {
-                    let result = Self::execute_tool_task(
-                        tools,
-                        context_manager,
-                        safety,
-                        tool_parent_id,
-                        &tool_name,
-                        params,
+                    let result = crate::tenancy::inherit(
+                        tenant,
+                        Self::execute_tool_task(
+                            tools,
+                            context_manager,
+                            safety,
+                            pause,
+                            tool_parent_id,
+                            &tool_name,
+                            params,
+                        ),
                     )
                     .await;
 
@@ -327,6 +352,7 @@ impl Scheduler {
         tools: Arc<ToolRegistry>,
         context_manager: Arc<ContextManager>,
         safety: Arc<SafetyLayer>,
+        pause: Arc<PauseSwitch>,
         job_id: Uuid,
         tool_name: &str,
         params: serde_json::Value,
@@ -357,6 +383,12 @@ impl Scheduler {
             .into());
         }
 
+        // Members can't run admin-only tools
+        crate::tenancy::check_tool_access(tool_name)?;
+
+        // Park here while the job's user is paused
+        pause.wait_until_resumed(&job_ctx.user_id).await;
+
         // Validate tool parameters
         let validation = safety.validator().validate_tool_params(&params);
         if !validation.is_valid {
@@ -375,7 +407,8 @@ impl Scheduler {
 
         // Execute with timeout
         let result = tokio::time::timeout(Duration::from_secs(60), async {
-            tool.execute(params, &job_ctx).await
+            crate::observability::instrument_tool(tool_name, tool.execute(params.clone(), &job_ctx))
+                .await
         })
         .await
         .map_err(|_| {
@@ -391,6 +424,10 @@ impl Scheduler {
             })
         })?;
 
+        context_manager
+            .record_tool_artifact(&job_ctx, tool_name, &params, &result.result)
+            .await;
+
         Ok(TaskOutput::new(result.result, start.elapsed()))
     }
 
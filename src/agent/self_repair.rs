@@ -7,8 +7,11 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+use crate::agent::routine_monitor::{RoutineMonitor, RunOutcome};
+use crate::channels::{NotificationRouter, NotifyTarget, OutgoingResponse};
 use crate::context::{ContextManager, JobState};
 use crate::error::RepairError;
+use crate::evaluation::format_failure_trends;
 use crate::history::Store;
 use crate::tools::{BuildRequirement, Language, SoftwareBuilder, SoftwareType, ToolRegistry};
 
@@ -22,11 +25,164 @@ pub struct StuckJob {
     pub repair_attempts: u32,
 }
 
+/// Category of a recorded tool failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FailureKind {
+    /// The tool ran and returned an error that fits no other category.
+    Error,
+    /// The tool didn't finish within its time limit.
+    Timeout,
+    /// Credentials were missing, expired, or lacked a permission.
+    Auth,
+    /// The upstream service throttled the call.
+    RateLimit,
+    /// The call's parameters were rejected, by the tool or upstream.
+    Validation,
+    /// The upstream service failed (5xx or unreachable).
+    Upstream,
+    /// The sandbox refused or stopped the tool (fuel, memory, capability).
+    Sandbox,
+}
+
+impl FailureKind {
+    /// Every category, in report order.
+    pub const ALL: [Self; 7] = [
+        Self::Error,
+        Self::Timeout,
+        Self::Auth,
+        Self::RateLimit,
+        Self::Validation,
+        Self::Upstream,
+        Self::Sandbox,
+    ];
+
+    /// Classify a tool execution error.
+    pub fn of(error: &crate::error::Error) -> Self {
+        use crate::error::{Error, ToolError};
+        match error {
+            Error::Tool(ToolError::Timeout { .. }) => Self::Timeout,
+            Error::Tool(ToolError::AuthRequired { .. }) => Self::Auth,
+            Error::Tool(ToolError::InvalidParameters { .. }) => Self::Validation,
+            Error::Tool(ToolError::Sandbox { .. }) => Self::Sandbox,
+            Error::Tool(ToolError::ExecutionFailed { reason, .. }) => Self::of_message(reason),
+            other => Self::of_message(&other.to_string()),
+        }
+    }
+
+    /// Classify a tool's error message: the structured errors of the Google
+    /// tools by their `http_status`, anything else by its wording.
+    pub fn of_message(message: &str) -> Self {
+        let status = serde_json::from_str::<serde_json::Value>(message.trim())
+            .ok()
+            .and_then(|v| v["http_status"].as_u64())
+            .or_else(|| http_status(message));
+        match status {
+            Some(401 | 403) => return Self::Auth,
+            Some(429) => return Self::RateLimit,
+            Some(408) => return Self::Timeout,
+            Some(400 | 404 | 409 | 412 | 422) => return Self::Validation,
+            Some(500..=599) => return Self::Upstream,
+            _ => {}
+        }
+
+        let message = message.to_lowercase();
+        let mentions = |words: &[&str]| words.iter().any(|w| message.contains(w));
+        if mentions(&[
+            "unauthorized",
+            "forbidden",
+            "invalid_grant",
+            "token expired",
+            "not authenticated",
+            "permission denied",
+        ]) {
+            Self::Auth
+        } else if mentions(&[
+            "rate limit",
+            "ratelimit",
+            "too many requests",
+            "quota exceeded",
+        ]) {
+            Self::RateLimit
+        } else if mentions(&["timed out", "timeout", "deadline exceeded"]) {
+            Self::Timeout
+        } else if mentions(&[
+            "fuel",
+            "memory limit",
+            "sandbox",
+            "not allowed by capabilities",
+            "not in allowlist",
+        ]) {
+            Self::Sandbox
+        } else if mentions(&[
+            "service unavailable",
+            "bad gateway",
+            "internal server error",
+            "connection refused",
+            "connection reset",
+            "upstream",
+        ]) {
+            Self::Upstream
+        } else if mentions(&[
+            "invalid parameters",
+            "invalid argument",
+            "missing field",
+            "unknown variant",
+            "bad request",
+        ]) {
+            Self::Validation
+        } else {
+            Self::Error
+        }
+    }
+
+    /// Name stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Timeout => "timeout",
+            Self::Auth => "auth",
+            Self::RateLimit => "rate_limit",
+            Self::Validation => "validation",
+            Self::Upstream => "upstream",
+            Self::Sandbox => "sandbox",
+        }
+    }
+
+    /// Parse a stored name, treating unknown values as errors.
+    pub fn parse(s: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .unwrap_or(Self::Error)
+    }
+
+    /// Whether rebuilding the tool could fix failures of this kind. Auth,
+    /// rate-limit and upstream failures are outside the tool's code.
+    pub fn needs_code_fix(&self) -> bool {
+        !matches!(self, Self::Auth | Self::RateLimit | Self::Upstream)
+    }
+}
+
+/// HTTP status quoted in an error message ("status 503", "HTTP 429").
+fn http_status(message: &str) -> Option<u64> {
+    let lower = message.to_lowercase();
+    ["status ", "status: ", "http ", "status code "]
+        .iter()
+        .find_map(|prefix| {
+            let rest = &lower[lower.find(prefix)? + prefix.len()..];
+            let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+            (digits.len() == 3).then(|| digits.parse().ok()).flatten()
+        })
+}
+
 /// A tool that has been detected as broken.
 #[derive(Debug, Clone)]
 pub struct BrokenTool {
     pub name: String,
     pub failure_count: u32,
+    /// How many of the failures were timeouts.
+    pub timeout_count: u32,
+    pub last_failure_kind: Option<FailureKind>,
     pub last_error: Option<String>,
     pub first_failure: DateTime<Utc>,
     pub last_failure: DateTime<Utc>,
@@ -189,7 +345,9 @@ impl SelfRepair for DefaultSelfRepair {
 
         // Threshold: 5 failures before considering a tool broken
         match store.get_broken_tools(5).await {
-            Ok(tools) => {
+            Ok(mut tools) => {
+                // Tools a rebuild could fix go first
+                tools.sort_by_key(|t| !t.last_failure_kind.is_none_or(|k| k.needs_code_fix()));
                 if !tools.is_empty() {
                     tracing::info!("Detected {} broken tools needing repair", tools.len());
                 }
@@ -203,6 +361,27 @@ impl SelfRepair for DefaultSelfRepair {
     }
 
     async fn repair_broken_tool(&self, tool: &BrokenTool) -> Result<RepairResult, RepairError> {
+        match tool.last_failure_kind {
+            Some(FailureKind::Auth) => {
+                return Ok(RepairResult::ManualRequired {
+                    message: format!(
+                        "Tool '{}' is failing authentication; its credentials need renewing",
+                        tool.name
+                    ),
+                });
+            }
+            Some(kind) if !kind.needs_code_fix() => {
+                return Ok(RepairResult::Retry {
+                    message: format!(
+                        "Tool '{}' is failing upstream ({}); not rebuilding",
+                        tool.name,
+                        kind.as_str()
+                    ),
+                });
+            }
+            _ => {}
+        }
+
         let Some(ref builder) = self.builder else {
             return Ok(RepairResult::ManualRequired {
                 message: format!("Builder not available for repairing tool '{}'", tool.name),
@@ -239,16 +418,7 @@ impl SelfRepair for DefaultSelfRepair {
         // Create BuildRequirement for repair
         let requirement = BuildRequirement {
             name: tool.name.clone(),
-            description: format!(
-                "Repair broken WASM tool.\n\n\
-                 Tool name: {}\n\
-                 Previous error: {}\n\
-                 Failure count: {}\n\n\
-                 Analyze the error, fix the implementation, and rebuild.",
-                tool.name,
-                tool.last_error.as_deref().unwrap_or("Unknown error"),
-                tool.failure_count
-            ),
+            description: repair_description(tool),
             software_type: SoftwareType::WasmTool,
             language: Language::Rust,
             input_spec: None,
@@ -309,6 +479,91 @@ impl SelfRepair for DefaultSelfRepair {
     }
 }
 
+/// Describe what a repair build needs to fix.
+fn repair_description(tool: &BrokenTool) -> String {
+    let mut description = format!(
+        "Repair broken WASM tool.\n\n\
+         Tool name: {}\n\
+         Previous error: {}\n\
+         Failure count: {} ({} timeouts)\n\n",
+        tool.name,
+        tool.last_error.as_deref().unwrap_or("Unknown error"),
+        tool.failure_count,
+        tool.timeout_count
+    );
+
+    if tool.last_failure_kind == Some(FailureKind::Timeout) {
+        description.push_str(
+            "The tool is being interrupted for running past its time limit. Look for \
+             unbounded loops, retries without a limit, or blocking waits, and make sure \
+             every path returns promptly.",
+        );
+    } else {
+        description.push_str("Analyze the error, fix the implementation, and rebuild.");
+    }
+
+    description
+}
+
+/// Built-in routine the tool failure report is recorded under.
+pub const FAILURE_REPORT_ROUTINE: &str = "tool-failure-report";
+
+/// Most tool and category pairs read for a failure report.
+const FAILURE_REPORT_ROWS: i64 = 100;
+
+/// Send `owner` the tool failure trends of the last `period` against the
+/// period before, unless nothing failed, and record it as a run of the
+/// built-in report routine.
+pub async fn send_failure_report(
+    store: &Store,
+    notifier: &NotificationRouter,
+    monitor: Option<&RoutineMonitor>,
+    owner: &str,
+    period: Duration,
+) {
+    let started_at = Utc::now();
+    let window = chrono::Duration::from_std(period).unwrap_or_else(|_| chrono::Duration::days(7));
+    let outcome = match store
+        .tool_failure_trends(started_at, window, FAILURE_REPORT_ROWS)
+        .await
+    {
+        Ok(trends) => {
+            let report = format_failure_trends(&trends, window.num_days().max(1));
+            let quiet = trends.iter().all(|t| t.current == 0);
+            if !quiet {
+                let response = OutgoingResponse {
+                    content: format!("📉 *Tool failure report*\n\n{}", report),
+                    thread_id: None,
+                    metadata: serde_json::json!({ "source": "self_repair" }),
+                    attachments: Vec::new(),
+                };
+                notifier.notify(NotifyTarget::default(), response).await;
+            }
+            RunOutcome::Completed {
+                summary: Some(report),
+                messages_sent: u32::from(!quiet),
+            }
+        }
+        Err(e) => {
+            tracing::warn!("Failed to load tool failure trends: {}", e);
+            RunOutcome::Failed(e.to_string())
+        }
+    };
+
+    let Some(monitor) = monitor else {
+        return;
+    };
+    let description = "Periodic report of tool failure trends by category";
+    if let Some(routine) = monitor
+        .builtin(owner, FAILURE_REPORT_ROUTINE, description)
+        .await
+    {
+        monitor
+            .record(owner, &routine, "cron", started_at, outcome, &[])
+            .await;
+    }
+}
+
 /// Background repair task that periodically checks for and repairs issues.
 pub struct RepairTask {
     repair: Arc<dyn SelfRepair>,
@@ -385,4 +640,66 @@ mod tests {
         };
         assert!(matches!(manual, RepairResult::ManualRequired { .. }));
     }
+
+    #[test]
+    fn test_failure_kind_classifies_timeouts() {
+        let timeout = crate::error::Error::Tool(crate::error::ToolError::Timeout {
+            name: "slow".to_string(),
+            timeout: Duration::from_secs(60),
+        });
+        assert_eq!(FailureKind::of(&timeout), FailureKind::Timeout);
+
+        let failed = crate::error::Error::Tool(crate::error::ToolError::ExecutionFailed {
+            name: "broken".to_string(),
+            reason: "boom".to_string(),
+        });
+        assert_eq!(FailureKind::of(&failed), FailureKind::Error);
+
+        for kind in FailureKind::ALL {
+            assert_eq!(FailureKind::parse(kind.as_str()), kind);
+        }
+        assert_eq!(FailureKind::parse("something-new"), FailureKind::Error);
+    }
+
+    #[test]
+    fn test_failure_kind_classifies_tool_errors() {
+        use crate::error::{Error, ToolError};
+
+        let auth = Error::Tool(ToolError::AuthRequired {
+            name: "gmail".to_string(),
+        });
+        assert_eq!(FailureKind::of(&auth), FailureKind::Auth);
+
+        let google = r#"{"api": "Google Drive", "message": "Quota exceeded", "http_status": 429}"#;
+        let failed = |reason: &str| {
+            Error::Tool(ToolError::ExecutionFailed {
+                name: "google-drive".to_string(),
+                reason: reason.to_string(),
+            })
+        };
+        assert_eq!(FailureKind::of(&failed(google)), FailureKind::RateLimit);
+        assert_eq!(
+            FailureKind::of(&failed("GitHub API returned status 502")),
+            FailureKind::Upstream
+        );
+        assert_eq!(
+            FailureKind::of(&failed("Invalid parameters: missing field `query`")),
+            FailureKind::Validation
+        );
+        assert_eq!(
+            FailureKind::of(&failed("wasm trap: all fuel consumed")),
+            FailureKind::Sandbox
+        );
+        assert_eq!(
+            FailureKind::of(&failed("401 Unauthorized")),
+            FailureKind::Auth
+        );
+        assert_eq!(
+            FailureKind::of(&failed("list index out of range")),
+            FailureKind::Error
+        );
+
+        assert!(FailureKind::Timeout.needs_code_fix());
+        assert!(!FailureKind::Upstream.needs_code_fix());
+    }
 }
@@ -398,6 +398,10 @@ pub struct Turn {
     pub completed_at: Option<DateTime<Utc>>,
     /// Error message (if failed).
     pub error: Option<String>,
+    /// Every tool call in this turn needs explicit approval, because a
+    /// content classifier flagged the user input.
+    #[serde(default)]
+    pub approval_required: bool,
 }
 
 impl Turn {
@@ -412,6 +416,7 @@ impl Turn {
             started_at: Utc::now(),
             completed_at: None,
             error: None,
+            approval_required: false,
         }
     }
 
@@ -46,6 +46,56 @@ impl SubmissionParser {
         if lower == "/quit" || lower == "/exit" || lower == "/shutdown" {
             return Submission::Quit;
         }
+        if lower == "/cost" {
+            return Submission::Cost;
+        }
+        if lower == "/redeliver" {
+            return Submission::Redeliver;
+        }
+        if lower == "/routines" {
+            return Submission::Routines;
+        }
+        if lower == "/toolerrors" {
+            return Submission::ToolErrors;
+        }
+
+        // /model [all|routine <name>] [model] - show or pin models
+        // (model names are case-sensitive)
+        if lower == "/model" {
+            return Submission::Model {
+                scope: ModelScope::Conversation,
+                name: None,
+            };
+        }
+        if lower.starts_with("/model ") {
+            return parse_model_command(trimmed["/model ".len()..].trim());
+        }
+
+        // /defer <when> (or /snooze) - bring the last message back later
+        for command in ["/defer", "/snooze"] {
+            if lower == command {
+                return Submission::Defer {
+                    when: String::new(),
+                };
+            }
+            if lower.starts_with(command) && lower[command.len()..].starts_with(' ') {
+                return Submission::Defer {
+                    when: trimmed[command.len()..].trim().to_string(),
+                };
+            }
+        }
+        if let Some(when) = crate::agent::defer::reminder_request(trimmed) {
+            return Submission::Defer {
+                when: when.to_string(),
+            };
+        }
+
+        // /quiet [22:00-07:00 | off | channel ... | urgent ...] - quiet hours
+        if lower == "/quiet" || lower.starts_with("/quiet ") {
+            return Submission::QuietHours {
+                args: trimmed["/quiet".len()..].trim().to_string(),
+            };
+        }
 
         // /thread <uuid> - switch thread
         if let Some(rest) = lower.strip_prefix("/thread ") {
@@ -64,6 +114,18 @@ impl SubmissionParser {
             }
         }
 
+        // /pause [all] and /resume [all] - emergency stop for automation
+        if lower == "/pause" || lower.starts_with("/pause ") {
+            return Submission::Pause {
+                args: trimmed["/pause".len()..].trim().to_string(),
+            };
+        }
+        if lower == "/resume" || lower.starts_with("/resume ") {
+            return Submission::Unpause {
+                args: trimmed["/resume".len()..].trim().to_string(),
+            };
+        }
+
         // Try structured JSON approval (from web gateway's /api/chat/approval endpoint)
         if trimmed.starts_with('{') {
             if let Ok(submission) = serde_json::from_str::<Submission>(trimmed) {
@@ -104,6 +166,40 @@ impl SubmissionParser {
     }
 }
 
+/// Parse the arguments of `/model`.
+fn parse_model_command(args: &str) -> Submission {
+    let mut words = args.split_whitespace();
+    let first = words.next().unwrap_or_default();
+    let rest: Vec<&str> = words.collect();
+    let name = |words: &[&str]| (!words.is_empty()).then(|| words.join(" "));
+
+    match first.to_lowercase().as_str() {
+        "all" => Submission::Model {
+            scope: ModelScope::User,
+            name: name(&rest),
+        },
+        "routine" if !rest.is_empty() => Submission::Model {
+            scope: ModelScope::Routine(rest[0].to_string()),
+            name: name(&rest[1..]),
+        },
+        _ => Submission::Model {
+            scope: ModelScope::Conversation,
+            name: Some(args.to_string()),
+        },
+    }
+}
+
+/// What a `/model` pin applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModelScope {
+    /// The current conversation (thread).
+    Conversation,
+    /// Every conversation of the user without its own pin.
+    User,
+    /// Runs of the named routine.
+    Routine(String),
+}
+
 /// A submission to the agent.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Submission {
@@ -170,6 +266,55 @@ pub enum Submission {
     /// Suggest next steps based on the current thread.
     Suggest,
 
+    /// Show the current model and available models, or pin `name` for
+    /// `scope` ("default" removes the pin).
+    Model {
+        /// What the pin applies to.
+        scope: ModelScope,
+        /// Model to pin. `None` shows the current models (or, for a routine,
+        /// its pin).
+        name: Option<String>,
+    },
+
+    /// Show LLM spend so far today.
+    Cost,
+
+    /// Re-send responses that could not be delivered.
+    Redeliver,
+
+    /// Show routine status and recent runs.
+    Routines,
+
+    /// Bring the thread's last user message back at `when` (e.g. "tomorrow
+    /// at 9", "in 2h").
+    Defer {
+        /// When to be reminded, as the user wrote it.
+        when: String,
+    },
+
+    /// Show the tools and models producing the most malformed tool calls.
+    ToolErrors,
+
+    /// Show or change the user's quiet hours for notifications.
+    QuietHours {
+        /// The command's arguments; empty to show the current setting.
+        args: String,
+    },
+
+    /// Stop launching tool calls, routines, heartbeats and job steps until
+    /// `/resume`.
+    Pause {
+        /// "all" to pause everyone (admin only); empty for the user's own.
+        args: String,
+    },
+
+    /// Lift a `/pause`.
+    Unpause {
+        /// "all" to lift a pause of everyone (admin only); empty for the
+        /// user's own.
+        args: String,
+    },
+
     /// Quit the agent. Bypasses thread-state checks.
     Quit,
 }
@@ -412,6 +557,124 @@ mod tests {
         assert!(matches!(submission, Submission::Suggest));
     }
 
+    #[test]
+    fn test_parser_model_and_cost() {
+        assert!(matches!(
+            SubmissionParser::parse("/model"),
+            Submission::Model { scope: ModelScope::Conversation, name: None }
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/model  Qwen/Qwen3-235B "),
+            Submission::Model { scope: ModelScope::Conversation, name: Some(ref n) }
+                if n == "Qwen/Qwen3-235B"
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/model all gpt-4o-mini"),
+            Submission::Model { scope: ModelScope::User, name: Some(ref n) } if n == "gpt-4o-mini"
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/model routine rss default"),
+            Submission::Model { scope: ModelScope::Routine(ref r), name: Some(ref n) }
+                if r == "rss" && n == "default"
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/model routine rss"),
+            Submission::Model { scope: ModelScope::Routine(ref r), name: None } if r == "rss"
+        ));
+        assert!(matches!(SubmissionParser::parse("/cost"), Submission::Cost));
+        assert!(matches!(
+            SubmissionParser::parse("/redeliver"),
+            Submission::Redeliver
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/routines"),
+            Submission::Routines
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/toolerrors"),
+            Submission::ToolErrors
+        ));
+    }
+
+    #[test]
+    fn test_parser_defer() {
+        assert!(matches!(
+            SubmissionParser::parse("/defer tomorrow at 9"),
+            Submission::Defer { ref when } if when == "tomorrow at 9"
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/snooze in 2h"),
+            Submission::Defer { ref when } if when == "in 2h"
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/defer"),
+            Submission::Defer { ref when } if when.is_empty()
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("Remind me about this in 3 days"),
+            Submission::Defer { ref when } if when == "in 3 days"
+        ));
+
+        // Only a time we understand turns the phrase into a command
+        assert!(matches!(
+            SubmissionParser::parse("remind me about this when the build is green"),
+            Submission::UserInput { .. }
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/deferred"),
+            Submission::UserInput { .. }
+        ));
+    }
+
+    #[test]
+    fn test_parser_quiet_hours() {
+        assert!(matches!(
+            SubmissionParser::parse("/quiet"),
+            Submission::QuietHours { ref args } if args.is_empty()
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/quiet 22:00-07:00 +02:00"),
+            Submission::QuietHours { ref args } if args == "22:00-07:00 +02:00"
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/Quiet channel Pushover off"),
+            Submission::QuietHours { ref args } if args == "channel Pushover off"
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/quietly"),
+            Submission::UserInput { .. }
+        ));
+    }
+
+    #[test]
+    fn test_parser_pause_and_unpause() {
+        assert!(matches!(
+            SubmissionParser::parse("/pause"),
+            Submission::Pause { ref args } if args.is_empty()
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/PAUSE all"),
+            Submission::Pause { ref args } if args == "all"
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/resume"),
+            Submission::Unpause { ref args } if args.is_empty()
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/resume all"),
+            Submission::Unpause { ref args } if args == "all"
+        ));
+        // A checkpoint id still resumes from the checkpoint
+        assert!(matches!(
+            SubmissionParser::parse(&format!("/resume {}", Uuid::new_v4())),
+            Submission::Resume { .. }
+        ));
+        assert!(matches!(
+            SubmissionParser::parse("/paused"),
+            Submission::UserInput { .. }
+        ));
+    }
+
     #[test]
     fn test_parser_invalid_commands_become_user_input() {
         // Invalid UUID should become user input
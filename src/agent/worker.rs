@@ -7,16 +7,23 @@ use futures::future::join_all;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
+use crate::agent::context_monitor::ContextMonitor;
+use crate::agent::idempotency::{self, Decision, IdempotencyLedger};
+use crate::agent::pause::PauseSwitch;
+use crate::agent::priority::{Priority, PriorityGate};
 use crate::agent::scheduler::WorkerMessage;
+use crate::agent::self_repair::FailureKind;
 use crate::agent::task::TaskOutput;
 use crate::context::{ContextManager, JobState};
 use crate::error::Error;
+use crate::estimation::Estimator;
+use crate::evaluation::ArgumentFailure;
 use crate::history::Store;
 use crate::llm::{
     ActionPlan, ChatMessage, LlmProvider, Reasoning, ReasoningContext, RespondResult, ToolSelection,
 };
 use crate::safety::SafetyLayer;
-use crate::tools::ToolRegistry;
+use crate::tools::{ToolError, ToolOutput, ToolRegistry};
 
 /// Shared dependencies for worker execution.
 ///
@@ -31,12 +38,31 @@ pub struct WorkerDeps {
     pub store: Option<Arc<Store>>,
     pub timeout: Duration,
     pub use_planning: bool,
+    /// Jobs run as routine work, yielding to chat turns between steps.
+    pub priority: Arc<PriorityGate>,
+    /// Jobs park between steps while their user is paused.
+    pub pause: Arc<PauseSwitch>,
+    /// Outcomes of mutating tool calls, so retries don't repeat them.
+    pub idempotency: Arc<IdempotencyLedger>,
+    /// Costs candidate plans so the cheaper strategy is picked up front.
+    pub estimator: Arc<Estimator>,
 }
 
+/// Per-attempt tool execution timeout.
+const TOOL_TIMEOUT: Duration = Duration::from_secs(60);
+/// Attempts per tool call when failures are transient.
+const TOOL_ATTEMPTS: u32 = 3;
+/// Delay before the first retry, doubled for each one after.
+const TOOL_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+/// Longest we honor a rate limiter's retry-after.
+const TOOL_MAX_BACKOFF: Duration = Duration::from_secs(10);
+
 /// Worker that executes a single job.
 pub struct Worker {
     job_id: Uuid,
     deps: WorkerDeps,
+    /// Token budgeting for each LLM call, using the provider's tokenizer.
+    context_monitor: ContextMonitor,
 }
 
 /// Result of a tool execution with metadata for context building.
@@ -47,7 +73,12 @@ struct ToolExecResult {
 impl Worker {
     /// Create a new worker for a specific job.
     pub fn new(job_id: Uuid, deps: WorkerDeps) -> Self {
-        Self { job_id, deps }
+        let context_monitor = ContextMonitor::new().with_tokenizer(deps.llm.tokenizer());
+        Self {
+            job_id,
+            deps,
+            context_monitor,
+        }
     }
 
     // Convenience accessors to avoid deps.field everywhere
@@ -79,6 +110,36 @@ impl Worker {
         self.deps.use_planning
     }
 
+    /// The view of `reason_ctx` sent to the LLM, trimmed to the token budget.
+    ///
+    /// The full history stays in `reason_ctx`; only the copy for this call is
+    /// trimmed, so later calls can still use messages that fit again.
+    fn budgeted(&self, reason_ctx: &ReasoningContext) -> ReasoningContext {
+        let breakdown = self
+            .context_monitor
+            .breakdown(&reason_ctx.messages, &reason_ctx.available_tools);
+        tracing::debug!(
+            "Job {} context: {} tokens (system {}, memory {}, tool schemas {}, tool outputs {}, history {})",
+            self.job_id,
+            breakdown.total_tokens,
+            breakdown.system_tokens,
+            breakdown.memory_tokens,
+            breakdown.tool_schema_tokens,
+            breakdown.tool_output_tokens,
+            breakdown.history_tokens
+        );
+
+        ReasoningContext {
+            messages: self
+                .context_monitor
+                .fit_messages(&reason_ctx.messages, &reason_ctx.available_tools),
+            available_tools: reason_ctx.available_tools.clone(),
+            job_description: reason_ctx.job_description.clone(),
+            current_state: reason_ctx.current_state.clone(),
+            cache_id: reason_ctx.cache_id.clone(),
+        }
+    }
+
     /// Fire-and-forget persistence of job status.
     fn persist_status(&self, status: JobState, reason: Option<String>) {
         if let Some(store) = self.store() {
@@ -96,6 +157,7 @@ impl Worker {
     }
 
     /// Run the worker until the job is complete or stopped.
+    #[tracing::instrument(name = "worker.job", skip_all, fields(job_id = %self.job_id))]
     pub async fn run(self, mut rx: mpsc::Receiver<WorkerMessage>) -> Result<(), Error> {
         tracing::info!("Worker starting for job {}", self.job_id);
 
@@ -113,7 +175,8 @@ impl Worker {
         let job_ctx = self.context_manager().get_context(self.job_id).await?;
 
         // Create reasoning engine
-        let reasoning = Reasoning::new(self.llm().clone(), self.safety().clone());
+        let reasoning = Reasoning::new(self.llm().clone(), self.safety().clone())
+            .with_estimator(self.deps.estimator.clone());
 
         // Build initial reasoning context (tool definitions refreshed each iteration in execution_loop)
         let mut reason_ctx = ReasoningContext::new().with_job(&job_ctx.description);
@@ -138,6 +201,17 @@ Report when the job is complete or if you encounter issues you cannot resolve."#
         })
         .await;
 
+        // Keep the transcript with the job, for job bundles
+        let messages = std::mem::take(&mut reason_ctx.messages);
+        let _ = self
+            .context_manager()
+            .update_memory(self.job_id, |mem| {
+                for message in messages {
+                    mem.add_message(message);
+                }
+            })
+            .await;
+
         match result {
             Ok(Ok(())) => {
                 tracing::info!("Worker for job {} completed successfully", self.job_id);
@@ -169,7 +243,7 @@ Report when the job is complete or if you encounter issues you cannot resolve."#
 
         // Generate plan if planning is enabled
         let plan = if self.use_planning() {
-            match reasoning.plan(reason_ctx).await {
+            match reasoning.plan(&self.budgeted(reason_ctx)).await {
                 Ok(p) => {
                     tracing::info!(
                         "Created plan for job {}: {} actions, {:.0}% confidence",
@@ -240,15 +314,20 @@ Report when the job is complete or if you encounter issues you cannot resolve."#
                 return Ok(());
             }
 
+            self.park_if_paused().await?;
+            let _step = self.deps.priority.acquire(Priority::Routine).await;
+
             // Refresh tool definitions so newly built tools become visible
             reason_ctx.available_tools = self.tools().tool_definitions().await;
 
             // Select next tool(s) to use
-            let selections = reasoning.select_tools(reason_ctx).await?;
+            let selections = reasoning.select_tools(&self.budgeted(reason_ctx)).await?;
 
             if selections.is_empty() {
                 // No tools from select_tools, ask LLM directly (may still return tool calls)
-                let respond_result = reasoning.respond_with_tools(reason_ctx).await?;
+                let respond_result = reasoning
+                    .respond_with_tools(&self.budgeted(reason_ctx))
+                    .await?;
 
                 match respond_result {
                     RespondResult::Text(response) => {
@@ -341,23 +420,11 @@ Report when the job is complete or if you encounter issues you cannot resolve."#
             .map(|selection| {
                 let tool_name = selection.tool_name.clone();
                 let params = selection.parameters.clone();
-                let tools = self.tools().clone();
-                let context_manager = self.context_manager().clone();
-                let safety = self.safety().clone();
+                let deps = self.deps.clone();
                 let job_id = self.job_id;
-                let store = self.deps.store.clone();
 
                 async move {
-                    let result = Self::execute_tool_inner(
-                        tools,
-                        context_manager,
-                        safety,
-                        store,
-                        job_id,
-                        &tool_name,
-                        &params,
-                    )
-                    .await;
+                    let result = Self::execute_tool_inner(&deps, job_id, &tool_name, &params).await;
                     ToolExecResult { result }
                 }
             })
@@ -367,21 +434,25 @@ Report when the job is complete or if you encounter issues you cannot resolve."#
     }
 
     /// Inner tool execution logic that can be called from both single and parallel paths.
+    ///
+    /// Transient failures are retried. Mutating calls consult the idempotency
+    /// ledger before every attempt, so a call that already went through is
+    /// replayed rather than repeated, and one that timed out is not retried.
     async fn execute_tool_inner(
-        tools: Arc<ToolRegistry>,
-        context_manager: Arc<ContextManager>,
-        safety: Arc<SafetyLayer>,
-        store: Option<Arc<Store>>,
+        deps: &WorkerDeps,
         job_id: Uuid,
         tool_name: &str,
         params: &serde_json::Value,
     ) -> Result<String, Error> {
-        let tool = tools
-            .get(tool_name)
-            .await
-            .ok_or_else(|| crate::error::ToolError::NotFound {
-                name: tool_name.to_string(),
-            })?;
+        let context_manager = &deps.context_manager;
+        let safety = &deps.safety;
+        let tool =
+            deps.tools
+                .get(tool_name)
+                .await
+                .ok_or_else(|| crate::error::ToolError::NotFound {
+                    name: tool_name.to_string(),
+                })?;
 
         // Tools requiring approval are blocked in autonomous jobs
         if tool.requires_approval() {
@@ -401,6 +472,12 @@ Report when the job is complete or if you encounter issues you cannot resolve."#
             .into());
         }
 
+        // Members can't run admin-only tools
+        crate::tenancy::check_tool_access(tool_name)?;
+
+        // Park before launching the call while the job's user is paused
+        deps.pause.wait_until_resumed(&job_ctx.user_id).await;
+
         // Validate tool parameters
         let validation = safety.validator().validate_tool_params(params);
         if !validation.is_valid {
@@ -417,12 +494,137 @@ Report when the job is complete or if you encounter issues you cannot resolve."#
             .into());
         }
 
-        // Execute with timeout and timing
+        // Reject arguments that don't match the tool's schema, keeping a record
+        // of which tool and model produced them
+        let model = deps.llm.model_name();
+        if let Some(failure) =
+            ArgumentFailure::check(tool_name, model, &tool.parameters_schema(), params)
+        {
+            tracing::warn!(
+                "Job {}: {} produced malformed arguments for {}: {}",
+                job_id,
+                model,
+                tool_name,
+                failure.errors.join("; ")
+            );
+            if let Some(store) = &deps.store
+                && let Err(e) = store.record_argument_failure(&failure).await
+            {
+                tracing::warn!("Failed to record malformed tool call: {}", e);
+            }
+            return Err(crate::error::ToolError::InvalidParameters {
+                name: tool_name.to_string(),
+                reason: failure.reason(),
+            }
+            .into());
+        }
+
+        // Serve a repeated read from the job's cache while it's fresh
+        let cache_ttl = tool.cache_ttl(params);
+        if cache_ttl.is_some()
+            && let Some(cached) = context_manager
+                .cached_tool_result(job_id, tool_name, params)
+                .await
+        {
+            tracing::debug!(
+                "Job {} reusing cached result of {} from {:?} ago",
+                job_id,
+                tool_name,
+                cached.age()
+            );
+            let output = serde_json::json!({
+                "cached": true,
+                "age_secs": cached.age().as_secs(),
+                "result": cached.result,
+            });
+            return serde_json::to_string_pretty(&output).map_err(|e| {
+                crate::error::ToolError::ExecutionFailed {
+                    name: tool_name.to_string(),
+                    reason: format!("Failed to serialize result: {}", e),
+                }
+                .into()
+            });
+        }
+
+        // Execute with timeout and timing, retrying transient failures
+        let key = tool
+            .is_mutating(params)
+            .then(|| idempotency::key_for(job_id, tool_name, params));
         let start = std::time::Instant::now();
-        let result = tokio::time::timeout(Duration::from_secs(60), async {
-            tool.execute(params.clone(), &job_ctx).await
-        })
-        .await;
+        let mut attempt = 1;
+        let (result, replayed) = loop {
+            if let Some(key) = &key {
+                match deps.idempotency.begin(key, job_id, tool_name).await {
+                    Decision::Execute => {}
+                    Decision::Replay(result) => {
+                        tracing::info!(
+                            "Job {} replaying earlier result of {} instead of running it again",
+                            job_id,
+                            tool_name
+                        );
+                        break (Ok(Ok(ToolOutput::success(result, Duration::ZERO))), true);
+                    }
+                    Decision::Uncertain => {
+                        return Err(crate::error::ToolError::ExecutionFailed {
+                            name: tool_name.to_string(),
+                            reason: "An earlier call with the same parameters may already have \
+                                     taken effect; not running it again"
+                                .to_string(),
+                        }
+                        .into());
+                    }
+                }
+            }
+
+            let result = tokio::time::timeout(TOOL_TIMEOUT, async {
+                crate::observability::instrument_tool(
+                    tool_name,
+                    tool.execute(params.clone(), &job_ctx),
+                )
+                .await
+            })
+            .await;
+
+            // A timeout leaves the outcome unknown: the call may have landed
+            let uncertain = matches!(result, Err(_) | Ok(Err(ToolError::Timeout(_))));
+            if let Some(key) = &key {
+                match &result {
+                    Ok(Ok(output)) => {
+                        deps.idempotency
+                            .complete(key, job_id, tool_name, &output.result)
+                            .await
+                    }
+                    _ if !uncertain => deps.idempotency.fail(key, job_id, tool_name).await,
+                    _ => {}
+                }
+            }
+
+            let transient = uncertain
+                || matches!(
+                    result,
+                    Ok(Err(
+                        ToolError::RateLimited(_) | ToolError::ExternalService(_)
+                    ))
+                );
+            if !transient || attempt >= TOOL_ATTEMPTS || (key.is_some() && uncertain) {
+                break (result, false);
+            }
+
+            let backoff = match &result {
+                Ok(Err(ToolError::RateLimited(Some(after)))) => (*after).min(TOOL_MAX_BACKOFF),
+                _ => TOOL_RETRY_BACKOFF * 2u32.pow(attempt - 1),
+            };
+            tracing::debug!(
+                "Job {} retrying {} in {:?} after transient failure (attempt {}/{})",
+                job_id,
+                tool_name,
+                backoff,
+                attempt,
+                TOOL_ATTEMPTS
+            );
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        };
         let elapsed = start.elapsed();
 
         // Record action in memory and get the ActionRecord for persistence
@@ -466,8 +668,35 @@ Report when the job is complete or if you encounter issues you cannot resolve."#
                 .ok(),
         };
 
+        // Cache reads. Any other call may have changed what this tool reads,
+        // and a mutating call what any tool reads
+        match (cache_ttl, &result) {
+            (Some(ttl), Ok(Ok(output))) => {
+                context_manager
+                    .cache_tool_result(job_id, tool_name, params, output.result.clone(), ttl)
+                    .await
+            }
+            (Some(_), _) => {}
+            (None, _) => {
+                let scope = if key.is_some() { None } else { Some(tool_name) };
+                context_manager.invalidate_tool_cache(job_id, scope).await
+            }
+        }
+
+        // Register anything the tool created so other jobs can reference it
+        if let (Ok(Ok(output)), false) = (&result, replayed) {
+            context_manager
+                .record_tool_artifact(&job_ctx, tool_name, params, &output.result)
+                .await;
+        }
+
+        // Index the call so results can be traced back to it
+        if let Some(ref action) = action {
+            context_manager.index_action(&job_ctx, action).await;
+        }
+
         // Persist action to database (fire-and-forget)
-        if let (Some(action), Some(store)) = (action, store) {
+        if let (Some(action), Some(store)) = (action, deps.store.clone()) {
             tokio::spawn(async move {
                 if let Err(e) = store.save_action(job_id, &action).await {
                     tracing::warn!("Failed to persist action for job {}: {}", job_id, e);
@@ -479,11 +708,17 @@ Report when the job is complete or if you encounter issues you cannot resolve."#
         let output = result
             .map_err(|_| crate::error::ToolError::Timeout {
                 name: tool_name.to_string(),
-                timeout: Duration::from_secs(60),
+                timeout: TOOL_TIMEOUT,
             })?
-            .map_err(|e| crate::error::ToolError::ExecutionFailed {
-                name: tool_name.to_string(),
-                reason: e.to_string(),
+            .map_err(|e| match e {
+                ToolError::Timeout(timeout) => crate::error::ToolError::Timeout {
+                    name: tool_name.to_string(),
+                    timeout,
+                },
+                e => crate::error::ToolError::ExecutionFailed {
+                    name: tool_name.to_string(),
+                    reason: e.to_string(),
+                },
             })?;
 
         // Return result as string
@@ -544,8 +779,11 @@ Report when the job is complete or if you encounter issues you cannot resolve."#
                     let store = store.clone();
                     let tool_name = selection.tool_name.clone();
                     let error_msg = e.to_string();
+                    let kind = FailureKind::of(&e);
                     tokio::spawn(async move {
-                        if let Err(db_err) = store.record_tool_failure(&tool_name, &error_msg).await
+                        if let Err(db_err) = store
+                            .record_tool_failure(&tool_name, &error_msg, kind)
+                            .await
                         {
                             tracing::warn!("Failed to record tool failure: {}", db_err);
                         }
@@ -599,6 +837,8 @@ Report when the job is complete or if you encounter issues you cannot resolve."#
             );
 
             // Execute the planned tool
+            self.park_if_paused().await?;
+            let _step = self.deps.priority.acquire(Priority::Routine).await;
             let result = self
                 .execute_tool(&action.tool_name, &action.parameters)
                 .await;
@@ -629,7 +869,7 @@ Report when the job is complete or if you encounter issues you cannot resolve."#
             "All planned actions have been executed. Is the job complete? If not, what else needs to be done?",
         ));
 
-        let response = reasoning.respond(reason_ctx).await?;
+        let response = reasoning.respond(&self.budgeted(reason_ctx)).await?;
         reason_ctx.messages.push(ChatMessage::assistant(&response));
 
         let response_lower = response.to_lowercase();
@@ -657,16 +897,24 @@ Report when the job is complete or if you encounter issues you cannot resolve."#
         tool_name: &str,
         params: &serde_json::Value,
     ) -> Result<String, Error> {
-        Self::execute_tool_inner(
-            self.tools().clone(),
-            self.context_manager().clone(),
-            self.safety().clone(),
-            self.deps.store.clone(),
-            self.job_id,
-            tool_name,
-            params,
-        )
-        .await
+        Self::execute_tool_inner(&self.deps, self.job_id, tool_name, params).await
+    }
+
+    /// Wait at a step boundary while the job's user is paused.
+    ///
+    /// Time parked still counts toward the job timeout.
+    async fn park_if_paused(&self) -> Result<(), Error> {
+        let user_id = self
+            .context_manager()
+            .get_context(self.job_id)
+            .await?
+            .user_id;
+        if self.deps.pause.is_paused(&user_id) {
+            tracing::info!("Job {} parked until /resume", self.job_id);
+            self.deps.pause.wait_until_resumed(&user_id).await;
+            tracing::info!("Job {} resumed", self.job_id);
+        }
+        Ok(())
     }
 
     async fn mark_completed(&self) -> Result<(), Error> {
@@ -0,0 +1,383 @@
+//! Working-set retrieval for chat turns.
+//!
+//! Instead of sending the whole thread history on every turn, the working set
+//! keeps the most recent exchanges, recalls the older exchanges most relevant
+//! to the new message (semantic similarity with a recency bias), and pulls
+//! matching chunks from workspace memory.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use crate::llm::{ChatMessage, Role};
+use crate::workspace::{EmbeddingProvider, Workspace};
+
+/// Most recent exchanges that are always included.
+pub const RECENT_EXCHANGES: usize = 4;
+
+/// Older exchanges recalled by relevance.
+pub const RECALLED_EXCHANGES: usize = 4;
+
+/// Workspace memory chunks pulled in per turn.
+pub const MEMORY_CHUNKS: usize = 3;
+
+/// Weight of recency against relevance when ranking older exchanges.
+const RECENCY_WEIGHT: f32 = 0.25;
+
+/// Minimum relevance for an older exchange to be recalled.
+const MIN_RELEVANCE: f32 = 0.2;
+
+/// Minimum search score for a memory chunk to be included.
+const MIN_MEMORY_SCORE: f32 = 0.1;
+
+/// Characters of an exchange used for similarity.
+const MAX_EXCHANGE_CHARS: usize = 2_000;
+
+/// Characters of a memory chunk included in the prompt.
+const MAX_MEMORY_CHARS: usize = 800;
+
+/// Cached exchange embeddings before the cache is reset.
+const EMBEDDING_CACHE_SIZE: usize = 2_048;
+
+/// Selects which history and memory go into a turn's context.
+pub struct WorkingSetRetriever {
+    embeddings: Option<Arc<dyn EmbeddingProvider>>,
+    /// Exchange embeddings keyed by a hash of their text.
+    cache: Mutex<HashMap<u64, Vec<f32>>>,
+}
+
+impl WorkingSetRetriever {
+    /// Create a retriever. Without an embedding provider, relevance falls
+    /// back to term overlap.
+    pub fn new(embeddings: Option<Arc<dyn EmbeddingProvider>>) -> Self {
+        Self {
+            embeddings,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Build the messages for a turn whose newest user message is `query`.
+    pub async fn build(
+        &self,
+        messages: Vec<ChatMessage>,
+        query: &str,
+        workspace: Option<&Workspace>,
+    ) -> Vec<ChatMessage> {
+        let exchanges = split_exchanges(&messages);
+        let mut notes = Vec::new();
+
+        let selected = if exchanges.len() > RECENT_EXCHANGES + RECALLED_EXCHANGES {
+            let older = &exchanges[..exchanges.len() - RECENT_EXCHANGES];
+            let texts: Vec<String> = older
+                .iter()
+                .map(|r| exchange_text(&messages[r.clone()]))
+                .collect();
+            let relevance = self.relevance(query, &texts).await;
+            let recalled: Vec<usize> = rank_exchanges(&relevance, RECENCY_WEIGHT)
+                .into_iter()
+                .filter(|&(i, _)| relevance[i] >= MIN_RELEVANCE)
+                .take(RECALLED_EXCHANGES)
+                .map(|(i, _)| i)
+                .collect();
+
+            tracing::debug!(
+                "Working set: {} recent + {} recalled of {} exchanges",
+                RECENT_EXCHANGES,
+                recalled.len(),
+                exchanges.len()
+            );
+            notes.push(
+                "Earlier parts of this conversation are left out; the earlier exchanges most \
+                 relevant to the current message are included in order."
+                    .to_string(),
+            );
+            assemble(&messages, &exchanges, &recalled, RECENT_EXCHANGES)
+        } else {
+            messages
+        };
+
+        if let Some(memory) = recall_memory(workspace, query).await {
+            notes.push(memory);
+        }
+
+        if notes.is_empty() {
+            return selected;
+        }
+        let mut out = Vec::with_capacity(selected.len() + 1);
+        out.push(ChatMessage::system(notes.join("\n\n")));
+        out.extend(selected);
+        out
+    }
+
+    /// Relevance of each text to the query, in `[0, 1]`.
+    async fn relevance(&self, query: &str, texts: &[String]) -> Vec<f32> {
+        if let Some(ref provider) = self.embeddings {
+            match self.embed_similarity(provider.as_ref(), query, texts).await {
+                Ok(scores) => return scores,
+                Err(e) => {
+                    tracing::debug!("Working set embedding failed, using term overlap: {}", e)
+                }
+            }
+        }
+        texts.iter().map(|t| term_overlap(query, t)).collect()
+    }
+
+    async fn embed_similarity(
+        &self,
+        provider: &dyn EmbeddingProvider,
+        query: &str,
+        texts: &[String],
+    ) -> Result<Vec<f32>, crate::workspace::EmbeddingError> {
+        let query_embedding = provider.embed(query).await?;
+
+        let keys: Vec<u64> = texts.iter().map(|t| hash_text(t)).collect();
+        let missing: Vec<String> = {
+            let cache = self.cache.lock().await;
+            texts
+                .iter()
+                .zip(&keys)
+                .filter(|(_, k)| !cache.contains_key(k))
+                .map(|(t, _)| t.clone())
+                .collect()
+        };
+        if !missing.is_empty() {
+            let embedded = provider.embed_batch(&missing).await?;
+            let mut cache = self.cache.lock().await;
+            if cache.len() + embedded.len() > EMBEDDING_CACHE_SIZE {
+                cache.clear();
+            }
+            for (text, embedding) in missing.iter().zip(embedded) {
+                cache.insert(hash_text(text), embedding);
+            }
+        }
+
+        let cache = self.cache.lock().await;
+        Ok(keys
+            .iter()
+            .map(|k| {
+                cache
+                    .get(k)
+                    .map(|e| cosine_similarity(&query_embedding, e).max(0.0))
+                    .unwrap_or(0.0)
+            })
+            .collect())
+    }
+}
+
+/// Split messages into exchanges, each starting at a user message.
+///
+/// Messages before the first user message belong to the first exchange.
+pub fn split_exchanges(messages: &[ChatMessage]) -> Vec<Range<usize>> {
+    let mut exchanges = Vec::new();
+    let mut start = 0;
+    let mut seen_user = false;
+    for (i, message) in messages.iter().enumerate() {
+        if message.role != Role::User {
+            continue;
+        }
+        if seen_user {
+            exchanges.push(start..i);
+            start = i;
+        }
+        seen_user = true;
+    }
+    if start < messages.len() {
+        exchanges.push(start..messages.len());
+    }
+    exchanges
+}
+
+/// Rank exchanges (oldest first in `relevance`) by relevance blended with
+/// recency, best first.
+pub fn rank_exchanges(relevance: &[f32], recency_weight: f32) -> Vec<(usize, f32)> {
+    let n = relevance.len() as f32;
+    let mut ranked: Vec<(usize, f32)> = relevance
+        .iter()
+        .enumerate()
+        .map(|(i, &r)| {
+            let recency = (i as f32 + 1.0) / n;
+            (i, (1.0 - recency_weight) * r + recency_weight * recency)
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked
+}
+
+/// Concatenate the recalled exchanges and the last `recent` exchanges, in
+/// conversation order.
+fn assemble(
+    messages: &[ChatMessage],
+    exchanges: &[Range<usize>],
+    recalled: &[usize],
+    recent: usize,
+) -> Vec<ChatMessage> {
+    let recent_from = exchanges.len().saturating_sub(recent);
+    exchanges
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i >= recent_from || recalled.contains(i))
+        .flat_map(|(_, r)| messages[r.clone()].iter().cloned())
+        .collect()
+}
+
+/// Render workspace memory relevant to `query` as a prompt block.
+async fn recall_memory(workspace: Option<&Workspace>, query: &str) -> Option<String> {
+    let workspace = workspace?;
+    let results = match workspace.search(query, MEMORY_CHUNKS).await {
+        Ok(results) => results,
+        Err(e) => {
+            tracing::debug!("Working set memory search failed: {}", e);
+            return None;
+        }
+    };
+
+    let chunks: Vec<String> = results
+        .into_iter()
+        .filter(|r| r.score >= MIN_MEMORY_SCORE)
+        .map(|r| {
+            let content: String = r.content.chars().take(MAX_MEMORY_CHARS).collect();
+            format!("- {}", content.trim().replace('\n', "\n  "))
+        })
+        .collect();
+    if chunks.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "## RECALLED MEMORY\nWorkspace memory relevant to the current message:\n{}",
+        chunks.join("\n")
+    ))
+}
+
+fn exchange_text(messages: &[ChatMessage]) -> String {
+    let text = messages
+        .iter()
+        .filter(|m| matches!(m.role, Role::User | Role::Assistant))
+        .map(|m| m.content.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.chars().take(MAX_EXCHANGE_CHARS).collect()
+}
+
+fn hash_text(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Cosine similarity of the sets of significant words in two texts.
+fn term_overlap(query: &str, text: &str) -> f32 {
+    fn terms(s: &str) -> std::collections::HashSet<String> {
+        s.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.chars().count() >= 3)
+            .map(|w| w.to_lowercase())
+            .collect()
+    }
+    let q = terms(query);
+    let t = terms(text);
+    if q.is_empty() || t.is_empty() {
+        return 0.0;
+    }
+    let shared = q.intersection(&t).count() as f32;
+    shared / ((q.len() * t.len()) as f32).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation(topics: &[&str]) -> Vec<ChatMessage> {
+        topics
+            .iter()
+            .flat_map(|t| {
+                [
+                    ChatMessage::user(format!("Tell me about {}", t)),
+                    ChatMessage::assistant(format!("Here is what I know about {}.", t)),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_split_exchanges() {
+        let mut messages = vec![ChatMessage::system("summary")];
+        messages.extend(conversation(&["a", "b"]));
+        messages.push(ChatMessage::user("pending"));
+
+        let exchanges = split_exchanges(&messages);
+        assert_eq!(exchanges, vec![0..3, 3..5, 5..6]);
+    }
+
+    #[test]
+    fn test_rank_prefers_relevance_then_recency() {
+        let ranked = rank_exchanges(&[0.9, 0.0, 0.0, 0.1], RECENCY_WEIGHT);
+        assert_eq!(ranked[0].0, 0);
+        assert_eq!(ranked[1].0, 3);
+
+        // Equal relevance: newer wins
+        let ranked = rank_exchanges(&[0.5, 0.5], RECENCY_WEIGHT);
+        assert_eq!(ranked[0].0, 1);
+    }
+
+    #[tokio::test]
+    async fn test_build_recalls_relevant_exchange() {
+        let topics = [
+            "kubernetes deployments",
+            "sourdough bread",
+            "tax returns",
+            "garden tomatoes",
+            "guitar chords",
+            "marathon training",
+            "python packaging",
+            "italian grammar",
+            "chess openings",
+            "solar panels",
+        ];
+        let mut messages = conversation(&topics);
+        messages.push(ChatMessage::user("How long should sourdough bread proof?"));
+
+        let retriever = WorkingSetRetriever::new(None);
+        let built = retriever
+            .build(
+                messages.clone(),
+                "How long should sourdough bread proof?",
+                None,
+            )
+            .await;
+
+        assert_eq!(built[0].role, Role::System);
+        assert!(built.len() < messages.len());
+        assert!(built.iter().any(|m| m.content.contains("sourdough bread.")));
+        assert!(!built.iter().any(|m| m.content.contains("tax returns")));
+        assert_eq!(
+            built.last().unwrap().content,
+            "How long should sourdough bread proof?"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_keeps_short_history() {
+        let messages = conversation(&["a", "b"]);
+        let built = WorkingSetRetriever::new(None)
+            .build(messages.clone(), "b", None)
+            .await;
+        assert_eq!(built.len(), messages.len());
+    }
+}
@@ -0,0 +1,190 @@
+//! Encrypted backup file format.
+//!
+//! ```text
+//! MAGIC (8 bytes) || salt (32 bytes) || nonce || ciphertext || tag
+//! ```
+//!
+//! The plaintext is the JSON-serialized [`Snapshot`], encrypted with
+//! [`SecretsCrypto`] under the secrets master key. When a backup is shipped
+//! through a text-only upload tool the same bytes are base64-encoded; [`open`]
+//! accepts either form.
+
+use std::collections::BTreeMap;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::backup::BackupError;
+use crate::secrets::SecretsCrypto;
+
+/// Leading bytes of every backup file.
+pub const MAGIC: &[u8; 8] = b"ICBACKUP";
+
+/// Snapshot layout version, bumped on incompatible changes.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Length of the key-derivation salt produced by [`SecretsCrypto::encrypt`].
+const SALT_SIZE: usize = 32;
+
+/// Point-in-time contents of the database tables and state directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    /// Rows per table, each row as a JSON object keyed by column.
+    pub tables: BTreeMap<String, Vec<serde_json::Value>>,
+    /// Files from the state directory, keyed by relative path, base64-encoded.
+    pub files: BTreeMap<String, String>,
+}
+
+impl Snapshot {
+    pub fn new() -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            created_at: Utc::now(),
+            tables: BTreeMap::new(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Total rows across all tables.
+    pub fn row_count(&self) -> usize {
+        self.tables.values().map(Vec::len).sum()
+    }
+}
+
+impl Default for Snapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Serialize and encrypt a snapshot into backup file bytes.
+pub fn seal(crypto: &SecretsCrypto, snapshot: &Snapshot) -> Result<Vec<u8>, BackupError> {
+    let plaintext = serde_json::to_vec(snapshot)
+        .map_err(|e| BackupError::Format(format!("failed to serialize snapshot: {}", e)))?;
+    let (encrypted, salt) = crypto.encrypt(&plaintext)?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + salt.len() + encrypted.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&encrypted);
+    Ok(out)
+}
+
+/// Decrypt and parse backup file bytes (raw or base64-encoded).
+pub fn open(crypto: &SecretsCrypto, bytes: &[u8]) -> Result<Snapshot, BackupError> {
+    let decoded;
+    let bytes = if bytes.starts_with(MAGIC) {
+        bytes
+    } else {
+        let text: Vec<u8> = bytes
+            .iter()
+            .copied()
+            .filter(|b| !b.is_ascii_whitespace())
+            .collect();
+        decoded = STANDARD
+            .decode(text)
+            .map_err(|_| BackupError::Format("not an IronClaw backup file".to_string()))?;
+        if !decoded.starts_with(MAGIC) {
+            return Err(BackupError::Format(
+                "not an IronClaw backup file".to_string(),
+            ));
+        }
+        &decoded
+    };
+
+    let body = &bytes[MAGIC.len()..];
+    if body.len() < SALT_SIZE {
+        return Err(BackupError::Format("backup file is truncated".to_string()));
+    }
+    let (salt, encrypted) = body.split_at(SALT_SIZE);
+    let plaintext = crypto.decrypt(encrypted, salt)?;
+
+    let snapshot: Snapshot = serde_json::from_str(plaintext.expose())
+        .map_err(|e| BackupError::Format(format!("invalid snapshot: {}", e)))?;
+    if snapshot.version > FORMAT_VERSION {
+        return Err(BackupError::Format(format!(
+            "backup format v{} is newer than this build supports (v{})",
+            snapshot.version, FORMAT_VERSION
+        )));
+    }
+    Ok(snapshot)
+}
+
+/// Base64 form of a sealed backup, for tools that only accept text content.
+pub fn to_text(sealed: &[u8]) -> String {
+    STANDARD.encode(sealed)
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::SecretString;
+
+    use super::*;
+
+    fn test_crypto() -> SecretsCrypto {
+        SecretsCrypto::new(SecretString::from(
+            "0123456789abcdef0123456789abcdef".to_string(),
+        ))
+        .unwrap()
+    }
+
+    fn sample() -> Snapshot {
+        let mut snapshot = Snapshot::new();
+        snapshot.tables.insert(
+            "memory_documents".to_string(),
+            vec![serde_json::json!({"path": "MEMORY.md", "content": "remember this"})],
+        );
+        snapshot
+            .files
+            .insert("settings.json".to_string(), STANDARD.encode(b"{}"));
+        snapshot
+    }
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let crypto = test_crypto();
+        let sealed = seal(&crypto, &sample()).unwrap();
+
+        assert!(sealed.starts_with(MAGIC));
+        assert!(
+            !String::from_utf8_lossy(&sealed).contains("remember this"),
+            "backup must not contain plaintext"
+        );
+
+        let opened = open(&crypto, &sealed).unwrap();
+        assert_eq!(opened.row_count(), 1);
+        assert_eq!(opened.files.len(), 1);
+    }
+
+    #[test]
+    fn test_open_accepts_base64_text() {
+        let crypto = test_crypto();
+        let text = to_text(&seal(&crypto, &sample()).unwrap());
+        let wrapped = format!("{}\n{}\n", &text[..40], &text[40..]);
+
+        let opened = open(&crypto, wrapped.as_bytes()).unwrap();
+        assert_eq!(opened.row_count(), 1);
+    }
+
+    #[test]
+    fn test_open_rejects_wrong_key_and_garbage() {
+        let sealed = seal(&test_crypto(), &sample()).unwrap();
+        let other = SecretsCrypto::new(SecretString::from(
+            "fedcba9876543210fedcba9876543210".to_string(),
+        ))
+        .unwrap();
+
+        assert!(matches!(open(&other, &sealed), Err(BackupError::Crypto(_))));
+        assert!(matches!(
+            open(&test_crypto(), b"hello world"),
+            Err(BackupError::Format(_))
+        ));
+        assert!(matches!(
+            open(&test_crypto(), MAGIC),
+            Err(BackupError::Format(_))
+        ));
+    }
+}
@@ -0,0 +1,471 @@
+//! Creating, listing, pruning, uploading and restoring backups.
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use deadpool_postgres::Pool;
+
+use crate::backup::archive::{self, Snapshot};
+use crate::backup::{BACKUP_TABLES, BackupError};
+use crate::config::BackupConfig;
+use crate::context::JobContext;
+use crate::secrets::SecretsCrypto;
+use crate::tools::ToolRegistry;
+
+/// Backup file names are `ironclaw-<timestamp>.icbk`.
+const FILE_PREFIX: &str = "ironclaw-";
+const FILE_EXTENSION: &str = "icbk";
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Files larger than this are left out of the snapshot.
+const MAX_FILE_BYTES: u64 = 8 * 1024 * 1024;
+
+/// State-directory entries that are never backed up (sandbox project
+/// checkouts can be arbitrarily large and are reproducible; the memory
+/// history repository can't be merged file-by-file on restore).
+const EXCLUDED_DIRS: &[&str] = &["backups", "projects", "memory-git"];
+
+/// A backup file on disk.
+#[derive(Debug, Clone)]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+/// What a restore wrote.
+#[derive(Debug, Default, Clone)]
+pub struct RestoreReport {
+    pub rows_inserted: u64,
+    /// Rows that already existed (matched on a unique key).
+    pub rows_skipped: u64,
+    pub files_restored: usize,
+    /// Files left alone because they already exist.
+    pub files_skipped: usize,
+}
+
+/// Creates and restores encrypted backups.
+pub struct BackupManager {
+    pool: Pool,
+    crypto: Arc<SecretsCrypto>,
+    config: BackupConfig,
+    state_dir: PathBuf,
+}
+
+impl BackupManager {
+    pub fn new(pool: Pool, crypto: Arc<SecretsCrypto>, config: BackupConfig) -> Self {
+        Self {
+            pool,
+            crypto,
+            config,
+            state_dir: dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(".ironclaw"),
+        }
+    }
+
+    /// Back up files from `dir` instead of `~/.ironclaw`.
+    pub fn with_state_dir(mut self, dir: PathBuf) -> Self {
+        self.state_dir = dir;
+        self
+    }
+
+    pub fn config(&self) -> &BackupConfig {
+        &self.config
+    }
+
+    /// Capture the backed-up tables and state files.
+    pub async fn snapshot(&self) -> Result<Snapshot, BackupError> {
+        let client = self.pool.get().await.map_err(db_err)?;
+        let mut snapshot = Snapshot::new();
+
+        for table in BACKUP_TABLES {
+            let columns = writable_columns(&client, table).await?;
+            if columns.is_empty() {
+                continue;
+            }
+            let sql = format!(
+                "SELECT row_to_json(t) FROM (SELECT {} FROM {}) t",
+                columns.join(", "),
+                table
+            );
+            let rows = client.query(&sql, &[]).await.map_err(db_err)?;
+            snapshot.tables.insert(
+                table.to_string(),
+                rows.iter()
+                    .map(|r| r.get::<_, serde_json::Value>(0))
+                    .collect(),
+            );
+        }
+
+        for (rel, path) in collect_files(&self.state_dir, &self.config.dir)? {
+            match std::fs::read(&path) {
+                Ok(bytes) => {
+                    snapshot.files.insert(rel, STANDARD.encode(bytes));
+                }
+                Err(e) => tracing::warn!("Skipping {} in backup: {}", path.display(), e),
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Write a new backup to the backup directory and prune old ones.
+    pub async fn create(&self) -> Result<BackupInfo, BackupError> {
+        let snapshot = self.snapshot().await?;
+        let sealed = archive::seal(&self.crypto, &snapshot)?;
+
+        std::fs::create_dir_all(&self.config.dir)?;
+        let path = self.config.dir.join(backup_file_name(snapshot.created_at));
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, &sealed)?;
+        std::fs::rename(&tmp, &path)?;
+
+        tracing::info!(
+            "Backup written to {} ({} rows, {} files)",
+            path.display(),
+            snapshot.row_count(),
+            snapshot.files.len()
+        );
+
+        let pruned = self.prune()?;
+        if pruned > 0 {
+            tracing::debug!("Pruned {} old backups", pruned);
+        }
+
+        Ok(BackupInfo {
+            path,
+            created_at: snapshot.created_at,
+            size_bytes: sealed.len() as u64,
+        })
+    }
+
+    /// Backups in the backup directory, oldest first.
+    pub fn list(&self) -> Result<Vec<BackupInfo>, BackupError> {
+        let entries = match std::fs::read_dir(&self.config.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut backups = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name();
+            let Some(created_at) = name.to_str().and_then(parse_backup_file_name) else {
+                continue;
+            };
+            backups.push(BackupInfo {
+                path: entry.path(),
+                created_at,
+                size_bytes: entry.metadata()?.len(),
+            });
+        }
+        backups.sort_by_key(|b| b.created_at);
+        Ok(backups)
+    }
+
+    /// Delete all but the newest `keep` backups, returning how many were removed.
+    pub fn prune(&self) -> Result<usize, BackupError> {
+        let backups = self.list()?;
+        let excess = backups.len().saturating_sub(self.config.keep.max(1));
+        for backup in &backups[..excess] {
+            std::fs::remove_file(&backup.path)?;
+        }
+        Ok(excess)
+    }
+
+    /// How long until the next scheduled backup is due.
+    pub fn next_due(&self) -> Duration {
+        let interval = Duration::from_secs(self.config.interval_secs);
+        let Some(latest) = self.list().ok().and_then(|b| b.last().cloned()) else {
+            return Duration::ZERO;
+        };
+        let age = (Utc::now() - latest.created_at)
+            .to_std()
+            .unwrap_or_default();
+        interval.saturating_sub(age)
+    }
+
+    /// Ship a backup through an upload tool's `upload_file` action.
+    ///
+    /// The file is base64-encoded because upload tools take text content;
+    /// `restore` accepts the encoded form directly.
+    pub async fn upload(
+        &self,
+        tools: &ToolRegistry,
+        tool_name: &str,
+        backup: &BackupInfo,
+    ) -> Result<(), BackupError> {
+        let upload_err = |reason: String| BackupError::Upload {
+            tool: tool_name.to_string(),
+            reason,
+        };
+
+        let tool = tools
+            .get(tool_name)
+            .await
+            .ok_or_else(|| upload_err("tool is not installed".to_string()))?;
+        let sealed = std::fs::read(&backup.path)?;
+        let name = format!(
+            "{}.b64",
+            backup
+                .path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("ironclaw.icbk")
+        );
+
+        let params = serde_json::json!({
+            "action": "upload_file",
+            "name": name,
+            "content": archive::to_text(&sealed),
+            "mime_type": "text/plain",
+        });
+        let ctx = JobContext::new("Backup upload", "Upload an encrypted backup");
+        tool.execute(params, &ctx)
+            .await
+            .map_err(|e| upload_err(e.to_string()))?;
+
+        tracing::info!("Uploaded backup {} via {}", name, tool_name);
+        Ok(())
+    }
+
+    /// Restore a backup file, keeping any rows and files that already exist.
+    pub async fn restore(&self, path: &Path) -> Result<RestoreReport, BackupError> {
+        let snapshot = archive::open(&self.crypto, &std::fs::read(path)?)?;
+        let mut report = RestoreReport::default();
+
+        let mut client = self.pool.get().await.map_err(db_err)?;
+        let mut columns = Vec::new();
+        for table in BACKUP_TABLES {
+            columns.push(writable_columns(&client, table).await?.join(", "));
+        }
+
+        let tx = client.transaction().await.map_err(db_err)?;
+        for (table, columns) in BACKUP_TABLES.iter().zip(columns) {
+            let Some(rows) = snapshot.tables.get(*table) else {
+                continue;
+            };
+            if columns.is_empty() {
+                continue;
+            }
+            let sql = format!(
+                "INSERT INTO {table} ({columns}) \
+                 SELECT {columns} FROM json_populate_record(NULL::{table}, $1::json) \
+                 ON CONFLICT DO NOTHING"
+            );
+            let stmt = tx.prepare(&sql).await.map_err(db_err)?;
+            for row in rows {
+                match tx.execute(&stmt, &[row]).await.map_err(db_err)? {
+                    0 => report.rows_skipped += 1,
+                    n => report.rows_inserted += n,
+                }
+            }
+        }
+        tx.commit().await.map_err(db_err)?;
+
+        for (rel, content) in &snapshot.files {
+            let target = safe_join(&self.state_dir, rel)?;
+            if target.exists() {
+                report.files_skipped += 1;
+                continue;
+            }
+            let bytes = STANDARD
+                .decode(content)
+                .map_err(|e| BackupError::Format(format!("file {}: {}", rel, e)))?;
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&target, bytes)?;
+            report.files_restored += 1;
+        }
+
+        Ok(report)
+    }
+}
+
+/// Run scheduled backups in the background, uploading each one if an upload
+/// tool is configured.
+pub fn spawn_backup_loop(
+    manager: Arc<BackupManager>,
+    tools: Arc<ToolRegistry>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(manager.next_due()).await;
+
+            let backup = match manager.create().await {
+                Ok(backup) => backup,
+                Err(e) => {
+                    tracing::error!("Scheduled backup failed: {}", e);
+                    // Avoid a hot loop when the failure persists
+                    tokio::time::sleep(Duration::from_secs(manager.config.interval_secs)).await;
+                    continue;
+                }
+            };
+
+            if let Some(ref tool) = manager.config.upload_tool
+                && let Err(e) = manager.upload(&tools, tool, &backup).await
+            {
+                tracing::warn!("{}", e);
+            }
+        }
+    })
+}
+
+fn db_err(e: impl std::fmt::Display) -> BackupError {
+    BackupError::Database(e.to_string())
+}
+
+/// Columns of `table` that can be written (excludes generated columns).
+async fn writable_columns(
+    client: &tokio_postgres::Client,
+    table: &str,
+) -> Result<Vec<String>, BackupError> {
+    let rows = client
+        .query(
+            "SELECT column_name::text FROM information_schema.columns \
+             WHERE table_schema = current_schema() AND table_name = $1 \
+             AND is_generated = 'NEVER' ORDER BY ordinal_position",
+            &[&table],
+        )
+        .await
+        .map_err(db_err)?;
+    Ok(rows
+        .iter()
+        .map(|r| format!("\"{}\"", r.get::<_, String>(0)))
+        .collect())
+}
+
+fn backup_file_name(created_at: DateTime<Utc>) -> String {
+    format!(
+        "{}{}.{}",
+        FILE_PREFIX,
+        created_at.format(TIMESTAMP_FORMAT),
+        FILE_EXTENSION
+    )
+}
+
+fn parse_backup_file_name(name: &str) -> Option<DateTime<Utc>> {
+    let stamp = name
+        .strip_prefix(FILE_PREFIX)?
+        .strip_suffix(FILE_EXTENSION)?
+        .strip_suffix('.')?;
+    NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT)
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+/// Regular files under `root` keyed by their `/`-separated relative path,
+/// skipping the backup directory, excluded directories and oversized files.
+fn collect_files(root: &Path, backup_dir: &Path) -> Result<Vec<(String, PathBuf)>, BackupError> {
+    let backup_dir = backup_dir.canonicalize().ok();
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+
+            if file_type.is_dir() {
+                let excluded = dir == root
+                    && EXCLUDED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref());
+                if !excluded && path.canonicalize().ok() != backup_dir {
+                    pending.push(path);
+                }
+            } else if file_type.is_file() {
+                if entry.metadata()?.len() > MAX_FILE_BYTES {
+                    tracing::warn!("Skipping {} in backup: file too large", path.display());
+                    continue;
+                }
+                let Ok(rel) = path.strip_prefix(root) else {
+                    continue;
+                };
+                let rel: Vec<_> = rel
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect();
+                files.push((rel.join("/"), path));
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Resolve a relative path from a backup under `root`, rejecting anything
+/// that would escape it.
+fn safe_join(root: &Path, rel: &str) -> Result<PathBuf, BackupError> {
+    let rel = Path::new(rel);
+    if !rel.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(BackupError::Format(format!(
+            "unsafe file path in backup: {}",
+            rel.display()
+        )));
+    }
+    Ok(root.join(rel))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_file_name_roundtrip() {
+        let at = DateTime::parse_from_rfc3339("2026-10-16T08:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let name = backup_file_name(at);
+
+        assert_eq!(name, "ironclaw-20261016T083000Z.icbk");
+        assert_eq!(parse_backup_file_name(&name), Some(at));
+        assert_eq!(
+            parse_backup_file_name("settings-20261016-083000.json"),
+            None
+        );
+        assert_eq!(parse_backup_file_name("database-20261016-083000.sql"), None);
+    }
+
+    #[test]
+    fn test_collect_files_skips_backups_and_projects() {
+        let root = tempfile::tempdir().unwrap();
+        let backups = root.path().join("backups");
+        std::fs::create_dir_all(&backups).unwrap();
+        std::fs::create_dir_all(root.path().join("projects/demo")).unwrap();
+        std::fs::create_dir_all(root.path().join("channels")).unwrap();
+        std::fs::write(root.path().join("settings.json"), "{}").unwrap();
+        std::fs::write(root.path().join("channels/slack.json"), "{}").unwrap();
+        std::fs::write(backups.join("ironclaw-20260101T000000Z.icbk"), "x").unwrap();
+        std::fs::write(root.path().join("projects/demo/main.rs"), "").unwrap();
+
+        let files: Vec<String> = collect_files(root.path(), &backups)
+            .unwrap()
+            .into_iter()
+            .map(|(rel, _)| rel)
+            .collect();
+        assert_eq!(files, vec!["channels/slack.json", "settings.json"]);
+    }
+
+    #[test]
+    fn test_safe_join_rejects_escapes() {
+        let root = Path::new("/state");
+        assert_eq!(
+            safe_join(root, "channels/slack.json").unwrap(),
+            PathBuf::from("/state/channels/slack.json")
+        );
+        assert!(safe_join(root, "../etc/passwd").is_err());
+        assert!(safe_join(root, "/etc/passwd").is_err());
+    }
+}
@@ -0,0 +1,56 @@
+//! Encrypted backups of agent state.
+//!
+//! A backup is a [`Snapshot`] of the memory, conversation, settings, secrets
+//! and routine tables plus the files in the state directory (`~/.ironclaw`),
+//! sealed with the secrets master key (see [`archive`] for the file format).
+//!
+//! Backups are written to a local directory and pruned to a retention count.
+//! When an upload tool is configured (e.g. `google-drive`, or any tool with a
+//! compatible `upload_file` action such as an S3 tool), each scheduled backup
+//! is also shipped off the machine.
+//!
+//! Restoring inserts rows that don't already exist and writes files that are
+//! missing, so it is safe to run against a partially populated install.
+
+pub mod archive;
+mod manager;
+
+pub use archive::Snapshot;
+pub use manager::{BackupInfo, BackupManager, RestoreReport, spawn_backup_loop};
+
+use crate::secrets::SecretError;
+
+/// Tables included in a backup, in restore (foreign-key) order.
+pub const BACKUP_TABLES: &[&str] = &[
+    "memory_documents",
+    "memory_chunks",
+    "conversations",
+    "conversation_messages",
+    "settings",
+    "secrets",
+    "heartbeat_state",
+    "routines",
+    "routine_runs",
+];
+
+/// Errors from creating, restoring or uploading backups.
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("Backups need a secrets master key (SECRETS_MASTER_KEY)")]
+    NoMasterKey,
+
+    #[error("Backup encryption failed: {0}")]
+    Crypto(#[from] SecretError),
+
+    #[error("Invalid backup: {0}")]
+    Format(String),
+
+    #[error("Database error: {0}")]
+    Database(String),
+
+    #[error("Upload via {tool} failed: {reason}")]
+    Upload { tool: String, reason: String },
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
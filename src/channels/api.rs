@@ -0,0 +1,490 @@
+//! Generic signed webhook channel for third-party applications.
+//!
+//! Applications POST `{"user_id", "content", "conversation_id"}` to
+//! `/webhook/api` and receive the agent's replies at a configured callback
+//! URL. Both directions are signed with the shared secret:
+//!
+//! ```text
+//! X-IronClaw-Timestamp: <unix seconds>
+//! X-IronClaw-Signature: sha256=<hex HMAC-SHA256(secret, "{timestamp}.{body}")>
+//! ```
+//!
+//! Requests older than five minutes are rejected, and each signature is
+//! accepted once, so a captured request can't be replayed.
+//!
+//! The application's user IDs are its own, not this deployment's: the agent
+//! sees them as `api:<user_id>`, so a caller can't pose as a local user or
+//! admin.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::{DefaultBodyLimit, State},
+    http::{HeaderMap, StatusCode},
+    routing::post,
+};
+use hmac::{Hmac, Mac};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+use tokio::sync::{RwLock, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use uuid::Uuid;
+
+use crate::channels::{
+    Channel, FormattingCapabilities, IncomingMessage, MessageStream, OutgoingResponse,
+};
+use crate::config::ApiChannelConfig;
+use crate::error::ChannelError;
+
+const CHANNEL_NAME: &str = "api";
+
+/// Header carrying the request's Unix timestamp.
+pub const TIMESTAMP_HEADER: &str = "x-ironclaw-timestamp";
+
+/// Header carrying the request's HMAC signature.
+pub const SIGNATURE_HEADER: &str = "x-ironclaw-signature";
+
+/// Oldest signed request accepted, in seconds.
+const MAX_SIGNATURE_AGE_SECS: i64 = 300;
+
+/// Maximum request body size.
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Maximum length of a caller-supplied user or conversation ID.
+const MAX_ID_LEN: usize = 256;
+
+/// Prefix marking agent user IDs that belong to the calling application.
+const USER_ID_PREFIX: &str = "api:";
+
+/// Signed webhook channel for third-party integrations.
+pub struct ApiChannel {
+    config: ApiChannelConfig,
+    state: Arc<ApiChannelState>,
+    client: reqwest::Client,
+}
+
+struct ApiChannelState {
+    /// Sender for incoming messages.
+    tx: RwLock<Option<mpsc::Sender<IncomingMessage>>>,
+    /// Shared secret for request and callback signatures.
+    secret: String,
+    /// Signatures accepted within the last `MAX_SIGNATURE_AGE_SECS`, with
+    /// their timestamps.
+    seen_signatures: Mutex<HashMap<String, i64>>,
+}
+
+impl ApiChannelState {
+    /// Record a valid signature, returning false if it was already used.
+    /// Entries older than the signature window are dropped, since requests
+    /// that old are rejected anyway.
+    fn first_use(&self, signature: &str, sent_at: i64, now: i64) -> bool {
+        let mut seen = self
+            .seen_signatures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        seen.retain(|_, at| (now - *at).abs() <= MAX_SIGNATURE_AGE_SECS);
+        seen.insert(signature.to_string(), sent_at).is_none()
+    }
+}
+
+impl ApiChannel {
+    /// Create a new API channel.
+    pub fn new(config: ApiChannelConfig) -> Self {
+        let secret = config.secret.expose_secret().to_string();
+        Self {
+            config,
+            state: Arc::new(ApiChannelState {
+                tx: RwLock::new(None),
+                secret,
+                seen_signatures: Mutex::new(HashMap::new()),
+            }),
+            client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Return the channel's axum routes with state applied.
+    pub fn routes(&self) -> Router {
+        Router::new()
+            .route("/webhook/api", post(webhook_handler))
+            .layer(DefaultBodyLimit::max(MAX_BODY_BYTES))
+            .with_state(self.state.clone())
+    }
+
+    /// Sign and POST `payload` to the callback URL.
+    async fn deliver(&self, payload: &CallbackPayload<'_>) -> Result<(), ChannelError> {
+        let send_failed = |reason: String| ChannelError::SendFailed {
+            name: CHANNEL_NAME.to_string(),
+            reason,
+        };
+
+        let body = serde_json::to_vec(payload).map_err(|e| send_failed(e.to_string()))?;
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let signature = sign(&self.state.secret, &timestamp, &body);
+
+        let response = self
+            .client
+            .post(&self.config.callback_url)
+            .header("content-type", "application/json")
+            .header(TIMESTAMP_HEADER, &timestamp)
+            .header(SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| send_failed(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(send_failed(format!(
+                "callback returned {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiRequest {
+    /// The application's identifier for the end user.
+    user_id: String,
+    /// Message content.
+    content: String,
+    /// The application's identifier for the conversation.
+    #[serde(default)]
+    conversation_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ApiResponse {
+    /// Message ID assigned to this request; replies reference it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message_id: Option<Uuid>,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ApiResponse {
+    fn error(status: StatusCode, error: impl Into<String>) -> (StatusCode, Json<Self>) {
+        (
+            status,
+            Json(Self {
+                message_id: None,
+                status: "error",
+                error: Some(error.into()),
+            }),
+        )
+    }
+}
+
+/// Body POSTed to the callback URL.
+#[derive(Debug, Serialize)]
+struct CallbackPayload<'a> {
+    /// ID of the message being answered; absent for agent-initiated messages.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    in_reply_to: Option<Uuid>,
+    user_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conversation_id: Option<&'a str>,
+    content: &'a str,
+}
+
+async fn webhook_handler(
+    State(state): State<Arc<ApiChannelState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Json<ApiResponse>) {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let (Some(timestamp), Some(signature)) = (header(TIMESTAMP_HEADER), header(SIGNATURE_HEADER))
+    else {
+        return ApiResponse::error(StatusCode::UNAUTHORIZED, "Missing signature headers");
+    };
+    let now = chrono::Utc::now().timestamp();
+    if !signature_valid(&state.secret, timestamp, signature, &body, now) {
+        return ApiResponse::error(StatusCode::UNAUTHORIZED, "Invalid or expired signature");
+    }
+    // signature_valid parsed the timestamp already
+    let sent_at = timestamp.parse().unwrap_or(now);
+    if !state.first_use(signature, sent_at, now) {
+        return ApiResponse::error(StatusCode::CONFLICT, "Request already received");
+    }
+
+    let req: ApiRequest = match serde_json::from_slice(&body) {
+        Ok(req) => req,
+        Err(e) => {
+            return ApiResponse::error(StatusCode::BAD_REQUEST, format!("Invalid JSON: {}", e));
+        }
+    };
+    if req.user_id.is_empty() || req.user_id.len() > MAX_ID_LEN {
+        return ApiResponse::error(StatusCode::BAD_REQUEST, "Invalid user_id");
+    }
+    if req
+        .conversation_id
+        .as_ref()
+        .is_some_and(|id| id.is_empty() || id.len() > MAX_ID_LEN)
+    {
+        return ApiResponse::error(StatusCode::BAD_REQUEST, "Invalid conversation_id");
+    }
+    if req.content.trim().is_empty() {
+        return ApiResponse::error(StatusCode::BAD_REQUEST, "Empty content");
+    }
+
+    let agent_user_id = format!("{}{}", USER_ID_PREFIX, req.user_id);
+    let mut msg = IncomingMessage::new(CHANNEL_NAME, &agent_user_id, &req.content).with_metadata(
+        serde_json::json!({
+            "user_id": req.user_id,
+            "conversation_id": req.conversation_id,
+        }),
+    );
+    if let Some(ref conversation_id) = req.conversation_id {
+        msg = msg.with_thread(conversation_id);
+    }
+    let message_id = msg.id;
+
+    let tx_guard = state.tx.read().await;
+    let Some(tx) = tx_guard.as_ref() else {
+        return ApiResponse::error(StatusCode::SERVICE_UNAVAILABLE, "Channel not started");
+    };
+    if tx.send(msg).await.is_err() {
+        return ApiResponse::error(StatusCode::INTERNAL_SERVER_ERROR, "Channel closed");
+    }
+
+    (
+        StatusCode::ACCEPTED,
+        Json(ApiResponse {
+            message_id: Some(message_id),
+            status: "accepted",
+            error: None,
+        }),
+    )
+}
+
+/// The application's own ID for an agent user.
+fn app_user_id(user_id: &str) -> &str {
+    user_id.strip_prefix(USER_ID_PREFIX).unwrap_or(user_id)
+}
+
+/// `sha256=<hex>` signature of `{timestamp}.{body}`.
+fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    let hex: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("sha256={}", hex)
+}
+
+/// Whether `signature` is a fresh, valid signature of `body`.
+fn signature_valid(secret: &str, timestamp: &str, signature: &str, body: &[u8], now: i64) -> bool {
+    let Ok(sent_at) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    if (now - sent_at).abs() > MAX_SIGNATURE_AGE_SECS {
+        return false;
+    }
+    sign(secret, timestamp, body)
+        .as_bytes()
+        .ct_eq(signature.as_bytes())
+        .into()
+}
+
+#[async_trait]
+impl Channel for ApiChannel {
+    fn name(&self) -> &str {
+        CHANNEL_NAME
+    }
+
+    async fn start(&self) -> Result<MessageStream, ChannelError> {
+        if self.state.secret.is_empty() {
+            return Err(ChannelError::StartupFailed {
+                name: CHANNEL_NAME.to_string(),
+                reason: "API channel secret is required (set API_CHANNEL_SECRET)".to_string(),
+            });
+        }
+
+        let (tx, rx) = mpsc::channel(256);
+        *self.state.tx.write().await = Some(tx);
+
+        tracing::info!(
+            "API channel ready, replies go to {}",
+            self.config.callback_url
+        );
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+
+    async fn respond(
+        &self,
+        msg: &IncomingMessage,
+        response: OutgoingResponse,
+    ) -> Result<(), ChannelError> {
+        let conversation_id = msg
+            .metadata
+            .get("conversation_id")
+            .and_then(|v| v.as_str())
+            .or(msg.thread_id.as_deref());
+        self.deliver(&CallbackPayload {
+            in_reply_to: Some(msg.id),
+            user_id: app_user_id(&msg.user_id),
+            conversation_id,
+            content: &response.content,
+        })
+        .await
+    }
+
+    async fn broadcast(
+        &self,
+        user_id: &str,
+        response: OutgoingResponse,
+    ) -> Result<(), ChannelError> {
+        self.deliver(&CallbackPayload {
+            in_reply_to: None,
+            user_id: app_user_id(user_id),
+            conversation_id: response.thread_id.as_deref(),
+            content: &response.content,
+        })
+        .await
+    }
+
+    async fn formatting(&self) -> FormattingCapabilities {
+        // The application decides how to render replies
+        FormattingCapabilities::default()
+    }
+
+    async fn health_check(&self) -> Result<(), ChannelError> {
+        if self.state.tx.read().await.is_some() {
+            Ok(())
+        } else {
+            Err(ChannelError::HealthCheckFailed {
+                name: CHANNEL_NAME.to_string(),
+            })
+        }
+    }
+
+    async fn shutdown(&self) -> Result<(), ChannelError> {
+        *self.state.tx.write().await = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_roundtrip() {
+        let body = br#"{"user_id":"u1","content":"hi"}"#;
+        let signature = sign("s3cret", "1700000000", body);
+
+        assert!(signature.starts_with("sha256="));
+        assert!(signature_valid(
+            "s3cret",
+            "1700000000",
+            &signature,
+            body,
+            1700000100
+        ));
+        // Wrong secret, tampered body, stale timestamp
+        assert!(!signature_valid(
+            "other",
+            "1700000000",
+            &signature,
+            body,
+            1700000100
+        ));
+        assert!(!signature_valid(
+            "s3cret",
+            "1700000000",
+            &signature,
+            b"{}",
+            1700000100
+        ));
+        assert!(!signature_valid(
+            "s3cret",
+            "1700000000",
+            &signature,
+            body,
+            1700000000 + MAX_SIGNATURE_AGE_SECS + 1
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_signed_request_reaches_agent() {
+        use futures::StreamExt;
+
+        let channel = ApiChannel::new(ApiChannelConfig {
+            secret: secrecy::SecretString::from("s3cret".to_string()),
+            callback_url: "http://127.0.0.1:9/callback".to_string(),
+        });
+        let mut stream = channel.start().await.unwrap();
+
+        let body = br#"{"user_id":"u1","content":"hello","conversation_id":"c1"}"#;
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let mut headers = HeaderMap::new();
+        headers.insert(TIMESTAMP_HEADER, timestamp.parse().unwrap());
+        headers.insert(
+            SIGNATURE_HEADER,
+            sign("s3cret", &timestamp, body).parse().unwrap(),
+        );
+
+        let (status, _) = webhook_handler(
+            State(channel.state.clone()),
+            headers.clone(),
+            Bytes::from_static(body),
+        )
+        .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        let msg = stream.next().await.unwrap();
+        assert_eq!(msg.channel, "api");
+        assert_eq!(msg.user_id, "api:u1");
+        assert_eq!(msg.thread_id.as_deref(), Some("c1"));
+
+        // The same signed request again is a replay
+        let (status, _) = webhook_handler(
+            State(channel.state.clone()),
+            headers,
+            Bytes::from_static(body),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    #[test]
+    fn test_app_user_id() {
+        assert_eq!(app_user_id("api:u1"), "u1");
+        assert_eq!(app_user_id("api:api:u1"), "api:u1");
+        assert_eq!(app_user_id("local"), "local");
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_request_rejected() {
+        let channel = ApiChannel::new(ApiChannelConfig {
+            secret: secrecy::SecretString::from("s3cret".to_string()),
+            callback_url: "http://127.0.0.1:9/callback".to_string(),
+        });
+        let _stream = channel.start().await.unwrap();
+
+        let (status, _) = webhook_handler(
+            State(channel.state.clone()),
+            HeaderMap::new(),
+            Bytes::from_static(b"{}"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,227 @@
+//! Files exchanged between channels and the agent.
+//!
+//! Messages never carry file bytes. A channel stores the bytes in the host's
+//! [`AttachmentStore`] and passes an [`Attachment`] that references the stored
+//! blob, in either direction. Blobs are content-addressed, so the same file
+//! received twice is stored once.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Largest attachment the host stores (20 MB, Telegram's download limit).
+pub const MAX_ATTACHMENT_BYTES: usize = 20 * 1024 * 1024;
+
+/// A file attached to a message or response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attachment {
+    /// Reference to the blob in the attachment store.
+    pub id: String,
+    /// File name, as the sender named it.
+    pub name: String,
+    /// MIME type (e.g., "image/jpeg").
+    pub mime_type: String,
+    /// Size in bytes.
+    pub size: u64,
+}
+
+impl Attachment {
+    /// One-line description for the model, which can't see message metadata.
+    pub fn describe(&self) -> String {
+        format!(
+            "[Attachment: {} ({}, {}) id={}]",
+            self.name,
+            self.mime_type,
+            format_size(self.size),
+            self.id
+        )
+    }
+}
+
+/// Prefix `content` with a description of each attachment.
+pub fn describe_attachments(content: &str, attachments: &[Attachment]) -> String {
+    if attachments.is_empty() {
+        return content.to_string();
+    }
+    let mut lines: Vec<String> = attachments.iter().map(Attachment::describe).collect();
+    if !content.is_empty() {
+        lines.push(content.to_string());
+    }
+    lines.join("\n")
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{} KB", b / 1024),
+        b => format!("{} bytes", b),
+    }
+}
+
+/// Errors from the attachment store.
+#[derive(Debug, thiserror::Error)]
+pub enum AttachmentError {
+    #[error("Attachment is {size} bytes, the limit is {max}")]
+    TooLarge { size: usize, max: usize },
+
+    #[error("Invalid attachment id: {0}")]
+    InvalidId(String),
+
+    #[error("Attachment not found: {0}")]
+    NotFound(String),
+
+    #[error("Attachment storage failed: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Host-side blob storage for attachments.
+///
+/// Blocking file I/O: WASM host calls already run on blocking threads, and
+/// async callers should use `spawn_blocking` for large files.
+#[derive(Debug, Clone)]
+pub struct AttachmentStore {
+    dir: PathBuf,
+}
+
+impl AttachmentStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns ~/.ironclaw/attachments/
+    pub fn default_dir() -> PathBuf {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".ironclaw")
+            .join("attachments")
+    }
+
+    /// Store a file and return its reference.
+    pub fn put(
+        &self,
+        name: &str,
+        mime_type: &str,
+        data: &[u8],
+    ) -> Result<Attachment, AttachmentError> {
+        if data.len() > MAX_ATTACHMENT_BYTES {
+            return Err(AttachmentError::TooLarge {
+                size: data.len(),
+                max: MAX_ATTACHMENT_BYTES,
+            });
+        }
+
+        let id: String = Sha256::digest(data)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let path = self.dir.join(&id);
+        if !path.exists() {
+            std::fs::create_dir_all(&self.dir)?;
+            // Write then rename so readers never see a partial file
+            let tmp = self.dir.join(format!("{}.tmp", id));
+            std::fs::write(&tmp, data)?;
+            std::fs::rename(&tmp, &path)?;
+        }
+
+        Ok(Attachment {
+            id,
+            name: name.to_string(),
+            mime_type: mime_type.to_string(),
+            size: data.len() as u64,
+        })
+    }
+
+    /// Read a stored file.
+    pub fn get(&self, id: &str) -> Result<Vec<u8>, AttachmentError> {
+        let path = self.path(id)?;
+        std::fs::read(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => AttachmentError::NotFound(id.to_string()),
+            _ => AttachmentError::Io(e),
+        })
+    }
+
+    /// Path of a stored file on disk.
+    pub fn path(&self, id: &str) -> Result<PathBuf, AttachmentError> {
+        // Ids are SHA-256 hex digests; anything else could escape the directory
+        if id.len() != 64 || !id.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(AttachmentError::InvalidId(id.to_string()));
+        }
+        Ok(self.dir.join(id))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Default for AttachmentStore {
+    fn default() -> Self {
+        Self::new(Self::default_dir())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_and_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AttachmentStore::new(dir.path());
+
+        let attachment = store.put("notes.txt", "text/plain", b"hello").unwrap();
+        assert_eq!(attachment.size, 5);
+        assert_eq!(attachment.id.len(), 64);
+        assert_eq!(store.get(&attachment.id).unwrap(), b"hello");
+
+        // Same content, same blob
+        let again = store.put("copy.txt", "text/plain", b"hello").unwrap();
+        assert_eq!(again.id, attachment.id);
+        assert_eq!(again.name, "copy.txt");
+    }
+
+    #[test]
+    fn test_rejects_bad_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AttachmentStore::new(dir.path());
+
+        assert!(matches!(
+            store.get("../secrets"),
+            Err(AttachmentError::InvalidId(_))
+        ));
+        assert!(matches!(
+            store.get(&"0".repeat(64)),
+            Err(AttachmentError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_oversized() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AttachmentStore::new(dir.path());
+        let data = vec![0u8; MAX_ATTACHMENT_BYTES + 1];
+        assert!(matches!(
+            store.put("big.bin", "application/octet-stream", &data),
+            Err(AttachmentError::TooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_describe_attachments() {
+        let attachment = Attachment {
+            id: "ab".repeat(32),
+            name: "report.pdf".to_string(),
+            mime_type: "application/pdf".to_string(),
+            size: 2048,
+        };
+        assert_eq!(describe_attachments("hi", &[]), "hi");
+        assert_eq!(
+            describe_attachments("summarize this", std::slice::from_ref(&attachment)),
+            format!(
+                "[Attachment: report.pdf (application/pdf, 2 KB) id={}]\nsummarize this",
+                attachment.id
+            )
+        );
+    }
+}
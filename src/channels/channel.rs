@@ -7,6 +7,7 @@ use chrono::{DateTime, Utc};
 use futures::Stream;
 use uuid::Uuid;
 
+use crate::channels::{Attachment, FormattingCapabilities};
 use crate::error::ChannelError;
 
 /// A message received from an external channel.
@@ -28,6 +29,10 @@ pub struct IncomingMessage {
     pub received_at: DateTime<Utc>,
     /// Channel-specific metadata.
     pub metadata: serde_json::Value,
+    /// Files sent with the message.
+    pub attachments: Vec<Attachment>,
+    /// The platform's own ID for this message, used to drop redeliveries.
+    pub external_id: Option<String>,
 }
 
 impl IncomingMessage {
@@ -46,6 +51,8 @@ impl IncomingMessage {
             thread_id: None,
             received_at: Utc::now(),
             metadata: serde_json::Value::Null,
+            attachments: Vec::new(),
+            external_id: None,
         }
     }
 
@@ -66,6 +73,18 @@ impl IncomingMessage {
         self.user_name = Some(name.into());
         self
     }
+
+    /// Set attachments.
+    pub fn with_attachments(mut self, attachments: Vec<Attachment>) -> Self {
+        self.attachments = attachments;
+        self
+    }
+
+    /// Set the platform's message ID.
+    pub fn with_external_id(mut self, id: impl Into<String>) -> Self {
+        self.external_id = Some(id.into());
+        self
+    }
 }
 
 /// Stream of incoming messages.
@@ -80,6 +99,8 @@ pub struct OutgoingResponse {
     pub thread_id: Option<String>,
     /// Channel-specific metadata for the response.
     pub metadata: serde_json::Value,
+    /// Files to send with the response.
+    pub attachments: Vec<Attachment>,
 }
 
 impl OutgoingResponse {
@@ -89,9 +110,16 @@ impl OutgoingResponse {
             content: content.into(),
             thread_id: None,
             metadata: serde_json::Value::Null,
+            attachments: Vec::new(),
         }
     }
 
+    /// Attach a file to the response.
+    pub fn with_attachment(mut self, attachment: Attachment) -> Self {
+        self.attachments.push(attachment);
+        self
+    }
+
     /// Set the thread ID for the response.
     pub fn in_thread(mut self, thread_id: impl Into<String>) -> Self {
         self.thread_id = Some(thread_id.into());
@@ -105,11 +133,19 @@ pub enum StatusUpdate {
     /// Agent is thinking/processing.
     Thinking(String),
     /// Tool execution started.
-    ToolStarted { name: String },
+    ToolStarted {
+        name: String,
+        parameters: serde_json::Value,
+    },
     /// Tool execution completed.
     ToolCompleted { name: String, success: bool },
-    /// Brief preview of tool execution output.
-    ToolResult { name: String, preview: String },
+    /// Tool execution output: a one-line preview and the (size-capped)
+    /// full output for channels that can show it on demand.
+    ToolResult {
+        name: String,
+        preview: String,
+        output: String,
+    },
     /// Streaming text chunk.
     StreamChunk(String),
     /// General status message.
@@ -195,6 +231,19 @@ pub trait Channel: Send + Sync {
         Ok(())
     }
 
+    /// Whether the channel only delivers notifications and never receives
+    /// messages (push services). Notifications and approval requests are
+    /// copied to these channels whichever channel they were meant for.
+    fn is_outbound_only(&self) -> bool {
+        false
+    }
+
+    /// Describe what the channel can display, so responses can be adapted
+    /// before they are sent.
+    async fn formatting(&self) -> FormattingCapabilities {
+        FormattingCapabilities::default()
+    }
+
     /// Check if the channel is healthy.
     async fn health_check(&self) -> Result<(), ChannelError>;
 
@@ -0,0 +1,546 @@
+//! Email channel for talking to the agent from any mail client.
+//!
+//! New mail in an IMAP mailbox is polled, filtered to allowed senders and
+//! emitted as messages. The conversation is keyed on the first Message-ID in
+//! the `References` chain, so a whole email thread maps to one agent thread.
+//! Replies go out over SMTP with `In-Reply-To` and `References` set, which
+//! keeps them threaded in the sender's mail client.
+//!
+//! Processed mail is marked `\Seen`. A dedicated mailbox (or address) for
+//! the agent is recommended so it doesn't mark personal mail as read.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use lettre::message::Mailbox;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use mail_parser::MessageParser;
+use secrecy::ExposeSecret;
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, mpsc};
+use tokio::task::JoinHandle;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::channels::{
+    Channel, FormattingCapabilities, IncomingMessage, MarkdownFlavor, MessageStream,
+    OutgoingResponse,
+};
+use crate::config::EmailConfig;
+use crate::error::ChannelError;
+
+const CHANNEL_NAME: &str = "email";
+
+/// Maximum content length taken from a single email, after quoted text is
+/// stripped.
+const MAX_CONTENT_BYTES: usize = 32 * 1024;
+
+/// Email channel: IMAP polling in, SMTP out.
+pub struct EmailChannel {
+    config: Arc<EmailConfig>,
+    smtp: AsyncSmtpTransport<Tokio1Executor>,
+    poller: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl EmailChannel {
+    /// Create the channel. Fails if the SMTP relay can't be configured.
+    pub fn new(config: EmailConfig) -> Result<Self, ChannelError> {
+        let startup_failed = |reason: String| ChannelError::StartupFailed {
+            name: CHANNEL_NAME.to_string(),
+            reason,
+        };
+
+        // Port 465 is implicit TLS; anything else upgrades with STARTTLS
+        let builder = if config.smtp_port == 465 {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
+        }
+        .map_err(|e| startup_failed(format!("invalid SMTP host: {e}")))?;
+
+        let smtp = builder
+            .port(config.smtp_port)
+            .credentials(Credentials::new(
+                config.username.clone(),
+                config.password.expose_secret().to_string(),
+            ))
+            .build();
+
+        Ok(Self {
+            config: Arc::new(config),
+            smtp,
+            poller: Mutex::new(None),
+        })
+    }
+
+    /// Send a plain-text email.
+    async fn send(&self, email: Message) -> Result<(), ChannelError> {
+        self.smtp
+            .send(email)
+            .await
+            .map(|_| ())
+            .map_err(|e| ChannelError::SendFailed {
+                name: CHANNEL_NAME.to_string(),
+                reason: e.to_string(),
+            })
+    }
+}
+
+#[async_trait]
+impl Channel for EmailChannel {
+    fn name(&self) -> &str {
+        CHANNEL_NAME
+    }
+
+    async fn start(&self) -> Result<MessageStream, ChannelError> {
+        if self.config.allowed_senders.is_empty() {
+            return Err(ChannelError::StartupFailed {
+                name: CHANNEL_NAME.to_string(),
+                reason: "EMAIL_ALLOWED_SENDERS is required (use '*' to allow anyone)".to_string(),
+            });
+        }
+
+        let (tx, rx) = mpsc::channel(64);
+        let config = Arc::clone(&self.config);
+        let handle = tokio::spawn(poll_loop(config, tx));
+        if let Some(old) = self.poller.lock().await.replace(handle) {
+            old.abort();
+        }
+
+        tracing::info!(
+            mailbox = %self.config.mailbox,
+            host = %self.config.imap_host,
+            "Email channel polling every {:?}",
+            self.config.poll_interval
+        );
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+
+    async fn respond(
+        &self,
+        msg: &IncomingMessage,
+        response: OutgoingResponse,
+    ) -> Result<(), ChannelError> {
+        let subject = msg
+            .metadata
+            .get("subject")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        let message_id = msg.metadata.get("message_id").and_then(|v| v.as_str());
+        let references = msg
+            .metadata
+            .get("references")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+
+        let mut builder = Message::builder()
+            .from(parse_address(&self.config.from_address)?)
+            .to(parse_address(&msg.user_id)?)
+            .subject(reply_subject(subject))
+            .header(ContentType::TEXT_PLAIN);
+        if let Some(id) = message_id {
+            builder = builder
+                .in_reply_to(id.to_string())
+                .references(reply_references(references, id));
+        }
+
+        let email = builder
+            .body(response.content)
+            .map_err(|e| ChannelError::SendFailed {
+                name: CHANNEL_NAME.to_string(),
+                reason: e.to_string(),
+            })?;
+        self.send(email).await
+    }
+
+    async fn broadcast(
+        &self,
+        user_id: &str,
+        response: OutgoingResponse,
+    ) -> Result<(), ChannelError> {
+        // Only mail people who are allowed to write to the agent
+        if !sender_allowed(&self.config.allowed_senders, user_id) {
+            return Err(ChannelError::SendFailed {
+                name: CHANNEL_NAME.to_string(),
+                reason: format!("{user_id} is not an allowed sender"),
+            });
+        }
+
+        let email = Message::builder()
+            .from(parse_address(&self.config.from_address)?)
+            .to(parse_address(user_id)?)
+            .subject("Message from IronClaw")
+            .header(ContentType::TEXT_PLAIN)
+            .body(response.content)
+            .map_err(|e| ChannelError::SendFailed {
+                name: CHANNEL_NAME.to_string(),
+                reason: e.to_string(),
+            })?;
+        self.send(email).await
+    }
+
+    async fn formatting(&self) -> FormattingCapabilities {
+        FormattingCapabilities {
+            markdown: MarkdownFlavor::Plain,
+            ..FormattingCapabilities::default()
+        }
+    }
+
+    async fn health_check(&self) -> Result<(), ChannelError> {
+        match self.poller.lock().await.as_ref() {
+            Some(handle) if !handle.is_finished() => Ok(()),
+            _ => Err(ChannelError::HealthCheckFailed {
+                name: CHANNEL_NAME.to_string(),
+            }),
+        }
+    }
+
+    async fn shutdown(&self) -> Result<(), ChannelError> {
+        if let Some(handle) = self.poller.lock().await.take() {
+            handle.abort();
+        }
+        Ok(())
+    }
+}
+
+/// Poll the mailbox until the receiver goes away.
+async fn poll_loop(config: Arc<EmailConfig>, tx: mpsc::Sender<IncomingMessage>) {
+    let mut interval = tokio::time::interval(config.poll_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    // Mail from senders we ignore stays unread; remember it so it isn't
+    // fetched on every poll.
+    let mut skipped = HashSet::new();
+
+    loop {
+        interval.tick().await;
+        if tx.is_closed() {
+            return;
+        }
+
+        let messages = match fetch_new_mail(&config, &mut skipped).await {
+            Ok(messages) => messages,
+            Err(e) => {
+                tracing::warn!(error = %e, "Email poll failed");
+                continue;
+            }
+        };
+
+        for message in messages {
+            if tx.send(message).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Fetch unseen mail, returning messages from allowed senders and marking
+/// them seen.
+async fn fetch_new_mail(
+    config: &EmailConfig,
+    skipped: &mut HashSet<u32>,
+) -> Result<Vec<IncomingMessage>, String> {
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let tls = TlsConnector::from(Arc::new(
+        ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    ));
+    let server_name = ServerName::try_from(config.imap_host.clone())
+        .map_err(|e| format!("invalid IMAP host: {e}"))?;
+
+    let tcp = TcpStream::connect((config.imap_host.as_str(), config.imap_port))
+        .await
+        .map_err(|e| format!("IMAP connect failed: {e}"))?;
+    let stream = tls
+        .connect(server_name, tcp)
+        .await
+        .map_err(|e| format!("IMAP TLS handshake failed: {e}"))?;
+
+    let mut session = async_imap::Client::new(stream)
+        .login(&config.username, config.password.expose_secret())
+        .await
+        .map_err(|(e, _)| format!("IMAP login failed: {e}"))?;
+    session
+        .select(&config.mailbox)
+        .await
+        .map_err(|e| format!("IMAP select failed: {e}"))?;
+
+    let unseen: Vec<u32> = session
+        .uid_search("UNSEEN")
+        .await
+        .map_err(|e| format!("IMAP search failed: {e}"))?
+        .into_iter()
+        .filter(|uid| !skipped.contains(uid))
+        .collect();
+
+    let mut messages = Vec::new();
+    if !unseen.is_empty() {
+        let fetches: Vec<_> = session
+            .uid_fetch(uid_set(&unseen), "(UID BODY.PEEK[])")
+            .await
+            .map_err(|e| format!("IMAP fetch failed: {e}"))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("IMAP fetch failed: {e}"))?;
+
+        let mut accepted = Vec::new();
+        for fetch in &fetches {
+            let Some(uid) = fetch.uid else { continue };
+            match fetch
+                .body()
+                .and_then(|raw| parse_incoming(raw, &config.allowed_senders))
+            {
+                Some(message) => {
+                    accepted.push(uid);
+                    messages.push(message);
+                }
+                None => {
+                    skipped.insert(uid);
+                }
+            }
+        }
+
+        if !accepted.is_empty() {
+            let _: Vec<_> = session
+                .uid_store(uid_set(&accepted), "+FLAGS (\\Seen)")
+                .await
+                .map_err(|e| format!("IMAP store failed: {e}"))?
+                .try_collect()
+                .await
+                .map_err(|e| format!("IMAP store failed: {e}"))?;
+        }
+    }
+
+    if let Err(e) = session.logout().await {
+        tracing::debug!(error = %e, "IMAP logout failed");
+    }
+
+    Ok(messages)
+}
+
+/// Parse an address for an outgoing email.
+fn parse_address(address: &str) -> Result<Mailbox, ChannelError> {
+    address.parse().map_err(|e| ChannelError::SendFailed {
+        name: CHANNEL_NAME.to_string(),
+        reason: format!("invalid address '{address}': {e}"),
+    })
+}
+
+/// Comma-separated UID set for IMAP commands.
+fn uid_set(uids: &[u32]) -> String {
+    uids.iter()
+        .map(|uid| uid.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Turn a raw RFC 5322 message into an incoming message, or `None` if it
+/// should be ignored (disallowed sender, auto-reply, no text).
+fn parse_incoming(raw: &[u8], allowed_senders: &[String]) -> Option<IncomingMessage> {
+    let email = MessageParser::default().parse(raw)?;
+
+    // Never answer auto-replies and bounces; that's how mail loops start
+    if email
+        .header_raw("Auto-Submitted")
+        .is_some_and(|v| !v.trim().eq_ignore_ascii_case("no"))
+    {
+        return None;
+    }
+
+    let from = email.from().and_then(|a| a.first())?;
+    let address = from.address()?.to_lowercase();
+    if !sender_allowed(allowed_senders, &address) {
+        tracing::info!(sender = %address, "Ignoring email from a sender not allowed");
+        return None;
+    }
+
+    let subject = email.subject().unwrap_or("").trim().to_string();
+    let message_id = email
+        .header_raw("Message-ID")
+        .and_then(|v| message_ids(v).into_iter().next());
+    let in_reply_to = email.header_raw("In-Reply-To").map(message_ids);
+    let references = email.header_raw("References").map(message_ids);
+
+    let body = email
+        .body_text(0)
+        .map(|text| strip_quoted_reply(&text))
+        .unwrap_or_default();
+    // A new email often carries the request in its subject
+    let mut content = if in_reply_to.is_none() && !subject.is_empty() {
+        format!("{subject}\n\n{body}").trim().to_string()
+    } else {
+        body
+    };
+    if content.is_empty() {
+        return None;
+    }
+    if content.len() > MAX_CONTENT_BYTES {
+        let mut end = MAX_CONTENT_BYTES;
+        while !content.is_char_boundary(end) {
+            end -= 1;
+        }
+        content.truncate(end);
+    }
+
+    let thread = references
+        .as_ref()
+        .and_then(|ids| ids.first())
+        .or_else(|| in_reply_to.as_ref().and_then(|ids| ids.first()))
+        .or(message_id.as_ref())
+        .cloned();
+
+    let mut message =
+        IncomingMessage::new(CHANNEL_NAME, address, content).with_metadata(serde_json::json!({
+            "subject": subject,
+            "message_id": message_id,
+            "references": references.unwrap_or_default().join(" "),
+        }));
+    if let Some(name) = from.name() {
+        message = message.with_user_name(name);
+    }
+    if let Some(thread) = thread {
+        message = message.with_thread(thread);
+    }
+    Some(message)
+}
+
+/// Whether a sender is allowed: `*`, the exact address, or `@domain`.
+fn sender_allowed(allowed: &[String], address: &str) -> bool {
+    let address = address.to_lowercase();
+    allowed.iter().any(|entry| {
+        entry == "*"
+            || *entry == address
+            || (entry.starts_with('@') && address.ends_with(entry.as_str()))
+    })
+}
+
+/// Message IDs in a header value, keeping their angle brackets.
+fn message_ids(value: &str) -> Vec<String> {
+    value
+        .split('<')
+        .skip(1)
+        .filter_map(|part| part.split_once('>'))
+        .map(|(id, _)| id.trim())
+        .filter(|id| !id.is_empty())
+        .map(|id| format!("<{id}>"))
+        .collect()
+}
+
+/// `References` for a reply: the original chain plus the message replied to.
+fn reply_references(references: &str, message_id: &str) -> String {
+    if references.is_empty() {
+        message_id.to_string()
+    } else {
+        format!("{references} {message_id}")
+    }
+}
+
+/// Subject for a reply, adding `Re:` once.
+fn reply_subject(subject: &str) -> String {
+    let trimmed = subject.trim();
+    if trimmed.is_empty() {
+        "Re: your message".to_string()
+    } else if trimmed
+        .get(..3)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("re:"))
+    {
+        trimmed.to_string()
+    } else {
+        format!("Re: {trimmed}")
+    }
+}
+
+/// Drop quoted history and signatures from a reply, keeping the new text.
+fn strip_quoted_reply(text: &str) -> String {
+    let mut kept = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let quote_starts = trimmed.starts_with('>')
+            || (trimmed.starts_with("On ") && trimmed.ends_with("wrote:"))
+            || trimmed.starts_with("-----Original Message-----")
+            || line.trim_end() == "--";
+        if quote_starts {
+            break;
+        }
+        kept.push(line);
+    }
+    kept.join("\n").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_ids_and_threading() {
+        assert_eq!(
+            message_ids(" <a@x.com>\r\n <b@y.com> "),
+            vec!["<a@x.com>", "<b@y.com>"]
+        );
+        assert!(message_ids("none here").is_empty());
+        assert_eq!(reply_references("", "<a@x>"), "<a@x>");
+        assert_eq!(
+            reply_references("<a@x> <b@x>", "<c@x>"),
+            "<a@x> <b@x> <c@x>"
+        );
+        assert_eq!(reply_subject("Lunch?"), "Re: Lunch?");
+        assert_eq!(reply_subject("RE: Lunch?"), "RE: Lunch?");
+    }
+
+    #[test]
+    fn test_strip_quoted_reply() {
+        let text = "Sounds good, go ahead.\n\n\
+            On Mon, Jan 6, 2025 at 9:00 AM Agent <a@x.com> wrote:\n\
+            > Shall I book it?";
+        assert_eq!(strip_quoted_reply(text), "Sounds good, go ahead.");
+        assert_eq!(strip_quoted_reply("Thanks\n-- \nAlice"), "Thanks");
+        assert_eq!(strip_quoted_reply("> only quotes"), "");
+    }
+
+    #[test]
+    fn test_sender_allowed() {
+        let allowed = vec!["alice@example.com".to_string(), "@corp.com".to_string()];
+        assert!(sender_allowed(&allowed, "Alice@Example.com"));
+        assert!(sender_allowed(&allowed, "bob@corp.com"));
+        assert!(!sender_allowed(&allowed, "mallory@evil.com"));
+        assert!(sender_allowed(&["*".to_string()], "anyone@anywhere.net"));
+        assert!(!sender_allowed(&[], "alice@example.com"));
+    }
+
+    #[test]
+    fn test_parse_incoming_reply() {
+        let raw = b"From: Alice <alice@example.com>\r\n\
+To: agent@example.com\r\n\
+Subject: Re: Trip plans\r\n\
+Message-ID: <m3@example.com>\r\n\
+In-Reply-To: <m2@example.com>\r\n\
+References: <m1@example.com> <m2@example.com>\r\n\
+Content-Type: text/plain\r\n\
+\r\n\
+Book the earlier flight.\r\n\
+\r\n\
+> Which flight?\r\n";
+        let allowed = vec!["alice@example.com".to_string()];
+
+        let message = parse_incoming(raw, &allowed).unwrap();
+        assert_eq!(message.user_id, "alice@example.com");
+        assert_eq!(message.user_name.as_deref(), Some("Alice"));
+        assert_eq!(message.content, "Book the earlier flight.");
+        assert_eq!(message.thread_id.as_deref(), Some("<m1@example.com>"));
+        assert_eq!(message.metadata["message_id"], "<m3@example.com>");
+        assert_eq!(
+            message.metadata["references"],
+            "<m1@example.com> <m2@example.com>"
+        );
+
+        assert!(parse_incoming(raw, &["bob@example.com".to_string()]).is_none());
+    }
+}
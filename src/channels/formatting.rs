@@ -0,0 +1,465 @@
+//! Adapting agent responses to what each channel can display.
+//!
+//! The agent writes GitHub-flavored Markdown. Channels declare a
+//! [`FormattingCapabilities`] describing the markup they render, how long a
+//! message may be and which extras (buttons, threads, files) they support,
+//! and [`format_response`] rewrites each response to fit before it is sent.
+
+use serde::{Deserialize, Serialize};
+
+use crate::channels::markdown_inline::{self, Inline};
+use crate::channels::{OutgoingResponse, describe_attachments};
+
+/// Markup a channel renders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkdownFlavor {
+    /// No markup; formatting characters are shown as typed.
+    Plain,
+    /// GitHub-flavored Markdown, sent unchanged.
+    #[default]
+    Markdown,
+    /// Slack's mrkdwn (`*bold*`, `_italic_`, `<url|label>`).
+    SlackMrkdwn,
+}
+
+/// What a channel can display.
+///
+/// The default describes the built-in channels: Markdown of any length,
+/// replies in threads, no buttons or files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormattingCapabilities {
+    /// Markup the channel renders.
+    pub markdown: MarkdownFlavor,
+    /// Longest message the channel accepts, in characters.
+    pub max_message_length: Option<usize>,
+    /// Whether `buttons` in response metadata are shown as buttons.
+    pub supports_buttons: bool,
+    /// Whether replies can go to a thread.
+    pub supports_threads: bool,
+    /// Whether the channel can send attachments.
+    pub supports_files: bool,
+}
+
+impl Default for FormattingCapabilities {
+    fn default() -> Self {
+        Self {
+            markdown: MarkdownFlavor::Markdown,
+            max_message_length: None,
+            supports_buttons: false,
+            supports_threads: true,
+            supports_files: false,
+        }
+    }
+}
+
+/// Rewrite a response for a channel, splitting it into several messages if
+/// it is too long.
+///
+/// Buttons and attachments the channel can't show are described in the text
+/// instead. They go with the last message so they follow the full answer.
+pub fn format_response(
+    mut response: OutgoingResponse,
+    capabilities: &FormattingCapabilities,
+) -> Vec<OutgoingResponse> {
+    if !capabilities.supports_threads {
+        response.thread_id = None;
+    }
+    if !capabilities.supports_files && !response.attachments.is_empty() {
+        let attachments = std::mem::take(&mut response.attachments);
+        let files = describe_attachments("", &attachments);
+        response.content = join_paragraphs(&response.content, &files);
+    }
+    if !capabilities.supports_buttons {
+        if let Some(choices) = take_buttons(&mut response.metadata) {
+            response.content = join_paragraphs(&response.content, &choices);
+        }
+    }
+
+    let content = convert(&response.content, capabilities.markdown);
+    let pieces = match capabilities.max_message_length {
+        Some(max) => split_message(&content, max),
+        None => vec![content],
+    };
+
+    let last = pieces.len() - 1;
+    pieces
+        .into_iter()
+        .enumerate()
+        .map(|(i, content)| {
+            if i == last {
+                OutgoingResponse {
+                    content,
+                    ..response.clone()
+                }
+            } else {
+                OutgoingResponse {
+                    content,
+                    thread_id: response.thread_id.clone(),
+                    metadata: without_buttons(&response.metadata),
+                    attachments: Vec::new(),
+                }
+            }
+        })
+        .collect()
+}
+
+fn join_paragraphs(content: &str, extra: &str) -> String {
+    if content.is_empty() {
+        extra.to_string()
+    } else {
+        format!("{}\n\n{}", content, extra)
+    }
+}
+
+/// Remove `buttons` from metadata, returning them as a line of text.
+fn take_buttons(metadata: &mut serde_json::Value) -> Option<String> {
+    let rows = metadata.as_object_mut()?.remove("buttons")?;
+    let choices: Vec<String> = rows
+        .as_array()?
+        .iter()
+        .filter_map(|row| row.as_array())
+        .flatten()
+        .filter_map(|button| {
+            let text = button.get("text")?.as_str()?;
+            let data = button.get("data")?.as_str()?;
+            Some(if text.eq_ignore_ascii_case(data) {
+                text.to_string()
+            } else {
+                format!("{} ({})", text, data)
+            })
+        })
+        .collect();
+    (!choices.is_empty()).then(|| format!("Reply with: {}", choices.join(", ")))
+}
+
+fn without_buttons(metadata: &serde_json::Value) -> serde_json::Value {
+    let mut metadata = metadata.clone();
+    if let Some(obj) = metadata.as_object_mut() {
+        obj.remove("buttons");
+    }
+    metadata
+}
+
+/// Convert agent Markdown to `flavor`.
+pub fn convert(text: &str, flavor: MarkdownFlavor) -> String {
+    if flavor == MarkdownFlavor::Markdown {
+        return text.to_string();
+    }
+
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+    for line in text.split('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            // Slack renders fences but not language tags; plain text drops them
+            if flavor == MarkdownFlavor::SlackMrkdwn {
+                lines.push("```".to_string());
+            }
+            continue;
+        }
+        if in_code_block {
+            lines.push(escape(line, flavor));
+            continue;
+        }
+
+        let indent = &line[..line.len() - trimmed.len()];
+        let converted = if let Some(heading) = strip_heading(trimmed) {
+            let heading = convert_inline(heading, flavor);
+            match flavor {
+                MarkdownFlavor::SlackMrkdwn => format!("*{}*", heading),
+                _ => heading,
+            }
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+        {
+            format!("{}• {}", indent, convert_inline(item, flavor))
+        } else {
+            format!("{}{}", indent, convert_inline(trimmed, flavor))
+        };
+        lines.push(converted);
+    }
+    lines.join("\n")
+}
+
+fn strip_heading(line: &str) -> Option<&str> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&level) {
+        line[level..].strip_prefix(' ').map(str::trim)
+    } else {
+        None
+    }
+}
+
+/// Slack treats `&`, `<` and `>` as control characters.
+fn escape(text: &str, flavor: MarkdownFlavor) -> String {
+    match flavor {
+        MarkdownFlavor::SlackMrkdwn => text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;"),
+        _ => text.to_string(),
+    }
+}
+
+/// Convert inline markup: code spans, bold, italics, strikethrough and
+/// links. Markers without a partner are left as typed.
+fn convert_inline(text: &str, flavor: MarkdownFlavor) -> String {
+    let mut out = String::with_capacity(text.len());
+    render_inline(&markdown_inline::parse(text), flavor, &mut out);
+    out
+}
+
+fn render_inline(nodes: &[Inline], flavor: MarkdownFlavor, out: &mut String) {
+    let slack = flavor == MarkdownFlavor::SlackMrkdwn;
+    for node in nodes {
+        let (marker, inner) = match node {
+            Inline::Text(text) => {
+                out.push_str(&escape(text, flavor));
+                continue;
+            }
+            Inline::Code(code) => {
+                if slack {
+                    out.push('`');
+                    out.push_str(&escape(code, flavor));
+                    out.push('`');
+                } else {
+                    out.push_str(code);
+                }
+                continue;
+            }
+            Inline::Link { label, url } => {
+                let mut text = String::new();
+                render_inline(label, flavor, &mut text);
+                if slack {
+                    out.push_str(&format!("<{}|{}>", url, text));
+                } else if text == *url {
+                    out.push_str(url);
+                } else {
+                    out.push_str(&format!("{} ({})", text, url));
+                }
+                continue;
+            }
+            Inline::Bold(inner) => ("*", inner),
+            Inline::Italic(inner) => ("_", inner),
+            Inline::Strike(inner) => ("~", inner),
+        };
+        let marker = if slack { marker } else { "" };
+        out.push_str(marker);
+        render_inline(inner, flavor, out);
+        out.push_str(marker);
+    }
+}
+
+/// Split text into pieces of at most `max_len` characters, breaking at
+/// paragraphs, then lines, then spaces. A code block cut in two is closed
+/// and reopened so each piece renders on its own.
+pub fn split_message(text: &str, max_len: usize) -> Vec<String> {
+    if text.chars().count() <= max_len {
+        return vec![text.to_string()];
+    }
+
+    // Leave room to close and reopen a code block
+    let budget = max_len.saturating_sub(8).max(1);
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for paragraph in text.split("\n\n") {
+        for part in split_long(paragraph, budget, "\n") {
+            if !current.is_empty() && len(&current) + 2 + len(&part) > budget {
+                pieces.push(std::mem::take(&mut current));
+            } else if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(&part);
+        }
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    balance_fences(pieces)
+}
+
+fn len(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Split at `separator`, falling back to spaces and then hard cuts for
+/// stretches longer than `budget`.
+fn split_long(text: &str, budget: usize, separator: &str) -> Vec<String> {
+    if len(text) <= budget {
+        return vec![text.to_string()];
+    }
+
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    for segment in text.split(separator) {
+        let segments = if len(segment) > budget {
+            match separator {
+                "\n" => split_long(segment, budget, " "),
+                _ => hard_split(segment, budget),
+            }
+        } else {
+            vec![segment.to_string()]
+        };
+        for segment in segments {
+            if !current.is_empty() && len(&current) + separator.len() + len(&segment) > budget {
+                parts.push(std::mem::take(&mut current));
+            } else if !current.is_empty() {
+                current.push_str(separator);
+            }
+            current.push_str(&segment);
+        }
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+fn hard_split(text: &str, budget: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(budget)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+fn balance_fences(pieces: Vec<String>) -> Vec<String> {
+    let mut open = false;
+    pieces
+        .into_iter()
+        .map(|piece| {
+            let mut out = String::new();
+            if open {
+                out.push_str("```\n");
+            }
+            let fences = piece
+                .lines()
+                .filter(|line| line.trim_start().starts_with("```"))
+                .count();
+            open ^= fences % 2 == 1;
+            out.push_str(&piece);
+            if open {
+                out.push_str("\n```");
+            }
+            out
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::Attachment;
+
+    #[test]
+    fn test_convert_to_plain() {
+        let text = "# Summary\n**Done**: see [docs](https://x.io) and `cargo test`.\n- item_one";
+        assert_eq!(
+            convert(text, MarkdownFlavor::Plain),
+            "Summary\nDone: see docs (https://x.io) and cargo test.\n• item_one"
+        );
+        assert_eq!(convert(text, MarkdownFlavor::Markdown), text);
+    }
+
+    #[test]
+    fn test_convert_to_slack() {
+        assert_eq!(
+            convert(
+                "**bold** *it* ~~old~~ [docs](https://x.io) a < b",
+                MarkdownFlavor::SlackMrkdwn
+            ),
+            "*bold* _it_ ~old~ <https://x.io|docs> a &lt; b"
+        );
+        assert_eq!(
+            convert("```rust\nif a > b {}\n```", MarkdownFlavor::SlackMrkdwn),
+            "```\nif a &gt; b {}\n```"
+        );
+    }
+
+    #[test]
+    fn test_split_message() {
+        let paragraph = "word ".repeat(20);
+        let text = [paragraph.trim(); 6].join("\n\n");
+        let pieces = split_message(&text, 250);
+        assert!(pieces.len() > 1);
+        assert!(pieces.iter().all(|p| len(p) <= 250));
+        assert_eq!(pieces.join("\n\n"), text);
+
+        let code = format!("```\n{}\n```", "line\n".repeat(80).trim_end());
+        let pieces = split_message(&code, 100);
+        assert!(pieces.iter().all(|p| len(p) <= 100));
+        assert!(
+            pieces
+                .iter()
+                .all(|p| p.starts_with("```\n") && p.ends_with("\n```"))
+        );
+    }
+
+    #[test]
+    fn test_format_response_without_extras() {
+        let attachment = Attachment {
+            id: "ab".repeat(32),
+            name: "report.pdf".to_string(),
+            mime_type: "application/pdf".to_string(),
+            size: 10,
+        };
+        let mut response = OutgoingResponse::text("Approve?")
+            .in_thread("t1")
+            .with_attachment(attachment);
+        response.metadata = serde_json::json!({
+            "chat_id": 1,
+            "buttons": [[{"text": "Approve", "data": "yes"}, {"text": "Deny", "data": "no"}]],
+        });
+
+        let capabilities = FormattingCapabilities {
+            markdown: MarkdownFlavor::Plain,
+            max_message_length: None,
+            supports_buttons: false,
+            supports_threads: false,
+            supports_files: false,
+        };
+        let pieces = format_response(response.clone(), &capabilities);
+        assert_eq!(pieces.len(), 1);
+        let piece = &pieces[0];
+        assert!(piece.thread_id.is_none());
+        assert!(piece.attachments.is_empty());
+        assert!(piece.metadata.get("buttons").is_none());
+        assert_eq!(piece.metadata["chat_id"], 1);
+        assert!(piece.content.contains("[Attachment: report.pdf"));
+        assert!(
+            piece
+                .content
+                .ends_with("Reply with: Approve (yes), Deny (no)")
+        );
+
+        // A capable channel gets the response unchanged
+        let capabilities = FormattingCapabilities {
+            supports_buttons: true,
+            supports_files: true,
+            ..Default::default()
+        };
+        let pieces = format_response(response.clone(), &capabilities);
+        assert_eq!(pieces[0].content, "Approve?");
+        assert_eq!(pieces[0].thread_id.as_deref(), Some("t1"));
+        assert_eq!(pieces[0].attachments.len(), 1);
+    }
+
+    #[test]
+    fn test_extras_go_with_last_piece() {
+        let mut response = OutgoingResponse::text("a".repeat(30) + "\n\n" + &"b".repeat(30));
+        response.metadata = serde_json::json!({ "buttons": [[{"text": "OK", "data": "ok"}]] });
+        let capabilities = FormattingCapabilities {
+            max_message_length: Some(40),
+            supports_buttons: true,
+            ..Default::default()
+        };
+        let pieces = format_response(response, &capabilities);
+        assert_eq!(pieces.len(), 2);
+        assert!(pieces[0].metadata.get("buttons").is_none());
+        assert!(pieces[1].metadata.get("buttons").is_some());
+    }
+}
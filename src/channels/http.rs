@@ -1,24 +1,44 @@
 //! HTTP webhook channel for receiving messages via HTTP POST.
-
+//!
+//! Besides the request/response webhook, clients can follow a conversation
+//! live: `GET /stream/{thread_id}` (SSE) and `GET /ws/{thread_id}`
+//! (WebSocket) deliver tool-call progress, status updates and each finished
+//! response for that thread. Responses arrive whole, not token by token. The
+//! WebSocket also accepts messages, so a web client can chat over a single
+//! connection.
+//!
+//! `GET /oauth/callback` completes one-click tool authorization links the
+//! agent sends when a tool is missing its credential.
+
+use std::convert::Infallible;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use axum::{
     Json, Router,
-    extract::{DefaultBodyLimit, State},
-    http::StatusCode,
-    response::IntoResponse,
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
+    response::{Html, IntoResponse, Response},
     routing::{get, post},
 };
+use futures::{SinkExt, StreamExt};
 use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
-use tokio::sync::{RwLock, mpsc, oneshot};
-use tokio_stream::wrappers::ReceiverStream;
+use tokio::sync::{RwLock, broadcast, mpsc, oneshot};
+use tokio_stream::wrappers::{BroadcastStream, ReceiverStream};
 use uuid::Uuid;
 
-use crate::channels::{Channel, IncomingMessage, MessageStream, OutgoingResponse};
+use crate::channels::{
+    Attachment, AttachmentError, AttachmentStore, Channel, IncomingMessage, MessageStream,
+    OutgoingResponse, StatusUpdate,
+};
 use crate::config::HttpConfig;
 use crate::error::ChannelError;
+use crate::extensions::oauth::{CALLBACK_PATH, OAuthFlows};
 
 /// HTTP webhook channel.
 pub struct HttpChannel {
@@ -37,6 +57,14 @@ struct HttpChannelState {
     user_id: String,
     /// Rate limiting state.
     rate_limit: tokio::sync::Mutex<RateLimitState>,
+    /// Events for streaming (SSE/WebSocket) clients, filtered by thread.
+    events: broadcast::Sender<StreamEvent>,
+    /// Number of open streaming connections.
+    stream_connections: Arc<AtomicUsize>,
+    /// Where uploaded attachments are stored.
+    attachments: AttachmentStore,
+    /// Tool authorization flows completed at the OAuth callback.
+    oauth: Option<Arc<OAuthFlows>>,
 }
 
 #[derive(Debug)]
@@ -45,8 +73,12 @@ struct RateLimitState {
     request_count: u32,
 }
 
-/// Maximum JSON body size for webhook requests (64 KB).
-const MAX_BODY_BYTES: usize = 64 * 1024;
+/// Maximum JSON body size for webhook requests (8 MB, room for base64
+/// encoded attachments).
+const MAX_BODY_BYTES: usize = 8 * 1024 * 1024;
+
+/// Maximum number of attachments on a single webhook request.
+const MAX_ATTACHMENTS_PER_REQUEST: usize = 10;
 
 /// Maximum number of pending wait-for-response requests.
 const MAX_PENDING_RESPONSES: usize = 100;
@@ -57,6 +89,122 @@ const MAX_REQUESTS_PER_MINUTE: u32 = 60;
 /// Maximum content length for a single message.
 const MAX_CONTENT_BYTES: usize = 32 * 1024;
 
+/// Maximum number of concurrent SSE/WebSocket connections.
+const MAX_STREAM_CONNECTIONS: usize = 20;
+
+/// Events buffered for streaming clients; slow clients miss older events.
+const STREAM_BUFFER: usize = 256;
+
+/// An event delivered to streaming clients following a thread.
+#[derive(Debug, Clone, Serialize)]
+struct StreamEvent {
+    thread_id: String,
+    #[serde(flatten)]
+    kind: StreamEventKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEventKind {
+    Thinking {
+        message: String,
+    },
+    ToolStarted {
+        name: String,
+    },
+    ToolCompleted {
+        name: String,
+        success: bool,
+    },
+    ToolResult {
+        name: String,
+        preview: String,
+    },
+    Status {
+        message: String,
+    },
+    JobStarted {
+        job_id: String,
+        title: String,
+    },
+    ApprovalNeeded {
+        request_id: String,
+        tool_name: String,
+        description: String,
+        parameters: serde_json::Value,
+    },
+    /// Final response to a message.
+    Response {
+        message_id: Uuid,
+        content: String,
+    },
+}
+
+impl StreamEventKind {
+    /// Map an agent status update; auth prompts are not relevant to HTTP
+    /// clients, and partial text is left out because responses are sent whole.
+    fn from_status(status: StatusUpdate) -> Option<Self> {
+        Some(match status {
+            StatusUpdate::Thinking(message) => Self::Thinking { message },
+            StatusUpdate::ToolStarted { name, .. } => Self::ToolStarted { name },
+            StatusUpdate::ToolCompleted { name, success } => Self::ToolCompleted { name, success },
+            StatusUpdate::ToolResult { name, preview, .. } => Self::ToolResult { name, preview },
+            StatusUpdate::Status(message) => Self::Status { message },
+            StatusUpdate::JobStarted { job_id, title, .. } => Self::JobStarted { job_id, title },
+            StatusUpdate::ApprovalNeeded {
+                request_id,
+                tool_name,
+                description,
+                parameters,
+            } => Self::ApprovalNeeded {
+                request_id,
+                tool_name,
+                description,
+                parameters,
+            },
+            StatusUpdate::StreamChunk(_)
+            | StatusUpdate::AuthRequired { .. }
+            | StatusUpdate::AuthCompleted { .. } => {
+                return None;
+            }
+        })
+    }
+
+    /// SSE event name.
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Thinking { .. } => "thinking",
+            Self::ToolStarted { .. } => "tool_started",
+            Self::ToolCompleted { .. } => "tool_completed",
+            Self::ToolResult { .. } => "tool_result",
+            Self::Status { .. } => "status",
+            Self::JobStarted { .. } => "job_started",
+            Self::ApprovalNeeded { .. } => "approval_needed",
+            Self::Response { .. } => "response",
+        }
+    }
+}
+
+/// Holds one streaming connection slot; released on drop.
+struct StreamSlot(Arc<AtomicUsize>);
+
+impl StreamSlot {
+    fn acquire(counter: &Arc<AtomicUsize>) -> Option<Self> {
+        counter
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                (current < MAX_STREAM_CONNECTIONS).then_some(current + 1)
+            })
+            .ok()?;
+        Some(Self(Arc::clone(counter)))
+    }
+}
+
+impl Drop for StreamSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 impl HttpChannel {
     /// Create a new HTTP channel.
     pub fn new(config: HttpConfig) -> Self {
@@ -65,6 +213,7 @@ impl HttpChannel {
             .as_ref()
             .map(|s| s.expose_secret().to_string());
         let user_id = config.user_id.clone();
+        let (events, _) = broadcast::channel(STREAM_BUFFER);
 
         Self {
             config,
@@ -77,10 +226,23 @@ impl HttpChannel {
                     window_start: std::time::Instant::now(),
                     request_count: 0,
                 }),
+                events,
+                stream_connections: Arc::new(AtomicUsize::new(0)),
+                attachments: AttachmentStore::default(),
+                oauth: None,
             }),
         }
     }
 
+    /// Complete tool authorization links at `GET /oauth/callback`.
+    pub fn with_oauth(mut self, flows: Arc<OAuthFlows>) -> Self {
+        // The state is only shared once `routes()` or `start()` is called
+        if let Some(state) = Arc::get_mut(&mut self.state) {
+            state.oauth = Some(flows);
+        }
+        self
+    }
+
     /// Return the channel's axum routes with state applied.
     ///
     /// The returned `Router` shares the same `Arc<HttpChannelState>` that
@@ -90,6 +252,9 @@ impl HttpChannel {
         Router::new()
             .route("/health", get(health_handler))
             .route("/webhook", post(webhook_handler))
+            .route("/stream/{thread_id}", get(stream_handler))
+            .route("/ws/{thread_id}", get(ws_handler))
+            .route(CALLBACK_PATH, get(oauth_callback_handler))
             .layer(DefaultBodyLimit::max(MAX_BODY_BYTES))
             .with_state(self.state.clone())
     }
@@ -109,11 +274,29 @@ struct WebhookRequest {
     content: String,
     /// Optional thread ID for conversation tracking.
     thread_id: Option<String>,
+    /// Optional client-chosen message ID; a retry with the same ID within
+    /// the dedup window is dropped instead of answered twice.
+    #[serde(default)]
+    message_id: Option<String>,
     /// Optional webhook secret for authentication.
     secret: Option<String>,
     /// Whether to wait for a synchronous response.
     #[serde(default)]
     wait_for_response: bool,
+    /// Files sent with the message.
+    #[serde(default)]
+    attachments: Vec<WebhookAttachment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookAttachment {
+    /// File name.
+    name: String,
+    /// MIME type; defaults to application/octet-stream.
+    #[serde(default)]
+    mime_type: Option<String>,
+    /// File contents, base64 encoded.
+    data: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -139,28 +322,30 @@ async fn health_handler() -> impl IntoResponse {
     })
 }
 
+/// Count a request against the per-minute limit; false if it is exceeded.
+async fn within_rate_limit(state: &HttpChannelState) -> bool {
+    let mut limiter = state.rate_limit.lock().await;
+    if limiter.window_start.elapsed() >= std::time::Duration::from_secs(60) {
+        limiter.window_start = std::time::Instant::now();
+        limiter.request_count = 0;
+    }
+    limiter.request_count += 1;
+    limiter.request_count <= MAX_REQUESTS_PER_MINUTE
+}
+
 async fn webhook_handler(
     State(state): State<Arc<HttpChannelState>>,
     Json(req): Json<WebhookRequest>,
 ) -> (StatusCode, Json<WebhookResponse>) {
-    // Rate limiting
-    {
-        let mut limiter = state.rate_limit.lock().await;
-        if limiter.window_start.elapsed() >= std::time::Duration::from_secs(60) {
-            limiter.window_start = std::time::Instant::now();
-            limiter.request_count = 0;
-        }
-        limiter.request_count += 1;
-        if limiter.request_count > MAX_REQUESTS_PER_MINUTE {
-            return (
-                StatusCode::TOO_MANY_REQUESTS,
-                Json(WebhookResponse {
-                    message_id: Uuid::nil(),
-                    status: "error".to_string(),
-                    response: Some("Rate limit exceeded".to_string()),
-                }),
-            );
-        }
+    if !within_rate_limit(&state).await {
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(WebhookResponse {
+                message_id: Uuid::nil(),
+                status: "error".to_string(),
+                response: Some("Rate limit exceeded".to_string()),
+            }),
+        );
     }
 
     let _ = req.user_id.as_ref().map(|user_id| {
@@ -210,11 +395,29 @@ async fn webhook_handler(
         );
     }
 
-    let msg = IncomingMessage::new("http", &state.user_id, &req.content).with_metadata(
-        serde_json::json!({
+    let attachments = match store_attachments(&state.attachments, req.attachments).await {
+        Ok(attachments) => attachments,
+        Err((status, reason)) => {
+            return (
+                status,
+                Json(WebhookResponse {
+                    message_id: Uuid::nil(),
+                    status: "error".to_string(),
+                    response: Some(reason),
+                }),
+            );
+        }
+    };
+
+    let mut msg = IncomingMessage::new("http", &state.user_id, &req.content)
+        .with_metadata(serde_json::json!({
             "wait_for_response": req.wait_for_response,
-        }),
-    );
+            "thread_id": req.thread_id,
+        }))
+        .with_attachments(attachments);
+    if let Some(message_id) = req.message_id.filter(|id| !id.is_empty()) {
+        msg = msg.with_external_id(message_id);
+    }
 
     if let Some(thread_id) = &req.thread_id {
         let msg = msg.with_thread(thread_id);
@@ -224,6 +427,56 @@ async fn webhook_handler(
     process_message(state, msg, req.wait_for_response).await
 }
 
+/// Decode and store uploaded attachments.
+async fn store_attachments(
+    store: &AttachmentStore,
+    uploads: Vec<WebhookAttachment>,
+) -> Result<Vec<Attachment>, (StatusCode, String)> {
+    use base64::Engine;
+
+    if uploads.len() > MAX_ATTACHMENTS_PER_REQUEST {
+        return Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Too many attachments (max {})", MAX_ATTACHMENTS_PER_REQUEST),
+        ));
+    }
+
+    let mut attachments = Vec::with_capacity(uploads.len());
+    for upload in uploads {
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(&upload.data)
+            .map_err(|_| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Attachment {} is not valid base64", upload.name),
+                )
+            })?;
+        let mime_type = upload
+            .mime_type
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+
+        let store = store.clone();
+        let result =
+            tokio::task::spawn_blocking(move || store.put(&upload.name, &mime_type, &data))
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        match result {
+            Ok(attachment) => attachments.push(attachment),
+            Err(e @ AttachmentError::TooLarge { .. }) => {
+                return Err((StatusCode::PAYLOAD_TOO_LARGE, e.to_string()));
+            }
+            Err(e) => {
+                tracing::error!("Failed to store HTTP attachment: {}", e);
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to store attachment".to_string(),
+                ));
+            }
+        }
+    }
+    Ok(attachments)
+}
+
 async fn process_message(
     state: Arc<HttpChannelState>,
     msg: IncomingMessage,
@@ -300,6 +553,226 @@ async fn process_message(
     )
 }
 
+/// Query parameters of an OAuth redirect.
+#[derive(Debug, Deserialize)]
+struct OAuthCallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+}
+
+/// `GET /oauth/callback`: finish a tool authorization link.
+///
+/// The `state` parameter ties the redirect to the flow the agent started, so
+/// no webhook secret is needed.
+async fn oauth_callback_handler(
+    State(state): State<Arc<HttpChannelState>>,
+    Query(query): Query<OAuthCallbackQuery>,
+) -> (StatusCode, Html<String>) {
+    if !within_rate_limit(&state).await {
+        return oauth_page(
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many requests. Try again later.",
+        );
+    }
+    let Some(ref flows) = state.oauth else {
+        return oauth_page(StatusCode::NOT_FOUND, "Tool authorization is not enabled.");
+    };
+    if let Some(error) = query.error {
+        return oauth_page(
+            StatusCode::BAD_REQUEST,
+            &format!("Authorization was not granted ({}).", error),
+        );
+    }
+    let (Some(code), Some(flow_state)) = (query.code, query.state) else {
+        return oauth_page(StatusCode::BAD_REQUEST, "Missing code or state.");
+    };
+
+    match flows.complete(&flow_state, &code).await {
+        Ok(display) => oauth_page(
+            StatusCode::OK,
+            &format!(
+                "{} connected. You can close this tab and return to the chat.",
+                display
+            ),
+        ),
+        Err(e) => {
+            tracing::warn!("OAuth callback failed: {}", e);
+            oauth_page(StatusCode::BAD_REQUEST, &e)
+        }
+    }
+}
+
+/// A minimal page for the browser tab the provider redirected.
+fn oauth_page(status: StatusCode, message: &str) -> (StatusCode, Html<String>) {
+    let message = message
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    (
+        status,
+        Html(format!(
+            "<!doctype html><html><head><title>IronClaw</title></head>\
+             <body><p>{}</p></body></html>",
+            message
+        )),
+    )
+}
+
+/// Check the webhook secret on a streaming request.
+///
+/// Accepts `Authorization: Bearer <secret>` or `X-Webhook-Secret: <secret>`.
+fn stream_authorized(state: &HttpChannelState, headers: &HeaderMap) -> bool {
+    let Some(ref expected) = state.webhook_secret else {
+        return false;
+    };
+    let provided = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| {
+            headers
+                .get("x-webhook-secret")
+                .and_then(|v| v.to_str().ok())
+        });
+    provided == Some(expected.as_str())
+}
+
+/// Subscribe to the events of one thread.
+fn thread_events(
+    state: &HttpChannelState,
+    thread_id: String,
+) -> impl futures::Stream<Item = StreamEvent> + Send + 'static + use<> {
+    BroadcastStream::new(state.events.subscribe()).filter_map(move |event| {
+        futures::future::ready(event.ok().filter(|e| e.thread_id == thread_id))
+    })
+}
+
+/// `GET /stream/{thread_id}`: server-sent events for one conversation.
+async fn stream_handler(
+    State(state): State<Arc<HttpChannelState>>,
+    Path(thread_id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    if !stream_authorized(&state, &headers) {
+        return (StatusCode::UNAUTHORIZED, "Invalid webhook secret").into_response();
+    }
+    let Some(slot) = StreamSlot::acquire(&state.stream_connections) else {
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many streaming connections",
+        )
+            .into_response();
+    };
+
+    let stream = thread_events(&state, thread_id).map(move |event| {
+        // The slot lives as long as the stream
+        let _slot = &slot;
+        let data = serde_json::to_string(&event).unwrap_or_default();
+        Ok::<_, Infallible>(Event::default().event(event.kind.name()).data(data))
+    });
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::new().interval(Duration::from_secs(30)).text(""))
+        .into_response()
+}
+
+/// A message sent by a WebSocket client.
+#[derive(Debug, Deserialize)]
+struct WsChatMessage {
+    content: String,
+}
+
+/// `GET /ws/{thread_id}`: WebSocket carrying the thread's events; text
+/// frames of the form `{"content": "..."}` are sent to the agent.
+async fn ws_handler(
+    State(state): State<Arc<HttpChannelState>>,
+    Path(thread_id): Path<String>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Response {
+    if !stream_authorized(&state, &headers) {
+        return (StatusCode::UNAUTHORIZED, "Invalid webhook secret").into_response();
+    }
+    let Some(slot) = StreamSlot::acquire(&state.stream_connections) else {
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many streaming connections",
+        )
+            .into_response();
+    };
+
+    ws.on_upgrade(move |socket| handle_ws(socket, state, thread_id, slot))
+}
+
+/// Validate a WebSocket chat frame and send it to the agent.
+async fn forward_ws_message(
+    state: &HttpChannelState,
+    thread_id: &str,
+    text: &str,
+) -> Result<(), String> {
+    let chat: WsChatMessage =
+        serde_json::from_str(text).map_err(|e| format!("Invalid message: {}", e))?;
+    if !within_rate_limit(state).await {
+        return Err("Rate limit exceeded".to_string());
+    }
+    if chat.content.len() > MAX_CONTENT_BYTES {
+        return Err("Content too large".to_string());
+    }
+
+    let msg = IncomingMessage::new("http", &state.user_id, &chat.content)
+        .with_thread(thread_id)
+        .with_metadata(serde_json::json!({ "thread_id": thread_id }));
+    let tx_guard = state.tx.read().await;
+    let tx = tx_guard
+        .as_ref()
+        .ok_or_else(|| "Channel not started".to_string())?;
+    tx.send(msg).await.map_err(|_| "Channel closed".to_string())
+}
+
+async fn handle_ws(
+    socket: WebSocket,
+    state: Arc<HttpChannelState>,
+    thread_id: String,
+    _slot: StreamSlot,
+) {
+    let (mut sink, mut incoming) = socket.split();
+    let mut events = Box::pin(thread_events(&state, thread_id.clone()));
+    let (error_tx, mut error_rx) = mpsc::channel::<String>(8);
+
+    let sender = tokio::spawn(async move {
+        loop {
+            let json = tokio::select! {
+                event = events.next() => match event {
+                    Some(event) => serde_json::to_string(&event).unwrap_or_default(),
+                    None => break,
+                },
+                error = error_rx.recv() => match error {
+                    Some(message) => serde_json::json!({ "type": "error", "message": message })
+                        .to_string(),
+                    None => break,
+                },
+            };
+            if sink.send(Message::Text(json.into())).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Some(Ok(frame)) = incoming.next().await {
+        let text = match frame {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        if let Err(message) = forward_ws_message(&state, &thread_id, &text).await {
+            let _ = error_tx.send(message).await;
+        }
+    }
+
+    sender.abort();
+}
+
 #[async_trait]
 impl Channel for HttpChannel {
     fn name(&self) -> &str {
@@ -331,6 +804,17 @@ impl Channel for HttpChannel {
         msg: &IncomingMessage,
         response: OutgoingResponse,
     ) -> Result<(), ChannelError> {
+        // Streaming clients following the thread get the response as an event
+        if let Some(ref thread_id) = msg.thread_id {
+            let _ = self.state.events.send(StreamEvent {
+                thread_id: thread_id.clone(),
+                kind: StreamEventKind::Response {
+                    message_id: msg.id,
+                    content: response.content.clone(),
+                },
+            });
+        }
+
         // Check if there's a pending response waiter
         if let Some(tx) = self.state.pending_responses.write().await.remove(&msg.id) {
             let _ = tx.send(response.content);
@@ -338,6 +822,24 @@ impl Channel for HttpChannel {
         Ok(())
     }
 
+    async fn send_status(
+        &self,
+        status: StatusUpdate,
+        metadata: &serde_json::Value,
+    ) -> Result<(), ChannelError> {
+        let Some(thread_id) = metadata.get("thread_id").and_then(|v| v.as_str()) else {
+            return Ok(());
+        };
+        if let Some(kind) = StreamEventKind::from_status(status) {
+            // No subscribers is fine
+            let _ = self.state.events.send(StreamEvent {
+                thread_id: thread_id.to_string(),
+                kind,
+            });
+        }
+        Ok(())
+    }
+
     async fn health_check(&self) -> Result<(), ChannelError> {
         if self.state.tx.read().await.is_some() {
             Ok(())
@@ -371,4 +873,96 @@ mod tests {
         let result = channel.start().await;
         assert!(result.is_err());
     }
+
+    fn test_channel() -> HttpChannel {
+        HttpChannel::new(HttpConfig {
+            host: "127.0.0.1".to_string(),
+            port: 0,
+            webhook_secret: Some(secrecy::SecretString::from("s3cret".to_string())),
+            user_id: "http".to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_status_and_response_stream_to_thread() {
+        let channel = test_channel();
+        let mut events = Box::pin(thread_events(&channel.state, "t1".to_string()));
+
+        let metadata = serde_json::json!({ "thread_id": "t1" });
+        channel
+            .send_status(
+                StatusUpdate::Status("other".into()),
+                &serde_json::json!({ "thread_id": "t2" }),
+            )
+            .await
+            .unwrap();
+        channel
+            .send_status(
+                StatusUpdate::ToolStarted {
+                    name: "http".into(),
+                    parameters: serde_json::json!({}),
+                },
+                &metadata,
+            )
+            .await
+            .unwrap();
+        let msg = IncomingMessage::new("http", "http", "hi").with_thread("t1");
+        channel
+            .respond(&msg, OutgoingResponse::text("hello"))
+            .await
+            .unwrap();
+
+        let first = events.next().await.unwrap();
+        assert_eq!(first.kind.name(), "tool_started");
+        let second = serde_json::to_value(events.next().await.unwrap()).unwrap();
+        assert_eq!(second["type"], "response");
+        assert_eq!(second["thread_id"], "t1");
+        assert_eq!(second["content"], "hello");
+    }
+
+    #[test]
+    fn test_stream_auth_and_slots() {
+        let channel = test_channel();
+        let mut headers = HeaderMap::new();
+        assert!(!stream_authorized(&channel.state, &headers));
+        headers.insert("authorization", "Bearer s3cret".parse().unwrap());
+        assert!(stream_authorized(&channel.state, &headers));
+        headers.insert("authorization", "Bearer nope".parse().unwrap());
+        assert!(!stream_authorized(&channel.state, &headers));
+
+        let slots: Vec<_> = (0..MAX_STREAM_CONNECTIONS)
+            .map(|_| StreamSlot::acquire(&channel.state.stream_connections).unwrap())
+            .collect();
+        assert!(StreamSlot::acquire(&channel.state.stream_connections).is_none());
+        drop(slots);
+        assert!(StreamSlot::acquire(&channel.state.stream_connections).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_store_attachments() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AttachmentStore::new(dir.path());
+        let upload = |data: &str| WebhookAttachment {
+            name: "notes.txt".to_string(),
+            mime_type: None,
+            data: data.to_string(),
+        };
+
+        let stored = store_attachments(&store, vec![upload("aGVsbG8=")])
+            .await
+            .unwrap();
+        assert_eq!(stored[0].mime_type, "application/octet-stream");
+        assert_eq!(store.get(&stored[0].id).unwrap(), b"hello");
+
+        let (status, _) = store_attachments(&store, vec![upload("not base64!")])
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let too_many = (0..=MAX_ATTACHMENTS_PER_REQUEST)
+            .map(|_| upload("aGVsbG8="))
+            .collect();
+        let (status, _) = store_attachments(&store, too_many).await.unwrap_err();
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
 }
@@ -6,7 +6,9 @@ use std::sync::Arc;
 use futures::stream;
 use tokio::sync::RwLock;
 
-use crate::channels::{Channel, IncomingMessage, MessageStream, OutgoingResponse, StatusUpdate};
+use crate::channels::{
+    Channel, FormattingCapabilities, IncomingMessage, MessageStream, OutgoingResponse, StatusUpdate,
+};
 use crate::error::ChannelError;
 
 /// Manages multiple input channels and merges their message streams.
@@ -93,6 +95,23 @@ impl ChannelManager {
         metadata: &serde_json::Value,
     ) -> Result<(), ChannelError> {
         let channels = self.channels.read().await;
+
+        // Someone away from the chat should still hear that the agent is
+        // waiting on them
+        if matches!(
+            status,
+            StatusUpdate::ApprovalNeeded { .. } | StatusUpdate::AuthRequired { .. }
+        ) {
+            for (name, channel) in channels.iter() {
+                if name != channel_name
+                    && channel.is_outbound_only()
+                    && let Err(e) = channel.send_status(status.clone(), metadata).await
+                {
+                    tracing::warn!("Failed to push status to {}: {}", name, e);
+                }
+            }
+        }
+
         if let Some(channel) = channels.get(channel_name) {
             channel.send_status(status, metadata).await
         } else {
@@ -101,6 +120,15 @@ impl ChannelManager {
         }
     }
 
+    /// Formatting capabilities of a channel (defaults if it isn't registered).
+    pub async fn formatting(&self, channel_name: &str) -> FormattingCapabilities {
+        let channels = self.channels.read().await;
+        match channels.get(channel_name) {
+            Some(channel) => channel.formatting().await,
+            None => FormattingCapabilities::default(),
+        }
+    }
+
     /// Broadcast a message to a specific user on a specific channel.
     ///
     /// Used for proactive notifications like heartbeat alerts.
@@ -163,6 +191,16 @@ impl ChannelManager {
         Ok(())
     }
 
+    /// Names of outbound-only (push notification) channels.
+    pub async fn outbound_only(&self) -> Vec<String> {
+        let channels = self.channels.read().await;
+        channels
+            .iter()
+            .filter(|(_, channel)| channel.is_outbound_only())
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
     /// Get list of channel names.
     pub async fn channel_names(&self) -> Vec<String> {
         self.channels.read().await.keys().cloned().collect()
@@ -0,0 +1,205 @@
+//! Inline Markdown parsing shared by the channel formatters.
+//!
+//! The host's channel formatting and the Telegram WASM channel both read the
+//! agent's inline markup here and render the result in their own markup. The
+//! Telegram channel includes this file with `#[path]`, so it uses nothing
+//! but std.
+
+/// A piece of inline Markdown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inline {
+    /// Text shown as typed, including markers without a partner.
+    Text(String),
+    /// A `code span`.
+    Code(String),
+    /// `**bold**` or `__bold__`.
+    Bold(Vec<Inline>),
+    /// `*italic*` or `_italic_`.
+    Italic(Vec<Inline>),
+    /// `~~strikethrough~~`.
+    Strike(Vec<Inline>),
+    /// `[label](url)`.
+    Link { label: Vec<Inline>, url: String },
+}
+
+/// Parse inline markup: code spans, bold, italics, strikethrough and links.
+/// A backslash before punctuation keeps it literal, and markers without a
+/// partner are left as text.
+pub fn parse(text: &str) -> Vec<Inline> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = Vec::new();
+    let mut literal = String::new();
+    let mut i = 0;
+
+    let flush = |literal: &mut String, out: &mut Vec<Inline>| {
+        if !literal.is_empty() {
+            out.push(Inline::Text(std::mem::take(literal)));
+        }
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let rest = &chars[i..];
+
+        if c == '\\' && rest.len() > 1 && rest[1].is_ascii_punctuation() {
+            literal.push(rest[1]);
+            i += 2;
+            continue;
+        }
+
+        let code_end = if c == '`' {
+            find(&chars, i + 1, &['`'])
+        } else {
+            None
+        };
+        if let Some(end) = code_end {
+            flush(&mut literal, &mut out);
+            out.push(Inline::Code(chars[i + 1..end].iter().collect()));
+            i = end + 1;
+            continue;
+        }
+
+        let paired = ["**", "__", "~~"].into_iter().find_map(|marker| {
+            let marker: Vec<char> = marker.chars().collect();
+            if !rest.starts_with(&marker) {
+                return None;
+            }
+            let end = find(&chars, i + 2, &marker).filter(|end| *end > i + 2)?;
+            Some((marker[0], end))
+        });
+        if let Some((marker, end)) = paired {
+            flush(&mut literal, &mut out);
+            let inner = parse(&chars[i + 2..end].iter().collect::<String>());
+            out.push(match marker {
+                '~' => Inline::Strike(inner),
+                _ => Inline::Bold(inner),
+            });
+            i = end + 2;
+            continue;
+        }
+
+        let italic_end = if (c == '*' || c == '_') && opens_italic(&chars, i) {
+            find_italic_close(&chars, i)
+        } else {
+            None
+        };
+        if let Some(end) = italic_end {
+            flush(&mut literal, &mut out);
+            let inner = parse(&chars[i + 1..end].iter().collect::<String>());
+            out.push(Inline::Italic(inner));
+            i = end + 1;
+            continue;
+        }
+
+        let link = if c == '[' {
+            parse_link(&chars, i)
+        } else {
+            None
+        };
+        if let Some((label, url, end)) = link {
+            flush(&mut literal, &mut out);
+            out.push(Inline::Link {
+                label: parse(&label),
+                url,
+            });
+            i = end;
+            continue;
+        }
+
+        literal.push(c);
+        i += 1;
+    }
+    flush(&mut literal, &mut out);
+    out
+}
+
+/// Index of the next occurrence of `pattern` at or after `from`.
+fn find(chars: &[char], from: usize, pattern: &[char]) -> Option<usize> {
+    (from..chars.len()).find(|&j| chars[j..].starts_with(pattern))
+}
+
+/// An italic marker opens at a word start and is followed by text, so
+/// `snake_case` and `2 * 3` stay literal.
+fn opens_italic(chars: &[char], i: usize) -> bool {
+    let before_ok = i == 0 || !chars[i - 1].is_alphanumeric();
+    let after_ok = chars
+        .get(i + 1)
+        .is_some_and(|c| !c.is_whitespace() && *c != chars[i]);
+    before_ok && after_ok
+}
+
+fn find_italic_close(chars: &[char], open: usize) -> Option<usize> {
+    let marker = chars[open];
+    (open + 2..chars.len()).find(|&j| {
+        chars[j] == marker
+            && !chars[j - 1].is_whitespace()
+            && chars.get(j + 1) != Some(&marker)
+            && !chars.get(j + 1).is_some_and(|c| c.is_alphanumeric())
+    })
+}
+
+/// Parse `[label](url)` starting at `open`, returning the end index.
+fn parse_link(chars: &[char], open: usize) -> Option<(String, String, usize)> {
+    let close = find(chars, open + 1, &[']'])?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    // URLs may contain balanced parentheses (e.g., Wikipedia links)
+    let mut depth = 0;
+    let url_end = (close + 2..chars.len()).find(|&j| match chars[j] {
+        '(' => {
+            depth += 1;
+            false
+        }
+        ')' if depth == 0 => true,
+        ')' => {
+            depth -= 1;
+            false
+        }
+        _ => false,
+    })?;
+    let url: String = chars[close + 2..url_end].iter().collect();
+    if url.is_empty() || url.contains(char::is_whitespace) {
+        return None;
+    }
+    let label: String = chars[open + 1..close].iter().collect();
+    Some((label, url, url_end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Inline {
+        Inline::Text(s.to_string())
+    }
+
+    #[test]
+    fn test_parse_inline() {
+        assert_eq!(
+            parse("**a _b_** `c` ~~d~~ [e](https://x.io/f_(g))"),
+            vec![
+                Inline::Bold(vec![text("a "), Inline::Italic(vec![text("b")])]),
+                text(" "),
+                Inline::Code("c".to_string()),
+                text(" "),
+                Inline::Strike(vec![text("d")]),
+                text(" "),
+                Inline::Link {
+                    label: vec![text("e")],
+                    url: "https://x.io/f_(g)".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unpaired_markers_stay_text() {
+        assert_eq!(
+            parse("snake_case and 2 * 3"),
+            vec![text("snake_case and 2 * 3")]
+        );
+        assert_eq!(parse(r"\*not italic\*"), vec![text("*not italic*")]);
+        assert_eq!(parse("[label] (url)"), vec![text("[label] (url)")]);
+    }
+}
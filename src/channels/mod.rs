@@ -1,6 +1,6 @@
 //! Multi-channel input system.
 //!
-//! Channels receive messages from external sources (CLI, HTTP, etc.)
+//! Channels receive messages from external sources (CLI, HTTP, email, etc.)
 //! and convert them to a unified message format for the agent to process.
 //!
 //! # Architecture
@@ -27,17 +27,35 @@
 //! WASM channels allow dynamic loading of channel implementations at runtime.
 //! See the [`wasm`] module for details.
 
+mod api;
+mod attachment;
 mod channel;
+mod email;
+mod formatting;
 mod http;
 mod manager;
+mod markdown_inline;
+mod notify;
+mod outbound;
+mod push;
+pub mod quiet_hours;
 mod repl;
 pub mod wasm;
 pub mod web;
 mod webhook_server;
 
+pub use api::ApiChannel;
+pub use attachment::{
+    Attachment, AttachmentError, AttachmentStore, MAX_ATTACHMENT_BYTES, describe_attachments,
+};
 pub use channel::{Channel, IncomingMessage, MessageStream, OutgoingResponse, StatusUpdate};
+pub use email::EmailChannel;
+pub use formatting::{FormattingCapabilities, MarkdownFlavor, format_response};
 pub use http::HttpChannel;
 pub use manager::ChannelManager;
+pub use notify::{NotificationRouter, NotifyTarget, QUIET_HOURS_CHECK_INTERVAL};
+pub use outbound::{DeliveryStatus, OutboundQueue, RetryPolicy};
+pub use push::PushChannel;
 pub use repl::ReplChannel;
 pub use web::GatewayChannel;
 pub use webhook_server::{WebhookServer, WebhookServerConfig};
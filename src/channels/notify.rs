@@ -0,0 +1,239 @@
+//! Routing for proactive notifications (heartbeat findings, routine alerts).
+//!
+//! Notifications go to an explicit channel and user when one is given, fall
+//! back to the configured defaults, and are otherwise broadcast on every
+//! channel so they reach someone instead of vanishing into logs. Outbound-only
+//! push channels (ntfy, Pushover) get a copy either way.
+//!
+//! During a user's quiet hours (see [`crate::channels::quiet_hours`]),
+//! non-urgent notifications are held in memory and delivered once the
+//! channel's quiet hours end.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::channels::quiet_hours::{QUIET_HOURS_KEY, QuietHours};
+use crate::channels::{ChannelManager, OutgoingResponse};
+use crate::config::QuietHoursConfig;
+use crate::db::Database;
+
+/// How often held notifications are checked for the end of quiet hours.
+pub const QUIET_HOURS_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Notifications held at once; the oldest are delivered early past this.
+const MAX_HELD: usize = 500;
+
+/// Where a notification should be delivered.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotifyTarget {
+    /// Channel to deliver on; every channel when unset.
+    pub channel: Option<String>,
+    /// User to deliver to; the default user when unset.
+    pub user: Option<String>,
+}
+
+impl NotifyTarget {
+    pub fn new(channel: Option<String>, user: Option<String>) -> Self {
+        Self { channel, user }
+    }
+
+    /// Target described by a routine's `notify` settings
+    /// (`{"channel": "...", "user": "..."}`).
+    pub fn from_json(value: &serde_json::Value) -> Self {
+        let field = |key: &str| value.get(key).and_then(|v| v.as_str()).map(String::from);
+        Self {
+            channel: field("channel"),
+            user: field("user"),
+        }
+    }
+
+    /// Fill unset fields from `fallback`.
+    pub fn or(self, fallback: &NotifyTarget) -> Self {
+        Self {
+            channel: self.channel.or_else(|| fallback.channel.clone()),
+            user: self.user.or_else(|| fallback.user.clone()),
+        }
+    }
+}
+
+/// A notification held until quiet hours end.
+struct Held {
+    channel: String,
+    user: String,
+    response: OutgoingResponse,
+    until: DateTime<Utc>,
+}
+
+/// Delivers notifications through the channel manager.
+#[derive(Clone)]
+pub struct NotificationRouter {
+    channels: Arc<ChannelManager>,
+    default_target: NotifyTarget,
+    /// Where users' quiet hours are stored; without it nothing is held.
+    store: Option<Arc<dyn Database>>,
+    quiet_hours: QuietHoursConfig,
+    held: Arc<Mutex<Vec<Held>>>,
+}
+
+impl NotificationRouter {
+    pub fn new(channels: Arc<ChannelManager>) -> Self {
+        Self {
+            channels,
+            default_target: NotifyTarget::default(),
+            store: None,
+            quiet_hours: QuietHoursConfig::default(),
+            held: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Deliver to `target` when a notification doesn't name its own.
+    pub fn with_default_target(mut self, target: NotifyTarget) -> Self {
+        self.default_target = target;
+        self
+    }
+
+    /// Hold non-urgent notifications during the quiet hours users set in
+    /// `store`.
+    pub fn with_quiet_hours(mut self, store: Arc<dyn Database>, config: QuietHoursConfig) -> Self {
+        self.store = Some(store);
+        self.quiet_hours = config;
+        self
+    }
+
+    /// Send `response` to `target`, filling gaps from the default target.
+    pub async fn notify(&self, target: NotifyTarget, response: OutgoingResponse) {
+        let target = target.or(&self.default_target);
+        let user = target.user.as_deref().unwrap_or("default");
+        let quiet = self.quiet_hours_for(user, &response).await;
+
+        let channels = match target.channel {
+            Some(ref channel) => {
+                let mut channels: Vec<String> = self
+                    .channels
+                    .outbound_only()
+                    .await
+                    .into_iter()
+                    .filter(|push| push != channel)
+                    .collect();
+                channels.push(channel.clone());
+                channels
+            }
+            None if quiet.is_some() => self.channels.channel_names().await,
+            None => {
+                for (channel, result) in self.channels.broadcast_all(user, response).await {
+                    if let Err(e) = result {
+                        tracing::warn!("Failed to broadcast notification to {}: {}", channel, e);
+                    }
+                }
+                return;
+            }
+        };
+        for channel in channels {
+            self.send_or_hold(quiet.as_ref(), &channel, user, response.clone())
+                .await;
+        }
+    }
+
+    /// Send `response` to the push channels only, for news that doesn't
+    /// warrant a message in the conversation channels (e.g. a routine run
+    /// finishing).
+    pub async fn push(&self, response: OutgoingResponse) {
+        let user = self.default_target.user.as_deref().unwrap_or("default");
+        let quiet = self.quiet_hours_for(user, &response).await;
+        for channel in self.channels.outbound_only().await {
+            self.send_or_hold(quiet.as_ref(), &channel, user, response.clone())
+                .await;
+        }
+    }
+
+    /// Deliver held notifications whose quiet hours have ended.
+    pub async fn release_held(&self) {
+        let now = Utc::now();
+        let due: Vec<Held> = {
+            let mut held = self.held.lock().unwrap_or_else(|e| e.into_inner());
+            let (due, waiting) = held.drain(..).partition(|h| h.until <= now);
+            *held = waiting;
+            due
+        };
+        for held in due {
+            self.send(&held.channel, &held.user, held.response).await;
+        }
+    }
+
+    /// `user`'s quiet hours, unless `response` is urgent enough to ignore
+    /// them.
+    async fn quiet_hours_for(&self, user: &str, response: &OutgoingResponse) -> Option<QuietHours> {
+        let store = self.store.as_ref()?;
+        let quiet = match store.get_setting_full(user, QUIET_HOURS_KEY).await {
+            Ok(record) => serde_json::from_value::<QuietHours>(record?.value)
+                .map_err(|e| tracing::warn!("Invalid quiet hours for {}: {}", user, e))
+                .ok()?,
+            Err(e) => {
+                tracing::warn!("Failed to load quiet hours for {}: {}", user, e);
+                return None;
+            }
+        };
+        (!quiet.is_urgent(response, &self.quiet_hours)).then_some(quiet)
+    }
+
+    /// Send now, or hold until `channel`'s quiet hours end.
+    async fn send_or_hold(
+        &self,
+        quiet: Option<&QuietHours>,
+        channel: &str,
+        user: &str,
+        mut response: OutgoingResponse,
+    ) {
+        let Some(until) = quiet.and_then(|q| q.quiet_until(channel, Utc::now())) else {
+            self.send(channel, user, response).await;
+            return;
+        };
+
+        response.content = format!("🌙 Held during quiet hours:\n\n{}", response.content);
+        let overflow = {
+            let mut held = self.held.lock().unwrap_or_else(|e| e.into_inner());
+            held.push(Held {
+                channel: channel.to_string(),
+                user: user.to_string(),
+                response,
+                until,
+            });
+            let excess = held.len().saturating_sub(MAX_HELD);
+            held.drain(..excess).collect::<Vec<_>>()
+        };
+        tracing::debug!("Notification for {}/{} held until {}", channel, user, until);
+        for held in overflow {
+            tracing::warn!("Too many held notifications, delivering the oldest early");
+            self.send(&held.channel, &held.user, held.response).await;
+        }
+    }
+
+    async fn send(&self, channel: &str, user: &str, response: OutgoingResponse) {
+        if let Err(e) = self.channels.broadcast(channel, user, response).await {
+            tracing::warn!("Failed to send notification to {}/{}: {}", channel, user, e);
+        } else {
+            tracing::debug!("Notification sent to {}/{}", channel, user);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notify_target_fallback() {
+        let configured = NotifyTarget::new(Some("telegram".to_string()), Some("alice".to_string()));
+
+        let routine = NotifyTarget::from_json(&serde_json::json!({"user": "bob"}));
+        assert_eq!(
+            routine.or(&configured),
+            NotifyTarget::new(Some("telegram".to_string()), Some("bob".to_string()))
+        );
+
+        let empty = NotifyTarget::from_json(&serde_json::json!({}));
+        assert_eq!(empty.or(&configured), configured);
+    }
+}
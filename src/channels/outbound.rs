@@ -0,0 +1,415 @@
+//! Outbound delivery of agent responses.
+//!
+//! A channel's `respond` can fail for reasons that clear up on their own: a
+//! network blip, a rate limit, a restarting bot API. Responses are queued per
+//! channel and retried with backoff, so a failing channel never holds up
+//! another and responses on one channel keep their order. A response that
+//! still can't be delivered is dead-lettered: recorded in `job_events` and
+//! kept so the user can ask for it again with `/redeliver`.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::channels::{ChannelManager, IncomingMessage, OutgoingResponse, format_response};
+use crate::history::Store;
+
+/// Dead letters kept in memory for `/redeliver`; older ones are dropped
+/// (they remain in `job_events`).
+const MAX_DEAD_LETTERS: usize = 100;
+
+/// Delivery receipts kept for status lookups.
+const MAX_RECEIPTS: usize = 1000;
+
+/// How hard to try before giving up on a response.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Attempts per response, including the first.
+    pub attempts: u32,
+    /// Delay before the first retry, doubled for each one after.
+    pub backoff: Duration,
+    /// Longest delay between retries.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 5,
+            backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Where a response is on its way to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// Queued or being retried.
+    Pending { attempts: u32 },
+    /// The channel accepted the response.
+    Delivered { attempts: u32 },
+    /// Every attempt failed; waiting for `/redeliver`.
+    DeadLettered { error: String },
+}
+
+struct Delivery {
+    message: IncomingMessage,
+    /// Messages to send, in order.
+    responses: Vec<OutgoingResponse>,
+    /// Whether `responses` were already adapted to the channel's formatting
+    /// (a dead letter is re-sent exactly as it was first tried).
+    formatted: bool,
+}
+
+/// Per-channel retry queue for agent responses.
+#[derive(Clone)]
+pub struct OutboundQueue {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    channels: Arc<ChannelManager>,
+    store: Option<Arc<Store>>,
+    policy: RetryPolicy,
+    /// One delivery worker per channel, started on first use.
+    workers: Mutex<HashMap<String, mpsc::UnboundedSender<Delivery>>>,
+    /// Status by incoming message ID, oldest first.
+    receipts: Mutex<VecDeque<(Uuid, DeliveryStatus)>>,
+    dead_letters: Mutex<VecDeque<Delivery>>,
+}
+
+impl OutboundQueue {
+    pub fn new(channels: Arc<ChannelManager>, store: Option<Arc<Store>>) -> Self {
+        Self::with_policy(channels, store, RetryPolicy::default())
+    }
+
+    pub fn with_policy(
+        channels: Arc<ChannelManager>,
+        store: Option<Arc<Store>>,
+        policy: RetryPolicy,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                channels,
+                store,
+                policy,
+                workers: Mutex::new(HashMap::new()),
+                receipts: Mutex::new(VecDeque::new()),
+                dead_letters: Mutex::new(VecDeque::new()),
+            }),
+        }
+    }
+
+    /// Queue `response` for delivery in reply to `message`.
+    pub fn send(&self, message: &IncomingMessage, response: OutgoingResponse) {
+        self.inner.enqueue(Delivery {
+            message: message.clone(),
+            responses: vec![response],
+            formatted: false,
+        });
+    }
+
+    /// Delivery status of the response to a message, if one was sent.
+    pub fn status(&self, message_id: Uuid) -> Option<DeliveryStatus> {
+        self.inner
+            .receipts
+            .lock()
+            .expect("receipts lock poisoned")
+            .iter()
+            .rev()
+            .find(|(id, _)| *id == message_id)
+            .map(|(_, status)| status.clone())
+    }
+
+    /// Queue every dead-lettered response for `user_id` again. Returns how
+    /// many were queued.
+    pub fn redeliver(&self, user_id: &str) -> usize {
+        let retry: Vec<Delivery> = {
+            let mut dead_letters = self
+                .inner
+                .dead_letters
+                .lock()
+                .expect("dead letter lock poisoned");
+            let (retry, keep): (VecDeque<_>, _) = dead_letters
+                .drain(..)
+                .partition(|d| d.message.user_id == user_id);
+            *dead_letters = keep;
+            Vec::from(retry)
+        };
+        let count = retry.len();
+        for delivery in retry {
+            self.inner.enqueue(delivery);
+        }
+        count
+    }
+}
+
+impl Inner {
+    fn enqueue(self: &Arc<Self>, delivery: Delivery) {
+        self.set_status(delivery.message.id, DeliveryStatus::Pending { attempts: 0 });
+
+        let mut workers = self.workers.lock().expect("workers lock poisoned");
+        let channel = delivery.message.channel.clone();
+        let delivery = match workers.get(&channel) {
+            Some(tx) => match tx.send(delivery) {
+                Ok(()) => return,
+                // The worker died; start a new one below
+                Err(mpsc::error::SendError(delivery)) => delivery,
+            },
+            None => delivery,
+        };
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let _ = tx.send(delivery);
+        workers.insert(channel, tx);
+        tokio::spawn(Arc::clone(self).run_worker(rx));
+    }
+
+    async fn run_worker(self: Arc<Self>, mut rx: mpsc::UnboundedReceiver<Delivery>) {
+        while let Some(delivery) = rx.recv().await {
+            self.deliver(delivery).await;
+        }
+    }
+
+    async fn deliver(&self, mut delivery: Delivery) {
+        if !delivery.formatted {
+            let capabilities = self.channels.formatting(&delivery.message.channel).await;
+            delivery.responses = std::mem::take(&mut delivery.responses)
+                .into_iter()
+                .flat_map(|response| format_response(response, &capabilities))
+                .collect();
+            delivery.formatted = true;
+        }
+
+        let mut attempts = 0;
+        let mut failure = None;
+        for (i, response) in delivery.responses.iter().enumerate() {
+            match self.send(&delivery.message, response).await {
+                Ok(n) => attempts = n,
+                Err(e) => {
+                    failure = Some((i, e));
+                    break;
+                }
+            }
+        }
+
+        match failure {
+            None => self.set_status(delivery.message.id, DeliveryStatus::Delivered { attempts }),
+            Some((sent, (attempts, error))) => {
+                // Keep the unsent rest together so a redelivery resumes where
+                // this one stopped
+                delivery.responses.drain(..sent);
+                self.dead_letter(delivery, attempts, error).await;
+            }
+        }
+    }
+
+    /// Send one message with retries, returning the attempts it took.
+    async fn send(
+        &self,
+        message: &IncomingMessage,
+        response: &OutgoingResponse,
+    ) -> Result<u32, (u32, String)> {
+        let message_id = message.id;
+        let mut backoff = self.policy.backoff;
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+            let error = match self.channels.respond(message, response.clone()).await {
+                Ok(()) => return Ok(attempts),
+                Err(e) => e.to_string(),
+            };
+
+            if attempts >= self.policy.attempts.max(1) {
+                return Err((attempts, error));
+            }
+
+            tracing::warn!(
+                "Response to {} on {} failed (attempt {}/{}), retrying in {:?}: {}",
+                message_id,
+                message.channel,
+                attempts,
+                self.policy.attempts,
+                backoff,
+                error
+            );
+            self.set_status(message_id, DeliveryStatus::Pending { attempts });
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(self.policy.max_backoff);
+        }
+    }
+
+    async fn dead_letter(&self, delivery: Delivery, attempts: u32, error: String) {
+        let message = &delivery.message;
+        tracing::error!(
+            "Giving up on response to {} on {} after {} attempts: {}",
+            message.id,
+            message.channel,
+            attempts,
+            error
+        );
+        self.set_status(
+            message.id,
+            DeliveryStatus::DeadLettered {
+                error: error.clone(),
+            },
+        );
+
+        if let Some(store) = &self.store {
+            let data = serde_json::json!({
+                "channel": message.channel,
+                "user_id": message.user_id,
+                "thread_id": message.thread_id,
+                "content": delivery
+                    .responses
+                    .iter()
+                    .map(|response| response.content.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n\n"),
+                "attempts": attempts,
+                "error": error,
+            });
+            if let Err(e) = store
+                .save_job_event(message.id, "delivery_failed", &data)
+                .await
+            {
+                tracing::warn!("Failed to record dead letter for {}: {}", message.id, e);
+            }
+        }
+
+        let mut dead_letters = self.dead_letters.lock().expect("dead letter lock poisoned");
+        if dead_letters.len() >= MAX_DEAD_LETTERS {
+            dead_letters.pop_front();
+        }
+        dead_letters.push_back(delivery);
+    }
+
+    fn set_status(&self, message_id: Uuid, status: DeliveryStatus) {
+        let mut receipts = self.receipts.lock().expect("receipts lock poisoned");
+        if let Some(entry) = receipts.iter_mut().rev().find(|(id, _)| *id == message_id) {
+            entry.1 = status;
+            return;
+        }
+        if receipts.len() >= MAX_RECEIPTS {
+            receipts.pop_front();
+        }
+        receipts.push_back((message_id, status));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::channels::{Channel, MessageStream};
+    use crate::error::ChannelError;
+
+    /// Fails the first `failures` responses, recording the ones it accepts.
+    struct FlakyChannel {
+        failures: AtomicU32,
+        delivered: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Channel for FlakyChannel {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn start(&self) -> Result<MessageStream, ChannelError> {
+            Ok(Box::pin(futures::stream::empty()))
+        }
+
+        async fn respond(
+            &self,
+            _msg: &IncomingMessage,
+            response: OutgoingResponse,
+        ) -> Result<(), ChannelError> {
+            let remaining = self.failures.load(Ordering::SeqCst);
+            if remaining > 0 {
+                self.failures.store(remaining - 1, Ordering::SeqCst);
+                return Err(ChannelError::SendFailed {
+                    name: "flaky".to_string(),
+                    reason: "network down".to_string(),
+                });
+            }
+            self.delivered.lock().unwrap().push(response.content);
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<(), ChannelError> {
+            Ok(())
+        }
+    }
+
+    fn queue(failures: u32, attempts: u32) -> (OutboundQueue, Arc<Mutex<Vec<String>>>) {
+        let delivered = Arc::new(Mutex::new(Vec::new()));
+        let mut channels = ChannelManager::new();
+        channels.add(Box::new(FlakyChannel {
+            failures: AtomicU32::new(failures),
+            delivered: Arc::clone(&delivered),
+        }));
+        let policy = RetryPolicy {
+            attempts,
+            backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+        };
+        let queue = OutboundQueue::with_policy(Arc::new(channels), None, policy);
+        (queue, delivered)
+    }
+
+    async fn settled(queue: &OutboundQueue, message_id: Uuid) -> DeliveryStatus {
+        for _ in 0..200 {
+            match queue.status(message_id) {
+                Some(DeliveryStatus::Pending { .. }) | None => {
+                    tokio::time::sleep(Duration::from_millis(5)).await
+                }
+                Some(status) => return status,
+            }
+        }
+        panic!("delivery did not settle");
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_delivered() {
+        let (queue, delivered) = queue(2, 5);
+        let message = IncomingMessage::new("flaky", "alice", "hi");
+
+        queue.send(&message, OutgoingResponse::text("hello"));
+        assert_eq!(
+            settled(&queue, message.id).await,
+            DeliveryStatus::Delivered { attempts: 3 }
+        );
+        assert_eq!(*delivered.lock().unwrap(), vec!["hello"]);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_and_redeliver() {
+        let (queue, delivered) = queue(2, 2);
+        let message = IncomingMessage::new("flaky", "alice", "hi");
+
+        queue.send(&message, OutgoingResponse::text("hello"));
+        assert!(matches!(
+            settled(&queue, message.id).await,
+            DeliveryStatus::DeadLettered { .. }
+        ));
+        assert!(delivered.lock().unwrap().is_empty());
+
+        assert_eq!(queue.redeliver("bob"), 0);
+        assert_eq!(queue.redeliver("alice"), 1);
+        assert_eq!(
+            settled(&queue, message.id).await,
+            DeliveryStatus::Delivered { attempts: 1 }
+        );
+        assert_eq!(*delivered.lock().unwrap(), vec!["hello"]);
+        assert_eq!(queue.redeliver("alice"), 0);
+    }
+}
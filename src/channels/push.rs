@@ -0,0 +1,281 @@
+//! Outbound-only push notification channels (ntfy and Pushover).
+//!
+//! These channels never receive messages. They deliver heartbeat findings,
+//! routine results and alerts, and approval requests as push notifications,
+//! so they reach the user's phone whatever channel the conversation is on.
+//! The [`NotificationRouter`](crate::channels::NotificationRouter) copies
+//! every notification to them.
+
+use async_trait::async_trait;
+use futures::stream;
+use secrecy::ExposeSecret;
+
+use crate::channels::{
+    Channel, FormattingCapabilities, IncomingMessage, MarkdownFlavor, MessageStream,
+    OutgoingResponse, StatusUpdate,
+};
+use crate::config::{NtfyConfig, PushoverConfig};
+use crate::error::ChannelError;
+
+const PUSHOVER_URL: &str = "https://api.pushover.net/1/messages.json";
+
+/// Title used when a notification doesn't start with one.
+const DEFAULT_TITLE: &str = "IronClaw";
+
+/// Longest first line treated as a title.
+const MAX_TITLE_CHARS: usize = 80;
+
+/// A notification as sent to a push service.
+#[derive(Debug, Clone, PartialEq)]
+struct Push {
+    title: String,
+    message: String,
+    /// Needs the user's attention (e.g. an approval the agent is waiting on).
+    urgent: bool,
+}
+
+impl Push {
+    fn from_response(response: &OutgoingResponse) -> Self {
+        let (title, message) = split_title(&response.content);
+        Self {
+            title: title.unwrap_or_else(|| DEFAULT_TITLE.to_string()),
+            message: message.to_string(),
+            urgent: false,
+        }
+    }
+
+    /// Push for statuses the user has to act on; `None` for progress updates.
+    fn from_status(status: &StatusUpdate) -> Option<Self> {
+        match status {
+            StatusUpdate::ApprovalNeeded {
+                tool_name,
+                description,
+                ..
+            } => Some(Self {
+                title: format!("Approval needed: {}", tool_name),
+                message: description.clone(),
+                urgent: true,
+            }),
+            StatusUpdate::AuthRequired {
+                extension_name,
+                instructions,
+                auth_url,
+                ..
+            } => {
+                let mut message = instructions
+                    .clone()
+                    .unwrap_or_else(|| format!("{} needs to be authenticated.", extension_name));
+                if let Some(url) = auth_url {
+                    message.push_str(&format!("\n{}", url));
+                }
+                Some(Self {
+                    title: format!("Authentication required: {}", extension_name),
+                    message,
+                    urgent: true,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Split a leading title line such as `🔔 *Heartbeat Alert*` off `content`.
+fn split_title(content: &str) -> (Option<String>, &str) {
+    let content = content.trim();
+    let Some((first, rest)) = content.split_once("\n\n") else {
+        return (None, content);
+    };
+    let first = first.trim();
+    if first.contains('\n') || first.chars().count() > MAX_TITLE_CHARS || !first.ends_with('*') {
+        return (None, content);
+    }
+    (Some(first.replace('*', "").trim().to_string()), rest.trim())
+}
+
+/// Cut `text` to at most `max` characters, marking the cut.
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+enum PushService {
+    Ntfy(NtfyConfig),
+    Pushover(PushoverConfig),
+}
+
+/// A push notification service exposed as an outbound-only channel.
+pub struct PushChannel {
+    service: PushService,
+    client: reqwest::Client,
+}
+
+impl PushChannel {
+    /// Channel publishing to an ntfy topic.
+    pub fn ntfy(config: NtfyConfig) -> Self {
+        Self::new(PushService::Ntfy(config))
+    }
+
+    /// Channel sending Pushover notifications.
+    pub fn pushover(config: PushoverConfig) -> Self {
+        Self::new(PushService::Pushover(config))
+    }
+
+    fn new(service: PushService) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self { service, client }
+    }
+
+    async fn send(&self, push: Push) -> Result<(), ChannelError> {
+        let request = match self.service {
+            PushService::Ntfy(ref config) => {
+                let url = format!("{}/{}", config.server.trim_end_matches('/'), config.topic);
+                let mut request = self
+                    .client
+                    .post(url)
+                    // ntfy caps messages at 4096 bytes
+                    .header("Title", truncate(&push.title, 250))
+                    .body(truncate(&push.message, 4000));
+                if push.urgent {
+                    request = request.header("Priority", "high");
+                }
+                if let Some(ref token) = config.token {
+                    request = request.bearer_auth(token.expose_secret());
+                }
+                request
+            }
+            PushService::Pushover(ref config) => {
+                let mut form = vec![
+                    ("token", config.app_token.expose_secret().to_string()),
+                    ("user", config.user_key.expose_secret().to_string()),
+                    ("title", truncate(&push.title, 250)),
+                    ("message", truncate(&push.message, 1024)),
+                ];
+                if push.urgent {
+                    form.push(("priority", "1".to_string()));
+                }
+                if let Some(ref device) = config.device {
+                    form.push(("device", device.clone()));
+                }
+                self.client.post(PUSHOVER_URL).form(&form)
+            }
+        };
+
+        let send_failed = |reason: String| ChannelError::SendFailed {
+            name: self.name().to_string(),
+            reason,
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| send_failed(e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(send_failed(format!("HTTP {}: {}", status, body)));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Channel for PushChannel {
+    fn name(&self) -> &str {
+        match self.service {
+            PushService::Ntfy(_) => "ntfy",
+            PushService::Pushover(_) => "pushover",
+        }
+    }
+
+    async fn start(&self) -> Result<MessageStream, ChannelError> {
+        // Nothing comes in; the empty stream just ends
+        Ok(Box::pin(stream::empty()))
+    }
+
+    async fn respond(
+        &self,
+        _msg: &IncomingMessage,
+        response: OutgoingResponse,
+    ) -> Result<(), ChannelError> {
+        self.send(Push::from_response(&response)).await
+    }
+
+    async fn send_status(
+        &self,
+        status: StatusUpdate,
+        _metadata: &serde_json::Value,
+    ) -> Result<(), ChannelError> {
+        match Push::from_status(&status) {
+            Some(push) => self.send(push).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn broadcast(
+        &self,
+        _user_id: &str,
+        response: OutgoingResponse,
+    ) -> Result<(), ChannelError> {
+        self.send(Push::from_response(&response)).await
+    }
+
+    fn is_outbound_only(&self) -> bool {
+        true
+    }
+
+    async fn formatting(&self) -> FormattingCapabilities {
+        FormattingCapabilities {
+            markdown: MarkdownFlavor::Plain,
+            supports_threads: false,
+            ..FormattingCapabilities::default()
+        }
+    }
+
+    async fn health_check(&self) -> Result<(), ChannelError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_title() {
+        let (title, message) = split_title("🔔 *Heartbeat Alert*\n\nThe build is red.");
+        assert_eq!(title.as_deref(), Some("🔔 Heartbeat Alert"));
+        assert_eq!(message, "The build is red.");
+
+        // A plain first paragraph isn't a title
+        let content = "Done.\n\nNothing else to report.";
+        assert_eq!(split_title(content), (None, content));
+        assert_eq!(split_title("Just text"), (None, "Just text"));
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("abcdefghij", 5), "abcd…");
+    }
+
+    #[test]
+    fn test_push_from_status() {
+        let approval = StatusUpdate::ApprovalNeeded {
+            request_id: "r1".to_string(),
+            tool_name: "shell".to_string(),
+            description: "rm -rf build".to_string(),
+            parameters: serde_json::json!({}),
+        };
+        let push = Push::from_status(&approval).unwrap();
+        assert_eq!(push.title, "Approval needed: shell");
+        assert!(push.urgent);
+
+        assert!(Push::from_status(&StatusUpdate::Thinking("...".to_string())).is_none());
+    }
+}
@@ -0,0 +1,329 @@
+//! Quiet hours for proactive notifications.
+//!
+//! Each user can set a daily window (e.g. 22:00-07:00) during which
+//! heartbeat findings, routine alerts and reports are held by the
+//! [`NotificationRouter`](crate::channels::NotificationRouter) and delivered
+//! when the window ends. Urgent notifications go out anyway. A notification
+//! is urgent if any of these hold:
+//! - its sender flags it (`"urgent": true` in the metadata)
+//! - its source is configured as urgent (by default `/defer` reminders)
+//! - it contains a configured or user-chosen urgent keyword
+//!
+//! A channel can have its own window, or none so it is always on (e.g. a
+//! pager push channel). Settings are stored per user under
+//! [`QUIET_HOURS_KEY`] and changed with `/quiet`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::{DateTime, FixedOffset, Local, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::channels::OutgoingResponse;
+use crate::config::QuietHoursConfig;
+
+/// Setting key a user's quiet hours are stored under.
+pub const QUIET_HOURS_KEY: &str = "quiet_hours";
+
+/// How `/quiet` is used.
+pub const QUIET_USAGE: &str = "Usage: /quiet 22:00-07:00 [+02:00] | /quiet off | \
+     /quiet channel <name> off|on|<from-to> | /quiet urgent <word, ...>|none";
+
+/// A daily time window. It crosses midnight when it ends before it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Window {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl Window {
+    /// Parse "22:00-07:00" (whole hours may drop the minutes: "22-7").
+    pub fn parse(s: &str) -> Option<Self> {
+        let (start, end) = s.split_once('-')?;
+        let (start, end) = (parse_time(start)?, parse_time(end)?);
+        (start != end).then_some(Self { start, end })
+    }
+
+    /// Whether `time` falls inside the window.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// The next time the window ends after `now`.
+    fn next_end(&self, now: DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+        let mut end = now.date_naive().and_time(self.end);
+        if end <= now.naive_local() {
+            end += chrono::Duration::days(1);
+        }
+        now + (end - now.naive_local())
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+impl TryFrom<String> for Window {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s).ok_or_else(|| format!("invalid quiet hours window '{}'", s))
+    }
+}
+
+impl From<Window> for String {
+    fn from(window: Window) -> Self {
+        window.to_string()
+    }
+}
+
+/// A user's quiet hours.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub window: Window,
+    /// UTC offset the windows are in, e.g. "+02:00"; the server's local
+    /// time when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub utc_offset: Option<String>,
+    /// Words that make a notification urgent for this user, on top of the
+    /// configured ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub urgent_keywords: Vec<String>,
+    /// Per-channel windows replacing `window`; `None` keeps a channel on.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub channels: BTreeMap<String, Option<Window>>,
+}
+
+impl QuietHours {
+    pub fn new(window: Window) -> Self {
+        Self {
+            window,
+            utc_offset: None,
+            urgent_keywords: Vec::new(),
+            channels: BTreeMap::new(),
+        }
+    }
+
+    /// When quiet hours on `channel` end, if `now` falls inside them.
+    pub fn quiet_until(&self, channel: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let window = match self.channels.get(channel) {
+            Some(window) => (*window)?,
+            None => self.window,
+        };
+        let local = match self.utc_offset.as_deref().and_then(parse_offset) {
+            Some(offset) => now.with_timezone(&offset),
+            None => now.with_timezone(&Local).fixed_offset(),
+        };
+        window
+            .contains(local.time())
+            .then(|| window.next_end(local).with_timezone(&Utc))
+    }
+
+    /// Whether `response` goes out even during quiet hours.
+    pub fn is_urgent(&self, response: &OutgoingResponse, config: &QuietHoursConfig) -> bool {
+        let metadata = &response.metadata;
+        if metadata.get("urgent").and_then(|v| v.as_bool()) == Some(true) {
+            return true;
+        }
+        if let Some(source) = metadata.get("source").and_then(|v| v.as_str())
+            && config.urgent_sources.iter().any(|s| s == source)
+        {
+            return true;
+        }
+        let content = response.content.to_lowercase();
+        config
+            .urgent_keywords
+            .iter()
+            .chain(&self.urgent_keywords)
+            .any(|keyword| content.contains(&keyword.to_lowercase()))
+    }
+
+    /// Summary for `/quiet`.
+    pub fn describe(&self) -> String {
+        let zone = match self.utc_offset {
+            Some(ref offset) => format!("UTC{}", offset),
+            None => "server time".to_string(),
+        };
+        let mut out = format!("🌙 Quiet hours {} ({}).", self.window, zone);
+        if !self.channels.is_empty() {
+            let channels: Vec<String> = self
+                .channels
+                .iter()
+                .map(|(name, window)| match window {
+                    Some(window) => format!("{} {}", name, window),
+                    None => format!("{} always on", name),
+                })
+                .collect();
+            out.push_str(&format!("\nChannels: {}.", channels.join(", ")));
+        }
+        if !self.urgent_keywords.is_empty() {
+            out.push_str(&format!(
+                "\nAlso urgent: {}.",
+                self.urgent_keywords.join(", ")
+            ));
+        }
+        out
+    }
+}
+
+/// Apply the arguments of a `/quiet` command to `current`. `None` means
+/// quiet hours are off.
+pub fn apply_command(
+    current: Option<QuietHours>,
+    args: &str,
+) -> Result<Option<QuietHours>, String> {
+    let words: Vec<&str> = args.split_whitespace().collect();
+    match words.as_slice() {
+        ["off"] => Ok(None),
+        ["channel", name, setting] => {
+            let mut quiet = current.ok_or("Set quiet hours first, e.g. /quiet 22:00-07:00.")?;
+            match *setting {
+                "on" => {
+                    quiet.channels.remove(*name);
+                }
+                "off" => {
+                    quiet.channels.insert(name.to_string(), None);
+                }
+                window => {
+                    let window = Window::parse(window).ok_or(QUIET_USAGE)?;
+                    quiet.channels.insert(name.to_string(), Some(window));
+                }
+            }
+            Ok(Some(quiet))
+        }
+        ["urgent", ..] => {
+            let mut quiet = current.ok_or("Set quiet hours first, e.g. /quiet 22:00-07:00.")?;
+            let list = args.trim()["urgent".len()..].trim();
+            quiet.urgent_keywords = if list.eq_ignore_ascii_case("none") {
+                Vec::new()
+            } else {
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect()
+            };
+            Ok(Some(quiet))
+        }
+        [window, rest @ ..] if rest.len() <= 1 => {
+            let window = Window::parse(window).ok_or(QUIET_USAGE)?;
+            let utc_offset = match rest.first() {
+                Some(offset) => {
+                    parse_offset(offset).ok_or(QUIET_USAGE)?;
+                    Some(offset.to_string())
+                }
+                None => current.as_ref().and_then(|q| q.utc_offset.clone()),
+            };
+            let mut quiet = current.unwrap_or_else(|| QuietHours::new(window));
+            quiet.window = window;
+            quiet.utc_offset = utc_offset;
+            Ok(Some(quiet))
+        }
+        _ => Err(QUIET_USAGE.to_string()),
+    }
+}
+
+/// "22:00" or "22".
+fn parse_time(s: &str) -> Option<NaiveTime> {
+    let s = s.trim();
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .ok()
+        .or_else(|| NaiveTime::from_hms_opt(s.parse().ok()?, 0, 0))
+}
+
+/// "+02:00" or "-05:30".
+fn parse_offset(s: &str) -> Option<FixedOffset> {
+    s.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 14, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_window_crossing_midnight() {
+        let window = Window::parse("22:00-7").unwrap();
+        assert_eq!(window.to_string(), "22:00-07:00");
+        let time = |h| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+        assert!(window.contains(time(23)));
+        assert!(window.contains(time(3)));
+        assert!(!window.contains(time(7)));
+        assert!(!window.contains(time(12)));
+        assert!(Window::parse("09:00-09:00").is_none());
+        assert!(Window::parse("25:00-07:00").is_none());
+    }
+
+    #[test]
+    fn test_quiet_until_per_channel() {
+        let quiet = apply_command(None, "22:00-07:00 +02:00").unwrap().unwrap();
+        let quiet = apply_command(Some(quiet), "channel pushover off")
+            .unwrap()
+            .unwrap();
+        let quiet = apply_command(Some(quiet), "channel web 23:30-06:00")
+            .unwrap()
+            .unwrap();
+
+        // 21:30 UTC is 23:30 at +02:00
+        assert_eq!(
+            quiet.quiet_until("telegram", at(21, 30)),
+            Some(at(5, 0) + chrono::Duration::days(1))
+        );
+        assert_eq!(quiet.quiet_until("pushover", at(21, 30)), None);
+        assert_eq!(quiet.quiet_until("web", at(21, 0)), None);
+        assert!(quiet.quiet_until("web", at(21, 30)).is_some());
+        // 10:00 UTC is noon: not quiet anywhere
+        assert_eq!(quiet.quiet_until("telegram", at(10, 0)), None);
+
+        let stored = serde_json::to_value(&quiet).unwrap();
+        assert_eq!(stored["window"], "22:00-07:00");
+        assert_eq!(stored["channels"]["pushover"], serde_json::Value::Null);
+        assert_eq!(serde_json::from_value::<QuietHours>(stored).unwrap(), quiet);
+    }
+
+    #[test]
+    fn test_is_urgent() {
+        let config = QuietHoursConfig::default();
+        let quiet = apply_command(None, "22-7").unwrap().unwrap();
+        let quiet = apply_command(Some(quiet), "urgent prod down, pager")
+            .unwrap()
+            .unwrap();
+        assert_eq!(quiet.urgent_keywords, vec!["prod down", "pager"]);
+
+        let mut response = OutgoingResponse::text("Weekly report is ready");
+        assert!(!quiet.is_urgent(&response, &config));
+
+        response.metadata = serde_json::json!({ "source": "defer" });
+        assert!(quiet.is_urgent(&response, &config));
+
+        response.metadata = serde_json::json!({ "source": "heartbeat", "urgent": true });
+        assert!(quiet.is_urgent(&response, &config));
+
+        assert!(quiet.is_urgent(&OutgoingResponse::text("PROD DOWN since 3am"), &config));
+        assert!(quiet.is_urgent(&OutgoingResponse::text("Urgent: disk full"), &config));
+    }
+
+    #[test]
+    fn test_apply_command_errors() {
+        assert_eq!(apply_command(None, "off").unwrap(), None);
+        assert!(apply_command(None, "channel web off").is_err());
+        assert!(apply_command(None, "late").is_err());
+        assert!(apply_command(None, "22:00-07:00 Mars/Base").is_err());
+    }
+}
@@ -14,12 +14,18 @@
 //! - `/clear` - Clear the conversation
 //! - `/compact` - Compact the context
 //! - `/new` - Start a new thread
+//! - `/model [name]` - Show models or pin one for this conversation
+//!   (`/model all <name>` for every conversation, `/model routine <r> <name>`)
+//! - `/cost` - Show LLM spend so far today
+//! - `/redeliver` - Re-send responses that could not be delivered
+//! - `/tools` - List this session's tool calls
+//! - `/expand [n]` - Show the full input and output of a tool call
 //! - `yes`/`no`/`always` - Respond to tool approval prompts
 
 use std::borrow::Cow;
 use std::io::{self, Write};
-use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 use rustyline::completion::Completer;
@@ -48,6 +54,11 @@ const SLASH_COMMANDS: &[&str] = &[
     "/compact",
     "/new",
     "/interrupt",
+    "/model",
+    "/cost",
+    "/redeliver",
+    "/tools",
+    "/expand",
 ];
 
 /// Rustyline helper for slash-command tab completion.
@@ -101,6 +112,107 @@ impl Highlighter for ReplHelper {
 impl Validator for ReplHelper {}
 impl Helper for ReplHelper {}
 
+/// A tool call seen during this REPL session, kept so it can be expanded.
+#[derive(Debug, Clone)]
+struct ToolCallRecord {
+    name: String,
+    parameters: serde_json::Value,
+    success: Option<bool>,
+    output: Option<String>,
+}
+
+type ToolLog = Arc<Mutex<Vec<ToolCallRecord>>>;
+
+/// One-line `key=value` summary of tool parameters (the collapsed view).
+fn summarize_params(params: &serde_json::Value, max_chars: usize) -> String {
+    let summary = match params {
+        serde_json::Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| match value {
+                serde_json::Value::String(s) => format!("{key}={s}"),
+                other => format!("{key}={other}"),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    };
+    let summary = summary.replace('\n', " ");
+    match summary.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}...", &summary[..idx]),
+        None => summary,
+    }
+}
+
+/// Full input and output of a tool call (the expanded view).
+fn render_tool_call(index: usize, call: &ToolCallRecord) -> String {
+    let status = match call.success {
+        Some(true) => "\x1b[32mok\x1b[0m",
+        Some(false) => "\x1b[31mfailed\x1b[0m",
+        None => "\x1b[33mrunning\x1b[0m",
+    };
+    let input = serde_json::to_string_pretty(&call.parameters)
+        .unwrap_or_else(|_| call.parameters.to_string());
+
+    let mut out = format!("  \x1b[1m[{index}] {}\x1b[0m ({status})\n", call.name);
+    out.push_str("  \x1b[36minput\x1b[0m\n");
+    for line in input.lines() {
+        out.push_str(&format!("    {line}\n"));
+    }
+    out.push_str("  \x1b[36moutput\x1b[0m\n");
+    match call.output {
+        Some(ref output) => {
+            for line in output.lines() {
+                out.push_str(&format!("    {line}\n"));
+            }
+        }
+        None => out.push_str("    \x1b[90m(none)\x1b[0m\n"),
+    }
+    out
+}
+
+/// Handle `/tools` and `/expand [n]` locally. Returns false for other input.
+fn handle_tool_command(line: &str, tool_log: &ToolLog) -> bool {
+    let mut parts = line.split_whitespace();
+    let command = parts.next().unwrap_or_default().to_lowercase();
+    let calls = tool_log.lock().unwrap_or_else(|e| e.into_inner());
+
+    match command.as_str() {
+        "/tools" => {
+            if calls.is_empty() {
+                println!("\x1b[90mno tool calls yet\x1b[0m");
+            }
+            for (i, call) in calls.iter().enumerate() {
+                let mark = match call.success {
+                    Some(true) => "\x1b[32m\u{25CF}\x1b[0m",
+                    Some(false) => "\x1b[31m\u{2717}\x1b[0m",
+                    None => "\x1b[33m\u{25CB}\x1b[0m",
+                };
+                println!(
+                    "  {mark} [{}] {}  \x1b[90m{}\x1b[0m",
+                    i + 1,
+                    call.name,
+                    summarize_params(&call.parameters, 60)
+                );
+            }
+            true
+        }
+        "/expand" => {
+            // Default to the most recent call; numbering starts at 1
+            let index = match parts.next() {
+                Some(n) => n.parse::<usize>().unwrap_or(0),
+                None => calls.len(),
+            };
+            match index.checked_sub(1).and_then(|i| calls.get(i)) {
+                Some(call) => print!("{}", render_tool_call(index, call)),
+                None => println!("\x1b[90mno such tool call (see /tools)\x1b[0m"),
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
 
 
 /// Format JSON params as `key: value` lines for the approval card.
@@ -151,6 +263,8 @@ pub struct ReplChannel {
     debug_mode: Arc<AtomicBool>,
     /// Whether we're currently streaming (chunks have been printed without a trailing newline).
     is_streaming: Arc<AtomicBool>,
+    /// Tool calls made this session, for `/tools` and `/expand`.
+    tool_log: ToolLog,
 }
 
 impl ReplChannel {
@@ -160,6 +274,7 @@ impl ReplChannel {
             single_message: None,
             debug_mode: Arc::new(AtomicBool::new(false)),
             is_streaming: Arc::new(AtomicBool::new(false)),
+            tool_log: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -169,6 +284,7 @@ impl ReplChannel {
             single_message: Some(message),
             debug_mode: Arc::new(AtomicBool::new(false)),
             is_streaming: Arc::new(AtomicBool::new(false)),
+            tool_log: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -206,6 +322,13 @@ fn print_help() {
     println!("  {c}/new{r}               {d}new conversation thread{r}");
     println!("  {c}/interrupt{r}         {d}stop current operation{r}");
     println!();
+    println!("  {h}Session{r}");
+    println!("  {c}/model{r} [name]       {d}show models or pin one for this thread{r}");
+    println!("  {c}/cost{r}              {d}show LLM spend today{r}");
+    println!("  {c}/redeliver{r}         {d}re-send undelivered responses{r}");
+    println!("  {c}/tools{r}             {d}list tool calls{r}");
+    println!("  {c}/expand{r} [n]         {d}show a tool call's full input and output{r}");
+    println!();
     println!("  {h}Approval responses{r}");
     println!("  {c}yes{r} ({c}y{r})            {d}approve tool execution{r}");
     println!("  {c}no{r} ({c}n{r})             {d}deny tool execution{r}");
@@ -231,6 +354,7 @@ impl Channel for ReplChannel {
         let (tx, rx) = mpsc::channel(32);
         let single_message = self.single_message.clone();
         let debug_mode = Arc::clone(&self.debug_mode);
+        let tool_log = Arc::clone(&self.tool_log);
 
         std::thread::spawn(move || {
             // Single message mode: send it and return
@@ -301,6 +425,9 @@ impl Channel for ReplChannel {
                             }
                             _ => {}
                         }
+                        if handle_tool_command(line, &tool_log) {
+                            continue;
+                        }
 
                         let msg = IncomingMessage::new("repl", "default", line);
                         if tx.blocking_send(msg).is_err() {
@@ -374,18 +501,63 @@ impl Channel for ReplChannel {
             StatusUpdate::Thinking(msg) => {
                 eprintln!("  \x1b[90m\u{25CB} {msg}\x1b[0m");
             }
-            StatusUpdate::ToolStarted { name } => {
-                eprintln!("  \x1b[33m\u{25CB} {name}\x1b[0m");
+            StatusUpdate::ToolStarted { name, parameters } => {
+                let summary = summarize_params(&parameters, 60);
+                let index = {
+                    let mut calls = self.tool_log.lock().unwrap_or_else(|e| e.into_inner());
+                    calls.push(ToolCallRecord {
+                        name: name.clone(),
+                        parameters,
+                        success: None,
+                        output: None,
+                    });
+                    calls.len()
+                };
+                eprintln!("  \x1b[33m\u{25CB} [{index}] {name}\x1b[0m  \x1b[90m{summary}\x1b[0m");
             }
             StatusUpdate::ToolCompleted { name, success } => {
+                let index = {
+                    let mut calls = self.tool_log.lock().unwrap_or_else(|e| e.into_inner());
+                    let pending = calls
+                        .iter_mut()
+                        .enumerate()
+                        .rev()
+                        .find(|(_, c)| c.name == name && c.success.is_none());
+                    pending.map(|(i, call)| {
+                        call.success = Some(success);
+                        i + 1
+                    })
+                };
+                let label = match index {
+                    Some(i) => format!("[{i}] {name}"),
+                    None => name,
+                };
                 if success {
-                    eprintln!("  \x1b[32m\u{25CF} {name}\x1b[0m");
+                    eprintln!("  \x1b[32m\u{25CF} {label}\x1b[0m");
                 } else {
-                    eprintln!("  \x1b[31m\u{2717} {name} (failed)\x1b[0m");
+                    eprintln!("  \x1b[31m\u{2717} {label} (failed)\x1b[0m");
                 }
             }
-            StatusUpdate::ToolResult { name: _, preview } => {
-                eprintln!("    \x1b[90m{preview}\x1b[0m");
+            StatusUpdate::ToolResult {
+                name,
+                preview,
+                output,
+            } => {
+                let mut calls = self.tool_log.lock().unwrap_or_else(|e| e.into_inner());
+                if let Some(call) = calls
+                    .iter_mut()
+                    .rev()
+                    .find(|c| c.name == name && c.output.is_none())
+                {
+                    call.output = Some(output);
+                }
+                drop(calls);
+
+                if debug {
+                    eprintln!("    \x1b[90m{preview}\x1b[0m");
+                } else {
+                    eprintln!("    \x1b[90m{preview}  (/expand for full output)\x1b[0m");
+                }
             }
             StatusUpdate::StreamChunk(chunk) => {
                 // Print separator on the false-to-true transition
@@ -505,3 +677,34 @@ impl Channel for ReplChannel {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_params_is_single_line_and_capped() {
+        let params = serde_json::json!({"path": "notes/a.md", "content": "line one\nline two"});
+        let summary = summarize_params(&params, 200);
+        assert!(summary.contains("path=notes/a.md"));
+        assert!(!summary.contains('\n'));
+
+        let long = serde_json::json!({"query": "x".repeat(100)});
+        assert_eq!(summarize_params(&long, 20).chars().count(), 23);
+        assert_eq!(summarize_params(&serde_json::Value::Null, 20), "");
+    }
+
+    #[test]
+    fn test_render_tool_call_shows_full_input_and_output() {
+        let call = ToolCallRecord {
+            name: "memory_search".to_string(),
+            parameters: serde_json::json!({"query": "deploy"}),
+            success: Some(true),
+            output: Some("first\nsecond".to_string()),
+        };
+        let rendered = render_tool_call(2, &call);
+        assert!(rendered.contains("[2] memory_search"));
+        assert!(rendered.contains("\"query\": \"deploy\""));
+        assert!(rendered.contains("    first\n    second\n"));
+    }
+}
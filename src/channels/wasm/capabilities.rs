@@ -17,6 +17,10 @@ pub const MIN_POLL_INTERVAL_MS: u32 = 30_000;
 pub const DEFAULT_EMIT_RATE_PER_MINUTE: u32 = 100;
 pub const DEFAULT_EMIT_RATE_PER_HOUR: u32 = 5000;
 
+/// Default per-user flood protection.
+pub const DEFAULT_USER_RATE_PER_MINUTE: u32 = 20;
+pub const DEFAULT_MAX_DEFERRED_PER_USER: u32 = 10;
+
 /// Capabilities specific to WASM channels.
 ///
 /// Extends tool capabilities with channel-specific permissions.
@@ -44,6 +48,9 @@ pub struct ChannelCapabilities {
     /// Rate limiting for emit_message calls.
     pub emit_rate_limit: EmitRateLimitConfig,
 
+    /// Cap on how often a single user's messages reach the agent.
+    pub user_rate_limit: UserRateLimitConfig,
+
     /// Maximum message content size in bytes.
     pub max_message_size: usize,
 
@@ -60,6 +67,7 @@ impl Default for ChannelCapabilities {
             min_poll_interval_ms: MIN_POLL_INTERVAL_MS,
             workspace_prefix: String::new(),
             emit_rate_limit: EmitRateLimitConfig::default(),
+            user_rate_limit: UserRateLimitConfig::default(),
             max_message_size: 64 * 1024, // 64 KB
             callback_timeout: Duration::from_secs(30),
         }
@@ -94,6 +102,12 @@ impl ChannelCapabilities {
         self
     }
 
+    /// Set the per-user rate limit.
+    pub fn with_user_rate_limit(mut self, rate_limit: UserRateLimitConfig) -> Self {
+        self.user_rate_limit = rate_limit;
+        self
+    }
+
     /// Set the callback timeout.
     pub fn with_callback_timeout(mut self, timeout: Duration) -> Self {
         self.callback_timeout = timeout;
@@ -228,6 +242,43 @@ impl From<RateLimitConfig> for EmitRateLimitConfig {
     }
 }
 
+/// What happens to a user's messages beyond their rate limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FloodAction {
+    /// Hold messages back and deliver them once the user is under the limit.
+    #[default]
+    Defer,
+    /// Discard messages.
+    Drop,
+}
+
+/// Messages-per-minute quota for each user of a channel.
+///
+/// Unlike [`EmitRateLimitConfig`], which caps a channel as a whole, this
+/// stops a single user from monopolizing the agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserRateLimitConfig {
+    /// Maximum messages per user per minute (0 disables the limit).
+    pub messages_per_minute: u32,
+
+    /// What to do with messages beyond the limit.
+    pub action: FloodAction,
+
+    /// Maximum deferred messages per user; any more are dropped.
+    pub max_deferred: u32,
+}
+
+impl Default for UserRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            messages_per_minute: DEFAULT_USER_RATE_PER_MINUTE,
+            action: FloodAction::Defer,
+            max_deferred: DEFAULT_MAX_DEFERRED_PER_USER,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::channels::wasm::capabilities::{
@@ -36,6 +36,9 @@ pub enum WasmChannelError {
     #[error("Channel {name} workspace path escape attempt: {path}")]
     WorkspaceEscape { name: String, path: String },
 
+    #[error("Channel {name} attachment error: {reason}")]
+    Attachment { name: String, reason: String },
+
     #[error("Channel {name} exhausted fuel limit ({limit})")]
     FuelExhausted { name: String, limit: u64 },
 
@@ -3,12 +3,17 @@
 //! Extends the base tool host state with channel-specific functionality:
 //! - Message emission (queueing messages to send to the agent)
 //! - Workspace write access (scoped to channel namespace)
-//! - Rate limiting for message emission
+//! - Rate limiting for message emission, per channel and per user
+//! - Attachment storage (files referenced from messages and responses)
 
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use crate::channels::wasm::capabilities::{ChannelCapabilities, EmitRateLimitConfig};
+use crate::channels::wasm::capabilities::{
+    ChannelCapabilities, EmitRateLimitConfig, FloodAction, UserRateLimitConfig,
+};
 use crate::channels::wasm::error::WasmChannelError;
+use crate::channels::{Attachment, AttachmentStore};
 use crate::tools::wasm::{HostState, LogLevel};
 
 /// Maximum emitted messages per callback execution.
@@ -17,6 +22,9 @@ const MAX_EMITS_PER_EXECUTION: usize = 100;
 /// Maximum message content size (64 KB).
 const MAX_MESSAGE_CONTENT_SIZE: usize = 64 * 1024;
 
+/// Maximum attachments stored per callback execution.
+const MAX_ATTACHMENTS_PER_EXECUTION: usize = 10;
+
 /// A message emitted by a WASM channel to be sent to the agent.
 #[derive(Debug, Clone)]
 pub struct EmittedMessage {
@@ -35,6 +43,9 @@ pub struct EmittedMessage {
     /// Channel-specific metadata as JSON string.
     pub metadata_json: String,
 
+    /// Files received with the message.
+    pub attachments: Vec<Attachment>,
+
     /// Timestamp when the message was emitted.
     pub emitted_at_millis: u64,
 }
@@ -48,6 +59,7 @@ impl EmittedMessage {
             content: content.into(),
             thread_id: None,
             metadata_json: "{}".to_string(),
+            attachments: Vec::new(),
             emitted_at_millis: SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .map(|d| d.as_millis() as u64)
@@ -72,6 +84,12 @@ impl EmittedMessage {
         self.metadata_json = metadata_json.into();
         self
     }
+
+    /// Set attachments.
+    pub fn with_attachments(mut self, attachments: Vec<Attachment>) -> Self {
+        self.attachments = attachments;
+        self
+    }
 }
 
 /// A pending workspace write operation.
@@ -112,6 +130,16 @@ pub struct ChannelHostState {
 
     /// Count of emits dropped due to rate limiting.
     emits_dropped: usize,
+
+    /// Where attachments are stored (None disables attachments).
+    attachment_store: Option<AttachmentStore>,
+
+    /// Attachments this execution may read: those stored during it and
+    /// those on the response being delivered.
+    readable_attachments: HashSet<String>,
+
+    /// Attachments stored during this execution.
+    attachments_stored: usize,
 }
 
 impl std::fmt::Debug for ChannelHostState {
@@ -141,9 +169,18 @@ impl ChannelHostState {
             emit_count: 0,
             emit_enabled: true,
             emits_dropped: 0,
+            attachment_store: None,
+            readable_attachments: HashSet::new(),
+            attachments_stored: 0,
         }
     }
 
+    /// Enable attachments, stored in `store`.
+    pub fn with_attachment_store(mut self, store: AttachmentStore) -> Self {
+        self.attachment_store = Some(store);
+        self
+    }
+
     /// Get the channel name.
     pub fn channel_name(&self) -> &str {
         &self.channel_name
@@ -186,6 +223,20 @@ impl ChannelHostState {
             return Ok(());
         }
 
+        // Only attachments this execution has access to can be passed on
+        let mut msg = msg;
+        msg.attachments.retain(|a| {
+            let readable = self.readable_attachments.contains(&a.id);
+            if !readable {
+                tracing::warn!(
+                    channel = %self.channel_name,
+                    id = %a.id,
+                    "Dropping attachment the channel did not store"
+                );
+            }
+            readable
+        });
+
         // Validate message content size
         if msg.content.len() > MAX_MESSAGE_CONTENT_SIZE {
             tracing::warn!(
@@ -245,6 +296,58 @@ impl ChannelHostState {
         Ok(())
     }
 
+    /// Store a received file with the host.
+    pub fn store_attachment(
+        &mut self,
+        name: &str,
+        mime_type: &str,
+        data: &[u8],
+    ) -> Result<Attachment, WasmChannelError> {
+        if self.attachments_stored >= MAX_ATTACHMENTS_PER_EXECUTION {
+            return Err(self.attachment_error(format!(
+                "limit of {} attachments per execution reached",
+                MAX_ATTACHMENTS_PER_EXECUTION
+            )));
+        }
+        let store = self.attachment_store()?;
+        let attachment = store
+            .put(name, mime_type, data)
+            .map_err(|e| self.attachment_error(e.to_string()))?;
+
+        self.attachments_stored += 1;
+        self.readable_attachments.insert(attachment.id.clone());
+        Ok(attachment)
+    }
+
+    /// Let this execution read an attachment, e.g. one on the response it
+    /// is delivering.
+    pub fn allow_attachment_read(&mut self, id: impl Into<String>) {
+        self.readable_attachments.insert(id.into());
+    }
+
+    /// Read a stored file this execution has access to.
+    pub fn read_attachment(&self, id: &str) -> Result<Vec<u8>, WasmChannelError> {
+        if !self.readable_attachments.contains(id) {
+            return Err(self.attachment_error(format!("attachment {} is not readable here", id)));
+        }
+        self.attachment_store()?
+            .get(id)
+            .map_err(|e| self.attachment_error(e.to_string()))
+    }
+
+    fn attachment_store(&self) -> Result<&AttachmentStore, WasmChannelError> {
+        self.attachment_store
+            .as_ref()
+            .ok_or_else(|| self.attachment_error("attachments are not available".to_string()))
+    }
+
+    fn attachment_error(&self, reason: String) -> WasmChannelError {
+        WasmChannelError::Attachment {
+            name: self.channel_name.clone(),
+            reason,
+        }
+    }
+
     /// Take all pending workspace writes (clears the queue).
     pub fn take_pending_writes(&mut self) -> Vec<PendingWorkspaceWrite> {
         std::mem::take(&mut self.pending_writes)
@@ -382,11 +485,102 @@ impl ChannelEmitRateLimiter {
     }
 }
 
+/// Sliding window for per-user rate limits.
+const USER_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Users tracked before idle ones are pruned.
+const MAX_TRACKED_USERS: usize = 1024;
+
+/// What to do with a message from a user, according to their rate limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloodDecision {
+    /// Deliver the message now.
+    Allow,
+    /// Deliver the message after the delay.
+    Defer(Duration),
+    /// Discard the message.
+    Drop,
+}
+
+/// Enforces a [`UserRateLimitConfig`] on the messages a channel emits.
+///
+/// Each user may send `messages_per_minute` messages in any sliding minute.
+/// With [`FloodAction::Defer`], messages beyond that are scheduled into the
+/// user's next free slots, up to `max_deferred` waiting at once; anything
+/// more is dropped.
+pub struct UserRateLimiter {
+    config: UserRateLimitConfig,
+    /// Per user, when each recent message was (or will be) delivered, in order.
+    slots: HashMap<String, VecDeque<Instant>>,
+}
+
+impl UserRateLimiter {
+    /// Create a new rate limiter with the given config.
+    pub fn new(config: UserRateLimitConfig) -> Self {
+        Self {
+            config,
+            slots: HashMap::new(),
+        }
+    }
+
+    /// Decide what to do with a message from `user_id` and record it.
+    pub fn check(&mut self, user_id: &str) -> FloodDecision {
+        self.check_at(user_id, Instant::now())
+    }
+
+    fn check_at(&mut self, user_id: &str, now: Instant) -> FloodDecision {
+        let limit = self.config.messages_per_minute as usize;
+        if limit == 0 {
+            return FloodDecision::Allow;
+        }
+
+        if self.slots.len() >= MAX_TRACKED_USERS {
+            self.slots.retain(|_, slots| {
+                slots
+                    .back()
+                    .is_some_and(|last| now.duration_since(*last) < USER_RATE_WINDOW)
+            });
+        }
+
+        let slots = self.slots.entry(user_id.to_string()).or_default();
+        while slots
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= USER_RATE_WINDOW)
+        {
+            slots.pop_front();
+        }
+
+        if slots.len() < limit {
+            slots.push_back(now);
+            return FloodDecision::Allow;
+        }
+
+        match self.config.action {
+            FloodAction::Drop => FloodDecision::Drop,
+            FloodAction::Defer => {
+                let waiting = slots.iter().filter(|t| **t > now).count();
+                if waiting >= self.config.max_deferred as usize {
+                    return FloodDecision::Drop;
+                }
+                // The next slot opens a window after the message `limit` places back
+                let slot = (slots[slots.len() - limit] + USER_RATE_WINDOW).max(now);
+                slots.push_back(slot);
+                FloodDecision::Defer(slot - now)
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::channels::wasm::capabilities::{ChannelCapabilities, EmitRateLimitConfig};
+    use std::time::{Duration, Instant};
+
+    use crate::channels::wasm::capabilities::{
+        ChannelCapabilities, EmitRateLimitConfig, FloodAction, UserRateLimitConfig,
+    };
     use crate::channels::wasm::host::{
-        ChannelEmitRateLimiter, ChannelHostState, EmittedMessage, MAX_EMITS_PER_EXECUTION,
+        ChannelEmitRateLimiter, ChannelHostState, EmittedMessage, FloodDecision,
+        MAX_EMITS_PER_EXECUTION, UserRateLimiter,
     };
 
     #[test]
@@ -426,6 +620,42 @@ mod tests {
         assert_eq!(messages[0].metadata_json, r#"{"key": "value"}"#);
     }
 
+    #[test]
+    fn test_attachments_scoped_to_execution() {
+        use crate::channels::AttachmentStore;
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = AttachmentStore::new(dir.path());
+        let caps = ChannelCapabilities::for_channel("test");
+        let mut state = ChannelHostState::new("test", caps).with_attachment_store(store.clone());
+
+        let stored = state
+            .store_attachment("photo.jpg", "image/jpeg", b"jpeg")
+            .unwrap();
+        assert_eq!(state.read_attachment(&stored.id).unwrap(), b"jpeg");
+
+        // Stored elsewhere: unreadable until granted
+        let other = store.put("notes.txt", "text/plain", b"private").unwrap();
+        assert!(state.read_attachment(&other.id).is_err());
+        state.allow_attachment_read(other.id.clone());
+        assert_eq!(state.read_attachment(&other.id).unwrap(), b"private");
+
+        // Emitted messages can only reference accessible attachments
+        let mut forged = stored.clone();
+        forged.id = "f".repeat(64);
+        let msg =
+            EmittedMessage::new("user", "look").with_attachments(vec![stored.clone(), forged]);
+        state.emit_message(msg).unwrap();
+        assert_eq!(state.take_emitted_messages()[0].attachments, vec![stored]);
+    }
+
+    #[test]
+    fn test_attachments_disabled_without_store() {
+        let caps = ChannelCapabilities::for_channel("test");
+        let mut state = ChannelHostState::new("test", caps);
+        assert!(state.store_attachment("a.txt", "text/plain", b"a").is_err());
+    }
+
     #[test]
     fn test_emit_per_execution_limit() {
         let caps = ChannelCapabilities::for_channel("test");
@@ -490,6 +720,60 @@ mod tests {
         assert!(!limiter.check_and_record());
     }
 
+    #[test]
+    fn test_user_rate_limiter_defers_then_drops() {
+        let mut limiter = UserRateLimiter::new(UserRateLimitConfig {
+            messages_per_minute: 2,
+            action: FloodAction::Defer,
+            max_deferred: 2,
+        });
+        let start = Instant::now();
+
+        assert_eq!(limiter.check_at("alice", start), FloodDecision::Allow);
+        let later = start + Duration::from_secs(10);
+        assert_eq!(limiter.check_at("alice", later), FloodDecision::Allow);
+
+        // Over the limit: deferred until the earlier messages leave the window
+        assert_eq!(
+            limiter.check_at("alice", later),
+            FloodDecision::Defer(Duration::from_secs(50))
+        );
+        assert_eq!(
+            limiter.check_at("alice", later),
+            FloodDecision::Defer(Duration::from_secs(60))
+        );
+        assert_eq!(limiter.check_at("alice", later), FloodDecision::Drop);
+
+        // Other users are unaffected
+        assert_eq!(limiter.check_at("bob", later), FloodDecision::Allow);
+
+        // Once the window has passed, messages flow again
+        let much_later = start + Duration::from_secs(200);
+        assert_eq!(limiter.check_at("alice", much_later), FloodDecision::Allow);
+    }
+
+    #[test]
+    fn test_user_rate_limiter_drop_action() {
+        let mut limiter = UserRateLimiter::new(UserRateLimitConfig {
+            messages_per_minute: 1,
+            action: FloodAction::Drop,
+            max_deferred: 10,
+        });
+        let now = Instant::now();
+        assert_eq!(limiter.check_at("alice", now), FloodDecision::Allow);
+        assert_eq!(limiter.check_at("alice", now), FloodDecision::Drop);
+
+        // A limit of 0 disables flood protection
+        let mut limiter = UserRateLimiter::new(UserRateLimitConfig {
+            messages_per_minute: 0,
+            action: FloodAction::Drop,
+            max_deferred: 0,
+        });
+        for _ in 0..100 {
+            assert_eq!(limiter.check_at("alice", now), FloodDecision::Allow);
+        }
+    }
+
     #[test]
     fn test_channel_name() {
         let caps = ChannelCapabilities::for_channel("telegram");
@@ -14,7 +14,7 @@ use tokio::fs;
 use crate::channels::wasm::capabilities::ChannelCapabilities;
 use crate::channels::wasm::error::WasmChannelError;
 use crate::channels::wasm::runtime::WasmChannelRuntime;
-use crate::channels::wasm::schema::ChannelCapabilitiesFile;
+use crate::channels::wasm::schema::{ChannelCapabilitiesFile, WebhookVerification};
 use crate::channels::wasm::wrapper::WasmChannel;
 
 /// Loads WASM channels from the filesystem.
@@ -237,6 +237,14 @@ impl LoadedChannel {
             .and_then(|f| f.webhook_secret_header())
     }
 
+    /// Get how webhook requests are verified, from capabilities.
+    pub fn webhook_verification(&self) -> WebhookVerification {
+        self.capabilities_file
+            .as_ref()
+            .map(|f| f.webhook_verification())
+            .unwrap_or_default()
+    }
+
     /// Get the webhook secret name from capabilities.
     pub fn webhook_secret_name(&self) -> String {
         self.capabilities_file
@@ -57,6 +57,7 @@
 //! | State pollution | Fresh instance per callback |
 //! | Workspace escape | Paths prefixed with `channels/<name>/` |
 //! | Message spam | Rate limiting on `emit_message` |
+//! | User flooding | Per-user rate limit defers or drops excess messages |
 //! | Resource exhaustion | Fuel metering, memory limits, callback timeout |
 //! | Polling abuse | Minimum 30s interval enforced |
 //!
@@ -90,9 +91,14 @@ mod wrapper;
 
 // Core types
 pub use bundled::{bundled_channel_names, install_bundled_channel};
-pub use capabilities::{ChannelCapabilities, EmitRateLimitConfig, HttpEndpointConfig, PollConfig};
+pub use capabilities::{
+    ChannelCapabilities, EmitRateLimitConfig, FloodAction, HttpEndpointConfig, PollConfig,
+    UserRateLimitConfig,
+};
 pub use error::WasmChannelError;
-pub use host::{ChannelEmitRateLimiter, ChannelHostState, EmittedMessage};
+pub use host::{
+    ChannelEmitRateLimiter, ChannelHostState, EmittedMessage, FloodDecision, UserRateLimiter,
+};
 pub use loader::{
     DiscoveredChannel, LoadResults, LoadedChannel, WasmChannelLoader, default_channels_dir,
     discover_channels,
@@ -101,5 +107,6 @@ pub use router::{RegisteredEndpoint, WasmChannelRouter, create_wasm_channel_rout
 pub use runtime::{PreparedChannelModule, WasmChannelRuntime, WasmChannelRuntimeConfig};
 pub use schema::{
     ChannelCapabilitiesFile, ChannelConfig, SecretSetupSchema, SetupSchema, WebhookSchema,
+    WebhookVerification,
 };
 pub use wrapper::{HttpResponse, SharedWasmChannel, WasmChannel};
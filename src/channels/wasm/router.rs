@@ -14,11 +14,19 @@ use axum::{
     response::IntoResponse,
     routing::{get, post},
 };
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
 use tokio::sync::RwLock;
 
+use crate::channels::wasm::schema::WebhookVerification;
 use crate::channels::wasm::wrapper::WasmChannel;
 
+/// How far a signed request's timestamp may be from now before it's treated
+/// as a replay. Slack recommends five minutes.
+const MAX_SIGNATURE_AGE_SECS: i64 = 5 * 60;
+
 /// A registered HTTP endpoint for a WASM channel.
 #[derive(Debug, Clone)]
 pub struct RegisteredEndpoint {
@@ -42,6 +50,8 @@ pub struct WasmChannelRouter {
     secrets: RwLock<HashMap<String, String>>,
     /// Webhook secret header names by channel name (e.g., "X-Telegram-Bot-Api-Secret-Token").
     secret_headers: RwLock<HashMap<String, String>>,
+    /// How each channel's secret is checked, if not a shared secret.
+    verifications: RwLock<HashMap<String, WebhookVerification>>,
 }
 
 impl WasmChannelRouter {
@@ -52,6 +62,7 @@ impl WasmChannelRouter {
             path_to_channel: RwLock::new(HashMap::new()),
             secrets: RwLock::new(HashMap::new()),
             secret_headers: RwLock::new(HashMap::new()),
+            verifications: RwLock::new(HashMap::new()),
         }
     }
 
@@ -110,11 +121,30 @@ impl WasmChannelRouter {
             .unwrap_or_else(|| "X-Webhook-Secret".to_string())
     }
 
+    /// Set how webhook requests to a channel are verified.
+    pub async fn set_verification(&self, channel_name: &str, verification: WebhookVerification) {
+        self.verifications
+            .write()
+            .await
+            .insert(channel_name.to_string(), verification);
+    }
+
+    /// Get how webhook requests to a channel are verified.
+    pub async fn verification(&self, channel_name: &str) -> WebhookVerification {
+        self.verifications
+            .read()
+            .await
+            .get(channel_name)
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Unregister a channel and its endpoints.
     pub async fn unregister(&self, channel_name: &str) {
         self.channels.write().await.remove(channel_name);
         self.secrets.write().await.remove(channel_name);
         self.secret_headers.write().await.remove(channel_name);
+        self.verifications.write().await.remove(channel_name);
 
         // Remove all paths for this channel
         self.path_to_channel
@@ -145,6 +175,27 @@ impl WasmChannelRouter {
         }
     }
 
+    /// Validate a Slack request signature for a channel.
+    pub async fn validate_slack_signature(
+        &self,
+        channel_name: &str,
+        timestamp: &str,
+        signature: &str,
+        body: &[u8],
+    ) -> bool {
+        let secrets = self.secrets.read().await;
+        match secrets.get(channel_name) {
+            Some(secret) => slack_signature_valid(
+                secret,
+                timestamp,
+                signature,
+                body,
+                chrono::Utc::now().timestamp(),
+            ),
+            None => true, // No secret required
+        }
+    }
+
     /// Check if a channel requires a secret.
     pub async fn requires_secret(&self, channel_name: &str) -> bool {
         self.secrets.read().await.contains_key(channel_name)
@@ -161,6 +212,40 @@ impl WasmChannelRouter {
     }
 }
 
+/// Check a Slack `X-Slack-Signature` header: `v0=` followed by the hex
+/// HMAC-SHA256 of `v0:{timestamp}:{body}`, keyed with the signing secret.
+fn slack_signature_valid(
+    secret: &str,
+    timestamp: &str,
+    signature: &str,
+    body: &[u8],
+    now: i64,
+) -> bool {
+    let Ok(sent_at) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    if (now - sent_at).abs() > MAX_SIGNATURE_AGE_SECS {
+        return false;
+    }
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(format!("v0:{}:", timestamp).as_bytes());
+    mac.update(body);
+    let expected: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    format!("v0={}", expected)
+        .as_bytes()
+        .ct_eq(signature.as_bytes())
+        .into()
+}
+
 impl Default for WasmChannelRouter {
     fn default() -> Self {
         Self::new()
@@ -264,54 +349,78 @@ async fn webhook_handler(
 
     // Check if secret is required
     if state.router.requires_secret(channel_name).await {
-        // Get the secret header name for this channel (from capabilities or default)
-        let secret_header_name = state.router.get_secret_header(channel_name).await;
-
-        // Try to get secret from query param or the channel's configured header
-        let provided_secret = query
-            .get("secret")
-            .cloned()
-            .or_else(|| {
-                headers
-                    .get(&secret_header_name)
-                    .and_then(|v| v.to_str().ok())
-                    .map(|s| s.to_string())
-            })
-            .or_else(|| {
-                // Fallback to generic header if different from configured
-                if secret_header_name != "X-Webhook-Secret" {
-                    headers
-                        .get("X-Webhook-Secret")
-                        .and_then(|v| v.to_str().ok())
-                        .map(|s| s.to_string())
-                } else {
-                    None
+        let verified = match state.router.verification(channel_name).await {
+            WebhookVerification::SlackSignature => {
+                let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+                match (
+                    header("X-Slack-Request-Timestamp"),
+                    header("X-Slack-Signature"),
+                ) {
+                    (Some(timestamp), Some(signature)) => Some(
+                        state
+                            .router
+                            .validate_slack_signature(channel_name, timestamp, signature, &body)
+                            .await,
+                    ),
+                    _ => None,
                 }
-            });
-
-        tracing::debug!(
-            channel = %channel_name,
-            has_provided_secret = provided_secret.is_some(),
-            provided_secret_len = provided_secret.as_ref().map(|s| s.len()),
-            "Checking webhook secret"
-        );
+            }
+            WebhookVerification::SharedSecret => {
+                // Get the secret header name for this channel (from capabilities or default)
+                let secret_header_name = state.router.get_secret_header(channel_name).await;
+
+                // Try to get secret from query param or the channel's configured header
+                let provided_secret = query
+                    .get("secret")
+                    .cloned()
+                    .or_else(|| {
+                        headers
+                            .get(&secret_header_name)
+                            .and_then(|v| v.to_str().ok())
+                            .map(|s| s.to_string())
+                    })
+                    .or_else(|| {
+                        // Fallback to generic header if different from configured
+                        if secret_header_name != "X-Webhook-Secret" {
+                            headers
+                                .get("X-Webhook-Secret")
+                                .and_then(|v| v.to_str().ok())
+                                .map(|s| s.to_string())
+                        } else {
+                            None
+                        }
+                    });
+
+                tracing::debug!(
+                    channel = %channel_name,
+                    has_provided_secret = provided_secret.is_some(),
+                    provided_secret_len = provided_secret.as_ref().map(|s| s.len()),
+                    "Checking webhook secret"
+                );
 
-        match provided_secret {
-            Some(secret) => {
-                if !state.router.validate_secret(channel_name, &secret).await {
-                    tracing::warn!(
-                        channel = %channel_name,
-                        "Webhook secret validation failed"
-                    );
-                    return (
-                        StatusCode::UNAUTHORIZED,
-                        Json(serde_json::json!({
-                            "error": "Invalid webhook secret"
-                        })),
-                    );
+                match provided_secret {
+                    Some(secret) => Some(state.router.validate_secret(channel_name, &secret).await),
+                    None => None,
                 }
+            }
+        };
+
+        match verified {
+            Some(true) => {
                 tracing::debug!(channel = %channel_name, "Webhook secret validated");
             }
+            Some(false) => {
+                tracing::warn!(
+                    channel = %channel_name,
+                    "Webhook secret validation failed"
+                );
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    Json(serde_json::json!({
+                        "error": "Invalid webhook secret"
+                    })),
+                );
+            }
             None => {
                 tracing::warn!(
                     channel = %channel_name,
@@ -473,7 +582,9 @@ mod tests {
     use std::sync::Arc;
 
     use crate::channels::wasm::capabilities::ChannelCapabilities;
-    use crate::channels::wasm::router::{RegisteredEndpoint, WasmChannelRouter};
+    use crate::channels::wasm::router::{
+        RegisteredEndpoint, WasmChannelRouter, slack_signature_valid,
+    };
     use crate::channels::wasm::runtime::{
         PreparedChannelModule, WasmChannelRuntime, WasmChannelRuntimeConfig,
     };
@@ -627,4 +738,42 @@ mod tests {
             .await;
         assert_eq!(router.get_secret_header("slack").await, "X-Webhook-Secret");
     }
+
+    #[test]
+    fn test_slack_signature() {
+        let body = br#"{"type":"event_callback"}"#;
+        let signature = "v0=8c8ea0ce250f36a087af12f8592da35f605ce730f086d896882637053da5628d";
+
+        assert!(slack_signature_valid(
+            "signing-secret",
+            "1700000000",
+            signature,
+            body,
+            1_700_000_060
+        ));
+        // Wrong secret
+        assert!(!slack_signature_valid(
+            "other-secret",
+            "1700000000",
+            signature,
+            body,
+            1_700_000_060
+        ));
+        // Tampered body
+        assert!(!slack_signature_valid(
+            "signing-secret",
+            "1700000000",
+            signature,
+            br#"{"type":"url_verification"}"#,
+            1_700_000_060
+        ));
+        // Replayed outside the allowed window
+        assert!(!slack_signature_valid(
+            "signing-secret",
+            "1700000000",
+            signature,
+            body,
+            1_700_001_000
+        ));
+    }
 }
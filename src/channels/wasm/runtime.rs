@@ -11,6 +11,7 @@ use std::time::Duration;
 use tokio::sync::RwLock;
 use wasmtime::{Config, Engine, OptLevel};
 
+use crate::channels::AttachmentStore;
 use crate::channels::wasm::error::WasmChannelError;
 use crate::tools::wasm::{FuelConfig, ResourceLimits};
 
@@ -29,6 +30,8 @@ pub struct WasmChannelRuntimeConfig {
     pub optimization_level: OptLevel,
     /// Default callback timeout.
     pub callback_timeout: Duration,
+    /// Where files exchanged with channels are stored.
+    pub attachments_dir: PathBuf,
 }
 
 impl Default for WasmChannelRuntimeConfig {
@@ -45,6 +48,7 @@ impl Default for WasmChannelRuntimeConfig {
             cache_dir: None,
             optimization_level: OptLevel::Speed,
             callback_timeout: Duration::from_secs(30),
+            attachments_dir: AttachmentStore::default_dir(),
         }
     }
 }
@@ -63,6 +67,7 @@ impl WasmChannelRuntimeConfig {
             cache_dir: None,
             optimization_level: OptLevel::None, // Faster compilation for tests
             callback_timeout: Duration::from_secs(5),
+            attachments_dir: std::env::temp_dir().join("ironclaw-test-attachments"),
         }
     }
 }
@@ -158,6 +163,11 @@ impl WasmChannelRuntime {
         &self.config
     }
 
+    /// Storage for files exchanged with channels.
+    pub fn attachments(&self) -> AttachmentStore {
+        AttachmentStore::new(&self.config.attachments_dir)
+    }
+
     /// Prepare a WASM channel component for execution.
     ///
     /// This validates and compiles the component.
@@ -29,7 +29,8 @@
 //!       "allowed_paths": ["/webhook/slack"],
 //!       "allow_polling": false,
 //!       "workspace_prefix": "channels/slack/",
-//!       "emit_rate_limit": { "messages_per_minute": 100 }
+//!       "emit_rate_limit": { "messages_per_minute": 100 },
+//!       "user_rate_limit": { "messages_per_minute": 20, "action": "defer" }
 //!     }
 //!   },
 //!   "config": {
@@ -43,8 +44,10 @@ use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+use crate::channels::FormattingCapabilities;
 use crate::channels::wasm::capabilities::{
-    ChannelCapabilities, EmitRateLimitConfig, MIN_POLL_INTERVAL_MS,
+    ChannelCapabilities, DEFAULT_MAX_DEFERRED_PER_USER, DEFAULT_USER_RATE_PER_MINUTE,
+    EmitRateLimitConfig, FloodAction, MIN_POLL_INTERVAL_MS, UserRateLimitConfig,
 };
 use crate::tools::wasm::{CapabilitiesFile as ToolCapabilitiesFile, RateLimitSchema};
 
@@ -111,6 +114,16 @@ impl ChannelCapabilitiesFile {
             .and_then(|w| w.secret_header.as_deref())
     }
 
+    /// Get how webhook requests to this channel are verified.
+    pub fn webhook_verification(&self) -> WebhookVerification {
+        self.capabilities
+            .channel
+            .as_ref()
+            .and_then(|c| c.webhook.as_ref())
+            .map(|w| w.verification)
+            .unwrap_or_default()
+    }
+
     /// Get the webhook secret name for this channel.
     ///
     /// Returns the configured secret name or defaults to "{channel_name}_webhook_secret".
@@ -162,6 +175,10 @@ impl ChannelCapabilitiesSchema {
                 caps.emit_rate_limit = rate.to_emit_rate_limit();
             }
 
+            if let Some(rate) = &channel.user_rate_limit {
+                caps.user_rate_limit = rate.to_user_rate_limit();
+            }
+
             if let Some(max_size) = channel.max_message_size {
                 caps.max_message_size = max_size;
             }
@@ -198,6 +215,11 @@ pub struct ChannelSpecificCapabilitiesSchema {
     #[serde(default)]
     pub emit_rate_limit: Option<EmitRateLimitSchema>,
 
+    /// How many messages one user may send per minute, and what happens to
+    /// the rest.
+    #[serde(default)]
+    pub user_rate_limit: Option<UserRateLimitSchema>,
+
     /// Maximum message content size in bytes.
     #[serde(default)]
     pub max_message_size: Option<usize>,
@@ -230,6 +252,23 @@ pub struct WebhookSchema {
     /// Default: "{channel_name}_webhook_secret"
     #[serde(default)]
     pub secret_name: Option<String>,
+
+    /// How the secret is checked against incoming requests.
+    #[serde(default)]
+    pub verification: WebhookVerification,
+}
+
+/// How a webhook request proves it came from the platform.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookVerification {
+    /// The request carries the secret itself, in `secret_header` or a
+    /// `secret` query parameter.
+    #[default]
+    SharedSecret,
+    /// Slack request signing: `X-Slack-Signature` holds an HMAC-SHA256 of
+    /// the timestamp and body, keyed with the signing secret.
+    SlackSignature,
 }
 
 /// Setup configuration schema.
@@ -319,6 +358,40 @@ impl From<RateLimitSchema> for EmitRateLimitSchema {
     }
 }
 
+/// Schema for per-user flood protection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserRateLimitSchema {
+    /// Maximum messages per user per minute (0 disables the limit).
+    #[serde(default = "default_user_messages_per_minute")]
+    pub messages_per_minute: u32,
+
+    /// Whether to defer or drop messages beyond the limit.
+    #[serde(default)]
+    pub action: FloodAction,
+
+    /// Maximum deferred messages per user.
+    #[serde(default = "default_max_deferred")]
+    pub max_deferred: u32,
+}
+
+fn default_user_messages_per_minute() -> u32 {
+    DEFAULT_USER_RATE_PER_MINUTE
+}
+
+fn default_max_deferred() -> u32 {
+    DEFAULT_MAX_DEFERRED_PER_USER
+}
+
+impl UserRateLimitSchema {
+    fn to_user_rate_limit(&self) -> UserRateLimitConfig {
+        UserRateLimitConfig {
+            messages_per_minute: self.messages_per_minute,
+            action: self.action,
+            max_deferred: self.max_deferred,
+        }
+    }
+}
+
 /// Channel configuration returned by on_start.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelConfig {
@@ -332,6 +405,10 @@ pub struct ChannelConfig {
     /// Polling configuration.
     #[serde(default)]
     pub poll: Option<PollConfigSchema>,
+
+    /// What the channel can display.
+    #[serde(default)]
+    pub formatting: FormattingCapabilities,
 }
 
 impl Default for ChannelConfig {
@@ -340,6 +417,7 @@ impl Default for ChannelConfig {
             display_name: "WASM Channel".to_string(),
             http_endpoints: Vec::new(),
             poll: None,
+            formatting: FormattingCapabilities::default(),
         }
     }
 }
@@ -372,7 +450,8 @@ pub struct PollConfigSchema {
 
 #[cfg(test)]
 mod tests {
-    use crate::channels::wasm::schema::ChannelCapabilitiesFile;
+    use crate::channels::wasm::capabilities::FloodAction;
+    use crate::channels::wasm::schema::{ChannelCapabilitiesFile, WebhookVerification};
 
     #[test]
     fn test_parse_minimal() {
@@ -514,6 +593,32 @@ mod tests {
         assert_eq!(caps.emit_rate_limit.messages_per_hour, 1000);
     }
 
+    #[test]
+    fn test_user_rate_limit() {
+        let json = r#"{
+            "name": "test",
+            "capabilities": {
+                "channel": {
+                    "user_rate_limit": { "messages_per_minute": 5, "action": "drop" }
+                }
+            }
+        }"#;
+
+        let caps = ChannelCapabilitiesFile::from_json(json)
+            .unwrap()
+            .to_capabilities();
+        assert_eq!(caps.user_rate_limit.messages_per_minute, 5);
+        assert_eq!(caps.user_rate_limit.action, FloodAction::Drop);
+        assert_eq!(caps.user_rate_limit.max_deferred, 10);
+
+        // Omitted section keeps the defaults
+        let caps = ChannelCapabilitiesFile::from_json(r#"{"name": "test"}"#)
+            .unwrap()
+            .to_capabilities();
+        assert_eq!(caps.user_rate_limit.messages_per_minute, 20);
+        assert_eq!(caps.user_rate_limit.action, FloodAction::Defer);
+    }
+
     #[test]
     fn test_webhook_schema() {
         let json = r#"{
@@ -535,6 +640,27 @@ mod tests {
             Some("X-Telegram-Bot-Api-Secret-Token")
         );
         assert_eq!(file.webhook_secret_name(), "telegram_webhook_secret");
+        assert_eq!(
+            file.webhook_verification(),
+            WebhookVerification::SharedSecret
+        );
+
+        let json = r#"{
+            "name": "slack",
+            "capabilities": {
+                "channel": {
+                    "webhook": {
+                        "secret_name": "slack_signing_secret",
+                        "verification": "slack_signature"
+                    }
+                }
+            }
+        }"#;
+        let file = ChannelCapabilitiesFile::from_json(json).unwrap();
+        assert_eq!(
+            file.webhook_verification(),
+            WebhookVerification::SlackSignature
+        );
     }
 
     #[test]
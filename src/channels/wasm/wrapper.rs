@@ -42,11 +42,16 @@ use wasmtime_wasi::{ResourceTable, WasiCtx, WasiCtxBuilder, WasiView};
 
 use crate::channels::wasm::capabilities::ChannelCapabilities;
 use crate::channels::wasm::error::WasmChannelError;
-use crate::channels::wasm::host::{ChannelEmitRateLimiter, ChannelHostState, EmittedMessage};
+use crate::channels::wasm::host::{
+    ChannelEmitRateLimiter, ChannelHostState, EmittedMessage, FloodDecision, UserRateLimiter,
+};
 use crate::channels::wasm::router::RegisteredEndpoint;
 use crate::channels::wasm::runtime::{PreparedChannelModule, WasmChannelRuntime};
 use crate::channels::wasm::schema::ChannelConfig;
-use crate::channels::{Channel, IncomingMessage, MessageStream, OutgoingResponse, StatusUpdate};
+use crate::channels::{
+    Attachment, AttachmentStore, Channel, FormattingCapabilities, IncomingMessage, MarkdownFlavor,
+    MessageStream, OutgoingResponse, StatusUpdate,
+};
 use crate::error::ChannelError;
 use crate::safety::LeakDetector;
 use crate::tools::wasm::LogLevel;
@@ -81,13 +86,15 @@ impl ChannelStoreData {
         channel_name: &str,
         capabilities: ChannelCapabilities,
         credentials: HashMap<String, String>,
+        attachments: AttachmentStore,
     ) -> Self {
         // Create a minimal WASI context (no filesystem, no env vars for security)
         let wasi = WasiCtxBuilder::new().build();
 
         Self {
             limiter: WasmResourceLimiter::new(memory_limit),
-            host_state: ChannelHostState::new(channel_name, capabilities),
+            host_state: ChannelHostState::new(channel_name, capabilities)
+                .with_attachment_store(attachments),
             wasi,
             table: ResourceTable::new(),
             credentials,
@@ -346,6 +353,24 @@ impl near::agent::channel_host::Host for ChannelStoreData {
         self.host_state.secret_exists(&name)
     }
 
+    fn store_attachment(
+        &mut self,
+        name: String,
+        mime_type: String,
+        data: Vec<u8>,
+    ) -> Result<near::agent::channel_host::Attachment, String> {
+        self.host_state
+            .store_attachment(&name, &mime_type, &data)
+            .map(|a| attachment_to_wit(&a))
+            .map_err(|e| e.to_string())
+    }
+
+    fn read_attachment(&mut self, id: String) -> Result<Vec<u8>, String> {
+        self.host_state
+            .read_attachment(&id)
+            .map_err(|e| e.to_string())
+    }
+
     fn emit_message(&mut self, msg: near::agent::channel_host::EmittedMessage) {
         tracing::info!(
             user_id = %msg.user_id,
@@ -362,6 +387,12 @@ impl near::agent::channel_host::Host for ChannelStoreData {
             emitted = emitted.with_thread_id(tid);
         }
         emitted = emitted.with_metadata(msg.metadata_json);
+        emitted = emitted.with_attachments(
+            msg.attachments
+                .into_iter()
+                .map(attachment_from_wit)
+                .collect(),
+        );
 
         match self.host_state.emit_message(emitted) {
             Ok(()) => {
@@ -407,6 +438,11 @@ pub struct WasmChannel {
     /// Wrapped in Arc for sharing with the polling task.
     rate_limiter: Arc<RwLock<ChannelEmitRateLimiter>>,
 
+    /// Each user's recent messages, checked before an emitted message is
+    /// forwarded to the agent.
+    /// Wrapped in Arc for sharing with the polling task.
+    user_limiter: Arc<RwLock<UserRateLimiter>>,
+
     /// Shutdown signal sender.
     shutdown_tx: RwLock<Option<oneshot::Sender<()>>>,
 
@@ -436,6 +472,7 @@ impl WasmChannel {
     ) -> Self {
         let name = prepared.name.clone();
         let rate_limiter = ChannelEmitRateLimiter::new(capabilities.emit_rate_limit.clone());
+        let user_limiter = UserRateLimiter::new(capabilities.user_rate_limit.clone());
 
         Self {
             name,
@@ -447,6 +484,7 @@ impl WasmChannel {
             message_tx: Arc::new(RwLock::new(None)),
             pending_responses: RwLock::new(HashMap::new()),
             rate_limiter: Arc::new(RwLock::new(rate_limiter)),
+            user_limiter: Arc::new(RwLock::new(user_limiter)),
             shutdown_tx: RwLock::new(None),
             poll_shutdown_tx: RwLock::new(None),
             endpoints: RwLock::new(Vec::new()),
@@ -543,6 +581,7 @@ impl WasmChannel {
             &prepared.name,
             capabilities.clone(),
             credentials,
+            runtime.attachments(),
         );
         let mut store = Store::new(engine, store_data);
 
@@ -633,6 +672,7 @@ impl WasmChannel {
                 display_name: self.prepared.description.clone(),
                 http_endpoints: Vec::new(),
                 poll: None,
+                formatting: FormattingCapabilities::default(),
             });
         }
 
@@ -896,6 +936,7 @@ impl WasmChannel {
         content: &str,
         thread_id: Option<&str>,
         metadata_json: &str,
+        attachments: &[Attachment],
     ) -> Result<(), WasmChannelError> {
         tracing::info!(
             channel = %self.name,
@@ -935,6 +976,7 @@ impl WasmChannel {
         let content = content.to_string();
         let thread_id = thread_id.map(|s| s.to_string());
         let metadata_json = metadata_json.to_string();
+        let attachments = attachments.to_vec();
 
         // Execute in blocking task with timeout
         tracing::info!(channel = %channel_name, "Starting on_respond WASM execution");
@@ -948,12 +990,21 @@ impl WasmChannel {
                 tracing::info!("Instantiating WASM component for on_respond");
                 let instance = Self::instantiate_component(&runtime, &prepared, &mut store)?;
 
+                // The channel may read the files it is asked to deliver
+                for attachment in &attachments {
+                    store
+                        .data_mut()
+                        .host_state
+                        .allow_attachment_read(attachment.id.clone());
+                }
+
                 // Build the WIT response type
                 let wit_response = wit_channel::AgentResponse {
                     message_id: message_id_str,
                     content: content.clone(),
                     thread_id,
                     metadata_json,
+                    attachments: attachments.iter().map(attachment_to_wit).collect(),
                 };
 
                 // Truncate at char boundary for logging (avoid panic on multi-byte UTF-8)
@@ -1208,6 +1259,28 @@ impl WasmChannel {
             StatusUpdate::StreamChunk(_) => {
                 // No-op, too noisy
             }
+            StatusUpdate::ApprovalNeeded {
+                tool_name,
+                description,
+                ..
+            } => {
+                // Sent as a regular message so the user can answer it, with
+                // buttons for channels that can render them
+                self.cancel_typing_task().await;
+
+                let (content, metadata) = approval_prompt(tool_name, description, metadata);
+                let metadata_json = serde_json::to_string(&metadata).unwrap_or_default();
+                if let Err(e) = self
+                    .call_on_respond(Uuid::new_v4(), &content, None, &metadata_json, &[])
+                    .await
+                {
+                    tracing::warn!(
+                        channel = %self.name,
+                        error = %e,
+                        "Failed to send approval prompt"
+                    );
+                }
+            }
             _ => {
                 // Done, Interrupted, Status, ToolStarted, ToolCompleted: cancel and fire once
                 self.cancel_typing_task().await;
@@ -1252,8 +1325,14 @@ impl WasmChannel {
         };
 
         let mut rate_limiter = self.rate_limiter.write().await;
+        let mut user_limiter = self.user_limiter.write().await;
 
         for emitted in messages {
+            let Some(delay) = Self::check_flood(&self.name, &mut user_limiter, &emitted.user_id)
+            else {
+                continue;
+            };
+
             // Check rate limit
             if !rate_limiter.check_and_record() {
                 tracing::warn!(
@@ -1281,6 +1360,13 @@ impl WasmChannel {
                 msg = msg.with_metadata(metadata);
             }
 
+            msg = msg.with_attachments(emitted.attachments);
+
+            if !delay.is_zero() {
+                Self::send_deferred(tx.clone(), msg, delay);
+                continue;
+            }
+
             // Send to stream
             tracing::info!(
                 channel = %self.name,
@@ -1318,6 +1404,7 @@ impl WasmChannel {
         let capabilities = self.capabilities.clone();
         let message_tx = self.message_tx.clone();
         let rate_limiter = self.rate_limiter.clone();
+        let user_limiter = self.user_limiter.clone();
         let credentials = self.credentials.clone();
         let callback_timeout = self.runtime.config().callback_timeout;
 
@@ -1352,6 +1439,7 @@ impl WasmChannel {
                                         emitted_messages,
                                         &message_tx,
                                         &rate_limiter,
+                                        &user_limiter,
                                     ).await {
                                         tracing::warn!(
                                             channel = %channel_name,
@@ -1451,6 +1539,47 @@ impl WasmChannel {
         }
     }
 
+    /// Apply per-user flood protection to a message from `user_id`.
+    ///
+    /// Returns how long to hold the message back (zero to send it now), or
+    /// `None` if it should be dropped.
+    fn check_flood(
+        channel_name: &str,
+        user_limiter: &mut UserRateLimiter,
+        user_id: &str,
+    ) -> Option<Duration> {
+        match user_limiter.check(user_id) {
+            FloodDecision::Allow => Some(Duration::ZERO),
+            FloodDecision::Defer(delay) => {
+                tracing::info!(
+                    channel = %channel_name,
+                    user_id = %user_id,
+                    delay_ms = delay.as_millis() as u64,
+                    "User over message rate limit, deferring message"
+                );
+                Some(delay)
+            }
+            FloodDecision::Drop => {
+                tracing::warn!(
+                    channel = %channel_name,
+                    user_id = %user_id,
+                    "User flooding channel, dropping message"
+                );
+                None
+            }
+        }
+    }
+
+    /// Send a message to the agent after `delay`.
+    fn send_deferred(tx: mpsc::Sender<IncomingMessage>, msg: IncomingMessage, delay: Duration) {
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            if tx.send(msg).await.is_err() {
+                tracing::debug!("Channel closed before deferred message was sent");
+            }
+        });
+    }
+
     /// Dispatch emitted messages to the message channel.
     ///
     /// This is a static helper used by the polling loop since it doesn't have
@@ -1460,6 +1589,7 @@ impl WasmChannel {
         messages: Vec<EmittedMessage>,
         message_tx: &RwLock<Option<mpsc::Sender<IncomingMessage>>>,
         rate_limiter: &RwLock<ChannelEmitRateLimiter>,
+        user_limiter: &RwLock<UserRateLimiter>,
     ) -> Result<(), WasmChannelError> {
         tracing::info!(
             channel = %channel_name,
@@ -1478,8 +1608,14 @@ impl WasmChannel {
         };
 
         let mut limiter = rate_limiter.write().await;
+        let mut user_limiter = user_limiter.write().await;
 
         for emitted in messages {
+            let Some(delay) = Self::check_flood(channel_name, &mut user_limiter, &emitted.user_id)
+            else {
+                continue;
+            };
+
             // Check rate limit
             if !limiter.check_and_record() {
                 tracing::warn!(
@@ -1507,6 +1643,13 @@ impl WasmChannel {
                 msg = msg.with_metadata(metadata);
             }
 
+            msg = msg.with_attachments(emitted.attachments);
+
+            if !delay.is_zero() {
+                Self::send_deferred(tx.clone(), msg, delay);
+                continue;
+            }
+
             // Send to stream
             tracing::info!(
                 channel = %channel_name,
@@ -1625,15 +1768,17 @@ impl Channel for WasmChannel {
         }
 
         // Call WASM on_respond
-        // IMPORTANT: Use the ORIGINAL message's metadata, not the response's metadata.
+        // IMPORTANT: Build on the ORIGINAL message's metadata, not the response's metadata.
         // The original metadata contains channel-specific routing info (e.g., Telegram chat_id)
         // that the WASM channel needs to send the reply to the correct destination.
-        let metadata_json = serde_json::to_string(&msg.metadata).unwrap_or_default();
+        let metadata = respond_metadata(&msg.metadata, &response.metadata);
+        let metadata_json = serde_json::to_string(&metadata).unwrap_or_default();
         self.call_on_respond(
             msg.id,
             &response.content,
             response.thread_id.as_deref(),
             &metadata_json,
+            &response.attachments,
         )
         .await
         .map_err(|e| ChannelError::SendFailed {
@@ -1653,6 +1798,16 @@ impl Channel for WasmChannel {
         self.handle_status_update(status, metadata).await
     }
 
+    async fn formatting(&self) -> FormattingCapabilities {
+        // Declared by on_start; defaults until the channel has started
+        self.channel_config
+            .read()
+            .await
+            .as_ref()
+            .map(|config| config.formatting.clone())
+            .unwrap_or_default()
+    }
+
     async fn health_check(&self) -> Result<(), ChannelError> {
         // Check if we have an active message sender
         if self.message_tx.read().await.is_some() {
@@ -1756,6 +1911,10 @@ impl Channel for SharedWasmChannel {
         self.inner.send_status(status, metadata).await
     }
 
+    async fn formatting(&self) -> FormattingCapabilities {
+        self.inner.formatting().await
+    }
+
     async fn health_check(&self) -> Result<(), ChannelError> {
         self.inner.health_check().await
     }
@@ -1772,6 +1931,24 @@ impl Channel for SharedWasmChannel {
 // Type aliases for the generated WIT types (exported interface)
 use exports::near::agent::channel as wit_channel;
 
+fn attachment_to_wit(attachment: &Attachment) -> near::agent::channel_host::Attachment {
+    near::agent::channel_host::Attachment {
+        id: attachment.id.clone(),
+        name: attachment.name.clone(),
+        mime_type: attachment.mime_type.clone(),
+        size: attachment.size,
+    }
+}
+
+fn attachment_from_wit(attachment: near::agent::channel_host::Attachment) -> Attachment {
+    Attachment {
+        id: attachment.id,
+        name: attachment.name,
+        mime_type: attachment.mime_type,
+        size: attachment.size,
+    }
+}
+
 /// Convert WIT-generated ChannelConfig to our internal type.
 fn convert_channel_config(wit: wit_channel::ChannelConfig) -> ChannelConfig {
     ChannelConfig {
@@ -1793,6 +1970,21 @@ fn convert_channel_config(wit: wit_channel::ChannelConfig) -> ChannelConfig {
                 interval_ms: p.interval_ms,
                 enabled: p.enabled,
             }),
+        formatting: convert_formatting(wit.formatting),
+    }
+}
+
+fn convert_formatting(wit: wit_channel::FormattingCapabilities) -> FormattingCapabilities {
+    FormattingCapabilities {
+        markdown: match wit.markdown {
+            wit_channel::MarkdownFlavor::Plain => MarkdownFlavor::Plain,
+            wit_channel::MarkdownFlavor::Markdown => MarkdownFlavor::Markdown,
+            wit_channel::MarkdownFlavor::SlackMrkdwn => MarkdownFlavor::SlackMrkdwn,
+        },
+        max_message_length: wit.max_message_length.map(|len| len as usize),
+        supports_buttons: wit.supports_buttons,
+        supports_threads: wit.supports_threads,
+        supports_files: wit.supports_files,
     }
 }
 
@@ -1807,6 +1999,45 @@ fn convert_http_response(wit: wit_channel::OutgoingHttpResponse) -> HttpResponse
 }
 
 /// Convert a StatusUpdate + metadata into the WIT StatusUpdate type.
+/// Metadata for `on_respond`: the incoming message's routing info plus any
+/// keys the response adds, such as `buttons`. Routing keys win on conflict.
+fn respond_metadata(
+    original: &serde_json::Value,
+    response: &serde_json::Value,
+) -> serde_json::Value {
+    let (Some(original_obj), Some(extra)) = (original.as_object(), response.as_object()) else {
+        return original.clone();
+    };
+    let mut merged = original_obj.clone();
+    for (key, value) in extra {
+        merged.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+    serde_json::Value::Object(merged)
+}
+
+/// Text and metadata for an approval prompt.
+///
+/// Button data matches the replies the submission parser accepts as approval
+/// answers, so a press works exactly like typing the word.
+fn approval_prompt(
+    tool_name: &str,
+    description: &str,
+    metadata: &serde_json::Value,
+) -> (String, serde_json::Value) {
+    let content = format!(
+        "Approval needed: {}\n{}\n\nReply yes, no, or always.",
+        tool_name, description
+    );
+    let buttons = serde_json::json!({
+        "buttons": [[
+            { "text": "Approve", "data": "yes" },
+            { "text": "Always", "data": "always" },
+            { "text": "Deny", "data": "no" },
+        ]]
+    });
+    (content, respond_metadata(metadata, &buttons))
+}
+
 fn status_to_wit(status: &StatusUpdate, metadata: &serde_json::Value) -> wit_channel::StatusUpdate {
     let metadata_json = serde_json::to_string(metadata).unwrap_or_default();
 
@@ -1816,7 +2047,7 @@ fn status_to_wit(status: &StatusUpdate, metadata: &serde_json::Value) -> wit_cha
             message: msg.clone(),
             metadata_json,
         },
-        StatusUpdate::ToolStarted { name } => wit_channel::StatusUpdate {
+        StatusUpdate::ToolStarted { name, .. } => wit_channel::StatusUpdate {
             status: wit_channel::StatusType::ToolStarted,
             message: name.clone(),
             metadata_json,
@@ -1826,7 +2057,7 @@ fn status_to_wit(status: &StatusUpdate, metadata: &serde_json::Value) -> wit_cha
             message: format!("{}: {}", name, if *success { "ok" } else { "failed" }),
             metadata_json,
         },
-        StatusUpdate::ToolResult { name, preview } => wit_channel::StatusUpdate {
+        StatusUpdate::ToolResult { name, preview, .. } => wit_channel::StatusUpdate {
             status: wit_channel::StatusType::ToolCompleted,
             message: format!("{}: {}", name, preview),
             metadata_json,
@@ -2062,6 +2293,11 @@ mod tests {
                 crate::channels::wasm::capabilities::EmitRateLimitConfig::default(),
             ),
         ));
+        let user_limiter = Arc::new(tokio::sync::RwLock::new(
+            crate::channels::wasm::host::UserRateLimiter::new(
+                crate::channels::wasm::capabilities::UserRateLimitConfig::default(),
+            ),
+        ));
 
         let messages = vec![
             EmittedMessage::new("user1", "Hello from polling!"),
@@ -2073,6 +2309,7 @@ mod tests {
             messages,
             &message_tx,
             &rate_limiter,
+            &user_limiter,
         )
         .await;
 
@@ -2091,6 +2328,47 @@ mod tests {
         assert!(rx.try_recv().is_err());
     }
 
+    #[tokio::test]
+    async fn test_dispatch_emitted_messages_drops_flood() {
+        use crate::channels::wasm::capabilities::{FloodAction, UserRateLimitConfig};
+        use crate::channels::wasm::host::EmittedMessage;
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        let message_tx = Arc::new(tokio::sync::RwLock::new(Some(tx)));
+        let rate_limiter = Arc::new(tokio::sync::RwLock::new(
+            crate::channels::wasm::host::ChannelEmitRateLimiter::new(
+                crate::channels::wasm::capabilities::EmitRateLimitConfig::default(),
+            ),
+        ));
+        let user_limiter = Arc::new(tokio::sync::RwLock::new(
+            crate::channels::wasm::host::UserRateLimiter::new(UserRateLimitConfig {
+                messages_per_minute: 1,
+                action: FloodAction::Drop,
+                max_deferred: 0,
+            }),
+        ));
+
+        let messages = vec![
+            EmittedMessage::new("user1", "first"),
+            EmittedMessage::new("user1", "second"),
+            EmittedMessage::new("user2", "other user"),
+        ];
+
+        let result = WasmChannel::dispatch_emitted_messages(
+            "test-channel",
+            messages,
+            &message_tx,
+            &rate_limiter,
+            &user_limiter,
+        )
+        .await;
+        assert!(result.is_ok());
+
+        assert_eq!(rx.try_recv().unwrap().content, "first");
+        assert_eq!(rx.try_recv().unwrap().content, "other user");
+        assert!(rx.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn test_dispatch_emitted_messages_no_sender_returns_ok() {
         use crate::channels::wasm::host::EmittedMessage;
@@ -2102,6 +2380,11 @@ mod tests {
                 crate::channels::wasm::capabilities::EmitRateLimitConfig::default(),
             ),
         ));
+        let user_limiter = Arc::new(tokio::sync::RwLock::new(
+            crate::channels::wasm::host::UserRateLimiter::new(
+                crate::channels::wasm::capabilities::UserRateLimitConfig::default(),
+            ),
+        ));
 
         let messages = vec![EmittedMessage::new("user1", "Hello!")];
 
@@ -2111,6 +2394,7 @@ mod tests {
             messages,
             &message_tx,
             &rate_limiter,
+            &user_limiter,
         )
         .await;
 
@@ -2358,6 +2642,42 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_respond_metadata_keeps_routing() {
+        use super::respond_metadata;
+
+        let original = serde_json::json!({"chat_id": 42, "message_id": 7});
+        let response =
+            serde_json::json!({"chat_id": 1, "buttons": [[{"text": "Yes", "data": "yes"}]]});
+        let merged = respond_metadata(&original, &response);
+        assert_eq!(merged["chat_id"], 42);
+        assert_eq!(merged["buttons"][0][0]["data"], "yes");
+
+        // A response without metadata leaves the original untouched
+        let merged = respond_metadata(&original, &serde_json::Value::Null);
+        assert_eq!(merged, original);
+    }
+
+    #[test]
+    fn test_approval_prompt_has_answer_buttons() {
+        use super::approval_prompt;
+
+        let (content, metadata) = approval_prompt(
+            "shell",
+            "rm -rf build/",
+            &serde_json::json!({"chat_id": 42}),
+        );
+        assert!(content.contains("shell"));
+        assert_eq!(metadata["chat_id"], 42);
+        let answers: Vec<&str> = metadata["buttons"][0]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| b["data"].as_str().unwrap())
+            .collect();
+        assert_eq!(answers, vec!["yes", "always", "no"]);
+    }
+
     #[test]
     fn test_clone_wit_status_update() {
         use super::{clone_wit_status_update, wit_channel};
@@ -245,7 +245,7 @@ impl Channel for GatewayChannel {
                 message: msg,
                 thread_id: thread_id.clone(),
             },
-            StatusUpdate::ToolStarted { name } => SseEvent::ToolStarted {
+            StatusUpdate::ToolStarted { name, .. } => SseEvent::ToolStarted {
                 name,
                 thread_id: thread_id.clone(),
             },
@@ -254,7 +254,7 @@ impl Channel for GatewayChannel {
                 success,
                 thread_id: thread_id.clone(),
             },
-            StatusUpdate::ToolResult { name, preview } => SseEvent::ToolResult {
+            StatusUpdate::ToolResult { name, preview, .. } => SseEvent::ToolResult {
                 name,
                 preview,
                 thread_id: thread_id.clone(),
@@ -1,6 +1,6 @@
 //! Axum HTTP server for the web gateway.
 //!
-//! Handles all API routes: chat, memory, jobs, health, and static file serving.
+//! Handles all API routes: chat, memory, jobs, health, metrics, and static file serving.
 
 use std::convert::Infallible;
 use std::net::SocketAddr;
@@ -186,6 +186,7 @@ pub async fn start_server(
         .route("/api/chat/thread/rename", post(chat_rename_thread_handler))
         .route("/api/chat/thread/delete", post(chat_delete_thread_handler))
         .route("/api/chat/rollback", post(chat_rollback_handler))
+        .route("/api/conversations", get(conversations_list_handler))
         // Memory
         .route("/api/memory/tree", get(memory_tree_handler))
         .route("/api/memory/list", get(memory_list_handler))
@@ -193,7 +194,10 @@ pub async fn start_server(
         .route("/api/memory/write", post(memory_write_handler))
         .route("/api/memory/search", post(memory_search_handler))
         // Jobs
-        .route("/api/jobs", get(jobs_list_handler))
+        .route(
+            "/api/jobs",
+            get(jobs_list_handler).post(jobs_create_handler),
+        )
         .route("/api/jobs/summary", get(jobs_summary_handler))
         .route("/api/jobs/{id}", get(jobs_detail_handler))
         .route("/api/jobs/{id}/cancel", post(jobs_cancel_handler))
@@ -218,11 +222,18 @@ pub async fn start_server(
             post(extensions_remove_handler),
         )
         // Routines
-        .route("/api/routines", get(routines_list_handler))
+        .route(
+            "/api/routines",
+            get(routines_list_handler).post(routines_create_handler),
+        )
         .route("/api/routines/summary", get(routines_summary_handler))
         .route("/api/routines/{id}", get(routines_detail_handler))
         .route("/api/routines/{id}/trigger", post(routines_trigger_handler))
         .route("/api/routines/{id}/toggle", post(routines_toggle_handler))
+        .route(
+            "/api/routines/{id}",
+            axum::routing::put(routines_update_handler),
+        )
         .route(
             "/api/routines/{id}",
             axum::routing::delete(routines_delete_handler),
@@ -243,6 +254,8 @@ pub async fn start_server(
         )
         // Gateway control plane
         .route("/api/gateway/status", get(gateway_status_handler))
+        // Prometheus scrape endpoint
+        .route("/metrics", get(metrics_handler))
         // OpenAI-compatible API
         .route(
             "/v1/chat/completions",
@@ -737,6 +750,7 @@ async fn chat_history_handler(
                         has_error: tc.error.is_some(),
                     })
                     .collect(),
+                usage: None,
             })
             .collect::<Vec<_>>()
     } else {
@@ -859,6 +873,7 @@ fn build_turns_from_db_messages(messages: &[crate::history::ConversationMessage]
                 started_at: msg.created_at.to_rfc3339(),
                 completed_at: None,
                 tool_calls: Vec::new(),
+                usage: None,
             };
 
             // Check if next message is an assistant response
@@ -868,6 +883,7 @@ fn build_turns_from_db_messages(messages: &[crate::history::ConversationMessage]
                 let assistant_msg = iter.next().expect("peeked");
                 turn.response = Some(assistant_msg.content.clone());
                 turn.completed_at = Some(assistant_msg.created_at.to_rfc3339());
+                turn.usage = assistant_msg.usage.clone();
             }
 
             // Incomplete turn (user message without response)
@@ -989,6 +1005,70 @@ async fn chat_threads_handler(
     }))
 }
 
+#[derive(Deserialize)]
+struct ConversationListQuery {
+    limit: Option<usize>,
+    before: Option<String>,
+}
+
+/// Paginated conversation list, newest activity first.
+///
+/// Pass the returned `next_before` as `?before=` to fetch the next page.
+async fn conversations_list_handler(
+    State(state): State<Arc<GatewayState>>,
+    Query(query): Query<ConversationListQuery>,
+) -> Result<Json<ConversationListResponse>, (StatusCode, String)> {
+    let store = state.store.as_ref().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Database not available".to_string(),
+    ))?;
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let before = query
+        .before
+        .as_deref()
+        .map(|s| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .map_err(|_| {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        "Invalid 'before' timestamp".to_string(),
+                    )
+                })
+        })
+        .transpose()?;
+
+    let (summaries, has_more) = store
+        .list_conversations_paginated(&state.user_id, "gateway", limit, before)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let next_before = if has_more {
+        summaries.last().map(|s| s.last_activity.to_rfc3339())
+    } else {
+        None
+    };
+
+    let conversations = summaries
+        .iter()
+        .map(|s| ThreadInfo {
+            id: s.id,
+            state: "Idle".to_string(),
+            turn_count: (s.message_count / 2).max(0) as usize,
+            created_at: s.started_at.to_rfc3339(),
+            updated_at: s.last_activity.to_rfc3339(),
+            title: s.title.clone(),
+            thread_type: s.thread_type.clone(),
+        })
+        .collect();
+
+    Ok(Json(ConversationListResponse {
+        conversations,
+        has_more,
+        next_before,
+    }))
+}
 
 async fn chat_new_thread_handler(
     State(state): State<Arc<GatewayState>>,
@@ -1301,8 +1381,14 @@ async fn memory_search_handler(
 
 // --- Jobs handlers ---
 
+#[derive(Deserialize)]
+struct JobListQuery {
+    limit: Option<usize>,
+}
+
 async fn jobs_list_handler(
     State(state): State<Arc<GatewayState>>,
+    Query(query): Query<JobListQuery>,
 ) -> Result<Json<JobListResponse>, (StatusCode, String)> {
     let store = state.store.as_ref().ok_or((
         StatusCode::SERVICE_UNAVAILABLE,
@@ -1310,8 +1396,9 @@ async fn jobs_list_handler(
     ))?;
 
     // Fetch sandbox jobs scoped to the authenticated user.
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
     let sandbox_jobs = store
-        .list_sandbox_jobs_for_user(&state.user_id, 50)
+        .list_sandbox_jobs_for_user(&state.user_id, limit)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
@@ -1342,6 +1429,91 @@ async fn jobs_list_handler(
     Ok(Json(JobListResponse { jobs }))
 }
 
+/// Create and start a sandbox job, the REST counterpart of the `create_job` tool.
+async fn jobs_create_handler(
+    State(state): State<Arc<GatewayState>>,
+    Json(req): Json<JobCreateRequest>,
+) -> Result<Json<JobCreateResponse>, (StatusCode, String)> {
+    let store = state.store.as_ref().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Database not available".to_string(),
+    ))?;
+    let jm = state.job_manager.as_ref().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Sandbox not enabled".to_string(),
+    ))?;
+
+    let task = req.task.trim();
+    if task.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Task is required".to_string()));
+    }
+    let mode = match req.mode.as_deref() {
+        None | Some("worker") => crate::orchestrator::job_manager::JobMode::Worker,
+        Some("claude_code") => crate::orchestrator::job_manager::JobMode::ClaudeCode,
+        Some(other) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Unknown mode '{}': expected worker or claude_code", other),
+            ));
+        }
+    };
+
+    let job_id = Uuid::new_v4();
+    let (project_dir, browse_id) = crate::tools::builtin::resolve_project_dir(
+        req.project_dir.map(std::path::PathBuf::from),
+        job_id,
+    )
+    .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let project_dir_str = project_dir.display().to_string();
+
+    let now = chrono::Utc::now();
+    let record = crate::history::SandboxJobRecord {
+        id: job_id,
+        task: task.to_string(),
+        status: "creating".to_string(),
+        user_id: state.user_id.clone(),
+        project_dir: project_dir_str.clone(),
+        success: None,
+        failure_reason: None,
+        created_at: now,
+        started_at: None,
+        completed_at: None,
+    };
+    store
+        .save_sandbox_job(&record)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if let Err(e) = jm.create_job(job_id, task, Some(project_dir), mode).await {
+        let _ = store
+            .update_sandbox_job_status(
+                job_id,
+                "failed",
+                Some(false),
+                Some(e.to_string()),
+                None,
+                Some(chrono::Utc::now()),
+            )
+            .await;
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to create container: {}", e),
+        ));
+    }
+
+    store
+        .update_sandbox_job_status(job_id, "running", None, None, Some(now), None)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(JobCreateResponse {
+        job_id,
+        status: "started",
+        project_dir: project_dir_str,
+        browse_url: format!("/projects/{}", browse_id),
+    }))
+}
+
 async fn jobs_summary_handler(
     State(state): State<Arc<GatewayState>>,
 ) -> Result<Json<JobSummaryResponse>, (StatusCode, String)> {
@@ -2165,8 +2337,7 @@ async fn routines_detail_handler(
         "Database not available".to_string(),
     ))?;
 
-    let routine_id = Uuid::parse_str(&id)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid routine ID".to_string()))?;
+    let routine_id = owned_routine_id(store.as_ref(), &state.user_id, &id).await?;
 
     let routine = store
         .get_routine(routine_id)
@@ -2179,35 +2350,7 @@ async fn routines_detail_handler(
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    let recent_runs: Vec<RoutineRunInfo> = runs
-        .iter()
-        .map(|run| RoutineRunInfo {
-            id: run.id,
-            trigger_type: run.trigger_type.clone(),
-            started_at: run.started_at.to_rfc3339(),
-            completed_at: run.completed_at.map(|dt| dt.to_rfc3339()),
-            status: format!("{:?}", run.status),
-            result_summary: run.result_summary.clone(),
-            tokens_used: run.tokens_used,
-        })
-        .collect();
-
-    Ok(Json(RoutineDetailResponse {
-        id: routine.id,
-        name: routine.name.clone(),
-        description: routine.description.clone(),
-        enabled: routine.enabled,
-        trigger: serde_json::to_value(&routine.trigger).unwrap_or_default(),
-        action: serde_json::to_value(&routine.action).unwrap_or_default(),
-        guardrails: serde_json::to_value(&routine.guardrails).unwrap_or_default(),
-        notify: serde_json::to_value(&routine.notify).unwrap_or_default(),
-        last_run_at: routine.last_run_at.map(|dt| dt.to_rfc3339()),
-        next_fire_at: routine.next_fire_at.map(|dt| dt.to_rfc3339()),
-        run_count: routine.run_count,
-        consecutive_failures: routine.consecutive_failures,
-        created_at: routine.created_at.to_rfc3339(),
-        recent_runs,
-    }))
+    Ok(Json(routine_to_detail(&routine, runs)))
 }
 
 async fn routines_trigger_handler(
@@ -2219,8 +2362,7 @@ async fn routines_trigger_handler(
         "Database not available".to_string(),
     ))?;
 
-    let routine_id = Uuid::parse_str(&id)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid routine ID".to_string()))?;
+    let routine_id = owned_routine_id(store.as_ref(), &state.user_id, &id).await?;
 
     let routine = store
         .get_routine(routine_id)
@@ -2236,8 +2378,15 @@ async fn routines_trigger_handler(
         } => format!("{}: {}", title, description),
     };
 
-    let content = format!("[routine:{}] {}", routine.name, prompt);
-    let msg = IncomingMessage::new("gateway", &state.user_id, content);
+    let content = format!(
+        "{}{}] {}",
+        crate::agent::priority::ROUTINE_MESSAGE_PREFIX,
+        routine.name,
+        prompt
+    );
+    let msg = IncomingMessage::new("gateway", &state.user_id, content).with_metadata(
+        serde_json::json!({ "routine_id": routine_id, "routine_trigger": "manual" }),
+    );
 
     let tx_guard = state.msg_tx.read().await;
     let tx = tx_guard.as_ref().ok_or((
@@ -2258,6 +2407,141 @@ async fn routines_trigger_handler(
     })))
 }
 
+async fn routines_create_handler(
+    State(state): State<Arc<GatewayState>>,
+    Json(req): Json<RoutineCreateRequest>,
+) -> Result<Json<RoutineDetailResponse>, (StatusCode, String)> {
+    let store = state.store.as_ref().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Database not available".to_string(),
+    ))?;
+
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Routine name is required".to_string(),
+        ));
+    }
+
+    let trigger: crate::agent::routine::Trigger = serde_json::from_value(req.trigger)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid trigger: {}", e)))?;
+    let action: crate::agent::routine::RoutineAction = serde_json::from_value(req.action)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid action: {}", e)))?;
+
+    let now = chrono::Utc::now();
+    let next_fire_at = trigger
+        .next_fire_after(now)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let routine = crate::agent::routine::Routine {
+        id: Uuid::new_v4(),
+        name: name.to_string(),
+        description: req.description,
+        enabled: req.enabled.unwrap_or(true),
+        trigger,
+        action,
+        guardrails: req.guardrails.unwrap_or_else(|| json!({})),
+        notify: req.notify.unwrap_or_else(|| json!({})),
+        last_run_at: None,
+        next_fire_at,
+        run_count: 0,
+        consecutive_failures: 0,
+        created_at: now,
+        updated_at: now,
+        version: 1,
+    };
+
+    store
+        .create_routine(&state.user_id, &routine)
+        .await
+        .map_err(|e| match e {
+            crate::error::DatabaseError::Constraint(_) => (
+                StatusCode::CONFLICT,
+                format!("A routine named '{}' already exists", routine.name),
+            ),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
+
+    Ok(Json(routine_to_detail(&routine, Vec::new())))
+}
+
+async fn routines_update_handler(
+    State(state): State<Arc<GatewayState>>,
+    Path(id): Path<String>,
+    Json(req): Json<RoutineUpdateRequest>,
+) -> Result<Json<RoutineDetailResponse>, (StatusCode, String)> {
+    let store = state.store.as_ref().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Database not available".to_string(),
+    ))?;
+
+    let routine_id = owned_routine_id(store.as_ref(), &state.user_id, &id).await?;
+
+    let mut routine = store
+        .get_routine(routine_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Routine not found".to_string()))?;
+
+    if let Some(version) = req.version
+        && version != routine.version
+    {
+        return Err(routine_conflict(routine.version));
+    }
+    if let Some(name) = req.name {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Routine name is required".to_string(),
+            ));
+        }
+        routine.name = name.to_string();
+    }
+    if let Some(description) = req.description {
+        routine.description = description;
+    }
+    if let Some(enabled) = req.enabled {
+        routine.enabled = enabled;
+    }
+    if let Some(trigger) = req.trigger {
+        routine.trigger = serde_json::from_value(trigger)
+            .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid trigger: {}", e)))?;
+        routine.next_fire_at = routine
+            .trigger
+            .next_fire_after(chrono::Utc::now())
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
+    if let Some(action) = req.action {
+        routine.action = serde_json::from_value(action)
+            .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid action: {}", e)))?;
+    }
+    if let Some(guardrails) = req.guardrails {
+        routine.guardrails = guardrails;
+    }
+    if let Some(notify) = req.notify {
+        routine.notify = notify;
+    }
+
+    routine.version = store.update_routine(&routine).await.map_err(|e| match e {
+        crate::error::DatabaseError::Constraint(_) => (
+            StatusCode::CONFLICT,
+            format!("A routine named '{}' already exists", routine.name),
+        ),
+        crate::error::DatabaseError::Conflict { current, .. } => routine_conflict(current),
+        e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })?;
+    routine.updated_at = chrono::Utc::now();
+
+    let runs = store
+        .list_routine_runs(routine_id, 20)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(routine_to_detail(&routine, runs)))
+}
+
 #[derive(Deserialize)]
 struct ToggleRequest {
     enabled: Option<bool>,
@@ -2273,8 +2557,7 @@ async fn routines_toggle_handler(
         "Database not available".to_string(),
     ))?;
 
-    let routine_id = Uuid::parse_str(&id)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid routine ID".to_string()))?;
+    let routine_id = owned_routine_id(store.as_ref(), &state.user_id, &id).await?;
 
     let mut routine = store
         .get_routine(routine_id)
@@ -2288,17 +2571,29 @@ async fn routines_toggle_handler(
         None => !routine.enabled,
     };
 
-    store
-        .update_routine(&routine)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let version = store.update_routine(&routine).await.map_err(|e| match e {
+        crate::error::DatabaseError::Conflict { current, .. } => routine_conflict(current),
+        e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })?;
 
     Ok(Json(serde_json::json!({
         "status": if routine.enabled { "enabled" } else { "disabled" },
         "routine_id": routine_id,
+        "version": version,
     })))
 }
 
+/// 409 for an edit made against an older version of a routine.
+fn routine_conflict(current: i64) -> (StatusCode, String) {
+    (
+        StatusCode::CONFLICT,
+        format!(
+            "Routine was changed elsewhere (now version {}); reload it and merge your changes",
+            current
+        ),
+    )
+}
+
 async fn routines_delete_handler(
     State(state): State<Arc<GatewayState>>,
     Path(id): Path<String>,
@@ -2308,8 +2603,7 @@ async fn routines_delete_handler(
         "Database not available".to_string(),
     ))?;
 
-    let routine_id = Uuid::parse_str(&id)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid routine ID".to_string()))?;
+    let routine_id = owned_routine_id(store.as_ref(), &state.user_id, &id).await?;
 
     let deleted = store
         .delete_routine(routine_id)
@@ -2335,26 +2629,14 @@ async fn routines_runs_handler(
         "Database not available".to_string(),
     ))?;
 
-    let routine_id = Uuid::parse_str(&id)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid routine ID".to_string()))?;
+    let routine_id = owned_routine_id(store.as_ref(), &state.user_id, &id).await?;
 
     let runs = store
         .list_routine_runs(routine_id, 50)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    let run_infos: Vec<RoutineRunInfo> = runs
-        .iter()
-        .map(|run| RoutineRunInfo {
-            id: run.id,
-            trigger_type: run.trigger_type.clone(),
-            started_at: run.started_at.to_rfc3339(),
-            completed_at: run.completed_at.map(|dt| dt.to_rfc3339()),
-            status: format!("{:?}", run.status),
-            result_summary: run.result_summary.clone(),
-            tokens_used: run.tokens_used,
-        })
-        .collect();
+    let run_infos: Vec<RoutineRunInfo> = runs.iter().map(run_to_info).collect();
 
     Ok(Json(serde_json::json!({
         "routine_id": routine_id,
@@ -2362,6 +2644,65 @@ async fn routines_runs_handler(
     })))
 }
 
+/// Parse a routine ID and check it belongs to the authenticated user.
+///
+/// Routines owned by someone else are reported as missing rather than forbidden.
+async fn owned_routine_id(
+    store: &dyn Database,
+    user_id: &str,
+    id: &str,
+) -> Result<Uuid, (StatusCode, String)> {
+    let routine_id = Uuid::parse_str(id)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid routine ID".to_string()))?;
+
+    let owned = store
+        .routine_belongs_to_user(routine_id, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !owned {
+        return Err((StatusCode::NOT_FOUND, "Routine not found".to_string()));
+    }
+    Ok(routine_id)
+}
+
+fn run_to_info(run: &crate::agent::routine::RoutineRun) -> RoutineRunInfo {
+    RoutineRunInfo {
+        id: run.id,
+        trigger_type: run.trigger_type.clone(),
+        started_at: run.started_at.to_rfc3339(),
+        completed_at: run.completed_at.map(|dt| dt.to_rfc3339()),
+        status: format!("{:?}", run.status),
+        result_summary: run.result_summary.clone(),
+        tokens_used: run.tokens_used,
+        cost: run.cost,
+        messages_sent: run.messages_sent,
+    }
+}
+
+/// Convert a Routine and its recent runs to the full detail response.
+fn routine_to_detail(
+    routine: &crate::agent::routine::Routine,
+    runs: Vec<crate::agent::routine::RoutineRun>,
+) -> RoutineDetailResponse {
+    RoutineDetailResponse {
+        id: routine.id,
+        name: routine.name.clone(),
+        description: routine.description.clone(),
+        enabled: routine.enabled,
+        trigger: serde_json::to_value(&routine.trigger).unwrap_or_default(),
+        action: serde_json::to_value(&routine.action).unwrap_or_default(),
+        guardrails: serde_json::to_value(&routine.guardrails).unwrap_or_default(),
+        notify: serde_json::to_value(&routine.notify).unwrap_or_default(),
+        last_run_at: routine.last_run_at.map(|dt| dt.to_rfc3339()),
+        next_fire_at: routine.next_fire_at.map(|dt| dt.to_rfc3339()),
+        run_count: routine.run_count,
+        consecutive_failures: routine.consecutive_failures,
+        created_at: routine.created_at.to_rfc3339(),
+        version: routine.version,
+        recent_runs: runs.iter().map(run_to_info).collect(),
+    }
+}
+
 /// Convert a Routine to the trimmed RoutineInfo for list display.
 fn routine_to_info(r: &crate::agent::routine::Routine) -> RoutineInfo {
     let (trigger_type, trigger_summary) = match &r.trigger {
@@ -2379,6 +2720,9 @@ fn routine_to_info(r: &crate::agent::routine::Routine) -> RoutineInfo {
             ("webhook".to_string(), format!("webhook: {}", p))
         }
         crate::agent::routine::Trigger::Manual => ("manual".to_string(), "manual only".to_string()),
+        crate::agent::routine::Trigger::Once { at } => {
+            ("once".to_string(), format!("once at {}", at.to_rfc3339()))
+        }
     };
 
     let action_type = match &r.action {
@@ -2430,6 +2774,7 @@ async fn settings_list_handler(
             key: r.key,
             value: r.value,
             updated_at: r.updated_at.to_rfc3339(),
+            version: r.version,
         })
         .collect();
 
@@ -2457,6 +2802,7 @@ async fn settings_get_handler(
         key: row.key,
         value: row.value,
         updated_at: row.updated_at.to_rfc3339(),
+        version: row.version,
     }))
 }
 
@@ -2464,20 +2810,29 @@ async fn settings_set_handler(
     State(state): State<Arc<GatewayState>>,
     Path(key): Path<String>,
     Json(body): Json<SettingWriteRequest>,
-) -> Result<StatusCode, StatusCode> {
-    let store = state
-        .store
-        .as_ref()
-        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
-    store
-        .set_setting(&state.user_id, &key, &body.value)
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let store = state.store.as_ref().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Database not available".to_string(),
+    ))?;
+    let version = store
+        .set_setting(&state.user_id, &key, &body.value, body.version)
         .await
-        .map_err(|e| {
-            tracing::error!("Failed to set setting '{}': {}", key, e);
-            StatusCode::INTERNAL_SERVER_ERROR
+        .map_err(|e| match e {
+            crate::error::DatabaseError::Conflict { current, .. } => (
+                StatusCode::CONFLICT,
+                format!(
+                    "Setting '{}' was changed elsewhere (now version {}); reload it and merge your changes",
+                    key, current
+                ),
+            ),
+            e => {
+                tracing::error!("Failed to set setting '{}': {}", key, e);
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+            }
         })?;
 
-    Ok(StatusCode::NO_CONTENT)
+    Ok(Json(serde_json::json!({ "key": key, "version": version })))
 }
 
 async fn settings_delete_handler(
@@ -2533,6 +2888,19 @@ async fn settings_import_handler(
     Ok(StatusCode::NO_CONTENT)
 }
 
+// --- Metrics handler ---
+
+/// Prometheus text exposition of the process-wide metrics registry.
+async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        crate::observability::metrics().render(),
+    )
+}
+
 // --- Gateway control plane handlers ---
 
 async fn gateway_status_handler(
@@ -2572,24 +2940,28 @@ mod tests {
                 role: "user".to_string(),
                 content: "Hello".to_string(),
                 created_at: now,
+                usage: None,
             },
             crate::history::ConversationMessage {
                 id: Uuid::new_v4(),
                 role: "assistant".to_string(),
                 content: "Hi there!".to_string(),
                 created_at: now + chrono::TimeDelta::seconds(1),
+                usage: None,
             },
             crate::history::ConversationMessage {
                 id: Uuid::new_v4(),
                 role: "user".to_string(),
                 content: "How are you?".to_string(),
                 created_at: now + chrono::TimeDelta::seconds(2),
+                usage: None,
             },
             crate::history::ConversationMessage {
                 id: Uuid::new_v4(),
                 role: "assistant".to_string(),
                 content: "Doing well!".to_string(),
                 created_at: now + chrono::TimeDelta::seconds(3),
+                usage: None,
             },
         ];
 
@@ -2602,6 +2974,36 @@ mod tests {
         assert_eq!(turns[1].response.as_deref(), Some("Doing well!"));
     }
 
+    #[test]
+    fn test_build_turns_from_db_messages_usage() {
+        let now = chrono::Utc::now();
+        let usage = crate::history::MessageUsage {
+            model: "gpt-test".to_string(),
+            input_tokens: 1200,
+            output_tokens: 80,
+            cost: rust_decimal::Decimal::new(42, 4),
+        };
+        let messages = vec![
+            crate::history::ConversationMessage {
+                id: Uuid::new_v4(),
+                role: "user".to_string(),
+                content: "Hello".to_string(),
+                created_at: now,
+                usage: None,
+            },
+            crate::history::ConversationMessage {
+                id: Uuid::new_v4(),
+                role: "assistant".to_string(),
+                content: "Hi!".to_string(),
+                created_at: now + chrono::TimeDelta::seconds(1),
+                usage: Some(usage.clone()),
+            },
+        ];
+
+        let turns = build_turns_from_db_messages(&messages);
+        assert_eq!(turns[0].usage, Some(usage));
+    }
+
     #[test]
     fn test_build_turns_from_db_messages_incomplete_last() {
         let now = chrono::Utc::now();
@@ -2611,18 +3013,21 @@ mod tests {
                 role: "user".to_string(),
                 content: "Hello".to_string(),
                 created_at: now,
+                usage: None,
             },
             crate::history::ConversationMessage {
                 id: Uuid::new_v4(),
                 role: "assistant".to_string(),
                 content: "Hi!".to_string(),
                 created_at: now + chrono::TimeDelta::seconds(1),
+                usage: None,
             },
             crate::history::ConversationMessage {
                 id: Uuid::new_v4(),
                 role: "user".to_string(),
                 content: "Lost message".to_string(),
                 created_at: now + chrono::TimeDelta::seconds(2),
+                usage: None,
             },
         ];
 
@@ -351,6 +351,7 @@ function addMessage(role, content) {
   }
   container.appendChild(div);
   container.scrollTop = container.scrollHeight;
+  return div;
 }
 
 function appendToLastAssistant(chunk) {
@@ -416,6 +417,11 @@ function showApproval(data) {
   container.scrollTop = container.scrollHeight;
 }
 
+function formatUsage(usage) {
+  return usage.model + ' \u00b7 ' + usage.input_tokens + ' in / ' + usage.output_tokens
+    + ' out tokens \u00b7 $' + Number(usage.cost).toFixed(4);
+}
+
 function loadHistory(before) {
   let url = '/api/chat/history?limit=50';
   if (currentThreadId) url += '&thread_id=' + encodeURIComponent(currentThreadId);
@@ -427,7 +433,10 @@ function loadHistory(before) {
 
     for (const turn of data.turns) {
       addMessage('user', turn.user_input);
-      if (turn.response) addMessage('assistant', turn.response);
+      if (turn.response) {
+        const div = addMessage('assistant', turn.response);
+        if (turn.usage) div.title = formatUsage(turn.usage);
+      }
     }
     container.scrollTop = container.scrollHeight;
     hasMore = data.has_more;
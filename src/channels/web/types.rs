@@ -30,6 +30,15 @@ pub struct ThreadInfo {
     pub thread_type: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ConversationListResponse {
+    pub conversations: Vec<ThreadInfo>,
+    pub has_more: bool,
+    /// Cursor for the next page (`?before=`), present when `has_more` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_before: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ThreadListResponse {
     /// The pinned assistant thread (always present after first load).
@@ -48,6 +57,9 @@ pub struct TurnInfo {
     pub started_at: String,
     pub completed_at: Option<String>,
     pub tool_calls: Vec<ToolCallInfo>,
+    /// Tokens and cost of the response, once it has been persisted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<crate::history::MessageUsage>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -274,6 +286,23 @@ pub struct JobListResponse {
     pub jobs: Vec<JobInfo>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct JobCreateRequest {
+    pub task: String,
+    /// Project directory under `~/.ironclaw/projects`; a fresh one is created if omitted.
+    pub project_dir: Option<String>,
+    /// "worker" (default) or "claude_code".
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobCreateResponse {
+    pub job_id: Uuid,
+    pub status: &'static str,
+    pub project_dir: String,
+    pub browse_url: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct JobSummaryResponse {
     pub total: usize,
@@ -530,6 +559,35 @@ pub struct RoutineListResponse {
     pub routines: Vec<RoutineInfo>,
 }
 
+/// Body for `POST /api/routines`. `trigger` and `action` use the same JSON
+/// shape as the detail endpoint returns.
+#[derive(Debug, Deserialize)]
+pub struct RoutineCreateRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub enabled: Option<bool>,
+    pub trigger: serde_json::Value,
+    pub action: serde_json::Value,
+    pub guardrails: Option<serde_json::Value>,
+    pub notify: Option<serde_json::Value>,
+}
+
+/// Body for `PUT /api/routines/{id}`; omitted fields are left unchanged.
+#[derive(Debug, Default, Deserialize)]
+pub struct RoutineUpdateRequest {
+    /// Version the edit was made against; a routine changed since is
+    /// refused with 409 Conflict instead of overwritten.
+    pub version: Option<i64>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub enabled: Option<bool>,
+    pub trigger: Option<serde_json::Value>,
+    pub action: Option<serde_json::Value>,
+    pub guardrails: Option<serde_json::Value>,
+    pub notify: Option<serde_json::Value>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct RoutineSummaryResponse {
     pub total: u64,
@@ -554,6 +612,7 @@ pub struct RoutineDetailResponse {
     pub run_count: u64,
     pub consecutive_failures: u32,
     pub created_at: String,
+    pub version: i64,
     pub recent_runs: Vec<RoutineRunInfo>,
 }
 
@@ -566,6 +625,8 @@ pub struct RoutineRunInfo {
     pub status: String,
     pub result_summary: Option<String>,
     pub tokens_used: Option<i32>,
+    pub cost: Option<rust_decimal::Decimal>,
+    pub messages_sent: u32,
 }
 
 // --- Settings ---
@@ -575,6 +636,7 @@ pub struct SettingResponse {
     pub key: String,
     pub value: serde_json::Value,
     pub updated_at: String,
+    pub version: i64,
 }
 
 #[derive(Debug, Serialize)]
@@ -585,6 +647,10 @@ pub struct SettingsListResponse {
 #[derive(Debug, Deserialize)]
 pub struct SettingWriteRequest {
     pub value: serde_json::Value,
+    /// Version the value was read at (0: the setting didn't exist); a
+    /// setting changed since is refused with 409 Conflict. Omit to overwrite.
+    #[serde(default)]
+    pub version: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,82 @@
+//! Backup CLI commands.
+//!
+//! Creates, lists and restores encrypted backups without starting the agent.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use clap::Subcommand;
+
+use crate::backup::{BackupError, BackupManager};
+use crate::cli::tool::format_size;
+use crate::config::Config;
+use crate::secrets::SecretsCrypto;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum BackupCommand {
+    /// Write a new encrypted backup to the backup directory
+    Create,
+
+    /// List local backups
+    List,
+
+    /// Restore a backup (rows and files that already exist are kept)
+    Restore {
+        /// Backup file (raw `.icbk` or its base64 upload)
+        path: PathBuf,
+    },
+}
+
+/// Run a backup command.
+pub async fn run_backup_command(
+    cmd: BackupCommand,
+    config: &Config,
+    pool: deadpool_postgres::Pool,
+) -> anyhow::Result<()> {
+    let master_key = config
+        .secrets
+        .master_key()
+        .ok_or(BackupError::NoMasterKey)?;
+    let crypto = Arc::new(SecretsCrypto::new(master_key.clone())?);
+    let manager = BackupManager::new(pool, crypto, config.backup.clone());
+
+    match cmd {
+        BackupCommand::Create => {
+            let backup = manager.create().await?;
+            println!(
+                "Backup written to {} ({})",
+                backup.path.display(),
+                format_size(backup.size_bytes)
+            );
+        }
+        BackupCommand::List => {
+            let backups = manager.list()?;
+            if backups.is_empty() {
+                println!("No backups in {}", config.backup.dir.display());
+                return Ok(());
+            }
+            for backup in backups {
+                println!(
+                    "{}  {:>10}  {}",
+                    backup.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                    format_size(backup.size_bytes),
+                    backup.path.display()
+                );
+            }
+        }
+        BackupCommand::Restore { path } => {
+            let report = manager.restore(&path).await?;
+            println!("Restored {}", path.display());
+            println!(
+                "  rows:  {} inserted, {} already present",
+                report.rows_inserted, report.rows_skipped
+            );
+            println!(
+                "  files: {} restored, {} already present",
+                report.files_restored, report.files_skipped
+            );
+        }
+    }
+
+    Ok(())
+}
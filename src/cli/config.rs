@@ -4,6 +4,8 @@
 
 use clap::Subcommand;
 
+use crate::config::{Config, ConfigLayers, ResolvedSetting, mask_value};
+use crate::db::Database;
 use crate::settings::Settings;
 
 #[derive(Subcommand, Debug, Clone)]
@@ -38,16 +40,26 @@ pub enum ConfigCommand {
 
     /// Show the settings file path
     Path,
+
+    /// Show the config file, or with --resolved the effective configuration
+    Show {
+        /// Resolve every layer (defaults, config file, env, database) and show
+        /// each setting's effective value and source, with secrets masked
+        #[arg(long)]
+        resolved: bool,
+    },
 }
 
 /// Run a config command.
-pub fn run_config_command(cmd: ConfigCommand) -> anyhow::Result<()> {
+pub async fn run_config_command(cmd: ConfigCommand) -> anyhow::Result<()> {
     match cmd {
         ConfigCommand::List { filter } => list_settings(filter),
         ConfigCommand::Get { path } => get_setting(&path),
         ConfigCommand::Set { path, value } => set_setting(&path, &value),
         ConfigCommand::Reset { path } => reset_setting(&path),
         ConfigCommand::Path => show_path(),
+        ConfigCommand::Show { resolved: false } => show_config_file(),
+        ConfigCommand::Show { resolved: true } => show_resolved().await,
     }
 }
 
@@ -165,6 +177,88 @@ fn show_path() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Show the settings defined in the config file.
+fn show_config_file() -> anyhow::Result<()> {
+    let _ = dotenvy::dotenv();
+    let path = ConfigLayers::file_path();
+    let layers = ConfigLayers::load()?;
+
+    println!("{}", path.display());
+    let settings: Vec<(&str, &str)> = layers.file_settings().collect();
+    if settings.is_empty() {
+        println!("  (no settings; run 'ironclaw config show --resolved' for effective values)");
+        return Ok(());
+    }
+
+    let width = settings.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+    for (key, value) in settings {
+        println!(
+            "  {:width$}  {}",
+            key,
+            mask_value(key, value),
+            width = width
+        );
+    }
+    Ok(())
+}
+
+/// Show every setting's effective value and the layer it came from.
+async fn show_resolved() -> anyhow::Result<()> {
+    let _ = dotenvy::dotenv();
+    let (config, mut resolved) = Config::resolve(ConfigLayers::load()?)?;
+
+    // Database overrides need a connection; show what we can without one
+    let mut db_note = None;
+    match crate::history::Store::new(&config.database).await {
+        Ok(store) => match store.get_all_settings("default").await {
+            Ok(settings) => {
+                let layers = ConfigLayers::load()?.with_database_settings(&settings);
+                if layers.has_database_overrides() {
+                    resolved = Config::resolve(layers)?.1;
+                }
+            }
+            Err(e) => db_note = Some(format!("database overrides not read: {}", e)),
+        },
+        Err(e) => db_note = Some(format!("database overrides not read: {}", e)),
+    }
+
+    print_resolved(&resolved);
+    if let Some(note) = db_note {
+        println!();
+        println!("  ({})", note);
+    }
+    Ok(())
+}
+
+fn print_resolved(resolved: &[ResolvedSetting]) {
+    let key_width = resolved.iter().map(|s| s.key.len()).max().unwrap_or(0);
+    let values: Vec<String> = resolved
+        .iter()
+        .map(|s| {
+            let value = s.display_value();
+            if value.chars().count() > 60 {
+                format!("{}...", value.chars().take(57).collect::<String>())
+            } else {
+                value
+            }
+        })
+        .collect();
+    let value_width = values.iter().map(|v| v.len()).max().unwrap_or(0);
+
+    println!("Effective configuration (database > env > config file > default):");
+    println!();
+    for (setting, value) in resolved.iter().zip(values) {
+        println!(
+            "  {:kw$}  {:vw$}  {}",
+            setting.key,
+            value,
+            setting.source,
+            kw = key_width,
+            vw = value_width
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,485 @@
+//! Runtime diagnostics CLI command.
+//!
+//! Unlike `status`, which only reports what is configured, `doctor` actively
+//! probes each dependency: database connectivity and migrations, stored
+//! secrets (with live token validation), tunnel reachability, WASM tool
+//! compilation, and LLM provider health. Validation reuses the same code as
+//! the setup wizard and `ironclaw tool auth`.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use secrecy::SecretString;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::history::Store;
+use crate::secrets::{PostgresSecretsStore, SecretsCrypto, SecretsStore};
+use crate::tools::wasm::{CapabilitiesFile, WasmRuntimeConfig, WasmToolRuntime, discover_tools};
+
+/// Timeout for each network probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl CheckStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Warn => "warn",
+            Self::Fail => "FAIL",
+            Self::Skip => "skip",
+        }
+    }
+}
+
+/// Result of one diagnostic check.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    /// Check name, grouped by prefix (e.g. "secrets.google_oauth_token").
+    pub name: String,
+    pub status: CheckStatus,
+    /// Human-readable detail (error message, counts, URLs).
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Full diagnostics report.
+#[derive(Debug, Default, Serialize)]
+pub struct DoctorReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    fn push(&mut self, name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) {
+        self.checks.push(CheckResult::new(name, status, detail));
+    }
+
+    /// Number of checks with the given status.
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+
+    /// Whether any check failed.
+    pub fn has_failures(&self) -> bool {
+        self.count(CheckStatus::Fail) > 0
+    }
+
+    /// Render the report as aligned plain text.
+    pub fn to_text(&self) -> String {
+        let width = self.checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+        let mut out = String::from("IronClaw Doctor\n===============\n\n");
+
+        for check in &self.checks {
+            out.push_str(&format!(
+                "  [{:>4}] {:<width$}  {}\n",
+                check.status.label(),
+                check.name,
+                check.detail,
+                width = width
+            ));
+        }
+
+        out.push_str(&format!(
+            "\n  {} ok, {} warnings, {} failed, {} skipped\n",
+            self.count(CheckStatus::Ok),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail),
+            self.count(CheckStatus::Skip)
+        ));
+        out
+    }
+}
+
+/// Run the doctor command, printing a report and failing if any check failed.
+pub async fn run_doctor_command(json: bool, user_id: &str) -> anyhow::Result<()> {
+    let report = collect_report(user_id).await;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report.to_text());
+    }
+
+    if report.has_failures() {
+        anyhow::bail!("{} check(s) failed", report.count(CheckStatus::Fail));
+    }
+    Ok(())
+}
+
+/// Run every check and collect the results.
+pub async fn collect_report(user_id: &str) -> DoctorReport {
+    let mut report = DoctorReport::default();
+
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            report.push("config", CheckStatus::Fail, e.to_string());
+            return report;
+        }
+    };
+    report.push("config", CheckStatus::Ok, "loaded");
+
+    let store = check_database(&config, &mut report).await;
+    let secrets = check_master_key(&config, store.as_ref(), &mut report);
+
+    check_tools(
+        &config.wasm.tools_dir,
+        secrets.as_ref(),
+        user_id,
+        &mut report,
+    )
+    .await;
+    check_telegram(secrets.as_ref(), user_id, &mut report).await;
+    check_tunnel(config.tunnel.public_url.as_deref(), &mut report).await;
+    check_llm(&config, &mut report).await;
+
+    report
+}
+
+async fn check_database(config: &Config, report: &mut DoctorReport) -> Option<Store> {
+    let store = match tokio::time::timeout(PROBE_TIMEOUT, Store::new(&config.database)).await {
+        Ok(Ok(store)) => store,
+        Ok(Err(e)) => {
+            report.push("database", CheckStatus::Fail, e.to_string());
+            return None;
+        }
+        Err(_) => {
+            report.push("database", CheckStatus::Fail, "connection timed out");
+            return None;
+        }
+    };
+
+    let pool = store.pool();
+    let connected = match pool.get().await {
+        Ok(client) => client
+            .execute("SELECT 1", &[])
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = connected {
+        report.push("database", CheckStatus::Fail, e);
+        return None;
+    }
+    report.push("database", CheckStatus::Ok, "connected");
+
+    match crate::setup::upgrade::pending_db_migrations(&pool).await {
+        Ok(pending) if pending.is_empty() => {
+            report.push("database.migrations", CheckStatus::Ok, "up to date")
+        }
+        Ok(pending) => report.push(
+            "database.migrations",
+            CheckStatus::Warn,
+            format!("{} pending: {}", pending.len(), pending.join(", ")),
+        ),
+        Err(e) => report.push("database.migrations", CheckStatus::Fail, e.to_string()),
+    }
+
+    Some(store)
+}
+
+fn check_master_key(
+    config: &Config,
+    store: Option<&Store>,
+    report: &mut DoctorReport,
+) -> Option<PostgresSecretsStore> {
+    let Some(master_key) = config.secrets.master_key() else {
+        report.push(
+            "secrets.master_key",
+            CheckStatus::Warn,
+            "not configured (run `ironclaw onboard`)",
+        );
+        return None;
+    };
+
+    let crypto = match SecretsCrypto::new(master_key.clone()) {
+        Ok(crypto) => crypto,
+        Err(e) => {
+            report.push("secrets.master_key", CheckStatus::Fail, e.to_string());
+            return None;
+        }
+    };
+    report.push("secrets.master_key", CheckStatus::Ok, "present");
+
+    store.map(|store| PostgresSecretsStore::new(store.pool(), Arc::new(crypto)))
+}
+
+/// Check that every installed tool compiles and has its credentials.
+async fn check_tools(
+    tools_dir: &Path,
+    secrets: Option<&PostgresSecretsStore>,
+    user_id: &str,
+    report: &mut DoctorReport,
+) {
+    let discovered = match discover_tools(tools_dir).await {
+        Ok(discovered) => discovered,
+        Err(_) => {
+            report.push(
+                "tools",
+                CheckStatus::Skip,
+                format!("no tools directory ({})", tools_dir.display()),
+            );
+            return;
+        }
+    };
+    if discovered.is_empty() {
+        report.push("tools", CheckStatus::Skip, "no tools installed");
+        return;
+    }
+
+    let runtime = match WasmToolRuntime::new(WasmRuntimeConfig {
+        cache_compiled: false,
+        ..WasmRuntimeConfig::default()
+    }) {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            report.push("tools.runtime", CheckStatus::Fail, e.to_string());
+            return;
+        }
+    };
+
+    let mut names: Vec<_> = discovered.keys().cloned().collect();
+    names.sort();
+
+    for name in names {
+        let tool = &discovered[&name];
+        let check_name = format!("tools.{}", name);
+
+        let loaded = match tokio::fs::read(&tool.wasm_path).await {
+            Ok(bytes) => runtime
+                .prepare(&name, &bytes, None)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match loaded {
+            Ok(()) => report.push(check_name, CheckStatus::Ok, "compiles"),
+            Err(e) => report.push(check_name, CheckStatus::Fail, e),
+        }
+
+        let Some(caps_path) = &tool.capabilities_path else {
+            continue;
+        };
+        let caps = match tokio::fs::read_to_string(caps_path)
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|raw| CapabilitiesFile::from_json(&raw).map_err(|e| e.to_string()))
+        {
+            Ok(caps) => caps,
+            Err(e) => {
+                report.push(format!("tools.{}.capabilities", name), CheckStatus::Fail, e);
+                continue;
+            }
+        };
+
+        if let Some(auth) = caps.auth {
+            check_tool_secret(&name, &auth, secrets, user_id, report).await;
+        }
+    }
+}
+
+async fn check_tool_secret(
+    tool: &str,
+    auth: &crate::tools::wasm::AuthCapabilitySchema,
+    secrets: Option<&PostgresSecretsStore>,
+    user_id: &str,
+    report: &mut DoctorReport,
+) {
+    let check_name = format!("secrets.{}", auth.secret_name);
+    if report.checks.iter().any(|c| c.name == check_name) {
+        // Shared secret (e.g. google_oauth_token) already checked for another tool
+        return;
+    }
+
+    let Some(secrets) = secrets else {
+        report.push(check_name, CheckStatus::Skip, "secrets store unavailable");
+        return;
+    };
+
+    let token = match secrets.get_decrypted(user_id, &auth.secret_name).await {
+        Ok(token) => token,
+        Err(_) => {
+            report.push(
+                check_name,
+                CheckStatus::Fail,
+                format!("missing (run `ironclaw tool auth {}`)", tool),
+            );
+            return;
+        }
+    };
+
+    let Some(validation) = &auth.validation_endpoint else {
+        report.push(
+            check_name,
+            CheckStatus::Ok,
+            "present (no validation endpoint)",
+        );
+        return;
+    };
+
+    match crate::cli::tool::validate_token(token.expose(), validation, &auth.secret_name).await {
+        Ok(()) => report.push(check_name, CheckStatus::Ok, "valid"),
+        Err(e) => report.push(check_name, CheckStatus::Fail, format!("rejected: {}", e)),
+    }
+}
+
+async fn check_telegram(
+    secrets: Option<&PostgresSecretsStore>,
+    user_id: &str,
+    report: &mut DoctorReport,
+) {
+    let Some(secrets) = secrets else {
+        return;
+    };
+    let Ok(token) = secrets.get_decrypted(user_id, "telegram_bot_token").await else {
+        return;
+    };
+
+    let token = SecretString::from(token.expose().to_string());
+    match crate::setup::validate_telegram_token(&token).await {
+        Ok(Some(username)) => report.push(
+            "secrets.telegram_bot_token",
+            CheckStatus::Ok,
+            format!("valid (@{})", username),
+        ),
+        Ok(None) => report.push("secrets.telegram_bot_token", CheckStatus::Ok, "valid"),
+        Err(e) => report.push("secrets.telegram_bot_token", CheckStatus::Fail, e),
+    }
+}
+
+async fn check_tunnel(public_url: Option<&str>, report: &mut DoctorReport) {
+    let Some(url) = public_url else {
+        report.push("tunnel", CheckStatus::Skip, "no public URL configured");
+        return;
+    };
+
+    let client = match reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            report.push("tunnel", CheckStatus::Fail, e.to_string());
+            return;
+        }
+    };
+
+    // Any HTTP response means the tunnel is forwarding; the agent may not
+    // serve anything at the root path.
+    match client.get(url).send().await {
+        Ok(response) => report.push(
+            "tunnel",
+            CheckStatus::Ok,
+            format!("{} reachable (HTTP {})", url, response.status().as_u16()),
+        ),
+        Err(e) => report.push("tunnel", CheckStatus::Fail, format!("{}: {}", url, e)),
+    }
+}
+
+async fn check_llm(config: &Config, report: &mut DoctorReport) {
+    let session = crate::llm::create_session_manager(crate::llm::SessionConfig {
+        auth_base_url: config.llm.nearai.auth_base_url.clone(),
+        session_path: config.llm.nearai.session_path.clone(),
+        ..Default::default()
+    })
+    .await;
+
+    let provider = match crate::llm::create_llm_provider(&config.llm, session) {
+        Ok(provider) => provider,
+        Err(e) => {
+            report.push("llm", CheckStatus::Fail, e.to_string());
+            return;
+        }
+    };
+
+    match tokio::time::timeout(PROBE_TIMEOUT, provider.list_models()).await {
+        Ok(Ok(models)) if models.is_empty() => report.push(
+            "llm",
+            CheckStatus::Ok,
+            format!("{} (model listing not supported)", provider.model_name()),
+        ),
+        Ok(Ok(models)) if !models.iter().any(|m| m == provider.model_name()) => report.push(
+            "llm",
+            CheckStatus::Warn,
+            format!(
+                "reachable, but {} is not in the provider's model list",
+                provider.model_name()
+            ),
+        ),
+        Ok(Ok(models)) => report.push(
+            "llm",
+            CheckStatus::Ok,
+            format!(
+                "{} ({} models available)",
+                provider.model_name(),
+                models.len()
+            ),
+        ),
+        Ok(Err(e)) => report.push("llm", CheckStatus::Fail, e.to_string()),
+        Err(_) => report.push("llm", CheckStatus::Fail, "provider timed out"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_report() -> DoctorReport {
+        let mut report = DoctorReport::default();
+        report.push("database", CheckStatus::Ok, "connected");
+        report.push("database.migrations", CheckStatus::Warn, "1 pending");
+        report.push("tunnel", CheckStatus::Skip, "no public URL configured");
+        report
+    }
+
+    #[test]
+    fn test_report_counts() {
+        let mut report = sample_report();
+        assert_eq!(report.count(CheckStatus::Ok), 1);
+        assert_eq!(report.count(CheckStatus::Warn), 1);
+        assert!(!report.has_failures());
+
+        report.push("llm", CheckStatus::Fail, "provider timed out");
+        assert!(report.has_failures());
+    }
+
+    #[test]
+    fn test_report_json() {
+        let json = serde_json::to_value(sample_report()).unwrap();
+        assert_eq!(json["checks"][0]["name"], "database");
+        assert_eq!(json["checks"][0]["status"], "ok");
+        assert_eq!(json["checks"][1]["status"], "warn");
+    }
+
+    #[test]
+    fn test_report_text() {
+        let text = sample_report().to_text();
+        assert!(text.contains("[  ok] database "));
+        assert!(text.contains("[warn] database.migrations  1 pending"));
+        assert!(text.contains("1 ok, 1 warnings, 0 failed, 1 skipped"));
+    }
+
+    #[tokio::test]
+    async fn test_tunnel_skipped_without_url() {
+        let mut report = DoctorReport::default();
+        check_tunnel(None, &mut report).await;
+        assert_eq!(report.checks[0].status, CheckStatus::Skip);
+    }
+}
@@ -2,24 +2,32 @@
 //!
 //! Provides subcommands for:
 //! - Running the agent (`run`)
+//! - Chatting with the agent in the terminal (`chat`)
 //! - Interactive onboarding wizard (`onboard`)
-//! - Managing configuration (`config list`, `config get`, `config set`)
-//! - Managing WASM tools (`tool install`, `tool list`, `tool remove`)
+//! - Managing configuration (`config list`, `config get`, `config set`, `config show`)
+//! - Managing WASM tools (`tool install`, `tool list`, `tool remove`, `tool auth`)
 //! - Managing MCP servers (`mcp add`, `mcp auth`, `mcp list`, `mcp test`)
 //! - Querying workspace memory (`memory search`, `memory read`, `memory write`)
 //! - Checking system health (`status`)
+//! - Encrypted backups (`backup create`, `backup list`, `backup restore`)
 
+mod backup;
 mod config;
+mod doctor;
 mod mcp;
 pub mod memory;
 pub mod status;
 mod tool;
 
+pub use backup::{BackupCommand, run_backup_command};
 pub use config::{ConfigCommand, run_config_command};
+pub use doctor::run_doctor_command;
 pub use mcp::{McpCommand, run_mcp_command};
 pub use memory::{MemoryCommand, run_memory_command};
 pub use status::run_status_command;
-pub use tool::{ToolCommand, run_tool_command};
+pub use tool::{
+    ToolCommand, authenticate_tool, merged_oauth_scopes, refresh_token_name, run_tool_command,
+};
 
 use clap::{Parser, Subcommand};
 
@@ -59,6 +67,9 @@ pub enum Command {
     /// Run the agent (default if no subcommand given)
     Run,
 
+    /// Chat with the agent in the terminal (no other channels are started)
+    Chat,
+
     /// Interactive onboarding wizard
     Onboard {
         /// Skip authentication (use existing session)
@@ -68,6 +79,18 @@ pub enum Command {
         /// Reconfigure channels only
         #[arg(long)]
         channels_only: bool,
+
+        /// Apply a named profile's defaults (personal-laptop, server, demo)
+        #[arg(long, value_enum)]
+        profile: Option<crate::setup::SetupProfile>,
+
+        /// Provision non-interactively from a setup.toml file
+        #[arg(long, value_name = "PATH")]
+        from_file: Option<std::path::PathBuf>,
+
+        /// Upgrade an existing install (settings and database migrations)
+        #[arg(long, conflicts_with = "from_file")]
+        upgrade: bool,
     },
 
     /// Manage configuration settings
@@ -86,13 +109,33 @@ pub enum Command {
     #[command(subcommand)]
     Memory(MemoryCommand),
 
+    /// Create, list and restore encrypted backups
+    #[command(subcommand)]
+    Backup(BackupCommand),
+
     /// Show system health and diagnostics
     Status,
+
+    /// Probe databases, secrets, tunnel, tools, and LLM provider for problems
+    Doctor {
+        /// Output the report as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// User ID whose secrets are checked (default: "default")
+        #[arg(short, long, default_value = "default")]
+        user: String,
+    },
 }
 
 impl Cli {
     /// Check if we should run the agent (default behavior or explicit `run` command).
     pub fn should_run_agent(&self) -> bool {
-        matches!(self.command, None | Some(Command::Run))
+        matches!(self.command, None | Some(Command::Run) | Some(Command::Chat))
+    }
+
+    /// Whether only the terminal REPL should be started (`chat` or `--cli-only`).
+    pub fn is_cli_only(&self) -> bool {
+        self.cli_only || matches!(self.command, Some(Command::Chat))
     }
 }
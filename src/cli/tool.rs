@@ -580,7 +580,7 @@ async fn show_tool_info(name_or_path: String, dir: Option<PathBuf>) -> anyhow::R
 }
 
 /// Format bytes as human-readable size.
-fn format_size(bytes: u64) -> String {
+pub(super) fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
 
@@ -681,6 +681,34 @@ fn print_capabilities_detail(caps: &CapabilitiesFile) {
 /// Configure authentication for a tool.
 async fn auth_tool(name: String, dir: Option<PathBuf>, user_id: String) -> anyhow::Result<()> {
     let tools_dir = dir.unwrap_or_else(default_tools_dir);
+
+    // Initialize secrets store
+    let config = Config::from_env()?;
+    let master_key = config.secrets.master_key().ok_or_else(|| {
+        anyhow::anyhow!(
+            "SECRETS_MASTER_KEY not set. Run 'ironclaw onboard' first or set it in .env"
+        )
+    })?;
+
+    let store = Store::new(&config.database).await?;
+    store.run_migrations().await?;
+
+    let crypto = SecretsCrypto::new(master_key.clone())?;
+    let secrets_store = PostgresSecretsStore::new(store.pool(), Arc::new(crypto));
+
+    authenticate_tool(&name, &tools_dir, &secrets_store, &user_id).await
+}
+
+/// Run the auth flow for an installed tool against an existing secrets store.
+///
+/// Shared by `ironclaw tool auth` and the setup wizard. Picks the env var,
+/// OAuth, or manual flow based on the tool's capabilities file.
+pub async fn authenticate_tool(
+    name: &str,
+    tools_dir: &Path,
+    secrets_store: &PostgresSecretsStore,
+    user_id: &str,
+) -> anyhow::Result<()> {
     let caps_path = tools_dir.join(format!("{}.capabilities.json", name));
 
     if !caps_path.exists() {
@@ -705,7 +733,7 @@ async fn auth_tool(name: String, dir: Option<PathBuf>, user_id: String) -> anyho
         )
     })?;
 
-    let display_name = auth.display_name.as_deref().unwrap_or(&name);
+    let display_name = auth.display_name.as_deref().unwrap_or(name);
 
     let header = format!("{} Authentication", display_name);
     println!();
@@ -714,23 +742,9 @@ async fn auth_tool(name: String, dir: Option<PathBuf>, user_id: String) -> anyho
     println!("╚════════════════════════════════════════════════════════════════╝");
     println!();
 
-    // Initialize secrets store
-    let config = Config::from_env()?;
-    let master_key = config.secrets.master_key().ok_or_else(|| {
-        anyhow::anyhow!(
-            "SECRETS_MASTER_KEY not set. Run 'ironclaw onboard' first or set it in .env"
-        )
-    })?;
-
-    let store = Store::new(&config.database).await?;
-    store.run_migrations().await?;
-
-    let crypto = SecretsCrypto::new(master_key.clone())?;
-    let secrets_store = Arc::new(PostgresSecretsStore::new(store.pool(), Arc::new(crypto)));
-
     // Check if already configured
     let already_configured = secrets_store
-        .exists(&user_id, &auth.secret_name)
+        .exists(user_id, &auth.secret_name)
         .await
         .unwrap_or(false);
 
@@ -772,13 +786,13 @@ async fn auth_tool(name: String, dir: Option<PathBuf>, user_id: String) -> anyho
                             println!("  Validation failed: {}", e);
                             println!();
                             println!("  Falling back to manual entry...");
-                            return auth_tool_manual(&secrets_store, &user_id, &auth).await;
+                            return auth_tool_manual(secrets_store, user_id, &auth).await;
                         }
                     }
                 }
 
                 // Save the token
-                save_token(&secrets_store, &user_id, &auth, &token).await?;
+                save_token(secrets_store, user_id, &auth, &token).await?;
                 print_success(display_name);
                 return Ok(());
             }
@@ -787,19 +801,25 @@ async fn auth_tool(name: String, dir: Option<PathBuf>, user_id: String) -> anyho
 
     // Check for OAuth configuration
     if let Some(ref oauth) = auth.oauth {
-        return auth_tool_oauth(&secrets_store, &user_id, &auth, oauth).await;
+        let scopes = merged_oauth_scopes(tools_dir, &auth.secret_name, &oauth.scopes).await;
+        return auth_tool_oauth(secrets_store, user_id, &auth, oauth, &scopes).await;
     }
 
     // Fall back to manual entry
-    auth_tool_manual(&secrets_store, &user_id, &auth).await
+    auth_tool_manual(secrets_store, user_id, &auth).await
 }
 
 /// OAuth browser-based login flow.
+///
+/// Uses a loopback redirect on localhost. `scopes` is the full scope set to
+/// request, which may be wider than the tool's own when several tools share
+/// one token (see [`merged_oauth_scopes`]).
 async fn auth_tool_oauth(
     store: &PostgresSecretsStore,
     user_id: &str,
     auth: &crate::tools::wasm::AuthCapabilitySchema,
     oauth: &crate::tools::wasm::OAuthConfigSchema,
+    scopes: &[String],
 ) -> anyhow::Result<()> {
     use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
     use rand::RngCore;
@@ -878,10 +898,10 @@ async fn auth_tool_oauth(
         urlencoding::encode(&redirect_uri)
     );
 
-    if !oauth.scopes.is_empty() {
+    if !scopes.is_empty() {
         auth_url.push_str(&format!(
             "&scope={}",
-            urlencoding::encode(&oauth.scopes.join(" "))
+            urlencoding::encode(&scopes.join(" "))
         ));
     }
 
@@ -1021,9 +1041,39 @@ async fn auth_tool_oauth(
             )
         })?;
 
+    // Verify the token with a live API call before persisting it
+    if let Some(ref validation) = auth.validation_endpoint {
+        print!("  Validating token...");
+        std::io::stdout().flush()?;
+
+        match validate_token(access_token, validation, &auth.secret_name).await {
+            Ok(()) => println!(" ✓"),
+            Err(e) => {
+                println!(" ✗");
+                return Err(anyhow::anyhow!(
+                    "Token validation failed: {}\n\
+                     Check that the required APIs are enabled for your OAuth client.",
+                    e
+                ));
+            }
+        }
+    }
+
     // Save the token
     save_token(store, user_id, auth, access_token).await?;
 
+    // Keep the refresh token so the access token can be renewed without re-consent
+    if let Some(refresh_token) = token_data.get("refresh_token").and_then(|v| v.as_str()) {
+        let mut params = CreateSecretParams::new(refresh_token_name(auth), refresh_token);
+        if let Some(ref provider) = auth.provider {
+            params = params.with_provider(provider);
+        }
+        store
+            .create(user_id, params)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to save refresh token: {}", e))?;
+    }
+
     // Extract any additional info for display
     let workspace_name = token_data
         .get("workspace_name")
@@ -1174,7 +1224,7 @@ fn read_hidden_input() -> anyhow::Result<String> {
 }
 
 /// Validate a token against the validation endpoint.
-async fn validate_token(
+pub(crate) async fn validate_token(
     token: &str,
     validation: &crate::tools::wasm::ValidationEndpointSchema,
     _secret_name: &str,
@@ -1236,6 +1286,65 @@ async fn save_token(
     Ok(())
 }
 
+/// Name of the secret holding the OAuth refresh token for an auth config.
+pub fn refresh_token_name(auth: &crate::tools::wasm::AuthCapabilitySchema) -> String {
+    format!("{}_refresh_token", auth.secret_name)
+}
+
+/// Collect the OAuth scopes of every installed tool sharing `secret_name`.
+///
+/// Google tools all store their token under `google_oauth_token`, so
+/// authenticating one tool with only its own scopes would break the others.
+/// The tool's own scopes come first, followed by any extra ones in file order.
+pub async fn merged_oauth_scopes(
+    tools_dir: &Path,
+    secret_name: &str,
+    own_scopes: &[String],
+) -> Vec<String> {
+    let mut scopes: Vec<String> = own_scopes.to_vec();
+
+    let Ok(mut entries) = fs::read_dir(tools_dir).await else {
+        return scopes;
+    };
+
+    let mut caps_files = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let is_caps = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.ends_with(".capabilities.json"))
+            .unwrap_or(false);
+        if is_caps {
+            caps_files.push(path);
+        }
+    }
+    caps_files.sort();
+
+    for path in caps_files {
+        let Ok(content) = fs::read_to_string(&path).await else {
+            continue;
+        };
+        let Ok(caps) = CapabilitiesFile::from_json(&content) else {
+            continue;
+        };
+        let Some(oauth) = caps
+            .auth
+            .filter(|a| a.secret_name == secret_name)
+            .and_then(|a| a.oauth)
+        else {
+            continue;
+        };
+        for scope in oauth.scopes {
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+    }
+
+    scopes
+}
+
 /// Print success message.
 fn print_success(display_name: &str) {
     println!();
@@ -1258,6 +1367,51 @@ mod tests {
         assert_eq!(format_size(2621440), "2.5 MB");
     }
 
+    #[tokio::test]
+    async fn test_merged_oauth_scopes() {
+        let dir = tempfile::tempdir().unwrap();
+        let google = |scope: &str| {
+            format!(
+                r#"{{"auth": {{"secret_name": "google_oauth_token", "oauth": {{
+                    "authorization_url": "https://accounts.google.com/o/oauth2/v2/auth",
+                    "token_url": "https://oauth2.googleapis.com/token",
+                    "scopes": ["{}"]
+                }}}}}}"#,
+                scope
+            )
+        };
+        std::fs::write(
+            dir.path().join("gmail-tool.capabilities.json"),
+            google("gmail.modify"),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("google-docs-tool.capabilities.json"),
+            google("documents"),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("slack-tool.capabilities.json"),
+            r#"{"auth": {"secret_name": "slack_bot_token"}}"#,
+        )
+        .unwrap();
+
+        let scopes =
+            merged_oauth_scopes(dir.path(), "google_oauth_token", &["documents".to_string()]).await;
+        assert_eq!(scopes, vec!["documents", "gmail.modify"]);
+    }
+
+    #[tokio::test]
+    async fn test_merged_oauth_scopes_missing_dir() {
+        let scopes = merged_oauth_scopes(
+            Path::new("/nonexistent/ironclaw/tools"),
+            "google_oauth_token",
+            &["documents".to_string()],
+        )
+        .await;
+        assert_eq!(scopes, vec!["documents"]);
+    }
+
     #[test]
     fn test_default_tools_dir() {
         let dir = default_tools_dir();
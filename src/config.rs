@@ -1,5 +1,16 @@
 //! Configuration for IronClaw.
+//!
+//! Settings resolve through layers (defaults, config file, environment,
+//! database overrides); see [`ConfigLayers`].
 
+mod layers;
+
+pub use layers::{
+    ConfigLayers, ConfigSource, DATABASE_OVERRIDE_PREFIX, KNOWN_KEYS, ResolvedSetting,
+    is_secret_key, mask_value,
+};
+
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -23,14 +34,30 @@ pub struct Config {
     pub heartbeat: HeartbeatConfig,
     pub sandbox: SandboxModeConfig,
     pub claude_code: ClaudeCodeConfig,
+    pub observability: ObservabilityConfig,
+    pub tenancy: TenancyConfig,
+    pub backup: BackupConfig,
+    pub memory_history: MemoryHistoryConfig,
+    pub marketplace: MarketplaceConfig,
+    pub transcription: TranscriptionConfig,
 }
 
 impl Config {
-    /// Load configuration from environment variables.
+    /// Load configuration from defaults, the config file and the environment.
     pub fn from_env() -> Result<Self, ConfigError> {
         // Load .env file if present (ignore errors if not found)
         let _ = dotenvy::dotenv();
 
+        Self::resolve(ConfigLayers::load()?).map(|(config, _)| config)
+    }
+
+    /// Load configuration from `layers` (plus the live environment),
+    /// returning every setting read along with where its value came from.
+    pub fn resolve(layers: ConfigLayers) -> Result<(Self, Vec<ResolvedSetting>), ConfigError> {
+        layers::resolve(layers, Self::load)
+    }
+
+    fn load() -> Result<Self, ConfigError> {
         Ok(Self {
             database: DatabaseConfig::from_env()?,
             llm: LlmConfig::from_env()?,
@@ -45,6 +72,12 @@ impl Config {
             heartbeat: HeartbeatConfig::from_env()?,
             sandbox: SandboxModeConfig::from_env()?,
             claude_code: ClaudeCodeConfig::from_env()?,
+            observability: ObservabilityConfig::from_env()?,
+            tenancy: TenancyConfig::from_env()?,
+            backup: BackupConfig::from_env()?,
+            memory_history: MemoryHistoryConfig::from_env()?,
+            marketplace: MarketplaceConfig::from_env()?,
+            transcription: TranscriptionConfig::from_env()?,
         })
     }
 }
@@ -80,7 +113,9 @@ pub struct TunnelConfig {
     /// Public URL from tunnel provider (e.g., "https://abc123.ngrok.io").
     ///
     /// When set, channels that support webhooks will register their endpoints
-    /// with this base URL instead of using polling.
+    /// with this base URL instead of using polling, and tools that support
+    /// OAuth can be connected with a one-click link redirecting to
+    /// `{public_url}/oauth/callback` on the HTTP channel.
     pub public_url: Option<String>,
 }
 
@@ -174,6 +209,15 @@ pub enum LlmProviderType {
     Google,
 }
 
+impl LlmProviderType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NearAi => "nearai",
+            Self::Google => "google",
+        }
+    }
+}
+
 impl std::str::FromStr for LlmProviderType {
     type Err = String;
 
@@ -195,6 +239,8 @@ pub struct LlmConfig {
     pub provider: LlmProviderType,
     pub nearai: NearAiConfig,
     pub google: GoogleConfig,
+    /// How often to refresh the model catalog, in seconds (0 disables).
+    pub catalog_refresh_secs: u64,
 }
 
 /// Google Gemini API configuration.
@@ -316,6 +362,7 @@ impl LlmConfig {
             provider,
             nearai,
             google,
+            catalog_refresh_secs: parse_optional_env("LLM_CATALOG_REFRESH_SECS", 86400)?,
         })
     }
 }
@@ -436,6 +483,10 @@ pub struct ChannelsConfig {
     pub cli: CliConfig,
     pub http: Option<HttpConfig>,
     pub gateway: Option<GatewayConfig>,
+    pub email: Option<EmailConfig>,
+    pub api: Option<ApiChannelConfig>,
+    pub ntfy: Option<NtfyConfig>,
+    pub pushover: Option<PushoverConfig>,
     /// Directory containing WASM channel modules (default: ~/.ironclaw/channels/).
     pub wasm_channels_dir: std::path::PathBuf,
     /// Whether WASM channels are enabled.
@@ -465,6 +516,54 @@ pub struct GatewayConfig {
     pub user_id: String,
 }
 
+/// Email channel configuration (IMAP for receiving, SMTP for replies).
+#[derive(Debug, Clone)]
+pub struct EmailConfig {
+    pub imap_host: String,
+    pub imap_port: u16,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub username: String,
+    pub password: SecretString,
+    /// Address replies are sent from.
+    pub from_address: String,
+    /// Mailbox polled for new mail.
+    pub mailbox: String,
+    pub poll_interval: Duration,
+    /// Lowercased sender addresses the agent answers; `*` allows anyone.
+    pub allowed_senders: Vec<String>,
+}
+
+/// Signed webhook channel for third-party applications.
+#[derive(Debug, Clone)]
+pub struct ApiChannelConfig {
+    /// Shared secret signing requests and callbacks.
+    pub secret: SecretString,
+    /// URL agent replies are POSTed to.
+    pub callback_url: String,
+}
+
+/// ntfy push notifications (outbound only).
+#[derive(Debug, Clone)]
+pub struct NtfyConfig {
+    /// Server base URL (default: https://ntfy.sh).
+    pub server: String,
+    pub topic: String,
+    /// Access token for protected topics.
+    pub token: Option<SecretString>,
+}
+
+/// Pushover push notifications (outbound only).
+#[derive(Debug, Clone)]
+pub struct PushoverConfig {
+    /// Application API token.
+    pub app_token: SecretString,
+    /// User (or group) key notifications are sent to.
+    pub user_key: SecretString,
+    /// Limit delivery to one of the user's devices.
+    pub device: Option<String>,
+}
+
 impl ChannelsConfig {
     fn from_env() -> Result<Self, ConfigError> {
         let http = if optional_env("HTTP_PORT")?.is_some() || optional_env("HTTP_HOST")?.is_some() {
@@ -506,6 +605,78 @@ impl ChannelsConfig {
             None
         };
 
+        let email = match optional_env("EMAIL_IMAP_HOST")? {
+            Some(imap_host) => {
+                let required = |key: &str| {
+                    optional_env(key)?.ok_or_else(|| ConfigError::MissingRequired {
+                        key: key.to_string(),
+                        hint: "Required when EMAIL_IMAP_HOST is set".to_string(),
+                    })
+                };
+                let username = required("EMAIL_USERNAME")?;
+                Some(EmailConfig {
+                    imap_host,
+                    imap_port: parse_optional_env("EMAIL_IMAP_PORT", 993)?,
+                    smtp_host: required("EMAIL_SMTP_HOST")?,
+                    smtp_port: parse_optional_env("EMAIL_SMTP_PORT", 587)?,
+                    password: SecretString::from(required("EMAIL_PASSWORD")?),
+                    from_address: optional_env("EMAIL_FROM")?.unwrap_or_else(|| username.clone()),
+                    username,
+                    mailbox: optional_env("EMAIL_MAILBOX")?.unwrap_or_else(|| "INBOX".to_string()),
+                    poll_interval: Duration::from_secs(parse_optional_env(
+                        "EMAIL_POLL_INTERVAL_SECS",
+                        60,
+                    )?),
+                    allowed_senders: optional_env("EMAIL_ALLOWED_SENDERS")?
+                        .map(|s| {
+                            s.split(',')
+                                .map(|a| a.trim().to_lowercase())
+                                .filter(|a| !a.is_empty())
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                })
+            }
+            None => None,
+        };
+
+        let api = match optional_env("API_CHANNEL_SECRET")? {
+            Some(secret) => Some(ApiChannelConfig {
+                secret: SecretString::from(secret),
+                callback_url: optional_env("API_CHANNEL_CALLBACK_URL")?.ok_or_else(|| {
+                    ConfigError::MissingRequired {
+                        key: "API_CHANNEL_CALLBACK_URL".to_string(),
+                        hint: "Required when API_CHANNEL_SECRET is set".to_string(),
+                    }
+                })?,
+            }),
+            None => None,
+        };
+
+        let ntfy = match optional_env("NTFY_TOPIC")? {
+            Some(topic) => Some(NtfyConfig {
+                server: optional_env("NTFY_SERVER")?
+                    .unwrap_or_else(|| "https://ntfy.sh".to_string()),
+                topic,
+                token: optional_env("NTFY_TOKEN")?.map(SecretString::from),
+            }),
+            None => None,
+        };
+
+        let pushover = match optional_env("PUSHOVER_APP_TOKEN")? {
+            Some(app_token) => Some(PushoverConfig {
+                app_token: SecretString::from(app_token),
+                user_key: optional_env("PUSHOVER_USER_KEY")?
+                    .map(SecretString::from)
+                    .ok_or_else(|| ConfigError::MissingRequired {
+                        key: "PUSHOVER_USER_KEY".to_string(),
+                        hint: "Required when PUSHOVER_APP_TOKEN is set".to_string(),
+                    })?,
+                device: optional_env("PUSHOVER_DEVICE")?,
+            }),
+            None => None,
+        };
+
         let cli_enabled = optional_env("CLI_ENABLED")?
             .map(|s| s.to_lowercase() != "false" && s != "0")
             .unwrap_or(true);
@@ -516,6 +687,10 @@ impl ChannelsConfig {
             },
             http,
             gateway,
+            email,
+            api,
+            ntfy,
+            pushover,
             wasm_channels_dir: optional_env("WASM_CHANNELS_DIR")?
                 .map(PathBuf::from)
                 .unwrap_or_else(default_channels_dir),
@@ -562,6 +737,31 @@ pub struct AgentConfig {
     pub cosmic_milkshake: bool,
     /// Whether Shitposting Mode (Chaos Engine) is enabled.
     pub shitposting_mode: bool,
+    /// Whether StakesEngine deliberation shapes the system prompt (personality blend).
+    pub stakes_engine: bool,
+    /// Council roster file (TOML or JSON), hot-reloaded when it changes.
+    pub council_roster_path: PathBuf,
+    /// Whether chat turns use working-set retrieval (recent + relevant history
+    /// and memory) instead of the full thread history.
+    pub working_set: bool,
+    /// Concurrency and yielding between interactive and background work.
+    pub priority: PriorityConfig,
+    /// Channels whose non-urgent messages are batched into periodic digests.
+    pub digest: DigestConfig,
+    /// Batched conversation titles and tags.
+    pub labels: LabelConfig,
+    /// Which notifications are delivered during a user's quiet hours.
+    pub quiet_hours: QuietHoursConfig,
+    /// Consecutive failed runs after which a routine's owner is alerted.
+    pub routine_alert_after: u32,
+    /// Summary of tools, tokens, cost and duration appended to routine
+    /// results; a routine's notify settings can override it.
+    pub routine_report: RoutineReport,
+    /// How long channel message IDs are remembered to drop redeliveries
+    /// (zero disables).
+    pub dedup_window: Duration,
+    /// How often the tool failure trend report is sent (zero disables).
+    pub failure_report_interval: Duration,
 }
 
 impl AgentConfig {
@@ -668,15 +868,273 @@ impl AgentConfig {
                     message: format!("must be 'true' or 'false': {e}"),
                 })?
                 .unwrap_or(true), // Default to TRUE per user request :3
+            stakes_engine: optional_env("STAKES_ENGINE_ENABLED")?
+                .map(|s| s.parse())
+                .transpose()
+                .map_err(|e| ConfigError::InvalidValue {
+                    key: "STAKES_ENGINE_ENABLED".to_string(),
+                    message: format!("must be 'true' or 'false': {e}"),
+                })?
+                .unwrap_or(true),
+            council_roster_path: optional_env("COUNCIL_ROSTER_PATH")?
+                .map(PathBuf::from)
+                .unwrap_or_else(default_council_roster_path),
+            working_set: optional_env("AGENT_WORKING_SET")?
+                .map(|s| s.parse())
+                .transpose()
+                .map_err(|e| ConfigError::InvalidValue {
+                    key: "AGENT_WORKING_SET".to_string(),
+                    message: format!("must be 'true' or 'false': {e}"),
+                })?
+                .unwrap_or(true),
+            priority: PriorityConfig::from_env()?,
+            digest: DigestConfig::from_env()?,
+            labels: LabelConfig::from_env()?,
+            quiet_hours: QuietHoursConfig::from_env()?,
+            routine_alert_after: parse_optional_env("AGENT_ROUTINE_ALERT_AFTER", 3)?,
+            routine_report: parse_optional_env("AGENT_ROUTINE_REPORT", RoutineReport::Off)?,
+            dedup_window: Duration::from_secs(parse_optional_env(
+                "AGENT_DEDUP_WINDOW_SECS",
+                600,
+            )?),
+            failure_report_interval: Duration::from_secs(
+                parse_optional_env::<u64>("SELF_REPAIR_REPORT_INTERVAL_DAYS", 7)? * 24 * 60 * 60,
+            ),
+        })
+    }
+}
+
+/// How much of a routine run is reported under its result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoutineReport {
+    /// Results only.
+    #[default]
+    Off,
+    /// One line with counts, tokens, cost and duration.
+    Brief,
+    /// Tools by name, API calls, token split, cost and duration.
+    Detailed,
+}
+
+impl std::str::FromStr for RoutineReport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" | "none" | "false" => Ok(Self::Off),
+            "brief" | "on" | "true" => Ok(Self::Brief),
+            "detailed" | "full" => Ok(Self::Detailed),
+            _ => Err(format!(
+                "invalid routine report '{}', expected 'off', 'brief' or 'detailed'",
+                s
+            )),
+        }
+    }
+}
+
+/// Priority classes for agent work (see [`crate::agent::priority`]).
+#[derive(Debug, Clone)]
+pub struct PriorityConfig {
+    /// Concurrent interactive (chat) turns.
+    pub interactive_slots: usize,
+    /// Concurrent routine steps, including scheduled jobs.
+    pub routine_slots: usize,
+    /// Concurrent maintenance tasks such as heartbeats.
+    pub maintenance_slots: usize,
+    /// Longest lower-priority work waits for higher-priority work, in seconds.
+    pub max_yield_secs: u64,
+}
+
+impl Default for PriorityConfig {
+    fn default() -> Self {
+        Self {
+            interactive_slots: 4,
+            routine_slots: 2,
+            maintenance_slots: 1,
+            max_yield_secs: 300,
+        }
+    }
+}
+
+impl PriorityConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        Ok(Self {
+            interactive_slots: parse_optional_env(
+                "AGENT_INTERACTIVE_SLOTS",
+                defaults.interactive_slots,
+            )?,
+            routine_slots: parse_optional_env("AGENT_ROUTINE_SLOTS", defaults.routine_slots)?,
+            maintenance_slots: parse_optional_env(
+                "AGENT_MAINTENANCE_SLOTS",
+                defaults.maintenance_slots,
+            )?,
+            max_yield_secs: parse_optional_env("AGENT_MAX_YIELD_SECS", defaults.max_yield_secs)?,
         })
     }
 }
 
+/// Digest mode for noisy channels (see [`crate::agent::digest`]).
+#[derive(Debug, Clone)]
+pub struct DigestConfig {
+    /// Channels in digest mode and how often each one's digest is handled.
+    /// The name "routine" matches routine-triggered messages.
+    pub channels: HashMap<String, Duration>,
+    /// Words that make a message skip the digest (case-insensitive).
+    pub urgent_keywords: Vec<String>,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            channels: HashMap::new(),
+            urgent_keywords: ["urgent", "asap", "emergency"].map(String::from).to_vec(),
+        }
+    }
+}
+
+impl DigestConfig {
+    /// Reads `AGENT_DIGEST_CHANNELS` as `name[:minutes]` entries separated by
+    /// commas, using `AGENT_DIGEST_INTERVAL_MINS` when minutes are omitted.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        let default_mins: u64 = parse_optional_env("AGENT_DIGEST_INTERVAL_MINS", 15)?;
+
+        let mut channels = HashMap::new();
+        for entry in optional_env("AGENT_DIGEST_CHANNELS")?
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            let (name, mins) = match entry.split_once(':') {
+                Some((name, mins)) => {
+                    let mins = mins.trim().parse().map_err(|e| ConfigError::InvalidValue {
+                        key: "AGENT_DIGEST_CHANNELS".to_string(),
+                        message: format!("bad interval for {name}: {e}"),
+                    })?;
+                    (name.trim(), mins)
+                }
+                None => (entry, default_mins),
+            };
+            channels.insert(name.to_string(), Duration::from_secs(mins * 60));
+        }
+
+        let urgent_keywords = match optional_env("AGENT_DIGEST_URGENT_KEYWORDS")? {
+            Some(list) => list
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect(),
+            None => defaults.urgent_keywords,
+        };
+
+        Ok(Self {
+            channels,
+            urgent_keywords,
+        })
+    }
+}
+
+/// Conversation titles and tags (see [`crate::agent::labels`]).
+#[derive(Debug, Clone)]
+pub struct LabelConfig {
+    /// Whether conversations are labeled at all.
+    pub enabled: bool,
+    /// Model for the labeling calls; the agent's model when unset.
+    pub model: Option<String>,
+    /// How often queued conversations are labeled.
+    pub interval: Duration,
+    /// Most conversations labeled in one call.
+    pub batch_size: usize,
+}
+
+impl Default for LabelConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            model: None,
+            interval: Duration::from_secs(300),
+            batch_size: 20,
+        }
+    }
+}
+
+impl LabelConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        Ok(Self {
+            enabled: parse_optional_env("AGENT_LABELS", defaults.enabled)?,
+            model: optional_env("AGENT_LABEL_MODEL")?,
+            interval: Duration::from_secs(parse_optional_env(
+                "AGENT_LABEL_INTERVAL_SECS",
+                defaults.interval.as_secs(),
+            )?),
+            batch_size: parse_optional_env("AGENT_LABEL_BATCH_SIZE", defaults.batch_size)?.max(1),
+        })
+    }
+}
+
+/// Which notifications skip a user's quiet hours (see
+/// [`crate::channels::quiet_hours`]).
+#[derive(Debug, Clone)]
+pub struct QuietHoursConfig {
+    /// Words that make a notification urgent (case-insensitive), on top of
+    /// each user's own.
+    pub urgent_keywords: Vec<String>,
+    /// Notification sources that are always urgent ("defer", "routine",
+    /// "heartbeat", "self_repair").
+    pub urgent_sources: Vec<String>,
+}
+
+impl Default for QuietHoursConfig {
+    fn default() -> Self {
+        Self {
+            urgent_keywords: ["urgent", "asap", "emergency"].map(String::from).to_vec(),
+            // Reminders were scheduled for that time on purpose
+            urgent_sources: vec!["defer".to_string()],
+        }
+    }
+}
+
+impl QuietHoursConfig {
+    /// Reads `AGENT_QUIET_URGENT_KEYWORDS` and `AGENT_QUIET_URGENT_SOURCES`
+    /// as comma-separated lists.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        let list = |key: &str, default: Vec<String>| -> Result<Vec<String>, ConfigError> {
+            Ok(match optional_env(key)? {
+                Some(list) => list
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect(),
+                None => default,
+            })
+        };
+        Ok(Self {
+            urgent_keywords: list("AGENT_QUIET_URGENT_KEYWORDS", defaults.urgent_keywords)?,
+            urgent_sources: list("AGENT_QUIET_URGENT_SOURCES", defaults.urgent_sources)?,
+        })
+    }
+}
+
+fn default_council_roster_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".ironclaw")
+        .join("council.toml")
+}
+
 /// Safety configuration.
 #[derive(Debug, Clone)]
 pub struct SafetyConfig {
     pub max_output_length: usize,
     pub injection_check_enabled: bool,
+    /// Rules file for the keyword content classifier (TOML, or JSON for `.json`).
+    pub classifier_rules: Option<PathBuf>,
 }
 
 impl SafetyConfig {
@@ -691,6 +1149,7 @@ impl SafetyConfig {
                     message: format!("must be 'true' or 'false': {e}"),
                 })?
                 .unwrap_or(true),
+            classifier_rules: optional_env("SAFETY_CLASSIFIER_RULES")?.map(PathBuf::from),
         })
     }
 }
@@ -1019,6 +1478,16 @@ pub struct SandboxModeConfig {
     pub auto_pull_image: bool,
     /// Additional domains to allow through the network proxy.
     pub extra_allowed_domains: Vec<String>,
+    /// Per-job workspace quota in megabytes.
+    pub workspace_quota_mb: u64,
+    /// Per-job workspace limit on files and directories.
+    pub workspace_max_files: u64,
+    /// Largest single file a job may write, in megabytes.
+    pub max_file_mb: u64,
+    /// Seconds to keep a finished job's scratch space before removing it.
+    pub scratch_retention_secs: u64,
+    /// Local port for the proxy inspection view (developer mode; None = off).
+    pub inspect_port: Option<u16>,
 }
 
 impl Default for SandboxModeConfig {
@@ -1032,6 +1501,11 @@ impl Default for SandboxModeConfig {
             image: "ghcr.io/nearai/sandbox:latest".to_string(),
             auto_pull_image: true,
             extra_allowed_domains: Vec::new(),
+            workspace_quota_mb: 1024,
+            workspace_max_files: 10_000,
+            max_file_mb: 100,
+            scratch_retention_secs: 3600,
+            inspect_port: None,
         }
     }
 }
@@ -1066,9 +1540,29 @@ impl SandboxModeConfig {
                 })?
                 .unwrap_or(true),
             extra_allowed_domains: extra_domains,
+            workspace_quota_mb: parse_optional_env("SANDBOX_WORKSPACE_QUOTA_MB", 1024)?,
+            workspace_max_files: parse_optional_env("SANDBOX_WORKSPACE_MAX_FILES", 10_000)?,
+            max_file_mb: parse_optional_env("SANDBOX_MAX_FILE_MB", 100)?,
+            scratch_retention_secs: parse_optional_env("SANDBOX_SCRATCH_RETENTION_SECS", 3600)?,
+            inspect_port: optional_env("SANDBOX_INSPECT_PORT")?
+                .map(|s| s.parse())
+                .transpose()
+                .map_err(|e| ConfigError::InvalidValue {
+                    key: "SANDBOX_INSPECT_PORT".to_string(),
+                    message: format!("must be a port number: {e}"),
+                })?,
         })
     }
 
+    /// Disk quota applied to each job's workspace.
+    pub fn workspace_quota(&self) -> crate::sandbox::WorkspaceQuota {
+        crate::sandbox::WorkspaceQuota {
+            max_bytes: self.workspace_quota_mb * 1024 * 1024,
+            max_files: self.workspace_max_files,
+            max_file_bytes: self.max_file_mb * 1024 * 1024,
+        }
+    }
+
     /// Convert to SandboxConfig for the sandbox module.
     pub fn to_sandbox_config(&self) -> crate::sandbox::SandboxConfig {
         use crate::sandbox::SandboxPolicy;
@@ -1089,6 +1583,8 @@ impl SandboxModeConfig {
             image: self.image.clone(),
             auto_pull_image: self.auto_pull_image,
             proxy_port: 0, // Auto-assign
+            workspace_quota: self.workspace_quota(),
+            inspect_port: self.inspect_port,
         }
     }
 }
@@ -1174,25 +1670,314 @@ impl ClaudeCodeConfig {
     }
 }
 
-// Helper functions
+/// Tracing export configuration.
+///
+/// Metrics are always collected and served at the gateway's `/metrics`
+/// endpoint; spans are only exported when an OTLP endpoint is set.
+#[derive(Debug, Clone)]
+pub struct ObservabilityConfig {
+    /// OTLP gRPC endpoint for trace export (e.g. "http://localhost:4317").
+    pub otlp_endpoint: Option<String>,
+    /// `service.name` reported on exported spans.
+    pub service_name: String,
+}
 
-fn optional_env(key: &str) -> Result<Option<String>, ConfigError> {
-    match std::env::var(key) {
-        Ok(val) => {
-            let trimmed = val.trim();
-            if trimmed.is_empty() {
-                Ok(None)
-            } else {
-                Ok(Some(trimmed.to_string()))
-            }
+impl Default for ObservabilityConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: "ironclaw".to_string(),
+        }
+    }
+}
+
+impl ObservabilityConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        Ok(Self {
+            otlp_endpoint: optional_env("OTEL_EXPORTER_OTLP_ENDPOINT")?,
+            service_name: optional_env("OTEL_SERVICE_NAME")?.unwrap_or(defaults.service_name),
+        })
+    }
+}
+
+/// Multi-user (tenant) configuration.
+///
+/// Disabled by default: every user shares the default workspace and has full
+/// access. When enabled, users get isolated workspaces, and roles and quotas
+/// come from the tenants file.
+#[derive(Debug, Clone)]
+pub struct TenancyConfig {
+    pub enabled: bool,
+    /// Path to the tenants file (default: ~/.ironclaw/tenants.toml).
+    pub tenants_path: PathBuf,
+}
+
+impl Default for TenancyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tenants_path: dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(".ironclaw")
+                .join("tenants.toml"),
+        }
+    }
+}
+
+impl TenancyConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        Ok(Self {
+            enabled: optional_env("TENANCY_ENABLED")?
+                .map(|s| s.parse())
+                .transpose()
+                .map_err(|e| ConfigError::InvalidValue {
+                    key: "TENANCY_ENABLED".to_string(),
+                    message: format!("must be 'true' or 'false': {e}"),
+                })?
+                .unwrap_or(defaults.enabled),
+            tenants_path: optional_env("TENANTS_PATH")?
+                .map(PathBuf::from)
+                .unwrap_or(defaults.tenants_path),
+        })
+    }
+}
+
+/// Scheduled encrypted backups (see [`crate::backup`]).
+#[derive(Debug, Clone)]
+pub struct BackupConfig {
+    pub enabled: bool,
+    /// Interval between scheduled backups in seconds.
+    pub interval_secs: u64,
+    /// Directory backups are written to (default: ~/.ironclaw/backups).
+    pub dir: PathBuf,
+    /// Number of local backups to keep.
+    pub keep: usize,
+    /// Tool whose `upload_file` action each backup is shipped through
+    /// (e.g. "google-drive").
+    pub upload_tool: Option<String>,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 86400, // daily
+            dir: dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(".ironclaw")
+                .join("backups"),
+            keep: 7,
+            upload_tool: None,
+        }
+    }
+}
+
+impl BackupConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        Ok(Self {
+            enabled: optional_env("BACKUP_ENABLED")?
+                .map(|s| s.parse())
+                .transpose()
+                .map_err(|e| ConfigError::InvalidValue {
+                    key: "BACKUP_ENABLED".to_string(),
+                    message: format!("must be 'true' or 'false': {e}"),
+                })?
+                .unwrap_or(defaults.enabled),
+            interval_secs: parse_optional_env("BACKUP_INTERVAL_SECS", defaults.interval_secs)?,
+            dir: optional_env("BACKUP_DIR")?
+                .map(PathBuf::from)
+                .unwrap_or(defaults.dir),
+            keep: parse_optional_env("BACKUP_KEEP", defaults.keep)?,
+            upload_tool: optional_env("BACKUP_UPLOAD_TOOL")?.filter(|s| !s.is_empty()),
+        })
+    }
+}
+
+/// Git-backed version history of workspace memory (see
+/// [`crate::workspace::MemoryHistory`]).
+#[derive(Debug, Clone)]
+pub struct MemoryHistoryConfig {
+    pub enabled: bool,
+    /// Repository location (default: ~/.ironclaw/memory-git).
+    pub dir: PathBuf,
+}
+
+impl Default for MemoryHistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(".ironclaw")
+                .join("memory-git"),
+        }
+    }
+}
+
+impl MemoryHistoryConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        Ok(Self {
+            enabled: optional_env("MEMORY_HISTORY_ENABLED")?
+                .map(|s| s.parse())
+                .transpose()
+                .map_err(|e| ConfigError::InvalidValue {
+                    key: "MEMORY_HISTORY_ENABLED".to_string(),
+                    message: format!("must be 'true' or 'false': {e}"),
+                })?
+                .unwrap_or(defaults.enabled),
+            dir: optional_env("MEMORY_HISTORY_DIR")?
+                .map(PathBuf::from)
+                .unwrap_or(defaults.dir),
+        })
+    }
+}
+
+/// Remote extension marketplace (see [`crate::extensions::marketplace`]).
+#[derive(Debug, Clone)]
+pub struct MarketplaceConfig {
+    /// URL of the marketplace index; the marketplace is off when unset.
+    pub index_url: Option<String>,
+    /// Base64 ed25519 publisher keys. When set, only releases signed by one
+    /// of these keys can be installed.
+    pub trusted_keys: Vec<String>,
+    /// Interval between update checks in seconds (0 disables them).
+    pub update_interval_secs: u64,
+}
+
+impl Default for MarketplaceConfig {
+    fn default() -> Self {
+        Self {
+            index_url: None,
+            trusted_keys: Vec::new(),
+            update_interval_secs: 86400, // daily
         }
-        Err(std::env::VarError::NotPresent) => Ok(None),
-        Err(e) => Err(ConfigError::ParseError(format!(
-            "failed to read {key}: {e}"
-        ))),
     }
 }
 
+impl MarketplaceConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        Ok(Self {
+            index_url: optional_env("MARKETPLACE_INDEX_URL")?.filter(|s| !s.is_empty()),
+            trusted_keys: optional_env("MARKETPLACE_TRUSTED_KEYS")?
+                .map(|s| {
+                    s.split(',')
+                        .map(|k| k.trim().to_string())
+                        .filter(|k| !k.is_empty())
+                        .collect()
+                })
+                .unwrap_or(defaults.trusted_keys),
+            update_interval_secs: parse_optional_env(
+                "MARKETPLACE_UPDATE_INTERVAL_SECS",
+                defaults.update_interval_secs,
+            )?,
+        })
+    }
+}
+
+/// Speech-to-text backend for voice notes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptionProviderType {
+    /// OpenAI Whisper, or any API compatible with its transcription endpoint.
+    Whisper,
+    /// Google Cloud Speech-to-Text.
+    Google,
+}
+
+impl std::str::FromStr for TranscriptionProviderType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "whisper" | "openai" => Ok(Self::Whisper),
+            "google" => Ok(Self::Google),
+            _ => Err(format!(
+                "invalid transcription provider '{}', expected 'whisper' or 'google'",
+                s
+            )),
+        }
+    }
+}
+
+/// Voice note transcription (see [`crate::transcription`]).
+#[derive(Debug, Clone)]
+pub struct TranscriptionConfig {
+    /// Backend to use; transcription is off when unset.
+    pub provider: Option<TranscriptionProviderType>,
+    /// API key. Defaults to OPENAI_API_KEY for Whisper and GOOGLE_API_KEY
+    /// for Google.
+    pub api_key: Option<SecretString>,
+    /// Whisper model.
+    pub model: String,
+    /// Base URL of the Whisper-compatible API.
+    pub base_url: String,
+    /// Spoken language as a BCP-47 tag (e.g. "en-US"). Whisper detects it
+    /// when unset; Google assumes "en-US".
+    pub language: Option<String>,
+    /// Voice notes longer than this many seconds are left untranscribed.
+    pub max_duration_secs: u64,
+}
+
+impl Default for TranscriptionConfig {
+    fn default() -> Self {
+        Self {
+            provider: None,
+            api_key: None,
+            model: "whisper-1".to_string(),
+            base_url: "https://api.openai.com/v1".to_string(),
+            language: None,
+            max_duration_secs: 300,
+        }
+    }
+}
+
+impl TranscriptionConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        let provider = optional_env("TRANSCRIPTION_PROVIDER")?
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.parse().map_err(|e| ConfigError::InvalidValue {
+                    key: "TRANSCRIPTION_PROVIDER".to_string(),
+                    message: e,
+                })
+            })
+            .transpose()?;
+
+        let fallback_key = match provider {
+            Some(TranscriptionProviderType::Whisper) => optional_env("OPENAI_API_KEY")?,
+            Some(TranscriptionProviderType::Google) => optional_env("GOOGLE_API_KEY")?,
+            None => None,
+        };
+        let api_key = optional_env("TRANSCRIPTION_API_KEY")?
+            .or(fallback_key)
+            .map(SecretString::from);
+
+        Ok(Self {
+            provider,
+            api_key,
+            model: optional_env("TRANSCRIPTION_MODEL")?.unwrap_or(defaults.model),
+            base_url: optional_env("TRANSCRIPTION_BASE_URL")?.unwrap_or(defaults.base_url),
+            language: optional_env("TRANSCRIPTION_LANGUAGE")?.filter(|s| !s.is_empty()),
+            max_duration_secs: parse_optional_env(
+                "TRANSCRIPTION_MAX_DURATION_SECS",
+                defaults.max_duration_secs,
+            )?,
+        })
+    }
+}
+
+// Helper functions
+
+/// Look up a setting through the config layers (see [`ConfigLayers`]).
+fn optional_env(key: &str) -> Result<Option<String>, ConfigError> {
+    layers::lookup(key)
+}
+
 fn parse_optional_env<T>(key: &str, default: T) -> Result<T, ConfigError>
 where
     T: std::str::FromStr,
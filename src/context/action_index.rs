@@ -0,0 +1,366 @@
+//! Cross-job index of recorded tool actions, for provenance answers.
+//!
+//! When the user asks "how did you get that number?", the answer should come
+//! from what the agent actually did, not from the LLM re-deriving it. Every
+//! tool call made by a job or a chat turn is indexed here, keyed by user,
+//! with the target it touched (range, file, URL, ...) and its output, so the
+//! call that produced a value can be found by searching for the value.
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::context::{ActionRecord, JobContext};
+
+/// Maximum number of actions kept per user; the oldest are evicted first.
+pub const MAX_ACTIONS_PER_USER: usize = 1000;
+
+/// Longest output kept per action.
+const MAX_OUTPUT_CHARS: usize = 8000;
+
+/// Characters of output shown on each side of a match.
+const EXCERPT_CONTEXT_CHARS: usize = 80;
+
+/// Parameters that say what a tool call read or changed, most specific first.
+const TARGET_KEYS: &[&str] = &[
+    "spreadsheet_id",
+    "document_id",
+    "presentation_id",
+    "file_id",
+    "range",
+    "sheet_name",
+    "path",
+    "url",
+    "query",
+    "command",
+    "channel",
+    "calendar_id",
+];
+
+/// A tool call recorded for provenance.
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexedAction {
+    /// ID of the underlying action record.
+    pub id: Uuid,
+    pub user_id: String,
+    pub job_id: Uuid,
+    /// Conversation thread, for chat turns.
+    pub conversation_id: Option<Uuid>,
+    pub tool_name: String,
+    /// The tool's `action` parameter, for multi-action tools.
+    pub action: Option<String>,
+    /// What the call read or changed, e.g. `spreadsheet_id=abc, range=A1:C9`.
+    pub target: Option<String>,
+    pub input: serde_json::Value,
+    /// Sanitized output, truncated.
+    pub output: String,
+    pub success: bool,
+    pub error: Option<String>,
+    pub executed_at: DateTime<Utc>,
+}
+
+impl IndexedAction {
+    /// Index entry for `record`, made in the job or chat turn `ctx`.
+    pub fn from_record(ctx: &JobContext, record: &ActionRecord) -> Self {
+        let output = record
+            .output_sanitized
+            .as_ref()
+            .map(|v| match v {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .or_else(|| record.output_raw.clone())
+            .unwrap_or_default();
+
+        Self {
+            id: record.id,
+            user_id: ctx.user_id.clone(),
+            job_id: ctx.job_id,
+            conversation_id: ctx.conversation_id,
+            tool_name: record.tool_name.clone(),
+            action: record
+                .input
+                .get("action")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            target: describe_target(&record.input),
+            input: record.input.clone(),
+            output: output.chars().take(MAX_OUTPUT_CHARS).collect(),
+            success: record.success,
+            error: record.error.clone(),
+            executed_at: record.executed_at,
+        }
+    }
+
+    /// The part of the output around `needle`, if the output contains it.
+    pub fn excerpt(&self, needle: &str) -> Option<String> {
+        let (start, len) = find(&self.output, needle)?;
+        let from = floor_char_boundary(&self.output, start.saturating_sub(EXCERPT_CONTEXT_CHARS));
+        let to = ceil_char_boundary(&self.output, start + len + EXCERPT_CONTEXT_CHARS);
+
+        let mut excerpt = String::new();
+        if from > 0 {
+            excerpt.push('…');
+        }
+        excerpt.push_str(&self.output[from..to]);
+        if to < self.output.len() {
+            excerpt.push('…');
+        }
+        Some(excerpt)
+    }
+
+    fn matches(&self, needle: &str) -> bool {
+        find(&self.output, needle).is_some()
+            || find(&self.input.to_string(), needle).is_some()
+            || self
+                .target
+                .as_deref()
+                .is_some_and(|t| find(t, needle).is_some())
+    }
+}
+
+/// Describe what a tool call targeted from its parameters.
+fn describe_target(input: &serde_json::Value) -> Option<String> {
+    let parts: Vec<String> = TARGET_KEYS
+        .iter()
+        .filter_map(|key| {
+            let value = input.get(*key)?;
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Number(n) => n.to_string(),
+                _ => return None,
+            };
+            Some(format!("{}={}", key, value))
+        })
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+/// Find `needle` in `haystack`, returning its byte offset and length.
+///
+/// Text matches case-insensitively anywhere. Numbers match whole numbers
+/// only, with thousands separators written either way ("42,315" finds
+/// "42315" but not "142315").
+fn find(haystack: &str, needle: &str) -> Option<(usize, usize)> {
+    let needle = needle.trim();
+    if needle.is_empty() {
+        return None;
+    }
+
+    // Signs are left out: "-5" and "5" both find "-5"
+    let number: String = needle
+        .trim_start_matches('-')
+        .chars()
+        .filter(|c| *c != ',')
+        .collect();
+    let is_number = number.chars().any(|c| c.is_ascii_digit())
+        && number.chars().all(|c| c.is_ascii_digit() || c == '.');
+    if !is_number {
+        // ASCII lowercasing keeps byte offsets valid
+        return haystack
+            .to_ascii_lowercase()
+            .find(&needle.to_ascii_lowercase())
+            .map(|i| (i, needle.len()));
+    }
+
+    let is_numeric = |c: char| c.is_ascii_digit() || c == ',' || c == '.';
+    let mut chars = haystack.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if !is_numeric(c) {
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        while let Some(&(i, c)) = chars.peek()
+            && is_numeric(c)
+        {
+            end = i + c.len_utf8();
+            chars.next();
+        }
+        // Leave out punctuation around the number, e.g. a full stop
+        let token = &haystack[start..end];
+        let trimmed = token
+            .trim_start_matches([',', '.'])
+            .trim_end_matches([',', '.']);
+        let offset = start + (token.len() - token.trim_start_matches([',', '.']).len());
+        let normalized: String = trimmed.chars().filter(|c| *c != ',').collect();
+        if normalized == number {
+            return Some((offset, trimmed.len()));
+        }
+    }
+    None
+}
+
+fn floor_char_boundary(s: &str, mut i: usize) -> usize {
+    i = i.min(s.len());
+    while !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+fn ceil_char_boundary(s: &str, mut i: usize) -> usize {
+    i = i.min(s.len());
+    while !s.is_char_boundary(i) {
+        i += 1;
+    }
+    i
+}
+
+/// Filter for [`ActionIndex::search`].
+#[derive(Debug, Clone, Default)]
+pub struct ActionQuery {
+    /// Value or text the action's output, input or target must contain.
+    pub contains: Option<String>,
+    pub tool_name: Option<String>,
+    pub job_id: Option<Uuid>,
+    pub conversation_id: Option<Uuid>,
+    /// Include failed calls.
+    pub include_failed: bool,
+    /// Maximum results (0 = no limit).
+    pub limit: usize,
+}
+
+/// In-memory index of tool actions shared by all jobs.
+#[derive(Default)]
+pub struct ActionIndex {
+    /// Actions in execution order (oldest first).
+    actions: RwLock<VecDeque<IndexedAction>>,
+}
+
+impl ActionIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an action, evicting the user's oldest past the limit.
+    pub async fn record(&self, action: IndexedAction) {
+        let mut actions = self.actions.write().await;
+        let user_id = action.user_id.clone();
+        actions.push_back(action);
+
+        let owned = actions.iter().filter(|a| a.user_id == user_id).count();
+        if owned > MAX_ACTIONS_PER_USER
+            && let Some(i) = actions.iter().position(|a| a.user_id == user_id)
+        {
+            actions.remove(i);
+        }
+    }
+
+    /// A user's actions matching `query`, most recent first.
+    pub async fn search(&self, user_id: &str, query: &ActionQuery) -> Vec<IndexedAction> {
+        let actions = self.actions.read().await;
+
+        let matches = actions.iter().rev().filter(|a| {
+            a.user_id == user_id
+                && (query.include_failed || a.success)
+                && query.tool_name.as_ref().is_none_or(|t| a.tool_name == *t)
+                && query.job_id.is_none_or(|j| a.job_id == j)
+                && query
+                    .conversation_id
+                    .is_none_or(|c| a.conversation_id == Some(c))
+                && query.contains.as_ref().is_none_or(|n| a.matches(n))
+        });
+
+        if query.limit > 0 {
+            matches.take(query.limit).cloned().collect()
+        } else {
+            matches.cloned().collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(user: &str, tool: &str, input: serde_json::Value, output: &str) -> IndexedAction {
+        let ctx = JobContext::with_user(user, "chat", "test");
+        let record = ActionRecord::new(0, tool, input).succeed(
+            None,
+            serde_json::Value::String(output.to_string()),
+            std::time::Duration::ZERO,
+        );
+        IndexedAction::from_record(&ctx, &record)
+    }
+
+    #[test]
+    fn test_from_record() {
+        let indexed = action(
+            "alice",
+            "google_sheets",
+            serde_json::json!({"action": "read_values", "spreadsheet_id": "s1", "range": "Q3!B2:B40"}),
+            "Total: 42315",
+        );
+        assert_eq!(indexed.action.as_deref(), Some("read_values"));
+        assert_eq!(
+            indexed.target.as_deref(),
+            Some("spreadsheet_id=s1, range=Q3!B2:B40")
+        );
+        assert_eq!(indexed.output, "Total: 42315");
+    }
+
+    #[test]
+    fn test_find_numbers_with_separators() {
+        assert_eq!(find("Total: 42315 units", "42,315"), Some((7, 5)));
+        assert_eq!(find("Revenue was 1,204.50", "1204.50"), Some((12, 8)));
+        assert_eq!(find("Revenue was 1,204.50.", "1204.50"), Some((12, 8)));
+        assert_eq!(find("Revenue was 1,204.50", "1204.5"), None);
+        assert_eq!(find("Total: 142315", "42315"), None);
+        assert_eq!(find("Balance: -5", "-5"), Some((10, 1)));
+        assert_eq!(find("The REVENUE", "revenue"), Some((4, 7)));
+        assert!(find("anything", " ").is_none());
+    }
+
+    #[test]
+    fn test_excerpt() {
+        let long = format!("{}Total: 42315{}", "a".repeat(200), "b".repeat(200));
+        let indexed = action("alice", "shell", serde_json::json!({}), &long);
+        let excerpt = indexed.excerpt("42,315").unwrap();
+        assert!(excerpt.starts_with('…') && excerpt.ends_with('…'));
+        assert!(excerpt.contains("Total: 42315"));
+        assert!(indexed.excerpt("99999").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search() {
+        let index = ActionIndex::new();
+        index
+            .record(action(
+                "alice",
+                "http",
+                serde_json::json!({"url": "https://a"}),
+                "price 10",
+            ))
+            .await;
+        index
+            .record(action(
+                "alice",
+                "shell",
+                serde_json::json!({"command": "wc -l"}),
+                "price 12",
+            ))
+            .await;
+        index
+            .record(action("bob", "http", serde_json::json!({}), "price 10"))
+            .await;
+
+        let query = ActionQuery {
+            contains: Some("price".to_string()),
+            ..Default::default()
+        };
+        let found = index.search("alice", &query).await;
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].tool_name, "shell"); // newest first
+
+        let query = ActionQuery {
+            contains: Some("10".to_string()),
+            ..Default::default()
+        };
+        let found = index.search("alice", &query).await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].target.as_deref(), Some("url=https://a"));
+    }
+}
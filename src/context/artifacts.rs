@@ -0,0 +1,407 @@
+//! Cross-job registry of things tools have created.
+//!
+//! Job contexts are isolated, so a deck built in one job is invisible to the
+//! next. Whenever a tool call succeeds, its output is inspected for a typed
+//! reference (Drive file, spreadsheet, sent message, ...) and recorded here,
+//! keyed by user, so any later job or conversation can look it up by kind or
+//! title instead of asking the user to repeat IDs.
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Maximum number of artifacts kept per user; the oldest are evicted first.
+pub const MAX_ARTIFACTS_PER_USER: usize = 500;
+
+/// What kind of thing an artifact refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    DriveFile,
+    DriveFolder,
+    Spreadsheet,
+    Document,
+    Presentation,
+    Email,
+    EmailDraft,
+    ChatMessage,
+    CalendarEvent,
+}
+
+impl ArtifactKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DriveFile => "drive_file",
+            Self::DriveFolder => "drive_folder",
+            Self::Spreadsheet => "spreadsheet",
+            Self::Document => "document",
+            Self::Presentation => "presentation",
+            Self::Email => "email",
+            Self::EmailDraft => "email_draft",
+            Self::ChatMessage => "chat_message",
+            Self::CalendarEvent => "calendar_event",
+        }
+    }
+
+    /// Parse the snake_case name used in tool parameters.
+    pub fn from_name(name: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+    }
+}
+
+/// A typed reference to something a tool created.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Artifact {
+    /// Registry ID.
+    pub id: Uuid,
+    /// User that owns the artifact.
+    pub user_id: String,
+    pub kind: ArtifactKind,
+    /// ID in the external service (file ID, message ID, ...).
+    pub external_id: String,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    /// Tool that created the artifact.
+    pub tool_name: String,
+    /// Job the artifact was created in.
+    pub job_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    /// Last time the artifact was touched by a tool.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Artifact {
+    /// Extract an artifact reference from a successful tool call.
+    ///
+    /// Recognizes the create/send actions of the Google, Gmail, Slack and
+    /// Telegram tools by their `action` parameter and output shape. Returns
+    /// `None` for read-only calls and unrecognized output.
+    pub fn from_tool_output(
+        tool_name: &str,
+        params: &serde_json::Value,
+        output: &serde_json::Value,
+        user_id: &str,
+        job_id: Uuid,
+    ) -> Option<Self> {
+        let action = params.get("action").and_then(|v| v.as_str())?;
+        let str_at = |v: &serde_json::Value, key: &str| {
+            v.get(key).and_then(|v| v.as_str()).map(String::from)
+        };
+
+        let (kind, external_id, title, url) = match action {
+            "create_spreadsheet" => (
+                ArtifactKind::Spreadsheet,
+                str_at(output, "spreadsheet_id")?,
+                str_at(output, "title"),
+                str_at(output, "url"),
+            ),
+            "create_document" => {
+                let id = str_at(output, "document_id")?;
+                let url = format!("https://docs.google.com/document/d/{}/edit", id);
+                (
+                    ArtifactKind::Document,
+                    id,
+                    str_at(output, "title"),
+                    Some(url),
+                )
+            }
+            "create_presentation" => {
+                let id = str_at(output, "presentation_id")?;
+                let url = format!("https://docs.google.com/presentation/d/{}/edit", id);
+                (
+                    ArtifactKind::Presentation,
+                    id,
+                    str_at(output, "title"),
+                    Some(url),
+                )
+            }
+            "upload_file" | "update_file" | "create_folder" => {
+                let file = output.get("file")?;
+                let kind = if file.get("is_folder").and_then(|v| v.as_bool()) == Some(true) {
+                    ArtifactKind::DriveFolder
+                } else {
+                    ArtifactKind::DriveFile
+                };
+                (
+                    kind,
+                    str_at(file, "id")?,
+                    str_at(file, "name"),
+                    str_at(file, "web_view_link"),
+                )
+            }
+            "create_event" | "update_event" => {
+                let event = output.get("event")?;
+                (
+                    ArtifactKind::CalendarEvent,
+                    str_at(event, "id")?,
+                    str_at(event, "summary"),
+                    str_at(event, "html_link"),
+                )
+            }
+            "create_draft" => (
+                ArtifactKind::EmailDraft,
+                str_at(output, "id")?,
+                str_at(params, "subject"),
+                None,
+            ),
+            "send_message" | "reply_to_message" => {
+                if output.get("thread_id").is_some() {
+                    // Gmail
+                    (
+                        ArtifactKind::Email,
+                        str_at(output, "id")?,
+                        str_at(params, "subject"),
+                        None,
+                    )
+                } else if let Some(ts) = str_at(output, "ts") {
+                    // Slack messages are identified by channel + timestamp
+                    let channel = str_at(output, "channel")?;
+                    (
+                        ArtifactKind::ChatMessage,
+                        format!("{}:{}", channel, ts),
+                        str_at(params, "text").map(|t| preview(&t)),
+                        None,
+                    )
+                } else {
+                    // Telegram
+                    let id = output.get("message_id").and_then(|v| v.as_i64())?;
+                    (
+                        ArtifactKind::ChatMessage,
+                        id.to_string(),
+                        str_at(params, "text").map(|t| preview(&t)),
+                        None,
+                    )
+                }
+            }
+            _ => return None,
+        };
+
+        let now = Utc::now();
+        Some(Self {
+            id: Uuid::new_v4(),
+            user_id: user_id.to_string(),
+            kind,
+            external_id,
+            title,
+            url,
+            tool_name: tool_name.to_string(),
+            job_id,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+}
+
+/// Shorten message text for use as an artifact title.
+fn preview(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() > 80 {
+        format!("{}...", line.chars().take(80).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+/// Filter for [`ArtifactStore::list`].
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactQuery {
+    pub kind: Option<ArtifactKind>,
+    /// Case-insensitive substring of the title.
+    pub title_contains: Option<String>,
+    pub job_id: Option<Uuid>,
+    /// Maximum results (0 = no limit).
+    pub limit: usize,
+}
+
+/// In-memory artifact registry shared by all jobs.
+#[derive(Default)]
+pub struct ArtifactStore {
+    /// Artifacts in insertion order (oldest first).
+    artifacts: RwLock<VecDeque<Artifact>>,
+}
+
+impl ArtifactStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an artifact.
+    ///
+    /// An artifact with the same user, kind and external ID replaces the
+    /// earlier entry (keeping its registry ID and creation time), so updating
+    /// a file does not produce duplicates.
+    pub async fn record(&self, artifact: Artifact) -> Artifact {
+        let mut artifacts = self.artifacts.write().await;
+
+        let existing = artifacts.iter().position(|a| {
+            a.user_id == artifact.user_id
+                && a.kind == artifact.kind
+                && a.external_id == artifact.external_id
+        });
+        let artifact = match existing.and_then(|i| artifacts.remove(i)) {
+            Some(old) => Artifact {
+                id: old.id,
+                created_at: old.created_at,
+                title: artifact.title.or(old.title),
+                url: artifact.url.or(old.url),
+                ..artifact
+            },
+            None => artifact,
+        };
+        artifacts.push_back(artifact.clone());
+
+        let owned = artifacts
+            .iter()
+            .filter(|a| a.user_id == artifact.user_id)
+            .count();
+        if owned > MAX_ARTIFACTS_PER_USER {
+            if let Some(i) = artifacts.iter().position(|a| a.user_id == artifact.user_id) {
+                artifacts.remove(i);
+            }
+        }
+
+        artifact
+    }
+
+    /// Get an artifact by registry ID.
+    pub async fn get(&self, user_id: &str, id: Uuid) -> Option<Artifact> {
+        self.artifacts
+            .read()
+            .await
+            .iter()
+            .find(|a| a.id == id && a.user_id == user_id)
+            .cloned()
+    }
+
+    /// List a user's artifacts matching `query`, most recently updated first.
+    pub async fn list(&self, user_id: &str, query: &ArtifactQuery) -> Vec<Artifact> {
+        let needle = query.title_contains.as_ref().map(|s| s.to_lowercase());
+        let artifacts = self.artifacts.read().await;
+
+        let matches = artifacts.iter().rev().filter(|a| {
+            a.user_id == user_id
+                && query.kind.is_none_or(|k| a.kind == k)
+                && query.job_id.is_none_or(|j| a.job_id == j)
+                && needle.as_ref().is_none_or(|n| {
+                    a.title
+                        .as_ref()
+                        .is_some_and(|t| t.to_lowercase().contains(n))
+                })
+        });
+
+        if query.limit > 0 {
+            matches.take(query.limit).cloned().collect()
+        } else {
+            matches.cloned().collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sheet(user: &str, id: &str, title: &str) -> Artifact {
+        Artifact::from_tool_output(
+            "google_sheets",
+            &serde_json::json!({ "action": "create_spreadsheet", "title": title }),
+            &serde_json::json!({
+                "spreadsheet_id": id,
+                "title": title,
+                "url": format!("https://docs.google.com/spreadsheets/d/{}", id),
+                "sheets": []
+            }),
+            user,
+            Uuid::new_v4(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_extract_known_outputs() {
+        let job = Uuid::new_v4();
+
+        let deck = Artifact::from_tool_output(
+            "google_slides",
+            &serde_json::json!({ "action": "create_presentation", "title": "Q3 Deck" }),
+            &serde_json::json!({ "presentation_id": "p1", "title": "Q3 Deck" }),
+            "u",
+            job,
+        )
+        .unwrap();
+        assert_eq!(deck.kind, ArtifactKind::Presentation);
+        assert_eq!(deck.external_id, "p1");
+        assert_eq!(deck.title.as_deref(), Some("Q3 Deck"));
+        assert!(deck.url.unwrap().contains("/presentation/d/p1"));
+
+        let slack = Artifact::from_tool_output(
+            "slack",
+            &serde_json::json!({ "action": "send_message", "channel": "C1", "text": "Deck is up\nmore" }),
+            &serde_json::json!({ "ok": true, "channel": "C1", "ts": "123.45" }),
+            "u",
+            job,
+        )
+        .unwrap();
+        assert_eq!(slack.kind, ArtifactKind::ChatMessage);
+        assert_eq!(slack.external_id, "C1:123.45");
+        assert_eq!(slack.title.as_deref(), Some("Deck is up"));
+
+        let folder = Artifact::from_tool_output(
+            "google_drive",
+            &serde_json::json!({ "action": "create_folder", "name": "Reports" }),
+            &serde_json::json!({ "file": { "id": "f1", "name": "Reports", "is_folder": true } }),
+            "u",
+            job,
+        )
+        .unwrap();
+        assert_eq!(folder.kind, ArtifactKind::DriveFolder);
+
+        // Read-only calls are not artifacts
+        assert!(
+            Artifact::from_tool_output(
+                "google_sheets",
+                &serde_json::json!({ "action": "read_values", "spreadsheet_id": "s1" }),
+                &serde_json::json!({ "range": "A1", "values": [] }),
+                "u",
+                job,
+            )
+            .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_store_is_shared_across_jobs() {
+        let store = ArtifactStore::new();
+        store.record(sheet("alice", "s1", "Budget 2026")).await;
+        store.record(sheet("alice", "s2", "Roadmap")).await;
+        store.record(sheet("bob", "s3", "Budget draft")).await;
+
+        let query = ArtifactQuery {
+            title_contains: Some("budget".into()),
+            ..Default::default()
+        };
+        let found = store.list("alice", &query).await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].external_id, "s1");
+
+        // Newest first
+        let all = store.list("alice", &ArtifactQuery::default()).await;
+        assert_eq!(all[0].external_id, "s2");
+        assert!(store.get("bob", all[0].id).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_record_replaces_same_external_id() {
+        let store = ArtifactStore::new();
+        let first = store.record(sheet("alice", "s1", "Budget")).await;
+        let second = store.record(sheet("alice", "s1", "Budget v2")).await;
+
+        assert_eq!(first.id, second.id);
+        let all = store.list("alice", &ArtifactQuery::default()).await;
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].title.as_deref(), Some("Budget v2"));
+    }
+}
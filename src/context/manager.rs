@@ -5,7 +5,10 @@ use std::collections::HashMap;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::context::{JobContext, Memory};
+use crate::context::{
+    ActionIndex, ActionRecord, Artifact, ArtifactStore, CachedResult, IndexedAction, JobContext,
+    Memory,
+};
 use crate::error::JobError;
 
 /// Manages contexts for multiple concurrent jobs.
@@ -16,6 +19,10 @@ pub struct ContextManager {
     memories: RwLock<HashMap<Uuid, Memory>>,
     /// Maximum concurrent jobs.
     max_jobs: usize,
+    /// Artifacts created by tools, shared across all jobs.
+    artifacts: ArtifactStore,
+    /// Tool calls made by any job or chat turn, for provenance lookups.
+    actions: ActionIndex,
 }
 
 impl ContextManager {
@@ -25,6 +32,8 @@ impl ContextManager {
             contexts: RwLock::new(HashMap::new()),
             memories: RwLock::new(HashMap::new()),
             max_jobs,
+            artifacts: ArtifactStore::new(),
+            actions: ActionIndex::new(),
         }
     }
 
@@ -108,6 +117,83 @@ impl ContextManager {
         Ok(f(memory))
     }
 
+    /// Artifact registry shared by all jobs.
+    pub fn artifacts(&self) -> &ArtifactStore {
+        &self.artifacts
+    }
+
+    /// Record the artifact (if any) referenced by a successful tool call.
+    pub async fn record_tool_artifact(
+        &self,
+        ctx: &JobContext,
+        tool_name: &str,
+        params: &serde_json::Value,
+        output: &serde_json::Value,
+    ) -> Option<Artifact> {
+        let artifact =
+            Artifact::from_tool_output(tool_name, params, output, &ctx.user_id, ctx.job_id)?;
+        let artifact = self.artifacts.record(artifact).await;
+        tracing::debug!(
+            "Recorded {} artifact {} from {}",
+            artifact.kind.as_str(),
+            artifact.external_id,
+            tool_name
+        );
+        Some(artifact)
+    }
+
+    /// Index of tool calls shared by all jobs.
+    pub fn actions(&self) -> &ActionIndex {
+        &self.actions
+    }
+
+    /// Index a tool call made in the job or chat turn `ctx`.
+    pub async fn index_action(&self, ctx: &JobContext, action: &ActionRecord) {
+        self.actions
+            .record(IndexedAction::from_record(ctx, action))
+            .await;
+    }
+
+    /// Get a fresh cached result of a read-only tool call made by this job.
+    pub async fn cached_tool_result(
+        &self,
+        job_id: Uuid,
+        tool_name: &str,
+        params: &serde_json::Value,
+    ) -> Option<CachedResult> {
+        self.memories
+            .read()
+            .await
+            .get(&job_id)?
+            .tool_cache
+            .get(tool_name, params)
+            .cloned()
+    }
+
+    /// Cache the result of a read-only tool call for `ttl`.
+    pub async fn cache_tool_result(
+        &self,
+        job_id: Uuid,
+        tool_name: &str,
+        params: &serde_json::Value,
+        result: serde_json::Value,
+        ttl: std::time::Duration,
+    ) {
+        if let Some(memory) = self.memories.write().await.get_mut(&job_id) {
+            memory.tool_cache.insert(tool_name, params, result, ttl);
+        }
+    }
+
+    /// Drop cached results of `tool_name`, or of every tool when `None`.
+    pub async fn invalidate_tool_cache(&self, job_id: Uuid, tool_name: Option<&str>) {
+        if let Some(memory) = self.memories.write().await.get_mut(&job_id) {
+            match tool_name {
+                Some(tool_name) => memory.tool_cache.invalidate_tool(tool_name),
+                None => memory.tool_cache.clear(),
+            }
+        }
+    }
+
     /// List all active job IDs.
     pub async fn active_jobs(&self) -> Vec<Uuid> {
         self.contexts
@@ -322,4 +408,42 @@ mod tests {
         let context = manager.get_context(job_id).await.unwrap();
         assert_eq!(context.state, crate::context::JobState::InProgress);
     }
+
+    #[tokio::test]
+    async fn test_tool_cache_is_per_job() {
+        let manager = ContextManager::new(5);
+        let job_a = manager.create_job("A", "Desc").await.unwrap();
+        let job_b = manager.create_job("B", "Desc").await.unwrap();
+        let params = serde_json::json!({"action": "get_presentation"});
+
+        manager
+            .cache_tool_result(
+                job_a,
+                "google_slides",
+                &params,
+                serde_json::json!({"ok": true}),
+                std::time::Duration::from_secs(60),
+            )
+            .await;
+        assert!(
+            manager
+                .cached_tool_result(job_a, "google_slides", &params)
+                .await
+                .is_some()
+        );
+        assert!(
+            manager
+                .cached_tool_result(job_b, "google_slides", &params)
+                .await
+                .is_none()
+        );
+
+        manager.invalidate_tool_cache(job_a, None).await;
+        assert!(
+            manager
+                .cached_tool_result(job_a, "google_slides", &params)
+                .await
+                .is_none()
+        );
+    }
 }
@@ -7,6 +7,7 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::context::ToolResultCache;
 use crate::llm::ChatMessage;
 
 /// A record of an action taken during job execution.
@@ -165,6 +166,8 @@ pub struct Memory {
     pub conversation: ConversationMemory,
     /// Action history.
     pub actions: Vec<ActionRecord>,
+    /// Results of read-only tool calls, reused within their freshness window.
+    pub tool_cache: ToolResultCache,
     /// Next action sequence number.
     next_sequence: u32,
 }
@@ -176,6 +179,7 @@ impl Memory {
             job_id,
             conversation: ConversationMemory::new(100),
             actions: Vec::new(),
+            tool_cache: ToolResultCache::new(),
             next_sequence: 0,
         }
     }
@@ -3,13 +3,27 @@
 //! Each job runs with its own isolated context that includes:
 //! - Conversation history
 //! - Action history
+//! - Cached results of read-only tool calls
 //! - State machine
 //! - Resource tracking
+//!
+//! Artifacts created by tools (files, sheets, messages) are the exception:
+//! they are recorded in a registry shared by all jobs, as is an index of every
+//! tool call made, used to answer where a result came from. Conversations also
+//! carry their own variables, persisted in conversation metadata.
 
+mod action_index;
+mod artifacts;
 mod manager;
 mod memory;
 mod state;
+mod tool_cache;
+pub mod variables;
 
+pub use action_index::{ActionIndex, ActionQuery, IndexedAction, MAX_ACTIONS_PER_USER};
+pub use artifacts::{Artifact, ArtifactKind, ArtifactQuery, ArtifactStore};
 pub use manager::ContextManager;
 pub use memory::{ActionRecord, ConversationMemory, Memory};
 pub use state::{JobContext, JobState, StateTransition};
+pub use tool_cache::{CachedResult, MAX_CACHED_RESULTS, ToolResultCache};
+pub use variables::{ConversationVariables, Variable, VariableError, VariableType};
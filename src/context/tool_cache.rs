@@ -0,0 +1,155 @@
+//! Per-job cache of read-only tool results.
+//!
+//! Jobs often repeat the same read, such as fetching a presentation after
+//! every edit. Tools that opt in (see [`Tool::cache_ttl`]) have their
+//! results kept here for a freshness window, keyed by tool name and
+//! parameters. Any other call to the same tool may have changed what it
+//! reads, so it drops that tool's entries.
+//!
+//! [`Tool::cache_ttl`]: crate::tools::Tool::cache_ttl
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Maximum number of results cached per job; the oldest are evicted first.
+pub const MAX_CACHED_RESULTS: usize = 64;
+
+/// A cached tool result.
+#[derive(Debug, Clone)]
+pub struct CachedResult {
+    /// The tool's output.
+    pub result: serde_json::Value,
+    /// When the result was produced.
+    pub cached_at: Instant,
+    /// How long the result stays fresh.
+    pub ttl: Duration,
+}
+
+impl CachedResult {
+    /// Time since the result was produced.
+    pub fn age(&self) -> Duration {
+        self.cached_at.elapsed()
+    }
+
+    /// Whether the result is still within its freshness window.
+    pub fn is_fresh(&self) -> bool {
+        self.age() < self.ttl
+    }
+}
+
+/// Results of read-only tool calls made by one job.
+#[derive(Debug, Clone, Default)]
+pub struct ToolResultCache {
+    entries: HashMap<(String, String), CachedResult>,
+}
+
+impl ToolResultCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a fresh result for this call, if any.
+    pub fn get(&self, tool_name: &str, params: &serde_json::Value) -> Option<&CachedResult> {
+        self.entries
+            .get(&(tool_name.to_string(), params.to_string()))
+            .filter(|cached| cached.is_fresh())
+    }
+
+    /// Cache the result of a call for `ttl`.
+    pub fn insert(
+        &mut self,
+        tool_name: &str,
+        params: &serde_json::Value,
+        result: serde_json::Value,
+        ttl: Duration,
+    ) {
+        self.entries.retain(|_, cached| cached.is_fresh());
+        if self.entries.len() >= MAX_CACHED_RESULTS
+            && let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, cached)| cached.cached_at)
+                .map(|(key, _)| key.clone())
+        {
+            self.entries.remove(&oldest);
+        }
+
+        self.entries.insert(
+            (tool_name.to_string(), params.to_string()),
+            CachedResult {
+                result,
+                cached_at: Instant::now(),
+                ttl,
+            },
+        );
+    }
+
+    /// Drop every cached result of one tool.
+    pub fn invalidate_tool(&mut self, tool_name: &str) {
+        self.entries.retain(|(tool, _), _| tool != tool_name);
+    }
+
+    /// Drop every cached result.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Number of cached results, fresh or not.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_matches_tool_and_params() {
+        let mut cache = ToolResultCache::new();
+        let params = serde_json::json!({"action": "get_presentation", "presentation_id": "p1"});
+        cache.insert(
+            "google_slides",
+            &params,
+            serde_json::json!({"slides": 3}),
+            Duration::from_secs(60),
+        );
+
+        let cached = cache.get("google_slides", &params).unwrap();
+        assert_eq!(cached.result["slides"], 3);
+
+        let other = serde_json::json!({"action": "get_presentation", "presentation_id": "p2"});
+        assert!(cache.get("google_slides", &other).is_none());
+        assert!(cache.get("google_docs", &params).is_none());
+    }
+
+    #[test]
+    fn test_expired_results_are_not_served() {
+        let mut cache = ToolResultCache::new();
+        let params = serde_json::json!({"q": "x"});
+        cache.insert("search", &params, serde_json::json!([]), Duration::ZERO);
+        assert!(cache.get("search", &params).is_none());
+    }
+
+    #[test]
+    fn test_invalidate_tool_and_eviction() {
+        let mut cache = ToolResultCache::new();
+        let ttl = Duration::from_secs(60);
+        cache.insert("a", &serde_json::json!(1), serde_json::json!(1), ttl);
+        cache.insert("b", &serde_json::json!(1), serde_json::json!(1), ttl);
+        cache.invalidate_tool("a");
+        assert!(cache.get("a", &serde_json::json!(1)).is_none());
+        assert!(cache.get("b", &serde_json::json!(1)).is_some());
+
+        for i in 0..MAX_CACHED_RESULTS + 5 {
+            cache.insert("c", &serde_json::json!(i), serde_json::json!(i), ttl);
+        }
+        assert_eq!(cache.len(), MAX_CACHED_RESULTS);
+    }
+}
@@ -0,0 +1,304 @@
+//! Conversation-scoped variables.
+//!
+//! A small typed key-value store that multi-turn workflows use to stash IDs
+//! and intermediate values ("remember 1AbC as budget_sheet") instead of
+//! burying them in prose. Variables are persisted under the `variables` field
+//! of the conversation's metadata.
+
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::DatabaseError;
+
+/// Conversation metadata field holding the variables.
+pub const METADATA_FIELD: &str = "variables";
+
+/// Maximum number of variables per conversation.
+pub const MAX_VARIABLES: usize = 100;
+
+/// Maximum serialized size of a single value, in bytes.
+pub const MAX_VALUE_BYTES: usize = 4096;
+
+/// Maximum key length.
+const MAX_KEY_LEN: usize = 64;
+
+/// Matches "remember <value> as <name>".
+static REMEMBER_AS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)^\s*(?:please\s+)?remember\s+["'`]?(.+?)["'`]?\s+as\s+["'`]?([A-Za-z](?:[\w.-]*\w)?)["'`]?\s*[.!]?\s*$"#)
+        .expect("valid regex")
+});
+
+/// Error from a variable operation.
+#[derive(Debug, thiserror::Error)]
+pub enum VariableError {
+    #[error(
+        "Invalid variable name '{0}': use letters, digits, '_', '-' or '.', starting with a letter (max 64)"
+    )]
+    InvalidKey(String),
+
+    #[error("Value for '{0}' is too large (max {MAX_VALUE_BYTES} bytes)")]
+    TooLarge(String),
+
+    #[error("Too many variables (max {MAX_VARIABLES})")]
+    TooMany,
+}
+
+/// Type of a stored value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VariableType {
+    String,
+    Number,
+    Boolean,
+    Json,
+}
+
+impl VariableType {
+    fn of(value: &serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::String(_) => Self::String,
+            serde_json::Value::Number(_) => Self::Number,
+            serde_json::Value::Bool(_) => Self::Boolean,
+            _ => Self::Json,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// A stored variable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Variable {
+    #[serde(rename = "type")]
+    pub var_type: VariableType,
+    pub value: serde_json::Value,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The variables of one conversation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ConversationVariables {
+    vars: BTreeMap<String, Variable>,
+}
+
+impl ConversationVariables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load from conversation metadata; a missing or malformed field is empty.
+    pub fn from_metadata(metadata: &serde_json::Value) -> Self {
+        metadata
+            .get(METADATA_FIELD)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Load a conversation's variables (empty if the conversation is unknown).
+    pub async fn load(db: &dyn Database, conversation_id: Uuid) -> Result<Self, DatabaseError> {
+        Ok(db
+            .get_conversation_metadata(conversation_id)
+            .await?
+            .map(|m| Self::from_metadata(&m))
+            .unwrap_or_default())
+    }
+
+    /// Persist into the conversation's metadata.
+    pub async fn save(
+        &self,
+        db: &dyn Database,
+        conversation_id: Uuid,
+    ) -> Result<(), DatabaseError> {
+        db.update_conversation_metadata_field(conversation_id, METADATA_FIELD, &self.to_value())
+            .await
+    }
+
+    /// Serialize for storage under [`METADATA_FIELD`].
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_else(|_| serde_json::json!({}))
+    }
+
+    /// Set a variable, replacing any previous value.
+    pub fn set(&mut self, key: &str, value: serde_json::Value) -> Result<(), VariableError> {
+        if !is_valid_key(key) {
+            return Err(VariableError::InvalidKey(key.to_string()));
+        }
+        if value.to_string().len() > MAX_VALUE_BYTES {
+            return Err(VariableError::TooLarge(key.to_string()));
+        }
+        if !self.vars.contains_key(key) && self.vars.len() >= MAX_VARIABLES {
+            return Err(VariableError::TooMany);
+        }
+
+        self.vars.insert(
+            key.to_string(),
+            Variable {
+                var_type: VariableType::of(&value),
+                value,
+                updated_at: Utc::now(),
+            },
+        );
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Variable> {
+        self.vars.get(key)
+    }
+
+    /// Remove a variable, returning whether it existed.
+    pub fn remove(&mut self, key: &str) -> bool {
+        self.vars.remove(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.vars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    /// Variables in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Variable)> {
+        self.vars.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Render the variables for the system prompt (empty if there are none).
+    pub fn prompt_block(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let mut block = String::from(
+            "\n\n## CONVERSATION VARIABLES\nValues stashed in this conversation (manage with the `conversation_vars` tool):\n",
+        );
+        for (key, var) in self.iter() {
+            block.push_str(&format!(
+                "- {} ({}): {}\n",
+                key,
+                var.var_type.as_str(),
+                var.value
+            ));
+        }
+        block
+    }
+}
+
+/// Parse an implicit "remember X as Y" request into `(name, value)`.
+///
+/// Numbers and booleans are captured with their type; everything else is
+/// stored as a string.
+pub fn parse_remember(text: &str) -> Option<(String, serde_json::Value)> {
+    // Only single-line messages, so prose that happens to contain the phrase is ignored
+    if text.trim().contains('\n') {
+        return None;
+    }
+    let caps = REMEMBER_AS.captures(text)?;
+    let raw = caps.get(1)?.as_str().trim();
+    let name = caps.get(2)?.as_str();
+    if raw.is_empty() || !is_valid_key(name) {
+        return None;
+    }
+
+    let value = match raw {
+        "true" => serde_json::Value::Bool(true),
+        "false" => serde_json::Value::Bool(false),
+        _ => raw
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .filter(|_| !raw.starts_with('0') || raw == "0" || raw.starts_with("0."))
+            .map(serde_json::Value::Number)
+            .unwrap_or_else(|| serde_json::Value::String(raw.to_string())),
+    };
+    Some((name.to_string(), value))
+}
+
+fn is_valid_key(key: &str) -> bool {
+    key.len() <= MAX_KEY_LEN
+        && key.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_get_roundtrip_through_metadata() {
+        let mut vars = ConversationVariables::new();
+        vars.set("deck_id", serde_json::json!("1AbC")).unwrap();
+        vars.set("rows", serde_json::json!(42)).unwrap();
+        vars.set("cfg", serde_json::json!({ "a": [1, 2] })).unwrap();
+
+        let metadata = serde_json::json!({ "title": "x", METADATA_FIELD: vars.to_value() });
+        let loaded = ConversationVariables::from_metadata(&metadata);
+        assert_eq!(loaded, vars);
+        assert_eq!(loaded.get("rows").unwrap().var_type, VariableType::Number);
+        assert_eq!(loaded.get("cfg").unwrap().var_type, VariableType::Json);
+
+        // Missing field is empty
+        assert!(ConversationVariables::from_metadata(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_set_validation() {
+        let mut vars = ConversationVariables::new();
+        assert!(matches!(
+            vars.set("1bad", serde_json::json!(1)),
+            Err(VariableError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            vars.set("big", serde_json::json!("x".repeat(MAX_VALUE_BYTES))),
+            Err(VariableError::TooLarge(_))
+        ));
+
+        for i in 0..MAX_VARIABLES {
+            vars.set(&format!("v{}", i), serde_json::json!(i)).unwrap();
+        }
+        assert!(matches!(
+            vars.set("extra", serde_json::json!(1)),
+            Err(VariableError::TooMany)
+        ));
+        // Overwriting an existing key is still allowed at the limit
+        vars.set("v0", serde_json::json!("new")).unwrap();
+    }
+
+    #[test]
+    fn test_parse_remember() {
+        assert_eq!(
+            parse_remember("remember 1AbC-xyz as budget_sheet"),
+            Some(("budget_sheet".into(), serde_json::json!("1AbC-xyz")))
+        );
+        assert_eq!(
+            parse_remember("Please remember \"Q3 planning deck\" as deck."),
+            Some(("deck".into(), serde_json::json!("Q3 planning deck")))
+        );
+        assert_eq!(
+            parse_remember("remember 250 as budget"),
+            Some(("budget".into(), serde_json::json!(250.0)))
+        );
+        // Leading zeros are identifiers, not numbers
+        assert_eq!(
+            parse_remember("remember 00123 as order"),
+            Some(("order".into(), serde_json::json!("00123")))
+        );
+        assert_eq!(parse_remember("do you remember me?"), None);
+        assert_eq!(parse_remember("remember this as\nwell as that"), None);
+    }
+}
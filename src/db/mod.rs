@@ -37,6 +37,16 @@ pub trait Database: Send + Sync {
         limit: usize,
     ) -> Result<Vec<ConversationSummary>, DatabaseError>;
 
+    /// List conversations most-recently-active first, starting strictly before
+    /// `before` (a `last_activity` cursor). Returns the page and whether more remain.
+    async fn list_conversations_paginated(
+        &self,
+        user_id: &str,
+        channel: &str,
+        limit: usize,
+        before: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<(Vec<ConversationSummary>, bool), DatabaseError>;
+
     async fn list_conversation_messages_paginated(
         &self,
         conversation_id: Uuid,
@@ -50,6 +60,11 @@ pub trait Database: Send + Sync {
         user_id: &str,
     ) -> Result<bool, DatabaseError>;
 
+    async fn get_conversation_metadata(
+        &self,
+        conversation_id: Uuid,
+    ) -> Result<Option<serde_json::Value>, DatabaseError>;
+
     async fn update_conversation_metadata_field(
         &self,
         conversation_id: Uuid,
@@ -117,19 +132,38 @@ pub trait Database: Send + Sync {
 
     async fn get_routine(&self, id: Uuid) -> Result<Option<Routine>, DatabaseError>;
 
-    async fn update_routine(&self, routine: &Routine) -> Result<(), DatabaseError>;
+    /// Enabled one-shot routines of every user whose fire time is at or
+    /// before `now`, with their owners, earliest first.
+    async fn list_due_one_shot_routines(&self, now: chrono::DateTime<chrono::Utc>) -> Result<Vec<(String, Routine)>, DatabaseError>;
+
+    async fn create_routine(&self, user_id: &str, routine: &Routine) -> Result<(), DatabaseError>;
+
+    async fn routine_belongs_to_user(&self, id: Uuid, user_id: &str) -> Result<bool, DatabaseError>;
+
+    /// Save changes to a routine read at `routine.version`, returning its new
+    /// version. Fails with [`DatabaseError::Conflict`] if the routine was
+    /// changed since it was read.
+    async fn update_routine(&self, routine: &Routine) -> Result<i64, DatabaseError>;
 
     async fn delete_routine(&self, id: Uuid) -> Result<bool, DatabaseError>;
 
     async fn list_routine_runs(&self, routine_id: Uuid, limit: usize) -> Result<Vec<RoutineRun>, DatabaseError>;
 
+    async fn create_routine_run(&self, run: &RoutineRun) -> Result<(), DatabaseError>;
+
+    /// Record a run's outcome (status, completion time, summary and usage).
+    async fn complete_routine_run(&self, run: &RoutineRun) -> Result<(), DatabaseError>;
+
     // --- Settings ---
 
     async fn list_settings(&self, user_id: &str) -> Result<Vec<SettingRecord>, DatabaseError>;
 
     async fn get_setting_full(&self, user_id: &str, key: &str) -> Result<Option<SettingRecord>, DatabaseError>;
 
-    async fn set_setting(&self, user_id: &str, key: &str, value: &serde_json::Value) -> Result<(), DatabaseError>;
+    /// Create or replace a setting, returning its new version. With
+    /// `expected_version` (0: not set yet) this is a compare-and-swap that
+    /// fails with [`DatabaseError::Conflict`] if the setting has moved on.
+    async fn set_setting(&self, user_id: &str, key: &str, value: &serde_json::Value, expected_version: Option<i64>) -> Result<i64, DatabaseError>;
 
     async fn delete_setting(&self, user_id: &str, key: &str) -> Result<(), DatabaseError>;
 
@@ -81,6 +81,13 @@ pub enum DatabaseError {
     #[error("Constraint violation: {0}")]
     Constraint(String),
 
+    #[error("Version conflict: {entity} {id} was changed elsewhere (now version {current})")]
+    Conflict {
+        entity: String,
+        id: String,
+        current: i64,
+    },
+
     #[error("Migration failed: {0}")]
     Migration(String),
 
@@ -312,6 +319,28 @@ pub enum WorkspaceError {
 
     #[error("Heartbeat error: {reason}")]
     HeartbeatError { reason: String },
+
+    #[error("Memory history error: {reason}")]
+    HistoryFailed { reason: String },
+
+    #[error("Invalid memory space name '{name}': use letters, digits, '-' or '_'")]
+    InvalidSpaceName { name: String },
+
+    #[error("Memory space not found: {name}")]
+    SpaceNotFound { name: String },
+
+    #[error("Memory space already exists: {name}")]
+    SpaceExists { name: String },
+
+    #[error("User {user_id} needs {needed} access to memory space {name}")]
+    SpaceAccessDenied {
+        name: String,
+        user_id: String,
+        needed: String,
+    },
+
+    #[error("Memory space {name} must keep at least one admin")]
+    LastSpaceAdmin { name: String },
 }
 
 /// Orchestrator errors (internal API, container management).
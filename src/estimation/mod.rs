@@ -42,6 +42,30 @@ pub struct ToolEstimate {
     pub confidence: f64,
 }
 
+/// Projected outcome of a candidate plan, computed before any tool runs.
+#[derive(Debug, Clone)]
+pub struct PlanEstimate {
+    /// Projected cost of every invocation in the plan.
+    pub cost: Decimal,
+    /// Projected wall-clock time if invocations run sequentially.
+    pub duration: Duration,
+    /// Confidence in the projection (0-1).
+    pub confidence: f64,
+    /// Number of tool invocations in the plan.
+    pub invocations: usize,
+    /// Breakdown by invocation, in plan order.
+    pub tool_breakdown: Vec<ToolEstimate>,
+}
+
+impl PlanEstimate {
+    /// Whether this plan is projected to be cheaper than `other`.
+    ///
+    /// Cost decides first; duration breaks ties.
+    pub fn is_cheaper_than(&self, other: &PlanEstimate) -> bool {
+        (self.cost, self.duration) < (other.cost, other.duration)
+    }
+}
+
 /// Combined estimator.
 pub struct Estimator {
     cost: CostEstimator,
@@ -68,15 +92,7 @@ impl Estimator {
         category: Option<&str>,
         tools: &[String],
     ) -> JobEstimate {
-        let tool_estimates: Vec<ToolEstimate> = tools
-            .iter()
-            .map(|t| ToolEstimate {
-                tool_name: t.clone(),
-                cost: self.cost.estimate_tool(t),
-                duration: self.time.estimate_tool(t),
-                confidence: 0.7, // Default confidence
-            })
-            .collect();
+        let tool_estimates = self.tool_estimates(tools);
 
         let total_cost: Decimal = tool_estimates.iter().map(|e| e.cost).sum();
         let total_duration: Duration = tool_estimates.iter().map(|e| e.duration).sum();
@@ -98,6 +114,53 @@ impl Estimator {
         }
     }
 
+    /// Project cost, time, and confidence for a candidate list of tool invocations.
+    ///
+    /// Each entry is one call, so a plan that calls a tool ten times is
+    /// estimated as ten calls. This lets planners compare strategies (one
+    /// batch call against many single calls) before executing either.
+    pub fn estimate_plan(&self, category: Option<&str>, invocations: &[String]) -> PlanEstimate {
+        let category = category.unwrap_or("general");
+        let tool_estimates = self.tool_estimates(invocations);
+
+        let total_cost: Decimal = tool_estimates.iter().map(|e| e.cost).sum();
+        let total_duration: Duration = tool_estimates.iter().map(|e| e.duration).sum();
+        let (cost, duration) = self.learner.adjust(category, total_cost, total_duration);
+
+        // Tools without a known profile fall back to defaults, so they pull
+        // the plan's confidence down.
+        let tool_confidence = if tool_estimates.is_empty() {
+            1.0
+        } else {
+            tool_estimates.iter().map(|e| e.confidence).sum::<f64>() / tool_estimates.len() as f64
+        };
+        let confidence = (self.learner.confidence(category) + tool_confidence) / 2.0;
+
+        PlanEstimate {
+            cost,
+            duration,
+            confidence,
+            invocations: tool_estimates.len(),
+            tool_breakdown: tool_estimates,
+        }
+    }
+
+    fn tool_estimates(&self, tools: &[String]) -> Vec<ToolEstimate> {
+        tools
+            .iter()
+            .map(|t| {
+                let known = self.cost.all_tool_costs().contains_key(t)
+                    && self.time.all_tool_durations().contains_key(t);
+                ToolEstimate {
+                    tool_name: t.clone(),
+                    cost: self.cost.estimate_tool(t),
+                    duration: self.time.estimate_tool(t),
+                    confidence: if known { 0.7 } else { 0.4 },
+                }
+            })
+            .collect()
+    }
+
     /// Record actual results for learning.
     pub fn record_actuals(
         &mut self,
@@ -137,3 +200,42 @@ impl Default for Estimator {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_plan_counts_each_invocation() {
+        let estimator = Estimator::new();
+
+        let single = estimator.estimate_plan(None, &["http".to_string()]);
+        let many = estimator.estimate_plan(None, &vec!["http".to_string(); 10]);
+
+        assert_eq!(many.invocations, 10);
+        assert_eq!(many.cost, single.cost * Decimal::from(10));
+        assert_eq!(many.duration, single.duration * 10);
+        assert!(single.is_cheaper_than(&many));
+        assert!(!many.is_cheaper_than(&single));
+    }
+
+    #[test]
+    fn test_estimate_plan_unknown_tools_lower_confidence() {
+        let estimator = Estimator::new();
+
+        let known = estimator.estimate_plan(None, &["echo".to_string()]);
+        let unknown = estimator.estimate_plan(None, &["mystery_tool".to_string()]);
+
+        assert!(unknown.confidence < known.confidence);
+    }
+
+    #[test]
+    fn test_estimate_empty_plan() {
+        let estimator = Estimator::new();
+
+        let estimate = estimator.estimate_plan(Some("research"), &[]);
+        assert_eq!(estimate.invocations, 0);
+        assert!(estimate.cost.is_zero());
+        assert!(estimate.duration.is_zero());
+    }
+}
@@ -0,0 +1,348 @@
+//! Validation of tool-call arguments against the tool's JSON schema.
+//!
+//! Models regularly produce tool calls whose arguments don't match the
+//! schema they were given: missing required fields, numbers as strings,
+//! values outside an enum. Such calls are rejected before the tool runs and
+//! recorded per tool and model, along with a snippet of the payload (secrets
+//! masked), so the worst offending schemas and prompts can be found and
+//! improved.
+//!
+//! The checker covers the subset of JSON Schema tool definitions use:
+//! `type`, `required`, `properties`, `additionalProperties: false`, `items`,
+//! `enum`, `anyOf` and `oneOf`. Anything else is accepted.
+
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::safety::LeakDetector;
+
+/// Longest payload snippet kept for a failure.
+const MAX_PAYLOAD_CHARS: usize = 1000;
+
+static LEAK_DETECTOR: LazyLock<LeakDetector> = LazyLock::new(LeakDetector::new);
+
+/// A tool call whose arguments failed schema validation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArgumentFailure {
+    pub tool_name: String,
+    /// Model that produced the call.
+    pub model: String,
+    /// What didn't match, one entry per problem.
+    pub errors: Vec<String>,
+    /// The arguments as sent, with secrets masked, truncated.
+    pub payload: String,
+}
+
+impl ArgumentFailure {
+    /// Check `arguments` against `schema`, returning the failure if they
+    /// don't match.
+    pub fn check(tool_name: &str, model: &str, schema: &Value, arguments: &Value) -> Option<Self> {
+        let errors = check_arguments(schema, arguments);
+        if errors.is_empty() {
+            return None;
+        }
+
+        // Masked before truncating, so a secret cut in half is still caught
+        let mut payload = LEAK_DETECTOR.mask_all(&arguments.to_string());
+        if payload.chars().count() > MAX_PAYLOAD_CHARS {
+            payload = payload.chars().take(MAX_PAYLOAD_CHARS).collect();
+            payload.push('…');
+        }
+
+        Some(Self {
+            tool_name: tool_name.to_string(),
+            model: model.to_string(),
+            errors,
+            payload,
+        })
+    }
+
+    /// Explanation for the model, so it can correct the call.
+    pub fn reason(&self) -> String {
+        format!(
+            "Arguments don't match the tool's schema: {}",
+            self.errors.join("; ")
+        )
+    }
+}
+
+/// Argument validation failures of one tool with one model.
+#[derive(Debug, Clone)]
+pub struct ArgumentFailureStats {
+    pub tool_name: String,
+    pub model: String,
+    pub failures: u64,
+    /// Errors of the most recent failure.
+    pub last_error: String,
+    /// Payload snippet of the most recent failure.
+    pub last_payload: String,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Report of the tools and models producing the most malformed calls.
+pub fn format_offenders(stats: &[ArgumentFailureStats]) -> String {
+    if stats.is_empty() {
+        return "No malformed tool calls recorded.".to_string();
+    }
+
+    let mut out = String::from("Malformed tool calls (worst first):\n");
+    for s in stats {
+        out.push_str(&format!(
+            "\n{} with {}: {} failure{} (last {})\n  {}\n  payload: {}\n",
+            s.tool_name,
+            s.model,
+            s.failures,
+            if s.failures == 1 { "" } else { "s" },
+            s.last_seen.format("%Y-%m-%d %H:%M UTC"),
+            s.last_error,
+            s.last_payload
+        ));
+    }
+    out
+}
+
+/// Check `value` against `schema`, returning a description of each mismatch.
+pub fn check_arguments(schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    check_value(schema, value, "", &mut errors);
+    errors
+}
+
+fn check_value(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    for key in ["anyOf", "oneOf"] {
+        if let Some(Value::Array(options)) = schema.get(key)
+            && !options.is_empty()
+            && !options.iter().any(|o| check_arguments(o, value).is_empty())
+        {
+            errors.push(format!(
+                "{} doesn't match any of the allowed forms",
+                display_path(path)
+            ));
+            return;
+        }
+    }
+
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(types) => types.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| has_type(value, t)) {
+            errors.push(format!(
+                "{}: expected {}, got {}",
+                display_path(path),
+                allowed.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(Value::Array(options)) = schema.get("enum")
+        && !options.contains(value)
+    {
+        let options: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+        errors.push(format!(
+            "{}: must be one of {}",
+            display_path(path),
+            options.join(", ")
+        ));
+        return;
+    }
+
+    match value {
+        Value::Object(fields) => {
+            let properties = schema.get("properties").and_then(|p| p.as_object());
+            let required: Vec<&str> = schema
+                .get("required")
+                .and_then(|r| r.as_array())
+                .map(|r| r.iter().filter_map(|f| f.as_str()).collect())
+                .unwrap_or_default();
+
+            for field in &required {
+                if !fields.contains_key(*field) {
+                    errors.push(format!("missing required field '{}'", join(path, field)));
+                }
+            }
+
+            for (name, field_value) in fields {
+                match properties.and_then(|p| p.get(name)) {
+                    // Models often send null for optional fields they leave unset
+                    Some(_) if field_value.is_null() && !required.contains(&name.as_str()) => {}
+                    Some(field_schema) => {
+                        check_value(field_schema, field_value, &join(path, name), errors)
+                    }
+                    None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                        errors.push(format!("unexpected field '{}'", join(path, name)));
+                    }
+                    None => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    check_value(item_schema, item, &format!("{}[{}]", path, i), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        // Types we don't know about aren't enforced
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// How a path is shown in errors.
+fn display_path(path: &str) -> &str {
+    if path.is_empty() { "arguments" } else { path }
+}
+
+/// Path of `field` within `parent`.
+fn join(parent: &str, field: &str) -> String {
+    if parent.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", parent, field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {"type": "string"},
+                "limit": {"type": "integer"},
+                "mode": {"type": "string", "enum": ["read", "write"]},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "options": {
+                    "type": "object",
+                    "properties": {"recursive": {"type": "boolean"}},
+                    "additionalProperties": false
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    #[test]
+    fn test_valid_arguments() {
+        let args = json!({
+            "path": "src",
+            "limit": 10.0,
+            "mode": "read",
+            "tags": ["a", "b"],
+            "options": {"recursive": true},
+            "extra": 1
+        });
+        assert!(check_arguments(&schema(), &args).is_empty());
+
+        // Optional fields may be null
+        assert!(check_arguments(&schema(), &json!({"path": "src", "limit": null})).is_empty());
+
+        // No schema, no constraints
+        assert!(check_arguments(&json!({}), &json!("anything")).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let args = json!({
+            "limit": "10",
+            "mode": "delete",
+            "tags": ["a", 2],
+            "options": {"recursive": "yes", "depth": 3}
+        });
+        // Field order follows the JSON map, which may or may not be sorted
+        let mut errors = check_arguments(&schema(), &args);
+        errors.sort();
+        assert_eq!(
+            errors,
+            vec![
+                "limit: expected integer, got string",
+                "missing required field 'path'",
+                "mode: must be one of \"read\", \"write\"",
+                "options.recursive: expected boolean, got string",
+                "tags[1]: expected string, got number",
+                "unexpected field 'options.depth'",
+            ]
+        );
+
+        assert_eq!(
+            check_arguments(&schema(), &json!("{\"path\": ")),
+            vec!["arguments: expected object, got string"]
+        );
+    }
+
+    #[test]
+    fn test_any_of() {
+        let schema = json!({"anyOf": [{"type": "string"}, {"type": "array"}]});
+        assert!(check_arguments(&schema, &json!("a")).is_empty());
+        assert_eq!(
+            check_arguments(&schema, &json!(1)),
+            vec!["arguments doesn't match any of the allowed forms"]
+        );
+    }
+
+    #[test]
+    fn test_argument_failure() {
+        let long = "x".repeat(2 * MAX_PAYLOAD_CHARS);
+        let failure =
+            ArgumentFailure::check("read_file", "gpt-4o", &schema(), &json!({"limit": long}))
+                .unwrap();
+        assert_eq!(failure.model, "gpt-4o");
+        assert_eq!(failure.payload.chars().count(), MAX_PAYLOAD_CHARS + 1);
+        assert_eq!(
+            failure.reason(),
+            "Arguments don't match the tool's schema: missing required field 'path'; \
+             limit: expected integer, got string"
+        );
+
+        assert!(
+            ArgumentFailure::check("read_file", "gpt-4o", &schema(), &json!({"path": "a"}))
+                .is_none()
+        );
+
+        let key = "sk-proj-test1234567890abcdefghij12345678";
+        let failure =
+            ArgumentFailure::check("read_file", "gpt-4o", &schema(), &json!({"limit": key}))
+                .unwrap();
+        assert!(!failure.payload.contains(key));
+        assert!(failure.payload.contains("[REDACTED]"));
+    }
+}
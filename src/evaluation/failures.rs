@@ -0,0 +1,154 @@
+//! Trends in tool failures by category.
+//!
+//! Every tool failure is recorded with a [`FailureKind`] (auth, rate limit,
+//! validation, upstream, sandbox, timeout, or other). Comparing one period's
+//! counts with the period before shows which tools are getting worse and
+//! whether the cause is the tool's own code, which self-repair can rebuild,
+//! or something an operator has to fix (credentials, quotas, an outage).
+
+use std::collections::BTreeMap;
+
+use crate::agent::FailureKind;
+
+/// Rows listed in a report before the rest are only counted.
+const MAX_REPORT_ROWS: usize = 15;
+
+/// Failures of one tool in one category, in the current period and the one
+/// before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolFailureTrend {
+    pub tool_name: String,
+    pub kind: FailureKind,
+    pub current: u64,
+    pub previous: u64,
+    /// Message of the most recent failure.
+    pub last_error: String,
+}
+
+/// Plain-text report of failure trends over periods of `days` days, worst
+/// first.
+pub fn format_failure_trends(trends: &[ToolFailureTrend], days: i64) -> String {
+    if trends.iter().all(|t| t.current == 0 && t.previous == 0) {
+        return format!("No tool failures in the last {} days.", days * 2);
+    }
+
+    let mut by_kind: BTreeMap<FailureKind, (u64, u64)> = BTreeMap::new();
+    for t in trends {
+        let entry = by_kind.entry(t.kind).or_default();
+        entry.0 += t.current;
+        entry.1 += t.previous;
+    }
+    let totals: Vec<String> = by_kind
+        .iter()
+        .map(|(kind, (current, previous))| {
+            format!(
+                "{} {} ({})",
+                kind.as_str(),
+                current,
+                change(*current, *previous)
+            )
+        })
+        .collect();
+
+    let mut out = format!(
+        "Tool failures, last {} days vs the {} before:\n\nBy category: {}\n\nBy tool (worst first):\n",
+        days,
+        days,
+        totals.join(", ")
+    );
+    let mut rows: Vec<&ToolFailureTrend> = trends.iter().collect();
+    rows.sort_by(|a, b| {
+        b.current
+            .cmp(&a.current)
+            .then(
+                (b.current as i64 - b.previous as i64).cmp(&(a.current as i64 - a.previous as i64)),
+            )
+            .then(a.tool_name.cmp(&b.tool_name))
+    });
+    for t in rows.iter().take(MAX_REPORT_ROWS) {
+        out.push_str(&format!(
+            "\n{} / {}: {} ({})\n  last: {}\n",
+            t.tool_name,
+            t.kind.as_str(),
+            t.current,
+            change(t.current, t.previous),
+            t.last_error
+        ));
+    }
+    if rows.len() > MAX_REPORT_ROWS {
+        out.push_str(&format!(
+            "\n... and {} more\n",
+            rows.len() - MAX_REPORT_ROWS
+        ));
+    }
+
+    let external: Vec<&str> = FailureKind::ALL
+        .iter()
+        .filter(|kind| !kind.needs_code_fix())
+        .filter(|kind| by_kind.get(kind).is_some_and(|(current, _)| *current > 0))
+        .map(|kind| kind.as_str())
+        .collect();
+    if !external.is_empty() {
+        out.push_str(&format!(
+            "\nNot fixable by self-repair ({}): check credentials, quotas and upstream status.\n",
+            external.join(", ")
+        ));
+    }
+    out
+}
+
+/// "was 3, +5" style comparison with the previous period.
+fn change(current: u64, previous: u64) -> String {
+    let delta = current as i64 - previous as i64;
+    match delta {
+        0 => format!("was {}, unchanged", previous),
+        d if d > 0 => format!("was {}, +{}", previous, d),
+        d => format!("was {}, {}", previous, d),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trend(tool: &str, kind: FailureKind, current: u64, previous: u64) -> ToolFailureTrend {
+        ToolFailureTrend {
+            tool_name: tool.to_string(),
+            kind,
+            current,
+            previous,
+            last_error: "boom".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_format_failure_trends() {
+        let report = format_failure_trends(
+            &[
+                trend("github", FailureKind::Error, 2, 2),
+                trend("google-drive", FailureKind::RateLimit, 9, 1),
+                trend("google-drive", FailureKind::Auth, 0, 4),
+            ],
+            7,
+        );
+        assert!(report.starts_with("Tool failures, last 7 days vs the 7 before:"));
+        assert!(report.contains(
+            "By category: error 2 (was 2, unchanged), auth 0 (was 4, -4), rate_limit 9 (was 1, +8)"
+        ));
+
+        let drive = report
+            .find("google-drive / rate_limit: 9 (was 1, +8)")
+            .unwrap();
+        let github = report.find("github / error: 2").unwrap();
+        assert!(drive < github);
+        assert!(report.contains("Not fixable by self-repair (rate_limit)"));
+    }
+
+    #[test]
+    fn test_format_failure_trends_empty() {
+        assert_eq!(
+            format_failure_trends(&[], 7),
+            "No tool failures in the last 14 days."
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! User feedback on agent responses.
+//!
+//! Channels report satisfaction signals, such as a thumbs up on one of the
+//! agent's messages, as an incoming message whose metadata has a `feedback`
+//! object:
+//!
+//! ```json
+//! {"feedback": {"rating": "positive", "message_id": "1234", "emoji": "👍"}}
+//! ```
+//!
+//! Such messages are recorded instead of being handled as a turn.
+
+use serde::{Deserialize, Serialize};
+
+use crate::channels::IncomingMessage;
+
+/// Metadata key carrying a feedback event.
+pub const FEEDBACK_METADATA_KEY: &str = "feedback";
+
+/// Whether the user liked a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackRating {
+    Positive,
+    Negative,
+}
+
+impl FeedbackRating {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Positive => "positive",
+            Self::Negative => "negative",
+        }
+    }
+}
+
+/// A user's rating of one of the agent's messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserFeedback {
+    pub channel: String,
+    pub user_id: String,
+    pub thread_id: Option<String>,
+    pub rating: FeedbackRating,
+    /// Channel-specific ID of the rated message.
+    pub message_id: Option<String>,
+    /// The reaction that expressed the rating, if any.
+    pub emoji: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FeedbackMetadata {
+    rating: FeedbackRating,
+    #[serde(default)]
+    message_id: Option<serde_json::Value>,
+    #[serde(default)]
+    emoji: Option<String>,
+}
+
+impl UserFeedback {
+    /// Parse the feedback event carried by `message`, if it is one.
+    pub fn from_message(message: &IncomingMessage) -> Option<Self> {
+        let feedback = message.metadata.get(FEEDBACK_METADATA_KEY)?;
+        let feedback: FeedbackMetadata = serde_json::from_value(feedback.clone()).ok()?;
+
+        // Channels may send the message ID as a number or a string
+        let message_id = feedback.message_id.map(|id| match id {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        });
+
+        Some(Self {
+            channel: message.channel.clone(),
+            user_id: message.user_id.clone(),
+            thread_id: message.thread_id.clone(),
+            rating: feedback.rating,
+            message_id,
+            emoji: feedback.emoji,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_message() {
+        let message = IncomingMessage::new("telegram", "42", "[feedback: positive]").with_metadata(
+            serde_json::json!({
+                "chat_id": 7,
+                "feedback": {"rating": "positive", "message_id": 1234, "emoji": "👍"}
+            }),
+        );
+        let feedback = UserFeedback::from_message(&message).unwrap();
+        assert_eq!(feedback.rating, FeedbackRating::Positive);
+        assert_eq!(feedback.message_id.as_deref(), Some("1234"));
+        assert_eq!(feedback.emoji.as_deref(), Some("👍"));
+        assert_eq!(feedback.channel, "telegram");
+    }
+
+    #[test]
+    fn test_ordinary_message_is_not_feedback() {
+        let message = IncomingMessage::new("telegram", "42", "thanks!");
+        assert!(UserFeedback::from_message(&message).is_none());
+
+        let malformed = IncomingMessage::new("telegram", "42", "x")
+            .with_metadata(serde_json::json!({"feedback": {"rating": "meh"}}));
+        assert!(UserFeedback::from_message(&malformed).is_none());
+    }
+}
@@ -5,6 +5,8 @@ use std::time::Duration;
 
 use rust_decimal::Decimal;
 
+use crate::evaluation::{ArgumentFailure, FeedbackRating};
+
 /// Quality metrics for evaluation.
 #[derive(Debug, Clone, Default)]
 pub struct QualityMetrics {
@@ -22,6 +24,12 @@ pub struct QualityMetrics {
     pub tool_metrics: HashMap<String, ToolMetrics>,
     /// Error types encountered.
     pub error_types: HashMap<String, u64>,
+    /// Responses users rated positively.
+    pub positive_feedback: u64,
+    /// Responses users rated negatively.
+    pub negative_feedback: u64,
+    /// Tool calls rejected for malformed arguments, by tool and model.
+    pub argument_failures: HashMap<(String, String), u64>,
 }
 
 /// Metrics for a single tool.
@@ -105,6 +113,47 @@ impl MetricsCollector {
         *self.metrics.error_types.entry(error_type).or_default() += 1;
     }
 
+    /// Record a tool call rejected because its arguments didn't match the
+    /// tool's schema.
+    pub fn record_argument_failure(&mut self, failure: &ArgumentFailure) {
+        *self
+            .metrics
+            .argument_failures
+            .entry((failure.tool_name.clone(), failure.model.clone()))
+            .or_default() += 1;
+    }
+
+    /// Tool and model pairs with the most malformed calls, worst first.
+    pub fn worst_argument_offenders(&self, limit: usize) -> Vec<(String, String, u64)> {
+        let mut offenders: Vec<_> = self
+            .metrics
+            .argument_failures
+            .iter()
+            .map(|((tool, model), count)| (tool.clone(), model.clone(), *count))
+            .collect();
+        offenders.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+        offenders.truncate(limit);
+        offenders
+    }
+
+    /// Record a user's rating of a response.
+    pub fn record_feedback(&mut self, rating: FeedbackRating) {
+        match rating {
+            FeedbackRating::Positive => self.metrics.positive_feedback += 1,
+            FeedbackRating::Negative => self.metrics.negative_feedback += 1,
+        }
+    }
+
+    /// Share of rated responses that were rated positively, if any were rated.
+    pub fn satisfaction_rate(&self) -> Option<f64> {
+        let rated = self.metrics.positive_feedback + self.metrics.negative_feedback;
+        if rated == 0 {
+            None
+        } else {
+            Some(self.metrics.positive_feedback as f64 / rated as f64)
+        }
+    }
+
     /// Get current metrics.
     pub fn metrics(&self) -> &QualityMetrics {
         &self.metrics
@@ -134,6 +183,7 @@ impl MetricsCollector {
         MetricsSummary {
             total_actions: self.metrics.total_actions,
             success_rate: self.success_rate(),
+            satisfaction_rate: self.satisfaction_rate(),
             total_time: self.metrics.total_time,
             total_cost: self.metrics.total_cost,
             most_used_tool: self
@@ -155,6 +205,7 @@ impl MetricsCollector {
                 .take(3)
                 .map(|(e, c)| (e.clone(), *c))
                 .collect(),
+            worst_argument_offenders: self.worst_argument_offenders(3),
         }
     }
 }
@@ -170,11 +221,14 @@ impl Default for MetricsCollector {
 pub struct MetricsSummary {
     pub total_actions: u64,
     pub success_rate: f64,
+    pub satisfaction_rate: Option<f64>,
     pub total_time: Duration,
     pub total_cost: Decimal,
     pub most_used_tool: Option<String>,
     pub most_failed_tool: Option<String>,
     pub top_errors: Vec<(String, u64)>,
+    /// Tool, model and count of the most malformed tool calls.
+    pub worst_argument_offenders: Vec<(String, String, u64)>,
 }
 
 /// Categorize an error message into a type.
@@ -238,4 +292,45 @@ mod tests {
         let rate = collector.success_rate();
         assert!((rate - 0.666).abs() < 0.01);
     }
+
+    #[test]
+    fn test_argument_offenders() {
+        let mut collector = MetricsCollector::new();
+        let schema = serde_json::json!({"type": "object", "required": ["path"]});
+        let args = serde_json::json!({});
+
+        for (tool, model) in [
+            ("read_file", "gpt-4o"),
+            ("read_file", "gpt-4o"),
+            ("read_file", "llama"),
+            ("shell", "gpt-4o"),
+            ("shell", "gpt-4o"),
+            ("shell", "gpt-4o"),
+        ] {
+            let failure = ArgumentFailure::check(tool, model, &schema, &args).unwrap();
+            collector.record_argument_failure(&failure);
+        }
+
+        assert_eq!(
+            collector.worst_argument_offenders(2),
+            vec![
+                ("shell".to_string(), "gpt-4o".to_string(), 3),
+                ("read_file".to_string(), "gpt-4o".to_string(), 2),
+            ]
+        );
+        assert_eq!(collector.summary().worst_argument_offenders.len(), 3);
+    }
+
+    #[test]
+    fn test_satisfaction_rate() {
+        let mut collector = MetricsCollector::new();
+        assert_eq!(collector.satisfaction_rate(), None);
+
+        collector.record_feedback(FeedbackRating::Positive);
+        collector.record_feedback(FeedbackRating::Positive);
+        collector.record_feedback(FeedbackRating::Positive);
+        collector.record_feedback(FeedbackRating::Negative);
+        assert_eq!(collector.satisfaction_rate(), Some(0.75));
+        assert_eq!(collector.summary().satisfaction_rate, Some(0.75));
+    }
 }
@@ -5,9 +5,17 @@
 //! - Requirements matching
 //! - Error rates
 //! - User feedback
+//! - Malformed tool-call arguments
+//! - Tool failure trends by category
 
+mod arguments;
+mod failures;
+mod feedback;
 mod metrics;
 mod success;
 
+pub use arguments::{ArgumentFailure, ArgumentFailureStats, check_arguments, format_offenders};
+pub use failures::{ToolFailureTrend, format_failure_trends};
+pub use feedback::{FEEDBACK_METADATA_KEY, FeedbackRating, UserFeedback};
 pub use metrics::{MetricsCollector, QualityMetrics};
 pub use success::{EvaluationResult, SuccessEvaluator};
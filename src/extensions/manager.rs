@@ -11,6 +11,10 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::extensions::discovery::OnlineDiscovery;
+use crate::extensions::marketplace::{
+    self, InstalledVersion, MarketplaceClient, MarketplaceEntry, UpdateAvailable,
+};
+use crate::extensions::oauth::OAuthFlows;
 use crate::extensions::registry::ExtensionRegistry;
 use crate::extensions::{
     ActivateResult, AuthResult, ExtensionError, ExtensionKind, ExtensionSource, InstallResult,
@@ -40,6 +44,10 @@ struct PendingAuth {
 pub struct ExtensionManager {
     registry: ExtensionRegistry,
     discovery: OnlineDiscovery,
+    /// Remote index of published WASM tools and channels.
+    marketplace: Option<MarketplaceClient>,
+    /// Results of the last marketplace update check.
+    updates: RwLock<Vec<UpdateAvailable>>,
 
     // MCP infrastructure
     mcp_session_manager: Arc<McpSessionManager>,
@@ -55,8 +63,8 @@ pub struct ExtensionManager {
     secrets: Arc<dyn SecretsStore + Send + Sync>,
     tool_registry: Arc<ToolRegistry>,
     pending_auth: RwLock<HashMap<String, PendingAuth>>,
-    /// Tunnel URL for remote OAuth callbacks (used in future iterations).
-    _tunnel_url: Option<String>,
+    /// OAuth links for WASM tools, completed at the HTTP channel's callback.
+    oauth: Arc<OAuthFlows>,
     user_id: String,
 }
 
@@ -75,19 +83,36 @@ impl ExtensionManager {
         Self {
             registry: ExtensionRegistry::new(),
             discovery: OnlineDiscovery::new(),
+            marketplace: None,
+            updates: RwLock::new(Vec::new()),
             mcp_session_manager,
             mcp_clients: RwLock::new(HashMap::new()),
             wasm_tool_runtime,
             wasm_tools_dir,
             wasm_channels_dir,
-            secrets,
             tool_registry,
             pending_auth: RwLock::new(HashMap::new()),
-            _tunnel_url: tunnel_url,
+            oauth: Arc::new(OAuthFlows::new(
+                Arc::clone(&secrets),
+                user_id.clone(),
+                tunnel_url.as_deref(),
+            )),
+            secrets,
             user_id,
         }
     }
 
+    /// OAuth flows whose callbacks the HTTP channel completes.
+    pub fn oauth_flows(&self) -> Arc<OAuthFlows> {
+        Arc::clone(&self.oauth)
+    }
+
+    /// Also search and install from a remote marketplace index.
+    pub fn with_marketplace(mut self, marketplace: MarketplaceClient) -> Self {
+        self.marketplace = Some(marketplace);
+        self
+    }
+
     /// Search for extensions. If `discover` is true, also searches online.
     pub async fn search(
         &self,
@@ -96,6 +121,26 @@ impl ExtensionManager {
     ) -> Result<Vec<SearchResult>, ExtensionError> {
         let mut results = self.registry.search(query).await;
 
+        if let Some(ref client) = self.marketplace {
+            match client.search(query).await {
+                Ok(entries) => {
+                    for entry in entries {
+                        if results.iter().any(|r| r.entry.name == entry.name) {
+                            continue;
+                        }
+                        results.push(SearchResult {
+                            entry: entry.to_registry_entry(),
+                            source: ResultSource::Marketplace,
+                            validated: true,
+                            permissions: entry.permissions(),
+                            version: Some(entry.version),
+                        });
+                    }
+                }
+                Err(e) => tracing::warn!("Marketplace search failed: {}", e),
+            }
+        }
+
         if discover && results.is_empty() {
             tracing::info!("No built-in results for '{}', searching online...", query);
             let discovered = self.discovery.discover(query).await;
@@ -110,6 +155,8 @@ impl ExtensionManager {
                         entry,
                         source: ResultSource::Discovered,
                         validated: true,
+                        version: None,
+                        permissions: Vec::new(),
                     });
                 }
             }
@@ -130,6 +177,14 @@ impl ExtensionManager {
             return self.install_from_entry(&entry).await;
         }
 
+        // Then a published marketplace release
+        if url.is_none()
+            && let Some(ref client) = self.marketplace
+            && let Some(entry) = client.get(name).await?
+        {
+            return self.install_from_marketplace(client, &entry).await;
+        }
+
         // If a URL was provided, determine kind and install
         if let Some(url) = url {
             let kind = kind_hint.unwrap_or_else(|| infer_kind_from_url(url));
@@ -152,6 +207,28 @@ impl ExtensionManager {
         )))
     }
 
+    /// The marketplace release `install(name, url, _)` would fetch, if any.
+    ///
+    /// Mirrors the lookup order of [`Self::install`] so the approval prompt
+    /// describes what will actually be installed.
+    pub async fn marketplace_install_entry(
+        &self,
+        name: &str,
+        url: Option<&str>,
+    ) -> Option<MarketplaceEntry> {
+        if url.is_some() || self.registry.get(name).await.is_some() {
+            return None;
+        }
+        let client = self.marketplace.as_ref()?;
+        match client.get(name).await {
+            Ok(entry) => entry,
+            Err(e) => {
+                tracing::warn!("Marketplace lookup for '{}' failed: {}", name, e);
+                None
+            }
+        }
+    }
+
     /// Authenticate an installed extension.
     pub async fn auth(
         &self,
@@ -188,6 +265,17 @@ impl ExtensionManager {
         kind_filter: Option<ExtensionKind>,
     ) -> Result<Vec<InstalledExtension>, ExtensionError> {
         let mut extensions = Vec::new();
+        let installed_versions = marketplace::load_installed(&self.wasm_tools_dir).await;
+        let updates = self.updates.read().await.clone();
+        let version_of = |name: &str| {
+            (
+                installed_versions.get(name).map(|v| v.version.clone()),
+                updates
+                    .iter()
+                    .find(|u| u.name == name)
+                    .map(|u| u.latest.clone()),
+            )
+        };
 
         // List MCP servers
         if kind_filter.is_none() || kind_filter == Some(ExtensionKind::McpServer) {
@@ -219,6 +307,8 @@ impl ExtensionManager {
                             authenticated,
                             active,
                             tools,
+                            version: None,
+                            update_available: None,
                         });
                     }
                 }
@@ -236,6 +326,7 @@ impl ExtensionManager {
                 Ok(tools) => {
                     for (name, _discovered) in tools {
                         let active = self.tool_registry.has(&name).await;
+                        let (version, update_available) = version_of(&name);
 
                         extensions.push(InstalledExtension {
                             name: name.clone(),
@@ -245,6 +336,8 @@ impl ExtensionManager {
                             authenticated: true, // WASM tools don't always need auth
                             active,
                             tools: if active { vec![name] } else { Vec::new() },
+                            version,
+                            update_available,
                         });
                     }
                 }
@@ -261,6 +354,7 @@ impl ExtensionManager {
             match crate::channels::wasm::discover_channels(&self.wasm_channels_dir).await {
                 Ok(channels) => {
                     for (name, _discovered) in channels {
+                        let (version, update_available) = version_of(&name);
                         extensions.push(InstalledExtension {
                             name,
                             kind: ExtensionKind::WasmChannel,
@@ -269,6 +363,8 @@ impl ExtensionManager {
                             authenticated: true,
                             active: true, // If loaded at startup, they're active
                             tools: Vec::new(),
+                            version,
+                            update_available,
                         });
                     }
                 }
@@ -333,6 +429,11 @@ impl ExtensionManager {
                     let _ = tokio::fs::remove_file(&cap_path).await;
                 }
 
+                let mut installed = marketplace::load_installed(&self.wasm_tools_dir).await;
+                if installed.remove(name).is_some() {
+                    marketplace::save_installed(&self.wasm_tools_dir, &installed).await?;
+                }
+
                 Ok(format!("Removed WASM tool '{}'", name))
             }
             ExtensionKind::WasmChannel => Err(ExtensionError::Other(
@@ -342,8 +443,106 @@ impl ExtensionManager {
         }
     }
 
+    /// Check the marketplace for newer versions of installed extensions.
+    ///
+    /// The result is remembered so `list` can flag outdated extensions.
+    pub async fn check_updates(&self) -> Result<Vec<UpdateAvailable>, ExtensionError> {
+        let Some(ref client) = self.marketplace else {
+            return Ok(Vec::new());
+        };
+        let installed = marketplace::load_installed(&self.wasm_tools_dir).await;
+        let updates = client.check_updates(&installed).await?;
+        *self.updates.write().await = updates.clone();
+        Ok(updates)
+    }
+
     // ── Private helpers ──────────────────────────────────────────────────
 
+    /// Download, verify and install (or update) a marketplace release.
+    async fn install_from_marketplace(
+        &self,
+        client: &MarketplaceClient,
+        entry: &MarketplaceEntry,
+    ) -> Result<InstallResult, ExtensionError> {
+        let mut installed = marketplace::load_installed(&self.wasm_tools_dir).await;
+        let previous = installed.get(&entry.name).map(|v| v.version.clone());
+        if previous.as_deref() == Some(entry.version.as_str()) {
+            return Err(ExtensionError::AlreadyInstalled(format!(
+                "{} {}",
+                entry.name, entry.version
+            )));
+        }
+
+        let capabilities = entry.capabilities_file()?;
+        let wasm = client.download(entry).await?;
+
+        let dir = match entry.kind {
+            ExtensionKind::WasmChannel => &self.wasm_channels_dir,
+            _ => &self.wasm_tools_dir,
+        };
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| ExtensionError::InstallFailed(e.to_string()))?;
+        tokio::fs::write(dir.join(format!("{}.wasm", entry.name)), &wasm)
+            .await
+            .map_err(|e| ExtensionError::InstallFailed(e.to_string()))?;
+        let capabilities_json = serde_json::to_vec_pretty(&capabilities)
+            .map_err(|e| ExtensionError::InstallFailed(e.to_string()))?;
+        tokio::fs::write(
+            dir.join(format!("{}.capabilities.json", entry.name)),
+            capabilities_json,
+        )
+        .await
+        .map_err(|e| ExtensionError::InstallFailed(e.to_string()))?;
+
+        installed.insert(
+            entry.name.clone(),
+            InstalledVersion {
+                kind: entry.kind,
+                version: entry.version.clone(),
+                sha256: entry.sha256.to_lowercase(),
+            },
+        );
+        marketplace::save_installed(&self.wasm_tools_dir, &installed).await?;
+        self.updates.write().await.retain(|u| u.name != entry.name);
+
+        // An updated tool is reloaded from the new module on next activate
+        if previous.is_some() && entry.kind == ExtensionKind::WasmTool {
+            self.tool_registry.unregister(&entry.name).await;
+        }
+
+        tracing::info!(
+            "Installed {} '{}' {} from marketplace ({} bytes)",
+            entry.kind,
+            entry.name,
+            entry.version,
+            wasm.len()
+        );
+
+        let next_step = match entry.kind {
+            ExtensionKind::WasmChannel => "Restart to activate it.",
+            _ => "Run activate to load it.",
+        };
+        let message = match previous {
+            Some(previous) => format!(
+                "Updated '{}' from {} to {}. {}",
+                entry.name, previous, entry.version, next_step
+            ),
+            None => format!(
+                "Installed '{}' {}. {}",
+                entry.name, entry.version, next_step
+            ),
+        };
+
+        Ok(InstallResult {
+            name: entry.name.clone(),
+            kind: entry.kind,
+            message,
+            version: Some(entry.version.clone()),
+            permissions: marketplace::describe_permissions(&capabilities),
+        })
+    }
+
     async fn install_from_entry(
         &self,
         entry: &RegistryEntry,
@@ -403,6 +602,8 @@ impl ExtensionManager {
                 "MCP server '{}' installed. Run auth next to authenticate.",
                 name
             ),
+            version: None,
+            permissions: Vec::new(),
         })
     }
 
@@ -457,6 +658,8 @@ impl ExtensionManager {
             name: name.to_string(),
             kind: ExtensionKind::WasmTool,
             message: format!("WASM tool '{}' installed. Run activate to load it.", name),
+            version: None,
+            permissions: Vec::new(),
         })
     }
 
@@ -743,6 +946,33 @@ impl ExtensionManager {
             });
         }
 
+        // Offer a one-click link when the tool supports OAuth and there's a
+        // public URL for the callback
+        if let Some(ref oauth) = auth.oauth {
+            let scopes = crate::cli::merged_oauth_scopes(
+                &self.wasm_tools_dir,
+                &auth.secret_name,
+                &oauth.scopes,
+            )
+            .await;
+            if let Some(url) = self.oauth.start(&auth, &scopes).await {
+                let display = auth.display_name.as_deref().unwrap_or(name);
+                return Ok(AuthResult {
+                    name: name.to_string(),
+                    kind: ExtensionKind::WasmTool,
+                    auth_url: Some(url),
+                    callback_type: Some("remote".to_string()),
+                    instructions: Some(format!(
+                        "Open the link to connect your {} account, then ask again.",
+                        display
+                    )),
+                    setup_url: auth.setup_url,
+                    awaiting_token: false,
+                    status: "awaiting_authorization".to_string(),
+                });
+            }
+        }
+
         // Return instructions for manual token entry
         let display = auth.display_name.unwrap_or_else(|| name.to_string());
         let instructions = auth
@@ -0,0 +1,680 @@
+//! Remote marketplace of published WASM tools and channels.
+//!
+//! The marketplace is a JSON index served over HTTPS:
+//!
+//! ```json
+//! {
+//!   "extensions": [{
+//!     "name": "weather",
+//!     "display_name": "Weather",
+//!     "kind": "wasm_tool",
+//!     "version": "1.2.0",
+//!     "description": "Forecasts and current conditions",
+//!     "keywords": ["forecast"],
+//!     "wasm_url": "https://example.com/weather-1.2.0.wasm",
+//!     "sha256": "<hex digest of the .wasm>",
+//!     "capabilities": { "http": { "allowlist": [{ "host": "api.weather.gov" }] } },
+//!     "signature": "<base64 ed25519 signature>"
+//!   }]
+//! }
+//! ```
+//!
+//! The signature covers the name, kind, version, module digest and
+//! capabilities manifest (see [`signed_message`]). Signatures are only
+//! checked when trusted publisher keys are configured
+//! (`MARKETPLACE_TRUSTED_KEYS`): then unsigned or mis-signed entries are
+//! refused, so a compromised mirror can neither swap the binary nor widen its
+//! permissions. Without keys only the published checksum is checked, which
+//! the index itself supplies.
+//!
+//! The permissions an entry requests are shown in the `tool_install`
+//! approval prompt, before anything is downloaded.
+//!
+//! Installed versions are recorded in `marketplace-lock.json` in the tools
+//! directory so newer releases can be detected by [`spawn_update_check_loop`].
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+
+use crate::extensions::registry::score_entry;
+use crate::extensions::{
+    AuthHint, ExtensionError, ExtensionKind, ExtensionManager, ExtensionSource, RegistryEntry,
+};
+use crate::tools::wasm::CapabilitiesFile;
+
+/// How long a fetched index is reused before refetching.
+const INDEX_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// File in the tools directory recording installed marketplace versions.
+const LOCK_FILE: &str = "marketplace-lock.json";
+
+/// A published extension in the marketplace index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketplaceEntry {
+    pub name: String,
+    pub display_name: String,
+    pub kind: ExtensionKind,
+    pub version: String,
+    pub description: String,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    pub wasm_url: String,
+    /// Hex SHA-256 of the `.wasm` module.
+    pub sha256: String,
+    /// Contents of the extension's `capabilities.json`.
+    #[serde(default)]
+    pub capabilities: serde_json::Value,
+    /// Base64 ed25519 signature over [`signed_message`].
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+impl MarketplaceEntry {
+    /// Parsed capabilities manifest (empty if the entry has none).
+    pub fn capabilities_file(&self) -> Result<CapabilitiesFile, ExtensionError> {
+        if self.capabilities.is_null() {
+            return Ok(CapabilitiesFile::default());
+        }
+        serde_json::from_value(self.capabilities.clone()).map_err(|e| {
+            ExtensionError::InstallFailed(format!(
+                "'{}' has an invalid capabilities manifest: {}",
+                self.name, e
+            ))
+        })
+    }
+
+    /// Human-readable list of what the extension is allowed to do.
+    pub fn permissions(&self) -> Vec<String> {
+        match self.capabilities_file() {
+            Ok(caps) => describe_permissions(&caps),
+            Err(e) => vec![e.to_string()],
+        }
+    }
+
+    /// What installing this entry grants, for the install approval prompt.
+    pub fn install_summary(&self) -> String {
+        let permissions = self.permissions();
+        let mut summary = format!(
+            "Install {} '{}' {} from the marketplace",
+            self.kind, self.name, self.version
+        );
+        if permissions.is_empty() {
+            summary.push_str(" (no permissions requested).");
+        } else {
+            summary.push_str(" with these permissions:");
+            for permission in permissions {
+                summary.push_str("\n- ");
+                summary.push_str(&permission);
+            }
+        }
+        summary
+    }
+
+    /// View of this entry as a registry entry, for search and display.
+    pub fn to_registry_entry(&self) -> RegistryEntry {
+        let needs_auth = self
+            .capabilities_file()
+            .map(|caps| caps.auth.is_some())
+            .unwrap_or(false);
+        RegistryEntry {
+            name: self.name.clone(),
+            display_name: self.display_name.clone(),
+            kind: self.kind,
+            description: self.description.clone(),
+            keywords: self.keywords.clone(),
+            source: ExtensionSource::WasmDownload {
+                wasm_url: self.wasm_url.clone(),
+                capabilities_url: None,
+            },
+            auth_hint: if needs_auth {
+                AuthHint::CapabilitiesAuth
+            } else {
+                AuthHint::None
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MarketplaceIndex {
+    extensions: Vec<MarketplaceEntry>,
+}
+
+/// Version of a marketplace extension installed locally.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledVersion {
+    pub kind: ExtensionKind,
+    pub version: String,
+    pub sha256: String,
+}
+
+/// A newer marketplace release of an installed extension.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateAvailable {
+    pub name: String,
+    pub kind: ExtensionKind,
+    pub installed: String,
+    pub latest: String,
+}
+
+/// Client for a remote marketplace index.
+pub struct MarketplaceClient {
+    index_url: String,
+    trusted_keys: Vec<VerifyingKey>,
+    http: reqwest::Client,
+    cache: RwLock<Option<(Instant, Arc<Vec<MarketplaceEntry>>)>>,
+}
+
+impl MarketplaceClient {
+    /// Create a client for `index_url`, trusting signatures from
+    /// `trusted_keys` (base64-encoded ed25519 public keys).
+    pub fn new(
+        index_url: impl Into<String>,
+        trusted_keys: &[String],
+    ) -> Result<Self, ExtensionError> {
+        let trusted_keys = trusted_keys
+            .iter()
+            .map(|key| parse_public_key(key))
+            .collect::<Result<Vec<_>, _>>()?;
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .map_err(|e| ExtensionError::Other(e.to_string()))?;
+
+        Ok(Self {
+            index_url: index_url.into(),
+            trusted_keys,
+            http,
+            cache: RwLock::new(None),
+        })
+    }
+
+    /// All published entries, refetching the index once it's stale.
+    ///
+    /// If the refetch fails the previous index is reused.
+    pub async fn entries(&self) -> Result<Arc<Vec<MarketplaceEntry>>, ExtensionError> {
+        if let Some((fetched_at, entries)) = self.cache.read().await.as_ref()
+            && fetched_at.elapsed() < INDEX_TTL
+        {
+            return Ok(Arc::clone(entries));
+        }
+
+        match self.fetch_index().await {
+            Ok(entries) => {
+                let entries = Arc::new(entries);
+                *self.cache.write().await = Some((Instant::now(), Arc::clone(&entries)));
+                Ok(entries)
+            }
+            Err(e) => match self.cache.read().await.as_ref() {
+                Some((_, entries)) => {
+                    tracing::warn!("Marketplace index refresh failed, using cached copy: {}", e);
+                    Ok(Arc::clone(entries))
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    async fn fetch_index(&self) -> Result<Vec<MarketplaceEntry>, ExtensionError> {
+        let response = self
+            .http
+            .get(&self.index_url)
+            .send()
+            .await
+            .map_err(|e| ExtensionError::DiscoveryFailed(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(ExtensionError::DiscoveryFailed(format!(
+                "marketplace index returned HTTP {}",
+                response.status()
+            )));
+        }
+        let index: MarketplaceIndex = response
+            .json()
+            .await
+            .map_err(|e| ExtensionError::DiscoveryFailed(format!("invalid index: {}", e)))?;
+
+        // Only WASM extensions are distributed through the marketplace
+        Ok(index
+            .extensions
+            .into_iter()
+            .filter(|e| e.kind != ExtensionKind::McpServer && is_valid_name(&e.name))
+            .collect())
+    }
+
+    /// Entries matching `query`, most relevant first.
+    pub async fn search(&self, query: &str) -> Result<Vec<MarketplaceEntry>, ExtensionError> {
+        let entries = self.entries().await?;
+        Ok(rank(&entries, query))
+    }
+
+    /// Look up an entry by exact name.
+    pub async fn get(&self, name: &str) -> Result<Option<MarketplaceEntry>, ExtensionError> {
+        Ok(self
+            .entries()
+            .await?
+            .iter()
+            .find(|e| e.name == name)
+            .cloned())
+    }
+
+    /// Download an entry's module and verify its digest and signature.
+    pub async fn download(&self, entry: &MarketplaceEntry) -> Result<Vec<u8>, ExtensionError> {
+        let response = self
+            .http
+            .get(&entry.wasm_url)
+            .send()
+            .await
+            .map_err(|e| ExtensionError::DownloadFailed(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(ExtensionError::DownloadFailed(format!(
+                "HTTP {}",
+                response.status()
+            )));
+        }
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| ExtensionError::DownloadFailed(e.to_string()))?;
+
+        self.verify(entry, &bytes)?;
+        Ok(bytes.to_vec())
+    }
+
+    /// Check `wasm` against the entry's digest and, when publisher keys are
+    /// configured, its signature.
+    pub fn verify(&self, entry: &MarketplaceEntry, wasm: &[u8]) -> Result<(), ExtensionError> {
+        if sha256_hex(wasm) != entry.sha256.to_lowercase() {
+            return Err(ExtensionError::InstallFailed(format!(
+                "'{}' {} does not match its published checksum",
+                entry.name, entry.version
+            )));
+        }
+
+        if self.trusted_keys.is_empty() {
+            return Ok(());
+        }
+        let signature = entry
+            .signature
+            .as_deref()
+            .ok_or_else(|| ExtensionError::InstallFailed(format!("'{}' is not signed", entry.name)))
+            .and_then(|s| {
+                let bytes = STANDARD.decode(s.trim()).ok();
+                bytes
+                    .and_then(|b| Signature::from_slice(&b).ok())
+                    .ok_or_else(|| {
+                        ExtensionError::InstallFailed(format!(
+                            "'{}' has a malformed signature",
+                            entry.name
+                        ))
+                    })
+            })?;
+
+        let message = signed_message(entry);
+        if self
+            .trusted_keys
+            .iter()
+            .any(|key| key.verify(&message, &signature).is_ok())
+        {
+            Ok(())
+        } else {
+            Err(ExtensionError::InstallFailed(format!(
+                "'{}' is not signed by a trusted publisher",
+                entry.name
+            )))
+        }
+    }
+
+    /// Installed extensions with a newer published version.
+    pub async fn check_updates(
+        &self,
+        installed: &BTreeMap<String, InstalledVersion>,
+    ) -> Result<Vec<UpdateAvailable>, ExtensionError> {
+        let entries = self.entries().await?;
+        Ok(installed
+            .iter()
+            .filter_map(|(name, current)| {
+                let latest = entries.iter().find(|e| &e.name == name)?;
+                is_newer(&latest.version, &current.version).then(|| UpdateAvailable {
+                    name: name.clone(),
+                    kind: current.kind,
+                    installed: current.version.clone(),
+                    latest: latest.version.clone(),
+                })
+            })
+            .collect())
+    }
+}
+
+/// Bytes a publisher signs for an entry.
+pub fn signed_message(entry: &MarketplaceEntry) -> Vec<u8> {
+    let capabilities = serde_json::to_vec(&entry.capabilities).unwrap_or_default();
+    format!(
+        "ironclaw-marketplace-v1\n{}\n{}\n{}\n{}\n{}",
+        entry.name,
+        entry.kind,
+        entry.version,
+        entry.sha256.to_lowercase(),
+        sha256_hex(&capabilities)
+    )
+    .into_bytes()
+}
+
+/// Summarize a capabilities manifest as one line per permission.
+pub fn describe_permissions(caps: &CapabilitiesFile) -> Vec<String> {
+    let mut permissions = Vec::new();
+
+    if let Some(ref http) = caps.http {
+        for endpoint in &http.allowlist {
+            let methods = if endpoint.methods.is_empty() {
+                "any".to_string()
+            } else {
+                endpoint.methods.join("/")
+            };
+            permissions.push(format!(
+                "HTTP ({}) to {}{}",
+                methods,
+                endpoint.host,
+                endpoint.path_prefix.as_deref().unwrap_or("")
+            ));
+        }
+        let mut credentials: Vec<&String> =
+            http.credentials.values().map(|c| &c.secret_name).collect();
+        credentials.sort();
+        credentials.dedup();
+        for secret in credentials {
+            permissions.push(format!("Send secret '{}' with its requests", secret));
+        }
+    }
+    if let Some(ref secrets) = caps.secrets
+        && !secrets.allowed_names.is_empty()
+    {
+        permissions.push(format!(
+            "Check whether secrets exist: {}",
+            secrets.allowed_names.join(", ")
+        ));
+    }
+    if let Some(ref tool_invoke) = caps.tool_invoke
+        && !tool_invoke.aliases.is_empty()
+    {
+        let mut tools: Vec<&String> = tool_invoke.aliases.values().collect();
+        tools.sort();
+        tools.dedup();
+        permissions.push(format!(
+            "Call other tools: {}",
+            tools
+                .iter()
+                .map(|t| t.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    if let Some(ref workspace) = caps.workspace
+        && !workspace.allowed_prefixes.is_empty()
+    {
+        permissions.push(format!(
+            "Read workspace files under: {}",
+            workspace.allowed_prefixes.join(", ")
+        ));
+    }
+    if let Some(ref auth) = caps.auth {
+        permissions.push(format!(
+            "Needs credential '{}'",
+            auth.display_name.as_deref().unwrap_or(&auth.secret_name)
+        ));
+    }
+
+    permissions
+}
+
+/// Installed marketplace versions recorded in `tools_dir`.
+pub async fn load_installed(tools_dir: &Path) -> BTreeMap<String, InstalledVersion> {
+    match tokio::fs::read(tools_dir.join(LOCK_FILE)).await {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable {}: {}", LOCK_FILE, e);
+            BTreeMap::new()
+        }),
+        Err(_) => BTreeMap::new(),
+    }
+}
+
+/// Persist installed marketplace versions to `tools_dir`.
+pub async fn save_installed(
+    tools_dir: &Path,
+    installed: &BTreeMap<String, InstalledVersion>,
+) -> Result<(), ExtensionError> {
+    let json =
+        serde_json::to_vec_pretty(installed).map_err(|e| ExtensionError::Config(e.to_string()))?;
+    tokio::fs::create_dir_all(tools_dir)
+        .await
+        .map_err(|e| ExtensionError::Config(e.to_string()))?;
+    tokio::fs::write(tools_dir.join(LOCK_FILE), json)
+        .await
+        .map_err(|e| ExtensionError::Config(e.to_string()))
+}
+
+/// Periodically check the marketplace for newer versions of installed
+/// extensions. Results are surfaced by `tool_list`.
+pub fn spawn_update_check_loop(
+    manager: Arc<ExtensionManager>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match manager.check_updates().await {
+                Ok(updates) if !updates.is_empty() => {
+                    for update in &updates {
+                        tracing::info!(
+                            "Update available for {} '{}': {} -> {}",
+                            update.kind,
+                            update.name,
+                            update.installed,
+                            update.latest
+                        );
+                    }
+                }
+                Ok(_) => tracing::debug!("Marketplace extensions are up to date"),
+                Err(e) => tracing::warn!("Marketplace update check failed: {}", e),
+            }
+        }
+    })
+}
+
+/// Whether `name` is safe to use as a file stem in the tools directory.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Compare dotted numeric versions ("1.10.0" > "1.9.2"); any non-numeric
+/// suffix on a component is ignored.
+fn is_newer(latest: &str, installed: &str) -> bool {
+    fn parts(v: &str) -> Vec<u64> {
+        v.trim_start_matches('v')
+            .split('.')
+            .map(|p| {
+                let digits: String = p.chars().take_while(|c| c.is_ascii_digit()).collect();
+                digits.parse().unwrap_or(0)
+            })
+            .collect()
+    }
+    let (latest, installed) = (parts(latest), parts(installed));
+    let len = latest.len().max(installed.len());
+    for i in 0..len {
+        let (a, b) = (
+            latest.get(i).copied().unwrap_or(0),
+            installed.get(i).copied().unwrap_or(0),
+        );
+        if a != b {
+            return a > b;
+        }
+    }
+    false
+}
+
+fn rank(entries: &[MarketplaceEntry], query: &str) -> Vec<MarketplaceEntry> {
+    let tokens: Vec<String> = query
+        .to_lowercase()
+        .split_whitespace()
+        .map(|s| s.to_string())
+        .collect();
+    if tokens.is_empty() {
+        return entries.to_vec();
+    }
+
+    let mut scored: Vec<(&MarketplaceEntry, u32)> = entries
+        .iter()
+        .map(|e| (e, score_entry(&e.to_registry_entry(), &tokens)))
+        .filter(|(_, score)| *score > 0)
+        .collect();
+    scored.sort_by(|a, b| b.1.cmp(&a.1));
+    scored.into_iter().map(|(e, _)| e.clone()).collect()
+}
+
+fn parse_public_key(key: &str) -> Result<VerifyingKey, ExtensionError> {
+    let invalid = || ExtensionError::Config(format!("invalid marketplace key '{}'", key));
+    let bytes: [u8; 32] = STANDARD
+        .decode(key.trim())
+        .map_err(|_| invalid())?
+        .try_into()
+        .map_err(|_| invalid())?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| invalid())
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+
+    const WASM: &[u8] = b"\0asm fake module";
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn entry() -> MarketplaceEntry {
+        let mut entry = MarketplaceEntry {
+            name: "weather".to_string(),
+            display_name: "Weather".to_string(),
+            kind: ExtensionKind::WasmTool,
+            version: "1.2.0".to_string(),
+            description: "Forecasts and current conditions".to_string(),
+            keywords: vec!["forecast".to_string()],
+            wasm_url: "https://example.com/weather.wasm".to_string(),
+            sha256: sha256_hex(WASM),
+            capabilities: serde_json::json!({
+                "http": {
+                    "allowlist": [{ "host": "api.weather.gov", "methods": ["GET"] }]
+                },
+                "workspace": { "allowed_prefixes": ["context/"] }
+            }),
+            signature: None,
+        };
+        let signature = signing_key().sign(&signed_message(&entry));
+        entry.signature = Some(STANDARD.encode(signature.to_bytes()));
+        entry
+    }
+
+    fn client(trusted: bool) -> MarketplaceClient {
+        let keys = if trusted {
+            vec![STANDARD.encode(signing_key().verifying_key().to_bytes())]
+        } else {
+            Vec::new()
+        };
+        MarketplaceClient::new("https://example.com/index.json", &keys).unwrap()
+    }
+
+    #[test]
+    fn test_verify_checks_digest_and_signature() {
+        let client = client(true);
+        assert!(client.verify(&entry(), WASM).is_ok());
+        assert!(client.verify(&entry(), b"tampered").is_err());
+
+        // Widening permissions after signing invalidates the signature
+        let mut widened = entry();
+        widened.capabilities["http"]["allowlist"][0]["host"] = "*".into();
+        assert!(client.verify(&widened, WASM).is_err());
+
+        let mut unsigned = entry();
+        unsigned.signature = None;
+        assert!(client.verify(&unsigned, WASM).is_err());
+        assert!(self::client(false).verify(&unsigned, WASM).is_ok());
+    }
+
+    #[test]
+    fn test_permissions_summary() {
+        assert_eq!(
+            entry().permissions(),
+            vec![
+                "HTTP (GET) to api.weather.gov".to_string(),
+                "Read workspace files under: context/".to_string(),
+            ]
+        );
+        assert!(describe_permissions(&CapabilitiesFile::default()).is_empty());
+    }
+
+    #[test]
+    fn test_install_summary_lists_permissions() {
+        assert_eq!(
+            entry().install_summary(),
+            "Install wasm_tool 'weather' 1.2.0 from the marketplace with these permissions:\n\
+             - HTTP (GET) to api.weather.gov\n\
+             - Read workspace files under: context/"
+        );
+
+        let mut bare = entry();
+        bare.capabilities = serde_json::Value::Null;
+        assert!(
+            bare.install_summary()
+                .ends_with("(no permissions requested).")
+        );
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("1.10.0", "1.9.2"));
+        assert!(is_newer("v2.0", "1.99.99"));
+        assert!(is_newer("1.2.1", "1.2"));
+        assert!(!is_newer("1.2.0", "1.2"));
+        assert!(!is_newer("1.0.0-beta", "1.0.0"));
+    }
+
+    #[test]
+    fn test_rank_and_names() {
+        let mut other = entry();
+        other.name = "stocks".to_string();
+        other.display_name = "Stocks".to_string();
+        other.keywords = Vec::new();
+        other.description = "Market quotes".to_string();
+        let entries = vec![other, entry()];
+
+        let ranked = rank(&entries, "forecast");
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].name, "weather");
+        assert_eq!(rank(&entries, "").len(), 2);
+
+        assert!(is_valid_name("weather_v2"));
+        assert!(!is_valid_name("../evil"));
+        assert!(!is_valid_name(""));
+    }
+}
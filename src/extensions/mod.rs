@@ -12,13 +12,25 @@
 //!    -> tool_auth("notion")        -> OAuth 2.1 flow, returns URL
 //!    -> tool_activate("notion")    -> connects, registers tools
 //! ```
+//!
+//! WASM tools and channels can also come from a remote [`marketplace`] index of
+//! signed, versioned releases; search results list the permissions each one
+//! requests, and installed releases are checked for updates on a schedule.
+//!
+//! When a tool fails for want of a credential, the agent prompts for it in the
+//! channel; OAuth-capable tools get a one-click link completed by the HTTP
+//! channel's callback (see [`oauth`]).
 
 pub mod discovery;
 pub mod manager;
+pub mod marketplace;
+pub mod oauth;
 pub mod registry;
 
 pub use discovery::OnlineDiscovery;
 pub use manager::ExtensionManager;
+pub use marketplace::{MarketplaceClient, MarketplaceEntry, UpdateAvailable};
+pub use oauth::OAuthFlows;
 pub use registry::ExtensionRegistry;
 
 use serde::{Deserialize, Serialize};
@@ -112,6 +124,8 @@ pub enum ResultSource {
     Registry,
     /// From online discovery (validated).
     Discovered,
+    /// From the remote marketplace index.
+    Marketplace,
 }
 
 /// Result of searching for extensions.
@@ -125,6 +139,12 @@ pub struct SearchResult {
     /// Whether the endpoint was validated (for discovered entries).
     #[serde(default)]
     pub validated: bool,
+    /// Published version (for marketplace entries).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// What the extension is allowed to do (for marketplace entries).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<String>,
 }
 
 /// Result of installing an extension.
@@ -133,6 +153,12 @@ pub struct InstallResult {
     pub name: String,
     pub kind: ExtensionKind,
     pub message: String,
+    /// Installed version (for marketplace installs).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Permissions granted by the installed capabilities manifest.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<String>,
 }
 
 /// Result of authenticating an extension.
@@ -184,6 +210,12 @@ pub struct InstalledExtension {
     /// Tool names if active.
     #[serde(default)]
     pub tools: Vec<String>,
+    /// Installed marketplace version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Newer marketplace version, if the last update check found one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_available: Option<String>,
 }
 
 /// Error type for extension operations.
@@ -0,0 +1,286 @@
+//! One-click OAuth for WASM tools, completed through the HTTP channel.
+//!
+//! When a tool can't run because its credential is missing, the agent asks
+//! the user to connect it. If the tool's capabilities file configures OAuth
+//! and the deployment has a public URL, the prompt carries an authorization
+//! link whose redirect is the HTTP channel's [`CALLBACK_PATH`]. The callback
+//! exchanges the code and stores the token, so nothing is pasted into chat.
+//!
+//! ```text
+//!  tool fails: auth required
+//!    -> OAuthFlows::start()        -> link with state, sent to the channel
+//!    -> user approves at provider  -> GET /oauth/callback?code=..&state=..
+//!    -> OAuthFlows::complete()     -> token (and refresh token) stored
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use rand::RngCore;
+use tokio::sync::Mutex;
+
+use crate::secrets::{CreateSecretParams, SecretsStore};
+use crate::tools::mcp::auth::{PkceChallenge, build_authorization_url};
+use crate::tools::wasm::{AuthCapabilitySchema, OAuthConfigSchema};
+
+/// Path of the HTTP channel's OAuth redirect endpoint.
+pub const CALLBACK_PATH: &str = "/oauth/callback";
+
+/// How long an authorization link stays valid.
+const FLOW_TTL: Duration = Duration::from_secs(600);
+
+/// An authorization link waiting for its callback.
+struct PendingFlow {
+    auth: AuthCapabilitySchema,
+    oauth: OAuthConfigSchema,
+    client_id: String,
+    client_secret: Option<String>,
+    pkce: Option<PkceChallenge>,
+    created_at: Instant,
+}
+
+/// OAuth flows started from chat and finished at the callback endpoint.
+pub struct OAuthFlows {
+    secrets: Arc<dyn SecretsStore + Send + Sync>,
+    /// Owner of the stored tokens.
+    user_id: String,
+    /// Full redirect URI; links can't be offered without a public URL.
+    redirect_uri: Option<String>,
+    /// Pending flows keyed by their `state` parameter.
+    pending: Mutex<HashMap<String, PendingFlow>>,
+}
+
+impl OAuthFlows {
+    pub fn new(
+        secrets: Arc<dyn SecretsStore + Send + Sync>,
+        user_id: String,
+        public_url: Option<&str>,
+    ) -> Self {
+        Self {
+            secrets,
+            user_id,
+            redirect_uri: public_url
+                .map(|url| format!("{}{}", url.trim_end_matches('/'), CALLBACK_PATH)),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Start a flow for `auth` and return the link to send the user, or
+    /// `None` when there is no public URL or OAuth client configured.
+    pub async fn start(&self, auth: &AuthCapabilitySchema, scopes: &[String]) -> Option<String> {
+        let redirect_uri = self.redirect_uri.as_ref()?;
+        let oauth = auth.oauth.as_ref()?;
+        let (client_id, client_secret) = client_credentials(oauth)?;
+
+        let pkce = oauth.use_pkce.then(PkceChallenge::generate);
+        let state = random_state();
+        let mut url = build_authorization_url(
+            &oauth.authorization_url,
+            &client_id,
+            redirect_uri,
+            scopes,
+            pkce.as_ref(),
+            &oauth.extra_params,
+        );
+        url.push_str(&format!("&state={}", state));
+
+        let mut pending = self.pending.lock().await;
+        pending.retain(|_, flow| flow.created_at.elapsed() < FLOW_TTL);
+        pending.insert(
+            state,
+            PendingFlow {
+                auth: auth.clone(),
+                oauth: oauth.clone(),
+                client_id,
+                client_secret,
+                pkce,
+                created_at: Instant::now(),
+            },
+        );
+        Some(url)
+    }
+
+    /// Finish the flow `state` belongs to: exchange `code` and store the
+    /// token. Returns the display name of what was connected.
+    pub async fn complete(&self, state: &str, code: &str) -> Result<String, String> {
+        let flow = self
+            .pending
+            .lock()
+            .await
+            .remove(state)
+            .filter(|flow| flow.created_at.elapsed() < FLOW_TTL)
+            .ok_or_else(|| "This link has expired or was already used.".to_string())?;
+        let redirect_uri = self
+            .redirect_uri
+            .as_deref()
+            .ok_or_else(|| "OAuth callbacks are not configured.".to_string())?;
+
+        let token = exchange_code(&flow, code, redirect_uri).await?;
+        let access_token = token
+            .get(&flow.oauth.access_token_field)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| format!("No {} in the token response", flow.oauth.access_token_field))?;
+
+        self.store(&flow.auth, &flow.auth.secret_name, access_token)
+            .await?;
+        if let Some(refresh_token) = token.get("refresh_token").and_then(|v| v.as_str()) {
+            let name = crate::cli::refresh_token_name(&flow.auth);
+            self.store(&flow.auth, &name, refresh_token).await?;
+        }
+
+        tracing::info!("Stored {} from OAuth callback", flow.auth.secret_name);
+        Ok(flow
+            .auth
+            .display_name
+            .clone()
+            .unwrap_or_else(|| flow.auth.secret_name.clone()))
+    }
+
+    async fn store(
+        &self,
+        auth: &AuthCapabilitySchema,
+        name: &str,
+        value: &str,
+    ) -> Result<(), String> {
+        let mut params = CreateSecretParams::new(name, value);
+        if let Some(ref provider) = auth.provider {
+            params = params.with_provider(provider);
+        }
+        self.secrets
+            .create(&self.user_id, params)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Failed to save token: {}", e))
+    }
+}
+
+/// Client ID and secret from the capabilities file or their env vars.
+fn client_credentials(oauth: &OAuthConfigSchema) -> Option<(String, Option<String>)> {
+    let from = |value: &Option<String>, env: &Option<String>| {
+        value
+            .clone()
+            .or_else(|| env.as_ref().and_then(|env| std::env::var(env).ok()))
+    };
+    let client_id = from(&oauth.client_id, &oauth.client_id_env)?;
+    Some((
+        client_id,
+        from(&oauth.client_secret, &oauth.client_secret_env),
+    ))
+}
+
+/// Exchange an authorization code for the token response.
+async fn exchange_code(
+    flow: &PendingFlow,
+    code: &str,
+    redirect_uri: &str,
+) -> Result<serde_json::Value, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut params = vec![
+        ("grant_type", "authorization_code".to_string()),
+        ("code", code.to_string()),
+        ("redirect_uri", redirect_uri.to_string()),
+    ];
+    if let Some(ref pkce) = flow.pkce {
+        params.push(("code_verifier", pkce.verifier.clone()));
+    }
+
+    // Basic auth when there is a client secret, otherwise the ID goes in the body
+    let mut request = client.post(&flow.oauth.token_url);
+    match flow.client_secret {
+        Some(ref secret) => request = request.basic_auth(&flow.client_id, Some(secret)),
+        None => params.push(("client_id", flow.client_id.clone())),
+    }
+
+    let response = request
+        .form(&params)
+        .send()
+        .await
+        .map_err(|e| format!("Token exchange failed: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Token exchange failed: {} - {}", status, body));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid token response: {}", e))
+}
+
+/// Unguessable `state` tying a callback to the flow that started it.
+fn random_state() -> String {
+    let mut bytes = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth() -> AuthCapabilitySchema {
+        AuthCapabilitySchema {
+            secret_name: "google_oauth_token".to_string(),
+            display_name: Some("Google".to_string()),
+            oauth: Some(OAuthConfigSchema {
+                authorization_url: "https://accounts.example.com/auth".to_string(),
+                token_url: "https://accounts.example.com/token".to_string(),
+                client_id: Some("client-1".to_string()),
+                use_pkce: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn flows(public_url: Option<&str>) -> OAuthFlows {
+        let crypto = crate::secrets::SecretsCrypto::new(secrecy::SecretString::from(
+            "0123456789abcdef0123456789abcdef".to_string(),
+        ))
+        .unwrap();
+        let secrets = Arc::new(crate::secrets::InMemorySecretsStore::new(Arc::new(crypto)));
+        OAuthFlows::new(secrets, "default".to_string(), public_url)
+    }
+
+    #[tokio::test]
+    async fn test_start_builds_callback_link() {
+        let flows = flows(Some("https://claw.example.com/"));
+        let url = flows
+            .start(&auth(), &["documents".to_string()])
+            .await
+            .unwrap();
+
+        assert!(url.starts_with("https://accounts.example.com/auth?client_id=client-1"));
+        assert!(url.contains(&format!(
+            "redirect_uri={}",
+            urlencoding::encode("https://claw.example.com/oauth/callback")
+        )));
+        assert!(url.contains("code_challenge_method=S256"));
+        assert!(url.contains("&state="));
+        assert_eq!(flows.pending.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_link_without_public_url_or_client() {
+        assert!(flows(None).start(&auth(), &[]).await.is_none());
+
+        let mut manual = auth();
+        manual.oauth = None;
+        let flows = flows(Some("https://claw.example.com"));
+        assert!(flows.start(&manual, &[]).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_state_is_rejected() {
+        let flows = flows(Some("https://claw.example.com"));
+        let err = flows.complete("nope", "code").await.unwrap_err();
+        assert!(err.contains("expired"));
+    }
+}
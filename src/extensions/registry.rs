@@ -46,6 +46,8 @@ impl ExtensionRegistry {
                     entry: e.clone(),
                     source: ResultSource::Registry,
                     validated: true,
+                    version: None,
+                    permissions: Vec::new(),
                 })
                 .collect();
         }
@@ -61,6 +63,8 @@ impl ExtensionRegistry {
                         entry: entry.clone(),
                         source: ResultSource::Registry,
                         validated: true,
+                        version: None,
+                        permissions: Vec::new(),
                     },
                     score,
                 ));
@@ -77,6 +81,8 @@ impl ExtensionRegistry {
                         entry: entry.clone(),
                         source: ResultSource::Discovered,
                         validated: true,
+                        version: None,
+                        permissions: Vec::new(),
                     },
                     score,
                 ));
@@ -115,7 +121,7 @@ impl Default for ExtensionRegistry {
 }
 
 /// Score an entry against search tokens. Higher = better match.
-fn score_entry(entry: &RegistryEntry, tokens: &[String]) -> u32 {
+pub(crate) fn score_entry(entry: &RegistryEntry, tokens: &[String]) -> u32 {
     let mut score = 0u32;
     let name_lower = entry.name.to_lowercase();
     let display_lower = entry.display_name.to_lowercase();
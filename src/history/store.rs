@@ -68,6 +68,31 @@ pub struct ConversationMessage {
     pub role: String,
     pub content: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// What producing the message cost (assistant messages only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<MessageUsage>,
+}
+
+/// LLM usage attributed to an assistant message.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MessageUsage {
+    /// Model of the last call, which wrote the answer.
+    pub model: String,
+    pub input_tokens: i32,
+    pub output_tokens: i32,
+    pub cost: Decimal,
+}
+
+impl MessageUsage {
+    /// Read the usage columns of a `conversation_messages` row.
+    fn from_row(row: &tokio_postgres::Row) -> Option<Self> {
+        Some(Self {
+            model: row.get::<_, Option<String>>("model")?,
+            input_tokens: row.get::<_, Option<i32>>("input_tokens").unwrap_or(0),
+            output_tokens: row.get::<_, Option<i32>>("output_tokens").unwrap_or(0),
+            cost: row.get::<_, Option<Decimal>>("cost").unwrap_or_default(),
+        })
+    }
 }
 
 /// Record for a user setting.
@@ -76,6 +101,8 @@ pub struct SettingRecord {
     pub key: String,
     pub value: serde_json::Value,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    /// Bumped by every write, for [`Store::set_setting`]'s compare-and-swap.
+    pub version: i64,
 }
 
 /// Summary for a conversation in the UI.
@@ -205,6 +232,42 @@ impl Store {
         Ok(id)
     }
 
+    /// Add an assistant message, attributing to it the LLM calls recorded for
+    /// the conversation since its previous message.
+    pub async fn add_assistant_message(
+        &self,
+        conversation_id: Uuid,
+        content: &str,
+    ) -> Result<Uuid, DatabaseError> {
+        let conn = self.conn().await?;
+        let id = Uuid::new_v4();
+
+        conn.execute(
+            r#"
+            INSERT INTO conversation_messages
+                (id, conversation_id, role, content, model, input_tokens, output_tokens, cost)
+            SELECT $1, $2, 'assistant', $3,
+                (array_agg(c.model ORDER BY c.created_at DESC))[1],
+                SUM(c.input_tokens)::INTEGER,
+                SUM(c.output_tokens)::INTEGER,
+                SUM(c.cost)
+            FROM llm_calls c
+            WHERE c.conversation_id = $2
+              AND c.created_at > COALESCE(
+                  (SELECT MAX(created_at) FROM conversation_messages WHERE conversation_id = $2),
+                  '-infinity'
+              )
+            "#,
+            &[&id, &conversation_id, &content],
+        )
+        .await?;
+
+        // Update conversation activity
+        self.touch_conversation(conversation_id).await?;
+
+        Ok(id)
+    }
+
     /// Delete a message from a conversation.
     pub async fn delete_conversation_message(&self, id: Uuid) -> Result<(), DatabaseError> {
         let conn = self.conn().await?;
@@ -551,7 +614,8 @@ fn parse_job_state(s: &str) -> JobState {
 
 // ==================== Tool Failures ====================
 
-use crate::agent::BrokenTool;
+use crate::agent::{BrokenTool, FailureKind};
+use crate::evaluation::ToolFailureTrend;
 
 impl Store {
     /// Record a tool failure (upsert: increment count if exists).
@@ -559,25 +623,79 @@ impl Store {
         &self,
         tool_name: &str,
         error_message: &str,
+        kind: FailureKind,
     ) -> Result<(), DatabaseError> {
         let conn = self.conn().await?;
+        let timeouts: i32 = if kind == FailureKind::Timeout { 1 } else { 0 };
 
         conn.execute(
             r#"
-            INSERT INTO tool_failures (tool_name, error_message, error_count, last_failure)
-            VALUES ($1, $2, 1, NOW())
+            INSERT INTO tool_failures (
+                tool_name, error_message, error_count, timeout_count, last_failure_kind,
+                last_failure
+            )
+            VALUES ($1, $2, 1, $3, $4, NOW())
             ON CONFLICT (tool_name) DO UPDATE SET
                 error_message = $2,
                 error_count = tool_failures.error_count + 1,
+                timeout_count = COALESCE(tool_failures.timeout_count, 0) + $3,
+                last_failure_kind = $4,
                 last_failure = NOW()
             "#,
-            &[&tool_name, &error_message],
+            &[&tool_name, &error_message, &timeouts, &kind.as_str()],
+        )
+        .await?;
+
+        conn.execute(
+            "INSERT INTO tool_failure_events (tool_name, category, error_message) VALUES ($1, $2, $3)",
+            &[&tool_name, &kind.as_str(), &error_message],
         )
         .await?;
 
         Ok(())
     }
 
+    /// Failures per tool and category in the `window` before `now` and in
+    /// the `window` before that, worst first.
+    pub async fn tool_failure_trends(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+        window: chrono::Duration,
+        limit: i64,
+    ) -> Result<Vec<ToolFailureTrend>, DatabaseError> {
+        let conn = self.conn().await?;
+        let current_since = now - window;
+        let previous_since = current_since - window;
+
+        let rows = conn
+            .query(
+                r#"
+                SELECT tool_name, category,
+                       COUNT(*) FILTER (WHERE created_at >= $2) AS current,
+                       COUNT(*) FILTER (WHERE created_at < $2) AS previous,
+                       (ARRAY_AGG(error_message ORDER BY created_at DESC))[1] AS last_error
+                FROM tool_failure_events
+                WHERE created_at >= $1 AND created_at < $3
+                GROUP BY tool_name, category
+                ORDER BY current DESC, previous DESC
+                LIMIT $4
+                "#,
+                &[&previous_since, &current_since, &now, &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ToolFailureTrend {
+                tool_name: row.get("tool_name"),
+                kind: FailureKind::parse(row.get("category")),
+                current: row.get::<_, i64>("current") as u64,
+                previous: row.get::<_, i64>("previous") as u64,
+                last_error: row.get("last_error"),
+            })
+            .collect())
+    }
+
     /// Get tools that have failed more than `threshold` times and haven't been repaired.
     pub async fn get_broken_tools(&self, threshold: i32) -> Result<Vec<BrokenTool>, DatabaseError> {
         let conn = self.conn().await?;
@@ -585,7 +703,8 @@ impl Store {
         let rows = conn
             .query(
                 r#"
-                SELECT tool_name, error_message, error_count, first_failure, last_failure,
+                SELECT tool_name, error_message, error_count, timeout_count,
+                       last_failure_kind, first_failure, last_failure,
                        last_build_result, repair_attempts
                 FROM tool_failures
                 WHERE error_count >= $1 AND repaired_at IS NULL
@@ -601,6 +720,10 @@ impl Store {
                 name: row.get("tool_name"),
                 last_error: row.get("error_message"),
                 failure_count: row.get::<_, i32>("error_count") as u32,
+                timeout_count: row.get::<_, Option<i32>>("timeout_count").unwrap_or(0) as u32,
+                last_failure_kind: row
+                    .get::<_, Option<String>>("last_failure_kind")
+                    .map(|kind| FailureKind::parse(&kind)),
                 first_failure: row.get("first_failure"),
                 last_failure: row.get("last_failure"),
                 last_build_result: row.get("last_build_result"),
@@ -614,7 +737,8 @@ impl Store {
         let conn = self.conn().await?;
 
         conn.execute(
-            "UPDATE tool_failures SET repaired_at = NOW(), error_count = 0 WHERE tool_name = $1",
+            "UPDATE tool_failures SET repaired_at = NOW(), error_count = 0, timeout_count = 0 \
+             WHERE tool_name = $1",
             &[&tool_name],
         )
         .await?;
@@ -635,7 +759,7 @@ impl Store {
         Ok(())
     }
 
-    /// Persist a job-related event.
+    /// Persist a job-related event, tagged with the active trace ID if any.
     pub async fn save_job_event(
         &self,
         job_id: Uuid,
@@ -643,6 +767,7 @@ impl Store {
         data: &serde_json::Value,
     ) -> Result<(), DatabaseError> {
         let conn = self.conn().await?;
+        let data = crate::observability::with_trace_id(data);
         conn.execute(
             "INSERT INTO job_events (job_id, event_type, data, created_at) VALUES ($1, $2, $3, NOW())",
             &[&job_id, &event_type, &data],
@@ -915,6 +1040,294 @@ impl Store {
     }
 }
 
+// ==================== Settings ====================
+
+impl Store {
+    /// List all settings for a user, ordered by key.
+    pub async fn list_settings(&self, user_id: &str) -> Result<Vec<SettingRecord>, DatabaseError> {
+        let conn = self.conn().await?;
+        let rows = conn
+            .query(
+                "SELECT key, value, updated_at, version FROM settings WHERE user_id = $1 ORDER BY key",
+                &[&user_id],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|r| SettingRecord {
+                key: r.get("key"),
+                value: r.get("value"),
+                updated_at: r.get("updated_at"),
+                version: r.get("version"),
+            })
+            .collect())
+    }
+
+    /// Get a single setting with its metadata.
+    pub async fn get_setting_full(
+        &self,
+        user_id: &str,
+        key: &str,
+    ) -> Result<Option<SettingRecord>, DatabaseError> {
+        let conn = self.conn().await?;
+        let row = conn
+            .query_opt(
+                "SELECT key, value, updated_at, version FROM settings WHERE user_id = $1 AND key = $2",
+                &[&user_id, &key],
+            )
+            .await?;
+
+        Ok(row.map(|r| SettingRecord {
+            key: r.get("key"),
+            value: r.get("value"),
+            updated_at: r.get("updated_at"),
+            version: r.get("version"),
+        }))
+    }
+
+    /// Create or replace a setting, returning its new version.
+    ///
+    /// With `expected_version`, the write only happens if the setting is
+    /// still at that version (0 for a setting that doesn't exist yet), and
+    /// otherwise fails with [`DatabaseError::Conflict`] so the caller can
+    /// merge instead of overwriting a change made elsewhere.
+    pub async fn set_setting(
+        &self,
+        user_id: &str,
+        key: &str,
+        value: &serde_json::Value,
+        expected_version: Option<i64>,
+    ) -> Result<i64, DatabaseError> {
+        let conn = self.conn().await?;
+        let row = match expected_version {
+            None => Some(
+                conn.query_one(
+                    r#"
+                    INSERT INTO settings (user_id, key, value, updated_at)
+                    VALUES ($1, $2, $3, NOW())
+                    ON CONFLICT (user_id, key) DO UPDATE SET
+                        value = EXCLUDED.value,
+                        version = settings.version + 1,
+                        updated_at = NOW()
+                    RETURNING version
+                    "#,
+                    &[&user_id, &key, value],
+                )
+                .await?,
+            ),
+            Some(0) => {
+                conn.query_opt(
+                    r#"
+                    INSERT INTO settings (user_id, key, value, updated_at)
+                    VALUES ($1, $2, $3, NOW())
+                    ON CONFLICT (user_id, key) DO NOTHING
+                    RETURNING version
+                    "#,
+                    &[&user_id, &key, value],
+                )
+                .await?
+            }
+            Some(expected) => {
+                conn.query_opt(
+                    r#"
+                    UPDATE settings SET value = $3, version = version + 1, updated_at = NOW()
+                    WHERE user_id = $1 AND key = $2 AND version = $4
+                    RETURNING version
+                    "#,
+                    &[&user_id, &key, value, &expected],
+                )
+                .await?
+            }
+        };
+
+        match row {
+            Some(row) => Ok(row.get("version")),
+            None => {
+                let current = conn
+                    .query_opt(
+                        "SELECT version FROM settings WHERE user_id = $1 AND key = $2",
+                        &[&user_id, &key],
+                    )
+                    .await?
+                    .map(|r| r.get("version"))
+                    .unwrap_or(0);
+                Err(DatabaseError::Conflict {
+                    entity: "setting".to_string(),
+                    id: key.to_string(),
+                    current,
+                })
+            }
+        }
+    }
+
+    /// Delete a setting. Missing keys are not an error.
+    pub async fn delete_setting(&self, user_id: &str, key: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn().await?;
+        conn.execute(
+            "DELETE FROM settings WHERE user_id = $1 AND key = $2",
+            &[&user_id, &key],
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+// ==================== Tool Idempotency ====================
+
+impl Store {
+    /// Look up an idempotency key, returning its status and recorded result.
+    pub async fn get_tool_idempotency(
+        &self,
+        key: &str,
+    ) -> Result<Option<(String, Option<serde_json::Value>)>, DatabaseError> {
+        let conn = self.conn().await?;
+        let row = conn
+            .query_opt(
+                "SELECT status, result FROM tool_idempotency WHERE key = $1",
+                &[&key],
+            )
+            .await?;
+
+        Ok(row.map(|r| (r.get("status"), r.get("result"))))
+    }
+
+    /// Create or update the record for an idempotency key.
+    pub async fn set_tool_idempotency(
+        &self,
+        key: &str,
+        job_id: Uuid,
+        tool_name: &str,
+        status: &str,
+        result: Option<&serde_json::Value>,
+    ) -> Result<(), DatabaseError> {
+        let conn = self.conn().await?;
+        conn.execute(
+            r#"
+            INSERT INTO tool_idempotency (key, job_id, tool_name, status, result, updated_at)
+            VALUES ($1, $2, $3, $4, $5, NOW())
+            ON CONFLICT (key) DO UPDATE SET
+                status = EXCLUDED.status,
+                result = EXCLUDED.result,
+                updated_at = NOW()
+            "#,
+            &[&key, &job_id, &tool_name, &status, &result],
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+// ==================== Message Feedback ====================
+
+use crate::evaluation::UserFeedback;
+
+impl Store {
+    /// Record a user's rating of one of the agent's messages.
+    pub async fn record_feedback(&self, feedback: &UserFeedback) -> Result<(), DatabaseError> {
+        let conn = self.conn().await?;
+        conn.execute(
+            r#"
+            INSERT INTO message_feedback (channel, user_id, thread_id, message_id, rating, emoji)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            &[
+                &feedback.channel,
+                &feedback.user_id,
+                &feedback.thread_id,
+                &feedback.message_id,
+                &feedback.rating.as_str(),
+                &feedback.emoji,
+            ],
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+// ==================== Tool Argument Failures ====================
+
+use crate::evaluation::{ArgumentFailure, ArgumentFailureStats};
+
+/// How long rejected tool calls are kept.
+const ARGUMENT_FAILURE_RETENTION_DAYS: i64 = 30;
+
+impl Store {
+    /// Record a tool call rejected for arguments that didn't match the schema.
+    ///
+    /// Rows older than the retention window are dropped as new ones arrive,
+    /// so the table (and the payloads in it) doesn't grow without bound.
+    pub async fn record_argument_failure(
+        &self,
+        failure: &ArgumentFailure,
+    ) -> Result<(), DatabaseError> {
+        let conn = self.conn().await?;
+        conn.execute(
+            r#"
+            INSERT INTO tool_argument_failures (tool_name, model, errors, payload)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            &[
+                &failure.tool_name,
+                &failure.model,
+                &failure.errors.join("; "),
+                &failure.payload,
+            ],
+        )
+        .await?;
+
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(ARGUMENT_FAILURE_RETENTION_DAYS);
+        conn.execute(
+            "DELETE FROM tool_argument_failures WHERE created_at < $1",
+            &[&cutoff],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Tool and model pairs with the most malformed calls since `since`,
+    /// worst first.
+    pub async fn argument_failure_stats(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<ArgumentFailureStats>, DatabaseError> {
+        let conn = self.conn().await?;
+
+        let rows = conn
+            .query(
+                r#"
+                SELECT tool_name, model, COUNT(*) AS failures, MAX(created_at) AS last_seen,
+                       (ARRAY_AGG(errors ORDER BY created_at DESC))[1] AS last_error,
+                       (ARRAY_AGG(payload ORDER BY created_at DESC))[1] AS last_payload
+                FROM tool_argument_failures
+                WHERE created_at >= $1
+                GROUP BY tool_name, model
+                ORDER BY failures DESC, last_seen DESC
+                LIMIT $2
+                "#,
+                &[&since, &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ArgumentFailureStats {
+                tool_name: row.get("tool_name"),
+                model: row.get("model"),
+                failures: row.get::<_, i64>("failures") as u64,
+                last_error: row.get("last_error"),
+                last_payload: row.get("last_payload"),
+                last_seen: row.get("last_seen"),
+            })
+            .collect())
+    }
+}
+
 #[async_trait]
 impl Database for Store {
     async fn save_job_event(
@@ -923,12 +1336,7 @@ impl Database for Store {
         event_type: &str,
         data: &serde_json::Value,
     ) -> Result<(), DatabaseError> {
-        let conn = self.conn().await?;
-        conn.execute(
-            "INSERT INTO job_events (job_id, event_type, data, created_at) VALUES ($1, $2, $3, NOW())",
-            &[&job_id, &event_type, &data],
-        ).await?;
-        Ok(())
+        self.save_job_event(job_id, event_type, data).await
     }
 
     async fn ensure_conversation(
@@ -1005,6 +1413,52 @@ impl Database for Store {
         }).collect())
     }
 
+    async fn list_conversations_paginated(
+        &self,
+        user_id: &str,
+        channel: &str,
+        limit: usize,
+        before: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<(Vec<ConversationSummary>, bool), DatabaseError> {
+        let conn = self.conn().await?;
+
+        // Same unified channel view as list_conversations_with_preview
+        let channels = if channel == "gateway" || channel == "repl" {
+            vec!["gateway", "repl", "assistant"]
+        } else {
+            vec![channel]
+        };
+
+        let rows = conn.query(
+            r#"
+            SELECT
+                c.id,
+                c.metadata->>'title' as title,
+                (SELECT COUNT(*)::int4 FROM conversation_messages WHERE conversation_id = c.id) as message_count,
+                c.started_at,
+                c.last_activity,
+                c.metadata->>'thread_type' as thread_type
+            FROM conversations c
+            WHERE c.user_id = $1 AND c.channel = ANY($2) AND ($3::timestamptz IS NULL OR c.last_activity < $3)
+            ORDER BY c.last_activity DESC
+            LIMIT $4
+            "#,
+            &[&user_id, &channels, &before, &(limit as i64 + 1)],
+        ).await?;
+
+        let has_more = rows.len() > limit;
+        let conversations = rows.iter().take(limit).map(|row| ConversationSummary {
+            id: row.get("id"),
+            title: row.get("title"),
+            message_count: row.get("message_count"),
+            started_at: row.get("started_at"),
+            last_activity: row.get("last_activity"),
+            thread_type: row.get("thread_type"),
+        }).collect();
+
+        Ok((conversations, has_more))
+    }
+
     async fn list_conversation_messages_paginated(
         &self,
         conversation_id: Uuid,
@@ -1015,7 +1469,7 @@ impl Database for Store {
         
         let (rows, has_more) = if let Some(before_ts) = before {
             let rows = conn.query(
-                "SELECT id, role, content, created_at FROM conversation_messages WHERE conversation_id = $1 AND created_at < $2 ORDER BY created_at DESC LIMIT $3",
+                "SELECT id, role, content, created_at, model, input_tokens, output_tokens, cost FROM conversation_messages WHERE conversation_id = $1 AND created_at < $2 ORDER BY created_at DESC LIMIT $3",
                 &[&conversation_id, &before_ts, &(limit as i64 + 1)],
             ).await?;
             let has_more = rows.len() > limit;
@@ -1023,7 +1477,7 @@ impl Database for Store {
             (rows, has_more)
         } else {
             let rows = conn.query(
-                "SELECT id, role, content, created_at FROM conversation_messages WHERE conversation_id = $1 ORDER BY created_at DESC LIMIT $2",
+                "SELECT id, role, content, created_at, model, input_tokens, output_tokens, cost FROM conversation_messages WHERE conversation_id = $1 ORDER BY created_at DESC LIMIT $2",
                 &[&conversation_id, &(limit as i64 + 1)],
             ).await?;
             let has_more = rows.len() > limit;
@@ -1036,6 +1490,7 @@ impl Database for Store {
             role: row.get("role"),
             content: row.get("content"),
             created_at: row.get("created_at"),
+            usage: MessageUsage::from_row(row),
         }).collect();
 
         Ok((messages, has_more))
@@ -1054,6 +1509,18 @@ impl Database for Store {
         Ok(row.is_some())
     }
 
+    async fn get_conversation_metadata(
+        &self,
+        conversation_id: Uuid,
+    ) -> Result<Option<serde_json::Value>, DatabaseError> {
+        let conn = self.conn().await?;
+        let row = conn.query_opt(
+            "SELECT metadata FROM conversations WHERE id = $1",
+            &[&conversation_id],
+        ).await?;
+        Ok(row.map(|r| r.get("metadata")))
+    }
+
     async fn update_conversation_metadata_field(
         &self,
         conversation_id: Uuid,
@@ -1145,47 +1612,223 @@ impl Database for Store {
         self.list_job_events(job_id).await
     }
 
-    async fn list_routines(&self, _user_id: &str) -> Result<Vec<Routine>, DatabaseError> {
-        Ok(vec![])
+    async fn list_routines(&self, user_id: &str) -> Result<Vec<Routine>, DatabaseError> {
+        let conn = self.conn().await?;
+        let rows = conn.query(
+            "SELECT * FROM routines WHERE user_id = $1 ORDER BY name",
+            &[&user_id],
+        ).await?;
+        rows.iter().map(row_to_routine).collect()
     }
 
-    async fn get_routine(&self, _id: Uuid) -> Result<Option<Routine>, DatabaseError> {
-        Ok(None)
+    async fn get_routine(&self, id: Uuid) -> Result<Option<Routine>, DatabaseError> {
+        let conn = self.conn().await?;
+        let row = conn.query_opt("SELECT * FROM routines WHERE id = $1", &[&id]).await?;
+        row.as_ref().map(row_to_routine).transpose()
     }
 
-    async fn update_routine(&self, _routine: &Routine) -> Result<(), DatabaseError> {
+    async fn list_due_one_shot_routines(&self, now: chrono::DateTime<chrono::Utc>) -> Result<Vec<(String, Routine)>, DatabaseError> {
+        let conn = self.conn().await?;
+        let rows = conn.query(
+            "SELECT * FROM routines WHERE enabled AND trigger_config ? 'Once' AND next_fire_at <= $1 ORDER BY next_fire_at",
+            &[&now],
+        ).await?;
+        rows.iter()
+            .map(|row| Ok((row.get("user_id"), row_to_routine(row)?)))
+            .collect()
+    }
+
+    async fn create_routine(&self, user_id: &str, routine: &Routine) -> Result<(), DatabaseError> {
+        let conn = self.conn().await?;
+        let trigger = serde_json::to_value(&routine.trigger).map_err(|e| DatabaseError::Serialization(e.to_string()))?;
+        let action = serde_json::to_value(&routine.action).map_err(|e| DatabaseError::Serialization(e.to_string()))?;
+        conn.execute(
+            r#"
+            INSERT INTO routines (id, user_id, name, description, enabled, trigger_config, action_config,
+                guardrails, notify, last_run_at, next_fire_at, run_count, consecutive_failures, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            "#,
+            &[
+                &routine.id, &user_id, &routine.name, &routine.description, &routine.enabled, &trigger, &action,
+                &routine.guardrails, &routine.notify, &routine.last_run_at, &routine.next_fire_at,
+                &(routine.run_count as i64), &(routine.consecutive_failures as i32), &routine.created_at, &routine.updated_at,
+            ],
+        ).await.map_err(map_unique_violation)?;
         Ok(())
     }
 
-    async fn delete_routine(&self, _id: Uuid) -> Result<bool, DatabaseError> {
-        Ok(false)
+    async fn routine_belongs_to_user(&self, id: Uuid, user_id: &str) -> Result<bool, DatabaseError> {
+        let conn = self.conn().await?;
+        let row = conn.query_opt(
+            "SELECT 1 FROM routines WHERE id = $1 AND user_id = $2",
+            &[&id, &user_id],
+        ).await?;
+        Ok(row.is_some())
     }
 
-    async fn list_routine_runs(&self, _routine_id: Uuid, _limit: usize) -> Result<Vec<RoutineRun>, DatabaseError> {
-        Ok(vec![])
+    async fn update_routine(&self, routine: &Routine) -> Result<i64, DatabaseError> {
+        let conn = self.conn().await?;
+        let trigger = serde_json::to_value(&routine.trigger).map_err(|e| DatabaseError::Serialization(e.to_string()))?;
+        let action = serde_json::to_value(&routine.action).map_err(|e| DatabaseError::Serialization(e.to_string()))?;
+        let row = conn.query_opt(
+            r#"
+            UPDATE routines SET name = $2, description = $3, enabled = $4, trigger_config = $5, action_config = $6,
+                guardrails = $7, notify = $8, last_run_at = $9, next_fire_at = $10, run_count = $11,
+                consecutive_failures = $12, version = version + 1, updated_at = NOW()
+            WHERE id = $1 AND version = $13
+            RETURNING version
+            "#,
+            &[
+                &routine.id, &routine.name, &routine.description, &routine.enabled, &trigger, &action,
+                &routine.guardrails, &routine.notify, &routine.last_run_at, &routine.next_fire_at,
+                &(routine.run_count as i64), &(routine.consecutive_failures as i32), &routine.version,
+            ],
+        ).await.map_err(map_unique_violation)?;
+        if let Some(row) = row {
+            return Ok(row.get("version"));
+        }
+
+        let current = conn.query_opt("SELECT version FROM routines WHERE id = $1", &[&routine.id]).await?;
+        match current {
+            Some(row) => Err(DatabaseError::Conflict {
+                entity: "routine".to_string(),
+                id: routine.id.to_string(),
+                current: row.get("version"),
+            }),
+            None => Err(DatabaseError::NotFound { entity: "routine".to_string(), id: routine.id.to_string() }),
+        }
     }
 
-    async fn list_settings(&self, _user_id: &str) -> Result<Vec<SettingRecord>, DatabaseError> {
-        Ok(vec![])
+    async fn delete_routine(&self, id: Uuid) -> Result<bool, DatabaseError> {
+        let conn = self.conn().await?;
+        let deleted = conn.execute("DELETE FROM routines WHERE id = $1", &[&id]).await?;
+        Ok(deleted > 0)
     }
 
-    async fn get_setting_full(&self, _user_id: &str, _key: &str) -> Result<Option<SettingRecord>, DatabaseError> {
-        Ok(None)
+    async fn list_routine_runs(&self, routine_id: Uuid, limit: usize) -> Result<Vec<RoutineRun>, DatabaseError> {
+        let conn = self.conn().await?;
+        let rows = conn.query(
+            "SELECT id, routine_id, trigger_type, status, started_at, completed_at, result_summary, tokens_used, cost, messages_sent FROM routine_runs WHERE routine_id = $1 ORDER BY started_at DESC LIMIT $2",
+            &[&routine_id, &(limit as i64)],
+        ).await?;
+
+        rows.iter().map(|row| {
+            let status: String = row.get("status");
+            let messages_sent: i32 = row.get("messages_sent");
+            Ok(RoutineRun {
+                id: row.get("id"),
+                routine_id: row.get("routine_id"),
+                trigger_type: row.get("trigger_type"),
+                status: status.parse().map_err(DatabaseError::Serialization)?,
+                started_at: row.get("started_at"),
+                completed_at: row.get("completed_at"),
+                result_summary: row.get("result_summary"),
+                tokens_used: row.get("tokens_used"),
+                cost: row.get("cost"),
+                messages_sent: messages_sent.max(0) as u32,
+            })
+        }).collect()
     }
 
-    async fn set_setting(&self, _user_id: &str, _key: &str, _value: &serde_json::Value) -> Result<(), DatabaseError> {
+    async fn create_routine_run(&self, run: &RoutineRun) -> Result<(), DatabaseError> {
+        let conn = self.conn().await?;
+        conn.execute(
+            r#"
+            INSERT INTO routine_runs (id, routine_id, trigger_type, status, started_at, completed_at,
+                result_summary, tokens_used, cost, messages_sent)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+            &[
+                &run.id, &run.routine_id, &run.trigger_type, &run.status.to_string(), &run.started_at,
+                &run.completed_at, &run.result_summary, &run.tokens_used, &run.cost, &(run.messages_sent as i32),
+            ],
+        ).await?;
         Ok(())
     }
 
-    async fn delete_setting(&self, _user_id: &str, _key: &str) -> Result<(), DatabaseError> {
+    async fn complete_routine_run(&self, run: &RoutineRun) -> Result<(), DatabaseError> {
+        let conn = self.conn().await?;
+        let updated = conn.execute(
+            r#"
+            UPDATE routine_runs SET status = $2, completed_at = $3, result_summary = $4, tokens_used = $5,
+                cost = $6, messages_sent = $7
+            WHERE id = $1
+            "#,
+            &[
+                &run.id, &run.status.to_string(), &run.completed_at, &run.result_summary, &run.tokens_used,
+                &run.cost, &(run.messages_sent as i32),
+            ],
+        ).await?;
+        if updated == 0 {
+            return Err(DatabaseError::NotFound { entity: "routine run".to_string(), id: run.id.to_string() });
+        }
         Ok(())
     }
 
-    async fn get_all_settings(&self, _user_id: &str) -> Result<std::collections::HashMap<String, serde_json::Value>, DatabaseError> {
-        Ok(std::collections::HashMap::new())
+    async fn list_settings(&self, user_id: &str) -> Result<Vec<SettingRecord>, DatabaseError> {
+        self.list_settings(user_id).await
+    }
+
+    async fn get_setting_full(&self, user_id: &str, key: &str) -> Result<Option<SettingRecord>, DatabaseError> {
+        self.get_setting_full(user_id, key).await
+    }
+
+    async fn set_setting(&self, user_id: &str, key: &str, value: &serde_json::Value, expected_version: Option<i64>) -> Result<i64, DatabaseError> {
+        self.set_setting(user_id, key, value, expected_version).await
+    }
+
+    async fn delete_setting(&self, user_id: &str, key: &str) -> Result<(), DatabaseError> {
+        self.delete_setting(user_id, key).await
     }
 
-    async fn set_all_settings(&self, _user_id: &str, _settings: &std::collections::HashMap<String, serde_json::Value>) -> Result<(), DatabaseError> {
+    async fn get_all_settings(&self, user_id: &str) -> Result<std::collections::HashMap<String, serde_json::Value>, DatabaseError> {
+        Ok(self
+            .list_settings(user_id)
+            .await?
+            .into_iter()
+            .map(|r| (r.key, r.value))
+            .collect())
+    }
+
+    async fn set_all_settings(&self, user_id: &str, settings: &std::collections::HashMap<String, serde_json::Value>) -> Result<(), DatabaseError> {
+        for (key, value) in settings {
+            self.set_setting(user_id, key, value, None).await?;
+        }
         Ok(())
     }
 }
+
+/// Surface unique-key violations as [`DatabaseError::Constraint`].
+fn map_unique_violation(e: tokio_postgres::Error) -> DatabaseError {
+    if e.code() == Some(&tokio_postgres::error::SqlState::UNIQUE_VIOLATION) {
+        DatabaseError::Constraint(e.to_string())
+    } else {
+        e.into()
+    }
+}
+
+/// Map a `routines` row to a [`Routine`].
+fn row_to_routine(row: &tokio_postgres::Row) -> Result<Routine, DatabaseError> {
+    let trigger: serde_json::Value = row.get("trigger_config");
+    let action: serde_json::Value = row.get("action_config");
+    let run_count: i64 = row.get("run_count");
+    let consecutive_failures: i32 = row.get("consecutive_failures");
+
+    Ok(Routine {
+        id: row.get("id"),
+        name: row.get("name"),
+        description: row.get("description"),
+        enabled: row.get("enabled"),
+        trigger: serde_json::from_value(trigger).map_err(|e| DatabaseError::Serialization(e.to_string()))?,
+        action: serde_json::from_value(action).map_err(|e| DatabaseError::Serialization(e.to_string()))?,
+        guardrails: row.get("guardrails"),
+        notify: row.get("notify"),
+        last_run_at: row.get("last_run_at"),
+        next_fire_at: row.get("next_fire_at"),
+        run_count: run_count.max(0) as u64,
+        consecutive_failures: consecutive_failures.max(0) as u32,
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        version: row.get("version"),
+    })
+}
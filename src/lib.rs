@@ -39,6 +39,7 @@
 //! - **Continuous learning** - Improve estimates from historical data
 
 pub mod agent;
+pub mod backup;
 pub mod channels;
 pub mod cli;
 pub mod config;
@@ -50,6 +51,7 @@ pub mod evaluation;
 pub mod extensions;
 pub mod history;
 pub mod llm;
+pub mod observability;
 pub mod orchestrator;
 pub mod safety;
 pub mod sandbox;
@@ -58,7 +60,9 @@ pub mod settings;
 pub mod setup;
 pub mod sneed_engine;
 pub mod spectral_oracle;
+pub mod tenancy;
 pub mod tools;
+pub mod transcription;
 pub mod worker;
 pub mod workspace;
 
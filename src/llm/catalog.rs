@@ -0,0 +1,396 @@
+//! Catalog of the models each provider offers.
+//!
+//! Providers describe their models with whatever metadata their APIs expose:
+//! context length, tool-use and vision support, and pricing. [`ModelCatalog`]
+//! refreshes this periodically and persists it to
+//! `~/.ironclaw/model_catalog.json`, so model selection and the setup wizard
+//! have it before the first refresh completes, or when the provider is
+//! unreachable.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+use crate::error::LlmError;
+use crate::llm::provider::LlmProvider;
+
+/// A model and what's known about it. Unknown capabilities are `None`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogModel {
+    /// Model identifier, as passed to the provider.
+    pub id: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Maximum input tokens.
+    #[serde(default)]
+    pub context_length: Option<u32>,
+    #[serde(default)]
+    pub supports_tools: Option<bool>,
+    #[serde(default)]
+    pub supports_vision: Option<bool>,
+    /// USD per input token.
+    #[serde(default)]
+    pub input_cost: Option<Decimal>,
+    /// USD per output token.
+    #[serde(default)]
+    pub output_cost: Option<Decimal>,
+}
+
+impl CatalogModel {
+    /// A model with no known metadata.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            display_name: None,
+            context_length: None,
+            supports_tools: None,
+            supports_vision: None,
+            input_cost: None,
+            output_cost: None,
+        }
+    }
+
+    /// Short description of the known capabilities, e.g.
+    /// "128k context, tools, vision, $3.00/$15.00 per 1M tokens".
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(tokens) = self.context_length {
+            if tokens >= 1_000_000 && tokens % 1_000_000 < 50_000 {
+                parts.push(format!("{}M context", tokens / 1_000_000));
+            } else {
+                parts.push(format!("{}k context", tokens / 1000));
+            }
+        }
+        match self.supports_tools {
+            Some(true) => parts.push("tools".to_string()),
+            Some(false) => parts.push("no tools".to_string()),
+            None => {}
+        }
+        if self.supports_vision == Some(true) {
+            parts.push("vision".to_string());
+        }
+        if let (Some(input), Some(output)) = (self.input_cost, self.output_cost) {
+            let per_million = Decimal::from(1_000_000);
+            parts.push(format!(
+                "${:.2}/${:.2} per 1M tokens",
+                input * per_million,
+                output * per_million
+            ));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Parse a model list response.
+///
+/// Accepts the shapes providers commonly use: `{"data": [...]}` (OpenAI
+/// style), `{"models": [...]}` (NEAR AI, Gemini), or a bare array. Metadata
+/// is read from the field names used by OpenAI-compatible gateways and by
+/// Gemini, at the top level of each entry or under `metadata`.
+pub fn parse_model_list(body: &str) -> Vec<CatalogModel> {
+    let Ok(value) = serde_json::from_str::<Value>(body) else {
+        return Vec::new();
+    };
+    let entries = match &value {
+        Value::Array(entries) => entries,
+        Value::Object(obj) => match obj.get("data").or_else(|| obj.get("models")) {
+            Some(Value::Array(entries)) => entries,
+            _ => return Vec::new(),
+        },
+        _ => return Vec::new(),
+    };
+    entries.iter().filter_map(parse_model_entry).collect()
+}
+
+/// Parse one entry of a model list response.
+pub fn parse_model_entry(entry: &Value) -> Option<CatalogModel> {
+    let id = lookup(
+        entry,
+        &[
+            "id",
+            "name",
+            "model",
+            "modelName",
+            "model_name",
+            "modelId",
+            "model_id",
+        ],
+    )
+    .and_then(Value::as_str)?;
+    // Gemini names models "models/<id>"
+    let id = id.strip_prefix("models/").unwrap_or(id);
+
+    let context_length = lookup(
+        entry,
+        &[
+            "context_length",
+            "context_window",
+            "max_context_length",
+            "contextLength",
+            "inputTokenLimit",
+            "max_input_tokens",
+        ],
+    )
+    .and_then(Value::as_u64)
+    .and_then(|n| u32::try_from(n).ok());
+
+    let capabilities = entry.get("capabilities");
+    let flag = |keys: &[&str]| {
+        keys.iter().find_map(|k| {
+            capabilities
+                .and_then(|c| c.get(*k))
+                .and_then(Value::as_bool)
+        })
+    };
+    let supports_tools = flag(&["tools", "function_calling", "tool_use"])
+        .or_else(|| lookup(entry, &["supports_tools", "supports_function_calling"])?.as_bool())
+        .or_else(|| list_contains(entry.get("supported_parameters"), "tools"));
+    let supports_vision = flag(&["vision", "image_input"])
+        .or_else(|| lookup(entry, &["supports_vision"])?.as_bool())
+        .or_else(|| {
+            let modalities = entry.get("architecture")?.get("input_modalities");
+            list_contains(modalities, "image")
+        });
+
+    let pricing = entry.get("pricing");
+    let price = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| pricing.and_then(|p| p.get(*k)))
+            .and_then(parse_decimal)
+    };
+
+    Some(CatalogModel {
+        id: id.to_string(),
+        display_name: lookup(entry, &["display_name", "displayName"])
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        context_length,
+        supports_tools,
+        supports_vision,
+        input_cost: price(&["prompt", "input"]),
+        output_cost: price(&["completion", "output"]),
+    })
+}
+
+/// First of `keys` present on the entry or its `metadata` object.
+fn lookup<'a>(entry: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    let metadata = entry.get("metadata");
+    keys.iter()
+        .find_map(|k| entry.get(*k).or_else(|| metadata.and_then(|m| m.get(*k))))
+        .filter(|v| !v.is_null())
+}
+
+/// Whether a JSON string array contains `item`; `None` if it isn't an array.
+fn list_contains(list: Option<&Value>, item: &str) -> Option<bool> {
+    Some(list?.as_array()?.iter().any(|v| v.as_str() == Some(item)))
+}
+
+fn parse_decimal(value: &Value) -> Option<Decimal> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_f64().and_then(Decimal::from_f64_retain),
+        _ => None,
+    }
+}
+
+/// Get the default catalog file path (~/.ironclaw/model_catalog.json).
+pub fn default_catalog_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".ironclaw")
+        .join("model_catalog.json")
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CatalogSnapshot {
+    refreshed_at: Option<DateTime<Utc>>,
+    models: Vec<CatalogModel>,
+}
+
+/// Models available from the configured provider, kept fresh in the
+/// background and persisted between runs.
+pub struct ModelCatalog {
+    /// Where the catalog is persisted, if anywhere.
+    path: Option<PathBuf>,
+    snapshot: RwLock<CatalogSnapshot>,
+}
+
+impl ModelCatalog {
+    /// Load the catalog persisted at `path`, or start empty.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let snapshot = match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw).unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Ignoring unreadable model catalog {}: {}",
+                    path.display(),
+                    e
+                );
+                CatalogSnapshot::default()
+            }),
+            Err(_) => CatalogSnapshot::default(),
+        };
+        Self {
+            path: Some(path),
+            snapshot: RwLock::new(snapshot),
+        }
+    }
+
+    /// A catalog that isn't persisted.
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            snapshot: RwLock::new(CatalogSnapshot::default()),
+        }
+    }
+
+    /// Fetch the provider's models and replace the catalog with them.
+    ///
+    /// An empty result leaves the current catalog in place, since providers
+    /// without a listing endpoint return nothing. Returns the number of
+    /// models fetched.
+    pub async fn refresh(&self, llm: &dyn LlmProvider) -> Result<usize, LlmError> {
+        let models = llm.model_catalog().await?;
+        if models.is_empty() {
+            return Ok(0);
+        }
+
+        let count = models.len();
+        let mut snapshot = self.snapshot.write().await;
+        snapshot.models = models;
+        snapshot.refreshed_at = Some(Utc::now());
+        if let Some(ref path) = self.path
+            && let Err(e) = save(path, &snapshot)
+        {
+            tracing::warn!(
+                "Failed to persist model catalog to {}: {}",
+                path.display(),
+                e
+            );
+        }
+        Ok(count)
+    }
+
+    /// Look up a model by ID.
+    pub async fn get(&self, id: &str) -> Option<CatalogModel> {
+        self.snapshot
+            .read()
+            .await
+            .models
+            .iter()
+            .find(|m| m.id == id)
+            .cloned()
+    }
+
+    /// All known models.
+    pub async fn models(&self) -> Vec<CatalogModel> {
+        self.snapshot.read().await.models.clone()
+    }
+
+    /// When the catalog was last refreshed.
+    pub async fn refreshed_at(&self) -> Option<DateTime<Utc>> {
+        self.snapshot.read().await.refreshed_at
+    }
+}
+
+fn save(path: &Path, snapshot: &CatalogSnapshot) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(snapshot).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
+
+/// Periodically refresh the catalog from `llm`. A catalog loaded from disk
+/// that is younger than `interval` isn't refetched at startup.
+pub fn spawn_catalog_refresh_loop(
+    catalog: Arc<ModelCatalog>,
+    llm: Arc<dyn LlmProvider>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut first = true;
+        loop {
+            ticker.tick().await;
+            if std::mem::take(&mut first)
+                && let Some(refreshed_at) = catalog.refreshed_at().await
+                && (Utc::now() - refreshed_at)
+                    .to_std()
+                    .is_ok_and(|age| age < interval)
+            {
+                continue;
+            }
+            match catalog.refresh(llm.as_ref()).await {
+                Ok(count) => tracing::debug!("Model catalog refreshed: {} models", count),
+                Err(e) => tracing::warn!("Model catalog refresh failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_openai_style_list() {
+        let body = r#"{"data": [
+            {"id": "anthropic/claude-sonnet-4", "context_length": 200000,
+             "pricing": {"prompt": "0.000003", "completion": "0.000015"},
+             "supported_parameters": ["tools", "temperature"],
+             "architecture": {"input_modalities": ["text", "image"]}},
+            {"id": "plain-model"}
+        ]}"#;
+        let models = parse_model_list(body);
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].context_length, Some(200_000));
+        assert_eq!(models[0].supports_tools, Some(true));
+        assert_eq!(models[0].supports_vision, Some(true));
+        assert_eq!(
+            models[0].summary(),
+            "200k context, tools, vision, $3.00/$15.00 per 1M tokens"
+        );
+        assert_eq!(models[1], CatalogModel::new("plain-model"));
+    }
+
+    #[test]
+    fn test_parse_gemini_list() {
+        let body = r#"{"models": [{
+            "name": "models/gemini-2.5-flash",
+            "displayName": "Gemini 2.5 Flash",
+            "inputTokenLimit": 1048576
+        }]}"#;
+        let models = parse_model_list(body);
+        assert_eq!(models[0].id, "gemini-2.5-flash");
+        assert_eq!(models[0].display_name.as_deref(), Some("Gemini 2.5 Flash"));
+        assert_eq!(models[0].summary(), "1M context");
+        assert!(parse_model_list("not json").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_catalog_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model_catalog.json");
+
+        let catalog = ModelCatalog::load(&path);
+        assert!(catalog.models().await.is_empty());
+        {
+            let mut snapshot = catalog.snapshot.write().await;
+            snapshot.models = vec![CatalogModel::new("m1")];
+            snapshot.refreshed_at = Some(Utc::now());
+            save(&path, &snapshot).unwrap();
+        }
+
+        let reloaded = ModelCatalog::load(&path);
+        assert_eq!(reloaded.get("m1").await, Some(CatalogModel::new("m1")));
+        assert!(reloaded.get("m2").await.is_none());
+        assert!(reloaded.refreshed_at().await.is_some());
+    }
+}
@@ -12,6 +12,7 @@ use secrecy::ExposeSecret;
 use serde_json::json;
 use crate::config::GoogleConfig;
 use crate::error::LlmError;
+use crate::llm::catalog::{CatalogModel, parse_model_entry};
 use crate::llm::provider::{
     ChatMessage, CompletionRequest, CompletionResponse, FinishReason, LlmProvider, Role, ToolCall,
     ToolCompletionRequest, ToolCompletionResponse, ToolDefinition,
@@ -113,10 +114,11 @@ impl GoogleGeminiProvider {
         })
     }
 
-    async fn send_generate_content(&self, body: serde_json::Value) -> Result<serde_json::Value, LlmError> {
+    async fn send_generate_content(&self, model: Option<&str>, body: serde_json::Value) -> Result<serde_json::Value, LlmError> {
         // Assume base_url is https://generativelanguage.googleapis.com
         let base_url = "https://generativelanguage.googleapis.com";
-        let url = format!("{}/v1beta/models/{}:generateContent?key={}", base_url, self.config.model, self.api_key());
+        let model = model.unwrap_or(&self.config.model);
+        let url = format!("{}/v1beta/models/{}:generateContent?key={}", base_url, model, self.api_key());
 
         tracing::debug!("Sending request to Google Gemini generateContent");
 
@@ -228,7 +230,7 @@ impl LlmProvider for GoogleGeminiProvider {
             body.as_object_mut().unwrap().insert("cachedContent".to_string(), json!(cache_id));
         }
 
-        let response_json = self.send_generate_content(body).await?;
+        let response_json = self.send_generate_content(req.model.as_deref(), body).await?;
         let (content, thought, _, finish_reason, input_tokens, output_tokens) = self.parse_generate_response(response_json)?;
 
         Ok(CompletionResponse {
@@ -266,7 +268,7 @@ impl LlmProvider for GoogleGeminiProvider {
             body.as_object_mut().unwrap().insert("tools".to_string(), json!(tools_json));
         }
 
-        let response_json = self.send_generate_content(body).await?;
+        let response_json = self.send_generate_content(req.model.as_deref(), body).await?;
         let (content, thought, tool_calls, finish_reason, input_tokens, output_tokens) = self.parse_generate_response(response_json)?;
 
         Ok(ToolCompletionResponse {
@@ -291,6 +293,58 @@ impl LlmProvider for GoogleGeminiProvider {
         Ok(vec![self.config.model.clone()])
     }
 
+    async fn model_catalog(&self) -> Result<Vec<CatalogModel>, LlmError> {
+        let url = format!(
+            "https://generativelanguage.googleapis.com/v1beta/models?pageSize=1000&key={}",
+            self.api_key()
+        );
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| LlmError::RequestFailed {
+                provider: "google".to_string(),
+                reason: format!("Failed to fetch models: {}", e),
+            })?;
+
+        let status = response.status();
+        let body: serde_json::Value =
+            response
+                .json()
+                .await
+                .map_err(|e| LlmError::InvalidResponse {
+                    provider: "google".to_string(),
+                    reason: e.to_string(),
+                })?;
+        if !status.is_success() {
+            return Err(LlmError::RequestFailed {
+                provider: "google".to_string(),
+                reason: format!("HTTP {}: {}", status, body),
+            });
+        }
+
+        // Skip embedding and other models that can't chat. Gemini models all
+        // take images and call functions, which the listing doesn't say.
+        let models = body["models"].as_array().cloned().unwrap_or_default();
+        Ok(models
+            .iter()
+            .filter(|m| {
+                m["supportedGenerationMethods"]
+                    .as_array()
+                    .is_some_and(|methods| methods.iter().any(|v| v == "generateContent"))
+            })
+            .filter_map(parse_model_entry)
+            .map(|mut model| {
+                if model.id.starts_with("gemini") {
+                    model.supports_tools.get_or_insert(true);
+                    model.supports_vision.get_or_insert(true);
+                }
+                model
+            })
+            .collect())
+    }
+
     async fn create_cache(
         &self,
         ttl_seconds: i32,
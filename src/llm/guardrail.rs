@@ -0,0 +1,228 @@
+//! Guardrail against hallucinated tool calls.
+//!
+//! Models sometimes call a tool that isn't registered, or pass an `action`
+//! the tool's schema doesn't offer (often one belonging to another tool).
+//! Running such a call only produces a "not found" error and, often, a
+//! dead-end turn. Instead the call is answered with a corrective tool result
+//! listing the valid tools and actions in compact form, and the model is
+//! asked once more before anything runs.
+
+use serde_json::Value;
+
+use crate::llm::{ChatMessage, ToolCall, ToolDefinition};
+
+/// Longest tool catalog sent back in a corrective turn.
+const MAX_CATALOG_CHARS: usize = 4000;
+
+/// A tool call naming a tool or action that doesn't exist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidCall {
+    /// Position of the call in the model's response.
+    pub index: usize,
+    /// What's wrong, addressed to the model.
+    pub problem: String,
+}
+
+/// Values a tool's schema allows for its `action` parameter, across
+/// `oneOf`/`anyOf` variants. Empty if the tool takes no action.
+pub fn schema_actions(schema: &Value) -> Vec<String> {
+    let mut actions = Vec::new();
+    collect_actions(schema, &mut actions);
+    actions
+}
+
+fn collect_actions(schema: &Value, actions: &mut Vec<String>) {
+    let action = &schema["properties"]["action"];
+    let values = action["const"].as_str().into_iter().chain(
+        action["enum"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str),
+    );
+    for value in values {
+        if !actions.iter().any(|a| a == value) {
+            actions.push(value.to_string());
+        }
+    }
+    for key in ["oneOf", "anyOf"] {
+        for option in schema[key].as_array().into_iter().flatten() {
+            collect_actions(option, actions);
+        }
+    }
+}
+
+/// Calls to unregistered tools, or with an `action` outside the tool's
+/// schema. Other argument problems are left to schema validation.
+pub fn find_invalid_calls(calls: &[ToolCall], tools: &[ToolDefinition]) -> Vec<InvalidCall> {
+    calls
+        .iter()
+        .enumerate()
+        .filter_map(|(index, call)| {
+            let problem = match tools.iter().find(|t| t.name == call.name) {
+                None => format!("There is no tool named '{}'.", call.name),
+                Some(tool) => {
+                    let action = call.arguments.get("action")?.as_str()?;
+                    let actions = schema_actions(&tool.parameters);
+                    if actions.is_empty() || actions.iter().any(|a| a == action) {
+                        return None;
+                    }
+                    format!(
+                        "Tool '{}' has no action '{}'. Its actions are: {}.",
+                        call.name,
+                        action,
+                        actions.join(", ")
+                    )
+                }
+            };
+            Some(InvalidCall { index, problem })
+        })
+        .collect()
+}
+
+/// One line per tool, with its actions if it has any.
+pub fn tool_catalog(tools: &[ToolDefinition]) -> String {
+    let mut out = String::new();
+    for tool in tools {
+        let actions = schema_actions(&tool.parameters);
+        let line = if actions.is_empty() {
+            format!("- {}\n", tool.name)
+        } else {
+            format!("- {}: {}\n", tool.name, actions.join(", "))
+        };
+        if out.len() + line.len() > MAX_CATALOG_CHARS {
+            out.push_str("- …\n");
+            break;
+        }
+        out.push_str(&line);
+    }
+    out
+}
+
+/// The model's `calls` followed by a result for each: the problem for the
+/// invalid ones, the catalog of valid tools with the first of those, and a
+/// request to repeat the valid ones, which don't run either.
+pub fn corrective_turn(
+    calls: &[ToolCall],
+    invalid: &[InvalidCall],
+    tools: &[ToolDefinition],
+) -> Vec<ChatMessage> {
+    let mut messages = vec![ChatMessage::assistant_with_tool_calls("", calls.to_vec())];
+    let mut catalog = Some(tool_catalog(tools));
+    for (index, call) in calls.iter().enumerate() {
+        let content = match invalid.iter().find(|c| c.index == index) {
+            Some(invalid) => match catalog.take() {
+                Some(catalog) => format!(
+                    "Error: {} Nothing was run. Use only these tools and actions:\n{}",
+                    invalid.problem, catalog
+                ),
+                None => format!(
+                    "Error: {} Nothing was run. See the tools listed above.",
+                    invalid.problem
+                ),
+            },
+            None => "Not run, because another call in the same response was invalid. \
+                     Repeat it if it's still needed."
+                .to_string(),
+        };
+        messages.push(ChatMessage::tool_result(&call.id, &call.name, content));
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tools() -> Vec<ToolDefinition> {
+        vec![
+            ToolDefinition {
+                name: "google-docs".to_string(),
+                description: "Docs".to_string(),
+                parameters: json!({
+                    "type": "object",
+                    "oneOf": [
+                        { "properties": { "action": { "const": "get_document" } } },
+                        { "properties": { "action": { "const": "insert_text" } } }
+                    ]
+                }),
+            },
+            ToolDefinition {
+                name: "memory_search".to_string(),
+                description: "Search memory".to_string(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": { "query": { "type": "string" } }
+                }),
+            },
+        ]
+    }
+
+    fn call(name: &str, arguments: Value) -> ToolCall {
+        ToolCall {
+            id: format!("call_{}", name),
+            name: name.to_string(),
+            arguments,
+            thought_signature: None,
+        }
+    }
+
+    #[test]
+    fn test_schema_actions() {
+        let tools = tools();
+        assert_eq!(
+            schema_actions(&tools[0].parameters),
+            vec!["get_document", "insert_text"]
+        );
+        assert!(schema_actions(&tools[1].parameters).is_empty());
+
+        let flat = json!({ "properties": { "action": { "enum": ["list", "get"] } } });
+        assert_eq!(schema_actions(&flat), vec!["list", "get"]);
+    }
+
+    #[test]
+    fn test_find_invalid_calls() {
+        let calls = vec![
+            call("google-docs", json!({ "action": "get_document" })),
+            call("google-docs", json!({ "action": "create_spreadsheet" })),
+            call("web_browse", json!({})),
+            call("memory_search", json!({ "action": "anything" })),
+        ];
+        let invalid = find_invalid_calls(&calls, &tools());
+        assert_eq!(
+            invalid,
+            vec![
+                InvalidCall {
+                    index: 1,
+                    problem: "Tool 'google-docs' has no action 'create_spreadsheet'. \
+                              Its actions are: get_document, insert_text."
+                        .to_string(),
+                },
+                InvalidCall {
+                    index: 2,
+                    problem: "There is no tool named 'web_browse'.".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_corrective_turn() {
+        let tools = tools();
+        let calls = vec![
+            call("memory_search", json!({ "query": "q" })),
+            call("web_browse", json!({})),
+        ];
+        let invalid = find_invalid_calls(&calls, &tools);
+        let messages = corrective_turn(&calls, &invalid, &tools);
+
+        assert_eq!(messages.len(), 3);
+        assert!(messages[1].content.starts_with("Not run"));
+        assert!(
+            messages[2]
+                .content
+                .contains("- google-docs: get_document, insert_text\n- memory_search\n")
+        );
+    }
+}
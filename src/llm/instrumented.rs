@@ -0,0 +1,234 @@
+//! Tracing, metrics and cost-accounting decorator for LLM providers.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use tracing::Instrument;
+
+use crate::error::LlmError;
+use crate::llm::catalog::CatalogModel;
+use crate::llm::provider::{
+    ChatMessage, CompletionRequest, CompletionResponse, LlmProvider, ToolCompletionRequest,
+    ToolCompletionResponse, ToolDefinition,
+};
+use crate::llm::tokenizer::Tokenizer;
+use crate::observability;
+use crate::tenancy;
+
+/// Token usage and cost of one completion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmUsage {
+    pub provider: &'static str,
+    pub model: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub cost: Decimal,
+}
+
+tokio::task_local! {
+    static USAGE: Arc<Mutex<Vec<LlmUsage>>>;
+}
+
+/// Run `fut`, returning the usage of every completion made while it ran.
+///
+/// Collections nest: usage is also reported to any enclosing collection.
+/// Like the tenant scope, this doesn't follow work onto `tokio::spawn`ed
+/// tasks.
+pub async fn collect_usage<F: Future>(fut: F) -> (F::Output, Vec<LlmUsage>) {
+    let usage = Arc::new(Mutex::new(Vec::new()));
+    let output = USAGE.scope(Arc::clone(&usage), fut).await;
+    let usage = std::mem::take(&mut *usage.lock().unwrap_or_else(|e| e.into_inner()));
+    for call in &usage {
+        record_usage(call.clone());
+    }
+    (output, usage)
+}
+
+/// Add to the usage being collected by the current task, if any.
+fn record_usage(usage: LlmUsage) {
+    let _ = USAGE.try_with(|log| log.lock().unwrap_or_else(|e| e.into_inner()).push(usage));
+}
+
+/// Wraps a provider so every completion runs in an `llm.complete` span, is
+/// counted in the request, latency and token metrics, is charged to the
+/// current tenant's daily spend, and is reported to [`collect_usage`].
+pub struct InstrumentedProvider {
+    inner: Arc<dyn LlmProvider>,
+    /// Backend name recorded with usage (e.g. "nearai").
+    provider: &'static str,
+}
+
+impl InstrumentedProvider {
+    pub fn new(inner: Arc<dyn LlmProvider>, provider: &'static str) -> Self {
+        Self { inner, provider }
+    }
+
+    /// Charge and report a successful completion.
+    fn account(&self, model: String, input_tokens: u32, output_tokens: u32) {
+        let cost = self.calculate_cost(input_tokens, output_tokens);
+        tenancy::charge_current(cost);
+        record_usage(LlmUsage {
+            provider: self.provider,
+            model,
+            input_tokens,
+            output_tokens,
+            cost,
+        });
+    }
+}
+
+#[async_trait]
+impl LlmProvider for InstrumentedProvider {
+    fn model_name(&self) -> &str {
+        self.inner.model_name()
+    }
+
+    fn cost_per_token(&self) -> (Decimal, Decimal) {
+        self.inner.cost_per_token()
+    }
+
+    async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, LlmError> {
+        let model = request
+            .model
+            .clone()
+            .unwrap_or_else(|| self.model_name().to_string());
+        let span = tracing::info_span!(
+            "llm.complete",
+            model = %model,
+            tools = false,
+            input_tokens = tracing::field::Empty,
+            output_tokens = tracing::field::Empty,
+        );
+        let start = Instant::now();
+        let result = self.inner.complete(request).instrument(span.clone()).await;
+
+        let (input, output) = match &result {
+            Ok(r) => (r.input_tokens, r.output_tokens),
+            Err(_) => (0, 0),
+        };
+        span.record("input_tokens", input);
+        span.record("output_tokens", output);
+        observability::record_llm_call(&model, result.is_ok(), start.elapsed(), input, output);
+        if result.is_ok() {
+            self.account(model, input, output);
+        }
+        result
+    }
+
+    async fn complete_with_tools(
+        &self,
+        request: ToolCompletionRequest,
+    ) -> Result<ToolCompletionResponse, LlmError> {
+        let model = request
+            .model
+            .clone()
+            .unwrap_or_else(|| self.model_name().to_string());
+        let span = tracing::info_span!(
+            "llm.complete",
+            model = %model,
+            tools = true,
+            input_tokens = tracing::field::Empty,
+            output_tokens = tracing::field::Empty,
+        );
+        let start = Instant::now();
+        let result = self
+            .inner
+            .complete_with_tools(request)
+            .instrument(span.clone())
+            .await;
+
+        let (input, output) = match &result {
+            Ok(r) => (r.input_tokens, r.output_tokens),
+            Err(_) => (0, 0),
+        };
+        span.record("input_tokens", input);
+        span.record("output_tokens", output);
+        observability::record_llm_call(&model, result.is_ok(), start.elapsed(), input, output);
+        if result.is_ok() {
+            self.account(model, input, output);
+        }
+        result
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, LlmError> {
+        self.inner.list_models().await
+    }
+
+    async fn model_catalog(&self) -> Result<Vec<CatalogModel>, LlmError> {
+        self.inner.model_catalog().await
+    }
+
+    async fn create_cache(
+        &self,
+        ttl_seconds: i32,
+        messages: Vec<ChatMessage>,
+        system_instruction: Option<String>,
+        tools: Vec<ToolDefinition>,
+    ) -> Result<String, LlmError> {
+        self.inner
+            .create_cache(ttl_seconds, messages, system_instruction, tools)
+            .await
+    }
+
+    async fn delete_cache(&self, cache_id: &str) -> Result<(), LlmError> {
+        self.inner.delete_cache(cache_id).await
+    }
+
+    async fn upload_file(
+        &self,
+        path: &std::path::Path,
+        mime_type: &str,
+    ) -> Result<String, LlmError> {
+        self.inner.upload_file(path, mime_type).await
+    }
+
+    fn tokenizer(&self) -> Arc<dyn Tokenizer> {
+        self.inner.tokenizer()
+    }
+
+    fn calculate_cost(&self, input_tokens: u32, output_tokens: u32) -> Decimal {
+        self.inner.calculate_cost(input_tokens, output_tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(model: &str) -> LlmUsage {
+        LlmUsage {
+            provider: "test",
+            model: model.to_string(),
+            input_tokens: 10,
+            output_tokens: 5,
+            cost: Decimal::ONE,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collect_usage() {
+        let (value, collected) = collect_usage(async {
+            record_usage(usage("a"));
+            record_usage(usage("b"));
+            7
+        })
+        .await;
+        assert_eq!(value, 7);
+        assert_eq!(collected, vec![usage("a"), usage("b")]);
+
+        // Nested collections report to the enclosing one too
+        let ((_, inner), outer) = collect_usage(async {
+            record_usage(usage("a"));
+            collect_usage(async { record_usage(usage("b")) }).await
+        })
+        .await;
+        assert_eq!(inner, vec![usage("b")]);
+        assert_eq!(outer, vec![usage("a"), usage("b")]);
+
+        // Outside a collection scope, usage is dropped silently
+        record_usage(usage("c"));
+    }
+}
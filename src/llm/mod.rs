@@ -4,22 +4,31 @@
 //! - **Responses API** (chat-api): Session-based auth, uses `/v1/responses` endpoint
 //! - **Chat Completions API** (cloud-api): API key auth, uses `/v1/chat/completions` endpoint
 
+mod catalog;
 mod nearai;
 mod nearai_chat;
 mod google;
+mod guardrail;
+mod instrumented;
 mod provider;
 mod reasoning;
 pub mod session;
+mod tokenizer;
 
+pub use catalog::{
+    CatalogModel, ModelCatalog, default_catalog_path, parse_model_list, spawn_catalog_refresh_loop,
+};
 pub use nearai::{ModelInfo, NearAiProvider};
 pub use nearai_chat::NearAiChatProvider;
 pub use google::GoogleGeminiProvider;
+pub use instrumented::{InstrumentedProvider, LlmUsage, collect_usage};
 pub use provider::{
     ChatMessage, CompletionRequest, CompletionResponse, FinishReason, LlmProvider, Role, ToolCall,
     ToolCompletionRequest, ToolCompletionResponse, ToolDefinition, ToolResult,
 };
 pub use reasoning::{ActionPlan, Reasoning, ReasoningContext, RespondResult, ToolSelection};
 pub use session::{SessionConfig, SessionManager, create_session_manager};
+pub use tokenizer::{ApproxTokenizer, MESSAGE_OVERHEAD_TOKENS, Tokenizer};
 
 use std::sync::Arc;
 
@@ -36,23 +45,25 @@ pub fn create_llm_provider(
 ) -> Result<Arc<dyn LlmProvider>, LlmError> {
     use crate::config::LlmProviderType;
 
-    match config.provider {
+    let provider: Arc<dyn LlmProvider> = match config.provider {
         LlmProviderType::NearAi => match config.nearai.api_mode {
             NearAiApiMode::Responses => {
                 tracing::info!("Using NEAR AI Responses API (chat-api) with session auth");
-                Ok(Arc::new(NearAiProvider::new(
-                    config.nearai.clone(),
-                    session,
-                )))
+                Arc::new(NearAiProvider::new(config.nearai.clone(), session))
             }
             NearAiApiMode::ChatCompletions => {
                 tracing::info!("Using NEAR AI Chat Completions API (cloud-api) with API key auth");
-                Ok(Arc::new(NearAiChatProvider::new(config.nearai.clone())?))
+                Arc::new(NearAiChatProvider::new(config.nearai.clone())?)
             }
         },
         LlmProviderType::Google => {
             tracing::info!("Using direct Google Gemini API (AI Studio)");
-            Ok(Arc::new(GoogleGeminiProvider::new(config.google.clone())?))
+            Arc::new(GoogleGeminiProvider::new(config.google.clone())?)
         }
-    }
+    };
+
+    Ok(Arc::new(InstrumentedProvider::new(
+        provider,
+        config.provider.as_str(),
+    )))
 }
@@ -14,6 +14,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::config::NearAiConfig;
 use crate::error::LlmError;
+use crate::llm::catalog::{CatalogModel, parse_model_list};
 use crate::llm::provider::{
     ChatMessage, CompletionRequest, CompletionResponse, FinishReason, LlmProvider, Role, ToolCall,
     ToolCompletionRequest, ToolCompletionResponse,
@@ -61,10 +62,8 @@ impl NearAiProvider {
         )
     }
 
-    /// Fetch available models from the NEAR AI API.
-    pub async fn list_models(&self) -> Result<Vec<ModelInfo>, LlmError> {
-        use secrecy::ExposeSecret;
-
+    /// Fetch the raw model list response.
+    async fn fetch_model_list(&self) -> Result<String, LlmError> {
         let token = self.session.get_token().await?;
         let url = self.api_url("model/list");
 
@@ -98,6 +97,13 @@ impl NearAiProvider {
             });
         }
 
+        Ok(response_text)
+    }
+
+    /// Fetch available models from the NEAR AI API.
+    pub async fn list_models(&self) -> Result<Vec<ModelInfo>, LlmError> {
+        let response_text = self.fetch_model_list().await?;
+
         // Parse the response - NEAR AI returns {"limit": N, "models": [...]}
         // Each model object may have the name in different fields
         #[derive(Deserialize)]
@@ -321,7 +327,7 @@ impl LlmProvider for NearAiProvider {
         let (instructions, input) = split_messages(req.messages);
 
         let request = NearAiRequest {
-            model: self.config.model.clone(),
+            model: req.model.unwrap_or_else(|| self.config.model.clone()),
             instructions,
             input,
             temperature: req.temperature,
@@ -452,7 +458,7 @@ impl LlmProvider for NearAiProvider {
             .collect();
 
         let request = NearAiRequest {
-            model: self.config.model.clone(),
+            model: req.model.unwrap_or_else(|| self.config.model.clone()),
             instructions,
             input,
             temperature: req.temperature,
@@ -591,6 +597,10 @@ impl LlmProvider for NearAiProvider {
         let models = NearAiProvider::list_models(self).await?;
         Ok(models.into_iter().map(|m| m.name).collect())
     }
+
+    async fn model_catalog(&self) -> Result<Vec<CatalogModel>, LlmError> {
+        Ok(parse_model_list(&self.fetch_model_list().await?))
+    }
 }
 
 // NEAR AI API types
@@ -12,6 +12,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::config::NearAiConfig;
 use crate::error::LlmError;
+use crate::llm::catalog::{CatalogModel, parse_model_list};
 use crate::llm::provider::{
     ChatMessage, CompletionRequest, CompletionResponse, FinishReason, LlmProvider, Role, ToolCall,
     ToolCompletionRequest, ToolCompletionResponse,
@@ -111,8 +112,8 @@ impl NearAiChatProvider {
         })
     }
 
-    /// Fetch available models.
-    pub async fn list_models(&self) -> Result<Vec<String>, LlmError> {
+    /// Fetch the raw model list response.
+    async fn fetch_model_list(&self) -> Result<String, LlmError> {
         let url = self.api_url("models");
 
         let response = self
@@ -136,6 +137,13 @@ impl NearAiChatProvider {
             });
         }
 
+        Ok(response_text)
+    }
+
+    /// Fetch available models.
+    pub async fn list_models(&self) -> Result<Vec<String>, LlmError> {
+        let response_text = self.fetch_model_list().await?;
+
         #[derive(Deserialize)]
         struct ModelsResponse {
             data: Vec<ModelEntry>,
@@ -163,7 +171,7 @@ impl LlmProvider for NearAiChatProvider {
             req.messages.into_iter().map(|m| m.into()).collect();
 
         let request = ChatCompletionRequest {
-            model: self.config.model.clone(),
+            model: req.model.unwrap_or_else(|| self.config.model.clone()),
             messages,
             temperature: req.temperature,
             max_tokens: req.max_tokens,
@@ -232,7 +240,7 @@ impl LlmProvider for NearAiChatProvider {
             .collect();
 
         let request = ChatCompletionRequest {
-            model: self.config.model.clone(),
+            model: req.model.unwrap_or_else(|| self.config.model.clone()),
             messages,
             temperature: req.temperature,
             max_tokens: req.max_tokens,
@@ -307,6 +315,10 @@ impl LlmProvider for NearAiChatProvider {
     async fn list_models(&self) -> Result<Vec<String>, LlmError> {
         NearAiChatProvider::list_models(self).await
     }
+
+    async fn model_catalog(&self) -> Result<Vec<CatalogModel>, LlmError> {
+        Ok(parse_model_list(&self.fetch_model_list().await?))
+    }
 }
 
 // OpenAI-compatible Chat Completions API types
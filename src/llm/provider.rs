@@ -1,10 +1,14 @@
 //! LLM provider trait and types.
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 use crate::error::LlmError;
+use crate::llm::catalog::CatalogModel;
+use crate::llm::tokenizer::{ApproxTokenizer, Tokenizer};
 
 /// Role in a conversation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -144,6 +148,8 @@ pub struct CompletionRequest {
     pub temperature: Option<f32>,
     pub stop_sequences: Option<Vec<String>>,
     pub cache_id: Option<String>,
+    /// Model to use instead of the provider's configured one.
+    pub model: Option<String>,
 }
 
 impl CompletionRequest {
@@ -155,6 +161,7 @@ impl CompletionRequest {
             temperature: None,
             stop_sequences: None,
             cache_id: None,
+            model: None,
         }
     }
 
@@ -175,6 +182,12 @@ impl CompletionRequest {
         self.cache_id = Some(cache_id.into());
         self
     }
+
+    /// Override the provider's model for this request.
+    pub fn with_model(mut self, model: Option<String>) -> Self {
+        self.model = model;
+        self
+    }
 }
 
 /// Response from a chat completion.
@@ -235,6 +248,8 @@ pub struct ToolCompletionRequest {
     /// How to handle tool use: "auto", "required", or "none".
     pub tool_choice: Option<String>,
     pub cache_id: Option<String>,
+    /// Model to use instead of the provider's configured one.
+    pub model: Option<String>,
 }
 
 impl ToolCompletionRequest {
@@ -247,6 +262,7 @@ impl ToolCompletionRequest {
             temperature: None,
             tool_choice: None,
             cache_id: None,
+            model: None,
         }
     }
 
@@ -273,6 +289,12 @@ impl ToolCompletionRequest {
         self.cache_id = Some(cache_id.into());
         self
     }
+
+    /// Override the provider's model for this request.
+    pub fn with_model(mut self, model: Option<String>) -> Self {
+        self.model = model;
+        self
+    }
 }
 
 /// Response from a completion with potential tool calls.
@@ -312,6 +334,17 @@ pub trait LlmProvider: Send + Sync {
         Ok(Vec::new())
     }
 
+    /// List available models with their capabilities and pricing.
+    /// Default implementation knows only the IDs from `list_models`.
+    async fn model_catalog(&self) -> Result<Vec<CatalogModel>, LlmError> {
+        Ok(self
+            .list_models()
+            .await?
+            .into_iter()
+            .map(CatalogModel::new)
+            .collect())
+    }
+
     /// Create a cached context block with a TTL.
     /// Default implementation returns an error (not supported).
     async fn create_cache(
@@ -343,6 +376,12 @@ pub trait LlmProvider: Send + Sync {
         })
     }
 
+    /// Tokenizer matching this provider's model, used for context budgeting.
+    /// Default implementation approximates subword tokenization.
+    fn tokenizer(&self) -> Arc<dyn Tokenizer> {
+        Arc::new(ApproxTokenizer)
+    }
+
     /// Calculate cost for a completion.
     fn calculate_cost(&self, input_tokens: u32, output_tokens: u32) -> Decimal {
         let (input_cost, output_cost) = self.cost_per_token();
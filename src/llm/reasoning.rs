@@ -2,15 +2,23 @@
 
 use std::sync::Arc;
 
+use rust_decimal::prelude::ToPrimitive;
 use serde::{Deserialize, Serialize};
 
 use crate::error::LlmError;
 
+use crate::estimation::{Estimator, PlanEstimate};
+use crate::llm::guardrail;
 use crate::llm::{
     ChatMessage, CompletionRequest, LlmProvider, ToolCall, ToolCompletionRequest, ToolDefinition,
 };
+use crate::observability;
 use crate::safety::SafetyLayer;
 
+/// How far below the most confident candidate a cheaper plan may fall and
+/// still be chosen.
+const PLAN_CONFIDENCE_SLACK: f64 = 0.1;
+
 /// Context for reasoning operations.
 pub struct ReasoningContext {
     /// Conversation history.
@@ -100,6 +108,16 @@ pub struct ActionPlan {
     pub estimated_time_secs: Option<u64>,
     /// Confidence in the plan (0-1).
     pub confidence: f64,
+    /// Alternative strategies for the same goal, if the planner offered any.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<ActionPlan>,
+}
+
+impl ActionPlan {
+    /// Tool names of every planned action, in order.
+    pub fn tool_names(&self) -> Vec<String> {
+        self.actions.iter().map(|a| a.tool_name.clone()).collect()
+    }
 }
 
 /// Result of tool selection.
@@ -133,6 +151,12 @@ pub struct Reasoning {
     safety: Arc<SafetyLayer>,
     /// Optional workspace for loading identity/system prompts.
     workspace_system_prompt: Option<String>,
+    /// Model to respond with instead of the provider's default.
+    model: Option<String>,
+    /// Language to respond in (English name, e.g. "French").
+    language: Option<String>,
+    /// Estimator used to cost candidate plans before execution.
+    estimator: Option<Arc<Estimator>>,
 }
 
 impl Reasoning {
@@ -142,7 +166,73 @@ impl Reasoning {
             llm,
             safety,
             workspace_system_prompt: None,
+            model: None,
+            language: None,
+            estimator: None,
+        }
+    }
+
+    /// Respond with `model` instead of the provider's configured model.
+    pub fn with_model(mut self, model: Option<String>) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Respond in `language` (e.g. "French") by default.
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Cost candidate plans with `estimator` and prefer the cheaper strategy.
+    pub fn with_estimator(mut self, estimator: Arc<Estimator>) -> Self {
+        self.estimator = Some(estimator);
+        self
+    }
+
+    /// Project the cost, time, and confidence of `plan` without executing it.
+    ///
+    /// Returns `None` when no estimator is configured.
+    pub fn estimate_plan(&self, plan: &ActionPlan) -> Option<PlanEstimate> {
+        self.estimator
+            .as_ref()
+            .map(|e| e.estimate_plan(None, &plan.tool_names()))
+    }
+
+    /// Pick the cheapest of `candidates`, filling in their projected cost and time.
+    ///
+    /// Candidates the planner is markedly less confident in are only chosen
+    /// when nothing more trustworthy is on the table. Without an estimator the
+    /// first candidate wins.
+    pub fn choose_plan(&self, candidates: Vec<ActionPlan>) -> Option<ActionPlan> {
+        let Some(ref estimator) = self.estimator else {
+            return candidates.into_iter().next();
+        };
+
+        let best_confidence = candidates
+            .iter()
+            .map(|p| p.confidence)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let mut chosen: Option<(ActionPlan, PlanEstimate)> = None;
+        for mut plan in candidates {
+            let estimate = estimator.estimate_plan(None, &plan.tool_names());
+            plan.estimated_cost = estimate.cost.to_f64();
+            plan.estimated_time_secs = Some(estimate.duration.as_secs());
+
+            if plan.confidence + PLAN_CONFIDENCE_SLACK < best_confidence {
+                continue;
+            }
+            let better = match chosen {
+                Some((_, ref current)) => estimate.is_cheaper_than(current),
+                None => true,
+            };
+            if better {
+                chosen = Some((plan, estimate));
+            }
         }
+
+        chosen.map(|(plan, _)| plan)
     }
 
     /// Set a custom system prompt from workspace identity files.
@@ -183,7 +273,22 @@ impl Reasoning {
         let response = self.llm.complete(request).await?;
 
         // Parse the plan from the response
-        self.parse_plan(&response.content)
+        let mut plan = self.parse_plan(&response.content)?;
+        if self.estimator.is_none() {
+            return Ok(plan);
+        }
+
+        // Weigh the primary plan against any alternatives before executing.
+        let alternatives = std::mem::take(&mut plan.alternatives);
+        let mut candidates = vec![plan];
+        candidates.extend(alternatives);
+        let chosen = self
+            .choose_plan(candidates)
+            .ok_or_else(|| LlmError::InvalidResponse {
+                provider: self.llm.model_name().to_string(),
+                reason: "Planner returned no plan".to_string(),
+            })?;
+        Ok(chosen)
     }
 
     /// Select the best tool for the current situation.
@@ -319,25 +424,46 @@ Respond in JSON format:
 
         // If we have tools, use tool completion mode
         if !context.available_tools.is_empty() {
-            let request = ToolCompletionRequest::new(messages, context.available_tools.clone())
-                .with_max_tokens(4096)
-                .with_temperature(0.7)
-                .with_tool_choice("auto");
-
-            let request = if let Some(ref cid) = context.cache_id {
-                // If using cache, we assume the system prompt is already cached
-                // So we omit it to avoid duplication or errors
-                let messages_without_system = request.messages.into_iter().skip(1).collect();
-                ToolCompletionRequest::new(messages_without_system, context.available_tools.clone())
-                    .with_max_tokens(4096)
-                    .with_temperature(0.7)
-                    .with_tool_choice("auto")
-                    .with_cache_id(cid.clone())
-            } else {
-                request
-            };
-
-            let response = self.llm.complete_with_tools(request).await?;
+            let mut response = self
+                .llm
+                .complete_with_tools(self.tool_request(messages.clone(), context))
+                .await?;
+
+            // Calls to tools or actions that don't exist get one corrective retry
+            let invalid =
+                guardrail::find_invalid_calls(&response.tool_calls, &context.available_tools);
+            if !invalid.is_empty() {
+                let problems: Vec<&str> = invalid.iter().map(|c| c.problem.as_str()).collect();
+                tracing::warn!("Retrying hallucinated tool calls: {}", problems.join(" "));
+                messages.extend(guardrail::corrective_turn(
+                    &response.tool_calls,
+                    &invalid,
+                    &context.available_tools,
+                ));
+                match self
+                    .llm
+                    .complete_with_tools(self.tool_request(messages, context))
+                    .await
+                {
+                    Ok(retried) => {
+                        let corrected = guardrail::find_invalid_calls(
+                            &retried.tool_calls,
+                            &context.available_tools,
+                        )
+                        .is_empty();
+                        observability::record_tool_guardrail(if corrected {
+                            "corrected"
+                        } else {
+                            "persisted"
+                        });
+                        response = retried;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Corrective retry failed, keeping original calls: {}", e);
+                        observability::record_tool_guardrail("failed");
+                    }
+                }
+            }
 
             // If there were tool calls, return them for execution
             if !response.tool_calls.is_empty() {
@@ -353,7 +479,8 @@ Respond in JSON format:
             // No tools, use simple completion
             let request = CompletionRequest::new(messages)
                 .with_max_tokens(4096)
-                .with_temperature(0.7);
+                .with_temperature(0.7)
+                .with_model(self.model.clone());
 
             let request = if let Some(ref cid) = context.cache_id {
                 let messages_without_system = request.messages.into_iter().skip(1).collect();
@@ -361,6 +488,7 @@ Respond in JSON format:
                     .with_max_tokens(4096)
                     .with_temperature(0.7)
                     .with_cache_id(cid.clone())
+                    .with_model(self.model.clone())
             } else {
                 request
             };
@@ -370,6 +498,34 @@ Respond in JSON format:
         }
     }
 
+    /// Tool completion request over `messages`, which start with the system
+    /// prompt.
+    fn tool_request(
+        &self,
+        messages: Vec<ChatMessage>,
+        context: &ReasoningContext,
+    ) -> ToolCompletionRequest {
+        let request = ToolCompletionRequest::new(messages, context.available_tools.clone())
+            .with_max_tokens(4096)
+            .with_temperature(0.7)
+            .with_tool_choice("auto")
+            .with_model(self.model.clone());
+
+        if let Some(ref cid) = context.cache_id {
+            // If using cache, we assume the system prompt is already cached
+            // So we omit it to avoid duplication or errors
+            let messages_without_system = request.messages.into_iter().skip(1).collect();
+            ToolCompletionRequest::new(messages_without_system, context.available_tools.clone())
+                .with_max_tokens(4096)
+                .with_temperature(0.7)
+                .with_tool_choice("auto")
+                .with_cache_id(cid.clone())
+                .with_model(self.model.clone())
+        } else {
+            request
+        }
+    }
+
     fn build_planning_prompt(&self, context: &ReasoningContext) -> String {
         let tools_desc = if context.available_tools.is_empty() {
             "No tools available.".to_string()
@@ -394,6 +550,10 @@ When creating a plan:
 3. Consider dependencies between steps
 4. Estimate costs and time realistically
 5. Identify potential failure points
+6. Prefer fewer, batched calls (e.g. one batch update instead of many single updates)
+
+If there is a meaningfully different strategy (for example batching instead of
+looping), include it under "alternatives" using the same plan format.
 
 Respond with a JSON plan in this format:
 {{
@@ -408,7 +568,8 @@ Respond with a JSON plan in this format:
     ],
     "estimated_cost": 0.0,
     "estimated_time_secs": 0,
-    "confidence": 0.0-1.0
+    "confidence": 0.0-1.0,
+    "alternatives": []
 }}"#
         )
     }
@@ -428,6 +589,14 @@ Respond with a JSON plan in this format:
             )
         };
 
+        let language_guideline = match self.language {
+            Some(ref language) => format!(
+                "- Respond in {} unless the user explicitly asks for another language\n    ",
+                language
+            ),
+            None => String::new(),
+        };
+
         if let Some(ref identity) = self.workspace_system_prompt {
             format!(
                 r#"{}
@@ -446,13 +615,13 @@ Respond with a JSON plan in this format:
     Here's the solution: [actual response to user]
     
     ## Guidelines
-    - Be concise and direct
+    {}- Be concise and direct
     - Use markdown formatting where helpful
     - For code, use appropriate code blocks with language tags
     - Call tools when they would help accomplish the task{}
     
     The user sees ONLY content outside <thinking> tags."#,
-                identity, tools_section
+                identity, language_guideline, tools_section
             )
         } else {
             format!(
@@ -472,13 +641,13 @@ Respond with a JSON plan in this format:
     Here's the solution: [actual response to user]
     
     ## Guidelines
-    - Be concise and direct
+    {}- Be concise and direct
     - Use markdown formatting where helpful
     - For code, use appropriate code blocks with language tags
     - Call tools when they would help accomplish the task{}
     
     The user sees ONLY content outside <thinking> tags."#,
-                tools_section
+                language_guideline, tools_section
             )
         }
     }
@@ -702,6 +871,24 @@ That's my plan."#;
         assert!(json.ends_with('}'));
     }
 
+    #[test]
+    fn test_plan_alternatives_are_optional() {
+        let plan: ActionPlan = serde_json::from_str(
+            r#"{"goal": "g", "actions": [{"tool_name": "http", "parameters": {},
+                "reasoning": "r", "expected_outcome": "o"}], "confidence": 0.8}"#,
+        )
+        .unwrap();
+        assert!(plan.alternatives.is_empty());
+        assert_eq!(plan.tool_names(), vec!["http".to_string()]);
+
+        let plan: ActionPlan = serde_json::from_str(
+            r#"{"goal": "g", "actions": [], "confidence": 0.8,
+                "alternatives": [{"goal": "g", "actions": [], "confidence": 0.6}]}"#,
+        )
+        .unwrap();
+        assert_eq!(plan.alternatives.len(), 1);
+    }
+
     #[test]
     fn test_reasoning_context_builder() {
         let context = ReasoningContext::new()
@@ -0,0 +1,106 @@
+//! Token counting for context budgeting.
+//!
+//! Providers expose a [`Tokenizer`] through [`LlmProvider::tokenizer`]. None
+//! of the current backends ship a local vocabulary, so the default is
+//! [`ApproxTokenizer`], which mimics how BPE/SentencePiece vocabularies split
+//! text (word pieces, punctuation, CJK characters) rather than counting words.
+//!
+//! [`LlmProvider::tokenizer`]: crate::llm::LlmProvider::tokenizer
+
+use crate::llm::{ChatMessage, ToolDefinition};
+
+/// Tokens of framing added per message (role markers, separators).
+pub const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Counts tokens the way a model's tokenizer would.
+pub trait Tokenizer: Send + Sync {
+    /// Number of tokens in `text`.
+    fn count(&self, text: &str) -> usize;
+
+    /// Number of tokens a chat message occupies, including tool calls and framing.
+    fn count_message(&self, message: &ChatMessage) -> usize {
+        let mut tokens = MESSAGE_OVERHEAD_TOKENS + self.count(&message.content);
+        if let Some(ref name) = message.name {
+            tokens += self.count(name);
+        }
+        if let Some(ref calls) = message.tool_calls {
+            for call in calls {
+                tokens += self.count(&call.name) + self.count(&call.arguments.to_string());
+            }
+        }
+        tokens
+    }
+
+    /// Number of tokens a tool schema occupies in the request.
+    fn count_tool(&self, tool: &ToolDefinition) -> usize {
+        self.count(&tool.name)
+            + self.count(&tool.description)
+            + self.count(&tool.parameters.to_string())
+    }
+}
+
+/// Vocabulary-free approximation of subword tokenization.
+///
+/// ASCII word runs cost one token per ~4 characters, each punctuation mark and
+/// each non-Latin (e.g. CJK) character costs one token, and whitespace is
+/// folded into the following token except for line breaks.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApproxTokenizer;
+
+/// Average characters per token for ASCII word runs.
+const CHARS_PER_TOKEN: usize = 4;
+
+impl Tokenizer for ApproxTokenizer {
+    fn count(&self, text: &str) -> usize {
+        let mut tokens = 0;
+        let mut run: usize = 0;
+
+        for c in text.chars() {
+            if c.is_ascii_alphanumeric() {
+                run += 1;
+                continue;
+            }
+            if run > 0 {
+                tokens += run.div_ceil(CHARS_PER_TOKEN);
+                run = 0;
+            }
+            if c == '\n' || !c.is_whitespace() {
+                tokens += 1;
+            }
+        }
+        tokens + run.div_ceil(CHARS_PER_TOKEN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approx_counts() {
+        let t = ApproxTokenizer;
+        assert_eq!(t.count(""), 0);
+        assert_eq!(t.count("hello"), 2);
+        assert_eq!(t.count("Hi, you!"), 4);
+        // Each CJK character is its own token
+        assert_eq!(t.count("你好"), 2);
+        // Code and JSON are denser than prose per word
+        assert!(t.count(r#"{"a":[1,2]}"#) > r#"{"a":[1,2]}"#.split_whitespace().count() * 2);
+    }
+
+    #[test]
+    fn test_message_includes_overhead_and_tool_calls() {
+        let t = ApproxTokenizer;
+        let plain = ChatMessage::assistant("ok");
+        assert_eq!(t.count_message(&plain), MESSAGE_OVERHEAD_TOKENS + 1);
+
+        let mut with_call = ChatMessage::assistant("ok");
+        with_call.tool_calls = Some(vec![crate::llm::ToolCall {
+            id: "1".into(),
+            name: "echo".into(),
+            arguments: serde_json::json!({ "message": "hi" }),
+            thought_signature: None,
+        }]);
+        assert!(t.count_message(&with_call) > t.count_message(&plain));
+    }
+}
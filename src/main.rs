@@ -9,9 +9,10 @@ use ironclaw::db::Database;
 
 use ironclaw::{
     agent::{Agent, AgentDeps, SessionManager},
+    backup::{BackupError, BackupManager, spawn_backup_loop},
     channels::{
-        ChannelManager, GatewayChannel, HttpChannel, ReplChannel, WebhookServer,
-        WebhookServerConfig,
+        ApiChannel, AttachmentStore, ChannelManager, EmailChannel, GatewayChannel, HttpChannel,
+        PushChannel, ReplChannel, WebhookServer, WebhookServerConfig,
         wasm::{
             RegisteredEndpoint, SharedWasmChannel, WasmChannelLoader, WasmChannelRouter,
             WasmChannelRuntime, WasmChannelRuntimeConfig, create_wasm_channel_router,
@@ -19,27 +20,36 @@ use ironclaw::{
         web::log_layer::{LogBroadcaster, WebLogLayer},
     },
     cli::{
-        Cli, Command, run_mcp_command, run_memory_command, run_status_command, run_tool_command,
+        Cli, Command, run_backup_command, run_doctor_command, run_mcp_command, run_memory_command,
+        run_status_command, run_tool_command,
     },
-    config::Config,
+    config::{Config, ConfigLayers},
     context::ContextManager,
-    extensions::ExtensionManager,
+    extensions::{ExtensionManager, MarketplaceClient, marketplace::spawn_update_check_loop},
     history::Store,
-    llm::{SessionConfig, create_llm_provider, create_session_manager},
+    llm::{
+        ModelCatalog, SessionConfig, create_llm_provider, create_session_manager,
+        default_catalog_path, spawn_catalog_refresh_loop,
+    },
     orchestrator::{
         ContainerJobConfig, ContainerJobManager, OrchestratorApi, TokenStore,
         api::OrchestratorState,
     },
-    safety::SafetyLayer,
+    safety::{KeywordClassifier, SafetyLayer},
     secrets::{PostgresSecretsStore, SecretsCrypto, SecretsStore},
     settings::Settings,
     setup::{SetupConfig, SetupWizard},
+    tenancy::TenantDirectory,
     tools::{
         ToolRegistry,
         mcp::{McpClient, McpSessionManager, config::load_mcp_servers, is_authenticated},
         wasm::{WasmToolLoader, WasmToolRuntime},
     },
-    workspace::{EmbeddingProvider, NearAiEmbeddings, OpenAiEmbeddings, Workspace},
+    transcription::Transcriber,
+    workspace::{
+        EmbeddingProvider, MemoryHistory, MemorySpaces, NearAiEmbeddings, OpenAiEmbeddings,
+        UserWorkspaces, Workspace,
+    },
 };
 
 #[tokio::main]
@@ -62,6 +72,7 @@ async fn main() -> anyhow::Result<()> {
         Some(Command::Config(config_cmd)) => {
             // Config commands don't need logging setup
             return ironclaw::cli::run_config_command(config_cmd.clone())
+                .await
                 .map_err(|e| anyhow::anyhow!("{}", e));
         }
         Some(Command::Mcp(mcp_cmd)) => {
@@ -127,6 +138,20 @@ async fn main() -> anyhow::Result<()> {
 
             return run_memory_command(mem_cmd.clone(), store.pool(), embeddings).await;
         }
+        Some(Command::Backup(backup_cmd)) => {
+            tracing_subscriber::fmt()
+                .with_env_filter(
+                    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+                )
+                .init();
+
+            let _ = dotenvy::dotenv();
+            let config = Config::from_env().map_err(|e| anyhow::anyhow!("{}", e))?;
+            let store = Store::new(&config.database).await?;
+            store.run_migrations().await?;
+
+            return run_backup_command(backup_cmd.clone(), &config, store.pool()).await;
+        }
         Some(Command::Status) => {
             let _ = dotenvy::dotenv();
             tracing_subscriber::fmt()
@@ -137,9 +162,22 @@ async fn main() -> anyhow::Result<()> {
 
             return run_status_command().await;
         }
+        Some(Command::Doctor { json, user }) => {
+            let _ = dotenvy::dotenv();
+            tracing_subscriber::fmt()
+                .with_env_filter(
+                    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+                )
+                .init();
+
+            return run_doctor_command(*json, user).await;
+        }
         Some(Command::Onboard {
             skip_auth,
             channels_only,
+            profile,
+            from_file,
+            upgrade,
         }) => {
             // Load .env before running onboarding wizard
             let _ = dotenvy::dotenv();
@@ -147,12 +185,15 @@ async fn main() -> anyhow::Result<()> {
             let config = SetupConfig {
                 skip_auth: *skip_auth,
                 channels_only: *channels_only,
+                profile: *profile,
+                from_file: from_file.clone(),
+                upgrade: *upgrade,
             };
             let mut wizard = SetupWizard::with_config(config);
             wizard.run().await?;
             return Ok(());
         }
-        None | Some(Command::Run) => {
+        None | Some(Command::Run) | Some(Command::Chat) => {
             // Continue to run agent
         }
     }
@@ -196,24 +237,44 @@ async fn main() -> anyhow::Result<()> {
     // Ensure we're authenticated before proceeding (may trigger login flow)
     session.ensure_authenticated().await?;
 
-    // Initialize tracing
-    let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new("ironclaw=info,tower_http=debug"));
+    // Initialize tracing (`chat` keeps the terminal to the conversation)
+    let default_filter = if matches!(cli.command, Some(Command::Chat)) {
+        "ironclaw=warn"
+    } else {
+        "ironclaw=info,tower_http=debug"
+    };
+    let env_filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
 
     // Create log broadcaster before tracing init so the WebLogLayer can capture all events.
     // This gets wired to the gateway's /api/logs/events SSE endpoint later.
     let log_broadcaster = Arc::new(LogBroadcaster::new());
 
+    // Optional OTLP trace export; the guard flushes buffered spans on exit.
+    let (otel_layer, _otel_guard) =
+        match ironclaw::observability::init_tracer(&config.observability) {
+            Ok(Some((tracer, guard))) => (
+                Some(tracing_opentelemetry::layer().with_tracer(tracer)),
+                Some(guard),
+            ),
+            Ok(None) => (None, None),
+            Err(e) => {
+                eprintln!("Warning: OpenTelemetry export disabled: {}", e);
+                (None, None)
+            }
+        };
+
     tracing_subscriber::registry()
         .with(env_filter)
         .with(tracing_subscriber::fmt::layer().with_target(false))
         .with(WebLogLayer::new(Arc::clone(&log_broadcaster)))
+        .with(otel_layer)
         .init();
 
     // Create CLI channel
     let repl_channel = if let Some(ref msg) = cli.message {
         Some(ReplChannel::with_message(msg.clone()))
-    } else if config.channels.cli.enabled {
+    } else if config.channels.cli.enabled || cli.is_cli_only() {
         Some(ReplChannel::new())
     } else {
         None
@@ -234,17 +295,63 @@ async fn main() -> anyhow::Result<()> {
         Some(Arc::new(store))
     };
 
+    // Settings stored in the database are the top config layer
+    let config = match store {
+        Some(ref store) => {
+            let settings = store.get_all_settings("default").await?;
+            let layers = ConfigLayers::load()?.with_database_settings(&settings);
+            if layers.has_database_overrides() {
+                tracing::info!("Applying configuration overrides from the database");
+                Config::resolve(layers)?.0
+            } else {
+                config
+            }
+        }
+        None => config,
+    };
+
     // Initialize LLM provider (clone session so we can reuse it for embeddings)
     let llm = create_llm_provider(&config.llm, session.clone())?;
     tracing::info!("LLM provider initialized: {}", llm.model_name());
 
+    let model_catalog = Arc::new(ModelCatalog::load(default_catalog_path()));
+    if config.llm.catalog_refresh_secs > 0 {
+        spawn_catalog_refresh_loop(
+            Arc::clone(&model_catalog),
+            Arc::clone(&llm),
+            std::time::Duration::from_secs(config.llm.catalog_refresh_secs),
+        );
+    }
+
     // Initialize safety layer
-    let safety = Arc::new(SafetyLayer::new(&config.safety));
+    let mut safety = SafetyLayer::new(&config.safety);
+    if let Some(ref path) = config.safety.classifier_rules {
+        let classifier = KeywordClassifier::load(path).map_err(|e| {
+            anyhow::anyhow!("Failed to load classifier rules {}: {}", path.display(), e)
+        })?;
+        safety = safety.with_classifier(Arc::new(classifier));
+        tracing::info!("Content classifier rules loaded from {}", path.display());
+    }
+    let safety = Arc::new(safety);
     tracing::info!("Safety layer initialized");
 
+    let transcriber =
+        match Transcriber::from_config(&config.transcription, AttachmentStore::default()) {
+            Ok(Some(transcriber)) => {
+                tracing::info!("Voice note transcription enabled");
+                Some(Arc::new(transcriber))
+            }
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Voice note transcription disabled: {}", e);
+                None
+            }
+        };
+
     // Initialize tool registry
     let tools = Arc::new(ToolRegistry::new());
     tools.register_builtin_tools();
+    tools.register_meeting_brief_tool();
     tracing::info!("Registered {} built-in tools", tools.count());
 
     // Create embeddings provider if configured
@@ -316,14 +423,44 @@ async fn main() -> anyhow::Result<()> {
         None
     };
 
-    // Register memory tools if database is available
-    if let Some(ref store) = store {
+    // Workspaces shared by memory tools and the agent; with tenancy enabled
+    // each user gets their own, next to named spaces shared by a team
+    let workspaces = store.as_ref().map(|store| {
         let mut workspace = Workspace::new("default", store.pool());
         if let Some(ref emb) = embeddings {
             workspace = workspace.with_embeddings(emb.clone());
         }
+        if config.memory_history.enabled {
+            match MemoryHistory::open(&config.memory_history.dir) {
+                Ok(history) => workspace = workspace.with_history(Arc::new(history)),
+                Err(e) => tracing::warn!("Memory history disabled: {}", e),
+            }
+        }
         let workspace = Arc::new(workspace);
-        tools.register_memory_tools(workspace, llm.clone());
+        let spaces = Arc::new(MemorySpaces::new(Arc::clone(&workspace), store.pool()));
+        let workspaces = if config.tenancy.enabled {
+            UserWorkspaces::isolated(workspace, store.pool())
+        } else {
+            UserWorkspaces::shared(workspace)
+        };
+        Arc::new(workspaces.with_spaces(spaces))
+    });
+
+    // Roles and quotas; refuse to start rather than serve everyone unchecked
+    let tenants = Arc::new(
+        TenantDirectory::from_config(&config.tenancy)
+            .map_err(|e| anyhow::anyhow!("{}: {}", config.tenancy.tenants_path.display(), e))?,
+    );
+    if tenants.is_enabled() {
+        tracing::info!(
+            "Multi-user tenancy enabled (tenants from {})",
+            config.tenancy.tenants_path.display()
+        );
+    }
+
+    // Register memory tools if database is available
+    if let Some(ref workspaces) = workspaces {
+        tools.register_memory_tools(Arc::clone(workspaces), llm.clone());
     }
 
     // Register builder tool if enabled
@@ -498,7 +635,7 @@ async fn main() -> anyhow::Result<()> {
 
     // Create extension manager for in-chat discovery/install/auth/activate
     let extension_manager = if let Some(ref secrets) = secrets_store {
-        let manager = Arc::new(ExtensionManager::new(
+        let mut manager = ExtensionManager::new(
             Arc::clone(&mcp_session_manager),
             Arc::clone(secrets),
             Arc::clone(&tools),
@@ -506,9 +643,31 @@ async fn main() -> anyhow::Result<()> {
             config.wasm.tools_dir.clone(),
             config.channels.wasm_channels_dir.clone(),
             config.tunnel.public_url.clone(),
+            // Extension installs and auth are admin-only, so their credentials
+            // belong to the deployment rather than any one tenant
             "default".to_string(),
-        ));
+        );
+        if let Some(ref index_url) = config.marketplace.index_url {
+            if config.marketplace.trusted_keys.is_empty() {
+                tracing::warn!(
+                    "MARKETPLACE_TRUSTED_KEYS is not set: marketplace signatures won't be \
+                     checked, so installs trust whatever the index at {} serves",
+                    index_url
+                );
+            }
+            match MarketplaceClient::new(index_url, &config.marketplace.trusted_keys) {
+                Ok(client) => manager = manager.with_marketplace(client),
+                Err(e) => tracing::warn!("Extension marketplace disabled: {}", e),
+            }
+        }
+        let manager = Arc::new(manager);
         tools.register_extension_tools(Arc::clone(&manager));
+        if config.marketplace.index_url.is_some() && config.marketplace.update_interval_secs > 0 {
+            spawn_update_check_loop(
+                Arc::clone(&manager),
+                std::time::Duration::from_secs(config.marketplace.update_interval_secs),
+            );
+        }
         tracing::info!("Extension manager initialized with in-chat discovery tools");
         Some(manager)
     } else {
@@ -540,7 +699,10 @@ async fn main() -> anyhow::Result<()> {
     let mut webhook_routes: Vec<axum::Router> = Vec::new();
 
     // Load WASM channels and register their webhook routes.
-    if config.channels.wasm_channels_enabled && config.channels.wasm_channels_dir.exists() {
+    if !cli.is_cli_only()
+        && config.channels.wasm_channels_enabled
+        && config.channels.wasm_channels_dir.exists()
+    {
         match WasmChannelRuntime::new(WasmChannelRuntimeConfig::default()) {
             Ok(runtime) => {
                 let runtime = Arc::new(runtime);
@@ -572,6 +734,7 @@ async fn main() -> anyhow::Result<()> {
 
                             let secret_header =
                                 loaded.webhook_secret_header().map(|s| s.to_string());
+                            let verification = loaded.webhook_verification();
 
                             let webhook_path = format!("/webhook/{}", channel_name);
                             let endpoints = vec![RegisteredEndpoint {
@@ -626,6 +789,9 @@ async fn main() -> anyhow::Result<()> {
                                     secret_header,
                                 )
                                 .await;
+                            wasm_router
+                                .set_verification(&channel_name, verification)
+                                .await;
                             has_webhook_channels = true;
 
                             if let Some(ref secrets) = secrets_store {
@@ -688,9 +854,12 @@ async fn main() -> anyhow::Result<()> {
     // Extract its routes for the unified server; the channel itself just
     // provides the mpsc stream.
     let mut webhook_server_addr: Option<std::net::SocketAddr> = None;
-    if !cli.cli_only {
+    if !cli.is_cli_only() {
         if let Some(ref http_config) = config.channels.http {
-            let http_channel = HttpChannel::new(http_config.clone());
+            let mut http_channel = HttpChannel::new(http_config.clone());
+            if let Some(ref ext_mgr) = extension_manager {
+                http_channel = http_channel.with_oauth(ext_mgr.oauth_flows());
+            }
             webhook_routes.push(http_channel.routes());
             let (host, port) = http_channel.addr();
             webhook_server_addr = Some(
@@ -705,6 +874,33 @@ async fn main() -> anyhow::Result<()> {
                 http_config.port
             );
         }
+
+        if let Some(ref api_config) = config.channels.api {
+            let api_channel = ApiChannel::new(api_config.clone());
+            webhook_routes.push(api_channel.routes());
+            channels.add(Box::new(api_channel));
+            tracing::info!("API channel enabled at /webhook/api");
+        }
+
+        if let Some(ref email_config) = config.channels.email {
+            match EmailChannel::new(email_config.clone()) {
+                Ok(email_channel) => {
+                    channels.add(Box::new(email_channel));
+                    tracing::info!("Email channel enabled for {}", email_config.username);
+                }
+                Err(e) => tracing::error!("Failed to set up email channel: {}", e),
+            }
+        }
+    }
+
+    // Push channels only send, so they're useful in CLI-only mode too
+    if let Some(ref ntfy_config) = config.channels.ntfy {
+        channels.add(Box::new(PushChannel::ntfy(ntfy_config.clone())));
+        tracing::info!("ntfy notifications enabled for topic {}", ntfy_config.topic);
+    }
+    if let Some(ref pushover_config) = config.channels.pushover {
+        channels.add(Box::new(PushChannel::pushover(pushover_config.clone())));
+        tracing::info!("Pushover notifications enabled");
     }
 
     // Start the unified webhook server if any routes were registered.
@@ -721,23 +917,16 @@ async fn main() -> anyhow::Result<()> {
         None
     };
 
-    // Create workspace for agent (shared with memory tools)
-    let workspace = if let Some(ref s) = store {
-        let mut ws = Workspace::new("default", s.pool());
-        if let Some(ref emb) = embeddings {
-            ws = ws.with_embeddings(emb.clone());
-        }
-        let ws = Arc::new(ws);
-
+    // The default workspace backs the heartbeat and the web gateway
+    let workspace = workspaces
+        .as_ref()
+        .map(|w| Arc::clone(w.default_workspace()));
+    if let Some(ref ws) = workspace {
         // Seed identity files from local filesystem if missing
         if let Err(e) = ws.seed_identity().await {
             tracing::warn!("Failed to seed workspace identity: {}", e);
         }
-
-        Some(ws)
-    } else {
-        None
-    };
+    }
 
     // Backfill embeddings if we just enabled the provider
     if let (Some(ws), Some(_)) = (&workspace, &embeddings) {
@@ -788,6 +977,10 @@ async fn main() -> anyhow::Result<()> {
             claude_code_max_turns: config.claude_code.max_turns,
             claude_code_memory_limit_mb: config.claude_code.memory_limit_mb,
             claude_code_allowed_tools: config.claude_code.allowed_tools.clone(),
+            workspace_quota: config.sandbox.workspace_quota(),
+            scratch_retention: std::time::Duration::from_secs(
+                config.sandbox.scratch_retention_secs,
+            ),
         };
         let jm = Arc::new(ContainerJobManager::new(job_config, token_store.clone()));
 
@@ -819,9 +1012,19 @@ async fn main() -> anyhow::Result<()> {
         container_job_manager.clone(),
         store.clone().map(|s| s as Arc<dyn Database>),
     );
+    tools.register_bundle_tool(
+        Arc::clone(&context_manager),
+        store.clone().map(|s| s as Arc<dyn Database>),
+    );
+
+    if let Some(ref store) = store {
+        tools.register_variable_tool(Arc::clone(store) as Arc<dyn Database>);
+    }
 
     // Add web gateway channel if configured
-    if let Some(ref gw_config) = config.channels.gateway {
+    if let Some(ref gw_config) = config.channels.gateway
+        && !cli.is_cli_only()
+    {
         let mut gw = GatewayChannel::new(gw_config.clone());
         if let Some(ref ws) = workspace {
             gw = gw.with_workspace(Arc::clone(ws));
@@ -871,14 +1074,38 @@ async fn main() -> anyhow::Result<()> {
         channels.add(Box::new(gw));
     }
 
+    // Scheduled encrypted backups (needs the database and the master key)
+    if config.backup.enabled {
+        match (&store, config.secrets.master_key()) {
+            (Some(store), Some(master_key)) => match SecretsCrypto::new(master_key.clone()) {
+                Ok(crypto) => {
+                    let manager =
+                        BackupManager::new(store.pool(), Arc::new(crypto), config.backup.clone());
+                    spawn_backup_loop(Arc::new(manager), Arc::clone(&tools));
+                    tracing::info!(
+                        "Backups enabled (every {}s to {})",
+                        config.backup.interval_secs,
+                        config.backup.dir.display()
+                    );
+                }
+                Err(e) => tracing::warn!("Backups disabled: {}", e),
+            },
+            (None, _) => tracing::warn!("Backups disabled: no database"),
+            (_, None) => tracing::warn!("Backups disabled: {}", BackupError::NoMasterKey),
+        }
+    }
+
     // Create and run the agent
     let deps = AgentDeps {
         store,
         llm,
+        model_catalog,
         safety,
-        tools,
-        workspace,
+        tools: Arc::clone(&tools),
+        workspace: workspaces,
         extension_manager,
+        tenants,
+        transcriber,
     };
     let agent = Agent::new(
         config.agent.clone(),
@@ -889,6 +1116,9 @@ async fn main() -> anyhow::Result<()> {
         Some(session_manager),
     );
 
+    // The diagnostics tool reads the agent's live engine state
+    tools.register_sneed_status_tool(agent.stakes_engines(), agent.sovereign_grid());
+
     tracing::info!("Agent initialized, starting main loop...");
 
     // Run the agent (blocks until shutdown)
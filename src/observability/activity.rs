@@ -0,0 +1,81 @@
+//! Per-task log of the tools run and API requests made, for reporting what a
+//! piece of background work actually did.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// Tools run and outbound API requests made while a future ran.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Activity {
+    /// Tool executions in order, with whether each succeeded.
+    pub tool_calls: Vec<(String, bool)>,
+    /// HTTP requests those tools made to external APIs.
+    pub api_calls: u32,
+}
+
+impl Activity {
+    fn merge(&mut self, other: &Activity) {
+        self.tool_calls.extend(other.tool_calls.iter().cloned());
+        self.api_calls += other.api_calls;
+    }
+}
+
+tokio::task_local! {
+    static ACTIVITY: Arc<Mutex<Activity>>;
+}
+
+/// Run `fut`, returning the tools it ran and API requests they made.
+///
+/// Collections nest: activity is also reported to any enclosing collection.
+/// Like [`crate::llm::collect_usage`], this doesn't follow work onto
+/// `tokio::spawn`ed tasks.
+pub async fn collect_activity<F: Future>(fut: F) -> (F::Output, Activity) {
+    let log = Arc::new(Mutex::new(Activity::default()));
+    let output = ACTIVITY.scope(Arc::clone(&log), fut).await;
+    let activity = std::mem::take(&mut *log.lock().unwrap_or_else(|e| e.into_inner()));
+    with_current(|current| current.merge(&activity));
+    (output, activity)
+}
+
+/// Add API requests made by the running tool to the current collection.
+pub fn record_api_calls(count: u32) {
+    if count > 0 {
+        with_current(|current| current.api_calls += count);
+    }
+}
+
+pub(super) fn record_tool(tool: &str, success: bool) {
+    with_current(|current| current.tool_calls.push((tool.to_string(), success)));
+}
+
+fn with_current(f: impl FnOnce(&mut Activity)) {
+    let _ = ACTIVITY.try_with(|log| f(&mut log.lock().unwrap_or_else(|e| e.into_inner())));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_collections_nest() {
+        let (((), inner), outer) = collect_activity(async {
+            record_tool("http", true);
+            let inner = collect_activity(async {
+                record_tool("google_docs", false);
+                record_api_calls(3);
+            })
+            .await;
+            record_api_calls(0);
+            inner
+        })
+        .await;
+
+        assert_eq!(inner.tool_calls, vec![("google_docs".to_string(), false)]);
+        assert_eq!(inner.api_calls, 3);
+        assert_eq!(outer.tool_calls.len(), 2);
+        assert_eq!(outer.api_calls, 3);
+
+        // Outside any collection, recording is a no-op
+        record_tool("http", true);
+    }
+}
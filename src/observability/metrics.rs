@@ -0,0 +1,273 @@
+//! In-process metrics registry rendered in the Prometheus text format.
+//!
+//! Series are keyed by metric name plus label set and created on first use,
+//! so call sites don't need to pre-register anything.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+/// Histogram bucket upper bounds, in seconds.
+pub const DEFAULT_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl Kind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+            Self::Histogram => "histogram",
+        }
+    }
+}
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Debug)]
+enum Series {
+    Value(f64),
+    Histogram {
+        /// Per-bucket (non-cumulative) counts, parallel to [`DEFAULT_BUCKETS`].
+        buckets: Vec<u64>,
+        sum: f64,
+        count: u64,
+    },
+}
+
+#[derive(Debug)]
+struct Family {
+    help: &'static str,
+    kind: Kind,
+    series: BTreeMap<Labels, Series>,
+}
+
+/// Counters, gauges and histograms for the `/metrics` endpoint.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    families: Mutex<BTreeMap<&'static str, Family>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `by` to a counter.
+    pub fn inc_counter(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&'static str, &str)],
+        by: f64,
+    ) {
+        self.with_series(name, help, Kind::Counter, labels, |series| {
+            if let Series::Value(v) = series {
+                *v += by;
+            }
+        });
+    }
+
+    /// Set a gauge to `value`.
+    pub fn set_gauge(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&'static str, &str)],
+        value: f64,
+    ) {
+        self.with_series(name, help, Kind::Gauge, labels, |series| {
+            if let Series::Value(v) = series {
+                *v = value;
+            }
+        });
+    }
+
+    /// Record one observation in a histogram.
+    pub fn observe(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&'static str, &str)],
+        value: f64,
+    ) {
+        self.with_series(name, help, Kind::Histogram, labels, |series| {
+            if let Series::Histogram {
+                buckets,
+                sum,
+                count,
+            } = series
+            {
+                if let Some(i) = DEFAULT_BUCKETS.iter().position(|b| value <= *b) {
+                    buckets[i] += 1;
+                }
+                *sum += value;
+                *count += 1;
+            }
+        });
+    }
+
+    fn with_series(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        kind: Kind,
+        labels: &[(&'static str, &str)],
+        f: impl FnOnce(&mut Series),
+    ) {
+        let mut families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let family = families.entry(name).or_insert_with(|| Family {
+            help,
+            kind,
+            series: BTreeMap::new(),
+        });
+        if family.kind != kind {
+            tracing::warn!(
+                "Metric {} used as both {:?} and {:?}",
+                name,
+                family.kind,
+                kind
+            );
+            return;
+        }
+
+        let key: Labels = labels.iter().map(|(k, v)| (*k, v.to_string())).collect();
+        let series = family.series.entry(key).or_insert_with(|| match kind {
+            Kind::Histogram => Series::Histogram {
+                buckets: vec![0; DEFAULT_BUCKETS.len()],
+                sum: 0.0,
+                count: 0,
+            },
+            _ => Series::Value(0.0),
+        });
+        f(series);
+    }
+
+    /// Render every series in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+
+        for (name, family) in families.iter() {
+            let _ = writeln!(out, "# HELP {} {}", name, family.help);
+            let _ = writeln!(out, "# TYPE {} {}", name, family.kind.as_str());
+
+            for (labels, series) in &family.series {
+                match series {
+                    Series::Value(v) => {
+                        let _ = writeln!(out, "{}{} {}", name, format_labels(labels, None), v);
+                    }
+                    Series::Histogram {
+                        buckets,
+                        sum,
+                        count,
+                    } => {
+                        let mut cumulative = 0;
+                        for (bound, n) in DEFAULT_BUCKETS.iter().zip(buckets) {
+                            cumulative += n;
+                            let le = bound.to_string();
+                            let _ = writeln!(
+                                out,
+                                "{}_bucket{} {}",
+                                name,
+                                format_labels(labels, Some(&le)),
+                                cumulative
+                            );
+                        }
+                        let _ = writeln!(
+                            out,
+                            "{}_bucket{} {}",
+                            name,
+                            format_labels(labels, Some("+Inf")),
+                            count
+                        );
+                        let _ =
+                            writeln!(out, "{}_sum{} {}", name, format_labels(labels, None), sum);
+                        let _ = writeln!(
+                            out,
+                            "{}_count{} {}",
+                            name,
+                            format_labels(labels, None),
+                            count
+                        );
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+        .collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{}\"", le));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters_and_gauges() {
+        let registry = MetricsRegistry::new();
+        registry.inc_counter("requests_total", "Requests.", &[("outcome", "ok")], 1.0);
+        registry.inc_counter("requests_total", "Requests.", &[("outcome", "ok")], 2.0);
+        registry.set_gauge("queue_depth", "Depth.", &[], 7.0);
+        registry.set_gauge("queue_depth", "Depth.", &[], 3.0);
+
+        let text = registry.render();
+        assert!(text.contains("# TYPE requests_total counter"));
+        assert!(text.contains("requests_total{outcome=\"ok\"} 3"));
+        assert!(text.contains("queue_depth 3"));
+    }
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let registry = MetricsRegistry::new();
+        for v in [0.003, 0.2, 90.0, 500.0] {
+            registry.observe("latency_seconds", "Latency.", &[("tool", "echo")], v);
+        }
+
+        let text = registry.render();
+        assert!(text.contains("latency_seconds_bucket{tool=\"echo\",le=\"0.005\"} 1"));
+        assert!(text.contains("latency_seconds_bucket{tool=\"echo\",le=\"0.25\"} 2"));
+        assert!(text.contains("latency_seconds_bucket{tool=\"echo\",le=\"120\"} 3"));
+        assert!(text.contains("latency_seconds_bucket{tool=\"echo\",le=\"+Inf\"} 4"));
+        assert!(text.contains("latency_seconds_count{tool=\"echo\"} 4"));
+    }
+
+    #[test]
+    fn test_label_values_are_escaped() {
+        let registry = MetricsRegistry::new();
+        registry.inc_counter("errors_total", "Errors.", &[("component", "a\"b\\c")], 1.0);
+        assert!(
+            registry
+                .render()
+                .contains(r#"errors_total{component="a\"b\\c"} 1"#)
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Tracing and metrics for production monitoring.
+//!
+//! - **Traces**: the core pipeline (channel receive → router → worker → tool →
+//!   sandbox proxy → LLM) runs inside `tracing` spans. When an OTLP endpoint is
+//!   configured those spans are exported through OpenTelemetry, and the active
+//!   trace ID is stamped onto persisted job events.
+//! - **Metrics**: counters, gauges and histograms are kept in a process-wide
+//!   [`MetricsRegistry`] and served in the Prometheus text format by the web
+//!   gateway at `/metrics`.
+//! - **Activity**: [`collect_activity`] gathers the tools a task ran and the
+//!   API requests they made, for per-run reports.
+
+mod activity;
+mod metrics;
+mod otel;
+
+pub use activity::{Activity, collect_activity, record_api_calls};
+pub use metrics::{DEFAULT_BUCKETS, MetricsRegistry};
+pub use otel::{OtelGuard, current_trace_id, init_tracer};
+
+use std::future::Future;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use tracing::Instrument;
+
+static REGISTRY: LazyLock<MetricsRegistry> = LazyLock::new(MetricsRegistry::new);
+
+/// The process-wide metrics registry.
+pub fn metrics() -> &'static MetricsRegistry {
+    &REGISTRY
+}
+
+/// Count a message received from a channel.
+pub fn record_message_received(channel: &str) {
+    metrics().inc_counter(
+        "ironclaw_messages_received_total",
+        "Messages received from channels.",
+        &[("channel", channel)],
+        1.0,
+    );
+}
+
+/// Record a tool execution and its latency.
+pub fn record_tool_call(tool: &str, success: bool, elapsed: Duration) {
+    let outcome = if success { "success" } else { "error" };
+    metrics().inc_counter(
+        "ironclaw_tool_calls_total",
+        "Tool executions by outcome.",
+        &[("tool", tool), ("outcome", outcome)],
+        1.0,
+    );
+    metrics().observe(
+        "ironclaw_tool_duration_seconds",
+        "Tool execution latency.",
+        &[("tool", tool)],
+        elapsed.as_secs_f64(),
+    );
+    if !success {
+        record_error("tool");
+    }
+}
+
+/// Run a tool execution inside a `tool.execute` span and record its outcome,
+/// including in any [`collect_activity`] collection.
+pub async fn instrument_tool<T, E>(
+    tool: &str,
+    execution: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let start = Instant::now();
+    let result = execution
+        .instrument(tracing::info_span!("tool.execute", tool = %tool))
+        .await;
+    record_tool_call(tool, result.is_ok(), start.elapsed());
+    activity::record_tool(tool, result.is_ok());
+    result
+}
+
+/// Record an LLM call, its latency and token usage.
+pub fn record_llm_call(
+    model: &str,
+    success: bool,
+    elapsed: Duration,
+    input_tokens: u32,
+    output_tokens: u32,
+) {
+    let outcome = if success { "success" } else { "error" };
+    metrics().inc_counter(
+        "ironclaw_llm_requests_total",
+        "LLM requests by outcome.",
+        &[("model", model), ("outcome", outcome)],
+        1.0,
+    );
+    metrics().observe(
+        "ironclaw_llm_request_duration_seconds",
+        "LLM request latency.",
+        &[("model", model)],
+        elapsed.as_secs_f64(),
+    );
+    if success {
+        metrics().inc_counter(
+            "ironclaw_llm_tokens_total",
+            "LLM tokens consumed.",
+            &[("model", model), ("kind", "input")],
+            input_tokens as f64,
+        );
+        metrics().inc_counter(
+            "ironclaw_llm_tokens_total",
+            "LLM tokens consumed.",
+            &[("model", model), ("kind", "output")],
+            output_tokens as f64,
+        );
+    } else {
+        record_error("llm");
+    }
+}
+
+/// Count a corrective retry after the model called an unknown tool or
+/// action, by whether the retried calls were valid.
+pub fn record_tool_guardrail(outcome: &str) {
+    metrics().inc_counter(
+        "ironclaw_tool_guardrail_retries_total",
+        "Corrective retries after calls to unknown tools or actions, by outcome.",
+        &[("outcome", outcome)],
+        1.0,
+    );
+}
+
+/// Count a request through the sandbox network proxy.
+pub fn record_proxy_request(decision: &str) {
+    metrics().inc_counter(
+        "ironclaw_proxy_requests_total",
+        "Sandbox proxy requests by policy decision.",
+        &[("decision", decision)],
+        1.0,
+    );
+}
+
+/// Count an error in a pipeline component.
+pub fn record_error(component: &str) {
+    metrics().inc_counter(
+        "ironclaw_errors_total",
+        "Errors by pipeline component.",
+        &[("component", component)],
+        1.0,
+    );
+}
+
+/// Set the current depth of a work queue.
+pub fn set_queue_depth(queue: &str, depth: usize) {
+    metrics().set_gauge(
+        "ironclaw_queue_depth",
+        "Items currently queued or in flight.",
+        &[("queue", queue)],
+        depth as f64,
+    );
+}
+
+/// Add the active trace ID to a job event payload, if there is one.
+pub fn with_trace_id(data: &serde_json::Value) -> serde_json::Value {
+    let mut data = data.clone();
+    if let (Some(trace_id), Some(obj)) = (current_trace_id(), data.as_object_mut()) {
+        obj.entry("trace_id")
+            .or_insert_with(|| serde_json::Value::String(trace_id));
+    }
+    data
+}
@@ -0,0 +1,62 @@
+//! OpenTelemetry trace export over OTLP.
+
+use opentelemetry::KeyValue;
+use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::config::ObservabilityConfig;
+
+/// Flushes and shuts down the tracer provider when dropped.
+pub struct OtelGuard {
+    provider: TracerProvider,
+}
+
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Failed to flush OpenTelemetry spans: {}", e);
+        }
+    }
+}
+
+/// Build an OTLP tracer if an endpoint is configured.
+///
+/// Returns `None` when export is disabled. Keep the guard alive for the
+/// lifetime of the process so buffered spans are flushed on exit.
+pub fn init_tracer(config: &ObservabilityConfig) -> Result<Option<(Tracer, OtelGuard)>, String> {
+    let Some(ref endpoint) = config.otlp_endpoint else {
+        return Ok(None);
+    };
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint.clone())
+        .build()
+        .map_err(|e| format!("failed to build OTLP exporter for {}: {}", endpoint, e))?;
+
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_resource(Resource::new(vec![
+            KeyValue::new("service.name", config.service_name.clone()),
+            KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+        ]))
+        .build();
+
+    let tracer = provider.tracer("ironclaw");
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    Ok(Some((tracer, OtelGuard { provider })))
+}
+
+/// Trace ID of the current span, when spans are being exported.
+pub fn current_trace_id() -> Option<String> {
+    let context = tracing::Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    span_context
+        .is_valid()
+        .then(|| span_context.trace_id().to_string())
+}
@@ -141,6 +141,7 @@ async fn llm_complete(
         temperature: req.temperature,
         stop_sequences: req.stop_sequences,
         cache_id: None,
+        model: None,
     };
 
     let resp = state.llm.complete(completion_req).await.map_err(|e| {
@@ -168,6 +169,7 @@ async fn llm_complete_with_tools(
         temperature: req.temperature,
         tool_choice: req.tool_choice,
         cache_id: None,
+        model: None,
     };
 
     let resp = state.llm.complete_with_tools(tool_req).await.map_err(|e| {
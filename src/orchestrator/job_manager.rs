@@ -6,6 +6,7 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
 use tokio::sync::RwLock;
@@ -14,6 +15,7 @@ use uuid::Uuid;
 use crate::error::OrchestratorError;
 use crate::orchestrator::auth::TokenStore;
 use crate::sandbox::connect_docker;
+use crate::sandbox::quota::{self, WorkspaceQuota};
 
 /// Which mode a sandbox container runs in.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -60,6 +62,10 @@ pub struct ContainerJobConfig {
     pub claude_code_memory_limit_mb: u64,
     /// Allowed tool patterns for Claude Code (passed as CLAUDE_CODE_ALLOWED_TOOLS env var).
     pub claude_code_allowed_tools: Vec<String>,
+    /// Disk quota for each job's workspace and scratch space.
+    pub workspace_quota: WorkspaceQuota,
+    /// How long a finished job's scratch space is kept before removal.
+    pub scratch_retention: Duration,
 }
 
 impl Default for ContainerJobConfig {
@@ -74,6 +80,8 @@ impl Default for ContainerJobConfig {
             claude_code_max_turns: 50,
             claude_code_memory_limit_mb: 4096,
             claude_code_allowed_tools: crate::config::ClaudeCodeConfig::default().allowed_tools,
+            workspace_quota: WorkspaceQuota::default(),
+            scratch_retention: Duration::from_secs(3600),
         }
     }
 }
@@ -241,10 +249,42 @@ impl ContainerJobManager {
                     ),
                 });
             }
+            // Refuse to start a job whose workspace is already over quota
+            quota::measure(&canonical)
+                .and_then(|usage| self.config.workspace_quota.check(usage))
+                .map_err(|e| OrchestratorError::ContainerCreationFailed {
+                    job_id,
+                    reason: e.to_string(),
+                })?;
             binds.push(format!("{}:/workspace:rw", canonical.display()));
             env_vec.push("IRONCLAW_WORKSPACE=/workspace".to_string());
         }
 
+        // Per-job scratch space, removed after the job finishes (see sweep_scratch)
+        let scratch = quota::scratch_dir(&quota::scratch_base(), job_id).map_err(|e| {
+            OrchestratorError::ContainerCreationFailed {
+                job_id,
+                reason: format!("failed to create scratch dir: {}", e),
+            }
+        })?;
+        binds.push(format!("{}:/scratch:rw", scratch.display()));
+        env_vec.push("IRONCLAW_SCRATCH=/scratch".to_string());
+
+        // Let the in-container file tools enforce the same quota
+        let workspace_quota = self.config.workspace_quota;
+        env_vec.push(format!(
+            "IRONCLAW_WORKSPACE_QUOTA_BYTES={}",
+            workspace_quota.max_bytes
+        ));
+        env_vec.push(format!(
+            "IRONCLAW_WORKSPACE_MAX_FILES={}",
+            workspace_quota.max_files
+        ));
+        env_vec.push(format!(
+            "IRONCLAW_MAX_FILE_BYTES={}",
+            workspace_quota.max_file_bytes
+        ));
+
         // Claude Code mode: mount host ~/.claude read-only for auth,
         // and pass the tool allowlist so the bridge can write settings.json.
         if mode == JobMode::ClaudeCode {
@@ -267,10 +307,10 @@ impl ContainerJobManager {
 
         // Create the container
         use bollard::container::{Config, CreateContainerOptions};
-        use bollard::models::HostConfig;
+        use bollard::models::{HostConfig, ResourcesUlimits};
 
         let host_config = HostConfig {
-            binds: if binds.is_empty() { None } else { Some(binds) },
+            binds: Some(binds),
             memory: Some((memory_mb * 1024 * 1024) as i64),
             cpu_shares: Some(self.config.cpu_shares as i64),
             network_mode: Some("bridge".to_string()),
@@ -278,10 +318,19 @@ impl ContainerJobManager {
             cap_drop: Some(vec!["ALL".to_string()]),
             cap_add: Some(vec!["CHOWN".to_string()]),
             security_opt: Some(vec!["no-new-privileges:true".to_string()]),
+            // Bind mounts can't be size-limited, but single files can
+            ulimits: Some(vec![ResourcesUlimits {
+                name: Some("fsize".to_string()),
+                soft: Some(workspace_quota.max_file_bytes as i64),
+                hard: Some(workspace_quota.max_file_bytes as i64),
+            }]),
             tmpfs: Some(
-                [("/tmp".to_string(), "size=512M".to_string())]
-                    .into_iter()
-                    .collect(),
+                [(
+                    "/tmp".to_string(),
+                    format!("size=512M,nr_inodes={}", workspace_quota.max_files),
+                )]
+                .into_iter()
+                .collect(),
             ),
             ..Default::default()
         };
@@ -418,6 +467,8 @@ impl ContainerJobManager {
 
         tracing::info!(job_id = %job_id, "Stopped worker container");
 
+        self.sweep_scratch().await;
+
         Ok(())
     }
 
@@ -477,9 +528,34 @@ impl ContainerJobManager {
         self.token_store.revoke(job_id).await;
 
         tracing::info!(job_id = %job_id, "Completed worker container");
+
+        self.sweep_scratch().await;
+
         Ok(())
     }
 
+    /// Remove scratch space of finished jobs older than the retention period.
+    pub async fn sweep_scratch(&self) {
+        let running: Vec<Uuid> = self
+            .containers
+            .read()
+            .await
+            .values()
+            .filter(|h| matches!(h.state, ContainerState::Creating | ContainerState::Running))
+            .map(|h| h.job_id)
+            .collect();
+
+        match quota::sweep_scratch(
+            &quota::scratch_base(),
+            self.config.scratch_retention,
+            |id| running.contains(&id),
+        ) {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("Removed scratch space of {} finished jobs", n),
+            Err(e) => tracing::warn!(error = %e, "Failed to sweep job scratch space"),
+        }
+    }
+
     /// Remove a completed job handle from memory (called after result is read).
     pub async fn cleanup_job(&self, job_id: Uuid) {
         self.containers.write().await.remove(&job_id);
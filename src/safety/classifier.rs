@@ -0,0 +1,375 @@
+//! Content classification for compliance filtering.
+//!
+//! Deployments that must keep certain content out of conversations register
+//! one or more [`ContentClassifier`]s with the [`SafetyLayer`]. Every inbound
+//! message and outbound response is classified, and each match carries an
+//! action:
+//!
+//! - `warn`: let the content through and log the category
+//! - `require_approval`: inbound only; the turn runs, but every tool call in
+//!   it needs explicit approval
+//! - `block`: reject the message, or withhold the response
+//!
+//! The built-in [`KeywordClassifier`] matches keywords and regex patterns read
+//! from a rules file (TOML, or JSON for `.json` files). LLM or moderation API
+//! backends plug in through the same trait. A classifier that fails is
+//! treated as a block, so an outage never lets content through unchecked.
+//!
+//! ```toml
+//! [[categories]]
+//! name = "payment_cards"
+//! patterns = ['\b(?:\d[ -]?){13,16}\b']
+//! action = "block"
+//!
+//! [[categories]]
+//! name = "legal"
+//! keywords = ["lawsuit", "subpoena"]
+//! action = "require_approval"
+//! applies_to = "inbound"
+//! ```
+//!
+//! [`SafetyLayer`]: crate::safety::SafetyLayer
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Whether content is coming from a user or going out to one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentDirection {
+    Inbound,
+    Outbound,
+}
+
+/// What to do with content in a category, least strict first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClassifierAction {
+    Warn,
+    RequireApproval,
+    Block,
+}
+
+/// Which direction a category is checked in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppliesTo {
+    Inbound,
+    Outbound,
+    #[default]
+    Both,
+}
+
+impl AppliesTo {
+    fn includes(self, direction: ContentDirection) -> bool {
+        match self {
+            Self::Both => true,
+            Self::Inbound => direction == ContentDirection::Inbound,
+            Self::Outbound => direction == ContentDirection::Outbound,
+        }
+    }
+}
+
+/// A category a classifier matched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Classification {
+    /// Classifier that produced the match.
+    pub classifier: String,
+    /// Category name.
+    pub category: String,
+    pub action: ClassifierAction,
+}
+
+/// Every category matched by every classifier.
+#[derive(Debug, Clone, Default)]
+pub struct ClassificationResult {
+    pub classifications: Vec<Classification>,
+}
+
+impl ClassificationResult {
+    /// The strictest action among the matches.
+    pub fn action(&self) -> Option<ClassifierAction> {
+        self.classifications.iter().map(|c| c.action).max()
+    }
+
+    /// Names of the categories matched with `action`.
+    pub fn categories(&self, action: ClassifierAction) -> Vec<&str> {
+        self.classifications
+            .iter()
+            .filter(|c| c.action == action)
+            .map(|c| c.category.as_str())
+            .collect()
+    }
+}
+
+/// Errors from loading or running a classifier.
+#[derive(Debug, thiserror::Error)]
+pub enum ClassifierError {
+    #[error("Failed to read classifier rules: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse classifier rules: {0}")]
+    Parse(String),
+
+    #[error("Invalid classifier rules: {0}")]
+    Invalid(String),
+
+    #[error("Classifier backend failed: {0}")]
+    Backend(String),
+}
+
+/// Assigns content to compliance categories.
+#[async_trait]
+pub trait ContentClassifier: Send + Sync {
+    /// Name used in logs and results.
+    fn name(&self) -> &str;
+
+    /// Categories `content` falls into when flowing in `direction`.
+    async fn classify(
+        &self,
+        content: &str,
+        direction: ContentDirection,
+    ) -> Result<Vec<Classification>, ClassifierError>;
+}
+
+/// A category as written in the rules file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategorySpec {
+    pub name: String,
+    /// Whole words or phrases, matched case-insensitively.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Regular expressions.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    pub action: ClassifierAction,
+    /// Defaults to both directions, or inbound for `require_approval`.
+    #[serde(default)]
+    pub applies_to: Option<AppliesTo>,
+}
+
+/// Contents of a classifier rules file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClassifierRules {
+    pub categories: Vec<CategorySpec>,
+}
+
+struct Category {
+    name: String,
+    matchers: Vec<Regex>,
+    action: ClassifierAction,
+    applies_to: AppliesTo,
+}
+
+/// Classifies content by keyword and regex matches.
+pub struct KeywordClassifier {
+    categories: Vec<Category>,
+}
+
+impl KeywordClassifier {
+    /// Load rules from a file; `.json` files are JSON, anything else TOML.
+    pub fn load(path: &Path) -> Result<Self, ClassifierError> {
+        let raw = std::fs::read_to_string(path)?;
+        let rules: ClassifierRules = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&raw).map_err(|e| ClassifierError::Parse(e.to_string()))?
+        } else {
+            toml::from_str(&raw).map_err(|e| ClassifierError::Parse(e.to_string()))?
+        };
+        Self::from_rules(rules)
+    }
+
+    /// Compile and validate rules.
+    pub fn from_rules(rules: ClassifierRules) -> Result<Self, ClassifierError> {
+        let mut categories = Vec::with_capacity(rules.categories.len());
+        for spec in rules.categories {
+            if spec.name.trim().is_empty() {
+                return Err(ClassifierError::Invalid(
+                    "category without a name".to_string(),
+                ));
+            }
+            if spec.keywords.is_empty() && spec.patterns.is_empty() {
+                return Err(ClassifierError::Invalid(format!(
+                    "category '{}' has no keywords or patterns",
+                    spec.name
+                )));
+            }
+
+            let applies_to = match (spec.applies_to, spec.action) {
+                (Some(applies_to), _) => applies_to,
+                (None, ClassifierAction::RequireApproval) => AppliesTo::Inbound,
+                (None, _) => AppliesTo::Both,
+            };
+            if spec.action == ClassifierAction::RequireApproval
+                && applies_to.includes(ContentDirection::Outbound)
+            {
+                return Err(ClassifierError::Invalid(format!(
+                    "category '{}': require_approval only applies to inbound messages",
+                    spec.name
+                )));
+            }
+
+            let keywords = spec
+                .keywords
+                .iter()
+                .map(|k| format!(r"(?i)\b{}\b", regex::escape(k.trim())));
+            let matchers = keywords
+                .chain(spec.patterns.iter().cloned())
+                .map(|pattern| {
+                    Regex::new(&pattern).map_err(|e| {
+                        ClassifierError::Invalid(format!("category '{}': {}", spec.name, e))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            categories.push(Category {
+                name: spec.name,
+                matchers,
+                action: spec.action,
+                applies_to,
+            });
+        }
+        Ok(Self { categories })
+    }
+}
+
+#[async_trait]
+impl ContentClassifier for KeywordClassifier {
+    fn name(&self) -> &str {
+        "keyword"
+    }
+
+    async fn classify(
+        &self,
+        content: &str,
+        direction: ContentDirection,
+    ) -> Result<Vec<Classification>, ClassifierError> {
+        Ok(self
+            .categories
+            .iter()
+            .filter(|c| c.applies_to.includes(direction))
+            .filter(|c| c.matchers.iter().any(|m| m.is_match(content)))
+            .map(|c| Classification {
+                classifier: self.name().to_string(),
+                category: c.name.clone(),
+                action: c.action,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r#"
+        [[categories]]
+        name = "payment_cards"
+        patterns = ['\b(?:\d[ -]?){13,16}\b']
+        action = "block"
+
+        [[categories]]
+        name = "legal"
+        keywords = ["sue"]
+        action = "require_approval"
+
+        [[categories]]
+        name = "profanity"
+        keywords = ["darn"]
+        action = "warn"
+        applies_to = "outbound"
+    "#;
+
+    fn classifier() -> KeywordClassifier {
+        KeywordClassifier::from_rules(toml::from_str(RULES).unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_keyword_and_pattern_matches() {
+        let classifier = classifier();
+
+        let matches = classifier
+            .classify("card 4111 1111 1111 1111", ContentDirection::Inbound)
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].category, "payment_cards");
+
+        // Keywords match whole words only
+        let matches = classifier
+            .classify("I will SUE them", ContentDirection::Inbound)
+            .await
+            .unwrap();
+        assert_eq!(matches[0].action, ClassifierAction::RequireApproval);
+        let matches = classifier
+            .classify("open an issue", ContentDirection::Inbound)
+            .await
+            .unwrap();
+        assert!(matches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_directions() {
+        let classifier = classifier();
+        let inbound = classifier
+            .classify("darn, sue", ContentDirection::Inbound)
+            .await
+            .unwrap();
+        assert_eq!(inbound.len(), 1);
+        assert_eq!(inbound[0].category, "legal");
+
+        let outbound = classifier
+            .classify("darn, sue", ContentDirection::Outbound)
+            .await
+            .unwrap();
+        assert_eq!(outbound.len(), 1);
+        assert_eq!(outbound[0].category, "profanity");
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let rules = ClassifierRules {
+            categories: vec![CategorySpec {
+                name: "empty".to_string(),
+                keywords: Vec::new(),
+                patterns: Vec::new(),
+                action: ClassifierAction::Block,
+                applies_to: None,
+            }],
+        };
+        assert!(KeywordClassifier::from_rules(rules).is_err());
+
+        let rules = ClassifierRules {
+            categories: vec![CategorySpec {
+                name: "outbound_approval".to_string(),
+                keywords: vec!["x".to_string()],
+                patterns: Vec::new(),
+                action: ClassifierAction::RequireApproval,
+                applies_to: Some(AppliesTo::Both),
+            }],
+        };
+        assert!(KeywordClassifier::from_rules(rules).is_err());
+    }
+
+    #[test]
+    fn test_result_action_is_strictest() {
+        let result = ClassificationResult {
+            classifications: vec![
+                Classification {
+                    classifier: "keyword".to_string(),
+                    category: "a".to_string(),
+                    action: ClassifierAction::Warn,
+                },
+                Classification {
+                    classifier: "keyword".to_string(),
+                    category: "b".to_string(),
+                    action: ClassifierAction::Block,
+                },
+            ],
+        };
+        assert_eq!(result.action(), Some(ClassifierAction::Block));
+        assert_eq!(result.categories(ClassifierAction::Block), vec!["b"]);
+        assert_eq!(ClassificationResult::default().action(), None);
+    }
+}
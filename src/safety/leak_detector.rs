@@ -282,6 +282,33 @@ impl LeakDetector {
             .unwrap_or_else(|| content.to_string()))
     }
 
+    /// Replace every match with a placeholder, whatever its action.
+    ///
+    /// For text that is stored or shared rather than sent on, where even a
+    /// warn-only match shouldn't be kept.
+    pub fn mask_all(&self, content: &str) -> String {
+        let mut locations: Vec<_> = self
+            .scan(content)
+            .matches
+            .into_iter()
+            .map(|m| m.location)
+            .collect();
+        locations.sort_by_key(|l| (l.start, l.end));
+
+        let mut out = String::with_capacity(content.len());
+        let mut pos = 0;
+        for location in locations {
+            if location.end <= pos {
+                continue;
+            }
+            out.push_str(&content[pos..location.start.max(pos)]);
+            out.push_str("[REDACTED]");
+            pos = location.end;
+        }
+        out.push_str(&content[pos..]);
+        out
+    }
+
     /// Scan an outbound HTTP request for potential secret leakage.
     ///
     /// This MUST be called before executing any HTTP request from WASM
@@ -612,6 +639,15 @@ mod tests {
         assert!(!redacted.contains("eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9"));
     }
 
+    #[test]
+    fn test_mask_all_masks_blocking_matches() {
+        let detector = LeakDetector::new();
+        let content = "key=sk-proj-test1234567890abcdefghij12345678 page=2";
+
+        assert_eq!(detector.mask_all(content), "key=[REDACTED] page=2");
+        assert_eq!(detector.mask_all("nothing here"), "nothing here");
+    }
+
     #[test]
     fn test_scan_and_clean_blocks() {
         let detector = LeakDetector::new();
@@ -6,12 +6,18 @@
 //! - Validating inputs before processing
 //! - Enforcing safety policies
 //! - Detecting secret leakage in outputs
+//! - Classifying messages and responses for compliance filtering
 
+mod classifier;
 mod leak_detector;
 mod policy;
 mod sanitizer;
 mod validator;
 
+pub use classifier::{
+    AppliesTo, CategorySpec, Classification, ClassificationResult, ClassifierAction,
+    ClassifierError, ClassifierRules, ContentClassifier, ContentDirection, KeywordClassifier,
+};
 pub use leak_detector::{
     LeakAction, LeakDetectionError, LeakDetector, LeakMatch, LeakPattern, LeakScanResult,
     LeakSeverity,
@@ -20,6 +26,8 @@ pub use policy::{Policy, PolicyAction, PolicyRule, Severity};
 pub use sanitizer::{InjectionWarning, SanitizedOutput, Sanitizer};
 pub use validator::{ValidationResult, Validator};
 
+use std::sync::Arc;
+
 use crate::config::SafetyConfig;
 
 /// Unified safety layer combining sanitizer, validator, and policy.
@@ -28,6 +36,7 @@ pub struct SafetyLayer {
     validator: Validator,
     policy: Policy,
     leak_detector: LeakDetector,
+    classifiers: Vec<Arc<dyn ContentClassifier>>,
     config: SafetyConfig,
 }
 
@@ -39,10 +48,43 @@ impl SafetyLayer {
             validator: Validator::new(),
             policy: Policy::default(),
             leak_detector: LeakDetector::new(),
+            classifiers: Vec::new(),
             config: config.clone(),
         }
     }
 
+    /// Add a content classifier applied to messages and responses.
+    pub fn with_classifier(mut self, classifier: Arc<dyn ContentClassifier>) -> Self {
+        self.classifiers.push(classifier);
+        self
+    }
+
+    /// Run every classifier over `content`.
+    ///
+    /// A classifier that errors counts as a block, so content is never let
+    /// through unchecked.
+    pub async fn classify(
+        &self,
+        content: &str,
+        direction: ContentDirection,
+    ) -> ClassificationResult {
+        let mut result = ClassificationResult::default();
+        for classifier in &self.classifiers {
+            match classifier.classify(content, direction).await {
+                Ok(classifications) => result.classifications.extend(classifications),
+                Err(e) => {
+                    tracing::error!("Content classifier '{}' failed: {}", classifier.name(), e);
+                    result.classifications.push(Classification {
+                        classifier: classifier.name().to_string(),
+                        category: "classifier_error".to_string(),
+                        action: ClassifierAction::Block,
+                    });
+                }
+            }
+        }
+        result
+    }
+
     /// Sanitize tool output before it reaches the LLM.
     pub fn sanitize_tool_output(&self, tool_name: &str, output: &str) -> SanitizedOutput {
         // Check length limits first
@@ -171,6 +213,7 @@ mod tests {
         let config = SafetyConfig {
             max_output_length: 100_000,
             injection_check_enabled: true,
+            classifier_rules: None,
         };
         let safety = SafetyLayer::new(&config);
 
@@ -0,0 +1,501 @@
+//! Reproducible job bundles for bug reports.
+//!
+//! A bundle holds what a maintainer needs to reproduce a failed job, without
+//! the user's data:
+//! - the settings that differ from the defaults, with secrets masked
+//! - the tool schemas the model saw
+//! - the action transcript (tool, input, sanitized output, error)
+//! - a SHA-256 hash of every LLM message, so a replay can be checked against
+//!   the original conversation without shipping its text
+//!
+//! Everything is anonymized before it is written: the user ID, email
+//! addresses, the home directory, sensitive URL parameters, and anything the
+//! [`LeakDetector`] recognizes as a secret. Maintainers load a bundle and
+//! replay it against the mock harness with [`ReplayProvider`], which answers
+//! the worker with the recorded tool calls in order.
+//!
+//! The sandbox proxy's log is not included: it is shared by every job and its
+//! entries can't be attributed to one.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::config::{ConfigSource, ResolvedSetting};
+use crate::context::{JobContext, JobState, Memory};
+use crate::error::LlmError;
+use crate::llm::{
+    ChatMessage, CompletionRequest, CompletionResponse, FinishReason, LlmProvider, ToolCall,
+    ToolCompletionRequest, ToolCompletionResponse, ToolDefinition,
+};
+use crate::safety::LeakDetector;
+use crate::sandbox::proxy::inspect::sanitize_url;
+
+/// Version of the bundle format, bumped on incompatible changes.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// What the user ID is replaced with.
+const USER_PLACEHOLDER: &str = "user";
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").expect("valid email regex")
+});
+
+static URL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"https?://[^\s"'<>]+"#).expect("valid URL regex"));
+
+/// An anonymized record of a job, for attaching to bug reports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobBundle {
+    pub format_version: u32,
+    /// Version of IronClaw that ran the job.
+    pub version: String,
+    pub created_at: DateTime<Utc>,
+    pub job: BundleJob,
+    /// Settings that differ from the defaults.
+    pub config: Vec<BundleSetting>,
+    pub tools: Vec<ToolDefinition>,
+    pub actions: Vec<BundleAction>,
+    pub llm_trace: Vec<LlmTraceEntry>,
+}
+
+/// The job itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleJob {
+    pub job_id: Uuid,
+    pub title: String,
+    pub description: String,
+    pub state: JobState,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub transitions: Vec<BundleTransition>,
+}
+
+/// A change of job state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleTransition {
+    pub from: JobState,
+    pub to: JobState,
+    pub at: DateTime<Utc>,
+    pub reason: Option<String>,
+}
+
+/// A non-default setting, masked like `ironclaw config show`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleSetting {
+    pub key: String,
+    pub value: String,
+    /// "config file", "env" or "database".
+    pub source: String,
+}
+
+/// One tool call from the job's transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleAction {
+    pub sequence: u32,
+    pub tool_name: String,
+    pub input: serde_json::Value,
+    pub output: Option<serde_json::Value>,
+    pub success: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub executed_at: DateTime<Utc>,
+}
+
+/// One message of the LLM conversation, by hash only.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmTraceEntry {
+    pub index: usize,
+    pub role: String,
+    /// SHA-256 of the message as the job sent or received it.
+    pub sha256: String,
+    pub content_chars: usize,
+    /// Tools the assistant called in this message.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<String>,
+}
+
+impl JobBundle {
+    /// Build an anonymized bundle for a job from its context and memory,
+    /// the resolved settings, and the tool schemas.
+    pub fn build(
+        job: &JobContext,
+        memory: &Memory,
+        settings: &[ResolvedSetting],
+        tools: Vec<ToolDefinition>,
+    ) -> Self {
+        let mut anon = Anonymizer::new(&job.user_id);
+
+        let config = settings
+            .iter()
+            .filter(|s| s.source != ConfigSource::Default)
+            .map(|s| BundleSetting {
+                key: s.key.clone(),
+                value: anon.text(&s.display_value()),
+                source: s.source.to_string(),
+            })
+            .collect();
+
+        let actions = memory
+            .actions
+            .iter()
+            .map(|a| BundleAction {
+                sequence: a.sequence,
+                tool_name: a.tool_name.clone(),
+                input: anon.value(&a.input),
+                output: a.output_sanitized.as_ref().map(|v| anon.value(v)),
+                success: a.success,
+                error: a.error.as_deref().map(|e| anon.text(e)),
+                duration_ms: a.duration.as_millis() as u64,
+                executed_at: a.executed_at,
+            })
+            .collect();
+
+        let llm_trace = memory
+            .conversation
+            .messages()
+            .iter()
+            .enumerate()
+            .map(|(index, message)| trace_entry(index, message))
+            .collect();
+
+        Self {
+            format_version: BUNDLE_FORMAT_VERSION,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: Utc::now(),
+            job: BundleJob {
+                job_id: job.job_id,
+                title: anon.text(&job.title),
+                description: anon.text(&job.description),
+                state: job.state,
+                created_at: job.created_at,
+                started_at: job.started_at,
+                completed_at: job.completed_at,
+                transitions: job
+                    .transitions
+                    .iter()
+                    .map(|t| BundleTransition {
+                        from: t.from,
+                        to: t.to,
+                        at: t.timestamp,
+                        reason: t.reason.as_deref().map(|r| anon.text(r)),
+                    })
+                    .collect(),
+            },
+            config,
+            tools,
+            actions,
+            llm_trace,
+        }
+    }
+
+    /// Write the bundle to `dir/job-<id>.json`, returning the path.
+    pub fn save(&self, dir: &Path) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!("job-{}.json", self.job.job_id));
+        std::fs::write(&path, serde_json::to_vec_pretty(self)?)?;
+        Ok(path)
+    }
+
+    /// Read a bundle written by [`save`](Self::save).
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let raw = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&raw)?)
+    }
+}
+
+/// Where bundles are written: `~/.ironclaw/bundles`.
+pub fn bundles_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".ironclaw")
+        .join("bundles")
+}
+
+fn trace_entry(index: usize, message: &ChatMessage) -> LlmTraceEntry {
+    let serialized = serde_json::to_vec(message).unwrap_or_default();
+    LlmTraceEntry {
+        index,
+        role: serde_json::to_value(message.role)
+            .ok()
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_default(),
+        sha256: Sha256::digest(&serialized)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect(),
+        content_chars: message.content.chars().count(),
+        tool_calls: message
+            .tool_calls
+            .iter()
+            .flatten()
+            .map(|c| c.name.clone())
+            .collect(),
+    }
+}
+
+/// Strips identifying data from text before it goes into a bundle.
+pub struct Anonymizer {
+    user_id: String,
+    home: Option<String>,
+    /// Each email address seen, with its stable placeholder.
+    emails: HashMap<String, String>,
+    leak_detector: LeakDetector,
+}
+
+impl Anonymizer {
+    pub fn new(user_id: &str) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            home: dirs::home_dir()
+                .map(|h| h.to_string_lossy().into_owned())
+                .filter(|h| h.len() > 1),
+            emails: HashMap::new(),
+            leak_detector: LeakDetector::new(),
+        }
+    }
+
+    /// Anonymize a piece of text.
+    pub fn text(&mut self, text: &str) -> String {
+        // Mask secrets first, while the detector's offsets still apply
+        let out = self.leak_detector.mask_all(text);
+
+        let out = URL.replace_all(&out, |c: &regex::Captures| sanitize_url(&c[0]));
+        let out = EMAIL.replace_all(&out, |c: &regex::Captures| {
+            let next = self.emails.len() + 1;
+            self.emails
+                .entry(c[0].to_lowercase())
+                .or_insert_with(|| format!("email-{}@example.com", next))
+                .clone()
+        });
+        let mut out = out.into_owned();
+        if let Some(ref home) = self.home {
+            out = out.replace(home.as_str(), "~");
+        }
+        // "default" is the single-user ID and too common a word to replace
+        if !self.user_id.is_empty() && self.user_id != "default" {
+            out = out.replace(&self.user_id, USER_PLACEHOLDER);
+        }
+        out
+    }
+
+    /// Anonymize every string in a JSON value, object keys included.
+    pub fn value(&mut self, value: &serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::String(s) => serde_json::Value::String(self.text(s)),
+            serde_json::Value::Array(items) => {
+                serde_json::Value::Array(items.iter().map(|v| self.value(v)).collect())
+            }
+            serde_json::Value::Object(map) => serde_json::Value::Object(
+                map.iter()
+                    .map(|(k, v)| (self.text(k), self.value(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+}
+
+/// An LLM that replays a bundle's tool calls.
+///
+/// Each completion with tools returns the next recorded action as a tool
+/// call; once they are used up it answers with text, which ends the job.
+pub struct ReplayProvider {
+    calls: Mutex<VecDeque<ToolCall>>,
+}
+
+impl ReplayProvider {
+    pub fn new(bundle: &JobBundle) -> Self {
+        let calls = bundle
+            .actions
+            .iter()
+            .map(|a| ToolCall {
+                id: format!("replay-{}", a.sequence),
+                name: a.tool_name.clone(),
+                arguments: a.input.clone(),
+                thought_signature: None,
+            })
+            .collect();
+        Self {
+            calls: Mutex::new(calls),
+        }
+    }
+
+    /// Number of recorded calls not yet replayed.
+    pub fn remaining(&self) -> usize {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ReplayProvider {
+    fn model_name(&self) -> &str {
+        "replay"
+    }
+
+    fn cost_per_token(&self) -> (Decimal, Decimal) {
+        (Decimal::ZERO, Decimal::ZERO)
+    }
+
+    async fn complete(&self, _request: CompletionRequest) -> Result<CompletionResponse, LlmError> {
+        Ok(CompletionResponse {
+            content: "Replay finished.".to_string(),
+            thought: None,
+            input_tokens: 0,
+            output_tokens: 0,
+            finish_reason: FinishReason::Stop,
+        })
+    }
+
+    async fn complete_with_tools(
+        &self,
+        _request: ToolCompletionRequest,
+    ) -> Result<ToolCompletionResponse, LlmError> {
+        let next = self
+            .calls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front();
+        Ok(match next {
+            Some(call) => ToolCompletionResponse {
+                content: None,
+                tool_calls: vec![call],
+                thought: None,
+                input_tokens: 0,
+                output_tokens: 0,
+                finish_reason: FinishReason::ToolUse,
+            },
+            None => ToolCompletionResponse {
+                content: Some("Replay finished.".to_string()),
+                tool_calls: Vec::new(),
+                thought: None,
+                input_tokens: 0,
+                output_tokens: 0,
+                finish_reason: FinishReason::Stop,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anonymizer() {
+        let mut anon = Anonymizer::new("alice-42");
+        let text = anon.text(
+            "alice-42 mailed Bob@Example.org and carol@corp.io, then bob@example.org \
+             via https://api.example.com/v1?api_key=abc123&page=2",
+        );
+        assert!(!text.contains("alice-42"));
+        assert!(text.starts_with("user mailed email-1@example.com and email-2@example.com"));
+        assert!(text.contains("then email-1@example.com"));
+        assert!(!text.contains("abc123"));
+        assert!(text.contains("page=2"));
+
+        let key = format!("sk-ant-api03-{}", "x".repeat(95));
+        let value = anon.value(&serde_json::json!({
+            "header": format!("Bearer {}", key),
+            "nested": [{ "owner": "alice-42" }, 7],
+        }));
+        assert!(!value.to_string().contains(&key));
+        assert!(value["header"].as_str().unwrap().contains("[REDACTED]"));
+        assert_eq!(value["nested"][0]["owner"], "user");
+        assert_eq!(value["nested"][1], 7);
+    }
+
+    #[test]
+    fn test_build_bundle() {
+        let mut job = JobContext::with_user("alice-42", "Fetch report", "Email alice@corp.io");
+        job.transition_to(JobState::InProgress, None).unwrap();
+        job.transition_to(JobState::Failed, Some("http 403".to_string()))
+            .unwrap();
+
+        let mut memory = Memory::new(job.job_id);
+        let ok = memory
+            .create_action(
+                "http",
+                serde_json::json!({ "url": "https://example.com/a" }),
+            )
+            .succeed(
+                None,
+                serde_json::json!({ "status": 200 }),
+                Default::default(),
+            );
+        memory.record_action(ok);
+        let failed = memory
+            .create_action(
+                "http",
+                serde_json::json!({ "url": "https://example.com/b" }),
+            )
+            .fail("403 for alice-42", Default::default());
+        memory.record_action(failed);
+        memory.add_message(ChatMessage::system("You are an agent"));
+        memory.add_message(ChatMessage::user("Fetch the report"));
+
+        let settings = vec![
+            ResolvedSetting {
+                key: "NEARAI_API_KEY".to_string(),
+                value: Some("secret".to_string()),
+                source: ConfigSource::Env,
+            },
+            ResolvedSetting {
+                key: "AGENT_NAME".to_string(),
+                value: None,
+                source: ConfigSource::Default,
+            },
+        ];
+
+        let bundle = JobBundle::build(&job, &memory, &settings, Vec::new());
+        assert_eq!(bundle.format_version, BUNDLE_FORMAT_VERSION);
+        assert_eq!(bundle.job.state, JobState::Failed);
+        assert_eq!(bundle.job.description, "Email email-1@example.com");
+        assert_eq!(bundle.job.transitions.len(), 2);
+        assert_eq!(bundle.config.len(), 1);
+        assert_eq!(bundle.config[0].value, "********");
+        assert_eq!(bundle.actions.len(), 2);
+        assert_eq!(bundle.actions[1].error.as_deref(), Some("403 for user"));
+        assert_eq!(bundle.llm_trace.len(), 2);
+        assert_eq!(bundle.llm_trace[0].role, "system");
+        assert_eq!(bundle.llm_trace[0].sha256.len(), 64);
+        assert_ne!(bundle.llm_trace[0].sha256, bundle.llm_trace[1].sha256);
+
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(!json.contains("alice"));
+        let parsed: JobBundle = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.actions[0].input["url"], "https://example.com/a");
+    }
+
+    #[tokio::test]
+    async fn test_replay_provider() {
+        let job = JobContext::new("Replay", "Replay a bundle");
+        let mut memory = Memory::new(job.job_id);
+        let action = memory.create_action("echo", serde_json::json!({ "message": "hi" }));
+        memory.record_action(action);
+        let bundle = JobBundle::build(&job, &memory, &[], Vec::new());
+
+        let provider = ReplayProvider::new(&bundle);
+        assert_eq!(provider.remaining(), 1);
+        let request = || ToolCompletionRequest::new(Vec::new(), Vec::new());
+
+        let first = provider.complete_with_tools(request()).await.unwrap();
+        assert_eq!(first.tool_calls.len(), 1);
+        assert_eq!(first.tool_calls[0].name, "echo");
+        assert_eq!(first.tool_calls[0].arguments["message"], "hi");
+
+        let done = provider.complete_with_tools(request()).await.unwrap();
+        assert!(done.tool_calls.is_empty());
+        assert_eq!(done.finish_reason, FinishReason::Stop);
+        assert_eq!(provider.remaining(), 0);
+    }
+}
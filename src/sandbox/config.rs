@@ -2,6 +2,8 @@
 
 use std::time::Duration;
 
+use crate::sandbox::quota::WorkspaceQuota;
+
 /// Configuration for the sandbox system.
 #[derive(Debug, Clone)]
 pub struct SandboxConfig {
@@ -23,6 +25,10 @@ pub struct SandboxConfig {
     pub auto_pull_image: bool,
     /// Port for the HTTP proxy (0 = auto-assign).
     pub proxy_port: u16,
+    /// Disk quota for writable workspaces.
+    pub workspace_quota: WorkspaceQuota,
+    /// Local port for the proxy inspection view (None = disabled).
+    pub inspect_port: Option<u16>,
 }
 
 impl Default for SandboxConfig {
@@ -37,6 +43,8 @@ impl Default for SandboxConfig {
             image: "ghcr.io/nearai/sandbox:latest".to_string(),
             auto_pull_image: true,
             proxy_port: 0,
+            workspace_quota: WorkspaceQuota::default(),
+            inspect_port: None,
         }
     }
 }
@@ -117,6 +125,8 @@ pub struct ResourceLimits {
     pub timeout: Duration,
     /// Maximum output size in bytes.
     pub max_output_bytes: usize,
+    /// Largest file a process may write (enforced via the `fsize` ulimit).
+    pub max_file_bytes: u64,
 }
 
 impl Default for ResourceLimits {
@@ -125,7 +135,8 @@ impl Default for ResourceLimits {
             memory_bytes: 2 * 1024 * 1024 * 1024, // 2 GB
             cpu_shares: 1024,
             timeout: Duration::from_secs(120),
-            max_output_bytes: 64 * 1024, // 64 KB
+            max_output_bytes: 64 * 1024,       // 64 KB
+            max_file_bytes: 100 * 1024 * 1024, // 100 MB
         }
     }
 }
@@ -35,7 +35,7 @@ use bollard::container::{
     StartContainerOptions, WaitContainerOptions,
 };
 use bollard::exec::{CreateExecOptions, StartExecResults};
-use bollard::models::HostConfig;
+use bollard::models::{HostConfig, ResourcesUlimits};
 use futures::StreamExt;
 
 use crate::sandbox::config::{ResourceLimits, SandboxPolicy};
@@ -288,6 +288,12 @@ impl ContainerRunner {
             security_opt: Some(vec!["no-new-privileges:true".to_string()]),
             // Read-only root filesystem (workspace is still writable if policy allows)
             readonly_rootfs: Some(policy == SandboxPolicy::ReadOnly),
+            // No single file may outgrow the quota, even on the workspace mount
+            ulimits: Some(vec![ResourcesUlimits {
+                name: Some("fsize".to_string()),
+                soft: Some(limits.max_file_bytes as i64),
+                hard: Some(limits.max_file_bytes as i64),
+            }]),
             // Tmpfs mounts for /tmp and cargo cache
             tmpfs: Some(
                 [
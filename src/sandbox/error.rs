@@ -29,6 +29,14 @@ pub enum SandboxError {
     #[error("Resource limit exceeded: {resource} limit of {limit}")]
     ResourceLimitExceeded { resource: String, limit: String },
 
+    /// Workspace disk quota exceeded.
+    #[error("Workspace quota exceeded: {resource} at {used}, limit is {limit}")]
+    QuotaExceeded {
+        resource: String,
+        used: u64,
+        limit: u64,
+    },
+
     /// Network proxy error.
     #[error("Proxy error: {reason}")]
     ProxyError { reason: String },
@@ -38,7 +38,9 @@ use tokio::sync::RwLock;
 use crate::sandbox::config::{ResourceLimits, SandboxConfig, SandboxPolicy};
 use crate::sandbox::container::{ContainerOutput, ContainerRunner, connect_docker};
 use crate::sandbox::error::{Result, SandboxError};
-use crate::sandbox::proxy::{HttpProxy, NetworkProxyBuilder};
+use crate::sandbox::proxy::inspect::DEFAULT_INSPECT_CAPACITY;
+use crate::sandbox::proxy::{HttpProxy, NetworkProxyBuilder, ProxyInspector};
+use crate::sandbox::quota::{self, WorkspaceQuota, WorkspaceUsage};
 
 /// Output from sandbox execution.
 #[derive(Debug, Clone)]
@@ -159,9 +161,13 @@ impl SandboxManager {
 
         // Start the network proxy if we're using a sandboxed policy
         if self.config.policy.is_sandboxed() {
-            let proxy = NetworkProxyBuilder::from_config(&self.config)
-                .build_and_start(self.config.proxy_port)
-                .await?;
+            let mut builder = NetworkProxyBuilder::from_config(&self.config);
+            if let Some(port) = self.config.inspect_port {
+                let inspector = Arc::new(ProxyInspector::new(DEFAULT_INSPECT_CAPACITY));
+                inspector.clone().serve(port).await?;
+                builder = builder.with_inspector(inspector);
+            }
+            let proxy = builder.build_and_start(self.config.proxy_port).await?;
 
             *self.proxy.write().await = Some(proxy);
         }
@@ -225,15 +231,29 @@ impl SandboxManager {
         let docker = connect_docker().await?;
         let runner = ContainerRunner::new(docker, self.config.image.clone(), proxy_port);
 
+        let quota = self.config.workspace_quota;
         let limits = ResourceLimits {
             memory_bytes: self.config.memory_limit_mb * 1024 * 1024,
             cpu_shares: self.config.cpu_shares,
             timeout: self.config.timeout,
             max_output_bytes: 64 * 1024,
+            max_file_bytes: quota.max_file_bytes,
         };
 
+        // Writable workspaces are bind mounts Docker can't size-limit, so
+        // check usage around the command instead.
+        let writable = policy == SandboxPolicy::WorkspaceWrite;
+        if writable {
+            quota.check(measure_workspace(cwd).await?)?;
+        }
+
         let container_output = runner.execute(command, cwd, policy, &limits, env).await?;
 
+        if writable && let Err(e) = quota.check(measure_workspace(cwd).await?) {
+            tracing::warn!(cwd = %cwd.display(), "Sandboxed command exceeded quota: {}", e);
+            return Err(e);
+        }
+
         Ok(container_output.into())
     }
 
@@ -328,6 +348,16 @@ impl Drop for SandboxManager {
     }
 }
 
+/// Measure a workspace off the async runtime.
+async fn measure_workspace(dir: &Path) -> Result<WorkspaceUsage> {
+    let dir = dir.to_path_buf();
+    tokio::task::spawn_blocking(move || quota::measure(&dir))
+        .await
+        .map_err(|e| SandboxError::ExecutionFailed {
+            reason: format!("workspace measurement failed: {}", e),
+        })?
+}
+
 /// Builder for creating a sandbox manager.
 pub struct SandboxManagerBuilder {
     config: SandboxConfig,
@@ -371,6 +401,12 @@ impl SandboxManagerBuilder {
         self
     }
 
+    /// Set the disk quota for writable workspaces.
+    pub fn workspace_quota(mut self, quota: WorkspaceQuota) -> Self {
+        self.config.workspace_quota = quota;
+        self
+    }
+
     /// Add domains to the network allowlist.
     pub fn allow_domains(mut self, domains: Vec<String>) -> Self {
         self.config.network_allowlist.extend(domains);
@@ -84,13 +84,17 @@
 //! - **Capability dropping**: All Linux capabilities dropped, only essential ones added back
 //! - **Auto-cleanup**: Containers are removed after execution (--rm + explicit cleanup)
 //! - **Timeout enforcement**: Commands are killed after the timeout
+//! - **Disk quotas**: Workspaces are capped in bytes, file count, and single-file size
 
+pub mod bundle;
 pub mod config;
 pub mod container;
 pub mod error;
 pub mod manager;
 pub mod proxy;
+pub mod quota;
 
+pub use bundle::{Anonymizer, BUNDLE_FORMAT_VERSION, JobBundle, ReplayProvider};
 pub use config::{
     CredentialLocation, CredentialMapping, ResourceLimits, SandboxConfig, SandboxPolicy,
 };
@@ -101,6 +105,7 @@ pub use proxy::{
     CredentialResolver, DefaultPolicyDecider, DomainAllowlist, EnvCredentialResolver, HttpProxy,
     NetworkDecision, NetworkPolicyDecider, NetworkProxyBuilder, NetworkRequest,
 };
+pub use quota::{WorkspaceQuota, WorkspaceUsage};
 
 /// Default allowlist getter (re-export for convenience).
 pub fn default_allowlist() -> Vec<String> {
@@ -14,6 +14,7 @@
 use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Instant;
 
 use bytes::Bytes;
 use http_body_util::{BodyExt, Empty, Full, combinators::BoxBody};
@@ -26,6 +27,9 @@ use tokio::sync::RwLock;
 
 use crate::sandbox::config::CredentialLocation;
 use crate::sandbox::error::{Result, SandboxError};
+use crate::sandbox::proxy::inspect::{
+    InspectDecision, ProxyExchange, ProxyInspector, sanitize_headers,
+};
 use crate::sandbox::proxy::policy::{NetworkDecision, NetworkPolicyDecider, NetworkRequest};
 
 /// State shared across proxy connections.
@@ -38,6 +42,18 @@ struct ProxyState {
     request_count: std::sync::atomic::AtomicU64,
     /// Whether the proxy is running.
     running: std::sync::atomic::AtomicBool,
+    /// Developer inspection log, if enabled.
+    inspector: Option<Arc<ProxyInspector>>,
+}
+
+impl ProxyState {
+    /// Mirror a finished exchange into the inspection log, if enabled.
+    async fn inspect(&self, exchange: Option<ProxyExchange>, started: Instant) {
+        if let (Some(inspector), Some(mut exchange)) = (&self.inspector, exchange) {
+            exchange.duration_ms = started.elapsed().as_millis() as u64;
+            inspector.record(exchange).await;
+        }
+    }
 }
 
 /// Resolves secret names to their values.
@@ -79,6 +95,15 @@ impl HttpProxy {
     pub fn new(
         decider: Arc<dyn NetworkPolicyDecider>,
         credential_resolver: Arc<dyn CredentialResolver>,
+    ) -> Self {
+        Self::with_inspector(decider, credential_resolver, None)
+    }
+
+    /// Create a new HTTP proxy that mirrors exchanges into `inspector`.
+    pub fn with_inspector(
+        decider: Arc<dyn NetworkPolicyDecider>,
+        credential_resolver: Arc<dyn CredentialResolver>,
+        inspector: Option<Arc<ProxyInspector>>,
     ) -> Self {
         Self {
             state: Arc::new(ProxyState {
@@ -86,6 +111,7 @@ impl HttpProxy {
                 credential_resolver,
                 request_count: std::sync::atomic::AtomicU64::new(0),
                 running: std::sync::atomic::AtomicBool::new(false),
+                inspector,
             }),
             addr: RwLock::new(None),
             shutdown_tx: RwLock::new(None),
@@ -182,6 +208,11 @@ impl HttpProxy {
         self.state.running.load(std::sync::atomic::Ordering::SeqCst)
     }
 
+    /// Get the inspection log, if developer mode is enabled.
+    pub fn inspector(&self) -> Option<&Arc<ProxyInspector>> {
+        self.state.inspector.as_ref()
+    }
+
     /// Get the number of requests handled.
     pub fn request_count(&self) -> u64 {
         self.state
@@ -191,6 +222,11 @@ impl HttpProxy {
 }
 
 /// Handle an incoming proxy request.
+#[tracing::instrument(
+    name = "proxy.request",
+    skip_all,
+    fields(method = %req.method(), host = req.uri().host().unwrap_or(""))
+)]
 async fn handle_request(
     req: Request<hyper::body::Incoming>,
     state: Arc<ProxyState>,
@@ -198,10 +234,11 @@ async fn handle_request(
     state
         .request_count
         .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    let started = Instant::now();
 
     // Handle CONNECT method for HTTPS tunneling
     if req.method() == Method::CONNECT {
-        return Ok(handle_connect(req, state).await);
+        return Ok(handle_connect(req, state, started).await);
     }
 
     // For HTTP requests, validate and forward
@@ -212,6 +249,15 @@ async fn handle_request(
         Some(r) => r,
         None => {
             tracing::warn!("Proxy: invalid URL: {}", uri);
+            crate::observability::record_proxy_request("invalid");
+            let exchange = state.inspector.as_ref().map(|_| {
+                let mut e = ProxyExchange::new(&method, &uri, "", "");
+                e.decision = InspectDecision::Invalid;
+                e.reason = Some("Invalid URL".to_string());
+                e.status = Some(StatusCode::BAD_REQUEST.as_u16());
+                e
+            });
+            state.inspect(exchange, started).await;
             return Ok(error_response(
                 StatusCode::BAD_REQUEST,
                 "Invalid URL".to_string(),
@@ -221,15 +267,38 @@ async fn handle_request(
 
     // Make policy decision
     let decision = state.decider.decide(&network_req).await;
+    crate::observability::record_proxy_request(if decision.is_allowed() {
+        "allow"
+    } else {
+        "deny"
+    });
+
+    let mut exchange = state.inspector.as_ref().map(|_| {
+        let mut e = ProxyExchange::new(&method, &uri, &network_req.host, &network_req.path);
+        e.request_headers = sanitize_headers(
+            req.headers()
+                .iter()
+                .map(|(n, v)| (n.as_str(), v.as_bytes())),
+        );
+        e
+    });
 
     match decision {
         NetworkDecision::Deny { reason } => {
             tracing::info!("Proxy: blocked {} {} - {}", method, uri, reason);
+            if let Some(e) = exchange.as_mut() {
+                e.decision = InspectDecision::Deny;
+                e.reason = Some(reason.clone());
+                e.status = Some(StatusCode::FORBIDDEN.as_u16());
+            }
+            state.inspect(exchange, started).await;
             Ok(error_response(StatusCode::FORBIDDEN, reason))
         }
         NetworkDecision::Allow | NetworkDecision::AllowWithCredentials { .. } => {
             // Forward the request
-            forward_request(req, decision, state).await
+            let response = forward_request(req, decision, state.clone(), exchange.as_mut()).await;
+            state.inspect(exchange, started).await;
+            response
         }
     }
 }
@@ -238,6 +307,7 @@ async fn handle_request(
 async fn handle_connect(
     req: Request<hyper::body::Incoming>,
     state: Arc<ProxyState>,
+    started: Instant,
 ) -> Response<BoxBody<Bytes, Infallible>> {
     // Extract host from CONNECT target
     let host = req.uri().authority().map(|a| a.host().to_string());
@@ -258,15 +328,36 @@ async fn handle_connect(
     };
 
     let decision = state.decider.decide(&network_req).await;
+    crate::observability::record_proxy_request(if decision.is_allowed() {
+        "allow"
+    } else {
+        "deny"
+    });
+
+    let mut exchange = state.inspector.as_ref().map(|_| {
+        let mut e = ProxyExchange::new("CONNECT", &network_req.url, &host, "/");
+        e.tunneled = true;
+        e
+    });
 
     if !decision.is_allowed() {
         if let NetworkDecision::Deny { reason } = decision {
             tracing::info!("Proxy: blocked CONNECT {} - {}", host, reason);
+            if let Some(e) = exchange.as_mut() {
+                e.decision = InspectDecision::Deny;
+                e.reason = Some(reason.clone());
+                e.status = Some(StatusCode::FORBIDDEN.as_u16());
+            }
+            state.inspect(exchange, started).await;
             return error_response(StatusCode::FORBIDDEN, reason);
         }
     }
 
     tracing::debug!("Proxy: allowing CONNECT to {}", host);
+    if let Some(e) = exchange.as_mut() {
+        e.status = Some(StatusCode::OK.as_u16());
+    }
+    state.inspect(exchange, started).await;
 
     // For CONNECT, we return 200 OK and the client will upgrade to TLS
     // The actual TLS connection goes directly to the target, we just act as a tunnel
@@ -276,11 +367,12 @@ async fn handle_connect(
         .unwrap()
 }
 
-/// Forward a request to the target server.
+/// Forward a request to the target server, filling in `exchange` if inspected.
 async fn forward_request(
     req: Request<hyper::body::Incoming>,
     decision: NetworkDecision,
     state: Arc<ProxyState>,
+    mut exchange: Option<&mut ProxyExchange>,
 ) -> std::result::Result<Response<BoxBody<Bytes, Infallible>>, Infallible> {
     let method = req.method().clone();
     let uri = req.uri().clone();
@@ -318,6 +410,9 @@ async fn forward_request(
                 }
             };
             tracing::debug!("Proxy: injected credential for {}", secret_name);
+            if let Some(e) = exchange.as_deref_mut() {
+                e.injected_credential = Some(secret_name);
+            }
         } else {
             tracing::warn!("Proxy: credential {} not found", secret_name);
         }
@@ -328,6 +423,10 @@ async fn forward_request(
         Ok(collected) => collected.to_bytes(),
         Err(e) => {
             tracing::error!("Proxy: failed to read request body: {}", e);
+            if let Some(ex) = exchange {
+                ex.status = Some(StatusCode::INTERNAL_SERVER_ERROR.as_u16());
+                ex.error = Some(format!("failed to read request body: {}", e));
+            }
             return Ok(error_response(
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Failed to read body".to_string(),
@@ -335,6 +434,9 @@ async fn forward_request(
         }
     };
 
+    if let Some(e) = exchange.as_deref_mut() {
+        e.request_bytes = body_bytes.len();
+    }
     if !body_bytes.is_empty() {
         builder = builder.body(body_bytes.to_vec());
     }
@@ -347,6 +449,13 @@ async fn forward_request(
 
             match response.bytes().await {
                 Ok(body) => {
+                    if let Some(e) = exchange {
+                        e.status = Some(status.as_u16());
+                        e.response_bytes = body.len();
+                        e.response_headers = sanitize_headers(
+                            headers.iter().map(|(n, v)| (n.as_str(), v.as_bytes())),
+                        );
+                    }
                     let mut builder = Response::builder().status(status.as_u16());
 
                     for (name, value) in headers.iter() {
@@ -359,6 +468,10 @@ async fn forward_request(
                 }
                 Err(e) => {
                     tracing::error!("Proxy: failed to read response body: {}", e);
+                    if let Some(ex) = exchange {
+                        ex.status = Some(StatusCode::BAD_GATEWAY.as_u16());
+                        ex.error = Some(format!("failed to read response body: {}", e));
+                    }
                     Ok(error_response(
                         StatusCode::BAD_GATEWAY,
                         "Failed to read response".to_string(),
@@ -368,6 +481,10 @@ async fn forward_request(
         }
         Err(e) => {
             tracing::error!("Proxy: request failed: {}", e);
+            if let Some(ex) = exchange {
+                ex.status = Some(StatusCode::BAD_GATEWAY.as_u16());
+                ex.error = Some(format!("request failed: {}", e));
+            }
             Ok(error_response(
                 StatusCode::BAD_GATEWAY,
                 format!("Request failed: {}", e),
@@ -433,6 +550,56 @@ mod tests {
         tokio::time::sleep(std::time::Duration::from_millis(50)).await;
     }
 
+    /// Send a raw request through the proxy and return the status line.
+    async fn send_raw(addr: SocketAddr, request: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response.lines().next().unwrap_or_default().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_inspected_requests_are_sanitized() {
+        let allowlist = DomainAllowlist::new(&["example.com".to_string()]);
+        let decider = Arc::new(DefaultPolicyDecider::new(allowlist, vec![]));
+        let inspector = Arc::new(ProxyInspector::new(10));
+        let proxy = HttpProxy::with_inspector(
+            decider,
+            Arc::new(NoCredentialResolver),
+            Some(inspector.clone()),
+        );
+        let addr = proxy.start(0).await.unwrap();
+
+        let status = send_raw(
+            addr,
+            "GET http://blocked.test/v1/items?api_key=s3cret HTTP/1.1\r\n\
+             Host: blocked.test\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(status.contains("403"), "{}", status);
+
+        let status = send_raw(
+            addr,
+            "GET /callback?token=s3cret HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(status.contains("400"), "{}", status);
+        proxy.stop().await;
+
+        let events = inspector.query(&Default::default()).await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].decision, InspectDecision::Invalid);
+        assert_eq!(events[1].decision, InspectDecision::Deny);
+        assert_eq!(events[1].host, "blocked.test");
+        assert_eq!(events[1].path, "/v1/items");
+        for e in &events {
+            assert!(!e.url.contains("s3cret"), "{}", e.url);
+        }
+    }
+
     #[test]
     fn test_hop_by_hop_headers() {
         assert!(is_hop_by_hop_header("connection"));
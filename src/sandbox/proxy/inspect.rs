@@ -0,0 +1,512 @@
+//! Developer inspection view for proxied traffic.
+//!
+//! When `SANDBOX_INSPECT_PORT` is set, every exchange handled by the sandbox
+//! proxy is mirrored into a bounded in-memory log and served on a local-only
+//! HTTP endpoint. Only metadata is recorded: credential headers and query
+//! parameters are redacted and bodies are reduced to their sizes, so the view
+//! is safe to leave open while debugging a new tool integration.
+//!
+//! ```text
+//! GET /          HTML page with a search box and filters
+//! GET /events    JSON list of exchanges, newest first
+//!                ?q=<text>&host=<host>&method=<verb>&status=<code|4xx>
+//!                &decision=<allow|deny|invalid>&limit=<n>
+//! DELETE /events Clear the log
+//! ```
+
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::Html;
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::sandbox::error::{Result, SandboxError};
+
+/// Default number of exchanges kept before the oldest are dropped.
+pub const DEFAULT_INSPECT_CAPACITY: usize = 500;
+
+/// Hard cap on the number of exchanges returned by one query.
+const MAX_QUERY_LIMIT: usize = 1000;
+
+/// Placeholder written in place of redacted values.
+const REDACTED: &str = "[REDACTED]";
+
+/// Header names whose values are never mirrored.
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+    "x-auth-token",
+];
+
+/// Substrings that mark a header or query parameter name as sensitive.
+const SENSITIVE_NAME_PARTS: &[&str] = &["token", "secret", "password", "key", "signature", "auth"];
+
+/// How the proxy policy treated a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InspectDecision {
+    Allow,
+    Deny,
+    Invalid,
+}
+
+/// Sanitized metadata for one proxied exchange.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxyExchange {
+    /// Monotonic ID assigned when the exchange is recorded.
+    pub id: u64,
+    pub started_at: DateTime<Utc>,
+    pub method: String,
+    /// Request URL with sensitive query parameters redacted.
+    pub url: String,
+    pub host: String,
+    /// Request path, without the query string.
+    pub path: String,
+    pub decision: InspectDecision,
+    /// Why the request was denied or rejected, if it was.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Secret injected by the proxy (name only, never the value).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub injected_credential: Option<String>,
+    /// Status returned to the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    pub request_headers: Vec<(String, String)>,
+    pub response_headers: Vec<(String, String)>,
+    pub request_bytes: usize,
+    pub response_bytes: usize,
+    /// True for CONNECT tunnels, whose contents the proxy cannot see.
+    pub tunneled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ProxyExchange {
+    /// Start an exchange record for a request. The ID is assigned on record.
+    pub fn new(method: &str, url: &str, host: &str, path: &str) -> Self {
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        Self {
+            id: 0,
+            started_at: Utc::now(),
+            method: method.to_string(),
+            url: sanitize_url(url),
+            host: host.to_string(),
+            path: path.to_string(),
+            decision: InspectDecision::Allow,
+            reason: None,
+            injected_credential: None,
+            status: None,
+            duration_ms: 0,
+            request_headers: Vec::new(),
+            response_headers: Vec::new(),
+            request_bytes: 0,
+            response_bytes: 0,
+            tunneled: false,
+            error: None,
+        }
+    }
+
+    fn matches(&self, filter: &InspectFilter) -> bool {
+        if let Some(host) = filter.host.as_deref().filter(|h| !h.is_empty())
+            && !self.host.eq_ignore_ascii_case(host)
+        {
+            return false;
+        }
+        if let Some(method) = filter.method.as_deref().filter(|m| !m.is_empty())
+            && !self.method.eq_ignore_ascii_case(method)
+        {
+            return false;
+        }
+        if let Some(decision) = filter.decision
+            && self.decision != decision
+        {
+            return false;
+        }
+        if let Some(status) = filter.status.as_deref().filter(|s| !s.is_empty())
+            && !status_matches(self.status, status)
+        {
+            return false;
+        }
+        if let Some(q) = filter.q.as_deref().filter(|q| !q.is_empty()) {
+            let q = q.to_lowercase();
+            let haystacks = [
+                Some(self.url.as_str()),
+                self.reason.as_deref(),
+                self.error.as_deref(),
+                self.injected_credential.as_deref(),
+            ];
+            let in_fields = haystacks
+                .iter()
+                .flatten()
+                .any(|h| h.to_lowercase().contains(&q));
+            let in_headers = self
+                .request_headers
+                .iter()
+                .chain(self.response_headers.iter())
+                .any(|(n, v)| n.to_lowercase().contains(&q) || v.to_lowercase().contains(&q));
+            if !in_fields && !in_headers {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Query parameters accepted by `GET /events`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct InspectFilter {
+    /// Case-insensitive text search over URL, headers, reason, and error.
+    pub q: Option<String>,
+    pub host: Option<String>,
+    pub method: Option<String>,
+    /// Exact code (`404`) or class (`4xx`).
+    pub status: Option<String>,
+    pub decision: Option<InspectDecision>,
+    pub limit: Option<usize>,
+}
+
+/// Bounded log of proxied exchanges.
+pub struct ProxyInspector {
+    events: RwLock<VecDeque<ProxyExchange>>,
+    capacity: usize,
+    next_id: AtomicU64,
+}
+
+impl ProxyInspector {
+    /// Create an inspector that keeps at most `capacity` exchanges.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: RwLock::new(VecDeque::with_capacity(capacity.min(1024))),
+            capacity: capacity.max(1),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Record a finished exchange, evicting the oldest if the log is full.
+    pub async fn record(&self, mut exchange: ProxyExchange) {
+        exchange.id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let mut events = self.events.write().await;
+        if events.len() >= self.capacity {
+            events.pop_front();
+        }
+        events.push_back(exchange);
+    }
+
+    /// Return matching exchanges, newest first.
+    pub async fn query(&self, filter: &InspectFilter) -> Vec<ProxyExchange> {
+        let limit = filter
+            .limit
+            .unwrap_or(DEFAULT_INSPECT_CAPACITY)
+            .min(MAX_QUERY_LIMIT);
+        self.events
+            .read()
+            .await
+            .iter()
+            .rev()
+            .filter(|e| e.matches(filter))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Drop every recorded exchange.
+    pub async fn clear(&self) {
+        self.events.write().await.clear();
+    }
+
+    /// Number of exchanges currently held.
+    pub async fn len(&self) -> usize {
+        self.events.read().await.len()
+    }
+
+    /// Whether the log is empty.
+    pub async fn is_empty(&self) -> bool {
+        self.events.read().await.is_empty()
+    }
+
+    /// Serve the inspection view on `127.0.0.1:port` (0 for auto-assign).
+    ///
+    /// The server runs until the process exits; it is only ever bound to
+    /// loopback because the log reveals which hosts tools talk to.
+    pub async fn serve(self: Arc<Self>, port: u16) -> Result<SocketAddr> {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+            .await
+            .map_err(|e| SandboxError::ProxyError {
+                reason: format!("failed to bind inspector: {}", e),
+            })?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| SandboxError::ProxyError {
+                reason: format!("failed to get inspector addr: {}", e),
+            })?;
+
+        let app = Router::new()
+            .route("/", get(index))
+            .route("/events", get(list_events).delete(clear_events))
+            .with_state(self);
+
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                tracing::error!("Proxy inspector stopped: {}", e);
+            }
+        });
+
+        tracing::info!("Proxy inspector listening on http://{}", addr);
+        Ok(addr)
+    }
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+async fn list_events(
+    State(inspector): State<Arc<ProxyInspector>>,
+    Query(filter): Query<InspectFilter>,
+) -> Json<Vec<ProxyExchange>> {
+    Json(inspector.query(&filter).await)
+}
+
+async fn clear_events(State(inspector): State<Arc<ProxyInspector>>) -> StatusCode {
+    inspector.clear().await;
+    StatusCode::NO_CONTENT
+}
+
+/// Whether a status matches an exact code (`404`) or a class (`4xx`).
+fn status_matches(status: Option<u16>, filter: &str) -> bool {
+    let Some(status) = status else {
+        return false;
+    };
+    let filter = filter.trim().to_lowercase();
+    if let Some(class) = filter.strip_suffix("xx") {
+        return class.parse::<u16>().is_ok_and(|c| status / 100 == c);
+    }
+    filter.parse::<u16>().is_ok_and(|code| code == status)
+}
+
+fn is_sensitive_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SENSITIVE_HEADERS.contains(&name.as_str())
+        || SENSITIVE_NAME_PARTS.iter().any(|part| name.contains(part))
+}
+
+/// Copy headers, replacing credential-bearing values with a placeholder.
+pub fn sanitize_headers<'a>(
+    headers: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+) -> Vec<(String, String)> {
+    headers
+        .into_iter()
+        .map(|(name, value)| {
+            let value = if is_sensitive_name(name) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value).into_owned()
+            };
+            (name.to_lowercase(), value)
+        })
+        .collect()
+}
+
+/// Redact sensitive query parameter values and any userinfo in a URL.
+///
+/// A URL that can't be parsed can't be redacted piecemeal, so it is
+/// replaced entirely.
+pub fn sanitize_url(raw: &str) -> String {
+    let Ok(mut url) = reqwest::Url::parse(raw) else {
+        return REDACTED.to_string();
+    };
+    if !url.username().is_empty() || url.password().is_some() {
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+    }
+    if url.query().is_some() {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(k, v)| {
+                let v = if is_sensitive_name(&k) {
+                    REDACTED.to_string()
+                } else {
+                    v.into_owned()
+                };
+                (k.into_owned(), v)
+            })
+            .collect();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url.to_string()
+}
+
+const INDEX_HTML: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>IronClaw proxy inspector</title>
+<style>
+body { font: 13px system-ui, sans-serif; margin: 1em; }
+form { display: flex; gap: .5em; margin-bottom: 1em; }
+table { border-collapse: collapse; width: 100%; }
+td, th { border-bottom: 1px solid #ddd; padding: 4px 6px; text-align: left; vertical-align: top; }
+tr.deny, tr.invalid { background: #fdecea; }
+tr.err { background: #fff4e5; }
+pre { margin: 0; white-space: pre-wrap; font-size: 12px; }
+details summary { cursor: pointer; }
+</style>
+</head>
+<body>
+<form id="f">
+  <input name="q" placeholder="search url, headers, errors" size="40">
+  <input name="host" placeholder="host">
+  <input name="method" placeholder="method" size="6">
+  <input name="status" placeholder="status / 4xx" size="8">
+  <select name="decision"><option value="">any</option><option>allow</option><option>deny</option><option>invalid</option></select>
+  <button>Filter</button>
+  <button type="button" id="clear">Clear log</button>
+  <label><input type="checkbox" id="live" checked> live</label>
+</form>
+<table>
+<thead><tr><th>#</th><th>time</th><th>method</th><th>url</th><th>decision</th><th>status</th><th>ms</th><th>bytes</th><th>detail</th></tr></thead>
+<tbody id="rows"></tbody>
+</table>
+<script>
+const f = document.getElementById('f');
+const esc = s => String(s ?? '').replace(/[&<>"]/g, c => ({'&':'&amp;','<':'&lt;','>':'&gt;','"':'&quot;'}[c]));
+const hdrs = h => h.map(([n, v]) => esc(n) + ': ' + esc(v)).join('\n');
+async function load() {
+  const params = new URLSearchParams();
+  for (const [k, v] of new FormData(f)) if (v) params.set(k, v);
+  const events = await (await fetch('/events?' + params)).json();
+  document.getElementById('rows').innerHTML = events.map(e => {
+    const cls = e.decision !== 'allow' ? e.decision : (e.error || (e.status >= 400) ? 'err' : '');
+    const note = [e.reason, e.error, e.injected_credential && ('credential: ' + e.injected_credential), e.tunneled && 'CONNECT tunnel'].filter(Boolean).map(esc).join('<br>');
+    return `<tr class="${cls}"><td>${e.id}</td><td>${esc(e.started_at.slice(11, 23))}</td><td>${esc(e.method)}</td>` +
+      `<td>${esc(e.url)}</td><td>${e.decision}</td><td>${e.status ?? ''}</td><td>${e.duration_ms}</td>` +
+      `<td>${e.request_bytes}/${e.response_bytes}</td><td>${note}<details><summary>headers</summary>` +
+      `<pre>${hdrs(e.request_headers)}\n\n${hdrs(e.response_headers)}</pre></details></td></tr>`;
+  }).join('');
+}
+f.onsubmit = ev => { ev.preventDefault(); load(); };
+document.getElementById('clear').onclick = async () => { await fetch('/events', {method: 'DELETE'}); load(); };
+setInterval(() => { if (document.getElementById('live').checked) load(); }, 2000);
+load();
+</script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(method: &str, url: &str, status: Option<u16>) -> ProxyExchange {
+        let parsed = reqwest::Url::parse(url).unwrap();
+        let mut e = ProxyExchange::new(method, url, parsed.host_str().unwrap_or(""), parsed.path());
+        e.status = status;
+        e
+    }
+
+    #[test]
+    fn test_sanitize_url_redacts_credentials() {
+        let url = sanitize_url("https://user:pw@api.example.com/v1/items?api_key=abc&page=2");
+        assert!(!url.contains("abc"));
+        assert!(!url.contains("pw@"));
+        assert!(url.contains("page=2"));
+        assert!(url.contains("api_key=%5BREDACTED%5D"));
+
+        assert_eq!(sanitize_url("/callback?token=abc"), REDACTED);
+    }
+
+    #[test]
+    fn test_exchange_path_drops_query() {
+        let e = ProxyExchange::new(
+            "GET",
+            "https://x.test/a?token=abc",
+            "x.test",
+            "/a?token=abc",
+        );
+        assert_eq!(e.path, "/a");
+    }
+
+    #[test]
+    fn test_sanitize_headers_redacts_auth() {
+        let headers = sanitize_headers([
+            ("Authorization", b"Bearer secret".as_slice()),
+            ("X-Goog-Api-Key", b"abc".as_slice()),
+            ("Content-Type", b"application/json".as_slice()),
+        ]);
+        assert_eq!(headers[0], ("authorization".into(), REDACTED.into()));
+        assert_eq!(headers[1].1, REDACTED);
+        assert_eq!(headers[2].1, "application/json");
+    }
+
+    #[test]
+    fn test_status_matches_code_and_class() {
+        assert!(status_matches(Some(404), "404"));
+        assert!(status_matches(Some(404), "4xx"));
+        assert!(!status_matches(Some(200), "4xx"));
+        assert!(!status_matches(None, "200"));
+    }
+
+    #[tokio::test]
+    async fn test_query_filters_and_orders_newest_first() {
+        let inspector = ProxyInspector::new(10);
+        inspector
+            .record(exchange("GET", "https://api.github.com/repos", Some(200)))
+            .await;
+        let mut denied = exchange("POST", "https://evil.example.com/x", Some(403));
+        denied.decision = InspectDecision::Deny;
+        denied.reason = Some("domain not in allowlist".into());
+        inspector.record(denied).await;
+        inspector
+            .record(exchange("GET", "https://api.github.com/user", Some(401)))
+            .await;
+
+        let all = inspector.query(&InspectFilter::default()).await;
+        assert_eq!(all.iter().map(|e| e.id).collect::<Vec<_>>(), vec![3, 2, 1]);
+
+        let github = InspectFilter {
+            host: Some("api.github.com".into()),
+            status: Some("4xx".into()),
+            ..Default::default()
+        };
+        let hits = inspector.query(&github).await;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].path, "/user");
+
+        let search = InspectFilter {
+            q: Some("ALLOWLIST".into()),
+            ..Default::default()
+        };
+        let hits = inspector.query(&search).await;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].decision, InspectDecision::Deny);
+    }
+
+    #[tokio::test]
+    async fn test_capacity_evicts_oldest() {
+        let inspector = ProxyInspector::new(2);
+        for path in ["a", "b", "c"] {
+            inspector
+                .record(exchange("GET", &format!("https://x.test/{path}"), None))
+                .await;
+        }
+        let events = inspector.query(&InspectFilter::default()).await;
+        assert_eq!(inspector.len().await, 2);
+        assert_eq!(events.last().unwrap().path, "/b");
+
+        inspector.clear().await;
+        assert!(inspector.is_empty().await);
+    }
+}
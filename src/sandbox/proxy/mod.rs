@@ -4,6 +4,7 @@
 //! - Domain allowlist validation
 //! - Credential injection for API calls
 //! - Request logging and monitoring
+//! - Optional developer inspection view of proxied traffic
 //!
 //! # Architecture
 //!
@@ -30,10 +31,12 @@
 
 pub mod allowlist;
 pub mod http;
+pub mod inspect;
 pub mod policy;
 
 pub use allowlist::{DomainAllowlist, DomainPattern, DomainValidationResult};
 pub use http::{CredentialResolver, EnvCredentialResolver, HttpProxy, NoCredentialResolver};
+pub use inspect::{InspectFilter, ProxyExchange, ProxyInspector};
 pub use policy::{
     AllowAllDecider, DefaultPolicyDecider, DenyAllDecider, NetworkDecision, NetworkPolicyDecider,
     NetworkRequest,
@@ -52,6 +55,7 @@ pub struct NetworkProxyBuilder {
     credential_mappings: Vec<CredentialMapping>,
     credential_resolver: Arc<dyn CredentialResolver>,
     policy: SandboxPolicy,
+    inspector: Option<Arc<ProxyInspector>>,
 }
 
 impl NetworkProxyBuilder {
@@ -62,6 +66,7 @@ impl NetworkProxyBuilder {
             credential_mappings: default_credential_mappings(),
             credential_resolver: Arc::new(EnvCredentialResolver),
             policy: SandboxPolicy::ReadOnly,
+            inspector: None,
         }
     }
 
@@ -72,6 +77,7 @@ impl NetworkProxyBuilder {
             credential_mappings: default_credential_mappings(),
             credential_resolver: Arc::new(EnvCredentialResolver),
             policy: config.policy,
+            inspector: None,
         }
     }
 
@@ -105,6 +111,12 @@ impl NetworkProxyBuilder {
         self
     }
 
+    /// Mirror proxied exchanges into a developer inspection log.
+    pub fn with_inspector(mut self, inspector: Arc<ProxyInspector>) -> Self {
+        self.inspector = Some(inspector);
+        self
+    }
+
     /// Build the HTTP proxy.
     pub fn build(self) -> HttpProxy {
         let decider: Arc<dyn NetworkPolicyDecider> = if self.policy.has_full_network() {
@@ -116,7 +128,7 @@ impl NetworkProxyBuilder {
             ))
         };
 
-        HttpProxy::new(decider, self.credential_resolver)
+        HttpProxy::with_inspector(decider, self.credential_resolver, self.inspector)
     }
 
     /// Build and start the proxy on the given port.
@@ -0,0 +1,277 @@
+//! Disk quotas for job workspaces and scratch space.
+//!
+//! Bind-mounted host directories can't be size-limited by Docker, so quotas
+//! are enforced at three points instead:
+//!
+//! - File tools check a write against the quota before touching disk
+//! - Containers get an `fsize` ulimit, so no single file can outgrow the limit
+//! - Sandboxed commands are refused when the workspace is already over quota,
+//!   and fail with a quota error when they push it over
+//!
+//! Scratch space for completed jobs is removed once its retention expires
+//! (see [`sweep_scratch`]).
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use uuid::Uuid;
+
+use crate::sandbox::error::{Result, SandboxError};
+
+/// Limits on how much a single job may store in its workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkspaceQuota {
+    /// Total bytes across all files.
+    pub max_bytes: u64,
+    /// Total entries (files, directories, and links), i.e. inodes.
+    pub max_files: u64,
+    /// Largest single file.
+    pub max_file_bytes: u64,
+}
+
+impl Default for WorkspaceQuota {
+    fn default() -> Self {
+        Self {
+            max_bytes: 1024 * 1024 * 1024, // 1 GB
+            max_files: 10_000,
+            max_file_bytes: 100 * 1024 * 1024, // 100 MB
+        }
+    }
+}
+
+/// Current disk usage of a workspace.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WorkspaceUsage {
+    /// Total bytes across all files.
+    pub bytes: u64,
+    /// Total entries, not counting the root itself.
+    pub files: u64,
+}
+
+impl WorkspaceQuota {
+    /// Read the workspace root and quota the orchestrator hands a job container.
+    ///
+    /// Returns `None` outside a job container.
+    pub fn from_env() -> Option<(PathBuf, Self)> {
+        let root = std::env::var("IRONCLAW_WORKSPACE").ok()?;
+        let var = |key: &str| std::env::var(key).ok()?.parse().ok();
+        let quota = Self {
+            max_bytes: var("IRONCLAW_WORKSPACE_QUOTA_BYTES")?,
+            max_files: var("IRONCLAW_WORKSPACE_MAX_FILES")?,
+            max_file_bytes: var("IRONCLAW_MAX_FILE_BYTES")?,
+        };
+        Some((PathBuf::from(root), quota))
+    }
+
+    /// Check that `usage` fits within the quota.
+    pub fn check(&self, usage: WorkspaceUsage) -> Result<()> {
+        if usage.bytes > self.max_bytes {
+            return Err(SandboxError::QuotaExceeded {
+                resource: "workspace bytes".to_string(),
+                used: usage.bytes,
+                limit: self.max_bytes,
+            });
+        }
+        if usage.files > self.max_files {
+            return Err(SandboxError::QuotaExceeded {
+                resource: "workspace files".to_string(),
+                used: usage.files,
+                limit: self.max_files,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that writing `new_len` bytes to `path` keeps `root` within quota.
+    ///
+    /// Overwriting an existing file only counts the difference in size.
+    /// Writes outside `root` are only held to the single-file limit.
+    pub fn check_write(&self, root: &Path, path: &Path, new_len: u64) -> Result<()> {
+        if new_len > self.max_file_bytes {
+            return Err(SandboxError::QuotaExceeded {
+                resource: "file size".to_string(),
+                used: new_len,
+                limit: self.max_file_bytes,
+            });
+        }
+        if !path.starts_with(root) {
+            return Ok(());
+        }
+
+        let mut usage = measure(root)?;
+        match std::fs::symlink_metadata(path) {
+            Ok(existing) if existing.is_file() => {
+                usage.bytes = usage.bytes.saturating_sub(existing.len());
+            }
+            Ok(_) => {}
+            Err(_) => usage.files += new_entries(root, path),
+        }
+        usage.bytes += new_len;
+
+        self.check(usage)
+    }
+}
+
+/// Measure the disk usage of everything under `root`.
+///
+/// Symlinks are counted but not followed. A missing root is empty.
+pub fn measure(root: &Path) -> Result<WorkspaceUsage> {
+    let mut usage = WorkspaceUsage::default();
+    if !root.exists() {
+        return Ok(usage);
+    }
+
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            usage.files += 1;
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                usage.bytes += metadata.len();
+            }
+        }
+    }
+
+    Ok(usage)
+}
+
+/// Number of entries a write to `path` would create: the file plus any
+/// missing parent directories below `root`.
+fn new_entries(root: &Path, path: &Path) -> u64 {
+    let mut count = 1;
+    let mut parent = path.parent();
+    while let Some(dir) = parent {
+        if !dir.starts_with(root) || dir == root || dir.exists() {
+            break;
+        }
+        count += 1;
+        parent = dir.parent();
+    }
+    count
+}
+
+/// Host directory holding scratch space for all jobs.
+pub fn scratch_base() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".ironclaw")
+        .join("scratch")
+}
+
+/// Scratch directory for a single job, created if missing.
+pub fn scratch_dir(base: &Path, job_id: Uuid) -> Result<PathBuf> {
+    let dir = base.join(job_id.to_string());
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Remove scratch directories of finished jobs untouched for `retention`.
+///
+/// Directories whose job is still running (per `is_running`) are kept no
+/// matter how old they are. Returns the number of directories removed.
+pub fn sweep_scratch(
+    base: &Path,
+    retention: Duration,
+    is_running: impl Fn(Uuid) -> bool,
+) -> Result<usize> {
+    if !base.exists() {
+        return Ok(0);
+    }
+
+    let now = SystemTime::now();
+    let mut removed = 0;
+    for entry in std::fs::read_dir(base)? {
+        let entry = entry?;
+        let Some(job_id) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| Uuid::parse_str(name).ok())
+        else {
+            continue;
+        };
+        if is_running(job_id) {
+            continue;
+        }
+
+        let age = entry
+            .metadata()?
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if age < retention {
+            continue;
+        }
+
+        std::fs::remove_dir_all(entry.path())?;
+        removed += 1;
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_counts_files_and_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(dir.path().join("README"), "hi").unwrap();
+
+        let usage = measure(dir.path()).unwrap();
+        assert_eq!(usage.files, 3);
+        assert_eq!(usage.bytes, 14);
+    }
+
+    #[test]
+    fn test_check_write_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let quota = WorkspaceQuota {
+            max_bytes: 10,
+            max_files: 2,
+            max_file_bytes: 8,
+        };
+
+        let file = dir.path().join("a.txt");
+        assert!(quota.check_write(dir.path(), &file, 9).is_err());
+        assert!(quota.check_write(dir.path(), &file, 8).is_ok());
+        std::fs::write(&file, "12345678").unwrap();
+
+        // Overwriting counts only the difference
+        assert!(quota.check_write(dir.path(), &file, 8).is_ok());
+        // A second file would exceed the byte budget
+        assert!(
+            quota
+                .check_write(dir.path(), &dir.path().join("b.txt"), 4)
+                .is_err()
+        );
+        // A nested file needs a directory too, exceeding the entry budget
+        assert!(
+            quota
+                .check_write(dir.path(), &dir.path().join("d/c.txt"), 1)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_sweep_scratch_keeps_running_jobs() {
+        let base = tempfile::tempdir().unwrap();
+        let running = Uuid::new_v4();
+        let finished = Uuid::new_v4();
+        scratch_dir(base.path(), running).unwrap();
+        scratch_dir(base.path(), finished).unwrap();
+        std::fs::create_dir(base.path().join("not-a-job")).unwrap();
+
+        let removed = sweep_scratch(base.path(), Duration::ZERO, |id| id == running).unwrap();
+        assert_eq!(removed, 1);
+        assert!(base.path().join(running.to_string()).exists());
+        assert!(!base.path().join(finished.to_string()).exists());
+        assert!(base.path().join("not-a-job").exists());
+    }
+}
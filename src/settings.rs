@@ -14,6 +14,11 @@ pub struct Settings {
     #[serde(default, alias = "setup_completed")]
     pub onboard_completed: bool,
 
+    /// Settings schema version (0 = written before versioning).
+    /// Bumped by the upgrade assistant, see `setup::upgrade`.
+    #[serde(default)]
+    pub settings_version: u32,
+
     // === Step 1: Database ===
     /// Database connection URL (postgres://...).
     #[serde(default)]
@@ -52,6 +52,16 @@ impl SecretsContext {
             .await
             .unwrap_or(false)
     }
+
+    /// Underlying secrets store (for flows that manage their own secrets).
+    pub fn store(&self) -> &PostgresSecretsStore {
+        &self.store
+    }
+
+    /// User ID secrets are saved under.
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
 }
 
 /// Result of Telegram setup.
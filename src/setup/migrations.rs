@@ -0,0 +1,13 @@
+//! Database migrations embedded from `migrations/`.
+//!
+//! The wizard applies them and the upgrade assistant lists the pending ones;
+//! both go through [`runner`] so they always agree on the set.
+
+mod embedded {
+    refinery::embed_migrations!("migrations");
+}
+
+/// A runner over every migration shipped with this build.
+pub fn runner() -> refinery::Runner {
+    embedded::migrations::runner()
+}
@@ -7,7 +7,12 @@
 //! 4. Model selection
 //! 5. Embeddings
 //! 6. Channel configuration (HTTP, Telegram, etc.)
-//! 7. Heartbeat (background tasks)
+//! 7. Tool authentication (Google OAuth, etc.)
+//! 8. Heartbeat (background tasks)
+//!
+//! Named profiles seed defaults, and a `setup.toml` file can drive the
+//! whole flow without prompts (see [`SetupFile`]). Upgrade mode checks an
+//! existing install for settings and database changes (see [`UpgradePlan`]).
 //!
 //! # Example
 //!
@@ -19,14 +24,19 @@
 //! ```
 
 mod channels;
+mod migrations;
+mod profile;
 mod prompts;
+pub mod upgrade;
 mod wizard;
 
 pub use channels::{
     SecretsContext, setup_http, setup_telegram, setup_tunnel, validate_telegram_token,
 };
+pub use profile::{SetupFile, SetupProfile, substitute_env};
 pub use prompts::{
     confirm, input, optional_input, print_error, print_header, print_info, print_step,
     print_success, secret_input, select_many, select_one,
 };
-pub use wizard::{SetupConfig, SetupWizard};
+pub use upgrade::UpgradePlan;
+pub use wizard::{SetupConfig, SetupError, SetupWizard};
@@ -0,0 +1,350 @@
+//! Setup profiles and non-interactive provisioning.
+//!
+//! Profiles are named presets (`personal-laptop`, `server`, `demo`) that seed
+//! sensible defaults before the wizard runs. A setup file lets the whole
+//! configuration be applied without prompts:
+//!
+//! ```toml
+//! profile = "server"
+//!
+//! [settings]
+//! database_url = "${DATABASE_URL}"
+//! selected_model = "${IRONCLAW_MODEL:-gemini-2.5-flash}"
+//!
+//! [settings.heartbeat]
+//! enabled = true
+//! interval_secs = 900
+//!
+//! [secrets]
+//! telegram_bot_token = "${TELEGRAM_BOT_TOKEN}"
+//! ```
+//!
+//! Inside string values, `${VAR}` is replaced with the environment variable's
+//! value (missing variables are an error), `${VAR:-default}` falls back to
+//! `default`, and `$$` produces a literal `$`. The file is parsed first, so a
+//! value containing quotes or newlines can't break the TOML, and comments are
+//! left alone.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::settings::{KeySource, Settings};
+use crate::setup::wizard::SetupError;
+
+/// Named preset of setup defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SetupProfile {
+    /// Single user on a desktop: keychain secrets, semantic search, no webhooks.
+    PersonalLaptop,
+    /// Headless host: env-provided master key, HTTP webhook, heartbeat on.
+    Server,
+    /// Throwaway demo: no secrets, no Docker sandbox, nothing in the background.
+    Demo,
+}
+
+impl SetupProfile {
+    /// Profile name as used on the command line and in setup files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PersonalLaptop => "personal-laptop",
+            Self::Server => "server",
+            Self::Demo => "demo",
+        }
+    }
+
+    /// Apply this profile's defaults to the settings.
+    pub fn apply(&self, settings: &mut Settings) {
+        match self {
+            Self::PersonalLaptop => {
+                settings.secrets_master_key_source = KeySource::Keychain;
+                settings.embeddings.enabled = true;
+                settings.channels.http_enabled = false;
+                settings.heartbeat.enabled = false;
+            }
+            Self::Server => {
+                settings.secrets_master_key_source = KeySource::Env;
+                settings.embeddings.enabled = true;
+                settings.channels.http_enabled = true;
+                settings.channels.http_port = Some(8080);
+                settings.channels.http_host = Some("0.0.0.0".to_string());
+                settings.heartbeat.enabled = true;
+                settings.heartbeat.interval_secs = 1800;
+            }
+            Self::Demo => {
+                settings.secrets_master_key_source = KeySource::None;
+                settings.embeddings.enabled = false;
+                settings.channels.http_enabled = false;
+                settings.channels.wasm_channels.clear();
+                settings.heartbeat.enabled = false;
+                settings.sandbox.enabled = false;
+            }
+        }
+    }
+}
+
+/// Contents of a `setup.toml` file for non-interactive setup.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetupFile {
+    /// Profile applied before the explicit settings.
+    #[serde(default)]
+    pub profile: Option<SetupProfile>,
+
+    /// Whether to run database migrations (default: true).
+    #[serde(default = "default_true")]
+    pub run_migrations: bool,
+
+    /// Settings overrides, using the same layout as settings.json.
+    #[serde(default)]
+    pub settings: toml::Table,
+
+    /// Secrets to store in the encrypted secrets store.
+    #[serde(default)]
+    pub secrets: BTreeMap<String, String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl SetupFile {
+    /// Read a setup file, substituting environment variables first.
+    pub fn load(path: &Path) -> Result<Self, SetupError> {
+        let raw = std::fs::read_to_string(path)?;
+        Self::parse(&raw, |name| std::env::var(name).ok())
+    }
+
+    /// Parse setup file contents, resolving variables in string values with `lookup`.
+    pub fn parse(raw: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<Self, SetupError> {
+        let table: toml::Table = toml::from_str(raw)
+            .map_err(|e| SetupError::Config(format!("Invalid setup file: {}", e)))?;
+        let mut value = toml::Value::Table(table);
+        substitute_strings(&mut value, &lookup)?;
+        value
+            .try_into()
+            .map_err(|e| SetupError::Config(format!("Invalid setup file: {}", e)))
+    }
+
+    /// Merge the `[settings]` table over the given settings.
+    pub fn apply_settings(&self, settings: &mut Settings) -> Result<(), SetupError> {
+        if self.settings.is_empty() {
+            return Ok(());
+        }
+
+        let mut current = serde_json::to_value(&*settings)
+            .map_err(|e| SetupError::Config(format!("Failed to serialize settings: {}", e)))?;
+        let overrides = serde_json::to_value(&self.settings)
+            .map_err(|e| SetupError::Config(format!("Invalid [settings] table: {}", e)))?;
+
+        merge_json(&mut current, overrides);
+
+        *settings = serde_json::from_value(current)
+            .map_err(|e| SetupError::Config(format!("Invalid [settings] table: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// Run [`substitute_env`] over every string in `value`, recursively.
+fn substitute_strings(
+    value: &mut toml::Value,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<(), SetupError> {
+    match value {
+        toml::Value::String(s) => *s = substitute_env(s, lookup)?,
+        toml::Value::Array(items) => {
+            for item in items {
+                substitute_strings(item, lookup)?;
+            }
+        }
+        toml::Value::Table(table) => {
+            for (_, item) in table.iter_mut() {
+                substitute_strings(item, lookup)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Recursively merge `overlay` into `base`; objects merge, everything else replaces.
+fn merge_json(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base_map), serde_json::Value::Object(overlay_map)) => {
+            for (key, value) in overlay_map {
+                match base_map.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Replace `${VAR}` and `${VAR:-default}` references in `input`.
+pub fn substitute_env(
+    input: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, SetupError> {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        if let Some(stripped) = after.strip_prefix('$') {
+            out.push('$');
+            rest = stripped;
+        } else if let Some(body) = after.strip_prefix('{') {
+            let end = body.find('}').ok_or_else(|| {
+                SetupError::Config("Unterminated ${...} in setup file".to_string())
+            })?;
+            let expr = &body[..end];
+            let (name, default) = match expr.split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (expr, None),
+            };
+
+            match lookup(name).filter(|v| !v.is_empty()) {
+                Some(value) => out.push_str(&value),
+                None => match default {
+                    Some(default) => out.push_str(default),
+                    None => {
+                        return Err(SetupError::Config(format!(
+                            "Environment variable {} is not set",
+                            name
+                        )));
+                    }
+                },
+            }
+            rest = &body[end + 1..];
+        } else {
+            out.push('$');
+            rest = after;
+        }
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "DB" => Some("postgres://localhost/ironclaw".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_substitute_env() {
+        assert_eq!(
+            substitute_env("url = ${DB}", env).unwrap(),
+            "url = postgres://localhost/ironclaw"
+        );
+        assert_eq!(
+            substitute_env("${MISSING:-fallback}", env).unwrap(),
+            "fallback"
+        );
+        assert_eq!(
+            substitute_env("${EMPTY:-fallback}", env).unwrap(),
+            "fallback"
+        );
+        assert_eq!(
+            substitute_env("cost: $$5 and $x", env).unwrap(),
+            "cost: $5 and $x"
+        );
+    }
+
+    #[test]
+    fn test_substitute_env_errors() {
+        assert!(substitute_env("${MISSING}", env).is_err());
+        assert!(substitute_env("${DB", env).is_err());
+    }
+
+    #[test]
+    fn test_profile_apply() {
+        let mut settings = Settings::default();
+        SetupProfile::Server.apply(&mut settings);
+        assert_eq!(settings.secrets_master_key_source, KeySource::Env);
+        assert!(settings.channels.http_enabled);
+        assert!(settings.heartbeat.enabled);
+
+        SetupProfile::Demo.apply(&mut settings);
+        assert_eq!(settings.secrets_master_key_source, KeySource::None);
+        assert!(!settings.heartbeat.enabled);
+        assert!(!settings.sandbox.enabled);
+    }
+
+    #[test]
+    fn test_parse_setup_file() {
+        let raw = r#"
+            profile = "personal-laptop"
+            run_migrations = false
+
+            [settings]
+            database_url = "${DB}"
+            selected_model = "${MODEL:-gemini-2.5-flash}"
+
+            [settings.heartbeat]
+            interval_secs = 600
+
+            [secrets]
+            telegram_bot_token = "123:abc"
+        "#;
+
+        let file = SetupFile::parse(raw, env).unwrap();
+        assert_eq!(file.profile, Some(SetupProfile::PersonalLaptop));
+        assert!(!file.run_migrations);
+        assert_eq!(file.secrets.get("telegram_bot_token").unwrap(), "123:abc");
+
+        let mut settings = Settings::default();
+        settings.heartbeat.notify_channel = Some("telegram".to_string());
+        file.apply_settings(&mut settings).unwrap();
+
+        assert_eq!(
+            settings.database_url.as_deref(),
+            Some("postgres://localhost/ironclaw")
+        );
+        assert_eq!(settings.selected_model.as_deref(), Some("gemini-2.5-flash"));
+        assert_eq!(settings.heartbeat.interval_secs, 600);
+        // Untouched nested fields survive the merge
+        assert_eq!(
+            settings.heartbeat.notify_channel.as_deref(),
+            Some("telegram")
+        );
+    }
+
+    #[test]
+    fn test_parse_setup_file_rejects_unknown_keys() {
+        assert!(SetupFile::parse("profil = \"server\"", env).is_err());
+        assert!(SetupFile::parse("profile = \"cloud\"", env).is_err());
+    }
+
+    #[test]
+    fn test_parse_setup_file_substitutes_inside_strings_only() {
+        let env = |name: &str| match name {
+            "TOKEN" => Some("a\"b\\c\nd".to_string()),
+            _ => None,
+        };
+        let raw = r#"
+            # Set ${UNSET_IN_COMMENT} before running
+            [secrets]
+            token = "${TOKEN}"
+        "#;
+
+        let file = SetupFile::parse(raw, env).unwrap();
+        assert_eq!(file.secrets.get("token").unwrap(), "a\"b\\c\nd");
+        assert!(SetupFile::parse("[secrets]\ntoken = \"${UNSET}\"", env).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! Upgrade assistant for existing installs.
+//!
+//! Compares an existing `settings.json` and database against what this build
+//! expects, and reports:
+//! - Settings file migrations (renamed or restructured keys)
+//! - Required settings that are missing
+//! - Database migrations that have not been applied yet
+//!
+//! The wizard's upgrade mode backs up the settings file (and the database,
+//! when `pg_dump` is available) before applying anything.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::settings::Settings;
+use crate::setup::migrations;
+use crate::setup::wizard::SetupError;
+
+/// Settings schema version written by this build.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// A single settings file migration.
+struct SettingsMigration {
+    /// Version the settings file is at after this migration.
+    version: u32,
+    /// Human-readable summary shown before applying.
+    description: &'static str,
+    apply: fn(&mut Map<String, Value>),
+}
+
+/// All settings migrations, in version order.
+const SETTINGS_MIGRATIONS: &[SettingsMigration] = &[SettingsMigration {
+    version: 1,
+    description: "Rename `setup_completed` to `onboard_completed`",
+    apply: rename_setup_completed,
+}];
+
+fn rename_setup_completed(obj: &mut Map<String, Value>) {
+    if let Some(value) = obj.remove("setup_completed") {
+        obj.entry("onboard_completed").or_insert(value);
+    }
+}
+
+/// Everything an upgrade would change.
+#[derive(Debug, Default)]
+pub struct UpgradePlan {
+    /// Settings version found on disk (0 = written before versioning).
+    pub from_version: u32,
+    /// Descriptions of pending settings migrations.
+    pub settings_changes: Vec<&'static str>,
+    /// Required settings that are not configured anywhere.
+    pub missing_settings: Vec<&'static str>,
+    /// Names of database migrations not yet applied (e.g. "V3__tool_failures").
+    pub pending_db_migrations: Vec<String>,
+}
+
+impl UpgradePlan {
+    /// Whether there is nothing to do.
+    pub fn is_empty(&self) -> bool {
+        self.settings_changes.is_empty()
+            && self.missing_settings.is_empty()
+            && self.pending_db_migrations.is_empty()
+    }
+}
+
+/// Read the settings version from raw settings JSON.
+pub fn settings_version(raw: &Value) -> u32 {
+    raw.get("settings_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32
+}
+
+/// Build the settings part of an upgrade plan from raw settings JSON.
+pub fn plan_settings_upgrade(raw: &Value) -> UpgradePlan {
+    let from_version = settings_version(raw);
+    let settings_changes = SETTINGS_MIGRATIONS
+        .iter()
+        .filter(|m| m.version > from_version)
+        .map(|m| m.description)
+        .collect();
+
+    let settings: Settings = serde_json::from_value(raw.clone()).unwrap_or_default();
+
+    UpgradePlan {
+        from_version,
+        settings_changes,
+        missing_settings: missing_required_settings(&settings),
+        pending_db_migrations: Vec::new(),
+    }
+}
+
+/// Apply pending settings migrations to raw JSON and stamp the new version.
+pub fn apply_settings_migrations(raw: &mut Value) -> Result<(), SetupError> {
+    let from_version = settings_version(raw);
+    let obj = raw
+        .as_object_mut()
+        .ok_or_else(|| SetupError::Config("settings.json is not a JSON object".to_string()))?;
+
+    for migration in SETTINGS_MIGRATIONS
+        .iter()
+        .filter(|m| m.version > from_version)
+    {
+        (migration.apply)(obj);
+    }
+
+    obj.insert("settings_version".to_string(), SETTINGS_VERSION.into());
+    Ok(())
+}
+
+/// Settings the agent cannot start without, checked against env vars too.
+pub fn missing_required_settings(settings: &Settings) -> Vec<&'static str> {
+    let mut missing = Vec::new();
+
+    if settings.database_url.is_none() && std::env::var("DATABASE_URL").is_err() {
+        missing.push("database_url");
+    }
+    if settings.selected_model.is_none() && std::env::var("NEARAI_MODEL").is_err() {
+        missing.push("selected_model");
+    }
+
+    missing
+}
+
+/// List embedded database migrations that have not been applied.
+pub async fn pending_db_migrations(
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<String>, SetupError> {
+    let mut client = pool
+        .get()
+        .await
+        .map_err(|e| SetupError::Database(format!("Pool error: {}", e)))?;
+
+    let runner = migrations::runner();
+    let applied: HashSet<u32> = runner
+        .get_applied_migrations_async(&mut **client)
+        .await
+        .map_err(|e| SetupError::Database(format!("Failed to read migration history: {}", e)))?
+        .iter()
+        .map(|m| m.version())
+        .collect();
+
+    Ok(runner
+        .get_migrations()
+        .iter()
+        .filter(|m| !applied.contains(&m.version()))
+        .map(|m| format!("V{}__{}", m.version(), m.name()))
+        .collect())
+}
+
+/// Directory upgrade backups are written to (~/.ironclaw/backups).
+pub fn backups_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".ironclaw")
+        .join("backups")
+}
+
+/// Copy a file into `dir` with a timestamp suffix, returning the backup path.
+pub fn backup_file(path: &Path, dir: &Path) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("backup");
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("bak");
+    let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+    let target = dir.join(format!("{}-{}.{}", stem, stamp, ext));
+
+    std::fs::copy(path, &target)?;
+    Ok(target)
+}
+
+/// Dump the database with `pg_dump` into `dir`.
+///
+/// Returns `Ok(None)` if `pg_dump` is not installed.
+pub fn backup_database(database_url: &str, dir: &Path) -> Result<Option<PathBuf>, SetupError> {
+    std::fs::create_dir_all(dir)?;
+
+    let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+    let target = dir.join(format!("database-{}.sql", stamp));
+
+    let output = match std::process::Command::new("pg_dump")
+        .arg("--file")
+        .arg(&target)
+        .arg(database_url)
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(SetupError::Io(e)),
+    };
+
+    if !output.status.success() {
+        return Err(SetupError::Database(format!(
+            "pg_dump failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(Some(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_for_unversioned_settings() {
+        let raw = serde_json::json!({
+            "setup_completed": true,
+            "database_url": "postgres://localhost/ironclaw",
+            "selected_model": "gemini-2.5-flash"
+        });
+
+        let plan = plan_settings_upgrade(&raw);
+        assert_eq!(plan.from_version, 0);
+        assert_eq!(plan.settings_changes.len(), SETTINGS_MIGRATIONS.len());
+        assert!(plan.missing_settings.is_empty());
+        assert!(!plan.is_empty());
+    }
+
+    #[test]
+    fn test_plan_for_current_settings() {
+        let raw = serde_json::json!({
+            "settings_version": SETTINGS_VERSION,
+            "database_url": "postgres://localhost/ironclaw",
+            "selected_model": "gemini-2.5-flash"
+        });
+
+        let plan = plan_settings_upgrade(&raw);
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn test_apply_settings_migrations() {
+        let mut raw = serde_json::json!({ "setup_completed": true });
+        apply_settings_migrations(&mut raw).unwrap();
+
+        assert!(raw.get("setup_completed").is_none());
+        assert_eq!(raw["onboard_completed"], true);
+        assert_eq!(settings_version(&raw), SETTINGS_VERSION);
+
+        let settings: Settings = serde_json::from_value(raw).unwrap();
+        assert!(settings.onboard_completed);
+    }
+
+    #[test]
+    fn test_backup_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("settings.json");
+        std::fs::write(&source, "{}").unwrap();
+
+        let backup = backup_file(&source, &dir.path().join("backups")).unwrap();
+        assert!(backup.exists());
+        assert!(
+            backup
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("settings-")
+        );
+        assert_eq!(std::fs::read_to_string(backup).unwrap(), "{}");
+    }
+}
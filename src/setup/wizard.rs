@@ -7,9 +7,11 @@
 //! 4. Model selection
 //! 5. Embeddings
 //! 6. Channel configuration
-//! 7. Heartbeat (background tasks)
+//! 7. Tool authentication (OAuth for Google and other installed tools)
+//! 8. Heartbeat (background tasks)
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use deadpool_postgres::{Config as PoolConfig, Runtime};
@@ -19,16 +21,23 @@ use tokio_postgres::NoTls;
 use crate::channels::wasm::{
     ChannelCapabilitiesFile, bundled_channel_names, install_bundled_channel,
 };
-use crate::llm::{SessionConfig, SessionManager};
+use crate::error::LlmError;
+use crate::llm::{
+    CatalogModel, LlmProvider, ModelCatalog, SessionConfig, SessionManager, default_catalog_path,
+};
 use crate::secrets::SecretsCrypto;
 use crate::settings::{KeySource, Settings};
 use crate::setup::channels::{
     SecretsContext, setup_http, setup_telegram, setup_tunnel, setup_wasm_channel,
 };
+use crate::setup::migrations;
+use crate::setup::profile::{SetupFile, SetupProfile};
 use crate::setup::prompts::{
     confirm, input, optional_input, print_error, print_header, print_info, print_step,
     print_success, select_many, select_one,
 };
+use crate::setup::upgrade::{self, SETTINGS_VERSION};
+use crate::tools::wasm::{AuthCapabilitySchema, CapabilitiesFile};
 
 /// Setup wizard error.
 #[derive(Debug, thiserror::Error)]
@@ -59,6 +68,12 @@ pub struct SetupConfig {
     pub skip_auth: bool,
     /// Only reconfigure channels.
     pub channels_only: bool,
+    /// Profile whose defaults are applied before any prompts.
+    pub profile: Option<SetupProfile>,
+    /// Provision from this setup file without prompting.
+    pub from_file: Option<PathBuf>,
+    /// Upgrade an existing install instead of running first-time setup.
+    pub upgrade: bool,
 }
 
 /// Interactive setup wizard for IronClaw.
@@ -103,14 +118,26 @@ impl SetupWizard {
 
     /// Run the setup wizard.
     pub async fn run(&mut self) -> Result<(), SetupError> {
+        if let Some(path) = self.config.from_file.clone() {
+            return self.run_from_file(&path).await;
+        }
+        if self.config.upgrade {
+            return self.run_upgrade().await;
+        }
+
         print_header("IronClaw Setup Wizard");
 
+        if let Some(profile) = self.config.profile {
+            profile.apply(&mut self.settings);
+            print_info(&format!("Using '{}' profile defaults", profile.name()));
+        }
+
         if self.config.channels_only {
             // Channels-only mode: just step 6
             print_step(1, 1, "Channel Configuration");
             self.step_channels().await?;
         } else {
-            let total_steps = 7;
+            let total_steps = 8;
 
             // Step 1: Database
             print_step(1, total_steps, "Database Connection");
@@ -140,8 +167,12 @@ impl SetupWizard {
             print_step(6, total_steps, "Channel Configuration");
             self.step_channels().await?;
 
-            // Step 7: Heartbeat
-            print_step(7, total_steps, "Background Tasks");
+            // Step 7: Tool authentication
+            print_step(7, total_steps, "Tool Authentication");
+            self.step_tool_auth().await?;
+
+            // Step 8: Heartbeat
+            print_step(8, total_steps, "Background Tasks");
             self.step_heartbeat()?;
         }
 
@@ -151,6 +182,177 @@ impl SetupWizard {
         Ok(())
     }
 
+    /// Provision from a setup file without any prompts.
+    ///
+    /// NEAR AI login needs a browser, so it is left for the first run; use
+    /// `NEARAI_API_KEY` or an existing session on headless hosts.
+    async fn run_from_file(&mut self, path: &std::path::Path) -> Result<(), SetupError> {
+        print_header("IronClaw Setup (non-interactive)");
+
+        let file = SetupFile::load(path)?;
+
+        // The command-line profile wins over the one named in the file
+        if let Some(profile) = self.config.profile.or(file.profile) {
+            profile.apply(&mut self.settings);
+            print_info(&format!("Applied '{}' profile", profile.name()));
+        }
+        file.apply_settings(&mut self.settings)?;
+
+        let database_url = self
+            .settings
+            .database_url
+            .clone()
+            .or_else(|| std::env::var("DATABASE_URL").ok());
+        if let Some(url) = database_url {
+            self.test_database_connection(&url).await?;
+            print_success("Database connection successful");
+            if file.run_migrations {
+                self.run_migrations().await?;
+            }
+            self.settings.database_url = Some(url);
+        }
+
+        if self.settings.secrets_master_key_source == KeySource::Keychain
+            && !crate::secrets::keychain::has_master_key()
+        {
+            let key = crate::secrets::keychain::generate_master_key();
+            crate::secrets::keychain::store_master_key(&key)
+                .map_err(|e| SetupError::Config(format!("Failed to store in keychain: {}", e)))?;
+            print_success("Master key generated and stored in OS keychain");
+        }
+
+        if !file.secrets.is_empty() {
+            let secrets = self.init_secrets_context().await?;
+            for (name, value) in &file.secrets {
+                secrets
+                    .save_secret(name, &SecretString::from(value.clone()))
+                    .await
+                    .map_err(SetupError::Config)?;
+            }
+            print_success(&format!("Saved {} secret(s)", file.secrets.len()));
+        }
+
+        self.save_and_summarize()
+    }
+
+    /// Upgrade an existing install: show what changed, back up, then apply.
+    async fn run_upgrade(&mut self) -> Result<(), SetupError> {
+        print_header("IronClaw Upgrade Assistant");
+
+        let settings_path = Settings::default_path();
+        let raw: serde_json::Value = match std::fs::read_to_string(&settings_path) {
+            Ok(data) => serde_json::from_str(&data).map_err(|e| {
+                SetupError::Config(format!(
+                    "{} is not valid JSON: {}",
+                    settings_path.display(),
+                    e
+                ))
+            })?,
+            Err(_) => {
+                print_info("No existing configuration found. Run `ironclaw onboard` instead.");
+                return Ok(());
+            }
+        };
+
+        let mut plan = upgrade::plan_settings_upgrade(&raw);
+
+        let database_url = self
+            .settings
+            .database_url
+            .clone()
+            .or_else(|| std::env::var("DATABASE_URL").ok());
+        if let Some(ref url) = database_url {
+            match self.test_database_connection(url).await {
+                Ok(()) => {
+                    if let Some(ref pool) = self.db_pool {
+                        plan.pending_db_migrations = upgrade::pending_db_migrations(pool).await?;
+                    }
+                }
+                Err(e) => print_error(&format!("Could not check database migrations: {}", e)),
+            }
+        }
+
+        print_info(&format!(
+            "Settings version: {} (current: {})",
+            plan.from_version, SETTINGS_VERSION
+        ));
+        println!();
+
+        if plan.is_empty() {
+            print_success("Everything is up to date");
+            return Ok(());
+        }
+
+        if !plan.settings_changes.is_empty() {
+            println!("Settings changes:");
+            for change in &plan.settings_changes {
+                println!("  - {}", change);
+            }
+        }
+        if !plan.missing_settings.is_empty() {
+            println!("New required settings:");
+            for key in &plan.missing_settings {
+                println!("  - {}", key);
+            }
+        }
+        if !plan.pending_db_migrations.is_empty() {
+            println!("Database migrations:");
+            for name in &plan.pending_db_migrations {
+                println!("  - {}", name);
+            }
+        }
+        println!();
+
+        if !confirm("Back up and apply these changes?", true).map_err(SetupError::Io)? {
+            return Err(SetupError::Cancelled);
+        }
+
+        // Backups first, so a failed upgrade can be rolled back by hand
+        let backups = upgrade::backups_dir();
+        let settings_backup = upgrade::backup_file(&settings_path, &backups)?;
+        print_success(&format!(
+            "Settings backed up to {}",
+            settings_backup.display()
+        ));
+
+        if !plan.pending_db_migrations.is_empty() {
+            if let Some(ref url) = database_url {
+                match upgrade::backup_database(url, &backups)? {
+                    Some(path) => {
+                        print_success(&format!("Database backed up to {}", path.display()))
+                    }
+                    None => {
+                        print_info("pg_dump not found; the database was not backed up.");
+                        if !confirm("Apply database migrations without a backup?", false)
+                            .map_err(SetupError::Io)?
+                        {
+                            return Err(SetupError::Cancelled);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut raw = raw;
+        upgrade::apply_settings_migrations(&mut raw)?;
+        self.settings = serde_json::from_value(raw)
+            .map_err(|e| SetupError::Config(format!("Failed to apply settings changes: {}", e)))?;
+
+        for key in &plan.missing_settings {
+            match *key {
+                "database_url" => self.step_database().await?,
+                "selected_model" => self.step_model_selection().await?,
+                _ => {}
+            }
+        }
+
+        if !plan.pending_db_migrations.is_empty() {
+            self.run_migrations().await?;
+        }
+
+        self.save_and_summarize()
+    }
+
     /// Step 1: Database connection.
     async fn step_database(&mut self) -> Result<(), SetupError> {
         // Check if we have an existing URL in env or settings
@@ -242,9 +444,6 @@ impl SetupWizard {
     /// Run database migrations.
     async fn run_migrations(&self) -> Result<(), SetupError> {
         if let Some(ref pool) = self.db_pool {
-            use refinery::embed_migrations;
-            embed_migrations!("migrations");
-
             print_info("Running migrations...");
 
             let mut client = pool
@@ -392,52 +591,35 @@ impl SetupWizard {
             }
         }
 
-        // Try to fetch available models
-        let models = if let Some(ref session) = self.session_manager {
-            self.fetch_available_models(session).await
-        } else {
-            vec![]
-        };
-
-        // Default models if we couldn't fetch
-        let default_models = [
-            (
-                "fireworks::accounts/fireworks/models/llama4-maverick-instruct-basic",
-                "Llama 4 Maverick (default, fast)",
-            ),
-            (
-                "gemini-2.5-flash",
-                "Gemini 2.5 Flash (Sophia Native)",
-            ),
-            (
-                "anthropic::claude-sonnet-4-20250514",
-                "Claude Sonnet 4 (best quality)",
-            ),
-            ("openai::gpt-4o", "GPT-4o"),
-        ];
-
-        println!("Available models:");
-        println!();
+        let models = self.fetch_available_models().await;
 
-        let options: Vec<&str> = if models.is_empty() {
-            default_models.iter().map(|(_, desc)| *desc).collect()
-        } else {
-            models.iter().map(|m| m.as_str()).collect()
-        };
-
-        // Add custom option
-        let mut all_options = options.clone();
-        all_options.push("Custom model ID");
-
-        let choice = select_one("Select a model:", &all_options).map_err(SetupError::Io)?;
-
-        let selected_model = if choice == all_options.len() - 1 {
-            // Custom model
+        let selected_model = if models.is_empty() {
+            print_info("No model list available. Enter a model ID from your provider.");
             input("Enter model ID").map_err(SetupError::Io)?
-        } else if models.is_empty() {
-            default_models[choice].0.to_string()
         } else {
-            models[choice].clone()
+            println!("Available models:");
+            println!();
+
+            let mut options: Vec<String> = models
+                .iter()
+                .map(|m| {
+                    let summary = m.summary();
+                    if summary.is_empty() {
+                        m.id.clone()
+                    } else {
+                        format!("{} ({})", m.id, summary)
+                    }
+                })
+                .collect();
+            options.push("Custom model ID".to_string());
+            let options: Vec<&str> = options.iter().map(String::as_str).collect();
+
+            let choice = select_one("Select a model:", &options).map_err(SetupError::Io)?;
+            if choice == models.len() {
+                input("Enter model ID").map_err(SetupError::Io)?
+            } else {
+                models[choice].id.clone()
+            }
         };
 
         self.settings.selected_model = Some(selected_model.clone());
@@ -446,8 +628,28 @@ impl SetupWizard {
         Ok(())
     }
 
-    /// Fetch available models from the API.
-    async fn fetch_available_models(&self, session: &Arc<SessionManager>) -> Vec<String> {
+    /// Refresh the model catalog from the API, falling back to the catalog
+    /// cached by a previous run.
+    async fn fetch_available_models(&self) -> Vec<CatalogModel> {
+        let catalog = ModelCatalog::load(default_catalog_path());
+        if let Some(ref session) = self.session_manager {
+            match self.catalog_provider(session) {
+                Ok(provider) => {
+                    if let Err(e) = catalog.refresh(provider.as_ref()).await {
+                        print_info(&format!("Could not fetch models: {}", e));
+                    }
+                }
+                Err(e) => print_info(&format!("Could not initialize provider: {}", e)),
+            }
+        }
+        catalog.models().await
+    }
+
+    /// Provider used to list models during setup.
+    fn catalog_provider(
+        &self,
+        session: &Arc<SessionManager>,
+    ) -> Result<Arc<dyn LlmProvider>, LlmError> {
         use crate::config::LlmConfig;
         use crate::llm::create_llm_provider;
 
@@ -471,24 +673,10 @@ impl SetupWizard {
                 api_key: None,
                 base_url: "https://generativelanguage.googleapis.com/v1beta/openai".to_string(),
             },
+            catalog_refresh_secs: 0,
         };
 
-        match create_llm_provider(&config, Arc::clone(session)) {
-            Ok(provider) => match provider.list_models().await {
-                Ok(models) => models,
-                Err(e) => {
-                    print_info(&format!("Could not fetch models: {}. Using defaults.", e));
-                    vec![]
-                }
-            },
-            Err(e) => {
-                print_info(&format!(
-                    "Could not initialize provider: {}. Using defaults.",
-                    e
-                ));
-                vec![]
-            }
-        }
+        create_llm_provider(&config, Arc::clone(session))
     }
 
     /// Step 5: Embeddings configuration.
@@ -751,7 +939,75 @@ impl SetupWizard {
         Ok(())
     }
 
-    /// Step 7: Heartbeat configuration.
+    /// Step 7: Tool authentication.
+    ///
+    /// Runs the OAuth flow for installed tools that declare one. Tools sharing
+    /// a secret (all Google tools use `google_oauth_token`) get a single login
+    /// that requests the union of their scopes.
+    async fn step_tool_auth(&mut self) -> Result<(), SetupError> {
+        let tools_dir = dirs::home_dir().unwrap_or_default().join(".ironclaw/tools");
+
+        let groups = group_tools_by_secret(discover_oauth_tools(&tools_dir).await);
+        if groups.is_empty() {
+            print_info("No installed tools need OAuth. Skipping.");
+            print_info("After installing one, run: ironclaw tool auth <tool>");
+            return Ok(());
+        }
+
+        let secrets = match self.init_secrets_context().await {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                print_info(&format!("Secrets not available: {}", e));
+                print_info("Tool tokens must be set via environment variables.");
+                return Ok(());
+            }
+        };
+
+        let mut options = Vec::with_capacity(groups.len());
+        for group in &groups {
+            let connected = secrets.secret_exists(&group.secret_name).await;
+            let label = format!(
+                "{} ({}){}",
+                group.display_name,
+                group.tools.join(", "),
+                if connected { " - connected" } else { "" }
+            );
+            options.push((label, !connected));
+        }
+        let options_refs: Vec<(&str, bool)> =
+            options.iter().map(|(s, b)| (s.as_str(), *b)).collect();
+
+        let selected = select_many("Which services do you want to connect?", &options_refs)
+            .map_err(SetupError::Io)?;
+
+        for (idx, group) in groups.iter().enumerate() {
+            if !selected.contains(&idx) {
+                continue;
+            }
+
+            if let Err(e) = crate::cli::authenticate_tool(
+                &group.tools[0],
+                &tools_dir,
+                secrets.store(),
+                secrets.user_id(),
+            )
+            .await
+            {
+                print_error(&format!(
+                    "{} authentication failed: {}",
+                    group.display_name, e
+                ));
+                print_info(&format!(
+                    "Retry later with: ironclaw tool auth {}",
+                    group.tools[0]
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Step 8: Heartbeat configuration.
     fn step_heartbeat(&mut self) -> Result<(), SetupError> {
         print_info("Heartbeat runs periodic background tasks (e.g., checking your calendar,");
         print_info("monitoring for notifications, running scheduled workflows).");
@@ -793,6 +1049,7 @@ impl SetupWizard {
     /// Save settings and print summary.
     fn save_and_summarize(&mut self) -> Result<(), SetupError> {
         self.settings.onboard_completed = true;
+        self.settings.settings_version = SETTINGS_VERSION;
 
         self.settings.save().map_err(|e| {
             SetupError::Io(std::io::Error::new(
@@ -984,6 +1241,80 @@ async fn discover_wasm_channels(dir: &std::path::Path) -> Vec<(String, ChannelCa
     channels
 }
 
+/// Discover installed WASM tools whose auth section declares an OAuth flow.
+///
+/// Returns a list of (tool_name, auth_schema) pairs sorted by tool name.
+async fn discover_oauth_tools(dir: &std::path::Path) -> Vec<(String, AuthCapabilitySchema)> {
+    let mut tools = Vec::new();
+
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(e) => e,
+        Err(_) => return tools,
+    };
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+        let Some(name) = file_name.strip_suffix(".capabilities.json") else {
+            continue;
+        };
+        if name.is_empty() || !dir.join(format!("{}.wasm", name)).exists() {
+            continue;
+        }
+
+        let Ok(content) = tokio::fs::read_to_string(&path).await else {
+            continue;
+        };
+        match CapabilitiesFile::from_json(&content) {
+            Ok(caps) => {
+                if let Some(auth) = caps.auth.filter(|a| a.oauth.is_some()) {
+                    tools.push((name.to_string(), auth));
+                }
+            }
+            Err(e) => {
+                tracing::warn!(
+                    path = %path.display(),
+                    error = %e,
+                    "Failed to parse tool capabilities file"
+                );
+            }
+        }
+    }
+
+    tools.sort_by(|a, b| a.0.cmp(&b.0));
+    tools
+}
+
+/// Tools that share a single OAuth secret.
+#[derive(Debug)]
+struct OAuthToolGroup {
+    secret_name: String,
+    display_name: String,
+    tools: Vec<String>,
+}
+
+/// Group OAuth tools by the secret they store their token under.
+fn group_tools_by_secret(tools: Vec<(String, AuthCapabilitySchema)>) -> Vec<OAuthToolGroup> {
+    let mut groups: BTreeMap<String, OAuthToolGroup> = BTreeMap::new();
+
+    for (name, auth) in tools {
+        let group = groups
+            .entry(auth.secret_name.clone())
+            .or_insert_with(|| OAuthToolGroup {
+                secret_name: auth.secret_name.clone(),
+                display_name: auth
+                    .display_name
+                    .clone()
+                    .unwrap_or_else(|| auth.secret_name.clone()),
+                tools: Vec::new(),
+            });
+        group.tools.push(name);
+    }
+
+    groups.into_values().collect()
+}
+
 /// Capitalize the first letter of a string.
 fn capitalize_first(s: &str) -> String {
     let mut chars = s.chars();
@@ -1074,6 +1405,7 @@ mod tests {
         let config = SetupConfig {
             skip_auth: true,
             channels_only: false,
+            ..Default::default()
         };
         let wizard = SetupWizard::with_config(config);
         assert!(wizard.config.skip_auth);
@@ -1100,6 +1432,64 @@ mod tests {
         assert_eq!(capitalize_first(""), "");
     }
 
+    #[test]
+    fn test_group_tools_by_secret() {
+        let google = |name: &str| {
+            (
+                name.to_string(),
+                AuthCapabilitySchema {
+                    secret_name: "google_oauth_token".to_string(),
+                    display_name: Some("Google".to_string()),
+                    ..Default::default()
+                },
+            )
+        };
+        let slack = (
+            "slack-tool".to_string(),
+            AuthCapabilitySchema {
+                secret_name: "slack_bot_token".to_string(),
+                ..Default::default()
+            },
+        );
+
+        let groups = group_tools_by_secret(vec![
+            google("gmail-tool"),
+            slack,
+            google("google-docs-tool"),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].secret_name, "google_oauth_token");
+        assert_eq!(groups[0].display_name, "Google");
+        assert_eq!(groups[0].tools, vec!["gmail-tool", "google-docs-tool"]);
+        assert_eq!(groups[1].display_name, "slack_bot_token");
+    }
+
+    #[tokio::test]
+    async fn test_discover_oauth_tools_requires_wasm_and_oauth() {
+        let dir = tempdir().unwrap();
+        let oauth_caps = r#"{"auth": {"secret_name": "google_oauth_token", "oauth": {
+            "authorization_url": "https://accounts.google.com/o/oauth2/v2/auth",
+            "token_url": "https://oauth2.googleapis.com/token"
+        }}}"#;
+
+        std::fs::write(dir.path().join("gmail-tool.wasm"), b"").unwrap();
+        std::fs::write(dir.path().join("gmail-tool.capabilities.json"), oauth_caps).unwrap();
+        // No .wasm alongside: ignored
+        std::fs::write(dir.path().join("orphan.capabilities.json"), oauth_caps).unwrap();
+        // Manual auth only: ignored
+        std::fs::write(dir.path().join("manual.wasm"), b"").unwrap();
+        std::fs::write(
+            dir.path().join("manual.capabilities.json"),
+            r#"{"auth": {"secret_name": "manual_token"}}"#,
+        )
+        .unwrap();
+
+        let tools = discover_oauth_tools(dir.path()).await;
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].0, "gmail-tool");
+    }
+
     #[tokio::test]
     async fn test_install_missing_bundled_channels_installs_telegram() {
         let dir = tempdir().unwrap();
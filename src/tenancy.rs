@@ -0,0 +1,540 @@
+//! Multi-user tenancy: roles, quotas and per-user usage accounting.
+//!
+//! Tenancy is opt-in (`TENANCY_ENABLED`). While it is off every user is an
+//! admin without quotas, which is what a single-user deployment expects.
+//! When it is on, the tenants file decides who may use the agent:
+//!
+//! ```toml
+//! # Role for users not listed below; omit to reject unknown users.
+//! default_role = "member"
+//!
+//! [defaults]
+//! requests_per_hour = 60
+//! daily_cost_usd = 5.0
+//!
+//! [[users]]
+//! id = "alice"
+//! role = "admin"
+//!
+//! [[users]]
+//! id = "123456789"   # e.g. a Telegram user ID
+//! requests_per_hour = 20
+//! ```
+//!
+//! Every admitted message is handled inside a [`TenantScope`] task-local, so
+//! code deep in the call stack (LLM cost accounting, tool gating, workspace
+//! resolution) can find the current user without threading it through every
+//! signature.
+//!
+//! Tool credentials are not scoped: they belong to the deployment, which is
+//! why connecting a tool (`tool_auth`) is reserved for admins.
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::config::TenancyConfig;
+
+/// Tools only admins may run: they change what the deployment can do for
+/// everyone (installing extensions, building tools) or reach the host.
+pub const ADMIN_ONLY_TOOLS: &[&str] = &[
+    "tool_install",
+    "tool_auth",
+    "tool_activate",
+    "tool_remove",
+    "build_software",
+    "shell",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum TenancyError {
+    #[error("Failed to read tenants file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse tenants file: {0}")]
+    Parse(String),
+
+    #[error("Invalid tenants file: {0}")]
+    Invalid(String),
+}
+
+/// Why a user's request was refused.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum QuotaError {
+    #[error("You are not authorized to use this agent.")]
+    UnknownUser,
+
+    #[error("This user ID is reserved.")]
+    ReservedUserId,
+
+    #[error("Rate limit reached ({limit} requests per hour). Please try again later.")]
+    RateLimited { limit: u32 },
+
+    #[error("Daily spending limit of ${limit} reached. Please try again tomorrow.")]
+    BudgetExceeded { limit: Decimal },
+}
+
+/// A user's role within the deployment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Admin,
+    #[default]
+    Member,
+}
+
+impl Role {
+    pub fn is_admin(self) -> bool {
+        self == Self::Admin
+    }
+
+    /// Whether this role may execute the named tool.
+    pub fn can_use_tool(self, tool_name: &str) -> bool {
+        self.is_admin() || !ADMIN_ONLY_TOOLS.contains(&tool_name)
+    }
+}
+
+/// Per-user limits. Unset fields are unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Quota {
+    pub requests_per_hour: Option<u32>,
+    pub daily_cost_usd: Option<f64>,
+}
+
+impl Quota {
+    /// This quota with unset fields taken from `defaults`.
+    fn or(&self, defaults: &Quota) -> Quota {
+        Quota {
+            requests_per_hour: self.requests_per_hour.or(defaults.requests_per_hour),
+            daily_cost_usd: self.daily_cost_usd.or(defaults.daily_cost_usd),
+        }
+    }
+}
+
+/// A user listed in the tenants file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TenantSpec {
+    pub id: String,
+    #[serde(default)]
+    pub role: Role,
+    #[serde(flatten)]
+    pub quota: Quota,
+}
+
+/// Contents of the tenants file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TenantsFile {
+    /// Role for users not listed in `users`; `None` rejects them.
+    pub default_role: Option<Role>,
+    #[serde(default)]
+    pub defaults: Quota,
+    #[serde(default)]
+    pub users: Vec<TenantSpec>,
+}
+
+impl TenantsFile {
+    /// Load and validate a tenants file (TOML).
+    pub fn load(path: &Path) -> Result<Self, TenancyError> {
+        let raw = std::fs::read_to_string(path)?;
+        let file: Self = toml::from_str(&raw).map_err(|e| TenancyError::Parse(e.to_string()))?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    pub fn validate(&self) -> Result<(), TenancyError> {
+        let mut seen = HashSet::new();
+        for user in &self.users {
+            if user.id.trim().is_empty() {
+                return Err(TenancyError::Invalid("user id cannot be empty".to_string()));
+            }
+            if !seen.insert(user.id.as_str()) {
+                return Err(TenancyError::Invalid(format!(
+                    "user '{}' is listed more than once",
+                    user.id
+                )));
+            }
+        }
+        for quota in std::iter::once(&self.defaults).chain(self.users.iter().map(|u| &u.quota)) {
+            if quota.daily_cost_usd.is_some_and(|c| c.is_nan() || c < 0.0) {
+                return Err(TenancyError::Invalid(
+                    "daily_cost_usd must be a non-negative number".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Request and spend counters for one user.
+#[derive(Debug, Default)]
+pub struct UsageAccount {
+    window: Mutex<UsageWindow>,
+}
+
+#[derive(Debug, Default)]
+struct UsageWindow {
+    hour_started: Option<DateTime<Utc>>,
+    requests: u32,
+    day: Option<NaiveDate>,
+    cost: Decimal,
+}
+
+impl UsageWindow {
+    fn roll(&mut self, now: DateTime<Utc>) {
+        if self
+            .hour_started
+            .is_none_or(|start| now - start >= chrono::Duration::hours(1))
+        {
+            self.hour_started = Some(now);
+            self.requests = 0;
+        }
+        if self.day != Some(now.date_naive()) {
+            self.day = Some(now.date_naive());
+            self.cost = Decimal::ZERO;
+        }
+    }
+}
+
+impl UsageAccount {
+    /// Count one request if it fits within `quota`.
+    fn admit(&self, quota: &Quota, now: DateTime<Utc>) -> Result<(), QuotaError> {
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        window.roll(now);
+
+        if let Some(limit) = quota.daily_cost_usd.and_then(Decimal::from_f64)
+            && window.cost >= limit
+        {
+            return Err(QuotaError::BudgetExceeded {
+                limit: limit.round_dp(2),
+            });
+        }
+        if let Some(limit) = quota.requests_per_hour
+            && window.requests >= limit
+        {
+            return Err(QuotaError::RateLimited { limit });
+        }
+
+        window.requests += 1;
+        Ok(())
+    }
+
+    fn add_cost_at(&self, cost: Decimal, now: DateTime<Utc>) {
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        window.roll(now);
+        window.cost += cost;
+    }
+
+    /// Add LLM spend to today's total.
+    pub fn add_cost(&self, cost: Decimal) {
+        self.add_cost_at(cost, Utc::now());
+    }
+
+    /// Spend so far today (UTC).
+    pub fn cost_today(&self) -> Decimal {
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        window.roll(Utc::now());
+        window.cost
+    }
+}
+
+/// The user a piece of work is being done for.
+#[derive(Debug, Clone)]
+pub struct TenantScope {
+    user_id: String,
+    role: Role,
+    account: Arc<UsageAccount>,
+}
+
+impl TenantScope {
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    /// Charge LLM spend to this user.
+    pub fn charge(&self, cost: Decimal) {
+        self.account.add_cost(cost);
+    }
+
+    /// This user's LLM spend so far today (UTC).
+    pub fn cost_today(&self) -> Decimal {
+        self.account.cost_today()
+    }
+}
+
+tokio::task_local! {
+    static CURRENT: TenantScope;
+}
+
+/// Run `fut` on behalf of the user in `scope`.
+pub async fn scoped<F: Future>(scope: TenantScope, fut: F) -> F::Output {
+    CURRENT.scope(scope, fut).await
+}
+
+/// Run `fut` in `scope` if there is one.
+///
+/// Task-locals are not inherited by `tokio::spawn`, so capture [`current`]
+/// before spawning and re-enter it inside the new task with this.
+pub async fn inherit<F: Future>(scope: Option<TenantScope>, fut: F) -> F::Output {
+    match scope {
+        Some(scope) => scoped(scope, fut).await,
+        None => fut.await,
+    }
+}
+
+/// The tenant the current task is working for, if any.
+pub fn current() -> Option<TenantScope> {
+    CURRENT.try_with(Clone::clone).ok()
+}
+
+/// Charge LLM spend to the current tenant, if there is one.
+pub fn charge_current(cost: Decimal) {
+    let _ = CURRENT.try_with(|scope| scope.charge(cost));
+}
+
+/// Check the current tenant may run `tool_name`.
+///
+/// Work outside any tenant scope (startup, routines, the web gateway's own
+/// job API) runs with deployment privileges and is always allowed.
+pub fn check_tool_access(tool_name: &str) -> Result<(), crate::error::ToolError> {
+    match current() {
+        Some(scope) if !scope.role().can_use_tool(tool_name) => {
+            Err(crate::error::ToolError::Disabled {
+                name: tool_name.to_string(),
+                reason: "requires the admin role".to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Who may use the deployment, with what role, and how much.
+pub struct TenantDirectory {
+    /// `None` when tenancy is disabled.
+    file: Option<TenantsFile>,
+    accounts: Mutex<HashMap<String, Arc<UsageAccount>>>,
+}
+
+impl TenantDirectory {
+    /// Single-user mode: everyone is an admin with no quotas.
+    pub fn disabled() -> Self {
+        Self {
+            file: None,
+            accounts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn new(file: TenantsFile) -> Self {
+        Self {
+            file: Some(file),
+            accounts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Build the directory from config, loading the tenants file if enabled.
+    pub fn from_config(config: &TenancyConfig) -> Result<Self, TenancyError> {
+        if !config.enabled {
+            return Ok(Self::disabled());
+        }
+        Ok(Self::new(TenantsFile::load(&config.tenants_path)?))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.file.is_some()
+    }
+
+    /// The user's role, or `None` if they may not use the deployment.
+    pub fn role(&self, user_id: &str) -> Option<Role> {
+        let Some(ref file) = self.file else {
+            return Some(Role::Admin);
+        };
+        file.users
+            .iter()
+            .find(|u| u.id == user_id)
+            .map(|u| u.role)
+            .or(file.default_role)
+    }
+
+    fn quota(&self, user_id: &str) -> Quota {
+        let Some(ref file) = self.file else {
+            return Quota::default();
+        };
+        match file.users.iter().find(|u| u.id == user_id) {
+            Some(user) => user.quota.or(&file.defaults),
+            None => file.defaults.clone(),
+        }
+    }
+
+    fn account(&self, user_id: &str) -> Arc<UsageAccount> {
+        let mut accounts = self.accounts.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(accounts.entry(user_id.to_string()).or_default())
+    }
+
+    /// Admit one request from `user_id`, counting it against their quota.
+    pub fn admit(&self, user_id: &str) -> Result<TenantScope, QuotaError> {
+        self.admit_at(user_id, Utc::now())
+    }
+
+    fn admit_at(&self, user_id: &str, now: DateTime<Utc>) -> Result<TenantScope, QuotaError> {
+        // Shared memory spaces are stored under these owner IDs
+        if user_id.starts_with(crate::workspace::SPACE_OWNER_PREFIX) {
+            return Err(QuotaError::ReservedUserId);
+        }
+        let role = self.role(user_id).ok_or(QuotaError::UnknownUser)?;
+        let account = self.account(user_id);
+        account.admit(&self.quota(user_id), now)?;
+        Ok(TenantScope {
+            user_id: user_id.to_string(),
+            role,
+            account,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directory(raw: &str) -> TenantDirectory {
+        let file: TenantsFile = toml::from_str(raw).unwrap();
+        file.validate().unwrap();
+        TenantDirectory::new(file)
+    }
+
+    #[test]
+    fn test_disabled_admits_everyone_as_admin() {
+        let tenants = TenantDirectory::disabled();
+        let scope = tenants.admit("anyone").unwrap();
+        assert_eq!(scope.role(), Role::Admin);
+        assert!(scope.role().can_use_tool("shell"));
+
+        assert_eq!(
+            tenants.admit("space:team").err(),
+            Some(QuotaError::ReservedUserId)
+        );
+    }
+
+    #[test]
+    fn test_roles_and_unknown_users() {
+        let tenants = directory(
+            r#"
+            [[users]]
+            id = "alice"
+            role = "admin"
+
+            [[users]]
+            id = "bob"
+            "#,
+        );
+        assert_eq!(tenants.role("alice"), Some(Role::Admin));
+        assert_eq!(tenants.role("bob"), Some(Role::Member));
+        assert_eq!(
+            tenants.admit("mallory").unwrap_err(),
+            QuotaError::UnknownUser
+        );
+
+        let open = directory("default_role = \"member\"");
+        assert_eq!(open.role("mallory"), Some(Role::Member));
+    }
+
+    #[test]
+    fn test_members_cannot_use_admin_tools() {
+        assert!(!Role::Member.can_use_tool("tool_install"));
+        assert!(!Role::Member.can_use_tool("shell"));
+        assert!(Role::Member.can_use_tool("memory_search"));
+        assert!(Role::Admin.can_use_tool("tool_install"));
+    }
+
+    #[test]
+    fn test_hourly_request_limit() {
+        let tenants = directory(
+            r#"
+            [defaults]
+            requests_per_hour = 5
+
+            [[users]]
+            id = "bob"
+            requests_per_hour = 2
+            "#,
+        );
+        let now = Utc::now();
+        assert!(tenants.admit_at("bob", now).is_ok());
+        assert!(tenants.admit_at("bob", now).is_ok());
+        assert_eq!(
+            tenants.admit_at("bob", now).unwrap_err(),
+            QuotaError::RateLimited { limit: 2 }
+        );
+        assert!(
+            tenants
+                .admit_at("bob", now + chrono::Duration::minutes(61))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_daily_cost_limit() {
+        let tenants = directory(
+            r#"
+            [[users]]
+            id = "bob"
+            daily_cost_usd = 1.0
+            "#,
+        );
+        let now = Utc::now();
+        let scope = tenants.admit_at("bob", now).unwrap();
+        scope.account.add_cost_at(Decimal::new(150, 2), now);
+        assert!(matches!(
+            tenants.admit_at("bob", now),
+            Err(QuotaError::BudgetExceeded { .. })
+        ));
+        assert!(
+            tenants
+                .admit_at("bob", now + chrono::Duration::days(1))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_rejects_duplicate_users() {
+        let file: TenantsFile = toml::from_str(
+            r#"
+            [[users]]
+            id = "bob"
+
+            [[users]]
+            id = "bob"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(file.validate(), Err(TenancyError::Invalid(_))));
+    }
+
+    #[tokio::test]
+    async fn test_scope_is_visible_to_nested_code() {
+        let tenants = TenantDirectory::new(TenantsFile {
+            default_role: Some(Role::Member),
+            ..Default::default()
+        });
+        let scope = tenants.admit("bob").unwrap();
+        let account = Arc::clone(&scope.account);
+
+        assert!(current().is_none());
+        scoped(scope, async {
+            assert_eq!(current().unwrap().user_id(), "bob");
+            assert!(check_tool_access("shell").is_err());
+            charge_current(Decimal::new(25, 2));
+        })
+        .await;
+        assert_eq!(account.cost_today(), Decimal::new(25, 2));
+        assert!(check_tool_access("shell").is_ok());
+    }
+}
@@ -0,0 +1,137 @@
+//! Artifact lookup tool.
+//!
+//! Lets the LLM find files, sheets, messages and events created by earlier
+//! jobs or conversations without asking the user for their IDs.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::context::{ArtifactKind, ArtifactQuery, ContextManager, JobContext};
+use crate::tools::tool::{Tool, ToolError, ToolOutput};
+
+/// Default number of artifacts returned.
+const DEFAULT_LIMIT: usize = 20;
+
+/// Tool for listing artifacts recorded across all jobs.
+pub struct ListArtifactsTool {
+    context_manager: Arc<ContextManager>,
+}
+
+impl ListArtifactsTool {
+    pub fn new(context_manager: Arc<ContextManager>) -> Self {
+        Self { context_manager }
+    }
+}
+
+#[async_trait]
+impl Tool for ListArtifactsTool {
+    fn name(&self) -> &str {
+        "list_artifacts"
+    }
+
+    fn description(&self) -> &str {
+        "List things created by tools in any job or conversation (Drive files, spreadsheets, \
+         documents, presentations, emails, chat messages, calendar events), newest first. \
+         Use this to find IDs and links of previously created items instead of asking the user."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "kind": {
+                    "type": "string",
+                    "description": "Only return artifacts of this kind",
+                    "enum": [
+                        "drive_file", "drive_folder", "spreadsheet", "document",
+                        "presentation", "email", "email_draft", "chat_message",
+                        "calendar_event"
+                    ]
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Case-insensitive substring of the artifact title"
+                },
+                "job_id": {
+                    "type": "string",
+                    "description": "Only return artifacts created by this job"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of results (default: 20)"
+                }
+            }
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+    ) -> Result<ToolOutput, ToolError> {
+        let start = std::time::Instant::now();
+
+        let kind = match params.get("kind").and_then(|v| v.as_str()) {
+            Some(name) => Some(ArtifactKind::from_name(name).ok_or_else(|| {
+                ToolError::InvalidParameters(format!("unknown artifact kind: {}", name))
+            })?),
+            None => None,
+        };
+
+        let job_id = match params.get("job_id").and_then(|v| v.as_str()) {
+            Some(id) => Some(
+                Uuid::parse_str(id)
+                    .map_err(|_| ToolError::InvalidParameters(format!("invalid job ID: {}", id)))?,
+            ),
+            None => None,
+        };
+
+        let query = ArtifactQuery {
+            kind,
+            title_contains: params
+                .get("title")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            job_id,
+            limit: params
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .map(|n| n as usize)
+                .unwrap_or(DEFAULT_LIMIT),
+        };
+
+        let artifacts: Vec<serde_json::Value> = self
+            .context_manager
+            .artifacts()
+            .list(&ctx.user_id, &query)
+            .await
+            .into_iter()
+            .map(|a| {
+                serde_json::json!({
+                    "id": a.id.to_string(),
+                    "kind": a.kind.as_str(),
+                    "external_id": a.external_id,
+                    "title": a.title,
+                    "url": a.url,
+                    "tool": a.tool_name,
+                    "job_id": a.job_id.to_string(),
+                    "created_at": a.created_at.to_rfc3339(),
+                    "updated_at": a.updated_at.to_rfc3339(),
+                })
+            })
+            .collect();
+
+        let result = serde_json::json!({
+            "count": artifacts.len(),
+            "artifacts": artifacts,
+        });
+
+        Ok(ToolOutput::success(result, start.elapsed()))
+    }
+
+    fn requires_sanitization(&self) -> bool {
+        false // Internal tool
+    }
+}
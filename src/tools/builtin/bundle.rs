@@ -0,0 +1,151 @@
+//! Job bundle export tool.
+//!
+//! Writes an anonymized bundle of a failed job for the user to attach to a
+//! bug report. See [`crate::sandbox::bundle`] for what a bundle holds.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::config::{Config, ConfigLayers, ResolvedSetting};
+use crate::context::{ContextManager, JobContext, JobState};
+use crate::db::Database;
+use crate::sandbox::bundle::{JobBundle, bundles_dir};
+use crate::tools::registry::ToolRegistry;
+use crate::tools::tool::{Tool, ToolError, ToolOutput};
+
+/// Tool for exporting a failed job as a bundle for a bug report.
+pub struct ExportJobBundleTool {
+    context_manager: Arc<ContextManager>,
+    tools: Arc<ToolRegistry>,
+    store: Option<Arc<dyn Database>>,
+}
+
+impl ExportJobBundleTool {
+    pub fn new(
+        context_manager: Arc<ContextManager>,
+        tools: Arc<ToolRegistry>,
+        store: Option<Arc<dyn Database>>,
+    ) -> Self {
+        Self {
+            context_manager,
+            tools,
+            store,
+        }
+    }
+
+    /// Effective settings, including database overrides when there is a store.
+    async fn settings(&self) -> Vec<ResolvedSetting> {
+        let layers = match ConfigLayers::load() {
+            Ok(layers) => layers,
+            Err(e) => {
+                tracing::warn!("Job bundle without config: {}", e);
+                return Vec::new();
+            }
+        };
+        let layers = match self.store {
+            Some(ref store) => match store.get_all_settings("default").await {
+                Ok(settings) => layers.with_database_settings(&settings),
+                Err(e) => {
+                    tracing::warn!("Job bundle without database overrides: {}", e);
+                    layers
+                }
+            },
+            None => layers,
+        };
+        match Config::resolve(layers) {
+            Ok((_, resolved)) => resolved,
+            Err(e) => {
+                tracing::warn!("Job bundle without config: {}", e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ExportJobBundleTool {
+    fn name(&self) -> &str {
+        "export_job_bundle"
+    }
+
+    fn description(&self) -> &str {
+        "Export a failed or stuck job as an anonymized bundle for a bug report: non-default \
+         settings (secrets masked), tool schemas, the tool calls it made, and hashes of the \
+         LLM messages. User IDs, email addresses, home paths and \
+         secrets are removed. Returns the path of the bundle file for the user to attach."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "job_id": {
+                    "type": "string",
+                    "description": "The UUID of the failed job"
+                }
+            },
+            "required": ["job_id"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+    ) -> Result<ToolOutput, ToolError> {
+        let start = std::time::Instant::now();
+
+        let job_id_str = params
+            .get("job_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("missing 'job_id' parameter".into()))?;
+        let job_id = Uuid::parse_str(job_id_str).map_err(|_| {
+            ToolError::InvalidParameters(format!("invalid job ID format: {}", job_id_str))
+        })?;
+
+        let job = match self.context_manager.get_context(job_id).await {
+            Ok(job) if job.user_id == ctx.user_id => job,
+            _ => {
+                return Err(ToolError::InvalidParameters(format!(
+                    "job not found: {}",
+                    job_id
+                )));
+            }
+        };
+        if !matches!(job.state, JobState::Failed | JobState::Stuck) {
+            return Err(ToolError::InvalidParameters(format!(
+                "job {} is {:?}; only failed or stuck jobs can be exported",
+                job_id, job.state
+            )));
+        }
+        let memory = self
+            .context_manager
+            .get_memory(job_id)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+
+        let settings = self.settings().await;
+        let tools = self.tools.tool_definitions().await;
+
+        let bundle = JobBundle::build(&job, &memory, &settings, tools);
+        let path = bundle.save(&bundles_dir()).map_err(|e| {
+            ToolError::ExecutionFailed(format!("failed to write job bundle: {}", e))
+        })?;
+
+        let result = serde_json::json!({
+            "job_id": job_id.to_string(),
+            "path": path.display().to_string(),
+            "config_settings": bundle.config.len(),
+            "tools": bundle.tools.len(),
+            "actions": bundle.actions.len(),
+            "llm_messages": bundle.llm_trace.len(),
+        });
+        Ok(ToolOutput::success(result, start.elapsed()))
+    }
+
+    fn requires_sanitization(&self) -> bool {
+        false // Internal tool
+    }
+}
@@ -31,6 +31,7 @@ impl Tool for ToolSearchTool {
 
     fn description(&self) -> &str {
         "Search for available extensions (MCP servers, WASM tools) to add. \
+         Results from the marketplace include their version and the permissions they request. \
          Use discover:true to search online if the built-in registry has no results."
     }
 
@@ -101,7 +102,8 @@ impl Tool for ToolInstallTool {
 
     fn description(&self) -> &str {
         "Install an extension (MCP server or WASM tool). \
-         Use the name from tool_search results, or provide an explicit URL."
+         Use the name from tool_search results, or provide an explicit URL. \
+         Installing a marketplace extension that is already installed updates it to the latest version."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -164,6 +166,16 @@ impl Tool for ToolInstallTool {
     fn requires_approval(&self) -> bool {
         true
     }
+
+    async fn approval_description(&self, params: &serde_json::Value) -> String {
+        let name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        let url = params.get("url").and_then(|v| v.as_str());
+
+        match self.manager.marketplace_install_entry(name, url).await {
+            Some(entry) => entry.install_summary(),
+            None => self.description().to_string(),
+        }
+    }
 }
 
 // ── tool_auth ────────────────────────────────────────────────────────────
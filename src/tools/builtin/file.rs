@@ -11,6 +11,7 @@ use async_trait::async_trait;
 use tokio::fs;
 
 use crate::context::JobContext;
+use crate::sandbox::WorkspaceQuota;
 use crate::tools::tool::{Tool, ToolError, ToolOutput};
 use crate::workspace::paths as ws_paths;
 
@@ -248,10 +249,30 @@ impl Tool for ReadFileTool {
     }
 }
 
+/// Disk quota for writes under a workspace root.
+#[derive(Debug, Clone)]
+struct WriteQuota {
+    root: PathBuf,
+    quota: WorkspaceQuota,
+}
+
+/// Reject a write of `len` bytes to `path` that would break the quota.
+async fn check_quota(quota: Option<&WriteQuota>, path: &Path, len: u64) -> Result<(), ToolError> {
+    let Some(WriteQuota { root, quota }) = quota.cloned() else {
+        return Ok(());
+    };
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || quota.check_write(&root, &path, len))
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(format!("Quota check failed: {}", e)))?
+        .map_err(|e| ToolError::Sandbox(e.to_string()))
+}
+
 /// Write file contents tool.
 #[derive(Debug, Default)]
 pub struct WriteFileTool {
     base_dir: Option<PathBuf>,
+    quota: Option<WriteQuota>,
 }
 
 impl WriteFileTool {
@@ -263,6 +284,12 @@ impl WriteFileTool {
         self.base_dir = Some(dir);
         self
     }
+
+    /// Enforce `quota` on everything written under `root`.
+    pub fn with_quota(mut self, root: PathBuf, quota: WorkspaceQuota) -> Self {
+        self.quota = Some(WriteQuota { root, quota });
+        self
+    }
 }
 
 #[async_trait]
@@ -330,6 +357,7 @@ impl Tool for WriteFileTool {
         }
 
         let path = validate_path(path_str, self.base_dir.as_deref())?;
+        check_quota(self.quota.as_ref(), &path, content.len() as u64).await?;
 
         // Create parent directories
         if let Some(parent) = path.parent() {
@@ -558,6 +586,7 @@ fn format_size(bytes: u64) -> String {
 #[derive(Debug, Default)]
 pub struct ApplyPatchTool {
     base_dir: Option<PathBuf>,
+    quota: Option<WriteQuota>,
 }
 
 impl ApplyPatchTool {
@@ -569,6 +598,12 @@ impl ApplyPatchTool {
         self.base_dir = Some(dir);
         self
     }
+
+    /// Enforce `quota` on everything written under `root`.
+    pub fn with_quota(mut self, root: PathBuf, quota: WorkspaceQuota) -> Self {
+        self.quota = Some(WriteQuota { root, quota });
+        self
+    }
 }
 
 #[async_trait]
@@ -664,6 +699,8 @@ impl Tool for ApplyPatchTool {
             1
         };
 
+        check_quota(self.quota.as_ref(), &path, new_content.len() as u64).await?;
+
         // Write back
         fs::write(&path, &new_content)
             .await
@@ -763,6 +800,32 @@ mod tests {
         assert!(content.contains("println!(\"new\")"));
     }
 
+    #[tokio::test]
+    async fn test_write_file_enforces_quota() {
+        let dir = TempDir::new().unwrap();
+        let quota = WorkspaceQuota {
+            max_bytes: 16,
+            max_files: 10,
+            max_file_bytes: 16,
+        };
+        let tool = WriteFileTool::new()
+            .with_base_dir(dir.path().to_path_buf())
+            .with_quota(dir.path().to_path_buf(), quota);
+        let ctx = JobContext::default();
+
+        let params = |name: &str| {
+            serde_json::json!({
+                "path": dir.path().join(name).to_str().unwrap(),
+                "content": "0123456789"
+            })
+        };
+
+        assert!(tool.execute(params("a.txt"), &ctx).await.is_ok());
+        let err = tool.execute(params("b.txt"), &ctx).await.unwrap_err();
+        assert!(err.to_string().contains("quota"));
+        assert!(!dir.path().join("b.txt").exists());
+    }
+
     #[tokio::test]
     async fn test_write_file_rejects_workspace_paths() {
         let dir = TempDir::new().unwrap();
@@ -315,7 +315,7 @@ fn projects_base() -> PathBuf {
 }
 
 /// Resolve the project directory, creating it if it doesn't exist.
-fn resolve_project_dir(
+pub(crate) fn resolve_project_dir(
     explicit: Option<PathBuf>,
     project_id: Uuid,
 ) -> Result<(PathBuf, String), ToolError> {
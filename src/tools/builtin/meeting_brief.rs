@@ -0,0 +1,540 @@
+//! Meeting brief tool.
+//!
+//! Builds a pre-meeting brief from a calendar event by calling the installed
+//! Google tools directly: the event and its attendees from `google-calendar`,
+//! recent threads with those attendees from `gmail`, and related documents
+//! from `google-drive`. The steps are fixed, so a brief costs a handful of
+//! API calls and no LLM turns. Gmail and Drive are optional; when one is
+//! missing or fails, the brief says so and carries on.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::context::JobContext;
+use crate::tools::registry::ToolRegistry;
+use crate::tools::tool::{Tool, ToolError, ToolOutput};
+
+const CALENDAR_TOOL: &str = "google-calendar";
+const GMAIL_TOOL: &str = "gmail";
+const DRIVE_TOOL: &str = "google-drive";
+
+/// Default days of mail searched for related threads.
+const DEFAULT_LOOKBACK_DAYS: u64 = 30;
+
+/// Default number of threads and documents in a brief.
+const DEFAULT_MAX_ITEMS: u64 = 5;
+
+/// Upper bound for `max_threads` and `max_files`.
+const MAX_ITEMS: u64 = 20;
+
+/// Attendees searched for; large meetings only use the first ones.
+const MAX_SEARCHED_ATTENDEES: usize = 10;
+
+/// Characters kept from the event description.
+const DESCRIPTION_CHARS: usize = 500;
+
+/// Tool that assembles a pre-meeting brief from calendar, mail, and Drive.
+pub struct MeetingBriefTool {
+    tools: Arc<ToolRegistry>,
+}
+
+impl MeetingBriefTool {
+    pub fn new(tools: Arc<ToolRegistry>) -> Self {
+        Self { tools }
+    }
+
+    /// Run an action on another registered tool.
+    async fn call(
+        &self,
+        tool_name: &str,
+        params: serde_json::Value,
+        ctx: &JobContext,
+    ) -> Result<serde_json::Value, String> {
+        let tool = self
+            .tools
+            .get(tool_name)
+            .await
+            .ok_or_else(|| format!("{} tool is not installed", tool_name))?;
+        tool.execute(params, ctx)
+            .await
+            .map(|output| output.result)
+            .map_err(|e| format!("{}: {}", tool_name, e))
+    }
+
+    /// Fetch the requested event, or the next timed event on the calendar.
+    async fn find_event(
+        &self,
+        calendar_id: &str,
+        event_id: Option<&str>,
+        ctx: &JobContext,
+    ) -> Result<serde_json::Value, ToolError> {
+        let result = match event_id {
+            Some(event_id) => {
+                let result = self
+                    .call(
+                        CALENDAR_TOOL,
+                        serde_json::json!({
+                            "action": "get_event",
+                            "calendar_id": calendar_id,
+                            "event_id": event_id,
+                        }),
+                        ctx,
+                    )
+                    .await
+                    .map_err(ToolError::ExternalService)?;
+                result.get("event").cloned()
+            }
+            None => {
+                let result = self
+                    .call(
+                        CALENDAR_TOOL,
+                        serde_json::json!({
+                            "action": "list_events",
+                            "calendar_id": calendar_id,
+                            "time_min": Utc::now().to_rfc3339(),
+                            "max_results": 10,
+                        }),
+                        ctx,
+                    )
+                    .await
+                    .map_err(ToolError::ExternalService)?;
+                result
+                    .get("events")
+                    .and_then(|e| e.as_array())
+                    .and_then(|events| {
+                        events
+                            .iter()
+                            .find(|e| e.pointer("/start/date_time").is_some())
+                            .cloned()
+                    })
+            }
+        };
+        result.ok_or_else(|| {
+            ToolError::ExecutionFailed(match event_id {
+                Some(id) => format!("event {} not found", id),
+                None => "no upcoming timed events on the calendar".to_string(),
+            })
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for MeetingBriefTool {
+    fn name(&self) -> &str {
+        "meeting_brief"
+    }
+
+    fn description(&self) -> &str {
+        "Prepare a brief for an upcoming meeting in one call: the event details, its \
+         attendees, recent email threads with them or about the meeting, and related Drive \
+         documents. Uses the google-calendar tool, plus gmail and google-drive when they are \
+         installed. Omit event_id to brief the next timed event on the calendar."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "event_id": {
+                    "type": "string",
+                    "description": "Calendar event ID. Default: the next timed event."
+                },
+                "calendar_id": {
+                    "type": "string",
+                    "description": "Calendar ID (default: 'primary')"
+                },
+                "lookback_days": {
+                    "type": "integer",
+                    "description": "Days of mail to search for related threads (default: 30)"
+                },
+                "max_threads": {
+                    "type": "integer",
+                    "description": "Maximum email threads in the brief (default: 5, max: 20)"
+                },
+                "max_files": {
+                    "type": "integer",
+                    "description": "Maximum Drive documents in the brief (default: 5, max: 20)"
+                }
+            }
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+    ) -> Result<ToolOutput, ToolError> {
+        let start = std::time::Instant::now();
+
+        let calendar_id = params
+            .get("calendar_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("primary");
+        let event_id = params.get("event_id").and_then(|v| v.as_str());
+        let number =
+            |key: &str, default: u64| params.get(key).and_then(|v| v.as_u64()).unwrap_or(default);
+        let lookback_days = number("lookback_days", DEFAULT_LOOKBACK_DAYS).max(1);
+        let max_threads = number("max_threads", DEFAULT_MAX_ITEMS).clamp(1, MAX_ITEMS);
+        let max_files = number("max_files", DEFAULT_MAX_ITEMS).clamp(1, MAX_ITEMS);
+
+        let event = self.find_event(calendar_id, event_id, ctx).await?;
+        let title = event
+            .get("summary")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        let attendees = attendees(&event);
+        let emails: Vec<&str> = attendees
+            .iter()
+            .filter_map(|a| a.get("email").and_then(|e| e.as_str()))
+            .take(MAX_SEARCHED_ATTENDEES)
+            .collect();
+
+        let mut unavailable = Vec::new();
+
+        let email_threads = match gmail_query(&emails, &title, lookback_days) {
+            Some(query) => match self
+                .call(
+                    GMAIL_TOOL,
+                    serde_json::json!({
+                        "action": "list_messages",
+                        "query": query,
+                        // Several hits can share a thread
+                        "max_results": max_threads * 2,
+                    }),
+                    ctx,
+                )
+                .await
+            {
+                Ok(result) => threads(&result, max_threads as usize),
+                Err(e) => {
+                    unavailable.push(e);
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+
+        let documents = match drive_query(&emails, &title) {
+            Some(query) => match self
+                .call(
+                    DRIVE_TOOL,
+                    serde_json::json!({
+                        "action": "list_files",
+                        "query": query,
+                        "order_by": "modifiedTime desc",
+                        "page_size": max_files,
+                    }),
+                    ctx,
+                )
+                .await
+            {
+                Ok(result) => documents(&result),
+                Err(e) => {
+                    unavailable.push(e);
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+
+        let description = event
+            .get("description")
+            .and_then(|v| v.as_str())
+            .map(|d| clip(d, DESCRIPTION_CHARS));
+        let brief = serde_json::json!({
+            "event": {
+                "id": event.get("id"),
+                "title": title,
+                "start": event.get("start"),
+                "end": event.get("end"),
+                "location": event.get("location"),
+                "description": description,
+                "organizer": event.get("organizer"),
+                "html_link": event.get("html_link"),
+            },
+            "attendees": attendees,
+            "email_threads": email_threads,
+            "documents": documents,
+            "unavailable": unavailable,
+        });
+
+        Ok(ToolOutput::success(brief, start.elapsed()))
+    }
+
+    fn estimated_duration(&self, _params: &serde_json::Value) -> Option<std::time::Duration> {
+        Some(std::time::Duration::from_secs(20))
+    }
+}
+
+/// Attendees worth researching: everyone except the user and room or
+/// equipment calendars.
+fn attendees(event: &serde_json::Value) -> Vec<serde_json::Value> {
+    event
+        .get("attendees")
+        .and_then(|a| a.as_array())
+        .map(|list| {
+            list.iter()
+                .filter(|a| !a.get("self").and_then(|s| s.as_bool()).unwrap_or(false))
+                .filter(|a| {
+                    a.get("email").and_then(|e| e.as_str()).is_some_and(|e| {
+                        !e.is_empty() && !e.ends_with("resource.calendar.google.com")
+                    })
+                })
+                .map(|a| {
+                    serde_json::json!({
+                        "email": a.get("email"),
+                        "name": a.get("display_name"),
+                        "response_status": a.get("response_status"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Gmail search for recent mail from or to any attendee, or mentioning the
+/// meeting title.
+fn gmail_query(emails: &[&str], title: &str, lookback_days: u64) -> Option<String> {
+    let mut terms: Vec<String> = emails
+        .iter()
+        .flat_map(|e| [format!("from:{}", e), format!("to:{}", e)])
+        .collect();
+    let title = title.replace('"', "");
+    if !title.trim().is_empty() {
+        terms.push(format!("subject:\"{}\"", title.trim()));
+    }
+    if terms.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "newer_than:{}d {{{}}}",
+        lookback_days,
+        terms.join(" ")
+    ))
+}
+
+/// Drive search for documents mentioning the meeting title or owned or
+/// edited by an attendee.
+fn drive_query(emails: &[&str], title: &str) -> Option<String> {
+    let quote = |s: &str| s.replace('\\', "\\\\").replace('\'', "\\'");
+    let mut terms: Vec<String> = Vec::new();
+    if !title.trim().is_empty() {
+        terms.push(format!("fullText contains '{}'", quote(title.trim())));
+    }
+    for email in emails {
+        terms.push(format!("'{}' in owners", quote(email)));
+        terms.push(format!("'{}' in writers", quote(email)));
+    }
+    if terms.is_empty() {
+        return None;
+    }
+    Some(format!("trashed = false and ({})", terms.join(" or ")))
+}
+
+/// Compact thread entries from a gmail list_messages result, one per thread.
+fn threads(result: &serde_json::Value, max: usize) -> Vec<serde_json::Value> {
+    let mut seen = std::collections::HashSet::new();
+    result
+        .get("messages")
+        .and_then(|m| m.as_array())
+        .map(|messages| {
+            messages
+                .iter()
+                .filter(|m| {
+                    let thread_id = m.get("thread_id").and_then(|t| t.as_str()).unwrap_or("");
+                    seen.insert(thread_id.to_string())
+                })
+                .take(max)
+                .map(|m| {
+                    serde_json::json!({
+                        "thread_id": m.get("thread_id"),
+                        "message_id": m.get("id"),
+                        "subject": m.get("subject"),
+                        "from": m.get("from"),
+                        "date": m.get("date"),
+                        "snippet": m.get("snippet"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Compact document entries from a google-drive list_files result.
+fn documents(result: &serde_json::Value) -> Vec<serde_json::Value> {
+    result
+        .get("files")
+        .and_then(|f| f.as_array())
+        .map(|files| {
+            files
+                .iter()
+                .map(|f| {
+                    serde_json::json!({
+                        "id": f.get("id"),
+                        "name": f.get("name"),
+                        "mime_type": f.get("mime_type"),
+                        "modified_time": f.get("modified_time"),
+                        "web_view_link": f.get("web_view_link"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn clip(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((i, _)) => format!("{}...", &text[..i]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Stand-in for an installed tool that returns a fixed result and
+    /// records the parameters it was called with.
+    struct FakeTool {
+        name: &'static str,
+        result: Result<serde_json::Value, String>,
+        calls: std::sync::Mutex<Vec<serde_json::Value>>,
+    }
+
+    impl FakeTool {
+        fn new(name: &'static str, result: Result<serde_json::Value, String>) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                result,
+                calls: std::sync::Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Tool for FakeTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "fake"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({})
+        }
+
+        async fn execute(
+            &self,
+            params: serde_json::Value,
+            _ctx: &JobContext,
+        ) -> Result<ToolOutput, ToolError> {
+            self.calls.lock().unwrap().push(params);
+            self.result
+                .clone()
+                .map(|r| ToolOutput::success(r, Duration::ZERO))
+                .map_err(ToolError::ExternalService)
+        }
+    }
+
+    fn event() -> serde_json::Value {
+        serde_json::json!({
+            "event": {
+                "id": "evt1",
+                "summary": "Q3 \"planning\"",
+                "start": { "date_time": "2026-10-20T10:00:00Z" },
+                "end": { "date_time": "2026-10-20T11:00:00Z" },
+                "attendees": [
+                    { "email": "me@example.com", "self": true },
+                    { "email": "ann@example.com", "display_name": "Ann", "response_status": "accepted" },
+                    { "email": "c_123@resource.calendar.google.com" }
+                ]
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_brief_gathers_threads_and_documents() {
+        let registry = Arc::new(ToolRegistry::new());
+        let gmail = FakeTool::new(
+            GMAIL_TOOL,
+            Ok(serde_json::json!({ "messages": [
+                { "id": "m1", "thread_id": "t1", "subject": "Agenda", "from": "ann@example.com" },
+                { "id": "m2", "thread_id": "t1", "subject": "Re: Agenda" },
+                { "id": "m3", "thread_id": "t2", "subject": "Numbers" }
+            ]})),
+        );
+        registry
+            .register(FakeTool::new(CALENDAR_TOOL, Ok(event())))
+            .await;
+        registry.register(Arc::clone(&gmail) as Arc<dyn Tool>).await;
+        registry
+            .register(FakeTool::new(
+                DRIVE_TOOL,
+                Err("not authenticated".to_string()),
+            ))
+            .await;
+
+        let tool = MeetingBriefTool::new(registry);
+        let output = tool
+            .execute(
+                serde_json::json!({ "event_id": "evt1" }),
+                &JobContext::default(),
+            )
+            .await
+            .unwrap();
+        let brief = output.result;
+
+        assert_eq!(brief["event"]["title"], "Q3 \"planning\"");
+        let attendees = brief["attendees"].as_array().unwrap();
+        assert_eq!(attendees.len(), 1);
+        assert_eq!(attendees[0]["email"], "ann@example.com");
+
+        let threads = brief["email_threads"].as_array().unwrap();
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0]["message_id"], "m1");
+
+        let query = gmail.calls.lock().unwrap()[0]["query"].clone();
+        assert_eq!(
+            query,
+            "newer_than:30d {from:ann@example.com to:ann@example.com subject:\"Q3 planning\"}"
+        );
+
+        assert_eq!(brief["documents"], serde_json::json!([]));
+        assert!(
+            brief["unavailable"][0]
+                .as_str()
+                .unwrap()
+                .contains("not authenticated")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_brief_requires_calendar() {
+        let tool = MeetingBriefTool::new(Arc::new(ToolRegistry::new()));
+        let err = tool
+            .execute(serde_json::json!({}), &JobContext::default())
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("google-calendar tool is not installed")
+        );
+    }
+
+    #[test]
+    fn test_drive_query_escapes_quotes() {
+        assert_eq!(
+            drive_query(&["o'neil@example.com"], "Bob's review").unwrap(),
+            "trashed = false and (fullText contains 'Bob\\'s review' or \
+             'o\\'neil@example.com' in owners or 'o\\'neil@example.com' in writers)"
+        );
+        assert!(drive_query(&[], " ").is_none());
+    }
+}
@@ -11,14 +11,25 @@
 //!
 //! Use `memory_write` to persist important facts that should be remembered
 //! across sessions.
+//!
+//! When memory history is enabled every write and delete is also committed to
+//! a git repository (see [`crate::workspace::MemoryHistory`]), and
+//! `memory_history` / `memory_restore` give the agent undo.
+//!
+//! Every tool takes an optional `space` naming a shared memory space (see
+//! [`crate::workspace::MemorySpaces`]) to use instead of the user's own
+//! memory. Reading needs read access to the space, changing it needs write
+//! access, and each change is recorded with the user behind it.
 
 use std::sync::Arc;
 
 use async_trait::async_trait;
 
 use crate::context::JobContext;
+use crate::error::WorkspaceError;
+use crate::tools::builtin::memory_spaces::space_error;
 use crate::tools::tool::{Tool, ToolError, ToolOutput};
-use crate::workspace::{Workspace, paths};
+use crate::workspace::{SpaceAccess, SpaceWrite, UserWorkspaces, Workspace, paths};
 
 /// Tool for searching workspace memory.
 ///
@@ -26,13 +37,13 @@ use crate::workspace::{Workspace, paths};
 /// The agent should call this tool before answering questions about
 /// prior work, decisions, preferences, or any historical context.
 pub struct MemorySearchTool {
-    workspace: Arc<Workspace>,
+    workspaces: Arc<UserWorkspaces>,
 }
 
 impl MemorySearchTool {
     /// Create a new memory search tool.
-    pub fn new(workspace: Arc<Workspace>) -> Self {
-        Self { workspace }
+    pub fn new(workspaces: Arc<UserWorkspaces>) -> Self {
+        Self { workspaces }
     }
 }
 
@@ -62,7 +73,8 @@ impl Tool for MemorySearchTool {
                     "default": 5,
                     "minimum": 1,
                     "maximum": 20
-                }
+                },
+                "space": space_param()
             },
             "required": ["query"]
         })
@@ -71,9 +83,11 @@ impl Tool for MemorySearchTool {
     async fn execute(
         &self,
         params: serde_json::Value,
-        _ctx: &JobContext,
+        ctx: &JobContext,
     ) -> Result<ToolOutput, ToolError> {
         let start = std::time::Instant::now();
+        let (workspace, _) =
+            resolve_workspace(&self.workspaces, &params, ctx, SpaceAccess::Read).await?;
 
         let query = params
             .get("query")
@@ -86,8 +100,7 @@ impl Tool for MemorySearchTool {
             .unwrap_or(5)
             .min(20) as usize;
 
-        let results = self
-            .workspace
+        let results = workspace
             .search(query, limit)
             .await
             .map_err(|e| ToolError::ExecutionFailed(format!("Search failed: {}", e)))?;
@@ -116,13 +129,13 @@ impl Tool for MemorySearchTool {
 /// Use this to persist important information that should be remembered
 /// across sessions: decisions, preferences, facts, lessons learned.
 pub struct MemoryWriteTool {
-    workspace: Arc<Workspace>,
+    workspaces: Arc<UserWorkspaces>,
 }
 
 impl MemoryWriteTool {
     /// Create a new memory write tool.
-    pub fn new(workspace: Arc<Workspace>) -> Self {
-        Self { workspace }
+    pub fn new(workspaces: Arc<UserWorkspaces>) -> Self {
+        Self { workspaces }
     }
 }
 
@@ -157,7 +170,8 @@ impl Tool for MemoryWriteTool {
                     "type": "boolean",
                     "description": "If true, append to existing content. If false, replace entirely.",
                     "default": true
-                }
+                },
+                "space": space_param()
             },
             "required": ["content"]
         })
@@ -166,9 +180,11 @@ impl Tool for MemoryWriteTool {
     async fn execute(
         &self,
         params: serde_json::Value,
-        _ctx: &JobContext,
+        ctx: &JobContext,
     ) -> Result<ToolOutput, ToolError> {
         let start = std::time::Instant::now();
+        let (workspace, space) =
+            resolve_workspace(&self.workspaces, &params, ctx, SpaceAccess::Write).await?;
 
         let content = params
             .get("content")
@@ -196,12 +212,12 @@ impl Tool for MemoryWriteTool {
         let path = match target {
             "memory" => {
                 if append {
-                    self.workspace
+                    workspace
                         .append_memory(content)
                         .await
                         .map_err(|e| ToolError::ExecutionFailed(format!("Write failed: {}", e)))?;
                 } else {
-                    self.workspace
+                    workspace
                         .write(paths::MEMORY, content)
                         .await
                         .map_err(|e| ToolError::ExecutionFailed(format!("Write failed: {}", e)))?;
@@ -209,7 +225,7 @@ impl Tool for MemoryWriteTool {
                 paths::MEMORY.to_string()
             }
             "daily_log" => {
-                self.workspace
+                workspace
                     .append_daily_log(content)
                     .await
                     .map_err(|e| ToolError::ExecutionFailed(format!("Write failed: {}", e)))?;
@@ -217,12 +233,12 @@ impl Tool for MemoryWriteTool {
             }
             "heartbeat" => {
                 if append {
-                    self.workspace
+                    workspace
                         .append(paths::HEARTBEAT, content)
                         .await
                         .map_err(|e| ToolError::ExecutionFailed(format!("Write failed: {}", e)))?;
                 } else {
-                    self.workspace
+                    workspace
                         .write(paths::HEARTBEAT, content)
                         .await
                         .map_err(|e| ToolError::ExecutionFailed(format!("Write failed: {}", e)))?;
@@ -231,12 +247,12 @@ impl Tool for MemoryWriteTool {
             }
             path => {
                 if append {
-                    self.workspace
+                    workspace
                         .append(path, content)
                         .await
                         .map_err(|e| ToolError::ExecutionFailed(format!("Write failed: {}", e)))?;
                 } else {
-                    self.workspace
+                    workspace
                         .write(path, content)
                         .await
                         .map_err(|e| ToolError::ExecutionFailed(format!("Write failed: {}", e)))?;
@@ -245,11 +261,27 @@ impl Tool for MemoryWriteTool {
             }
         };
 
+        let version = record_write(&workspace, ctx, "memory_write", &path).await;
+        let action = if append || target == "daily_log" {
+            "append"
+        } else {
+            "write"
+        };
+        record_space_writes(
+            &self.workspaces,
+            space.as_deref(),
+            ctx,
+            action,
+            &[path.clone()],
+        )
+        .await;
+
         let output = serde_json::json!({
             "status": "written",
             "path": path,
             "append": append,
             "content_length": content.len(),
+            "version": version,
         });
 
         Ok(ToolOutput::success(output, start.elapsed()))
@@ -264,13 +296,13 @@ impl Tool for MemoryWriteTool {
 ///
 /// Use this to read the full content of any file in the workspace.
 pub struct MemoryReadTool {
-    workspace: Arc<Workspace>,
+    workspaces: Arc<UserWorkspaces>,
 }
 
 impl MemoryReadTool {
     /// Create a new memory read tool.
-    pub fn new(workspace: Arc<Workspace>) -> Self {
-        Self { workspace }
+    pub fn new(workspaces: Arc<UserWorkspaces>) -> Self {
+        Self { workspaces }
     }
 }
 
@@ -294,7 +326,8 @@ impl Tool for MemoryReadTool {
                 "path": {
                     "type": "string",
                     "description": "Path to the file (e.g., 'MEMORY.md', 'daily/2024-01-15.md', 'projects/alpha/notes.md')"
-                }
+                },
+                "space": space_param()
             },
             "required": ["path"]
         })
@@ -303,27 +336,37 @@ impl Tool for MemoryReadTool {
     async fn execute(
         &self,
         params: serde_json::Value,
-        _ctx: &JobContext,
+        ctx: &JobContext,
     ) -> Result<ToolOutput, ToolError> {
         let start = std::time::Instant::now();
+        let (workspace, space) =
+            resolve_workspace(&self.workspaces, &params, ctx, SpaceAccess::Read).await?;
 
         let path = params
             .get("path")
             .and_then(|v| v.as_str())
             .ok_or_else(|| ToolError::InvalidParameters("missing 'path' parameter".to_string()))?;
 
-        let doc = self
-            .workspace
+        let doc = workspace
             .read(path)
             .await
             .map_err(|e| ToolError::ExecutionFailed(format!("Read failed: {}", e)))?;
 
-        let output = serde_json::json!({
+        let mut output = serde_json::json!({
             "path": doc.path,
             "content": doc.content,
             "word_count": doc.word_count(),
             "updated_at": doc.updated_at.to_rfc3339(),
         });
+        if let (Some(space), Some(spaces)) = (space, self.workspaces.spaces()) {
+            let last = spaces
+                .activity(&space, &ctx.user_id, Some(&doc.path), 1)
+                .await
+                .ok()
+                .and_then(|writes| writes.into_iter().next());
+            output["space"] = serde_json::json!(space);
+            output["last_written_by"] = serde_json::json!(last);
+        }
 
         Ok(ToolOutput::success(output, start.elapsed()))
     }
@@ -337,13 +380,13 @@ impl Tool for MemoryReadTool {
 ///
 /// Returns a hierarchical view of files and directories with configurable depth.
 pub struct MemoryTreeTool {
-    workspace: Arc<Workspace>,
+    workspaces: Arc<UserWorkspaces>,
 }
 
 impl MemoryTreeTool {
     /// Create a new memory tree tool.
-    pub fn new(workspace: Arc<Workspace>) -> Self {
-        Self { workspace }
+    pub fn new(workspaces: Arc<UserWorkspaces>) -> Self {
+        Self { workspaces }
     }
 
     /// Recursively build tree structure.
@@ -351,6 +394,7 @@ impl MemoryTreeTool {
     /// Returns a compact format where directories end with `/` and may have children.
     async fn build_tree(
         &self,
+        workspace: &Workspace,
         path: &str,
         current_depth: usize,
         max_depth: usize,
@@ -359,8 +403,7 @@ impl MemoryTreeTool {
             return Ok(Vec::new());
         }
 
-        let entries = self
-            .workspace
+        let entries = workspace
             .list(path)
             .await
             .map_err(|e| ToolError::ExecutionFailed(format!("Tree failed: {}", e)))?;
@@ -376,7 +419,8 @@ impl MemoryTreeTool {
 
             if entry.is_directory && current_depth < max_depth {
                 let children =
-                    Box::pin(self.build_tree(&entry.path, current_depth + 1, max_depth)).await?;
+                    Box::pin(self.build_tree(workspace, &entry.path, current_depth + 1, max_depth))
+                        .await?;
                 if children.is_empty() {
                     result.push(serde_json::Value::String(display_path));
                 } else {
@@ -418,7 +462,8 @@ impl Tool for MemoryTreeTool {
                     "default": 1,
                     "minimum": 1,
                     "maximum": 10
-                }
+                },
+                "space": space_param()
             }
         })
     }
@@ -426,9 +471,11 @@ impl Tool for MemoryTreeTool {
     async fn execute(
         &self,
         params: serde_json::Value,
-        _ctx: &JobContext,
+        ctx: &JobContext,
     ) -> Result<ToolOutput, ToolError> {
         let start = std::time::Instant::now();
+        let (workspace, _) =
+            resolve_workspace(&self.workspaces, &params, ctx, SpaceAccess::Read).await?;
 
         let path = params.get("path").and_then(|v| v.as_str()).unwrap_or("");
 
@@ -438,7 +485,7 @@ impl Tool for MemoryTreeTool {
             .unwrap_or(1)
             .clamp(1, 10) as usize;
 
-        let tree = self.build_tree(path, 1, depth).await?;
+        let tree = self.build_tree(&workspace, path, 1, depth).await?;
 
         // Compact output: just the tree array
         Ok(ToolOutput::success(
@@ -454,12 +501,12 @@ impl Tool for MemoryTreeTool {
 
 /// Tool for deleting workspace files or directories.
 pub struct MemoryDeleteTool {
-    workspace: Arc<Workspace>,
+    workspaces: Arc<UserWorkspaces>,
 }
 
 impl MemoryDeleteTool {
-    pub fn new(workspace: Arc<Workspace>) -> Self {
-        Self { workspace }
+    pub fn new(workspaces: Arc<UserWorkspaces>) -> Self {
+        Self { workspaces }
     }
 }
 
@@ -486,7 +533,8 @@ impl Tool for MemoryDeleteTool {
                     "type": "boolean",
                     "description": "If true, treat the path as a directory and delete all files within it.",
                     "default": false
-                }
+                },
+                "space": space_param()
             },
             "required": ["path"]
         })
@@ -495,9 +543,11 @@ impl Tool for MemoryDeleteTool {
     async fn execute(
         &self,
         params: serde_json::Value,
-        _ctx: &JobContext,
+        ctx: &JobContext,
     ) -> Result<ToolOutput, ToolError> {
         let start = std::time::Instant::now();
+        let (workspace, space) =
+            resolve_workspace(&self.workspaces, &params, ctx, SpaceAccess::Write).await?;
 
         let path = params
             .get("path")
@@ -510,9 +560,30 @@ impl Tool for MemoryDeleteTool {
             .unwrap_or(false);
 
         if is_directory {
-            let count = self.workspace.delete_directory(path).await
+            // Collect the files first so the deletion can be recorded in
+            // history and the space's provenance
+            let deleted: Vec<String> = if workspace.history().is_some() || space.is_some() {
+                let prefix = format!("{}/", path.trim().trim_matches('/'));
+                workspace
+                    .list_all()
+                    .await
+                    .map(|all| all.into_iter().filter(|p| p.starts_with(&prefix)).collect())
+                    .unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+
+            let count = workspace.delete_directory(path).await
                 .map_err(|e| ToolError::ExecutionFailed(format!("Directory deletion failed: {}", e)))?;
-            
+            record_space_writes(&self.workspaces, space.as_deref(), ctx, "delete", &deleted).await;
+            record_changes(
+                &workspace,
+                ctx,
+                format!("memory_delete: {}/", path.trim().trim_matches('/')),
+                deleted.into_iter().map(|p| (p, None)).collect(),
+            )
+            .await;
+
             Ok(ToolOutput::success(
                 serde_json::json!({
                     "status": "deleted",
@@ -523,8 +594,23 @@ impl Tool for MemoryDeleteTool {
                 start.elapsed(),
             ))
         } else {
-            self.workspace.delete(path).await
+            workspace.delete(path).await
                 .map_err(|e| ToolError::ExecutionFailed(format!("File deletion failed: {}", e)))?;
+            record_space_writes(
+                &self.workspaces,
+                space.as_deref(),
+                ctx,
+                "delete",
+                &[path.to_string()],
+            )
+            .await;
+            record_changes(
+                &workspace,
+                ctx,
+                format!("memory_delete: {}", path),
+                vec![(path.to_string(), None)],
+            )
+            .await;
 
             Ok(ToolOutput::success(
                 serde_json::json!({
@@ -542,12 +628,359 @@ impl Tool for MemoryDeleteTool {
     }
 }
 
+/// Tool for listing earlier versions of a workspace file.
+pub struct MemoryHistoryTool {
+    workspaces: Arc<UserWorkspaces>,
+}
+
+impl MemoryHistoryTool {
+    pub fn new(workspaces: Arc<UserWorkspaces>) -> Self {
+        Self { workspaces }
+    }
+}
+
+#[async_trait]
+impl Tool for MemoryHistoryTool {
+    fn name(&self) -> &str {
+        "memory_history"
+    }
+
+    fn description(&self) -> &str {
+        "List previous versions of a workspace memory file, newest first. Each version \
+         shows which job/turn made the change. Use with memory_restore to undo a bad write."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the file (e.g., 'MEMORY.md', 'projects/alpha/notes.md')"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of versions to return",
+                    "default": 20
+                },
+                "space": space_param()
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+    ) -> Result<ToolOutput, ToolError> {
+        let start = std::time::Instant::now();
+        let (workspace, _) =
+            resolve_workspace(&self.workspaces, &params, ctx, SpaceAccess::Read).await?;
+        let history = workspace.history().cloned().ok_or_else(history_disabled)?;
+
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("missing 'path' parameter".to_string()))?
+            .to_string();
+
+        let limit = params
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(20)
+            .clamp(1, 200) as usize;
+
+        let user_id = workspace.user_id().to_string();
+        let versions = {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || history.log(&user_id, &path, limit))
+                .await
+                .map_err(|e| ToolError::ExecutionFailed(format!("History failed: {}", e)))?
+                .map_err(|e| ToolError::ExecutionFailed(format!("History failed: {}", e)))?
+        };
+
+        let output = serde_json::json!({
+            "path": path,
+            "versions": versions,
+        });
+
+        Ok(ToolOutput::success(output, start.elapsed()))
+    }
+
+    fn requires_sanitization(&self) -> bool {
+        false // Internal memory
+    }
+}
+
+/// Tool for restoring a workspace file to an earlier version.
+pub struct MemoryRestoreTool {
+    workspaces: Arc<UserWorkspaces>,
+}
+
+impl MemoryRestoreTool {
+    pub fn new(workspaces: Arc<UserWorkspaces>) -> Self {
+        Self { workspaces }
+    }
+}
+
+#[async_trait]
+impl Tool for MemoryRestoreTool {
+    fn name(&self) -> &str {
+        "memory_restore"
+    }
+
+    fn description(&self) -> &str {
+        "Restore a workspace memory file to a previous version from memory_history. \
+         The restore is itself recorded as a new version, so it can be undone too."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the file to restore"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Version ID from memory_history"
+                },
+                "space": space_param()
+            },
+            "required": ["path", "version"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+    ) -> Result<ToolOutput, ToolError> {
+        let start = std::time::Instant::now();
+        let (workspace, space) =
+            resolve_workspace(&self.workspaces, &params, ctx, SpaceAccess::Write).await?;
+        let history = workspace.history().cloned().ok_or_else(history_disabled)?;
+
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("missing 'path' parameter".to_string()))?
+            .to_string();
+
+        let version = params
+            .get("version")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("missing 'version' parameter".to_string()))?
+            .to_string();
+
+        let content = {
+            let user_id = workspace.user_id().to_string();
+            let (path, version) = (path.clone(), version.clone());
+            tokio::task::spawn_blocking(move || history.content_at(&user_id, &path, &version))
+                .await
+                .map_err(|e| ToolError::ExecutionFailed(format!("Restore failed: {}", e)))?
+                .map_err(|e| ToolError::ExecutionFailed(format!("Restore failed: {}", e)))?
+        };
+        let content = content.ok_or_else(|| {
+            ToolError::ExecutionFailed(format!(
+                "'{}' did not exist at version {} (use memory_delete to remove it)",
+                path, version
+            ))
+        })?;
+
+        workspace
+            .write(&path, &content)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Restore failed: {}", e)))?;
+        record_space_writes(
+            &self.workspaces,
+            space.as_deref(),
+            ctx,
+            "restore",
+            &[path.clone()],
+        )
+        .await;
+        let new_version = record_changes(
+            &workspace,
+            ctx,
+            format!("memory_restore: {} to {}", path, version),
+            vec![(path.clone(), Some(content.clone()))],
+        )
+        .await;
+
+        let output = serde_json::json!({
+            "status": "restored",
+            "path": path,
+            "restored_from": version,
+            "version": new_version,
+            "content_length": content.len(),
+        });
+
+        Ok(ToolOutput::success(output, start.elapsed()))
+    }
+
+    fn requires_sanitization(&self) -> bool {
+        false // Internal tool
+    }
+}
+
+/// Schema of the optional `space` parameter shared by the memory tools.
+fn space_param() -> serde_json::Value {
+    serde_json::json!({
+        "type": "string",
+        "description": "Shared memory space to use instead of your own memory (see memory_spaces). Omit for your own memory."
+    })
+}
+
+/// The workspace a call works on, and the space it belongs to: the space
+/// named by `space` if the user has `needed` access to it, otherwise the
+/// user's own memory.
+async fn resolve_workspace(
+    workspaces: &UserWorkspaces,
+    params: &serde_json::Value,
+    ctx: &JobContext,
+    needed: SpaceAccess,
+) -> Result<(Arc<Workspace>, Option<String>), ToolError> {
+    let Some(space) = params
+        .get("space")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+    else {
+        return Ok((workspaces.for_user(&ctx.user_id), None));
+    };
+    let spaces = workspaces.spaces().ok_or_else(|| {
+        ToolError::ExecutionFailed("Shared memory spaces are not enabled".to_string())
+    })?;
+    let workspace = spaces
+        .open(space, &ctx.user_id, needed)
+        .await
+        .map_err(space_error)?;
+    let name = workspace
+        .user_id()
+        .trim_start_matches(crate::workspace::SPACE_OWNER_PREFIX)
+        .to_string();
+    Ok((workspace, Some(name)))
+}
+
+/// Record who changed `paths` in a shared space. Best-effort, like history.
+async fn record_space_writes(
+    workspaces: &UserWorkspaces,
+    space: Option<&str>,
+    ctx: &JobContext,
+    action: &str,
+    paths: &[String],
+) {
+    let (Some(space), Some(spaces)) = (space, workspaces.spaces()) else {
+        return;
+    };
+    for path in paths {
+        let write = SpaceWrite {
+            path: path.clone(),
+            user_id: ctx.user_id.clone(),
+            action: action.to_string(),
+            job_id: Some(ctx.job_id),
+            conversation_id: ctx.conversation_id,
+            created_at: chrono::Utc::now(),
+        };
+        if let Err(e) = spaces.record_write(space, &write).await {
+            tracing::warn!("Failed to record write to space {}: {}", space, e);
+        }
+    }
+}
+
+fn history_disabled() -> ToolError {
+    ToolError::ExecutionFailed(
+        "Memory history is disabled (set MEMORY_HISTORY_ENABLED=true)".to_string(),
+    )
+}
+
+/// Record the current content of `path` in memory history after a write.
+async fn record_write(
+    workspace: &Workspace,
+    ctx: &JobContext,
+    tool: &str,
+    path: &str,
+) -> Option<String> {
+    workspace.history()?;
+    let content = match workspace.read(path).await {
+        Ok(doc) => Some(doc.content),
+        Err(WorkspaceError::DocumentNotFound { .. }) => None,
+        Err(e) => {
+            tracing::warn!("Could not read {} for memory history: {}", path, e);
+            return None;
+        }
+    };
+    record_changes(
+        workspace,
+        ctx,
+        format!("{}: {}", tool, path),
+        vec![(path.to_string(), content)],
+    )
+    .await
+}
+
+/// Commit `changes` (path, new content or `None` when deleted) to memory
+/// history, returning the new version ID.
+///
+/// History is best-effort: failures are logged and never fail the tool call.
+async fn record_changes(
+    workspace: &Workspace,
+    ctx: &JobContext,
+    summary: String,
+    changes: Vec<(String, Option<String>)>,
+) -> Option<String> {
+    let history = Arc::clone(workspace.history()?);
+    if changes.is_empty() {
+        return None;
+    }
+    let user_id = workspace.user_id().to_string();
+    let message = commit_message(&summary, ctx);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let changes: Vec<(&str, Option<&str>)> = changes
+            .iter()
+            .map(|(path, content)| (path.as_str(), content.as_deref()))
+            .collect();
+        history.record(&user_id, &changes, &message)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(version)) => version,
+        Ok(Err(e)) => {
+            tracing::warn!("Failed to record memory history: {}", e);
+            None
+        }
+        Err(e) => {
+            tracing::warn!("Memory history task failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Commit message naming the user, job and conversation turn behind a change.
+fn commit_message(summary: &str, ctx: &JobContext) -> String {
+    let mut message = format!(
+        "{}\n\nUser: {}\nJob: {} ({})\n",
+        summary, ctx.user_id, ctx.job_id, ctx.title
+    );
+    if let Some(conversation_id) = ctx.conversation_id {
+        message.push_str(&format!("Conversation: {}\n", conversation_id));
+    }
+    if let Some(turn) = ctx.metadata.get("turn").and_then(|v| v.as_u64()) {
+        message.push_str(&format!("Turn: {}\n", turn));
+    }
+    message
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn make_test_workspace() -> Arc<Workspace> {
-        Arc::new(Workspace::new(
+    fn make_test_workspace() -> Arc<UserWorkspaces> {
+        Arc::new(UserWorkspaces::shared(Arc::new(Workspace::new(
             "test_user",
             deadpool_postgres::Pool::builder(deadpool_postgres::Manager::new(
                 tokio_postgres::Config::new(),
@@ -555,7 +988,7 @@ mod tests {
             ))
             .build()
             .unwrap(),
-        ))
+        ))))
     }
 
     #[test]
@@ -587,6 +1020,7 @@ mod tests {
         assert!(schema["properties"]["content"].is_object());
         assert!(schema["properties"]["target"].is_object());
         assert!(schema["properties"]["append"].is_object());
+        assert!(schema["properties"]["space"].is_object());
     }
 
     #[test]
@@ -618,4 +1052,29 @@ mod tests {
         assert!(schema["properties"]["depth"].is_object());
         assert_eq!(schema["properties"]["depth"]["default"], 1);
     }
+
+    #[test]
+    fn test_memory_restore_schema() {
+        let tool = MemoryRestoreTool::new(make_test_workspace());
+
+        assert_eq!(tool.name(), "memory_restore");
+
+        let required = tool.parameters_schema()["required"].clone();
+        assert!(required.as_array().unwrap().contains(&"path".into()));
+        assert!(required.as_array().unwrap().contains(&"version".into()));
+    }
+
+    #[test]
+    fn test_commit_message_names_user_job_and_turn() {
+        let mut ctx = JobContext::with_user("alice", "chat", "Interactive chat session");
+        let conversation_id = uuid::Uuid::new_v4();
+        ctx.conversation_id = Some(conversation_id);
+        ctx.metadata = serde_json::json!({ "turn": 3 });
+
+        let message = commit_message("memory_write: MEMORY.md", &ctx);
+        assert!(message.starts_with("memory_write: MEMORY.md\n\nUser: alice\n"));
+        assert!(message.contains(&format!("Job: {} (chat)", ctx.job_id)));
+        assert!(message.contains(&format!("Conversation: {}", conversation_id)));
+        assert!(message.contains("Turn: 3"));
+    }
 }
@@ -0,0 +1,220 @@
+//! Shared memory space management tool.
+//!
+//! Lists the spaces a user can use, creates new ones, manages who may read
+//! or write them, and shows who changed what. Reading and writing a space's
+//! files goes through the regular memory tools with their `space` parameter.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::context::JobContext;
+use crate::error::WorkspaceError;
+use crate::tools::tool::{Tool, ToolError, ToolOutput};
+use crate::workspace::{MemorySpaces, SpaceAccess};
+
+/// Tool for listing, creating and sharing memory spaces.
+pub struct MemorySpacesTool {
+    spaces: Arc<MemorySpaces>,
+}
+
+impl MemorySpacesTool {
+    pub fn new(spaces: Arc<MemorySpaces>) -> Self {
+        Self { spaces }
+    }
+}
+
+#[async_trait]
+impl Tool for MemorySpacesTool {
+    fn name(&self) -> &str {
+        "memory_spaces"
+    }
+
+    fn description(&self) -> &str {
+        "Manage shared memory spaces: named memories (e.g. 'team-wiki') that several users \
+         read and write, separate from your own memory. Use the memory_* tools with a \
+         'space' parameter to search, read or write a space's files. Actions: 'list' the \
+         spaces you can use, 'create' a space, 'grant' or 'revoke' a user's access (space \
+         admins only), 'members' of a space, 'activity' showing who changed which file."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "create", "grant", "revoke", "members", "activity"],
+                    "description": "Operation to perform"
+                },
+                "space": {
+                    "type": "string",
+                    "description": "Space name (lowercase letters, digits, '-', '_'); required except for list"
+                },
+                "description": {
+                    "type": "string",
+                    "description": "What the space is for (create)"
+                },
+                "user_id": {
+                    "type": "string",
+                    "description": "User to grant or revoke access for"
+                },
+                "access": {
+                    "type": "string",
+                    "enum": ["read", "write", "admin"],
+                    "description": "Access to grant: read, write, or admin (write plus managing members)",
+                    "default": "read"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Only show changes to this file (activity)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of changes to return (activity)",
+                    "default": 20,
+                    "minimum": 1,
+                    "maximum": 200
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+    ) -> Result<ToolOutput, ToolError> {
+        let start = std::time::Instant::now();
+
+        let action = params
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("missing 'action' parameter".to_string())
+            })?;
+        let required = |name: &str| {
+            params
+                .get(name)
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.trim().is_empty())
+                .ok_or_else(|| {
+                    ToolError::InvalidParameters(format!("missing '{}' parameter", name))
+                })
+        };
+        let user_id = ctx.user_id.as_str();
+
+        let result = match action {
+            "list" => {
+                let spaces = self.spaces.list(user_id).await.map_err(space_error)?;
+                let spaces: Vec<serde_json::Value> = spaces
+                    .into_iter()
+                    .map(|(space, access)| {
+                        serde_json::json!({
+                            "name": space.name,
+                            "description": space.description,
+                            "created_by": space.created_by,
+                            "access": access,
+                        })
+                    })
+                    .collect();
+                serde_json::json!({ "count": spaces.len(), "spaces": spaces })
+            }
+            "create" => {
+                let description = params
+                    .get("description")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("");
+                let space = self
+                    .spaces
+                    .create(required("space")?, description, user_id)
+                    .await
+                    .map_err(space_error)?;
+                serde_json::json!({ "status": "created", "space": space, "access": "admin" })
+            }
+            "grant" => {
+                let space = required("space")?;
+                let member = required("user_id")?;
+                let access = match params.get("access").and_then(|v| v.as_str()) {
+                    None => SpaceAccess::Read,
+                    Some(a) => SpaceAccess::parse(a).ok_or_else(|| {
+                        ToolError::InvalidParameters(format!(
+                            "unknown access '{}': expected read, write or admin",
+                            a
+                        ))
+                    })?,
+                };
+                self.spaces
+                    .grant(space, user_id, member, access)
+                    .await
+                    .map_err(space_error)?;
+                serde_json::json!({
+                    "status": "granted",
+                    "space": space,
+                    "user_id": member,
+                    "access": access,
+                })
+            }
+            "revoke" => {
+                let space = required("space")?;
+                let member = required("user_id")?;
+                let removed = self
+                    .spaces
+                    .revoke(space, user_id, member)
+                    .await
+                    .map_err(space_error)?;
+                serde_json::json!({
+                    "status": "revoked",
+                    "space": space,
+                    "user_id": member,
+                    "was_member": removed,
+                })
+            }
+            "members" => {
+                let space = required("space")?;
+                let members = self
+                    .spaces
+                    .members(space, user_id)
+                    .await
+                    .map_err(space_error)?;
+                serde_json::json!({ "space": space, "members": members })
+            }
+            "activity" => {
+                let space = required("space")?;
+                let path = params.get("path").and_then(|v| v.as_str());
+                let limit = params
+                    .get("limit")
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or(20)
+                    .clamp(1, 200);
+                let writes = self
+                    .spaces
+                    .activity(space, user_id, path, limit)
+                    .await
+                    .map_err(space_error)?;
+                serde_json::json!({ "space": space, "changes": writes })
+            }
+            other => {
+                return Err(ToolError::InvalidParameters(format!(
+                    "unknown action '{}': expected list, create, grant, revoke, members or activity",
+                    other
+                )));
+            }
+        };
+
+        Ok(ToolOutput::success(result, start.elapsed()))
+    }
+
+    fn requires_sanitization(&self) -> bool {
+        false // Internal tool
+    }
+}
+
+/// Bad space names are the caller's mistake; anything else failed to run.
+pub(super) fn space_error(e: WorkspaceError) -> ToolError {
+    match e {
+        WorkspaceError::InvalidSpaceName { .. } => ToolError::InvalidParameters(e.to_string()),
+        e => ToolError::ExecutionFailed(e.to_string()),
+    }
+}
@@ -1,5 +1,7 @@
 //! Built-in tools that come with the agent.
 
+mod artifacts;
+mod bundle;
 mod echo;
 mod ecommerce;
 pub mod extension_tools;
@@ -9,15 +11,21 @@ mod http;
 mod job;
 mod json;
 mod marketplace;
+mod meeting_brief;
 mod memory;
 mod memory_search;
+mod memory_spaces;
+mod provenance;
 mod restaurant;
 mod shell;
 mod search;
 mod sneed;
 mod taskrabbit;
 mod time;
+mod variables;
 
+pub use artifacts::ListArtifactsTool;
+pub use bundle::ExportJobBundleTool;
 pub use echo::EchoTool;
 pub use ecommerce::EcommerceTool;
 pub use extension_tools::{
@@ -27,13 +35,21 @@ pub use file::{ApplyPatchTool, ListDirTool, ReadFileTool, WriteFileTool};
 pub use help::HelpTool;
 pub use http::HttpTool;
 pub use job::{CancelJobTool, CreateJobTool, JobStatusTool, ListJobsTool};
+pub(crate) use job::resolve_project_dir;
 pub use json::JsonTool;
 pub use marketplace::MarketplaceTool;
-pub use memory::{MemoryDeleteTool, MemoryReadTool, MemorySearchTool, MemoryTreeTool, MemoryWriteTool};
+pub use meeting_brief::MeetingBriefTool;
+pub use memory::{
+    MemoryDeleteTool, MemoryHistoryTool, MemoryReadTool, MemoryRestoreTool, MemorySearchTool,
+    MemoryTreeTool, MemoryWriteTool,
+};
 pub use memory_search::MemoryUploadTool;
+pub use memory_spaces::MemorySpacesTool;
+pub use provenance::ExplainActionsTool;
 pub use restaurant::RestaurantTool;
 pub use shell::ShellTool;
 pub use search::SearchTool;
-pub use sneed::SneedTool;
+pub use sneed::{SneedStatusTool, SneedTool};
 pub use taskrabbit::TaskRabbitTool;
 pub use time::TimeTool;
+pub use variables::ConversationVarsTool;
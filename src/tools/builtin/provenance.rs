@@ -0,0 +1,159 @@
+//! Provenance tool.
+//!
+//! Lets the LLM answer "how did you get that?" from the tool calls that were
+//! actually made (which tool, which range or file, when) instead of
+//! reconstructing an explanation from memory.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::context::{ActionQuery, ContextManager, JobContext};
+use crate::tools::tool::{Tool, ToolError, ToolOutput};
+
+/// Default number of actions returned.
+const DEFAULT_LIMIT: usize = 10;
+
+/// Tool for looking up the recorded tool calls behind a result.
+pub struct ExplainActionsTool {
+    context_manager: Arc<ContextManager>,
+}
+
+impl ExplainActionsTool {
+    pub fn new(context_manager: Arc<ContextManager>) -> Self {
+        Self { context_manager }
+    }
+}
+
+#[async_trait]
+impl Tool for ExplainActionsTool {
+    fn name(&self) -> &str {
+        "explain_actions"
+    }
+
+    fn description(&self) -> &str {
+        "Look up the tool calls you actually made, newest first: which tool, what it read or \
+         changed (spreadsheet range, file, URL, command), when, and the part of its output \
+         containing a value. Use this when the user asks how you got a number or fact, and \
+         answer from the recorded calls rather than from memory. If nothing matches, say the \
+         value wasn't produced by a recorded tool call."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "value": {
+                    "type": "string",
+                    "description": "Number or text to trace, e.g. '42,315' or 'Acme Corp'. \
+                                    Numbers match with or without thousands separators."
+                },
+                "tool": {
+                    "type": "string",
+                    "description": "Only return calls to this tool"
+                },
+                "job_id": {
+                    "type": "string",
+                    "description": "Only return calls made by this job"
+                },
+                "this_conversation": {
+                    "type": "boolean",
+                    "description": "Only return calls made in the current conversation (default: false)"
+                },
+                "include_failed": {
+                    "type": "boolean",
+                    "description": "Include calls that failed (default: false)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of results (default: 10)"
+                }
+            }
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+    ) -> Result<ToolOutput, ToolError> {
+        let start = std::time::Instant::now();
+
+        let job_id = match params.get("job_id").and_then(|v| v.as_str()) {
+            Some(id) => Some(
+                Uuid::parse_str(id)
+                    .map_err(|_| ToolError::InvalidParameters(format!("invalid job ID: {}", id)))?,
+            ),
+            None => None,
+        };
+        let flag = |key: &str| params.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+        let value = params
+            .get("value")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+
+        let query = ActionQuery {
+            contains: value.clone(),
+            tool_name: params
+                .get("tool")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            job_id,
+            conversation_id: if flag("this_conversation") {
+                ctx.conversation_id
+            } else {
+                None
+            },
+            include_failed: flag("include_failed"),
+            // Limited below, after leaving out earlier lookups
+            limit: 0,
+        };
+        let limit = params
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_LIMIT);
+
+        let actions: Vec<serde_json::Value> = self
+            .context_manager
+            .actions()
+            .search(&ctx.user_id, &query)
+            .await
+            .into_iter()
+            // Earlier lookups contain the value too, but aren't its source
+            .filter(|a| a.tool_name != self.name())
+            .take(limit)
+            .map(|a| {
+                let excerpt = value.as_deref().and_then(|v| a.excerpt(v));
+                serde_json::json!({
+                    "tool": a.tool_name,
+                    "action": a.action,
+                    "target": a.target,
+                    "executed_at": a.executed_at.to_rfc3339(),
+                    "job_id": a.job_id.to_string(),
+                    "in_this_conversation": ctx.conversation_id.is_some()
+                        && a.conversation_id == ctx.conversation_id,
+                    "success": a.success,
+                    "error": a.error,
+                    "input": a.input,
+                    "output_excerpt": excerpt,
+                })
+            })
+            .collect();
+
+        let count = actions.len();
+        let mut result = serde_json::json!({
+            "count": count,
+            "actions": actions,
+        });
+        if count == 0 && value.is_some() {
+            result["note"] = serde_json::json!(
+                "No recorded tool call contains this value. It may have been computed or stated \
+                 without a tool; say so instead of guessing a source."
+            );
+        }
+
+        Ok(ToolOutput::success(result, start.elapsed()))
+    }
+}
@@ -1,9 +1,12 @@
 use async_trait::async_trait;
 use serde_json::Value;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
 
 use crate::context::JobContext;
-use crate::sneed_engine::{GlyphWave, StakesEngine};
+use crate::sneed_engine::{GlyphWave, LuoShuGate, SovereignGrid, StakesEngine, UserStakes};
 use crate::tools::{Tool, ToolError, ToolOutput};
 
 /// Tool for performing a retrocausal logic audit using the Sneed Engine.
@@ -55,9 +58,9 @@ impl Tool for SneedTool {
         let mut grid = crate::sneed_engine::SovereignGrid::new(3, 8);
         
         // Perturb potentials based on node index to model a non-trivial potential field V
-        for (i, node) in grid.nodes.iter_mut().enumerate() {
+        for (i, scale) in grid.scales_mut().iter_mut().enumerate() {
             let val = (i as f64 * 0.2).sin().abs() * 2.0 + 1.0;
-            node.spatial_attention_scale = val;
+            *scale = val;
         }
 
         // Build bio input from detected stakes
@@ -71,10 +74,14 @@ impl Tool for SneedTool {
         
         let (coherence, alpha, sigma) = grid.get_spectral_metrics();
         
-        let total_potential: f64 = grid.nodes.iter().map(|n| n.spatial_attention_scale).sum();
-        let avg_potential = total_potential / grid.nodes.len() as f64;
-        let min_potential = grid.nodes.iter().map(|n| n.spatial_attention_scale).fold(f64::INFINITY, f64::min);
-        let max_potential = grid.nodes.iter().map(|n| n.spatial_attention_scale).fold(f64::NEG_INFINITY, f64::max);
+        let total_potential: f64 = grid.scales().iter().sum();
+        let avg_potential = total_potential / grid.len() as f64;
+        let min_potential = grid.scales().iter().copied().fold(f64::INFINITY, f64::min);
+        let max_potential = grid
+            .scales()
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
 
         let header = GlyphWave::render("SOVEREIGN LOGIC AUDIT");
         let resonance_report = engine.get_resonance_report();
@@ -109,3 +116,127 @@ impl Tool for SneedTool {
         ))
     }
 }
+
+/// Tool reporting the live state of the agent's Sneed Engine as structured JSON.
+///
+/// Unlike `sneed`, which audits a query against a fresh engine, this reads the
+/// calling user's engine and the grid shared with the agent loop, so channel
+/// users can inspect the state that is actually steering their responses.
+pub struct SneedStatusTool {
+    stakes: Arc<UserStakes>,
+    grid: Arc<Mutex<SovereignGrid>>,
+}
+
+impl SneedStatusTool {
+    pub fn new(stakes: Arc<UserStakes>, grid: Arc<Mutex<SovereignGrid>>) -> Self {
+        Self { stakes, grid }
+    }
+}
+
+#[async_trait]
+impl Tool for SneedStatusTool {
+    fn name(&self) -> &str {
+        "sneed_status"
+    }
+
+    fn description(&self) -> &str {
+        "Report the current Sneed Engine state: LuoShu gate check on the grid's cell energies, \
+         grid density factor, coherence values, personality blend, and recent deliberation scores."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {}
+        })
+    }
+
+    async fn execute(&self, _params: Value, ctx: &JobContext) -> Result<ToolOutput, ToolError> {
+        let start = Instant::now();
+
+        let (luoshu_gate, density_factor, coherence, grid_nodes) = {
+            let grid = self.grid.lock().await;
+            let matrix = grid.luoshu_matrix();
+            let (spectral, alpha, sigma) = grid.get_spectral_metrics();
+            let nodes = grid.coherence();
+            let (mean, min, max) = if nodes.is_empty() {
+                (0.0, 0.0, 0.0)
+            } else {
+                (
+                    nodes.iter().sum::<f64>() / nodes.len() as f64,
+                    nodes.iter().copied().fold(f64::INFINITY, f64::min),
+                    nodes.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                )
+            };
+
+            (
+                serde_json::json!({
+                    "passed": LuoShuGate::check_invariants(&matrix),
+                    "invariant": crate::sneed_engine::LUOSHU_INVARIANT,
+                    "cell_energies": matrix,
+                }),
+                grid.get_density_factor(),
+                serde_json::json!({
+                    "spectral": spectral,
+                    "stable": spectral >= crate::sneed_engine::COHERENCE_THRESHOLD,
+                    "threshold": crate::sneed_engine::COHERENCE_THRESHOLD,
+                    "alpha": alpha,
+                    "sigma": sigma,
+                    "node_mean": mean,
+                    "node_min": min,
+                    "node_max": max,
+                }),
+                grid.len(),
+            )
+        };
+
+        // A user who hasn't chatted yet has a fresh engine's state
+        let engine = self
+            .stakes
+            .get(&ctx.user_id)
+            .unwrap_or_else(|| Arc::new(Mutex::new(StakesEngine::new())));
+        let stakes = engine.lock().await;
+        let mut active: Vec<(&'static str, f64)> = stakes
+            .stakes
+            .iter()
+            .map(|(s, v)| (s.as_str(), *v))
+            .collect();
+        active.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+        let council: Vec<Value> = stakes
+            .council
+            .iter()
+            .map(|m| {
+                serde_json::json!({
+                    "name": m.name,
+                    "role": m.role,
+                    "recent_resonance": m.resonance_history,
+                })
+            })
+            .collect();
+
+        let result = serde_json::json!({
+            "luoshu_gate": luoshu_gate,
+            "density_factor": density_factor,
+            "coherence": coherence,
+            "grid_nodes": grid_nodes,
+            "personality_blend": stakes.get_personality_blend(),
+            "emotional_resonance": stakes.emotional_resonance,
+            "qualia_intensity": stakes.qualia_intensity,
+            "identity_strength": stakes.identity_strength,
+            "c_norm": stakes.current_c_norm,
+            "stakes": active
+                .iter()
+                .map(|(name, v)| serde_json::json!({ "stake": name, "value": v }))
+                .collect::<Vec<_>>(),
+            "recent_agency_scores": stakes.recent_agency,
+            "council": council,
+            "custom_roster": stakes.roster.is_some(),
+        });
+
+        Ok(ToolOutput::success(result, start.elapsed()))
+    }
+
+    fn requires_sanitization(&self) -> bool {
+        false
+    }
+}
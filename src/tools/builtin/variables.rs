@@ -0,0 +1,141 @@
+//! Conversation variable tool.
+//!
+//! Lets the LLM stash and recall typed values (IDs, counts, small JSON
+//! blobs) scoped to the current conversation.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::context::{ConversationVariables, JobContext};
+use crate::db::Database;
+use crate::tools::tool::{Tool, ToolError, ToolOutput};
+
+/// Tool for setting, getting, listing and deleting conversation variables.
+pub struct ConversationVarsTool {
+    store: Arc<dyn Database>,
+}
+
+impl ConversationVarsTool {
+    pub fn new(store: Arc<dyn Database>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for ConversationVarsTool {
+    fn name(&self) -> &str {
+        "conversation_vars"
+    }
+
+    fn description(&self) -> &str {
+        "Store and recall named values scoped to this conversation (IDs, links, counts, \
+         small JSON objects). Use it to keep intermediate results across turns instead of \
+         repeating them in prose. Actions: 'set', 'get', 'list', 'delete'."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["set", "get", "list", "delete"],
+                    "description": "Operation to perform"
+                },
+                "key": {
+                    "type": "string",
+                    "description": "Variable name (letters, digits, '_', '-', '.'); required for set/get/delete"
+                },
+                "value": {
+                    "description": "Value to store (string, number, boolean, or JSON); required for set"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+    ) -> Result<ToolOutput, ToolError> {
+        let start = std::time::Instant::now();
+
+        let conversation_id = ctx.conversation_id.ok_or_else(|| {
+            ToolError::ExecutionFailed("No conversation is associated with this job".to_string())
+        })?;
+
+        let action = params
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("missing 'action' parameter".to_string())
+            })?;
+        let key = || {
+            params
+                .get("key")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| ToolError::InvalidParameters("missing 'key' parameter".to_string()))
+        };
+
+        let mut vars = ConversationVariables::load(self.store.as_ref(), conversation_id)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to load variables: {}", e)))?;
+
+        let result = match action {
+            "set" => {
+                let key = key()?;
+                let value = params.get("value").cloned().ok_or_else(|| {
+                    ToolError::InvalidParameters("missing 'value' parameter".to_string())
+                })?;
+                vars.set(key, value)
+                    .map_err(|e| ToolError::InvalidParameters(e.to_string()))?;
+                vars.save(self.store.as_ref(), conversation_id)
+                    .await
+                    .map_err(|e| {
+                        ToolError::ExecutionFailed(format!("Failed to save variables: {}", e))
+                    })?;
+                serde_json::json!({ "status": "set", "key": key, "variable": vars.get(key) })
+            }
+            "get" => {
+                let key = key()?;
+                match vars.get(key) {
+                    Some(var) => serde_json::json!({ "key": key, "variable": var, "found": true }),
+                    None => serde_json::json!({ "key": key, "variable": null, "found": false }),
+                }
+            }
+            "list" => {
+                let variables: serde_json::Map<String, serde_json::Value> = vars
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), serde_json::json!(v)))
+                    .collect();
+                serde_json::json!({ "count": vars.len(), "variables": variables })
+            }
+            "delete" => {
+                let key = key()?;
+                let removed = vars.remove(key);
+                if removed {
+                    vars.save(self.store.as_ref(), conversation_id)
+                        .await
+                        .map_err(|e| {
+                            ToolError::ExecutionFailed(format!("Failed to save variables: {}", e))
+                        })?;
+                }
+                serde_json::json!({ "status": "deleted", "key": key, "existed": removed })
+            }
+            other => {
+                return Err(ToolError::InvalidParameters(format!(
+                    "unknown action '{}': expected set, get, list or delete",
+                    other
+                )));
+            }
+        };
+
+        Ok(ToolOutput::success(result, start.elapsed()))
+    }
+
+    fn requires_sanitization(&self) -> bool {
+        false // Internal tool
+    }
+}
@@ -3,7 +3,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 
 use crate::context::ContextManager;
 use crate::db::Database;
@@ -11,11 +11,13 @@ use crate::extensions::ExtensionManager;
 use crate::llm::{LlmProvider, ToolDefinition};
 use crate::orchestrator::ContainerJobManager;
 use crate::safety::SafetyLayer;
+use crate::sandbox::WorkspaceQuota;
+use crate::sneed_engine::{SovereignGrid, UserStakes};
 use crate::tools::builder::{BuildSoftwareTool, BuilderConfig, LlmSoftwareBuilder};
 use crate::tools::builtin::{
-    ApplyPatchTool, CancelJobTool, CreateJobTool, EchoTool, EcommerceTool, HelpTool, HttpTool, JobStatusTool, JsonTool,
-    ListDirTool, ListJobsTool, MemoryDeleteTool, MemoryReadTool, MemorySearchTool, MemoryTreeTool, MemoryWriteTool, MemoryUploadTool,
-    ReadFileTool, RestaurantTool, SearchTool, ShellTool, SneedTool, TaskRabbitTool, TimeTool, ToolActivateTool, ToolAuthTool, ToolInstallTool,
+    ApplyPatchTool, CancelJobTool, ConversationVarsTool, CreateJobTool, EchoTool, EcommerceTool, ExplainActionsTool, ExportJobBundleTool, HelpTool, HttpTool, JobStatusTool, JsonTool,
+    ListArtifactsTool, ListDirTool, ListJobsTool, MeetingBriefTool, MemoryDeleteTool, MemoryHistoryTool, MemoryReadTool, MemoryRestoreTool, MemorySearchTool, MemorySpacesTool, MemoryTreeTool, MemoryWriteTool, MemoryUploadTool,
+    ReadFileTool, RestaurantTool, SearchTool, ShellTool, SneedStatusTool, SneedTool, TaskRabbitTool, TimeTool, ToolActivateTool, ToolAuthTool, ToolInstallTool,
     ToolListTool, ToolRemoveTool, ToolSearchTool, WriteFileTool,
 };
 use crate::tools::tool::Tool;
@@ -23,7 +25,7 @@ use crate::tools::wasm::{
     Capabilities, ResourceLimits, WasmError, WasmStorageError, WasmToolRuntime, WasmToolStore,
     WasmToolWrapper,
 };
-use crate::workspace::Workspace;
+use crate::workspace::UserWorkspaces;
 
 /// Registry of available tools.
 pub struct ToolRegistry {
@@ -134,33 +136,71 @@ impl ToolRegistry {
     /// capabilities needed for the software builder. Call this after
     /// `register_builtin_tools()` to enable code generation features.
     pub fn register_dev_tools(&self) {
+        let mut write_file = WriteFileTool::new();
+        let mut apply_patch = ApplyPatchTool::new();
+        // Inside a job container, hold writes to the orchestrator's quota
+        if let Some((root, quota)) = WorkspaceQuota::from_env() {
+            write_file = write_file.with_quota(root.clone(), quota);
+            apply_patch = apply_patch.with_quota(root, quota);
+        }
+
         self.register_sync(Arc::new(ShellTool::new()));
         self.register_sync(Arc::new(ReadFileTool::new()));
-        self.register_sync(Arc::new(WriteFileTool::new()));
+        self.register_sync(Arc::new(write_file));
         self.register_sync(Arc::new(ListDirTool::new()));
-        self.register_sync(Arc::new(ApplyPatchTool::new()));
+        self.register_sync(Arc::new(apply_patch));
 
         tracing::info!("Registered 5 development tools");
     }
 
     /// Register memory tools with a workspace.
     ///
-    /// Memory tools require a workspace for persistence. Call this after
-    /// `register_builtin_tools()` if you have a workspace available.
-    pub fn register_memory_tools(&self, workspace: Arc<Workspace>, llm: Arc<dyn LlmProvider>) {
-        self.register_sync(Arc::new(MemorySearchTool::new(Arc::clone(&workspace))));
-        self.register_sync(Arc::new(MemoryWriteTool::new(Arc::clone(&workspace))));
-        self.register_sync(Arc::new(MemoryReadTool::new(Arc::clone(&workspace))));
-        self.register_sync(Arc::new(MemoryTreeTool::new(Arc::clone(&workspace))));
-        self.register_sync(Arc::new(MemoryDeleteTool::new(workspace)));
+    /// Memory tools require a workspace for persistence. Each call resolves
+    /// the calling user's workspace, so tenants never see each other's memory.
+    /// Call this after `register_builtin_tools()` if you have a workspace available.
+    pub fn register_memory_tools(
+        &self,
+        workspaces: Arc<UserWorkspaces>,
+        llm: Arc<dyn LlmProvider>,
+    ) {
+        self.register_sync(Arc::new(MemorySearchTool::new(Arc::clone(&workspaces))));
+        self.register_sync(Arc::new(MemoryWriteTool::new(Arc::clone(&workspaces))));
+        self.register_sync(Arc::new(MemoryReadTool::new(Arc::clone(&workspaces))));
+        self.register_sync(Arc::new(MemoryTreeTool::new(Arc::clone(&workspaces))));
+        self.register_sync(Arc::new(MemoryDeleteTool::new(Arc::clone(&workspaces))));
         self.register_sync(Arc::new(MemoryUploadTool::new(llm)));
+        if let Some(spaces) = workspaces.spaces() {
+            self.register_sync(Arc::new(MemorySpacesTool::new(Arc::clone(spaces))));
+            tracing::info!("Registered memory_spaces tool");
+        }
 
-        tracing::info!("Registered 6 memory tools");
+        if workspaces.default_workspace().history().is_some() {
+            self.register_sync(Arc::new(MemoryHistoryTool::new(Arc::clone(&workspaces))));
+            self.register_sync(Arc::new(MemoryRestoreTool::new(workspaces)));
+            tracing::info!("Registered 8 memory tools (with version history)");
+        } else {
+            tracing::info!("Registered 6 memory tools");
+        }
+    }
+
+    /// Register the Sneed Engine diagnostics tool.
+    ///
+    /// Takes the per-user engines and grid owned by the agent loop so the tool
+    /// reports live state. Call this after the `Agent` has been created.
+    pub fn register_sneed_status_tool(
+        &self,
+        stakes: Arc<UserStakes>,
+        grid: Arc<Mutex<SovereignGrid>>,
+    ) {
+        self.register_sync(Arc::new(SneedStatusTool::new(stakes, grid)));
+        tracing::info!("Registered sneed_status tool");
     }
 
     /// Register job management tools.
     ///
-    /// Job tools allow the LLM to create, list, check status, and cancel jobs.
+    /// Job tools allow the LLM to create, list, check status, and cancel jobs,
+    /// to look up artifacts created by any job, and to trace results back to
+    /// the tool calls that produced them.
     /// These enable natural language job management without hardcoded intent parsing.
     pub fn register_job_tools(
         &self,
@@ -176,9 +216,45 @@ impl ToolRegistry {
         self.register_sync(Arc::new(create_job));
         self.register_sync(Arc::new(ListJobsTool::new(Arc::clone(&context_manager))));
         self.register_sync(Arc::new(JobStatusTool::new(Arc::clone(&context_manager))));
-        self.register_sync(Arc::new(CancelJobTool::new(context_manager)));
+        self.register_sync(Arc::new(CancelJobTool::new(Arc::clone(&context_manager))));
+        self.register_sync(Arc::new(ListArtifactsTool::new(Arc::clone(&context_manager))));
+        self.register_sync(Arc::new(ExplainActionsTool::new(context_manager)));
+
+        tracing::info!("Registered 6 job management tools");
+    }
 
-        tracing::info!("Registered 4 job management tools");
+    /// Register the job bundle export tool.
+    ///
+    /// Bundles include every tool's schema, so the tool reads them from this
+    /// registry.
+    pub fn register_bundle_tool(
+        self: &Arc<Self>,
+        context_manager: Arc<ContextManager>,
+        db: Option<Arc<dyn Database>>,
+    ) {
+        self.register_sync(Arc::new(ExportJobBundleTool::new(
+            context_manager,
+            Arc::clone(self),
+            db,
+        )));
+        tracing::info!("Registered export_job_bundle tool");
+    }
+
+    /// Register the conversation variable tool.
+    ///
+    /// Variables are persisted in conversation metadata, so this needs a database.
+    pub fn register_variable_tool(&self, store: Arc<dyn Database>) {
+        self.register_sync(Arc::new(ConversationVarsTool::new(store)));
+        tracing::info!("Registered conversation_vars tool");
+    }
+
+    /// Register the meeting brief tool.
+    ///
+    /// The tool calls the google-calendar, gmail, and google-drive tools
+    /// through this registry, so they can be installed before or after it.
+    pub fn register_meeting_brief_tool(self: &Arc<Self>) {
+        self.register_sync(Arc::new(MeetingBriefTool::new(Arc::clone(self))));
+        tracing::info!("Registered meeting_brief tool");
     }
 
     /// Register extension management tools (search, install, auth, activate, list, remove).
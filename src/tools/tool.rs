@@ -24,6 +24,10 @@ pub enum ToolError {
     #[error("Not authorized: {0}")]
     NotAuthorized(String),
 
+    /// A credential the tool needs hasn't been set up yet.
+    #[error("Authentication required: {0}")]
+    AuthRequired(String),
+
     #[error("Rate limited, retry after {0:?}")]
     RateLimited(Option<Duration>),
 
@@ -177,6 +181,32 @@ pub trait Tool: Send + Sync {
         false
     }
 
+    /// Text shown when asking the user to approve this call.
+    ///
+    /// Defaults to the tool description. Override to describe what this
+    /// particular call would do.
+    async fn approval_description(&self, _params: &serde_json::Value) -> String {
+        self.description().to_string()
+    }
+
+    /// Whether this call changes something outside the agent, such as
+    /// sending a message or appending rows.
+    ///
+    /// Mutating calls get an idempotency key from the worker: a retry of the
+    /// same call replays the recorded result instead of running it again.
+    fn is_mutating(&self, _params: &serde_json::Value) -> bool {
+        false
+    }
+
+    /// How long the result of this call may be reused within a job.
+    ///
+    /// Only read-only calls should return `Some`. A repeat of the same call
+    /// inside the window is served from the job's cache and marked as cached;
+    /// any other call to this tool drops its cached results.
+    fn cache_ttl(&self, _params: &serde_json::Value) -> Option<Duration> {
+        None
+    }
+
     /// Get the tool schema for LLM function calling.
     fn schema(&self) -> ToolSchema {
         ToolSchema {
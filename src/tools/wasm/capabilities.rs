@@ -5,7 +5,8 @@
 //!
 //! # Capability Types
 //!
-//! - **Workspace**: Read files from the agent's workspace
+//! - **Workspace**: Read files from the agent's workspace, and write under
+//!   declared prefixes
 //! - **HTTP**: Make HTTP requests to allowlisted endpoints
 //! - **ToolInvoke**: Call other tools via aliases
 //! - **Secrets**: Check if secrets exist (never read values)
@@ -26,12 +27,21 @@ use crate::secrets::CredentialMapping;
 pub struct Capabilities {
     /// Read files from workspace.
     pub workspace_read: Option<WorkspaceCapability>,
+    /// Write files to workspace.
+    pub workspace_write: Option<WorkspaceWriteCapability>,
     /// Make HTTP requests.
     pub http: Option<HttpCapability>,
     /// Invoke other tools.
     pub tool_invoke: Option<ToolInvokeCapability>,
     /// Check if secrets exist.
     pub secrets: Option<SecretsCapability>,
+    /// Actions that mutate external state and must run at most once per call.
+    pub mutating_actions: Vec<String>,
+    /// Values of the `action` parameter whose results are cached for
+    /// `cache_ttl` within a job.
+    pub cacheable_actions: Vec<String>,
+    /// How long a cached result stays fresh.
+    pub cache_ttl: Duration,
 }
 
 impl Capabilities {
@@ -49,6 +59,15 @@ impl Capabilities {
         self
     }
 
+    /// Enable workspace writes under the given prefixes.
+    pub fn with_workspace_write(mut self, prefixes: Vec<String>) -> Self {
+        self.workspace_write = Some(WorkspaceWriteCapability {
+            allowed_prefixes: prefixes,
+            writer: None,
+        });
+        self
+    }
+
     /// Enable HTTP requests with the given configuration.
     pub fn with_http(mut self, http: HttpCapability) -> Self {
         self.http = Some(http);
@@ -98,6 +117,29 @@ pub trait WorkspaceReader: Send + Sync {
     fn read(&self, path: &str) -> Option<String>;
 }
 
+/// Workspace write capability configuration.
+#[derive(Clone, Default)]
+pub struct WorkspaceWriteCapability {
+    /// Allowed path prefixes. Unlike reads, empty means nothing is writable.
+    pub allowed_prefixes: Vec<String>,
+    /// Function to actually write to workspace, injected by the runtime.
+    pub writer: Option<Arc<dyn WorkspaceWriter>>,
+}
+
+impl std::fmt::Debug for WorkspaceWriteCapability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkspaceWriteCapability")
+            .field("allowed_prefixes", &self.allowed_prefixes)
+            .field("writer", &self.writer.is_some())
+            .finish()
+    }
+}
+
+/// Trait for writing to workspace (allows mocking in tests).
+pub trait WorkspaceWriter: Send + Sync {
+    fn write(&self, path: &str, content: &str) -> Result<(), String>;
+}
+
 /// HTTP request capability configuration.
 #[derive(Debug, Clone)]
 pub struct HttpCapability {
@@ -23,7 +23,8 @@
 //!   },
 //!   "secrets": {
 //!     "allowed_names": ["slack_bot_token"]
-//!   }
+//!   },
+//!   "limits": { "memory_mb": 10, "fuel": 10000000, "timeout_secs": 30 }
 //! }
 //! ```
 
@@ -34,8 +35,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::secrets::{CredentialLocation, CredentialMapping};
 use crate::tools::wasm::{
-    Capabilities, EndpointPattern, HttpCapability, RateLimitConfig, SecretsCapability,
-    ToolInvokeCapability, WorkspaceCapability,
+    Capabilities, EndpointPattern, HttpCapability, RateLimitConfig, ResourceLimits,
+    SecretsCapability, ToolInvokeCapability, WorkspaceCapability, WorkspaceWriteCapability,
 };
 
 /// Root schema for a capabilities JSON file.
@@ -61,6 +62,18 @@ pub struct CapabilitiesFile {
     /// Used by `ironclaw config` to guide users through auth setup.
     #[serde(default)]
     pub auth: Option<AuthCapabilitySchema>,
+
+    /// Actions with side effects that must not be repeated on retry.
+    #[serde(default)]
+    pub idempotency: Option<IdempotencySchema>,
+
+    /// Read-only actions and how long their results stay fresh.
+    #[serde(default)]
+    pub cache: Option<CacheSchema>,
+
+    /// Per-invocation execution limits. Unset fields use the runtime defaults.
+    #[serde(default)]
+    pub limits: Option<LimitsSchema>,
 }
 
 impl CapabilitiesFile {
@@ -104,10 +117,97 @@ impl CapabilitiesFile {
                 allowed_prefixes: workspace.allowed_prefixes.clone(),
                 reader: None, // Injected at runtime
             });
+            if !workspace.write_prefixes.is_empty() {
+                caps.workspace_write = Some(WorkspaceWriteCapability {
+                    allowed_prefixes: workspace.write_prefixes.clone(),
+                    writer: None, // Injected at runtime
+                });
+            }
+        }
+
+        if let Some(idempotency) = &self.idempotency {
+            caps.mutating_actions = idempotency.mutating_actions.clone();
+        }
+
+        if let Some(cache) = &self.cache {
+            caps.cacheable_actions = cache.actions.clone();
+            caps.cache_ttl = Duration::from_secs(cache.ttl_secs);
         }
 
         caps
     }
+
+    /// Execution limits declared by the tool, if any.
+    pub fn to_resource_limits(&self) -> Option<ResourceLimits> {
+        self.limits.as_ref().map(LimitsSchema::to_resource_limits)
+    }
+}
+
+/// Execution limits for a single tool invocation.
+///
+/// A call that runs past `timeout_secs` is interrupted and reported as a
+/// timeout, so a hung tool can't hold a worker indefinitely.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LimitsSchema {
+    /// Maximum linear memory in megabytes.
+    #[serde(default)]
+    pub memory_mb: Option<u64>,
+
+    /// Fuel (instruction budget) per invocation.
+    #[serde(default)]
+    pub fuel: Option<u64>,
+
+    /// Wall-clock timeout per invocation in seconds.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+impl LimitsSchema {
+    /// Convert to runtime limits, filling unset fields with defaults.
+    pub fn to_resource_limits(&self) -> ResourceLimits {
+        let mut limits = ResourceLimits::default();
+        if let Some(mb) = self.memory_mb {
+            limits = limits.with_memory(mb * 1024 * 1024);
+        }
+        if let Some(fuel) = self.fuel {
+            limits = limits.with_fuel(fuel);
+        }
+        if let Some(secs) = self.timeout_secs {
+            limits = limits.with_timeout(Duration::from_secs(secs));
+        }
+        limits
+    }
+}
+
+/// Which of a tool's actions change something outside the agent.
+///
+/// The worker gives each call to one of these actions an idempotency key, so
+/// a retry after a transient failure replays the first result instead of
+/// sending the email or message twice.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IdempotencySchema {
+    /// Values of the `action` parameter that mutate (e.g., "send_message").
+    #[serde(default)]
+    pub mutating_actions: Vec<String>,
+}
+
+/// Which of a tool's actions only read, and how long their results stay fresh.
+///
+/// Repeating one of these calls with the same parameters inside `ttl_secs`
+/// returns the earlier result, flagged as cached, instead of calling the API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheSchema {
+    /// Values of the `action` parameter that are read-only (e.g., "get_presentation").
+    #[serde(default)]
+    pub actions: Vec<String>,
+
+    /// Freshness window in seconds.
+    #[serde(default = "default_cache_ttl_secs")]
+    pub ttl_secs: u64,
+}
+
+fn default_cache_ttl_secs() -> u64 {
+    60
 }
 
 /// HTTP capability schema.
@@ -318,12 +418,15 @@ pub struct ToolInvokeCapabilitySchema {
     pub rate_limit: Option<RateLimitSchema>,
 }
 
-/// Workspace read capability schema.
+/// Workspace capability schema.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct WorkspaceCapabilitySchema {
     /// Allowed path prefixes (e.g., ["context/", "daily/"]).
     #[serde(default)]
     pub allowed_prefixes: Vec<String>,
+    /// Prefixes the tool may also write under. Empty means read-only.
+    #[serde(default)]
+    pub write_prefixes: Vec<String>,
 }
 
 /// Authentication setup schema.
@@ -492,6 +595,9 @@ fn default_success_status() -> u16 {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use crate::tools::wasm::ResourceLimits;
     use crate::tools::wasm::capabilities_schema::{CapabilitiesFile, CredentialLocationSchema};
 
     #[test]
@@ -647,8 +753,68 @@ mod tests {
         }"#;
 
         let caps = CapabilitiesFile::from_json(json).unwrap();
+        assert!(caps.to_capabilities().workspace_write.is_none());
         let workspace = caps.workspace.unwrap();
         assert_eq!(workspace.allowed_prefixes, vec!["context/", "daily/"]);
+        assert!(workspace.write_prefixes.is_empty());
+    }
+
+    #[test]
+    fn test_parse_workspace_write_prefixes() {
+        let json = r#"{
+            "workspace": {
+                "allowed_prefixes": ["sheets/snapshots/"],
+                "write_prefixes": ["sheets/snapshots/"]
+            }
+        }"#;
+
+        let caps = CapabilitiesFile::from_json(json).unwrap().to_capabilities();
+        let write = caps.workspace_write.unwrap();
+        assert_eq!(write.allowed_prefixes, vec!["sheets/snapshots/"]);
+        assert!(write.writer.is_none());
+    }
+
+    #[test]
+    fn test_parse_idempotency() {
+        let json = r#"{
+            "idempotency": {
+                "mutating_actions": ["send_message", "create_draft"]
+            }
+        }"#;
+
+        let caps = CapabilitiesFile::from_json(json).unwrap().to_capabilities();
+        assert_eq!(caps.mutating_actions, vec!["send_message", "create_draft"]);
+
+        let caps = CapabilitiesFile::from_json("{}").unwrap().to_capabilities();
+        assert!(caps.mutating_actions.is_empty());
+    }
+
+    #[test]
+    fn test_parse_cache() {
+        let json = r#"{ "cache": { "actions": ["get_presentation"] } }"#;
+
+        let caps = CapabilitiesFile::from_json(json).unwrap().to_capabilities();
+        assert_eq!(caps.cacheable_actions, vec!["get_presentation"]);
+        assert_eq!(caps.cache_ttl, Duration::from_secs(60));
+
+        let caps = CapabilitiesFile::from_json("{}").unwrap().to_capabilities();
+        assert!(caps.cacheable_actions.is_empty());
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        let json = r#"{ "limits": { "fuel": 1000, "timeout_secs": 5 } }"#;
+
+        let limits = CapabilitiesFile::from_json(json)
+            .unwrap()
+            .to_resource_limits()
+            .unwrap();
+        assert_eq!(limits.fuel, 1000);
+        assert_eq!(limits.timeout, Duration::from_secs(5));
+        assert_eq!(limits.memory_bytes, ResourceLimits::default().memory_bytes);
+
+        let file = CapabilitiesFile::from_json("{}").unwrap();
+        assert!(file.to_resource_limits().is_none());
     }
 
     #[test]
@@ -2,6 +2,7 @@
 
 use std::fmt;
 
+use serde::Deserialize;
 use thiserror::Error;
 
 /// Errors that can occur during WASM tool execution.
@@ -88,10 +89,54 @@ impl From<std::io::Error> for WasmError {
 
 impl From<WasmError> for crate::tools::ToolError {
     fn from(e: WasmError) -> Self {
-        crate::tools::ToolError::Sandbox(e.to_string())
+        match e {
+            WasmError::ToolReturnedError(message) => match classify_tool_error(&message) {
+                Some(err) => err,
+                None => crate::tools::ToolError::Sandbox(
+                    WasmError::ToolReturnedError(message).to_string(),
+                ),
+            },
+            WasmError::Timeout(timeout) => crate::tools::ToolError::Timeout(timeout),
+            e => crate::tools::ToolError::Sandbox(e.to_string()),
+        }
     }
 }
 
+/// The fields of a structured tool error that decide how it's handled.
+///
+/// Tools may return a JSON object as their error string (see `response` in
+/// `tool.wit`); anything else stays an opaque sandbox error.
+#[derive(Debug, Deserialize)]
+struct ErrorEnvelope {
+    action: String,
+    #[serde(default)]
+    retryable: bool,
+    #[serde(default)]
+    retry_after_secs: Option<u64>,
+}
+
+/// Map a structured tool error onto the `ToolError` the worker acts on:
+/// retryable errors are retried, auth errors aren't, and a missing credential
+/// prompts the user to connect it. The envelope itself is kept as the message
+/// so the LLM sees every field.
+fn classify_tool_error(message: &str) -> Option<crate::tools::ToolError> {
+    use crate::tools::ToolError;
+
+    let envelope: ErrorEnvelope = serde_json::from_str(message).ok()?;
+    Some(if envelope.action == "authenticate" {
+        ToolError::AuthRequired(message.to_string())
+    } else if envelope.action == "reauthenticate" {
+        ToolError::NotAuthorized(message.to_string())
+    } else if envelope.retryable {
+        match envelope.retry_after_secs {
+            Some(secs) => ToolError::RateLimited(Some(std::time::Duration::from_secs(secs))),
+            None => ToolError::ExternalService(message.to_string()),
+        }
+    } else {
+        ToolError::ExecutionFailed(message.to_string())
+    })
+}
+
 /// Details about a trap that occurred during execution.
 #[derive(Debug, Clone)]
 pub struct TrapInfo {
@@ -195,4 +240,39 @@ mod tests {
             _ => panic!("Expected Sandbox variant"),
         }
     }
+
+    #[test]
+    fn test_structured_tool_error_conversion() {
+        use crate::tools::ToolError;
+        use std::time::Duration;
+
+        let convert = |message: &str| -> ToolError {
+            WasmError::ToolReturnedError(message.to_string()).into()
+        };
+
+        let rate_limited = r#"{"action": "retry", "retryable": true, "retry_after_secs": 7}"#;
+        assert!(matches!(
+            convert(rate_limited),
+            ToolError::RateLimited(Some(d)) if d == Duration::from_secs(7)
+        ));
+
+        let unavailable = r#"{"action": "retry", "retryable": true, "http_status": 503}"#;
+        match convert(unavailable) {
+            ToolError::ExternalService(msg) => assert_eq!(msg, unavailable),
+            other => panic!("Expected ExternalService, got {:?}", other),
+        }
+
+        let expired = r#"{"action": "reauthenticate", "retryable": false, "http_status": 401}"#;
+        assert!(matches!(convert(expired), ToolError::NotAuthorized(_)));
+
+        let missing = r#"{"action": "authenticate", "retryable": false}"#;
+        assert!(matches!(convert(missing), ToolError::AuthRequired(_)));
+
+        let not_found = r#"{"action": "fix_request", "retryable": false, "http_status": 404}"#;
+        assert!(matches!(convert(not_found), ToolError::ExecutionFailed(_)));
+
+        // Plain strings and unrelated JSON stay sandbox errors
+        assert!(matches!(convert("file not found"), ToolError::Sandbox(_)));
+        assert!(matches!(convert(r#"{"code": 1}"#), ToolError::Sandbox(_)));
+    }
 }
@@ -33,6 +33,12 @@ const MAX_LOG_ENTRIES: usize = 1000;
 /// Maximum bytes per log message.
 const MAX_LOG_MESSAGE_BYTES: usize = 4096;
 
+/// Maximum workspace writes per execution.
+const MAX_WORKSPACE_WRITES: u32 = 32;
+
+/// Maximum bytes per workspace write.
+const MAX_WORKSPACE_WRITE_BYTES: usize = 1024 * 1024;
+
 /// Log levels matching the WIT interface.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LogLevel {
@@ -82,6 +88,8 @@ pub struct HostState {
     http_request_count: u32,
     /// Tool invoke count for rate limiting within this execution.
     tool_invoke_count: u32,
+    /// Workspace write count for rate limiting within this execution.
+    workspace_write_count: u32,
 }
 
 impl std::fmt::Debug for HostState {
@@ -93,6 +101,7 @@ impl std::fmt::Debug for HostState {
             .field("user_id", &self.user_id)
             .field("http_request_count", &self.http_request_count)
             .field("tool_invoke_count", &self.tool_invoke_count)
+            .field("workspace_write_count", &self.workspace_write_count)
             .finish()
     }
 }
@@ -108,6 +117,7 @@ impl HostState {
             user_id: None,
             http_request_count: 0,
             tool_invoke_count: 0,
+            workspace_write_count: 0,
         }
     }
 
@@ -121,6 +131,7 @@ impl HostState {
             user_id: Some(user_id.into()),
             http_request_count: 0,
             tool_invoke_count: 0,
+            workspace_write_count: 0,
         }
     }
 
@@ -223,6 +234,49 @@ impl HostState {
         }
     }
 
+    /// Write to workspace if capability granted and the path is under a
+    /// declared write prefix.
+    pub fn workspace_write(&mut self, path: &str, content: &str) -> Result<(), String> {
+        let capability = self
+            .capabilities
+            .workspace_write
+            .as_ref()
+            .ok_or_else(|| "workspace write capability not granted".to_string())?;
+
+        validate_workspace_path(path).map_err(|e| e.to_string())?;
+
+        if !capability
+            .allowed_prefixes
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+        {
+            return Err(format!(
+                "workspace write denied: '{}' not in allowed prefixes",
+                path
+            ));
+        }
+        if content.len() > MAX_WORKSPACE_WRITE_BYTES {
+            return Err(format!(
+                "workspace write too large: {} bytes (max {})",
+                content.len(),
+                MAX_WORKSPACE_WRITE_BYTES
+            ));
+        }
+        if self.workspace_write_count >= MAX_WORKSPACE_WRITES {
+            return Err(format!(
+                "workspace write limit reached ({} per execution)",
+                MAX_WORKSPACE_WRITES
+            ));
+        }
+
+        let writer = capability
+            .writer
+            .clone()
+            .ok_or_else(|| "workspace writes are not available in this runtime".to_string())?;
+        self.workspace_write_count += 1;
+        writer.write(path, content)
+    }
+
     /// Get collected logs after execution.
     pub fn take_logs(&mut self) -> Vec<LogEntry> {
         std::mem::take(&mut self.logs)
@@ -383,9 +437,11 @@ mod tests {
 
     use crate::tools::wasm::capabilities::{
         Capabilities, SecretsCapability, WorkspaceCapability, WorkspaceReader,
+        WorkspaceWriteCapability, WorkspaceWriter,
     };
     use crate::tools::wasm::host::{
-        HostState, LogLevel, MAX_LOG_ENTRIES, MAX_LOG_MESSAGE_BYTES, validate_workspace_path,
+        HostState, LogLevel, MAX_LOG_ENTRIES, MAX_LOG_MESSAGE_BYTES, MAX_WORKSPACE_WRITES,
+        validate_workspace_path,
     };
 
     struct MockReader {
@@ -451,6 +507,69 @@ mod tests {
         assert!(now > 1577836800000); // Jan 1, 2020
     }
 
+    #[derive(Default)]
+    struct MockWriter {
+        written: std::sync::Mutex<Vec<(String, String)>>,
+    }
+
+    impl WorkspaceWriter for MockWriter {
+        fn write(&self, path: &str, content: &str) -> Result<(), String> {
+            self.written
+                .lock()
+                .unwrap()
+                .push((path.to_string(), content.to_string()));
+            Ok(())
+        }
+    }
+
+    fn writable(prefix: &str, writer: Arc<MockWriter>) -> HostState {
+        HostState::new(Capabilities {
+            workspace_write: Some(WorkspaceWriteCapability {
+                allowed_prefixes: vec![prefix.to_string()],
+                writer: Some(writer),
+            }),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_workspace_write_no_capability() {
+        let mut state = HostState::minimal();
+        assert!(state.workspace_write("context/test.md", "x").is_err());
+    }
+
+    #[test]
+    fn test_workspace_write_prefix_and_path_checks() {
+        let writer = Arc::new(MockWriter::default());
+        let mut state = writable("sheets/snapshots/", writer.clone());
+
+        state
+            .workspace_write("sheets/snapshots/a.json", "{}")
+            .unwrap();
+        assert!(state.workspace_write("context/a.md", "x").is_err());
+        assert!(
+            state
+                .workspace_write("sheets/snapshots/../../secrets", "x")
+                .is_err()
+        );
+        assert_eq!(writer.written.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_workspace_write_rate_limited() {
+        let writer = Arc::new(MockWriter::default());
+        let mut state = writable("out/", writer.clone());
+
+        for i in 0..MAX_WORKSPACE_WRITES {
+            state.workspace_write(&format!("out/{}", i), "x").unwrap();
+        }
+        assert!(state.workspace_write("out/extra", "x").is_err());
+        assert_eq!(
+            writer.written.lock().unwrap().len(),
+            MAX_WORKSPACE_WRITES as usize
+        );
+    }
+
     #[test]
     fn test_workspace_read_no_capability() {
         let state = HostState::minimal();
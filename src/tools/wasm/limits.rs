@@ -15,6 +15,10 @@ pub const DEFAULT_FUEL_LIMIT: u64 = 10_000_000;
 /// Default execution timeout: 60 seconds.
 pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// How often the runtime advances the engine epoch. Wall-clock timeouts are
+/// enforced to this granularity.
+pub const EPOCH_TICK: Duration = Duration::from_millis(100);
+
 /// Resource limits for a single WASM execution.
 #[derive(Debug, Clone)]
 pub struct ResourceLimits {
@@ -54,6 +58,12 @@ impl ResourceLimits {
         self.timeout = timeout;
         self
     }
+
+    /// Epoch ticks before an execution is interrupted, rounded up.
+    pub fn epoch_deadline(&self) -> u64 {
+        let ticks = self.timeout.as_millis().div_ceil(EPOCH_TICK.as_millis());
+        u64::try_from(ticks).unwrap_or(u64::MAX).max(1)
+    }
 }
 
 /// Wasmtime ResourceLimiter implementation for enforcing memory limits.
@@ -229,6 +239,15 @@ mod tests {
         assert_eq!(limits.timeout, std::time::Duration::from_secs(30));
     }
 
+    #[test]
+    fn test_epoch_deadline_rounds_up() {
+        let limits = ResourceLimits::default().with_timeout(std::time::Duration::from_millis(250));
+        assert_eq!(limits.epoch_deadline(), 3);
+
+        let limits = ResourceLimits::default().with_timeout(std::time::Duration::ZERO);
+        assert_eq!(limits.epoch_deadline(), 1);
+    }
+
     #[test]
     fn test_resource_limiter_allows_growth_within_limit() {
         let mut limiter = WasmResourceLimiter::new(10 * 1024 * 1024);
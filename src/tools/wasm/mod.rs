@@ -99,7 +99,8 @@ pub use wrapper::WasmToolWrapper;
 // Capabilities (V2)
 pub use capabilities::{
     Capabilities, EndpointPattern, HttpCapability, RateLimitConfig, SecretsCapability,
-    ToolInvokeCapability, WorkspaceCapability, WorkspaceReader,
+    ToolInvokeCapability, WorkspaceCapability, WorkspaceReader, WorkspaceWriteCapability,
+    WorkspaceWriter,
 };
 
 // Security components (V2)
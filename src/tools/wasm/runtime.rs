@@ -12,7 +12,7 @@ use tokio::sync::RwLock;
 use wasmtime::{Config, Engine, OptLevel};
 
 use crate::tools::wasm::error::WasmError;
-use crate::tools::wasm::limits::{FuelConfig, ResourceLimits};
+use crate::tools::wasm::limits::{EPOCH_TICK, FuelConfig, ResourceLimits};
 
 /// Configuration for the WASM runtime.
 #[derive(Debug, Clone)]
@@ -123,6 +123,24 @@ impl WasmToolRuntime {
             WasmError::EngineCreationFailed(format!("Failed to create Wasmtime engine: {}", e))
         })?;
 
+        // Advance the epoch so executions hit their deadline; the thread
+        // exits once the engine is dropped.
+        let weak = engine.weak();
+        std::thread::Builder::new()
+            .name("wasm-epoch".to_string())
+            .spawn(move || {
+                loop {
+                    std::thread::sleep(EPOCH_TICK);
+                    match weak.upgrade() {
+                        Some(engine) => engine.increment_epoch(),
+                        None => break,
+                    }
+                }
+            })
+            .map_err(|e| {
+                WasmError::EngineCreationFailed(format!("Failed to start epoch ticker: {}", e))
+            })?;
+
         Ok(Self {
             engine,
             config,
@@ -4,6 +4,7 @@
 //! isolation and deterministic behavior.
 
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
@@ -89,6 +90,7 @@ impl WasmToolWrapper {
         &self,
         params: serde_json::Value,
         context_json: Option<String>,
+        api_calls: &AtomicU32,
     ) -> Result<(String, Vec<crate::tools::wasm::host::LogEntry>), WasmError> {
         let engine = self.runtime.engine();
         let limits = &self.prepared.limits;
@@ -104,9 +106,10 @@ impl WasmToolWrapper {
                 .map_err(|e| WasmError::ConfigError(format!("Failed to set fuel: {}", e)))?;
         }
 
-        // Configure epoch deadline for timeout backup
+        // Interrupt the guest once its wall-clock budget is spent, so a hung
+        // tool doesn't keep a blocking thread busy after the caller gives up
         store.epoch_deadline_trap();
-        store.set_epoch_deadline(1);
+        store.set_epoch_deadline(limits.epoch_deadline());
 
         // Set up resource limiter
         store.limiter(|data| &mut data.limiter);
@@ -151,19 +154,25 @@ impl WasmToolWrapper {
 
         // Call the function
         let mut results = vec![Val::Bool(false)]; // Placeholder for response
-        execute_func
-            .call(&mut store, &[request], &mut results)
-            .map_err(|e| {
-                // Check for specific trap types
-                let error_str = e.to_string();
-                if error_str.contains("out of fuel") {
-                    WasmError::FuelExhausted { limit: limits.fuel }
-                } else if error_str.contains("unreachable") {
-                    WasmError::Trapped("unreachable code executed".to_string())
-                } else {
-                    WasmError::Trapped(error_str)
-                }
-            })?;
+        let call = execute_func.call(&mut store, &[request], &mut results);
+        // Requests count even when the call fails, they were still made
+        api_calls.store(
+            store.data().host_state.http_request_count(),
+            Ordering::Relaxed,
+        );
+        call.map_err(|e| {
+            // Check for specific trap types
+            let error_str = e.to_string();
+            if let Some(wasmtime::Trap::Interrupt) = e.downcast_ref::<wasmtime::Trap>() {
+                WasmError::Timeout(limits.timeout)
+            } else if error_str.contains("out of fuel") {
+                WasmError::FuelExhausted { limit: limits.fuel }
+            } else if error_str.contains("unreachable") {
+                WasmError::Trapped("unreachable code executed".to_string())
+            } else {
+                WasmError::Trapped(error_str)
+            }
+        })?;
 
         // Post-call completion (cleanup)
         execute_func
@@ -245,6 +254,22 @@ impl WasmToolWrapper {
                 WasmError::ConfigError(format!("Failed to add workspace-read function: {}", e))
             })?;
 
+        // host.workspace-write(path: string, content: string) -> result<_, string>
+        linker
+            .root()
+            .func_wrap(
+                "workspace-write",
+                |mut ctx: wasmtime::StoreContextMut<'_, StoreData>,
+                 (path, content): (String, String)|
+                 -> anyhow::Result<(Result<(), String>,)> {
+                    let result = ctx.data_mut().host_state.workspace_write(&path, &content);
+                    Ok((result,))
+                },
+            )
+            .map_err(|e| {
+                WasmError::ConfigError(format!("Failed to add workspace-write function: {}", e))
+            })?;
+
         Ok(())
     }
 }
@@ -315,6 +340,8 @@ impl Tool for WasmToolWrapper {
         let capabilities = self.capabilities.clone();
         let description = self.description.clone();
         let schema = self.schema.clone();
+        let api_calls = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&api_calls);
 
         // Execute in blocking task with timeout
         let result = tokio::time::timeout(timeout, async move {
@@ -326,13 +353,16 @@ impl Tool for WasmToolWrapper {
                 schema,
             };
 
-            tokio::task::spawn_blocking(move || wrapper.execute_sync(params, context_json))
-                .await
-                .map_err(|e| WasmError::ExecutionPanicked(e.to_string()))?
+            tokio::task::spawn_blocking(move || {
+                wrapper.execute_sync(params, context_json, &counter)
+            })
+            .await
+            .map_err(|e| WasmError::ExecutionPanicked(e.to_string()))?
         })
         .await;
 
         let duration = start.elapsed();
+        crate::observability::record_api_calls(api_calls.load(Ordering::Relaxed));
 
         match result {
             Ok(Ok((result_json, logs))) => {
@@ -367,6 +397,27 @@ impl Tool for WasmToolWrapper {
         // Use the timeout as a conservative estimate
         Some(self.prepared.limits.timeout)
     }
+
+    fn is_mutating(&self, params: &serde_json::Value) -> bool {
+        params
+            .get("action")
+            .and_then(|a| a.as_str())
+            .is_some_and(|action| {
+                self.capabilities
+                    .mutating_actions
+                    .iter()
+                    .any(|m| m == action)
+            })
+    }
+
+    fn cache_ttl(&self, params: &serde_json::Value) -> Option<Duration> {
+        let action = params.get("action").and_then(|a| a.as_str())?;
+        self.capabilities
+            .cacheable_actions
+            .iter()
+            .any(|c| c == action)
+            .then_some(self.capabilities.cache_ttl)
+    }
 }
 
 impl std::fmt::Debug for WasmToolWrapper {
@@ -404,5 +455,6 @@ mod tests {
         assert!(caps.http.is_none());
         assert!(caps.tool_invoke.is_none());
         assert!(caps.secrets.is_none());
+        assert!(caps.mutating_actions.is_empty());
     }
 }
@@ -0,0 +1,138 @@
+//! Google Cloud Speech-to-Text transcription.
+//!
+//! Uses synchronous recognition, which accepts up to a minute of audio.
+//! That covers typical voice notes; longer ones fail and stay attached.
+
+use async_trait::async_trait;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use reqwest::Client;
+use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::transcription::{SpeechToText, TranscriptionError};
+
+const RECOGNIZE_URL: &str = "https://speech.googleapis.com/v1/speech:recognize";
+
+/// Google Cloud Speech-to-Text backend.
+pub struct GoogleSpeechToText {
+    client: Client,
+    api_key: SecretString,
+    /// BCP-47 language code.
+    language: String,
+}
+
+impl GoogleSpeechToText {
+    /// `language` defaults to "en-US".
+    pub fn new(api_key: SecretString, language: Option<String>) -> Self {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(120))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+
+        Self {
+            client,
+            api_key,
+            language: language.unwrap_or_else(|| "en-US".to_string()),
+        }
+    }
+}
+
+/// Recognition config for an audio format: the encoding and sample rate to
+/// declare, or `None` for formats whose header carries them.
+fn audio_encoding(mime_type: &str) -> Result<Option<(&'static str, u32)>, TranscriptionError> {
+    let essence = mime_type.split(';').next().unwrap_or_default().trim();
+    match essence {
+        // Opus voice notes (Telegram, WhatsApp) are recorded at 48 kHz
+        "audio/ogg" | "audio/opus" => Ok(Some(("OGG_OPUS", 48_000))),
+        "audio/webm" => Ok(Some(("WEBM_OPUS", 48_000))),
+        "audio/flac" | "audio/x-flac" | "audio/wav" | "audio/x-wav" | "audio/wave" => Ok(None),
+        other => Err(TranscriptionError::UnsupportedFormat(other.to_string())),
+    }
+}
+
+#[derive(Deserialize)]
+struct RecognizeResponse {
+    #[serde(default)]
+    results: Vec<RecognitionResult>,
+}
+
+#[derive(Deserialize)]
+struct RecognitionResult {
+    #[serde(default)]
+    alternatives: Vec<Alternative>,
+}
+
+#[derive(Deserialize)]
+struct Alternative {
+    #[serde(default)]
+    transcript: String,
+}
+
+#[async_trait]
+impl SpeechToText for GoogleSpeechToText {
+    fn name(&self) -> &str {
+        "google"
+    }
+
+    async fn transcribe(
+        &self,
+        audio: Vec<u8>,
+        _file_name: &str,
+        mime_type: &str,
+    ) -> Result<String, TranscriptionError> {
+        let mut config = json!({ "languageCode": self.language });
+        if let Some((encoding, sample_rate)) = audio_encoding(mime_type)? {
+            config["encoding"] = json!(encoding);
+            config["sampleRateHertz"] = json!(sample_rate);
+        }
+        let body = json!({
+            "config": config,
+            "audio": { "content": STANDARD.encode(&audio) },
+        });
+
+        let response = self
+            .client
+            .post(RECOGNIZE_URL)
+            .query(&[("key", self.api_key.expose_secret())])
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| TranscriptionError::RequestFailed(e.to_string()))?;
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(TranscriptionError::Api {
+                status: status.as_u16(),
+                body,
+            });
+        }
+
+        let parsed: RecognizeResponse = serde_json::from_str(&body)
+            .map_err(|e| TranscriptionError::InvalidResponse(e.to_string()))?;
+        // Each result covers a consecutive stretch of the audio
+        Ok(parsed
+            .results
+            .iter()
+            .filter_map(|r| r.alternatives.first())
+            .map(|a| a.transcript.trim())
+            .collect::<Vec<_>>()
+            .join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_encoding() {
+        assert_eq!(
+            audio_encoding("audio/ogg; codecs=opus").unwrap(),
+            Some(("OGG_OPUS", 48_000))
+        );
+        assert_eq!(audio_encoding("audio/wav").unwrap(), None);
+        assert!(audio_encoding("audio/mpeg").is_err());
+    }
+}
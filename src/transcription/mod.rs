@@ -0,0 +1,303 @@
+//! Speech-to-text for voice notes.
+//!
+//! Channels deliver voice notes as audio attachments. Before a message is
+//! routed, the agent runs its voice notes through the configured
+//! [`SpeechToText`] backend and replaces them with their transcripts, so a
+//! voice note is handled exactly like typed text.
+//!
+//! Channels that know more about their audio describe it in the message
+//! metadata:
+//!
+//! ```json
+//! {"audio": [{"attachment_id": "<id>", "voice": true, "duration": 4}]}
+//! ```
+//!
+//! When a message has this, only attachments marked `voice` are transcribed,
+//! which leaves music and other audio files alone. Otherwise every `audio/*`
+//! attachment is. Voice notes longer than the configured limit are skipped.
+
+mod google;
+mod whisper;
+
+pub use google::GoogleSpeechToText;
+pub use whisper::WhisperSpeechToText;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::channels::{Attachment, AttachmentError, AttachmentStore, IncomingMessage};
+use crate::config::{TranscriptionConfig, TranscriptionProviderType};
+
+/// Metadata key describing a message's audio attachments.
+pub const AUDIO_METADATA_KEY: &str = "audio";
+
+/// Errors from transcribing audio.
+#[derive(Debug, thiserror::Error)]
+pub enum TranscriptionError {
+    #[error("Transcription is not configured: {0}")]
+    NotConfigured(String),
+
+    #[error("Unsupported audio format: {0}")]
+    UnsupportedFormat(String),
+
+    #[error("Transcription request failed: {0}")]
+    RequestFailed(String),
+
+    #[error("Transcription API returned HTTP {status}: {body}")]
+    Api { status: u16, body: String },
+
+    #[error("Invalid transcription response: {0}")]
+    InvalidResponse(String),
+
+    #[error("Failed to read audio: {0}")]
+    Attachment(#[from] AttachmentError),
+}
+
+/// A speech-to-text backend.
+#[async_trait]
+pub trait SpeechToText: Send + Sync {
+    /// Name used in logs.
+    fn name(&self) -> &str;
+
+    /// Transcribe an audio file.
+    async fn transcribe(
+        &self,
+        audio: Vec<u8>,
+        file_name: &str,
+        mime_type: &str,
+    ) -> Result<String, TranscriptionError>;
+}
+
+/// A channel's description of one audio attachment.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AudioInfo {
+    pub attachment_id: String,
+    /// Whether this is speech to transcribe, as opposed to e.g. music.
+    #[serde(default)]
+    pub voice: bool,
+    /// Length in seconds, if known.
+    #[serde(default)]
+    pub duration: Option<u64>,
+}
+
+/// Replaces voice notes in incoming messages with their transcripts.
+pub struct Transcriber {
+    backend: Arc<dyn SpeechToText>,
+    attachments: AttachmentStore,
+    max_duration_secs: u64,
+}
+
+impl Transcriber {
+    pub fn new(backend: Arc<dyn SpeechToText>, attachments: AttachmentStore) -> Self {
+        Self {
+            backend,
+            attachments,
+            max_duration_secs: TranscriptionConfig::default().max_duration_secs,
+        }
+    }
+
+    /// Build the configured backend, or `None` if transcription is off.
+    pub fn from_config(
+        config: &TranscriptionConfig,
+        attachments: AttachmentStore,
+    ) -> Result<Option<Self>, TranscriptionError> {
+        let Some(provider) = config.provider else {
+            return Ok(None);
+        };
+        let api_key = config.api_key.clone().ok_or_else(|| {
+            TranscriptionError::NotConfigured(
+                "set TRANSCRIPTION_API_KEY or the provider's API key".to_string(),
+            )
+        })?;
+
+        let backend: Arc<dyn SpeechToText> = match provider {
+            TranscriptionProviderType::Whisper => Arc::new(WhisperSpeechToText::new(
+                &config.base_url,
+                api_key,
+                &config.model,
+                config.language.clone(),
+            )),
+            TranscriptionProviderType::Google => {
+                Arc::new(GoogleSpeechToText::new(api_key, config.language.clone()))
+            }
+        };
+        Ok(Some(
+            Self::new(backend, attachments).with_max_duration(config.max_duration_secs),
+        ))
+    }
+
+    /// Skip voice notes longer than `secs` seconds.
+    pub fn with_max_duration(mut self, secs: u64) -> Self {
+        self.max_duration_secs = secs;
+        self
+    }
+
+    /// The message with its voice notes transcribed into the content, or
+    /// `None` if it has none. Voice notes that fail to transcribe stay
+    /// attached so the agent can still tell the user about them.
+    pub async fn transcribe_message(&self, message: &IncomingMessage) -> Option<IncomingMessage> {
+        let voice_notes = voice_notes(message, self.max_duration_secs);
+        if voice_notes.is_empty() {
+            return None;
+        }
+
+        let mut transcripts = Vec::new();
+        let mut transcribed = Vec::new();
+        for attachment in voice_notes {
+            match self.transcribe_attachment(attachment).await {
+                Ok(text) => {
+                    tracing::debug!(
+                        "Transcribed voice note {} from {} on {} ({} chars)",
+                        attachment.id,
+                        message.user_id,
+                        message.channel,
+                        text.len()
+                    );
+                    transcripts.push(text);
+                    transcribed.push(attachment.id.clone());
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to transcribe voice note {} with {}: {}",
+                    attachment.id,
+                    self.backend.name(),
+                    e
+                ),
+            }
+        }
+        if transcribed.is_empty() {
+            return None;
+        }
+
+        let mut message = message.clone();
+        message.attachments.retain(|a| !transcribed.contains(&a.id));
+        let transcript = transcripts.join("\n");
+        message.content = if message.content.trim().is_empty() {
+            transcript
+        } else {
+            format!("{}\n{}", message.content, transcript)
+        };
+        Some(message)
+    }
+
+    async fn transcribe_attachment(
+        &self,
+        attachment: &Attachment,
+    ) -> Result<String, TranscriptionError> {
+        let store = self.attachments.clone();
+        let id = attachment.id.clone();
+        let audio = tokio::task::spawn_blocking(move || store.get(&id))
+            .await
+            .map_err(|e| TranscriptionError::RequestFailed(e.to_string()))??;
+        let text = self
+            .backend
+            .transcribe(audio, &attachment.name, &attachment.mime_type)
+            .await?;
+        Ok(text.trim().to_string())
+    }
+}
+
+/// Attachments of `message` to transcribe.
+fn voice_notes(message: &IncomingMessage, max_duration_secs: u64) -> Vec<&Attachment> {
+    let audio: Option<Vec<AudioInfo>> = message
+        .metadata
+        .get(AUDIO_METADATA_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok());
+
+    message
+        .attachments
+        .iter()
+        .filter(|attachment| match &audio {
+            Some(audio) => audio.iter().any(|info| {
+                info.attachment_id == attachment.id
+                    && info.voice
+                    && info.duration.is_none_or(|d| d <= max_duration_secs)
+            }),
+            None => attachment.mime_type.starts_with("audio/"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    struct FakeSpeechToText {
+        calls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl SpeechToText for FakeSpeechToText {
+        fn name(&self) -> &str {
+            "fake"
+        }
+
+        async fn transcribe(
+            &self,
+            audio: Vec<u8>,
+            file_name: &str,
+            _mime_type: &str,
+        ) -> Result<String, TranscriptionError> {
+            self.calls.lock().unwrap().push(file_name.to_string());
+            Ok(format!(" {} ", String::from_utf8_lossy(&audio)))
+        }
+    }
+
+    fn attachment(store: &AttachmentStore, name: &str, mime_type: &str) -> Attachment {
+        store.put(name, mime_type, name.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_voice_notes_selection() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AttachmentStore::new(dir.path());
+        let voice = attachment(&store, "voice.oga", "audio/ogg");
+        let song = attachment(&store, "song.mp3", "audio/mpeg");
+        let photo = attachment(&store, "photo.jpg", "image/jpeg");
+
+        // Without channel metadata, every audio attachment counts
+        let message = IncomingMessage::new("telegram", "42", "").with_attachments(vec![
+            voice.clone(),
+            song.clone(),
+            photo,
+        ]);
+        assert_eq!(voice_notes(&message, 300).len(), 2);
+
+        // With it, only voice notes within the length limit
+        let message = message.with_metadata(serde_json::json!({"audio": [
+            {"attachment_id": voice.id, "voice": true, "duration": 4},
+            {"attachment_id": song.id, "voice": false, "duration": 180}
+        ]}));
+        let notes = voice_notes(&message, 300);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].name, "voice.oga");
+        assert!(voice_notes(&message, 3).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AttachmentStore::new(dir.path());
+        let voice = attachment(&store, "hello there", "audio/ogg");
+        let photo = attachment(&store, "photo.jpg", "image/jpeg");
+
+        let backend = Arc::new(FakeSpeechToText {
+            calls: Mutex::new(Vec::new()),
+        });
+        let transcriber = Transcriber::new(backend.clone(), store);
+
+        let message =
+            IncomingMessage::new("telegram", "42", "").with_attachments(vec![voice, photo.clone()]);
+        let transcribed = transcriber.transcribe_message(&message).await.unwrap();
+        assert_eq!(transcribed.content, "hello there");
+        assert_eq!(transcribed.attachments, vec![photo.clone()]);
+        assert_eq!(backend.calls.lock().unwrap().len(), 1);
+
+        // Nothing to transcribe
+        let message = IncomingMessage::new("telegram", "42", "hi").with_attachments(vec![photo]);
+        assert!(transcriber.transcribe_message(&message).await.is_none());
+    }
+}
@@ -0,0 +1,98 @@
+//! OpenAI Whisper transcription.
+//!
+//! Works with any API that implements OpenAI's
+//! `POST /audio/transcriptions` endpoint.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use reqwest::multipart::{Form, Part};
+use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+
+use crate::transcription::{SpeechToText, TranscriptionError};
+
+/// Whisper speech-to-text backend.
+pub struct WhisperSpeechToText {
+    client: Client,
+    base_url: String,
+    api_key: SecretString,
+    model: String,
+    /// ISO-639-1 language code; detected by the API when unset.
+    language: Option<String>,
+}
+
+impl WhisperSpeechToText {
+    /// `language` may be a BCP-47 tag; only the language subtag is sent.
+    pub fn new(
+        base_url: &str,
+        api_key: SecretString,
+        model: &str,
+        language: Option<String>,
+    ) -> Self {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(120))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model: model.to_string(),
+            language: language.map(|l| l.split(['-', '_']).next().unwrap_or(&l).to_lowercase()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+#[async_trait]
+impl SpeechToText for WhisperSpeechToText {
+    fn name(&self) -> &str {
+        "whisper"
+    }
+
+    async fn transcribe(
+        &self,
+        audio: Vec<u8>,
+        file_name: &str,
+        mime_type: &str,
+    ) -> Result<String, TranscriptionError> {
+        // The API infers the format from the file extension
+        let file = Part::bytes(audio)
+            .file_name(file_name.to_string())
+            .mime_str(mime_type)
+            .map_err(|e| TranscriptionError::UnsupportedFormat(e.to_string()))?;
+        let mut form = Form::new()
+            .text("model", self.model.clone())
+            .part("file", file);
+        if let Some(ref language) = self.language {
+            form = form.text("language", language.clone());
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/audio/transcriptions", self.base_url))
+            .bearer_auth(self.api_key.expose_secret())
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| TranscriptionError::RequestFailed(e.to_string()))?;
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(TranscriptionError::Api {
+                status: status.as_u16(),
+                body,
+            });
+        }
+
+        let parsed: TranscriptionResponse = serde_json::from_str(&body)
+            .map_err(|e| TranscriptionError::InvalidResponse(e.to_string()))?;
+        Ok(parsed.text)
+    }
+}
@@ -0,0 +1,314 @@
+//! Git-backed version history for workspace memory.
+//!
+//! Memory writes are mirrored into an embedded bare git repository (via
+//! gitoxide) so earlier versions of a file can be listed and restored. Each
+//! user's files live under a top-level directory named after their user ID,
+//! and commit messages record the job and turn that made the change.
+//!
+//! The database stays the source of truth; the repository only holds history.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use gix::object::tree::EntryKind;
+use gix::refs::transaction::PreviousValue;
+use serde::Serialize;
+
+use crate::error::WorkspaceError;
+
+/// Branch every memory commit is recorded on.
+const BRANCH: &str = "refs/heads/main";
+
+/// Hex length of the version IDs handed to the agent.
+const SHORT_ID_LEN: usize = 10;
+
+/// One recorded version of a memory file.
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryVersion {
+    /// Abbreviated commit ID, accepted by [`MemoryHistory::content_at`].
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    /// First line of the commit message.
+    pub summary: String,
+    /// The file was deleted in this version.
+    pub deleted: bool,
+}
+
+/// Embedded git repository holding every version of every memory file.
+pub struct MemoryHistory {
+    repo: gix::ThreadSafeRepository,
+    /// Serializes commits so concurrent writes don't race on the branch tip.
+    lock: Mutex<()>,
+}
+
+impl MemoryHistory {
+    /// Open the repository at `dir`, creating it on first use.
+    pub fn open(dir: &Path) -> Result<Self, WorkspaceError> {
+        let repo = if dir.join("HEAD").exists() {
+            gix::open(dir).map_err(history_err)?
+        } else {
+            std::fs::create_dir_all(dir).map_err(history_err)?;
+            gix::init_bare(dir).map_err(history_err)?
+        };
+        Ok(Self {
+            repo: repo.into_sync(),
+            lock: Mutex::new(()),
+        })
+    }
+
+    /// Commit new content for `user_id`'s files in one commit.
+    ///
+    /// Each change is a path and its new content, or `None` for a deletion.
+    /// Returns the new version ID, or `None` if nothing actually changed.
+    pub fn record(
+        &self,
+        user_id: &str,
+        changes: &[(&str, Option<&str>)],
+        message: &str,
+    ) -> Result<Option<String>, WorkspaceError> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let repo = self.repo.to_thread_local();
+
+        let parent = tip(&repo)?;
+        let base_tree = match parent {
+            Some(id) => repo
+                .find_commit(id)
+                .map_err(history_err)?
+                .tree_id()
+                .map_err(history_err)?
+                .detach(),
+            None => gix::ObjectId::empty_tree(repo.object_hash()),
+        };
+
+        let mut editor = repo.edit_tree(base_tree).map_err(history_err)?;
+        for (path, content) in changes {
+            let key = repo_path(user_id, path);
+            match content {
+                Some(content) => {
+                    let blob = repo.write_blob(content.as_bytes()).map_err(history_err)?;
+                    editor
+                        .upsert(key.as_str(), EntryKind::Blob, blob.detach())
+                        .map_err(history_err)?;
+                }
+                None => {
+                    editor.remove(key.as_str()).map_err(history_err)?;
+                }
+            }
+        }
+        let tree = editor.write().map_err(history_err)?.detach();
+        if tree == base_tree {
+            return Ok(None);
+        }
+
+        let signature = gix::actor::Signature {
+            name: "ironclaw".into(),
+            email: "ironclaw@localhost".into(),
+            time: gix::date::Time::now_utc(),
+        };
+        let commit = gix::objs::Commit {
+            tree,
+            parents: parent.into_iter().collect(),
+            author: signature.clone(),
+            committer: signature,
+            encoding: None,
+            message: message.into(),
+            extra_headers: Vec::new(),
+        };
+        let id = repo.write_object(&commit).map_err(history_err)?.detach();
+        repo.reference(BRANCH, id, PreviousValue::Any, "memory write")
+            .map_err(history_err)?;
+
+        Ok(Some(id.to_hex_with_len(SHORT_ID_LEN).to_string()))
+    }
+
+    /// Versions of `path` that changed it, newest first.
+    pub fn log(
+        &self,
+        user_id: &str,
+        path: &str,
+        limit: usize,
+    ) -> Result<Vec<MemoryVersion>, WorkspaceError> {
+        let repo = self.repo.to_thread_local();
+        let Some(tip) = tip(&repo)? else {
+            return Ok(Vec::new());
+        };
+        let key = repo_path(user_id, path);
+
+        let mut versions = Vec::new();
+        for info in repo.rev_walk([tip]).all().map_err(history_err)? {
+            let info = info.map_err(history_err)?;
+            let commit = info.object().map_err(history_err)?;
+            let current = blob_at(&commit, &key)?;
+            let previous = match info.parent_ids().next() {
+                Some(parent) => blob_at(
+                    &repo.find_commit(parent.detach()).map_err(history_err)?,
+                    &key,
+                )?,
+                None => None,
+            };
+            if current == previous {
+                continue;
+            }
+
+            let time = commit.time().map_err(history_err)?;
+            versions.push(MemoryVersion {
+                id: info.id.to_hex_with_len(SHORT_ID_LEN).to_string(),
+                timestamp: DateTime::from_timestamp(time.seconds, 0).unwrap_or_default(),
+                summary: commit
+                    .message()
+                    .map(|m| m.summary().to_string())
+                    .unwrap_or_default(),
+                deleted: current.is_none(),
+            });
+            if versions.len() >= limit {
+                break;
+            }
+        }
+        Ok(versions)
+    }
+
+    /// Content of `path` as of `version`, or `None` if it didn't exist then.
+    pub fn content_at(
+        &self,
+        user_id: &str,
+        path: &str,
+        version: &str,
+    ) -> Result<Option<String>, WorkspaceError> {
+        let repo = self.repo.to_thread_local();
+        let unknown = || WorkspaceError::HistoryFailed {
+            reason: format!("unknown version '{}'", version),
+        };
+
+        let commit = repo
+            .rev_parse_single(version)
+            .map_err(|_| unknown())?
+            .object()
+            .map_err(history_err)?
+            .try_into_commit()
+            .map_err(|_| unknown())?;
+        let Some(blob) = blob_at(&commit, &repo_path(user_id, path))? else {
+            return Ok(None);
+        };
+
+        let data = repo.find_object(blob).map_err(history_err)?.detach().data;
+        String::from_utf8(data).map(Some).map_err(history_err)
+    }
+}
+
+/// Current commit on the memory branch, if any commits exist yet.
+fn tip(repo: &gix::Repository) -> Result<Option<gix::ObjectId>, WorkspaceError> {
+    Ok(repo
+        .try_find_reference(BRANCH)
+        .map_err(history_err)?
+        .and_then(|r| r.target().try_id().map(ToOwned::to_owned)))
+}
+
+/// Blob ID of `key` in `commit`'s tree.
+fn blob_at(commit: &gix::Commit<'_>, key: &str) -> Result<Option<gix::ObjectId>, WorkspaceError> {
+    let tree = commit.tree().map_err(history_err)?;
+    Ok(tree
+        .lookup_entry_by_path(key)
+        .map_err(history_err)?
+        .map(|entry| entry.object_id()))
+}
+
+/// Location of a user's memory file in the repository.
+///
+/// User IDs come from channels and may contain anything, so characters that
+/// could escape the user's directory are replaced.
+fn repo_path(user_id: &str, path: &str) -> String {
+    let user: String = user_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '@' | '+') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}/{}", user, super::normalize_path(path))
+}
+
+fn history_err(e: impl std::fmt::Display) -> WorkspaceError {
+    WorkspaceError::HistoryFailed {
+        reason: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_restore_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = MemoryHistory::open(dir.path()).unwrap();
+
+        let first = history
+            .record("alice", &[("MEMORY.md", Some("likes tea"))], "first")
+            .unwrap()
+            .unwrap();
+        history
+            .record("alice", &[("MEMORY.md", Some("likes coffee"))], "second")
+            .unwrap()
+            .unwrap();
+        history
+            .record("alice", &[("notes.md", Some("unrelated"))], "other file")
+            .unwrap();
+
+        let log = history.log("alice", "MEMORY.md", 10).unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].summary, "second");
+        assert_eq!(log[1].id, first);
+
+        assert_eq!(
+            history.content_at("alice", "MEMORY.md", &first).unwrap(),
+            Some("likes tea".to_string())
+        );
+        assert_eq!(
+            history.content_at("alice", "notes.md", &first).unwrap(),
+            None
+        );
+        assert!(history.content_at("alice", "MEMORY.md", "nope").is_err());
+    }
+
+    #[test]
+    fn test_unchanged_and_deleted() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = MemoryHistory::open(dir.path()).unwrap();
+
+        history
+            .record("bob", &[("a.md", Some("x"))], "create")
+            .unwrap();
+        assert!(
+            history
+                .record("bob", &[("a.md", Some("x"))], "same")
+                .unwrap()
+                .is_none()
+        );
+        history.record("bob", &[("a.md", None)], "delete").unwrap();
+
+        let log = history.log("bob", "a.md", 10).unwrap();
+        assert_eq!(log.len(), 2);
+        assert!(log[0].deleted);
+
+        // Reopening picks up the existing repository
+        drop(history);
+        let history = MemoryHistory::open(dir.path()).unwrap();
+        assert_eq!(history.log("bob", "a.md", 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_users_are_separate() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = MemoryHistory::open(dir.path()).unwrap();
+
+        history
+            .record("alice", &[("MEMORY.md", Some("alice's"))], "a")
+            .unwrap();
+        assert!(history.log("bob", "MEMORY.md", 10).unwrap().is_empty());
+        assert_eq!(repo_path("../evil", "/x//y.md"), "___evil/x/y.md");
+    }
+}
@@ -43,14 +43,23 @@
 mod chunker;
 mod document;
 mod embeddings;
+mod history;
 mod repository;
+mod resolver;
 mod search;
+mod spaces;
 
 pub use chunker::{ChunkConfig, chunk_document};
 pub use document::{MemoryChunk, MemoryDocument, WorkspaceEntry, paths};
-pub use embeddings::{EmbeddingProvider, GoogleEmbeddings, MockEmbeddings, NearAiEmbeddings, OpenAiEmbeddings, LocalEmbeddings};
+pub use embeddings::{EmbeddingError, EmbeddingProvider, GoogleEmbeddings, MockEmbeddings, NearAiEmbeddings, OpenAiEmbeddings, LocalEmbeddings};
+pub use history::{MemoryHistory, MemoryVersion};
 pub use repository::Repository;
+pub use resolver::UserWorkspaces;
 pub use search::{SearchConfig, SearchResult};
+pub use spaces::{
+    MemorySpace, MemorySpaces, SPACE_OWNER_PREFIX, SpaceAccess, SpaceMember, SpaceWrite,
+    normalize_space_name,
+};
 
 use std::sync::Arc;
 
@@ -90,6 +99,8 @@ pub struct Workspace {
     repo: Repository,
     /// Embedding provider for semantic search.
     embeddings: Option<Arc<dyn EmbeddingProvider>>,
+    /// Git-backed version history of memory files.
+    history: Option<Arc<MemoryHistory>>,
 }
 
 impl Workspace {
@@ -100,6 +111,7 @@ impl Workspace {
             agent_id: None,
             repo: Repository::new(pool),
             embeddings: None,
+            history: None,
         }
     }
 
@@ -115,6 +127,22 @@ impl Workspace {
         self
     }
 
+    /// Record memory file versions in `history`.
+    pub fn with_history(mut self, history: Arc<MemoryHistory>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// A workspace for `user_id` with the same agent, embedding provider and
+    /// history as this one.
+    pub(crate) fn derive(&self, user_id: impl Into<String>, pool: Pool) -> Self {
+        let mut ws = Workspace::new(user_id, pool);
+        ws.agent_id = self.agent_id;
+        ws.embeddings = self.embeddings.clone();
+        ws.history = self.history.clone();
+        ws
+    }
+
     /// Get the user ID.
     pub fn user_id(&self) -> &str {
         &self.user_id
@@ -125,6 +153,16 @@ impl Workspace {
         self.agent_id
     }
 
+    /// Get the embedding provider, if semantic search is enabled.
+    pub fn embeddings(&self) -> Option<&Arc<dyn EmbeddingProvider>> {
+        self.embeddings.as_ref()
+    }
+
+    /// Get the version history, if enabled.
+    pub fn history(&self) -> Option<&Arc<MemoryHistory>> {
+        self.history.as_ref()
+    }
+
     // ==================== File Operations ====================
 
     /// Read a file by path.
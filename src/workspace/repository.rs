@@ -3,6 +3,8 @@
 //! All workspace data is stored in PostgreSQL:
 //! - Documents in `memory_documents` table
 //! - Chunks in `memory_chunks` table (with FTS and vector indexes)
+//! - Shared spaces in `memory_spaces`, `memory_space_members` and
+//!   `memory_space_writes`
 
 use chrono::{DateTime, Utc};
 use deadpool_postgres::Pool;
@@ -13,6 +15,7 @@ use crate::error::WorkspaceError;
 
 use crate::workspace::document::{MemoryChunk, MemoryDocument, WorkspaceEntry};
 use crate::workspace::search::{RankedResult, SearchConfig, SearchResult, reciprocal_rank_fusion};
+use crate::workspace::spaces::{MemorySpace, SpaceAccess, SpaceMember, SpaceWrite};
 
 /// Database repository for workspace operations.
 pub struct Repository {
@@ -544,4 +547,277 @@ impl Repository {
             })
             .collect())
     }
+
+    // ==================== Space Operations ====================
+
+    /// Create a space with `created_by` as its admin. Returns false if a
+    /// space with that name already exists.
+    pub async fn create_space(
+        &self,
+        name: &str,
+        description: &str,
+        created_by: &str,
+    ) -> Result<bool, WorkspaceError> {
+        let conn = self.conn().await?;
+
+        let inserted = conn
+            .execute(
+                r#"
+                WITH space AS (
+                    INSERT INTO memory_spaces (name, description, created_by)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (name) DO NOTHING
+                    RETURNING name
+                )
+                INSERT INTO memory_space_members (space, user_id, access, granted_by)
+                SELECT name, $3, 'admin', $3 FROM space
+                "#,
+                &[&name, &description, &created_by],
+            )
+            .await
+            .map_err(|e| WorkspaceError::SearchFailed {
+                reason: format!("Create space failed: {}", e),
+            })?;
+
+        Ok(inserted > 0)
+    }
+
+    /// Get a space by name.
+    pub async fn get_space(&self, name: &str) -> Result<Option<MemorySpace>, WorkspaceError> {
+        let conn = self.conn().await?;
+
+        let row = conn
+            .query_opt(
+                r#"
+                SELECT name, description, created_by, created_at
+                FROM memory_spaces
+                WHERE name = $1
+                "#,
+                &[&name],
+            )
+            .await
+            .map_err(|e| WorkspaceError::SearchFailed {
+                reason: format!("Query failed: {}", e),
+            })?;
+
+        Ok(row.map(|r| row_to_space(&r)))
+    }
+
+    /// List the spaces `user_id` is a member of, with their access, or every
+    /// space if `all` is set.
+    pub async fn list_spaces(
+        &self,
+        user_id: &str,
+        all: bool,
+    ) -> Result<Vec<(MemorySpace, Option<SpaceAccess>)>, WorkspaceError> {
+        let conn = self.conn().await?;
+
+        let rows = conn
+            .query(
+                r#"
+                SELECT s.name, s.description, s.created_by, s.created_at, m.access
+                FROM memory_spaces s
+                LEFT JOIN memory_space_members m ON m.space = s.name AND m.user_id = $1
+                WHERE $2 OR m.user_id IS NOT NULL
+                ORDER BY s.name
+                "#,
+                &[&user_id, &all],
+            )
+            .await
+            .map_err(|e| WorkspaceError::SearchFailed {
+                reason: format!("List spaces failed: {}", e),
+            })?;
+
+        Ok(rows
+            .iter()
+            .map(|r| {
+                let access: Option<String> = r.get("access");
+                (row_to_space(r), access.as_deref().map(parse_access))
+            })
+            .collect())
+    }
+
+    /// Get `user_id`'s access to a space, if any.
+    pub async fn space_access(
+        &self,
+        space: &str,
+        user_id: &str,
+    ) -> Result<Option<SpaceAccess>, WorkspaceError> {
+        let conn = self.conn().await?;
+
+        let row = conn
+            .query_opt(
+                "SELECT access FROM memory_space_members WHERE space = $1 AND user_id = $2",
+                &[&space, &user_id],
+            )
+            .await
+            .map_err(|e| WorkspaceError::SearchFailed {
+                reason: format!("Query failed: {}", e),
+            })?;
+
+        Ok(row.map(|r| parse_access(r.get("access"))))
+    }
+
+    /// Give `user_id` `access` to a space, replacing any access they had.
+    pub async fn set_space_member(
+        &self,
+        space: &str,
+        user_id: &str,
+        access: SpaceAccess,
+        granted_by: &str,
+    ) -> Result<(), WorkspaceError> {
+        let conn = self.conn().await?;
+
+        conn.execute(
+            r#"
+            INSERT INTO memory_space_members (space, user_id, access, granted_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (space, user_id) DO UPDATE
+            SET access = EXCLUDED.access, granted_by = EXCLUDED.granted_by,
+                granted_at = NOW()
+            "#,
+            &[&space, &user_id, &access.as_str(), &granted_by],
+        )
+        .await
+        .map_err(|e| WorkspaceError::SearchFailed {
+            reason: format!("Grant failed: {}", e),
+        })?;
+
+        Ok(())
+    }
+
+    /// Remove `user_id` from a space. Returns whether they were a member.
+    pub async fn remove_space_member(
+        &self,
+        space: &str,
+        user_id: &str,
+    ) -> Result<bool, WorkspaceError> {
+        let conn = self.conn().await?;
+
+        let removed = conn
+            .execute(
+                "DELETE FROM memory_space_members WHERE space = $1 AND user_id = $2",
+                &[&space, &user_id],
+            )
+            .await
+            .map_err(|e| WorkspaceError::SearchFailed {
+                reason: format!("Revoke failed: {}", e),
+            })?;
+
+        Ok(removed > 0)
+    }
+
+    /// List the members of a space.
+    pub async fn space_members(&self, space: &str) -> Result<Vec<SpaceMember>, WorkspaceError> {
+        let conn = self.conn().await?;
+
+        let rows = conn
+            .query(
+                r#"
+                SELECT user_id, access, granted_by, granted_at
+                FROM memory_space_members
+                WHERE space = $1
+                ORDER BY user_id
+                "#,
+                &[&space],
+            )
+            .await
+            .map_err(|e| WorkspaceError::SearchFailed {
+                reason: format!("Query failed: {}", e),
+            })?;
+
+        Ok(rows
+            .iter()
+            .map(|r| SpaceMember {
+                user_id: r.get("user_id"),
+                access: parse_access(r.get("access")),
+                granted_by: r.get("granted_by"),
+                granted_at: r.get("granted_at"),
+            })
+            .collect())
+    }
+
+    /// Record a change to one of a space's files.
+    pub async fn record_space_write(
+        &self,
+        space: &str,
+        write: &SpaceWrite,
+    ) -> Result<(), WorkspaceError> {
+        let conn = self.conn().await?;
+
+        conn.execute(
+            r#"
+            INSERT INTO memory_space_writes
+                (space, path, user_id, action, job_id, conversation_id, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            &[
+                &space,
+                &write.path,
+                &write.user_id,
+                &write.action,
+                &write.job_id,
+                &write.conversation_id,
+                &write.created_at,
+            ],
+        )
+        .await
+        .map_err(|e| WorkspaceError::SearchFailed {
+            reason: format!("Record space write failed: {}", e),
+        })?;
+
+        Ok(())
+    }
+
+    /// List the most recent changes to a space, or to one of its files.
+    pub async fn space_writes(
+        &self,
+        space: &str,
+        path: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<SpaceWrite>, WorkspaceError> {
+        let conn = self.conn().await?;
+
+        let rows = conn
+            .query(
+                r#"
+                SELECT path, user_id, action, job_id, conversation_id, created_at
+                FROM memory_space_writes
+                WHERE space = $1 AND ($2::TEXT IS NULL OR path = $2)
+                ORDER BY created_at DESC
+                LIMIT $3
+                "#,
+                &[&space, &path, &limit],
+            )
+            .await
+            .map_err(|e| WorkspaceError::SearchFailed {
+                reason: format!("Query failed: {}", e),
+            })?;
+
+        Ok(rows
+            .iter()
+            .map(|r| SpaceWrite {
+                path: r.get("path"),
+                user_id: r.get("user_id"),
+                action: r.get("action"),
+                job_id: r.get("job_id"),
+                conversation_id: r.get("conversation_id"),
+                created_at: r.get("created_at"),
+            })
+            .collect())
+    }
+}
+
+fn row_to_space(row: &tokio_postgres::Row) -> MemorySpace {
+    MemorySpace {
+        name: row.get("name"),
+        description: row.get("description"),
+        created_by: row.get("created_by"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Stored access level; anything unrecognized grants only reading.
+fn parse_access(access: &str) -> SpaceAccess {
+    SpaceAccess::parse(access).unwrap_or(SpaceAccess::Read)
 }
@@ -0,0 +1,187 @@
+//! Per-user workspace resolution.
+//!
+//! A single-user deployment keeps everything in one shared workspace. With
+//! tenancy enabled each user gets an isolated workspace root (their own
+//! `user_id` scope in the memory tables), created on first use.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+
+use deadpool_postgres::Pool;
+
+use crate::workspace::{MemorySpaces, SPACE_OWNER_PREFIX, Workspace};
+
+/// Marks a user ID that was escaped to keep it out of the space namespace.
+const ESCAPE_PREFIX: char = '~';
+
+/// Resolves the workspace a given user's memory lives in.
+pub struct UserWorkspaces {
+    shared: Arc<Workspace>,
+    /// Present when each user gets their own workspace.
+    isolated: Option<Isolated>,
+    /// Named memory spaces shared between users, if enabled.
+    spaces: Option<Arc<MemorySpaces>>,
+}
+
+struct Isolated {
+    pool: Pool,
+    workspaces: RwLock<HashMap<String, Arc<Workspace>>>,
+    /// Users whose workspace has had its identity files seeded.
+    seeded: Mutex<HashSet<String>>,
+}
+
+impl UserWorkspaces {
+    /// Every user reads and writes the same workspace.
+    pub fn shared(workspace: Arc<Workspace>) -> Self {
+        Self {
+            shared: workspace,
+            isolated: None,
+            spaces: None,
+        }
+    }
+
+    /// Each user gets a workspace scoped to their own user ID.
+    ///
+    /// `default` is returned for the default user and supplies the embedding
+    /// provider for every workspace created afterwards.
+    pub fn isolated(default: Arc<Workspace>, pool: Pool) -> Self {
+        let mut workspaces = HashMap::new();
+        workspaces.insert(default.user_id().to_string(), Arc::clone(&default));
+        let seeded = HashSet::from([default.user_id().to_string()]);
+        Self {
+            shared: default,
+            isolated: Some(Isolated {
+                pool,
+                workspaces: RwLock::new(workspaces),
+                seeded: Mutex::new(seeded),
+            }),
+            spaces: None,
+        }
+    }
+
+    /// Let users share memory through named spaces.
+    pub fn with_spaces(mut self, spaces: Arc<MemorySpaces>) -> Self {
+        self.spaces = Some(spaces);
+        self
+    }
+
+    /// The shared memory spaces, if enabled.
+    pub fn spaces(&self) -> Option<&Arc<MemorySpaces>> {
+        self.spaces.as_ref()
+    }
+
+    /// Whether users get separate workspaces.
+    pub fn is_isolated(&self) -> bool {
+        self.isolated.is_some()
+    }
+
+    /// The deployment's default workspace (heartbeat, web gateway).
+    pub fn default_workspace(&self) -> &Arc<Workspace> {
+        &self.shared
+    }
+
+    /// The workspace holding `user_id`'s memory.
+    pub fn for_user(&self, user_id: &str) -> Arc<Workspace> {
+        let Some(ref isolated) = self.isolated else {
+            return Arc::clone(&self.shared);
+        };
+        let owner = workspace_owner(user_id);
+        let user_id = owner.as_ref();
+
+        if let Some(ws) = isolated
+            .workspaces
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(user_id)
+        {
+            return Arc::clone(ws);
+        }
+
+        let mut workspaces = isolated
+            .workspaces
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        let ws = workspaces
+            .entry(user_id.to_string())
+            .or_insert_with(|| Arc::new(self.shared.derive(user_id, isolated.pool.clone())));
+        Arc::clone(ws)
+    }
+
+    /// Resolve `user_id`'s workspace, seeding identity files (AGENTS.md,
+    /// SOUL.md, ...) the first time a new isolated workspace is used.
+    pub async fn prepare(&self, user_id: &str) -> Arc<Workspace> {
+        let ws = self.for_user(user_id);
+        let Some(ref isolated) = self.isolated else {
+            return ws;
+        };
+
+        let first_use = isolated
+            .seeded
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(user_id.to_string());
+        if first_use && let Err(e) = ws.seed_identity().await {
+            tracing::warn!("Failed to seed workspace identity for {}: {}", user_id, e);
+        }
+        ws
+    }
+}
+
+/// Owner ID a user's isolated workspace is stored under.
+///
+/// Shared spaces live under `space:<name>`, so a user ID with that prefix is
+/// escaped rather than handed a space's documents. IDs already starting with
+/// the escape character are escaped too, keeping the mapping one-to-one.
+fn workspace_owner(user_id: &str) -> Cow<'_, str> {
+    if user_id.starts_with(SPACE_OWNER_PREFIX) || user_id.starts_with(ESCAPE_PREFIX) {
+        Cow::Owned(format!("{}{}", ESCAPE_PREFIX, user_id))
+    } else {
+        Cow::Borrowed(user_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_pool() -> Pool {
+        Pool::builder(deadpool_postgres::Manager::new(
+            tokio_postgres::Config::new(),
+            tokio_postgres::NoTls,
+        ))
+        .build()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_shared_returns_same_workspace() {
+        let ws = Arc::new(Workspace::new("default", test_pool()));
+        let resolver = UserWorkspaces::shared(Arc::clone(&ws));
+
+        assert!(!resolver.is_isolated());
+        assert!(Arc::ptr_eq(&resolver.for_user("alice"), &ws));
+        assert!(Arc::ptr_eq(&resolver.for_user("bob"), &ws));
+    }
+
+    #[test]
+    fn test_isolated_scopes_by_user() {
+        let ws = Arc::new(Workspace::new("default", test_pool()));
+        let resolver = UserWorkspaces::isolated(Arc::clone(&ws), test_pool());
+
+        assert!(Arc::ptr_eq(&resolver.for_user("default"), &ws));
+        let alice = resolver.for_user("alice");
+        assert_eq!(alice.user_id(), "alice");
+        assert!(Arc::ptr_eq(&alice, &resolver.for_user("alice")));
+        assert_eq!(resolver.for_user("bob").user_id(), "bob");
+    }
+
+    #[test]
+    fn test_isolated_escapes_space_owners() {
+        let ws = Arc::new(Workspace::new("default", test_pool()));
+        let resolver = UserWorkspaces::isolated(ws, test_pool());
+
+        assert_eq!(resolver.for_user("space:team").user_id(), "~space:team");
+        assert_eq!(resolver.for_user("~space:team").user_id(), "~~space:team");
+    }
+}
@@ -0,0 +1,343 @@
+//! Shared memory spaces.
+//!
+//! A space is a named workspace ("team-wiki") that several users read and
+//! write, next to each user's private memory. Its documents live in the
+//! memory tables under the owner `space:<name>`, so search, chunking and
+//! version history work as for any workspace.
+//!
+//! Access is granted per user: `read`, `write`, or `admin` (write plus
+//! managing members). Whoever creates a space is its first admin, and
+//! deployment admins (see [`crate::tenancy`]) manage every space. Every
+//! change is recorded with the user, job and conversation behind it.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Pool;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::error::WorkspaceError;
+use crate::workspace::Workspace;
+use crate::workspace::repository::Repository;
+
+/// Prefix of the owner ID a space's documents are stored under.
+pub const SPACE_OWNER_PREFIX: &str = "space:";
+
+/// Longest allowed space name.
+const MAX_NAME_LEN: usize = 64;
+
+/// What a user may do in a space. Each level includes the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpaceAccess {
+    Read,
+    Write,
+    /// Write, plus granting and revoking access.
+    Admin,
+}
+
+impl SpaceAccess {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Admin => "admin",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "read" => Some(Self::Read),
+            "write" => Some(Self::Write),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SpaceAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A shared memory space.
+#[derive(Debug, Clone, Serialize)]
+pub struct MemorySpace {
+    pub name: String,
+    pub description: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A user's access to a space.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpaceMember {
+    pub user_id: String,
+    pub access: SpaceAccess,
+    pub granted_by: String,
+    pub granted_at: DateTime<Utc>,
+}
+
+/// One change to a space's files and who made it.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpaceWrite {
+    pub path: String,
+    pub user_id: String,
+    /// "write", "append", "delete" or "restore".
+    pub action: String,
+    pub job_id: Option<Uuid>,
+    pub conversation_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Check and normalize a space name: 1-64 lowercase letters, digits, `-` or
+/// `_`, starting with a letter or digit.
+pub fn normalize_space_name(name: &str) -> Result<String, WorkspaceError> {
+    let normalized = name.trim().to_lowercase();
+    let valid = normalized.len() <= MAX_NAME_LEN
+        && normalized
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphanumeric())
+        && normalized
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(normalized)
+    } else {
+        Err(WorkspaceError::InvalidSpaceName {
+            name: name.to_string(),
+        })
+    }
+}
+
+/// The shared memory spaces of a deployment and who may use them.
+pub struct MemorySpaces {
+    repo: Repository,
+    pool: Pool,
+    /// Supplies embeddings, history and agent ID to space workspaces.
+    template: Arc<Workspace>,
+    workspaces: RwLock<HashMap<String, Arc<Workspace>>>,
+}
+
+impl MemorySpaces {
+    /// Spaces stored in `pool`, with workspaces configured like `template`.
+    pub fn new(template: Arc<Workspace>, pool: Pool) -> Self {
+        Self {
+            repo: Repository::new(pool.clone()),
+            pool,
+            template,
+            workspaces: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Create a space with `user_id` as its admin.
+    pub async fn create(
+        &self,
+        name: &str,
+        description: &str,
+        user_id: &str,
+    ) -> Result<MemorySpace, WorkspaceError> {
+        let name = normalize_space_name(name)?;
+        if !self.repo.create_space(&name, description, user_id).await? {
+            return Err(WorkspaceError::SpaceExists { name });
+        }
+        self.repo
+            .get_space(&name)
+            .await?
+            .ok_or(WorkspaceError::SpaceNotFound { name })
+    }
+
+    /// Spaces `user_id` can use, with their access. Deployment admins see
+    /// every space.
+    pub async fn list(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<(MemorySpace, SpaceAccess)>, WorkspaceError> {
+        let admin = is_deployment_admin();
+        let spaces = self.repo.list_spaces(user_id, admin).await?;
+        Ok(spaces
+            .into_iter()
+            .map(|(space, access)| {
+                let access = if admin {
+                    SpaceAccess::Admin
+                } else {
+                    access.unwrap_or(SpaceAccess::Read)
+                };
+                (space, access)
+            })
+            .collect())
+    }
+
+    /// `user_id`'s access to a space, or `None` if they have none.
+    pub async fn access(
+        &self,
+        name: &str,
+        user_id: &str,
+    ) -> Result<Option<SpaceAccess>, WorkspaceError> {
+        let name = normalize_space_name(name)?;
+        if self.repo.get_space(&name).await?.is_none() {
+            return Err(WorkspaceError::SpaceNotFound { name });
+        }
+        if is_deployment_admin() {
+            return Ok(Some(SpaceAccess::Admin));
+        }
+        self.repo.space_access(&name, user_id).await
+    }
+
+    /// Fail unless `user_id` has at least `needed` access to the space.
+    async fn require(
+        &self,
+        name: &str,
+        user_id: &str,
+        needed: SpaceAccess,
+    ) -> Result<(), WorkspaceError> {
+        match self.access(name, user_id).await? {
+            Some(access) if access >= needed => Ok(()),
+            _ => Err(WorkspaceError::SpaceAccessDenied {
+                name: name.to_string(),
+                user_id: user_id.to_string(),
+                needed: needed.to_string(),
+            }),
+        }
+    }
+
+    /// The space's workspace, if `user_id` has at least `needed` access.
+    pub async fn open(
+        &self,
+        name: &str,
+        user_id: &str,
+        needed: SpaceAccess,
+    ) -> Result<Arc<Workspace>, WorkspaceError> {
+        let name = normalize_space_name(name)?;
+        self.require(&name, user_id, needed).await?;
+
+        if let Some(ws) = self
+            .workspaces
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&name)
+        {
+            return Ok(Arc::clone(ws));
+        }
+        let mut workspaces = self.workspaces.write().unwrap_or_else(|e| e.into_inner());
+        let ws = workspaces.entry(name.clone()).or_insert_with(|| {
+            let owner = format!("{}{}", SPACE_OWNER_PREFIX, name);
+            Arc::new(self.template.derive(owner, self.pool.clone()))
+        });
+        Ok(Arc::clone(ws))
+    }
+
+    /// Give `user_id` `access` to a space. Only the space's admins may.
+    pub async fn grant(
+        &self,
+        name: &str,
+        granted_by: &str,
+        user_id: &str,
+        access: SpaceAccess,
+    ) -> Result<(), WorkspaceError> {
+        let name = normalize_space_name(name)?;
+        self.require(&name, granted_by, SpaceAccess::Admin).await?;
+        if access < SpaceAccess::Admin {
+            self.keep_an_admin(&name, user_id).await?;
+        }
+        self.repo
+            .set_space_member(&name, user_id, access, granted_by)
+            .await
+    }
+
+    /// Remove `user_id`'s access to a space. Only the space's admins may.
+    /// Returns whether they had any.
+    pub async fn revoke(
+        &self,
+        name: &str,
+        revoked_by: &str,
+        user_id: &str,
+    ) -> Result<bool, WorkspaceError> {
+        let name = normalize_space_name(name)?;
+        self.require(&name, revoked_by, SpaceAccess::Admin).await?;
+        self.keep_an_admin(&name, user_id).await?;
+        self.repo.remove_space_member(&name, user_id).await
+    }
+
+    /// Refuse to demote or remove `user_id` if they are the last admin.
+    async fn keep_an_admin(&self, name: &str, user_id: &str) -> Result<(), WorkspaceError> {
+        let members = self.repo.space_members(name).await?;
+        let admins: Vec<&str> = members
+            .iter()
+            .filter(|m| m.access == SpaceAccess::Admin)
+            .map(|m| m.user_id.as_str())
+            .collect();
+        if admins == [user_id] {
+            return Err(WorkspaceError::LastSpaceAdmin {
+                name: name.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Members of a space, for anyone who can read it.
+    pub async fn members(
+        &self,
+        name: &str,
+        user_id: &str,
+    ) -> Result<Vec<SpaceMember>, WorkspaceError> {
+        let name = normalize_space_name(name)?;
+        self.require(&name, user_id, SpaceAccess::Read).await?;
+        self.repo.space_members(&name).await
+    }
+
+    /// Record a change to a space's file.
+    pub async fn record_write(&self, name: &str, write: &SpaceWrite) -> Result<(), WorkspaceError> {
+        self.repo.record_space_write(name, write).await
+    }
+
+    /// Most recent changes to a space, or to one of its files, newest first.
+    pub async fn activity(
+        &self,
+        name: &str,
+        user_id: &str,
+        path: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<SpaceWrite>, WorkspaceError> {
+        let name = normalize_space_name(name)?;
+        self.require(&name, user_id, SpaceAccess::Read).await?;
+        self.repo.space_writes(&name, path, limit).await
+    }
+}
+
+/// Whether the current task runs for a deployment admin.
+fn is_deployment_admin() -> bool {
+    crate::tenancy::current().is_some_and(|t| t.role().is_admin())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_space_name() {
+        assert_eq!(normalize_space_name(" Team-Wiki ").unwrap(), "team-wiki");
+        assert_eq!(normalize_space_name("ops_2024").unwrap(), "ops_2024");
+        assert!(normalize_space_name("").is_err());
+        assert!(normalize_space_name("-wiki").is_err());
+        assert!(normalize_space_name("team wiki").is_err());
+        assert!(normalize_space_name("a/b").is_err());
+        assert!(normalize_space_name(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_space_access_order() {
+        assert!(SpaceAccess::Admin > SpaceAccess::Write);
+        assert!(SpaceAccess::Write > SpaceAccess::Read);
+        for access in [SpaceAccess::Read, SpaceAccess::Write, SpaceAccess::Admin] {
+            assert_eq!(SpaceAccess::parse(access.as_str()), Some(access));
+        }
+        assert_eq!(SpaceAccess::parse("Write"), Some(SpaceAccess::Write));
+        assert_eq!(SpaceAccess::parse("owner"), None);
+    }
+}
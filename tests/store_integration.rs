@@ -0,0 +1,167 @@
+//! Integration tests for the store's versioned (compare-and-swap) writes.
+//!
+//! Requires a running PostgreSQL with the migrations applied.
+//! Set DATABASE_URL=postgres://localhost/ironclaw_test
+
+use chrono::Utc;
+use secrecy::SecretString;
+use uuid::Uuid;
+
+use ironclaw::agent::routine::{Routine, RoutineAction, Trigger};
+use ironclaw::config::DatabaseConfig;
+use ironclaw::db::Database;
+use ironclaw::error::DatabaseError;
+use ironclaw::history::Store;
+
+async fn get_store() -> Store {
+    let database_url = std::env::var("DATABASE_URL")
+        .unwrap_or_else(|_| "postgres://localhost/ironclaw_test".to_string());
+
+    Store::new(&DatabaseConfig {
+        url: SecretString::from(database_url),
+        pool_size: 4,
+    })
+    .await
+    .expect("Failed to connect")
+}
+
+async fn cleanup_user(store: &Store, user_id: &str) {
+    let conn = store.conn().await.expect("Failed to get connection");
+    conn.execute("DELETE FROM settings WHERE user_id = $1", &[&user_id])
+        .await
+        .ok();
+    conn.execute("DELETE FROM routines WHERE user_id = $1", &[&user_id])
+        .await
+        .ok();
+}
+
+fn routine(name: &str) -> Routine {
+    let now = Utc::now();
+    Routine {
+        id: Uuid::new_v4(),
+        name: name.to_string(),
+        description: String::new(),
+        enabled: true,
+        trigger: Trigger::Manual,
+        action: RoutineAction::Lightweight {
+            prompt: "check".to_string(),
+        },
+        guardrails: serde_json::json!({}),
+        notify: serde_json::json!({}),
+        last_run_at: None,
+        next_fire_at: None,
+        run_count: 0,
+        consecutive_failures: 0,
+        created_at: now,
+        updated_at: now,
+        version: 1,
+    }
+}
+
+#[tokio::test]
+async fn test_set_setting_create_only_refuses_existing_row() {
+    let store = get_store().await;
+    let user_id = "test_setting_create_only";
+    cleanup_user(&store, user_id).await;
+
+    let version = store
+        .set_setting(user_id, "model", &serde_json::json!("a"), Some(0))
+        .await
+        .expect("Failed to create");
+    assert_eq!(version, 1);
+
+    // A second create must not overwrite the row the first one made
+    let err = store
+        .set_setting(user_id, "model", &serde_json::json!("b"), Some(0))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DatabaseError::Conflict { current: 1, .. }));
+
+    let record = store
+        .get_setting_full(user_id, "model")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(record.value, serde_json::json!("a"));
+
+    cleanup_user(&store, user_id).await;
+}
+
+#[tokio::test]
+async fn test_set_setting_stale_version_conflicts() {
+    let store = get_store().await;
+    let user_id = "test_setting_stale";
+    cleanup_user(&store, user_id).await;
+
+    let v1 = store
+        .set_setting(user_id, "model", &serde_json::json!("a"), None)
+        .await
+        .unwrap();
+    let v2 = store
+        .set_setting(user_id, "model", &serde_json::json!("b"), Some(v1))
+        .await
+        .expect("Write at the current version succeeds");
+    assert_eq!(v2, v1 + 1);
+
+    // Another writer read v1 before our update landed
+    let err = store
+        .set_setting(user_id, "model", &serde_json::json!("c"), Some(v1))
+        .await
+        .unwrap_err();
+    match err {
+        DatabaseError::Conflict {
+            entity,
+            id,
+            current,
+        } => {
+            assert_eq!(entity, "setting");
+            assert_eq!(id, "model");
+            assert_eq!(current, v2);
+        }
+        other => panic!("expected Conflict, got {other:?}"),
+    }
+
+    let record = store
+        .get_setting_full(user_id, "model")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(record.value, serde_json::json!("b"));
+    assert_eq!(record.version, v2);
+
+    cleanup_user(&store, user_id).await;
+}
+
+#[tokio::test]
+async fn test_update_routine_bumps_version_and_refuses_stale() {
+    let store = get_store().await;
+    let user_id = "test_routine_version";
+    cleanup_user(&store, user_id).await;
+
+    let mut routine = routine("nightly");
+    store.create_routine(user_id, &routine).await.unwrap();
+
+    let stale = routine.clone();
+    routine.description = "first edit".to_string();
+    let version = store
+        .update_routine(&routine)
+        .await
+        .expect("Failed to update");
+    assert_eq!(version, 2);
+    assert_eq!(
+        store
+            .get_routine(routine.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .version,
+        2
+    );
+
+    let err = store.update_routine(&stale).await.unwrap_err();
+    assert!(matches!(err, DatabaseError::Conflict { current: 2, .. }));
+    let stored = store.get_routine(routine.id).await.unwrap().unwrap();
+    assert_eq!(stored.description, "first edit");
+
+    cleanup_user(&store, user_id).await;
+}
@@ -0,0 +1,140 @@
+//! Error envelopes for failed API calls.
+//!
+//! Like the Google tools' envelope, a failed call becomes JSON rather than
+//! raw response text, so the agent can tell a transient failure (retry) from
+//! a credential problem (re-authenticate) from a bad request (fix the
+//! parameters):
+//!
+//! ```json
+//! {"api": "Slack", "message": "channel_not_found", "http_status": 200,
+//!  "retryable": false, "action": "fix_request"}
+//! ```
+
+use serde::Serialize;
+
+/// What the agent should do about an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorAction {
+    /// Transient; the same call may succeed later.
+    Retry,
+    /// The credential is missing, expired, or under-scoped.
+    Reauthenticate,
+    /// The request itself is wrong.
+    FixRequest,
+    /// Needs the user.
+    Escalate,
+}
+
+impl ErrorAction {
+    /// Classify by HTTP status.
+    pub fn for_status(status: u16) -> Self {
+        match status {
+            401 | 403 => Self::Reauthenticate,
+            408 | 429 | 500..=599 => Self::Retry,
+            400..=499 => Self::FixRequest,
+            _ => Self::Escalate,
+        }
+    }
+}
+
+/// A structured API error, serialized as the tool's error string.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorEnvelope {
+    pub api: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_status: Option<u16>,
+    pub retryable: bool,
+    pub action: ErrorAction,
+}
+
+impl ErrorEnvelope {
+    /// Build an envelope, deriving the action from the HTTP status.
+    pub fn from_status(api: &str, status: u16, message: impl Into<String>) -> Self {
+        let action = ErrorAction::for_status(status);
+        Self {
+            api: api.to_string(),
+            message: message.into(),
+            http_status: Some(status),
+            retryable: action == ErrorAction::Retry,
+            action,
+        }
+    }
+
+    /// Build an envelope for an error the API reported in a 2xx body
+    /// (e.g. Slack's `{"ok": false, "error": "..."}`).
+    pub fn with_action(api: &str, action: ErrorAction, message: impl Into<String>) -> Self {
+        Self {
+            api: api.to_string(),
+            message: message.into(),
+            http_status: None,
+            retryable: action == ErrorAction::Retry,
+            action,
+        }
+    }
+
+    /// Serialize for returning as the tool error.
+    pub fn into_error(self) -> String {
+        serde_json::to_string(&self).unwrap_or(self.message)
+    }
+}
+
+/// Pull a human-readable message out of a JSON error body, falling back to
+/// the raw text. Understands `{"error": "..."}`, `{"error": {"message": ..}}`,
+/// `{"message": "..."}` and `{"description": "..."}`.
+pub fn message_from_body(body: &str) -> String {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(body) else {
+        return body.trim().to_string();
+    };
+    let candidates = [
+        value.get("error").and_then(|e| e.get("message")),
+        value.get("error"),
+        value.get("message"),
+        value.get("description"),
+    ];
+    let message = candidates
+        .into_iter()
+        .flatten()
+        .find_map(|v| v.as_str())
+        .map(str::to_string);
+    message.unwrap_or_else(|| body.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_for_status() {
+        assert_eq!(ErrorAction::for_status(401), ErrorAction::Reauthenticate);
+        assert_eq!(ErrorAction::for_status(429), ErrorAction::Retry);
+        assert_eq!(ErrorAction::for_status(503), ErrorAction::Retry);
+        assert_eq!(ErrorAction::for_status(404), ErrorAction::FixRequest);
+        assert_eq!(ErrorAction::for_status(302), ErrorAction::Escalate);
+    }
+
+    #[test]
+    fn test_envelope_serializes() {
+        let err = ErrorEnvelope::from_status("Example", 429, "slow down").into_error();
+        let value: serde_json::Value = serde_json::from_str(&err).unwrap();
+        assert_eq!(value["api"], "Example");
+        assert_eq!(value["http_status"], 429);
+        assert_eq!(value["retryable"], true);
+        assert_eq!(value["action"], "retry");
+    }
+
+    #[test]
+    fn test_message_from_body() {
+        assert_eq!(
+            message_from_body(r#"{"error": {"message": "bad id"}}"#),
+            "bad id"
+        );
+        assert_eq!(
+            message_from_body(r#"{"error": "not_authed"}"#),
+            "not_authed"
+        );
+        assert_eq!(message_from_body(r#"{"description": "nope"}"#), "nope");
+        assert_eq!(message_from_body("plain failure\n"), "plain failure");
+    }
+}
@@ -0,0 +1,67 @@
+//! The host functions every component can import.
+
+/// Log severity, mirroring the WIT `log-level` enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// Response from [`Host::http_request`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    /// Response headers as a JSON object string.
+    pub headers_json: String,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Whether the status is 2xx.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// The body as text, replacing invalid UTF-8.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// Capabilities shared by the tool `host` and channel `channel-host`
+/// interfaces.
+///
+/// Implemented for the generated bindings by [`crate::bind_host!`] and for
+/// tests by [`crate::mock::MockHost`].
+pub trait Host {
+    fn log(&self, level: LogLevel, message: &str);
+
+    fn now_millis(&self) -> u64;
+
+    fn workspace_read(&self, path: &str) -> Option<String>;
+
+    fn http_request(
+        &self,
+        method: &str,
+        url: &str,
+        headers_json: &str,
+        body: Option<&[u8]>,
+    ) -> Result<HttpResponse, String>;
+
+    fn secret_exists(&self, name: &str) -> bool;
+
+    /// Fail with a setup hint unless `name` is configured.
+    fn require_secret(&self, name: &str, service: &str) -> Result<(), String> {
+        if self.secret_exists(name) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} credentials not configured. Please add the '{}' secret.",
+                service, name
+            ))
+        }
+    }
+}
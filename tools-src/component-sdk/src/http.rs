@@ -0,0 +1,205 @@
+//! JSON-over-HTTP helpers on top of [`Host::http_request`].
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::{message_from_body, ErrorEnvelope};
+use crate::host::{Host, LogLevel};
+
+/// Percent-encode a string for use as a URL query parameter value.
+pub fn url_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char);
+            }
+            _ => {
+                out.push('%');
+                out.push(char::from(b"0123456789ABCDEF"[(b >> 4) as usize]));
+                out.push(char::from(b"0123456789ABCDEF"[(b & 0xf) as usize]));
+            }
+        }
+    }
+    out
+}
+
+/// Builder for an encoded query string.
+#[derive(Debug, Default, Clone)]
+pub struct Query {
+    pairs: Vec<(String, String)>,
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a parameter.
+    pub fn param(mut self, key: &str, value: impl ToString) -> Self {
+        self.pairs.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add a parameter only when `value` is present.
+    pub fn opt(self, key: &str, value: Option<impl ToString>) -> Self {
+        match value {
+            Some(v) => self.param(key, v),
+            None => self,
+        }
+    }
+
+    /// Append to `path`, with a leading `?` when there are parameters.
+    pub fn to_path(&self, path: &str) -> String {
+        if self.pairs.is_empty() {
+            return path.to_string();
+        }
+        let encoded: Vec<String> = self
+            .pairs
+            .iter()
+            .map(|(k, v)| format!("{}={}", url_encode(k), url_encode(v)))
+            .collect();
+        let sep = if path.contains('?') { '&' } else { '?' };
+        format!("{}{}{}", path, sep, encoded.join("&"))
+    }
+}
+
+/// A JSON API client for one base URL.
+///
+/// Non-2xx responses become an [`ErrorEnvelope`] string, so every caller
+/// reports failures the same way.
+pub struct HttpClient<'a, H: Host + ?Sized> {
+    host: &'a H,
+    api: &'a str,
+    base_url: &'a str,
+}
+
+impl<'a, H: Host + ?Sized> HttpClient<'a, H> {
+    /// `api` is the human-readable name used in logs and error envelopes.
+    pub fn new(host: &'a H, api: &'a str, base_url: &'a str) -> Self {
+        Self {
+            host,
+            api,
+            base_url,
+        }
+    }
+
+    /// Send a request and return the body text of a 2xx response.
+    pub fn request(&self, method: &str, path: &str, body: Option<&str>) -> Result<String, String> {
+        let url = format!("{}{}", self.base_url, path);
+        let headers = if body.is_some() {
+            r#"{"Content-Type": "application/json; charset=utf-8"}"#
+        } else {
+            "{}"
+        };
+
+        self.host.log(
+            LogLevel::Debug,
+            &format!("{} API: {} {}", self.api, method, path),
+        );
+
+        let response = self
+            .host
+            .http_request(method, &url, headers, body.map(|b| b.as_bytes()))?;
+
+        if !response.is_success() {
+            let text = response.text();
+            return Err(ErrorEnvelope::from_status(
+                self.api,
+                response.status,
+                message_from_body(&text),
+            )
+            .into_error());
+        }
+
+        String::from_utf8(response.body).map_err(|e| format!("Invalid UTF-8 in response: {}", e))
+    }
+
+    /// `GET` and deserialize the response.
+    pub fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, String> {
+        let text = self.request("GET", path, None)?;
+        parse_body(&text)
+    }
+
+    /// Send a JSON body and deserialize the response.
+    pub fn send_json<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        method: &str,
+        path: &str,
+        body: &B,
+    ) -> Result<T, String> {
+        let payload = serde_json::to_string(body).map_err(|e| e.to_string())?;
+        let text = self.request(method, path, Some(&payload))?;
+        parse_body(&text)
+    }
+}
+
+fn parse_body<T: DeserializeOwned>(text: &str) -> Result<T, String> {
+    // Empty 2xx bodies (204s) deserialize as JSON null.
+    let text = if text.trim().is_empty() { "null" } else { text };
+    serde_json::from_str(text).map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockHost;
+
+    #[test]
+    fn test_url_encode() {
+        assert_eq!(url_encode("a b&c"), "a%20b%26c");
+        assert_eq!(url_encode("safe-_.~"), "safe-_.~");
+    }
+
+    #[test]
+    fn test_query_to_path() {
+        let q = Query::new()
+            .param("q", "in:inbox")
+            .opt("limit", Some(5))
+            .opt("page", None::<u32>);
+        assert_eq!(q.to_path("/search"), "/search?q=in%3Ainbox&limit=5");
+        assert_eq!(q.to_path("/x?a=1"), "/x?a=1&q=in%3Ainbox&limit=5");
+        assert_eq!(Query::new().to_path("/x"), "/x");
+    }
+
+    #[test]
+    fn test_get_json_success() {
+        let host = MockHost::new().with_response(200, r#"{"id": 7}"#);
+        let client = HttpClient::new(&host, "Example", "https://api.example.com");
+
+        let value: serde_json::Value = client.get_json("/items/7").unwrap();
+        assert_eq!(value["id"], 7);
+
+        let requests = host.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].url, "https://api.example.com/items/7");
+    }
+
+    #[test]
+    fn test_send_json_sets_content_type() {
+        let host = MockHost::new().with_response(204, "");
+        let client = HttpClient::new(&host, "Example", "https://api.example.com");
+
+        let _: serde_json::Value = client
+            .send_json("POST", "/items", &serde_json::json!({"name": "x"}))
+            .unwrap();
+
+        let request = &host.requests()[0];
+        assert!(request.headers_json.contains("application/json"));
+        assert_eq!(request.body_text().as_deref(), Some(r#"{"name":"x"}"#));
+    }
+
+    #[test]
+    fn test_error_status_becomes_envelope() {
+        let host = MockHost::new().with_response(404, r#"{"error": {"message": "no such item"}}"#);
+        let client = HttpClient::new(&host, "Example", "https://api.example.com");
+
+        let err = client
+            .get_json::<serde_json::Value>("/items/9")
+            .unwrap_err();
+        let value: serde_json::Value = serde_json::from_str(&err).unwrap();
+        assert_eq!(value["message"], "no such item");
+        assert_eq!(value["action"], "fix_request");
+    }
+}
@@ -0,0 +1,100 @@
+//! Shared plumbing for IronClaw WASM components.
+//!
+//! Every crate under `tools-src/` and `channels-src/` used to carry its own
+//! copy of the same glue: a URL encoder, an HTTP wrapper that checks the
+//! status and decodes the body, `serde_json::to_string(..).map_err(..)` after
+//! every action, and a hand-rolled `Response { output, error }`. This crate
+//! holds that glue once.
+//!
+//! The WIT bindings are generated inside each component, so the SDK cannot
+//! call the host directly. Instead it talks to a [`Host`] trait, and the
+//! component implements it for its generated bindings with [`bind_host!`]:
+//!
+//! ```ignore
+//! wit_bindgen::generate!({ world: "sandboxed-tool", path: "../../wit/tool.wit" });
+//!
+//! ironclaw_component_sdk::bind_host!(WitHost, crate::near::agent::host);
+//!
+//! fn execute_inner(params: &str) -> Result<String, String> {
+//!     let action: MyAction = sdk::parse_params(params)?;
+//!     let api = sdk::HttpClient::new(&WitHost, "My API", "https://api.example.com");
+//!     sdk::to_output(&api.get_json::<Item>("/items/1")?)
+//! }
+//! ```
+//!
+//! Channels pass `crate::near::agent::channel_host` instead; both interfaces
+//! share the log, clock, workspace, HTTP, and secret functions.
+//!
+//! Tests swap the bindings for [`mock::MockHost`], which replays canned HTTP
+//! responses and records every request and log line, so API code can be
+//! unit-tested natively without a WASM runtime.
+
+pub mod error;
+pub mod host;
+pub mod http;
+pub mod mock;
+pub mod response;
+
+pub use error::{ErrorAction, ErrorEnvelope};
+pub use host::{Host, HttpResponse, LogLevel};
+pub use http::{url_encode, HttpClient, Query};
+pub use response::{parse_params, respond, to_output};
+
+/// Implement [`Host`] for a unit struct backed by a component's generated
+/// WIT bindings.
+///
+/// The first argument names the struct to define, the second is the path of
+/// the generated host module (`crate::near::agent::host` for tools,
+/// `crate::near::agent::channel_host` for channels).
+#[macro_export]
+macro_rules! bind_host {
+    ($name:ident, $module:path) => {
+        /// Host functions from the generated WIT bindings.
+        #[derive(Debug, Clone, Copy, Default)]
+        pub struct $name;
+
+        impl $crate::Host for $name {
+            fn log(&self, level: $crate::LogLevel, message: &str) {
+                use $module as wit;
+                let level = match level {
+                    $crate::LogLevel::Trace => wit::LogLevel::Trace,
+                    $crate::LogLevel::Debug => wit::LogLevel::Debug,
+                    $crate::LogLevel::Info => wit::LogLevel::Info,
+                    $crate::LogLevel::Warn => wit::LogLevel::Warn,
+                    $crate::LogLevel::Error => wit::LogLevel::Error,
+                };
+                wit::log(level, message);
+            }
+
+            fn now_millis(&self) -> u64 {
+                use $module as wit;
+                wit::now_millis()
+            }
+
+            fn workspace_read(&self, path: &str) -> Option<String> {
+                use $module as wit;
+                wit::workspace_read(path)
+            }
+
+            fn http_request(
+                &self,
+                method: &str,
+                url: &str,
+                headers_json: &str,
+                body: Option<&[u8]>,
+            ) -> Result<$crate::HttpResponse, String> {
+                use $module as wit;
+                wit::http_request(method, url, headers_json, body).map(|r| $crate::HttpResponse {
+                    status: r.status,
+                    headers_json: r.headers_json,
+                    body: r.body,
+                })
+            }
+
+            fn secret_exists(&self, name: &str) -> bool {
+                use $module as wit;
+                wit::secret_exists(name)
+            }
+        }
+    };
+}
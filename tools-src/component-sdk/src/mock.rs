@@ -0,0 +1,192 @@
+//! An in-memory [`Host`] for unit tests.
+//!
+//! ```
+//! use ironclaw_component_sdk::{mock::MockHost, HttpClient};
+//!
+//! let host = MockHost::new()
+//!     .with_secret("api_token")
+//!     .with_response(200, r#"{"ok": true}"#);
+//! let client = HttpClient::new(&host, "Example", "https://api.example.com");
+//! let body: serde_json::Value = client.get_json("/ping").unwrap();
+//!
+//! assert_eq!(body["ok"], true);
+//! assert_eq!(host.requests()[0].url, "https://api.example.com/ping");
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::host::{Host, HttpResponse, LogLevel};
+
+/// An HTTP request the component made.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers_json: String,
+    pub body: Option<Vec<u8>>,
+}
+
+impl RecordedRequest {
+    /// The body as UTF-8 text, if any.
+    pub fn body_text(&self) -> Option<String> {
+        self.body
+            .as_ref()
+            .map(|b| String::from_utf8_lossy(b).into_owned())
+    }
+}
+
+/// A scripted host: canned HTTP responses are returned in order, and every
+/// request and log line is recorded for assertions.
+///
+/// When the response queue runs dry, `http_request` returns an error, which
+/// catches components that make more calls than the test expected.
+#[derive(Debug, Default)]
+pub struct MockHost {
+    responses: RefCell<VecDeque<Result<HttpResponse, String>>>,
+    requests: RefCell<Vec<RecordedRequest>>,
+    logs: RefCell<Vec<(LogLevel, String)>>,
+    secrets: HashSet<String>,
+    workspace: HashMap<String, String>,
+    now: Cell<u64>,
+}
+
+impl MockHost {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a response with the given status and body.
+    pub fn with_response(self, status: u16, body: &str) -> Self {
+        self.push_response(status, body);
+        self
+    }
+
+    /// Queue a transport failure (the host refusing or failing the request).
+    pub fn with_http_error(self, error: &str) -> Self {
+        self.responses
+            .borrow_mut()
+            .push_back(Err(error.to_string()));
+        self
+    }
+
+    /// Mark a secret as configured.
+    pub fn with_secret(mut self, name: &str) -> Self {
+        self.secrets.insert(name.to_string());
+        self
+    }
+
+    /// Seed a workspace file.
+    pub fn with_workspace_file(mut self, path: &str, content: &str) -> Self {
+        self.workspace.insert(path.to_string(), content.to_string());
+        self
+    }
+
+    /// Set the clock.
+    pub fn with_now(self, millis: u64) -> Self {
+        self.now.set(millis);
+        self
+    }
+
+    /// Queue a response after construction.
+    pub fn push_response(&self, status: u16, body: &str) {
+        self.responses.borrow_mut().push_back(Ok(HttpResponse {
+            status,
+            headers_json: "{}".to_string(),
+            body: body.as_bytes().to_vec(),
+        }));
+    }
+
+    /// Advance the clock.
+    pub fn advance(&self, millis: u64) {
+        self.now.set(self.now.get() + millis);
+    }
+
+    /// Requests made so far, in order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.borrow().clone()
+    }
+
+    /// Log lines written so far, in order.
+    pub fn logs(&self) -> Vec<(LogLevel, String)> {
+        self.logs.borrow().clone()
+    }
+
+    /// Queued responses not yet consumed.
+    pub fn pending_responses(&self) -> usize {
+        self.responses.borrow().len()
+    }
+}
+
+impl Host for MockHost {
+    fn log(&self, level: LogLevel, message: &str) {
+        self.logs.borrow_mut().push((level, message.to_string()));
+    }
+
+    fn now_millis(&self) -> u64 {
+        self.now.get()
+    }
+
+    fn workspace_read(&self, path: &str) -> Option<String> {
+        self.workspace.get(path).cloned()
+    }
+
+    fn http_request(
+        &self,
+        method: &str,
+        url: &str,
+        headers_json: &str,
+        body: Option<&[u8]>,
+    ) -> Result<HttpResponse, String> {
+        self.requests.borrow_mut().push(RecordedRequest {
+            method: method.to_string(),
+            url: url.to_string(),
+            headers_json: headers_json.to_string(),
+            body: body.map(|b| b.to_vec()),
+        });
+        self.responses.borrow_mut().pop_front().unwrap_or_else(|| {
+            Err(format!(
+                "MockHost: no response queued for {} {}",
+                method, url
+            ))
+        })
+    }
+
+    fn secret_exists(&self, name: &str) -> bool {
+        self.secrets.contains(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unqueued_request_fails() {
+        let host = MockHost::new();
+        let err = host
+            .http_request("GET", "https://x", "{}", None)
+            .unwrap_err();
+        assert!(err.contains("no response queued"));
+        assert_eq!(host.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_require_secret() {
+        let host = MockHost::new().with_secret("token");
+        assert!(host.require_secret("token", "Example").is_ok());
+        let err = host.require_secret("other", "Example").unwrap_err();
+        assert!(err.contains("'other'"));
+    }
+
+    #[test]
+    fn test_clock_and_workspace() {
+        let host = MockHost::new()
+            .with_now(1_000)
+            .with_workspace_file("state/a", "1");
+        host.advance(500);
+        assert_eq!(host.now_millis(), 1_500);
+        assert_eq!(host.workspace_read("state/a").as_deref(), Some("1"));
+        assert_eq!(host.workspace_read("state/b"), None);
+    }
+}
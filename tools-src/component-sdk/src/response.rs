@@ -0,0 +1,57 @@
+//! Tool parameter and response plumbing.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Parse the tool's JSON parameters into an action type.
+pub fn parse_params<T: DeserializeOwned>(params: &str) -> Result<T, String> {
+    serde_json::from_str(params).map_err(|e| format!("Invalid parameters: {}", e))
+}
+
+/// Serialize an action result as the tool output.
+pub fn to_output<T: Serialize + ?Sized>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| e.to_string())
+}
+
+/// Split a result into the `(output, error)` pair of the WIT `response`
+/// record:
+///
+/// ```ignore
+/// fn execute(req: Request) -> Response {
+///     let (output, error) = sdk::respond(execute_inner(&req.params));
+///     Response { output, error }
+/// }
+/// ```
+pub fn respond(result: Result<String, String>) -> (Option<String>, Option<String>) {
+    match result {
+        Ok(output) => (Some(output), None),
+        Err(error) => (None, Some(error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, serde::Deserialize, PartialEq)]
+    #[serde(tag = "action", rename_all = "snake_case")]
+    enum Action {
+        Ping { n: u32 },
+    }
+
+    #[test]
+    fn test_parse_params() {
+        assert_eq!(
+            parse_params::<Action>(r#"{"action": "ping", "n": 2}"#).unwrap(),
+            Action::Ping { n: 2 }
+        );
+        let err = parse_params::<Action>(r#"{"action": "pong"}"#).unwrap_err();
+        assert!(err.starts_with("Invalid parameters:"));
+    }
+
+    #[test]
+    fn test_respond() {
+        assert_eq!(respond(Ok("x".into())), (Some("x".into()), None));
+        assert_eq!(respond(Err("e".into())), (None, Some("e".into())));
+    }
+}
@@ -4,10 +4,23 @@
 //! credential injection and rate limiting. The WASM tool never sees
 //! the actual OAuth token.
 
+use crate::api_error::{api_error, GoogleApi};
 use crate::near::agent::host;
 use crate::types::*;
 
 const GMAIL_API_BASE: &str = "https://gmail.googleapis.com/gmail/v1/users/me";
+const DRIVE_UPLOAD_BASE: &str = "https://www.googleapis.com/upload/drive/v3";
+
+const API: GoogleApi = GoogleApi {
+    name: "Gmail",
+    scope: "https://www.googleapis.com/auth/gmail.modify \
+        https://www.googleapis.com/auth/gmail.compose",
+};
+
+const DRIVE_API: GoogleApi = GoogleApi {
+    name: "Google Drive",
+    scope: "https://www.googleapis.com/auth/drive.file",
+};
 
 /// Make a Gmail API call.
 fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, String> {
@@ -29,10 +42,11 @@ fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, Stri
     let response = host::http_request(method, &url, headers, body_bytes.as_deref())?;
 
     if response.status < 200 || response.status >= 300 {
-        let body_text = String::from_utf8_lossy(&response.body);
-        return Err(format!(
-            "Gmail API returned status {}: {}",
-            response.status, body_text
+        return Err(api_error(
+            &API,
+            response.status,
+            &response.headers_json,
+            &response.body,
         ));
     }
 
@@ -107,6 +121,30 @@ fn extract_body(payload: &serde_json::Value) -> String {
     String::new()
 }
 
+/// Collect attachments from a Gmail message payload, walking nested parts.
+fn collect_attachments(payload: &serde_json::Value, out: &mut Vec<Attachment>) {
+    let filename = payload["filename"].as_str().unwrap_or("");
+    if let Some(attachment_id) = payload["body"]["attachmentId"].as_str() {
+        if !filename.is_empty() {
+            out.push(Attachment {
+                attachment_id: attachment_id.to_string(),
+                filename: filename.to_string(),
+                mime_type: payload["mimeType"]
+                    .as_str()
+                    .unwrap_or("application/octet-stream")
+                    .to_string(),
+                size: payload["body"]["size"].as_u64().unwrap_or(0),
+            });
+        }
+    }
+
+    if let Some(parts) = payload["parts"].as_array() {
+        for part in parts {
+            collect_attachments(part, out);
+        }
+    }
+}
+
 /// Parse a full message from the API response.
 fn parse_message(v: &serde_json::Value) -> Message {
     let payload = &v["payload"];
@@ -119,6 +157,9 @@ fn parse_message(v: &serde_json::Value) -> Message {
         })
         .unwrap_or_default();
 
+    let mut attachments = Vec::new();
+    collect_attachments(payload, &mut attachments);
+
     Message {
         id: v["id"].as_str().unwrap_or("").to_string(),
         thread_id: v["threadId"].as_str().unwrap_or("").to_string(),
@@ -138,6 +179,7 @@ fn parse_message(v: &serde_json::Value) -> Message {
         snippet: v["snippet"].as_str().unwrap_or("").to_string(),
         is_unread: label_ids.contains(&"UNREAD".to_string()),
         label_ids,
+        attachments,
     }
 }
 
@@ -382,6 +424,636 @@ pub fn trash_message(message_id: &str) -> Result<TrashResult, String> {
     })
 }
 
+/// Pick the attachment to save: by ID, then by filename, then the only one.
+fn select_attachment<'a>(
+    attachments: &'a [Attachment],
+    attachment_id: Option<&str>,
+    filename: Option<&str>,
+) -> Result<&'a Attachment, String> {
+    let names = || {
+        attachments
+            .iter()
+            .map(|a| a.filename.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    if let Some(id) = attachment_id {
+        // Attachment IDs are not stable across fetches, so fall back to the
+        // filename if the ID no longer matches.
+        if let Some(found) = attachments.iter().find(|a| a.attachment_id == id) {
+            return Ok(found);
+        }
+        if filename.is_none() {
+            return Err(format!(
+                "Attachment ID not found on message. Attachments: {}",
+                names()
+            ));
+        }
+    }
+
+    if let Some(name) = filename {
+        return attachments
+            .iter()
+            .find(|a| a.filename == name)
+            .ok_or_else(|| format!("No attachment named '{}'. Attachments: {}", name, names()));
+    }
+
+    match attachments {
+        [] => Err("Message has no attachments".to_string()),
+        [only] => Ok(only),
+        _ => Err(format!(
+            "Message has several attachments; pass filename or attachment_id. Attachments: {}",
+            names()
+        )),
+    }
+}
+
+/// Build a multipart/related body with JSON metadata and binary content.
+fn build_multipart_upload(
+    boundary: &str,
+    metadata: &str,
+    mime_type: &str,
+    content: &[u8],
+) -> Vec<u8> {
+    let mut body = Vec::with_capacity(content.len() + metadata.len() + 256);
+    body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+    body.extend_from_slice(b"Content-Type: application/json; charset=UTF-8\r\n\r\n");
+    body.extend_from_slice(metadata.as_bytes());
+    body.extend_from_slice(format!("\r\n--{}\r\n", boundary).as_bytes());
+    body.extend_from_slice(format!("Content-Type: {}\r\n\r\n", mime_type).as_bytes());
+    body.extend_from_slice(content);
+    body.extend_from_slice(format!("\r\n--{}--", boundary).as_bytes());
+    body
+}
+
+/// Save a message attachment to Google Drive.
+///
+/// The attachment is fetched and uploaded inside the tool, so only the
+/// resulting file's metadata is returned to the agent.
+pub fn save_attachment_to_drive(
+    message_id: &str,
+    attachment_id: Option<&str>,
+    filename: Option<&str>,
+    folder_id: Option<&str>,
+    name: Option<&str>,
+) -> Result<SavedAttachmentResult, String> {
+    let message = get_message(message_id)?;
+    let attachment = select_attachment(&message.attachments, attachment_id, filename)?;
+
+    let path = format!(
+        "messages/{}/attachments/{}",
+        url_encode(message_id),
+        url_encode(&attachment.attachment_id)
+    );
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+    let content = parsed["data"]
+        .as_str()
+        .and_then(base64url_decode_bytes)
+        .ok_or_else(|| "Attachment data missing or not valid base64".to_string())?;
+
+    let mut metadata = serde_json::json!({
+        "name": name.unwrap_or(&attachment.filename),
+        "mimeType": attachment.mime_type,
+    });
+    if let Some(folder) = folder_id {
+        metadata["parents"] = serde_json::json!([folder]);
+    }
+    let metadata_str = serde_json::to_string(&metadata).map_err(|e| e.to_string())?;
+
+    let boundary = "ironclaw_attachment_boundary_42";
+    let body = build_multipart_upload(boundary, &metadata_str, &attachment.mime_type, &content);
+
+    let url = format!(
+        "{}/files?uploadType=multipart&fields=id,name,mimeType,size,webViewLink\
+         &supportsAllDrives=true",
+        DRIVE_UPLOAD_BASE
+    );
+    let headers = format!(
+        r#"{{"Content-Type": "multipart/related; boundary={}"}}"#,
+        boundary
+    );
+
+    host::log(
+        host::LogLevel::Debug,
+        &format!(
+            "Drive API: POST upload/files (multipart, {} bytes)",
+            content.len()
+        ),
+    );
+
+    let response = host::http_request("POST", &url, &headers, Some(&body))?;
+
+    if response.status < 200 || response.status >= 300 {
+        return Err(api_error(
+            &DRIVE_API,
+            response.status,
+            &response.headers_json,
+            &response.body,
+        ));
+    }
+
+    let file: serde_json::Value = serde_json::from_slice(&response.body)
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    Ok(SavedAttachmentResult {
+        file_id: file["id"].as_str().unwrap_or("").to_string(),
+        name: file["name"].as_str().unwrap_or("").to_string(),
+        mime_type: file["mimeType"]
+            .as_str()
+            .unwrap_or(&attachment.mime_type)
+            .to_string(),
+        // Drive returns size as a string
+        size: file["size"]
+            .as_str()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(content.len() as u64),
+        web_view_link: file["webViewLink"].as_str().map(|s| s.to_string()),
+    })
+}
+
+// ==================== Follow-ups ====================
+//
+// Snoozes and reply tracking are stored as Gmail labels named after their due
+// date, so the state lives in the mailbox itself. `check_follow_ups` acts on
+// whatever is due; a routine runs it on a schedule.
+
+const SNOOZE_LABEL_PREFIX: &str = "IronClaw/Snoozed/";
+const AWAITING_REPLY_LABEL_PREFIX: &str = "IronClaw/Awaiting reply/";
+
+/// A mailbox label.
+struct Label {
+    id: String,
+    name: String,
+}
+
+/// List all labels in the mailbox.
+fn list_labels() -> Result<Vec<Label>, String> {
+    let response = api_call("GET", "labels", None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    Ok(parsed["labels"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|l| {
+                    Some(Label {
+                        id: l["id"].as_str()?.to_string(),
+                        name: l["name"].as_str()?.to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Find a label by name, creating it if it doesn't exist.
+fn ensure_label(name: &str) -> Result<String, String> {
+    if let Some(label) = list_labels()?.into_iter().find(|l| l.name == name) {
+        return Ok(label.id);
+    }
+
+    let payload = serde_json::json!({
+        "name": name,
+        "labelListVisibility": "labelShow",
+        "messageListVisibility": "show"
+    });
+    let response = api_call("POST", "labels", Some(&payload.to_string()))?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    parsed["id"]
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| format!("Creating label '{}' returned no ID", name))
+}
+
+/// Delete a label (messages keep their other labels).
+fn delete_label(label_id: &str) -> Result<(), String> {
+    api_call("DELETE", &format!("labels/{}", url_encode(label_id)), None)?;
+    Ok(())
+}
+
+/// Add and remove labels on every message in a thread.
+fn modify_thread(thread_id: &str, add: &[&str], remove: &[&str]) -> Result<(), String> {
+    let payload = serde_json::json!({
+        "addLabelIds": add,
+        "removeLabelIds": remove
+    });
+    let path = format!("threads/{}/modify", url_encode(thread_id));
+    api_call("POST", &path, Some(&payload.to_string()))?;
+    Ok(())
+}
+
+/// Look up the thread a message belongs to.
+fn thread_of(message_id: &str) -> Result<String, String> {
+    let path = format!("messages/{}?format=minimal", url_encode(message_id));
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    parsed["threadId"]
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| format!("Message {} has no thread", message_id))
+}
+
+/// IDs of threads carrying a label.
+fn threads_with_label(label_id: &str) -> Result<Vec<String>, String> {
+    let path = format!("threads?labelIds={}&maxResults=100", url_encode(label_id));
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    Ok(parsed["threads"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|t| t["id"].as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Summarize a thread, and report whether its latest message is one we
+/// received rather than sent.
+fn inspect_thread(thread_id: &str) -> Result<(FollowUpThread, bool), String> {
+    let path = format!(
+        "threads/{}?format=metadata&metadataHeaders=Subject&metadataHeaders=From",
+        url_encode(thread_id)
+    );
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    Ok(follow_up_thread(thread_id, &parsed))
+}
+
+/// Summarize a fetched thread; a thread counts as replied to when its
+/// latest message isn't one we sent.
+fn follow_up_thread(thread_id: &str, thread: &serde_json::Value) -> (FollowUpThread, bool) {
+    let messages = thread["messages"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    let subject = messages
+        .first()
+        .map(|m| get_header(&m["payload"], "Subject"))
+        .unwrap_or_default();
+    let last = messages.last();
+    let last_from = last
+        .map(|m| get_header(&m["payload"], "From"))
+        .unwrap_or_default();
+    let replied = last.is_some_and(|m| {
+        !m["labelIds"]
+            .as_array()
+            .is_some_and(|labels| labels.iter().any(|l| l == "SENT"))
+    });
+
+    (
+        FollowUpThread {
+            thread_id: thread_id.to_string(),
+            subject,
+            last_from,
+        },
+        replied,
+    )
+}
+
+/// Today's date (UTC) as days since the Unix epoch.
+fn today() -> i64 {
+    (host::now_millis() / 86_400_000) as i64
+}
+
+/// Snooze a message's thread: take it out of the inbox until a date.
+pub fn snooze_message(
+    message_id: &str,
+    until: Option<&str>,
+    days: Option<u32>,
+) -> Result<SnoozeResult, String> {
+    let until = format_date(snooze_due(until, days, today())?);
+    let thread_id = thread_of(message_id)?;
+    let label_id = ensure_label(&format!("{}{}", SNOOZE_LABEL_PREFIX, until))?;
+    modify_thread(&thread_id, &[&label_id], &["INBOX"])?;
+
+    Ok(SnoozeResult {
+        message_id: message_id.to_string(),
+        thread_id,
+        until,
+    })
+}
+
+/// The day a snooze ends: an explicit date wins over a number of days, and
+/// either must land after today.
+fn snooze_due(until: Option<&str>, days: Option<u32>, today: i64) -> Result<i64, String> {
+    let due = match (until, days) {
+        (Some(date), _) => parse_date(date)
+            .ok_or_else(|| format!("Invalid date '{}', expected YYYY-MM-DD", date))?,
+        (None, Some(days)) => today + i64::from(days),
+        (None, None) => return Err("Pass either until (YYYY-MM-DD) or days".to_string()),
+    };
+    if due <= today {
+        return Err("Snooze date must be after today".to_string());
+    }
+    Ok(due)
+}
+
+/// Track a sent message's thread for a reply within `days`.
+pub fn track_reply(message_id: &str, days: u32) -> Result<TrackReplyResult, String> {
+    let due = format_date(today() + i64::from(days));
+    let thread_id = thread_of(message_id)?;
+    let label_id = ensure_label(&format!("{}{}", AWAITING_REPLY_LABEL_PREFIX, due))?;
+    modify_thread(&thread_id, &[&label_id], &[])?;
+
+    Ok(TrackReplyResult {
+        message_id: message_id.to_string(),
+        thread_id,
+        due,
+    })
+}
+
+/// Wake due snoozed threads and settle tracked threads that were replied to
+/// or are overdue. Labels left with nothing to track are deleted.
+pub fn check_follow_ups() -> Result<FollowUpReport, String> {
+    let today = today();
+    let mut report = FollowUpReport {
+        today: format_date(today),
+        woken: Vec::new(),
+        replied: Vec::new(),
+        overdue: Vec::new(),
+        pending: 0,
+    };
+
+    for label in list_labels()? {
+        if let Some(due) = label
+            .name
+            .strip_prefix(SNOOZE_LABEL_PREFIX)
+            .and_then(parse_date)
+        {
+            let threads = threads_with_label(&label.id)?;
+            if due > today {
+                report.pending += threads.len() as u32;
+                continue;
+            }
+            for thread_id in threads {
+                modify_thread(&thread_id, &["INBOX"], &[&label.id])?;
+                report.woken.push(inspect_thread(&thread_id)?.0);
+            }
+            delete_label(&label.id)?;
+        } else if let Some(due) = label
+            .name
+            .strip_prefix(AWAITING_REPLY_LABEL_PREFIX)
+            .and_then(parse_date)
+        {
+            let mut waiting = 0;
+            for thread_id in threads_with_label(&label.id)? {
+                let (thread, replied) = inspect_thread(&thread_id)?;
+                if replied {
+                    modify_thread(&thread_id, &[], &[&label.id])?;
+                    report.replied.push(thread);
+                } else if due <= today {
+                    modify_thread(&thread_id, &[], &[&label.id])?;
+                    report.overdue.push(thread);
+                } else {
+                    waiting += 1;
+                }
+            }
+            report.pending += waiting;
+            if waiting == 0 {
+                delete_label(&label.id)?;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+// ==================== Triage ====================
+//
+// `triage_inbox` reads unread threads once, reduces each to a few compact
+// features, and sorts them into priority buckets with fixed heuristics. The
+// output is sized for a morning briefing: no bodies, short snippets, and no
+// snippets at all for the low bucket.
+
+/// Most threads a single triage reads; each one is a separate API call.
+const MAX_TRIAGE_THREADS: u32 = 100;
+
+/// Characters kept from subjects and snippets.
+const TRIAGE_TEXT_CHARS: usize = 120;
+
+const TRIAGE_HEADERS: &[&str] = &[
+    "From",
+    "To",
+    "Subject",
+    "Date",
+    "List-Id",
+    "List-Unsubscribe",
+    "Precedence",
+    "Auto-Submitted",
+];
+
+/// What triage scoring looks at for one thread.
+#[derive(Debug, Default)]
+struct ThreadFeatures {
+    message_count: usize,
+    unread_count: usize,
+    you_replied: bool,
+    addressed_to_you: bool,
+    starred: bool,
+    important: bool,
+    mailing_list: bool,
+    automated: bool,
+    category: Option<String>,
+}
+
+/// Score a thread. Returns the bucket and the signals behind it.
+fn triage_score(f: &ThreadFeatures) -> (TriagePriority, Vec<&'static str>) {
+    let mut score = 0;
+    let mut signals = Vec::new();
+    let mut signal = |applies: bool, points: i32, name: &'static str| {
+        if applies {
+            score += points;
+            signals.push(name);
+        }
+    };
+
+    signal(f.starred, 3, "starred");
+    signal(f.important, 2, "important");
+    signal(f.you_replied, 2, "you_replied");
+    signal(f.addressed_to_you, 1, "addressed_to_you");
+    signal(f.message_count >= 3, 1, "long_thread");
+    signal(f.mailing_list, -3, "mailing_list");
+    signal(f.automated, -3, "automated");
+    match f.category.as_deref() {
+        Some("promotions") | Some("social") => signal(true, -2, "promotions_or_social"),
+        Some("updates") | Some("forums") => signal(true, -1, "updates_or_forums"),
+        _ => {}
+    }
+
+    let priority = if score >= 3 {
+        TriagePriority::High
+    } else if score >= 0 {
+        TriagePriority::Normal
+    } else {
+        TriagePriority::Low
+    };
+    (priority, signals)
+}
+
+/// Whether a sender address looks like a robot.
+fn is_automated_sender(from: &str) -> bool {
+    let from = from.to_ascii_lowercase();
+    [
+        "noreply",
+        "no-reply",
+        "donotreply",
+        "do-not-reply",
+        "notifications@",
+        "notification@",
+        "mailer-daemon",
+    ]
+    .iter()
+    .any(|p| from.contains(p))
+}
+
+/// Features of a thread fetched with `format=metadata`, plus its latest
+/// message.
+fn thread_features<'a>(
+    thread: &'a serde_json::Value,
+    own_address: &str,
+) -> Option<(ThreadFeatures, &'a serde_json::Value)> {
+    let messages = thread["messages"].as_array()?;
+    let latest = messages.last()?;
+    let payload = &latest["payload"];
+    let has_label = |m: &serde_json::Value, label: &str| {
+        m["labelIds"]
+            .as_array()
+            .is_some_and(|labels| labels.iter().any(|l| l == label))
+    };
+
+    let precedence = get_header(payload, "Precedence").to_ascii_lowercase();
+    let auto_submitted = get_header(payload, "Auto-Submitted").to_ascii_lowercase();
+    let own_address = own_address.to_ascii_lowercase();
+    let category = latest["labelIds"].as_array().and_then(|labels| {
+        labels.iter().find_map(|l| {
+            l.as_str()?
+                .strip_prefix("CATEGORY_")
+                .map(str::to_ascii_lowercase)
+        })
+    });
+
+    let features = ThreadFeatures {
+        message_count: messages.len(),
+        unread_count: messages.iter().filter(|m| has_label(m, "UNREAD")).count(),
+        you_replied: messages.iter().any(|m| has_label(m, "SENT")),
+        addressed_to_you: !own_address.is_empty()
+            && get_header(payload, "To")
+                .to_ascii_lowercase()
+                .contains(&own_address),
+        starred: messages.iter().any(|m| has_label(m, "STARRED")),
+        important: has_label(latest, "IMPORTANT"),
+        mailing_list: !get_header(payload, "List-Id").is_empty()
+            || !get_header(payload, "List-Unsubscribe").is_empty()
+            || matches!(precedence.as_str(), "bulk" | "list" | "junk"),
+        automated: (!auto_submitted.is_empty() && auto_submitted != "no")
+            || is_automated_sender(&get_header(payload, "From")),
+        category: category.filter(|c| c != "personal"),
+    };
+    Some((features, latest))
+}
+
+/// Truncate to `max_chars`, marking the cut.
+fn clip(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(max_chars) {
+        Some((i, _)) => format!("{}...", &text[..i]),
+        None => text.to_string(),
+    }
+}
+
+/// Triage recent unread inbox threads into priority buckets.
+pub fn triage_inbox(
+    newer_than_days: u32,
+    query: Option<&str>,
+    max_threads: u32,
+) -> Result<TriageResult, String> {
+    let mut q = format!("in:inbox is:unread newer_than:{}d", newer_than_days.max(1));
+    if let Some(extra) = query.filter(|q| !q.trim().is_empty()) {
+        q.push(' ');
+        q.push_str(extra.trim());
+    }
+
+    let profile = api_call("GET", "profile", None)?;
+    let profile: serde_json::Value =
+        serde_json::from_str(&profile).map_err(|e| format!("Failed to parse response: {}", e))?;
+    let own_address = profile["emailAddress"].as_str().unwrap_or("");
+
+    let path = format!(
+        "threads?q={}&maxResults={}",
+        url_encode(&q),
+        max_threads.clamp(1, MAX_TRIAGE_THREADS)
+    );
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+    let thread_ids: Vec<&str> = parsed["threads"]
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|t| t["id"].as_str()).collect())
+        .unwrap_or_default();
+
+    let headers: String = TRIAGE_HEADERS
+        .iter()
+        .map(|h| format!("&metadataHeaders={}", url_encode(h)))
+        .collect();
+    let mut result = TriageResult {
+        query: q.clone(),
+        scanned_threads: 0,
+        high: Vec::new(),
+        normal: Vec::new(),
+        low: Vec::new(),
+    };
+
+    for thread_id in thread_ids {
+        let path = format!(
+            "threads/{}?format=metadata{}",
+            url_encode(thread_id),
+            headers
+        );
+        let response = api_call("GET", &path, None)?;
+        let thread: serde_json::Value = serde_json::from_str(&response)
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+        let Some((features, latest)) = thread_features(&thread, own_address) else {
+            continue;
+        };
+        result.scanned_threads += 1;
+
+        let (priority, signals) = triage_score(&features);
+        let payload = &latest["payload"];
+        let item = TriageItem {
+            thread_id: thread_id.to_string(),
+            message_id: latest["id"].as_str().unwrap_or("").to_string(),
+            from: get_header(payload, "From"),
+            subject: clip(&get_header(payload, "Subject"), TRIAGE_TEXT_CHARS),
+            date: get_header(payload, "Date"),
+            snippet: (priority != TriagePriority::Low)
+                .then(|| clip(latest["snippet"].as_str().unwrap_or(""), TRIAGE_TEXT_CHARS)),
+            message_count: features.message_count,
+            unread_count: features.unread_count,
+            signals: signals.into_iter().map(str::to_string).collect(),
+        };
+        match priority {
+            TriagePriority::High => result.high.push(item),
+            TriagePriority::Normal => result.normal.push(item),
+            TriagePriority::Low => result.low.push(item),
+        }
+    }
+
+    Ok(result)
+}
+
 // ==================== Encoding Utilities ====================
 
 const BASE64URL_CHARS: &[u8; 64] =
@@ -412,8 +1084,13 @@ fn base64url_encode(input: &[u8]) -> String {
     result
 }
 
-/// Base64url-decode a string. Returns None on invalid input.
+/// Base64url-decode a string as UTF-8 text. Returns None on invalid input.
 fn base64url_decode(input: &str) -> Option<String> {
+    base64url_decode_bytes(input).and_then(|bytes| String::from_utf8(bytes).ok())
+}
+
+/// Base64url-decode a string to bytes. Returns None on invalid input.
+fn base64url_decode_bytes(input: &str) -> Option<Vec<u8>> {
     let input = input.trim_end_matches('=');
     let mut bytes = Vec::with_capacity(input.len() * 3 / 4);
 
@@ -443,7 +1120,44 @@ fn base64url_decode(input: &str) -> Option<String> {
         }
     }
 
-    String::from_utf8(bytes).ok()
+    Some(bytes)
+}
+
+/// Parse a `YYYY-MM-DD` date into days since the Unix epoch.
+fn parse_date(s: &str) -> Option<i64> {
+    let mut parts = s.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Days from civil date (Howard Hinnant's algorithm)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    // Reject dates like 2025-02-30 that don't survive a round trip
+    (format_date(days) == s).then_some(days)
+}
+
+/// Format days since the Unix epoch as `YYYY-MM-DD`.
+fn format_date(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
 }
 
 /// Minimal percent-encoding for URL path segments and query values.
@@ -465,3 +1179,216 @@ fn url_encode(s: &str) -> String {
 }
 
 const HEX: [u8; 16] = *b"0123456789ABCDEF";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thread(messages: Vec<serde_json::Value>) -> serde_json::Value {
+        serde_json::json!({ "messages": messages })
+    }
+
+    fn message(labels: &[&str], headers: &[(&str, &str)]) -> serde_json::Value {
+        let headers: Vec<_> = headers
+            .iter()
+            .map(|(name, value)| serde_json::json!({ "name": name, "value": value }))
+            .collect();
+        serde_json::json!({
+            "id": "m",
+            "labelIds": labels,
+            "payload": { "headers": headers },
+        })
+    }
+
+    #[test]
+    fn test_active_conversation_is_high() {
+        let t = thread(vec![
+            message(&["INBOX"], &[("From", "Ann <ann@example.com>")]),
+            message(&["SENT"], &[("From", "me@example.com")]),
+            message(
+                &["INBOX", "UNREAD", "IMPORTANT"],
+                &[
+                    ("From", "Ann <ann@example.com>"),
+                    ("To", "Me <ME@example.com>"),
+                ],
+            ),
+        ]);
+        let (features, _) = thread_features(&t, "me@example.com").unwrap();
+        assert!(features.you_replied && features.addressed_to_you);
+        assert_eq!(features.unread_count, 1);
+
+        let (priority, signals) = triage_score(&features);
+        assert_eq!(priority, TriagePriority::High);
+        assert_eq!(
+            signals,
+            vec![
+                "important",
+                "you_replied",
+                "addressed_to_you",
+                "long_thread"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_newsletters_and_robots_are_low() {
+        let t = thread(vec![message(
+            &["INBOX", "UNREAD", "CATEGORY_PROMOTIONS"],
+            &[
+                ("From", "Deals <news@shop.example>"),
+                ("List-Unsubscribe", "<mailto:unsub@shop.example>"),
+            ],
+        )]);
+        let (features, _) = thread_features(&t, "me@example.com").unwrap();
+        assert_eq!(features.category.as_deref(), Some("promotions"));
+        assert_eq!(triage_score(&features).0, TriagePriority::Low);
+
+        let t = thread(vec![message(
+            &["INBOX", "UNREAD", "CATEGORY_PERSONAL"],
+            &[("From", "GitHub <notifications@github.com>")],
+        )]);
+        let (features, _) = thread_features(&t, "").unwrap();
+        assert!(features.automated && features.category.is_none());
+        assert_eq!(triage_score(&features).0, TriagePriority::Low);
+    }
+
+    #[test]
+    fn test_plain_message_is_normal() {
+        let t = thread(vec![message(
+            &["INBOX", "UNREAD"],
+            &[("From", "bob@example.com"), ("To", "team@example.com")],
+        )]);
+        let (features, _) = thread_features(&t, "me@example.com").unwrap();
+        assert_eq!(triage_score(&features), (TriagePriority::Normal, vec![]));
+    }
+
+    fn attachment(id: &str, filename: &str) -> Attachment {
+        Attachment {
+            attachment_id: id.to_string(),
+            filename: filename.to_string(),
+            mime_type: "application/pdf".to_string(),
+            size: 10,
+        }
+    }
+
+    #[test]
+    fn test_collect_attachments_walks_nested_parts() {
+        let payload = serde_json::json!({
+            "mimeType": "multipart/mixed",
+            "filename": "",
+            "parts": [
+                { "mimeType": "text/plain", "filename": "", "body": { "size": 5 } },
+                {
+                    "mimeType": "multipart/related",
+                    "filename": "",
+                    "parts": [{
+                        "mimeType": "image/png",
+                        "filename": "chart.png",
+                        "body": { "attachmentId": "a2", "size": 42 },
+                    }],
+                },
+                { "filename": "", "body": { "attachmentId": "inline" } },
+            ],
+        });
+        let mut out = Vec::new();
+        collect_attachments(&payload, &mut out);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].attachment_id, "a2");
+        assert_eq!(out[0].filename, "chart.png");
+        assert_eq!(out[0].mime_type, "image/png");
+        assert_eq!(out[0].size, 42);
+    }
+
+    #[test]
+    fn test_select_attachment() {
+        let two = [attachment("a1", "one.pdf"), attachment("a2", "two.pdf")];
+        assert_eq!(
+            select_attachment(&two, Some("a2"), None).unwrap().filename,
+            "two.pdf"
+        );
+        // A stale ID falls back to the filename
+        assert_eq!(
+            select_attachment(&two, Some("old"), Some("one.pdf"))
+                .unwrap()
+                .attachment_id,
+            "a1"
+        );
+        assert!(select_attachment(&two, Some("old"), None).is_err());
+        assert!(select_attachment(&two, None, Some("three.pdf")).is_err());
+        let err = select_attachment(&two, None, None).unwrap_err();
+        assert!(err.contains("one.pdf, two.pdf"));
+
+        let one = [attachment("a1", "one.pdf")];
+        assert_eq!(
+            select_attachment(&one, None, None).unwrap().attachment_id,
+            "a1"
+        );
+        assert!(select_attachment(&[], None, None).is_err());
+    }
+
+    #[test]
+    fn test_build_multipart_upload() {
+        let body = build_multipart_upload("b", r#"{"name":"x"}"#, "image/png", &[0, 159]);
+        let mut expected = Vec::new();
+        expected.extend_from_slice(b"--b\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n");
+        expected.extend_from_slice(br#"{"name":"x"}"#);
+        expected.extend_from_slice(b"\r\n--b\r\nContent-Type: image/png\r\n\r\n");
+        expected.extend_from_slice(&[0, 159]);
+        expected.extend_from_slice(b"\r\n--b--");
+        assert_eq!(body, expected);
+    }
+
+    #[test]
+    fn test_dates_round_trip() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(parse_date("2024-02-29"), Some(19_782));
+        assert_eq!(format_date(19_782), "2024-02-29");
+        assert_eq!(parse_date("2025-02-29"), None);
+        assert_eq!(parse_date("2025-13-01"), None);
+        assert_eq!(parse_date("2025-1-5"), None);
+        assert_eq!(parse_date("soon"), None);
+    }
+
+    #[test]
+    fn test_snooze_due() {
+        let today = parse_date("2025-03-10").unwrap();
+        assert_eq!(
+            snooze_due(Some("2025-03-12"), Some(30), today).map(format_date),
+            Ok("2025-03-12".to_string())
+        );
+        assert_eq!(
+            snooze_due(None, Some(3), today).map(format_date),
+            Ok("2025-03-13".to_string())
+        );
+        assert!(snooze_due(Some("2025-03-10"), None, today).is_err());
+        assert!(snooze_due(None, Some(0), today).is_err());
+        assert!(snooze_due(Some("next week"), None, today).is_err());
+        assert!(snooze_due(None, None, today).is_err());
+    }
+
+    #[test]
+    fn test_follow_up_thread_replied_when_last_message_received() {
+        let t = thread(vec![
+            message(
+                &["SENT"],
+                &[("From", "me@example.com"), ("Subject", "Quote")],
+            ),
+            message(&["INBOX"], &[("From", "Ann <ann@example.com>")]),
+        ]);
+        let (summary, replied) = follow_up_thread("t1", &t);
+        assert!(replied);
+        assert_eq!(summary.thread_id, "t1");
+        assert_eq!(summary.subject, "Quote");
+        assert_eq!(summary.last_from, "Ann <ann@example.com>");
+
+        let t = thread(vec![message(&["SENT"], &[("From", "me@example.com")])]);
+        assert!(!follow_up_thread("t2", &t).1);
+        assert!(!follow_up_thread("t3", &thread(vec![])).1);
+    }
+
+    #[test]
+    fn test_clip() {
+        assert_eq!(clip("  short  ", 10), "short");
+        assert_eq!(clip("héllo world", 5), "héllo...");
+    }
+}
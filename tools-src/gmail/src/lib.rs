@@ -6,6 +6,7 @@
 //! # Capabilities Required
 //!
 //! - HTTP: `gmail.googleapis.com/gmail/v1/*` (GET, POST, DELETE)
+//! - HTTP: `www.googleapis.com/upload/drive/v3/*` (POST, for saving attachments)
 //! - Secrets: `google_oauth_token` (shared OAuth 2.0 token, injected automatically)
 //!
 //! # Supported Actions
@@ -16,14 +17,33 @@
 //! - `create_draft`: Create a draft email
 //! - `reply_to_message`: Reply to an existing message (or reply-all)
 //! - `trash_message`: Move a message to trash
+//! - `save_attachment_to_drive`: Save an attachment to Google Drive without
+//!   passing its content through the conversation
+//! - `snooze_message`: Take a thread out of the inbox until a date
+//! - `track_reply`: Flag a sent thread if nobody replies within N days
+//! - `check_follow_ups`: Bring back due snoozes and report replies and
+//!   overdue follow-ups; run it from a scheduled routine
+//! - `triage_inbox`: Sort recent unread threads into high/normal/low buckets
+//!   with compact features, for briefing routines
+//!
+//! Snoozes and tracked threads are kept as dated labels under `IronClaw/`,
+//! so they survive restarts and are visible in Gmail.
+//!
+//! Triage is heuristic: starred, important, threads you replied to, and mail
+//! addressed to you rank up; mailing lists, automated senders, and the
+//! promotions/social/updates/forums categories rank down. Each item lists the
+//! `signals` that applied.
 //!
 //! # Example Usage
 //!
 //! ```json
 //! {"action": "list_messages", "query": "is:unread from:boss@company.com", "max_results": 5}
+//! {"action": "triage_inbox", "newer_than_days": 1, "max_threads": 40}
 //! ```
 
 mod api;
+#[path = "../../google-common/api_error.rs"]
+mod api_error;
 mod types;
 
 use types::GmailAction;
@@ -164,6 +184,91 @@ impl exports::near::agent::tool::Guest for GmailTool {
                         }
                     },
                     "required": ["action", "message_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "save_attachment_to_drive" },
+                        "message_id": {
+                            "type": "string",
+                            "description": "The message the attachment belongs to"
+                        },
+                        "attachment_id": {
+                            "type": "string",
+                            "description": "Attachment ID from get_message"
+                        },
+                        "filename": {
+                            "type": "string",
+                            "description": "Attachment filename (optional if the message has a single attachment)"
+                        },
+                        "folder_id": {
+                            "type": "string",
+                            "description": "Drive folder ID to save into (default: My Drive root)"
+                        },
+                        "name": {
+                            "type": "string",
+                            "description": "Name for the Drive file (default: the attachment's filename)"
+                        }
+                    },
+                    "required": ["action", "message_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "snooze_message" },
+                        "message_id": {
+                            "type": "string",
+                            "description": "A message in the thread to snooze"
+                        },
+                        "until": {
+                            "type": "string",
+                            "description": "Date to bring the thread back to the inbox (YYYY-MM-DD, UTC)"
+                        },
+                        "days": {
+                            "type": "integer",
+                            "description": "Days from today to bring the thread back (if until is not given)"
+                        }
+                    },
+                    "required": ["action", "message_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "track_reply" },
+                        "message_id": {
+                            "type": "string",
+                            "description": "The sent message to track"
+                        },
+                        "days": {
+                            "type": "integer",
+                            "description": "Days to wait for a reply before flagging (default: 3)",
+                            "default": 3
+                        }
+                    },
+                    "required": ["action", "message_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "check_follow_ups" }
+                    },
+                    "required": ["action"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "triage_inbox" },
+                        "newer_than_days": {
+                            "type": "integer",
+                            "description": "Only threads with mail from the last N days (default: 1)",
+                            "default": 1
+                        },
+                        "query": {
+                            "type": "string",
+                            "description": "Extra Gmail search terms to narrow the scan, e.g. '-category:promotions'"
+                        },
+                        "max_threads": {
+                            "type": "integer",
+                            "description": "Maximum unread threads to scan (default: 30, max: 100)",
+                            "default": 30
+                        }
+                    },
+                    "required": ["action"]
                 }
             ]
         }"#
@@ -173,18 +278,20 @@ impl exports::near::agent::tool::Guest for GmailTool {
     fn description() -> String {
         "Gmail integration for reading, searching, sending, drafting, and replying to emails. \
          Supports Gmail search query syntax (is:unread, from:, subject:, after:, etc.). \
-         Requires a Google OAuth token with gmail.modify and gmail.compose scopes."
+         Attachments can be saved directly to Google Drive with save_attachment_to_drive, \
+         which returns the new file ID instead of the file content. \
+         snooze_message and track_reply schedule follow-ups; check_follow_ups acts on the \
+         ones that are due and should be run daily from a routine. \
+         triage_inbox returns unread threads grouped into high/normal/low priority with compact \
+         features, sized for morning briefings. \
+         Requires a Google OAuth token with gmail.modify, gmail.compose, and drive.file scopes."
             .to_string()
     }
 }
 
 fn execute_inner(params: &str) -> Result<String, String> {
     if !crate::near::agent::host::secret_exists("google_oauth_token") {
-        return Err(
-            "Google OAuth token not configured. Run `ironclaw tool auth gmail` to set up \
-             OAuth, or set the GOOGLE_OAUTH_TOKEN environment variable."
-                .to_string(),
-        );
+        return Err(api_error::missing_token("gmail"));
     }
 
     let action: GmailAction =
@@ -245,6 +352,51 @@ fn execute_inner(params: &str) -> Result<String, String> {
             let result = api::trash_message(&message_id)?;
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
+
+        GmailAction::SaveAttachmentToDrive {
+            message_id,
+            attachment_id,
+            filename,
+            folder_id,
+            name,
+        } => {
+            let result = api::save_attachment_to_drive(
+                &message_id,
+                attachment_id.as_deref(),
+                filename.as_deref(),
+                folder_id.as_deref(),
+                name.as_deref(),
+            )?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GmailAction::SnoozeMessage {
+            message_id,
+            until,
+            days,
+        } => {
+            let result = api::snooze_message(&message_id, until.as_deref(), days)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GmailAction::TrackReply { message_id, days } => {
+            let result = api::track_reply(&message_id, days)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GmailAction::CheckFollowUps => {
+            let result = api::check_follow_ups()?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GmailAction::TriageInbox {
+            newer_than_days,
+            query,
+            max_threads,
+        } => {
+            let result = api::triage_inbox(newer_than_days, query.as_deref(), max_threads)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
     };
 
     Ok(result)
@@ -75,12 +75,82 @@ pub enum GmailAction {
         /// The message ID to trash.
         message_id: String,
     },
+
+    /// Save an attachment straight to Google Drive, without passing its
+    /// content through the conversation.
+    SaveAttachmentToDrive {
+        /// The message the attachment belongs to.
+        message_id: String,
+        /// Attachment ID from get_message. Takes precedence over `filename`.
+        #[serde(default)]
+        attachment_id: Option<String>,
+        /// Attachment filename. Optional if the message has one attachment.
+        #[serde(default)]
+        filename: Option<String>,
+        /// Drive folder to save into. Default: My Drive root.
+        #[serde(default)]
+        folder_id: Option<String>,
+        /// Name for the Drive file. Default: the attachment's filename.
+        #[serde(default)]
+        name: Option<String>,
+    },
+
+    /// Take a message's thread out of the inbox until a later date.
+    SnoozeMessage {
+        /// A message in the thread to snooze.
+        message_id: String,
+        /// Date to bring it back, as YYYY-MM-DD (UTC).
+        #[serde(default)]
+        until: Option<String>,
+        /// Days from today to bring it back. Used if `until` is not set.
+        #[serde(default)]
+        days: Option<u32>,
+    },
+
+    /// Watch a sent message's thread and flag it if nobody replies in time.
+    TrackReply {
+        /// The sent message to track.
+        message_id: String,
+        /// Days to wait for a reply (default: 3).
+        #[serde(default = "default_follow_up_days")]
+        days: u32,
+    },
+
+    /// Bring back due snoozed threads and report replies and overdue
+    /// follow-ups. Meant to run from a scheduled routine.
+    CheckFollowUps,
+
+    /// Sort recent unread inbox threads into priority buckets with compact
+    /// per-thread features. Meant to feed briefing routines.
+    TriageInbox {
+        /// Only threads with mail from the last N days (default: 1).
+        #[serde(default = "default_triage_days")]
+        newer_than_days: u32,
+        /// Extra Gmail search terms to narrow the scan (e.g., "-from:me").
+        #[serde(default)]
+        query: Option<String>,
+        /// Maximum threads to scan (default: 30, max: 100).
+        #[serde(default = "default_triage_max_threads")]
+        max_threads: u32,
+    },
 }
 
 fn default_max_results() -> u32 {
     20
 }
 
+fn default_follow_up_days() -> u32 {
+    3
+}
+
+fn default_triage_days() -> u32 {
+    1
+}
+
+fn default_triage_max_threads() -> u32 {
+    30
+}
+
 /// A Gmail message summary (from list endpoint).
 #[derive(Debug, Serialize)]
 pub struct MessageSummary {
@@ -110,6 +180,17 @@ pub struct Message {
     pub snippet: String,
     pub label_ids: Vec<String>,
     pub is_unread: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+}
+
+/// An attachment on a message (metadata only).
+#[derive(Debug, Clone, Serialize)]
+pub struct Attachment {
+    pub attachment_id: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub size: u64,
 }
 
 /// Result from list_messages.
@@ -142,3 +223,91 @@ pub struct TrashResult {
     pub id: String,
     pub trashed: bool,
 }
+
+/// Result from save_attachment_to_drive.
+#[derive(Debug, Serialize)]
+pub struct SavedAttachmentResult {
+    pub file_id: String,
+    pub name: String,
+    pub mime_type: String,
+    pub size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_view_link: Option<String>,
+}
+
+/// Result from snooze_message.
+#[derive(Debug, Serialize)]
+pub struct SnoozeResult {
+    pub message_id: String,
+    pub thread_id: String,
+    pub until: String,
+}
+
+/// Result from track_reply.
+#[derive(Debug, Serialize)]
+pub struct TrackReplyResult {
+    pub message_id: String,
+    pub thread_id: String,
+    pub due: String,
+}
+
+/// A thread reported by check_follow_ups.
+#[derive(Debug, Serialize)]
+pub struct FollowUpThread {
+    pub thread_id: String,
+    pub subject: String,
+    /// Sender of the latest message in the thread.
+    pub last_from: String,
+}
+
+/// Result from check_follow_ups.
+#[derive(Debug, Serialize)]
+pub struct FollowUpReport {
+    pub today: String,
+    /// Snoozed threads returned to the inbox.
+    pub woken: Vec<FollowUpThread>,
+    /// Tracked threads that got a reply; no longer tracked.
+    pub replied: Vec<FollowUpThread>,
+    /// Tracked threads past their due date without a reply; no longer tracked.
+    pub overdue: Vec<FollowUpThread>,
+    /// Snoozed or tracked threads not yet due.
+    pub pending: u32,
+}
+
+/// Priority bucket assigned by triage_inbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriagePriority {
+    High,
+    Normal,
+    Low,
+}
+
+/// One thread in a triage bucket.
+#[derive(Debug, Serialize)]
+pub struct TriageItem {
+    pub thread_id: String,
+    /// Latest message in the thread.
+    pub message_id: String,
+    pub from: String,
+    pub subject: String,
+    pub date: String,
+    /// Omitted for the low bucket.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    pub message_count: usize,
+    pub unread_count: usize,
+    /// Heuristics that placed the thread in its bucket (e.g., "you_replied",
+    /// "mailing_list").
+    pub signals: Vec<String>,
+}
+
+/// Result from triage_inbox.
+#[derive(Debug, Serialize)]
+pub struct TriageResult {
+    /// Gmail search query that was scanned.
+    pub query: String,
+    pub scanned_threads: usize,
+    pub high: Vec<TriageItem>,
+    pub normal: Vec<TriageItem>,
+    pub low: Vec<TriageItem>,
+}
@@ -4,11 +4,17 @@
 //! credential injection and rate limiting. The WASM tool never sees
 //! the actual OAuth token.
 
+use crate::api_error::{api_error, GoogleApi};
 use crate::near::agent::host;
 use crate::types::*;
 
 const CALENDAR_API_BASE: &str = "https://www.googleapis.com/calendar/v3";
 
+const API: GoogleApi = GoogleApi {
+    name: "Google Calendar",
+    scope: "https://www.googleapis.com/auth/calendar.events",
+};
+
 /// Make a Google Calendar API call.
 fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, String> {
     let url = format!("{}/{}", CALENDAR_API_BASE, path);
@@ -29,10 +35,11 @@ fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, Stri
     let response = host::http_request(method, &url, headers, body_bytes.as_deref())?;
 
     if response.status < 200 || response.status >= 300 {
-        let body_text = String::from_utf8_lossy(&response.body);
-        return Err(format!(
-            "Google Calendar API returned status {}: {}",
-            response.status, body_text
+        return Err(api_error(
+            &API,
+            response.status,
+            &response.headers_json,
+            &response.body,
         ));
     }
 
@@ -63,6 +70,7 @@ fn parse_event(v: &serde_json::Value) -> Event {
                         email: a["email"].as_str().unwrap_or("").to_string(),
                         display_name: a["displayName"].as_str().map(|s| s.to_string()),
                         response_status: a["responseStatus"].as_str().map(|s| s.to_string()),
+                        is_self: a["self"].as_bool().unwrap_or(false),
                     })
                     .collect()
             })
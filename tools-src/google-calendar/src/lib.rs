@@ -23,6 +23,8 @@
 //! ```
 
 mod api;
+#[path = "../../google-common/api_error.rs"]
+mod api_error;
 mod types;
 
 use types::GoogleCalendarAction;
@@ -226,11 +228,7 @@ impl exports::near::agent::tool::Guest for GoogleCalendarTool {
 
 fn execute_inner(params: &str) -> Result<String, String> {
     if !crate::near::agent::host::secret_exists("google_oauth_token") {
-        return Err(
-            "Google OAuth token not configured. Run `ironclaw tool auth google-calendar` \
-             to set up OAuth, or set the GOOGLE_OAUTH_TOKEN environment variable."
-                .to_string(),
-        );
+        return Err(api_error::missing_token("google-calendar"));
     }
 
     let action: GoogleCalendarAction =
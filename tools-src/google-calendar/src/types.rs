@@ -161,6 +161,9 @@ pub struct Attendee {
     pub display_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub response_status: Option<String>,
+    /// True for the attendee entry of the calendar's owner.
+    #[serde(rename = "self", skip_serializing_if = "std::ops::Not::not")]
+    pub is_self: bool,
 }
 
 /// Event organizer.
@@ -0,0 +1,358 @@
+//! Structured errors for Google API tools.
+//!
+//! Shared by the Google tool crates, each of which includes this file with
+//! `#[path]`. A failed API call becomes a JSON envelope instead of the raw
+//! response text, so the agent can tell a rate limit (retry) from an expired
+//! or under-scoped token (re-authenticate) from a bad request (fix the
+//! parameters) from something only the user can resolve (escalate). A tool
+//! run before any token is stored returns the same envelope with action
+//! "authenticate", which the agent turns into a prompt to connect the account.
+//!
+//! The envelope is returned as the tool's error string:
+//!
+//! ```json
+//! {
+//!   "api": "Google Drive",
+//!   "message": "Request had insufficient authentication scopes.",
+//!   "http_status": 403,
+//!   "status": "PERMISSION_DENIED",
+//!   "reason": "ACCESS_TOKEN_SCOPE_INSUFFICIENT",
+//!   "domain": "googleapis.com",
+//!   "retryable": false,
+//!   "required_scope": "https://www.googleapis.com/auth/drive",
+//!   "action": "reauthenticate"
+//! }
+//! ```
+
+use serde::Serialize;
+
+/// The Google API a tool talks to.
+pub struct GoogleApi {
+    /// Human-readable name, e.g. "Google Drive".
+    pub name: &'static str,
+    /// OAuth scopes the tool requests (space-separated), reported when a
+    /// token lacks one and the response doesn't say which.
+    pub scope: &'static str,
+}
+
+/// What the agent should do about an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorAction {
+    /// No token is stored yet; the user has to connect the account.
+    Authenticate,
+    /// Transient; the same call may succeed later.
+    Retry,
+    /// The token is expired, revoked, or missing a scope.
+    Reauthenticate,
+    /// The request itself is wrong (bad ID, invalid field, conflict).
+    FixRequest,
+    /// Needs the user: permissions, exhausted quota, unexpected failures.
+    Escalate,
+}
+
+/// Quota details from a quota or rate limit error.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct QuotaInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// A failed Google API call.
+#[derive(Debug, Serialize)]
+pub struct ApiError {
+    pub api: &'static str,
+    pub message: String,
+    pub http_status: u16,
+    /// Canonical status, e.g. "PERMISSION_DENIED".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// Google's machine-readable reason, e.g. "rateLimitExceeded".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    pub retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quota: Option<QuotaInfo>,
+    pub action: ErrorAction,
+}
+
+/// Reasons for a token that lacks a needed scope.
+const SCOPE_REASONS: &[&str] = &["insufficientPermissions", "ACCESS_TOKEN_SCOPE_INSUFFICIENT"];
+
+/// Reasons for a short-term rate limit, which clears on its own.
+const RATE_LIMIT_REASONS: &[&str] = &[
+    "rateLimitExceeded",
+    "userRateLimitExceeded",
+    "RATE_LIMIT_EXCEEDED",
+];
+
+/// Reasons for an exhausted (usually daily) quota, which won't clear soon.
+const QUOTA_REASONS: &[&str] = &["quotaExceeded", "dailyLimitExceeded"];
+
+impl ApiError {
+    /// Build an error from a non-2xx response.
+    pub fn from_response(api: &GoogleApi, status: u16, headers_json: &str, body: &[u8]) -> Self {
+        let body: serde_json::Value = serde_json::from_slice(body).unwrap_or_default();
+        let error = &body["error"];
+        let details = error["details"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let error_info = details.iter().find(|d| is_detail(d, "ErrorInfo"));
+
+        // Legacy `errors[]` reasons are more specific than ErrorInfo's
+        // (e.g. "userRateLimitExceeded" vs "RATE_LIMIT_EXCEEDED")
+        let first = &error["errors"][0];
+        let reason = first["reason"]
+            .as_str()
+            .or_else(|| error_info.and_then(|i| i["reason"].as_str()))
+            .map(str::to_string);
+        let domain = first["domain"]
+            .as_str()
+            .or_else(|| error_info.and_then(|i| i["domain"].as_str()))
+            .map(str::to_string);
+        let message = error["message"]
+            .as_str()
+            .or_else(|| error.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{} API returned status {}", api.name, status));
+
+        let www_authenticate = header(headers_json, "www-authenticate");
+        let scope_error = reason
+            .as_deref()
+            .is_some_and(|r| SCOPE_REASONS.contains(&r))
+            || www_authenticate
+                .as_deref()
+                .is_some_and(|h| h.contains("insufficient_scope"));
+        let action = classify(status, reason.as_deref(), scope_error);
+        let required_scope = scope_error.then(|| {
+            www_authenticate
+                .as_deref()
+                .and_then(|h| auth_param(h, "scope"))
+                .unwrap_or_else(|| api.scope.to_string())
+        });
+
+        let retry_after_secs = header(headers_json, "retry-after")
+            .and_then(|v| v.trim().parse().ok())
+            .or_else(|| {
+                details
+                    .iter()
+                    .find(|d| is_detail(d, "RetryInfo"))
+                    .and_then(|d| d["retryDelay"].as_str())
+                    .and_then(parse_duration_secs)
+            });
+
+        Self {
+            api: api.name,
+            message,
+            http_status: status,
+            status: error["status"].as_str().map(str::to_string),
+            reason,
+            domain,
+            retryable: action == ErrorAction::Retry,
+            retry_after_secs,
+            required_scope,
+            quota: quota_info(details, error_info),
+            action,
+        }
+    }
+
+    /// The envelope as the tool's error string.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| self.message.clone())
+    }
+}
+
+/// Error envelope for a non-2xx response, ready to return from a tool.
+pub fn api_error(api: &GoogleApi, status: u16, headers_json: &str, body: &[u8]) -> String {
+    ApiError::from_response(api, status, headers_json, body).to_json()
+}
+
+/// Error envelope for a tool run before any Google token is stored.
+pub fn missing_token(tool: &str) -> String {
+    serde_json::json!({
+        "api": "Google",
+        "message": format!(
+            "Google OAuth token not configured. Run `ironclaw tool auth {}` to set up \
+             OAuth, or set the GOOGLE_OAUTH_TOKEN environment variable.",
+            tool
+        ),
+        "retryable": false,
+        "action": ErrorAction::Authenticate,
+    })
+    .to_string()
+}
+
+fn classify(status: u16, reason: Option<&str>, scope_error: bool) -> ErrorAction {
+    let reason_in = |reasons: &[&str]| reason.is_some_and(|r| reasons.contains(&r));
+    if status == 401 || scope_error {
+        ErrorAction::Reauthenticate
+    } else if reason_in(QUOTA_REASONS) {
+        ErrorAction::Escalate
+    } else if matches!(status, 429 | 500 | 502 | 503 | 504)
+        || reason_in(RATE_LIMIT_REASONS)
+        || reason == Some("backendError")
+    {
+        ErrorAction::Retry
+    } else if matches!(status, 400 | 404 | 409 | 412 | 413) {
+        ErrorAction::FixRequest
+    } else {
+        ErrorAction::Escalate
+    }
+}
+
+fn is_detail(detail: &serde_json::Value, type_name: &str) -> bool {
+    detail["@type"]
+        .as_str()
+        .is_some_and(|t| t == format!("type.googleapis.com/google.rpc.{}", type_name))
+}
+
+fn quota_info(
+    details: &[serde_json::Value],
+    error_info: Option<&serde_json::Value>,
+) -> Option<QuotaInfo> {
+    let metadata = error_info.map(|i| &i["metadata"]);
+    let field = |key: &str| metadata.and_then(|m| m[key].as_str()).map(str::to_string);
+    let description = details
+        .iter()
+        .find(|d| is_detail(d, "QuotaFailure"))
+        .and_then(|d| d["violations"][0]["description"].as_str())
+        .map(str::to_string);
+
+    let quota = QuotaInfo {
+        metric: field("quota_metric"),
+        limit: field("quota_limit"),
+        limit_value: field("quota_limit_value"),
+        description,
+    };
+    (quota != QuotaInfo::default()).then_some(quota)
+}
+
+/// Case-insensitive lookup in the host's JSON header object.
+fn header(headers_json: &str, name: &str) -> Option<String> {
+    let headers: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(headers_json).ok()?;
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .and_then(|(_, value)| value.as_str())
+        .map(str::to_string)
+}
+
+/// A quoted parameter from a `WWW-Authenticate` challenge.
+fn auth_param(challenge: &str, name: &str) -> Option<String> {
+    let start = challenge.find(&format!("{}=\"", name))? + name.len() + 2;
+    let len = challenge[start..].find('"')?;
+    Some(challenge[start..start + len].to_string())
+}
+
+/// Parse a protobuf duration like "30s" or "1.5s", rounding up.
+fn parse_duration_secs(duration: &str) -> Option<u64> {
+    let secs: f64 = duration.strip_suffix('s')?.parse().ok()?;
+    Some(secs.ceil() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DRIVE: GoogleApi = GoogleApi {
+        name: "Google Drive",
+        scope: "https://www.googleapis.com/auth/drive",
+    };
+
+    #[test]
+    fn test_rate_limit_is_retryable() {
+        let body = br#"{"error": {"code": 403, "message": "User rate limit exceeded.",
+            "errors": [{"domain": "usageLimits", "reason": "userRateLimitExceeded"}]}}"#;
+        let error = ApiError::from_response(&DRIVE, 403, r#"{"Retry-After": "7"}"#, body);
+
+        assert_eq!(error.action, ErrorAction::Retry);
+        assert!(error.retryable);
+        assert_eq!(error.reason.as_deref(), Some("userRateLimitExceeded"));
+        assert_eq!(error.domain.as_deref(), Some("usageLimits"));
+        assert_eq!(error.retry_after_secs, Some(7));
+    }
+
+    #[test]
+    fn test_insufficient_scope_asks_for_reauth() {
+        let body = br#"{"error": {"code": 403, "status": "PERMISSION_DENIED",
+            "message": "Request had insufficient authentication scopes.",
+            "details": [{"@type": "type.googleapis.com/google.rpc.ErrorInfo",
+                "reason": "ACCESS_TOKEN_SCOPE_INSUFFICIENT", "domain": "googleapis.com"}]}}"#;
+        let error = ApiError::from_response(&DRIVE, 403, "{}", body);
+
+        assert_eq!(error.action, ErrorAction::Reauthenticate);
+        assert!(!error.retryable);
+        assert_eq!(error.status.as_deref(), Some("PERMISSION_DENIED"));
+        assert_eq!(error.required_scope.as_deref(), Some(DRIVE.scope));
+
+        // The challenge header names the exact scope when present
+        let headers = r#"{"www-authenticate": "Bearer error=\"insufficient_scope\", scope=\"https://www.googleapis.com/auth/drive.file\""}"#;
+        let error = ApiError::from_response(&DRIVE, 403, headers, b"{}");
+        assert_eq!(error.action, ErrorAction::Reauthenticate);
+        assert_eq!(
+            error.required_scope.as_deref(),
+            Some("https://www.googleapis.com/auth/drive.file")
+        );
+    }
+
+    #[test]
+    fn test_quota_errors() {
+        let body = br#"{"error": {"code": 429, "status": "RESOURCE_EXHAUSTED", "message": "Quota exceeded.",
+            "details": [
+                {"@type": "type.googleapis.com/google.rpc.ErrorInfo", "reason": "RATE_LIMIT_EXCEEDED",
+                 "metadata": {"quota_metric": "drive.googleapis.com/default", "quota_limit_value": "20000"}},
+                {"@type": "type.googleapis.com/google.rpc.RetryInfo", "retryDelay": "1.5s"}]}}"#;
+        let error = ApiError::from_response(&DRIVE, 429, "{}", body);
+        assert_eq!(error.action, ErrorAction::Retry);
+        assert_eq!(error.retry_after_secs, Some(2));
+        let quota = error.quota.unwrap();
+        assert_eq!(
+            quota.metric.as_deref(),
+            Some("drive.googleapis.com/default")
+        );
+        assert_eq!(quota.limit_value.as_deref(), Some("20000"));
+
+        let body = br#"{"error": {"code": 403, "message": "Daily Limit Exceeded",
+            "errors": [{"domain": "usageLimits", "reason": "dailyLimitExceeded"}]}}"#;
+        let error = ApiError::from_response(&DRIVE, 403, "{}", body);
+        assert_eq!(error.action, ErrorAction::Escalate);
+        assert!(!error.retryable);
+    }
+
+    #[test]
+    fn test_non_json_body() {
+        let error = ApiError::from_response(&DRIVE, 404, "{}", b"Not Found");
+        assert_eq!(error.action, ErrorAction::FixRequest);
+        assert_eq!(error.message, "Google Drive API returned status 404");
+
+        let json: serde_json::Value = serde_json::from_str(&error.to_json()).unwrap();
+        assert_eq!(json["http_status"], 404);
+        assert_eq!(json["action"], "fix_request");
+        assert!(json.get("reason").is_none());
+    }
+
+    #[test]
+    fn test_missing_token() {
+        let json: serde_json::Value = serde_json::from_str(&missing_token("google-docs")).unwrap();
+        assert_eq!(json["action"], "authenticate");
+        assert_eq!(json["retryable"], false);
+        assert!(json["message"]
+            .as_str()
+            .unwrap()
+            .contains("ironclaw tool auth google-docs"));
+    }
+}
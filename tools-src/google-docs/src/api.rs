@@ -4,10 +4,107 @@
 //! credential injection and rate limiting. The WASM tool never sees
 //! the actual OAuth token.
 
+use crate::api_error::{api_error, GoogleApi};
+use crate::diff;
+use crate::edits;
 use crate::near::agent::host;
 use crate::types::*;
 
 const DOCS_API_BASE: &str = "https://docs.googleapis.com/v1/documents";
+const DRIVE_API_BASE: &str = "https://www.googleapis.com/drive/v3";
+const DRIVE_UPLOAD_BASE: &str = "https://www.googleapis.com/upload/drive/v3";
+
+/// What get_document reads at each detail level. Text styles make up most
+/// of a full response, so paragraphs are fetched without them.
+const SUMMARY_FIELDS: &str = "documentId,title,revisionId,body/content/endIndex";
+const OUTLINE_FIELDS: &str = "documentId,title,revisionId,namedRanges,\
+    body/content(startIndex,endIndex,\
+    paragraph(paragraphStyle/namedStyleType,elements/textRun/content))";
+
+/// What read_section reads: paragraph styles and text, with table cell
+/// contents so tables inside a section are included.
+const SECTION_FIELDS: &str = "documentId,revisionId,\
+    body/content(startIndex,endIndex,\
+    paragraph(paragraphStyle/namedStyleType,elements/textRun/content),\
+    table/tableRows/tableCells/content)";
+
+/// What the table actions read: each table's rows and cells with the
+/// ranges and text of their paragraphs.
+const TABLE_FIELDS: &str = "documentId,revisionId,\
+    body/content(startIndex,endIndex,table/tableRows/tableCells(\
+    content(startIndex,endIndex,paragraph/elements/textRun/content)))";
+
+/// What the table of contents actions read: heading styles, IDs and text,
+/// and named ranges to find a table of contents made earlier.
+const TOC_FIELDS: &str = "documentId,revisionId,namedRanges,\
+    body/content(startIndex,endIndex,\
+    paragraph(paragraphStyle(namedStyleType,headingId),elements/textRun/content))";
+
+/// What the named range actions read: named ranges, and every text run's
+/// start so ranges can be matched to text (table cells included).
+const NAMED_RANGE_FIELDS: &str = "documentId,revisionId,namedRanges,\
+    body/content(paragraph/elements(startIndex,textRun/content),\
+    table/tableRows/tableCells/content(paragraph/elements(startIndex,textRun/content)))";
+
+/// What add_comment reads to find the text a comment quotes.
+const TEXT_FIELDS: &str = "documentId,\
+    body/content(paragraph/elements(startIndex,textRun/content),\
+    table/tableRows/tableCells/content(paragraph/elements(startIndex,textRun/content)))";
+
+/// What update_document_style reads to turn the current page around.
+const PAGE_SIZE_FIELDS: &str = "documentId,documentStyle/pageSize";
+
+/// What the comment actions read back from the Drive API.
+const REPLY_FIELDS: &str = "id,author/displayName,content,createdTime,action,deleted";
+const COMMENT_FIELDS: &str = "id,author/displayName,content,createdTime,modifiedTime,\
+    resolved,deleted,quotedFileContent/value,\
+    replies(id,author/displayName,content,createdTime,action,deleted)";
+
+/// Comments per page, and pages, list_comments reads.
+const COMMENTS_PAGE_SIZE: usize = 100;
+const MAX_COMMENT_PAGES: usize = 10;
+
+/// Prefix of the named range around a table of contents made by
+/// generate_toc. The rest of the name records how it was made, so
+/// refresh_toc rebuilds it the same way: `ironclaw-toc|<max_level>|<title>`.
+const TOC_RANGE_PREFIX: &str = "ironclaw-toc|";
+
+/// Indentation of table of contents entries per heading level, in points.
+const TOC_INDENT_PT: f64 = 18.0;
+
+/// Largest export returned inline as base64; bigger ones must be saved to
+/// Drive.
+const MAX_INLINE_EXPORT_BYTES: usize = 512 * 1024;
+
+/// Revisions read per page, and at most this many pages.
+const REVISIONS_PAGE_SIZE: usize = 1000;
+const MAX_REVISION_PAGES: usize = 10;
+
+/// Longest diff returned by compare_revisions, in bytes.
+const MAX_DIFF_CHARS: usize = 20_000;
+
+/// Paper sizes update_document_style accepts, portrait, in points.
+const PAGE_SIZES: [(&str, f64, f64); 6] = [
+    ("LETTER", 612.0, 792.0),
+    ("LEGAL", 612.0, 1008.0),
+    ("TABLOID", 792.0, 1224.0),
+    ("A3", 841.89, 1190.55),
+    ("A4", 595.28, 841.89),
+    ("A5", 419.53, 595.28),
+];
+
+/// Characters of heading text kept below `Detail::Full`.
+const TEXT_PREVIEW_CHARS: usize = 200;
+
+const API: GoogleApi = GoogleApi {
+    name: "Google Docs",
+    scope: "https://www.googleapis.com/auth/documents",
+};
+
+const DRIVE_API: GoogleApi = GoogleApi {
+    name: "Google Drive",
+    scope: "https://www.googleapis.com/auth/drive",
+};
 
 /// Make a Google Docs API call.
 fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, String> {
@@ -16,35 +113,61 @@ fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, Stri
     } else {
         format!("{}/{}", DOCS_API_BASE, path)
     };
+    http_call(&API, method, &url, body)
+}
+
+/// Make a Google Drive API call (used for comments).
+fn drive_call(method: &str, path: &str, body: Option<&str>) -> Result<String, String> {
+    let url = format!("{}/{}", DRIVE_API_BASE, path);
+    http_call(&DRIVE_API, method, &url, body)
+}
 
+fn http_call(
+    api: &GoogleApi,
+    method: &str,
+    url: &str,
+    body: Option<&str>,
+) -> Result<String, String> {
     let headers = if body.is_some() {
         r#"{"Content-Type": "application/json"}"#
     } else {
         "{}"
     };
 
-    let body_bytes = body.map(|b| b.as_bytes().to_vec());
+    let response = http_bytes(api, method, url, headers, body.map(str::as_bytes))?;
+
+    if response.is_empty() {
+        return Ok(String::new());
+    }
+
+    String::from_utf8(response).map_err(|e| format!("Invalid UTF-8 in response: {}", e))
+}
 
+/// Make an API call and return the raw response body.
+fn http_bytes(
+    api: &GoogleApi,
+    method: &str,
+    url: &str,
+    headers: &str,
+    body: Option<&[u8]>,
+) -> Result<Vec<u8>, String> {
     host::log(
         host::LogLevel::Debug,
-        &format!("Google Docs API: {} {}", method, url),
+        &format!("{} API: {} {}", api.name, method, url),
     );
 
-    let response = host::http_request(method, &url, headers, body_bytes.as_deref())?;
+    let response = host::http_request(method, url, headers, body)?;
 
     if response.status < 200 || response.status >= 300 {
-        let body_text = String::from_utf8_lossy(&response.body);
-        return Err(format!(
-            "Google Docs API returned status {}: {}",
-            response.status, body_text
+        return Err(api_error(
+            api,
+            response.status,
+            &response.headers_json,
+            &response.body,
         ));
     }
 
-    if response.body.is_empty() {
-        return Ok(String::new());
-    }
-
-    String::from_utf8(response.body).map_err(|e| format!("Invalid UTF-8 in response: {}", e))
+    Ok(response.body)
 }
 
 /// Send a batchUpdate to the document and return the parsed response.
@@ -85,8 +208,12 @@ pub fn create_document(title: &str) -> Result<CreateDocumentResult, String> {
 }
 
 /// Get document metadata.
-pub fn get_document(document_id: &str) -> Result<DocumentMetadata, String> {
-    let path = url_encode(document_id);
+pub fn get_document(document_id: &str, detail: Detail) -> Result<DocumentMetadata, String> {
+    let fields = match detail {
+        Detail::Summary => SUMMARY_FIELDS,
+        Detail::Standard | Detail::Full => OUTLINE_FIELDS,
+    };
+    let path = format!("{}?fields={}", url_encode(document_id), url_encode(fields));
 
     let response = api_call("GET", &path, None)?;
     let parsed: serde_json::Value =
@@ -99,9 +226,20 @@ pub fn get_document(document_id: &str) -> Result<DocumentMetadata, String> {
         .and_then(|el| el["endIndex"].as_i64())
         .unwrap_or(1);
 
-    // Extract named ranges
+    Ok(DocumentMetadata {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        title: parsed["title"].as_str().unwrap_or("").to_string(),
+        revision_id: parsed["revisionId"].as_str().unwrap_or("").to_string(),
+        body_length,
+        named_ranges: parse_named_ranges(&parsed),
+        paragraphs: parse_paragraphs(&parsed["body"]["content"], detail),
+    })
+}
+
+/// Every range of every named range, without text.
+fn parse_named_ranges(document: &serde_json::Value) -> Vec<DocumentNamedRange> {
     let mut named_ranges = Vec::new();
-    if let Some(nr_map) = parsed["namedRanges"].as_object() {
+    if let Some(nr_map) = document["namedRanges"].as_object() {
         for (_name, nr_group) in nr_map {
             if let Some(ranges) = nr_group["namedRanges"].as_array() {
                 for nr in ranges {
@@ -114,6 +252,7 @@ pub fn get_document(document_id: &str) -> Result<DocumentMetadata, String> {
                                 named_range_id: id.clone(),
                                 start_index: range["startIndex"].as_i64().unwrap_or(0),
                                 end_index: range["endIndex"].as_i64().unwrap_or(0),
+                                text: None,
                             });
                         }
                     }
@@ -121,14 +260,62 @@ pub fn get_document(document_id: &str) -> Result<DocumentMetadata, String> {
             }
         }
     }
+    named_ranges
+}
 
-    Ok(DocumentMetadata {
-        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
-        title: parsed["title"].as_str().unwrap_or("").to_string(),
-        revision_id: parsed["revisionId"].as_str().unwrap_or("").to_string(),
-        body_length,
-        named_ranges,
-    })
+/// Top-level paragraphs for `detail`: headings with previews, or all
+/// non-empty paragraphs in full.
+fn parse_paragraphs(content: &serde_json::Value, detail: Detail) -> Vec<DocumentParagraph> {
+    let Some(content) = content.as_array() else {
+        return Vec::new();
+    };
+    content
+        .iter()
+        .filter_map(|el| {
+            let para = el.get("paragraph")?;
+            let style = para["paragraphStyle"]["namedStyleType"]
+                .as_str()
+                .unwrap_or("NORMAL_TEXT");
+            let is_heading =
+                style.starts_with("HEADING_") || style == "TITLE" || style == "SUBTITLE";
+            if detail == Detail::Summary || (detail == Detail::Standard && !is_heading) {
+                return None;
+            }
+
+            let mut text = String::new();
+            extract_text_from_elements(std::slice::from_ref(el), &mut text);
+            let text = match detail {
+                Detail::Full => text.trim_end_matches('\n').to_string(),
+                _ => preview(&text, TEXT_PREVIEW_CHARS),
+            };
+            if text.trim().is_empty() {
+                return None;
+            }
+
+            Some(DocumentParagraph {
+                style: style.to_string(),
+                start_index: el["startIndex"].as_i64().unwrap_or(0),
+                end_index: el["endIndex"].as_i64().unwrap_or(0),
+                text,
+            })
+        })
+        .collect()
+}
+
+/// The first `max_chars` characters of `text`, trimmed, with an ellipsis if
+/// anything was cut.
+fn preview(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => format!("{}…", text[..cut].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+/// Get a document as the raw API response, limited to a field mask.
+pub fn get_document_fields(document_id: &str, fields: &str) -> Result<String, String> {
+    let path = format!("{}?fields={}", url_encode(document_id), url_encode(fields));
+    api_call("GET", &path, None)
 }
 
 /// Read the document body as plain text by walking the structural elements.
@@ -183,6 +370,209 @@ fn extract_text_from_elements(elements: &[serde_json::Value], out: &mut String)
     }
 }
 
+/// Get the heading hierarchy with the index range of each section.
+pub fn get_outline(document_id: &str) -> Result<OutlineResult, String> {
+    let path = format!(
+        "{}?fields={}",
+        url_encode(document_id),
+        url_encode(OUTLINE_FIELDS)
+    );
+
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    let content = parsed["body"]["content"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let body_length = body_end(&content);
+
+    Ok(OutlineResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        title: parsed["title"].as_str().unwrap_or("").to_string(),
+        revision_id: parsed["revisionId"].as_str().unwrap_or("").to_string(),
+        body_length,
+        headings: nest_headings(find_headings(&content, true)),
+    })
+}
+
+/// Read the plain text of the section under the heading matching `heading`.
+pub fn read_section(
+    document_id: &str,
+    heading: &str,
+    include_subsections: bool,
+) -> Result<SectionResult, String> {
+    let path = format!(
+        "{}?fields={}",
+        url_encode(document_id),
+        url_encode(SECTION_FIELDS)
+    );
+
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    let content = parsed["body"]["content"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let headings = find_headings(&content, include_subsections);
+    let found = match_heading(&headings, heading)?;
+
+    Ok(SectionResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: parsed["revisionId"].as_str().unwrap_or("").to_string(),
+        heading: found.text.clone(),
+        level: found.level,
+        heading_start_index: found.start_index,
+        start_index: found.heading_end_index,
+        end_index: found.end_index,
+        text: section_text(&content, found.heading_end_index, found.end_index),
+    })
+}
+
+/// End index of the body: the last element's end.
+fn body_end(content: &[serde_json::Value]) -> i64 {
+    content
+        .last()
+        .and_then(|el| el["endIndex"].as_i64())
+        .unwrap_or(1)
+}
+
+/// Outline level of a paragraph style: 0 for the title, 1-6 for headings.
+fn heading_level(style: &str) -> Option<u8> {
+    match style {
+        "TITLE" => Some(0),
+        _ => style
+            .strip_prefix("HEADING_")?
+            .parse()
+            .ok()
+            .filter(|level| (1..=6).contains(level)),
+    }
+}
+
+/// Top-level headings in document order, without children. A section ends
+/// at the next heading at the same or a higher level, or at any heading
+/// unless `include_subsections`.
+fn find_headings(content: &[serde_json::Value], include_subsections: bool) -> Vec<OutlineHeading> {
+    let mut headings: Vec<OutlineHeading> = content
+        .iter()
+        .filter_map(|el| {
+            let style = el["paragraph"]["paragraphStyle"]["namedStyleType"].as_str()?;
+            let level = heading_level(style)?;
+            let mut text = String::new();
+            extract_text_from_elements(std::slice::from_ref(el), &mut text);
+            let text = text.trim().to_string();
+            if text.is_empty() {
+                return None;
+            }
+            Some(OutlineHeading {
+                level,
+                text,
+                start_index: el["startIndex"].as_i64().unwrap_or(0),
+                heading_end_index: el["endIndex"].as_i64().unwrap_or(0),
+                end_index: 0,
+                children: Vec::new(),
+            })
+        })
+        .collect();
+
+    let end = body_end(content);
+    for i in 0..headings.len() {
+        let level = headings[i].level;
+        headings[i].end_index = headings[i + 1..]
+            .iter()
+            .find(|next| !include_subsections || next.level <= level)
+            .map_or(end, |next| next.start_index);
+    }
+    headings
+}
+
+/// Nest headings in document order under the nearest preceding heading at
+/// a higher level.
+fn nest_headings(flat: Vec<OutlineHeading>) -> Vec<OutlineHeading> {
+    fn close(stack: &mut Vec<OutlineHeading>, roots: &mut Vec<OutlineHeading>) {
+        if let Some(done) = stack.pop() {
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(done),
+                None => roots.push(done),
+            }
+        }
+    }
+
+    let mut roots = Vec::new();
+    let mut stack: Vec<OutlineHeading> = Vec::new();
+    for heading in flat {
+        while stack.last().is_some_and(|open| open.level >= heading.level) {
+            close(&mut stack, &mut roots);
+        }
+        stack.push(heading);
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut roots);
+    }
+    roots
+}
+
+/// The heading whose text equals `wanted` (ignoring case and spacing), or
+/// the only one containing it.
+fn match_heading<'a>(
+    headings: &'a [OutlineHeading],
+    wanted: &str,
+) -> Result<&'a OutlineHeading, String> {
+    let normalize = |s: &str| {
+        s.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    };
+    let wanted = normalize(wanted);
+
+    if let Some(exact) = headings.iter().find(|h| normalize(&h.text) == wanted) {
+        return Ok(exact);
+    }
+    let partial: Vec<&OutlineHeading> = headings
+        .iter()
+        .filter(|h| normalize(&h.text).contains(&wanted))
+        .collect();
+    match partial.as_slice() {
+        [only] => Ok(only),
+        [] => Err(format!(
+            "No heading matches '{}'. Headings: {}",
+            wanted,
+            heading_list(headings.iter())
+        )),
+        several => Err(format!(
+            "'{}' matches several headings: {}. Use the full heading text.",
+            wanted,
+            heading_list(several.iter().copied())
+        )),
+    }
+}
+
+/// Quoted heading texts for error messages.
+fn heading_list<'a>(headings: impl Iterator<Item = &'a OutlineHeading>) -> String {
+    let list: Vec<String> = headings.map(|h| format!("'{}'", h.text)).collect();
+    if list.is_empty() {
+        "none".to_string()
+    } else {
+        list.join(", ")
+    }
+}
+
+/// Plain text of the body elements starting within `start..end`.
+fn section_text(content: &[serde_json::Value], start: i64, end: i64) -> String {
+    let mut text = String::new();
+    for el in content {
+        let el_start = el["startIndex"].as_i64().unwrap_or(0);
+        if el_start >= start && el_start < end {
+            extract_text_from_elements(std::slice::from_ref(el), &mut text);
+        }
+    }
+    text.trim_end_matches('\n').to_string()
+}
+
 /// Insert text at a position.
 pub fn insert_text(
     document_id: &str,
@@ -190,7 +580,20 @@ pub fn insert_text(
     index: i64,
     segment_id: &str,
 ) -> Result<UpdateResult, String> {
-    let request = if index < 0 {
+    let request = insert_text_request(text, index, segment_id);
+
+    let parsed = batch_update_raw(document_id, vec![request])?;
+
+    Ok(UpdateResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: extract_revision_id(&parsed),
+    })
+}
+
+/// An insertText request at `index`, or at the end of the segment when
+/// negative.
+fn insert_text_request(text: &str, index: i64, segment_id: &str) -> serde_json::Value {
+    if index < 0 {
         // Append at end of segment
         let mut loc = serde_json::json!({});
         if !segment_id.is_empty() {
@@ -213,14 +616,7 @@ pub fn insert_text(
                 "location": loc,
             }
         })
-    };
-
-    let parsed = batch_update_raw(document_id, vec![request])?;
-
-    Ok(UpdateResult {
-        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
-        revision_id: extract_revision_id(&parsed),
-    })
+    }
 }
 
 /// Delete content in a range.
@@ -230,6 +626,18 @@ pub fn delete_content(
     end_index: i64,
     segment_id: &str,
 ) -> Result<UpdateResult, String> {
+    let request = delete_range_request(start_index, end_index, segment_id);
+
+    let parsed = batch_update_raw(document_id, vec![request])?;
+
+    Ok(UpdateResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: extract_revision_id(&parsed),
+    })
+}
+
+/// A deleteContentRange request.
+fn delete_range_request(start_index: i64, end_index: i64, segment_id: &str) -> serde_json::Value {
     let mut range = serde_json::json!({
         "startIndex": start_index,
         "endIndex": end_index,
@@ -238,15 +646,8 @@ pub fn delete_content(
         range["segmentId"] = serde_json::Value::String(segment_id.to_string());
     }
 
-    let request = serde_json::json!({
+    serde_json::json!({
         "deleteContentRange": { "range": range }
-    });
-
-    let parsed = batch_update_raw(document_id, vec![request])?;
-
-    Ok(UpdateResult {
-        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
-        revision_id: extract_revision_id(&parsed),
     })
 }
 
@@ -317,6 +718,18 @@ pub struct FormatTextOptions<'a> {
 
 /// Format text in a range.
 pub fn format_text(opts: FormatTextOptions<'_>) -> Result<UpdateResult, String> {
+    let request = text_style_request(&opts)?;
+
+    let parsed = batch_update_raw(opts.document_id, vec![request])?;
+
+    Ok(UpdateResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: extract_revision_id(&parsed),
+    })
+}
+
+/// An updateTextStyle request setting the options given in `opts`.
+fn text_style_request(opts: &FormatTextOptions<'_>) -> Result<serde_json::Value, String> {
     let mut style = serde_json::json!({});
     let mut fields = Vec::new();
 
@@ -361,7 +774,7 @@ pub fn format_text(opts: FormatTextOptions<'_>) -> Result<UpdateResult, String>
         return Err("No formatting options specified".to_string());
     }
 
-    let request = serde_json::json!({
+    Ok(serde_json::json!({
         "updateTextStyle": {
             "range": {
                 "startIndex": opts.start_index,
@@ -370,9 +783,22 @@ pub fn format_text(opts: FormatTextOptions<'_>) -> Result<UpdateResult, String>
             "textStyle": style,
             "fields": fields.join(","),
         }
-    });
+    }))
+}
 
-    let parsed = batch_update_raw(opts.document_id, vec![request])?;
+/// Format paragraph style.
+pub fn format_paragraph(
+    document_id: &str,
+    start_index: i64,
+    end_index: i64,
+    named_style: Option<&str>,
+    alignment: Option<&str>,
+    line_spacing: Option<f64>,
+) -> Result<UpdateResult, String> {
+    let request =
+        paragraph_style_request(start_index, end_index, named_style, alignment, line_spacing)?;
+
+    let parsed = batch_update_raw(document_id, vec![request])?;
 
     Ok(UpdateResult {
         document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
@@ -380,15 +806,14 @@ pub fn format_text(opts: FormatTextOptions<'_>) -> Result<UpdateResult, String>
     })
 }
 
-/// Format paragraph style.
-pub fn format_paragraph(
-    document_id: &str,
+/// An updateParagraphStyle request setting the given options.
+fn paragraph_style_request(
     start_index: i64,
     end_index: i64,
     named_style: Option<&str>,
     alignment: Option<&str>,
     line_spacing: Option<f64>,
-) -> Result<UpdateResult, String> {
+) -> Result<serde_json::Value, String> {
     let mut para_style = serde_json::json!({});
     let mut fields = Vec::new();
 
@@ -409,7 +834,7 @@ pub fn format_paragraph(
         return Err("No paragraph style options specified".to_string());
     }
 
-    let request = serde_json::json!({
+    Ok(serde_json::json!({
         "updateParagraphStyle": {
             "range": {
                 "startIndex": start_index,
@@ -418,14 +843,7 @@ pub fn format_paragraph(
             "paragraphStyle": para_style,
             "fields": fields.join(","),
         }
-    });
-
-    let parsed = batch_update_raw(document_id, vec![request])?;
-
-    Ok(UpdateResult {
-        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
-        revision_id: extract_revision_id(&parsed),
-    })
+    }))
 }
 
 /// Insert a table at a position.
@@ -451,24 +869,47 @@ pub fn insert_table(
     })
 }
 
-/// Create a bulleted or numbered list from paragraphs in a range.
-pub fn create_list(
-    document_id: &str,
-    start_index: i64,
-    end_index: i64,
-    bullet_preset: &str,
-) -> Result<UpdateResult, String> {
+/// Parameters for document-wide page setup.
+pub struct DocumentStyleOptions<'a> {
+    pub document_id: &'a str,
+    pub page_size: Option<&'a str>,
+    pub orientation: Option<&'a str>,
+    pub margin_top: Option<f64>,
+    pub margin_bottom: Option<f64>,
+    pub margin_left: Option<f64>,
+    pub margin_right: Option<f64>,
+}
+
+/// Set the page size, orientation and margins of the whole document.
+pub fn update_document_style(opts: DocumentStyleOptions<'_>) -> Result<UpdateResult, String> {
+    // Turning the page around without a new size needs the current one
+    let current = match (opts.page_size, opts.orientation) {
+        (None, Some(_)) => {
+            let path = format!(
+                "{}?fields={}",
+                url_encode(opts.document_id),
+                url_encode(PAGE_SIZE_FIELDS)
+            );
+            let response = api_call("GET", &path, None)?;
+            let parsed: serde_json::Value = serde_json::from_str(&response)
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+            let size = &parsed["documentStyle"]["pageSize"];
+            size["width"]["magnitude"]
+                .as_f64()
+                .zip(size["height"]["magnitude"].as_f64())
+        }
+        _ => None,
+    };
+
+    let (style, fields) = document_style(&opts, current)?;
     let request = serde_json::json!({
-        "createParagraphBullets": {
-            "range": {
-                "startIndex": start_index,
-                "endIndex": end_index,
-            },
-            "bulletPreset": bullet_preset,
+        "updateDocumentStyle": {
+            "documentStyle": style,
+            "fields": fields,
         }
     });
 
-    let parsed = batch_update_raw(document_id, vec![request])?;
+    let parsed = batch_update_raw(opts.document_id, vec![request])?;
 
     Ok(UpdateResult {
         document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
@@ -476,26 +917,1394 @@ pub fn create_list(
     })
 }
 
-/// Execute a raw batch update with arbitrary requests.
-pub fn batch_update(
+/// The documentStyle and field mask for `opts`. `current` is the page size
+/// the document has, used when only the orientation changes.
+fn document_style(
+    opts: &DocumentStyleOptions<'_>,
+    current: Option<(f64, f64)>,
+) -> Result<(serde_json::Value, String), String> {
+    let mut style = serde_json::json!({});
+    let mut fields = Vec::new();
+
+    if opts.page_size.is_some() || opts.orientation.is_some() {
+        let (mut width, mut height) = match opts.page_size {
+            Some(name) => PAGE_SIZES
+                .iter()
+                .find(|(size, _, _)| size.eq_ignore_ascii_case(name))
+                .map(|(_, width, height)| (*width, *height))
+                .ok_or_else(|| {
+                    let names: Vec<&str> = PAGE_SIZES.iter().map(|(size, _, _)| *size).collect();
+                    format!(
+                        "Unknown page_size '{}', expected one of {}",
+                        name,
+                        names.join(", ")
+                    )
+                })?,
+            None => current.unwrap_or((PAGE_SIZES[0].1, PAGE_SIZES[0].2)),
+        };
+        let landscape = match opts.orientation {
+            Some(o) if o.eq_ignore_ascii_case("LANDSCAPE") => Some(true),
+            Some(o) if o.eq_ignore_ascii_case("PORTRAIT") => Some(false),
+            Some(o) => {
+                return Err(format!(
+                    "Unknown orientation '{}', expected PORTRAIT or LANDSCAPE",
+                    o
+                ))
+            }
+            None => None,
+        };
+        if let Some(landscape) = landscape {
+            if landscape != (width > height) {
+                std::mem::swap(&mut width, &mut height);
+            }
+        }
+        style["pageSize"] = serde_json::json!({
+            "width": { "magnitude": width, "unit": "PT" },
+            "height": { "magnitude": height, "unit": "PT" },
+        });
+        fields.push("pageSize");
+    }
+
+    let margins = [
+        ("marginTop", opts.margin_top),
+        ("marginBottom", opts.margin_bottom),
+        ("marginLeft", opts.margin_left),
+        ("marginRight", opts.margin_right),
+    ];
+    for (field, margin) in margins {
+        if let Some(points) = margin {
+            if points < 0.0 {
+                return Err(format!("Margins can't be negative (got {} pt)", points));
+            }
+            style[field] = serde_json::json!({ "magnitude": points, "unit": "PT" });
+            fields.push(field);
+        }
+    }
+
+    if fields.is_empty() {
+        return Err("No document style options specified".to_string());
+    }
+    Ok((style, fields.join(",")))
+}
+
+/// Insert a section break, so what follows starts a new section.
+pub fn insert_section_break(
     document_id: &str,
-    requests: Vec<serde_json::Value>,
-) -> Result<BatchUpdateResult, String> {
-    let parsed = batch_update_raw(document_id, requests)?;
+    index: i64,
+    section_type: &str,
+) -> Result<UpdateResult, String> {
+    let section_type = section_type.to_ascii_uppercase();
+    if section_type != "NEXT_PAGE" && section_type != "CONTINUOUS" {
+        return Err(format!(
+            "Unknown section_type '{}', expected NEXT_PAGE or CONTINUOUS",
+            section_type
+        ));
+    }
+    let mut request = body_location(index);
+    request["sectionType"] = serde_json::Value::String(section_type);
 
-    let replies = parsed["replies"]
-        .as_array()
-        .map(|arr| arr.to_vec())
-        .unwrap_or_default();
+    let parsed = batch_update_raw(
+        document_id,
+        vec![serde_json::json!({ "insertSectionBreak": request })],
+    )?;
 
-    Ok(BatchUpdateResult {
+    Ok(UpdateResult {
         document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
         revision_id: extract_revision_id(&parsed),
-        replies,
     })
 }
 
-/// Minimal percent-encoding for URL path segments.
+/// Insert a page break.
+pub fn insert_page_break(document_id: &str, index: i64) -> Result<UpdateResult, String> {
+    let request = serde_json::json!({ "insertPageBreak": body_location(index) });
+
+    let parsed = batch_update_raw(document_id, vec![request])?;
+
+    Ok(UpdateResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: extract_revision_id(&parsed),
+    })
+}
+
+/// Where a break goes: at `index` in the body, or at its end when negative.
+fn body_location(index: i64) -> serde_json::Value {
+    if index < 0 {
+        serde_json::json!({ "endOfSegmentLocation": {} })
+    } else {
+        serde_json::json!({ "location": { "index": index } })
+    }
+}
+
+/// Read a table's cells with their text and content ranges.
+pub fn read_table(document_id: &str, table_index: usize) -> Result<TableResult, String> {
+    let path = format!(
+        "{}?fields={}",
+        url_encode(document_id),
+        url_encode(TABLE_FIELDS)
+    );
+
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    parse_table(&parsed, table_index)
+}
+
+/// Replace the text of one table cell.
+pub fn write_table_cell(
+    document_id: &str,
+    table_index: usize,
+    row: usize,
+    column: usize,
+    text: &str,
+) -> Result<UpdateResult, String> {
+    let table = read_table(document_id, table_index)?;
+    let cell = table
+        .cells
+        .get(row)
+        .and_then(|cells| cells.get(column))
+        .ok_or_else(|| {
+            format!(
+                "Cell ({}, {}) is outside table {}, which has {} rows and {} columns",
+                row, column, table_index, table.rows, table.columns
+            )
+        })?;
+
+    let requests = cell_requests(cell, text);
+    if requests.is_empty() {
+        return Ok(UpdateResult {
+            document_id: table.document_id,
+            revision_id: table.revision_id,
+        });
+    }
+    let parsed = batch_update_raw(document_id, requests)?;
+
+    Ok(UpdateResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: extract_revision_id(&parsed),
+    })
+}
+
+/// Add a row below the last row of a table and fill it with `values`.
+pub fn append_table_row(
+    document_id: &str,
+    table_index: usize,
+    values: &[String],
+) -> Result<AppendRowResult, String> {
+    let table = read_table(document_id, table_index)?;
+    if values.len() > table.columns {
+        return Err(format!(
+            "Got {} values for table {}, which has {} columns",
+            values.len(),
+            table_index,
+            table.columns
+        ));
+    }
+
+    let request = serde_json::json!({
+        "insertTableRow": {
+            "tableCellLocation": {
+                "tableStartLocation": { "index": table.start_index },
+                "rowIndex": table.rows.saturating_sub(1),
+                "columnIndex": 0,
+            },
+            "insertBelow": true,
+        }
+    });
+    let mut parsed = batch_update_raw(document_id, vec![request])?;
+
+    // The new row's cell indices are only known once it exists
+    let table = read_table(document_id, table_index)?;
+    let row = table.cells.last().map(Vec::as_slice).unwrap_or_default();
+    let requests = fill_row_requests(row, values);
+    if !requests.is_empty() {
+        parsed = batch_update_raw(document_id, requests)?;
+    }
+
+    Ok(AppendRowResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: extract_revision_id(&parsed),
+        row: table.rows.saturating_sub(1),
+    })
+}
+
+/// Find the body's `table_index`th table and read its cells.
+fn parse_table(document: &serde_json::Value, table_index: usize) -> Result<TableResult, String> {
+    let content = document["body"]["content"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let tables: Vec<&serde_json::Value> = content
+        .iter()
+        .filter(|el| el.get("table").is_some())
+        .collect();
+    let el = tables.get(table_index).ok_or_else(|| match tables.len() {
+        0 => "The document has no tables".to_string(),
+        n => format!(
+            "Table {} not found: the document has {} (numbered from 0)",
+            table_index, n
+        ),
+    })?;
+
+    let cells: Vec<Vec<TableCell>> = el["table"]["tableRows"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|row| {
+            row["tableCells"]
+                .as_array()
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .map(parse_cell)
+                .collect()
+        })
+        .collect();
+
+    Ok(TableResult {
+        document_id: document["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: document["revisionId"].as_str().unwrap_or("").to_string(),
+        table_index,
+        start_index: el["startIndex"].as_i64().unwrap_or(0),
+        end_index: el["endIndex"].as_i64().unwrap_or(0),
+        rows: cells.len(),
+        columns: cells.iter().map(Vec::len).max().unwrap_or(0),
+        cells,
+    })
+}
+
+/// A cell's text and content range.
+fn parse_cell(cell: &serde_json::Value) -> TableCell {
+    let content = cell["content"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut text = String::new();
+    extract_text_from_elements(content, &mut text);
+
+    TableCell {
+        text: text.trim_end_matches('\n').to_string(),
+        start_index: content
+            .first()
+            .and_then(|el| el["startIndex"].as_i64())
+            .unwrap_or(0),
+        end_index: content
+            .last()
+            .and_then(|el| el["endIndex"].as_i64())
+            .unwrap_or(0),
+    }
+}
+
+/// Requests replacing a cell's text with `text`. The cell's final newline
+/// can't be deleted, so it is kept.
+fn cell_requests(cell: &TableCell, text: &str) -> Vec<serde_json::Value> {
+    let mut requests = Vec::new();
+    if cell.end_index - 1 > cell.start_index {
+        requests.push(serde_json::json!({
+            "deleteContentRange": {
+                "range": {
+                    "startIndex": cell.start_index,
+                    "endIndex": cell.end_index - 1,
+                }
+            }
+        }));
+    }
+    if !text.is_empty() {
+        requests.push(serde_json::json!({
+            "insertText": {
+                "text": text,
+                "location": { "index": cell.start_index },
+            }
+        }));
+    }
+    requests
+}
+
+/// Requests filling a row's cells, last column first so each insert leaves
+/// the earlier cells' indices unchanged.
+fn fill_row_requests(row: &[TableCell], values: &[String]) -> Vec<serde_json::Value> {
+    row.iter()
+        .zip(values)
+        .rev()
+        .flat_map(|(cell, value)| cell_requests(cell, value))
+        .collect()
+}
+
+/// Insert a table of contents linking to the headings up to `max_level`.
+pub fn generate_toc(
+    document_id: &str,
+    index: Option<i64>,
+    max_level: u8,
+    title: Option<&str>,
+) -> Result<TocResult, String> {
+    check_toc_level(max_level)?;
+    let document = read_toc_fields(document_id)?;
+    if find_toc(&document).is_some() {
+        return Err(
+            "The document already has a table of contents; use refresh_toc to rebuild it"
+                .to_string(),
+        );
+    }
+    let content = body_content(&document);
+    let index = index.unwrap_or_else(|| default_toc_index(content));
+
+    let entries = toc_entries(content, max_level)?;
+    let (requests, end_index) = toc_requests(&entries, title, index, max_level);
+    let parsed = batch_update_raw(document_id, requests)?;
+
+    Ok(TocResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: extract_revision_id(&parsed),
+        start_index: index,
+        end_index,
+        max_level,
+        entries,
+    })
+}
+
+/// Rebuild the table of contents made by generate_toc from the current
+/// headings, keeping its position and, unless overridden, its options.
+pub fn refresh_toc(
+    document_id: &str,
+    max_level: Option<u8>,
+    title: Option<&str>,
+) -> Result<TocResult, String> {
+    let document = read_toc_fields(document_id)?;
+    let existing = find_toc(&document).ok_or(
+        "The document has no table of contents made by generate_toc; use generate_toc first",
+    )?;
+    let max_level = max_level.unwrap_or(existing.max_level);
+    check_toc_level(max_level)?;
+    let title = title.or(existing.title.as_deref());
+
+    let content = body_content(&document);
+    let entries = toc_entries(content, max_level)?;
+
+    let mut requests = vec![serde_json::json!({
+        "deleteNamedRange": { "name": existing.name }
+    })];
+    // The body's final newline can't be deleted
+    let end = existing.end_index.min(body_end(content) - 1);
+    if end > existing.start_index {
+        requests.push(serde_json::json!({
+            "deleteContentRange": {
+                "range": { "startIndex": existing.start_index, "endIndex": end }
+            }
+        }));
+    }
+    let (insert, end_index) = toc_requests(&entries, title, existing.start_index, max_level);
+    requests.extend(insert);
+    let parsed = batch_update_raw(document_id, requests)?;
+
+    Ok(TocResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: extract_revision_id(&parsed),
+        start_index: existing.start_index,
+        end_index,
+        max_level,
+        entries,
+    })
+}
+
+/// A table of contents made by generate_toc.
+struct ExistingToc {
+    /// Name of the named range around it.
+    name: String,
+    start_index: i64,
+    end_index: i64,
+    max_level: u8,
+    title: Option<String>,
+}
+
+fn check_toc_level(max_level: u8) -> Result<(), String> {
+    if (1..=6).contains(&max_level) {
+        Ok(())
+    } else {
+        Err(format!("max_level must be 1-6, got {}", max_level))
+    }
+}
+
+fn read_toc_fields(document_id: &str) -> Result<serde_json::Value, String> {
+    let path = format!(
+        "{}?fields={}",
+        url_encode(document_id),
+        url_encode(TOC_FIELDS)
+    );
+    let response = api_call("GET", &path, None)?;
+    serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+fn body_content(document: &serde_json::Value) -> &[serde_json::Value] {
+    document["body"]["content"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// The table of contents recorded in the document's named ranges, if any.
+fn find_toc(document: &serde_json::Value) -> Option<ExistingToc> {
+    let (name, group) = document["namedRanges"]
+        .as_object()?
+        .iter()
+        .find(|(name, _)| name.starts_with(TOC_RANGE_PREFIX))?;
+    let range = &group["namedRanges"][0]["ranges"][0];
+    let mut options = name[TOC_RANGE_PREFIX.len()..].splitn(2, '|');
+    Some(ExistingToc {
+        name: name.clone(),
+        start_index: range["startIndex"].as_i64()?,
+        end_index: range["endIndex"].as_i64()?,
+        max_level: options.next()?.parse().ok()?,
+        title: options.next().filter(|t| !t.is_empty()).map(String::from),
+    })
+}
+
+/// Where a table of contents goes by default: after the title and subtitle
+/// at the top of the document.
+fn default_toc_index(content: &[serde_json::Value]) -> i64 {
+    content
+        .iter()
+        .filter(|el| el.get("sectionBreak").is_none())
+        .find(|el| {
+            let style = el["paragraph"]["paragraphStyle"]["namedStyleType"].as_str();
+            !matches!(style, Some("TITLE" | "SUBTITLE"))
+        })
+        .and_then(|el| el["startIndex"].as_i64())
+        .unwrap_or(1)
+}
+
+/// Headings from level 1 to `max_level`, in document order.
+fn toc_entries(content: &[serde_json::Value], max_level: u8) -> Result<Vec<TocEntry>, String> {
+    let entries: Vec<TocEntry> = content
+        .iter()
+        .filter_map(|el| {
+            let style = &el["paragraph"]["paragraphStyle"];
+            let level = heading_level(style["namedStyleType"].as_str()?)?;
+            if level == 0 || level > max_level {
+                return None;
+            }
+            let mut text = String::new();
+            extract_text_from_elements(std::slice::from_ref(el), &mut text);
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            if text.is_empty() {
+                return None;
+            }
+            Some(TocEntry {
+                level,
+                text,
+                heading_id: style["headingId"].as_str().map(String::from),
+            })
+        })
+        .collect();
+    if entries.is_empty() {
+        return Err(format!(
+            "The document has no headings up to HEADING_{} to list",
+            max_level
+        ));
+    }
+    Ok(entries)
+}
+
+/// Requests inserting a table of contents at `index`, and where it ends.
+/// Each entry is a normal paragraph, indented by level and linked to its
+/// heading; a named range around the whole marks it for refresh_toc.
+fn toc_requests(
+    entries: &[TocEntry],
+    title: Option<&str>,
+    index: i64,
+    max_level: u8,
+) -> (Vec<serde_json::Value>, i64) {
+    let title = title.map(str::trim).filter(|t| !t.is_empty());
+    let mut text = String::new();
+    if let Some(title) = title {
+        text.push_str(title);
+        text.push('\n');
+    }
+    let first_entry = index + utf16_len(&text);
+    for entry in entries {
+        text.push_str(&entry.text);
+        text.push('\n');
+    }
+    let end = index + utf16_len(&text);
+
+    let mut requests = vec![
+        serde_json::json!({
+            "insertText": { "text": text, "location": { "index": index } }
+        }),
+        // Inserted paragraphs take the style of the one they were inserted into
+        serde_json::json!({
+            "updateParagraphStyle": {
+                "range": { "startIndex": index, "endIndex": end },
+                "paragraphStyle": {
+                    "namedStyleType": "NORMAL_TEXT",
+                    "indentStart": { "magnitude": 0, "unit": "PT" },
+                    "indentFirstLine": { "magnitude": 0, "unit": "PT" },
+                },
+                "fields": "namedStyleType,indentStart,indentFirstLine",
+            }
+        }),
+    ];
+    if title.is_some() {
+        requests.push(serde_json::json!({
+            "updateTextStyle": {
+                "range": { "startIndex": index, "endIndex": first_entry - 1 },
+                "textStyle": { "bold": true },
+                "fields": "bold",
+            }
+        }));
+    }
+
+    let mut start = first_entry;
+    for entry in entries {
+        let text_end = start + utf16_len(&entry.text);
+        if entry.level > 1 {
+            let indent = serde_json::json!({
+                "magnitude": TOC_INDENT_PT * f64::from(entry.level - 1),
+                "unit": "PT",
+            });
+            requests.push(serde_json::json!({
+                "updateParagraphStyle": {
+                    "range": { "startIndex": start, "endIndex": text_end },
+                    "paragraphStyle": { "indentStart": indent, "indentFirstLine": indent },
+                    "fields": "indentStart,indentFirstLine",
+                }
+            }));
+        }
+        if let Some(ref heading_id) = entry.heading_id {
+            requests.push(serde_json::json!({
+                "updateTextStyle": {
+                    "range": { "startIndex": start, "endIndex": text_end },
+                    "textStyle": { "link": { "headingId": heading_id } },
+                    "fields": "link",
+                }
+            }));
+        }
+        start = text_end + 1;
+    }
+
+    requests.push(serde_json::json!({
+        "createNamedRange": {
+            "name": format!("{}{}|{}", TOC_RANGE_PREFIX, max_level, title.unwrap_or("")),
+            "range": { "startIndex": index, "endIndex": end },
+        }
+    }));
+    (requests, end)
+}
+
+/// Length in the UTF-16 code units Docs indices count.
+fn utf16_len(s: &str) -> i64 {
+    s.encode_utf16().count() as i64
+}
+
+/// Name a range, or each occurrence of `find`. Names are unique here, so
+/// replace_named_range_content always knows what it replaces.
+pub fn create_named_range(
+    document_id: &str,
+    name: &str,
+    find: Option<&str>,
+    start_index: Option<i64>,
+    end_index: Option<i64>,
+) -> Result<CreateNamedRangeResult, String> {
+    if name.is_empty() || utf16_len(name) > 256 {
+        return Err("Named range names must be 1-256 characters".to_string());
+    }
+    let document = read_named_range_fields(document_id)?;
+    if parse_named_ranges(&document)
+        .iter()
+        .any(|nr| nr.name == name)
+    {
+        return Err(format!(
+            "A named range called '{}' already exists; use replace_named_range_content to \
+             change its text",
+            name
+        ));
+    }
+
+    let ranges = match (find, start_index, end_index) {
+        (Some(find), None, None) => {
+            let ranges = find_ranges(&indexed_chars(body_content(&document)), find);
+            if ranges.is_empty() {
+                return Err(format!("'{}' was not found in the document", find));
+            }
+            ranges
+        }
+        (None, Some(start), Some(end)) if start < end => vec![(start, end)],
+        (None, Some(start), Some(end)) => {
+            return Err(format!(
+                "start_index {} must be less than end_index {}",
+                start, end
+            ));
+        }
+        _ => return Err("Give either find or both start_index and end_index".to_string()),
+    };
+
+    let requests = ranges
+        .iter()
+        .map(|(start, end)| {
+            serde_json::json!({
+                "createNamedRange": {
+                    "name": name,
+                    "range": { "startIndex": start, "endIndex": end },
+                }
+            })
+        })
+        .collect();
+    let parsed = batch_update_raw(document_id, requests)?;
+
+    let replies = parsed["replies"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let named_ranges = ranges
+        .iter()
+        .enumerate()
+        .map(|(i, (start, end))| DocumentNamedRange {
+            name: name.to_string(),
+            named_range_id: replies
+                .get(i)
+                .and_then(|r| r["createNamedRange"]["namedRangeId"].as_str())
+                .unwrap_or("")
+                .to_string(),
+            start_index: *start,
+            end_index: *end,
+            text: None,
+        })
+        .collect();
+
+    Ok(CreateNamedRangeResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: extract_revision_id(&parsed),
+        name: name.to_string(),
+        named_ranges,
+    })
+}
+
+/// List the named ranges with their current indices and text.
+pub fn list_named_ranges(document_id: &str) -> Result<NamedRangesResult, String> {
+    let document = read_named_range_fields(document_id)?;
+    let chars = indexed_chars(body_content(&document));
+
+    let mut named_ranges = parse_named_ranges(&document);
+    named_ranges.sort_by_key(|nr| (nr.start_index, nr.end_index));
+    for nr in &mut named_ranges {
+        nr.text = Some(
+            chars
+                .iter()
+                .filter(|(index, _)| (nr.start_index..nr.end_index).contains(index))
+                .map(|(_, c)| c)
+                .collect(),
+        );
+    }
+
+    Ok(NamedRangesResult {
+        document_id: document["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: document["revisionId"].as_str().unwrap_or("").to_string(),
+        named_ranges,
+    })
+}
+
+/// Replace the text of every range named `name`.
+pub fn replace_named_range_content(
+    document_id: &str,
+    name: &str,
+    text: &str,
+) -> Result<ReplaceNamedRangeResult, String> {
+    // A range left empty is removed, and with it the name
+    if text.is_empty() {
+        return Err(
+            "text must not be empty: an emptied named range is removed. Use delete_content \
+             to clear it instead"
+                .to_string(),
+        );
+    }
+    let document = read_named_range_fields(document_id)?;
+    let named_ranges = parse_named_ranges(&document);
+    let ranges_replaced = named_ranges.iter().filter(|nr| nr.name == name).count();
+    if ranges_replaced == 0 {
+        let mut names: Vec<&str> = named_ranges.iter().map(|nr| nr.name.as_str()).collect();
+        names.sort_unstable();
+        names.dedup();
+        return Err(format!(
+            "No named range called '{}'. Named ranges: {}",
+            name,
+            if names.is_empty() {
+                "none".to_string()
+            } else {
+                names.join(", ")
+            }
+        ));
+    }
+
+    let request = serde_json::json!({
+        "replaceNamedRangeContent": {
+            "namedRangeName": name,
+            "text": text,
+        }
+    });
+    let parsed = batch_update_raw(document_id, vec![request])?;
+
+    Ok(ReplaceNamedRangeResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: extract_revision_id(&parsed),
+        name: name.to_string(),
+        ranges_replaced,
+    })
+}
+
+fn read_named_range_fields(document_id: &str) -> Result<serde_json::Value, String> {
+    let path = format!(
+        "{}?fields={}",
+        url_encode(document_id),
+        url_encode(NAMED_RANGE_FIELDS)
+    );
+    let response = api_call("GET", &path, None)?;
+    serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+/// Every character of the body with its index, table cells included.
+fn indexed_chars(elements: &[serde_json::Value]) -> Vec<(i64, char)> {
+    let mut chars = Vec::new();
+    for el in elements {
+        for pe in el["paragraph"]["elements"].as_array().into_iter().flatten() {
+            let (Some(mut index), Some(content)) =
+                (pe["startIndex"].as_i64(), pe["textRun"]["content"].as_str())
+            else {
+                continue;
+            };
+            for c in content.chars() {
+                chars.push((index, c));
+                index += c.len_utf16() as i64;
+            }
+        }
+        for row in el["table"]["tableRows"].as_array().into_iter().flatten() {
+            for cell in row["tableCells"].as_array().into_iter().flatten() {
+                let content = cell["content"]
+                    .as_array()
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                chars.extend(indexed_chars(content));
+            }
+        }
+    }
+    chars
+}
+
+/// Ranges of the non-overlapping occurrences of `find`.
+fn find_ranges(chars: &[(i64, char)], find: &str) -> Vec<(i64, i64)> {
+    let wanted: Vec<char> = find.chars().collect();
+    let mut ranges = Vec::new();
+    if wanted.is_empty() {
+        return ranges;
+    }
+    let mut i = 0;
+    while i + wanted.len() <= chars.len() {
+        let window = &chars[i..i + wanted.len()];
+        if window.iter().map(|(_, c)| *c).eq(wanted.iter().copied()) {
+            let (last_index, last) = window[window.len() - 1];
+            ranges.push((window[0].0, last_index + last.len_utf16() as i64));
+            i += wanted.len();
+        } else {
+            i += 1;
+        }
+    }
+    ranges
+}
+
+/// List the document's comments with their replies, oldest first.
+pub fn list_comments(document_id: &str, include_resolved: bool) -> Result<CommentsResult, String> {
+    let mut comments = Vec::new();
+    let mut page_token: Option<String> = None;
+    for _ in 0..MAX_COMMENT_PAGES {
+        let mut path = format!(
+            "files/{}/comments?pageSize={}&fields={}",
+            url_encode(document_id),
+            COMMENTS_PAGE_SIZE,
+            url_encode(&format!("nextPageToken,comments({})", COMMENT_FIELDS))
+        );
+        if let Some(ref token) = page_token {
+            path.push_str(&format!("&pageToken={}", url_encode(token)));
+        }
+        let response = drive_call("GET", &path, None)?;
+        let parsed: serde_json::Value = serde_json::from_str(&response)
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        comments.extend(
+            parsed["comments"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|c| c["deleted"].as_bool() != Some(true))
+                .map(parse_comment)
+                .filter(|c| include_resolved || !c.resolved),
+        );
+        page_token = parsed["nextPageToken"].as_str().map(String::from);
+        if page_token.is_none() {
+            break;
+        }
+    }
+
+    Ok(CommentsResult {
+        document_id: document_id.to_string(),
+        truncated: page_token.is_some(),
+        comments,
+    })
+}
+
+/// Comment on the text `quote` (its first occurrence) or at
+/// `start_index..end_index`, or on the whole document if neither is given.
+pub fn add_comment(
+    document_id: &str,
+    content: &str,
+    quote: Option<&str>,
+    start_index: Option<i64>,
+    end_index: Option<i64>,
+) -> Result<AddCommentResult, String> {
+    if content.trim().is_empty() {
+        return Err("content is empty".to_string());
+    }
+    let range = match (quote, start_index, end_index) {
+        (None, None, None) => None,
+        (Some(""), None, None) => return Err("quote is empty".to_string()),
+        (Some(_), None, None) | (None, Some(_), Some(_)) => {
+            let document = read_text_fields(document_id)?;
+            let chars = indexed_chars(body_content(&document));
+            Some(quoted_range(&chars, quote, start_index, end_index)?)
+        }
+        _ => {
+            return Err("Give either quote or both start_index and end_index".to_string());
+        }
+    };
+
+    let mut body = serde_json::json!({ "content": content });
+    if let Some((_, _, ref text)) = range {
+        body["quotedFileContent"] = serde_json::json!({
+            "mimeType": "text/plain",
+            "value": text,
+        });
+    }
+    let path = format!(
+        "files/{}/comments?fields={}",
+        url_encode(document_id),
+        url_encode(COMMENT_FIELDS)
+    );
+    let body_str = serde_json::to_string(&body).map_err(|e| e.to_string())?;
+    let response = drive_call("POST", &path, Some(&body_str))?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    Ok(AddCommentResult {
+        document_id: document_id.to_string(),
+        start_index: range.as_ref().map(|(start, _, _)| *start),
+        end_index: range.as_ref().map(|(_, end, _)| *end),
+        comment: parse_comment(&parsed),
+    })
+}
+
+/// Reply to a comment; `resolve` true resolves it, false reopens it.
+pub fn reply_to_comment(
+    document_id: &str,
+    comment_id: &str,
+    content: &str,
+    resolve: Option<bool>,
+) -> Result<ReplyResult, String> {
+    if content.trim().is_empty() && resolve.is_none() {
+        return Err("content is empty".to_string());
+    }
+    let mut body = serde_json::json!({ "content": content });
+    if let Some(resolve) = resolve {
+        body["action"] = serde_json::json!(if resolve { "resolve" } else { "reopen" });
+    }
+    let path = format!(
+        "files/{}/comments/{}/replies?fields={}",
+        url_encode(document_id),
+        url_encode(comment_id),
+        url_encode(REPLY_FIELDS)
+    );
+    let body_str = serde_json::to_string(&body).map_err(|e| e.to_string())?;
+    let response = drive_call("POST", &path, Some(&body_str))?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    Ok(ReplyResult {
+        document_id: document_id.to_string(),
+        comment_id: comment_id.to_string(),
+        reply: parse_reply(&parsed),
+    })
+}
+
+fn read_text_fields(document_id: &str) -> Result<serde_json::Value, String> {
+    let path = format!(
+        "{}?fields={}",
+        url_encode(document_id),
+        url_encode(TEXT_FIELDS)
+    );
+    let response = api_call("GET", &path, None)?;
+    serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+/// The range and text a comment quotes: the first occurrence of `quote`,
+/// or the text at `start..end`.
+fn quoted_range(
+    chars: &[(i64, char)],
+    quote: Option<&str>,
+    start_index: Option<i64>,
+    end_index: Option<i64>,
+) -> Result<(i64, i64, String), String> {
+    let (start, end) = match (quote, start_index, end_index) {
+        (Some(quote), _, _) => *find_ranges(chars, quote)
+            .first()
+            .ok_or_else(|| format!("'{}' was not found in the document", quote))?,
+        (None, Some(start), Some(end)) => (start, end),
+        _ => return Err("Give either quote or both start_index and end_index".to_string()),
+    };
+    let text: String = chars
+        .iter()
+        .filter(|(index, _)| (start..end).contains(index))
+        .map(|(_, c)| c)
+        .collect();
+    if text.is_empty() {
+        return Err(format!("No text between index {} and {}", start, end));
+    }
+    Ok((start, end, text))
+}
+
+fn parse_comment(v: &serde_json::Value) -> DocComment {
+    DocComment {
+        comment_id: v["id"].as_str().unwrap_or("").to_string(),
+        author: v["author"]["displayName"]
+            .as_str()
+            .unwrap_or("")
+            .to_string(),
+        content: v["content"].as_str().unwrap_or("").to_string(),
+        created_time: v["createdTime"].as_str().unwrap_or("").to_string(),
+        modified_time: v["modifiedTime"].as_str().map(String::from),
+        resolved: v["resolved"].as_bool().unwrap_or(false),
+        quoted_text: v["quotedFileContent"]["value"].as_str().map(String::from),
+        replies: v["replies"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|r| r["deleted"].as_bool() != Some(true))
+            .map(parse_reply)
+            .collect(),
+    }
+}
+
+fn parse_reply(v: &serde_json::Value) -> CommentReply {
+    CommentReply {
+        reply_id: v["id"].as_str().unwrap_or("").to_string(),
+        author: v["author"]["displayName"]
+            .as_str()
+            .unwrap_or("")
+            .to_string(),
+        content: v["content"].as_str().unwrap_or("").to_string(),
+        created_time: v["createdTime"].as_str().unwrap_or("").to_string(),
+        action: v["action"].as_str().map(String::from),
+    }
+}
+
+/// Export the document through Drive as PDF, DOCX, HTML or plain text, and
+/// either return it base64-encoded or save it to Drive.
+pub fn export_document(
+    document_id: &str,
+    format: &str,
+    save_to_drive: bool,
+    folder_id: Option<&str>,
+    name: Option<&str>,
+) -> Result<ExportResult, String> {
+    let (mime_type, extension) = export_format(format)?;
+
+    let url = format!(
+        "{}/files/{}/export?mimeType={}",
+        DRIVE_API_BASE,
+        url_encode(document_id),
+        url_encode(mime_type)
+    );
+    let bytes = http_bytes(&DRIVE_API, "GET", &url, "{}", None)?;
+
+    let mut result = ExportResult {
+        document_id: document_id.to_string(),
+        format: extension.to_string(),
+        mime_type: mime_type.to_string(),
+        size_bytes: bytes.len(),
+        content_base64: None,
+        file: None,
+    };
+
+    if !save_to_drive && folder_id.is_none() {
+        if bytes.len() > MAX_INLINE_EXPORT_BYTES {
+            return Err(format!(
+                "The {} export is {} bytes, over the {} byte limit for returning it inline; \
+                 set save_to_drive to save it to Drive instead",
+                extension,
+                bytes.len(),
+                MAX_INLINE_EXPORT_BYTES
+            ));
+        }
+        result.content_base64 = Some(base64_encode(&bytes));
+        return Ok(result);
+    }
+
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => {
+            let path = format!("files/{}?fields=name", url_encode(document_id));
+            let response = drive_call("GET", &path, None)?;
+            let parsed: serde_json::Value = serde_json::from_str(&response)
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+            let title = parsed["name"].as_str().unwrap_or("Untitled");
+            format!("{}.{}", title, extension)
+        }
+    };
+    result.file = Some(upload_file(&name, mime_type, &bytes, folder_id)?);
+    Ok(result)
+}
+
+/// Export MIME type and file extension for a format name.
+fn export_format(format: &str) -> Result<(&'static str, &'static str), String> {
+    match format.to_ascii_lowercase().as_str() {
+        "pdf" => Ok(("application/pdf", "pdf")),
+        "docx" => Ok((
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            "docx",
+        )),
+        "html" => Ok(("text/html", "html")),
+        "txt" | "text" => Ok(("text/plain", "txt")),
+        _ => Err(format!(
+            "Unsupported export format '{}': expected pdf, docx, html, or txt",
+            format
+        )),
+    }
+}
+
+/// Upload a file to Drive with a multipart upload.
+fn upload_file(
+    name: &str,
+    mime_type: &str,
+    content: &[u8],
+    folder_id: Option<&str>,
+) -> Result<DriveFile, String> {
+    let boundary = "ironclaw_upload_boundary_42";
+
+    let mut metadata = serde_json::json!({
+        "name": name,
+        "mimeType": mime_type,
+    });
+    if let Some(folder_id) = folder_id {
+        metadata["parents"] = serde_json::json!([folder_id]);
+    }
+    let metadata_str = serde_json::to_string(&metadata).map_err(|e| e.to_string())?;
+    let body = multipart_body(boundary, &metadata_str, mime_type, content);
+
+    let url = format!(
+        "{}/files?uploadType=multipart&fields=id,name,webViewLink&supportsAllDrives=true",
+        DRIVE_UPLOAD_BASE
+    );
+    let headers = format!(
+        r#"{{"Content-Type": "multipart/related; boundary={}"}}"#,
+        boundary
+    );
+    let response = http_bytes(&DRIVE_API, "POST", &url, &headers, Some(&body))?;
+
+    let parsed: serde_json::Value = serde_json::from_slice(&response)
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    Ok(DriveFile {
+        file_id: parsed["id"].as_str().unwrap_or("").to_string(),
+        name: parsed["name"].as_str().unwrap_or(name).to_string(),
+        web_view_link: parsed["webViewLink"].as_str().map(String::from),
+    })
+}
+
+/// A `multipart/related` body: JSON metadata, then the binary content.
+fn multipart_body(boundary: &str, metadata: &str, mime_type: &str, content: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(content.len() + metadata.len() + 256);
+    body.extend_from_slice(
+        format!(
+            "--{}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{}\r\n--{}\r\nContent-Type: {}\r\n\r\n",
+            boundary, metadata, boundary, mime_type
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(content);
+    body.extend_from_slice(format!("\r\n--{}--", boundary).as_bytes());
+    body
+}
+
+/// Diff two revisions of the document, exported as plain text through Drive.
+pub fn compare_revisions(
+    document_id: &str,
+    from_revision: Option<&str>,
+    to_revision: Option<&str>,
+    since: Option<&str>,
+    context_lines: usize,
+) -> Result<RevisionDiffResult, String> {
+    let revisions = list_revisions(document_id)?;
+    let (from_index, to_index) = select_revisions(&revisions, from_revision, to_revision, since)?;
+    let from = &revisions[from_index];
+    let to = &revisions[to_index];
+
+    let old = revision_text(document_id, &from.revision_id)?;
+    let new = revision_text(document_id, &to.revision_id)?;
+    let label = |r: &RevisionInfo| format!("revision {} ({})", r.revision_id, r.modified_time);
+    let diff = diff::unified_diff(
+        &old,
+        &new,
+        &label(from),
+        &label(to),
+        context_lines,
+        MAX_DIFF_CHARS,
+    );
+
+    Ok(RevisionDiffResult {
+        document_id: document_id.to_string(),
+        from: from.clone(),
+        to: to.clone(),
+        revisions_between: to_index - from_index,
+        stats: diff.stats,
+        diff: diff.unified,
+        truncated: diff.truncated,
+    })
+}
+
+/// The document's revisions, oldest first.
+fn list_revisions(document_id: &str) -> Result<Vec<RevisionInfo>, String> {
+    let mut revisions = Vec::new();
+    let mut page_token: Option<String> = None;
+    for _ in 0..MAX_REVISION_PAGES {
+        let mut path = format!(
+            "files/{}/revisions?pageSize={}&fields={}",
+            url_encode(document_id),
+            REVISIONS_PAGE_SIZE,
+            url_encode("nextPageToken,revisions(id,modifiedTime,lastModifyingUser/displayName)")
+        );
+        if let Some(ref token) = page_token {
+            path.push_str(&format!("&pageToken={}", url_encode(token)));
+        }
+        let response = drive_call("GET", &path, None)?;
+        let parsed: serde_json::Value = serde_json::from_str(&response)
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        revisions.extend(
+            parsed["revisions"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|r| RevisionInfo {
+                    revision_id: r["id"].as_str().unwrap_or("").to_string(),
+                    modified_time: r["modifiedTime"].as_str().unwrap_or("").to_string(),
+                    modified_by: r["lastModifyingUser"]["displayName"]
+                        .as_str()
+                        .map(String::from),
+                }),
+        );
+        page_token = parsed["nextPageToken"].as_str().map(String::from);
+        if page_token.is_none() {
+            break;
+        }
+    }
+    Ok(revisions)
+}
+
+/// Indices of the revisions to compare.
+///
+/// `to` defaults to the latest revision. `from` defaults to the last revision
+/// at or before `since` (the first one if all are later), or else the one
+/// before `to`.
+fn select_revisions(
+    revisions: &[RevisionInfo],
+    from: Option<&str>,
+    to: Option<&str>,
+    since: Option<&str>,
+) -> Result<(usize, usize), String> {
+    let find = |id: &str| {
+        revisions
+            .iter()
+            .position(|r| r.revision_id == id)
+            .ok_or_else(|| {
+                format!(
+                    "Revision '{}' not found; the document has {} revisions",
+                    id,
+                    revisions.len()
+                )
+            })
+    };
+
+    let to_index = match to {
+        Some(id) => find(id)?,
+        None => revisions
+            .len()
+            .checked_sub(1)
+            .ok_or_else(|| "The document has no revisions".to_string())?,
+    };
+    let from_index = match (from, since) {
+        (Some(id), _) => find(id)?,
+        (None, Some(since)) => {
+            // Both are RFC 3339 in UTC, so the date and time compare as text
+            let since = since.get(..19).unwrap_or(since);
+            revisions
+                .iter()
+                .rposition(|r| r.modified_time.get(..19).unwrap_or(&r.modified_time) <= since)
+                .unwrap_or(0)
+        }
+        (None, None) => to_index.checked_sub(1).ok_or_else(|| {
+            "There is no earlier revision to compare with; give from_revision or since".to_string()
+        })?,
+    };
+
+    if from_index > to_index {
+        return Err(format!(
+            "Revision '{}' is newer than revision '{}'",
+            revisions[from_index].revision_id, revisions[to_index].revision_id
+        ));
+    }
+    Ok((from_index, to_index))
+}
+
+/// The plain text of one revision, through its Drive export link.
+fn revision_text(document_id: &str, revision_id: &str) -> Result<String, String> {
+    let path = format!(
+        "files/{}/revisions/{}?fields=exportLinks",
+        url_encode(document_id),
+        url_encode(revision_id)
+    );
+    let response = drive_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+    let url = parsed["exportLinks"]["text/plain"]
+        .as_str()
+        .ok_or_else(|| format!("Revision '{}' has no plain text export", revision_id))?;
+
+    let bytes = http_bytes(&DRIVE_API, "GET", url, "{}", None)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Create a bulleted or numbered list from paragraphs in a range.
+pub fn create_list(
+    document_id: &str,
+    start_index: i64,
+    end_index: i64,
+    bullet_preset: &str,
+) -> Result<UpdateResult, String> {
+    let request = serde_json::json!({
+        "createParagraphBullets": {
+            "range": {
+                "startIndex": start_index,
+                "endIndex": end_index,
+            },
+            "bulletPreset": bullet_preset,
+        }
+    });
+
+    let parsed = batch_update_raw(document_id, vec![request])?;
+
+    Ok(UpdateResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: extract_revision_id(&parsed),
+    })
+}
+
+/// Apply edits given against the document as read in one batchUpdate,
+/// ordered by [`edits::plan`] so no edit shifts another's indices.
+pub fn apply_edits(document_id: &str, edits: &[DocEdit]) -> Result<ApplyEditsResult, String> {
+    if edits.is_empty() {
+        return Err("No edits given".to_string());
+    }
+
+    let mut requests = Vec::new();
+    for i in edits::plan(edits)? {
+        let edit_requests =
+            edit_requests(&edits[i]).map_err(|e| format!("Edit {}: {}", i + 1, e))?;
+        requests.extend(edit_requests);
+    }
+
+    let parsed = batch_update_raw(document_id, requests)?;
+
+    Ok(ApplyEditsResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: extract_revision_id(&parsed),
+        edits_applied: edits.len(),
+    })
+}
+
+/// The batchUpdate requests for one edit.
+fn edit_requests(edit: &DocEdit) -> Result<Vec<serde_json::Value>, String> {
+    Ok(match edit {
+        DocEdit::Insert { index, text } => vec![insert_text_request(text, *index, "")],
+        DocEdit::Delete {
+            start_index,
+            end_index,
+        } => vec![delete_range_request(*start_index, *end_index, "")],
+        DocEdit::Replace {
+            start_index,
+            end_index,
+            text,
+        } => {
+            let mut requests = vec![delete_range_request(*start_index, *end_index, "")];
+            if !text.is_empty() {
+                requests.push(insert_text_request(text, *start_index, ""));
+            }
+            requests
+        }
+        DocEdit::FormatText {
+            start_index,
+            end_index,
+            bold,
+            italic,
+            underline,
+            strikethrough,
+            font_size,
+            font_family,
+            foreground_color,
+            background_color,
+        } => vec![text_style_request(&FormatTextOptions {
+            document_id: "",
+            start_index: *start_index,
+            end_index: *end_index,
+            bold: *bold,
+            italic: *italic,
+            underline: *underline,
+            strikethrough: *strikethrough,
+            font_size: *font_size,
+            font_family: font_family.as_deref(),
+            foreground_color: foreground_color.as_deref(),
+            background_color: background_color.as_deref(),
+        })?],
+        DocEdit::FormatParagraph {
+            start_index,
+            end_index,
+            named_style,
+            alignment,
+            line_spacing,
+        } => vec![paragraph_style_request(
+            *start_index,
+            *end_index,
+            named_style.as_deref(),
+            alignment.as_deref(),
+            *line_spacing,
+        )?],
+    })
+}
+
+/// Execute a raw batch update with arbitrary requests.
+pub fn batch_update(
+    document_id: &str,
+    requests: Vec<serde_json::Value>,
+) -> Result<BatchUpdateResult, String> {
+    let parsed = batch_update_raw(document_id, requests)?;
+
+    let replies = parsed["replies"]
+        .as_array()
+        .map(|arr| arr.to_vec())
+        .unwrap_or_default();
+
+    Ok(BatchUpdateResult {
+        document_id: parsed["documentId"].as_str().unwrap_or("").to_string(),
+        revision_id: extract_revision_id(&parsed),
+        replies,
+    })
+}
+
+/// Minimal percent-encoding for URL path segments.
 fn url_encode(s: &str) -> String {
     let mut encoded = String::with_capacity(s.len());
     for b in s.bytes() {
@@ -514,3 +2323,476 @@ fn url_encode(s: &str) -> String {
 }
 
 const HEX: [u8; 16] = *b"0123456789ABCDEF";
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard base64 with padding.
+fn base64_encode(input: &[u8]) -> String {
+    let mut result = String::with_capacity(input.len().div_ceil(3) * 4);
+
+    for chunk in input.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = chunk.get(1).copied().unwrap_or(0) as u32;
+        let b2 = chunk.get(2).copied().unwrap_or(0) as u32;
+        let triple = (b0 << 16) | (b1 << 8) | b2;
+
+        result.push(BASE64_CHARS[((triple >> 18) & 0x3F) as usize] as char);
+        result.push(BASE64_CHARS[((triple >> 12) & 0x3F) as usize] as char);
+        if chunk.len() > 1 {
+            result.push(BASE64_CHARS[((triple >> 6) & 0x3F) as usize] as char);
+        } else {
+            result.push('=');
+        }
+        if chunk.len() > 2 {
+            result.push(BASE64_CHARS[(triple & 0x3F) as usize] as char);
+        } else {
+            result.push('=');
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paragraph(start: i64, style: &str, text: &str) -> serde_json::Value {
+        json!({
+            "startIndex": start,
+            "endIndex": start + text.chars().count() as i64,
+            "paragraph": {
+                "paragraphStyle": { "namedStyleType": style },
+                "elements": [{ "textRun": { "content": text } }]
+            }
+        })
+    }
+
+    fn body() -> Vec<serde_json::Value> {
+        vec![
+            json!({ "endIndex": 1, "sectionBreak": {} }),
+            paragraph(1, "TITLE", "Plan\n"),
+            paragraph(6, "HEADING_1", "Goals\n"),
+            paragraph(12, "NORMAL_TEXT", "Ship it.\n"),
+            paragraph(21, "HEADING_2", "Stretch\n"),
+            paragraph(29, "NORMAL_TEXT", "More.\n"),
+            paragraph(35, "HEADING_1", "Risks\n"),
+            paragraph(41, "NORMAL_TEXT", "None.\n"),
+        ]
+    }
+
+    #[test]
+    fn test_paragraphs_by_detail() {
+        let content = serde_json::Value::Array(body());
+        assert!(parse_paragraphs(&content, Detail::Summary).is_empty());
+
+        let standard = parse_paragraphs(&content, Detail::Standard);
+        let headings: Vec<_> = standard.iter().map(|p| p.text.as_str()).collect();
+        assert_eq!(headings, ["Plan", "Goals", "Stretch", "Risks"]);
+        assert_eq!((standard[1].start_index, standard[1].end_index), (6, 12));
+
+        let full = parse_paragraphs(&content, Detail::Full);
+        assert_eq!(full.len(), 7);
+        assert_eq!(full[2].style, "NORMAL_TEXT");
+        assert_eq!(full[2].text, "Ship it.");
+    }
+
+    #[test]
+    fn test_preview_cuts_at_chars() {
+        assert_eq!(preview("  short  ", 10), "short");
+        assert_eq!(preview("héllo wörld", 6), "héllo…");
+        assert_eq!(preview("abc", 3), "abc");
+    }
+
+    #[test]
+    fn test_outline_nests_headings_with_section_ranges() {
+        let outline = nest_headings(find_headings(&body(), true));
+
+        assert_eq!(outline.len(), 1);
+        let title = &outline[0];
+        assert_eq!((title.level, title.text.as_str()), (0, "Plan"));
+        assert_eq!(title.end_index, 47);
+
+        let goals = &title.children[0];
+        assert_eq!((goals.start_index, goals.heading_end_index), (6, 12));
+        assert_eq!(goals.end_index, 35);
+        assert_eq!(goals.children[0].text, "Stretch");
+        assert_eq!(goals.children[0].end_index, 35);
+        assert_eq!(title.children[1].text, "Risks");
+    }
+
+    #[test]
+    fn test_read_section_text() {
+        let content = body();
+        let headings = find_headings(&content, true);
+        let goals = match_heading(&headings, "  goals ").unwrap();
+        assert_eq!(
+            section_text(&content, goals.heading_end_index, goals.end_index),
+            "Ship it.\nStretch\nMore."
+        );
+
+        let headings = find_headings(&content, false);
+        let goals = match_heading(&headings, "Goals").unwrap();
+        assert_eq!(
+            section_text(&content, goals.heading_end_index, goals.end_index),
+            "Ship it."
+        );
+
+        assert_eq!(match_heading(&headings, "stret").unwrap().text, "Stretch");
+        assert!(match_heading(&headings, "s").is_err());
+        assert!(match_heading(&headings, "budget").is_err());
+    }
+
+    fn cell(start: i64, text: &str) -> serde_json::Value {
+        json!({ "content": [paragraph(start, "NORMAL_TEXT", text)] })
+    }
+
+    fn document_with_table() -> serde_json::Value {
+        // A 2x2 table at 10: each row and cell takes one index before its
+        // content, and the table ends one index after its last cell.
+        json!({
+            "documentId": "doc",
+            "revisionId": "rev",
+            "body": { "content": [
+                paragraph(1, "NORMAL_TEXT", "Intro\n"),
+                { "startIndex": 7, "endIndex": 10, "paragraph": {} },
+                {
+                    "startIndex": 10,
+                    "endIndex": 29,
+                    "table": { "tableRows": [
+                        { "tableCells": [cell(12, "Name\n"), cell(18, "Qty\n")] },
+                        { "tableCells": [cell(24, "\n"), cell(26, "2\n")] },
+                    ] }
+                },
+            ] }
+        })
+    }
+
+    #[test]
+    fn test_parse_table_cells() {
+        let table = parse_table(&document_with_table(), 0).unwrap();
+        assert_eq!((table.rows, table.columns), (2, 2));
+        assert_eq!((table.start_index, table.end_index), (10, 29));
+        assert_eq!(table.cells[0][0].text, "Name");
+        assert_eq!(
+            (table.cells[0][1].start_index, table.cells[0][1].end_index),
+            (18, 22)
+        );
+        assert_eq!(table.cells[1][0].text, "");
+
+        assert!(parse_table(&document_with_table(), 1).is_err());
+        assert!(parse_table(&json!({ "body": { "content": [] } }), 0).is_err());
+    }
+
+    #[test]
+    fn test_cell_requests() {
+        let table = parse_table(&document_with_table(), 0).unwrap();
+
+        // Replacing keeps the cell's final newline
+        let requests = cell_requests(&table.cells[0][1], "Quantity");
+        assert_eq!(
+            requests[0]["deleteContentRange"]["range"],
+            json!({ "startIndex": 18, "endIndex": 21 })
+        );
+        assert_eq!(requests[1]["insertText"]["location"]["index"], 18);
+
+        // An empty cell only needs the insert; clearing one needs nothing
+        assert_eq!(cell_requests(&table.cells[1][0], "x").len(), 1);
+        assert!(cell_requests(&table.cells[1][0], "").is_empty());
+
+        // Filling a row goes right to left; missing values are skipped
+        let requests = fill_row_requests(&table.cells[1], &["a".to_string(), "b".to_string()]);
+        let targets: Vec<i64> = requests
+            .iter()
+            .filter_map(|r| r["insertText"]["location"]["index"].as_i64())
+            .collect();
+        assert_eq!(targets, vec![26, 24]);
+        assert_eq!(
+            fill_row_requests(&table.cells[1], &["a".to_string()]).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_find_placeholder_ranges() {
+        // Runs split mid-placeholder, an emoji taking two indices, and a cell
+        let content = vec![
+            json!({ "paragraph": { "elements": [
+                { "startIndex": 1, "textRun": { "content": "Hi 🎉 {{na" } },
+                { "startIndex": 11, "textRun": { "content": "me}}\n" } },
+            ] } }),
+            json!({ "table": { "tableRows": [{ "tableCells": [{ "content": [
+                { "paragraph": { "elements": [
+                    { "startIndex": 20, "textRun": { "content": "{{name}}\n" } },
+                ] } },
+            ] }] }] } }),
+        ];
+        let chars = indexed_chars(&content);
+        assert_eq!(chars[3], (4, '🎉'));
+        assert_eq!(chars[4], (6, ' '));
+
+        assert_eq!(find_ranges(&chars, "{{name}}"), vec![(7, 15), (20, 28)]);
+        assert!(find_ranges(&chars, "{{title}}").is_empty());
+        assert!(find_ranges(&chars, "").is_empty());
+    }
+
+    fn heading(start: i64, style: &str, id: &str, text: &str) -> serde_json::Value {
+        let mut el = paragraph(start, style, text);
+        el["paragraph"]["paragraphStyle"]["headingId"] = json!(id);
+        el
+    }
+
+    #[test]
+    fn test_toc_entries_and_requests() {
+        let content = vec![
+            json!({ "endIndex": 1, "sectionBreak": {} }),
+            paragraph(1, "TITLE", "Plan\n"),
+            heading(6, "HEADING_1", "h.goals", "Goals\n"),
+            heading(12, "HEADING_2", "h.cafe", "Café plan\n"),
+            heading(22, "HEADING_3", "h.deep", "Deep\n"),
+        ];
+        assert_eq!(default_toc_index(&content), 6);
+        assert!(toc_entries(&content[..2], 3).is_err());
+
+        let entries = toc_entries(&content, 2).unwrap();
+        let texts: Vec<&str> = entries.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, vec!["Goals", "Café plan"]);
+
+        let (requests, end) = toc_requests(&entries, Some("Contents"), 6, 2);
+        assert_eq!(
+            requests[0]["insertText"]["text"],
+            "Contents\nGoals\nCafé plan\n"
+        );
+        assert_eq!(end, 6 + 9 + 6 + 10);
+        assert_eq!(requests[2]["updateTextStyle"]["range"]["endIndex"], 14);
+
+        let links: Vec<(i64, i64, &str)> = requests
+            .iter()
+            .filter_map(|r| {
+                let style = &r["updateTextStyle"];
+                let id = style["textStyle"]["link"]["headingId"].as_str()?;
+                let range = &style["range"];
+                Some((
+                    range["startIndex"].as_i64()?,
+                    range["endIndex"].as_i64()?,
+                    id,
+                ))
+            })
+            .collect();
+        assert_eq!(links, vec![(15, 20, "h.goals"), (21, 30, "h.cafe")]);
+
+        let indents = requests
+            .iter()
+            .filter(|r| r["updateParagraphStyle"]["fields"] == "indentStart,indentFirstLine")
+            .count();
+        assert_eq!(indents, 1);
+        assert_eq!(
+            requests.last().unwrap()["createNamedRange"]["name"],
+            "ironclaw-toc|2|Contents"
+        );
+    }
+
+    #[test]
+    fn test_find_toc() {
+        let document = json!({
+            "namedRanges": {
+                "notes": { "namedRanges": [{ "name": "notes", "ranges": [] }] },
+                "ironclaw-toc|3|Contents | Index": { "namedRanges": [{
+                    "name": "ironclaw-toc|3|Contents | Index",
+                    "ranges": [{ "startIndex": 6, "endIndex": 40 }]
+                }] }
+            }
+        });
+        let toc = find_toc(&document).unwrap();
+        assert_eq!((toc.start_index, toc.end_index), (6, 40));
+        assert_eq!(toc.max_level, 3);
+        assert_eq!(toc.title.as_deref(), Some("Contents | Index"));
+
+        let untitled = json!({ "namedRanges": { "ironclaw-toc|2|": { "namedRanges": [{
+            "ranges": [{ "startIndex": 1, "endIndex": 9 }]
+        }] } } });
+        assert_eq!(find_toc(&untitled).unwrap().title, None);
+        assert!(find_toc(&json!({})).is_none());
+    }
+
+    #[test]
+    fn test_quoted_range() {
+        let content = vec![json!({ "paragraph": { "elements": [
+            { "startIndex": 1, "textRun": { "content": "We ship in May, not in May 2027.\n" } },
+        ] } })];
+        let chars = indexed_chars(&content);
+
+        assert_eq!(
+            quoted_range(&chars, Some("in May"), None, None).unwrap(),
+            (9, 15, "in May".to_string())
+        );
+        assert_eq!(
+            quoted_range(&chars, None, Some(1), Some(8)).unwrap(),
+            (1, 8, "We ship".to_string())
+        );
+        assert!(quoted_range(&chars, Some("June"), None, None).is_err());
+        assert!(quoted_range(&chars, None, Some(8), Some(8)).is_err());
+    }
+
+    #[test]
+    fn test_parse_comment_skips_deleted_replies() {
+        let comment = parse_comment(&json!({
+            "id": "c1",
+            "author": { "displayName": "Ana" },
+            "content": "Is May realistic?",
+            "createdTime": "2026-03-02T10:00:00.000Z",
+            "resolved": true,
+            "quotedFileContent": { "mimeType": "text/html", "value": "ship in May" },
+            "replies": [
+                { "id": "r1", "content": "", "deleted": true },
+                { "id": "r2", "author": { "displayName": "Bo" }, "content": "Moved.", "action": "resolve" },
+            ],
+        }));
+        assert_eq!(comment.comment_id, "c1");
+        assert!(comment.resolved);
+        assert_eq!(comment.quoted_text.as_deref(), Some("ship in May"));
+        assert_eq!(comment.replies.len(), 1);
+        assert_eq!(comment.replies[0].author, "Bo");
+        assert_eq!(comment.replies[0].action.as_deref(), Some("resolve"));
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(&[0xFB, 0xFF]), "+/8=");
+    }
+
+    #[test]
+    fn test_export_format() {
+        assert_eq!(export_format("PDF").unwrap(), ("application/pdf", "pdf"));
+        assert_eq!(export_format("text").unwrap(), ("text/plain", "txt"));
+        assert!(export_format("odt").is_err());
+    }
+
+    #[test]
+    fn test_select_revisions() {
+        let revisions: Vec<RevisionInfo> = [
+            ("1", "2024-05-01T08:00:00.000Z"),
+            ("5", "2024-05-01T17:30:00.000Z"),
+            ("9", "2024-05-02T10:00:00.000Z"),
+            ("12", "2024-05-02T11:00:00.000Z"),
+        ]
+        .iter()
+        .map(|(id, time)| RevisionInfo {
+            revision_id: id.to_string(),
+            modified_time: time.to_string(),
+            modified_by: None,
+        })
+        .collect();
+
+        // Latest against the one before it
+        assert_eq!(
+            select_revisions(&revisions, None, None, None).unwrap(),
+            (2, 3)
+        );
+        assert_eq!(
+            select_revisions(&revisions, Some("1"), Some("9"), None).unwrap(),
+            (0, 2)
+        );
+
+        // Since yesterday evening: the state as of then is revision 5
+        let since = Some("2024-05-01T18:00:00Z");
+        assert_eq!(
+            select_revisions(&revisions, None, None, since).unwrap(),
+            (1, 3)
+        );
+        let before_all = Some("2024-04-01T00:00:00Z");
+        assert_eq!(
+            select_revisions(&revisions, None, None, before_all).unwrap(),
+            (0, 3)
+        );
+
+        assert!(select_revisions(&revisions, Some("9"), Some("5"), None).is_err());
+        assert!(select_revisions(&revisions, Some("7"), None, None).is_err());
+        assert!(select_revisions(&revisions[..1], None, None, None).is_err());
+        assert!(select_revisions(&[], None, None, None).is_err());
+    }
+
+    #[test]
+    fn test_document_style() {
+        let opts = DocumentStyleOptions {
+            document_id: "doc",
+            page_size: Some("a4"),
+            orientation: Some("landscape"),
+            margin_top: None,
+            margin_bottom: None,
+            margin_left: Some(36.0),
+            margin_right: Some(36.0),
+        };
+        let (style, fields) = document_style(&opts, None).unwrap();
+        assert_eq!(fields, "pageSize,marginLeft,marginRight");
+        assert_eq!(style["pageSize"]["width"]["magnitude"], json!(841.89));
+        assert_eq!(style["pageSize"]["height"]["magnitude"], json!(595.28));
+        assert_eq!(
+            style["marginLeft"],
+            json!({ "magnitude": 36.0, "unit": "PT" })
+        );
+
+        // Orientation alone turns the current page around
+        let turn = DocumentStyleOptions {
+            page_size: None,
+            orientation: Some("PORTRAIT"),
+            margin_left: None,
+            margin_right: None,
+            ..opts
+        };
+        let (style, fields) = document_style(&turn, Some((792.0, 612.0))).unwrap();
+        assert_eq!(fields, "pageSize");
+        assert_eq!(style["pageSize"]["width"]["magnitude"], json!(612.0));
+        assert_eq!(style["pageSize"]["height"]["magnitude"], json!(792.0));
+
+        let nothing = DocumentStyleOptions {
+            orientation: None,
+            ..turn
+        };
+        assert!(document_style(&nothing, None).is_err());
+        let unknown = DocumentStyleOptions {
+            page_size: Some("B5"),
+            ..nothing
+        };
+        assert!(document_style(&unknown, None).unwrap_err().contains("A4"));
+    }
+
+    #[test]
+    fn test_edit_requests() {
+        let replace = DocEdit::Replace {
+            start_index: 5,
+            end_index: 9,
+            text: "June".to_string(),
+        };
+        assert_eq!(
+            edit_requests(&replace).unwrap(),
+            vec![
+                json!({ "deleteContentRange": { "range": { "startIndex": 5, "endIndex": 9 } } }),
+                json!({ "insertText": { "text": "June", "location": { "index": 5 } } }),
+            ]
+        );
+
+        let append = DocEdit::Insert {
+            index: -1,
+            text: "End\n".to_string(),
+        };
+        assert_eq!(
+            edit_requests(&append).unwrap()[0]["insertText"]["endOfSegmentLocation"],
+            json!({})
+        );
+
+        let no_style = DocEdit::FormatParagraph {
+            start_index: 1,
+            end_index: 5,
+            named_style: None,
+            alignment: None,
+            line_spacing: None,
+        };
+        assert!(edit_requests(&no_style).is_err());
+    }
+}
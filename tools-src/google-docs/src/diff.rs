@@ -0,0 +1,274 @@
+//! Line diffs between two versions of a document's text.
+//!
+//! `compare_revisions` exports two revisions as plain text and diffs them
+//! here, returning a unified diff (the format `diff -u` prints) and counts of
+//! what changed, so the agent can summarize "what changed since yesterday"
+//! without reading both versions.
+//!
+//! Paragraphs are lines in a Docs text export, so the diff is per paragraph:
+//! a one-word edit shows as the paragraph removed and re-added.
+
+use crate::types::DiffStats;
+
+/// Most cells in the longest-common-subsequence table; past this the changed
+/// middle is reported as removed then added instead of matched line by line.
+const MAX_LCS_CELLS: usize = 1_000_000;
+
+/// A unified diff and its counts.
+pub struct TextDiff {
+    pub unified: String,
+    pub stats: DiffStats,
+    /// True when the diff text was cut at `max_chars`.
+    pub truncated: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Op {
+    Equal,
+    Remove,
+    Add,
+}
+
+/// Diff `old` against `new` with `context` unchanged lines around each
+/// change, labelling the sides `old_label` and `new_label`.
+pub fn unified_diff(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    context: usize,
+    max_chars: usize,
+) -> TextDiff {
+    let old_text = normalize(old);
+    let new_text = normalize(new);
+    let old_lines: Vec<&str> = old_text.lines().collect();
+    let new_lines: Vec<&str> = new_text.lines().collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+
+    let mut stats = DiffStats::default();
+    for (op, line) in &ops {
+        match op {
+            Op::Remove => {
+                stats.lines_removed += 1;
+                stats.words_removed += line.split_whitespace().count();
+            }
+            Op::Add => {
+                stats.lines_added += 1;
+                stats.words_added += line.split_whitespace().count();
+            }
+            Op::Equal => {}
+        }
+    }
+
+    let hunks = hunks(&ops, context);
+    stats.hunks = hunks.len();
+
+    let mut unified = String::new();
+    let mut truncated = false;
+    if !hunks.is_empty() {
+        unified.push_str(&format!("--- {}\n+++ {}\n", old_label, new_label));
+    }
+    'hunks: for hunk in hunks {
+        let mut text = format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(hunk.old_start, hunk.old_len),
+            hunk_range(hunk.new_start, hunk.new_len)
+        );
+        for (op, line) in &ops[hunk.ops.clone()] {
+            let prefix = match op {
+                Op::Equal => ' ',
+                Op::Remove => '-',
+                Op::Add => '+',
+            };
+            text.push(prefix);
+            text.push_str(line);
+            text.push('\n');
+        }
+        for line in text.split_inclusive('\n') {
+            if unified.len() + line.len() > max_chars {
+                truncated = true;
+                break 'hunks;
+            }
+            unified.push_str(line);
+        }
+    }
+
+    TextDiff {
+        unified,
+        stats,
+        truncated,
+    }
+}
+
+/// Drop the byte-order mark and carriage returns a Docs text export has.
+fn normalize(text: &str) -> String {
+    text.trim_start_matches('\u{feff}').replace("\r\n", "\n")
+}
+
+/// Edit script turning `old` into `new`, one entry per line.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Op, &'a str)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<(Op, &str)> = old[..prefix].iter().map(|l| (Op::Equal, *l)).collect();
+    if old_mid.len() * new_mid.len() <= MAX_LCS_CELLS {
+        ops.extend(lcs_ops(old_mid, new_mid));
+    } else {
+        ops.extend(old_mid.iter().map(|l| (Op::Remove, *l)));
+        ops.extend(new_mid.iter().map(|l| (Op::Add, *l)));
+    }
+    ops.extend(old[old.len() - suffix..].iter().map(|l| (Op::Equal, *l)));
+    ops
+}
+
+/// Edit script from a longest-common-subsequence table, removals before
+/// additions within each change.
+fn lcs_ops<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Op, &'a str)> {
+    let width = new.len() + 1;
+    // lengths[i * width + j]: LCS length of old[i..] and new[j..]
+    let mut lengths = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * width + j] = if old[i] == new[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(old.len() + new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((Op::Equal, old[i]));
+            i += 1;
+            j += 1;
+        } else if j == new.len()
+            || (i < old.len() && lengths[(i + 1) * width + j] >= lengths[i * width + j + 1])
+        {
+            ops.push((Op::Remove, old[i]));
+            i += 1;
+        } else {
+            ops.push((Op::Add, new[j]));
+            j += 1;
+        }
+    }
+    ops
+}
+
+/// A run of changes with its context, as line numbers on both sides.
+struct Hunk {
+    ops: std::ops::Range<usize>,
+    old_start: usize,
+    old_len: usize,
+    new_start: usize,
+    new_len: usize,
+}
+
+/// Group changes at most `2 * context` unchanged lines apart into hunks.
+fn hunks(ops: &[(Op, &str)], context: usize) -> Vec<Hunk> {
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _))| *op != Op::Equal)
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut ranges: Vec<std::ops::Range<usize>> = Vec::new();
+    for &i in &changes {
+        let start = i.saturating_sub(context);
+        let end = (i + 1 + context).min(ops.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.end => last.end = end,
+            _ => ranges.push(start..end),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|range| {
+            let before = &ops[..range.start];
+            let old_before = before.iter().filter(|(op, _)| *op != Op::Add).count();
+            let new_before = before.iter().filter(|(op, _)| *op != Op::Remove).count();
+            let within = &ops[range.clone()];
+            Hunk {
+                old_start: old_before + 1,
+                old_len: within.iter().filter(|(op, _)| *op != Op::Add).count(),
+                new_start: new_before + 1,
+                new_len: within.iter().filter(|(op, _)| *op != Op::Remove).count(),
+                ops: range,
+            }
+        })
+        .collect()
+}
+
+/// A hunk header range: `start,len`, with an empty side starting one line
+/// earlier as `diff -u` prints it.
+fn hunk_range(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{},0", start - 1),
+        1 => start.to_string(),
+        _ => format!("{},{}", start, len),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "\u{feff}Title\r\nIntro\r\nShip in May.\r\nRisks\r\nNone\r\nEnd\r\n";
+        let new = "Title\nIntro\nShip in June.\nRisks\nNone\nEnd\nOwner: Ana\n";
+        let diff = unified_diff(old, new, "rev 1", "rev 2", 1, 10_000);
+
+        assert_eq!(
+            diff.unified,
+            "--- rev 1\n+++ rev 2\n\
+             @@ -2,3 +2,3 @@\n Intro\n-Ship in May.\n+Ship in June.\n Risks\n\
+             @@ -6 +6,2 @@\n End\n+Owner: Ana\n"
+        );
+        assert_eq!(
+            diff.stats,
+            DiffStats {
+                lines_added: 2,
+                lines_removed: 1,
+                words_added: 5,
+                words_removed: 3,
+                hunks: 2,
+            }
+        );
+        assert!(!diff.truncated);
+
+        // Nearby changes share a hunk once the context overlaps
+        assert_eq!(unified_diff(old, new, "a", "b", 3, 10_000).stats.hunks, 1);
+    }
+
+    #[test]
+    fn test_identical_and_empty() {
+        let same = unified_diff("a\nb\n", "a\r\nb\r\n", "a", "b", 3, 10_000);
+        assert!(same.unified.is_empty());
+        assert_eq!(same.stats, DiffStats::default());
+
+        let created = unified_diff("", "Hello\n", "a", "b", 3, 10_000);
+        assert_eq!(created.unified, "--- a\n+++ b\n@@ -0,0 +1 @@\n+Hello\n");
+    }
+
+    #[test]
+    fn test_truncated_diff_keeps_full_stats() {
+        let old: String = (0..100).map(|i| format!("line {}\n", i)).collect();
+        let diff = unified_diff(&old, "", "a", "b", 3, 200);
+        assert!(diff.truncated);
+        assert!(diff.unified.len() <= 200);
+        assert!(diff.unified.ends_with('\n'));
+        assert_eq!(diff.stats.lines_removed, 100);
+    }
+}
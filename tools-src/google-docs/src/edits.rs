@@ -0,0 +1,192 @@
+//! Ordering for apply_edits.
+//!
+//! Every edit given to apply_edits uses indices from the document as it was
+//! read. A batchUpdate applies its requests one after another, and an insert
+//! or delete shifts everything after it, so the edits are applied from the
+//! end of the document backwards: each one only moves text that has already
+//! been edited. Edits that change the same text are refused, since no order
+//! would do what both meant.
+
+use crate::types::DocEdit;
+
+/// Where an edit applies in the document as read.
+#[derive(Clone, Copy)]
+enum Span {
+    /// Insert at an index (`i64::MAX` for the end of the body).
+    Point(i64),
+    /// A range; `shifts` when the edit removes its text.
+    Range { start: i64, end: i64, shifts: bool },
+}
+
+impl Span {
+    /// Edits are applied in descending order of this.
+    fn key(self) -> i64 {
+        match self {
+            Span::Point(index) => index,
+            Span::Range { end, .. } => end,
+        }
+    }
+}
+
+fn span(edit: &DocEdit) -> Result<Span, String> {
+    let range = |start: i64, end: i64, shifts: bool| {
+        if start < 1 {
+            Err("start_index must be at least 1".to_string())
+        } else if end <= start {
+            Err("end_index must be greater than start_index".to_string())
+        } else {
+            Ok(Span::Range { start, end, shifts })
+        }
+    };
+    match *edit {
+        DocEdit::Insert { index: -1, .. } => Ok(Span::Point(i64::MAX)),
+        DocEdit::Insert { index, .. } if index < 1 => {
+            Err("index must be at least 1, or -1 to append".to_string())
+        }
+        DocEdit::Insert { index, .. } => Ok(Span::Point(index)),
+        DocEdit::Delete {
+            start_index,
+            end_index,
+        }
+        | DocEdit::Replace {
+            start_index,
+            end_index,
+            ..
+        } => range(start_index, end_index, true),
+        DocEdit::FormatText {
+            start_index,
+            end_index,
+            ..
+        }
+        | DocEdit::FormatParagraph {
+            start_index,
+            end_index,
+            ..
+        } => range(start_index, end_index, false),
+    }
+}
+
+/// Whether two edits change the same text.
+fn conflicts(a: Span, b: Span) -> bool {
+    match (a, b) {
+        (Span::Point(_), Span::Point(_)) => false,
+        (Span::Point(index), Span::Range { start, end, shifts })
+        | (Span::Range { start, end, shifts }, Span::Point(index)) => {
+            shifts && start < index && index < end
+        }
+        (
+            Span::Range {
+                start: a_start,
+                end: a_end,
+                shifts: a_shifts,
+            },
+            Span::Range {
+                start: b_start,
+                end: b_end,
+                shifts: b_shifts,
+            },
+        ) => (a_shifts || b_shifts) && a_start < b_end && b_start < a_end,
+    }
+}
+
+/// The order to apply `edits` in, as positions in `edits`.
+///
+/// Later edits in the document go first. At the same index an insert goes
+/// before a range ending there, so the range still covers the text it was
+/// given for, and inserts go last-listed first so their text ends up in the
+/// order listed.
+pub fn plan(edits: &[DocEdit]) -> Result<Vec<usize>, String> {
+    let spans = edits
+        .iter()
+        .enumerate()
+        .map(|(i, edit)| span(edit).map_err(|e| format!("Edit {}: {}", i + 1, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (i, a) in spans.iter().enumerate() {
+        for (j, b) in spans.iter().enumerate().skip(i + 1) {
+            if conflicts(*a, *b) {
+                return Err(format!(
+                    "Edits {} and {} change the same text; combine them into one edit",
+                    i + 1,
+                    j + 1
+                ));
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..edits.len()).collect();
+    order.sort_by(|&a, &b| {
+        let is_range = |i: usize| matches!(spans[i], Span::Range { .. });
+        spans[b]
+            .key()
+            .cmp(&spans[a].key())
+            .then(is_range(a).cmp(&is_range(b)))
+            .then(b.cmp(&a))
+    });
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert(index: i64, text: &str) -> DocEdit {
+        DocEdit::Insert {
+            index,
+            text: text.to_string(),
+        }
+    }
+
+    fn delete(start_index: i64, end_index: i64) -> DocEdit {
+        DocEdit::Delete {
+            start_index,
+            end_index,
+        }
+    }
+
+    fn bold(start_index: i64, end_index: i64) -> DocEdit {
+        DocEdit::FormatText {
+            start_index,
+            end_index,
+            bold: Some(true),
+            italic: None,
+            underline: None,
+            strikethrough: None,
+            font_size: None,
+            font_family: None,
+            foreground_color: None,
+            background_color: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_orders_from_the_end() {
+        let edits = vec![
+            insert(1, "Title\n"),
+            delete(5, 10),
+            bold(12, 20),
+            insert(-1, "The end\n"),
+            insert(15, "new "),
+            insert(10, "B"),
+            insert(10, "A"),
+        ];
+        // Append first; the bold range before the insert inside it; inserts
+        // at 10 ahead of the delete ending there, last listed first
+        assert_eq!(plan(&edits).unwrap(), vec![3, 2, 4, 6, 5, 1, 0]);
+    }
+
+    #[test]
+    fn test_plan_rejects_overlapping_changes() {
+        assert!(plan(&[bold(1, 10), bold(5, 15)]).is_ok());
+        assert!(plan(&[delete(5, 10), insert(5, "x"), insert(10, "y")]).is_ok());
+
+        let err = plan(&[bold(1, 3), delete(5, 10), bold(8, 12)]).unwrap_err();
+        assert_eq!(
+            err,
+            "Edits 2 and 3 change the same text; combine them into one edit"
+        );
+        assert!(plan(&[delete(5, 10), insert(7, "x")]).is_err());
+        assert!(plan(&[insert(0, "x")]).unwrap_err().starts_with("Edit 1:"));
+        assert!(plan(&[delete(5, 5)]).is_err());
+    }
+}
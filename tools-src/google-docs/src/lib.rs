@@ -6,20 +6,43 @@
 //!
 //! # Capabilities Required
 //!
-//! - HTTP: `docs.googleapis.com/v1/documents*`
+//! - HTTP: `docs.googleapis.com/v1/documents*`, and
+//!   `www.googleapis.com/drive/v3/files*` for comments, exports and revisions,
+//!   and `docs.google.com/feeds/download/documents/export/*` for revision text
 //! - Secrets: `google_oauth_token` (shared OAuth 2.0 token, injected automatically)
 //!
 //! # Supported Actions
 //!
 //! - `create_document`: Create a new blank document
-//! - `get_document`: Get document metadata (title, length, named ranges)
+//! - `get_document`: Get document metadata (title, length, named ranges,
+//!   outline), at a chosen `detail` or limited to a `fields` mask
 //! - `read_content`: Read entire document body as plain text
+//! - `get_outline`: Heading hierarchy with the index range of each section
+//! - `read_section`: Plain text of the section under a named heading
 //! - `insert_text`: Insert text at a position (or append at end)
 //! - `delete_content`: Delete text in a range
 //! - `replace_text`: Find and replace all occurrences
 //! - `format_text`: Format text (bold, italic, font, color, size)
 //! - `format_paragraph`: Set heading level, alignment, spacing
+//! - `update_document_style`: Set page size, orientation and margins
+//! - `insert_section_break`: Start a new section, on a new page or continuous
+//! - `insert_page_break`: Start a new page
 //! - `insert_table`: Insert a table at a position
+//! - `read_table`: A table's cells with their text and index ranges
+//! - `write_table_cell`: Replace the text of one table cell
+//! - `append_table_row`: Add a row to the end of a table and fill it
+//! - `generate_toc`: Insert a table of contents linking to the headings
+//! - `refresh_toc`: Rebuild that table of contents after edits
+//! - `create_named_range`: Name a range, or each occurrence of a placeholder
+//! - `list_named_ranges`: Named ranges with their current indices and text
+//! - `replace_named_range_content`: Replace the text of a named range
+//! - `list_comments`: Open (or all) comments with their replies
+//! - `add_comment`: Comment on a passage of text or the whole document
+//! - `reply_to_comment`: Reply to a comment, optionally resolving it
+//! - `export_document`: Export as PDF, DOCX, HTML or text, inline or to Drive
+//! - `compare_revisions`: Unified diff and change counts between two revisions
+//! - `apply_edits`: Several inserts, deletes, replacements and formatting
+//!   changes in one batch, ordered automatically
 //! - `create_list`: Create bulleted/numbered list from paragraphs
 //! - `batch_update`: Execute multiple raw Docs API operations atomically
 //!
@@ -30,21 +53,65 @@
 //! - Indexes are 0-based character offsets. An empty document body starts
 //!   with a newline at index 0, so insert at index 1 to prepend text.
 //! - Use index -1 to append at the end of the document.
-//! - When doing multiple edits, process from highest index to lowest
-//!   to avoid index shifting issues.
+//! - For several edits, use apply_edits with indices from the document as
+//!   you read it; it orders them so earlier edits don't shift later ones.
+//!   Edits that change the same text are refused, so combine those.
+//! - To edit one section, use get_outline or read_section for its range
+//!   instead of reading the whole document. The last section ends at the end
+//!   of the body, whose final newline can't be deleted.
+//! - Margins are in points (72 points = 1 inch). Page size, orientation and
+//!   margins apply to the whole document; use insert_page_break to start a
+//!   chapter on a new page.
+//! - Tables are numbered from 0 in body order, and so are their rows and
+//!   columns. Fill a new table with write_table_cell instead of computing
+//!   cell indices by hand.
+//! - Run refresh_toc after adding, renaming or removing headings; it finds
+//!   the table of contents generate_toc made and keeps its position.
+//! - For templates, name each placeholder once with create_named_range
+//!   (`"find": "{{summary}}"`), then fill it with replace_named_range_content.
+//!   The range keeps its name around the new text, so filling it again
+//!   replaces the previous text wherever edits have moved it.
+//! - For reviews: list_comments shows each comment's `quoted_text`, so find
+//!   that passage, edit it, then reply_to_comment with `"resolve": true`.
+//!   add_comment quotes the passage it is about; the Docs editor shows the
+//!   quote with the comment but doesn't highlight it in the text.
+//! - export_document returns small exports as base64 (up to 512 KiB); set
+//!   `save_to_drive` or `folder_id` for larger ones or to share the file.
+//! - For "what changed since yesterday", call compare_revisions with `since`
+//!   and summarize from `stats` and the diff. Lines are paragraphs, so a
+//!   small edit shows as the whole paragraph removed and re-added.
 //!
 //! # Example Usage
 //!
 //! ```json
 //! {"action": "create_document", "title": "Meeting Notes"}
 //! {"action": "read_content", "document_id": "abc123"}
+//! {"action": "get_outline", "document_id": "abc123"}
+//! {"action": "read_section", "document_id": "abc123", "heading": "Risks"}
 //! {"action": "insert_text", "document_id": "abc123", "text": "Hello World\n", "index": 1}
 //! {"action": "replace_text", "document_id": "abc123", "find": "Hello", "replace": "Hi"}
 //! {"action": "format_text", "document_id": "abc123", "start_index": 1, "end_index": 12, "bold": true, "font_size": 18}
 //! {"action": "format_paragraph", "document_id": "abc123", "start_index": 1, "end_index": 12, "named_style": "HEADING_1"}
+//! {"action": "update_document_style", "document_id": "abc123", "page_size": "A4", "margin_left": 72, "margin_right": 72}
+//! {"action": "insert_page_break", "document_id": "abc123", "index": 250}
+//! {"action": "write_table_cell", "document_id": "abc123", "table_index": 0, "row": 0, "column": 1, "text": "Owner"}
+//! {"action": "append_table_row", "document_id": "abc123", "values": ["Launch", "Ana", "May 4"]}
+//! {"action": "generate_toc", "document_id": "abc123", "max_level": 2, "title": "Contents"}
+//! {"action": "refresh_toc", "document_id": "abc123"}
+//! {"action": "create_named_range", "document_id": "abc123", "name": "executive_summary", "find": "{{executive_summary}}"}
+//! {"action": "replace_named_range_content", "document_id": "abc123", "name": "executive_summary", "text": "Revenue grew 12%."}
+//! {"action": "add_comment", "document_id": "abc123", "quote": "ship in May", "content": "Is May still realistic?"}
+//! {"action": "reply_to_comment", "document_id": "abc123", "comment_id": "AAAA1", "content": "Moved to June.", "resolve": true}
+//! {"action": "export_document", "document_id": "abc123", "format": "pdf", "folder_id": "folder456"}
+//! {"action": "apply_edits", "document_id": "abc123", "edits": [{"type": "replace", "start_index": 40, "end_index": 43, "text": "June"}, {"type": "insert", "index": 1, "text": "Draft\n"}, {"type": "format_text", "start_index": 10, "end_index": 20, "bold": true}]}
+//! {"action": "compare_revisions", "document_id": "abc123", "since": "2024-05-01T09:00:00Z"}
 //! ```
 
 mod api;
+#[path = "../../google-common/api_error.rs"]
+mod api_error;
+mod diff;
+mod edits;
 mod types;
 
 use types::GoogleDocsAction;
@@ -91,6 +158,16 @@ impl exports::near::agent::tool::Guest for GoogleDocsTool {
                         "document_id": {
                             "type": "string",
                             "description": "The document ID (same as Google Drive file ID)"
+                        },
+                        "detail": {
+                            "type": "string",
+                            "enum": ["summary", "standard", "full"],
+                            "description": "'summary': title and length; 'standard': named ranges and a heading outline with indices; 'full': every paragraph with its indices and complete text",
+                            "default": "standard"
+                        },
+                        "fields": {
+                            "type": "string",
+                            "description": "Google API field mask (e.g. 'title,body/content(startIndex,table/rows)'). Returns the raw API response limited to these fields instead of the summary"
                         }
                     },
                     "required": ["action", "document_id"]
@@ -105,6 +182,35 @@ impl exports::near::agent::tool::Guest for GoogleDocsTool {
                     },
                     "required": ["action", "document_id"]
                 },
+                {
+                    "properties": {
+                        "action": { "const": "get_outline" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        }
+                    },
+                    "required": ["action", "document_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "read_section" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "heading": {
+                            "type": "string",
+                            "description": "Heading text (case-insensitive; a unique partial match also works)"
+                        },
+                        "include_subsections": {
+                            "type": "boolean",
+                            "description": "Include text under lower-level headings within the section (default: true)",
+                            "default": true
+                        }
+                    },
+                    "required": ["action", "document_id", "heading"]
+                },
                 {
                     "properties": {
                         "action": { "const": "insert_text" },
@@ -257,6 +363,78 @@ impl exports::near::agent::tool::Guest for GoogleDocsTool {
                     },
                     "required": ["action", "document_id", "start_index", "end_index"]
                 },
+                {
+                    "properties": {
+                        "action": { "const": "update_document_style" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "page_size": {
+                            "type": "string",
+                            "enum": ["LETTER", "LEGAL", "TABLOID", "A3", "A4", "A5"],
+                            "description": "Paper size"
+                        },
+                        "orientation": {
+                            "type": "string",
+                            "enum": ["PORTRAIT", "LANDSCAPE"],
+                            "description": "Page orientation; without page_size the current size is turned"
+                        },
+                        "margin_top": {
+                            "type": "number",
+                            "description": "Top margin in points (72 points = 1 inch)"
+                        },
+                        "margin_bottom": {
+                            "type": "number",
+                            "description": "Bottom margin in points"
+                        },
+                        "margin_left": {
+                            "type": "number",
+                            "description": "Left margin in points"
+                        },
+                        "margin_right": {
+                            "type": "number",
+                            "description": "Right margin in points"
+                        }
+                    },
+                    "required": ["action", "document_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "insert_section_break" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "index": {
+                            "type": "integer",
+                            "description": "Character index to insert at. Use -1 to append at end.",
+                            "default": -1
+                        },
+                        "section_type": {
+                            "type": "string",
+                            "enum": ["NEXT_PAGE", "CONTINUOUS"],
+                            "description": "Start the new section on a new page, or right after the break",
+                            "default": "NEXT_PAGE"
+                        }
+                    },
+                    "required": ["action", "document_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "insert_page_break" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "index": {
+                            "type": "integer",
+                            "description": "Character index to insert at. Use -1 to append at end.",
+                            "default": -1
+                        }
+                    },
+                    "required": ["action", "document_id"]
+                },
                 {
                     "properties": {
                         "action": { "const": "insert_table" },
@@ -279,6 +457,337 @@ impl exports::near::agent::tool::Guest for GoogleDocsTool {
                     },
                     "required": ["action", "document_id", "rows", "columns", "index"]
                 },
+                {
+                    "properties": {
+                        "action": { "const": "read_table" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "table_index": {
+                            "type": "integer",
+                            "description": "Which table, counting the body's tables from 0",
+                            "default": 0
+                        }
+                    },
+                    "required": ["action", "document_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "write_table_cell" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "table_index": {
+                            "type": "integer",
+                            "description": "Which table, counting the body's tables from 0",
+                            "default": 0
+                        },
+                        "row": {
+                            "type": "integer",
+                            "description": "Row, from 0"
+                        },
+                        "column": {
+                            "type": "integer",
+                            "description": "Column, from 0"
+                        },
+                        "text": {
+                            "type": "string",
+                            "description": "New cell text, replacing what is there (empty clears the cell)"
+                        }
+                    },
+                    "required": ["action", "document_id", "row", "column", "text"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "append_table_row" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "table_index": {
+                            "type": "integer",
+                            "description": "Which table, counting the body's tables from 0",
+                            "default": 0
+                        },
+                        "values": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Cell texts from the first column; missing ones stay empty"
+                        }
+                    },
+                    "required": ["action", "document_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "generate_toc" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "index": {
+                            "type": "integer",
+                            "description": "Character index to insert at (default: after the title and subtitle at the top)"
+                        },
+                        "max_level": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "maximum": 6,
+                            "description": "Deepest heading level to list (default: 3)",
+                            "default": 3
+                        },
+                        "title": {
+                            "type": "string",
+                            "description": "Line shown above the entries, e.g. 'Contents' (default: none)"
+                        }
+                    },
+                    "required": ["action", "document_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "refresh_toc" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "max_level": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "maximum": 6,
+                            "description": "New deepest heading level (default: as generated)"
+                        },
+                        "title": {
+                            "type": "string",
+                            "description": "New title line; empty removes it (default: as generated)"
+                        }
+                    },
+                    "required": ["action", "document_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "create_named_range" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "name": {
+                            "type": "string",
+                            "description": "Name for the range, e.g. 'executive_summary'; must not already exist"
+                        },
+                        "find": {
+                            "type": "string",
+                            "description": "Text to name every occurrence of, e.g. '{{executive_summary}}'. Use this or start_index and end_index"
+                        },
+                        "start_index": {
+                            "type": "integer",
+                            "description": "Start index (inclusive)"
+                        },
+                        "end_index": {
+                            "type": "integer",
+                            "description": "End index (exclusive)"
+                        }
+                    },
+                    "required": ["action", "document_id", "name"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "list_named_ranges" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        }
+                    },
+                    "required": ["action", "document_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "replace_named_range_content" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "name": {
+                            "type": "string",
+                            "description": "Name of the range; every range with this name is replaced"
+                        },
+                        "text": {
+                            "type": "string",
+                            "description": "New text (not empty). The range keeps its name, so this can be repeated"
+                        }
+                    },
+                    "required": ["action", "document_id", "name", "text"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "list_comments" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "include_resolved": {
+                            "type": "boolean",
+                            "description": "Include resolved comments",
+                            "default": false
+                        }
+                    },
+                    "required": ["action", "document_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "add_comment" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "content": {
+                            "type": "string",
+                            "description": "Comment text"
+                        },
+                        "quote": {
+                            "type": "string",
+                            "description": "Text the comment is about; its first occurrence is quoted. Use this or start_index and end_index; omit both to comment on the whole document"
+                        },
+                        "start_index": {
+                            "type": "integer",
+                            "description": "Start index (inclusive) of the text the comment is about"
+                        },
+                        "end_index": {
+                            "type": "integer",
+                            "description": "End index (exclusive)"
+                        }
+                    },
+                    "required": ["action", "document_id", "content"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "reply_to_comment" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "comment_id": {
+                            "type": "string",
+                            "description": "Comment ID from list_comments or add_comment"
+                        },
+                        "content": {
+                            "type": "string",
+                            "description": "Reply text (may be empty when resolve is set)"
+                        },
+                        "resolve": {
+                            "type": "boolean",
+                            "description": "true resolves the comment with this reply; false reopens a resolved one"
+                        }
+                    },
+                    "required": ["action", "document_id", "comment_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "export_document" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "format": {
+                            "type": "string",
+                            "enum": ["pdf", "docx", "html", "txt"],
+                            "description": "Export format (default: pdf)",
+                            "default": "pdf"
+                        },
+                        "save_to_drive": {
+                            "type": "boolean",
+                            "description": "Save the export to Drive and return its file ID instead of base64 content (default: false)",
+                            "default": false
+                        },
+                        "folder_id": {
+                            "type": "string",
+                            "description": "Drive folder to save the export in; implies save_to_drive (default: My Drive)"
+                        },
+                        "name": {
+                            "type": "string",
+                            "description": "File name for the saved export (default: document title plus extension)"
+                        }
+                    },
+                    "required": ["action", "document_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "compare_revisions" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "from_revision": {
+                            "type": "string",
+                            "description": "Older revision ID (default: the last revision at or before since, or else the one before to_revision)"
+                        },
+                        "to_revision": {
+                            "type": "string",
+                            "description": "Newer revision ID (default: the latest)"
+                        },
+                        "since": {
+                            "type": "string",
+                            "description": "UTC timestamp to compare from, e.g. '2024-05-01T09:00:00Z'"
+                        },
+                        "context_lines": {
+                            "type": "integer",
+                            "description": "Unchanged lines shown around each change (default: 3)",
+                            "default": 3
+                        }
+                    },
+                    "required": ["action", "document_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "apply_edits" },
+                        "document_id": {
+                            "type": "string",
+                            "description": "The document ID"
+                        },
+                        "edits": {
+                            "type": "array",
+                            "description": "Edits in any order, with indices from the document as read before any of them",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "type": {
+                                        "type": "string",
+                                        "enum": ["insert", "delete", "replace", "format_text", "format_paragraph"]
+                                    },
+                                    "index": {
+                                        "type": "integer",
+                                        "description": "insert: character index, or -1 to append"
+                                    },
+                                    "start_index": {
+                                        "type": "integer",
+                                        "description": "Range start (inclusive)"
+                                    },
+                                    "end_index": {
+                                        "type": "integer",
+                                        "description": "Range end (exclusive)"
+                                    },
+                                    "text": {
+                                        "type": "string",
+                                        "description": "insert and replace: the new text"
+                                    },
+                                    "bold": { "type": "boolean" },
+                                    "italic": { "type": "boolean" },
+                                    "underline": { "type": "boolean" },
+                                    "strikethrough": { "type": "boolean" },
+                                    "font_size": { "type": "number" },
+                                    "font_family": { "type": "string" },
+                                    "foreground_color": { "type": "string" },
+                                    "background_color": { "type": "string" },
+                                    "named_style": { "type": "string" },
+                                    "alignment": { "type": "string" },
+                                    "line_spacing": { "type": "number" }
+                                },
+                                "required": ["type"]
+                            }
+                        }
+                    },
+                    "required": ["action", "document_id", "edits"]
+                },
                 {
                     "properties": {
                         "action": { "const": "create_list" },
@@ -325,23 +834,29 @@ impl exports::near::agent::tool::Guest for GoogleDocsTool {
 
     fn description() -> String {
         "Google Docs integration for creating, reading, editing, and formatting documents. \
-         Supports text operations (insert, delete, find-replace), text formatting (bold, italic, \
-         font, color, size), paragraph styling (headings, alignment, spacing), tables, and \
-         bulleted/numbered lists. Also provides a batch_update action for complex multi-step \
-         edits executed atomically. Document IDs are the same as Google Drive file IDs, so use \
-         the google-drive tool to search for existing documents. Requires a Google OAuth token \
-         with the documents scope."
+         Supports reading a document's heading outline or a single section by heading, \
+         text operations (insert, delete, find-replace), text formatting (bold, italic, \
+         font, color, size), paragraph styling (headings, alignment, spacing), page setup \
+         (size, orientation, margins) with page and section breaks, tables (create, read, \
+         write cells, append rows), bulleted/numbered lists, a linked table of contents \
+         that can be refreshed after edits, and named ranges for filling template \
+         placeholders repeatably. Takes part in reviews by listing comments, adding \
+         comments on passages of text, and replying to or resolving them. Exports documents \
+         as PDF, DOCX, HTML or plain text, returned as base64 or saved to a Drive folder, \
+         and compares revisions with a unified diff and change counts to report what \
+         changed since a given time. Several edits can be applied in one batch with \
+         apply_edits, which orders them so their indices stay valid, and batch_update \
+         runs raw Docs API requests atomically. Document IDs are the same as Google Drive \
+         file IDs, so use the google-drive tool to search for existing documents. Requires \
+         a Google OAuth token with the documents scope (plus the drive scope for comments, \
+         exports and revisions)."
             .to_string()
     }
 }
 
 fn execute_inner(params: &str) -> Result<String, String> {
     if !crate::near::agent::host::secret_exists("google_oauth_token") {
-        return Err(
-            "Google OAuth token not configured. Run `ironclaw tool auth google-docs` to set up \
-             OAuth, or set the GOOGLE_OAUTH_TOKEN environment variable."
-                .to_string(),
-        );
+        return Err(api_error::missing_token("google-docs"));
     }
 
     let action: GoogleDocsAction =
@@ -358,16 +873,37 @@ fn execute_inner(params: &str) -> Result<String, String> {
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
-        GoogleDocsAction::GetDocument { document_id } => {
-            let result = api::get_document(&document_id)?;
-            serde_json::to_string(&result).map_err(|e| e.to_string())?
-        }
+        GoogleDocsAction::GetDocument {
+            document_id,
+            detail,
+            fields,
+        } => match fields {
+            Some(fields) => api::get_document_fields(&document_id, &fields)?,
+            None => {
+                let result = api::get_document(&document_id, detail)?;
+                serde_json::to_string(&result).map_err(|e| e.to_string())?
+            }
+        },
 
         GoogleDocsAction::ReadContent { document_id } => {
             let result = api::read_content(&document_id)?;
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
+        GoogleDocsAction::GetOutline { document_id } => {
+            let result = api::get_outline(&document_id)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::ReadSection {
+            document_id,
+            heading,
+            include_subsections,
+        } => {
+            let result = api::read_section(&document_id, &heading, include_subsections)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
         GoogleDocsAction::InsertText {
             document_id,
             text,
@@ -446,6 +982,41 @@ fn execute_inner(params: &str) -> Result<String, String> {
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
+        GoogleDocsAction::UpdateDocumentStyle {
+            document_id,
+            page_size,
+            orientation,
+            margin_top,
+            margin_bottom,
+            margin_left,
+            margin_right,
+        } => {
+            let result = api::update_document_style(api::DocumentStyleOptions {
+                document_id: &document_id,
+                page_size: page_size.as_deref(),
+                orientation: orientation.as_deref(),
+                margin_top,
+                margin_bottom,
+                margin_left,
+                margin_right,
+            })?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::InsertSectionBreak {
+            document_id,
+            index,
+            section_type,
+        } => {
+            let result = api::insert_section_break(&document_id, index, &section_type)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::InsertPageBreak { document_id, index } => {
+            let result = api::insert_page_break(&document_id, index)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
         GoogleDocsAction::InsertTable {
             document_id,
             rows,
@@ -456,6 +1027,158 @@ fn execute_inner(params: &str) -> Result<String, String> {
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
+        GoogleDocsAction::ReadTable {
+            document_id,
+            table_index,
+        } => {
+            let result = api::read_table(&document_id, table_index)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::WriteTableCell {
+            document_id,
+            table_index,
+            row,
+            column,
+            text,
+        } => {
+            let result = api::write_table_cell(&document_id, table_index, row, column, &text)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::AppendTableRow {
+            document_id,
+            table_index,
+            values,
+        } => {
+            let result = api::append_table_row(&document_id, table_index, &values)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::GenerateToc {
+            document_id,
+            index,
+            max_level,
+            title,
+        } => {
+            let result = api::generate_toc(&document_id, index, max_level, title.as_deref())?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::RefreshToc {
+            document_id,
+            max_level,
+            title,
+        } => {
+            let result = api::refresh_toc(&document_id, max_level, title.as_deref())?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::CreateNamedRange {
+            document_id,
+            name,
+            find,
+            start_index,
+            end_index,
+        } => {
+            let result = api::create_named_range(
+                &document_id,
+                &name,
+                find.as_deref(),
+                start_index,
+                end_index,
+            )?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::ListNamedRanges { document_id } => {
+            let result = api::list_named_ranges(&document_id)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::ReplaceNamedRangeContent {
+            document_id,
+            name,
+            text,
+        } => {
+            let result = api::replace_named_range_content(&document_id, &name, &text)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::ListComments {
+            document_id,
+            include_resolved,
+        } => {
+            let result = api::list_comments(&document_id, include_resolved)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::AddComment {
+            document_id,
+            content,
+            quote,
+            start_index,
+            end_index,
+        } => {
+            let result = api::add_comment(
+                &document_id,
+                &content,
+                quote.as_deref(),
+                start_index,
+                end_index,
+            )?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::ReplyToComment {
+            document_id,
+            comment_id,
+            content,
+            resolve,
+        } => {
+            let result = api::reply_to_comment(&document_id, &comment_id, &content, resolve)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::ExportDocument {
+            document_id,
+            format,
+            save_to_drive,
+            folder_id,
+            name,
+        } => {
+            let result = api::export_document(
+                &document_id,
+                &format,
+                save_to_drive,
+                folder_id.as_deref(),
+                name.as_deref(),
+            )?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::CompareRevisions {
+            document_id,
+            from_revision,
+            to_revision,
+            since,
+            context_lines,
+        } => {
+            let result = api::compare_revisions(
+                &document_id,
+                from_revision.as_deref(),
+                to_revision.as_deref(),
+                since.as_deref(),
+                context_lines,
+            )?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDocsAction::ApplyEdits { document_id, edits } => {
+            let result = api::apply_edits(&document_id, &edits)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
         GoogleDocsAction::CreateList {
             document_id,
             start_index,
@@ -16,6 +16,13 @@ pub enum GoogleDocsAction {
     GetDocument {
         /// The document ID (same as Google Drive file ID).
         document_id: String,
+        /// How much of the document structure to include (default: standard).
+        #[serde(default)]
+        detail: Detail,
+        /// Google API field mask. When set, the raw API response limited to
+        /// these fields is returned instead of the summarized result.
+        #[serde(default)]
+        fields: Option<String>,
     },
 
     /// Read the document body as plain text.
@@ -24,6 +31,24 @@ pub enum GoogleDocsAction {
         document_id: String,
     },
 
+    /// Get the heading hierarchy with the index range of each section.
+    GetOutline {
+        /// The document ID.
+        document_id: String,
+    },
+
+    /// Read the plain text of the section under a heading.
+    ReadSection {
+        /// The document ID.
+        document_id: String,
+        /// Heading text to find (case-insensitive; a unique partial match
+        /// also works).
+        heading: String,
+        /// Include subsections under lower-level headings (default: true).
+        #[serde(default = "default_true")]
+        include_subsections: bool,
+    },
+
     /// Insert text at a position.
     InsertText {
         /// The document ID.
@@ -130,6 +155,241 @@ pub enum GoogleDocsAction {
         index: i64,
     },
 
+    /// Set page size, orientation and margins for the whole document.
+    UpdateDocumentStyle {
+        /// The document ID.
+        document_id: String,
+        /// Paper size: "LETTER", "LEGAL", "TABLOID", "A3", "A4" or "A5".
+        #[serde(default)]
+        page_size: Option<String>,
+        /// "PORTRAIT" or "LANDSCAPE".
+        #[serde(default)]
+        orientation: Option<String>,
+        /// Top margin in points (72 points = 1 inch).
+        #[serde(default)]
+        margin_top: Option<f64>,
+        /// Bottom margin in points.
+        #[serde(default)]
+        margin_bottom: Option<f64>,
+        /// Left margin in points.
+        #[serde(default)]
+        margin_left: Option<f64>,
+        /// Right margin in points.
+        #[serde(default)]
+        margin_right: Option<f64>,
+    },
+
+    /// Insert a section break.
+    InsertSectionBreak {
+        /// The document ID.
+        document_id: String,
+        /// Character index to insert at. Use -1 to append at end.
+        #[serde(default = "default_insert_index")]
+        index: i64,
+        /// "NEXT_PAGE" starts the new section on a new page, "CONTINUOUS"
+        /// right after the break.
+        #[serde(default = "default_section_type")]
+        section_type: String,
+    },
+
+    /// Insert a page break.
+    InsertPageBreak {
+        /// The document ID.
+        document_id: String,
+        /// Character index to insert at. Use -1 to append at end.
+        #[serde(default = "default_insert_index")]
+        index: i64,
+    },
+
+    /// Read a table's cells with their text and index ranges.
+    ReadTable {
+        /// The document ID.
+        document_id: String,
+        /// Which table, counting the body's tables from 0 (default: 0).
+        #[serde(default)]
+        table_index: usize,
+    },
+
+    /// Replace the text of one table cell.
+    WriteTableCell {
+        /// The document ID.
+        document_id: String,
+        /// Which table, counting the body's tables from 0 (default: 0).
+        #[serde(default)]
+        table_index: usize,
+        /// Row, from 0.
+        row: usize,
+        /// Column, from 0.
+        column: usize,
+        /// New cell text; empty clears the cell.
+        text: String,
+    },
+
+    /// Add a row to the end of a table and fill it.
+    AppendTableRow {
+        /// The document ID.
+        document_id: String,
+        /// Which table, counting the body's tables from 0 (default: 0).
+        #[serde(default)]
+        table_index: usize,
+        /// Cell texts from the first column; missing ones stay empty.
+        #[serde(default)]
+        values: Vec<String>,
+    },
+
+    /// Insert a linked table of contents built from the headings.
+    GenerateToc {
+        /// The document ID.
+        document_id: String,
+        /// Character index to insert at (default: after the title and
+        /// subtitle at the top of the document).
+        #[serde(default)]
+        index: Option<i64>,
+        /// Deepest heading level listed, 1-6 (default: 3).
+        #[serde(default = "default_toc_max_level")]
+        max_level: u8,
+        /// Line shown above the entries, e.g. "Contents" (default: none).
+        #[serde(default)]
+        title: Option<String>,
+    },
+
+    /// Rebuild the table of contents inserted by generate_toc.
+    RefreshToc {
+        /// The document ID.
+        document_id: String,
+        /// New deepest heading level (default: as generated).
+        #[serde(default)]
+        max_level: Option<u8>,
+        /// New title line; empty removes it (default: as generated).
+        #[serde(default)]
+        title: Option<String>,
+    },
+
+    /// Name a range, or every occurrence of a placeholder, so it can be
+    /// found again after edits shift the indices.
+    CreateNamedRange {
+        /// The document ID.
+        document_id: String,
+        /// Name of the range, e.g. "executive_summary".
+        name: String,
+        /// Text to name each occurrence of, e.g. "{{executive_summary}}".
+        /// Use this or start_index and end_index.
+        #[serde(default)]
+        find: Option<String>,
+        /// Start index (inclusive).
+        #[serde(default)]
+        start_index: Option<i64>,
+        /// End index (exclusive).
+        #[serde(default)]
+        end_index: Option<i64>,
+    },
+
+    /// List the named ranges with their current indices and text.
+    ListNamedRanges {
+        /// The document ID.
+        document_id: String,
+    },
+
+    /// Replace the text of every range with a name. The ranges keep the
+    /// name, so this can be repeated.
+    ReplaceNamedRangeContent {
+        /// The document ID.
+        document_id: String,
+        /// Name of the range.
+        name: String,
+        /// New text; must not be empty.
+        text: String,
+    },
+
+    /// List the comments on the document, with their replies.
+    ListComments {
+        /// The document ID.
+        document_id: String,
+        /// Include resolved comments (default: open ones only).
+        #[serde(default)]
+        include_resolved: bool,
+    },
+
+    /// Comment on a passage of text, or on the whole document.
+    AddComment {
+        /// The document ID.
+        document_id: String,
+        /// Comment text.
+        content: String,
+        /// Text the comment is about; its first occurrence is quoted.
+        /// Use this or start_index and end_index.
+        #[serde(default)]
+        quote: Option<String>,
+        /// Start index (inclusive).
+        #[serde(default)]
+        start_index: Option<i64>,
+        /// End index (exclusive).
+        #[serde(default)]
+        end_index: Option<i64>,
+    },
+
+    /// Reply to a comment, optionally resolving or reopening it.
+    ReplyToComment {
+        /// The document ID.
+        document_id: String,
+        /// Comment ID from list_comments or add_comment.
+        comment_id: String,
+        /// Reply text; may be empty when `resolve` is set.
+        #[serde(default)]
+        content: String,
+        /// true resolves the comment with this reply, false reopens it.
+        #[serde(default)]
+        resolve: Option<bool>,
+    },
+
+    /// Export the document as PDF, DOCX, HTML or plain text.
+    ExportDocument {
+        /// The document ID.
+        document_id: String,
+        /// "pdf" (default), "docx", "html", or "txt".
+        #[serde(default = "default_export_format")]
+        format: String,
+        /// Save the export to Drive instead of returning it as base64.
+        /// Implied by folder_id.
+        #[serde(default)]
+        save_to_drive: bool,
+        /// Drive folder to save the export in (default: My Drive).
+        #[serde(default)]
+        folder_id: Option<String>,
+        /// File name for the saved export (default: the document title
+        /// with the format's extension).
+        #[serde(default)]
+        name: Option<String>,
+    },
+
+    /// Diff two revisions of the document as plain text.
+    CompareRevisions {
+        /// The document ID.
+        document_id: String,
+        /// Older revision (default: the last one at or before `since`, or
+        /// else the one before `to_revision`).
+        #[serde(default)]
+        from_revision: Option<String>,
+        /// Newer revision (default: the latest).
+        #[serde(default)]
+        to_revision: Option<String>,
+        /// UTC timestamp (RFC 3339) to compare from, e.g.
+        /// "2024-05-01T09:00:00Z". Ignored when from_revision is given.
+        #[serde(default)]
+        since: Option<String>,
+        /// Unchanged lines shown around each change.
+        #[serde(default = "default_context_lines")]
+        context_lines: usize,
+    },
+
+    /// Apply several edits in one batch, ordered so their indices stay valid.
+    ApplyEdits {
+        /// The document ID.
+        document_id: String,
+        /// Edits with indices into the document as read, before any of them.
+        edits: Vec<DocEdit>,
+    },
+
     /// Create a bulleted or numbered list from a range of paragraphs.
     CreateList {
         /// The document ID.
@@ -155,14 +415,83 @@ pub enum GoogleDocsAction {
     },
 }
 
+/// One edit for apply_edits. Indices refer to the document as it was read;
+/// the order the edits are listed in doesn't matter.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DocEdit {
+    /// Insert text at an index, or at the end of the body with -1.
+    Insert {
+        #[serde(default = "default_insert_index")]
+        index: i64,
+        text: String,
+    },
+    /// Delete a range.
+    Delete { start_index: i64, end_index: i64 },
+    /// Replace a range with new text.
+    Replace {
+        start_index: i64,
+        end_index: i64,
+        text: String,
+    },
+    /// Text style for a range; options left out are unchanged.
+    FormatText {
+        start_index: i64,
+        end_index: i64,
+        #[serde(default)]
+        bold: Option<bool>,
+        #[serde(default)]
+        italic: Option<bool>,
+        #[serde(default)]
+        underline: Option<bool>,
+        #[serde(default)]
+        strikethrough: Option<bool>,
+        #[serde(default)]
+        font_size: Option<f64>,
+        #[serde(default)]
+        font_family: Option<String>,
+        #[serde(default)]
+        foreground_color: Option<String>,
+        #[serde(default)]
+        background_color: Option<String>,
+    },
+    /// Paragraph style for the paragraphs a range touches.
+    FormatParagraph {
+        start_index: i64,
+        end_index: i64,
+        #[serde(default)]
+        named_style: Option<String>,
+        #[serde(default)]
+        alignment: Option<String>,
+        #[serde(default)]
+        line_spacing: Option<f64>,
+    },
+}
+
 fn default_insert_index() -> i64 {
     -1
 }
 
+fn default_section_type() -> String {
+    "NEXT_PAGE".to_string()
+}
+
 fn default_true() -> bool {
     true
 }
 
+fn default_toc_max_level() -> u8 {
+    3
+}
+
+fn default_export_format() -> String {
+    "pdf".to_string()
+}
+
+fn default_context_lines() -> usize {
+    3
+}
+
 fn default_bullet_preset() -> String {
     "BULLET_DISC_CIRCLE_SQUARE".to_string()
 }
@@ -183,6 +512,32 @@ pub struct DocumentMetadata {
     pub body_length: i64,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub named_ranges: Vec<DocumentNamedRange>,
+    /// Headings (standard detail) or every paragraph (full detail).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub paragraphs: Vec<DocumentParagraph>,
+}
+
+/// How much of a document get_document returns.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Detail {
+    /// Title, revision and length only.
+    Summary,
+    /// Named ranges and a heading outline with text previews.
+    #[default]
+    Standard,
+    /// Named ranges and every paragraph with its complete text.
+    Full,
+}
+
+/// A top-level paragraph, with the indices needed to edit it.
+#[derive(Debug, Serialize)]
+pub struct DocumentParagraph {
+    /// Named style, e.g. "HEADING_1" or "NORMAL_TEXT".
+    pub style: String,
+    pub start_index: i64,
+    pub end_index: i64,
+    pub text: String,
 }
 
 /// Named range within a document.
@@ -192,6 +547,135 @@ pub struct DocumentNamedRange {
     pub named_range_id: String,
     pub start_index: i64,
     pub end_index: i64,
+    /// Text currently in the range (list_named_ranges only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+/// Result from get_outline.
+#[derive(Debug, Serialize)]
+pub struct OutlineResult {
+    pub document_id: String,
+    pub title: String,
+    pub revision_id: String,
+    pub body_length: i64,
+    pub headings: Vec<OutlineHeading>,
+}
+
+/// A heading and the section it opens.
+#[derive(Debug, Clone, Serialize)]
+pub struct OutlineHeading {
+    /// 0 for the title, 1-6 for HEADING_1 to HEADING_6.
+    pub level: u8,
+    pub text: String,
+    /// Start of the heading paragraph.
+    pub start_index: i64,
+    /// End of the heading paragraph, where the section's content begins.
+    pub heading_end_index: i64,
+    /// End of the section: the next heading at the same or a higher level,
+    /// or the end of the body.
+    pub end_index: i64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<OutlineHeading>,
+}
+
+/// Result from read_section.
+#[derive(Debug, Serialize)]
+pub struct SectionResult {
+    pub document_id: String,
+    pub revision_id: String,
+    pub heading: String,
+    pub level: u8,
+    /// Start of the heading paragraph.
+    pub heading_start_index: i64,
+    /// Range of the section's content, after the heading.
+    pub start_index: i64,
+    pub end_index: i64,
+    pub text: String,
+}
+
+/// Result from read_table.
+#[derive(Debug, Serialize)]
+pub struct TableResult {
+    pub document_id: String,
+    pub revision_id: String,
+    pub table_index: usize,
+    pub start_index: i64,
+    pub end_index: i64,
+    pub rows: usize,
+    pub columns: usize,
+    /// Cells by row, then column.
+    pub cells: Vec<Vec<TableCell>>,
+}
+
+/// A table cell's text and the range of its content.
+#[derive(Debug, Serialize)]
+pub struct TableCell {
+    pub text: String,
+    /// Where the cell's content starts; insert here to prepend.
+    pub start_index: i64,
+    /// End of the cell's content, after its final newline.
+    pub end_index: i64,
+}
+
+/// Result from append_table_row.
+#[derive(Debug, Serialize)]
+pub struct AppendRowResult {
+    pub document_id: String,
+    pub revision_id: String,
+    /// Index of the new row, from 0.
+    pub row: usize,
+}
+
+/// Result from generate_toc and refresh_toc.
+#[derive(Debug, Serialize)]
+pub struct TocResult {
+    pub document_id: String,
+    pub revision_id: String,
+    /// Range the table of contents now occupies.
+    pub start_index: i64,
+    pub end_index: i64,
+    pub max_level: u8,
+    pub entries: Vec<TocEntry>,
+}
+
+/// A heading listed in the table of contents.
+#[derive(Debug, Clone, Serialize)]
+pub struct TocEntry {
+    pub level: u8,
+    pub text: String,
+    /// The heading's ID, which the entry links to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_id: Option<String>,
+}
+
+/// Result from create_named_range.
+#[derive(Debug, Serialize)]
+pub struct CreateNamedRangeResult {
+    pub document_id: String,
+    pub revision_id: String,
+    pub name: String,
+    /// One named range per marked range.
+    pub named_ranges: Vec<DocumentNamedRange>,
+}
+
+/// Result from list_named_ranges.
+#[derive(Debug, Serialize)]
+pub struct NamedRangesResult {
+    pub document_id: String,
+    pub revision_id: String,
+    /// One entry per range, in document order.
+    pub named_ranges: Vec<DocumentNamedRange>,
+}
+
+/// Result from replace_named_range_content.
+#[derive(Debug, Serialize)]
+pub struct ReplaceNamedRangeResult {
+    pub document_id: String,
+    pub revision_id: String,
+    pub name: String,
+    /// How many ranges with the name were replaced.
+    pub ranges_replaced: usize,
 }
 
 /// Result from read_content.
@@ -217,6 +701,14 @@ pub struct ReplaceResult {
     pub occurrences_changed: i64,
 }
 
+/// Result from apply_edits.
+#[derive(Debug, Serialize)]
+pub struct ApplyEditsResult {
+    pub document_id: String,
+    pub revision_id: String,
+    pub edits_applied: usize,
+}
+
 /// Result from batch_update.
 #[derive(Debug, Serialize)]
 pub struct BatchUpdateResult {
@@ -224,3 +716,120 @@ pub struct BatchUpdateResult {
     pub revision_id: String,
     pub replies: Vec<serde_json::Value>,
 }
+
+/// A comment on a document.
+#[derive(Debug, Serialize)]
+pub struct DocComment {
+    pub comment_id: String,
+    pub author: String,
+    pub content: String,
+    pub created_time: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_time: Option<String>,
+    pub resolved: bool,
+    /// The document text the comment is about.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quoted_text: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub replies: Vec<CommentReply>,
+}
+
+/// A reply to a comment.
+#[derive(Debug, Serialize)]
+pub struct CommentReply {
+    pub reply_id: String,
+    pub author: String,
+    pub content: String,
+    pub created_time: String,
+    /// "resolve" or "reopen" when the reply changed the comment's state.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+}
+
+/// Result from list_comments.
+#[derive(Debug, Serialize)]
+pub struct CommentsResult {
+    pub document_id: String,
+    /// True when there were more comments than were read.
+    pub truncated: bool,
+    pub comments: Vec<DocComment>,
+}
+
+/// Result from add_comment.
+#[derive(Debug, Serialize)]
+pub struct AddCommentResult {
+    pub document_id: String,
+    /// Range of the quoted text, when the comment quotes some.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_index: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_index: Option<i64>,
+    pub comment: DocComment,
+}
+
+/// Result from reply_to_comment.
+#[derive(Debug, Serialize)]
+pub struct ReplyResult {
+    pub document_id: String,
+    pub comment_id: String,
+    pub reply: CommentReply,
+}
+
+/// A file saved to Drive.
+#[derive(Debug, Serialize)]
+pub struct DriveFile {
+    pub file_id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_view_link: Option<String>,
+}
+
+/// Result from export_document.
+#[derive(Debug, Serialize)]
+pub struct ExportResult {
+    pub document_id: String,
+    pub format: String,
+    pub mime_type: String,
+    pub size_bytes: usize,
+    /// The export, when it was returned inline.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_base64: Option<String>,
+    /// The saved file, when the export went to Drive.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<DriveFile>,
+}
+
+/// A revision from the Drive revisions API.
+#[derive(Debug, Clone, Serialize)]
+pub struct RevisionInfo {
+    pub revision_id: String,
+    pub modified_time: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_by: Option<String>,
+}
+
+/// What changed between two revisions.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct DiffStats {
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub words_added: usize,
+    pub words_removed: usize,
+    /// Separate places in the document that changed.
+    pub hunks: usize,
+}
+
+/// Result from compare_revisions.
+#[derive(Debug, Serialize)]
+pub struct RevisionDiffResult {
+    pub document_id: String,
+    pub from: RevisionInfo,
+    pub to: RevisionInfo,
+    /// Revisions between the two, counting `to`.
+    pub revisions_between: usize,
+    pub stats: DiffStats,
+    /// Unified diff of the two versions' text, one line per paragraph.
+    pub diff: String,
+    /// True when the diff was cut short; the stats still cover all of it.
+    pub truncated: bool,
+}
@@ -4,19 +4,25 @@
 //! credential injection and rate limiting. The WASM tool never sees
 //! the actual OAuth token.
 
+use crate::api_error::{api_error, GoogleApi};
 use crate::near::agent::host;
 use crate::types::*;
 
 const DRIVE_API_BASE: &str = "https://www.googleapis.com/drive/v3";
 const UPLOAD_API_BASE: &str = "https://www.googleapis.com/upload/drive/v3";
 
+const API: GoogleApi = GoogleApi {
+    name: "Google Drive",
+    scope: "https://www.googleapis.com/auth/drive",
+};
+
 /// Standard fields to request for file metadata.
 const FILE_FIELDS: &str = "id,name,mimeType,description,size,createdTime,modifiedTime,\
     webViewLink,parents,shared,starred,trashed,ownedByMe,driveId,\
     owners(emailAddress,displayName)";
 
 /// Make a Drive API call.
-fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, String> {
+pub(crate) fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, String> {
     let url = format!("{}/{}", DRIVE_API_BASE, path);
 
     let headers = if body.is_some() {
@@ -35,10 +41,11 @@ fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, Stri
     let response = host::http_request(method, &url, headers, body_bytes.as_deref())?;
 
     if response.status < 200 || response.status >= 300 {
-        let body_text = String::from_utf8_lossy(&response.body);
-        return Err(format!(
-            "Drive API returned status {}: {}",
-            response.status, body_text
+        return Err(api_error(
+            &API,
+            response.status,
+            &response.headers_json,
+            &response.body,
         ));
     }
 
@@ -59,10 +66,11 @@ fn api_call_raw(method: &str, url: &str) -> Result<Vec<u8>, String> {
     let response = host::http_request(method, url, "{}", None)?;
 
     if response.status < 200 || response.status >= 300 {
-        let body_text = String::from_utf8_lossy(&response.body);
-        return Err(format!(
-            "Drive API returned status {}: {}",
-            response.status, body_text
+        return Err(api_error(
+            &API,
+            response.status,
+            &response.headers_json,
+            &response.body,
         ));
     }
 
@@ -265,10 +273,11 @@ pub fn upload_file(
     let response = host::http_request("POST", &url, &headers, Some(body.as_bytes()))?;
 
     if response.status < 200 || response.status >= 300 {
-        let body_text = String::from_utf8_lossy(&response.body);
-        return Err(format!(
-            "Upload failed with status {}: {}",
-            response.status, body_text
+        return Err(api_error(
+            &API,
+            response.status,
+            &response.headers_json,
+            &response.body,
         ));
     }
 
@@ -494,7 +503,7 @@ pub fn list_shared_drives(page_size: u32) -> Result<ListSharedDrivesResult, Stri
 }
 
 /// Minimal percent-encoding for URL path segments and query values.
-fn url_encode(s: &str) -> String {
+pub(crate) fn url_encode(s: &str) -> String {
     let mut encoded = String::with_capacity(s.len());
     for b in s.bytes() {
         match b {
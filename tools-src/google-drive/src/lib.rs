@@ -7,6 +7,7 @@
 //! # Capabilities Required
 //!
 //! - HTTP: `www.googleapis.com/drive/v3/*` and `www.googleapis.com/upload/drive/v3/*`
+//! - HTTP: Docs, Slides and Sheets `batchUpdate` endpoints (for templates)
 //! - Secrets: `google_oauth_token` (shared OAuth 2.0 token, injected automatically)
 //!
 //! # Supported Actions
@@ -23,6 +24,9 @@
 //! - `list_permissions`: See who has access
 //! - `remove_permission`: Revoke access
 //! - `list_shared_drives`: List organizational shared drives
+//! - `register_template`: Register a Docs/Slides/Sheets file as a named template
+//! - `list_templates`: List registered templates and their placeholders
+//! - `instantiate_template`: Copy a template and fill in its placeholders
 //!
 //! # Example Usage
 //!
@@ -30,9 +34,13 @@
 //! {"action": "list_files", "query": "name contains 'report' and mimeType = 'application/pdf'"}
 //! {"action": "list_files", "corpora": "drive", "drive_id": "0ABcd...", "query": "trashed = false"}
 //! {"action": "share_file", "file_id": "abc123", "email": "alice@company.com", "role": "writer"}
+//! {"action": "instantiate_template", "template": "proposal", "values": {"client": "Acme"}}
 //! ```
 
 mod api;
+#[path = "../../google-common/api_error.rs"]
+mod api_error;
+mod templates;
 mod types;
 
 use types::GoogleDriveAction;
@@ -269,6 +277,69 @@ impl exports::near::agent::tool::Guest for GoogleDriveTool {
                         }
                     },
                     "required": ["action"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "register_template" },
+                        "file_id": {
+                            "type": "string",
+                            "description": "Google Docs, Slides or Sheets file to use as the template"
+                        },
+                        "name": {
+                            "type": "string",
+                            "description": "Name to instantiate the template by"
+                        },
+                        "placeholders": {
+                            "type": "array",
+                            "description": "Placeholders in the file, written as {{name}}",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "name": { "type": "string" },
+                                    "kind": {
+                                        "type": "string",
+                                        "enum": ["text", "image", "cell"],
+                                        "description": "text: replace {{name}} with the value. image (Slides): replace shapes containing {{name}} with the image at the value's URL. cell (Sheets): write the value to range.",
+                                        "default": "text"
+                                    },
+                                    "range": {
+                                        "type": "string",
+                                        "description": "A1 range for cell placeholders (e.g., 'Summary!B4')"
+                                    }
+                                },
+                                "required": ["name"]
+                            }
+                        }
+                    },
+                    "required": ["action", "file_id", "name", "placeholders"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "list_templates" }
+                    },
+                    "required": ["action"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "instantiate_template" },
+                        "template": {
+                            "type": "string",
+                            "description": "Registered template name"
+                        },
+                        "values": {
+                            "type": "object",
+                            "description": "Value for every placeholder, by name"
+                        },
+                        "title": {
+                            "type": "string",
+                            "description": "Name for the new file (default: 'Copy of <template file>')"
+                        },
+                        "folder_id": {
+                            "type": "string",
+                            "description": "Folder for the new file (default: the template's folder)"
+                        }
+                    },
+                    "required": ["action", "template", "values"]
                 }
             ]
         }"#
@@ -279,19 +350,16 @@ impl exports::near::agent::tool::Guest for GoogleDriveTool {
         "Google Drive integration for searching, accessing, uploading, sharing, and organizing \
          files and folders. Supports personal drives and shared (organizational) drives via the \
          corpora parameter. Can search with Drive query syntax, download text files, upload new \
-         files, manage folder structure, and control sharing permissions. Requires a Google OAuth \
-         token with the drive scope."
+         files, manage folder structure, and control sharing permissions. Docs, Slides and Sheets \
+         files can be registered as named templates; instantiate_template copies one and fills \
+         in its placeholders in a single call. Requires a Google OAuth token with the drive scope."
             .to_string()
     }
 }
 
 fn execute_inner(params: &str) -> Result<String, String> {
     if !crate::near::agent::host::secret_exists("google_oauth_token") {
-        return Err(
-            "Google OAuth token not configured. Run `ironclaw tool auth google-drive` to set up \
-             OAuth, or set the GOOGLE_OAUTH_TOKEN environment variable."
-                .to_string(),
-        );
+        return Err(api_error::missing_token("google-drive"));
     }
 
     let action: GoogleDriveAction =
@@ -415,6 +483,35 @@ fn execute_inner(params: &str) -> Result<String, String> {
             let result = api::list_shared_drives(page_size)?;
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
+
+        GoogleDriveAction::RegisterTemplate {
+            file_id,
+            name,
+            placeholders,
+        } => {
+            let result = templates::register_template(&file_id, &name, &placeholders)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDriveAction::ListTemplates => {
+            let result = templates::list_templates()?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleDriveAction::InstantiateTemplate {
+            template,
+            values,
+            title,
+            folder_id,
+        } => {
+            let result = templates::instantiate_template(
+                &template,
+                &values,
+                title.as_deref(),
+                folder_id.as_deref(),
+            )?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
     };
 
     Ok(result)
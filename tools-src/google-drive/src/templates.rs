@@ -0,0 +1,624 @@
+//! Template library for Docs, Slides and Sheets.
+//!
+//! A template is a Drive file registered under a name with the placeholders
+//! it contains. Instantiating one copies the file and fills the copy in with
+//! the editor API's own replace requests, so a whole "copy, replace text,
+//! swap images, write cells" workflow is a single call.
+//!
+//! The registry is kept in the template files' `appProperties`, which are
+//! private to this app and travel with the file:
+//!
+//! - `ironclawTemplate` = `true` marks a template
+//! - `ironclawTemplateName` holds its name
+//! - `ph:<placeholder>` holds `text`, `image`, or `cell:<range>`
+
+use std::collections::BTreeMap;
+
+use crate::api::{api_call, url_encode};
+use crate::api_error::{api_error, GoogleApi};
+use crate::near::agent::host;
+use crate::types::*;
+
+const DOCS_MIME: &str = "application/vnd.google-apps.document";
+const SLIDES_MIME: &str = "application/vnd.google-apps.presentation";
+const SHEETS_MIME: &str = "application/vnd.google-apps.spreadsheet";
+
+const MARKER_KEY: &str = "ironclawTemplate";
+const NAME_KEY: &str = "ironclawTemplateName";
+const PLACEHOLDER_PREFIX: &str = "ph:";
+
+/// Drive's limit on an `appProperties` key plus value, in bytes.
+const MAX_PROPERTY_BYTES: usize = 124;
+
+/// Drive allows 30 `appProperties` per app on a file; two are taken by the
+/// marker and the name.
+const MAX_PLACEHOLDERS: usize = 28;
+
+const TEMPLATE_FIELDS: &str = "id,name,mimeType,webViewLink,appProperties";
+
+const DOCS_API: GoogleApi = GoogleApi {
+    name: "Google Docs",
+    scope: "https://www.googleapis.com/auth/drive",
+};
+
+const SLIDES_API: GoogleApi = GoogleApi {
+    name: "Google Slides",
+    scope: "https://www.googleapis.com/auth/drive",
+};
+
+const SHEETS_API: GoogleApi = GoogleApi {
+    name: "Google Sheets",
+    scope: "https://www.googleapis.com/auth/drive",
+};
+
+/// POST a JSON body to a Docs, Slides or Sheets endpoint.
+fn editor_call(api: &GoogleApi, url: &str, body: &serde_json::Value) -> Result<(), String> {
+    host::log(
+        host::LogLevel::Debug,
+        &format!("{} API: POST {}", api.name, url),
+    );
+
+    let body = body.to_string();
+    let response = host::http_request(
+        "POST",
+        url,
+        r#"{"Content-Type": "application/json"}"#,
+        Some(body.as_bytes()),
+    )?;
+
+    if response.status < 200 || response.status >= 300 {
+        return Err(api_error(
+            api,
+            response.status,
+            &response.headers_json,
+            &response.body,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Register a file as a named template.
+pub fn register_template(
+    file_id: &str,
+    name: &str,
+    placeholders: &[Placeholder],
+) -> Result<Template, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Template name must not be empty".to_string());
+    }
+    if NAME_KEY.len() + name.len() > MAX_PROPERTY_BYTES {
+        return Err(format!("Template name '{}' is too long", name));
+    }
+    if placeholders.len() > MAX_PLACEHOLDERS {
+        return Err(format!(
+            "A template can declare at most {} placeholders",
+            MAX_PLACEHOLDERS
+        ));
+    }
+
+    let path = format!(
+        "files/{}?fields={}&supportsAllDrives=true",
+        url_encode(file_id),
+        TEMPLATE_FIELDS
+    );
+    let response = api_call("GET", &path, None)?;
+    let file: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+    let mime_type = file["mimeType"].as_str().unwrap_or("");
+    check_placeholders(mime_type, placeholders)?;
+
+    if let Some(existing) = find_template(name)? {
+        if existing["id"].as_str() != Some(file_id) {
+            return Err(format!(
+                "A template named '{}' already exists (file {})",
+                name,
+                existing["id"].as_str().unwrap_or("")
+            ));
+        }
+    }
+
+    let mut properties = serde_json::Map::new();
+    // Drop placeholders left over from an earlier registration
+    if let Some(old) = file["appProperties"].as_object() {
+        for key in old.keys().filter(|k| k.starts_with(PLACEHOLDER_PREFIX)) {
+            properties.insert(key.clone(), serde_json::Value::Null);
+        }
+    }
+    properties.insert(MARKER_KEY.to_string(), "true".into());
+    properties.insert(NAME_KEY.to_string(), name.into());
+    for placeholder in placeholders {
+        let (key, value) = encode_placeholder(placeholder)?;
+        properties.insert(key, value.into());
+    }
+
+    let body = serde_json::json!({ "appProperties": properties }).to_string();
+    let response = api_call("PATCH", &path, Some(&body))?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    parse_template(&parsed).ok_or_else(|| "Drive did not save the template properties".to_string())
+}
+
+/// List registered templates.
+pub fn list_templates() -> Result<ListTemplatesResult, String> {
+    let query = format!(
+        "appProperties has {{ key='{}' and value='true' }} and trashed = false",
+        MARKER_KEY
+    );
+    let templates = search(&query)?.iter().filter_map(parse_template).collect();
+
+    Ok(ListTemplatesResult { templates })
+}
+
+/// Copy a template and fill in its placeholders.
+pub fn instantiate_template(
+    template: &str,
+    values: &BTreeMap<String, serde_json::Value>,
+    title: Option<&str>,
+    folder_id: Option<&str>,
+) -> Result<InstantiateResult, String> {
+    let file = find_template(template)?.ok_or_else(|| {
+        format!(
+            "No template named '{}'. Use list_templates to see registered templates.",
+            template
+        )
+    })?;
+    let registered = parse_template(&file)
+        .ok_or_else(|| format!("Template '{}' has invalid properties", template))?;
+    check_values(&registered.placeholders, values)?;
+
+    // Copying applies the body with patch semantics, so nulling the
+    // registry keys keeps the copy from being a template itself.
+    let mut copy = serde_json::json!({});
+    if let Some(t) = title {
+        copy["name"] = serde_json::Value::String(t.to_string());
+    }
+    if let Some(folder) = folder_id {
+        copy["parents"] = serde_json::json!([folder]);
+    }
+    let cleared: serde_json::Map<String, serde_json::Value> = file["appProperties"]
+        .as_object()
+        .map(|props| {
+            props
+                .keys()
+                .map(|k| (k.clone(), serde_json::Value::Null))
+                .collect()
+        })
+        .unwrap_or_default();
+    copy["appProperties"] = serde_json::Value::Object(cleared);
+
+    let path = format!(
+        "files/{}/copy?fields=id,name,mimeType,webViewLink&supportsAllDrives=true",
+        url_encode(&registered.file_id)
+    );
+    let response = api_call("POST", &path, Some(&copy.to_string()))?;
+    let copied: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+    let copy_id = copied["id"].as_str().unwrap_or("").to_string();
+
+    if let Err(e) = fill(
+        &registered.mime_type,
+        &copy_id,
+        &registered.placeholders,
+        values,
+    ) {
+        // Don't leave a half-filled copy behind
+        let path = format!("files/{}?supportsAllDrives=true", url_encode(&copy_id));
+        if let Err(cleanup) = api_call("DELETE", &path, None) {
+            host::log(
+                host::LogLevel::Warn,
+                &format!("Failed to delete partial copy {}: {}", copy_id, cleanup),
+            );
+        }
+        return Err(e);
+    }
+
+    Ok(InstantiateResult {
+        template: registered.name,
+        file_id: copy_id,
+        name: copied["name"].as_str().unwrap_or("").to_string(),
+        mime_type: registered.mime_type,
+        web_view_link: copied["webViewLink"].as_str().map(|s| s.to_string()),
+    })
+}
+
+/// Run the replace requests for the file's editor.
+fn fill(
+    mime_type: &str,
+    file_id: &str,
+    placeholders: &[Placeholder],
+    values: &BTreeMap<String, serde_json::Value>,
+) -> Result<(), String> {
+    let id = url_encode(file_id);
+    let (requests, cells) = fill_requests(mime_type, placeholders, values);
+
+    if !requests.is_empty() {
+        let body = serde_json::json!({ "requests": requests });
+        match mime_type {
+            DOCS_MIME => editor_call(
+                &DOCS_API,
+                &format!(
+                    "https://docs.googleapis.com/v1/documents/{}:batchUpdate",
+                    id
+                ),
+                &body,
+            )?,
+            SLIDES_MIME => editor_call(
+                &SLIDES_API,
+                &format!(
+                    "https://slides.googleapis.com/v1/presentations/{}:batchUpdate",
+                    id
+                ),
+                &body,
+            )?,
+            _ => editor_call(
+                &SHEETS_API,
+                &format!(
+                    "https://sheets.googleapis.com/v4/spreadsheets/{}:batchUpdate",
+                    id
+                ),
+                &body,
+            )?,
+        }
+    }
+
+    if !cells.is_empty() {
+        editor_call(
+            &SHEETS_API,
+            &format!(
+                "https://sheets.googleapis.com/v4/spreadsheets/{}/values:batchUpdate",
+                id
+            ),
+            &serde_json::json!({
+                "valueInputOption": "USER_ENTERED",
+                "data": cells
+            }),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Build the editor's replace requests and, for Sheets, the cell writes.
+fn fill_requests(
+    mime_type: &str,
+    placeholders: &[Placeholder],
+    values: &BTreeMap<String, serde_json::Value>,
+) -> (Vec<serde_json::Value>, Vec<serde_json::Value>) {
+    let mut requests = Vec::new();
+    let mut cells = Vec::new();
+
+    for placeholder in placeholders {
+        let value = &values[&placeholder.name];
+        let token = format!("{{{{{}}}}}", placeholder.name);
+        match (mime_type, placeholder.kind) {
+            (SHEETS_MIME, PlaceholderKind::Text) => requests.push(serde_json::json!({
+                "findReplace": {
+                    "find": token,
+                    "replacement": text_value(value),
+                    "matchCase": true,
+                    "allSheets": true
+                }
+            })),
+            (SHEETS_MIME, PlaceholderKind::Cell) => cells.push(serde_json::json!({
+                "range": placeholder.range,
+                "values": [[value]]
+            })),
+            (SLIDES_MIME, PlaceholderKind::Image) => requests.push(serde_json::json!({
+                "replaceAllShapesWithImage": {
+                    "imageUrl": text_value(value),
+                    "imageReplaceMethod": "CENTER_INSIDE",
+                    "containsText": { "text": token, "matchCase": true }
+                }
+            })),
+            (_, PlaceholderKind::Text) => requests.push(serde_json::json!({
+                "replaceAllText": {
+                    "containsText": { "text": token, "matchCase": true },
+                    "replaceText": text_value(value)
+                }
+            })),
+            // Rejected at registration
+            _ => {}
+        }
+    }
+
+    (requests, cells)
+}
+
+/// Find the template registered under a name.
+fn find_template(name: &str) -> Result<Option<serde_json::Value>, String> {
+    let query = format!(
+        "appProperties has {{ key='{}' and value='{}' }} and trashed = false",
+        NAME_KEY,
+        escape_query(name)
+    );
+    Ok(search(&query)?.into_iter().next())
+}
+
+/// Search all drives, returning template fields for each match.
+fn search(query: &str) -> Result<Vec<serde_json::Value>, String> {
+    let path = format!(
+        "files?q={}&fields=files({})&pageSize=100&corpora=allDrives\
+         &includeItemsFromAllDrives=true&supportsAllDrives=true",
+        url_encode(query),
+        TEMPLATE_FIELDS
+    );
+    let response = api_call("GET", &path, None)?;
+    let mut parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    match parsed["files"].take() {
+        serde_json::Value::Array(files) => Ok(files),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Build a template from a file's registry properties.
+fn parse_template(file: &serde_json::Value) -> Option<Template> {
+    let properties = file["appProperties"].as_object()?;
+    if properties.get(MARKER_KEY)?.as_str() != Some("true") {
+        return None;
+    }
+
+    Some(Template {
+        name: properties.get(NAME_KEY)?.as_str()?.to_string(),
+        file_id: file["id"].as_str()?.to_string(),
+        file_name: file["name"].as_str().unwrap_or("").to_string(),
+        mime_type: file["mimeType"].as_str().unwrap_or("").to_string(),
+        placeholders: properties
+            .iter()
+            .filter_map(|(key, value)| decode_placeholder(key, value.as_str()?))
+            .collect(),
+        web_view_link: file["webViewLink"].as_str().map(|s| s.to_string()),
+    })
+}
+
+/// Check placeholders are well-formed and usable in this kind of file.
+fn check_placeholders(mime_type: &str, placeholders: &[Placeholder]) -> Result<(), String> {
+    if ![DOCS_MIME, SLIDES_MIME, SHEETS_MIME].contains(&mime_type) {
+        return Err(format!(
+            "Templates must be Google Docs, Slides or Sheets files, not {}",
+            mime_type
+        ));
+    }
+
+    for placeholder in placeholders {
+        let name = placeholder.name.as_str();
+        if name.is_empty() || name.contains(['{', '}']) {
+            return Err(format!("Invalid placeholder name '{}'", name));
+        }
+        match placeholder.kind {
+            PlaceholderKind::Text => {}
+            PlaceholderKind::Image if mime_type == SLIDES_MIME => {}
+            PlaceholderKind::Cell if mime_type == SHEETS_MIME => {
+                if placeholder.range.is_none() {
+                    return Err(format!("Cell placeholder '{}' needs a range", name));
+                }
+            }
+            kind => {
+                return Err(format!(
+                    "Placeholder '{}': {:?} placeholders aren't supported in {} files",
+                    name, kind, mime_type
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check the values cover exactly the declared placeholders.
+fn check_values(
+    placeholders: &[Placeholder],
+    values: &BTreeMap<String, serde_json::Value>,
+) -> Result<(), String> {
+    let missing: Vec<&str> = placeholders
+        .iter()
+        .map(|p| p.name.as_str())
+        .filter(|name| !values.contains_key(*name))
+        .collect();
+    let unknown: Vec<&str> = values
+        .keys()
+        .map(|k| k.as_str())
+        .filter(|key| !placeholders.iter().any(|p| p.name == *key))
+        .collect();
+
+    if missing.is_empty() && unknown.is_empty() {
+        return Ok(());
+    }
+
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!("missing values for {}", missing.join(", ")));
+    }
+    if !unknown.is_empty() {
+        problems.push(format!("unknown placeholders {}", unknown.join(", ")));
+    }
+    Err(format!(
+        "Template values don't match: {}",
+        problems.join("; ")
+    ))
+}
+
+/// Encode a placeholder as an `appProperties` entry.
+fn encode_placeholder(placeholder: &Placeholder) -> Result<(String, String), String> {
+    let key = format!("{}{}", PLACEHOLDER_PREFIX, placeholder.name);
+    let value = match (placeholder.kind, &placeholder.range) {
+        (PlaceholderKind::Text, _) => "text".to_string(),
+        (PlaceholderKind::Image, _) => "image".to_string(),
+        (PlaceholderKind::Cell, Some(range)) => format!("cell:{}", range),
+        (PlaceholderKind::Cell, None) => {
+            return Err(format!(
+                "Cell placeholder '{}' needs a range",
+                placeholder.name
+            ))
+        }
+    };
+
+    if key.len() + value.len() > MAX_PROPERTY_BYTES {
+        return Err(format!(
+            "Placeholder '{}' is too long to store",
+            placeholder.name
+        ));
+    }
+    Ok((key, value))
+}
+
+/// Decode an `appProperties` entry into a placeholder, if it is one.
+fn decode_placeholder(key: &str, value: &str) -> Option<Placeholder> {
+    let name = key.strip_prefix(PLACEHOLDER_PREFIX)?.to_string();
+    let (kind, range) = match value {
+        "text" => (PlaceholderKind::Text, None),
+        "image" => (PlaceholderKind::Image, None),
+        _ => (
+            PlaceholderKind::Cell,
+            Some(value.strip_prefix("cell:")?.to_string()),
+        ),
+    };
+    Some(Placeholder { name, kind, range })
+}
+
+/// A value as replacement text (strings unquoted, null as empty).
+fn text_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Escape a value for a single-quoted Drive query string.
+fn escape_query(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholder(name: &str, kind: PlaceholderKind, range: Option<&str>) -> Placeholder {
+        Placeholder {
+            name: name.to_string(),
+            kind,
+            range: range.map(|r| r.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_placeholder_round_trips_through_properties() {
+        for p in [
+            placeholder("client", PlaceholderKind::Text, None),
+            placeholder("logo", PlaceholderKind::Image, None),
+            placeholder("total", PlaceholderKind::Cell, Some("Summary!B4")),
+        ] {
+            let (key, value) = encode_placeholder(&p).unwrap();
+            assert_eq!(decode_placeholder(&key, &value), Some(p));
+        }
+
+        assert_eq!(decode_placeholder(NAME_KEY, "Invoice"), None);
+        assert_eq!(decode_placeholder("ph:x", "bogus"), None);
+        assert!(encode_placeholder(&placeholder("total", PlaceholderKind::Cell, None)).is_err());
+        let long = "x".repeat(MAX_PROPERTY_BYTES);
+        assert!(encode_placeholder(&placeholder(&long, PlaceholderKind::Text, None)).is_err());
+    }
+
+    #[test]
+    fn test_parse_template() {
+        let file = serde_json::json!({
+            "id": "f1",
+            "name": "Invoice template",
+            "mimeType": SHEETS_MIME,
+            "appProperties": {
+                MARKER_KEY: "true",
+                NAME_KEY: "invoice",
+                "ph:total": "cell:Summary!B4",
+                "unrelated": "value",
+            },
+        });
+        let template = parse_template(&file).unwrap();
+        assert_eq!(template.name, "invoice");
+        assert_eq!(template.file_id, "f1");
+        assert_eq!(
+            template.placeholders,
+            vec![placeholder(
+                "total",
+                PlaceholderKind::Cell,
+                Some("Summary!B4")
+            )]
+        );
+
+        let unmarked = serde_json::json!({ "id": "f2", "appProperties": { NAME_KEY: "x" } });
+        assert!(parse_template(&unmarked).is_none());
+    }
+
+    #[test]
+    fn test_check_placeholders_by_file_type() {
+        let image = [placeholder("logo", PlaceholderKind::Image, None)];
+        assert!(check_placeholders(SLIDES_MIME, &image).is_ok());
+        assert!(check_placeholders(DOCS_MIME, &image).is_err());
+
+        let cell = [placeholder("total", PlaceholderKind::Cell, Some("A1"))];
+        assert!(check_placeholders(SHEETS_MIME, &cell).is_ok());
+        assert!(check_placeholders(SLIDES_MIME, &cell).is_err());
+
+        let text = [placeholder("a{b}", PlaceholderKind::Text, None)];
+        assert!(check_placeholders(DOCS_MIME, &text).is_err());
+        assert!(check_placeholders("application/pdf", &[]).is_err());
+    }
+
+    #[test]
+    fn test_check_values_reports_missing_and_unknown() {
+        let placeholders = [
+            placeholder("client", PlaceholderKind::Text, None),
+            placeholder("date", PlaceholderKind::Text, None),
+        ];
+        let mut values = BTreeMap::new();
+        values.insert("client".to_string(), serde_json::json!("Acme"));
+        values.insert("extra".to_string(), serde_json::json!(1));
+
+        assert_eq!(
+            check_values(&placeholders, &values).unwrap_err(),
+            "Template values don't match: missing values for date; unknown placeholders extra"
+        );
+
+        values.remove("extra");
+        values.insert("date".to_string(), serde_json::Value::Null);
+        assert!(check_values(&placeholders, &values).is_ok());
+    }
+
+    #[test]
+    fn test_fill_requests() {
+        let placeholders = [
+            placeholder("client", PlaceholderKind::Text, None),
+            placeholder("total", PlaceholderKind::Cell, Some("Summary!B4")),
+        ];
+        let mut values = BTreeMap::new();
+        values.insert("client".to_string(), serde_json::json!("Acme"));
+        values.insert("total".to_string(), serde_json::json!(42));
+
+        let (requests, cells) = fill_requests(SHEETS_MIME, &placeholders, &values);
+        assert_eq!(requests[0]["findReplace"]["find"], "{{client}}");
+        assert_eq!(requests[0]["findReplace"]["replacement"], "Acme");
+        assert_eq!(
+            cells,
+            vec![serde_json::json!({ "range": "Summary!B4", "values": [[42]] })]
+        );
+
+        let (requests, cells) = fill_requests(DOCS_MIME, &placeholders[..1], &values);
+        assert_eq!(
+            requests[0]["replaceAllText"]["containsText"]["text"],
+            "{{client}}"
+        );
+        assert!(cells.is_empty());
+    }
+
+    #[test]
+    fn test_text_value_and_query_escaping() {
+        assert_eq!(text_value(&serde_json::json!("a")), "a");
+        assert_eq!(text_value(&serde_json::Value::Null), "");
+        assert_eq!(text_value(&serde_json::json!(1.5)), "1.5");
+        assert_eq!(escape_query(r"Bob's \ file"), r"Bob\'s \\ file");
+    }
+}
@@ -1,5 +1,7 @@
 //! Types for Google Drive API requests and responses.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Input parameters for the Google Drive tool.
@@ -143,6 +145,34 @@ pub enum GoogleDriveAction {
         #[serde(default = "default_page_size")]
         page_size: u32,
     },
+
+    /// Register a Docs, Slides or Sheets file as a named template.
+    /// Registering the same file again replaces its name and placeholders.
+    RegisterTemplate {
+        /// The template file ID.
+        file_id: String,
+        /// Name to instantiate the template by.
+        name: String,
+        /// Placeholders in the file, written as `{{name}}`.
+        placeholders: Vec<Placeholder>,
+    },
+
+    /// List registered templates.
+    ListTemplates,
+
+    /// Copy a template and fill in its placeholders.
+    InstantiateTemplate {
+        /// Template name.
+        template: String,
+        /// Values by placeholder name. Every declared placeholder needs one.
+        values: BTreeMap<String, serde_json::Value>,
+        /// Name for the new file. Default: "Copy of <template file>".
+        #[serde(default)]
+        title: Option<String>,
+        /// Folder for the new file. Default: the template's folder.
+        #[serde(default)]
+        folder_id: Option<String>,
+    },
 }
 
 fn default_page_size() -> u32 {
@@ -161,6 +191,32 @@ fn default_role() -> String {
     "reader".to_string()
 }
 
+/// A placeholder declared by a template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Placeholder {
+    pub name: String,
+    /// How the value is applied (default: text).
+    #[serde(default)]
+    pub kind: PlaceholderKind,
+    /// Target range for `cell` placeholders (e.g., "Summary!B4").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<String>,
+}
+
+/// How a placeholder's value is applied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaceholderKind {
+    /// `{{name}}` is replaced with the value as text.
+    #[default]
+    Text,
+    /// Shapes containing `{{name}}` are replaced with the image at the
+    /// value's URL (Slides only).
+    Image,
+    /// The value is written to `range` (Sheets only).
+    Cell,
+}
+
 /// A Google Drive file or folder.
 #[derive(Debug, Serialize)]
 pub struct DriveFile {
@@ -267,3 +323,32 @@ pub struct ListPermissionsResult {
 pub struct ListSharedDrivesResult {
     pub drives: Vec<SharedDrive>,
 }
+
+/// A registered template.
+#[derive(Debug, Serialize)]
+pub struct Template {
+    pub name: String,
+    pub file_id: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub placeholders: Vec<Placeholder>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_view_link: Option<String>,
+}
+
+/// Result from list_templates.
+#[derive(Debug, Serialize)]
+pub struct ListTemplatesResult {
+    pub templates: Vec<Template>,
+}
+
+/// Result from instantiate_template.
+#[derive(Debug, Serialize)]
+pub struct InstantiateResult {
+    pub template: String,
+    pub file_id: String,
+    pub name: String,
+    pub mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_view_link: Option<String>,
+}
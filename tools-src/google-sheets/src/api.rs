@@ -4,11 +4,17 @@
 //! credential injection and rate limiting. The WASM tool never sees
 //! the actual OAuth token.
 
+use crate::api_error::{api_error, GoogleApi};
 use crate::near::agent::host;
 use crate::types::*;
 
 const SHEETS_API_BASE: &str = "https://sheets.googleapis.com/v4/spreadsheets";
 
+const API: GoogleApi = GoogleApi {
+    name: "Google Sheets",
+    scope: "https://www.googleapis.com/auth/spreadsheets",
+};
+
 /// Make a Google Sheets API call.
 fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, String> {
     let url = if path.is_empty() {
@@ -33,10 +39,11 @@ fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, Stri
     let response = host::http_request(method, &url, headers, body_bytes.as_deref())?;
 
     if response.status < 200 || response.status >= 300 {
-        let body_text = String::from_utf8_lossy(&response.body);
-        return Err(format!(
-            "Google Sheets API returned status {}: {}",
-            response.status, body_text
+        return Err(api_error(
+            &API,
+            response.status,
+            &response.headers_json,
+            &response.body,
         ));
     }
 
@@ -48,15 +55,19 @@ fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, Stri
 }
 
 /// Parse sheet info from the API's JSON.
-fn parse_sheet_info(v: &serde_json::Value) -> SheetInfo {
+fn parse_sheet_info(v: &serde_json::Value, detail: Detail) -> SheetInfo {
     let props = &v["properties"];
     let grid = &props["gridProperties"];
+    let full = detail == Detail::Full;
     SheetInfo {
         sheet_id: props["sheetId"].as_i64().unwrap_or(0),
         title: props["title"].as_str().unwrap_or("").to_string(),
         index: props["index"].as_i64().unwrap_or(0),
         row_count: grid["rowCount"].as_i64().unwrap_or(0),
         column_count: grid["columnCount"].as_i64().unwrap_or(0),
+        frozen_row_count: grid["frozenRowCount"].as_i64().filter(|_| full),
+        frozen_column_count: grid["frozenColumnCount"].as_i64().filter(|_| full),
+        hidden: props["hidden"].as_bool().filter(|_| full),
     }
 }
 
@@ -117,17 +128,34 @@ pub fn create_spreadsheet(
         url: parsed["spreadsheetUrl"].as_str().unwrap_or("").to_string(),
         sheets: parsed["sheets"]
             .as_array()
-            .map(|arr| arr.iter().map(parse_sheet_info).collect())
+            .map(|arr| {
+                arr.iter()
+                    .map(|s| parse_sheet_info(s, Detail::Standard))
+                    .collect()
+            })
             .unwrap_or_default(),
     })
 }
 
 /// Get spreadsheet metadata.
-pub fn get_spreadsheet(spreadsheet_id: &str) -> Result<SpreadsheetMetadata, String> {
-    let path = format!(
-        "{}?fields=spreadsheetId,properties.title,spreadsheetUrl,sheets.properties,namedRanges",
-        url_encode(spreadsheet_id)
-    );
+pub fn get_spreadsheet(
+    spreadsheet_id: &str,
+    detail: Detail,
+) -> Result<SpreadsheetMetadata, String> {
+    let fields = match detail {
+        Detail::Summary => {
+            "spreadsheetId,properties.title,spreadsheetUrl,\
+            sheets.properties(sheetId,title,index,gridProperties(rowCount,columnCount))"
+        }
+        Detail::Standard => {
+            "spreadsheetId,properties.title,spreadsheetUrl,sheets.properties,namedRanges"
+        }
+        Detail::Full => {
+            "spreadsheetId,properties(title,locale,timeZone),spreadsheetUrl,\
+            sheets.properties,namedRanges"
+        }
+    };
+    let path = format!("{}?fields={}", url_encode(spreadsheet_id), fields);
 
     let response = api_call("GET", &path, None)?;
     let parsed: serde_json::Value =
@@ -140,9 +168,15 @@ pub fn get_spreadsheet(spreadsheet_id: &str) -> Result<SpreadsheetMetadata, Stri
             .unwrap_or("")
             .to_string(),
         url: parsed["spreadsheetUrl"].as_str().unwrap_or("").to_string(),
+        locale: parsed["properties"]["locale"]
+            .as_str()
+            .map(|s| s.to_string()),
+        time_zone: parsed["properties"]["timeZone"]
+            .as_str()
+            .map(|s| s.to_string()),
         sheets: parsed["sheets"]
             .as_array()
-            .map(|arr| arr.iter().map(parse_sheet_info).collect())
+            .map(|arr| arr.iter().map(|s| parse_sheet_info(s, detail)).collect())
             .unwrap_or_default(),
         named_ranges: parsed["namedRanges"]
             .as_array()
@@ -151,12 +185,52 @@ pub fn get_spreadsheet(spreadsheet_id: &str) -> Result<SpreadsheetMetadata, Stri
     })
 }
 
+/// Get a spreadsheet as the raw API response, limited to a field mask.
+pub fn get_spreadsheet_fields(spreadsheet_id: &str, fields: &str) -> Result<String, String> {
+    let path = format!(
+        "{}?fields={}",
+        url_encode(spreadsheet_id),
+        url_encode(fields)
+    );
+    api_call("GET", &path, None)
+}
+
 /// Read values from a single range.
 pub fn read_values(spreadsheet_id: &str, range: &str) -> Result<ValuesResult, String> {
+    read_values_rendered(spreadsheet_id, range, "FORMATTED_VALUE")
+}
+
+/// Read values from a single range with an explicit `valueRenderOption`
+/// ("FORMATTED_VALUE", "UNFORMATTED_VALUE" or "FORMULA").
+pub fn read_values_rendered(
+    spreadsheet_id: &str,
+    range: &str,
+    value_render_option: &str,
+) -> Result<ValuesResult, String> {
+    let query = format!("valueRenderOption={}", url_encode(value_render_option));
+    read_values_query(spreadsheet_id, range, &query)
+}
+
+/// Read values from a single range as typed values: numbers come back as
+/// numbers, dates and times as their formatted text.
+pub fn read_values_typed(spreadsheet_id: &str, range: &str) -> Result<ValuesResult, String> {
+    read_values_query(
+        spreadsheet_id,
+        range,
+        "valueRenderOption=UNFORMATTED_VALUE&dateTimeRenderOption=FORMATTED_STRING",
+    )
+}
+
+fn read_values_query(
+    spreadsheet_id: &str,
+    range: &str,
+    query: &str,
+) -> Result<ValuesResult, String> {
     let path = format!(
-        "{}/values/{}",
+        "{}/values/{}?{}",
         url_encode(spreadsheet_id),
-        url_encode(range)
+        url_encode(range),
+        query
     );
 
     let response = api_call("GET", &path, None)?;
@@ -340,6 +414,9 @@ pub fn add_sheet(spreadsheet_id: &str, title: &str) -> Result<AddSheetResult, St
             column_count: reply["gridProperties"]["columnCount"]
                 .as_i64()
                 .unwrap_or(26),
+            frozen_row_count: None,
+            frozen_column_count: None,
+            hidden: None,
         },
     })
 }
@@ -522,3 +599,31 @@ fn url_encode(s: &str) -> String {
 }
 
 const HEX: [u8; 16] = *b"0123456789ABCDEF";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sheet_info_by_detail() {
+        let sheet = serde_json::json!({
+            "properties": {
+                "sheetId": 7,
+                "title": "Data",
+                "index": 1,
+                "hidden": true,
+                "gridProperties": { "rowCount": 100, "columnCount": 26, "frozenRowCount": 1 },
+            }
+        });
+
+        let standard = parse_sheet_info(&sheet, Detail::Standard);
+        assert_eq!((standard.sheet_id, standard.row_count), (7, 100));
+        assert_eq!(standard.frozen_row_count, None);
+        assert_eq!(standard.hidden, None);
+
+        let full = parse_sheet_info(&sheet, Detail::Full);
+        assert_eq!(full.frozen_row_count, Some(1));
+        assert_eq!(full.frozen_column_count, None);
+        assert_eq!(full.hidden, Some(true));
+    }
+}
@@ -0,0 +1,407 @@
+//! Cross-spreadsheet consolidation.
+//!
+//! `consolidate_ranges` reads the same range (a tab, an A1 range or a named
+//! range) from several spreadsheets and writes all of their rows, each tagged
+//! with the title of the spreadsheet it came from, to one tab of a target
+//! spreadsheet: the weekly "roll up every team's tracker" in one call.
+//!
+//! With a header row, columns are matched by header name, so trackers whose
+//! columns are in another order, or that lack some, still line up. Cells
+//! under an empty header are left out. With `group_by`, rows that share the
+//! values of those columns become one row: numbers are summed, other cells
+//! keep their first non-empty value, and the source column lists every
+//! spreadsheet that contributed.
+//!
+//! Cells are read as typed values (numbers as numbers, dates as their
+//! formatted text) and written as USER_ENTERED, so dates stay dates. The
+//! target tab is created if missing and cleared before each write, so running
+//! the roll-up again replaces the previous one.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::api;
+use crate::types::*;
+
+/// Header of the column naming each row's source.
+const DEFAULT_SOURCE_COLUMN: &str = "Source";
+
+/// Parameters for consolidate_ranges.
+pub struct ConsolidateOptions<'a> {
+    pub spreadsheet_ids: &'a [String],
+    pub range: &'a str,
+    pub target_spreadsheet_id: &'a str,
+    pub target_sheet: &'a str,
+    pub has_header: bool,
+    pub source_column: Option<&'a str>,
+    pub group_by: &'a [String],
+}
+
+/// The rows read from one spreadsheet.
+struct SourceRows {
+    /// Spreadsheet title, written to the source column.
+    label: String,
+    values: Vec<Vec<Value>>,
+}
+
+/// Read `range` from every spreadsheet and write the combined rows to the
+/// target tab.
+pub fn consolidate_ranges(opts: ConsolidateOptions<'_>) -> Result<ConsolidateResult, String> {
+    if opts.spreadsheet_ids.is_empty() {
+        return Err("spreadsheet_ids is empty".to_string());
+    }
+    if opts.target_sheet.trim().is_empty() {
+        return Err("target_sheet is empty".to_string());
+    }
+    if !opts.group_by.is_empty() && !opts.has_header {
+        return Err("group_by names header columns, so it needs has_header".to_string());
+    }
+
+    let mut sources = Vec::new();
+    let mut summaries = Vec::new();
+    for spreadsheet_id in opts.spreadsheet_ids {
+        match read_source(spreadsheet_id, opts.range) {
+            Ok(source) => {
+                let header_rows = usize::from(opts.has_header);
+                summaries.push(ConsolidatedSource {
+                    spreadsheet_id: spreadsheet_id.clone(),
+                    title: Some(source.label.clone()),
+                    rows: source.values.len().saturating_sub(header_rows),
+                    error: None,
+                });
+                sources.push(source);
+            }
+            Err(e) => summaries.push(ConsolidatedSource {
+                spreadsheet_id: spreadsheet_id.clone(),
+                title: None,
+                rows: 0,
+                error: Some(e),
+            }),
+        }
+    }
+    if sources.is_empty() {
+        let errors: Vec<String> = summaries
+            .iter()
+            .filter_map(|s| Some(format!("{}: {}", s.spreadsheet_id, s.error.as_ref()?)))
+            .collect();
+        return Err(format!("Could not read any source. {}", errors.join("; ")));
+    }
+
+    let source_column = opts.source_column.unwrap_or(DEFAULT_SOURCE_COLUMN);
+    let grid = if opts.has_header {
+        merge_by_header(&sources, source_column, opts.group_by)?
+    } else {
+        concat_rows(&sources)
+    };
+
+    let sheet = sheet_ref(opts.target_sheet);
+    let target = api::get_spreadsheet(opts.target_spreadsheet_id, Detail::Summary)?;
+    if target.sheets.iter().any(|s| s.title == opts.target_sheet) {
+        api::clear_values(opts.target_spreadsheet_id, &sheet)?;
+    } else {
+        api::add_sheet(opts.target_spreadsheet_id, opts.target_sheet)?;
+    }
+
+    let updated_range = if grid.is_empty() {
+        String::new()
+    } else {
+        api::write_values(
+            opts.target_spreadsheet_id,
+            &format!("{}!A1", sheet),
+            &grid,
+            "USER_ENTERED",
+        )?
+        .updated_range
+    };
+
+    Ok(ConsolidateResult {
+        target_spreadsheet_id: opts.target_spreadsheet_id.to_string(),
+        updated_range,
+        rows: grid.len().saturating_sub(usize::from(opts.has_header)),
+        columns: grid.iter().map(Vec::len).max().unwrap_or(0),
+        sources: summaries,
+    })
+}
+
+fn read_source(spreadsheet_id: &str, range: &str) -> Result<SourceRows, String> {
+    let response = api::get_spreadsheet_fields(spreadsheet_id, "properties.title")?;
+    let parsed: Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+    let label = parsed["properties"]["title"]
+        .as_str()
+        .unwrap_or(spreadsheet_id)
+        .to_string();
+    let values = api::read_values_typed(spreadsheet_id, range)?.values;
+    Ok(SourceRows { label, values })
+}
+
+/// Every non-blank row of every source, behind a column with its label.
+fn concat_rows(sources: &[SourceRows]) -> Vec<Vec<Value>> {
+    sources
+        .iter()
+        .flat_map(|source| {
+            source
+                .values
+                .iter()
+                .filter(|row| !row.iter().all(is_blank))
+                .map(|row| {
+                    let mut out = vec![Value::String(source.label.clone())];
+                    out.extend(row.iter().cloned());
+                    out
+                })
+        })
+        .collect()
+}
+
+/// A header row (source column, then every header name in first-seen order)
+/// followed by each source's rows, aligned by header name and merged on the
+/// `group_by` columns if any.
+fn merge_by_header(
+    sources: &[SourceRows],
+    source_column: &str,
+    group_by: &[String],
+) -> Result<Vec<Vec<Value>>, String> {
+    let mut columns: Vec<String> = Vec::new();
+    for header in sources.iter().filter_map(|s| s.values.first()) {
+        for name in header.iter().map(cell_text) {
+            if !name.is_empty() && !columns.contains(&name) {
+                columns.push(name);
+            }
+        }
+    }
+    let keys = group_by
+        .iter()
+        .map(|name| {
+            columns
+                .iter()
+                .position(|c| c == name)
+                .ok_or_else(|| format!("group_by column '{}' is in no header", name))
+        })
+        .collect::<Result<Vec<usize>, String>>()?;
+
+    let mut rows = Vec::new();
+    for source in sources {
+        let Some((header, body)) = source.values.split_first() else {
+            continue;
+        };
+        let positions: Vec<Option<usize>> = header
+            .iter()
+            .map(|name| {
+                let name = cell_text(name);
+                columns.iter().position(|c| *c == name)
+            })
+            .collect();
+        for row in body.iter().filter(|row| !row.iter().all(is_blank)) {
+            let mut aligned = vec![Value::String(String::new()); columns.len()];
+            for (cell, position) in row.iter().zip(&positions) {
+                if let Some(position) = *position {
+                    aligned[position] = cell.clone();
+                }
+            }
+            rows.push((source.label.clone(), aligned));
+        }
+    }
+
+    let groups = if keys.is_empty() {
+        rows.into_iter()
+            .map(|(label, row)| (vec![label], row))
+            .collect()
+    } else {
+        group_rows(rows, &keys)
+    };
+
+    let mut header = vec![Value::String(source_column.to_string())];
+    header.extend(columns.into_iter().map(Value::String));
+    let mut grid = vec![header];
+    for (labels, row) in groups {
+        let mut out = vec![Value::String(labels.join(", "))];
+        out.extend(row);
+        grid.push(out);
+    }
+    Ok(grid)
+}
+
+/// Merge rows with the same values in the `keys` columns, in first-seen
+/// order, keeping the labels of every source that contributed.
+fn group_rows(rows: Vec<(String, Vec<Value>)>, keys: &[usize]) -> Vec<(Vec<String>, Vec<Value>)> {
+    let mut groups: Vec<(Vec<String>, Vec<Value>)> = Vec::new();
+    let mut index: HashMap<Vec<String>, usize> = HashMap::new();
+    for (label, row) in rows {
+        let key: Vec<String> = keys.iter().map(|&k| cell_text(&row[k])).collect();
+        let Some(&i) = index.get(&key) else {
+            index.insert(key, groups.len());
+            groups.push((vec![label], row));
+            continue;
+        };
+        let (labels, merged) = &mut groups[i];
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+        for (col, cell) in row.into_iter().enumerate() {
+            if !keys.contains(&col) {
+                merged[col] = combine(&merged[col], cell);
+            }
+        }
+    }
+    groups
+}
+
+/// Numbers add up; otherwise the first non-empty value wins.
+fn combine(current: &Value, next: Value) -> Value {
+    match (current, &next) {
+        (Value::Number(a), Value::Number(b)) => {
+            let sum = match (a.as_i64(), b.as_i64()) {
+                (Some(a), Some(b)) => a.checked_add(b).map(Value::from),
+                _ => None,
+            };
+            sum.or_else(|| {
+                let total = a.as_f64().unwrap_or(0.0) + b.as_f64().unwrap_or(0.0);
+                serde_json::Number::from_f64(total).map(Value::Number)
+            })
+            .unwrap_or(next)
+        }
+        _ if is_blank(current) => next,
+        _ => current.clone(),
+    }
+}
+
+/// A cell as text, for matching headers and group keys.
+fn cell_text(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.trim().to_string(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn is_blank(v: &Value) -> bool {
+    cell_text(v).is_empty()
+}
+
+/// A sheet title quoted for A1 notation ("Q1 Roll-up" -> "'Q1 Roll-up'").
+fn sheet_ref(title: &str) -> String {
+    format!("'{}'", title.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn source(label: &str, values: Value) -> SourceRows {
+        SourceRows {
+            label: label.to_string(),
+            values: serde_json::from_value(values).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_merge_by_header_aligns_columns() {
+        let sources = [
+            source(
+                "Team A",
+                json!([["Task", "Owner", "Hours"], ["Login", "Ann", 3], []]),
+            ),
+            source(
+                "Team B",
+                json!([
+                    ["Hours", "Task", "Status", ""],
+                    [5, "Search", "done", "note"]
+                ]),
+            ),
+            source("Team C", json!([])),
+        ];
+        let grid = merge_by_header(&sources, "Team", &[]).unwrap();
+        assert_eq!(
+            grid,
+            vec![
+                vec![
+                    json!("Team"),
+                    json!("Task"),
+                    json!("Owner"),
+                    json!("Hours"),
+                    json!("Status")
+                ],
+                vec![
+                    json!("Team A"),
+                    json!("Login"),
+                    json!("Ann"),
+                    json!(3),
+                    json!("")
+                ],
+                vec![
+                    json!("Team B"),
+                    json!("Search"),
+                    json!(""),
+                    json!(5),
+                    json!("done")
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_by_sums_numbers() {
+        let sources = [
+            source(
+                "Team A",
+                json!([
+                    ["Project", "Hours", "Lead"],
+                    ["Apollo", 3, ""],
+                    ["Zeus", 1.5, "Bo"]
+                ]),
+            ),
+            source(
+                "Team B",
+                json!([
+                    ["Project", "Hours", "Lead"],
+                    ["Apollo", 4, "Cy"],
+                    ["Zeus", 2, "Di"]
+                ]),
+            ),
+        ];
+        let grid = merge_by_header(&sources, "Source", &["Project".to_string()]).unwrap();
+        assert_eq!(
+            grid[1..],
+            [
+                vec![
+                    json!("Team A, Team B"),
+                    json!("Apollo"),
+                    json!(7),
+                    json!("Cy")
+                ],
+                vec![
+                    json!("Team A, Team B"),
+                    json!("Zeus"),
+                    json!(3.5),
+                    json!("Bo")
+                ],
+            ]
+        );
+
+        let unknown = merge_by_header(&sources, "Source", &["Owner".to_string()]);
+        assert!(unknown.unwrap_err().contains("'Owner'"));
+    }
+
+    #[test]
+    fn test_concat_rows_without_header() {
+        let sources = [
+            source("Team A", json!([["Login", 3], [""]])),
+            source("Team B", json!([["Search"]])),
+        ];
+        assert_eq!(
+            concat_rows(&sources),
+            vec![
+                vec![json!("Team A"), json!("Login"), json!(3)],
+                vec![json!("Team B"), json!("Search")],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sheet_ref_quotes() {
+        assert_eq!(sheet_ref("Roll-up"), "'Roll-up'");
+        assert_eq!(sheet_ref("Q1's totals"), "'Q1''s totals'");
+    }
+}
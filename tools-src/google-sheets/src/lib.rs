@@ -8,11 +8,13 @@
 //!
 //! - HTTP: `sheets.googleapis.com/v4/spreadsheets*`
 //! - Secrets: `google_oauth_token` (shared OAuth 2.0 token, injected automatically)
+//! - Workspace: read/write under `sheets/snapshots/` (snapshot_range, diff_range)
 //!
 //! # Supported Actions
 //!
 //! - `create_spreadsheet`: Create a new spreadsheet with optional sheet names
-//! - `get_spreadsheet`: Get metadata (title, sheets, named ranges)
+//! - `get_spreadsheet`: Get metadata (title, sheets, named ranges), at a
+//!   chosen `detail` or limited to a `fields` mask
 //! - `read_values`: Read cell values from a range (A1 notation)
 //! - `batch_read_values`: Read from multiple ranges at once
 //! - `write_values`: Write values to a range (overwrites)
@@ -22,6 +24,10 @@
 //! - `delete_sheet`: Delete a sheet (tab)
 //! - `rename_sheet`: Rename a sheet (tab)
 //! - `format_cells`: Format cells (bold, colors, alignment, number format)
+//! - `snapshot_range`: Store a range's current cells in the workspace
+//! - `diff_range`: List cells changed since a snapshot, with rollback values
+//! - `consolidate_ranges`: Combine the same range from several spreadsheets
+//!   into one tab, with a source column
 //!
 //! # Tips
 //!
@@ -29,6 +35,12 @@
 //!   tool's list_files to find spreadsheets.
 //! - Use A1 notation for ranges: "Sheet1!A1:D10", "A1:B5", "Sheet1!A:E"
 //! - Sheet IDs (numeric) are different from sheet names. Get them via get_spreadsheet.
+//! - Take a snapshot_range before bulk edits, then diff_range to review them.
+//!   To undo, pass the diff's `rollback.range` and `rollback.values` to write_values.
+//! - For roll-ups (e.g. every team's tracker into one sheet), consolidate_ranges
+//!   matches columns by header name and tags each row with its spreadsheet's
+//!   title. Add `group_by` to merge rows that share those columns, summing
+//!   numbers. The target tab is replaced on each run.
 //!
 //! # Example Usage
 //!
@@ -38,9 +50,16 @@
 //! {"action": "write_values", "spreadsheet_id": "abc123", "range": "Sheet1!A1", "values": [["Name", "Age"], ["Alice", 30]]}
 //! {"action": "append_values", "spreadsheet_id": "abc123", "range": "Sheet1!A:B", "values": [["Bob", 25]]}
 //! {"action": "format_cells", "spreadsheet_id": "abc123", "sheet_id": 0, "start_row": 0, "end_row": 1, "start_column": 0, "end_column": 4, "bold": true, "background_color": "#4285F4", "text_color": "#FFFFFF"}
+//! {"action": "snapshot_range", "spreadsheet_id": "abc123", "range": "Sheet1!A1:D50", "snapshot_id": "before-cleanup"}
+//! {"action": "diff_range", "snapshot_id": "before-cleanup"}
+//! {"action": "consolidate_ranges", "spreadsheet_ids": ["team-a", "team-b"], "range": "Tracker", "target_spreadsheet_id": "rollup1", "target_sheet": "This week"}
 //! ```
 
 mod api;
+#[path = "../../google-common/api_error.rs"]
+mod api_error;
+mod consolidate;
+mod snapshot;
 mod types;
 
 use types::GoogleSheetsAction;
@@ -92,6 +111,16 @@ impl exports::near::agent::tool::Guest for GoogleSheetsTool {
                         "spreadsheet_id": {
                             "type": "string",
                             "description": "The spreadsheet ID (same as Google Drive file ID)"
+                        },
+                        "detail": {
+                            "type": "string",
+                            "enum": ["summary", "standard", "full"],
+                            "description": "'summary': sheet names and sizes; 'standard': sheets and named ranges; 'full': also frozen rows/columns, hidden sheets, locale and time zone",
+                            "default": "standard"
+                        },
+                        "fields": {
+                            "type": "string",
+                            "description": "Google API field mask (e.g. 'sheets(properties.title,conditionalFormats)'). Returns the raw API response limited to these fields instead of the summary"
                         }
                     },
                     "required": ["action", "spreadsheet_id"]
@@ -298,6 +327,72 @@ impl exports::near::agent::tool::Guest for GoogleSheetsTool {
                         }
                     },
                     "required": ["action", "spreadsheet_id", "sheet_id", "start_row", "end_row", "start_column", "end_column"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "snapshot_range" },
+                        "spreadsheet_id": {
+                            "type": "string",
+                            "description": "The spreadsheet ID"
+                        },
+                        "range": {
+                            "type": "string",
+                            "description": "Range to snapshot in A1 notation (e.g., 'Sheet1!A1:D50')"
+                        },
+                        "snapshot_id": {
+                            "type": "string",
+                            "description": "Snapshot ID (letters, digits, '-', '_'; max 64). Generated if omitted. Reusing an ID replaces the snapshot."
+                        }
+                    },
+                    "required": ["action", "spreadsheet_id", "range"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "diff_range" },
+                        "snapshot_id": {
+                            "type": "string",
+                            "description": "Snapshot ID returned by snapshot_range"
+                        }
+                    },
+                    "required": ["action", "snapshot_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "consolidate_ranges" },
+                        "spreadsheet_ids": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Spreadsheets to read from"
+                        },
+                        "range": {
+                            "type": "string",
+                            "description": "Tab name, A1 range or named range to read from each spreadsheet (e.g., 'Tracker', 'Tracker!A1:F200')"
+                        },
+                        "target_spreadsheet_id": {
+                            "type": "string",
+                            "description": "Spreadsheet to write the combined rows to"
+                        },
+                        "target_sheet": {
+                            "type": "string",
+                            "description": "Tab to write to. Created if missing; its contents are replaced"
+                        },
+                        "has_header": {
+                            "type": "boolean",
+                            "description": "First row of each range is a header; columns are matched by header name",
+                            "default": true
+                        },
+                        "source_column": {
+                            "type": "string",
+                            "description": "Header of the column naming each row's source spreadsheet",
+                            "default": "Source"
+                        },
+                        "group_by": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Header names to merge rows on across sources. Numbers in merged rows are summed; other cells keep the first non-empty value"
+                        }
+                    },
+                    "required": ["action", "spreadsheet_ids", "range", "target_spreadsheet_id", "target_sheet"]
                 }
             ]
         }"#
@@ -308,7 +403,10 @@ impl exports::near::agent::tool::Guest for GoogleSheetsTool {
         "Google Sheets integration for creating, reading, writing, and formatting spreadsheets. \
          Supports cell value operations (read, write, append, clear) using A1 notation, sheet \
          (tab) management (add, delete, rename), and cell formatting (bold, colors, alignment, \
-         number formats). Spreadsheet IDs are the same as Google Drive file IDs, so use the \
+         number formats). Snapshots a range before edits and diffs it afterwards, returning \
+         changed cells, a readable report, and values for rollback. Consolidates the same \
+         range from several spreadsheets into one tab, with a source column and optional \
+         grouped totals. Spreadsheet IDs are the same as Google Drive file IDs, so use the \
          google-drive tool to search for existing spreadsheets. Requires a Google OAuth token \
          with the spreadsheets scope."
             .to_string()
@@ -317,11 +415,7 @@ impl exports::near::agent::tool::Guest for GoogleSheetsTool {
 
 fn execute_inner(params: &str) -> Result<String, String> {
     if !crate::near::agent::host::secret_exists("google_oauth_token") {
-        return Err(
-            "Google OAuth token not configured. Run `ironclaw tool auth google-sheets` to set up \
-             OAuth, or set the GOOGLE_OAUTH_TOKEN environment variable."
-                .to_string(),
-        );
+        return Err(api_error::missing_token("google-sheets"));
     }
 
     let action: GoogleSheetsAction =
@@ -338,10 +432,17 @@ fn execute_inner(params: &str) -> Result<String, String> {
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
-        GoogleSheetsAction::GetSpreadsheet { spreadsheet_id } => {
-            let result = api::get_spreadsheet(&spreadsheet_id)?;
-            serde_json::to_string(&result).map_err(|e| e.to_string())?
-        }
+        GoogleSheetsAction::GetSpreadsheet {
+            spreadsheet_id,
+            detail,
+            fields,
+        } => match fields {
+            Some(fields) => api::get_spreadsheet_fields(&spreadsheet_id, &fields)?,
+            None => {
+                let result = api::get_spreadsheet(&spreadsheet_id, detail)?;
+                serde_json::to_string(&result).map_err(|e| e.to_string())?
+            }
+        },
 
         GoogleSheetsAction::ReadValues {
             spreadsheet_id,
@@ -446,6 +547,41 @@ fn execute_inner(params: &str) -> Result<String, String> {
             })?;
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
+
+        GoogleSheetsAction::SnapshotRange {
+            spreadsheet_id,
+            range,
+            snapshot_id,
+        } => {
+            let result = snapshot::snapshot_range(&spreadsheet_id, &range, snapshot_id.as_deref())?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSheetsAction::DiffRange { snapshot_id } => {
+            let result = snapshot::diff_range(&snapshot_id)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSheetsAction::ConsolidateRanges {
+            spreadsheet_ids,
+            range,
+            target_spreadsheet_id,
+            target_sheet,
+            has_header,
+            source_column,
+            group_by,
+        } => {
+            let result = consolidate::consolidate_ranges(consolidate::ConsolidateOptions {
+                spreadsheet_ids: &spreadsheet_ids,
+                range: &range,
+                target_spreadsheet_id: &target_spreadsheet_id,
+                target_sheet: &target_sheet,
+                has_header,
+                source_column: source_column.as_deref(),
+                group_by: &group_by,
+            })?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
     };
 
     Ok(result)
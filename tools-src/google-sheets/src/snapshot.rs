@@ -0,0 +1,347 @@
+//! Range snapshots for change review.
+//!
+//! `snapshot_range` stores a range's current cells as JSON in the agent's
+//! workspace under `sheets/snapshots/<id>.json`; `diff_range` reads the same
+//! range again and reports every cell that changed, together with the
+//! snapshot grid in a shape `write_values` accepts, so an edit can be undone.
+//!
+//! Cells are read with `valueRenderOption=FORMULA`: formula cells compare by
+//! formula (recalculation alone is not a change) and restore as formulas.
+
+use serde::{Deserialize, Serialize};
+
+use crate::api;
+use crate::near::agent::host;
+use crate::types::*;
+
+/// Workspace directory holding snapshots.
+const SNAPSHOT_DIR: &str = "sheets/snapshots/";
+
+/// Changes listed individually before the rest are only counted.
+const MAX_LISTED_CHANGES: usize = 500;
+
+/// Changes spelled out in the plain-text report.
+const MAX_REPORTED_CHANGES: usize = 50;
+
+/// A stored snapshot.
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    snapshot_id: String,
+    spreadsheet_id: String,
+    /// Range as resolved by the API (e.g., "Sheet1!A1:D20").
+    range: String,
+    taken_at_millis: u64,
+    values: Vec<Vec<serde_json::Value>>,
+}
+
+fn snapshot_path(snapshot_id: &str) -> String {
+    format!("{}{}.json", SNAPSHOT_DIR, snapshot_id)
+}
+
+fn validate_snapshot_id(snapshot_id: &str) -> Result<(), String> {
+    let valid = !snapshot_id.is_empty()
+        && snapshot_id.len() <= 64
+        && snapshot_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid snapshot_id '{}': use 1-64 letters, digits, '-' or '_'",
+            snapshot_id
+        ))
+    }
+}
+
+/// Store the current cells of `range` in the workspace.
+pub fn snapshot_range(
+    spreadsheet_id: &str,
+    range: &str,
+    snapshot_id: Option<&str>,
+) -> Result<SnapshotResult, String> {
+    let now = host::now_millis();
+    let snapshot_id = match snapshot_id {
+        Some(id) => id.to_string(),
+        None => format!("snap-{}", now),
+    };
+    validate_snapshot_id(&snapshot_id)?;
+
+    let current = api::read_values_rendered(spreadsheet_id, range, "FORMULA")?;
+    let snapshot = Snapshot {
+        snapshot_id: snapshot_id.clone(),
+        spreadsheet_id: spreadsheet_id.to_string(),
+        range: current.range,
+        taken_at_millis: now,
+        values: current.values,
+    };
+
+    let path = snapshot_path(&snapshot_id);
+    let content = serde_json::to_string(&snapshot).map_err(|e| e.to_string())?;
+    host::workspace_write(&path, &content)
+        .map_err(|e| format!("Failed to store snapshot: {}", e))?;
+
+    Ok(SnapshotResult {
+        snapshot_id,
+        path,
+        range: snapshot.range,
+        rows: snapshot.values.len(),
+        columns: snapshot.values.iter().map(Vec::len).max().unwrap_or(0),
+        non_empty_cells: snapshot
+            .values
+            .iter()
+            .flatten()
+            .filter(|v| !is_blank(v))
+            .count(),
+    })
+}
+
+/// Compare the snapshotted range with its current cells.
+pub fn diff_range(snapshot_id: &str) -> Result<DiffResult, String> {
+    validate_snapshot_id(snapshot_id)?;
+    let path = snapshot_path(snapshot_id);
+    let content = host::workspace_read(&path)
+        .ok_or_else(|| format!("Snapshot '{}' not found at {}", snapshot_id, path))?;
+    let snapshot: Snapshot = serde_json::from_str(&content)
+        .map_err(|e| format!("Snapshot '{}' is corrupt: {}", snapshot_id, e))?;
+
+    let current = api::read_values_rendered(&snapshot.spreadsheet_id, &snapshot.range, "FORMULA")?;
+    let (sheet, start_col, start_row) = range_start(&snapshot.range)?;
+    let changes = diff_values(&snapshot.values, &current.values, start_col, start_row);
+
+    let changed_cells = changes.len();
+    let report = change_report(&snapshot.range, &changes);
+    let rollback = RollbackData {
+        range: format!("{}{}{}", sheet, column_name(start_col), start_row),
+        values: pad_grid(&snapshot.values, &current.values),
+    };
+
+    Ok(DiffResult {
+        snapshot_id: snapshot.snapshot_id,
+        spreadsheet_id: snapshot.spreadsheet_id,
+        range: snapshot.range,
+        taken_at_millis: snapshot.taken_at_millis,
+        changed_cells,
+        truncated: changed_cells > MAX_LISTED_CHANGES,
+        changes: changes.into_iter().take(MAX_LISTED_CHANGES).collect(),
+        report,
+        rollback,
+    })
+}
+
+/// Empty strings and missing cells read back the same way.
+fn is_blank(v: &serde_json::Value) -> bool {
+    v.is_null() || v.as_str() == Some("")
+}
+
+fn cell_at(grid: &[Vec<serde_json::Value>], row: usize, col: usize) -> serde_json::Value {
+    grid.get(row)
+        .and_then(|r| r.get(col))
+        .filter(|v| !is_blank(v))
+        .cloned()
+        .unwrap_or_else(|| serde_json::Value::String(String::new()))
+}
+
+/// Cells that differ between two grids anchored at `start_col`/`start_row`
+/// (1-based), in row-major order.
+fn diff_values(
+    before: &[Vec<serde_json::Value>],
+    after: &[Vec<serde_json::Value>],
+    start_col: u32,
+    start_row: u32,
+) -> Vec<CellChange> {
+    let rows = before.len().max(after.len());
+    let mut changes = Vec::new();
+    for row in 0..rows {
+        let cols = before
+            .get(row)
+            .map_or(0, Vec::len)
+            .max(after.get(row).map_or(0, Vec::len));
+        for col in 0..cols {
+            let old = cell_at(before, row, col);
+            let new = cell_at(after, row, col);
+            if old != new {
+                changes.push(CellChange {
+                    cell: format!(
+                        "{}{}",
+                        column_name(start_col + col as u32),
+                        start_row + row as u32
+                    ),
+                    before: old,
+                    after: new,
+                });
+            }
+        }
+    }
+    changes
+}
+
+/// The snapshot grid, widened with blanks to cover everything written since,
+/// so writing it back also clears new cells.
+fn pad_grid(
+    before: &[Vec<serde_json::Value>],
+    after: &[Vec<serde_json::Value>],
+) -> Vec<Vec<serde_json::Value>> {
+    let rows = before.len().max(after.len());
+    let cols = before
+        .iter()
+        .chain(after.iter())
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0);
+    (0..rows)
+        .map(|row| (0..cols).map(|col| cell_at(before, row, col)).collect())
+        .collect()
+}
+
+fn change_report(range: &str, changes: &[CellChange]) -> String {
+    if changes.is_empty() {
+        return format!("No changes in {}.", range);
+    }
+    let mut lines = vec![format!(
+        "{} cell{} changed in {}:",
+        changes.len(),
+        if changes.len() == 1 { "" } else { "s" },
+        range
+    )];
+    for change in changes.iter().take(MAX_REPORTED_CHANGES) {
+        lines.push(format!(
+            "{}: {} -> {}",
+            change.cell,
+            display_value(&change.before),
+            display_value(&change.after)
+        ));
+    }
+    if changes.len() > MAX_REPORTED_CHANGES {
+        lines.push(format!(
+            "... and {} more",
+            changes.len() - MAX_REPORTED_CHANGES
+        ));
+    }
+    lines.join("\n")
+}
+
+fn display_value(v: &serde_json::Value) -> String {
+    match v {
+        serde_json::Value::String(s) if s.is_empty() => "(empty)".to_string(),
+        serde_json::Value::String(s) => format!("\"{}\"", s),
+        other => other.to_string(),
+    }
+}
+
+/// Split a resolved range like "'My Sheet'!B2:D9" into the sheet prefix
+/// (including the `!`, empty if absent) and its top-left column and row,
+/// both 1-based.
+fn range_start(range: &str) -> Result<(String, u32, u32), String> {
+    let (sheet, cells) = match range.rfind('!') {
+        Some(i) => (&range[..=i], &range[i + 1..]),
+        None => ("", range),
+    };
+    let start = cells.split(':').next().unwrap_or("");
+    let letters: String = start
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    let digits = &start[letters.len()..];
+
+    let col = if letters.is_empty() {
+        1
+    } else {
+        letters.to_ascii_uppercase().bytes().fold(0u32, |acc, b| {
+            acc.saturating_mul(26).saturating_add((b - b'A' + 1) as u32)
+        })
+    };
+    let row = if digits.is_empty() {
+        1
+    } else {
+        digits
+            .parse::<u32>()
+            .map_err(|_| format!("Cannot parse range start '{}'", start))?
+    };
+    Ok((sheet.to_string(), col, row))
+}
+
+/// Column letters for a 1-based column number (1 -> A, 27 -> AA).
+fn column_name(mut col: u32) -> String {
+    let mut name = Vec::new();
+    while col > 0 {
+        let rem = (col - 1) % 26;
+        name.push(b'A' + rem as u8);
+        col = (col - 1) / 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_range_start() {
+        assert_eq!(
+            range_start("Sheet1!B2:D9").unwrap(),
+            ("Sheet1!".to_string(), 2, 2)
+        );
+        assert_eq!(
+            range_start("'Q1 Data'!AA10:AB12").unwrap(),
+            ("'Q1 Data'!".to_string(), 27, 10)
+        );
+        assert_eq!(range_start("A:E").unwrap(), (String::new(), 1, 1));
+    }
+
+    #[test]
+    fn test_column_name() {
+        assert_eq!(column_name(1), "A");
+        assert_eq!(column_name(26), "Z");
+        assert_eq!(column_name(27), "AA");
+        assert_eq!(column_name(703), "AAA");
+    }
+
+    #[test]
+    fn test_diff_values_reports_edits_additions_and_clears() {
+        let before = vec![
+            vec![json!("Name"), json!("Score")],
+            vec![json!("Ann"), json!(9)],
+        ];
+        let after = vec![
+            vec![json!("Name"), json!("Score")],
+            vec![json!("Ann")],
+            vec![json!("Bob"), json!("=B2+1")],
+        ];
+        let changes = diff_values(&before, &after, 2, 5);
+        let cells: Vec<&str> = changes.iter().map(|c| c.cell.as_str()).collect();
+        assert_eq!(cells, vec!["C6", "B7", "C7"]);
+        assert_eq!(changes[0].before, json!(9));
+        assert_eq!(changes[0].after, json!(""));
+
+        let report = change_report("Sheet1!B5:C7", &changes);
+        assert!(report.starts_with("3 cells changed in Sheet1!B5:C7:"));
+        assert!(report.contains("C6: 9 -> (empty)"));
+    }
+
+    #[test]
+    fn test_blank_cells_are_not_changes() {
+        let before = vec![vec![json!("a"), json!("")]];
+        let after = vec![vec![json!("a")]];
+        assert!(diff_values(&before, &after, 1, 1).is_empty());
+    }
+
+    #[test]
+    fn test_pad_grid_clears_new_cells() {
+        let before = vec![vec![json!(1)]];
+        let after = vec![vec![json!(1), json!(2)], vec![json!(3)]];
+        assert_eq!(
+            pad_grid(&before, &after),
+            vec![vec![json!(1), json!("")], vec![json!(""), json!("")]]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_id_validation() {
+        assert!(validate_snapshot_id("before-bulk-edit_2").is_ok());
+        assert!(validate_snapshot_id("../secrets").is_err());
+        assert!(validate_snapshot_id("").is_err());
+    }
+}
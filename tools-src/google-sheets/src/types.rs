@@ -19,6 +19,13 @@ pub enum GoogleSheetsAction {
     GetSpreadsheet {
         /// The spreadsheet ID (same as Google Drive file ID).
         spreadsheet_id: String,
+        /// How much metadata to include (default: standard).
+        #[serde(default)]
+        detail: Detail,
+        /// Google API field mask. When set, the raw API response limited to
+        /// these fields is returned instead of the summarized result.
+        #[serde(default)]
+        fields: Option<String>,
     },
 
     /// Read cell values from a range.
@@ -136,12 +143,58 @@ pub enum GoogleSheetsAction {
         #[serde(default)]
         number_format_type: Option<String>,
     },
+
+    /// Store the current cells of a range in the workspace for later review.
+    SnapshotRange {
+        /// The spreadsheet ID.
+        spreadsheet_id: String,
+        /// Range in A1 notation (e.g., "Sheet1!A1:D20").
+        range: String,
+        /// Snapshot ID (letters, digits, '-', '_'). Generated if omitted;
+        /// reusing an ID replaces that snapshot.
+        #[serde(default)]
+        snapshot_id: Option<String>,
+    },
+
+    /// Compare a range's current cells against a stored snapshot.
+    DiffRange {
+        /// Snapshot ID returned by snapshot_range.
+        snapshot_id: String,
+    },
+
+    /// Read the same range from several spreadsheets and write the combined
+    /// rows, with a source column, to a tab of a target spreadsheet.
+    ConsolidateRanges {
+        /// Spreadsheets to read from.
+        spreadsheet_ids: Vec<String>,
+        /// Tab name, A1 range or named range, the same in every source
+        /// (e.g., "Tracker", "Tracker!A1:F200").
+        range: String,
+        /// Spreadsheet to write to.
+        target_spreadsheet_id: String,
+        /// Tab to write to; created if missing, cleared otherwise.
+        target_sheet: String,
+        /// Whether the first row of each range is a header (default true).
+        /// Columns are then matched by header name.
+        #[serde(default = "default_true")]
+        has_header: bool,
+        /// Header of the column holding each row's source (default "Source").
+        #[serde(default)]
+        source_column: Option<String>,
+        /// Header names to merge rows on; numbers in merged rows are summed.
+        #[serde(default)]
+        group_by: Vec<String>,
+    },
 }
 
 fn default_value_input_option() -> String {
     "USER_ENTERED".to_string()
 }
 
+fn default_true() -> bool {
+    true
+}
+
 /// Sheet (tab) info within a spreadsheet.
 #[derive(Debug, Serialize)]
 pub struct SheetInfo {
@@ -150,6 +203,25 @@ pub struct SheetInfo {
     pub index: i64,
     pub row_count: i64,
     pub column_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frozen_row_count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frozen_column_count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hidden: Option<bool>,
+}
+
+/// How much of a spreadsheet get_spreadsheet returns.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Detail {
+    /// Sheet names and sizes only.
+    Summary,
+    /// Sheets and named ranges.
+    #[default]
+    Standard,
+    /// Also frozen rows/columns, hidden sheets, locale and time zone.
+    Full,
 }
 
 /// Named range within a spreadsheet.
@@ -175,6 +247,10 @@ pub struct SpreadsheetMetadata {
     pub spreadsheet_id: String,
     pub title: String,
     pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
     pub sheets: Vec<SheetInfo>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub named_ranges: Vec<NamedRange>,
@@ -227,3 +303,77 @@ pub struct FormatResult {
     pub spreadsheet_id: String,
     pub success: bool,
 }
+
+/// Result from snapshot_range.
+#[derive(Debug, Serialize)]
+pub struct SnapshotResult {
+    pub snapshot_id: String,
+    /// Workspace path the snapshot was stored at.
+    pub path: String,
+    /// Range as resolved by the API.
+    pub range: String,
+    pub rows: usize,
+    pub columns: usize,
+    pub non_empty_cells: usize,
+}
+
+/// A single cell that differs from its snapshot.
+#[derive(Debug, Serialize)]
+pub struct CellChange {
+    /// A1 reference without the sheet name (e.g., "C7").
+    pub cell: String,
+    /// Snapshot value; "" for an empty cell.
+    pub before: serde_json::Value,
+    /// Current value; "" for an empty cell.
+    pub after: serde_json::Value,
+}
+
+/// Values that restore a range to its snapshot when passed to write_values
+/// with value_input_option "USER_ENTERED".
+#[derive(Debug, Serialize)]
+pub struct RollbackData {
+    /// Top-left cell of the snapshotted range.
+    pub range: String,
+    pub values: Vec<Vec<serde_json::Value>>,
+}
+
+/// Result from diff_range.
+#[derive(Debug, Serialize)]
+pub struct DiffResult {
+    pub snapshot_id: String,
+    pub spreadsheet_id: String,
+    pub range: String,
+    pub taken_at_millis: u64,
+    pub changed_cells: usize,
+    /// True when `changes` lists only the first changes.
+    pub truncated: bool,
+    pub changes: Vec<CellChange>,
+    /// Plain-text summary of the changes.
+    pub report: String,
+    pub rollback: RollbackData,
+}
+
+/// Result from consolidate_ranges.
+#[derive(Debug, Serialize)]
+pub struct ConsolidateResult {
+    pub target_spreadsheet_id: String,
+    /// Range written, as resolved by the API; empty when nothing was written.
+    pub updated_range: String,
+    /// Rows written, not counting the header.
+    pub rows: usize,
+    pub columns: usize,
+    pub sources: Vec<ConsolidatedSource>,
+}
+
+/// What was read from one source spreadsheet.
+#[derive(Debug, Serialize)]
+pub struct ConsolidatedSource {
+    pub spreadsheet_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Rows read, not counting the header.
+    pub rows: usize,
+    /// Why the source was skipped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
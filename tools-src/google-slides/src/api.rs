@@ -4,10 +4,51 @@
 //! credential injection and rate limiting. The WASM tool never sees
 //! the actual OAuth token.
 
+use std::collections::BTreeMap;
+
+use crate::api_error::{api_error, GoogleApi};
 use crate::near::agent::host;
 use crate::types::*;
 
 const SLIDES_API_BASE: &str = "https://slides.googleapis.com/v1/presentations";
+const DRIVE_API_BASE: &str = "https://www.googleapis.com/drive/v3";
+const DRIVE_UPLOAD_BASE: &str = "https://www.googleapis.com/upload/drive/v3";
+
+/// Just what get_presentation reads. Text styles, layouts and masters make
+/// up most of a full response.
+const PRESENTATION_FIELDS: &str = "presentationId,title,revisionId,\
+    slides(objectId,slideProperties/layoutObjectId,pageElements(objectId,size,transform,\
+    shape(shapeType,placeholder/type,text/textElements/textRun/content),\
+    image/contentUrl,table(rows,columns),line/lineType,video/id,elementGroup/children/objectId))";
+
+/// Characters of text kept per element below `Detail::Full`.
+const TEXT_PREVIEW_CHARS: usize = 200;
+
+/// Slide titles, for publish_deck's outline and previews.
+const OUTLINE_FIELDS: &str = "presentationId,title,\
+    slides(objectId,pageElements(shape(placeholder/type,text/textElements/textRun/content)))";
+
+/// Masters and layouts with their placeholders, for list_layouts.
+const LAYOUT_FIELDS: &str = "presentationId,\
+    masters(objectId,masterProperties/displayName),\
+    layouts(objectId,layoutProperties,pageElements(objectId,shape/placeholder))";
+
+/// Placeholders on a slide and the text they hold.
+const SLIDE_PLACEHOLDER_FIELDS: &str =
+    "pageElements(objectId,shape(placeholder,text/textElements/textRun/content))";
+
+/// Most thumbnails publish_deck fetches; each one is a separate API call.
+const MAX_PREVIEWS: usize = 25;
+
+const API: GoogleApi = GoogleApi {
+    name: "Google Slides",
+    scope: "https://www.googleapis.com/auth/presentations",
+};
+
+const DRIVE_API: GoogleApi = GoogleApi {
+    name: "Google Drive",
+    scope: "https://www.googleapis.com/auth/drive",
+};
 
 /// Make a Google Slides API call.
 fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, String> {
@@ -16,7 +57,21 @@ fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, Stri
     } else {
         format!("{}/{}", SLIDES_API_BASE, path)
     };
+    http_call(&API, method, &url, body)
+}
+
+/// Make a Google Drive API call (used for sharing).
+fn drive_call(method: &str, path: &str, body: Option<&str>) -> Result<String, String> {
+    let url = format!("{}/{}", DRIVE_API_BASE, path);
+    http_call(&DRIVE_API, method, &url, body)
+}
 
+fn http_call(
+    api: &GoogleApi,
+    method: &str,
+    url: &str,
+    body: Option<&str>,
+) -> Result<String, String> {
     let headers = if body.is_some() {
         r#"{"Content-Type": "application/json"}"#
     } else {
@@ -27,16 +82,17 @@ fn api_call(method: &str, path: &str, body: Option<&str>) -> Result<String, Stri
 
     host::log(
         host::LogLevel::Debug,
-        &format!("Google Slides API: {} {}", method, url),
+        &format!("{} API: {} {}", api.name, method, url),
     );
 
-    let response = host::http_request(method, &url, headers, body_bytes.as_deref())?;
+    let response = host::http_request(method, url, headers, body_bytes.as_deref())?;
 
     if response.status < 200 || response.status >= 300 {
-        let body_text = String::from_utf8_lossy(&response.body);
-        return Err(format!(
-            "Google Slides API returned status {}: {}",
-            response.status, body_text
+        return Err(api_error(
+            api,
+            response.status,
+            &response.headers_json,
+            &response.body,
         ));
     }
 
@@ -77,15 +133,18 @@ fn extract_text_from_shape(shape: &serde_json::Value) -> Option<String> {
     }
 }
 
-/// Parse a page element into ElementInfo.
-fn parse_element(el: &serde_json::Value) -> ElementInfo {
+/// Parse a page element into ElementInfo, with its text shaped for `detail`.
+fn parse_element(el: &serde_json::Value, detail: Detail) -> ElementInfo {
     let object_id = el["objectId"].as_str().unwrap_or("").to_string();
 
     let (element_type, text_content, placeholder_type) = if el.get("shape").is_some() {
         let pt = el["shape"]["placeholder"]["type"]
             .as_str()
             .map(|s| s.to_string());
-        let text = extract_text_from_shape(&el["shape"]);
+        let text = extract_text_from_shape(&el["shape"]).map(|text| match detail {
+            Detail::Full => text,
+            _ => preview(&text, TEXT_PREVIEW_CHARS),
+        });
         ("shape".to_string(), text, pt)
     } else if el.get("image").is_some() {
         ("image".to_string(), None, None)
@@ -106,6 +165,31 @@ fn parse_element(el: &serde_json::Value) -> ElementInfo {
         element_type,
         text_content,
         placeholder_type,
+        geometry: element_geometry(el),
+    }
+}
+
+/// Text of a slide's title placeholder.
+fn slide_title(elements: &[serde_json::Value]) -> Option<String> {
+    elements
+        .iter()
+        .find(|el| {
+            matches!(
+                el["shape"]["placeholder"]["type"].as_str(),
+                Some("TITLE" | "CENTERED_TITLE")
+            )
+        })
+        .and_then(|el| extract_text_from_shape(&el["shape"]))
+        .map(|text| preview(&text, TEXT_PREVIEW_CHARS))
+}
+
+/// The first `max_chars` characters of `text`, trimmed, with an ellipsis if
+/// anything was cut.
+fn preview(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => format!("{}…", text[..cut].trim_end()),
+        None => text.to_string(),
     }
 }
 
@@ -124,9 +208,16 @@ pub fn create_presentation(title: &str) -> Result<CreatePresentationResult, Stri
     })
 }
 
-/// Get presentation metadata and slides.
-pub fn get_presentation(presentation_id: &str) -> Result<PresentationMetadata, String> {
-    let path = url_encode(presentation_id);
+/// Get presentation metadata and slides, summarized to `detail`.
+pub fn get_presentation(
+    presentation_id: &str,
+    detail: Detail,
+) -> Result<PresentationMetadata, String> {
+    let path = format!(
+        "{}?fields={}",
+        url_encode(presentation_id),
+        url_encode(PRESENTATION_FIELDS)
+    );
 
     let response = api_call("GET", &path, None)?;
     let parsed: serde_json::Value =
@@ -137,10 +228,17 @@ pub fn get_presentation(presentation_id: &str) -> Result<PresentationMetadata, S
         .map(|arr| {
             arr.iter()
                 .map(|slide| {
-                    let elements = slide["pageElements"]
+                    let page_elements = slide["pageElements"]
                         .as_array()
-                        .map(|els| els.iter().map(parse_element).collect())
+                        .map(Vec::as_slice)
                         .unwrap_or_default();
+                    let elements = match detail {
+                        Detail::Summary => Vec::new(),
+                        _ => page_elements
+                            .iter()
+                            .map(|el| parse_element(el, detail))
+                            .collect(),
+                    };
 
                     SlideInfo {
                         object_id: slide["objectId"].as_str().unwrap_or("").to_string(),
@@ -148,6 +246,8 @@ pub fn get_presentation(presentation_id: &str) -> Result<PresentationMetadata, S
                             .as_str()
                             .unwrap_or("")
                             .to_string(),
+                        title: slide_title(page_elements),
+                        element_count: page_elements.len(),
                         elements,
                     }
                 })
@@ -166,10 +266,27 @@ pub fn get_presentation(presentation_id: &str) -> Result<PresentationMetadata, S
     })
 }
 
-/// Get a thumbnail URL for a slide.
+/// Get a presentation as the raw API response, limited to a field mask.
+pub fn get_presentation_fields(presentation_id: &str, fields: &str) -> Result<String, String> {
+    let path = format!(
+        "{}?fields={}",
+        url_encode(presentation_id),
+        url_encode(fields)
+    );
+    api_call("GET", &path, None)
+}
+
+/// Where get_thumbnail stores a copy of the image.
+pub struct SaveToDrive<'a> {
+    pub folder_id: Option<&'a str>,
+    pub file_name: Option<&'a str>,
+}
+
+/// Get a thumbnail URL for a slide, optionally saving the PNG to Drive.
 pub fn get_thumbnail(
     presentation_id: &str,
     slide_object_id: &str,
+    save: Option<SaveToDrive<'_>>,
 ) -> Result<ThumbnailResult, String> {
     let path = format!(
         "{}/pages/{}/thumbnail",
@@ -180,25 +297,309 @@ pub fn get_thumbnail(
     let response = api_call("GET", &path, None)?;
     let parsed: serde_json::Value =
         serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+    let content_url = parsed["contentUrl"].as_str().unwrap_or("").to_string();
+
+    let drive_file = match save {
+        Some(save) => {
+            let default_name = format!("slide-{}.png", slide_object_id);
+            let name = save.file_name.unwrap_or(&default_name);
+            let png = download(&content_url)?;
+            Some(upload_png(name, &png, save.folder_id)?)
+        }
+        None => None,
+    };
 
     Ok(ThumbnailResult {
-        content_url: parsed["contentUrl"].as_str().unwrap_or("").to_string(),
+        content_url,
         width: parsed["width"].as_i64().unwrap_or(0),
         height: parsed["height"].as_i64().unwrap_or(0),
+        drive_file,
+    })
+}
+
+/// Fetch a thumbnail image. Content URLs are pre-signed, so no credential is
+/// attached.
+fn download(url: &str) -> Result<Vec<u8>, String> {
+    if url.is_empty() {
+        return Err("Thumbnail response had no contentUrl".to_string());
+    }
+    host::log(host::LogLevel::Debug, "Downloading slide thumbnail");
+    let response = host::http_request("GET", url, "{}", None)?;
+    if response.status < 200 || response.status >= 300 {
+        return Err(format!(
+            "Thumbnail download failed with status {}",
+            response.status
+        ));
+    }
+    Ok(response.body)
+}
+
+/// Upload a PNG to Drive with a multipart upload.
+fn upload_png(name: &str, png: &[u8], folder_id: Option<&str>) -> Result<DriveFile, String> {
+    let boundary = "ironclaw_upload_boundary_42";
+
+    let mut metadata = serde_json::json!({
+        "name": name,
+        "mimeType": "image/png",
+    });
+    if let Some(folder_id) = folder_id {
+        metadata["parents"] = serde_json::json!([folder_id]);
+    }
+    let metadata_str = serde_json::to_string(&metadata).map_err(|e| e.to_string())?;
+    let body = multipart_body(boundary, &metadata_str, "image/png", png);
+
+    let url = format!(
+        "{}/files?uploadType=multipart&fields=id,name,webViewLink&supportsAllDrives=true",
+        DRIVE_UPLOAD_BASE
+    );
+    let headers = format!(
+        r#"{{"Content-Type": "multipart/related; boundary={}"}}"#,
+        boundary
+    );
+
+    host::log(
+        host::LogLevel::Debug,
+        "Google Drive API: POST upload/files (multipart)",
+    );
+    let response = host::http_request("POST", &url, &headers, Some(&body))?;
+    if response.status < 200 || response.status >= 300 {
+        return Err(api_error(
+            &DRIVE_API,
+            response.status,
+            &response.headers_json,
+            &response.body,
+        ));
+    }
+
+    let parsed: serde_json::Value = serde_json::from_slice(&response.body)
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    Ok(DriveFile {
+        file_id: parsed["id"].as_str().unwrap_or("").to_string(),
+        name: parsed["name"].as_str().unwrap_or(name).to_string(),
+        web_view_link: parsed["webViewLink"].as_str().map(str::to_string),
+    })
+}
+
+/// A `multipart/related` body: JSON metadata, then the binary content.
+fn multipart_body(boundary: &str, metadata: &str, mime_type: &str, content: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(content.len() + metadata.len() + 256);
+    body.extend_from_slice(
+        format!(
+            "--{}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{}\r\n--{}\r\nContent-Type: {}\r\n\r\n",
+            boundary, metadata, boundary, mime_type
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(content);
+    body.extend_from_slice(format!("\r\n--{}--", boundary).as_bytes());
+    body
+}
+
+/// Options for publish_deck.
+pub struct PublishOptions<'a> {
+    pub presentation_id: &'a str,
+    pub share: &'a str,
+    pub domain: Option<&'a str>,
+    pub role: &'a str,
+    pub max_previews: usize,
+    pub thumbnail_size: &'a str,
+}
+
+/// Share a presentation by link, fetch thumbnails of its first slides, and
+/// compose a summary message.
+pub fn publish_deck(opts: PublishOptions) -> Result<PublishDeckResult, String> {
+    if !matches!(opts.role, "reader" | "commenter" | "writer") {
+        return Err(format!(
+            "Invalid role '{}': expected reader, commenter, or writer",
+            opts.role
+        ));
+    }
+    if !matches!(opts.thumbnail_size, "SMALL" | "MEDIUM" | "LARGE") {
+        return Err(format!(
+            "Invalid thumbnail_size '{}': expected SMALL, MEDIUM, or LARGE",
+            opts.thumbnail_size
+        ));
+    }
+    let permission = match (opts.share, opts.domain) {
+        ("none", _) => None,
+        ("anyone", _) => Some(serde_json::json!({
+            "type": "anyone",
+            "role": opts.role,
+            "allowFileDiscovery": false,
+        })),
+        ("domain", Some(domain)) => Some(serde_json::json!({
+            "type": "domain",
+            "role": opts.role,
+            "domain": domain,
+            "allowFileDiscovery": false,
+        })),
+        ("domain", None) => return Err("share 'domain' requires a domain".to_string()),
+        (other, _) => {
+            return Err(format!(
+                "Invalid share '{}': expected anyone, domain, or none",
+                other
+            ))
+        }
+    };
+
+    let outline = get_presentation_fields(opts.presentation_id, OUTLINE_FIELDS)?;
+    let outline: serde_json::Value =
+        serde_json::from_str(&outline).map_err(|e| format!("Failed to parse response: {}", e))?;
+    let title = outline["title"].as_str().unwrap_or("").to_string();
+    let slides: Vec<(String, Option<String>)> = outline["slides"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .map(|slide| {
+                    let elements = slide["pageElements"]
+                        .as_array()
+                        .map(Vec::as_slice)
+                        .unwrap_or_default();
+                    (
+                        slide["objectId"].as_str().unwrap_or("").to_string(),
+                        slide_title(elements),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let sharing = match permission {
+        Some(permission) => {
+            let body = serde_json::to_string(&permission).map_err(|e| e.to_string())?;
+            let path = format!(
+                "files/{}/permissions?supportsAllDrives=true&sendNotificationEmail=false",
+                url_encode(opts.presentation_id)
+            );
+            let response = drive_call("POST", &path, Some(&body))?;
+            let parsed: serde_json::Value = serde_json::from_str(&response)
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+            Some(LinkSharing {
+                permission_id: parsed["id"].as_str().unwrap_or("").to_string(),
+                share: opts.share.to_string(),
+                domain: opts
+                    .domain
+                    .filter(|_| opts.share == "domain")
+                    .map(str::to_string),
+                role: parsed["role"].as_str().unwrap_or(opts.role).to_string(),
+            })
+        }
+        None => None,
+    };
+
+    let mut previews = Vec::new();
+    for (slide_index, (slide_object_id, slide_title)) in slides
+        .iter()
+        .enumerate()
+        .take(opts.max_previews.min(MAX_PREVIEWS))
+    {
+        let path = format!(
+            "{}/pages/{}/thumbnail?thumbnailProperties.thumbnailSize={}",
+            url_encode(opts.presentation_id),
+            url_encode(slide_object_id),
+            opts.thumbnail_size
+        );
+        let response = api_call("GET", &path, None)?;
+        let parsed: serde_json::Value = serde_json::from_str(&response)
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+        previews.push(SlidePreview {
+            slide_object_id: slide_object_id.clone(),
+            slide_index,
+            title: slide_title.clone(),
+            thumbnail_url: parsed["contentUrl"].as_str().unwrap_or("").to_string(),
+            width: parsed["width"].as_i64().unwrap_or(0),
+            height: parsed["height"].as_i64().unwrap_or(0),
+        });
+    }
+
+    let link = presentation_link(opts.presentation_id);
+    let titles: Vec<Option<&str>> = slides.iter().map(|(_, t)| t.as_deref()).collect();
+    let summary = deck_summary(&title, &link, sharing.as_ref(), &titles);
+
+    Ok(PublishDeckResult {
+        presentation_id: opts.presentation_id.to_string(),
+        title,
+        link,
+        slide_count: slides.len(),
+        sharing,
+        previews,
+        summary,
     })
 }
 
+/// Browser link to a presentation.
+fn presentation_link(presentation_id: &str) -> String {
+    format!(
+        "https://docs.google.com/presentation/d/{}/edit?usp=sharing",
+        url_encode(presentation_id)
+    )
+}
+
+/// Plain-text message announcing a published deck.
+fn deck_summary(
+    title: &str,
+    link: &str,
+    sharing: Option<&LinkSharing>,
+    slide_titles: &[Option<&str>],
+) -> String {
+    let title = if title.is_empty() {
+        "Untitled deck"
+    } else {
+        title
+    };
+    let count = slide_titles.len();
+    let mut summary = format!(
+        "{} ({} slide{})\n{}",
+        title,
+        count,
+        if count == 1 { "" } else { "s" },
+        link
+    );
+
+    if let Some(sharing) = sharing {
+        let who = match sharing.domain.as_deref() {
+            Some(domain) => format!("Anyone at {}", domain),
+            None => "Anyone".to_string(),
+        };
+        let can = match sharing.role.as_str() {
+            "writer" => "edit",
+            "commenter" => "comment",
+            _ => "view",
+        };
+        summary.push_str(&format!("\n{} with the link can {}.", who, can));
+    }
+
+    let outline: Vec<String> = slide_titles
+        .iter()
+        .enumerate()
+        .filter_map(|(i, t)| {
+            t.map(|t| t.lines().next().unwrap_or("").trim())
+                .filter(|t| !t.is_empty())
+                .map(|t| format!("{}. {}", i + 1, t))
+        })
+        .collect();
+    if !outline.is_empty() {
+        summary.push_str("\n\n");
+        summary.push_str(&outline.join("\n"));
+    }
+
+    summary
+}
+
 /// Create a new slide.
 pub fn create_slide(
     presentation_id: &str,
     insertion_index: Option<i64>,
     layout: &str,
+    layout_object_id: Option<&str>,
 ) -> Result<UpdateResult, String> {
+    let layout_reference = match layout_object_id {
+        Some(id) => serde_json::json!({ "layoutId": id }),
+        None => serde_json::json!({ "predefinedLayout": layout }),
+    };
     let mut request = serde_json::json!({
         "createSlide": {
-            "slideLayoutReference": {
-                "predefinedLayout": layout,
-            }
+            "slideLayoutReference": layout_reference,
         }
     });
 
@@ -218,6 +619,191 @@ pub fn create_slide(
     })
 }
 
+/// List the presentation's masters and layouts with their placeholders.
+pub fn list_layouts(presentation_id: &str) -> Result<ListLayoutsResult, String> {
+    let response = get_presentation_fields(presentation_id, LAYOUT_FIELDS)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    let masters = parsed["masters"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .map(|m| MasterInfo {
+                    object_id: m["objectId"].as_str().unwrap_or("").to_string(),
+                    display_name: m["masterProperties"]["displayName"]
+                        .as_str()
+                        .unwrap_or("")
+                        .to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let layouts = parsed["layouts"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .map(|l| {
+                    let props = &l["layoutProperties"];
+                    let elements = l["pageElements"]
+                        .as_array()
+                        .map(Vec::as_slice)
+                        .unwrap_or_default();
+                    LayoutInfo {
+                        object_id: l["objectId"].as_str().unwrap_or("").to_string(),
+                        name: props["name"].as_str().map(|s| s.to_string()),
+                        display_name: props["displayName"].as_str().unwrap_or("").to_string(),
+                        master_object_id: props["masterObjectId"]
+                            .as_str()
+                            .unwrap_or("")
+                            .to_string(),
+                        placeholders: elements
+                            .iter()
+                            .filter_map(|el| {
+                                let placeholder = &el["shape"]["placeholder"];
+                                Some(PlaceholderInfo {
+                                    object_id: el["objectId"].as_str()?.to_string(),
+                                    placeholder_type: placeholder["type"].as_str()?.to_string(),
+                                    index: placeholder["index"].as_i64().unwrap_or(0),
+                                })
+                            })
+                            .collect(),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(ListLayoutsResult {
+        presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
+        masters,
+        layouts,
+    })
+}
+
+/// Find the placeholder a key like "BODY" or "BODY:1" names among a slide's
+/// elements. Returns its object ID and whether it holds text.
+///
+/// Placeholders of one type are ordered by their index. TITLE and
+/// CENTERED_TITLE stand in for each other, since layouts use one or the other.
+fn find_placeholder(elements: &[serde_json::Value], key: &str) -> Result<(String, bool), String> {
+    let (wanted, nth) = match key.split_once(':') {
+        Some((t, n)) => (
+            t,
+            n.parse::<usize>()
+                .map_err(|_| format!("Invalid placeholder '{}': expected TYPE or TYPE:n", key))?,
+        ),
+        None => (key, 0),
+    };
+    let wanted = wanted.trim().to_uppercase();
+
+    let of_type = |t: &str| -> Vec<&serde_json::Value> {
+        let mut found: Vec<&serde_json::Value> = elements
+            .iter()
+            .filter(|el| el["shape"]["placeholder"]["type"].as_str() == Some(t))
+            .collect();
+        found.sort_by_key(|el| el["shape"]["placeholder"]["index"].as_i64().unwrap_or(0));
+        found
+    };
+    let mut found = of_type(&wanted);
+    if found.is_empty() {
+        match wanted.as_str() {
+            "TITLE" => found = of_type("CENTERED_TITLE"),
+            "CENTERED_TITLE" => found = of_type("TITLE"),
+            _ => {}
+        }
+    }
+
+    let el = found.get(nth).ok_or_else(|| {
+        let mut available: Vec<&str> = elements
+            .iter()
+            .filter_map(|el| el["shape"]["placeholder"]["type"].as_str())
+            .collect();
+        available.dedup();
+        format!(
+            "Placeholder '{}' not found on slide (available: {})",
+            key,
+            if available.is_empty() {
+                "none".to_string()
+            } else {
+                available.join(", ")
+            }
+        )
+    })?;
+
+    Ok((
+        el["objectId"].as_str().unwrap_or("").to_string(),
+        extract_text_from_shape(&el["shape"]).is_some(),
+    ))
+}
+
+/// Replace the text of a slide's placeholders, addressed by type.
+pub fn apply_layout_placeholder_text(
+    presentation_id: &str,
+    slide_object_id: &str,
+    placeholders: &BTreeMap<String, String>,
+) -> Result<PlaceholderTextResult, String> {
+    if placeholders.is_empty() {
+        return Err("No placeholders specified".to_string());
+    }
+
+    let path = format!(
+        "{}/pages/{}?fields={}",
+        url_encode(presentation_id),
+        url_encode(slide_object_id),
+        url_encode(SLIDE_PLACEHOLDER_FIELDS)
+    );
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+    let elements = parsed["pageElements"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let mut requests = Vec::new();
+    let mut filled = Vec::new();
+    for (key, text) in placeholders {
+        let (object_id, has_text) = find_placeholder(elements, key)?;
+        // Deleting from an empty shape fails
+        if has_text {
+            requests.push(serde_json::json!({
+                "deleteText": {
+                    "objectId": object_id,
+                    "textRange": { "type": "ALL" },
+                }
+            }));
+        }
+        if !text.is_empty() {
+            requests.push(serde_json::json!({
+                "insertText": {
+                    "objectId": object_id,
+                    "text": text,
+                    "insertionIndex": 0,
+                }
+            }));
+        }
+        filled.push(FilledPlaceholder {
+            placeholder: key.clone(),
+            object_id,
+        });
+    }
+
+    let presentation_id = if requests.is_empty() {
+        presentation_id.to_string()
+    } else {
+        let parsed = batch_update_raw(presentation_id, requests)?;
+        parsed["presentationId"].as_str().unwrap_or("").to_string()
+    };
+
+    Ok(PlaceholderTextResult {
+        presentation_id,
+        slide_object_id: slide_object_id.to_string(),
+        filled,
+    })
+}
+
 /// Delete a slide or page element.
 pub fn delete_object(presentation_id: &str, object_id: &str) -> Result<UpdateResult, String> {
     let request = serde_json::json!({
@@ -232,22 +818,165 @@ pub fn delete_object(presentation_id: &str, object_id: &str) -> Result<UpdateRes
     })
 }
 
+/// Slide and element IDs, with groups expanded, for building a duplicate's
+/// ID map.
+const SLIDE_IDS_FIELDS: &str = "slides(objectId,pageElements(objectId,elementGroup))";
+
+/// Collect the object IDs of page elements, including those nested in groups.
+fn collect_element_ids(elements: &[serde_json::Value], ids: &mut Vec<String>) {
+    for el in elements {
+        if let Some(id) = el["objectId"].as_str() {
+            ids.push(id.to_string());
+        }
+        if let Some(children) = el["elementGroup"]["children"].as_array() {
+            collect_element_ids(children, ids);
+        }
+    }
+}
+
+/// Give every original ID a copy ID, keeping caller-chosen ones.
+///
+/// Generated IDs share a per-call prefix so they can't collide with each
+/// other, and stay within the API's 5-50 character limit.
+fn duplicate_id_map(
+    original_ids: &[String],
+    requested: &BTreeMap<String, String>,
+    prefix: &str,
+) -> BTreeMap<String, String> {
+    original_ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let copy = requested
+                .get(id)
+                .cloned()
+                .unwrap_or_else(|| format!("{}_{}", prefix, i));
+            (id.clone(), copy)
+        })
+        .collect()
+}
+
+/// Duplicate a slide and return the IDs of the copy and its elements.
+pub fn duplicate_slide(
+    presentation_id: &str,
+    slide_object_id: &str,
+    object_ids: &BTreeMap<String, String>,
+) -> Result<DuplicateSlideResult, String> {
+    let path = format!(
+        "{}?fields={}",
+        url_encode(presentation_id),
+        url_encode(SLIDE_IDS_FIELDS)
+    );
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    let slide = parsed["slides"]
+        .as_array()
+        .and_then(|slides| {
+            slides
+                .iter()
+                .find(|s| s["objectId"].as_str() == Some(slide_object_id))
+        })
+        .ok_or_else(|| format!("Slide '{}' not found in presentation", slide_object_id))?;
+
+    let mut original_ids = vec![slide_object_id.to_string()];
+    collect_element_ids(
+        slide["pageElements"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default(),
+        &mut original_ids,
+    );
+
+    if let Some(unknown) = object_ids.keys().find(|id| !original_ids.contains(id)) {
+        return Err(format!(
+            "object_ids key '{}' is not the slide or one of its elements",
+            unknown
+        ));
+    }
+
+    let prefix = format!("dup{}", host::now_millis());
+    let id_map = duplicate_id_map(&original_ids, object_ids, &prefix);
+
+    let request = serde_json::json!({
+        "duplicateObject": {
+            "objectId": slide_object_id,
+            "objectIds": id_map,
+        }
+    });
+    let parsed = batch_update_raw(presentation_id, vec![request])?;
+
+    let new_slide_object_id = parsed["replies"][0]["duplicateObject"]["objectId"]
+        .as_str()
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| id_map[slide_object_id].clone());
+
+    Ok(DuplicateSlideResult {
+        presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
+        new_slide_object_id,
+        object_id_map: id_map,
+    })
+}
+
+/// Move slides to a new position.
+pub fn reorder_slides(
+    presentation_id: &str,
+    slide_object_ids: &[String],
+    insertion_index: i64,
+) -> Result<UpdateResult, String> {
+    if slide_object_ids.is_empty() {
+        return Err("slide_object_ids must list at least one slide".to_string());
+    }
+    if insertion_index < 0 {
+        return Err("insertion_index must be 0 or greater".to_string());
+    }
+
+    let request = serde_json::json!({
+        "updateSlidesPosition": {
+            "slideObjectIds": slide_object_ids,
+            "insertionIndex": insertion_index,
+        }
+    });
+
+    let parsed = batch_update_raw(presentation_id, vec![request])?;
+
+    Ok(UpdateResult {
+        presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
+        created_object_id: None,
+    })
+}
+
 /// Insert text into a shape.
 pub fn insert_text(
     presentation_id: &str,
     object_id: &str,
     text: &str,
     insertion_index: i64,
+    link_url: Option<&str>,
+    link_slide_index: Option<i64>,
 ) -> Result<UpdateResult, String> {
-    let request = serde_json::json!({
+    let mut requests = vec![serde_json::json!({
         "insertText": {
             "objectId": object_id,
             "text": text,
             "insertionIndex": insertion_index,
         }
-    });
+    })];
+    if let Some(link) = text_link(link_url, link_slide_index)? {
+        // Indexes count UTF-16 code units
+        let end_index = insertion_index + text.encode_utf16().count() as i64;
+        requests.push(serde_json::json!({
+            "updateTextStyle": {
+                "objectId": object_id,
+                "textRange": text_range(Some(insertion_index), Some(end_index)),
+                "style": { "link": link },
+                "fields": "link",
+            }
+        }));
+    }
 
-    let parsed = batch_update_raw(presentation_id, vec![request])?;
+    let parsed = batch_update_raw(presentation_id, requests)?;
 
     Ok(UpdateResult {
         presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
@@ -324,31 +1053,212 @@ fn pt_to_emu(pt: f64) -> f64 {
     pt * 12700.0
 }
 
-/// Create a shape on a slide.
-pub fn create_shape(
-    presentation_id: &str,
-    slide_object_id: &str,
-    shape_type: &str,
-    x: f64,
-    y: f64,
-    width: f64,
-    height: f64,
-) -> Result<UpdateResult, String> {
-    let request = serde_json::json!({
-        "createShape": {
-            "shapeType": shape_type,
-            "elementProperties": {
-                "pageObjectId": slide_object_id,
-                "size": {
-                    "width": { "magnitude": pt_to_emu(width), "unit": "EMU" },
-                    "height": { "magnitude": pt_to_emu(height), "unit": "EMU" },
-                },
-                "transform": {
-                    "scaleX": 1.0,
-                    "scaleY": 1.0,
-                    "shearX": 0.0,
-                    "shearY": 0.0,
-                    "translateX": pt_to_emu(x),
+/// Points in one unit of a Slides dimension ("EMU" or "PT").
+fn unit_points(unit: Option<&str>) -> f64 {
+    match unit {
+        Some("PT") => 1.0,
+        _ => 1.0 / 12700.0,
+    }
+}
+
+/// Round to hundredths of a point (or degree) for readable output.
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+/// Rotate `(a, b)` clockwise by `theta` radians in page coordinates (y down).
+fn rotate(a: f64, b: f64, theta: f64) -> (f64, f64) {
+    let (sin, cos) = theta.sin_cos();
+    (a * cos - b * sin, a * sin + b * cos)
+}
+
+/// An element's intrinsic size in points, if it has one.
+fn element_size(el: &serde_json::Value) -> Option<(f64, f64)> {
+    let size = el.get("size")?;
+    let dim = |d: &serde_json::Value| {
+        d["magnitude"]
+            .as_f64()
+            .map(|m| m * unit_points(d["unit"].as_str()))
+    };
+    Some((dim(&size["width"])?, dim(&size["height"])?))
+}
+
+/// Decompose an element's affine transform into friendly geometry.
+///
+/// The transform maps the element's intrinsic box onto the page as
+/// `[scaleX shearX; shearY scaleY] * p + translate`. Shear beyond what a
+/// rotation produces is folded into the scale, which is exact for anything
+/// the editor or update_element_transform can create.
+fn element_geometry(el: &serde_json::Value) -> Option<ElementGeometry> {
+    let (size_w, size_h) = element_size(el)?;
+    let t = &el["transform"];
+    let unit = unit_points(t["unit"].as_str());
+    let scale_x = t["scaleX"].as_f64().unwrap_or(1.0);
+    let scale_y = t["scaleY"].as_f64().unwrap_or(1.0);
+    let shear_x = t["shearX"].as_f64().unwrap_or(0.0);
+    let shear_y = t["shearY"].as_f64().unwrap_or(0.0);
+    let translate_x = t["translateX"].as_f64().unwrap_or(0.0) * unit;
+    let translate_y = t["translateY"].as_f64().unwrap_or(0.0) * unit;
+
+    let width = size_w * scale_x.hypot(shear_y);
+    let height = size_h * shear_x.hypot(scale_y);
+    let theta = shear_y.atan2(scale_x);
+
+    // The transform pivots on the element's origin; report the unrotated
+    // box, which shares the rotated element's center
+    let (half_x, half_y) = rotate(width / 2.0, height / 2.0, theta);
+    let center_x = translate_x + half_x;
+    let center_y = translate_y + half_y;
+
+    Some(ElementGeometry {
+        x: round2(center_x - width / 2.0),
+        y: round2(center_y - height / 2.0),
+        width: round2(width),
+        height: round2(height),
+        rotation: round2(theta.to_degrees().rem_euclid(360.0)),
+    })
+}
+
+/// The absolute transform (in EMU) placing an element of intrinsic size
+/// `size` at `geometry`.
+fn transform_for(geometry: &ElementGeometry, size: (f64, f64)) -> serde_json::Value {
+    let theta = geometry.rotation.to_radians();
+    let (sin, cos) = theta.sin_cos();
+    let sx = geometry.width / size.0;
+    let sy = geometry.height / size.1;
+
+    let center_x = geometry.x + geometry.width / 2.0;
+    let center_y = geometry.y + geometry.height / 2.0;
+    let (half_x, half_y) = rotate(geometry.width / 2.0, geometry.height / 2.0, theta);
+
+    serde_json::json!({
+        "scaleX": sx * cos,
+        "scaleY": sy * cos,
+        "shearX": -sy * sin,
+        "shearY": sx * sin,
+        "translateX": pt_to_emu(center_x - half_x),
+        "translateY": pt_to_emu(center_y - half_y),
+        "unit": "EMU",
+    })
+}
+
+/// Find a page element by ID on any slide, including inside groups.
+fn find_element<'a>(
+    elements: &'a [serde_json::Value],
+    object_id: &str,
+) -> Option<&'a serde_json::Value> {
+    elements.iter().find_map(|el| {
+        if el["objectId"].as_str() == Some(object_id) {
+            return Some(el);
+        }
+        el["elementGroup"]["children"]
+            .as_array()
+            .and_then(|children| find_element(children, object_id))
+    })
+}
+
+/// Parameters for update_element_transform. `None` keeps the current value.
+pub struct TransformOptions<'a> {
+    pub presentation_id: &'a str,
+    pub object_id: &'a str,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub rotation: Option<f64>,
+}
+
+/// Move, resize, or rotate a page element, keeping whatever isn't given.
+pub fn update_element_transform(opts: TransformOptions<'_>) -> Result<TransformResult, String> {
+    if opts.width.is_some_and(|w| w <= 0.0) || opts.height.is_some_and(|h| h <= 0.0) {
+        return Err("width and height must be greater than 0".to_string());
+    }
+
+    let fields = "slides(pageElements(objectId,size,transform,elementGroup))";
+    let path = format!(
+        "{}?fields={}",
+        url_encode(opts.presentation_id),
+        url_encode(fields)
+    );
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    let element = parsed["slides"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .find_map(|slide| {
+            find_element(
+                slide["pageElements"]
+                    .as_array()
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+                opts.object_id,
+            )
+        })
+        .ok_or_else(|| format!("Element '{}' not found in presentation", opts.object_id))?;
+
+    let (Some(size), Some(current)) = (element_size(element), element_geometry(element)) else {
+        return Err(format!(
+            "Element '{}' has no size (groups can't be transformed this way)",
+            opts.object_id
+        ));
+    };
+    if size.0 <= 0.0 || size.1 <= 0.0 {
+        return Err(format!("Element '{}' has zero size", opts.object_id));
+    }
+
+    let geometry = ElementGeometry {
+        x: opts.x.unwrap_or(current.x),
+        y: opts.y.unwrap_or(current.y),
+        width: opts.width.unwrap_or(current.width),
+        height: opts.height.unwrap_or(current.height),
+        rotation: opts.rotation.unwrap_or(current.rotation),
+    };
+
+    let request = serde_json::json!({
+        "updatePageElementTransform": {
+            "objectId": opts.object_id,
+            "applyMode": "ABSOLUTE",
+            "transform": transform_for(&geometry, size),
+        }
+    });
+    let parsed = batch_update_raw(opts.presentation_id, vec![request])?;
+
+    Ok(TransformResult {
+        presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
+        object_id: opts.object_id.to_string(),
+        geometry,
+    })
+}
+
+/// Create a shape on a slide.
+pub fn create_shape(
+    presentation_id: &str,
+    slide_object_id: &str,
+    shape_type: &str,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+) -> Result<UpdateResult, String> {
+    let request = serde_json::json!({
+        "createShape": {
+            "shapeType": shape_type,
+            "elementProperties": {
+                "pageObjectId": slide_object_id,
+                "size": {
+                    "width": { "magnitude": pt_to_emu(width), "unit": "EMU" },
+                    "height": { "magnitude": pt_to_emu(height), "unit": "EMU" },
+                },
+                "transform": {
+                    "scaleX": 1.0,
+                    "scaleY": 1.0,
+                    "shearX": 0.0,
+                    "shearY": 0.0,
+                    "translateX": pt_to_emu(x),
                     "translateY": pt_to_emu(y),
                     "unit": "EMU",
                 },
@@ -412,6 +1322,463 @@ pub fn insert_image(
     })
 }
 
+/// Options for create_chart_from_sheets.
+pub struct SheetsChartOptions<'a> {
+    pub presentation_id: &'a str,
+    pub slide_object_id: &'a str,
+    pub spreadsheet_id: &'a str,
+    pub chart_id: i64,
+    pub linking_mode: &'a str,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Embed a chart from a Google Sheets spreadsheet on a slide.
+pub fn create_chart_from_sheets(opts: SheetsChartOptions<'_>) -> Result<UpdateResult, String> {
+    let request = sheets_chart_request(&opts)?;
+    let parsed = batch_update_raw(opts.presentation_id, vec![request])?;
+
+    let created_id = parsed["replies"][0]["createSheetsChart"]["objectId"]
+        .as_str()
+        .map(|s| s.to_string());
+
+    Ok(UpdateResult {
+        presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
+        created_object_id: created_id,
+    })
+}
+
+/// createSheetsChart request, checking the linking mode.
+fn sheets_chart_request(opts: &SheetsChartOptions<'_>) -> Result<serde_json::Value, String> {
+    if !matches!(opts.linking_mode, "LINKED" | "NOT_LINKED_IMAGE") {
+        return Err(format!(
+            "Invalid linking_mode '{}': expected LINKED or NOT_LINKED_IMAGE",
+            opts.linking_mode
+        ));
+    }
+
+    let request = serde_json::json!({
+        "createSheetsChart": {
+            "spreadsheetId": opts.spreadsheet_id,
+            "chartId": opts.chart_id,
+            "linkingMode": opts.linking_mode,
+            "elementProperties": {
+                "pageObjectId": opts.slide_object_id,
+                "size": {
+                    "width": { "magnitude": pt_to_emu(opts.width), "unit": "EMU" },
+                    "height": { "magnitude": pt_to_emu(opts.height), "unit": "EMU" },
+                },
+                "transform": {
+                    "scaleX": 1.0,
+                    "scaleY": 1.0,
+                    "shearX": 0.0,
+                    "shearY": 0.0,
+                    "translateX": pt_to_emu(opts.x),
+                    "translateY": pt_to_emu(opts.y),
+                    "unit": "EMU",
+                },
+            },
+        }
+    });
+
+    Ok(request)
+}
+
+/// Options for insert_video.
+pub struct VideoOptions<'a> {
+    pub presentation_id: &'a str,
+    pub slide_object_id: &'a str,
+    pub video: &'a str,
+    pub source: Option<&'a str>,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub autoplay: Option<bool>,
+    pub mute: Option<bool>,
+    pub start_seconds: Option<u32>,
+    pub end_seconds: Option<u32>,
+}
+
+/// Source ("YOUTUBE" or "DRIVE") and ID of a video URL or bare ID.
+fn video_source(video: &str, source: Option<&str>) -> Result<(String, String), String> {
+    let video = video.trim();
+    let from_url = parse_video_url(video);
+    let source = match (source, &from_url) {
+        (Some(source), _) => source.to_ascii_uppercase(),
+        (None, Some((source, _))) => source.to_string(),
+        (None, None) => "YOUTUBE".to_string(),
+    };
+    if !matches!(source.as_str(), "YOUTUBE" | "DRIVE") {
+        return Err(format!(
+            "Invalid source '{}': expected YOUTUBE or DRIVE",
+            source
+        ));
+    }
+    let id = from_url.map_or_else(|| video.to_string(), |(_, id)| id);
+    if id.is_empty() || id.contains(['/', '?', '&', ' ']) {
+        return Err(format!("No YouTube or Drive video ID found in '{}'", video));
+    }
+    Ok((source, id))
+}
+
+/// Source and ID of a YouTube or Google Drive video URL.
+fn parse_video_url(url: &str) -> Option<(&'static str, String)> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let rest = rest.split('#').next().unwrap_or(rest);
+    let rest = rest.strip_prefix("www.").unwrap_or(rest);
+    let rest = rest.strip_prefix("m.").unwrap_or(rest);
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let (path, query) = path.split_once('?').unwrap_or((path, ""));
+
+    let param = |name: &str| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+            .map(str::to_string)
+    };
+    let segment = |path: &str| {
+        path.split('/')
+            .next()
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+
+    match host {
+        "youtu.be" => segment(path).map(|id| ("YOUTUBE", id)),
+        "youtube.com" | "youtube-nocookie.com" => {
+            let id = match path.split_once('/') {
+                Some(("embed" | "shorts" | "live" | "v", id)) => segment(id),
+                _ if path == "watch" => param("v"),
+                _ => None,
+            };
+            id.map(|id| ("YOUTUBE", id))
+        }
+        "drive.google.com" => {
+            let id = match path.strip_prefix("file/d/") {
+                Some(rest) => segment(rest),
+                None => param("id"),
+            };
+            id.map(|id| ("DRIVE", id))
+        }
+        _ => None,
+    }
+}
+
+/// Embed a YouTube or Drive video on a slide, then apply its playback
+/// settings.
+pub fn insert_video(opts: VideoOptions<'_>) -> Result<UpdateResult, String> {
+    let (source, id) = video_source(opts.video, opts.source)?;
+    if let (Some(start), Some(end)) = (opts.start_seconds, opts.end_seconds) {
+        if end <= start {
+            return Err("end_seconds must be after start_seconds".to_string());
+        }
+    }
+
+    let request = serde_json::json!({
+        "createVideo": {
+            "source": source,
+            "id": id,
+            "elementProperties": {
+                "pageObjectId": opts.slide_object_id,
+                "size": {
+                    "width": { "magnitude": pt_to_emu(opts.width), "unit": "EMU" },
+                    "height": { "magnitude": pt_to_emu(opts.height), "unit": "EMU" },
+                },
+                "transform": {
+                    "scaleX": 1.0,
+                    "scaleY": 1.0,
+                    "shearX": 0.0,
+                    "shearY": 0.0,
+                    "translateX": pt_to_emu(opts.x),
+                    "translateY": pt_to_emu(opts.y),
+                    "unit": "EMU",
+                },
+            },
+        }
+    });
+
+    let parsed = batch_update_raw(opts.presentation_id, vec![request])?;
+
+    let created_id = parsed["replies"][0]["createVideo"]["objectId"]
+        .as_str()
+        .map(|s| s.to_string());
+
+    let mut properties = serde_json::json!({});
+    let mut fields = Vec::new();
+    if let Some(autoplay) = opts.autoplay {
+        properties["autoPlay"] = serde_json::Value::Bool(autoplay);
+        fields.push("autoPlay");
+    }
+    if let Some(mute) = opts.mute {
+        properties["mute"] = serde_json::Value::Bool(mute);
+        fields.push("mute");
+    }
+    if let Some(start) = opts.start_seconds {
+        properties["start"] = serde_json::json!(start);
+        fields.push("start");
+    }
+    if let Some(end) = opts.end_seconds {
+        properties["end"] = serde_json::json!(end);
+        fields.push("end");
+    }
+
+    // Playback settings can only be set once the video's ID is known
+    if let (Some(video_id), false) = (&created_id, fields.is_empty()) {
+        let request = serde_json::json!({
+            "updateVideoProperties": {
+                "objectId": video_id,
+                "videoProperties": properties,
+                "fields": fields.join(","),
+            }
+        });
+        batch_update_raw(opts.presentation_id, vec![request])?;
+    }
+
+    Ok(UpdateResult {
+        presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
+        created_object_id: created_id,
+    })
+}
+
+/// One end of a line: a point, a shape to connect to, or both.
+pub struct LineEnd<'a> {
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub object_id: Option<&'a str>,
+    pub site_index: Option<i64>,
+}
+
+impl LineEnd<'_> {
+    /// The end's point, if it was given.
+    fn point(&self, name: &str) -> Result<Option<(f64, f64)>, String> {
+        match (self.x, self.y) {
+            (Some(x), Some(y)) => Ok(Some((x, y))),
+            (None, None) => Ok(None),
+            _ => Err(format!("{}_x and {}_y must be given together", name, name)),
+        }
+    }
+}
+
+/// Options for create_line.
+pub struct LineOptions<'a> {
+    pub presentation_id: &'a str,
+    pub slide_object_id: &'a str,
+    pub category: &'a str,
+    pub start: LineEnd<'a>,
+    pub end: LineEnd<'a>,
+    pub color: Option<&'a str>,
+    pub weight: Option<f64>,
+    pub dash_style: Option<&'a str>,
+    pub start_arrow: Option<&'a str>,
+    pub end_arrow: Option<&'a str>,
+}
+
+/// Middle of the side of `shape` that faces `toward`, with its connection
+/// site (0 top, 1 left, 2 bottom, 3 right, as on rectangles and text boxes).
+/// Rotation is ignored.
+fn facing_side(shape: &ElementGeometry, toward: (f64, f64)) -> (i64, (f64, f64)) {
+    let center_x = shape.x + shape.width / 2.0;
+    let center_y = shape.y + shape.height / 2.0;
+    // Compare offsets relative to the box so wide shapes favor their long sides
+    let dx = (toward.0 - center_x) / shape.width.max(1.0);
+    let dy = (toward.1 - center_y) / shape.height.max(1.0);
+    if dx.abs() >= dy.abs() {
+        if dx >= 0.0 {
+            (3, (shape.x + shape.width, center_y))
+        } else {
+            (1, (shape.x, center_y))
+        }
+    } else if dy >= 0.0 {
+        (2, (center_x, shape.y + shape.height))
+    } else {
+        (0, (center_x, shape.y))
+    }
+}
+
+/// Element properties for a line from `start` to `end`. The line runs from
+/// the top-left to the bottom-right of its box, so the box is flipped for
+/// lines running left or up.
+fn line_element_properties(
+    slide_object_id: &str,
+    start: (f64, f64),
+    end: (f64, f64),
+) -> serde_json::Value {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let flip = |d: f64| if d < 0.0 { -1.0 } else { 1.0 };
+    serde_json::json!({
+        "pageObjectId": slide_object_id,
+        "size": {
+            "width": { "magnitude": pt_to_emu(dx.abs()), "unit": "EMU" },
+            "height": { "magnitude": pt_to_emu(dy.abs()), "unit": "EMU" },
+        },
+        "transform": {
+            "scaleX": flip(dx),
+            "scaleY": flip(dy),
+            "shearX": 0.0,
+            "shearY": 0.0,
+            "translateX": pt_to_emu(start.0),
+            "translateY": pt_to_emu(start.1),
+            "unit": "EMU",
+        },
+    })
+}
+
+/// Geometry of an element on a slide, for attaching a line to it.
+fn slide_element_geometry(
+    presentation_id: &str,
+    slide_object_id: &str,
+    object_id: &str,
+) -> Result<ElementGeometry, String> {
+    let fields = "slides(objectId,pageElements(objectId,size,transform,elementGroup))";
+    let path = format!(
+        "{}?fields={}",
+        url_encode(presentation_id),
+        url_encode(fields)
+    );
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    let slide = parsed["slides"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .find(|slide| slide["objectId"].as_str() == Some(slide_object_id))
+        .ok_or_else(|| format!("Slide '{}' not found in presentation", slide_object_id))?;
+    let element = find_element(
+        slide["pageElements"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default(),
+        object_id,
+    )
+    .ok_or_else(|| format!("Element '{}' not found on slide", object_id))?;
+    element_geometry(element).ok_or_else(|| format!("Element '{}' has no size", object_id))
+}
+
+/// Draw a line or connector. An end attached to a shape without a point
+/// starts from the middle of the shape's side facing the other end.
+pub fn create_line(opts: LineOptions<'_>) -> Result<UpdateResult, String> {
+    if !matches!(opts.category, "STRAIGHT" | "BENT" | "CURVED") {
+        return Err(format!(
+            "Invalid category '{}': expected STRAIGHT, BENT, or CURVED",
+            opts.category
+        ));
+    }
+    let start_point = opts.start.point("start")?;
+    let end_point = opts.end.point("end")?;
+    let shape = |end: &LineEnd<'_>| {
+        end.object_id
+            .map(|id| slide_element_geometry(opts.presentation_id, opts.slide_object_id, id))
+            .transpose()
+    };
+    let start_shape = shape(&opts.start)?;
+    let end_shape = shape(&opts.end)?;
+
+    // Where each end aims for: its point, or the center of its shape
+    let anchor = |point: Option<(f64, f64)>, shape: Option<&ElementGeometry>, name: &str| {
+        point
+            .or_else(|| shape.map(|s| (s.x + s.width / 2.0, s.y + s.height / 2.0)))
+            .ok_or_else(|| format!("{0}_x/{0}_y or {0}_object_id is required", name))
+    };
+    let start_anchor = anchor(start_point, start_shape.as_ref(), "start")?;
+    let end_anchor = anchor(end_point, end_shape.as_ref(), "end")?;
+
+    let resolve = |point: Option<(f64, f64)>,
+                   shape: Option<&ElementGeometry>,
+                   site_index: Option<i64>,
+                   toward: (f64, f64)| {
+        match shape.map(|s| facing_side(s, toward)) {
+            Some((site, side)) => (point.unwrap_or(side), Some(site_index.unwrap_or(site))),
+            None => (point.unwrap_or(toward), None),
+        }
+    };
+    let (start, start_site) = resolve(
+        start_point,
+        start_shape.as_ref(),
+        opts.start.site_index,
+        end_anchor,
+    );
+    let (end, end_site) = resolve(
+        end_point,
+        end_shape.as_ref(),
+        opts.end.site_index,
+        start_anchor,
+    );
+
+    let request = serde_json::json!({
+        "createLine": {
+            "category": opts.category,
+            "elementProperties": line_element_properties(opts.slide_object_id, start, end),
+        }
+    });
+
+    let parsed = batch_update_raw(opts.presentation_id, vec![request])?;
+
+    let created_id = parsed["replies"][0]["createLine"]["objectId"]
+        .as_str()
+        .map(|s| s.to_string());
+
+    let mut properties = serde_json::json!({});
+    let mut fields = Vec::new();
+    if let Some(color) = opts.color {
+        let color = parse_hex_color(color)
+            .ok_or_else(|| format!("Invalid color '{}', expected hex like #FF0000", color))?;
+        properties["lineFill"] =
+            serde_json::json!({ "solidFill": { "color": color["opaqueColor"] } });
+        fields.push("lineFill.solidFill.color");
+    }
+    if let Some(weight) = opts.weight {
+        properties["weight"] = serde_json::json!({ "magnitude": weight, "unit": "PT" });
+        fields.push("weight");
+    }
+    if let Some(dash_style) = opts.dash_style {
+        properties["dashStyle"] = serde_json::Value::String(dash_style.to_string());
+        fields.push("dashStyle");
+    }
+    if let Some(arrow) = opts.start_arrow {
+        properties["startArrow"] = serde_json::Value::String(arrow.to_string());
+        fields.push("startArrow");
+    }
+    if let Some(arrow) = opts.end_arrow {
+        properties["endArrow"] = serde_json::Value::String(arrow.to_string());
+        fields.push("endArrow");
+    }
+    for (key, object_id, site) in [
+        ("startConnection", opts.start.object_id, start_site),
+        ("endConnection", opts.end.object_id, end_site),
+    ] {
+        if let (Some(object_id), Some(site)) = (object_id, site) {
+            properties[key] = serde_json::json!({
+                "connectedObjectId": object_id,
+                "connectionSiteIndex": site,
+            });
+            fields.push(key);
+        }
+    }
+
+    // Style and connections can only be set once the line's ID is known
+    if let (Some(line_id), false) = (&created_id, fields.is_empty()) {
+        let request = serde_json::json!({
+            "updateLineProperties": {
+                "objectId": line_id,
+                "lineProperties": properties,
+                "fields": fields.join(","),
+            }
+        });
+        batch_update_raw(opts.presentation_id, vec![request])?;
+    }
+
+    Ok(UpdateResult {
+        presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
+        created_object_id: created_id,
+    })
+}
+
 /// Parse a hex color like "#FF0000" into Slides API color format.
 fn parse_hex_color(hex: &str) -> Option<serde_json::Value> {
     let hex = hex.strip_prefix('#').unwrap_or(hex);
@@ -438,16 +1805,41 @@ pub struct FormatTextOptions<'a> {
     pub object_id: &'a str,
     pub start_index: Option<i64>,
     pub end_index: Option<i64>,
+    pub style: TextStyleOptions<'a>,
+}
+
+/// Text style changes; anything left as `None` is kept.
+pub struct TextStyleOptions<'a> {
     pub bold: Option<bool>,
     pub italic: Option<bool>,
     pub underline: Option<bool>,
     pub font_size: Option<f64>,
     pub font_family: Option<&'a str>,
     pub foreground_color: Option<&'a str>,
+    pub link_url: Option<&'a str>,
+    pub link_slide_index: Option<i64>,
 }
 
-/// Format text in a shape.
-pub fn format_text(opts: FormatTextOptions<'_>) -> Result<UpdateResult, String> {
+/// A text style `link` to a URL or to a slide by position, if either is set.
+fn text_link(
+    url: Option<&str>,
+    slide_index: Option<i64>,
+) -> Result<Option<serde_json::Value>, String> {
+    match (url, slide_index) {
+        (Some(_), Some(_)) => Err("Pass either link_url or link_slide_index, not both".to_string()),
+        (Some(url), None) if url.trim().is_empty() => Err("link_url is empty".to_string()),
+        (Some(url), None) => Ok(Some(serde_json::json!({ "url": url.trim() }))),
+        (None, Some(index)) if index < 0 => Err(format!(
+            "Invalid link_slide_index {}: must be 0 or more",
+            index
+        )),
+        (None, Some(index)) => Ok(Some(serde_json::json!({ "slideIndex": index }))),
+        (None, None) => Ok(None),
+    }
+}
+
+/// The `style` and `fields` of an updateTextStyle request.
+fn text_style_update(opts: &TextStyleOptions<'_>) -> Result<(serde_json::Value, String), String> {
     let mut style = serde_json::json!({});
     let mut fields = Vec::new();
 
@@ -477,30 +1869,27 @@ pub fn format_text(opts: FormatTextOptions<'_>) -> Result<UpdateResult, String>
             fields.push("foregroundColor");
         }
     }
+    if let Some(link) = text_link(opts.link_url, opts.link_slide_index)? {
+        style["link"] = link;
+        fields.push("link");
+    }
 
     if fields.is_empty() {
         return Err("No formatting options specified".to_string());
     }
+    Ok((style, fields.join(",")))
+}
 
-    let text_range = match (opts.start_index, opts.end_index) {
-        (Some(start), Some(end)) => serde_json::json!({
-            "type": "FIXED_RANGE",
-            "startIndex": start,
-            "endIndex": end,
-        }),
-        (Some(start), None) => serde_json::json!({
-            "type": "FROM_START_INDEX",
-            "startIndex": start,
-        }),
-        _ => serde_json::json!({ "type": "ALL" }),
-    };
+/// Format text in a shape.
+pub fn format_text(opts: FormatTextOptions<'_>) -> Result<UpdateResult, String> {
+    let (style, fields) = text_style_update(&opts.style)?;
 
     let request = serde_json::json!({
         "updateTextStyle": {
             "objectId": opts.object_id,
-            "textRange": text_range,
+            "textRange": text_range(opts.start_index, opts.end_index),
             "style": style,
-            "fields": fields.join(","),
+            "fields": fields,
         }
     });
 
@@ -512,15 +1901,104 @@ pub fn format_text(opts: FormatTextOptions<'_>) -> Result<UpdateResult, String>
     })
 }
 
-/// Format paragraph alignment in a shape.
-pub fn format_paragraph(
-    presentation_id: &str,
-    object_id: &str,
-    alignment: &str,
-    start_index: Option<i64>,
-    end_index: Option<i64>,
-) -> Result<UpdateResult, String> {
-    let text_range = match (start_index, end_index) {
+/// Parameters for style_matching_text.
+pub struct StyleMatchingTextOptions<'a> {
+    pub presentation_id: &'a str,
+    pub pattern: &'a str,
+    /// Treat `pattern` as a regular expression rather than literal text.
+    pub regex: bool,
+    pub match_case: bool,
+    pub slide_object_id: Option<&'a str>,
+    pub style: TextStyleOptions<'a>,
+}
+
+/// Style every occurrence of a string or regex across the deck (or one
+/// slide), in shapes and table cells alike.
+pub fn style_matching_text(
+    opts: StyleMatchingTextOptions<'_>,
+) -> Result<StyleMatchingTextResult, String> {
+    if opts.pattern.is_empty() {
+        return Err("pattern is empty".to_string());
+    }
+    let (style, fields) = text_style_update(&opts.style)?;
+    let pattern = if opts.regex {
+        opts.pattern.to_string()
+    } else {
+        regex_lite::escape(opts.pattern)
+    };
+    let re = regex_lite::RegexBuilder::new(&pattern)
+        .case_insensitive(!opts.match_case)
+        .build()
+        .map_err(|e| format!("Invalid pattern: {}", e))?;
+
+    let text = extract_text(opts.presentation_id, opts.slide_object_id)?;
+    let matches = matching_text(&text.slides, &re);
+
+    if !matches.is_empty() {
+        let requests = matches
+            .iter()
+            .map(|m| {
+                let mut request = serde_json::json!({
+                    "objectId": m.object_id,
+                    "textRange": text_range(Some(m.start_index), Some(m.end_index)),
+                    "style": style,
+                    "fields": fields,
+                });
+                if let (Some(row), Some(column)) = (m.row_index, m.column_index) {
+                    request["cellLocation"] =
+                        serde_json::json!({ "rowIndex": row, "columnIndex": column });
+                }
+                serde_json::json!({ "updateTextStyle": request })
+            })
+            .collect();
+        batch_update_raw(opts.presentation_id, requests)?;
+    }
+
+    Ok(StyleMatchingTextResult {
+        presentation_id: text.presentation_id,
+        match_count: matches.len(),
+        matches,
+    })
+}
+
+/// Non-empty matches of `re` in each shape's and table cell's text.
+fn matching_text(slides: &[SlideText], re: &regex_lite::Regex) -> Vec<TextMatch> {
+    let mut matches = Vec::new();
+    for slide in slides {
+        for shape in &slide.shapes {
+            for m in re.find_iter(&shape.text).filter(|m| !m.is_empty()) {
+                matches.push(TextMatch {
+                    slide_object_id: slide.slide_object_id.clone(),
+                    slide_index: slide.slide_index,
+                    object_id: shape.object_id.clone(),
+                    row_index: shape.row_index,
+                    column_index: shape.column_index,
+                    start_index: text_index(&shape.runs, m.start()),
+                    end_index: text_index(&shape.runs, m.end()),
+                    text: m.as_str().to_string(),
+                });
+            }
+        }
+    }
+    matches
+}
+
+/// The API index of byte offset `offset` into the concatenated `runs`.
+/// API indexes count UTF-16 code units.
+fn text_index(runs: &[TextRunInfo], mut offset: usize) -> i64 {
+    for run in runs {
+        if offset < run.content.len() {
+            let units = run.content[..offset].encode_utf16().count() as i64;
+            return run.start_index + units;
+        }
+        offset -= run.content.len();
+    }
+    runs.last().map_or(0, |run| run.end_index)
+}
+
+/// A text range covering `start..end`, `start..`, or all text.
+fn text_range(start_index: Option<i64>, end_index: Option<i64>) -> serde_json::Value {
+    match (start_index, end_index) {
         (Some(start), Some(end)) => serde_json::json!({
             "type": "FIXED_RANGE",
             "startIndex": start,
@@ -530,13 +2008,27 @@ pub fn format_paragraph(
             "type": "FROM_START_INDEX",
             "startIndex": start,
         }),
-        _ => serde_json::json!({ "type": "ALL" }),
-    };
+        (None, Some(end)) => serde_json::json!({
+            "type": "FIXED_RANGE",
+            "startIndex": 0,
+            "endIndex": end,
+        }),
+        (None, None) => serde_json::json!({ "type": "ALL" }),
+    }
+}
 
+/// Format paragraph alignment in a shape.
+pub fn format_paragraph(
+    presentation_id: &str,
+    object_id: &str,
+    alignment: &str,
+    start_index: Option<i64>,
+    end_index: Option<i64>,
+) -> Result<UpdateResult, String> {
     let request = serde_json::json!({
         "updateParagraphStyle": {
             "objectId": object_id,
-            "textRange": text_range,
+            "textRange": text_range(start_index, end_index),
             "style": { "alignment": alignment },
             "fields": "alignment",
         }
@@ -550,6 +2042,55 @@ pub fn format_paragraph(
     })
 }
 
+/// Turn paragraphs in a shape into a bulleted or numbered list.
+///
+/// Leading tabs on each paragraph set its nesting level; the API removes
+/// them, so character indexes after the range may shift.
+pub fn create_bullets(
+    presentation_id: &str,
+    object_id: &str,
+    preset: BulletPreset,
+    start_index: Option<i64>,
+    end_index: Option<i64>,
+) -> Result<UpdateResult, String> {
+    let request = serde_json::json!({
+        "createParagraphBullets": {
+            "objectId": object_id,
+            "textRange": text_range(start_index, end_index),
+            "bulletPreset": preset.as_str(),
+        }
+    });
+
+    let parsed = batch_update_raw(presentation_id, vec![request])?;
+
+    Ok(UpdateResult {
+        presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
+        created_object_id: None,
+    })
+}
+
+/// Remove bullets and numbering from paragraphs in a shape.
+pub fn delete_bullets(
+    presentation_id: &str,
+    object_id: &str,
+    start_index: Option<i64>,
+    end_index: Option<i64>,
+) -> Result<UpdateResult, String> {
+    let request = serde_json::json!({
+        "deleteParagraphBullets": {
+            "objectId": object_id,
+            "textRange": text_range(start_index, end_index),
+        }
+    });
+
+    let parsed = batch_update_raw(presentation_id, vec![request])?;
+
+    Ok(UpdateResult {
+        presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
+        created_object_id: None,
+    })
+}
+
 /// Replace all shapes containing text with an image.
 pub fn replace_shapes_with_image(
     presentation_id: &str,
@@ -580,6 +2121,549 @@ pub fn replace_shapes_with_image(
     })
 }
 
+/// Just what speaker notes lookups read.
+const NOTES_FIELDS: &str = "presentationId,slides(objectId,slideProperties/notesPage(\
+    notesProperties/speakerNotesObjectId,\
+    pageElements(objectId,shape/text/textElements/textRun/content)))";
+
+/// Speaker notes of every slide, in order.
+fn read_speaker_notes(presentation_id: &str) -> Result<(String, Vec<SlideNotes>), String> {
+    let path = format!(
+        "{}?fields={}",
+        url_encode(presentation_id),
+        url_encode(NOTES_FIELDS)
+    );
+
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    let id = parsed["presentationId"].as_str().unwrap_or("").to_string();
+    Ok((id, slide_notes(&parsed)))
+}
+
+/// Speaker notes of every slide in a presentation fetched with
+/// `NOTES_FIELDS`.
+fn slide_notes(presentation: &serde_json::Value) -> Vec<SlideNotes> {
+    presentation["slides"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .map(|(slide_index, slide)| {
+            let notes_page = &slide["slideProperties"]["notesPage"];
+            let notes_object_id = notes_page["notesProperties"]["speakerNotesObjectId"]
+                .as_str()
+                .unwrap_or("")
+                .to_string();
+            // The notes shape only exists once notes have been written
+            let notes = notes_page["pageElements"]
+                .as_array()
+                .and_then(|elements| {
+                    elements
+                        .iter()
+                        .find(|el| el["objectId"].as_str() == Some(notes_object_id.as_str()))
+                })
+                .and_then(|el| extract_text_from_shape(&el["shape"]))
+                .unwrap_or_default();
+
+            SlideNotes {
+                slide_object_id: slide["objectId"].as_str().unwrap_or("").to_string(),
+                slide_index,
+                notes_object_id,
+                notes: notes.trim_end_matches('\n').to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Get the speaker notes of one slide, or of every slide.
+pub fn get_speaker_notes(
+    presentation_id: &str,
+    slide_object_id: Option<&str>,
+) -> Result<SpeakerNotesResult, String> {
+    let (id, mut slides) = read_speaker_notes(presentation_id)?;
+
+    if let Some(slide_id) = slide_object_id {
+        slides.retain(|s| s.slide_object_id == slide_id);
+        if slides.is_empty() {
+            return Err(format!("Slide '{}' not found in presentation", slide_id));
+        }
+    }
+
+    Ok(SpeakerNotesResult {
+        presentation_id: id,
+        slides,
+    })
+}
+
+/// Replace the speaker notes of a slide.
+pub fn set_speaker_notes(
+    presentation_id: &str,
+    slide_object_id: &str,
+    text: &str,
+) -> Result<SpeakerNotesResult, String> {
+    let (_, slides) = read_speaker_notes(presentation_id)?;
+    let slide = slides
+        .into_iter()
+        .find(|s| s.slide_object_id == slide_object_id)
+        .ok_or_else(|| format!("Slide '{}' not found in presentation", slide_object_id))?;
+    if slide.notes_object_id.is_empty() {
+        return Err(format!("Slide '{}' has no notes page", slide_object_id));
+    }
+
+    let requests = replace_notes_requests(&slide, text);
+    let presentation_id = if requests.is_empty() {
+        presentation_id.to_string()
+    } else {
+        let parsed = batch_update_raw(presentation_id, requests)?;
+        parsed["presentationId"].as_str().unwrap_or("").to_string()
+    };
+
+    Ok(SpeakerNotesResult {
+        presentation_id,
+        slides: vec![SlideNotes {
+            notes: text.to_string(),
+            ..slide
+        }],
+    })
+}
+
+/// Requests replacing a slide's notes with `text`.
+fn replace_notes_requests(slide: &SlideNotes, text: &str) -> Vec<serde_json::Value> {
+    // Deleting from an empty shape fails, and inserting creates the shape
+    let mut requests = Vec::new();
+    if !slide.notes.is_empty() {
+        requests.push(serde_json::json!({
+            "deleteText": {
+                "objectId": slide.notes_object_id,
+                "textRange": { "type": "ALL" },
+            }
+        }));
+    }
+    if !text.is_empty() {
+        requests.push(serde_json::json!({
+            "insertText": {
+                "objectId": slide.notes_object_id,
+                "text": text,
+                "insertionIndex": 0,
+            }
+        }));
+    }
+    requests
+}
+
+/// Text elements of every shape and table cell, with their indexes and styles.
+const TEXT_FIELDS: &str = "presentationId,slides(objectId,pageElements(objectId,\
+    shape(placeholder/type,text/textElements),\
+    table/tableRows/tableCells(location,text/textElements),elementGroup))";
+
+/// Text runs of every shape and table cell, per slide, with the character
+/// indexes delete_text and format_text take.
+pub fn extract_text(
+    presentation_id: &str,
+    slide_object_id: Option<&str>,
+) -> Result<ExtractTextResult, String> {
+    let path = format!(
+        "{}?fields={}",
+        url_encode(presentation_id),
+        url_encode(TEXT_FIELDS)
+    );
+
+    let response = api_call("GET", &path, None)?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    let mut slides: Vec<SlideText> = parsed["slides"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .map(|(slide_index, slide)| {
+            let mut shapes = Vec::new();
+            for el in slide["pageElements"].as_array().into_iter().flatten() {
+                collect_shape_text(el, &mut shapes);
+            }
+            SlideText {
+                slide_object_id: slide["objectId"].as_str().unwrap_or("").to_string(),
+                slide_index,
+                shapes,
+            }
+        })
+        .collect();
+
+    if let Some(slide_id) = slide_object_id {
+        slides.retain(|s| s.slide_object_id == slide_id);
+        if slides.is_empty() {
+            return Err(format!("Slide '{}' not found in presentation", slide_id));
+        }
+    }
+
+    Ok(ExtractTextResult {
+        presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
+        slides,
+    })
+}
+
+/// Text of a page element (a shape, each cell of a table, or everything in a
+/// group), appended to `out`.
+fn collect_shape_text(el: &serde_json::Value, out: &mut Vec<ShapeText>) {
+    let object_id = el["objectId"].as_str().unwrap_or("");
+
+    if let Some(elements) = el["shape"]["text"]["textElements"].as_array() {
+        let mut text = shape_text(object_id, elements);
+        text.placeholder_type = el["shape"]["placeholder"]["type"]
+            .as_str()
+            .map(String::from);
+        out.push(text);
+    } else if let Some(rows) = el["table"]["tableRows"].as_array() {
+        for cell in rows
+            .iter()
+            .filter_map(|row| row["tableCells"].as_array())
+            .flatten()
+        {
+            let Some(elements) = cell["text"]["textElements"].as_array() else {
+                continue;
+            };
+            let mut text = shape_text(object_id, elements);
+            text.row_index = Some(cell["location"]["rowIndex"].as_i64().unwrap_or(0));
+            text.column_index = Some(cell["location"]["columnIndex"].as_i64().unwrap_or(0));
+            out.push(text);
+        }
+    } else if let Some(children) = el["elementGroup"]["children"].as_array() {
+        for child in children {
+            collect_shape_text(child, out);
+        }
+    }
+}
+
+/// Runs and paragraphs of a shape's (or table cell's) `textElements`.
+fn shape_text(object_id: &str, elements: &[serde_json::Value]) -> ShapeText {
+    let mut text = String::new();
+    let mut runs = Vec::new();
+    let mut paragraphs = Vec::new();
+
+    for el in elements {
+        // The API leaves out a start index of 0
+        let start_index = el["startIndex"].as_i64().unwrap_or(0);
+        let end_index = el["endIndex"].as_i64().unwrap_or(start_index);
+
+        if let Some(marker) = el.get("paragraphMarker") {
+            paragraphs.push(ParagraphRange {
+                start_index,
+                end_index,
+                alignment: marker["style"]["alignment"].as_str().map(String::from),
+                bullet_nesting_level: marker
+                    .get("bullet")
+                    .map(|bullet| bullet["nestingLevel"].as_i64().unwrap_or(0)),
+            });
+            continue;
+        }
+
+        let (run, auto_text) = match (el.get("textRun"), el.get("autoText")) {
+            (Some(run), _) => (run, None),
+            (None, Some(auto)) => (auto, auto["type"].as_str().map(String::from)),
+            (None, None) => continue,
+        };
+        let content = run["content"].as_str().unwrap_or("").to_string();
+        text.push_str(&content);
+        runs.push(TextRunInfo {
+            start_index,
+            end_index,
+            content,
+            auto_text,
+            style: text_style(&run["style"]),
+        });
+    }
+
+    ShapeText {
+        object_id: object_id.to_string(),
+        placeholder_type: None,
+        row_index: None,
+        column_index: None,
+        text,
+        paragraphs,
+        runs,
+    }
+}
+
+/// The parts of a Slides `TextStyle` that format_text can set, plus
+/// strikethrough.
+fn text_style(style: &serde_json::Value) -> TextStyleInfo {
+    let link = &style["link"];
+    TextStyleInfo {
+        bold: style["bold"].as_bool(),
+        italic: style["italic"].as_bool(),
+        underline: style["underline"].as_bool(),
+        strikethrough: style["strikethrough"].as_bool(),
+        font_family: style["fontFamily"].as_str().map(String::from),
+        font_size: style["fontSize"]["magnitude"].as_f64(),
+        foreground_color: color_name(&style["foregroundColor"]),
+        link_url: link["url"].as_str().map(String::from),
+        link_slide_index: link["slideIndex"].as_i64(),
+    }
+}
+
+/// "#RRGGBB" for an RGB color, or the theme color's name (e.g. "ACCENT1").
+fn color_name(color: &serde_json::Value) -> Option<String> {
+    let opaque = &color["opaqueColor"];
+    if let Some(theme) = opaque["themeColor"].as_str() {
+        return Some(theme.to_string());
+    }
+    let rgb = opaque.get("rgbColor")?;
+    // Channels at 0 are left out
+    let channel = |name: &str| (rgb[name].as_f64().unwrap_or(0.0) * 255.0).round() as u8;
+    Some(format!(
+        "#{:02X}{:02X}{:02X}",
+        channel("red"),
+        channel("green"),
+        channel("blue")
+    ))
+}
+
+/// Parameters for table creation.
+pub struct CreateTableOptions<'a> {
+    pub presentation_id: &'a str,
+    pub slide_object_id: &'a str,
+    pub rows: i64,
+    pub columns: i64,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub data: &'a [Vec<String>],
+}
+
+/// Create a table on a slide, then fill in any cell text.
+pub fn create_table(opts: CreateTableOptions<'_>) -> Result<UpdateResult, String> {
+    let request = create_table_request(&opts)?;
+    let parsed = batch_update_raw(opts.presentation_id, vec![request])?;
+
+    let created_id = parsed["replies"][0]["createTable"]["objectId"]
+        .as_str()
+        .map(|s| s.to_string());
+
+    // Cells can only be addressed once the table's ID is known
+    if let Some(ref table_id) = created_id {
+        let requests = table_text_requests(table_id, opts.data);
+        if !requests.is_empty() {
+            batch_update_raw(opts.presentation_id, requests)?;
+        }
+    }
+
+    Ok(UpdateResult {
+        presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
+        created_object_id: created_id,
+    })
+}
+
+/// createTable request, checking the data fits the table.
+fn create_table_request(opts: &CreateTableOptions<'_>) -> Result<serde_json::Value, String> {
+    if opts.rows < 1 || opts.columns < 1 {
+        return Err("A table needs at least one row and one column".to_string());
+    }
+    if opts.data.len() as i64 > opts.rows
+        || opts.data.iter().any(|row| row.len() as i64 > opts.columns)
+    {
+        return Err(format!(
+            "data doesn't fit in a {}x{} table",
+            opts.rows, opts.columns
+        ));
+    }
+
+    let mut element_properties = serde_json::json!({ "pageObjectId": opts.slide_object_id });
+    if let (Some(width), Some(height)) = (opts.width, opts.height) {
+        element_properties["size"] = serde_json::json!({
+            "width": { "magnitude": pt_to_emu(width), "unit": "EMU" },
+            "height": { "magnitude": pt_to_emu(height), "unit": "EMU" },
+        });
+    }
+    if let (Some(x), Some(y)) = (opts.x, opts.y) {
+        element_properties["transform"] = serde_json::json!({
+            "scaleX": 1.0,
+            "scaleY": 1.0,
+            "shearX": 0.0,
+            "shearY": 0.0,
+            "translateX": pt_to_emu(x),
+            "translateY": pt_to_emu(y),
+            "unit": "EMU",
+        });
+    }
+
+    let request = serde_json::json!({
+        "createTable": {
+            "elementProperties": element_properties,
+            "rows": opts.rows,
+            "columns": opts.columns,
+        }
+    });
+
+    Ok(request)
+}
+
+/// insertText requests for the non-empty cells of `data`.
+fn table_text_requests(table_object_id: &str, data: &[Vec<String>]) -> Vec<serde_json::Value> {
+    let mut requests = Vec::new();
+    for (row, cells) in data.iter().enumerate() {
+        for (column, text) in cells.iter().enumerate() {
+            if !text.is_empty() {
+                requests.push(insert_cell_text_request(
+                    table_object_id,
+                    row as i64,
+                    column as i64,
+                    text,
+                    0,
+                ));
+            }
+        }
+    }
+    requests
+}
+
+/// insertText request for a table cell.
+fn insert_cell_text_request(
+    table_object_id: &str,
+    row_index: i64,
+    column_index: i64,
+    text: &str,
+    insertion_index: i64,
+) -> serde_json::Value {
+    serde_json::json!({
+        "insertText": {
+            "objectId": table_object_id,
+            "cellLocation": {
+                "rowIndex": row_index,
+                "columnIndex": column_index,
+            },
+            "text": text,
+            "insertionIndex": insertion_index,
+        }
+    })
+}
+
+/// Insert text into a table cell.
+pub fn insert_table_text(
+    presentation_id: &str,
+    table_object_id: &str,
+    row_index: i64,
+    column_index: i64,
+    text: &str,
+    insertion_index: i64,
+) -> Result<UpdateResult, String> {
+    let request = insert_cell_text_request(
+        table_object_id,
+        row_index,
+        column_index,
+        text,
+        insertion_index,
+    );
+
+    let parsed = batch_update_raw(presentation_id, vec![request])?;
+
+    Ok(UpdateResult {
+        presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
+        created_object_id: None,
+    })
+}
+
+/// Parameters for table cell styling.
+pub struct TableCellStyleOptions<'a> {
+    pub presentation_id: &'a str,
+    pub table_object_id: &'a str,
+    pub row_index: i64,
+    pub column_index: i64,
+    pub row_span: i64,
+    pub column_span: i64,
+    pub background_color: Option<&'a str>,
+    pub border_color: Option<&'a str>,
+    pub border_weight: Option<f64>,
+    pub border_dash_style: Option<&'a str>,
+    pub border_position: &'a str,
+}
+
+/// Set the background fill and borders of a range of table cells.
+pub fn style_table_cell(opts: TableCellStyleOptions<'_>) -> Result<UpdateResult, String> {
+    let requests = table_cell_style_requests(&opts)?;
+    let parsed = batch_update_raw(opts.presentation_id, requests)?;
+
+    Ok(UpdateResult {
+        presentation_id: parsed["presentationId"].as_str().unwrap_or("").to_string(),
+        created_object_id: None,
+    })
+}
+
+/// Fill and border requests for a range of table cells.
+fn table_cell_style_requests(
+    opts: &TableCellStyleOptions<'_>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let table_range = serde_json::json!({
+        "location": {
+            "rowIndex": opts.row_index,
+            "columnIndex": opts.column_index,
+        },
+        "rowSpan": opts.row_span,
+        "columnSpan": opts.column_span,
+    });
+    let color = |hex: &str| {
+        parse_hex_color(hex)
+            .map(|c| c["opaqueColor"].clone())
+            .ok_or_else(|| format!("Invalid color '{}', expected hex like #FF0000", hex))
+    };
+
+    let mut requests = Vec::new();
+
+    if let Some(background) = opts.background_color {
+        requests.push(serde_json::json!({
+            "updateTableCellProperties": {
+                "objectId": opts.table_object_id,
+                "tableRange": table_range,
+                "tableCellProperties": {
+                    "tableCellBackgroundFill": {
+                        "solidFill": { "color": color(background)? }
+                    }
+                },
+                "fields": "tableCellBackgroundFill.solidFill.color",
+            }
+        }));
+    }
+
+    let mut border = serde_json::json!({});
+    let mut fields = Vec::new();
+    if let Some(border_color) = opts.border_color {
+        border["tableBorderFill"] = serde_json::json!({
+            "solidFill": { "color": color(border_color)? }
+        });
+        fields.push("tableBorderFill.solidFill.color");
+    }
+    if let Some(weight) = opts.border_weight {
+        border["weight"] = serde_json::json!({ "magnitude": weight, "unit": "PT" });
+        fields.push("weight");
+    }
+    if let Some(dash_style) = opts.border_dash_style {
+        border["dashStyle"] = serde_json::Value::String(dash_style.to_string());
+        fields.push("dashStyle");
+    }
+    if !fields.is_empty() {
+        requests.push(serde_json::json!({
+            "updateTableBorderProperties": {
+                "objectId": opts.table_object_id,
+                "tableRange": table_range,
+                "borderPosition": opts.border_position,
+                "tableBorderProperties": border,
+                "fields": fields.join(","),
+            }
+        }));
+    }
+
+    if requests.is_empty() {
+        return Err("No table cell styling specified".to_string());
+    }
+
+    Ok(requests)
+}
+
 /// Execute a raw batch update with arbitrary requests.
 pub fn batch_update(
     presentation_id: &str,
@@ -617,3 +2701,657 @@ fn url_encode(s: &str) -> String {
 }
 
 const HEX: [u8; 16] = *b"0123456789ABCDEF";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(geometry: &ElementGeometry, size: (f64, f64)) -> serde_json::Value {
+        serde_json::json!({
+            "objectId": "shape1",
+            "size": {
+                "width": { "magnitude": pt_to_emu(size.0), "unit": "EMU" },
+                "height": { "magnitude": pt_to_emu(size.1), "unit": "EMU" },
+            },
+            "transform": transform_for(geometry, size),
+        })
+    }
+
+    fn text_shape(placeholder: &str, text: &str) -> serde_json::Value {
+        serde_json::json!({
+            "objectId": "s1",
+            "shape": {
+                "placeholder": { "type": placeholder },
+                "text": { "textElements": [{ "textRun": { "content": text } }] },
+            },
+        })
+    }
+
+    #[test]
+    fn test_element_text_by_detail() {
+        let long = "word ".repeat(100);
+        let el = text_shape("BODY", &long);
+        let standard = parse_element(&el, Detail::Standard).text_content.unwrap();
+        assert!(standard.ends_with('…'));
+        assert_eq!(standard.chars().count(), TEXT_PREVIEW_CHARS);
+        assert_eq!(parse_element(&el, Detail::Full).text_content.unwrap(), long);
+    }
+
+    #[test]
+    fn test_slide_title() {
+        let elements = vec![
+            text_shape("BODY", "Details"),
+            text_shape("CENTERED_TITLE", "  Q3 review\n"),
+        ];
+        assert_eq!(slide_title(&elements).as_deref(), Some("Q3 review"));
+        assert_eq!(slide_title(&elements[..1]), None);
+    }
+
+    #[test]
+    fn test_geometry_of_unrotated_element() {
+        let el = serde_json::json!({
+            "size": {
+                "width": { "magnitude": 3000000, "unit": "EMU" },
+                "height": { "magnitude": 3000000, "unit": "EMU" },
+            },
+            "transform": {
+                "scaleX": 0.5, "scaleY": 0.25, "translateX": 1270000, "translateY": 635000,
+                "unit": "EMU",
+            },
+        });
+        let geometry = element_geometry(&el).unwrap();
+        assert_eq!(geometry.x, 100.0);
+        assert_eq!(geometry.y, 50.0);
+        assert_eq!(geometry.width, round2(3000000.0 / 12700.0 * 0.5));
+        assert_eq!(geometry.rotation, 0.0);
+    }
+
+    #[test]
+    fn test_transform_round_trips() {
+        for rotation in [0.0, 30.0, 90.0, 200.0] {
+            let wanted = ElementGeometry {
+                x: 120.0,
+                y: 80.0,
+                width: 300.0,
+                height: 40.0,
+                rotation,
+            };
+            let got = element_geometry(&element(&wanted, (150.0, 60.0))).unwrap();
+            assert_eq!(got, wanted, "rotation {}", rotation);
+        }
+    }
+
+    #[test]
+    fn test_rotation_keeps_center() {
+        let size = (100.0, 50.0);
+        let flat = ElementGeometry {
+            x: 10.0,
+            y: 20.0,
+            width: 100.0,
+            height: 50.0,
+            rotation: 0.0,
+        };
+        let turned = ElementGeometry {
+            rotation: 90.0,
+            ..flat
+        };
+        let t = transform_for(&turned, size);
+        // Intrinsic center (50, 25) lands on the unrotated box's center (60, 45)
+        let cx = t["scaleX"].as_f64().unwrap() * 50.0
+            + t["shearX"].as_f64().unwrap() * 25.0
+            + t["translateX"].as_f64().unwrap() / 12700.0;
+        let cy = t["shearY"].as_f64().unwrap() * 50.0
+            + t["scaleY"].as_f64().unwrap() * 25.0
+            + t["translateY"].as_f64().unwrap() / 12700.0;
+        assert!((cx - 60.0).abs() < 1e-9 && (cy - 45.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_text_range_variants() {
+        assert_eq!(text_range(None, None)["type"], "ALL");
+        assert_eq!(text_range(Some(4), None)["type"], "FROM_START_INDEX");
+        let fixed = text_range(None, Some(9));
+        assert_eq!(fixed["type"], "FIXED_RANGE");
+        assert_eq!(fixed["startIndex"], 0);
+        assert_eq!(fixed["endIndex"], 9);
+    }
+
+    #[test]
+    fn test_bullet_preset_names_match_serde() {
+        for name in [
+            "BULLET_DISC_CIRCLE_SQUARE",
+            "BULLET_DIAMONDX_ARROW3D_SQUARE",
+            "BULLET_CHECKBOX",
+            "BULLET_ARROW_DIAMOND_DISC",
+            "BULLET_STAR_CIRCLE_SQUARE",
+            "BULLET_ARROW3D_CIRCLE_SQUARE",
+            "BULLET_LEFTTRIANGLE_DIAMOND_DISC",
+            "BULLET_DIAMONDX_HOLLOWDIAMOND_SQUARE",
+            "BULLET_DIAMOND_CIRCLE_SQUARE",
+            "NUMBERED_DIGIT_ALPHA_ROMAN",
+            "NUMBERED_DIGIT_ALPHA_ROMAN_PARENS",
+            "NUMBERED_DIGIT_NESTED",
+            "NUMBERED_UPPERALPHA_ALPHA_ROMAN",
+            "NUMBERED_UPPERROMAN_UPPERALPHA_DIGIT",
+            "NUMBERED_ZERODIGIT_ALPHA_ROMAN",
+        ] {
+            let preset: BulletPreset = serde_json::from_value(serde_json::json!(name)).unwrap();
+            assert_eq!(preset.as_str(), name);
+        }
+        assert_eq!(
+            BulletPreset::default().as_str(),
+            "BULLET_DISC_CIRCLE_SQUARE"
+        );
+    }
+
+    #[test]
+    fn test_deck_summary() {
+        let sharing = LinkSharing {
+            permission_id: "anyoneWithLink".to_string(),
+            share: "anyone".to_string(),
+            domain: None,
+            role: "commenter".to_string(),
+        };
+        let summary = deck_summary(
+            "Q1 Report",
+            "https://docs.google.com/presentation/d/abc/edit?usp=sharing",
+            Some(&sharing),
+            &[Some("Q1 Report\nFinance"), None, Some("Revenue")],
+        );
+        assert_eq!(
+            summary,
+            "Q1 Report (3 slides)\n\
+             https://docs.google.com/presentation/d/abc/edit?usp=sharing\n\
+             Anyone with the link can comment.\n\n\
+             1. Q1 Report\n\
+             3. Revenue"
+        );
+
+        let bare = deck_summary("", "https://x", None, &[None]);
+        assert_eq!(bare, "Untitled deck (1 slide)\nhttps://x");
+    }
+
+    #[test]
+    fn test_find_placeholder() {
+        let placeholder = |id: &str, kind: &str, index: i64, text: Option<&str>| {
+            let mut el = serde_json::json!({
+                "objectId": id,
+                "shape": { "placeholder": { "type": kind, "index": index } },
+            });
+            if let Some(text) = text {
+                el["shape"]["text"] =
+                    serde_json::json!({ "textElements": [{ "textRun": { "content": text } }] });
+            }
+            el
+        };
+        let elements = vec![
+            placeholder("title", "CENTERED_TITLE", 0, Some("Click to add title\n")),
+            placeholder("right", "BODY", 1, None),
+            placeholder("left", "BODY", 0, None),
+        ];
+
+        assert_eq!(
+            find_placeholder(&elements, "TITLE").unwrap(),
+            ("title".to_string(), true)
+        );
+        assert_eq!(find_placeholder(&elements, "body").unwrap().0, "left");
+        assert_eq!(find_placeholder(&elements, "BODY:1").unwrap().0, "right");
+        assert!(find_placeholder(&elements, "BODY:2")
+            .unwrap_err()
+            .contains("available: CENTERED_TITLE, BODY"));
+        assert!(find_placeholder(&elements, "BODY:x").is_err());
+    }
+
+    #[test]
+    fn test_multipart_body() {
+        let body = multipart_body("b", r#"{"name":"x.png"}"#, "image/png", &[0x89, b'P']);
+        let mut expected = b"--b\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n\
+            {\"name\":\"x.png\"}\r\n--b\r\nContent-Type: image/png\r\n\r\n"
+            .to_vec();
+        expected.extend_from_slice(&[0x89, b'P']);
+        expected.extend_from_slice(b"\r\n--b--");
+        assert_eq!(body, expected);
+    }
+
+    #[test]
+    fn test_text_link() {
+        assert_eq!(
+            text_link(Some(" https://example.com "), None).unwrap(),
+            Some(serde_json::json!({ "url": "https://example.com" }))
+        );
+        assert_eq!(
+            text_link(None, Some(3)).unwrap(),
+            Some(serde_json::json!({ "slideIndex": 3 }))
+        );
+        assert_eq!(text_link(None, None).unwrap(), None);
+        assert!(text_link(Some("https://example.com"), Some(1)).is_err());
+        assert!(text_link(None, Some(-1)).is_err());
+    }
+
+    #[test]
+    fn test_shape_text_runs_and_paragraphs() {
+        let elements = serde_json::json!([
+            { "endIndex": 6, "paragraphMarker": { "style": { "alignment": "START" } } },
+            { "endIndex": 6, "textRun": { "content": "Hello ", "style": {} } },
+            { "startIndex": 6, "endIndex": 12, "textRun": {
+                "content": "world\n",
+                "style": {
+                    "bold": true,
+                    "fontSize": { "magnitude": 18, "unit": "PT" },
+                    "foregroundColor": { "opaqueColor": { "rgbColor": { "red": 1, "blue": 0.5 } } },
+                    "link": { "slideIndex": 2 }
+                }
+            } },
+            { "startIndex": 12, "endIndex": 15, "paragraphMarker": { "bullet": { "listId": "l1" } } },
+            { "startIndex": 12, "endIndex": 14, "autoText": { "type": "SLIDE_NUMBER", "content": "4" } },
+        ]);
+        let text = shape_text("shape1", elements.as_array().unwrap());
+
+        assert_eq!(text.text, "Hello world\n4");
+        assert_eq!(text.runs.len(), 3);
+        assert_eq!(text.runs[0].start_index, 0);
+        assert!(text.runs[0].style.is_empty());
+
+        let world = &text.runs[1];
+        assert_eq!((world.start_index, world.end_index), (6, 12));
+        assert_eq!(world.style.bold, Some(true));
+        assert_eq!(world.style.font_size, Some(18.0));
+        assert_eq!(world.style.foreground_color.as_deref(), Some("#FF0080"));
+        assert_eq!(world.style.link_slide_index, Some(2));
+
+        assert_eq!(text.runs[2].auto_text.as_deref(), Some("SLIDE_NUMBER"));
+        assert_eq!(text.paragraphs[0].alignment.as_deref(), Some("START"));
+        assert_eq!(text.paragraphs[0].bullet_nesting_level, None);
+        assert_eq!(text.paragraphs[1].bullet_nesting_level, Some(0));
+    }
+
+    #[test]
+    fn test_collect_shape_text_tables_and_groups() {
+        let cell = |row: i64, column: i64, content: &str| {
+            serde_json::json!({
+                "location": { "rowIndex": row, "columnIndex": column },
+                "text": { "textElements": [
+                    { "endIndex": content.len(), "textRun": { "content": content } }
+                ] }
+            })
+        };
+        let group = serde_json::json!({
+            "objectId": "group1",
+            "elementGroup": { "children": [
+                { "objectId": "image1", "image": {} },
+                { "objectId": "table1", "table": { "tableRows": [
+                    { "tableCells": [cell(0, 0, "Name\n"), cell(0, 1, "Score\n")] },
+                    { "tableCells": [{ "location": { "rowIndex": 1 } }, cell(1, 1, "9\n")] }
+                ] } },
+                { "objectId": "title1", "shape": {
+                    "placeholder": { "type": "TITLE" },
+                    "text": { "textElements": [{ "endIndex": 3, "textRun": { "content": "Q1\n" } }] }
+                } }
+            ] }
+        });
+
+        let mut shapes = Vec::new();
+        collect_shape_text(&group, &mut shapes);
+        let summary: Vec<(&str, Option<i64>, Option<i64>, &str)> = shapes
+            .iter()
+            .map(|s| {
+                (
+                    s.object_id.as_str(),
+                    s.row_index,
+                    s.column_index,
+                    s.text.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("table1", Some(0), Some(0), "Name\n"),
+                ("table1", Some(0), Some(1), "Score\n"),
+                ("table1", Some(1), Some(1), "9\n"),
+                ("title1", None, None, "Q1\n"),
+            ]
+        );
+        assert_eq!(shapes[3].placeholder_type.as_deref(), Some("TITLE"));
+    }
+
+    #[test]
+    fn test_matching_text_indexes() {
+        let title = shape_text(
+            "title1",
+            serde_json::json!([
+                { "endIndex": 9, "textRun": { "content": "🎉 Acme, " } },
+                { "startIndex": 9, "endIndex": 19, "textRun": { "content": "ACME Pro\n" } },
+            ])
+            .as_array()
+            .unwrap(),
+        );
+        let mut cell = shape_text(
+            "table1",
+            serde_json::json!([{ "endIndex": 5, "textRun": { "content": "acme\n" } }])
+                .as_array()
+                .unwrap(),
+        );
+        cell.row_index = Some(1);
+        cell.column_index = Some(0);
+        let slides = [SlideText {
+            slide_object_id: "slide1".to_string(),
+            slide_index: 2,
+            shapes: vec![title, cell],
+        }];
+
+        let re = regex_lite::RegexBuilder::new(&regex_lite::escape("acme"))
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        let matches = matching_text(&slides, &re);
+        let found: Vec<(&str, Option<i64>, i64, i64, &str)> = matches
+            .iter()
+            .map(|m| {
+                (
+                    m.object_id.as_str(),
+                    m.row_index,
+                    m.start_index,
+                    m.end_index,
+                    m.text.as_str(),
+                )
+            })
+            .collect();
+        // The emoji is two UTF-16 code units
+        assert_eq!(
+            found,
+            vec![
+                ("title1", None, 3, 7, "Acme"),
+                ("title1", None, 9, 13, "ACME"),
+                ("table1", Some(1), 0, 4, "acme"),
+            ]
+        );
+
+        let empty = regex_lite::Regex::new("x*").unwrap();
+        assert!(matching_text(&slides, &empty).is_empty());
+    }
+
+    #[test]
+    fn test_color_name() {
+        let theme = serde_json::json!({ "opaqueColor": { "themeColor": "ACCENT1" } });
+        assert_eq!(color_name(&theme).as_deref(), Some("ACCENT1"));
+        assert_eq!(color_name(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn test_video_source() {
+        let youtube = |id: &str| Ok(("YOUTUBE".to_string(), id.to_string()));
+        assert_eq!(
+            video_source("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42", None),
+            youtube("dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            video_source("https://youtu.be/dQw4w9WgXcQ?si=abc", None),
+            youtube("dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            video_source("youtube.com/shorts/dQw4w9WgXcQ", None),
+            youtube("dQw4w9WgXcQ")
+        );
+        assert_eq!(video_source("dQw4w9WgXcQ", None), youtube("dQw4w9WgXcQ"));
+        assert_eq!(
+            video_source(
+                "https://drive.google.com/file/d/1AbC-xyz/view?usp=sharing",
+                None
+            ),
+            Ok(("DRIVE".to_string(), "1AbC-xyz".to_string()))
+        );
+        assert_eq!(
+            video_source("1AbC-xyz", Some("drive")),
+            Ok(("DRIVE".to_string(), "1AbC-xyz".to_string()))
+        );
+        assert!(video_source("https://vimeo.com/123", None).is_err());
+        assert!(video_source("dQw4w9WgXcQ", Some("VIMEO")).is_err());
+    }
+
+    #[test]
+    fn test_facing_side() {
+        let shape = ElementGeometry {
+            x: 100.0,
+            y: 100.0,
+            width: 200.0,
+            height: 50.0,
+            rotation: 0.0,
+        };
+        assert_eq!(facing_side(&shape, (500.0, 150.0)), (3, (300.0, 125.0)));
+        assert_eq!(facing_side(&shape, (0.0, 100.0)), (1, (100.0, 125.0)));
+        assert_eq!(facing_side(&shape, (200.0, 400.0)), (2, (200.0, 150.0)));
+        // Relative to the box, 100pt above outweighs 200pt to the right
+        assert_eq!(facing_side(&shape, (400.0, 25.0)), (0, (200.0, 100.0)));
+    }
+
+    #[test]
+    fn test_line_element_properties_flip() {
+        let props = line_element_properties("slide1", (300.0, 100.0), (100.0, 200.0));
+        assert_eq!(props["size"]["width"]["magnitude"], pt_to_emu(200.0));
+        assert_eq!(props["size"]["height"]["magnitude"], pt_to_emu(100.0));
+        assert_eq!(props["transform"]["scaleX"], -1.0);
+        assert_eq!(props["transform"]["scaleY"], 1.0);
+        assert_eq!(props["transform"]["translateX"], pt_to_emu(300.0));
+        assert_eq!(props["transform"]["translateY"], pt_to_emu(100.0));
+    }
+
+    fn table_options(data: &[Vec<String>]) -> CreateTableOptions<'_> {
+        CreateTableOptions {
+            presentation_id: "p",
+            slide_object_id: "s1",
+            rows: 2,
+            columns: 2,
+            x: Some(10.0),
+            y: Some(20.0),
+            width: None,
+            height: Some(50.0),
+            data,
+        }
+    }
+
+    #[test]
+    fn test_create_table_request() {
+        let request = create_table_request(&table_options(&[])).unwrap();
+        let table = &request["createTable"];
+        assert_eq!(table["rows"], 2);
+        assert_eq!(table["elementProperties"]["pageObjectId"], "s1");
+        // Size needs both dimensions; position is set on its own
+        assert!(table["elementProperties"].get("size").is_none());
+        assert_eq!(
+            table["elementProperties"]["transform"]["translateY"],
+            pt_to_emu(20.0)
+        );
+
+        let too_wide = [vec!["a".to_string(); 3]];
+        assert!(create_table_request(&table_options(&too_wide)).is_err());
+        let too_tall = vec![vec![]; 3];
+        assert!(create_table_request(&table_options(&too_tall)).is_err());
+        let empty = CreateTableOptions {
+            rows: 0,
+            ..table_options(&[])
+        };
+        assert!(create_table_request(&empty).is_err());
+    }
+
+    #[test]
+    fn test_table_text_requests_skip_empty_cells() {
+        let data = [
+            vec!["Name".to_string(), String::new()],
+            vec![String::new(), "42".to_string()],
+        ];
+        let requests = table_text_requests("t1", &data);
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[1]["insertText"],
+            serde_json::json!({
+                "objectId": "t1",
+                "cellLocation": { "rowIndex": 1, "columnIndex": 1 },
+                "text": "42",
+                "insertionIndex": 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_table_cell_style_requests() {
+        let opts = TableCellStyleOptions {
+            presentation_id: "p",
+            table_object_id: "t1",
+            row_index: 0,
+            column_index: 1,
+            row_span: 1,
+            column_span: 2,
+            background_color: Some("#FF0000"),
+            border_color: None,
+            border_weight: Some(2.0),
+            border_dash_style: Some("DASH"),
+            border_position: "OUTER",
+        };
+        let requests = table_cell_style_requests(&opts).unwrap();
+        assert_eq!(requests.len(), 2);
+        let fill = &requests[0]["updateTableCellProperties"];
+        assert_eq!(fill["tableRange"]["columnSpan"], 2);
+        let background = &fill["tableCellProperties"]["tableCellBackgroundFill"];
+        assert_eq!(background["solidFill"]["color"]["rgbColor"]["red"], 1.0);
+        let border = &requests[1]["updateTableBorderProperties"];
+        assert_eq!(border["borderPosition"], "OUTER");
+        assert_eq!(border["fields"], "weight,dashStyle");
+
+        let bad_color = TableCellStyleOptions {
+            background_color: Some("red"),
+            ..opts
+        };
+        assert!(table_cell_style_requests(&bad_color).is_err());
+        let nothing = TableCellStyleOptions {
+            background_color: None,
+            border_weight: None,
+            border_dash_style: None,
+            ..opts
+        };
+        assert!(table_cell_style_requests(&nothing).is_err());
+    }
+
+    #[test]
+    fn test_slide_notes() {
+        let presentation = serde_json::json!({
+            "slides": [
+                {
+                    "objectId": "s1",
+                    "slideProperties": { "notesPage": {
+                        "notesProperties": { "speakerNotesObjectId": "n1" },
+                        "pageElements": [
+                            { "objectId": "other", "shape": { "text": { "textElements": [
+                                { "textRun": { "content": "Slide image" } }
+                            ] } } },
+                            { "objectId": "n1", "shape": { "text": { "textElements": [
+                                { "textRun": { "content": "Open with the numbers\n" } }
+                            ] } } },
+                        ],
+                    } },
+                },
+                // Notes never written: the notes shape doesn't exist yet
+                {
+                    "objectId": "s2",
+                    "slideProperties": { "notesPage": {
+                        "notesProperties": { "speakerNotesObjectId": "n2" },
+                        "pageElements": [],
+                    } },
+                },
+            ],
+        });
+        let notes = slide_notes(&presentation);
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].notes, "Open with the numbers");
+        assert_eq!(notes[1].slide_index, 1);
+        assert_eq!(notes[1].notes_object_id, "n2");
+        assert_eq!(notes[1].notes, "");
+    }
+
+    #[test]
+    fn test_replace_notes_requests() {
+        let mut slide = SlideNotes {
+            slide_object_id: "s1".to_string(),
+            slide_index: 0,
+            notes_object_id: "n1".to_string(),
+            notes: String::new(),
+        };
+        let requests = replace_notes_requests(&slide, "New notes");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["insertText"]["objectId"], "n1");
+
+        slide.notes = "Old notes".to_string();
+        let requests = replace_notes_requests(&slide, "New notes");
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0]["deleteText"]["textRange"]["type"], "ALL");
+
+        // Clearing notes only deletes
+        assert_eq!(replace_notes_requests(&slide, "").len(), 1);
+        slide.notes.clear();
+        assert!(replace_notes_requests(&slide, "").is_empty());
+    }
+
+    #[test]
+    fn test_collect_element_ids_expands_groups() {
+        let elements = [
+            serde_json::json!({ "objectId": "title" }),
+            serde_json::json!({
+                "objectId": "group",
+                "elementGroup": { "children": [
+                    { "objectId": "a" },
+                    { "objectId": "inner", "elementGroup": { "children": [{ "objectId": "b" }] } },
+                ] },
+            }),
+        ];
+        let mut ids = vec!["slide".to_string()];
+        collect_element_ids(&elements, &mut ids);
+        assert_eq!(ids, ["slide", "title", "group", "a", "inner", "b"]);
+    }
+
+    #[test]
+    fn test_duplicate_id_map_keeps_requested_ids() {
+        let originals = ["slide".to_string(), "title".to_string(), "body".to_string()];
+        let mut requested = BTreeMap::new();
+        requested.insert("title".to_string(), "copy_title".to_string());
+
+        let map = duplicate_id_map(&originals, &requested, "dup1700000000000");
+        assert_eq!(map["slide"], "dup1700000000000_0");
+        assert_eq!(map["title"], "copy_title");
+        assert_eq!(map["body"], "dup1700000000000_2");
+    }
+
+    #[test]
+    fn test_sheets_chart_request() {
+        let opts = SheetsChartOptions {
+            presentation_id: "p",
+            slide_object_id: "s1",
+            spreadsheet_id: "sheet",
+            chart_id: 7,
+            linking_mode: "LINKED",
+            x: 10.0,
+            y: 20.0,
+            width: 300.0,
+            height: 200.0,
+        };
+        let request = sheets_chart_request(&opts).unwrap();
+        let chart = &request["createSheetsChart"];
+        assert_eq!(chart["spreadsheetId"], "sheet");
+        assert_eq!(chart["chartId"], 7);
+        assert_eq!(chart["linkingMode"], "LINKED");
+        let props = &chart["elementProperties"];
+        assert_eq!(props["pageObjectId"], "s1");
+        assert_eq!(props["size"]["width"]["magnitude"], pt_to_emu(300.0));
+        assert_eq!(props["transform"]["translateY"], pt_to_emu(20.0));
+
+        let image = SheetsChartOptions {
+            linking_mode: "NOT_LINKED_IMAGE",
+            ..opts
+        };
+        assert!(sheets_chart_request(&image).is_ok());
+        let bad = SheetsChartOptions {
+            linking_mode: "linked",
+            ..opts
+        };
+        assert!(sheets_chart_request(&bad).is_err());
+    }
+}
@@ -6,24 +6,55 @@
 //!
 //! # Capabilities Required
 //!
-//! - HTTP: `slides.googleapis.com/v1/presentations*`
+//! - HTTP: `slides.googleapis.com/v1/presentations*`,
+//!   `www.googleapis.com/drive/v3/files*` for publish_deck's link sharing, and
+//!   `*.googleusercontent.com` plus `www.googleapis.com/upload/drive/v3/files`
+//!   for get_thumbnail's `save_to_drive`
 //! - Secrets: `google_oauth_token` (shared OAuth 2.0 token, injected automatically)
 //!
 //! # Supported Actions
 //!
 //! - `create_presentation`: Create a new blank presentation
-//! - `get_presentation`: Get presentation metadata (slides, elements, text)
-//! - `get_thumbnail`: Get a thumbnail image URL for a slide
-//! - `create_slide`: Add a new slide with a predefined layout
+//! - `get_presentation`: Get presentation metadata (slides, elements, text),
+//!   at a chosen `detail` or limited to a `fields` mask
+//! - `extract_text`: Get every shape's and table cell's text runs with their
+//!   character indexes and styling
+//! - `get_thumbnail`: Get a thumbnail image URL for a slide, optionally saving
+//!   the PNG to a Drive folder
+//! - `create_slide`: Add a new slide with a predefined or presentation layout
+//! - `list_layouts`: List the presentation's masters and layouts with their
+//!   placeholder IDs
+//! - `apply_layout_placeholder_text`: Fill a slide's TITLE/BODY/... placeholders
+//!   by type
 //! - `delete_object`: Delete a slide or page element
-//! - `insert_text`: Insert text into a shape or text box
+//! - `duplicate_slide`: Copy a slide, returning the IDs of the copy and its elements
+//! - `reorder_slides`: Move slides to a new position
+//! - `insert_text`: Insert text into a shape or text box, optionally as a link
 //! - `delete_text`: Delete text from a shape
 //! - `replace_all_text`: Find and replace text across the presentation
 //! - `create_shape`: Create a text box or shape on a slide
 //! - `insert_image`: Insert an image on a slide
-//! - `format_text`: Format text (bold, italic, font, color, size)
+//! - `create_chart_from_sheets`: Embed a (linked) chart from a Google Sheets
+//!   spreadsheet on a slide
+//! - `insert_video`: Embed a YouTube or Drive video, with autoplay, mute, and
+//!   start/end times
+//! - `create_line`: Draw a straight, bent, or curved line between points or
+//!   connecting two shapes, with color, weight, dash, and arrow heads
+//! - `format_text`: Format text (bold, italic, font, color, size, links)
+//! - `style_matching_text`: Format every occurrence of a string or regex
+//!   across the deck
 //! - `format_paragraph`: Set paragraph alignment
+//! - `create_bullets`: Turn paragraphs into a bulleted or numbered list
+//! - `delete_bullets`: Remove bullets and numbering from paragraphs
 //! - `replace_shapes_with_image`: Replace placeholder shapes with an image
+//! - `update_element_transform`: Move, resize, or rotate an existing element
+//! - `get_speaker_notes`: Get the speaker notes of a slide or of every slide
+//! - `set_speaker_notes`: Replace the speaker notes of a slide
+//! - `create_table`: Create a table on a slide, optionally filled with data
+//! - `insert_table_text`: Insert text into a table cell
+//! - `style_table_cell`: Set table cell background fill and borders
+//! - `publish_deck`: Share a presentation by link and return a ready-to-send
+//!   summary with slide thumbnails
 //! - `batch_update`: Execute multiple raw Slides API operations atomically
 //!
 //! # Tips
@@ -34,22 +65,75 @@
 //!   A standard slide is 720x405 points (10x5.625 inches).
 //! - To add text to a slide: first create_shape (TEXT_BOX), then
 //!   insert_text into the returned object_id.
-//! - Use get_presentation to discover object IDs for existing elements.
+//! - To follow the deck's theme instead: create_slide with a layout that has
+//!   placeholders (or a `layout_object_id` from list_layouts), then
+//!   apply_layout_placeholder_text with e.g. {"TITLE": ..., "BODY": ...}.
+//! - Use get_presentation to discover object IDs for existing elements; each
+//!   element's `geometry` (x, y, width, height in points, rotation in degrees)
+//!   is what update_element_transform accepts.
+//! - Before editing existing text in place, extract_text gives each run's
+//!   `start_index`/`end_index`, ready for delete_text and format_text. Indexes
+//!   count UTF-16 code units; the end index is exclusive.
 //! - For template workflows: create shapes with placeholder text, then
 //!   use replace_all_text or replace_shapes_with_image.
+//! - To assemble a deck from a styled slide: duplicate_slide it once per
+//!   entry, edit each copy through the returned `object_id_map`, then
+//!   reorder_slides into place.
+//! - For lists: insert_text with one paragraph per line ("\n"-separated,
+//!   leading tabs for nesting), then create_bullets with a `preset`. Nesting
+//!   tabs are consumed, so re-read indexes before formatting further.
+//! - To hand a finished deck to someone, publish_deck shares it and returns a
+//!   `summary` message to send as-is. Thumbnail URLs expire after about 30
+//!   minutes, so fetch them right before sending.
+//! - For a preview that outlives that, get_thumbnail with `save_to_drive`
+//!   stores the PNG in Drive and returns its `file_id`.
+//! - For data-driven decks: build the data and chart with the google-sheets
+//!   tool, find the chart's `chartId` with get_spreadsheet (fields
+//!   "sheets(charts(chartId,spec/title))"), then create_chart_from_sheets.
+//!   LINKED charts stay connected to the sheet; NOT_LINKED_IMAGE is a snapshot.
+//! - For diagrams: create the boxes with create_shape, then create_line with
+//!   `start_object_id`/`end_object_id`. Without points, each end sits on the
+//!   middle of the side facing the other shape, and the connector follows the
+//!   shapes when they're moved in the editor.
+//! - insert_video accepts YouTube and Drive links as-is; Drive videos play
+//!   only for viewers who can open the file.
+//! - Links: `link_url` points text at a web page; `link_slide_index` at another
+//!   slide of the deck (0-based), e.g. for a table of contents or to cite
+//!   an appendix slide.
+//! - To style a word wherever it appears (e.g. the product name in bold and
+//!   the brand color), use style_matching_text rather than format_text on
+//!   each shape. Matching is case-insensitive unless `match_case` is set;
+//!   `"regex": true` takes a regular expression such as `"Q[1-4] 2025"`.
+//! - Speaker notes are addressed by slide; the notes page and its shape are
+//!   resolved automatically.
+//! - For data tables: create_table with `data`, then style_table_cell to
+//!   shade the header row and set borders. Cell rows and columns are 0-based.
 //!
 //! # Example Usage
 //!
 //! ```json
 //! {"action": "create_presentation", "title": "Q1 Report"}
 //! {"action": "create_slide", "presentation_id": "abc123", "layout": "TITLE_AND_BODY"}
+//! {"action": "apply_layout_placeholder_text", "presentation_id": "abc123", "slide_object_id": "slide1", "placeholders": {"TITLE": "Q1 Results", "BODY": "Revenue up 12%"}}
 //! {"action": "get_presentation", "presentation_id": "abc123"}
+//! {"action": "get_presentation", "presentation_id": "abc123", "detail": "summary"}
+//! {"action": "extract_text", "presentation_id": "abc123", "slide_object_id": "slide1"}
 //! {"action": "create_shape", "presentation_id": "abc123", "slide_object_id": "slide1", "shape_type": "TEXT_BOX", "x": 50, "y": 50, "width": 300, "height": 40}
 //! {"action": "insert_text", "presentation_id": "abc123", "object_id": "shape1", "text": "Hello World"}
 //! {"action": "format_text", "presentation_id": "abc123", "object_id": "shape1", "bold": true, "font_size": 24}
+//! {"action": "style_matching_text", "presentation_id": "abc123", "pattern": "IronClaw", "match_case": true, "bold": true, "foreground_color": "#E8451E"}
+//! {"action": "create_bullets", "presentation_id": "abc123", "object_id": "shape1", "preset": "NUMBERED_DIGIT_ALPHA_ROMAN"}
+//! {"action": "set_speaker_notes", "presentation_id": "abc123", "slide_object_id": "slide1", "text": "Open with the Q1 revenue numbers."}
+//! {"action": "create_table", "presentation_id": "abc123", "slide_object_id": "slide1", "rows": 3, "columns": 2, "data": [["Name", "Score"], ["Ann", "9"], ["Bob", "7"]]}
+//! {"action": "insert_video", "presentation_id": "abc123", "slide_object_id": "slide1", "video": "https://youtu.be/dQw4w9WgXcQ", "x": 160, "y": 90, "width": 400, "height": 225, "autoplay": true, "start_seconds": 30}
+//! {"action": "create_line", "presentation_id": "abc123", "slide_object_id": "slide1", "category": "BENT", "start_object_id": "box1", "end_object_id": "box2", "end_arrow": "FILL_ARROW"}
+//! {"action": "publish_deck", "presentation_id": "abc123", "share": "domain", "domain": "example.com", "role": "commenter"}
+//! {"action": "style_table_cell", "presentation_id": "abc123", "table_object_id": "table1", "row_index": 0, "column_index": 0, "column_span": 2, "background_color": "#DDDDDD"}
 //! ```
 
 mod api;
+#[path = "../../google-common/api_error.rs"]
+mod api_error;
 mod types;
 
 use types::GoogleSlidesAction;
@@ -96,6 +180,30 @@ impl exports::near::agent::tool::Guest for GoogleSlidesTool {
                         "presentation_id": {
                             "type": "string",
                             "description": "The presentation ID (same as Google Drive file ID)"
+                        },
+                        "detail": {
+                            "type": "string",
+                            "enum": ["summary", "standard", "full"],
+                            "description": "'summary': slide titles and element counts; 'standard': element inventory with text previews; 'full': complete element text",
+                            "default": "standard"
+                        },
+                        "fields": {
+                            "type": "string",
+                            "description": "Google API field mask (e.g. 'slides(objectId,pageElements(objectId,size))'). Returns the raw API response limited to these fields instead of the summary"
+                        }
+                    },
+                    "required": ["action", "presentation_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "extract_text" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "slide_object_id": {
+                            "type": "string",
+                            "description": "Only this slide (default: every slide)"
                         }
                     },
                     "required": ["action", "presentation_id"]
@@ -110,6 +218,18 @@ impl exports::near::agent::tool::Guest for GoogleSlidesTool {
                         "slide_object_id": {
                             "type": "string",
                             "description": "The slide's object ID"
+                        },
+                        "save_to_drive": {
+                            "type": "boolean",
+                            "description": "Also upload the PNG to Drive and return its file ID (default: false)"
+                        },
+                        "folder_id": {
+                            "type": "string",
+                            "description": "Drive folder ID for the upload (default: My Drive root)"
+                        },
+                        "file_name": {
+                            "type": "string",
+                            "description": "File name for the upload (default: 'slide-<slide_object_id>.png')"
                         }
                     },
                     "required": ["action", "presentation_id", "slide_object_id"]
@@ -130,10 +250,43 @@ impl exports::near::agent::tool::Guest for GoogleSlidesTool {
                             "enum": ["BLANK", "TITLE", "TITLE_AND_BODY", "TITLE_AND_TWO_COLUMNS", "TITLE_ONLY", "SECTION_HEADER", "CAPTION_ONLY", "BIG_NUMBER", "ONE_COLUMN_TEXT", "MAIN_POINT"],
                             "description": "Predefined layout (default: BLANK)",
                             "default": "BLANK"
+                        },
+                        "layout_object_id": {
+                            "type": "string",
+                            "description": "Object ID of a layout from list_layouts, to use the presentation's own theme layouts. Overrides layout."
+                        }
+                    },
+                    "required": ["action", "presentation_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "list_layouts" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
                         }
                     },
                     "required": ["action", "presentation_id"]
                 },
+                {
+                    "properties": {
+                        "action": { "const": "apply_layout_placeholder_text" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "slide_object_id": {
+                            "type": "string",
+                            "description": "Slide object ID"
+                        },
+                        "placeholders": {
+                            "type": "object",
+                            "additionalProperties": { "type": "string" },
+                            "description": "Text keyed by placeholder type: TITLE, SUBTITLE, BODY, etc. Use 'BODY:1' for the second placeholder of a type. Existing text is replaced."
+                        }
+                    },
+                    "required": ["action", "presentation_id", "slide_object_id", "placeholders"]
+                },
                 {
                     "properties": {
                         "action": { "const": "delete_object" },
@@ -148,6 +301,44 @@ impl exports::near::agent::tool::Guest for GoogleSlidesTool {
                     },
                     "required": ["action", "presentation_id", "object_id"]
                 },
+                {
+                    "properties": {
+                        "action": { "const": "duplicate_slide" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "slide_object_id": {
+                            "type": "string",
+                            "description": "Object ID of the slide to copy. The copy is placed right after it."
+                        },
+                        "object_ids": {
+                            "type": "object",
+                            "additionalProperties": { "type": "string" },
+                            "description": "Optional IDs for the copies, keyed by original object ID (the slide or its elements). Others are generated; all are returned in object_id_map."
+                        }
+                    },
+                    "required": ["action", "presentation_id", "slide_object_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "reorder_slides" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "slide_object_ids": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Slides to move, in the order they should appear"
+                        },
+                        "insertion_index": {
+                            "type": "integer",
+                            "description": "Position to move them to (0-based), counted before the move"
+                        }
+                    },
+                    "required": ["action", "presentation_id", "slide_object_ids", "insertion_index"]
+                },
                 {
                     "properties": {
                         "action": { "const": "insert_text" },
@@ -167,6 +358,14 @@ impl exports::near::agent::tool::Guest for GoogleSlidesTool {
                             "type": "integer",
                             "description": "Character index to insert at (0-based). Default: 0.",
                             "default": 0
+                        },
+                        "link_url": {
+                            "type": "string",
+                            "description": "Make the inserted text a hyperlink to this URL"
+                        },
+                        "link_slide_index": {
+                            "type": "integer",
+                            "description": "Make the inserted text a link to the slide at this position (0-based)"
                         }
                     },
                     "required": ["action", "presentation_id", "object_id", "text"]
@@ -287,6 +486,178 @@ impl exports::near::agent::tool::Guest for GoogleSlidesTool {
                     },
                     "required": ["action", "presentation_id", "slide_object_id", "image_url", "x", "y", "width", "height"]
                 },
+                {
+                    "properties": {
+                        "action": { "const": "create_chart_from_sheets" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "slide_object_id": {
+                            "type": "string",
+                            "description": "Slide object ID to place the chart on"
+                        },
+                        "spreadsheet_id": {
+                            "type": "string",
+                            "description": "Spreadsheet that holds the chart"
+                        },
+                        "chart_id": {
+                            "type": "integer",
+                            "description": "Chart ID within the spreadsheet (google-sheets get_spreadsheet with fields 'sheets(charts(chartId,spec/title))')"
+                        },
+                        "linking_mode": {
+                            "type": "string",
+                            "enum": ["LINKED", "NOT_LINKED_IMAGE"],
+                            "description": "LINKED keeps a link to the sheet so the chart can be refreshed; NOT_LINKED_IMAGE embeds a static picture (default: LINKED)"
+                        },
+                        "x": {
+                            "type": "number",
+                            "description": "X position in points"
+                        },
+                        "y": {
+                            "type": "number",
+                            "description": "Y position in points"
+                        },
+                        "width": {
+                            "type": "number",
+                            "description": "Width in points"
+                        },
+                        "height": {
+                            "type": "number",
+                            "description": "Height in points"
+                        }
+                    },
+                    "required": ["action", "presentation_id", "slide_object_id", "spreadsheet_id", "chart_id", "x", "y", "width", "height"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "insert_video" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "slide_object_id": {
+                            "type": "string",
+                            "description": "Slide object ID to place the video on"
+                        },
+                        "video": {
+                            "type": "string",
+                            "description": "YouTube or Google Drive video URL, or a bare video/file ID"
+                        },
+                        "source": {
+                            "type": "string",
+                            "enum": ["YOUTUBE", "DRIVE"],
+                            "description": "Where the video lives (default: taken from the URL; YOUTUBE for a bare ID)"
+                        },
+                        "x": {
+                            "type": "number",
+                            "description": "X position in points"
+                        },
+                        "y": {
+                            "type": "number",
+                            "description": "Y position in points"
+                        },
+                        "width": {
+                            "type": "number",
+                            "description": "Width in points"
+                        },
+                        "height": {
+                            "type": "number",
+                            "description": "Height in points"
+                        },
+                        "autoplay": {
+                            "type": "boolean",
+                            "description": "Start playing when the slide is presented"
+                        },
+                        "mute": {
+                            "type": "boolean",
+                            "description": "Play without sound"
+                        },
+                        "start_seconds": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "Second to start playing from"
+                        },
+                        "end_seconds": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "Second to stop playing at"
+                        }
+                    },
+                    "required": ["action", "presentation_id", "slide_object_id", "video", "x", "y", "width", "height"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "create_line" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "slide_object_id": {
+                            "type": "string",
+                            "description": "Slide object ID to draw the line on"
+                        },
+                        "category": {
+                            "type": "string",
+                            "enum": ["STRAIGHT", "BENT", "CURVED"],
+                            "description": "Line shape (default: STRAIGHT)"
+                        },
+                        "start_x": {
+                            "type": "number",
+                            "description": "Start point X in points (optional with start_object_id)"
+                        },
+                        "start_y": {
+                            "type": "number",
+                            "description": "Start point Y in points"
+                        },
+                        "start_object_id": {
+                            "type": "string",
+                            "description": "Shape to connect the start to; without a point, the line starts on its side facing the other end"
+                        },
+                        "start_site_index": {
+                            "type": "integer",
+                            "description": "Connection site on the start shape (rectangles: 0 top, 1 left, 2 bottom, 3 right; default: the side facing the other end)"
+                        },
+                        "end_x": {
+                            "type": "number",
+                            "description": "End point X in points (optional with end_object_id)"
+                        },
+                        "end_y": {
+                            "type": "number",
+                            "description": "End point Y in points"
+                        },
+                        "end_object_id": {
+                            "type": "string",
+                            "description": "Shape to connect the end to"
+                        },
+                        "end_site_index": {
+                            "type": "integer",
+                            "description": "Connection site on the end shape"
+                        },
+                        "color": {
+                            "type": "string",
+                            "description": "Line color as hex (e.g., '#333333')"
+                        },
+                        "weight": {
+                            "type": "number",
+                            "description": "Line weight in points"
+                        },
+                        "dash_style": {
+                            "type": "string",
+                            "enum": ["SOLID", "DOT", "DASH", "DASH_DOT", "LONG_DASH", "LONG_DASH_DOT"],
+                            "description": "Dash style"
+                        },
+                        "start_arrow": {
+                            "type": "string",
+                            "description": "Arrow head at the start: NONE, FILL_ARROW, STEALTH_ARROW, OPEN_ARROW, FILL_CIRCLE, OPEN_CIRCLE, FILL_SQUARE, OPEN_SQUARE, FILL_DIAMOND, OPEN_DIAMOND"
+                        },
+                        "end_arrow": {
+                            "type": "string",
+                            "description": "Arrow head at the end (same values as start_arrow)"
+                        }
+                    },
+                    "required": ["action", "presentation_id", "slide_object_id"]
+                },
                 {
                     "properties": {
                         "action": { "const": "format_text" },
@@ -329,10 +700,78 @@ impl exports::near::agent::tool::Guest for GoogleSlidesTool {
                         "foreground_color": {
                             "type": "string",
                             "description": "Text color as hex (e.g., '#FF0000' for red)"
+                        },
+                        "link_url": {
+                            "type": "string",
+                            "description": "Turn the range into a hyperlink to this URL"
+                        },
+                        "link_slide_index": {
+                            "type": "integer",
+                            "description": "Turn the range into a link to the slide at this position (0-based). Not with link_url."
                         }
                     },
                     "required": ["action", "presentation_id", "object_id"]
                 },
+                {
+                    "properties": {
+                        "action": { "const": "style_matching_text" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "pattern": {
+                            "type": "string",
+                            "description": "Text to find in every shape and table cell, or a regular expression when regex is true"
+                        },
+                        "regex": {
+                            "type": "boolean",
+                            "description": "Treat pattern as a regular expression",
+                            "default": false
+                        },
+                        "match_case": {
+                            "type": "boolean",
+                            "description": "Match case exactly",
+                            "default": false
+                        },
+                        "slide_object_id": {
+                            "type": "string",
+                            "description": "Only this slide (default: every slide)"
+                        },
+                        "bold": {
+                            "type": "boolean",
+                            "description": "Make matches bold"
+                        },
+                        "italic": {
+                            "type": "boolean",
+                            "description": "Make matches italic"
+                        },
+                        "underline": {
+                            "type": "boolean",
+                            "description": "Underline matches"
+                        },
+                        "font_size": {
+                            "type": "number",
+                            "description": "Font size in points"
+                        },
+                        "font_family": {
+                            "type": "string",
+                            "description": "Font family (e.g., 'Arial')"
+                        },
+                        "foreground_color": {
+                            "type": "string",
+                            "description": "Text color as hex (e.g., '#FF0000' for red)"
+                        },
+                        "link_url": {
+                            "type": "string",
+                            "description": "Turn each match into a hyperlink to this URL"
+                        },
+                        "link_slide_index": {
+                            "type": "integer",
+                            "description": "Turn each match into a link to the slide at this position (0-based). Not with link_url."
+                        }
+                    },
+                    "required": ["action", "presentation_id", "pattern"]
+                },
                 {
                     "properties": {
                         "action": { "const": "format_paragraph" },
@@ -362,39 +801,327 @@ impl exports::near::agent::tool::Guest for GoogleSlidesTool {
                 },
                 {
                     "properties": {
-                        "action": { "const": "replace_shapes_with_image" },
+                        "action": { "const": "create_bullets" },
                         "presentation_id": {
                             "type": "string",
                             "description": "The presentation ID"
                         },
-                        "find": {
+                        "object_id": {
                             "type": "string",
-                            "description": "Text to match in shapes"
+                            "description": "Object ID of the shape"
                         },
-                        "image_url": {
+                        "preset": {
                             "type": "string",
-                            "description": "Image URL to replace matched shapes with"
+                            "enum": [
+                                "BULLET_DISC_CIRCLE_SQUARE", "BULLET_DIAMONDX_ARROW3D_SQUARE",
+                                "BULLET_CHECKBOX", "BULLET_ARROW_DIAMOND_DISC",
+                                "BULLET_STAR_CIRCLE_SQUARE", "BULLET_ARROW3D_CIRCLE_SQUARE",
+                                "BULLET_LEFTTRIANGLE_DIAMOND_DISC",
+                                "BULLET_DIAMONDX_HOLLOWDIAMOND_SQUARE",
+                                "BULLET_DIAMOND_CIRCLE_SQUARE", "NUMBERED_DIGIT_ALPHA_ROMAN",
+                                "NUMBERED_DIGIT_ALPHA_ROMAN_PARENS", "NUMBERED_DIGIT_NESTED",
+                                "NUMBERED_UPPERALPHA_ALPHA_ROMAN",
+                                "NUMBERED_UPPERROMAN_UPPERALPHA_DIGIT",
+                                "NUMBERED_ZERODIGIT_ALPHA_ROMAN"
+                            ],
+                            "description": "Bullet glyphs for the first three nesting levels (default: BULLET_DISC_CIRCLE_SQUARE). NUMBERED_* presets make numbered lists. Nesting comes from leading tabs on each paragraph."
                         },
-                        "match_case": {
-                            "type": "boolean",
-                            "description": "Case-sensitive match (default: true)",
-                            "default": true
+                        "start_index": {
+                            "type": "integer",
+                            "description": "Start index (inclusive). Omit to apply to all paragraphs."
+                        },
+                        "end_index": {
+                            "type": "integer",
+                            "description": "End index (exclusive). Omit to apply to the end."
                         }
                     },
-                    "required": ["action", "presentation_id", "find", "image_url"]
+                    "required": ["action", "presentation_id", "object_id"]
                 },
                 {
                     "properties": {
-                        "action": { "const": "batch_update" },
+                        "action": { "const": "delete_bullets" },
                         "presentation_id": {
                             "type": "string",
                             "description": "The presentation ID"
                         },
-                        "requests": {
-                            "type": "array",
-                            "items": { "type": "object" },
-                            "description": "Array of raw Slides API batchUpdate request objects"
-                        }
+                        "object_id": {
+                            "type": "string",
+                            "description": "Object ID of the shape"
+                        },
+                        "start_index": {
+                            "type": "integer",
+                            "description": "Start index (inclusive). Omit to apply to all paragraphs."
+                        },
+                        "end_index": {
+                            "type": "integer",
+                            "description": "End index (exclusive). Omit to apply to the end."
+                        }
+                    },
+                    "required": ["action", "presentation_id", "object_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "replace_shapes_with_image" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "find": {
+                            "type": "string",
+                            "description": "Text to match in shapes"
+                        },
+                        "image_url": {
+                            "type": "string",
+                            "description": "Image URL to replace matched shapes with"
+                        },
+                        "match_case": {
+                            "type": "boolean",
+                            "description": "Case-sensitive match (default: true)",
+                            "default": true
+                        }
+                    },
+                    "required": ["action", "presentation_id", "find", "image_url"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "update_element_transform" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "object_id": {
+                            "type": "string",
+                            "description": "Object ID of the element (see geometry in get_presentation)"
+                        },
+                        "x": {
+                            "type": "number",
+                            "description": "Left edge in points (before rotation). Omit to keep."
+                        },
+                        "y": {
+                            "type": "number",
+                            "description": "Top edge in points (before rotation). Omit to keep."
+                        },
+                        "width": {
+                            "type": "number",
+                            "description": "Width in points. Omit to keep."
+                        },
+                        "height": {
+                            "type": "number",
+                            "description": "Height in points. Omit to keep."
+                        },
+                        "rotation": {
+                            "type": "number",
+                            "description": "Clockwise rotation in degrees about the element's center. Omit to keep."
+                        }
+                    },
+                    "required": ["action", "presentation_id", "object_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "get_speaker_notes" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "slide_object_id": {
+                            "type": "string",
+                            "description": "Slide object ID. Omit to get the notes of every slide."
+                        }
+                    },
+                    "required": ["action", "presentation_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "set_speaker_notes" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "slide_object_id": {
+                            "type": "string",
+                            "description": "Slide object ID"
+                        },
+                        "text": {
+                            "type": "string",
+                            "description": "New speaker notes, replacing the current ones. Empty clears them."
+                        }
+                    },
+                    "required": ["action", "presentation_id", "slide_object_id", "text"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "create_table" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "slide_object_id": {
+                            "type": "string",
+                            "description": "Slide object ID to place the table on"
+                        },
+                        "rows": {
+                            "type": "integer",
+                            "description": "Number of rows"
+                        },
+                        "columns": {
+                            "type": "integer",
+                            "description": "Number of columns"
+                        },
+                        "x": {
+                            "type": "number",
+                            "description": "X position in points. Omit x and y to center the table."
+                        },
+                        "y": {
+                            "type": "number",
+                            "description": "Y position in points"
+                        },
+                        "width": {
+                            "type": "number",
+                            "description": "Width in points. Omit width and height for the default size."
+                        },
+                        "height": {
+                            "type": "number",
+                            "description": "Height in points"
+                        },
+                        "data": {
+                            "type": "array",
+                            "items": { "type": "array", "items": { "type": "string" } },
+                            "description": "Cell text, row by row (e.g., [[\"Name\", \"Score\"], [\"Ann\", \"9\"]]). Must fit within rows and columns."
+                        }
+                    },
+                    "required": ["action", "presentation_id", "slide_object_id", "rows", "columns"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "insert_table_text" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "table_object_id": {
+                            "type": "string",
+                            "description": "Object ID of the table"
+                        },
+                        "row_index": {
+                            "type": "integer",
+                            "description": "Row of the cell (0-based)"
+                        },
+                        "column_index": {
+                            "type": "integer",
+                            "description": "Column of the cell (0-based)"
+                        },
+                        "text": {
+                            "type": "string",
+                            "description": "Text to insert"
+                        },
+                        "insertion_index": {
+                            "type": "integer",
+                            "description": "Character index to insert at (0-based). Default: 0.",
+                            "default": 0
+                        }
+                    },
+                    "required": ["action", "presentation_id", "table_object_id", "row_index", "column_index", "text"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "style_table_cell" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "table_object_id": {
+                            "type": "string",
+                            "description": "Object ID of the table"
+                        },
+                        "row_index": {
+                            "type": "integer",
+                            "description": "Row of the top-left cell to style (0-based)"
+                        },
+                        "column_index": {
+                            "type": "integer",
+                            "description": "Column of the top-left cell to style (0-based)"
+                        },
+                        "row_span": {
+                            "type": "integer",
+                            "description": "Rows to style, starting at row_index (default: 1)",
+                            "default": 1
+                        },
+                        "column_span": {
+                            "type": "integer",
+                            "description": "Columns to style, starting at column_index (default: 1)",
+                            "default": 1
+                        },
+                        "background_color": {
+                            "type": "string",
+                            "description": "Cell background color as hex (e.g., '#F3F3F3')"
+                        },
+                        "border_color": {
+                            "type": "string",
+                            "description": "Border color as hex (e.g., '#000000')"
+                        },
+                        "border_weight": {
+                            "type": "number",
+                            "description": "Border weight in points"
+                        },
+                        "border_dash_style": {
+                            "type": "string",
+                            "enum": ["SOLID", "DOT", "DASH", "DASH_DOT", "LONG_DASH", "LONG_DASH_DOT"],
+                            "description": "Border dash style"
+                        },
+                        "border_position": {
+                            "type": "string",
+                            "enum": ["ALL", "OUTER", "INNER", "TOP", "BOTTOM", "LEFT", "RIGHT", "INNER_HORIZONTAL", "INNER_VERTICAL"],
+                            "description": "Which borders of the range to style (default: ALL)",
+                            "default": "ALL"
+                        }
+                    },
+                    "required": ["action", "presentation_id", "table_object_id", "row_index", "column_index"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "publish_deck" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "share": {
+                            "type": "string",
+                            "enum": ["anyone", "domain", "none"],
+                            "description": "Who the link works for (default: anyone). 'none' leaves sharing unchanged."
+                        },
+                        "domain": {
+                            "type": "string",
+                            "description": "Domain for share 'domain' (e.g., 'example.com')"
+                        },
+                        "role": {
+                            "type": "string",
+                            "enum": ["reader", "commenter", "writer"],
+                            "description": "Access granted by the link (default: reader)"
+                        },
+                        "max_previews": {
+                            "type": "integer",
+                            "description": "Slides to fetch thumbnails for, from the start (default: 10, max: 25)"
+                        },
+                        "thumbnail_size": {
+                            "type": "string",
+                            "enum": ["SMALL", "MEDIUM", "LARGE"],
+                            "description": "Thumbnail size (default: MEDIUM)"
+                        }
+                    },
+                    "required": ["action", "presentation_id"]
+                },
+                {
+                    "properties": {
+                        "action": { "const": "batch_update" },
+                        "presentation_id": {
+                            "type": "string",
+                            "description": "The presentation ID"
+                        },
+                        "requests": {
+                            "type": "array",
+                            "items": { "type": "object" },
+                            "description": "Array of raw Slides API batchUpdate request objects"
+                        }
                     },
                     "required": ["action", "presentation_id", "requests"]
                 }
@@ -405,24 +1132,31 @@ impl exports::near::agent::tool::Guest for GoogleSlidesTool {
 
     fn description() -> String {
         "Google Slides integration for creating, reading, editing, and formatting presentations. \
-         Supports slide management (create, delete, reorder), text operations (insert, delete, \
-         find-replace), shapes and text boxes, image insertion, text formatting (bold, italic, \
-         font, color, size), paragraph alignment, thumbnails, and template-based image replacement. \
+         Supports slide management (create, delete, duplicate, reorder), theme layouts and \
+         filling TITLE/BODY placeholders by type, text operations \
+         (insert, delete, find-replace, extracting styled runs with character indexes), \
+         shapes and text boxes, image insertion, moving, \
+         resizing and rotating elements, text formatting (bold, italic, font, color, size) \
+         of a range or of every match of a string or regex across the deck, \
+         hyperlinks to URLs or other slides, \
+         paragraph alignment, bulleted and numbered lists, speaker notes, data tables \
+         (create, cell text, borders, fill), charts embedded from Google Sheets, embedded \
+         YouTube and Drive videos, lines and connectors between shapes, thumbnails \
+         (optionally saved to Drive), and template-based image replacement. \
+         publish_deck shares a deck by link and returns a ready-to-send summary with slide \
+         previews. \
          Also provides a batch_update action for complex multi-step edits executed atomically. \
          Positions and sizes use points (standard slide is 720x405 pt). Presentation IDs are the \
          same as Google Drive file IDs, so use the google-drive tool to search for existing \
-         presentations. Requires a Google OAuth token with the presentations scope."
+         presentations. Requires a Google OAuth token with the presentations scope (plus the \
+         drive scope for publish_deck, save_to_drive, Sheets charts, and Drive videos)."
             .to_string()
     }
 }
 
 fn execute_inner(params: &str) -> Result<String, String> {
     if !crate::near::agent::host::secret_exists("google_oauth_token") {
-        return Err(
-            "Google OAuth token not configured. Run `ironclaw tool auth google-slides` to set up \
-             OAuth, or set the GOOGLE_OAUTH_TOKEN environment variable."
-                .to_string(),
-        );
+        return Err(api_error::missing_token("google-slides"));
     }
 
     let action: GoogleSlidesAction =
@@ -439,16 +1173,38 @@ fn execute_inner(params: &str) -> Result<String, String> {
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
-        GoogleSlidesAction::GetPresentation { presentation_id } => {
-            let result = api::get_presentation(&presentation_id)?;
+        GoogleSlidesAction::GetPresentation {
+            presentation_id,
+            detail,
+            fields,
+        } => match fields {
+            Some(fields) => api::get_presentation_fields(&presentation_id, &fields)?,
+            None => {
+                let result = api::get_presentation(&presentation_id, detail)?;
+                serde_json::to_string(&result).map_err(|e| e.to_string())?
+            }
+        },
+
+        GoogleSlidesAction::ExtractText {
+            presentation_id,
+            slide_object_id,
+        } => {
+            let result = api::extract_text(&presentation_id, slide_object_id.as_deref())?;
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
         GoogleSlidesAction::GetThumbnail {
             presentation_id,
             slide_object_id,
+            save_to_drive,
+            folder_id,
+            file_name,
         } => {
-            let result = api::get_thumbnail(&presentation_id, &slide_object_id)?;
+            let save = save_to_drive.then_some(api::SaveToDrive {
+                folder_id: folder_id.as_deref(),
+                file_name: file_name.as_deref(),
+            });
+            let result = api::get_thumbnail(&presentation_id, &slide_object_id, save)?;
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
@@ -456,8 +1212,32 @@ fn execute_inner(params: &str) -> Result<String, String> {
             presentation_id,
             insertion_index,
             layout,
+            layout_object_id,
+        } => {
+            let result = api::create_slide(
+                &presentation_id,
+                insertion_index,
+                &layout,
+                layout_object_id.as_deref(),
+            )?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSlidesAction::ListLayouts { presentation_id } => {
+            let result = api::list_layouts(&presentation_id)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSlidesAction::ApplyLayoutPlaceholderText {
+            presentation_id,
+            slide_object_id,
+            placeholders,
         } => {
-            let result = api::create_slide(&presentation_id, insertion_index, &layout)?;
+            let result = api::apply_layout_placeholder_text(
+                &presentation_id,
+                &slide_object_id,
+                &placeholders,
+            )?;
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
@@ -469,13 +1249,40 @@ fn execute_inner(params: &str) -> Result<String, String> {
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
+        GoogleSlidesAction::DuplicateSlide {
+            presentation_id,
+            slide_object_id,
+            object_ids,
+        } => {
+            let result = api::duplicate_slide(&presentation_id, &slide_object_id, &object_ids)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSlidesAction::ReorderSlides {
+            presentation_id,
+            slide_object_ids,
+            insertion_index,
+        } => {
+            let result = api::reorder_slides(&presentation_id, &slide_object_ids, insertion_index)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
         GoogleSlidesAction::InsertText {
             presentation_id,
             object_id,
             text,
             insertion_index,
+            link_url,
+            link_slide_index,
         } => {
-            let result = api::insert_text(&presentation_id, &object_id, &text, insertion_index)?;
+            let result = api::insert_text(
+                &presentation_id,
+                &object_id,
+                &text,
+                insertion_index,
+                link_url.as_deref(),
+                link_slide_index,
+            )?;
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
@@ -541,6 +1348,105 @@ fn execute_inner(params: &str) -> Result<String, String> {
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
+        GoogleSlidesAction::CreateChartFromSheets {
+            presentation_id,
+            slide_object_id,
+            spreadsheet_id,
+            chart_id,
+            linking_mode,
+            x,
+            y,
+            width,
+            height,
+        } => {
+            let result = api::create_chart_from_sheets(api::SheetsChartOptions {
+                presentation_id: &presentation_id,
+                slide_object_id: &slide_object_id,
+                spreadsheet_id: &spreadsheet_id,
+                chart_id,
+                linking_mode: &linking_mode,
+                x,
+                y,
+                width,
+                height,
+            })?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSlidesAction::InsertVideo {
+            presentation_id,
+            slide_object_id,
+            video,
+            source,
+            x,
+            y,
+            width,
+            height,
+            autoplay,
+            mute,
+            start_seconds,
+            end_seconds,
+        } => {
+            let result = api::insert_video(api::VideoOptions {
+                presentation_id: &presentation_id,
+                slide_object_id: &slide_object_id,
+                video: &video,
+                source: source.as_deref(),
+                x,
+                y,
+                width,
+                height,
+                autoplay,
+                mute,
+                start_seconds,
+                end_seconds,
+            })?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSlidesAction::CreateLine {
+            presentation_id,
+            slide_object_id,
+            category,
+            start_x,
+            start_y,
+            start_object_id,
+            start_site_index,
+            end_x,
+            end_y,
+            end_object_id,
+            end_site_index,
+            color,
+            weight,
+            dash_style,
+            start_arrow,
+            end_arrow,
+        } => {
+            let result = api::create_line(api::LineOptions {
+                presentation_id: &presentation_id,
+                slide_object_id: &slide_object_id,
+                category: &category,
+                start: api::LineEnd {
+                    x: start_x,
+                    y: start_y,
+                    object_id: start_object_id.as_deref(),
+                    site_index: start_site_index,
+                },
+                end: api::LineEnd {
+                    x: end_x,
+                    y: end_y,
+                    object_id: end_object_id.as_deref(),
+                    site_index: end_site_index,
+                },
+                color: color.as_deref(),
+                weight,
+                dash_style: dash_style.as_deref(),
+                start_arrow: start_arrow.as_deref(),
+                end_arrow: end_arrow.as_deref(),
+            })?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
         GoogleSlidesAction::FormatText {
             presentation_id,
             object_id,
@@ -552,18 +1458,59 @@ fn execute_inner(params: &str) -> Result<String, String> {
             font_size,
             font_family,
             foreground_color,
+            link_url,
+            link_slide_index,
         } => {
             let result = api::format_text(api::FormatTextOptions {
                 presentation_id: &presentation_id,
                 object_id: &object_id,
                 start_index,
                 end_index,
-                bold,
-                italic,
-                underline,
-                font_size,
-                font_family: font_family.as_deref(),
-                foreground_color: foreground_color.as_deref(),
+                style: api::TextStyleOptions {
+                    bold,
+                    italic,
+                    underline,
+                    font_size,
+                    font_family: font_family.as_deref(),
+                    foreground_color: foreground_color.as_deref(),
+                    link_url: link_url.as_deref(),
+                    link_slide_index,
+                },
+            })?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSlidesAction::StyleMatchingText {
+            presentation_id,
+            pattern,
+            regex,
+            match_case,
+            slide_object_id,
+            bold,
+            italic,
+            underline,
+            font_size,
+            font_family,
+            foreground_color,
+            link_url,
+            link_slide_index,
+        } => {
+            let result = api::style_matching_text(api::StyleMatchingTextOptions {
+                presentation_id: &presentation_id,
+                pattern: &pattern,
+                regex,
+                match_case,
+                slide_object_id: slide_object_id.as_deref(),
+                style: api::TextStyleOptions {
+                    bold,
+                    italic,
+                    underline,
+                    font_size,
+                    font_family: font_family.as_deref(),
+                    foreground_color: foreground_color.as_deref(),
+                    link_url: link_url.as_deref(),
+                    link_slide_index,
+                },
             })?;
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
@@ -585,6 +1532,28 @@ fn execute_inner(params: &str) -> Result<String, String> {
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
+        GoogleSlidesAction::CreateBullets {
+            presentation_id,
+            object_id,
+            preset,
+            start_index,
+            end_index,
+        } => {
+            let result =
+                api::create_bullets(&presentation_id, &object_id, preset, start_index, end_index)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSlidesAction::DeleteBullets {
+            presentation_id,
+            object_id,
+            start_index,
+            end_index,
+        } => {
+            let result = api::delete_bullets(&presentation_id, &object_id, start_index, end_index)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
         GoogleSlidesAction::ReplaceShapesWithImage {
             presentation_id,
             find,
@@ -596,6 +1565,136 @@ fn execute_inner(params: &str) -> Result<String, String> {
             serde_json::to_string(&result).map_err(|e| e.to_string())?
         }
 
+        GoogleSlidesAction::UpdateElementTransform {
+            presentation_id,
+            object_id,
+            x,
+            y,
+            width,
+            height,
+            rotation,
+        } => {
+            let result = api::update_element_transform(api::TransformOptions {
+                presentation_id: &presentation_id,
+                object_id: &object_id,
+                x,
+                y,
+                width,
+                height,
+                rotation,
+            })?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSlidesAction::GetSpeakerNotes {
+            presentation_id,
+            slide_object_id,
+        } => {
+            let result = api::get_speaker_notes(&presentation_id, slide_object_id.as_deref())?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSlidesAction::SetSpeakerNotes {
+            presentation_id,
+            slide_object_id,
+            text,
+        } => {
+            let result = api::set_speaker_notes(&presentation_id, &slide_object_id, &text)?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSlidesAction::CreateTable {
+            presentation_id,
+            slide_object_id,
+            rows,
+            columns,
+            x,
+            y,
+            width,
+            height,
+            data,
+        } => {
+            let result = api::create_table(api::CreateTableOptions {
+                presentation_id: &presentation_id,
+                slide_object_id: &slide_object_id,
+                rows,
+                columns,
+                x,
+                y,
+                width,
+                height,
+                data: &data,
+            })?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSlidesAction::InsertTableText {
+            presentation_id,
+            table_object_id,
+            row_index,
+            column_index,
+            text,
+            insertion_index,
+        } => {
+            let result = api::insert_table_text(
+                &presentation_id,
+                &table_object_id,
+                row_index,
+                column_index,
+                &text,
+                insertion_index,
+            )?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSlidesAction::StyleTableCell {
+            presentation_id,
+            table_object_id,
+            row_index,
+            column_index,
+            row_span,
+            column_span,
+            background_color,
+            border_color,
+            border_weight,
+            border_dash_style,
+            border_position,
+        } => {
+            let result = api::style_table_cell(api::TableCellStyleOptions {
+                presentation_id: &presentation_id,
+                table_object_id: &table_object_id,
+                row_index,
+                column_index,
+                row_span,
+                column_span,
+                background_color: background_color.as_deref(),
+                border_color: border_color.as_deref(),
+                border_weight,
+                border_dash_style: border_dash_style.as_deref(),
+                border_position: &border_position,
+            })?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
+        GoogleSlidesAction::PublishDeck {
+            presentation_id,
+            share,
+            domain,
+            role,
+            max_previews,
+            thumbnail_size,
+        } => {
+            let result = api::publish_deck(api::PublishOptions {
+                presentation_id: &presentation_id,
+                share: &share,
+                domain: domain.as_deref(),
+                role: &role,
+                max_previews,
+                thumbnail_size: &thumbnail_size,
+            })?;
+            serde_json::to_string(&result).map_err(|e| e.to_string())?
+        }
+
         GoogleSlidesAction::BatchUpdate {
             presentation_id,
             requests,
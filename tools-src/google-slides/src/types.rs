@@ -1,5 +1,7 @@
 //! Types for Google Slides API requests and responses.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Input parameters for the Google Slides tool.
@@ -16,6 +18,23 @@ pub enum GoogleSlidesAction {
     GetPresentation {
         /// The presentation ID (same as Google Drive file ID).
         presentation_id: String,
+        /// How much of each slide to include (default: standard).
+        #[serde(default)]
+        detail: Detail,
+        /// Google API field mask. When set, the raw API response limited to
+        /// these fields is returned instead of the summarized result.
+        #[serde(default)]
+        fields: Option<String>,
+    },
+
+    /// Get every shape's and table cell's text runs with their character
+    /// indexes and styles.
+    ExtractText {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Only this slide. Default: every slide.
+        #[serde(default)]
+        slide_object_id: Option<String>,
     },
 
     /// Get a thumbnail image URL for a specific slide.
@@ -24,6 +43,15 @@ pub enum GoogleSlidesAction {
         presentation_id: String,
         /// The slide's object ID.
         slide_object_id: String,
+        /// Also upload the PNG to Drive and return the new file.
+        #[serde(default)]
+        save_to_drive: bool,
+        /// Drive folder for the upload. Default: My Drive root.
+        #[serde(default)]
+        folder_id: Option<String>,
+        /// File name for the upload. Default: "slide-<slide_object_id>.png".
+        #[serde(default)]
+        file_name: Option<String>,
     },
 
     /// Create a new slide.
@@ -38,6 +66,29 @@ pub enum GoogleSlidesAction {
         /// "CAPTION_ONLY", "BIG_NUMBER", "ONE_COLUMN_TEXT", "MAIN_POINT".
         #[serde(default = "default_layout")]
         layout: String,
+        /// Object ID of one of the presentation's own layouts (from
+        /// list_layouts). Takes precedence over `layout`.
+        #[serde(default)]
+        layout_object_id: Option<String>,
+    },
+
+    /// List the presentation's masters and layouts with their placeholders.
+    ListLayouts {
+        /// The presentation ID.
+        presentation_id: String,
+    },
+
+    /// Fill a slide's placeholders (e.g., TITLE, BODY) by type, replacing any
+    /// text they hold.
+    ApplyLayoutPlaceholderText {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Slide object ID.
+        slide_object_id: String,
+        /// Text keyed by placeholder type ("TITLE", "SUBTITLE", "BODY", ...).
+        /// Append ":n" to pick the n-th placeholder of a type (0-based), e.g.
+        /// "BODY:1" for the right column of TITLE_AND_TWO_COLUMNS.
+        placeholders: BTreeMap<String, String>,
     },
 
     /// Delete a slide or page element.
@@ -48,6 +99,29 @@ pub enum GoogleSlidesAction {
         object_id: String,
     },
 
+    /// Duplicate a slide, placing the copy right after the original.
+    DuplicateSlide {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Object ID of the slide to copy.
+        slide_object_id: String,
+        /// Object IDs to give the copies, keyed by original object ID (the
+        /// slide or any element on it). Unlisted objects get generated IDs.
+        #[serde(default)]
+        object_ids: BTreeMap<String, String>,
+    },
+
+    /// Move slides to a new position, keeping their relative order.
+    ReorderSlides {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Object IDs of the slides to move.
+        slide_object_ids: Vec<String>,
+        /// Index to move the slides to (0-based), counted in the slide order
+        /// before the move.
+        insertion_index: i64,
+    },
+
     /// Insert text into a shape or text box.
     InsertText {
         /// The presentation ID.
@@ -59,6 +133,12 @@ pub enum GoogleSlidesAction {
         /// Character index to insert at (0-based). Default: 0.
         #[serde(default)]
         insertion_index: i64,
+        /// Make the inserted text a hyperlink to this URL.
+        #[serde(default)]
+        link_url: Option<String>,
+        /// Make the inserted text a link to the slide at this position (0-based).
+        #[serde(default)]
+        link_slide_index: Option<i64>,
     },
 
     /// Delete text from a shape.
@@ -125,6 +205,118 @@ pub enum GoogleSlidesAction {
         height: f64,
     },
 
+    /// Embed a chart from a Google Sheets spreadsheet on a slide.
+    CreateChartFromSheets {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Slide object ID to place the chart on.
+        slide_object_id: String,
+        /// Spreadsheet holding the chart.
+        spreadsheet_id: String,
+        /// Chart ID within the spreadsheet.
+        chart_id: i64,
+        /// "LINKED" (can be refreshed from the sheet) or "NOT_LINKED_IMAGE"
+        /// (static picture). Default: LINKED.
+        #[serde(default = "default_linking_mode")]
+        linking_mode: String,
+        /// X position in points.
+        x: f64,
+        /// Y position in points.
+        y: f64,
+        /// Width in points.
+        width: f64,
+        /// Height in points.
+        height: f64,
+    },
+
+    /// Embed a YouTube or Google Drive video on a slide.
+    InsertVideo {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Slide object ID to place the video on.
+        slide_object_id: String,
+        /// YouTube or Drive URL, or a bare video/file ID.
+        video: String,
+        /// "YOUTUBE" or "DRIVE". Default: taken from the URL, YOUTUBE for a
+        /// bare ID.
+        #[serde(default)]
+        source: Option<String>,
+        /// X position in points.
+        x: f64,
+        /// Y position in points.
+        y: f64,
+        /// Width in points.
+        width: f64,
+        /// Height in points.
+        height: f64,
+        /// Start playing when the slide is presented.
+        #[serde(default)]
+        autoplay: Option<bool>,
+        /// Play without sound.
+        #[serde(default)]
+        mute: Option<bool>,
+        /// Second to start playing from.
+        #[serde(default)]
+        start_seconds: Option<u32>,
+        /// Second to stop playing at.
+        #[serde(default)]
+        end_seconds: Option<u32>,
+    },
+
+    /// Draw a line or connector between two points, optionally attached to
+    /// shapes.
+    CreateLine {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Slide object ID to draw the line on.
+        slide_object_id: String,
+        /// "STRAIGHT", "BENT", or "CURVED". Default: STRAIGHT.
+        #[serde(default = "default_line_category")]
+        category: String,
+        /// Start point X in points. Optional when `start_object_id` is set.
+        #[serde(default)]
+        start_x: Option<f64>,
+        /// Start point Y in points.
+        #[serde(default)]
+        start_y: Option<f64>,
+        /// Shape to connect the start of the line to.
+        #[serde(default)]
+        start_object_id: Option<String>,
+        /// Connection site on the start shape. Default: the side facing the
+        /// other end.
+        #[serde(default)]
+        start_site_index: Option<i64>,
+        /// End point X in points. Optional when `end_object_id` is set.
+        #[serde(default)]
+        end_x: Option<f64>,
+        /// End point Y in points.
+        #[serde(default)]
+        end_y: Option<f64>,
+        /// Shape to connect the end of the line to.
+        #[serde(default)]
+        end_object_id: Option<String>,
+        /// Connection site on the end shape.
+        #[serde(default)]
+        end_site_index: Option<i64>,
+        /// Line color as hex (e.g., "#333333").
+        #[serde(default)]
+        color: Option<String>,
+        /// Line weight in points.
+        #[serde(default)]
+        weight: Option<f64>,
+        /// Dash style: "SOLID", "DOT", "DASH", "DASH_DOT", "LONG_DASH",
+        /// "LONG_DASH_DOT".
+        #[serde(default)]
+        dash_style: Option<String>,
+        /// Arrow head at the start (e.g., "NONE", "FILL_ARROW",
+        /// "STEALTH_ARROW", "OPEN_ARROW", "FILL_CIRCLE").
+        #[serde(default)]
+        start_arrow: Option<String>,
+        /// Arrow head at the end.
+        #[serde(default)]
+        end_arrow: Option<String>,
+    },
+
     /// Format text in a shape (bold, italic, font, color, size).
     FormatText {
         /// The presentation ID.
@@ -155,6 +347,53 @@ pub enum GoogleSlidesAction {
         /// Text color as hex (e.g., "#FF0000").
         #[serde(default)]
         foreground_color: Option<String>,
+        /// Turn the range into a hyperlink to this URL.
+        #[serde(default)]
+        link_url: Option<String>,
+        /// Turn the range into a link to the slide at this position (0-based).
+        #[serde(default)]
+        link_slide_index: Option<i64>,
+    },
+
+    /// Style every occurrence of a string or regex across the deck.
+    StyleMatchingText {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Text to find, or a regular expression when `regex` is set.
+        pattern: String,
+        /// Treat `pattern` as a regular expression.
+        #[serde(default)]
+        regex: bool,
+        /// Match case exactly (default: case-insensitive).
+        #[serde(default)]
+        match_case: bool,
+        /// Only this slide (default: every slide).
+        #[serde(default)]
+        slide_object_id: Option<String>,
+        /// Make text bold.
+        #[serde(default)]
+        bold: Option<bool>,
+        /// Make text italic.
+        #[serde(default)]
+        italic: Option<bool>,
+        /// Underline text.
+        #[serde(default)]
+        underline: Option<bool>,
+        /// Font size in points.
+        #[serde(default)]
+        font_size: Option<f64>,
+        /// Font family name (e.g., "Arial").
+        #[serde(default)]
+        font_family: Option<String>,
+        /// Text color as hex (e.g., "#FF0000").
+        #[serde(default)]
+        foreground_color: Option<String>,
+        /// Turn each match into a hyperlink to this URL.
+        #[serde(default)]
+        link_url: Option<String>,
+        /// Turn each match into a link to the slide at this position (0-based).
+        #[serde(default)]
+        link_slide_index: Option<i64>,
     },
 
     /// Set paragraph alignment for text in a shape.
@@ -173,6 +412,38 @@ pub enum GoogleSlidesAction {
         end_index: Option<i64>,
     },
 
+    /// Turn the paragraphs in a range of a shape's text into a bulleted or
+    /// numbered list.
+    CreateBullets {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Object ID of the shape.
+        object_id: String,
+        /// Glyph preset for the list. Default: BULLET_DISC_CIRCLE_SQUARE.
+        #[serde(default)]
+        preset: BulletPreset,
+        /// Start index (inclusive). Omit to apply to all paragraphs.
+        #[serde(default)]
+        start_index: Option<i64>,
+        /// End index (exclusive). Omit to apply to the end.
+        #[serde(default)]
+        end_index: Option<i64>,
+    },
+
+    /// Remove bullets and numbering from paragraphs in a shape.
+    DeleteBullets {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Object ID of the shape.
+        object_id: String,
+        /// Start index (inclusive). Omit to apply to all paragraphs.
+        #[serde(default)]
+        start_index: Option<i64>,
+        /// End index (exclusive). Omit to apply to the end.
+        #[serde(default)]
+        end_index: Option<i64>,
+    },
+
     /// Replace all shapes containing specific text with an image.
     ReplaceShapesWithImage {
         /// The presentation ID.
@@ -186,6 +457,151 @@ pub enum GoogleSlidesAction {
         match_case: bool,
     },
 
+    /// Move, resize, or rotate an existing page element.
+    UpdateElementTransform {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Object ID of the element.
+        object_id: String,
+        /// New left edge in points. Omit to keep.
+        #[serde(default)]
+        x: Option<f64>,
+        /// New top edge in points. Omit to keep.
+        #[serde(default)]
+        y: Option<f64>,
+        /// New width in points. Omit to keep.
+        #[serde(default)]
+        width: Option<f64>,
+        /// New height in points. Omit to keep.
+        #[serde(default)]
+        height: Option<f64>,
+        /// New clockwise rotation in degrees, about the element's center.
+        /// Omit to keep.
+        #[serde(default)]
+        rotation: Option<f64>,
+    },
+
+    /// Get the speaker notes of one slide, or of every slide.
+    GetSpeakerNotes {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Slide object ID. Omit to get the notes of every slide.
+        #[serde(default)]
+        slide_object_id: Option<String>,
+    },
+
+    /// Replace the speaker notes of a slide.
+    SetSpeakerNotes {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Slide object ID.
+        slide_object_id: String,
+        /// New notes text. Empty clears the notes.
+        text: String,
+    },
+
+    /// Create a table on a slide, optionally filled with data.
+    CreateTable {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Slide object ID to place the table on.
+        slide_object_id: String,
+        /// Number of rows.
+        rows: i64,
+        /// Number of columns.
+        columns: i64,
+        /// X position in points. Omit x and y to center the table.
+        #[serde(default)]
+        x: Option<f64>,
+        /// Y position in points.
+        #[serde(default)]
+        y: Option<f64>,
+        /// Width in points. Omit width and height for the default size.
+        #[serde(default)]
+        width: Option<f64>,
+        /// Height in points.
+        #[serde(default)]
+        height: Option<f64>,
+        /// Cell text, row by row (e.g., [["Name", "Score"], ["Ann", "9"]]).
+        #[serde(default)]
+        data: Vec<Vec<String>>,
+    },
+
+    /// Insert text into a table cell.
+    InsertTableText {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Object ID of the table.
+        table_object_id: String,
+        /// Row of the cell (0-based).
+        row_index: i64,
+        /// Column of the cell (0-based).
+        column_index: i64,
+        /// Text to insert.
+        text: String,
+        /// Character index to insert at (0-based). Default: 0.
+        #[serde(default)]
+        insertion_index: i64,
+    },
+
+    /// Set the background fill and borders of a range of table cells.
+    StyleTableCell {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Object ID of the table.
+        table_object_id: String,
+        /// Row of the top-left cell (0-based).
+        row_index: i64,
+        /// Column of the top-left cell (0-based).
+        column_index: i64,
+        /// Rows to style, starting at row_index. Default: 1.
+        #[serde(default = "default_span")]
+        row_span: i64,
+        /// Columns to style, starting at column_index. Default: 1.
+        #[serde(default = "default_span")]
+        column_span: i64,
+        /// Cell background color as hex (e.g., "#F3F3F3").
+        #[serde(default)]
+        background_color: Option<String>,
+        /// Border color as hex (e.g., "#000000").
+        #[serde(default)]
+        border_color: Option<String>,
+        /// Border weight in points.
+        #[serde(default)]
+        border_weight: Option<f64>,
+        /// Border dash style: "SOLID", "DOT", "DASH", "DASH_DOT",
+        /// "LONG_DASH", "LONG_DASH_DOT".
+        #[serde(default)]
+        border_dash_style: Option<String>,
+        /// Which borders to style: "ALL", "OUTER", "INNER", "TOP", "BOTTOM",
+        /// "LEFT", "RIGHT", "INNER_HORIZONTAL", "INNER_VERTICAL".
+        #[serde(default = "default_border_position")]
+        border_position: String,
+    },
+
+    /// Share a presentation by link and return a ready-to-send summary with
+    /// slide previews.
+    PublishDeck {
+        /// The presentation ID.
+        presentation_id: String,
+        /// Who the link works for: "anyone", "domain", or "none" to leave
+        /// sharing unchanged. Default: anyone.
+        #[serde(default = "default_share")]
+        share: String,
+        /// Domain for `share: "domain"` (e.g., "example.com").
+        #[serde(default)]
+        domain: Option<String>,
+        /// Access granted by the link: "reader", "commenter", or "writer".
+        #[serde(default = "default_share_role")]
+        role: String,
+        /// Slides to fetch thumbnails for, from the start. Default: 10.
+        #[serde(default = "default_max_previews")]
+        max_previews: usize,
+        /// Thumbnail size: "SMALL", "MEDIUM", or "LARGE". Default: MEDIUM.
+        #[serde(default = "default_thumbnail_size")]
+        thumbnail_size: String,
+    },
+
     /// Execute multiple raw Slides API operations atomically.
     BatchUpdate {
         /// The presentation ID.
@@ -207,15 +623,110 @@ fn default_shape_type() -> String {
     "TEXT_BOX".to_string()
 }
 
+fn default_span() -> i64 {
+    1
+}
+
+fn default_border_position() -> String {
+    "ALL".to_string()
+}
+
+fn default_share() -> String {
+    "anyone".to_string()
+}
+
+fn default_share_role() -> String {
+    "reader".to_string()
+}
+
+fn default_max_previews() -> usize {
+    10
+}
+
+fn default_linking_mode() -> String {
+    "LINKED".to_string()
+}
+
+fn default_line_category() -> String {
+    "STRAIGHT".to_string()
+}
+
+fn default_thumbnail_size() -> String {
+    "MEDIUM".to_string()
+}
+
+/// Glyph preset for create_bullets, as defined by the Slides API. Each preset
+/// lists the glyphs for the first three nesting levels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BulletPreset {
+    #[default]
+    BulletDiscCircleSquare,
+    BulletDiamondxArrow3dSquare,
+    BulletCheckbox,
+    BulletArrowDiamondDisc,
+    BulletStarCircleSquare,
+    BulletArrow3dCircleSquare,
+    BulletLefttriangleDiamondDisc,
+    BulletDiamondxHollowdiamondSquare,
+    BulletDiamondCircleSquare,
+    NumberedDigitAlphaRoman,
+    NumberedDigitAlphaRomanParens,
+    NumberedDigitNested,
+    NumberedUpperalphaAlphaRoman,
+    NumberedUpperromanUpperalphaDigit,
+    NumberedZerodigitAlphaRoman,
+}
+
+impl BulletPreset {
+    /// The API's name for the preset.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::BulletDiscCircleSquare => "BULLET_DISC_CIRCLE_SQUARE",
+            Self::BulletDiamondxArrow3dSquare => "BULLET_DIAMONDX_ARROW3D_SQUARE",
+            Self::BulletCheckbox => "BULLET_CHECKBOX",
+            Self::BulletArrowDiamondDisc => "BULLET_ARROW_DIAMOND_DISC",
+            Self::BulletStarCircleSquare => "BULLET_STAR_CIRCLE_SQUARE",
+            Self::BulletArrow3dCircleSquare => "BULLET_ARROW3D_CIRCLE_SQUARE",
+            Self::BulletLefttriangleDiamondDisc => "BULLET_LEFTTRIANGLE_DIAMOND_DISC",
+            Self::BulletDiamondxHollowdiamondSquare => "BULLET_DIAMONDX_HOLLOWDIAMOND_SQUARE",
+            Self::BulletDiamondCircleSquare => "BULLET_DIAMOND_CIRCLE_SQUARE",
+            Self::NumberedDigitAlphaRoman => "NUMBERED_DIGIT_ALPHA_ROMAN",
+            Self::NumberedDigitAlphaRomanParens => "NUMBERED_DIGIT_ALPHA_ROMAN_PARENS",
+            Self::NumberedDigitNested => "NUMBERED_DIGIT_NESTED",
+            Self::NumberedUpperalphaAlphaRoman => "NUMBERED_UPPERALPHA_ALPHA_ROMAN",
+            Self::NumberedUpperromanUpperalphaDigit => "NUMBERED_UPPERROMAN_UPPERALPHA_DIGIT",
+            Self::NumberedZerodigitAlphaRoman => "NUMBERED_ZERODIGIT_ALPHA_ROMAN",
+        }
+    }
+}
+
 /// Slide info.
 #[derive(Debug, Serialize)]
 pub struct SlideInfo {
     pub object_id: String,
     pub layout_object_id: String,
+    /// Text of the slide's title placeholder, if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub element_count: usize,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub elements: Vec<ElementInfo>,
 }
 
+/// How much of a presentation get_presentation returns.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Detail {
+    /// Slide titles and element counts only.
+    Summary,
+    /// Element inventory with text previews.
+    #[default]
+    Standard,
+    /// Element inventory with complete text.
+    Full,
+}
+
 /// Page element info.
 #[derive(Debug, Serialize)]
 pub struct ElementInfo {
@@ -225,6 +736,23 @@ pub struct ElementInfo {
     pub text_content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub placeholder_type: Option<String>,
+    /// Position, size and rotation, when the element has a size.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geometry: Option<ElementGeometry>,
+}
+
+/// Where an element sits on its slide, in points.
+///
+/// `x`/`y` are the top-left corner of the element before rotation;
+/// `rotation` turns it clockwise about its center.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ElementGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    /// Clockwise, in degrees.
+    pub rotation: f64,
 }
 
 /// Result from create_presentation.
@@ -250,6 +778,18 @@ pub struct ThumbnailResult {
     pub content_url: String,
     pub width: i64,
     pub height: i64,
+    /// Set when `save_to_drive` was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drive_file: Option<DriveFile>,
+}
+
+/// A file uploaded to Drive.
+#[derive(Debug, Serialize)]
+pub struct DriveFile {
+    pub file_id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_view_link: Option<String>,
 }
 
 /// Result from a batchUpdate operation.
@@ -260,6 +800,253 @@ pub struct UpdateResult {
     pub created_object_id: Option<String>,
 }
 
+/// Result from duplicate_slide.
+#[derive(Debug, Serialize)]
+pub struct DuplicateSlideResult {
+    pub presentation_id: String,
+    pub new_slide_object_id: String,
+    /// Object ID of every copy, keyed by the original's object ID.
+    pub object_id_map: BTreeMap<String, String>,
+}
+
+/// Result from update_element_transform.
+#[derive(Debug, Serialize)]
+pub struct TransformResult {
+    pub presentation_id: String,
+    pub object_id: String,
+    pub geometry: ElementGeometry,
+}
+
+/// Speaker notes of one slide.
+#[derive(Debug, Serialize)]
+pub struct SlideNotes {
+    pub slide_object_id: String,
+    /// Position of the slide (0-based).
+    pub slide_index: usize,
+    /// Object ID of the shape holding the notes.
+    pub notes_object_id: String,
+    pub notes: String,
+}
+
+/// Result from get_speaker_notes.
+#[derive(Debug, Serialize)]
+pub struct SpeakerNotesResult {
+    pub presentation_id: String,
+    pub slides: Vec<SlideNotes>,
+}
+
+/// Result from extract_text.
+#[derive(Debug, Serialize)]
+pub struct ExtractTextResult {
+    pub presentation_id: String,
+    pub slides: Vec<SlideText>,
+}
+
+/// Result from style_matching_text.
+#[derive(Debug, Serialize)]
+pub struct StyleMatchingTextResult {
+    pub presentation_id: String,
+    pub match_count: usize,
+    pub matches: Vec<TextMatch>,
+}
+
+/// One styled occurrence.
+#[derive(Debug, Serialize)]
+pub struct TextMatch {
+    pub slide_object_id: String,
+    /// Position of the slide (0-based).
+    pub slide_index: usize,
+    /// Object ID of the shape, or of the table for a cell.
+    pub object_id: String,
+    /// Cell row (0-based), for table cells.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_index: Option<i64>,
+    /// Cell column (0-based), for table cells.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column_index: Option<i64>,
+    pub start_index: i64,
+    pub end_index: i64,
+    pub text: String,
+}
+
+/// Text on one slide.
+#[derive(Debug, Serialize)]
+pub struct SlideText {
+    pub slide_object_id: String,
+    /// Position of the slide (0-based).
+    pub slide_index: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub shapes: Vec<ShapeText>,
+}
+
+/// Text of a shape, or of one table cell.
+#[derive(Debug, Serialize)]
+pub struct ShapeText {
+    /// Object ID of the shape, or of the table for a cell.
+    pub object_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder_type: Option<String>,
+    /// Cell row (0-based), for table cells.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_index: Option<i64>,
+    /// Cell column (0-based), for table cells.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column_index: Option<i64>,
+    /// All of the text, in order.
+    pub text: String,
+    pub paragraphs: Vec<ParagraphRange>,
+    pub runs: Vec<TextRunInfo>,
+}
+
+/// A paragraph's character range. Indexes count UTF-16 code units, as the
+/// API does, and the end is exclusive.
+#[derive(Debug, Serialize)]
+pub struct ParagraphRange {
+    pub start_index: i64,
+    pub end_index: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alignment: Option<String>,
+    /// Set when the paragraph is a list item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bullet_nesting_level: Option<i64>,
+}
+
+/// A stretch of text with a single style.
+#[derive(Debug, Serialize)]
+pub struct TextRunInfo {
+    pub start_index: i64,
+    pub end_index: i64,
+    pub content: String,
+    /// Type of generated text, e.g. "SLIDE_NUMBER".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_text: Option<String>,
+    #[serde(skip_serializing_if = "TextStyleInfo::is_empty")]
+    pub style: TextStyleInfo,
+}
+
+/// Styling set directly on a run; anything left out is inherited.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct TextStyleInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bold: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub italic: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub underline: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strikethrough: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_family: Option<String>,
+    /// In points.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<f64>,
+    /// "#RRGGBB", or a theme color name such as "ACCENT1".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub foreground_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_slide_index: Option<i64>,
+}
+
+impl TextStyleInfo {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Link sharing applied by publish_deck.
+#[derive(Debug, Serialize)]
+pub struct LinkSharing {
+    pub permission_id: String,
+    /// "anyone" or "domain".
+    pub share: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    pub role: String,
+}
+
+/// Thumbnail of one slide.
+#[derive(Debug, Serialize)]
+pub struct SlidePreview {
+    pub slide_object_id: String,
+    /// Position of the slide (0-based).
+    pub slide_index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Short-lived image URL (valid for about 30 minutes).
+    pub thumbnail_url: String,
+    pub width: i64,
+    pub height: i64,
+}
+
+/// Result from publish_deck.
+#[derive(Debug, Serialize)]
+pub struct PublishDeckResult {
+    pub presentation_id: String,
+    pub title: String,
+    pub link: String,
+    pub slide_count: usize,
+    /// Absent when `share` was "none".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sharing: Option<LinkSharing>,
+    pub previews: Vec<SlidePreview>,
+    /// Plain-text message with the title, link, access, and slide outline.
+    pub summary: String,
+}
+
+/// A placeholder shape on a layout.
+#[derive(Debug, Serialize)]
+pub struct PlaceholderInfo {
+    pub object_id: String,
+    pub placeholder_type: String,
+    /// Distinguishes placeholders of the same type on one page.
+    pub index: i64,
+}
+
+/// A slide layout.
+#[derive(Debug, Serialize)]
+pub struct LayoutInfo {
+    pub object_id: String,
+    /// Predefined layout name (e.g., "TITLE_AND_BODY"), if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub display_name: String,
+    pub master_object_id: String,
+    pub placeholders: Vec<PlaceholderInfo>,
+}
+
+/// A slide master.
+#[derive(Debug, Serialize)]
+pub struct MasterInfo {
+    pub object_id: String,
+    pub display_name: String,
+}
+
+/// Result from list_layouts.
+#[derive(Debug, Serialize)]
+pub struct ListLayoutsResult {
+    pub presentation_id: String,
+    pub masters: Vec<MasterInfo>,
+    pub layouts: Vec<LayoutInfo>,
+}
+
+/// A placeholder filled by apply_layout_placeholder_text.
+#[derive(Debug, Serialize)]
+pub struct FilledPlaceholder {
+    /// The key it was requested under (e.g., "BODY:1").
+    pub placeholder: String,
+    pub object_id: String,
+}
+
+/// Result from apply_layout_placeholder_text.
+#[derive(Debug, Serialize)]
+pub struct PlaceholderTextResult {
+    pub presentation_id: String,
+    pub slide_object_id: String,
+    pub filled: Vec<FilledPlaceholder>,
+}
+
 /// Result from replace_all_text.
 #[derive(Debug, Serialize)]
 pub struct ReplaceResult {
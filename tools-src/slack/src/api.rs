@@ -4,62 +4,56 @@
 //! credential injection and rate limiting. The WASM tool never sees
 //! the actual bot token.
 
-use crate::near::agent::host;
-use crate::types::*;
+use ironclaw_component_sdk::{ErrorAction, ErrorEnvelope, Host, HttpClient, Query};
 
-const SLACK_API_BASE: &str = "https://slack.com/api";
-
-/// Percent-encode a string for use as a URL query parameter value.
-fn url_encode(s: &str) -> String {
-    let mut out = String::with_capacity(s.len());
-    for b in s.bytes() {
-        match b {
-            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
-                out.push(b as char);
-            }
-            _ => {
-                out.push('%');
-                out.push(char::from(b"0123456789ABCDEF"[(b >> 4) as usize]));
-                out.push(char::from(b"0123456789ABCDEF"[(b & 0xf) as usize]));
-            }
-        }
-    }
-    out
-}
-
-/// Make a Slack API call.
-fn slack_api_call(method: &str, endpoint: &str, body: Option<&str>) -> Result<String, String> {
-    let url = format!("{}/{}", SLACK_API_BASE, endpoint);
+use crate::types::*;
 
-    // Content-Type header for POST requests
-    let headers = if body.is_some() {
-        r#"{"Content-Type": "application/json; charset=utf-8"}"#
-    } else {
-        "{}"
+const SLACK_API_BASE: &str = "https://slack.com/api/";
+
+/// Make a Slack API call and check the `ok` field of the response.
+///
+/// Slack reports most failures as HTTP 200 with `{"ok": false, "error": ..}`;
+/// `tolerated` lists error codes that should count as success.
+fn slack_api_call<H: Host + ?Sized>(
+    host: &H,
+    method: &str,
+    endpoint: &str,
+    body: Option<&serde_json::Value>,
+    tolerated: &[&str],
+) -> Result<serde_json::Value, String> {
+    let client = HttpClient::new(host, "Slack", SLACK_API_BASE);
+    let parsed: serde_json::Value = match body {
+        Some(body) => client.send_json(method, endpoint, body)?,
+        None => client.get_json(endpoint)?,
     };
 
-    let body_bytes = body.map(|b| b.as_bytes().to_vec());
-
-    host::log(
-        host::LogLevel::Debug,
-        &format!("Slack API: {} {}", method, endpoint),
-    );
+    if !parsed["ok"].as_bool().unwrap_or(false) {
+        let error = parsed["error"].as_str().unwrap_or("unknown_error");
+        if !tolerated.contains(&error) {
+            return Err(
+                ErrorEnvelope::with_action("Slack", error_action(error), error).into_error(),
+            );
+        }
+    }
 
-    let response = host::http_request(method, &url, headers, body_bytes.as_deref())?;
+    Ok(parsed)
+}
 
-    if response.status < 200 || response.status >= 300 {
-        return Err(format!(
-            "Slack API returned status {}: {}",
-            response.status,
-            String::from_utf8_lossy(&response.body)
-        ));
+/// Classify a Slack error code.
+fn error_action(code: &str) -> ErrorAction {
+    match code {
+        "ratelimited" | "service_unavailable" | "request_timeout" | "fatal_error" => {
+            ErrorAction::Retry
+        }
+        "not_authed" | "invalid_auth" | "account_inactive" | "token_revoked" | "token_expired"
+        | "missing_scope" => ErrorAction::Reauthenticate,
+        _ => ErrorAction::FixRequest,
     }
-
-    String::from_utf8(response.body).map_err(|e| format!("Invalid UTF-8 in response: {}", e))
 }
 
 /// Send a message to a Slack channel.
-pub fn send_message(
+pub fn send_message<H: Host + ?Sized>(
+    host: &H,
     channel: &str,
     text: &str,
     thread_ts: Option<&str>,
@@ -73,16 +67,7 @@ pub fn send_message(
         payload["thread_ts"] = serde_json::Value::String(ts.to_string());
     }
 
-    let body = serde_json::to_string(&payload).map_err(|e| e.to_string())?;
-    let response = slack_api_call("POST", "chat.postMessage", Some(&body))?;
-
-    let parsed: serde_json::Value =
-        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
-
-    if !parsed["ok"].as_bool().unwrap_or(false) {
-        let error = parsed["error"].as_str().unwrap_or("unknown_error");
-        return Err(format!("Slack API error: {}", error));
-    }
+    let parsed = slack_api_call(host, "POST", "chat.postMessage", Some(&payload), &[])?;
 
     Ok(SendMessageResult {
         ok: true,
@@ -97,21 +82,12 @@ pub fn send_message(
 }
 
 /// List channels the bot has access to.
-pub fn list_channels(limit: u32) -> Result<ListChannelsResult, String> {
-    let url = format!(
-        "conversations.list?types=public_channel,private_channel&limit={}",
-        limit
-    );
-
-    let response = slack_api_call("GET", &url, None)?;
-
-    let parsed: serde_json::Value =
-        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
-
-    if !parsed["ok"].as_bool().unwrap_or(false) {
-        let error = parsed["error"].as_str().unwrap_or("unknown_error");
-        return Err(format!("Slack API error: {}", error));
-    }
+pub fn list_channels<H: Host + ?Sized>(host: &H, limit: u32) -> Result<ListChannelsResult, String> {
+    let path = Query::new()
+        .param("types", "public_channel,private_channel")
+        .param("limit", limit)
+        .to_path("conversations.list");
+    let parsed = slack_api_call(host, "GET", &path, None, &[])?;
 
     let channels = parsed["channels"]
         .as_array()
@@ -133,22 +109,16 @@ pub fn list_channels(limit: u32) -> Result<ListChannelsResult, String> {
 }
 
 /// Get message history from a channel.
-pub fn get_channel_history(channel: &str, limit: u32) -> Result<ChannelHistoryResult, String> {
-    let url = format!(
-        "conversations.history?channel={}&limit={}",
-        url_encode(channel),
-        limit
-    );
-
-    let response = slack_api_call("GET", &url, None)?;
-
-    let parsed: serde_json::Value =
-        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
-
-    if !parsed["ok"].as_bool().unwrap_or(false) {
-        let error = parsed["error"].as_str().unwrap_or("unknown_error");
-        return Err(format!("Slack API error: {}", error));
-    }
+pub fn get_channel_history<H: Host + ?Sized>(
+    host: &H,
+    channel: &str,
+    limit: u32,
+) -> Result<ChannelHistoryResult, String> {
+    let path = Query::new()
+        .param("channel", channel)
+        .param("limit", limit)
+        .to_path("conversations.history");
+    let parsed = slack_api_call(host, "GET", &path, None, &[])?;
 
     let messages = parsed["messages"]
         .as_array()
@@ -168,7 +138,8 @@ pub fn get_channel_history(channel: &str, limit: u32) -> Result<ChannelHistoryRe
 }
 
 /// Add a reaction to a message.
-pub fn post_reaction(
+pub fn post_reaction<H: Host + ?Sized>(
+    host: &H,
     channel: &str,
     timestamp: &str,
     emoji: &str,
@@ -179,36 +150,25 @@ pub fn post_reaction(
         "name": emoji,
     });
 
-    let body = serde_json::to_string(&payload).map_err(|e| e.to_string())?;
-    let response = slack_api_call("POST", "reactions.add", Some(&body))?;
-
-    let parsed: serde_json::Value =
-        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
-
-    if !parsed["ok"].as_bool().unwrap_or(false) {
-        let error = parsed["error"].as_str().unwrap_or("unknown_error");
-        // "already_reacted" is not really an error
-        if error != "already_reacted" {
-            return Err(format!("Slack API error: {}", error));
-        }
-    }
+    // "already_reacted" is not really an error
+    slack_api_call(
+        host,
+        "POST",
+        "reactions.add",
+        Some(&payload),
+        &["already_reacted"],
+    )?;
 
     Ok(PostReactionResult { ok: true })
 }
 
 /// Get information about a user.
-pub fn get_user_info(user_id: &str) -> Result<GetUserInfoResult, String> {
-    let url = format!("users.info?user={}", url_encode(user_id));
-
-    let response = slack_api_call("GET", &url, None)?;
-
-    let parsed: serde_json::Value =
-        serde_json::from_str(&response).map_err(|e| format!("Failed to parse response: {}", e))?;
-
-    if !parsed["ok"].as_bool().unwrap_or(false) {
-        let error = parsed["error"].as_str().unwrap_or("unknown_error");
-        return Err(format!("Slack API error: {}", error));
-    }
+pub fn get_user_info<H: Host + ?Sized>(
+    host: &H,
+    user_id: &str,
+) -> Result<GetUserInfoResult, String> {
+    let path = Query::new().param("user", user_id).to_path("users.info");
+    let parsed = slack_api_call(host, "GET", &path, None, &[])?;
 
     let user = &parsed["user"];
     let profile = &user["profile"];
@@ -225,3 +185,51 @@ pub fn get_user_info(user_id: &str) -> Result<GetUserInfoResult, String> {
         },
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ironclaw_component_sdk::mock::MockHost;
+
+    #[test]
+    fn test_send_message_in_thread() {
+        let host = MockHost::new().with_response(
+            200,
+            r#"{"ok": true, "channel": "C1", "ts": "1.2", "message": {"text": "hi", "ts": "1.2"}}"#,
+        );
+
+        let result = send_message(&host, "C1", "hi", Some("1.0")).unwrap();
+        assert_eq!(result.ts, "1.2");
+
+        let request = &host.requests()[0];
+        assert_eq!(request.url, "https://slack.com/api/chat.postMessage");
+        let body: serde_json::Value = serde_json::from_str(&request.body_text().unwrap()).unwrap();
+        assert_eq!(body["thread_ts"], "1.0");
+    }
+
+    #[test]
+    fn test_history_query_is_encoded() {
+        let host = MockHost::new().with_response(200, r#"{"ok": true, "messages": []}"#);
+        get_channel_history(&host, "C 1", 5).unwrap();
+        assert_eq!(
+            host.requests()[0].url,
+            "https://slack.com/api/conversations.history?channel=C%201&limit=5"
+        );
+    }
+
+    #[test]
+    fn test_slack_error_envelope() {
+        let host = MockHost::new().with_response(200, r#"{"ok": false, "error": "invalid_auth"}"#);
+        let err = get_user_info(&host, "U1").unwrap_err();
+        let value: serde_json::Value = serde_json::from_str(&err).unwrap();
+        assert_eq!(value["message"], "invalid_auth");
+        assert_eq!(value["action"], "reauthenticate");
+    }
+
+    #[test]
+    fn test_already_reacted_is_success() {
+        let host =
+            MockHost::new().with_response(200, r#"{"ok": false, "error": "already_reacted"}"#);
+        assert!(post_reaction(&host, "C1", "1.2", "thumbsup").unwrap().ok);
+    }
+}
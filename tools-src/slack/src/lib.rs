@@ -26,6 +26,8 @@
 mod api;
 mod types;
 
+use ironclaw_component_sdk as sdk;
+use sdk::{Host, LogLevel};
 use types::SlackAction;
 
 // Generate bindings from the WIT interface.
@@ -35,21 +37,15 @@ wit_bindgen::generate!({
     path: "../../wit/tool.wit",
 });
 
+sdk::bind_host!(WitHost, crate::near::agent::host);
+
 /// Implementation of the tool interface.
 struct SlackTool;
 
 impl exports::near::agent::tool::Guest for SlackTool {
     fn execute(req: exports::near::agent::tool::Request) -> exports::near::agent::tool::Response {
-        match execute_inner(&req.params) {
-            Ok(result) => exports::near::agent::tool::Response {
-                output: Some(result),
-                error: None,
-            },
-            Err(e) => exports::near::agent::tool::Response {
-                output: None,
-                error: Some(e),
-            },
-        }
+        let (output, error) = sdk::respond(execute_inner(&WitHost, &req.params));
+        exports::near::agent::tool::Response { output, error }
     }
 
     fn schema() -> String {
@@ -145,60 +141,46 @@ impl exports::near::agent::tool::Guest for SlackTool {
 }
 
 /// Inner execution logic with proper error handling.
-fn execute_inner(params: &str) -> Result<String, String> {
+fn execute_inner<H: Host>(host: &H, params: &str) -> Result<String, String> {
     // Check if the Slack token is configured
-    if !crate::near::agent::host::secret_exists("slack_bot_token") {
-        return Err(
-            "Slack bot token not configured. Please add the 'slack_bot_token' secret.".to_string(),
-        );
-    }
+    host.require_secret("slack_bot_token", "Slack")?;
 
-    // Parse the action from JSON
-    let action: SlackAction =
-        serde_json::from_str(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+    let action: SlackAction = sdk::parse_params(params)?;
 
-    crate::near::agent::host::log(
-        crate::near::agent::host::LogLevel::Info,
+    host.log(
+        LogLevel::Info,
         &format!("Executing Slack action: {:?}", action),
     );
 
     // Dispatch to the appropriate handler
-    let result = match action {
+    match action {
         SlackAction::SendMessage {
             channel,
             text,
             thread_ts,
-        } => {
-            let result = api::send_message(&channel, &text, thread_ts.as_deref())?;
-            serde_json::to_string(&result).map_err(|e| e.to_string())?
-        }
+        } => sdk::to_output(&api::send_message(
+            host,
+            &channel,
+            &text,
+            thread_ts.as_deref(),
+        )?),
 
-        SlackAction::ListChannels { limit } => {
-            let result = api::list_channels(limit)?;
-            serde_json::to_string(&result).map_err(|e| e.to_string())?
-        }
+        SlackAction::ListChannels { limit } => sdk::to_output(&api::list_channels(host, limit)?),
 
         SlackAction::GetChannelHistory { channel, limit } => {
-            let result = api::get_channel_history(&channel, limit)?;
-            serde_json::to_string(&result).map_err(|e| e.to_string())?
+            sdk::to_output(&api::get_channel_history(host, &channel, limit)?)
         }
 
         SlackAction::PostReaction {
             channel,
             timestamp,
             emoji,
-        } => {
-            let result = api::post_reaction(&channel, &timestamp, &emoji)?;
-            serde_json::to_string(&result).map_err(|e| e.to_string())?
-        }
+        } => sdk::to_output(&api::post_reaction(host, &channel, &timestamp, &emoji)?),
 
         SlackAction::GetUserInfo { user_id } => {
-            let result = api::get_user_info(&user_id)?;
-            serde_json::to_string(&result).map_err(|e| e.to_string())?
+            sdk::to_output(&api::get_user_info(host, &user_id)?)
         }
-    };
-
-    Ok(result)
+    }
 }
 
 // Export the tool implementation.